@@ -1,6 +1,9 @@
+use crate::entry_selection::EntrySelection;
 use async_trait::async_trait;
 use la_core::error::Result;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use tracing::warn;
 
 // CR-15: async_zip dependency removed; streaming archive handlers migrated to sync zip via spawn_blocking
 
@@ -70,6 +73,77 @@ pub trait ArchiveHandler: Send + Sync {
         .await
     }
 
+    /**
+     * 只提取包含列表选中的条目，未选中的条目记入 `ExtractionSummary::skipped`
+     *
+     * 默认实现先完整提取再删除未选中的文件；可流式跳过条目的格式（ZIP、TAR）
+     * 覆盖此方法，未选中的条目不会写入磁盘。
+     */
+    async fn extract_selected(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: &EntrySelection,
+    ) -> Result<ExtractionSummary> {
+        let mut summary = self
+            .extract_with_limits(
+                source,
+                target_dir,
+                max_file_size,
+                max_total_size,
+                max_file_count,
+            )
+            .await?;
+        summary.retain_selected(target_dir, selection);
+        Ok(summary)
+    }
+
+    /**
+     * 使用密码提取加密的压缩包，`selection` 为 `Some` 时只提取选中的条目
+     *
+     * 默认实现忽略密码（格式不支持加密）；ZIP、RAR 覆盖此方法。
+     * 缺少密码或密码错误时返回 `ErrorCategory::PasswordRequired` 错误。
+     */
+    #[allow(clippy::too_many_arguments)]
+    async fn extract_with_password(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: Option<&EntrySelection>,
+        password: &ArchivePassword,
+    ) -> Result<ExtractionSummary> {
+        let _ = password;
+        match selection {
+            Some(selection) => {
+                self.extract_selected(
+                    source,
+                    target_dir,
+                    max_file_size,
+                    max_total_size,
+                    max_file_count,
+                    selection,
+                )
+                .await
+            }
+            None => {
+                self.extract_with_limits(
+                    source,
+                    target_dir,
+                    max_file_size,
+                    max_total_size,
+                    max_file_count,
+                )
+                .await
+            }
+        }
+    }
+
     /**
      * 获取支持的文件扩展名
      *
@@ -79,6 +153,35 @@ pub trait ArchiveHandler: Send + Sync {
     fn file_extensions(&self) -> Vec<&str>;
 }
 
+/**
+ * 压缩包密码
+ *
+ * Debug 输出不包含明文，避免密码出现在日志中
+ */
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ArchivePassword(String);
+
+impl ArchivePassword {
+    pub fn new(password: impl Into<String>) -> Self {
+        Self(password.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl std::fmt::Debug for ArchivePassword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ArchivePassword(***)")
+    }
+}
+
 /**
  * 提取摘要
  */
@@ -92,6 +195,51 @@ pub struct ExtractionSummary {
     pub errors: Vec<String>,
     /// 提取的文件路径列表
     pub extracted_files: Vec<PathBuf>,
+    /// 因路径穿越等安全原因被隔离（未解压）的条目
+    pub quarantined: Vec<QuarantinedEntry>,
+    /// 未被包含列表选中而跳过的条目
+    pub skipped: Vec<SkippedEntry>,
+    /// 因 IO 错误未能解压的条目（可从 CAS 中的压缩包重试）
+    pub failed: Vec<FailedEntry>,
+    /// 按 GBK / Shift-JIS 解码的条目名及其原始字节
+    pub raw_names: Vec<(String, Vec<u8>)>,
+}
+
+/**
+ * 被隔离的压缩包条目
+ *
+ * 条目内容不会写入工作区，仅保留名称与原因供元数据记录和安全告警使用
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantinedEntry {
+    /// 压缩包内的原始条目名
+    pub entry_name: String,
+    /// 隔离原因
+    pub reason: String,
+    /// 条目声明的解压大小（字节）
+    pub size: u64,
+}
+
+/**
+ * 选择性解压时跳过的条目
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEntry {
+    /// 压缩包内的条目路径
+    pub entry_name: String,
+    /// 条目声明的解压大小（字节）
+    pub size: u64,
+}
+
+/**
+ * 解压失败的条目
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedEntry {
+    /// 压缩包内的条目路径
+    pub entry_name: String,
+    /// 失败原因
+    pub error: String,
 }
 
 impl Default for ExtractionSummary {
@@ -110,6 +258,10 @@ impl ExtractionSummary {
             total_size: 0,
             errors: Vec::new(),
             extracted_files: Vec::new(),
+            quarantined: Vec::new(),
+            skipped: Vec::new(),
+            failed: Vec::new(),
+            raw_names: Vec::new(),
         }
     }
 
@@ -122,6 +274,80 @@ impl ExtractionSummary {
         self.extracted_files.push(path);
     }
 
+    /**
+     * 隔离不安全的条目（如 `../../etc/passwd`）
+     */
+    pub fn quarantine(
+        &mut self,
+        entry_name: impl Into<String>,
+        reason: impl Into<String>,
+        size: u64,
+    ) {
+        let entry_name = entry_name.into();
+        let reason = reason.into();
+        warn!(entry = %entry_name, reason = %reason, "压缩包条目存在路径穿越风险，已隔离");
+        self.quarantined.push(QuarantinedEntry {
+            entry_name,
+            reason,
+            size,
+        });
+    }
+
+    /**
+     * 记录未被选中的条目
+     */
+    pub fn skip(&mut self, entry_name: impl Into<String>, size: u64) {
+        self.skipped.push(SkippedEntry {
+            entry_name: entry_name.into(),
+            size,
+        });
+    }
+
+    /**
+     * 记录条目名解码前的原始字节
+     */
+    pub fn record_raw_name(&mut self, entry_name: impl Into<String>, raw_name: Vec<u8>) {
+        self.raw_names.push((entry_name.into(), raw_name));
+    }
+
+    /**
+     * 记录解压失败的条目，同时计入错误列表
+     */
+    pub fn fail(&mut self, entry_name: impl Into<String>, error: impl Into<String>) {
+        let entry_name = entry_name.into();
+        let error = error.into();
+        self.errors
+            .push(format!("Failed to extract entry {entry_name}: {error}"));
+        self.failed.push(FailedEntry { entry_name, error });
+    }
+
+    /**
+     * 删除已提取但未被选中的文件（用于无法流式跳过的格式）
+     */
+    pub fn retain_selected(&mut self, target_dir: &Path, selection: &EntrySelection) {
+        let extracted = std::mem::take(&mut self.extracted_files);
+        for path in extracted {
+            let relative = path.strip_prefix(target_dir).unwrap_or(&path);
+            let entry_name = relative.to_string_lossy().replace('\\', "/");
+            if selection.matches(&entry_name) {
+                self.extracted_files.push(path);
+                continue;
+            }
+            let full_path = if path.is_absolute() {
+                path.clone()
+            } else {
+                target_dir.join(&path)
+            };
+            let size = std::fs::metadata(&full_path).map(|m| m.len()).unwrap_or(0);
+            if let Err(e) = std::fs::remove_file(&full_path) {
+                warn!(path = %full_path.display(), error = %e, "删除未选中的条目失败");
+            }
+            self.files_extracted = self.files_extracted.saturating_sub(1);
+            self.total_size = self.total_size.saturating_sub(size);
+            self.skip(entry_name, size);
+        }
+    }
+
     /**
      * 添加错误信息
      */
@@ -221,6 +447,14 @@ mod tests {
         assert!((rate - 66.67).abs() < 0.01); // 使用浮点数精度比较
     }
 
+    #[test]
+    fn test_archive_password_is_redacted() {
+        let password = ArchivePassword::new("s3cret");
+        assert_eq!(password.as_bytes(), b"s3cret");
+        assert!(!format!("{password:?}").contains("s3cret"));
+        assert_eq!(serde_json::to_string(&password).unwrap(), "\"s3cret\"");
+    }
+
     #[test]
     fn test_extraction_error() {
         let error = ExtractionError::new("Extract failed".to_string())
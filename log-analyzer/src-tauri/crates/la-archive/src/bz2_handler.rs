@@ -0,0 +1,85 @@
+use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
+use crate::gz_handler::{decompressed_file_name, open_for_streaming, stream_decompress};
+use crate::tar_handler::has_tar_stem;
+use async_compression::tokio::bufread::BzDecoder;
+use async_trait::async_trait;
+use la_core::error::{AppError, Result};
+use std::path::Path;
+
+/**
+ * BZ2文件处理器
+ *
+ * 处理单个 bzip2 压缩文件（`.bz2`），解压为单个文件；`.tar.bz2` / `.tbz2` 由 TarHandler 处理。
+ *
+ * 使用流式解压，与 GzHandler 的流式模式共用单文件大小限制与解压炸弹检测。
+ */
+pub struct Bz2Handler;
+
+#[async_trait]
+impl ArchiveHandler for Bz2Handler {
+    fn can_handle(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|s| s.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("bz2") && !has_tar_stem(path))
+            .unwrap_or(false)
+    }
+
+    async fn extract_with_limits(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+    ) -> Result<ExtractionSummary> {
+        // 安全检查：文件数量限制（BZ2只包含一个文件）
+        if max_file_count < 1 {
+            return Err(AppError::archive_error(
+                format!("Extraction would exceed file count limit of {max_file_count} files"),
+                Some(source.to_path_buf()),
+            ));
+        }
+
+        let reader = open_for_streaming(source, target_dir, "BZ2").await?;
+        let mut decoder = BzDecoder::new(reader);
+        // pbzip2 会写出多个连续流
+        decoder.multiple_members(true);
+        let output_path = target_dir.join(decompressed_file_name(source, "bz2"));
+        stream_decompress(
+            decoder,
+            source,
+            output_path,
+            max_file_size.min(max_total_size),
+            "bzip2",
+        )
+        .await
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec!["bz2"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_extract_bz2_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("messages.bz2");
+        let content = b"2024-01-01 INFO rotated\n".repeat(100);
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(&content).unwrap();
+        std::fs::write(&source, encoder.finish().unwrap()).unwrap();
+
+        let output_dir = temp_dir.path().join("out");
+        let summary = Bz2Handler.extract(&source, &output_dir).await.unwrap();
+
+        assert_eq!(summary.files_extracted, 1);
+        assert_eq!(std::fs::read(output_dir.join("messages")).unwrap(), content);
+        assert!(!Bz2Handler.can_handle(Path::new("bundle.tar.bz2")));
+    }
+}
@@ -0,0 +1,325 @@
+//! 导入前的目录扫描
+//!
+//! NAS 上数百万文件的目录，单是递归遍历就要很久。导入目录前先在阻塞线程中遍历一次，
+//! 实时累计已访问的目录数与发现的文件数（供任务进度展示），并在每个目录项之间检查
+//! 取消令牌。扫描记下遍历到的目录项，处理阶段按相同顺序回放而不再遍历一次，
+//! 文件数直接作为导入进度的分母（见 `CasProcessingContext::with_prescanned`）。
+//!
+//! 遍历方式（符号链接策略、最大深度）与处理阶段一致。
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use la_core::error::{AppError, ErrorCategory, Result};
+use la_core::models::SymlinkPolicy;
+use la_core::traits::AppConfigProvider;
+use tokio_util::sync::CancellationToken;
+use walkdir::WalkDir;
+
+use crate::processor::{directory_max_depth, load_extraction_policy_safe, resolve_symlink_policy};
+
+/// 扫描进行中的计数，扫描线程写入、进度上报方读取
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    directories: AtomicUsize,
+    files: AtomicUsize,
+}
+
+impl ScanProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// (已访问的目录数, 已发现的文件数)
+    pub fn counts(&self) -> (usize, usize) {
+        (
+            self.directories.load(Ordering::Relaxed),
+            self.files.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// 扫描结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanSummary {
+    /// 已访问的目录数（含根目录）
+    pub directories: usize,
+    /// 发现的文件数（含符号链接等非目录项）
+    pub files: usize,
+    /// 无法读取而跳过的目录项数
+    pub errors: usize,
+}
+
+/// 遍历到的一个目录项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryEntry {
+    pub path: PathBuf,
+    /// 是否为目录（跟随符号链接时为链接目标的类型）
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+impl DirectoryEntry {
+    fn from_walkdir(entry: &walkdir::DirEntry) -> Self {
+        Self {
+            path: entry.path().to_path_buf(),
+            is_dir: entry.file_type().is_dir(),
+            is_symlink: entry.path_is_symlink(),
+        }
+    }
+}
+
+/// 目录项，或无法读取的目录项的错误信息
+pub type WalkedEntry = std::result::Result<DirectoryEntry, String>;
+
+/// 扫描结果：统计与按遍历顺序记下的目录项
+#[derive(Debug, Clone, Default)]
+pub struct ScannedDirectory {
+    pub root: PathBuf,
+    pub summary: ScanSummary,
+    entries: Vec<WalkedEntry>,
+}
+
+impl ScannedDirectory {
+    pub fn entries(&self) -> &[WalkedEntry] {
+        &self.entries
+    }
+}
+
+/// 处理阶段的目录遍历：有扫描结果时按扫描顺序回放，否则现场遍历
+pub(crate) enum DirectoryWalk {
+    Live(walkdir::IntoIter),
+    Replay {
+        scanned: Arc<ScannedDirectory>,
+        next: usize,
+        /// `skip_current_dir` 跳过的目录，其下的目录项不再返回
+        skipped: Option<PathBuf>,
+    },
+}
+
+impl DirectoryWalk {
+    pub(crate) fn live(root: &Path, follow_links: bool, max_depth: usize) -> Self {
+        Self::Live(walker(root, follow_links, max_depth).into_iter())
+    }
+
+    pub(crate) fn replay(scanned: Arc<ScannedDirectory>) -> Self {
+        Self::Replay {
+            scanned,
+            next: 0,
+            skipped: None,
+        }
+    }
+
+    /// 不再进入刚返回的目录（与 `walkdir::IntoIter::skip_current_dir` 相同，只在
+    /// 刚返回目录项时调用）
+    pub(crate) fn skip_current_dir(&mut self) {
+        match self {
+            Self::Live(iter) => iter.skip_current_dir(),
+            Self::Replay {
+                scanned,
+                next,
+                skipped,
+            } => {
+                *skipped = next
+                    .checked_sub(1)
+                    .and_then(|i| scanned.entries[i].as_ref().ok())
+                    .map(|entry| entry.path.clone());
+            }
+        }
+    }
+}
+
+impl Iterator for DirectoryWalk {
+    type Item = WalkedEntry;
+
+    fn next(&mut self) -> Option<WalkedEntry> {
+        match self {
+            Self::Live(iter) => iter.next().map(|entry| {
+                entry
+                    .map(|entry| DirectoryEntry::from_walkdir(&entry))
+                    .map_err(|e| e.to_string())
+            }),
+            Self::Replay {
+                scanned,
+                next,
+                skipped,
+            } => loop {
+                let entry = scanned.entries.get(*next)?.clone();
+                *next += 1;
+                // 深度优先遍历：被跳过目录下的目录项连续出现
+                match (&entry, skipped.as_deref()) {
+                    (Ok(e), Some(dir)) if e.path.starts_with(dir) => continue,
+                    (Err(_), Some(_)) => continue,
+                    _ => *skipped = None,
+                }
+                return Some(entry);
+            },
+        }
+    }
+}
+
+fn walker(root: &Path, follow_links: bool, max_depth: usize) -> WalkDir {
+    WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .follow_links(follow_links)
+}
+
+/// 按导入配置的符号链接策略扫描 `root`；`cancel` 被取消时尽快返回取消错误
+pub async fn scan_import_directory(
+    root: &Path,
+    provider: &dyn AppConfigProvider,
+    progress: Arc<ScanProgress>,
+    cancel: CancellationToken,
+) -> Result<ScannedDirectory> {
+    let policy = resolve_symlink_policy(&load_extraction_policy_safe(provider));
+    let follow_links = policy == SymlinkPolicy::FollowWithinRoot;
+    let root = root.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        scan_directory(
+            &root,
+            follow_links,
+            directory_max_depth(),
+            &progress,
+            &cancel,
+        )
+    })
+    .await
+    .map_err(|e| AppError::internal_error(format!("Directory scan task failed: {e}")))?
+}
+
+/// 同步遍历 `root`，每个目录项更新 `progress` 并检查 `cancel`
+pub fn scan_directory(
+    root: &Path,
+    follow_links: bool,
+    max_depth: usize,
+    progress: &ScanProgress,
+    cancel: &CancellationToken,
+) -> Result<ScannedDirectory> {
+    let mut summary = ScanSummary {
+        directories: 1,
+        ..ScanSummary::default()
+    };
+    let mut entries = Vec::new();
+    progress.directories.store(1, Ordering::Relaxed);
+
+    for entry in walker(root, follow_links, max_depth) {
+        if cancel.is_cancelled() {
+            return Err(scan_cancelled(root));
+        }
+        match entry {
+            Ok(entry) => {
+                let entry = DirectoryEntry::from_walkdir(&entry);
+                if entry.is_dir {
+                    summary.directories += 1;
+                    progress.directories.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    summary.files += 1;
+                    progress.files.fetch_add(1, Ordering::Relaxed);
+                }
+                entries.push(Ok(entry));
+            }
+            Err(e) => {
+                tracing::debug!(
+                    root = %root.display(),
+                    error = %e,
+                    "Skipping unreadable entry during scan"
+                );
+                summary.errors += 1;
+                entries.push(Err(e.to_string()));
+            }
+        }
+    }
+    Ok(ScannedDirectory {
+        root: root.to_path_buf(),
+        summary,
+        entries,
+    })
+}
+
+fn scan_cancelled(root: &Path) -> AppError {
+    AppError::Archive {
+        category: ErrorCategory::CancellationRequested,
+        _message: "Directory scan cancelled".to_string(),
+        _path: Some(PathBuf::from(root)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_counts_directories_and_files_and_honours_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::create_dir_all(dir.path().join("c")).unwrap();
+        for file in ["root.log", "a/one.log", "a/b/two.log", "a/b/three.gz"] {
+            std::fs::write(dir.path().join(file), b"x").unwrap();
+        }
+
+        let progress = ScanProgress::new();
+        let cancel = CancellationToken::new();
+        let scanned = scan_directory(dir.path(), false, usize::MAX, &progress, &cancel).unwrap();
+        assert_eq!(
+            scanned.summary,
+            ScanSummary {
+                directories: 4,
+                files: 4,
+                errors: 0
+            }
+        );
+        assert_eq!(progress.counts(), (4, 4));
+        assert_eq!(scanned.entries().len(), 7);
+
+        cancel.cancel();
+        let err = scan_directory(dir.path(), false, usize::MAX, &ScanProgress::new(), &cancel)
+            .unwrap_err();
+        assert_eq!(err.category(), ErrorCategory::CancellationRequested);
+    }
+
+    #[test]
+    fn test_replay_matches_live_walk_and_skips_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::create_dir_all(dir.path().join("c")).unwrap();
+        for file in ["a/one.log", "a/b/two.log", "c/three.log", "root.log"] {
+            std::fs::write(dir.path().join(file), b"x").unwrap();
+        }
+        let scanned = Arc::new(
+            scan_directory(
+                dir.path(),
+                false,
+                usize::MAX,
+                &ScanProgress::new(),
+                &CancellationToken::new(),
+            )
+            .unwrap(),
+        );
+
+        // 按相同规则跳过名为 `a` 的目录，回放与现场遍历返回相同的目录项
+        let visit = |mut walk: DirectoryWalk| {
+            let mut files = Vec::new();
+            while let Some(entry) = walk.next() {
+                let entry = entry.unwrap();
+                if entry.is_dir && entry.path.ends_with("a") {
+                    walk.skip_current_dir();
+                } else if !entry.is_dir {
+                    files.push(entry.path);
+                }
+            }
+            files.sort();
+            files
+        };
+        let replayed = visit(DirectoryWalk::replay(Arc::clone(&scanned)));
+        assert_eq!(
+            replayed,
+            visit(DirectoryWalk::live(dir.path(), false, usize::MAX))
+        );
+        assert_eq!(
+            replayed,
+            vec![dir.path().join("c/three.log"), dir.path().join("root.log")]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! 压缩包条目名的编码识别
+//!
+//! 中文 / 日文 Windows 上创建的 ZIP、RAR 常以本地代码页（GBK、Shift-JIS）保存条目名，
+//! 且不设置 UTF-8 标志；`zip` 库会按 CP437 解码，得到乱码的虚拟路径。这里依次尝试
+//! UTF-8、GBK 与 Shift-JIS，在两种双字节编码都能解码时按字符分布选择更可信的一种。
+//!
+//! 解码结果只用于展示和虚拟路径；原始字节由调用方另行保存（见
+//! `MetadataStore::record_raw_entry_names`），以便之后按原始名称定位条目。
+
+use encoding_rs::{GBK, SHIFT_JIS, WINDOWS_1252};
+
+/// 以 UTF-8、GBK 或 Shift-JIS 解码条目名；都不能无损解码时返回 `None`
+pub fn decode_legacy_name(raw: &[u8]) -> Option<String> {
+    if let Ok(name) = std::str::from_utf8(raw) {
+        return Some(name.to_string());
+    }
+    let decode = |encoding: &'static encoding_rs::Encoding| {
+        encoding
+            .decode_without_bom_handling_and_without_replacement(raw)
+            .map(|name| name.into_owned())
+    };
+    match (decode(GBK), decode(SHIFT_JIS)) {
+        // 两者都能解码时优先 GBK，除非 Shift-JIS 的结果明显更像日文
+        (Some(gbk), Some(sjis)) => Some(if plausibility(&sjis) > plausibility(&gbk) {
+            sjis
+        } else {
+            gbk
+        }),
+        (gbk, sjis) => gbk.or(sjis),
+    }
+}
+
+/// 解码条目名，无法识别时按 Windows-1252 解码（不会失败）
+pub fn decode_entry_name(raw: &[u8]) -> String {
+    decode_legacy_name(raw).unwrap_or_else(|| WINDOWS_1252.decode(raw).0.into_owned())
+}
+
+/// ZIP 条目名：`decoded` 为 `zip` 库给出的名称，`raw` 为原始字节
+///
+/// 条目带 UTF-8 标志或 Unicode 路径扩展字段时 `zip` 库已正确解码，直接使用；
+/// 否则库按 CP437 逐字节解码（字符数与字节数相同），改用 [`decode_legacy_name`]。
+/// 返回值第二项为需要保存的原始字节（名称不是原样 UTF-8 时）。
+pub fn zip_entry_name(decoded: &str, raw: &[u8]) -> (String, Option<Vec<u8>>) {
+    if raw.is_ascii() || std::str::from_utf8(raw) == Ok(decoded) {
+        return (decoded.to_string(), None);
+    }
+    if decoded.chars().count() != raw.len() {
+        return (decoded.to_string(), Some(raw.to_vec()));
+    }
+    let name = decode_legacy_name(raw).unwrap_or_else(|| decoded.to_string());
+    (name, Some(raw.to_vec()))
+}
+
+/// 还原被逐字节宽化的 RAR 条目名
+///
+/// 旧版 RAR 只保存 OEM 代码页的名称，libunrar 在 UTF-8 环境下无法转换时会把每个
+/// 非 ASCII 字节映射为 U+E080..U+E0FF 的私用区字符。能还原出原始字节且识别为
+/// GBK / Shift-JIS 时返回解码后的名称与原始字节；名称正常时返回 `None`。
+pub fn repair_widened_name(name: &str) -> Option<(String, Vec<u8>)> {
+    let raw = name
+        .chars()
+        .map(|c| match c as u32 {
+            code @ 0..=0x7f => Some(code as u8),
+            code @ 0xe080..=0xe0ff => Some((code - 0xe000) as u8),
+            _ => None,
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    if raw.is_ascii() {
+        return None;
+    }
+    decode_legacy_name(&raw).map(|decoded| (decoded, raw))
+}
+
+/// 名称像自然文本的程度：假名强烈指向日文，半角片假名与控制字符多半是误解码
+fn plausibility(name: &str) -> i32 {
+    name.chars()
+        .map(|c| match c {
+            '\u{3040}'..='\u{30ff}' => 2,
+            '\u{3000}'..='\u{303f}' | '\u{4e00}'..='\u{9fff}' | '\u{ff01}'..='\u{ff5e}' => 1,
+            '\u{ff61}'..='\u{ff9f}' => -2,
+            '\u{e000}'..='\u{f8ff}' => -3,
+            c if c.is_control() => -3,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_gbk_and_shift_jis_names() {
+        assert_eq!(
+            decode_legacy_name(b"logs/\xc4\xe3\xba\xc3.log").as_deref(),
+            Some("logs/你好.log")
+        );
+        // Shift-JIS 的「ログ」同样是合法的 GBK 字节，按假名判定为日文
+        assert_eq!(
+            decode_legacy_name(b"\x83\x8d\x83\x4f.txt").as_deref(),
+            Some("ログ.txt")
+        );
+        assert_eq!(
+            decode_legacy_name("日志.log".as_bytes()).as_deref(),
+            Some("日志.log")
+        );
+        assert_eq!(decode_entry_name(b"caf\xe9"), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_zip_entry_name_keeps_names_the_library_decoded() {
+        assert_eq!(
+            zip_entry_name("app.log", b"app.log"),
+            ("app.log".into(), None)
+        );
+        assert_eq!(
+            zip_entry_name("日志.log", "日志.log".as_bytes()),
+            ("日志.log".into(), None)
+        );
+        // CP437 解码：每个字节一个字符
+        let raw = b"\xc4\xe3\xba\xc3.log";
+        assert_eq!(
+            zip_entry_name("─π║├.log", raw),
+            ("你好.log".into(), Some(raw.to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_repairs_bytewise_widened_rar_names() {
+        assert_eq!(
+            repair_widened_name("\u{e0c4}\u{e0e3}\u{e0ba}\u{e0c3}.log"),
+            Some(("你好.log".into(), b"\xc4\xe3\xba\xc3.log".to_vec()))
+        );
+        assert_eq!(repair_widened_name("app.log"), None);
+        assert_eq!(repair_widened_name("日志.log"), None);
+        assert_eq!(repair_widened_name("caf\u{e9}.log"), None);
+    }
+}
@@ -0,0 +1,243 @@
+//! 重试导入时失败的压缩包条目
+//!
+//! 解压或入库时因瞬时 IO 错误失败的条目记录在元数据的 `failed_entries` 中，
+//! 不会中断整个压缩包的导入。重试时从 CAS 中保存的父压缩包流式取出该条目，
+//! 按导入时的虚拟路径与深度重新处理：成功后删除记录，仍失败则更新错误信息。
+//!
+//! - 虚拟路径 = 压缩包虚拟路径 + `/` + 条目名（与导入时的拼接规则一致）
+//! - 父压缩包损坏、为 RAR/7Z（不支持流式读取）或条目已找不到时无法重试
+
+use std::path::PathBuf;
+
+use la_core::error::{AppError, Result};
+use la_core::traits::AppConfigProvider;
+use la_core::utils::path::normalize_path_separator;
+use la_storage::FailedEntryRecord;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::processor::{process_path_with_cas_and_checkpoints, CasProcessingContext};
+
+/// 一次重试的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryReport {
+    /// 尝试重试的条目数
+    pub retried: usize,
+    /// 成功导入的条目虚拟路径
+    pub recovered: Vec<String>,
+    /// 仍然失败的条目（附最新的错误）
+    pub still_failed: Vec<FailedEntryRecord>,
+}
+
+/// 重新处理所有记录为失败的条目
+pub async fn retry_failed_entries(
+    context: &CasProcessingContext,
+    provider: &dyn AppConfigProvider,
+    task_id: &str,
+    workspace_id: &str,
+) -> Result<RetryReport> {
+    let failed = context.metadata_store.get_failed_entries().await?;
+    let mut report = RetryReport {
+        retried: failed.len(),
+        ..Default::default()
+    };
+
+    for record in failed {
+        match retry_entry(context, provider, task_id, workspace_id, &record).await {
+            Ok(virtual_path) => {
+                context
+                    .metadata_store
+                    .remove_failed_entry(record.archive_id, &record.entry_name)
+                    .await?;
+                info!(archive = %record.archive_virtual_path, entry = %record.entry_name, "Recovered failed archive entry");
+                report.recovered.push(virtual_path);
+            }
+            Err(e) => {
+                warn!(archive = %record.archive_virtual_path, entry = %record.entry_name, error = %e, "Retry of failed archive entry failed");
+                let error = e.to_string();
+                context
+                    .metadata_store
+                    .record_failed_entries(
+                        record.archive_id,
+                        &[(record.entry_name.clone(), error.clone())],
+                    )
+                    .await?;
+                report
+                    .still_failed
+                    .push(FailedEntryRecord { error, ..record });
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// 从父压缩包取出条目并重新导入，返回条目的虚拟路径
+async fn retry_entry(
+    context: &CasProcessingContext,
+    provider: &dyn AppConfigProvider,
+    task_id: &str,
+    workspace_id: &str,
+    record: &FailedEntryRecord,
+) -> Result<String> {
+    let archive = context
+        .metadata_store
+        .get_archive_by_id(record.archive_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Parent archive no longer exists"))?;
+
+    if !context.cas.verify_integrity(&archive.sha256_hash).await? {
+        return Err(AppError::archive_error(
+            "Parent archive is corrupted",
+            Some(context.cas.get_object_path(&archive.sha256_hash)),
+        ));
+    }
+
+    // 暂存文件沿用条目的文件名，处理器据此识别嵌套压缩包并记录原始文件名
+    let temp_root: PathBuf = context.cas.objects_dir().with_file_name("tmp");
+    let file_name = record
+        .entry_name
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&record.entry_name)
+        .to_string();
+    let archive_path = context.cas.get_object_path(&archive.sha256_hash);
+    let archive_name = archive.original_name.clone();
+    let entry_name = record.entry_name.clone();
+    let raw_name = context
+        .metadata_store
+        .get_raw_entry_name(record.archive_id, &record.entry_name)
+        .await?;
+    let (staging_dir, staged_path) = tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&temp_root)
+            .map_err(|e| AppError::io_error(e.to_string(), Some(temp_root.clone())))?;
+        let staging_dir = tempfile::TempDir::new_in(&temp_root)
+            .map_err(|e| AppError::io_error(e.to_string(), Some(temp_root.clone())))?;
+        let staged_path = staging_dir.path().join(&file_name);
+        let mut out = std::fs::File::create(&staged_path)
+            .map_err(|e| AppError::io_error(e.to_string(), Some(staged_path.clone())))?;
+        let found = crate::entry_stream::copy_entry(
+            &archive_path,
+            &archive_name,
+            &entry_name,
+            raw_name.as_deref(),
+            &mut out,
+        )?;
+        if !found {
+            return Err(AppError::not_found(format!(
+                "Entry {entry_name} not found in the archive"
+            )));
+        }
+        Ok((staging_dir, staged_path))
+    })
+    .await
+    .map_err(|e| AppError::internal_error(format!("Entry staging task failed: {e}")))??;
+
+    let virtual_path = format!("{}/{}", archive.virtual_path, record.entry_name);
+    process_path_with_cas_and_checkpoints(
+        &staged_path,
+        &virtual_path,
+        context,
+        provider,
+        task_id,
+        workspace_id,
+        Some(archive.id),
+        archive.depth_level + 1,
+    )
+    .await?;
+    drop(staging_dir);
+
+    Ok(normalize_path_separator(&virtual_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use la_storage::{ArchiveMetadata, ContentAddressableStorage, MetadataStore};
+    use std::io::Write;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    struct TestConfigProvider {
+        dir: PathBuf,
+    }
+
+    impl AppConfigProvider for TestConfigProvider {
+        fn config_dir(&self) -> std::result::Result<PathBuf, String> {
+            Ok(self.dir.clone())
+        }
+    }
+
+    fn zip_bytes(name: &str, content: &[u8]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options: zip::write::FileOptions<'_, ()> = zip::write::FileOptions::default();
+        writer.start_file(name, options).unwrap();
+        writer.write_all(content).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_retry_imports_failed_entry_from_parent_archive() {
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+
+        let content = b"2024-01-01 00:00:00 ERROR disk full\n".repeat(100);
+        let archive_hash = cas
+            .store_content(&zip_bytes("logs/app.log", &content))
+            .await
+            .unwrap();
+        let archive_id = metadata
+            .insert_archive(&ArchiveMetadata {
+                id: 0,
+                sha256_hash: archive_hash,
+                virtual_path: "upload/bundle.zip".into(),
+                original_name: "bundle.zip".into(),
+                archive_type: "zip".into(),
+                parent_archive_id: None,
+                depth_level: 0,
+                extraction_status: "completed".into(),
+            })
+            .await
+            .unwrap();
+        metadata
+            .record_failed_entries(
+                archive_id,
+                &[
+                    ("logs/app.log".into(), "Input/output error".into()),
+                    ("logs/gone.log".into(), "Input/output error".into()),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let context =
+            CasProcessingContext::new(workspace_dir, Arc::clone(&cas), Arc::clone(&metadata));
+        let provider = TestConfigProvider {
+            dir: temp.path().join("config"),
+        };
+        let report = retry_failed_entries(&context, &provider, "retry", "ws")
+            .await
+            .unwrap();
+
+        assert_eq!(report.retried, 2);
+        assert_eq!(report.recovered, vec!["upload/bundle.zip/logs/app.log"]);
+        assert_eq!(report.still_failed.len(), 1);
+        assert!(report.still_failed[0].error.contains("not found"));
+
+        let file = metadata
+            .get_file_by_virtual_path("upload/bundle.zip/logs/app.log")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(file.parent_archive_id, Some(archive_id));
+        assert_eq!(file.depth_level, 1);
+        assert_eq!(cas.read_content(&file.sha256_hash).await.unwrap(), content);
+
+        let remaining = metadata.get_failed_entries().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].entry_name, "logs/gone.log");
+    }
+}
@@ -0,0 +1,294 @@
+//! 选择性解压：只提取压缩包中选定的条目
+//!
+//! 大型支持包（上百 GB）通常只有少数服务的日志有用。导入时可为指定压缩包
+//! 提供包含列表，未命中的条目不写入磁盘、不进入 CAS 与索引，仅记录为已跳过。
+//!
+//! 也可以为整个导入提供包含 / 排除列表（[`EntrySelection::filter`]），作用于所有
+//! 未单独列出的压缩包（含嵌套压缩包）。此时嵌套压缩包本身不受包含列表限制，
+//! 以便继续在其中筛选；排除列表对压缩包同样生效。
+//!
+//! 模式语法（匹配压缩包内以 `/` 分隔的相对路径，大小写敏感）：
+//! - `*` 匹配单个路径段内的任意字符，`?` 匹配单个字符
+//! - `**` 匹配任意层级（`services/**/app.log`）
+//! - 不含通配符的模式匹配同名文件或该目录下的所有条目（`services/api`）
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 单个压缩包的包含列表（前端传入的原始形式）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSelection {
+    /// 压缩包的虚拟路径；也可只写导入根下的相对路径或文件名
+    pub archive: String,
+    /// 要提取的条目路径或 glob
+    pub include: Vec<String>,
+}
+
+/// 作用于整个导入的包含 / 排除列表（前端传入的原始形式）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryFilter {
+    /// 要提取的条目路径或 glob；为空表示全部
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// 不提取的条目路径或 glob，优先于包含列表
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// 嵌套压缩包的后缀（全局过滤时不受包含列表限制）
+const ARCHIVE_SUFFIXES: &[&str] = &[
+    ".zip", ".rar", ".7z", ".tar", ".tgz", ".tzst", ".txz", ".tbz2", ".tbz", ".gz", ".zst", ".xz",
+    ".bz2", ".lz4",
+];
+
+/// 编译后的条目选择：包含列表（为空表示全部）与排除列表
+#[derive(Debug, Clone)]
+pub struct EntrySelection {
+    patterns: Vec<Regex>,
+    exclude: Vec<Regex>,
+    /// 压缩包条目不受包含列表限制（全局过滤）
+    keep_archives: bool,
+}
+
+impl EntrySelection {
+    /// 编译包含列表；存在无效模式时返回错误
+    pub fn new(include: &[String]) -> Result<Self, String> {
+        let patterns = compile_patterns(include, "include")?;
+        if patterns.is_empty() {
+            return Err("Include list must contain at least one pattern".to_string());
+        }
+        Ok(Self {
+            patterns,
+            exclude: Vec::new(),
+            keep_archives: false,
+        })
+    }
+
+    /// 编译作用于整个导入的包含 / 排除列表；两者都为空时返回 `None`（不过滤）
+    pub fn filter(include: &[String], exclude: &[String]) -> Result<Option<Self>, String> {
+        let patterns = compile_patterns(include, "include")?;
+        let exclude = compile_patterns(exclude, "exclude")?;
+        if patterns.is_empty() && exclude.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            patterns,
+            exclude,
+            keep_archives: true,
+        }))
+    }
+
+    /// 条目（压缩包内的相对路径）是否被选中
+    pub fn matches(&self, entry_path: &str) -> bool {
+        let normalized = normalize_entry_path(entry_path);
+        if self.exclude.iter().any(|p| p.is_match(&normalized)) {
+            return false;
+        }
+        if self.patterns.is_empty() || (self.keep_archives && is_archive_name(&normalized)) {
+            return true;
+        }
+        self.patterns.iter().any(|p| p.is_match(&normalized))
+    }
+}
+
+fn compile_patterns(patterns: &[String], kind: &str) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            Regex::new(&pattern_to_regex(p))
+                .map_err(|e| format!("Invalid {kind} pattern '{p}': {e}"))
+        })
+        .collect()
+}
+
+fn is_archive_name(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    ARCHIVE_SUFFIXES
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+}
+
+/// 一次导入中所有压缩包的包含列表
+#[derive(Debug, Clone, Default)]
+pub struct ExtractionSelection {
+    archives: Vec<(String, EntrySelection)>,
+    /// 未单独列出的压缩包使用的全局过滤
+    filter: Option<EntrySelection>,
+}
+
+impl ExtractionSelection {
+    pub fn new(selections: &[ArchiveSelection]) -> Result<Self, String> {
+        let archives = selections
+            .iter()
+            .map(|s| {
+                let archive = normalize_entry_path(&s.archive);
+                if archive.is_empty() {
+                    return Err("Archive path in selection must not be empty".to_string());
+                }
+                Ok((archive, EntrySelection::new(&s.include)?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            archives,
+            filter: None,
+        })
+    }
+
+    /// 为未单独列出的压缩包设置全局过滤（见 [`EntrySelection::filter`]）
+    pub fn with_filter(mut self, filter: Option<EntrySelection>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.archives.is_empty() && self.filter.is_none()
+    }
+
+    /// 查找压缩包对应的包含列表；未列出的压缩包使用全局过滤，没有全局过滤时完整提取
+    ///
+    /// 依次匹配完整虚拟路径、以 `/<archive>` 结尾的路径（相对路径或文件名）。
+    pub fn for_archive(&self, virtual_path: &str) -> Option<&EntrySelection> {
+        let virtual_path = normalize_entry_path(virtual_path);
+        self.archives
+            .iter()
+            .find(|(archive, _)| *archive == virtual_path)
+            .or_else(|| {
+                self.archives.iter().find(|(archive, _)| {
+                    virtual_path
+                        .strip_suffix(archive.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('/'))
+                })
+            })
+            .map(|(_, selection)| selection)
+            .or(self.filter.as_ref())
+    }
+}
+
+fn normalize_entry_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path.trim_start_matches("./").trim_matches('/');
+    path.to_string()
+}
+
+fn pattern_to_regex(pattern: &str) -> String {
+    let pattern = normalize_entry_path(pattern);
+    let has_wildcard = pattern.contains(['*', '?']);
+    let mut out = String::with_capacity(pattern.len() * 2 + 8);
+    out.push('^');
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` 也匹配零层目录
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    out.push_str("(?:.*/)?");
+                } else {
+                    out.push_str(".*");
+                }
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    if !has_wildcard {
+        // 目录前缀选中其下全部条目
+        out.push_str("(?:/.*)?");
+    }
+    out.push('$');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(patterns: &[&str]) -> EntrySelection {
+        EntrySelection::new(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn test_entry_patterns() {
+        let sel = selection(&["services/api", "logs/*.log", "**/gateway/*.txt"]);
+        assert!(sel.matches("services/api/app.log"));
+        assert!(sel.matches("./services/api"));
+        assert!(!sel.matches("services/api-v2/app.log"));
+        assert!(sel.matches("logs/a.log"));
+        assert!(!sel.matches("logs/old/a.log"));
+        assert!(sel.matches("gateway/out.txt"));
+        assert!(sel.matches("node1/var/gateway/out.txt"));
+        assert!(!sel.matches("node1/var/gateway/out.log"));
+
+        assert!(EntrySelection::new(&[]).is_err());
+        assert!(EntrySelection::new(&[" ".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_archive_lookup() {
+        let selections = ExtractionSelection::new(&[
+            ArchiveSelection {
+                archive: "bundle/big.tar.gz".into(),
+                include: vec!["api/**".into()],
+            },
+            ArchiveSelection {
+                archive: "inner.zip".into(),
+                include: vec!["*.log".into()],
+            },
+        ])
+        .unwrap();
+
+        assert!(selections.for_archive("bundle/big.tar.gz").is_some());
+        assert!(selections.for_archive("import/bundle/big.tar.gz").is_some());
+        assert!(selections
+            .for_archive("import/bundle/big.tar.gz/x/inner.zip")
+            .is_some());
+        assert!(selections.for_archive("import/notinner.zip").is_none());
+        assert!(selections.for_archive("import/other.tar").is_none());
+    }
+
+    #[test]
+    fn test_import_wide_filter() {
+        let to_vec = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let filter = EntrySelection::filter(
+            &to_vec(&["**/*.log", "**/var/log/**"]),
+            &to_vec(&["**/debug/**"]),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(filter.matches("node1/app.log"));
+        assert!(filter.matches("node1/var/log/messages"));
+        assert!(!filter.matches("node1/bin/server"));
+        assert!(!filter.matches("node1/screenshot.png"));
+        assert!(!filter.matches("node1/debug/trace.log"));
+        // 嵌套压缩包继续展开筛选，排除列表仍然生效
+        assert!(filter.matches("node1/rotated.tar.gz"));
+        assert!(!filter.matches("node1/debug/dump.zip"));
+
+        let exclude_only = EntrySelection::filter(&[], &to_vec(&["*.png"]))
+            .unwrap()
+            .unwrap();
+        assert!(exclude_only.matches("bin/server"));
+        assert!(!exclude_only.matches("shot.png"));
+        assert!(EntrySelection::filter(&[], &[" ".to_string()])
+            .unwrap()
+            .is_none());
+
+        let selections = ExtractionSelection::new(&[ArchiveSelection {
+            archive: "bundle.zip".into(),
+            include: vec!["api/**".into()],
+        }])
+        .unwrap()
+        .with_filter(Some(filter));
+        // 单独列出的压缩包使用自己的包含列表
+        let listed = selections.for_archive("import/bundle.zip").unwrap();
+        assert!(listed.matches("api/a.png"));
+        let other = selections.for_archive("import/other.zip").unwrap();
+        assert!(!other.matches("api/a.png"));
+    }
+}
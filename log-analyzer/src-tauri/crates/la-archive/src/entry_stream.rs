@@ -0,0 +1,451 @@
+//! 流式遍历压缩包条目（不落盘）
+//!
+//! 供“快速扫描”使用：边解压边把每个条目的内容流交给调用方，不写入磁盘、
+//! 不进入 CAS；导入时也用它把条目直接写入 CAS，省去解压临时目录。支持 ZIP、TAR、以 GZ/ZST/XZ/BZ2 压缩的 TAR、单文件 GZ/ZST/XZ/BZ2/LZ4
+//! 以及普通文件；
+//! RAR/7Z 需要随机访问或外部库，只能走完整导入。
+//!
+//! 嵌套压缩包不递归展开，只记录名称，提升为完整工作区后才会被解压。
+
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use la_core::error::{AppError, Result};
+use serde::Serialize;
+use zip::ZipArchive;
+
+use crate::entry_names::zip_entry_name;
+
+/// 回调的返回值：继续或提前结束遍历
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    Continue,
+    Stop,
+}
+
+/// 遍历统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamStats {
+    /// 交给回调的条目数
+    pub entries_visited: usize,
+    /// 未展开的嵌套压缩包
+    pub nested_archives: Vec<String>,
+    /// 回调要求提前结束
+    pub stopped: bool,
+    /// 名称不是 UTF-8、按 GBK / Shift-JIS 解码的 ZIP 条目：(条目名, 原始字节)
+    #[serde(skip)]
+    pub raw_names: Vec<(String, Vec<u8>)>,
+}
+
+/// 单文件压缩格式
+#[derive(Clone, Copy)]
+enum Codec {
+    Gz,
+    Zst,
+    Xz,
+    Bz2,
+    Lz4,
+}
+
+impl Codec {
+    fn decoder(self, file: File) -> std::io::Result<Box<dyn Read>> {
+        Ok(match self {
+            Codec::Gz => Box::new(GzDecoder::new(BufReader::new(file))),
+            Codec::Zst => Box::new(zstd::stream::read::Decoder::new(file)?),
+            Codec::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(BufReader::new(
+                file,
+            ))),
+            Codec::Bz2 => Box::new(bzip2::read::MultiBzDecoder::new(BufReader::new(file))),
+            Codec::Lz4 => Box::new(lz4::Decoder::new(BufReader::new(file))?),
+        })
+    }
+}
+
+/// 压缩 TAR 的后缀（含 .tgz 之类的缩写）
+const COMPRESSED_TAR_SUFFIXES: [(&str, Codec); 9] = [
+    (".tar.gz", Codec::Gz),
+    (".tgz", Codec::Gz),
+    (".tar.zst", Codec::Zst),
+    (".tzst", Codec::Zst),
+    (".tar.xz", Codec::Xz),
+    (".txz", Codec::Xz),
+    (".tar.bz2", Codec::Bz2),
+    (".tbz2", Codec::Bz2),
+    (".tbz", Codec::Bz2),
+];
+
+/// 单文件压缩的后缀
+const COMPRESSED_FILE_SUFFIXES: [(&str, Codec); 5] = [
+    (".gz", Codec::Gz),
+    (".zst", Codec::Zst),
+    (".xz", Codec::Xz),
+    (".bz2", Codec::Bz2),
+    (".lz4", Codec::Lz4),
+];
+
+enum StreamFormat {
+    Zip,
+    Tar,
+    CompressedTar(Codec),
+    /// 单文件压缩；`usize` 为后缀长度，条目名为去掉后缀的文件名
+    Compressed(Codec, usize),
+    Plain,
+}
+
+fn detect_format(file_name: &str) -> Option<StreamFormat> {
+    let name = file_name.to_lowercase();
+    let suffix = |suffixes: &[(&str, Codec)]| {
+        suffixes
+            .iter()
+            .find(|(suffix, _)| name.ends_with(suffix))
+            .map(|(suffix, codec)| (suffix.len(), *codec))
+    };
+    if name.ends_with(".zip") {
+        Some(StreamFormat::Zip)
+    } else if name.ends_with(".tar") {
+        Some(StreamFormat::Tar)
+    } else if let Some((_, codec)) = suffix(&COMPRESSED_TAR_SUFFIXES) {
+        Some(StreamFormat::CompressedTar(codec))
+    } else if let Some((len, codec)) = suffix(&COMPRESSED_FILE_SUFFIXES) {
+        Some(StreamFormat::Compressed(codec, len))
+    } else if name.ends_with(".rar") || name.ends_with(".7z") {
+        None
+    } else {
+        Some(StreamFormat::Plain)
+    }
+}
+
+/// 是否支持流式遍历
+pub fn can_stream(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| detect_format(&name.to_string_lossy()))
+        .is_some()
+}
+
+/// 条目名是否为（本模块或完整导入支持的）压缩包
+fn is_nested_archive(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    [
+        ".zip", ".tar", ".tgz", ".gz", ".tzst", ".zst", ".txz", ".xz", ".tbz2", ".tbz", ".bz2",
+        ".lz4", ".rar", ".7z",
+    ]
+    .iter()
+    .any(|ext| lower.ends_with(ext))
+}
+
+/// 依次把每个文件条目的名称与内容流交给 `visit`
+///
+/// 条目名为压缩包内以 `/` 分隔的相对路径；单文件压缩的条目名为去掉压缩后缀
+/// （如 `.gz`）后的文件名，普通文件为文件名本身。读取单个条目失败会中止遍历并返回错误。
+pub fn stream_entries<F>(path: &Path, visit: F) -> Result<StreamStats>
+where
+    F: FnMut(&str, &mut dyn Read) -> std::io::Result<Visit>,
+{
+    let file_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    walk_entries(path, &file_name, true, visit)
+}
+
+/// 与 [`stream_entries`] 相同，但嵌套压缩包也交给 `visit`，由调用方决定落盘递归解压还是原样保存
+pub fn stream_entries_with_nested<F>(path: &Path, visit: F) -> Result<StreamStats>
+where
+    F: FnMut(&str, &mut dyn Read) -> std::io::Result<Visit>,
+{
+    let file_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    walk_entries(path, &file_name, false, visit)
+}
+
+/// 把压缩包中名为 `entry_name` 的条目（可为嵌套压缩包）写入 `out`
+///
+/// `archive_name` 决定格式，用于文件名不带扩展名的压缩包（如 CAS 对象）。
+/// `raw_name` 为导入时保存的原始条目名字节（条目名由 GBK / Shift-JIS 解码时才有），
+/// ZIP 优先按它匹配，不受解码规则变化影响。返回是否找到该条目。
+pub fn copy_entry(
+    path: &Path,
+    archive_name: &str,
+    entry_name: &str,
+    raw_name: Option<&[u8]>,
+    out: &mut dyn Write,
+) -> Result<bool> {
+    if let (Some(raw_name), Some(StreamFormat::Zip)) = (raw_name, detect_format(archive_name)) {
+        if copy_zip_entry_by_raw_name(path, raw_name, out)? {
+            return Ok(true);
+        }
+    }
+    let mut found = false;
+    walk_entries(path, archive_name, false, |name, reader| {
+        if name != entry_name {
+            return Ok(Visit::Continue);
+        }
+        std::io::copy(reader, out)?;
+        found = true;
+        Ok(Visit::Stop)
+    })?;
+    Ok(found)
+}
+
+fn copy_zip_entry_by_raw_name(path: &Path, raw_name: &[u8], out: &mut dyn Write) -> Result<bool> {
+    let archive_err =
+        |e: zip::result::ZipError| AppError::archive_error(e.to_string(), Some(path.into()));
+    let file = File::open(path)
+        .map_err(|e| AppError::io_error(format!("Failed to open {e}"), Some(path.to_path_buf())))?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).map_err(archive_err)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(archive_err)?;
+        if !entry.is_dir() && entry.name_raw() == raw_name {
+            std::io::copy(&mut entry, out)
+                .map_err(|e| AppError::archive_error(e.to_string(), Some(path.into())))?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// `skip_nested` 为真时嵌套压缩包只记录名称、不交给回调
+fn walk_entries<F>(
+    path: &Path,
+    file_name: &str,
+    skip_nested: bool,
+    mut visit: F,
+) -> Result<StreamStats>
+where
+    F: FnMut(&str, &mut dyn Read) -> std::io::Result<Visit>,
+{
+    let format = detect_format(file_name).ok_or_else(|| {
+        AppError::archive_error(
+            "Quick scan does not support this archive format; import it instead",
+            Some(path.to_path_buf()),
+        )
+    })?;
+    let file = File::open(path)
+        .map_err(|e| AppError::io_error(format!("Failed to open {e}"), Some(path.to_path_buf())))?;
+
+    let mut stats = StreamStats::default();
+    let io_err = |e: std::io::Error| AppError::archive_error(e.to_string(), Some(path.into()));
+
+    match format {
+        StreamFormat::Zip => {
+            let mut archive = ZipArchive::new(BufReader::new(file))
+                .map_err(|e| AppError::archive_error(e.to_string(), Some(path.into())))?;
+            for i in 0..archive.len() {
+                let mut entry = archive
+                    .by_index(i)
+                    .map_err(|e| AppError::archive_error(e.to_string(), Some(path.into())))?;
+                if entry.is_dir() {
+                    continue;
+                }
+                let (name, raw_name) = zip_entry_name(entry.name(), entry.name_raw());
+                let name = normalize_name(&name);
+                if let Some(raw_name) = raw_name {
+                    stats.raw_names.push((name.clone(), raw_name));
+                }
+                if visit_entry(&mut stats, skip_nested, &name, &mut entry, &mut visit)
+                    .map_err(io_err)?
+                    == Visit::Stop
+                {
+                    break;
+                }
+            }
+        }
+        StreamFormat::Tar => {
+            stream_tar(BufReader::new(file), &mut stats, skip_nested, &mut visit).map_err(io_err)?
+        }
+        StreamFormat::CompressedTar(codec) => stream_tar(
+            codec.decoder(file).map_err(io_err)?,
+            &mut stats,
+            skip_nested,
+            &mut visit,
+        )
+        .map_err(io_err)?,
+        StreamFormat::Compressed(codec, suffix_len) => {
+            let name = file_name
+                .get(..file_name.len() - suffix_len)
+                .unwrap_or(file_name);
+            let mut reader = codec.decoder(file).map_err(io_err)?;
+            visit_entry(&mut stats, skip_nested, name, &mut reader, &mut visit).map_err(io_err)?;
+        }
+        StreamFormat::Plain => {
+            let mut reader = BufReader::new(file);
+            visit_entry(&mut stats, skip_nested, file_name, &mut reader, &mut visit)
+                .map_err(io_err)?;
+        }
+    }
+
+    Ok(stats)
+}
+
+fn stream_tar<R: Read, F>(
+    reader: R,
+    stats: &mut StreamStats,
+    skip_nested: bool,
+    visit: &mut F,
+) -> std::io::Result<()>
+where
+    F: FnMut(&str, &mut dyn Read) -> std::io::Result<Visit>,
+{
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = normalize_name(&entry.path()?.to_string_lossy());
+        if visit_entry(stats, skip_nested, &name, &mut entry, visit)? == Visit::Stop {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn visit_entry<F>(
+    stats: &mut StreamStats,
+    skip_nested: bool,
+    name: &str,
+    reader: &mut dyn Read,
+    visit: &mut F,
+) -> std::io::Result<Visit>
+where
+    F: FnMut(&str, &mut dyn Read) -> std::io::Result<Visit>,
+{
+    if skip_nested && is_nested_archive(name) {
+        stats.nested_archives.push(name.to_string());
+        return Ok(Visit::Continue);
+    }
+    stats.entries_visited += 1;
+    let result = visit(name, reader)?;
+    if result == Visit::Stop {
+        stats.stopped = true;
+    }
+    Ok(result)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.replace('\\', "/")
+        .trim_start_matches("./")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+        let file = File::create(path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_path(name).unwrap();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *content).unwrap();
+        }
+        builder
+            .into_inner()
+            .unwrap()
+            .finish()
+            .unwrap()
+            .flush()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_streams_tar_gz_entries_without_extracting() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("bundle.tar.gz");
+        write_tar_gz(
+            &archive,
+            &[
+                ("./api/app.log", b"line 1\nline 2\n"),
+                ("nested.zip", b"PK"),
+                ("db/slow.log", b"slow\n"),
+            ],
+        );
+
+        let mut seen = Vec::new();
+        let stats = stream_entries(&archive, |name, reader| {
+            let mut content = String::new();
+            reader.read_to_string(&mut content)?;
+            seen.push((name.to_string(), content));
+            Ok(Visit::Continue)
+        })
+        .unwrap();
+
+        assert_eq!(
+            seen,
+            vec![
+                ("api/app.log".to_string(), "line 1\nline 2\n".to_string()),
+                ("db/slow.log".to_string(), "slow\n".to_string()),
+            ]
+        );
+        assert_eq!(stats.entries_visited, 2);
+        assert_eq!(stats.nested_archives, vec!["nested.zip".to_string()]);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let stats = stream_entries(&archive, |_, _| Ok(Visit::Stop)).unwrap();
+        assert!(stats.stopped);
+        assert_eq!(stats.entries_visited, 1);
+
+        assert!(!can_stream(Path::new("a.rar")));
+    }
+
+    #[test]
+    fn test_streams_xz_bz2_and_lz4_sources() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let tar_bz2 = dir.path().join("rotated.tar.bz2");
+        let encoder = bzip2::write::BzEncoder::new(
+            File::create(&tar_bz2).unwrap(),
+            bzip2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_path("var/log/messages").unwrap();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append(&header, &b"boot\n"[..]).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let journal = dir.path().join("system.journal.XZ");
+        let mut encoder = xz2::write::XzEncoder::new(File::create(&journal).unwrap(), 6);
+        encoder.write_all(b"journal\n").unwrap();
+        encoder.finish().unwrap();
+
+        let lz4_log = dir.path().join("app.log.lz4");
+        let mut encoder = lz4::EncoderBuilder::new()
+            .build(File::create(&lz4_log).unwrap())
+            .unwrap();
+        encoder.write_all(b"lz4\n").unwrap();
+        encoder.finish().1.unwrap();
+
+        for (path, expected) in [
+            (&tar_bz2, ("var/log/messages", "boot\n")),
+            (&journal, ("system.journal", "journal\n")),
+            (&lz4_log, ("app.log", "lz4\n")),
+        ] {
+            let mut seen = Vec::new();
+            stream_entries(path, |name, reader| {
+                let mut content = String::new();
+                reader.read_to_string(&mut content)?;
+                seen.push((name.to_string(), content));
+                Ok(Visit::Continue)
+            })
+            .unwrap();
+            assert_eq!(seen, vec![(expected.0.to_string(), expected.1.to_string())]);
+        }
+    }
+}
@@ -4,9 +4,10 @@
 //! plus the extraction context/stack data structures that were previously in a
 //! separate extraction_context module (P10: merge tightly-coupled data structures).
 
+use crate::archive_handler::{ArchivePassword, QuarantinedEntry};
 use crate::path_manager::PathManager;
 use crate::security_detector::SecurityDetector;
-use la_core::error::{AppError, Result};
+use la_core::error::{AppError, ErrorCategory, Result};
 use moka::sync::Cache;
 use sha2::{Digest, Sha256};
 use std::collections::HashSet;
@@ -198,6 +199,8 @@ pub struct ExtractionPolicy {
     pub max_parallel_files: usize,
     /// Maximum number of files per archive (default: 10_000, ZIP bomb 防护)
     pub max_file_count: usize,
+    /// Password for encrypted ZIP/RAR archives (default: None)
+    pub password: Option<ArchivePassword>,
 }
 
 impl Default for ExtractionPolicy {
@@ -210,6 +213,7 @@ impl Default for ExtractionPolicy {
             dir_batch_size: 10,                // Batch 10 directories
             max_parallel_files: 4,             // Extract up to 4 files in parallel
             max_file_count: 10_000,            // 单次压缩包最多解压文件数，与 ExtractionLimits 对齐
+            password: None,
         }
     }
 }
@@ -273,6 +277,9 @@ pub struct ExtractionResult {
     pub path_shortenings_applied: usize,
     /// Number of archives skipped due to depth limit
     pub depth_limit_skips: usize,
+    /// Entries withheld for security reasons (e.g. path traversal); names of
+    /// entries in nested archives are prefixed with the nested archive's path
+    pub quarantined: Vec<QuarantinedEntry>,
     /// Extraction duration in seconds
     pub extraction_duration_secs: f64,
     /// Extraction speed in bytes per second
@@ -584,9 +591,11 @@ impl ExtractionEngine {
             total_bytes: 0,
             path_shortenings_applied: 0,
             depth_limit_skips: 0,
+            quarantined: Vec::new(),
             extraction_duration_secs: 0.0,
             extraction_speed_bytes_per_sec: 0.0,
         };
+        let root_dir = initial_item.target_dir.clone();
 
         // Push initial item onto stack
         stack
@@ -645,7 +654,13 @@ impl ExtractionEngine {
 
             // Process this archive
             match self
-                .process_archive_file(&item, &mut stack, result.total_bytes)
+                .process_archive_file(
+                    &item,
+                    &mut stack,
+                    result.total_bytes,
+                    &root_dir,
+                    &mut result.quarantined,
+                )
                 .await
             {
                 Ok((extracted_files, bytes_extracted, skips, shortenings)) => {
@@ -680,6 +695,11 @@ impl ExtractionEngine {
                     result.extracted_files.extend(extracted_files);
                 }
                 Err(e) => {
+                    // 顶层压缩包缺少密码时整体失败，嵌套压缩包仍按警告处理
+                    if item.depth == 0 && e.category() == ErrorCategory::PasswordRequired {
+                        return Err(e);
+                    }
+
                     // Archive-level error: log warning and continue with other archives in stack
                     warn!(
                         "Archive-level error processing {:?} at depth {}: {}",
@@ -749,6 +769,8 @@ impl ExtractionEngine {
     ///
     /// * `item` - The extraction item to process
     /// * `stack` - The extraction stack for nested archives
+    /// * `root_dir` - Target directory of the top-level archive
+    /// * `quarantined` - Receives the entries the handler withheld
     ///
     /// # Returns
     ///
@@ -762,6 +784,8 @@ impl ExtractionEngine {
         item: &ExtractionItem,
         stack: &mut ExtractionStack,
         current_workspace_bytes: u64,
+        root_dir: &Path,
+        quarantined: &mut Vec<QuarantinedEntry>,
     ) -> Result<(Vec<PathBuf>, u64, usize, usize)> {
         // Ensure target directory exists
         fs::create_dir_all(&item.target_dir).await.map_err(|e| {
@@ -793,21 +817,42 @@ impl ExtractionEngine {
         );
 
         // Extract archive using handler
-        let summary = handler
-            .extract_with_limits(
-                &item.archive_path,
-                &item.target_dir,
-                self.policy.max_file_size,
-                self.policy.max_total_size,
-                self.policy.max_file_count, // 使用策略配置的文件数限制，防止 ZIP bomb
+        let extraction = match &self.policy.password {
+            Some(password) => {
+                handler
+                    .extract_with_password(
+                        &item.archive_path,
+                        &item.target_dir,
+                        self.policy.max_file_size,
+                        self.policy.max_total_size,
+                        self.policy.max_file_count,
+                        None,
+                        password,
+                    )
+                    .await
+            }
+            None => {
+                handler
+                    .extract_with_limits(
+                        &item.archive_path,
+                        &item.target_dir,
+                        self.policy.max_file_size,
+                        self.policy.max_total_size,
+                        self.policy.max_file_count, // 使用策略配置的文件数限制，防止 ZIP bomb
+                    )
+                    .await
+            }
+        };
+        let summary = extraction.map_err(|e| {
+            // 保留密码错误的类别，调用方据此提示用户输入密码
+            if e.category() == ErrorCategory::PasswordRequired {
+                return e;
+            }
+            AppError::archive_error(
+                format!("Failed to extract archive: {e}"),
+                Some(item.archive_path.clone()),
             )
-            .await
-            .map_err(|e| {
-                AppError::archive_error(
-                    format!("Failed to extract archive: {e}"),
-                    Some(item.archive_path.clone()),
-                )
-            })?;
+        })?;
 
         debug!(
             "Extracted {} files ({} bytes) from {:?}",
@@ -831,6 +876,19 @@ impl ExtractionEngine {
             return Err(AppError::security_error(message));
         }
 
+        // Entries of nested archives are named after the archive they came from
+        let nested_prefix = (item.depth > 0)
+            .then(|| item.archive_path.strip_prefix(root_dir).ok())
+            .flatten()
+            .map(|path| path.to_string_lossy().replace('\\', "/"));
+        quarantined.extend(summary.quarantined.iter().map(|entry| QuarantinedEntry {
+            entry_name: match &nested_prefix {
+                Some(prefix) => format!("{prefix}/{}", entry.entry_name),
+                None => entry.entry_name.clone(),
+            },
+            ..entry.clone()
+        }));
+
         // Process extracted files
         let mut extracted_files = Vec::new();
         let mut depth_limit_skips = 0;
@@ -934,6 +992,10 @@ impl ExtractionEngine {
         registry.register(Box::new(crate::zip_handler::ZipHandler));
         registry.register(Box::new(crate::tar_handler::TarHandler));
         registry.register(Box::new(crate::gz_handler::GzHandler));
+        registry.register(Box::new(crate::zstd_handler::ZstdHandler));
+        registry.register(Box::new(crate::xz_handler::XzHandler));
+        registry.register(Box::new(crate::bz2_handler::Bz2Handler));
+        registry.register(Box::new(crate::lz4_handler::Lz4Handler));
         registry.register(Box::new(crate::rar_handler::RarHandler));
         registry.register(Box::new(crate::sevenz_handler::SevenZHandler));
 
@@ -942,7 +1004,10 @@ impl ExtractionEngine {
 
     /// Check if a file is an archive based on extension
     fn is_archive_file(&self, path: &Path) -> bool {
-        let extensions = ["zip", "rar", "tar", "gz", "tgz", "7z"];
+        let extensions = [
+            "zip", "rar", "tar", "gz", "tgz", "zst", "tzst", "xz", "txz", "bz2", "tbz2", "tbz",
+            "lz4", "7z",
+        ];
 
         if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
             let ext_lower = ext.to_lowercase();
@@ -1228,6 +1293,7 @@ mod tests {
             total_bytes: 0,
             path_shortenings_applied: 0,
             depth_limit_skips: 0,
+            quarantined: Vec::new(),
             extraction_duration_secs: 0.0,
             extraction_speed_bytes_per_sec: 0.0,
         };
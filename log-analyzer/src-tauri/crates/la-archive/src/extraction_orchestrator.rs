@@ -340,6 +340,7 @@ mod tests {
             total_bytes: 128,
             path_shortenings_applied: 0,
             depth_limit_skips: 0,
+            quarantined: Vec::new(),
             extraction_duration_secs: 0.5,
             extraction_speed_bytes_per_sec: 256.0,
         }
@@ -2,11 +2,56 @@ use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
 use async_compression::tokio::bufread::GzipDecoder;
 use async_trait::async_trait;
 use la_core::error::{AppError, Result};
-use std::path::Path;
+use la_core::utils::path::to_extended_length_path;
+use la_core::utils::path_security::{
+    validate_and_sanitize_path, PathValidationResult, SecurityConfig,
+};
+use std::path::{Path, PathBuf};
 use tokio::fs;
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
 use tracing::warn;
 
+/// 计算解压输出文件名：去掉 .gz 扩展名，并清理 Windows 保留名 / 非法字符
+fn output_file_name(source: &Path) -> String {
+    decompressed_file_name(source, "gz")
+}
+
+/// 单文件压缩格式（.gz / .zst）的输出文件名：去掉 `extension` 扩展名并清理
+///
+/// Security: 使用 file_name() 而非 file_stem()，避免保留 "../../etc/passwd" 之类的目录组件。
+pub(crate) fn decompressed_file_name(source: &Path, extension: &str) -> String {
+    let file_name = source
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("output");
+    let suffix_len = extension.len() + 1;
+    let stem = if file_name.eq_ignore_ascii_case(extension) {
+        "output"
+    } else if file_name.len() > suffix_len
+        && file_name.is_char_boundary(file_name.len() - suffix_len)
+        && file_name[file_name.len() - suffix_len..].eq_ignore_ascii_case(&format!(".{extension}"))
+    {
+        &file_name[..file_name.len() - suffix_len]
+    } else {
+        file_name
+    };
+
+    match validate_and_sanitize_path(stem, &SecurityConfig::default()) {
+        PathValidationResult::Valid(name) | PathValidationResult::RequiresSanitization(_, name) => {
+            name
+        }
+        // 如 "app..log"：替换分隔符后保留原名，仅 "." / ".." 之类回退为 output
+        PathValidationResult::Unsafe(_) => {
+            let name = stem.replace(['/', '\\', ':'], "_");
+            if name.trim_matches('.').trim().is_empty() {
+                "output".to_string()
+            } else {
+                name
+            }
+        }
+    }
+}
+
 /**
  * GZ文件处理器
  *
@@ -110,152 +155,158 @@ impl GzHandler {
         target_dir: &Path,
         max_file_size: u64,
     ) -> Result<ExtractionSummary> {
-        const BUFFER_SIZE: usize = 1024 * 1024; // 1MB buffer for streaming (优化: 从 64KB 增大，减少 16x syscall 次数)
-                                                // 解压炸弹防御：压缩比超过 100:1 视为恶意文件
-        const MAX_DECOMPRESSION_RATIO: u64 = 100;
-        // 检测阈值：在前 200 个块之后开始检查压缩比（避免小文件误判）
-        const RATIO_CHECK_AFTER_CHUNKS: u64 = 200;
+        let reader = open_for_streaming(source, target_dir, "GZ").await?;
 
-        // 获取源文件压缩大小，用于计算解压比
-        let compressed_size = fs::metadata(source).await.map(|m| m.len()).unwrap_or(0);
+        // Create gzip decoder that streams decompression
+        let decoder = GzipDecoder::new(reader);
 
-        // Ensure target directory exists
-        fs::create_dir_all(target_dir).await.map_err(|e| {
-            AppError::archive_error(
-                format!("Failed to create target directory: {e}"),
-                Some(target_dir.to_path_buf()),
-            )
-        })?;
+        // Determine output file name (remove .gz extension, sanitized)
+        let output_path = target_dir.join(output_file_name(source));
+        stream_decompress(decoder, source, output_path, max_file_size, "gzip").await
+    }
+}
 
-        // Open source file for streaming
-        let file = fs::File::open(source).await.map_err(|e| {
-            AppError::archive_error(
-                format!("Failed to open GZ file: {e}"),
-                Some(source.to_path_buf()),
-            )
-        })?;
+/// 流式解压的读缓冲区大小（优化: 从 64KB 增大，减少 16x syscall 次数）
+const BUFFER_SIZE: usize = 1024 * 1024;
+/// 解压炸弹防御：压缩比超过 100:1 视为恶意文件
+const MAX_DECOMPRESSION_RATIO: u64 = 100;
+/// 检测阈值：在前 200 个块之后开始检查压缩比（避免小文件误判）
+const RATIO_CHECK_AFTER_CHUNKS: u64 = 200;
+
+/// 创建目标目录并以带缓冲的方式打开源文件；`format` 用于错误信息（如 "GZ"）
+pub(crate) async fn open_for_streaming(
+    source: &Path,
+    target_dir: &Path,
+    format: &str,
+) -> Result<BufReader<fs::File>> {
+    // Ensure target directory exists
+    fs::create_dir_all(target_dir).await.map_err(|e| {
+        AppError::archive_error(
+            format!("Failed to create target directory: {e}"),
+            Some(target_dir.to_path_buf()),
+        )
+    })?;
 
-        // Create buffered reader for efficient I/O
-        let reader = BufReader::with_capacity(BUFFER_SIZE, file);
+    // Open source file for streaming
+    let file = fs::File::open(source).await.map_err(|e| {
+        AppError::archive_error(
+            format!("Failed to open {format} file: {e}"),
+            Some(source.to_path_buf()),
+        )
+    })?;
 
-        // Create gzip decoder that streams decompression
-        let mut decoder = GzipDecoder::new(reader);
-
-        // Determine output file name (remove .gz extension)
-        // Security: use file_name() instead of file_stem() to prevent ZipSlip.
-        // file_stem() would preserve directory components like "../../etc/passwd",
-        // allowing path traversal. file_name() ensures only the basename is used.
-        let output_name = source
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        // Strip .gz extension safely
-        let output_name = if output_name.eq_ignore_ascii_case("gz") {
-            "output"
-        } else if let Some(stem) = output_name.strip_suffix(".gz") {
-            stem
-        } else if let Some(stem) = output_name.strip_suffix(".GZ") {
-            stem
-        } else {
-            output_name
-        };
-
-        let output_path = target_dir.join(output_name);
-
-        // Create output file with buffered writer
-        let output_file = fs::File::create(&output_path).await.map_err(|e| {
+    // Create buffered reader for efficient I/O
+    Ok(BufReader::with_capacity(BUFFER_SIZE, file))
+}
+
+/// 把单文件压缩流解压到 `output_path`，执行单文件大小限制与解压炸弹检测
+///
+/// `format` 用于错误信息（如 "gzip"）。失败时删除已写出的部分文件。
+pub(crate) async fn stream_decompress<D: AsyncRead + Unpin>(
+    mut decoder: D,
+    source: &Path,
+    output_path: PathBuf,
+    max_file_size: u64,
+    format: &str,
+) -> Result<ExtractionSummary> {
+    // 获取源文件压缩大小，用于计算解压比
+    let compressed_size = fs::metadata(source).await.map(|m| m.len()).unwrap_or(0);
+
+    // Create output file with buffered writer
+    let output_file = fs::File::create(to_extended_length_path(&output_path))
+        .await
+        .map_err(|e| {
             AppError::archive_error(
                 format!("Failed to create output file: {e}"),
                 Some(output_path.clone()),
             )
         })?;
 
-        let mut writer = BufWriter::with_capacity(BUFFER_SIZE, output_file);
+    let mut writer = BufWriter::with_capacity(BUFFER_SIZE, output_file);
 
-        // Stream decompression with size tracking
-        let mut buffer = vec![0u8; BUFFER_SIZE];
-        let mut total_bytes = 0u64;
-        let mut chunk_count = 0u64;
+    // Stream decompression with size tracking
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut total_bytes = 0u64;
+    let mut chunk_count = 0u64;
 
-        loop {
-            let bytes_read = decoder.read(&mut buffer).await.map_err(|e| {
-                AppError::archive_error(
-                    format!("Failed to decompress gzip stream: {e}"),
-                    Some(source.to_path_buf()),
-                )
-            })?;
+    loop {
+        let bytes_read = decoder.read(&mut buffer).await.map_err(|e| {
+            AppError::archive_error(
+                format!("Failed to decompress {format} stream: {e}"),
+                Some(source.to_path_buf()),
+            )
+        })?;
 
-            if bytes_read == 0 {
-                break; // EOF
-            }
+        if bytes_read == 0 {
+            break; // EOF
+        }
 
-            chunk_count += 1;
-            total_bytes += bytes_read as u64;
-            // Safety check: enforce size limit
-            if total_bytes > max_file_size {
-                // Clean up partial file
-                drop(writer);
-                let _ = fs::remove_file(&output_path).await;
-
-                return Err(AppError::archive_error(
-                    format!(
-                        "File {} exceeds maximum size limit of {} bytes (got {} bytes)",
-                        source.display(),
-                        max_file_size,
-                        total_bytes
-                    ),
-                    Some(source.to_path_buf()),
-                ));
-            }
+        chunk_count += 1;
+        total_bytes += bytes_read as u64;
+        // Safety check: enforce size limit
+        if total_bytes > max_file_size {
+            // Clean up partial file
+            drop(writer);
+            let _ = fs::remove_file(to_extended_length_path(&output_path)).await;
 
-            // 解压炸弹检测：在处理足够多的块后检查压缩比
-            if chunk_count >= RATIO_CHECK_AFTER_CHUNKS
-                && compressed_size > 0
-                && total_bytes > compressed_size.saturating_mul(MAX_DECOMPRESSION_RATIO)
-            {
-                drop(writer);
-                let _ = fs::remove_file(&output_path).await;
-                warn!(
-                    source = %source.display(),
-                    compressed_size,
-                    decompressed_bytes = total_bytes,
-                    ratio = total_bytes / compressed_size,
-                    max_ratio = MAX_DECOMPRESSION_RATIO,
-                    "检测到疑似解压炸弹，已中止解压"
-                );
-                return Err(AppError::archive_error(
-                    format!(
-                        "Suspected decompression bomb: {} (ratio {}:1 exceeds limit {}:1)",
-                        source.display(),
-                        total_bytes / compressed_size,
-                        MAX_DECOMPRESSION_RATIO
-                    ),
-                    Some(source.to_path_buf()),
-                ));
-            }
+            return Err(AppError::archive_error(
+                format!(
+                    "File {} exceeds maximum size limit of {} bytes (got {} bytes)",
+                    source.display(),
+                    max_file_size,
+                    total_bytes
+                ),
+                Some(source.to_path_buf()),
+            ));
+        }
 
-            // Write decompressed data
-            writer.write_all(&buffer[..bytes_read]).await.map_err(|e| {
-                AppError::archive_error(
-                    format!("Failed to write decompressed data: {e}"),
-                    Some(output_path.clone()),
-                )
-            })?;
+        // 解压炸弹检测：在处理足够多的块后检查压缩比
+        if chunk_count >= RATIO_CHECK_AFTER_CHUNKS
+            && compressed_size > 0
+            && total_bytes > compressed_size.saturating_mul(MAX_DECOMPRESSION_RATIO)
+        {
+            drop(writer);
+            let _ = fs::remove_file(to_extended_length_path(&output_path)).await;
+            warn!(
+                source = %source.display(),
+                compressed_size,
+                decompressed_bytes = total_bytes,
+                ratio = total_bytes / compressed_size,
+                max_ratio = MAX_DECOMPRESSION_RATIO,
+                "检测到疑似解压炸弹，已中止解压"
+            );
+            return Err(AppError::archive_error(
+                format!(
+                    "Suspected decompression bomb: {} (ratio {}:1 exceeds limit {}:1)",
+                    source.display(),
+                    total_bytes / compressed_size,
+                    MAX_DECOMPRESSION_RATIO
+                ),
+                Some(source.to_path_buf()),
+            ));
         }
 
-        // Flush remaining data
-        writer.flush().await.map_err(|e| {
+        // Write decompressed data
+        writer.write_all(&buffer[..bytes_read]).await.map_err(|e| {
             AppError::archive_error(
-                format!("Failed to flush output file: {e}"),
+                format!("Failed to write decompressed data: {e}"),
                 Some(output_path.clone()),
             )
         })?;
+    }
 
-        let mut summary = ExtractionSummary::new();
-        summary.add_file(output_path, total_bytes);
+    // Flush remaining data
+    writer.flush().await.map_err(|e| {
+        AppError::archive_error(
+            format!("Failed to flush output file: {e}"),
+            Some(output_path.clone()),
+        )
+    })?;
 
-        Ok(summary)
-    }
+    let mut summary = ExtractionSummary::new();
+    summary.add_file(output_path, total_bytes);
+
+    Ok(summary)
 }
 
 #[async_trait]
@@ -342,26 +393,11 @@ impl ArchiveHandler for GzHandler {
             ));
         }
 
-        // 确定输出文件名（去掉.gz扩展名）
-        // Security: use file_name() instead of file_stem() to prevent ZipSlip.
-        let output_name = source
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        let output_name = if output_name.eq_ignore_ascii_case("gz") {
-            "output"
-        } else if let Some(stem) = output_name.strip_suffix(".gz") {
-            stem
-        } else if let Some(stem) = output_name.strip_suffix(".GZ") {
-            stem
-        } else {
-            output_name
-        };
-
-        let output_path = target_dir.join(output_name);
+        // 确定输出文件名（去掉.gz扩展名并清理）
+        let output_path = target_dir.join(output_file_name(source));
 
         // 写入解压后的文件
-        fs::write(&output_path, decompressed_data)
+        fs::write(to_extended_length_path(&output_path), decompressed_data)
             .await
             .map_err(|e| {
                 AppError::archive_error(
@@ -448,6 +484,15 @@ mod tests {
         assert!(!is_tar_gz(Path::new("document.txt")));
     }
 
+    #[test]
+    fn test_output_file_name_sanitizes_windows_names() {
+        assert_eq!(output_file_name(Path::new("app.log.gz")), "app.log");
+        assert_eq!(output_file_name(Path::new("aux.log.gz")), "_aux.log");
+        assert_eq!(output_file_name(Path::new("report. .gz")), "report__");
+        assert_eq!(output_file_name(Path::new("app..log.gz")), "app..log");
+        assert_eq!(output_file_name(Path::new("...gz")), "output");
+    }
+
     #[test]
     fn test_gz_handler_file_extensions() {
         let handler = GzHandler;
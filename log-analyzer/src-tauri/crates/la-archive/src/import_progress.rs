@@ -0,0 +1,108 @@
+//! 渐进式导入进度
+//!
+//! 导入按文件流水线进行（存入 CAS → 写入元数据 → 后台统计），已处理的文件立即可搜索。
+//! 本模块按文件计数估算整体进度：目录在遍历前统计文件数，压缩包解压后追加其条目数，
+//! 每个文件或压缩包处理结束（含跳过、失败）时计为完成。
+//!
+//! 压缩包的条目数在解压前未知，遇到新的压缩包时百分比可能回落。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// 导入进行中时上报的最高百分比；100% 只在导入结束后由调用方表示
+pub const MAX_RUNNING_PERCENT: u8 = 99;
+
+/// 一次导入的文件计数
+#[derive(Debug, Default)]
+pub struct ImportProgress {
+    discovered: AtomicUsize,
+    settled: AtomicUsize,
+}
+
+impl ImportProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录新发现的待处理文件
+    pub fn discover(&self, count: usize) {
+        self.discovered.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// 记录处理结束的文件
+    pub fn settle(&self, count: usize) {
+        self.settled.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// (已处理, 已发现)
+    pub fn counts(&self) -> (usize, usize) {
+        (
+            self.settled.load(Ordering::Relaxed),
+            self.discovered.load(Ordering::Relaxed),
+        )
+    }
+
+    /// 估算的完成百分比（进行中最高 [`MAX_RUNNING_PERCENT`]）
+    pub fn percent(&self) -> u8 {
+        let (settled, discovered) = self.counts();
+        if discovered == 0 {
+            return 0;
+        }
+        let percent = settled.min(discovered).saturating_mul(100) / discovered;
+        (percent as u8).min(MAX_RUNNING_PERCENT)
+    }
+}
+
+/// 离开作用域时把一个文件计为完成，覆盖所有提前返回的路径
+pub(crate) struct SettleOnDrop(Option<Arc<ImportProgress>>);
+
+impl SettleOnDrop {
+    pub(crate) fn new(progress: Option<&Arc<ImportProgress>>) -> Self {
+        Self(progress.cloned())
+    }
+}
+
+impl Drop for SettleOnDrop {
+    fn drop(&mut self) {
+        if let Some(progress) = &self.0 {
+            progress.settle(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_follows_settled_files_and_stays_below_done() {
+        let progress = ImportProgress::new();
+        assert_eq!(progress.percent(), 0);
+
+        progress.discover(8);
+        progress.settle(2);
+        assert_eq!(progress.percent(), 25);
+
+        // 解压出新的压缩包条目时百分比回落
+        progress.discover(12);
+        assert_eq!(progress.percent(), 10);
+
+        progress.settle(18);
+        assert_eq!(progress.counts(), (20, 20));
+        assert_eq!(progress.percent(), MAX_RUNNING_PERCENT);
+    }
+
+    #[test]
+    fn settle_guard_counts_on_drop() {
+        let progress = Arc::new(ImportProgress::new());
+        progress.discover(1);
+        {
+            let _guard = SettleOnDrop::new(Some(&progress));
+            assert_eq!(progress.counts(), (0, 1));
+        }
+        assert_eq!(progress.counts(), (1, 1));
+
+        drop(SettleOnDrop::new(None));
+        assert_eq!(progress.counts(), (1, 1));
+    }
+}
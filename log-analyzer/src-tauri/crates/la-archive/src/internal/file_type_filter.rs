@@ -97,58 +97,25 @@ impl FileTypeFilter {
         })?
     }
 
-    /// 第1层：二进制文件检测（仅读取前1KB）
-    fn detect_binary_file(&self, path: &Path) -> std::io::Result<bool> {
-        const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
-            (&[0xFF, 0xD8, 0xFF], "JPEG"),
-            (&[0x89, 0x50, 0x4E, 0x47], "PNG"),
-            (&[0x47, 0x49, 0x46], "GIF"),
-            (&[0x42, 0x4D], "BMP"),
-            (&[0x49, 0x49, 0x2A, 0x00], "TIFF"),
-            (&[0x00, 0x00, 0x00, 0x18, 0x66, 0x74, 0x79, 0x70], "MP4"),
-            (&[0x1A, 0x45, 0xDF, 0xA3], "MKV"),
-            (&[0x49, 0x44, 0x33], "MP3"),
-            (&[0xFF, 0xFB], "MP3"),
-            (&[0x52, 0x49, 0x46, 0x46], "WAV/AVI"),
-            (&[0x4D, 0x5A], "EXE"),
-            (&[0x7F, 0x45, 0x4C, 0x46], "ELF"),
-            (&[0xFE, 0xED, 0xFA, 0xCF], "Mach-O"),
-            (&[0x50, 0x4B, 0x03, 0x04], "ZIP"),
-            (&[0x1F, 0x8B], "GZ"),
-        ];
-
-        let mut file = File::open(path)?;
-        let mut buffer = [0u8; 1024];
-        let n = file.read(&mut buffer)?;
-
-        if n == 0 {
-            return Ok(false);
-        }
-
-        for &(magic, name) in MAGIC_NUMBERS {
-            if buffer.starts_with(magic) {
-                tracing::debug!(
-                    file = %path.display(),
-                    file_type = name,
-                    "Detected binary file by magic number"
-                );
-                return Ok(true);
-            }
-        }
-
-        let null_count = buffer[..n].iter().filter(|&&b| b == 0).count();
-        let null_ratio = null_count as f64 / n as f64;
-
-        if null_ratio > 0.05 {
+    /// 检查流式导入的压缩包条目是否应该被导入；`head` 为条目的前 [`BINARY_PROBE_BYTES`] 字节
+    /// （条目更短时为全部内容），`name` 为条目路径
+    pub fn should_import_entry(&self, name: &Path, head: &[u8]) -> bool {
+        if self.config.binary_detection_enabled && is_binary_head(name, head) {
             tracing::debug!(
-                file = %path.display(),
-                null_ratio = null_ratio,
-                "Detected binary file by null byte ratio"
+                file = %name.display(),
+                "Entry skipped: detected as binary"
             );
-            return Ok(true);
+            return false;
         }
+        !self.config.enabled || self.apply_filter_rules(name)
+    }
 
-        Ok(false)
+    /// 第1层：二进制文件检测（仅读取前1KB）
+    fn detect_binary_file(&self, path: &Path) -> std::io::Result<bool> {
+        let mut file = File::open(path)?;
+        let mut buffer = [0u8; BINARY_PROBE_BYTES];
+        let n = file.read(&mut buffer)?;
+        Ok(is_binary_head(path, &buffer[..n]))
     }
 
     /// 第2层：应用智能过滤规则
@@ -192,3 +159,57 @@ impl FileTypeFilter {
         }
     }
 }
+
+/// 二进制检测读取的文件开头字节数
+pub const BINARY_PROBE_BYTES: usize = 1024;
+
+/// 按文件开头判断是否为二进制文件（魔数 + 空字节比例）；`path` 只用于日志
+fn is_binary_head(path: &Path, head: &[u8]) -> bool {
+    const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+        (&[0xFF, 0xD8, 0xFF], "JPEG"),
+        (&[0x89, 0x50, 0x4E, 0x47], "PNG"),
+        (&[0x47, 0x49, 0x46], "GIF"),
+        (&[0x42, 0x4D], "BMP"),
+        (&[0x49, 0x49, 0x2A, 0x00], "TIFF"),
+        (&[0x00, 0x00, 0x00, 0x18, 0x66, 0x74, 0x79, 0x70], "MP4"),
+        (&[0x1A, 0x45, 0xDF, 0xA3], "MKV"),
+        (&[0x49, 0x44, 0x33], "MP3"),
+        (&[0xFF, 0xFB], "MP3"),
+        (&[0x52, 0x49, 0x46, 0x46], "WAV/AVI"),
+        (&[0x4D, 0x5A], "EXE"),
+        (&[0x7F, 0x45, 0x4C, 0x46], "ELF"),
+        (&[0xFE, 0xED, 0xFA, 0xCF], "Mach-O"),
+        (&[0x50, 0x4B, 0x03, 0x04], "ZIP"),
+        (&[0x1F, 0x8B], "GZ"),
+    ];
+
+    if head.is_empty() {
+        return false;
+    }
+    let head = &head[..head.len().min(BINARY_PROBE_BYTES)];
+
+    for &(magic, name) in MAGIC_NUMBERS {
+        if head.starts_with(magic) {
+            tracing::debug!(
+                file = %path.display(),
+                file_type = name,
+                "Detected binary file by magic number"
+            );
+            return true;
+        }
+    }
+
+    let null_count = head.iter().filter(|&&b| b == 0).count();
+    let null_ratio = null_count as f64 / head.len() as f64;
+
+    if null_ratio > 0.05 {
+        tracing::debug!(
+            file = %path.display(),
+            null_ratio = null_ratio,
+            "Detected binary file by null byte ratio"
+        );
+        return true;
+    }
+
+    false
+}
@@ -1,4 +1,4 @@
-//! la-archive: ZIP/TAR/GZ/RAR/7Z 递归解压模块
+//! la-archive: ZIP/TAR/GZ/ZST/XZ/BZ2/LZ4/RAR/7Z 递归解压模块
 //!
 //! 提供统一的接口处理各种压缩格式，支持递归解压嵌套压缩包。
 //!
@@ -9,16 +9,26 @@
 //! 当前无禁用场景，保留仅用于将来的最小化构建评估。
 
 pub mod archive_handler;
+pub mod bz2_handler;
 #[cfg(feature = "enhanced-extraction")]
 pub mod checkpoint_manager;
+pub mod directory_scan;
+pub mod entry_names;
+pub mod entry_retry;
+pub mod entry_selection;
+pub mod entry_stream;
 #[cfg(feature = "enhanced-extraction")]
 pub mod extraction_engine; // P10: extraction_context types merged into extraction_engine
 #[cfg(feature = "enhanced-extraction")]
 pub mod extraction_orchestrator;
 pub mod gz_handler;
+pub mod import_progress;
 pub mod internal;
+pub mod lz4_handler;
+pub mod object_repair;
 #[cfg(feature = "enhanced-extraction")]
 pub mod path_manager;
+pub mod post_extract;
 pub mod processor;
 #[cfg(feature = "enhanced-extraction")]
 pub mod public_api;
@@ -29,12 +39,26 @@ pub mod sevenz_handler;
 pub mod stats;
 mod symlink_guard;
 pub mod tar_handler;
+pub mod xz_handler;
 pub mod zip_handler;
+pub mod zstd_handler;
 
 // 重新导出核心类型
-pub use archive_handler::{ArchiveHandler, ExtractionSummary};
+pub use archive_handler::{
+    ArchiveHandler, ArchivePassword, ExtractionSummary, FailedEntry, QuarantinedEntry, SkippedEntry,
+};
+pub use bz2_handler::Bz2Handler;
 #[cfg(feature = "enhanced-extraction")]
 pub use checkpoint_manager::{Checkpoint, CheckpointConfig, CheckpointManager};
+pub use directory_scan::{
+    scan_import_directory, DirectoryEntry, ScanProgress, ScanSummary, ScannedDirectory, WalkedEntry,
+};
+pub use entry_names::{decode_entry_name, decode_legacy_name};
+pub use entry_retry::{retry_failed_entries, RetryReport};
+pub use entry_selection::{ArchiveSelection, EntryFilter, EntrySelection, ExtractionSelection};
+pub use entry_stream::{
+    can_stream, copy_entry, stream_entries, stream_entries_with_nested, StreamStats, Visit,
+};
 #[cfg(feature = "enhanced-extraction")]
 pub use extraction_engine::{
     ExtractionContext, ExtractionEngine, ExtractionItem, ExtractionPolicy, ExtractionStack,
@@ -42,8 +66,12 @@ pub use extraction_engine::{
 #[cfg(feature = "enhanced-extraction")]
 pub use extraction_orchestrator::ExtractionOrchestrator;
 pub use gz_handler::GzHandler;
+pub use import_progress::ImportProgress;
+pub use lz4_handler::Lz4Handler;
+pub use object_repair::{reextract_object, RepairAttempt};
 #[cfg(feature = "enhanced-extraction")]
 pub use path_manager::{PathConfig, PathManager};
+pub use post_extract::{ExtractionPlugin, HookOutcome, PostExtractHook};
 pub use processor::{process_path_with_cas, CasProcessingContext};
 #[cfg(feature = "enhanced-extraction")]
 pub use public_api::{extract_archive_async, extract_archive_sync, ExtractionResult};
@@ -52,7 +80,9 @@ pub use rar_handler::RarHandler;
 pub use security_detector::{SecurityDetector, SecurityPolicy};
 pub use sevenz_handler::SevenZHandler;
 pub use tar_handler::TarHandler;
+pub use xz_handler::XzHandler;
 pub use zip_handler::ZipHandler;
+pub use zstd_handler::ZstdHandler;
 
 use la_core::error::Result;
 use la_core::models::config::ArchiveConfig;
@@ -66,6 +96,8 @@ pub struct ArchiveManager {
     max_file_size: u64,
     max_total_size: u64,
     max_file_count: usize,
+    /// 加密压缩包的密码（None 表示不解密）
+    password: Option<ArchivePassword>,
 }
 
 impl ArchiveManager {
@@ -78,6 +110,10 @@ impl ArchiveManager {
         let handlers: Vec<Box<dyn ArchiveHandler>> = vec![
             Box::new(TarHandler),
             Box::new(GzHandler),
+            Box::new(ZstdHandler),
+            Box::new(XzHandler),
+            Box::new(Bz2Handler),
+            Box::new(Lz4Handler),
             Box::new(ZipHandler),
             Box::new(RarHandler),
             Box::new(SevenZHandler),
@@ -88,6 +124,7 @@ impl ArchiveManager {
             max_file_size: config.max_file_size,
             max_total_size: config.max_total_size,
             max_file_count: config.max_file_count,
+            password: None,
         }
     }
 
@@ -99,7 +136,7 @@ impl ArchiveManager {
         config: ArchiveConfig,
         handlers_cfg: &la_core::models::extraction_policy::HandlersConfig,
     ) -> Self {
-        let mut handlers: Vec<Box<dyn ArchiveHandler>> = Vec::with_capacity(5);
+        let mut handlers: Vec<Box<dyn ArchiveHandler>> = Vec::with_capacity(9);
 
         if handlers_cfg.tar {
             handlers.push(Box::new(TarHandler));
@@ -107,6 +144,18 @@ impl ArchiveManager {
         if handlers_cfg.gz {
             handlers.push(Box::new(GzHandler));
         }
+        if handlers_cfg.zstd {
+            handlers.push(Box::new(ZstdHandler));
+        }
+        if handlers_cfg.xz {
+            handlers.push(Box::new(XzHandler));
+        }
+        if handlers_cfg.bzip2 {
+            handlers.push(Box::new(Bz2Handler));
+        }
+        if handlers_cfg.lz4 {
+            handlers.push(Box::new(Lz4Handler));
+        }
         if handlers_cfg.zip {
             handlers.push(Box::new(ZipHandler));
         }
@@ -122,9 +171,16 @@ impl ArchiveManager {
             max_file_size: config.max_file_size,
             max_total_size: config.max_total_size,
             max_file_count: config.max_file_count,
+            password: None,
         }
     }
 
+    /// 使用 `password` 解密 ZIP、RAR 等加密压缩包
+    pub fn with_password(mut self, password: Option<ArchivePassword>) -> Self {
+        self.password = password;
+        self
+    }
+
     pub fn get_config(&self) -> ArchiveConfig {
         ArchiveConfig {
             max_file_size: self.max_file_size,
@@ -146,6 +202,20 @@ impl ArchiveManager {
             )
         })?;
 
+        if let Some(password) = &self.password {
+            return handler
+                .extract_with_password(
+                    source,
+                    target_dir,
+                    self.max_file_size,
+                    self.max_total_size,
+                    self.max_file_count,
+                    None,
+                    password,
+                )
+                .await;
+        }
+
         handler
             .extract_with_limits(
                 source,
@@ -157,6 +227,46 @@ impl ArchiveManager {
             .await
     }
 
+    /// 只提取 `selection` 选中的条目
+    pub async fn extract_archive_selected(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        selection: &EntrySelection,
+    ) -> Result<ExtractionSummary> {
+        let handler = self.find_handler(source).ok_or_else(|| {
+            la_core::error::AppError::archive_error(
+                format!("Unsupported archive format: {:?}", source.extension()),
+                Some(source.to_path_buf()),
+            )
+        })?;
+
+        if let Some(password) = &self.password {
+            return handler
+                .extract_with_password(
+                    source,
+                    target_dir,
+                    self.max_file_size,
+                    self.max_total_size,
+                    self.max_file_count,
+                    Some(selection),
+                    password,
+                )
+                .await;
+        }
+
+        handler
+            .extract_selected(
+                source,
+                target_dir,
+                self.max_file_size,
+                self.max_total_size,
+                self.max_file_count,
+                selection,
+            )
+            .await
+    }
+
     fn find_handler(&self, path: &Path) -> Option<&dyn ArchiveHandler> {
         self.handlers
             .iter()
@@ -206,6 +316,16 @@ mod tests {
         assert!(manager.find_handler(Path::new("test.tar")).is_some());
         assert!(manager.find_handler(Path::new("test.tar.gz")).is_some());
         assert!(manager.find_handler(Path::new("test.gz")).is_some());
+        assert!(manager.find_handler(Path::new("test.tar.zst")).is_some());
+        assert!(manager.find_handler(Path::new("test.log.zst")).is_some());
+        assert!(manager.find_handler(Path::new("test.tar.xz")).is_some());
+        assert!(manager.find_handler(Path::new("test.tar.bz2")).is_some());
+        assert!(manager.find_handler(Path::new("test.tbz2")).is_some());
+        assert!(manager
+            .find_handler(Path::new("system.journal.xz"))
+            .is_some());
+        assert!(manager.find_handler(Path::new("messages.1.bz2")).is_some());
+        assert!(manager.find_handler(Path::new("app.log.lz4")).is_some());
         assert!(manager.find_handler(Path::new("test.txt")).is_none());
     }
 
@@ -0,0 +1,138 @@
+use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
+use crate::gz_handler::{decompressed_file_name, stream_decompress};
+use async_trait::async_trait;
+use bytes::Bytes;
+use la_core::error::{AppError, Result};
+use std::io::Read;
+use std::path::Path;
+use tokio::io::AsyncRead;
+use tokio::sync::mpsc;
+use tokio_util::io::StreamReader;
+
+/// 阻塞线程每次交给异步侧的解压块大小
+const DECODE_CHUNK_SIZE: usize = 256 * 1024;
+
+/// 通道中最多缓存的解压块数，消费方跟不上时阻塞解压线程
+const DECODE_CHANNEL_CAPACITY: usize = 4;
+
+/**
+ * LZ4文件处理器
+ *
+ * 处理单个 LZ4 帧格式压缩文件（`.lz4`），解压为单个文件。
+ *
+ * 使用流式解压，与 GzHandler 的流式模式共用单文件大小限制与解压炸弹检测。
+ * async-compression 的 LZ4 支持需要换用与 zip 的 lzma-sys 冲突的 liblzma，
+ * 因此在阻塞线程中用 `lz4::Decoder` 解压，再按块交给异步写出。
+ */
+pub struct Lz4Handler;
+
+#[async_trait]
+impl ArchiveHandler for Lz4Handler {
+    fn can_handle(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|s| s.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("lz4"))
+            .unwrap_or(false)
+    }
+
+    async fn extract_with_limits(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+    ) -> Result<ExtractionSummary> {
+        // 安全检查：文件数量限制（LZ4只包含一个文件）
+        if max_file_count < 1 {
+            return Err(AppError::archive_error(
+                format!("Extraction would exceed file count limit of {max_file_count} files"),
+                Some(source.to_path_buf()),
+            ));
+        }
+
+        tokio::fs::create_dir_all(target_dir).await.map_err(|e| {
+            AppError::archive_error(
+                format!("Failed to create target directory: {e}"),
+                Some(target_dir.to_path_buf()),
+            )
+        })?;
+        // 在这里打开源文件，无法打开时直接报错，而不是由解压线程写入读取错误
+        let file = tokio::fs::File::open(source).await.map_err(|e| {
+            AppError::archive_error(
+                format!("Failed to open LZ4 file: {e}"),
+                Some(source.to_path_buf()),
+            )
+        })?;
+        let output_path = target_dir.join(decompressed_file_name(source, "lz4"));
+        stream_decompress(
+            spawn_lz4_decoder(file.into_std().await),
+            source,
+            output_path,
+            max_file_size.min(max_total_size),
+            "lz4",
+        )
+        .await
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec!["lz4"]
+    }
+}
+
+/// 在阻塞线程中解压已打开的 `file`，返回按块读取解压结果的异步读取器
+///
+/// 读取器被丢弃（如超出大小限制）后发送失败，解压线程随之退出。
+fn spawn_lz4_decoder(file: std::fs::File) -> impl AsyncRead + Unpin {
+    let (tx, rx) = mpsc::channel::<std::io::Result<Bytes>>(DECODE_CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || {
+        let result = lz4::Decoder::new(std::io::BufReader::new(file)).and_then(|mut decoder| {
+            let mut buffer = vec![0u8; DECODE_CHUNK_SIZE];
+            loop {
+                let n = decoder.read(&mut buffer)?;
+                if n == 0
+                    || tx
+                        .blocking_send(Ok(Bytes::copy_from_slice(&buffer[..n])))
+                        .is_err()
+                {
+                    return Ok(());
+                }
+            }
+        });
+        if let Err(e) = result {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+    let chunks = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+    StreamReader::new(Box::pin(chunks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_extract_lz4_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("app.log.lz4");
+        let content = b"2024-01-01 WARN slow query\n".repeat(100);
+        let mut encoder = lz4::EncoderBuilder::new().build(Vec::new()).unwrap();
+        encoder.write_all(&content).unwrap();
+        let (compressed, result) = encoder.finish();
+        result.unwrap();
+        std::fs::write(&source, compressed).unwrap();
+
+        let output_dir = temp_dir.path().join("out");
+        let summary = Lz4Handler.extract(&source, &output_dir).await.unwrap();
+
+        assert_eq!(summary.files_extracted, 1);
+        assert_eq!(std::fs::read(output_dir.join("app.log")).unwrap(), content);
+
+        let missing = temp_dir.path().join("missing.lz4");
+        assert!(Lz4Handler.extract(&missing, &output_dir).await.is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! 从父压缩包重新解压损坏的 CAS 对象
+//!
+//! 块校验（见 `la_storage::chunk_checksums`）发现对象损坏后，若该对象是某个
+//! 压缩包的条目，而压缩包本身也存于 CAS 中且完好，就重新流式读取该条目，
+//! 哈希一致时覆盖损坏的对象。
+//!
+//! - 条目名 = 对象虚拟路径去掉压缩包虚拟路径前缀（与导入时的拼接规则一致）
+//! - 父压缩包本身损坏、为 RAR/7Z（不支持流式读取）或条目已找不到时无法修复
+
+use std::path::PathBuf;
+
+use la_core::error::{AppError, Result};
+use la_storage::{ContentAddressableStorage, MetadataStore};
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// 一次修复尝试的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairAttempt {
+    pub restored: bool,
+    /// 用于修复（或最后尝试）的父压缩包虚拟路径
+    pub source: Option<String>,
+    /// 未能修复的原因
+    pub reason: Option<String>,
+}
+
+impl RepairAttempt {
+    fn failed(source: Option<String>, reason: impl Into<String>) -> Self {
+        Self {
+            restored: false,
+            source,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+/// 尝试从父压缩包重新解压 `hash` 对应的对象
+///
+/// 同一内容可能来自多个压缩包条目，依次尝试直到成功。
+pub async fn reextract_object(
+    cas: &ContentAddressableStorage,
+    metadata: &MetadataStore,
+    hash: &str,
+) -> Result<RepairAttempt> {
+    // 文件条目与嵌套压缩包都可能引用该对象
+    let mut candidates: Vec<(String, i64)> = metadata
+        .get_all_files()
+        .await?
+        .into_iter()
+        .filter(|file| file.sha256_hash == hash)
+        .filter_map(|file| Some((file.virtual_path, file.parent_archive_id?)))
+        .collect();
+    candidates.extend(
+        metadata
+            .get_all_archives()
+            .await?
+            .into_iter()
+            .filter(|archive| archive.sha256_hash == hash)
+            .filter_map(|archive| Some((archive.virtual_path, archive.parent_archive_id?))),
+    );
+    if candidates.is_empty() {
+        return Ok(RepairAttempt::failed(
+            None,
+            "Object was not extracted from an archive",
+        ));
+    }
+
+    let mut last = RepairAttempt::failed(None, "Parent archive not found");
+    for (virtual_path, archive_id) in candidates {
+        let Some(archive) = metadata.get_archive_by_id(archive_id).await? else {
+            continue;
+        };
+        let source = Some(archive.virtual_path.clone());
+        let Some(entry_name) = virtual_path
+            .strip_prefix(&archive.virtual_path)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+
+        // 父压缩包损坏时解压出的内容不可信，哈希校验也会失败，直接跳过
+        match cas.verify_integrity(&archive.sha256_hash).await {
+            Ok(true) => {}
+            Ok(false) => {
+                last = RepairAttempt::failed(source, "Parent archive is corrupted too");
+                continue;
+            }
+            Err(e) => {
+                last = RepairAttempt::failed(source, format!("Parent archive unreadable: {e}"));
+                continue;
+            }
+        }
+
+        let raw_name = metadata.get_raw_entry_name(archive_id, &entry_name).await?;
+        let staged = match stage_entry(
+            cas,
+            &archive.sha256_hash,
+            &archive.original_name,
+            &entry_name,
+            raw_name,
+        )
+        .await
+        {
+            Ok(Some(staged)) => staged,
+            Ok(None) => {
+                last = RepairAttempt::failed(
+                    source,
+                    format!("Entry {entry_name} not found in the archive"),
+                );
+                continue;
+            }
+            Err(e) => {
+                warn!(hash = %hash, archive = %archive.virtual_path, error = %e, "Re-extraction failed");
+                last = RepairAttempt::failed(source, e.to_string());
+                continue;
+            }
+        };
+
+        let file = tokio::fs::File::open(staged.path()).await.map_err(|e| {
+            AppError::io_error(
+                format!("Failed to open re-extracted entry: {e}"),
+                Some(staged.path().to_path_buf()),
+            )
+        })?;
+        if cas.restore_object(hash, file).await? {
+            info!(hash = %hash, archive = %archive.virtual_path, entry = %entry_name, "Re-extracted corrupted object");
+            return Ok(RepairAttempt {
+                restored: true,
+                source,
+                reason: None,
+            });
+        }
+        last = RepairAttempt::failed(
+            source,
+            "Re-extracted content does not match the object hash",
+        );
+    }
+    Ok(last)
+}
+
+/// 把条目解压到工作区 tmp 目录下的临时文件；找不到条目时返回 `None`
+///
+/// `raw_name` 为导入时保存的原始条目名字节（见 [`crate::entry_stream::copy_entry`]）
+async fn stage_entry(
+    cas: &ContentAddressableStorage,
+    archive_hash: &str,
+    archive_name: &str,
+    entry_name: &str,
+    raw_name: Option<Vec<u8>>,
+) -> Result<Option<tempfile::NamedTempFile>> {
+    let archive_path = cas.get_object_path(archive_hash);
+    let temp_dir: PathBuf = cas.objects_dir().with_file_name("tmp");
+    let archive_name = archive_name.to_string();
+    let entry_name = entry_name.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&temp_dir)
+            .map_err(|e| AppError::io_error(e.to_string(), Some(temp_dir.clone())))?;
+        let mut staged = tempfile::NamedTempFile::new_in(&temp_dir)
+            .map_err(|e| AppError::io_error(e.to_string(), Some(temp_dir.clone())))?;
+        let found = crate::entry_stream::copy_entry(
+            &archive_path,
+            &archive_name,
+            &entry_name,
+            raw_name.as_deref(),
+            staged.as_file_mut(),
+        )?;
+        Ok(found.then_some(staged))
+    })
+    .await
+    .map_err(|e| AppError::internal_error(format!("Re-extraction task failed: {e}")))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use la_storage::{ArchiveMetadata, FileMetadata};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn zip_bytes(name: &str, content: &[u8]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options: zip::write::FileOptions<'_, ()> = zip::write::FileOptions::default();
+        writer.start_file(name, options).unwrap();
+        writer.write_all(content).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn test_reextracts_corrupted_entry_from_parent_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let cas = ContentAddressableStorage::new(temp_dir.path().to_path_buf());
+        let metadata = MetadataStore::new(temp_dir.path()).await.unwrap();
+
+        let content = b"2024-01-01 ERROR disk full\n".repeat(1000);
+        let archive_hash = cas
+            .store_content(&zip_bytes("logs/app.log", &content))
+            .await
+            .unwrap();
+        let hash = cas.store_content(&content).await.unwrap();
+        let archive_id = metadata
+            .insert_archive(&ArchiveMetadata {
+                id: 0,
+                sha256_hash: archive_hash,
+                virtual_path: "upload/bundle.zip".into(),
+                original_name: "bundle.zip".into(),
+                archive_type: "zip".into(),
+                parent_archive_id: None,
+                depth_level: 0,
+                extraction_status: "completed".into(),
+            })
+            .await
+            .unwrap();
+        metadata
+            .insert_file(&FileMetadata {
+                id: 0,
+                sha256_hash: hash.clone(),
+                virtual_path: "upload/bundle.zip/logs/app.log".into(),
+                original_name: "app.log".into(),
+                size: content.len() as i64,
+                modified_time: 0,
+                mime_type: None,
+                parent_archive_id: Some(archive_id),
+                depth_level: 1,
+                min_timestamp: None,
+                max_timestamp: None,
+                level_mask: None,
+                analysis_status: la_core::storage_types::AnalysisStatus::Pending,
+            })
+            .await
+            .unwrap();
+
+        std::fs::write(cas.get_object_path(&hash), b"bit rot").unwrap();
+        let attempt = reextract_object(&cas, &metadata, &hash).await.unwrap();
+        assert!(attempt.restored, "{attempt:?}");
+        assert_eq!(attempt.source.as_deref(), Some("upload/bundle.zip"));
+        assert_eq!(cas.read_content(&hash).await.unwrap(), content);
+
+        let loose = cas.store_content(b"not from an archive").await.unwrap();
+        let attempt = reextract_object(&cas, &metadata, &loose).await.unwrap();
+        assert!(!attempt.restored);
+    }
+}
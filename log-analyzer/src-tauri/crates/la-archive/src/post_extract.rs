@@ -0,0 +1,378 @@
+//! 解压后处理钩子
+//!
+//! 每个工作区可配置一组在压缩包解压完成、条目进入 CAS 之前执行的动作，
+//! 只提供安全的内置动作，不执行任意命令：
+//!
+//! - `decompressGz`：把单文件 `.gz`（不含 `.tar.gz`/`.tgz`）就地解压为同名文件，
+//!   避免轮转日志在虚拟路径中多出一层 `app.log.gz/app.log`
+//! - `splitLargeFiles`：按行边界把超过 `maxBytes` 的文件切分为 `name.partNNN.ext`
+//! - `plugin`：调用进程内注册的插件（见 [`register_plugin`]）
+//!
+//! 单个钩子失败只记录在结果中，不影响后续钩子与导入本身。
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+/// 保存钩子配置的工作区设置键
+pub const POST_EXTRACT_HOOKS_SETTING: &str = "post_extract_hooks";
+/// 切分阈值下限，防止误配置产生海量小文件
+pub const MIN_SPLIT_BYTES: u64 = 1024 * 1024;
+/// `.gz` 解压输出上限（防止解压炸弹），超过时保留原文件
+const MAX_GZ_OUTPUT_BYTES: u64 = 16 * 1024 * 1024 * 1024;
+
+/// 解压后处理动作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum PostExtractHook {
+    DecompressGz,
+    #[serde(rename_all = "camelCase")]
+    SplitLargeFiles {
+        max_bytes: u64,
+    },
+    Plugin {
+        name: String,
+    },
+}
+
+impl PostExtractHook {
+    /// 结果中展示的钩子名称
+    pub fn label(&self) -> String {
+        match self {
+            Self::DecompressGz => "decompressGz".to_string(),
+            Self::SplitLargeFiles { max_bytes } => format!("splitLargeFiles({max_bytes})"),
+            Self::Plugin { name } => format!("plugin:{name}"),
+        }
+    }
+}
+
+/// 进程内注册的解压后处理插件
+///
+/// `run` 接收解压目录与当前文件列表（绝对路径），可增删改文件，
+/// 返回受影响的文件数。新增的文件必须位于 `extract_dir` 内。
+pub trait ExtractionPlugin: Send + Sync {
+    fn name(&self) -> &str;
+    fn run(&self, extract_dir: &Path, files: &mut Vec<PathBuf>) -> Result<usize, String>;
+}
+
+static PLUGINS: Lazy<RwLock<HashMap<String, Arc<dyn ExtractionPlugin>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 注册插件；同名插件会被替换
+pub fn register_plugin(plugin: Arc<dyn ExtractionPlugin>) {
+    PLUGINS.write().insert(plugin.name().to_string(), plugin);
+}
+
+/// 已注册的插件名（排序后）
+pub fn registered_plugins() -> Vec<String> {
+    let mut names: Vec<String> = PLUGINS.read().keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// 校验钩子配置（切分阈值、插件是否已注册）
+pub fn validate_hooks(hooks: &[PostExtractHook]) -> Result<(), String> {
+    for hook in hooks {
+        match hook {
+            PostExtractHook::DecompressGz => {}
+            PostExtractHook::SplitLargeFiles { max_bytes } => {
+                if *max_bytes < MIN_SPLIT_BYTES {
+                    return Err(format!(
+                        "splitLargeFiles maxBytes must be at least {MIN_SPLIT_BYTES}"
+                    ));
+                }
+            }
+            PostExtractHook::Plugin { name } => {
+                if !PLUGINS.read().contains_key(name) {
+                    return Err(format!("Unknown extraction plugin: {name}"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 单个钩子的执行结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutcome {
+    pub hook: String,
+    pub files_affected: usize,
+    pub error: Option<String>,
+}
+
+/// 依次执行钩子，返回处理后的文件列表（绝对路径）与各钩子的结果
+///
+/// 同步执行，调用方应放在 `spawn_blocking` 中。
+pub fn run_post_extract_hooks(
+    hooks: &[PostExtractHook],
+    extract_dir: &Path,
+    files: Vec<PathBuf>,
+) -> (Vec<PathBuf>, Vec<HookOutcome>) {
+    let mut files: Vec<PathBuf> = files
+        .into_iter()
+        .map(|f| {
+            if f.is_absolute() {
+                f
+            } else {
+                extract_dir.join(f)
+            }
+        })
+        .collect();
+
+    let outcomes = hooks
+        .iter()
+        .map(|hook| {
+            let result = match hook {
+                PostExtractHook::DecompressGz => Ok(decompress_gz_files(&mut files)),
+                PostExtractHook::SplitLargeFiles { max_bytes } => {
+                    Ok(split_large_files(&mut files, (*max_bytes).max(MIN_SPLIT_BYTES)))
+                }
+                PostExtractHook::Plugin { name } => {
+                    let plugin = PLUGINS.read().get(name).cloned();
+                    match plugin {
+                        Some(plugin) => plugin.run(extract_dir, &mut files),
+                        None => Err(format!("Unknown extraction plugin: {name}")),
+                    }
+                }
+            };
+            // 插件可能引入目录外的路径，这里统一剔除
+            files.retain(|f| f.starts_with(extract_dir));
+            match result {
+                Ok(files_affected) => HookOutcome {
+                    hook: hook.label(),
+                    files_affected,
+                    error: None,
+                },
+                Err(error) => {
+                    tracing::warn!(hook = %hook.label(), error = %error, "Post-extraction hook failed");
+                    HookOutcome {
+                        hook: hook.label(),
+                        files_affected: 0,
+                        error: Some(error),
+                    }
+                }
+            }
+        })
+        .collect();
+
+    (files, outcomes)
+}
+
+fn is_single_gz(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase();
+    name.ends_with(".gz") && !name.ends_with(".tar.gz") && name.len() > 3
+}
+
+/// 解压单文件 `.gz`；单个文件失败时保留原文件并继续
+fn decompress_gz_files(files: &mut [PathBuf]) -> usize {
+    let mut affected = 0;
+    for file in files.iter_mut().filter(|f| is_single_gz(f)) {
+        let target = file.with_extension("");
+        if target.exists() {
+            continue;
+        }
+        match decompress_gz(file, &target) {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&*file);
+                *file = target;
+                affected += 1;
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&target);
+                tracing::warn!(file = %file.display(), error = %e, "Failed to decompress .gz file");
+            }
+        }
+    }
+    affected
+}
+
+fn decompress_gz(source: &Path, target: &Path) -> std::io::Result<()> {
+    let mut decoder =
+        GzDecoder::new(BufReader::new(File::open(source)?)).take(MAX_GZ_OUTPUT_BYTES + 1);
+    let mut out = BufWriter::new(File::create(target)?);
+    let written = std::io::copy(&mut decoder, &mut out)?;
+    out.flush()?;
+    if written > MAX_GZ_OUTPUT_BYTES {
+        return Err(std::io::Error::other("decompressed size exceeds limit"));
+    }
+    Ok(())
+}
+
+/// 切分超过阈值的文件；切分成功后删除原文件
+fn split_large_files(files: &mut Vec<PathBuf>, max_bytes: u64) -> usize {
+    let mut affected = 0;
+    let mut result = Vec::with_capacity(files.len());
+    for file in files.drain(..) {
+        let too_large = std::fs::metadata(&file)
+            .map(|m| m.len() > max_bytes)
+            .unwrap_or(false);
+        if !too_large {
+            result.push(file);
+            continue;
+        }
+        match split_file(&file, max_bytes) {
+            Ok(parts) => {
+                let _ = std::fs::remove_file(&file);
+                result.extend(parts);
+                affected += 1;
+            }
+            Err(e) => {
+                tracing::warn!(file = %file.display(), error = %e, "Failed to split large file");
+                result.push(file);
+            }
+        }
+    }
+    *files = result;
+    affected
+}
+
+/// `app.log` → `app.part001.log`（保留扩展名，文件过滤规则仍然适用）
+fn part_path(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.part{index:03}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.part{index:03}"),
+    };
+    path.with_file_name(name)
+}
+
+fn split_file(path: &Path, max_bytes: u64) -> std::io::Result<Vec<PathBuf>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut parts = Vec::new();
+    let mut writer: Option<BufWriter<File>> = None;
+    let mut written = 0u64;
+    let mut line = Vec::new();
+
+    let result = (|| {
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            if writer.is_none() || (written > 0 && written + line.len() as u64 > max_bytes) {
+                if let Some(mut w) = writer.take() {
+                    w.flush()?;
+                }
+                let part = part_path(path, parts.len() + 1);
+                writer = Some(BufWriter::new(File::create(&part)?));
+                parts.push(part);
+                written = 0;
+            }
+            if let Some(w) = writer.as_mut() {
+                w.write_all(&line)?;
+            }
+            written += line.len() as u64;
+        }
+        if let Some(mut w) = writer.take() {
+            w.flush()?;
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        for part in &parts {
+            let _ = std::fs::remove_file(part);
+        }
+        return Err(e);
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DropEmpty;
+
+    impl ExtractionPlugin for DropEmpty {
+        fn name(&self) -> &str {
+            "drop-empty"
+        }
+
+        fn run(&self, _extract_dir: &Path, files: &mut Vec<PathBuf>) -> Result<usize, String> {
+            let before = files.len();
+            files.retain(|f| std::fs::metadata(f).map(|m| m.len() > 0).unwrap_or(false));
+            Ok(before - files.len())
+        }
+    }
+
+    #[test]
+    fn test_builtin_hooks_and_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"rotated\n").unwrap();
+        std::fs::write(root.join("app.log.1.gz"), encoder.finish().unwrap()).unwrap();
+        let big: String = (0..30_000).map(|i| format!("line {i:06}\n")).collect();
+        std::fs::write(root.join("big.log"), &big).unwrap();
+        std::fs::write(root.join("empty.log"), b"").unwrap();
+
+        register_plugin(Arc::new(DropEmpty));
+        let hooks = vec![
+            PostExtractHook::DecompressGz,
+            PostExtractHook::SplitLargeFiles {
+                max_bytes: MIN_SPLIT_BYTES,
+            },
+            PostExtractHook::Plugin {
+                name: "drop-empty".into(),
+            },
+            PostExtractHook::Plugin {
+                name: "missing".into(),
+            },
+        ];
+        let (mut files, outcomes) = run_post_extract_hooks(
+            &hooks,
+            root,
+            vec![
+                PathBuf::from("app.log.1.gz"),
+                PathBuf::from("big.log"),
+                PathBuf::from("empty.log"),
+            ],
+        );
+
+        assert_eq!(
+            outcomes
+                .iter()
+                .map(|o| o.files_affected)
+                .collect::<Vec<_>>(),
+            vec![1, 0, 1, 0]
+        );
+        assert!(outcomes[3].error.is_some());
+        files.sort();
+        assert_eq!(files, vec![root.join("app.log.1"), root.join("big.log")]);
+        assert_eq!(std::fs::read(root.join("app.log.1")).unwrap(), b"rotated\n");
+
+        // 330 KB 的文件在 100 KB 阈值下切分为 4 份，内容按行拼回一致
+        let (files, _) = split_and_collect(root.join("big.log"), 100 * 1024);
+        assert_eq!(files.len(), 4);
+        let joined: Vec<u8> = files
+            .iter()
+            .flat_map(|f| std::fs::read(f).unwrap())
+            .collect();
+        assert_eq!(joined, big.as_bytes());
+        assert_eq!(files[0], root.join("big.part001.log"));
+
+        assert!(validate_hooks(&hooks[..3]).is_ok());
+        assert!(validate_hooks(&hooks).is_err());
+        assert!(validate_hooks(&[PostExtractHook::SplitLargeFiles { max_bytes: 10 }]).is_err());
+    }
+
+    fn split_and_collect(file: PathBuf, max_bytes: u64) -> (Vec<PathBuf>, usize) {
+        let mut files = vec![file];
+        let affected = split_large_files(&mut files, max_bytes);
+        (files, affected)
+    }
+}
@@ -9,27 +9,49 @@
 
 #[cfg(feature = "enhanced-extraction")]
 use crate::checkpoint_manager::{Checkpoint, CheckpointManager};
+use crate::directory_scan::{DirectoryWalk, ScannedDirectory};
+use crate::entry_stream::{can_stream, stream_entries_with_nested, Visit};
 #[cfg(feature = "enhanced-extraction")]
 use crate::extraction_engine::ExtractionPolicy;
-use crate::internal::file_type_filter::FileTypeFilter;
+use crate::import_progress::{ImportProgress, SettleOnDrop};
+use crate::internal::file_type_filter::{FileTypeFilter, BINARY_PROBE_BYTES};
+use crate::post_extract::{run_post_extract_hooks, HookOutcome, PostExtractHook};
 #[cfg(feature = "enhanced-extraction")]
-use crate::public_api::extract_archive_async;
-use crate::ArchiveManager;
-use la_core::error::{AppError, Result};
+use crate::public_api::{extract_archive_async, ErrorCode};
+use crate::{
+    ArchiveManager, ArchivePassword, EntryFilter, EntrySelection, ExtractionSelection, FailedEntry,
+    QuarantinedEntry, SkippedEntry,
+};
+use la_core::error::{AppError, ErrorCategory, Result};
 use la_core::models::config::{ArchiveConfig, FileFilterConfig};
+use la_core::models::SymlinkPolicy;
 use la_core::storage_types::{ArchiveMetadata, FileMetadata};
 use la_core::traits::AppConfigProvider;
 use la_core::utils::path::normalize_path_separator;
-use la_storage::{ContentAddressableStorage, MetadataStore};
+use la_core::utils::path_security::{
+    validate_and_sanitize_archive_path, PathValidationResult, SecurityConfig,
+};
+use la_core::utils::{
+    assess_content, daily_level_counts, detect_crash_artifact, fingerprint_content,
+    summarize_network_capture, SymlinkDecision, SymlinkGuard, TermBloom, TimeIndex,
+};
+use la_storage::{
+    ContentAddressableStorage, GzipFrameStore, MetadataStore, QuarantinedEntryRecord, SymlinkRecord,
+};
 use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
-use walkdir::WalkDir;
 
 const DIRECTORY_METADATA_BATCH_SIZE: usize = 500;
+/// 目录导入中两次元数据批量写入的最长间隔
+const PROGRESSIVE_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
 const SMALL_FILE_INLINE_CAS_THRESHOLD_BYTES: u64 = 1024 * 1024;
+/// 压缩包条目虚拟路径的最大长度，防止嵌套压缩包路径无限膨胀（DoS）
+const MAX_VIRTUAL_PATH_LENGTH: usize = 1024;
+/// 抓包与 HAR 摘要文件的虚拟路径后缀（见 [`store_network_summary`]）
+pub const NETWORK_SUMMARY_SUFFIX: &str = ".summary.log";
 
 /// 检查是否启用增强提取系统
 ///
@@ -59,24 +81,30 @@ fn extraction_policy_from_archive_config(config: &ArchiveConfig) -> ExtractionPo
         dir_batch_size: config.directory_batch_size,
         max_parallel_files: config.max_parallel_files,
         max_file_count: config.max_file_count,
+        password: None,
     }
 }
 
-/// 检查是否跟随目录中的符号链接
+/// 确定目录导入时的符号链接策略
 ///
 /// 优先级：
-/// 1. 环境变量 FOLLOW_SYMLINKS（用于测试和临时覆盖）
-/// 2. 默认为 false（不跟随符号链接）
+/// 1. 环境变量 FOLLOW_SYMLINKS（用于测试和临时覆盖）：true 对应 follow_within_root，
+///    其他值对应 skip
+/// 2. 提取策略 `extraction_policy.toml` 的 `security.symlink_policy`（默认 skip）
 ///
 /// # 安全说明
 ///
-/// 启用此选项可能存在安全风险，因为符号链接可能指向目录外的位置。
-/// 路径遍历检查会捕获大多数此类情况，但仍需谨慎使用。
-fn should_follow_symlinks() -> bool {
+/// 跟随模式下只会进入位于导入根目录内的链接目标，逃逸根目录的链接与
+/// 指向已遍历目录的链接（环路）都会被跳过。
+pub(crate) fn resolve_symlink_policy(policy: &la_core::models::ExtractionPolicy) -> SymlinkPolicy {
     if let Ok(env_value) = std::env::var("FOLLOW_SYMLINKS") {
-        return env_value.to_lowercase() == "true";
+        return if env_value.to_lowercase() == "true" {
+            SymlinkPolicy::FollowWithinRoot
+        } else {
+            SymlinkPolicy::Skip
+        };
     }
-    false
+    policy.security.symlink_policy
 }
 
 /// 检查路径是否安全（防止路径遍历攻击）
@@ -145,12 +173,153 @@ fn validate_path_safety(path: &Path, base_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// 记录选择性解压跳过的条目；失败只影响跳过列表的展示
+async fn record_skipped_entries(
+    context: &CasProcessingContext,
+    archive_id: i64,
+    entries: &[SkippedEntry],
+) {
+    let records: Vec<(String, i64)> = entries
+        .iter()
+        .map(|e| (e.entry_name.clone(), e.size.min(i64::MAX as u64) as i64))
+        .collect();
+    if let Err(e) = context
+        .metadata_store
+        .record_skipped_entries(archive_id, &records)
+        .await
+    {
+        warn!(
+            archive_id = archive_id,
+            count = records.len(),
+            error = %e,
+            "Failed to record skipped archive entries"
+        );
+    }
+}
+
+/// 记录解压或导入失败的条目，供 `retry_failed_entries` 重试；记录失败只告警
+async fn record_failed_entries(
+    context: &CasProcessingContext,
+    archive_id: i64,
+    entries: &[(String, String)],
+) {
+    if let Err(e) = context
+        .metadata_store
+        .record_failed_entries(archive_id, entries)
+        .await
+    {
+        warn!(
+            archive_id = archive_id,
+            count = entries.len(),
+            error = %e,
+            "Failed to record failed archive entries"
+        );
+    }
+}
+
+/// 记录按 GBK / Shift-JIS 解码的条目名对应的原始字节，供重试与重新解压定位条目；
+/// 失败只告警
+async fn record_raw_entry_names(
+    context: &CasProcessingContext,
+    archive_id: i64,
+    entries: &[(String, Vec<u8>)],
+) {
+    if let Err(e) = context
+        .metadata_store
+        .record_raw_entry_names(archive_id, entries)
+        .await
+    {
+        warn!(
+            archive_id = archive_id,
+            count = entries.len(),
+            error = %e,
+            "Failed to record raw archive entry names"
+        );
+    }
+}
+
+/// 解压摘要中的失败条目
+fn failed_entry_pairs(entries: &[FailedEntry]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .map(|e| (e.entry_name.clone(), e.error.clone()))
+        .collect()
+}
+
+/// 记录解压后处理钩子的执行结果；失败只告警
+async fn record_post_extract_outcomes(
+    context: &CasProcessingContext,
+    archive_id: i64,
+    outcomes: &[HookOutcome],
+) {
+    let records: Vec<(String, i64, Option<String>)> = outcomes
+        .iter()
+        .map(|o| (o.hook.clone(), o.files_affected as i64, o.error.clone()))
+        .collect();
+    if let Err(e) = context
+        .metadata_store
+        .record_post_extract_runs(archive_id, &records)
+        .await
+    {
+        warn!(
+            archive_id = archive_id,
+            error = %e,
+            "Failed to record post-extraction hook results"
+        );
+    }
+}
+
+/// 将被隔离的压缩包条目记录到元数据库，与父压缩包关联
+///
+/// 记录失败只告警，不影响其余条目的导入。
+async fn record_quarantined_entries(
+    context: &CasProcessingContext,
+    archive_id: i64,
+    entries: &[QuarantinedEntry],
+) {
+    let detected_at = chrono::Utc::now().timestamp();
+    for entry in entries {
+        let record = QuarantinedEntryRecord {
+            id: 0,
+            archive_id,
+            archive_virtual_path: String::new(),
+            entry_name: entry.entry_name.clone(),
+            reason: entry.reason.clone(),
+            size: entry.size.min(i64::MAX as u64) as i64,
+            detected_at,
+        };
+        if let Err(e) = context
+            .metadata_store
+            .record_quarantined_entry(&record)
+            .await
+        {
+            warn!(
+                archive_id = archive_id,
+                entry = %entry.entry_name,
+                error = %e,
+                "Failed to record quarantined archive entry"
+            );
+        }
+    }
+}
+
+/// 目录遍历的最大深度，默认无限制（遍历所有子目录）；
+/// 通过环境变量 PROCESSOR_MAX_DEPTH 可设置深度限制
+pub(crate) fn directory_max_depth() -> usize {
+    const DEFAULT_MAX_DEPTH: usize = usize::MAX;
+    std::env::var("PROCESSOR_MAX_DEPTH")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_MAX_DEPTH)
+}
+
 /// Helper struct to track directory processing statistics
 #[derive(Default)]
 struct DirectoryProcessingStats {
     processed_count: usize,
     skipped_error_count: usize,
     skipped_symlink_count: usize,
+    recorded_symlink_count: usize,
 }
 
 impl DirectoryProcessingStats {
@@ -159,6 +328,7 @@ impl DirectoryProcessingStats {
             path = %path.display(),
             processed = self.processed_count,
             skipped_symlinks = self.skipped_symlink_count,
+            recorded_symlinks = self.recorded_symlink_count,
             skipped_errors = self.skipped_error_count,
             "{} completed",
             context
@@ -238,6 +408,16 @@ pub struct CasProcessingContext {
     pub checkpoint: Option<Arc<Mutex<Checkpoint>>>,
     pub files_since_checkpoint: Arc<Mutex<usize>>,
     pub bytes_since_checkpoint: Arc<Mutex<u64>>,
+    /// 选择性解压的包含列表（None 表示完整提取所有压缩包）
+    pub selection: Option<Arc<ExtractionSelection>>,
+    /// 每个压缩包解压后依次执行的处理钩子
+    pub post_extract_hooks: Arc<Vec<PostExtractHook>>,
+    /// 渐进式导入的文件计数（None 表示不统计）
+    pub progress: Option<Arc<ImportProgress>>,
+    /// 加密压缩包的密码（None 表示不解密）
+    pub password: Option<ArchivePassword>,
+    /// 调用方已扫描并计入 `progress` 的导入根目录，处理时回放其目录项
+    pub prescanned: Option<Arc<ScannedDirectory>>,
 }
 
 impl CasProcessingContext {
@@ -257,6 +437,54 @@ impl CasProcessingContext {
             checkpoint: None,
             files_since_checkpoint: Arc::new(Mutex::new(0)),
             bytes_since_checkpoint: Arc::new(Mutex::new(0)),
+            selection: None,
+            post_extract_hooks: Arc::new(Vec::new()),
+            progress: None,
+            password: None,
+            prescanned: None,
+        }
+    }
+
+    /// Only extract the selected entries of the archives listed in `selection`
+    pub fn with_selection(mut self, selection: Option<Arc<ExtractionSelection>>) -> Self {
+        self.selection = selection.filter(|s| !s.is_empty());
+        self
+    }
+
+    /// Run `hooks` on every extracted archive before its entries are stored
+    pub fn with_post_extract_hooks(mut self, hooks: Vec<PostExtractHook>) -> Self {
+        self.post_extract_hooks = Arc::new(hooks);
+        self
+    }
+
+    /// Count discovered and processed files into `progress`
+    pub fn with_progress(mut self, progress: Arc<ImportProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// `scanned.root` was already walked and its files discovered in `progress`:
+    /// processing replays the scanned entries instead of walking it again
+    pub fn with_prescanned(mut self, scanned: Option<Arc<ScannedDirectory>>) -> Self {
+        self.prescanned = scanned;
+        self
+    }
+
+    /// Decrypt password-protected archives with `password`
+    pub fn with_password(mut self, password: Option<ArchivePassword>) -> Self {
+        self.password = password;
+        self
+    }
+
+    fn discover(&self, count: usize) {
+        if let Some(progress) = &self.progress {
+            progress.discover(count);
+        }
+    }
+
+    fn settle(&self, count: usize) {
+        if let Some(progress) = &self.progress {
+            progress.settle(count);
         }
     }
 
@@ -352,6 +580,10 @@ pub async fn process_path_with_cas_and_checkpoints(
     parent_archive_id: Option<i64>,
     depth_level: i32,
 ) -> Result<()> {
+    // 文件与压缩包由调用方计入已发现，处理结束（含跳过与失败）时计为完成；
+    // 压缩包在其全部条目处理完后才算完成
+    let _settle = (!path.is_dir()).then(|| SettleOnDrop::new(context.progress.as_ref()));
+
     // Check if file was already extracted (checkpoint resume)
     if context.is_file_extracted(path).await {
         debug!(
@@ -377,95 +609,118 @@ pub async fn process_path_with_cas_and_checkpoints(
     if path.is_dir() {
         // Track processing statistics for debugging
         let mut stats = DirectoryProcessingStats::default();
-        let follow_symlinks = should_follow_symlinks();
-
-        // 使用可配置的最大遍历深度，默认无限制（遍历所有子目录）
-        // 通过环境变量 PROCESSOR_MAX_DEPTH 可设置深度限制
-        const DEFAULT_MAX_DEPTH: usize = usize::MAX;
-        let max_depth = std::env::var("PROCESSOR_MAX_DEPTH")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(DEFAULT_MAX_DEPTH);
-
-        let walkdir_iter = WalkDir::new(path)
-            .min_depth(1)
-            .max_depth(max_depth)
-            .follow_links(follow_symlinks)
-            .into_iter();
+        let symlink_policy = resolve_symlink_policy(&load_extraction_policy_safe(provider));
+        let follow_symlinks = symlink_policy == SymlinkPolicy::FollowWithinRoot;
+        let mut symlink_guard = SymlinkGuard::new(path, symlink_policy);
+
+        let max_depth = directory_max_depth();
+        // 调用方已扫描过的导入根目录（见 `scan_import_directory`）回放扫描结果；
+        // 其余目录预先统计文件数作为进度分母（只遍历目录项，不读取内容）
+        let prescanned = context
+            .prescanned
+            .as_ref()
+            .filter(|scanned| scanned.root == path);
+        let mut walkdir_iter = match prescanned {
+            Some(scanned) => DirectoryWalk::replay(Arc::clone(scanned)),
+            None => {
+                if context.progress.is_some() {
+                    let files = DirectoryWalk::live(path, follow_symlinks, max_depth)
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| !entry.is_dir)
+                        .count();
+                    context.discover(files);
+                }
+                DirectoryWalk::live(path, follow_symlinks, max_depth)
+            }
+        };
         let mut pending_files = Vec::with_capacity(DIRECTORY_METADATA_BATCH_SIZE);
+        // 批量写入元数据，但至少每隔 PROGRESSIVE_FLUSH_INTERVAL 写入一次，
+        // 使已存入 CAS 的文件尽快可搜索
+        let mut last_flush = std::time::Instant::now();
 
-        for entry_result in walkdir_iter {
+        while let Some(entry_result) = walkdir_iter.next() {
             match entry_result {
                 Ok(entry) => {
+                    let relative_path = entry.path.strip_prefix(path).unwrap_or(&entry.path);
+                    let new_virtual = build_child_virtual_path(virtual_path, relative_path);
+
                     // 处理符号链接
-                    let target_path: Option<PathBuf> = if entry.path_is_symlink() {
-                        if follow_symlinks {
-                            // 尝试解析符号链接目标
-                            match entry.path().read_link() {
-                                Ok(target) => {
-                                    let resolved = if target.is_absolute() {
-                                        target
-                                    } else {
-                                        entry
-                                            .path()
-                                            .parent()
-                                            .unwrap_or(std::path::Path::new(""))
-                                            .join(&target)
-                                    };
-                                    // 验证符号链接目标不逃逸基础目录（B-H1 修复）
-                                    let canonical_base =
-                                        path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-                                    let check_path = resolved
-                                        .canonicalize()
-                                        .unwrap_or_else(|_| resolved.clone());
-                                    if !check_path.starts_with(&canonical_base) {
-                                        warn!(
-                                            symlink = %entry.path().display(),
-                                            target = %resolved.display(),
-                                            "符号链接目标逃逸提取目录，已忽略"
-                                        );
-                                        stats.skipped_symlink_count += 1;
-                                        continue;
-                                    }
-                                    info!(
-                                        symlink = %entry.path().display(),
-                                        target = %resolved.display(),
-                                        "Following symlink to target during CAS processing"
+                    let target_path: Option<PathBuf> = if entry.is_symlink {
+                        match symlink_guard.check(&entry.path) {
+                            SymlinkDecision::Follow { target } => {
+                                if entry.is_dir && !symlink_guard.enter_dir(&entry.path) {
+                                    warn!(
+                                        symlink = %entry.path.display(),
+                                        target = %target.display(),
+                                        "符号链接指向已遍历的目录（环路或重复链接），已忽略"
                                     );
-                                    Some(resolved)
+                                    walkdir_iter.skip_current_dir();
+                                    stats.skipped_symlink_count += 1;
+                                    continue;
                                 }
-                                Err(e) => {
+                                info!(
+                                    symlink = %entry.path.display(),
+                                    target = %target.display(),
+                                    "Following symlink to target during CAS processing"
+                                );
+                                Some(target)
+                            }
+                            SymlinkDecision::Record {
+                                target,
+                                within_root,
+                            } => {
+                                let record = SymlinkRecord {
+                                    virtual_path: new_virtual.clone(),
+                                    target: target.to_string_lossy().to_string(),
+                                    within_root,
+                                    created_at: chrono::Utc::now().timestamp(),
+                                };
+                                context.settle(1);
+                                if let Err(e) = context.metadata_store.record_symlink(&record).await
+                                {
                                     warn!(
-                                        path = %entry.path().display(),
+                                        symlink = %entry.path.display(),
                                         error = %e,
-                                        "Failed to read symlink target, skipping"
+                                        "Failed to record symlink metadata"
                                     );
                                     stats.skipped_symlink_count += 1;
-                                    continue;
+                                } else {
+                                    stats.recorded_symlink_count += 1;
                                 }
+                                continue;
+                            }
+                            SymlinkDecision::Skip { reason } => {
+                                warn!(
+                                    path = %entry.path.display(),
+                                    reason = %reason,
+                                    "Skipping symlink during CAS directory processing"
+                                );
+                                if entry.is_dir {
+                                    walkdir_iter.skip_current_dir();
+                                } else {
+                                    context.settle(1);
+                                }
+                                stats.skipped_symlink_count += 1;
+                                continue;
                             }
-                        } else {
-                            warn!(
-                                path = %entry.path().display(),
-                                "Skipping symlink during CAS directory processing (set FOLLOW_SYMLINKS=true to follow)"
-                            );
-                            stats.skipped_symlink_count += 1;
-                            continue;
                         }
                     } else {
+                        if follow_symlinks && entry.is_dir && !symlink_guard.enter_dir(&entry.path)
+                        {
+                            // 该目录已经通过符号链接导入过
+                            walkdir_iter.skip_current_dir();
+                            continue;
+                        }
                         None
                     };
 
                     // 确定实际要处理的路径。WalkDir 已经递归遍历子目录；
                     // 如果这里再递归目录，会让嵌套文件被重复处理。
-                    let path_to_process = target_path.as_deref().unwrap_or_else(|| entry.path());
+                    let path_to_process = target_path.as_deref().unwrap_or(&entry.path);
                     if path_to_process.is_dir() {
                         continue;
                     }
 
-                    let relative_path = entry.path().strip_prefix(path).unwrap_or(entry.path());
-                    let new_virtual = build_child_virtual_path(virtual_path, relative_path);
-
                     if is_archive_file(path_to_process) {
                         flush_pending_directory_files(context, &mut pending_files).await?;
                         if let Err(e) = Box::pin(process_path_with_cas_and_checkpoints(
@@ -501,6 +756,7 @@ pub async fn process_path_with_cas_and_checkpoints(
                                 path = %path_to_process.display(),
                                 "File skipped by filter configuration (user-configured)"
                             );
+                            context.settle(1);
                             continue;
                         }
 
@@ -520,11 +776,15 @@ pub async fn process_path_with_cas_and_checkpoints(
                                     error = %e,
                                     "Failed to stage directory file import, continuing with others"
                                 );
+                                context.settle(1);
                             }
                         }
 
-                        if pending_files.len() >= DIRECTORY_METADATA_BATCH_SIZE {
+                        if pending_files.len() >= DIRECTORY_METADATA_BATCH_SIZE
+                            || last_flush.elapsed() >= PROGRESSIVE_FLUSH_INTERVAL
+                        {
                             flush_pending_directory_files(context, &mut pending_files).await?;
+                            last_flush = std::time::Instant::now();
                         }
                     }
                     stats.processed_count += 1;
@@ -665,6 +925,7 @@ async fn flush_pending_directory_files(
     }
 
     let imports = std::mem::take(pending_files);
+    let count = imports.len();
     let files: Vec<FileMetadata> = imports
         .iter()
         .map(|pending| pending.metadata.clone())
@@ -699,6 +960,8 @@ async fn flush_pending_directory_files(
         }
     }
 
+    // 写入失败的文件不再重试，同样计为完成
+    context.settle(count);
     Ok(())
 }
 
@@ -730,11 +993,23 @@ fn spawn_file_stats_update(context: &CasProcessingContext, metadata: &FileMetada
     let metadata_store = Arc::clone(&context.metadata_store);
     let hash = metadata.sha256_hash.clone();
     let virtual_path_for_stats = metadata.virtual_path.clone();
+    let metadata = metadata.clone();
 
     tokio::task::spawn(async move {
         match cas.read_content(&hash).await {
             Ok(content) => {
                 let (min_ts, max_ts, level_mask) = crate::stats::compute_file_stats(&content);
+                store_content_summaries(&metadata_store, &hash, &content).await;
+                store_gzip_frames(&cas, &metadata_store, &hash).await;
+                if let Err(e) =
+                    store_network_summary(&cas, &metadata_store, &metadata, &content).await
+                {
+                    tracing::warn!(
+                        virtual_path = %virtual_path_for_stats,
+                        error = %e,
+                        "Failed to store network capture summary"
+                    );
+                }
                 if let Err(e) = metadata_store
                     .update_file_ready(&virtual_path_for_stats, min_ts, max_ts, level_mask)
                     .await
@@ -763,6 +1038,142 @@ fn spawn_file_stats_update(context: &CasProcessingContext, metadata: &FileMetada
     });
 }
 
+/// 记录内容的三元组摘要、稀疏时间索引、每日级别分布、数据质量与格式指纹，供搜索跳过文件、
+/// 按时间定位、工作区概览、文件树提示与索引解析使用；崩溃产物另登记到产物目录。失败只影响这几项
+async fn store_content_summaries(metadata_store: &MetadataStore, hash: &str, content: &[u8]) {
+    if let Some(bloom) = TermBloom::from_content(content) {
+        if let Err(e) = metadata_store
+            .set_term_sketch(hash, &bloom.to_bytes())
+            .await
+        {
+            tracing::warn!(hash = %hash, error = %e, "Failed to store term sketch");
+        }
+    }
+    if let Some(time_index) = TimeIndex::from_content(content) {
+        if let Err(e) = metadata_store
+            .set_time_index(hash, &time_index.to_bytes())
+            .await
+        {
+            tracing::warn!(hash = %hash, error = %e, "Failed to store time index");
+        }
+    }
+    if let Err(e) = metadata_store
+        .set_level_histogram(hash, &daily_level_counts(content))
+        .await
+    {
+        tracing::warn!(hash = %hash, error = %e, "Failed to store level histogram");
+    }
+    if let Err(e) = metadata_store
+        .set_data_quality(hash, &assess_content(content))
+        .await
+    {
+        tracing::warn!(hash = %hash, error = %e, "Failed to store data quality");
+    }
+    if let Err(e) = metadata_store
+        .set_format_fingerprint(hash, &fingerprint_content(content))
+        .await
+    {
+        tracing::warn!(hash = %hash, error = %e, "Failed to store format fingerprint");
+    }
+    if let Some(artifact) = detect_crash_artifact(content) {
+        if let Err(e) = metadata_store.set_crash_artifact(hash, &artifact).await {
+            tracing::warn!(hash = %hash, error = %e, "Failed to catalog crash artifact");
+        }
+    }
+}
+
+/// 为原样入库的较大 gzip 对象（深度超限的嵌套 `.gz` 等）生成分帧副本，按时间或偏移定位与
+/// 读取上下文时只解压所需的帧；分帧时建立的时间索引替代对象本身（压缩字节）无法建立的索引。
+/// 失败只影响定位速度
+pub async fn store_gzip_frames(
+    cas: &Arc<ContentAddressableStorage>,
+    metadata_store: &MetadataStore,
+    hash: &str,
+) {
+    let task_cas = Arc::clone(cas);
+    let task_hash = hash.to_string();
+    let framed = tokio::task::spawn_blocking(move || {
+        if !GzipFrameStore::should_frame(&task_cas, &task_hash)? {
+            return Ok(None);
+        }
+        GzipFrameStore::for_cas(&task_cas).build(&task_cas, &task_hash)
+    })
+    .await;
+    let framed = match framed {
+        Ok(Ok(Some(framed))) => framed,
+        Ok(Ok(None)) => return,
+        Ok(Err(e)) => {
+            warn!(hash = %hash, error = %e, "Failed to build gzip frame copy");
+            return;
+        }
+        Err(e) => {
+            warn!(hash = %hash, error = %e, "Gzip framing task failed");
+            return;
+        }
+    };
+    debug!(hash = %hash, frames = framed.index.frames().len(), "Built gzip frame copy");
+    if let Some(time_index) = framed.time_index {
+        if let Err(e) = metadata_store
+            .set_time_index(hash, &time_index.to_bytes())
+            .await
+        {
+            warn!(hash = %hash, error = %e, "Failed to store gzip time index");
+        }
+    }
+}
+
+/// 为抓包（pcap/pcapng）或 HAR 文件写入按连接/请求汇总的 `<虚拟路径>.summary.log`，
+/// 与原文件并列，使网络记录可以搜索并与应用日志按时间对照
+///
+/// 返回摘要文件的元数据；其他内容返回 `None`。内容无法解析时只记录警告，不影响原文件。
+pub async fn store_network_summary(
+    cas: &ContentAddressableStorage,
+    metadata_store: &MetadataStore,
+    file: &FileMetadata,
+    content: &[u8],
+) -> Result<Option<FileMetadata>> {
+    let summary = match summarize_network_capture(content) {
+        Ok(Some(summary)) => summary,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            warn!(
+                virtual_path = %file.virtual_path,
+                error = %e,
+                "Network capture could not be summarized"
+            );
+            return Ok(None);
+        }
+    };
+
+    let summary = summary.into_bytes();
+    let hash = cas.store_content(&summary).await?;
+    let (min_timestamp, max_timestamp, level_mask) = crate::stats::compute_file_stats(&summary);
+    let metadata = FileMetadata {
+        id: 0,
+        sha256_hash: hash.clone(),
+        virtual_path: format!("{}{NETWORK_SUMMARY_SUFFIX}", file.virtual_path),
+        original_name: format!("{}{NETWORK_SUMMARY_SUFFIX}", file.original_name),
+        size: summary.len() as i64,
+        modified_time: file.modified_time,
+        mime_type: Some("text/plain".to_string()),
+        parent_archive_id: file.parent_archive_id,
+        depth_level: file.depth_level,
+        min_timestamp,
+        max_timestamp,
+        level_mask,
+        analysis_status: la_core::storage_types::AnalysisStatus::Ready,
+    };
+    metadata_store.insert_file(&metadata).await?;
+    store_content_summaries(metadata_store, &hash, &summary).await;
+
+    debug!(
+        virtual_path = %metadata.virtual_path,
+        source = %file.virtual_path,
+        "Stored network capture summary"
+    );
+    Ok(Some(metadata))
+}
+
 async fn store_regular_file_content(
     context: &CasProcessingContext,
     path: &Path,
@@ -789,6 +1200,10 @@ fn detect_mime_type(path: &Path) -> Option<String> {
             "json" => Some("application/json".to_string()),
             "xml" => Some("application/xml".to_string()),
             "gz" => Some("application/gzip".to_string()),
+            "zst" => Some("application/zstd".to_string()),
+            "xz" => Some("application/x-xz".to_string()),
+            "bz2" => Some("application/x-bzip2".to_string()),
+            "lz4" => Some("application/x-lz4".to_string()),
             "zip" => Some("application/zip".to_string()),
             _ => None,
         })
@@ -818,6 +1233,7 @@ fn build_child_virtual_path(parent_virtual_path: &str, relative_path: &Path) ->
 /// * `workspace_id` - Workspace ID
 /// * `parent_archive_id` - Parent archive ID (None for root level)
 /// * `depth_level` - Current nesting depth
+/// * `selection` - 选择性解压的包含列表（None 表示完整提取）
 ///
 /// # Requirements
 ///
@@ -834,12 +1250,14 @@ pub async fn process_path_with_cas(
     workspace_id: &str,
     parent_archive_id: Option<i64>,
     depth_level: i32,
+    selection: Option<Arc<ExtractionSelection>>,
 ) -> Result<()> {
     // Wrap CAS in Arc for the context
     let cas_arc = Arc::new(cas.clone());
 
     // Create context without checkpoints for backward compatibility
-    let context = CasProcessingContext::new(workspace_dir.to_path_buf(), cas_arc, metadata_store);
+    let context = CasProcessingContext::new(workspace_dir.to_path_buf(), cas_arc, metadata_store)
+        .with_selection(selection);
 
     process_path_with_cas_and_checkpoints(
         path,
@@ -854,6 +1272,282 @@ pub async fn process_path_with_cas(
     .await
 }
 
+/// 是否把压缩包条目直接流式写入 CAS，而不是先解压到临时目录
+///
+/// 加密、解压后钩子、断点续传与增强提取都依赖解压目录，仍走完整解压；
+/// RAR/7Z 不支持流式读取。环境变量 `STREAM_EXTRACTION=false` 可关闭（用于排查问题）。
+fn should_stream_into_cas(archive_path: &Path, context: &CasProcessingContext) -> bool {
+    if std::env::var("STREAM_EXTRACTION").is_ok_and(|value| value.eq_ignore_ascii_case("false")) {
+        return false;
+    }
+    #[cfg(feature = "enhanced-extraction")]
+    let needs_extract_dir = is_enhanced_extraction_enabled() || context.checkpoint.is_some();
+    #[cfg(not(feature = "enhanced-extraction"))]
+    let needs_extract_dir = false;
+
+    !needs_extract_dir
+        && context.password.is_none()
+        && context.post_extract_hooks.is_empty()
+        && can_stream(archive_path)
+}
+
+/// 流式导入的条目
+enum StreamedEntry {
+    /// 已写入 CAS 的文件（深度超限的嵌套压缩包也原样存为文件）
+    Stored {
+        name: String,
+        hash: String,
+        size: u64,
+    },
+    /// 需要递归解压的嵌套压缩包，已落盘到 `path`
+    Nested { name: String, path: PathBuf },
+}
+
+#[derive(Default)]
+struct StreamedArchive {
+    entries: Vec<StreamedEntry>,
+    quarantined: Vec<QuarantinedEntry>,
+    /// 未被选中的条目（流式遍历不提供条目大小，记为 0）
+    skipped: Vec<SkippedEntry>,
+    /// 按 GBK / Shift-JIS 解码的条目名及其原始字节
+    raw_names: Vec<(String, Vec<u8>)>,
+}
+
+/// 边解压边把条目写入 CAS（阻塞调用）
+///
+/// 每个条目只写一次磁盘：内容边哈希边写入 CAS 临时对象，完成后重命名到位；只有需要
+/// 继续解压的嵌套压缩包写到 `nested_dir`。路径校验、文件过滤与大小/数量限制与完整解压
+/// 一致，超限或被过滤的条目跳过。读取压缩包出错时返回错误，由调用方回退到完整解压。
+fn stream_entries_into_cas(
+    archive_path: &Path,
+    cas: &ContentAddressableStorage,
+    nested_dir: &Path,
+    config: &ArchiveConfig,
+    filter: Option<&FileTypeFilter>,
+    selection: Option<&EntrySelection>,
+    recurse_nested: bool,
+) -> Result<StreamedArchive> {
+    use std::io::{Read, Write};
+
+    let security_config = SecurityConfig::default();
+    let mut streamed = StreamedArchive::default();
+    let mut total_size = 0u64;
+    let to_io = |e: AppError| std::io::Error::other(e.to_string());
+
+    let stats = stream_entries_with_nested(archive_path, |raw_name, reader| {
+        let name = match validate_and_sanitize_archive_path(raw_name, &security_config) {
+            PathValidationResult::Unsafe(reason) => {
+                streamed.quarantined.push(QuarantinedEntry {
+                    entry_name: raw_name.to_string(),
+                    reason,
+                    size: 0,
+                });
+                return Ok(Visit::Continue);
+            }
+            PathValidationResult::Valid(name)
+            | PathValidationResult::RequiresSanitization(_, name) => name,
+        };
+        if selection.is_some_and(|selection| !selection.matches(&name)) {
+            streamed.skipped.push(SkippedEntry {
+                entry_name: name,
+                size: 0,
+            });
+            return Ok(Visit::Continue);
+        }
+        if streamed.entries.len() >= config.max_file_count {
+            warn!(
+                file = %name,
+                max_count = config.max_file_count,
+                "Skipping entry - would exceed max_file_count limit"
+            );
+            return Ok(Visit::Continue);
+        }
+
+        // 多读一个字节即可判断是否超限，超限条目的剩余内容不再解压
+        let limit = config
+            .max_file_size
+            .min(config.max_total_size.saturating_sub(total_size));
+        let mut reader = reader.take(limit.saturating_add(1));
+        let entry_path = Path::new(&name);
+        let is_archive = is_archive_file(entry_path);
+
+        let entry = if is_archive && recurse_nested {
+            let dir = nested_dir.join(streamed.entries.len().to_string());
+            std::fs::create_dir_all(&dir)?;
+            let path = dir.join(entry_path.file_name().unwrap_or_default());
+            let size = std::io::copy(&mut reader, &mut std::fs::File::create(&path)?)?;
+            if size > limit {
+                std::fs::remove_dir_all(&dir)?;
+                None
+            } else {
+                Some((StreamedEntry::Nested { name, path }, size))
+            }
+        } else {
+            let mut head = Vec::with_capacity(BINARY_PROBE_BYTES);
+            (&mut reader)
+                .take(BINARY_PROBE_BYTES as u64)
+                .read_to_end(&mut head)?;
+            // 压缩包不经过文件过滤，与完整解压一致
+            if !is_archive && filter.is_some_and(|f| !f.should_import_entry(entry_path, &head)) {
+                debug!(file = %name, "Entry skipped by filter configuration");
+                return Ok(Visit::Continue);
+            }
+            let mut writer = cas.object_writer_sync().map_err(to_io)?;
+            writer.write_all(&head)?;
+            std::io::copy(&mut reader, &mut writer)?;
+            let size = writer.bytes_written();
+            if size > limit {
+                None
+            } else {
+                let hash = writer.finish().map_err(to_io)?;
+                Some((StreamedEntry::Stored { name, hash, size }, size))
+            }
+        };
+
+        match entry {
+            Some((entry, size)) => {
+                total_size += size;
+                streamed.entries.push(entry);
+            }
+            None => warn!(
+                file = %raw_name,
+                max_file_size = config.max_file_size,
+                current_total = total_size,
+                max_total = config.max_total_size,
+                "Skipping entry exceeding max_file_size or max_total_size limit"
+            ),
+        }
+        Ok(Visit::Continue)
+    })?;
+    streamed.raw_names = stats.raw_names;
+
+    Ok(streamed)
+}
+
+/// 写入流式导入条目的元数据，并递归处理落盘的嵌套压缩包
+#[allow(clippy::too_many_arguments)]
+async fn import_streamed_entries(
+    streamed: StreamedArchive,
+    virtual_path: &str,
+    extract_dir: &Path,
+    context: &CasProcessingContext,
+    provider: &dyn AppConfigProvider,
+    task_id: &str,
+    workspace_id: &str,
+    archive_id: i64,
+    depth_level: i32,
+) -> Result<()> {
+    record_quarantined_entries(context, archive_id, &streamed.quarantined).await;
+    record_skipped_entries(context, archive_id, &streamed.skipped).await;
+    record_raw_entry_names(context, archive_id, &streamed.raw_names).await;
+    context.discover(streamed.entries.len());
+    context
+        .metadata_store
+        .update_archive_status(archive_id, "extracting")
+        .await?;
+
+    let total_files = streamed.entries.len();
+    info!(
+        archive_id = archive_id,
+        total_files = total_files,
+        "Streamed archive entries into CAS"
+    );
+
+    let modified_time = chrono::Utc::now().timestamp();
+    let mut pending_files = Vec::new();
+    for entry in streamed.entries {
+        let name = match &entry {
+            StreamedEntry::Stored { name, .. } | StreamedEntry::Nested { name, .. } => name,
+        };
+        let new_virtual = format!("{virtual_path}/{name}");
+        if new_virtual.len() > MAX_VIRTUAL_PATH_LENGTH {
+            warn!(
+                path = %new_virtual,
+                length = new_virtual.len(),
+                max_allowed = MAX_VIRTUAL_PATH_LENGTH,
+                "虚拟路径超出最大长度限制，跳过该条目"
+            );
+            context.settle(1);
+            continue;
+        }
+
+        match entry {
+            StreamedEntry::Stored { name, hash, size } => {
+                let entry_path = Path::new(&name);
+                // 深度超限的嵌套压缩包原样存为文件
+                let mime_type = if is_archive_file(entry_path) {
+                    Some("application/octet-stream".to_string())
+                } else {
+                    detect_mime_type(entry_path)
+                };
+                pending_files.push(PendingFileImport {
+                    metadata: FileMetadata {
+                        id: 0,
+                        sha256_hash: hash,
+                        virtual_path: normalize_path_separator(&new_virtual),
+                        original_name: entry_path
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .to_string(),
+                        size: size as i64,
+                        modified_time,
+                        mime_type,
+                        parent_archive_id: Some(archive_id),
+                        depth_level,
+                        min_timestamp: None,
+                        max_timestamp: None,
+                        level_mask: None,
+                        analysis_status: la_core::storage_types::AnalysisStatus::Pending,
+                    },
+                    source_path: PathBuf::from(&new_virtual),
+                });
+                if pending_files.len() >= DIRECTORY_METADATA_BATCH_SIZE {
+                    flush_pending_directory_files(context, &mut pending_files).await?;
+                }
+            }
+            StreamedEntry::Nested { name, path } => {
+                flush_pending_directory_files(context, &mut pending_files).await?;
+                if let Err(e) = Box::pin(process_path_with_cas_and_checkpoints(
+                    &path,
+                    &new_virtual,
+                    context,
+                    provider,
+                    task_id,
+                    workspace_id,
+                    Some(archive_id),
+                    depth_level + 1,
+                ))
+                .await
+                {
+                    error!(file = %name, error = %e, "Failed to process nested archive");
+                    record_failed_entries(context, archive_id, &[(name, e.to_string())]).await;
+                }
+            }
+        }
+    }
+    flush_pending_directory_files(context, &mut pending_files).await?;
+
+    context
+        .metadata_store
+        .update_archive_status(archive_id, "completed")
+        .await?;
+    info!(
+        archive_id = archive_id,
+        files = total_files,
+        "Archive processing completed"
+    );
+
+    if let Err(e) = fs::remove_dir_all(extract_dir).await {
+        warn!(
+            path = %extract_dir.display(),
+            error = %e,
+            "Failed to remove extraction temp directory"
+        );
+    }
+    Ok(())
+}
+
 /// Extract and process archive using CAS with checkpoint support
 ///
 /// This function handles nested archive extraction with depth tracking and checkpoint support.
@@ -966,18 +1660,124 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
         )
     })?;
 
-    // Extract archive
-    let archive_manager = ArchiveManager::with_config(archive_config.clone());
-    #[cfg(feature = "enhanced-extraction")]
-    let extracted_files = if is_enhanced_extraction_enabled() {
-        let policy = extraction_policy_from_archive_config(&archive_config);
-        match extract_archive_async(archive_path, &extract_dir, workspace_id, Some(policy)).await {
-            Ok(result) => {
-                info!(
-                    files = result.extracted_files.len(),
+    let entry_selection = context
+        .selection
+        .as_deref()
+        .and_then(|selection| selection.for_archive(virtual_path));
+
+    // 流式导入：条目边解压边写入 CAS，只有需要递归解压的嵌套压缩包落盘
+    if should_stream_into_cas(archive_path, context) {
+        let filter_config = load_file_filter_config_safe(provider)
+            .await
+            .unwrap_or_default();
+        let filter = (filter_config.enabled || filter_config.binary_detection_enabled)
+            .then(|| FileTypeFilter::new(filter_config));
+        let cas = Arc::clone(&context.cas);
+        let source = archive_path.to_path_buf();
+        let nested_dir = extract_dir.clone();
+        let config = archive_config.clone();
+        let selection = entry_selection.cloned();
+        let streamed = tokio::task::spawn_blocking(move || {
+            stream_entries_into_cas(
+                &source,
+                &cas,
+                &nested_dir,
+                &config,
+                filter.as_ref(),
+                selection.as_ref(),
+                !is_at_max_depth,
+            )
+        })
+        .await
+        .map_err(|e| AppError::archive_error(format!("Streaming import task failed: {e}"), None))
+        .and_then(|result| result);
+
+        match streamed {
+            Ok(streamed) => {
+                return import_streamed_entries(
+                    streamed,
+                    virtual_path,
+                    &extract_dir,
+                    context,
+                    provider,
+                    task_id,
+                    workspace_id,
+                    archive_id,
+                    depth_level,
+                )
+                .await;
+            }
+            Err(e) => {
+                // 已写入的 CAS 对象尚无元数据引用，由垃圾回收清理
+                warn!(
+                    archive = %file_name,
+                    error = %e,
+                    "Streaming import failed, falling back to extraction"
+                );
+                let _ = fs::remove_dir_all(&extract_dir).await;
+                fs::create_dir_all(&extract_dir).await.map_err(|e| {
+                    AppError::archive_error(
+                        format!("Failed to create extraction directory: {e}"),
+                        Some(extract_dir.clone()),
+                    )
+                })?;
+            }
+        }
+    }
+
+    // Extract archive
+    let archive_manager =
+        ArchiveManager::with_config(archive_config.clone()).with_password(context.password.clone());
+    #[cfg(feature = "enhanced-extraction")]
+    let extracted_files = if let Some(selection) = entry_selection {
+        // 增强提取引擎不支持条目过滤，选择性解压始终走处理器路径
+        match archive_manager
+            .extract_archive_selected(archive_path, &extract_dir, selection)
+            .await
+        {
+            Ok(summary) => {
+                info!(
+                    files = summary.files_extracted,
+                    bytes = summary.total_size,
+                    skipped = summary.skipped.len(),
+                    quarantined = summary.quarantined.len(),
+                    "Selective extraction completed"
+                );
+                record_quarantined_entries(context, archive_id, &summary.quarantined).await;
+                record_skipped_entries(context, archive_id, &summary.skipped).await;
+                record_raw_entry_names(context, archive_id, &summary.raw_names).await;
+                record_failed_entries(context, archive_id, &failed_entry_pairs(&summary.failed))
+                    .await;
+                summary.extracted_files
+            }
+            Err(e) => {
+                context
+                    .metadata_store
+                    .update_archive_status(archive_id, "failed")
+                    .await?;
+                // 清理失败解压的临时目录
+                let _ = fs::remove_dir_all(&extract_dir).await;
+                if e.category() == ErrorCategory::PasswordRequired {
+                    return Err(e);
+                }
+                return Err(AppError::archive_error(
+                    format!("Selective extraction failed: {e}"),
+                    Some(archive_path.to_path_buf()),
+                ));
+            }
+        }
+    } else if is_enhanced_extraction_enabled() {
+        let mut policy = extraction_policy_from_archive_config(&archive_config);
+        policy.password = context.password.clone();
+        match extract_archive_async(archive_path, &extract_dir, workspace_id, Some(policy)).await {
+            Ok(result) => {
+                info!(
+                    files = result.extracted_files.len(),
                     bytes = result.performance_metrics.bytes_extracted,
+                    quarantined = result.quarantined.len(),
                     "Enhanced extraction completed"
                 );
+                record_quarantined_entries(context, archive_id, &result.quarantined).await;
                 result.extracted_files
             }
             Err(e) => {
@@ -987,6 +1787,12 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                     .await?;
                 // 清理失败解压的临时目录
                 let _ = fs::remove_dir_all(&extract_dir).await;
+                if e.error_code == ErrorCode::PasswordRequired {
+                    return Err(AppError::password_required(
+                        e.error_message,
+                        Some(archive_path.to_path_buf()),
+                    ));
+                }
                 return Err(AppError::archive_error(
                     format!("Enhanced extraction failed: {e}"),
                     Some(archive_path.to_path_buf()),
@@ -1002,8 +1808,14 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                 info!(
                     files = summary.files_extracted,
                     bytes = summary.total_size,
+                    quarantined = summary.quarantined.len(),
+                    failed = summary.failed.len(),
                     "Legacy extraction completed"
                 );
+                record_quarantined_entries(context, archive_id, &summary.quarantined).await;
+                record_raw_entry_names(context, archive_id, &summary.raw_names).await;
+                record_failed_entries(context, archive_id, &failed_entry_pairs(&summary.failed))
+                    .await;
                 summary.extracted_files
             }
             Err(e) => {
@@ -1013,6 +1825,9 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                     .await?;
                 // 清理失败解压的临时目录
                 let _ = fs::remove_dir_all(&extract_dir).await;
+                if e.category() == ErrorCategory::PasswordRequired {
+                    return Err(e);
+                }
                 return Err(AppError::archive_error(
                     format!("Legacy extraction failed: {e}"),
                     Some(archive_path.to_path_buf()),
@@ -1021,6 +1836,34 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
         }
     };
 
+    // 解压后处理钩子（解压 .gz、切分大文件、插件）
+    let extracted_files = if context.post_extract_hooks.is_empty() {
+        extracted_files
+    } else {
+        let hooks = Arc::clone(&context.post_extract_hooks);
+        let dir = extract_dir.clone();
+        let fallback = extracted_files.clone();
+        match tokio::task::spawn_blocking(move || {
+            run_post_extract_hooks(&hooks, &dir, extracted_files)
+        })
+        .await
+        {
+            Ok((files, outcomes)) => {
+                record_post_extract_outcomes(context, archive_id, &outcomes).await;
+                files
+            }
+            Err(e) => {
+                warn!(archive_id = archive_id, error = %e, "Post-extraction hooks panicked");
+                fallback
+                    .into_iter()
+                    .filter(|f| extract_dir.join(f).exists())
+                    .collect()
+            }
+        }
+    };
+
+    context.discover(extracted_files.len());
+
     // Update archive status to extracting
     context
         .metadata_store
@@ -1058,6 +1901,7 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                 error = %e,
                 "Skipping unsafe file"
             );
+            context.settle(1);
             continue;
         }
 
@@ -1088,7 +1932,6 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
         let new_virtual = format!("{}/{}", virtual_path, relative_path.to_string_lossy());
 
         // 虚拟路径长度守卫：防止嵌套压缩包路径无限膨胀（DoS）
-        const MAX_VIRTUAL_PATH_LENGTH: usize = 1024;
         if new_virtual.len() > MAX_VIRTUAL_PATH_LENGTH {
             warn!(
                 path = %new_virtual,
@@ -1096,6 +1939,7 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                 max_allowed = MAX_VIRTUAL_PATH_LENGTH,
                 "虚拟路径超出最大长度限制，跳过该条目"
             );
+            context.settle(1);
             continue;
         }
 
@@ -1129,6 +1973,7 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                 analysis_status: la_core::storage_types::AnalysisStatus::Pending,
             };
             context.metadata_store.insert_file(&nested_metadata).await?;
+            context.settle(1);
 
             // 增量分析：深层嵌套压缩包也计算统计
             let cas = Arc::clone(&context.cas);
@@ -1139,6 +1984,8 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                     Ok(content) => {
                         let (min_ts, max_ts, level_mask) =
                             crate::stats::compute_file_stats(&content);
+                        store_content_summaries(&metadata_store, &hash, &content).await;
+                        store_gzip_frames(&cas, &metadata_store, &hash).await;
                         if let Err(e) = metadata_store
                             .update_file_ready(&vp, min_ts, max_ts, level_mask)
                             .await
@@ -1183,6 +2030,8 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
                     "Failed to process file"
                 );
                 // Continue processing other files instead of failing the entire batch
+                let entry_name = relative_path.to_string_lossy().replace('\\', "/");
+                record_failed_entries(context, archive_id, &[(entry_name, e.to_string())]).await;
             }
         }
     }
@@ -1221,8 +2070,12 @@ async fn extract_and_process_archive_with_cas_and_checkpoints(
 ///
 /// - ZIP (.zip)
 /// - RAR (.rar)
-/// - TAR (.tar, .tar.gz, .tgz)
+/// - TAR (.tar, .tar.gz, .tgz, .tar.zst, .tzst, .tar.xz, .txz, .tar.bz2, .tbz2, .tbz)
 /// - GZ (.gz)
+/// - ZST (.zst)
+/// - XZ (.xz)
+/// - BZ2 (.bz2)
+/// - LZ4 (.lz4)
 fn is_archive_file(path: &Path) -> bool {
     let _archive_manager = ArchiveManager::new();
     _archive_manager.supported_extensions().iter().any(|ext| {
@@ -1318,7 +2171,7 @@ async fn load_file_filter_config_safe(
     }
 }
 
-async fn load_archive_config_safe(provider: &dyn AppConfigProvider) -> ArchiveConfig {
+pub(crate) async fn load_archive_config_safe(provider: &dyn AppConfigProvider) -> ArchiveConfig {
     match load_config_from_provider(provider).await {
         Ok(config) => config.archive,
         Err(e) => {
@@ -1331,6 +2184,49 @@ async fn load_archive_config_safe(provider: &dyn AppConfigProvider) -> ArchiveCo
     }
 }
 
+/// 从配置目录加载提取策略 `extraction_policy.toml`；文件不存在或解析失败时使用默认策略
+pub(crate) fn load_extraction_policy_safe(
+    provider: &dyn AppConfigProvider,
+) -> la_core::models::ExtractionPolicy {
+    let policy_path = match provider.config_dir() {
+        Ok(dir) => dir.join("extraction_policy.toml"),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to resolve config dir, using default extraction policy");
+            return la_core::models::ExtractionPolicy::default();
+        }
+    };
+    if !policy_path.exists() {
+        return la_core::models::ExtractionPolicy::default();
+    }
+    la_core::models::ExtractionPolicy::from_file(&policy_path).unwrap_or_else(|e| {
+        tracing::warn!(
+            path = %policy_path.display(),
+            error = %e,
+            "提取策略解析失败，使用默认策略"
+        );
+        la_core::models::ExtractionPolicy::default()
+    })
+}
+
+/// 编译导入的全局包含 / 排除列表；`filter` 为 `None` 时使用配置
+/// `archive.include_patterns` / `archive.exclude_patterns`
+pub async fn resolve_entry_filter(
+    provider: &dyn AppConfigProvider,
+    filter: Option<EntryFilter>,
+) -> std::result::Result<Option<EntrySelection>, String> {
+    let filter = match filter {
+        Some(filter) => filter,
+        None => {
+            let config = load_archive_config_safe(provider).await;
+            EntryFilter {
+                include: config.include_patterns,
+                exclude: config.exclude_patterns,
+            }
+        }
+    };
+    EntrySelection::filter(&filter.include, &filter.exclude)
+}
+
 /// 从 AppConfigProvider 加载应用配置
 async fn load_config_from_provider(
     provider: &dyn AppConfigProvider,
@@ -1383,6 +2279,59 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn network_summary_is_stored_next_to_har_file() {
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = ContentAddressableStorage::new(workspace_dir.clone());
+        let metadata_store = MetadataStore::new(&workspace_dir).await.unwrap();
+
+        let har = br#"{"log":{"creator":{"name":"b"},"entries":[{"startedDateTime":"2024-01-02T03:04:05Z","time":5,"request":{"method":"GET","url":"https://example.com/a"},"response":{"status":404,"statusText":"Not Found"}}]}}"#;
+        let file = FileMetadata {
+            id: 0,
+            sha256_hash: cas.store_content(har).await.unwrap(),
+            virtual_path: "bundle/session.har".to_string(),
+            original_name: "session.har".to_string(),
+            size: har.len() as i64,
+            modified_time: 0,
+            mime_type: None,
+            parent_archive_id: None,
+            depth_level: 0,
+            min_timestamp: None,
+            max_timestamp: None,
+            level_mask: None,
+            analysis_status: la_core::storage_types::AnalysisStatus::Pending,
+        };
+
+        let summary = store_network_summary(&cas, &metadata_store, &file, har)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.virtual_path, "bundle/session.har.summary.log");
+        assert!(summary.min_timestamp.is_some());
+        let content = cas.read_content(&summary.sha256_hash).await.unwrap();
+        assert_eq!(
+            String::from_utf8(content).unwrap(),
+            "2024-01-02 03:04:05.000 WARN GET https://example.com/a -> 404 Not Found time=5ms\n"
+        );
+        assert!(metadata_store
+            .get_file_by_virtual_path("bundle/session.har.summary.log")
+            .await
+            .unwrap()
+            .is_some());
+
+        let plain = FileMetadata {
+            virtual_path: "bundle/app.log".to_string(),
+            ..file
+        };
+        assert!(
+            store_network_summary(&cas, &metadata_store, &plain, b"INFO started\n")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
     fn create_many_file_zip(path: &Path, file_count: usize) {
         let file = std::fs::File::create(path).unwrap();
         let mut zip = zip::ZipWriter::new(file);
@@ -1491,6 +2440,50 @@ mod tests {
         assert_eq!(metadata_store.count_files().await.unwrap(), 12);
     }
 
+    #[tokio::test]
+    async fn import_progress_counts_directory_files_and_archive_entries() {
+        let temp = TempDir::new().unwrap();
+        let source_dir = temp.path().join("bundle");
+        tokio::fs::create_dir_all(&source_dir).await.unwrap();
+        for i in 0..3 {
+            tokio::fs::write(
+                source_dir.join(format!("app-{i}.log")),
+                format!("INFO {i}\n"),
+            )
+            .await
+            .unwrap();
+        }
+        create_many_file_zip(&source_dir.join("nested.zip"), 4);
+
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata_store = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let progress = Arc::new(ImportProgress::new());
+        let context =
+            CasProcessingContext::new(workspace_dir, Arc::clone(&cas), Arc::clone(&metadata_store))
+                .with_progress(Arc::clone(&progress));
+        let provider = TestConfigProvider {
+            dir: temp.path().join("config"),
+        };
+
+        process_path_with_cas_and_checkpoints(
+            &source_dir,
+            "bundle",
+            &context,
+            &provider,
+            "task-1",
+            "workspace-1",
+            None,
+            0,
+        )
+        .await
+        .unwrap();
+
+        // 3 个日志 + 压缩包本身 + 压缩包内 4 个条目
+        assert_eq!(progress.counts(), (8, 8));
+        assert_eq!(metadata_store.count_files().await.unwrap(), 7);
+    }
+
     #[tokio::test]
     #[ignore = "local performance smoke test; run explicitly when tuning import latency"]
     async fn directory_imports_1000_small_files_in_seconds() {
@@ -1577,4 +2570,364 @@ mod tests {
         );
         eprintln!("1000-file zip import smoke elapsed: {elapsed:?}");
     }
+
+    #[cfg(unix)]
+    async fn import_with_symlink_policy(
+        policy: SymlinkPolicy,
+    ) -> (Vec<String>, Arc<MetadataStore>, TempDir) {
+        use std::os::unix::fs::symlink;
+
+        let temp = TempDir::new().unwrap();
+        let outside_dir = temp.path().join("outside");
+        let source_dir = temp.path().join("source");
+        let logs_dir = source_dir.join("logs");
+        std::fs::create_dir_all(&outside_dir).unwrap();
+        std::fs::create_dir_all(&logs_dir).unwrap();
+        std::fs::write(outside_dir.join("secret.log"), b"secret\n").unwrap();
+        std::fs::write(logs_dir.join("app.log"), b"app\n").unwrap();
+        symlink(&outside_dir, source_dir.join("escape")).unwrap();
+        symlink(&source_dir, logs_dir.join("loop")).unwrap();
+
+        let config_dir = temp.path().join("config");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        let mut extraction_policy = la_core::models::ExtractionPolicy::default();
+        extraction_policy.security.symlink_policy = policy;
+        std::fs::write(
+            config_dir.join("extraction_policy.toml"),
+            toml::to_string(&extraction_policy).unwrap(),
+        )
+        .unwrap();
+
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata_store = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let context =
+            CasProcessingContext::new(workspace_dir, Arc::clone(&cas), Arc::clone(&metadata_store));
+        let provider = TestConfigProvider { dir: config_dir };
+
+        process_path_with_cas_and_checkpoints(
+            &source_dir,
+            "source",
+            &context,
+            &provider,
+            "task-1",
+            "workspace-1",
+            None,
+            0,
+        )
+        .await
+        .unwrap();
+
+        let mut paths: Vec<String> = metadata_store
+            .get_all_files()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.virtual_path)
+            .collect();
+        paths.sort();
+        (paths, metadata_store, temp)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn directory_import_follow_within_root_skips_escapes_and_loops() {
+        let (paths, _store, _temp) =
+            import_with_symlink_policy(SymlinkPolicy::FollowWithinRoot).await;
+        assert_eq!(paths, vec!["source/logs/app.log".to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn directory_import_records_symlinks_as_metadata() {
+        let (paths, store, _temp) =
+            import_with_symlink_policy(SymlinkPolicy::RecordAsMetadata).await;
+        assert_eq!(paths, vec!["source/logs/app.log".to_string()]);
+
+        let links = store.get_symlinks().await.unwrap();
+        let summary: Vec<(String, bool)> = links
+            .into_iter()
+            .map(|link| (link.virtual_path, link.within_root))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("source/escape".to_string(), false),
+                ("source/logs/loop".to_string(), true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn archive_import_records_quarantined_entries() {
+        let temp = TempDir::new().unwrap();
+        let archive_path = temp.path().join("evil.zip");
+        {
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::FileOptions::<'_, ()>::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            zip.start_file("../../etc/passwd", options).unwrap();
+            zip.write_all(b"root").unwrap();
+            zip.start_file("app.log", options).unwrap();
+            zip.write_all(b"ok\n").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata_store = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let context =
+            CasProcessingContext::new(workspace_dir, Arc::clone(&cas), Arc::clone(&metadata_store));
+        let provider = TestConfigProvider {
+            dir: temp.path().join("config"),
+        };
+
+        process_path_with_cas_and_checkpoints(
+            &archive_path,
+            "evil.zip",
+            &context,
+            &provider,
+            "task-1",
+            "workspace-1",
+            None,
+            0,
+        )
+        .await
+        .unwrap();
+
+        let quarantined = metadata_store.get_quarantined_entries(0).await.unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].entry_name, "../../etc/passwd");
+        assert_eq!(quarantined[0].archive_virtual_path, "evil.zip");
+        assert_eq!(metadata_store.count_files().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn archive_import_streams_entries_into_cas() {
+        let temp = TempDir::new().unwrap();
+        let nested_zip = temp.path().join("nested.zip");
+        create_many_file_zip(&nested_zip, 2);
+        let archive_path = temp.path().join("bundle.tar.gz");
+        {
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            let nested = std::fs::read(&nested_zip).unwrap();
+            let entries: [(&str, &[u8]); 3] = [
+                ("logs/app.log", b"2024-01-01 00:00:00 INFO started\n"),
+                ("../escape.log", b"outside\n"),
+                ("nested.zip", &nested),
+            ];
+            for (name, content) in entries {
+                let mut header = tar::Header::new_gnu();
+                // set_path 拒绝 `..`，直接写入名称字段
+                header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append(&header, content).unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata_store = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let context = CasProcessingContext::new(
+            workspace_dir.clone(),
+            Arc::clone(&cas),
+            Arc::clone(&metadata_store),
+        );
+        let provider = TestConfigProvider {
+            dir: temp.path().join("config"),
+        };
+        assert!(should_stream_into_cas(&archive_path, &context));
+
+        process_path_with_cas_and_checkpoints(
+            &archive_path,
+            "bundle.tar.gz",
+            &context,
+            &provider,
+            "task-1",
+            "workspace-1",
+            None,
+            0,
+        )
+        .await
+        .unwrap();
+
+        let mut files = metadata_store.get_all_files().await.unwrap();
+        files.sort_by(|a, b| a.virtual_path.cmp(&b.virtual_path));
+        let paths: Vec<&str> = files.iter().map(|f| f.virtual_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "bundle.tar.gz/logs/app.log",
+                "bundle.tar.gz/nested.zip/file-0000.log",
+                "bundle.tar.gz/nested.zip/file-0001.log",
+            ]
+        );
+        assert_eq!(
+            cas.read_content(&files[0].sha256_hash).await.unwrap(),
+            b"2024-01-01 00:00:00 INFO started\n"
+        );
+        assert_eq!(files[0].size, 33);
+
+        let quarantined = metadata_store.get_quarantined_entries(0).await.unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].entry_name, "../escape.log");
+
+        // 普通条目不经过解压目录，嵌套压缩包的落盘副本处理完即删除
+        let leftovers = std::fs::read_dir(workspace_dir.join("extracted"))
+            .map(|dir| dir.count())
+            .unwrap_or(0);
+        assert_eq!(leftovers, 0);
+    }
+
+    #[tokio::test]
+    async fn archive_import_filter_skips_unselected_entries() {
+        let temp = TempDir::new().unwrap();
+        let nested_zip = temp.path().join("nested.zip");
+        create_many_file_zip(&nested_zip, 2);
+        let archive_path = temp.path().join("bundle.tar.gz");
+        {
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            let nested = std::fs::read(&nested_zip).unwrap();
+            let entries: [(&str, &[u8]); 4] = [
+                ("logs/app.log", b"2024-01-01 00:00:00 INFO started\n"),
+                ("bin/server", b"binary"),
+                ("images/shot.png", b"png"),
+                ("nested.zip", &nested),
+            ];
+            for (name, content) in entries {
+                let mut header = tar::Header::new_gnu();
+                header.set_path(name).unwrap();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append(&header, content).unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata_store = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let filter =
+            EntrySelection::filter(&["**/*.log".to_string()], &["**/file-0001.log".to_string()])
+                .unwrap();
+        let selection = ExtractionSelection::new(&[]).unwrap().with_filter(filter);
+        let context = CasProcessingContext::new(
+            workspace_dir.clone(),
+            Arc::clone(&cas),
+            Arc::clone(&metadata_store),
+        )
+        .with_selection(Some(Arc::new(selection)));
+        let provider = TestConfigProvider {
+            dir: temp.path().join("config"),
+        };
+
+        process_path_with_cas_and_checkpoints(
+            &archive_path,
+            "bundle.tar.gz",
+            &context,
+            &provider,
+            "task-1",
+            "workspace-1",
+            None,
+            0,
+        )
+        .await
+        .unwrap();
+
+        let mut paths: Vec<String> = metadata_store
+            .get_all_files()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.virtual_path)
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "bundle.tar.gz/logs/app.log",
+                "bundle.tar.gz/nested.zip/file-0000.log",
+            ]
+        );
+
+        // 跳过的条目按所属压缩包记录，供文件树置灰显示
+        let skipped: Vec<(String, String)> = metadata_store
+            .get_skipped_entries()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.archive_virtual_path, e.entry_name))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("bundle.tar.gz".to_string(), "bin/server".to_string()),
+                ("bundle.tar.gz".to_string(), "images/shot.png".to_string()),
+                (
+                    "bundle.tar.gz/nested.zip".to_string(),
+                    "file-0001.log".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn archive_import_runs_post_extract_hooks() {
+        let temp = TempDir::new().unwrap();
+        let archive_path = temp.path().join("bundle.zip");
+        {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(b"rotated\n").unwrap();
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::FileOptions::<'_, ()>::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            zip.start_file("app.log.gz", options).unwrap();
+            zip.write_all(&encoder.finish().unwrap()).unwrap();
+            zip.finish().unwrap();
+        }
+
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata_store = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let context =
+            CasProcessingContext::new(workspace_dir, Arc::clone(&cas), Arc::clone(&metadata_store))
+                .with_post_extract_hooks(vec![PostExtractHook::DecompressGz]);
+        let provider = TestConfigProvider {
+            dir: temp.path().join("config"),
+        };
+
+        process_path_with_cas_and_checkpoints(
+            &archive_path,
+            "bundle.zip",
+            &context,
+            &provider,
+            "task-1",
+            "workspace-1",
+            None,
+            0,
+        )
+        .await
+        .unwrap();
+
+        let files = metadata_store.get_all_files().await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].virtual_path, "bundle.zip/app.log");
+
+        let runs = metadata_store.get_post_extract_runs().await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].hook, "decompressGz");
+        assert_eq!(runs[0].files_affected, 1);
+        assert_eq!(runs[0].archive_virtual_path, "bundle.zip");
+    }
 }
@@ -3,6 +3,7 @@
 //! This module provides both synchronous and asynchronous interfaces for
 //! archive extraction with comprehensive error handling and result structures.
 
+use crate::archive_handler::QuarantinedEntry;
 use crate::extraction_engine::{
     ExtractionEngine, ExtractionPolicy, ExtractionResult as InternalExtractionResult,
     WarningCategory as InternalWarningCategory,
@@ -32,6 +33,10 @@ pub struct ExtractionResult {
     pub performance_metrics: PerformanceMetrics,
     /// Security events detected during extraction
     pub security_events: Vec<SecurityEvent>,
+    /// Entries withheld for security reasons (e.g. path traversal), to be
+    /// recorded by the caller; not part of the serialized result
+    #[serde(skip)]
+    pub quarantined: Vec<QuarantinedEntry>,
 }
 
 /// Warning encountered during extraction
@@ -156,6 +161,8 @@ pub enum ErrorCode {
     UnsupportedFormat,
     /// Archive file is corrupted
     CorruptedArchive,
+    /// Archive is encrypted and no (correct) password was given
+    PasswordRequired,
     /// Permission denied for file access
     PermissionDenied,
     /// Zip bomb detected
@@ -203,6 +210,7 @@ impl From<AppError> for ExtractionError {
             la_core::ErrorCategory::PathTooLong => ErrorCode::PathTooLong,
             la_core::ErrorCategory::UnsupportedFormat => ErrorCode::UnsupportedFormat,
             la_core::ErrorCategory::CorruptedArchive => ErrorCode::CorruptedArchive,
+            la_core::ErrorCategory::PasswordRequired => ErrorCode::PasswordRequired,
             la_core::ErrorCategory::PermissionDenied => ErrorCode::PermissionDenied,
             la_core::ErrorCategory::ZipBombDetected => ErrorCode::ZipBombDetected,
             la_core::ErrorCategory::DepthLimitExceeded => ErrorCode::DepthLimitExceeded,
@@ -223,6 +231,9 @@ impl From<AppError> for ExtractionError {
             ErrorCode::CorruptedArchive => {
                 "Verify the archive file is not corrupted and try re-downloading".to_string()
             }
+            ErrorCode::PasswordRequired => {
+                "Provide the archive password and import again".to_string()
+            }
             ErrorCode::PermissionDenied => {
                 "Check file permissions and ensure the application has necessary access rights"
                     .to_string()
@@ -404,8 +415,8 @@ pub async fn extract_archive_async(
     )
     .map_err(ExtractionError::from)?;
 
-    // Create orchestrator for concurrency control
-    let orchestrator = ExtractionOrchestrator::new(Arc::new(engine), Some(num_cpus::get() / 2));
+    // Create orchestrator for concurrency control (half the CPUs, at least one)
+    let orchestrator = ExtractionOrchestrator::new(Arc::new(engine), None);
 
     // Perform extraction
     let internal_result: InternalExtractionResult = orchestrator
@@ -473,6 +484,7 @@ pub async fn extract_archive_async(
         warnings,
         performance_metrics,
         security_events,
+        quarantined: internal_result.quarantined,
     })
 }
 
@@ -532,6 +544,11 @@ mod tests {
                 ErrorCategory::CorruptedArchive,
                 ErrorCode::CorruptedArchive,
             ),
+            (
+                "password required",
+                ErrorCategory::PasswordRequired,
+                ErrorCode::PasswordRequired,
+            ),
             (
                 "permission denied",
                 ErrorCategory::PermissionDenied,
@@ -633,6 +650,7 @@ mod tests {
                 disk_io_operations: 20,
             },
             security_events: vec![],
+            quarantined: vec![],
         };
 
         // Test serialization
@@ -776,4 +794,32 @@ mod tests {
             SecurityPolicy::default().max_workspace_size
         );
     }
+
+    #[tokio::test]
+    async fn test_extraction_reports_quarantined_entries() {
+        use std::io::Write;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let archive_path = temp.path().join("evil.zip");
+        {
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let mut zip = zip::ZipWriter::new(file);
+            let options = zip::write::FileOptions::<'_, ()>::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            zip.start_file("../../etc/passwd", options).unwrap();
+            zip.write_all(b"root").unwrap();
+            zip.start_file("app.log", options).unwrap();
+            zip.write_all(b"ok\n").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let target = temp.path().join("out");
+        let result = extract_archive_async(&archive_path, &target, "ws", None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.extracted_files.len(), 1);
+        assert_eq!(result.quarantined.len(), 1);
+        assert_eq!(result.quarantined[0].entry_name, "../../etc/passwd");
+    }
 }
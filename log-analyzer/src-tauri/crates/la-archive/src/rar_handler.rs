@@ -1,5 +1,9 @@
 #[cfg(feature = "rar-support")]
-use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
+use crate::archive_handler::{ArchiveHandler, ArchivePassword, ExtractionSummary};
+#[cfg(feature = "rar-support")]
+use crate::entry_names::repair_widened_name;
+#[cfg(feature = "rar-support")]
+use crate::entry_selection::EntrySelection;
 #[cfg(feature = "rar-support")]
 use crate::symlink_guard::{ensure_no_symlink_components, reject_extracted_symlink};
 #[cfg(feature = "rar-support")]
@@ -7,6 +11,8 @@ use async_trait::async_trait;
 #[cfg(feature = "rar-support")]
 use la_core::error::{AppError, Result};
 #[cfg(feature = "rar-support")]
+use la_core::utils::path::to_extended_length_path;
+#[cfg(feature = "rar-support")]
 use la_core::utils::path_security::{
     validate_and_sanitize_archive_path, PathValidationResult, SecurityConfig,
 };
@@ -18,6 +24,8 @@ use tracing::warn;
 #[cfg(feature = "rar-support")]
 use tokio::fs;
 
+#[cfg(feature = "rar-support")]
+use unrar::error::{Code, UnrarError};
 #[cfg(feature = "rar-support")]
 use unrar::Archive;
 
@@ -44,6 +52,72 @@ impl ArchiveHandler for RarHandler {
         max_file_size: u64,
         max_total_size: u64,
         max_file_count: usize,
+    ) -> Result<ExtractionSummary> {
+        Self::extract_entries(
+            source,
+            target_dir,
+            max_file_size,
+            max_total_size,
+            max_file_count,
+            None,
+        )
+        .await
+    }
+
+    async fn extract_with_password(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: Option<&EntrySelection>,
+        password: &ArchivePassword,
+    ) -> Result<ExtractionSummary> {
+        let mut summary = Self::extract_entries(
+            source,
+            target_dir,
+            max_file_size,
+            max_total_size,
+            max_file_count,
+            Some(password.clone()),
+        )
+        .await?;
+        if let Some(selection) = selection {
+            summary.retain_selected(target_dir, selection);
+        }
+        Ok(summary)
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec!["rar"]
+    }
+}
+
+/// 缺少密码或密码错误时对应的 `PasswordRequired` 错误，其他错误返回 `None`
+#[cfg(feature = "rar-support")]
+fn password_error(error: &UnrarError, source: &Path) -> Option<AppError> {
+    let message = match error.code {
+        Code::MissingPassword => "Archive is encrypted; a password is required",
+        Code::BadPassword => "Incorrect archive password",
+        _ => return None,
+    };
+    Some(AppError::password_required(
+        message,
+        Some(source.to_path_buf()),
+    ))
+}
+
+#[cfg(feature = "rar-support")]
+impl RarHandler {
+    /// 提取条目；加密压缩包缺少密码或密码错误时返回 `PasswordRequired`
+    async fn extract_entries(
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        password: Option<ArchivePassword>,
     ) -> Result<ExtractionSummary> {
         fs::create_dir_all(target_dir).await?;
 
@@ -53,33 +127,40 @@ impl ArchiveHandler for RarHandler {
         // unrar crate uses libunrar, which is synchronous
         let summary = tokio::task::spawn_blocking(move || {
             let mut summary = ExtractionSummary::new();
-            let mut archive = Archive::new(&source_path)
-                .open_for_processing()
-                .map_err(|e| {
+            let archive = match &password {
+                Some(password) => Archive::with_password(&source_path, password.as_bytes()),
+                None => Archive::new(&source_path),
+            };
+            let mut archive = archive.open_for_processing().map_err(|e| {
+                password_error(&e, &source_path).unwrap_or_else(|| {
                     AppError::archive_error(
                         format!("Failed to open RAR: {e}"),
                         Some(source_path.clone()),
                     )
-                })?;
+                })
+            })?;
 
-            while let Some(header) = archive
-                .read_header()
-                .map_err(|e| AppError::archive_error(e.to_string(), None))?
-            {
+            while let Some(header) = archive.read_header().map_err(|e| {
+                password_error(&e, &source_path)
+                    .unwrap_or_else(|| AppError::archive_error(e.to_string(), None))
+            })? {
                 let (name, safe_path, out_path, size, is_directory) = {
                     let entry = header.entry();
-                    let name = entry.filename.to_string_lossy().to_string();
+                    let mut name = entry.filename.to_string_lossy().to_string();
+                    if let Some((decoded, raw_name)) = repair_widened_name(&name) {
+                        name = decoded;
+                        summary.record_raw_name(name.replace('\\', "/"), raw_name);
+                    }
+                    let size = entry.unpacked_size;
 
                     let validation =
                         validate_and_sanitize_archive_path(&name, &SecurityConfig::default());
                     let safe_path = match validation {
-                        PathValidationResult::Unsafe(_) => {
+                        PathValidationResult::Unsafe(reason) => {
                             archive = header
                                 .skip()
                                 .map_err(|e| AppError::archive_error(e.to_string(), None))?;
-                            summary.add_error(format!(
-                                "Skipped unsafe RAR entry {name}"
-                            ));
+                            summary.quarantine(name, reason, size);
                             continue;
                         }
                         PathValidationResult::Valid(p) => std::path::PathBuf::from(p),
@@ -92,19 +173,16 @@ impl ArchiveHandler for RarHandler {
 
                     // 防御性边界检查：确保最终路径不逃逸提取目录（防御 ZIP Slip）
                     if !out_path.starts_with(&target_path) {
-                        warn!(
-                            path = %safe_path.display(),
-                            "RAR 条目路径逃逸提取目录，跳过此条目"
-                        );
                         archive = header
                             .skip()
                             .map_err(|e| AppError::archive_error(e.to_string(), None))?;
-                        summary.add_error(format!(
-                            "Skipped RAR entry {name} due to path traversal"
-                        ));
+                        summary.quarantine(
+                            name,
+                            format!("RAR 条目路径逃逸提取目录: {}", safe_path.display()),
+                            size,
+                        );
                         continue;
                     }
-                    let size = entry.unpacked_size;
                     let is_directory = entry.is_directory();
 
                     (name, safe_path, out_path, size, is_directory)
@@ -125,7 +203,7 @@ impl ArchiveHandler for RarHandler {
                             .map_err(|e| AppError::archive_error(e.to_string(), None))?;
                         continue;
                     }
-                    if let Err(e) = std::fs::create_dir_all(&out_path) {
+                    if let Err(e) = std::fs::create_dir_all(to_extended_length_path(&out_path)) {
                         warn!(path = ?out_path, error = %e, "创建 RAR 目录条目失败，跳过");
                     }
                     archive = header
@@ -147,7 +225,7 @@ impl ArchiveHandler for RarHandler {
 
                     if let Some(parent) = out_path.parent() {
                         ensure_no_symlink_components(&target_path, parent)?;
-                        std::fs::create_dir_all(parent).map_err(|e| {
+                        std::fs::create_dir_all(to_extended_length_path(parent)).map_err(|e| {
                             AppError::archive_error(
                                 format!("创建 RAR 条目父目录失败: {e}"),
                                 Some(parent.to_path_buf()),
@@ -157,7 +235,7 @@ impl ArchiveHandler for RarHandler {
 
                     ensure_no_symlink_components(&target_path, &out_path)?;
 
-                    match header.extract_to(&out_path) {
+                    match header.extract_to(to_extended_length_path(&out_path)) {
                         Ok(new_archive) => {
                             archive = new_archive;
                             if let Err(error) = reject_extracted_symlink(&out_path) {
@@ -173,14 +251,14 @@ impl ArchiveHandler for RarHandler {
                             }
                             // TOCTOU defense: re-verify after extraction that the file
                             // did not escape the target directory via symlink race.
-                            if let Ok(canonical) = out_path.canonicalize() {
+                            if let Ok(canonical) = to_extended_length_path(&out_path).canonicalize() {
                                 if let Ok(canonical_target) = target_path.canonicalize() {
                                     if !canonical.starts_with(&canonical_target) {
                                         warn!(
                                             path = %out_path.display(),
                                             "RAR TOCTOU path traversal detected, removing and skipping"
                                         );
-                                        let _ = std::fs::remove_file(&out_path);
+                                        let _ = std::fs::remove_file(to_extended_length_path(&out_path));
                                         summary.add_error(format!(
                                             "Removed RAR entry {name} due to path traversal (TOCTOU)"
                                         ));
@@ -191,6 +269,10 @@ impl ArchiveHandler for RarHandler {
                             summary.add_file(safe_path, size);
                         }
                         Err(e) => {
+                            if let Some(error) = password_error(&e, &source_path) {
+                                let _ = std::fs::remove_file(to_extended_length_path(&out_path));
+                                return Err(error);
+                            }
                             let error_message =
                                 format!("Failed to extract RAR entry {name}: {e}");
                             warn!(
@@ -201,7 +283,7 @@ impl ArchiveHandler for RarHandler {
                             summary.add_error(error_message.clone());
                             // 清理可能的残留文件，防止不完整文件留在磁盘
                             if out_path.exists() {
-                                let _ = std::fs::remove_file(&out_path);
+                                let _ = std::fs::remove_file(to_extended_length_path(&out_path));
                             }
                             if summary.files_extracted == 0 {
                                 return Err(AppError::archive_error(error_message, None));
@@ -225,10 +307,6 @@ impl ArchiveHandler for RarHandler {
 
         Ok(summary)
     }
-
-    fn file_extensions(&self) -> Vec<&str> {
-        vec!["rar"]
-    }
 }
 
 #[cfg(test)]
@@ -2,6 +2,7 @@ use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
 use crate::symlink_guard::ensure_no_symlink_components;
 use async_trait::async_trait;
 use la_core::error::{AppError, Result};
+use la_core::utils::path::to_extended_length_path;
 use la_core::utils::path_security::{
     validate_and_sanitize_archive_path, PathValidationResult, SecurityConfig,
 };
@@ -57,7 +58,10 @@ impl ArchiveHandler for SevenZHandler {
                     let validation = validate_and_sanitize_archive_path(name, &security_config);
 
                     let safe_path = match validation {
-                        PathValidationResult::Unsafe(_) => return Ok(true),
+                        PathValidationResult::Unsafe(reason) => {
+                            summary.quarantine(name, reason, entry.size());
+                            return Ok(true);
+                        }
                         PathValidationResult::Valid(p) => std::path::PathBuf::from(p),
                         PathValidationResult::RequiresSanitization(_, p) => {
                             std::path::PathBuf::from(p)
@@ -67,9 +71,10 @@ impl ArchiveHandler for SevenZHandler {
                     let out_path = target_path.join(&safe_path);
                     // 验证最终路径不逃逸提取目录（防御 7z Slip 末级绕过）
                     if !out_path.starts_with(&target_path) {
-                        warn!(
-                            path = %safe_path.display(),
-                            "7z Slip 尝试被拦截，跳过此条目"
+                        summary.quarantine(
+                            name,
+                            format!("7z Slip 尝试被拦截: {}", safe_path.display()),
+                            entry.size(),
                         );
                         return Ok(true);
                     }
@@ -84,7 +89,7 @@ impl ArchiveHandler for SevenZHandler {
                             );
                             return Ok(true);
                         }
-                        let _ = std::fs::create_dir_all(&out_path);
+                        let _ = std::fs::create_dir_all(to_extended_length_path(&out_path));
                     } else {
                         if size > max_file_size
                             || summary.total_size + size > max_total_size
@@ -102,7 +107,7 @@ impl ArchiveHandler for SevenZHandler {
                                 );
                                 return Ok(true);
                             }
-                            let _ = std::fs::create_dir_all(parent);
+                            let _ = std::fs::create_dir_all(to_extended_length_path(parent));
                         }
 
                         if let Err(error) = ensure_no_symlink_components(&target_path, &out_path) {
@@ -114,21 +119,21 @@ impl ArchiveHandler for SevenZHandler {
                             return Ok(true);
                         }
 
-                        match std::fs::File::create(&out_path) {
+                        match std::fs::File::create(to_extended_length_path(&out_path)) {
                             Ok(mut out_file) => {
                                 if let Err(e) = std::io::copy(reader, &mut out_file) {
                                     warn!("Failed to extract 7z entry {:?}: {}", out_path, e);
                                 } else {
                                     // TOCTOU defense: re-verify after extraction that the file
                                     // did not escape the target directory via symlink race.
-                                    if let Ok(canonical) = out_path.canonicalize() {
+                                    if let Ok(canonical) = to_extended_length_path(&out_path).canonicalize() {
                                         if let Ok(canonical_target) = target_path.canonicalize() {
                                             if !canonical.starts_with(&canonical_target) {
                                                 warn!(
                                                     path = %out_path.display(),
                                                     "7z TOCTOU path traversal detected, removing and skipping"
                                                 );
-                                                let _ = std::fs::remove_file(&out_path);
+                                                let _ = std::fs::remove_file(to_extended_length_path(&out_path));
                                             } else {
                                                 summary.add_file(safe_path, size);
                                             }
@@ -93,10 +93,8 @@ fn parse_log_timestamp_to_unix(timestamp: &str) -> Option<i64> {
 /// # Returns
 /// `(min_timestamp, max_timestamp, level_mask)`
 pub fn compute_file_stats(content: &[u8]) -> (Option<i64>, Option<i64>, Option<u8>) {
-    let text = match std::str::from_utf8(content) {
-        Ok(s) => s,
-        Err(_) => return (None, None, None),
-    };
+    // 个别无效字节不影响其余行的时间戳与级别
+    let text = String::from_utf8_lossy(content);
 
     // 大文件优化：超过 10MB 只解析前 1000 行和后 1000 行
     const MAX_FULL_PARSE_BYTES: usize = 10 * 1024 * 1024;
@@ -132,7 +130,7 @@ pub fn compute_file_stats(content: &[u8]) -> (Option<i64>, Option<i64>, Option<u
     };
 
     if text.len() > MAX_FULL_PARSE_BYTES {
-        let all_lines: Vec<&str> = text.lines().collect();
+        let all_lines: Vec<&str> = la_core::utils::split_lines(&text).collect();
         let total = all_lines.len();
         if total > MAX_LINES_SAMPLE * 2 {
             for line in &all_lines[..MAX_LINES_SAMPLE] {
@@ -147,7 +145,7 @@ pub fn compute_file_stats(content: &[u8]) -> (Option<i64>, Option<i64>, Option<u
             }
         }
     } else {
-        for line in text.lines() {
+        for line in la_core::utils::split_lines(&text) {
             process_line(line);
         }
     }
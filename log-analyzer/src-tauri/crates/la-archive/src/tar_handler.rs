@@ -1,6 +1,8 @@
 use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
+use crate::entry_selection::EntrySelection;
 use async_trait::async_trait;
 use la_core::error::{AppError, Result};
+use la_core::utils::path::to_extended_length_path;
 use la_core::utils::path_security::{
     validate_and_sanitize_archive_path, PathValidationResult, SecurityConfig,
 };
@@ -8,8 +10,10 @@ use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::warn;
 
+use bzip2::read::MultiBzDecoder;
 use flate2::read::GzDecoder;
 use tar::Archive;
+use xz2::read::XzDecoder;
 
 /**
  * TAR文件处理器 (稳定版本)
@@ -21,15 +25,14 @@ impl ArchiveHandler for TarHandler {
     fn can_handle(&self, path: &Path) -> bool {
         if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
             let lower_ext = ext.to_lowercase();
-            if lower_ext == "tar" || lower_ext == "tgz" {
+            if matches!(
+                lower_ext.as_str(),
+                "tar" | "tgz" | "tzst" | "txz" | "tbz2" | "tbz"
+            ) {
                 return true;
             }
-            if lower_ext == "gz" {
-                if let Some(stem) = path.file_stem() {
-                    if let Some(stem_str) = stem.to_str() {
-                        return stem_str.to_lowercase().ends_with(".tar");
-                    }
-                }
+            if matches!(lower_ext.as_str(), "gz" | "zst" | "xz" | "bz2") {
+                return has_tar_stem(path);
             }
         }
         false
@@ -42,6 +45,63 @@ impl ArchiveHandler for TarHandler {
         max_file_size: u64,
         max_total_size: u64,
         max_file_count: usize,
+    ) -> Result<ExtractionSummary> {
+        Self::extract_entries(
+            source,
+            target_dir,
+            max_file_size,
+            max_total_size,
+            max_file_count,
+            None,
+        )
+        .await
+    }
+
+    async fn extract_selected(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: &EntrySelection,
+    ) -> Result<ExtractionSummary> {
+        Self::extract_entries(
+            source,
+            target_dir,
+            max_file_size,
+            max_total_size,
+            max_file_count,
+            Some(selection.clone()),
+        )
+        .await
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec![
+            "tar", "tar.gz", "tgz", "tar.zst", "tzst", "tar.xz", "txz", "tar.bz2", "tbz2", "tbz",
+        ]
+    }
+}
+
+/**
+ * 判断复合扩展名的主干是否为 .tar（如 `logs.tar.xz`）
+ */
+pub(crate) fn has_tar_stem(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.to_lowercase().ends_with(".tar"))
+}
+
+impl TarHandler {
+    /// 提取条目；`selection` 为 `Some` 时未选中的文件条目只记录、不写入
+    async fn extract_entries(
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: Option<EntrySelection>,
     ) -> Result<ExtractionSummary> {
         fs::create_dir_all(target_dir).await?;
 
@@ -53,36 +113,31 @@ impl ArchiveHandler for TarHandler {
             let mut summary = ExtractionSummary::new();
             let security_config = SecurityConfig::default();
 
-            let is_gzipped = source_path
+            let extension = source_path
                 .extension()
                 .and_then(|s| s.to_str())
-                .map(|ext| ext.eq_ignore_ascii_case("gz") || ext.eq_ignore_ascii_case("tgz"))
-                .unwrap_or(false);
-
-            if is_gzipped {
-                let decoder = GzDecoder::new(file);
-                let mut archive = Archive::new(decoder);
-                Self::extract_sync(
-                    &mut archive,
-                    &target_path,
-                    &mut summary,
-                    max_file_size,
-                    max_total_size,
-                    max_file_count,
-                    &security_config,
-                )?;
-            } else {
-                let mut archive = Archive::new(file);
-                Self::extract_sync(
-                    &mut archive,
-                    &target_path,
-                    &mut summary,
-                    max_file_size,
-                    max_total_size,
-                    max_file_count,
-                    &security_config,
-                )?;
-            }
+                .map(str::to_lowercase)
+                .unwrap_or_default();
+
+            let reader: Box<dyn std::io::Read> = match extension.as_str() {
+                "gz" | "tgz" => Box::new(GzDecoder::new(file)),
+                "zst" | "tzst" => Box::new(zstd::stream::read::Decoder::new(file)?),
+                // 多线程 xz / pbzip2 会写出多个连续流
+                "xz" | "txz" => Box::new(XzDecoder::new_multi_decoder(file)),
+                "bz2" | "tbz2" | "tbz" => Box::new(MultiBzDecoder::new(file)),
+                _ => Box::new(file),
+            };
+            let mut archive = Archive::new(reader);
+            Self::extract_sync(
+                &mut archive,
+                &target_path,
+                &mut summary,
+                max_file_size,
+                max_total_size,
+                max_file_count,
+                &security_config,
+                selection.as_ref(),
+            )?;
 
             Ok::<ExtractionSummary, AppError>(summary)
         })
@@ -98,12 +153,7 @@ impl ArchiveHandler for TarHandler {
         Ok(summary)
     }
 
-    fn file_extensions(&self) -> Vec<&str> {
-        vec!["tar", "tar.gz", "tgz"]
-    }
-}
-
-impl TarHandler {
+    #[allow(clippy::too_many_arguments)]
     fn extract_sync<R: std::io::Read>(
         archive: &mut Archive<R>,
         target_dir: &Path,
@@ -112,6 +162,7 @@ impl TarHandler {
         max_total_size: u64,
         max_file_count: usize,
         security_config: &SecurityConfig,
+        selection: Option<&EntrySelection>,
     ) -> Result<()> {
         let entries = archive
             .entries()
@@ -136,7 +187,10 @@ impl TarHandler {
 
             let validation = validate_and_sanitize_archive_path(&path_str, security_config);
             let safe_path = match validation {
-                PathValidationResult::Unsafe(_) => continue,
+                PathValidationResult::Unsafe(reason) => {
+                    summary.quarantine(path_str, reason, entry.size());
+                    continue;
+                }
                 PathValidationResult::Valid(p) => PathBuf::from(p),
                 PathValidationResult::RequiresSanitization(_, p) => PathBuf::from(p),
             };
@@ -145,8 +199,7 @@ impl TarHandler {
             // 验证最终路径不逃逸提取目录（防御 TAR Slip 末级绕过）
             if !out_path.starts_with(target_dir) {
                 let msg = format!("TAR Slip 尝试被拦截: {}", safe_path.display());
-                warn!("{}", msg);
-                summary.errors.push(msg);
+                summary.quarantine(path_str, msg, entry.size());
                 continue;
             }
             let size = entry.header().size().unwrap_or(0);
@@ -162,6 +215,13 @@ impl TarHandler {
             }
 
             if entry.header().entry_type().is_file() {
+                if let Some(selection) = selection {
+                    if !selection.matches(&safe_path.to_string_lossy()) {
+                        summary.skip(path_str, size);
+                        continue;
+                    }
+                }
+
                 // Check limits before extraction
                 let would_exceed_limits = size > max_file_size
                     || summary.total_size + size > max_total_size
@@ -197,24 +257,24 @@ impl TarHandler {
                 }
 
                 if let Some(parent) = out_path.parent() {
-                    if let Err(e) = std::fs::create_dir_all(parent) {
+                    if let Err(e) = std::fs::create_dir_all(to_extended_length_path(parent)) {
                         warn!(path = ?parent, error = %e, "创建 TAR 条目父目录失败，跳过此文件");
+                        summary.fail(path_str, e.to_string());
                         continue;
                     }
                 }
 
-                if let Err(e) = entry.unpack(&out_path) {
-                    let msg = format!("Failed to unpack TAR entry {}: {}", out_path.display(), e);
-                    warn!("{}", msg);
-                    summary.errors.push(msg);
+                if let Err(e) = entry.unpack(to_extended_length_path(&out_path)) {
+                    warn!("Failed to unpack TAR entry {}: {}", out_path.display(), e);
+                    summary.fail(path_str, e.to_string());
                 } else {
                     // TOCTOU 防护: 创建后验证实际路径仍在目标目录内
                     if let (Ok(real_path), Ok(real_target)) = (
-                        std::fs::canonicalize(&out_path),
+                        std::fs::canonicalize(to_extended_length_path(&out_path)),
                         std::fs::canonicalize(target_dir),
                     ) {
                         if !real_path.starts_with(&real_target) {
-                            std::fs::remove_file(&out_path).ok();
+                            std::fs::remove_file(to_extended_length_path(&out_path)).ok();
                             let msg = format!("符号链接逃逸已拦截 (TAR): {}", safe_path.display());
                             warn!("{}", msg);
                             summary.errors.push(msg);
@@ -224,7 +284,7 @@ impl TarHandler {
                     summary.add_file(safe_path, size);
                 }
             } else if entry.header().entry_type().is_dir() {
-                if let Err(e) = std::fs::create_dir_all(&out_path) {
+                if let Err(e) = std::fs::create_dir_all(to_extended_length_path(&out_path)) {
                     warn!(path = ?out_path, error = %e, "创建 TAR 目录条目失败，跳过");
                 }
             }
@@ -301,8 +361,23 @@ mod tests {
         assert!(handler.can_handle(Path::new("test.tgz")));
         assert!(handler.can_handle(Path::new("test.TGZ")));
 
-        // 不应该处理纯 .gz 文件（由 GzHandler 处理）
+        // 应该能处理 .tar.zst / .tzst 文件
+        assert!(handler.can_handle(Path::new("bundle.tar.zst")));
+        assert!(handler.can_handle(Path::new("bundle.TAR.ZST")));
+        assert!(handler.can_handle(Path::new("bundle.tzst")));
+
+        // 应该能处理 .tar.xz / .tar.bz2 及其缩写
+        assert!(handler.can_handle(Path::new("journal.tar.xz")));
+        assert!(handler.can_handle(Path::new("journal.txz")));
+        assert!(handler.can_handle(Path::new("rotated.TAR.BZ2")));
+        assert!(handler.can_handle(Path::new("rotated.tbz2")));
+        assert!(handler.can_handle(Path::new("rotated.tbz")));
+
+        // 不应该处理纯 .gz / .zst 文件（由 GzHandler / ZstdHandler 处理）
         assert!(!handler.can_handle(Path::new("test.gz")));
+        assert!(!handler.can_handle(Path::new("test.log.zst")));
+        assert!(!handler.can_handle(Path::new("system.journal.xz")));
+        assert!(!handler.can_handle(Path::new("messages.1.bz2")));
 
         // 不应该处理其他格式
         assert!(!handler.can_handle(Path::new("test.zip")));
@@ -316,7 +391,13 @@ mod tests {
         let handler = TarHandler;
         let extensions = handler.file_extensions();
 
-        assert_eq!(extensions, vec!["tar", "tar.gz", "tgz"]);
+        assert_eq!(
+            extensions,
+            vec![
+                "tar", "tar.gz", "tgz", "tar.zst", "tzst", "tar.xz", "txz", "tar.bz2", "tbz2",
+                "tbz"
+            ]
+        );
     }
 
     #[tokio::test]
@@ -356,6 +437,40 @@ mod tests {
         assert_eq!(content3, "Nested file content");
     }
 
+    #[tokio::test]
+    async fn test_extract_selected_tar_entries() {
+        let temp_dir = TempDir::new().expect("创建临时目录失败");
+        let tar_file = temp_dir.path().join("bundle.tar");
+        let output_dir = temp_dir.path().join("output");
+
+        let files = vec![
+            ("api/app.log", b"api" as &[u8]),
+            ("api/old/app.log", b"old api"),
+            ("db/slow.log", b"db"),
+        ];
+        create_test_tar(&tar_file, files).expect("创建 TAR 文件失败");
+
+        let selection = EntrySelection::new(&["api".to_string()]).unwrap();
+        let summary = TarHandler
+            .extract_selected(
+                &tar_file,
+                &output_dir,
+                1024 * 1024,
+                1024 * 1024,
+                100,
+                &selection,
+            )
+            .await
+            .expect("选择性解压失败");
+
+        assert_eq!(summary.files_extracted, 2);
+        assert!(output_dir.join("api/old/app.log").exists());
+        assert!(!output_dir.join("db/slow.log").exists());
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].entry_name, "db/slow.log");
+        assert_eq!(summary.skipped[0].size, 2);
+    }
+
     #[tokio::test]
     async fn test_extract_tar_gz_file() {
         let temp_dir = TempDir::new().expect("创建临时目录失败");
@@ -385,6 +500,72 @@ mod tests {
         assert_eq!(content1, "Hello from tar.gz!");
     }
 
+    #[tokio::test]
+    async fn test_extract_tar_zst_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let tar_file = temp_dir.path().join("bundle.tar");
+        create_test_tar(
+            &tar_file,
+            vec![
+                ("logs/app.log", b"Hello from tar.zst!" as &[u8]),
+                ("logs/db.log", b"Second file in tar.zst"),
+            ],
+        )
+        .expect("创建 TAR 文件失败");
+        let tar_zst_file = temp_dir.path().join("bundle.tar.zst");
+        let tar_bytes = std::fs::read(&tar_file).unwrap();
+        std::fs::write(&tar_zst_file, zstd::encode_all(&tar_bytes[..], 3).unwrap()).unwrap();
+
+        let output_dir = temp_dir.path().join("output");
+        let summary = TarHandler
+            .extract(&tar_zst_file, &output_dir)
+            .await
+            .expect("解压 TAR.ZST 失败");
+
+        assert_eq!(summary.files_extracted, 2);
+        let content = std::fs::read_to_string(output_dir.join("logs/app.log")).unwrap();
+        assert_eq!(content, "Hello from tar.zst!");
+    }
+
+    #[tokio::test]
+    async fn test_extract_tar_xz_and_tar_bz2_files() {
+        use std::io::Write;
+
+        let temp_dir = TempDir::new().unwrap();
+        let tar_file = temp_dir.path().join("bundle.tar");
+        create_test_tar(
+            &tar_file,
+            vec![("var/log/syslog", b"rotated syslog" as &[u8])],
+        )
+        .expect("创建 TAR 文件失败");
+        let tar_bytes = std::fs::read(&tar_file).unwrap();
+
+        let mut xz = xz2::write::XzEncoder::new(Vec::new(), 6);
+        xz.write_all(&tar_bytes).unwrap();
+        let tar_xz = temp_dir.path().join("bundle.tar.xz");
+        std::fs::write(&tar_xz, xz.finish().unwrap()).unwrap();
+
+        let mut bz2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        bz2.write_all(&tar_bytes).unwrap();
+        let tbz2 = temp_dir.path().join("bundle.tbz2");
+        std::fs::write(&tbz2, bz2.finish().unwrap()).unwrap();
+
+        for source in [tar_xz, tbz2] {
+            let output_dir = temp_dir.path().join(format!(
+                "out-{}",
+                source.extension().unwrap().to_string_lossy()
+            ));
+            let summary = TarHandler
+                .extract(&source, &output_dir)
+                .await
+                .expect("解压压缩 TAR 失败");
+
+            assert_eq!(summary.files_extracted, 1);
+            let content = std::fs::read_to_string(output_dir.join("var/log/syslog")).unwrap();
+            assert_eq!(content, "rotated syslog");
+        }
+    }
+
     #[tokio::test]
     async fn test_extract_tgz_file() {
         let temp_dir = TempDir::new().expect("创建临时目录失败");
@@ -0,0 +1,98 @@
+use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
+use crate::gz_handler::{decompressed_file_name, open_for_streaming, stream_decompress};
+use crate::tar_handler::has_tar_stem;
+use async_compression::tokio::bufread::XzDecoder;
+use async_trait::async_trait;
+use la_core::error::{AppError, Result};
+use std::path::Path;
+
+/**
+ * XZ文件处理器
+ *
+ * 处理单个 xz 压缩文件（`.xz`，如 journald 导出与 logrotate 轮转日志），解压为单个文件；
+ * `.tar.xz` / `.txz` 由 TarHandler 处理。
+ *
+ * 使用流式解压，与 GzHandler 的流式模式共用单文件大小限制与解压炸弹检测。
+ */
+pub struct XzHandler;
+
+#[async_trait]
+impl ArchiveHandler for XzHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|s| s.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("xz") && !has_tar_stem(path))
+            .unwrap_or(false)
+    }
+
+    async fn extract_with_limits(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+    ) -> Result<ExtractionSummary> {
+        // 安全检查：文件数量限制（XZ只包含一个文件）
+        if max_file_count < 1 {
+            return Err(AppError::archive_error(
+                format!("Extraction would exceed file count limit of {max_file_count} files"),
+                Some(source.to_path_buf()),
+            ));
+        }
+
+        let reader = open_for_streaming(source, target_dir, "XZ").await?;
+        let mut decoder = XzDecoder::new(reader);
+        // 多线程 xz 会写出多个连续流
+        decoder.multiple_members(true);
+        let output_path = target_dir.join(decompressed_file_name(source, "xz"));
+        stream_decompress(
+            decoder,
+            source,
+            output_path,
+            max_file_size.min(max_total_size),
+            "xz",
+        )
+        .await
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec!["xz"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn xz_bytes(content: &[u8]) -> Vec<u8> {
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_xz_handler_can_handle() {
+        let handler = XzHandler;
+        assert!(handler.can_handle(Path::new("system.journal.xz")));
+        assert!(handler.can_handle(Path::new("SYSLOG.XZ")));
+        assert!(!handler.can_handle(Path::new("bundle.tar.xz")));
+        assert!(!handler.can_handle(Path::new("app.log.gz")));
+    }
+
+    #[tokio::test]
+    async fn test_extract_xz_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("syslog.1.xz");
+        let content = b"Jan  1 00:00:00 host kernel: eth0 link up\n".repeat(100);
+        std::fs::write(&source, xz_bytes(&content)).unwrap();
+
+        let output_dir = temp_dir.path().join("out");
+        let summary = XzHandler.extract(&source, &output_dir).await.unwrap();
+
+        assert_eq!(summary.files_extracted, 1);
+        assert_eq!(std::fs::read(output_dir.join("syslog.1")).unwrap(), content);
+    }
+}
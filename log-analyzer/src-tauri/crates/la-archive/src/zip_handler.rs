@@ -1,6 +1,9 @@
-use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
+use crate::archive_handler::{ArchiveHandler, ArchivePassword, ExtractionSummary};
+use crate::entry_names::zip_entry_name;
+use crate::entry_selection::EntrySelection;
 use async_trait::async_trait;
 use la_core::error::{AppError, Result};
+use la_core::utils::path::to_extended_length_path;
 use la_core::utils::path_security::{
     validate_and_sanitize_archive_path, PathValidationResult, SecurityConfig,
 };
@@ -8,6 +11,7 @@ use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::warn;
 
+use zip::result::ZipError;
 use zip::ZipArchive;
 
 /**
@@ -31,6 +35,79 @@ impl ArchiveHandler for ZipHandler {
         max_file_size: u64,
         max_total_size: u64,
         max_file_count: usize,
+    ) -> Result<ExtractionSummary> {
+        Self::extract_entries(
+            source,
+            target_dir,
+            max_file_size,
+            max_total_size,
+            max_file_count,
+            None,
+            None,
+        )
+        .await
+    }
+
+    async fn extract_selected(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: &EntrySelection,
+    ) -> Result<ExtractionSummary> {
+        Self::extract_entries(
+            source,
+            target_dir,
+            max_file_size,
+            max_total_size,
+            max_file_count,
+            Some(selection.clone()),
+            None,
+        )
+        .await
+    }
+
+    async fn extract_with_password(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: Option<&EntrySelection>,
+        password: &ArchivePassword,
+    ) -> Result<ExtractionSummary> {
+        Self::extract_entries(
+            source,
+            target_dir,
+            max_file_size,
+            max_total_size,
+            max_file_count,
+            selection.cloned(),
+            Some(password.clone()),
+        )
+        .await
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec!["zip"]
+    }
+}
+
+impl ZipHandler {
+    /// 提取条目；`selection` 为 `Some` 时未选中的文件条目只记录、不写入
+    ///
+    /// 加密条目缺少密码或密码错误时整体失败（`PasswordRequired`），而不是逐条跳过
+    async fn extract_entries(
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+        selection: Option<EntrySelection>,
+        password: Option<ArchivePassword>,
     ) -> Result<ExtractionSummary> {
         fs::create_dir_all(target_dir).await?;
 
@@ -45,18 +122,40 @@ impl ArchiveHandler for ZipHandler {
             let security_config = SecurityConfig::default();
 
             for i in 0..archive.len() {
-                let mut file = match archive.by_index(i) {
+                let entry = match &password {
+                    Some(password) => archive.by_index_decrypt(i, password.as_bytes()),
+                    None => archive.by_index(i),
+                };
+                let mut file = match entry {
                     Ok(f) => f,
+                    Err(ZipError::UnsupportedArchive(msg)) if msg == ZipError::PASSWORD_REQUIRED => {
+                        return Err(AppError::password_required(
+                            "Archive is encrypted; a password is required",
+                            Some(source_path.clone()),
+                        ));
+                    }
+                    Err(ZipError::InvalidPassword) => {
+                        return Err(AppError::password_required(
+                            "Incorrect archive password",
+                            Some(source_path.clone()),
+                        ));
+                    }
                     Err(e) => {
                         warn!("Failed to read zip entry {}: {}", i, e);
                         continue;
                     }
                 };
-                let name = file.name().to_string();
+                let (name, raw_name) = zip_entry_name(file.name(), file.name_raw());
+                if let Some(raw_name) = raw_name.filter(|_| !file.is_dir()) {
+                    summary.record_raw_name(name.replace('\\', "/"), raw_name);
+                }
 
                 let validation = validate_and_sanitize_archive_path(&name, &security_config);
                 let safe_path = match validation {
-                    PathValidationResult::Unsafe(_) => continue,
+                    PathValidationResult::Unsafe(reason) => {
+                        summary.quarantine(name, reason, file.size());
+                        continue;
+                    }
                     PathValidationResult::Valid(p) => PathBuf::from(p),
                     PathValidationResult::RequiresSanitization(_, p) => PathBuf::from(p),
                 };
@@ -65,8 +164,7 @@ impl ArchiveHandler for ZipHandler {
                 // 验证最终路径不逃逸提取目录（防御 ZIP Slip 末级绕过）
                 if !out_path.starts_with(&target_path) {
                     let msg = format!("ZIP Slip 尝试被拦截: {}", safe_path.display());
-                    warn!("{}", msg);
-                    summary.errors.push(msg);
+                    summary.quarantine(name, msg, file.size());
                     continue;
                 }
                 // 安全检查：跳过 ZIP 内的符号链接，防止沙箱逃逸
@@ -89,8 +187,15 @@ impl ArchiveHandler for ZipHandler {
 
                 let size = file.size();
 
+                if let Some(selection) = &selection {
+                    if !file.is_dir() && !selection.matches(&safe_path.to_string_lossy()) {
+                        summary.skip(name, size);
+                        continue;
+                    }
+                }
+
                 if file.is_dir() {
-                    if let Err(e) = std::fs::create_dir_all(&out_path) {
+                    if let Err(e) = std::fs::create_dir_all(to_extended_length_path(&out_path)) {
                         warn!(path = ?out_path, error = %e, "创建 ZIP 目录条目失败，跳过");
                     }
                 } else {
@@ -128,25 +233,25 @@ impl ArchiveHandler for ZipHandler {
                     }
 
                     if let Some(parent) = out_path.parent() {
-                        if let Err(e) = std::fs::create_dir_all(parent) {
+                        if let Err(e) = std::fs::create_dir_all(to_extended_length_path(parent)) {
                             warn!(path = ?parent, error = %e, "创建 ZIP 条目父目录失败，跳过此文件");
+                            summary.fail(name, e.to_string());
                             continue;
                         }
                     }
 
-                    match std::fs::File::create(&out_path) {
+                    match std::fs::File::create(to_extended_length_path(&out_path)) {
                         Ok(mut out_file) => {
                             if let Err(e) = std::io::copy(&mut file, &mut out_file) {
-                                let msg = format!("Failed to extract file {}: {}", out_path.display(), e);
-                                warn!("{}", msg);
-                                summary.errors.push(msg);
+                                warn!("Failed to extract file {}: {}", out_path.display(), e);
+                                summary.fail(name, e.to_string());
                             } else {
                                 // TOCTOU 防护: 创建后验证实际路径仍在目标目录内
                                 if let (Ok(real_path), Ok(real_target)) =
-                                    (std::fs::canonicalize(&out_path), std::fs::canonicalize(&target_path))
+                                    (std::fs::canonicalize(to_extended_length_path(&out_path)), std::fs::canonicalize(&target_path))
                                 {
                                     if !real_path.starts_with(&real_target) {
-                                        std::fs::remove_file(&out_path).ok();
+                                        std::fs::remove_file(to_extended_length_path(&out_path)).ok();
                                         let msg = format!("符号链接逃逸已拦截 (ZIP): {}", safe_path.display());
                                         warn!("{}", msg);
                                         summary.errors.push(msg);
@@ -161,6 +266,7 @@ impl ArchiveHandler for ZipHandler {
                         }
                         Err(e) => {
                             warn!("Failed to create file {:?}: {}", out_path, e);
+                            summary.fail(name, e.to_string());
                         }
                     }
                 }
@@ -180,16 +286,13 @@ impl ArchiveHandler for ZipHandler {
 
         Ok(summary)
     }
-
-    fn file_extensions(&self) -> Vec<&str> {
-        vec!["zip"]
-    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
     use tempfile::TempDir;
+    use zip::unstable::write::FileOptionsExt;
     use zip::write::FileOptions;
 
     /// 创建测试用的 ZIP 文件
@@ -402,6 +505,140 @@ mod tests {
         assert!(output_dir.join("file_with_underscores.txt").exists());
     }
 
+    /// 手工构造只含一个存储条目、条目名为原始字节且不带 UTF-8 标志的 ZIP
+    /// （`ZipWriter` 总是按 UTF-8 写入名称）
+    fn legacy_zip_bytes(raw_name: &[u8], content: &[u8]) -> Vec<u8> {
+        let mut crc = flate2::Crc::new();
+        crc.update(content);
+        let header = |bytes: &mut Vec<u8>| {
+            bytes.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0x21, 0]);
+            bytes.extend_from_slice(&crc.sum().to_le_bytes());
+            bytes.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(raw_name.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(&[0, 0]);
+        };
+
+        let mut zip = 0x0403_4b50u32.to_le_bytes().to_vec();
+        header(&mut zip);
+        zip.extend_from_slice(raw_name);
+        zip.extend_from_slice(content);
+
+        let central_offset = zip.len() as u32;
+        zip.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        zip.extend_from_slice(&[20, 0]);
+        header(&mut zip);
+        zip.extend_from_slice(&[0; 14]);
+        zip.extend_from_slice(raw_name);
+        let central_size = zip.len() as u32 - central_offset;
+
+        zip.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        zip.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+        zip.extend_from_slice(&central_size.to_le_bytes());
+        zip.extend_from_slice(&central_offset.to_le_bytes());
+        zip.extend_from_slice(&[0, 0]);
+        zip
+    }
+
+    #[tokio::test]
+    async fn test_extract_zip_decodes_gbk_entry_names() {
+        let temp_dir = TempDir::new().expect("创建临时目录失败");
+        let zip_file = temp_dir.path().join("legacy.zip");
+        let output_dir = temp_dir.path().join("output");
+        let raw_name = b"logs/\xc4\xe3\xba\xc3.log";
+        std::fs::write(&zip_file, legacy_zip_bytes(raw_name, b"hello")).unwrap();
+
+        let summary = ZipHandler
+            .extract(&zip_file, &output_dir)
+            .await
+            .expect("解压 ZIP 文件失败");
+
+        assert_eq!(summary.files_extracted, 1);
+        assert_eq!(
+            std::fs::read(output_dir.join("logs").join("你好.log")).unwrap(),
+            b"hello"
+        );
+        assert_eq!(
+            summary.raw_names,
+            vec![("logs/你好.log".to_string(), raw_name.to_vec())]
+        );
+
+        // 按原始字节定位条目，即使传入的名称已过时
+        let mut out = Vec::new();
+        let found = crate::entry_stream::copy_entry(
+            &zip_file,
+            "legacy.zip",
+            "logs/─π║├.log",
+            Some(raw_name),
+            &mut out,
+        )
+        .unwrap();
+        assert!(found);
+        assert_eq!(out, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_extract_zip_quarantines_traversal_entries() {
+        let temp_dir = TempDir::new().expect("创建临时目录失败");
+        let zip_file = temp_dir.path().join("evil.zip");
+        let output_dir = temp_dir.path().join("output");
+
+        let files = vec![
+            ("../../etc/passwd", b"root" as &[u8]),
+            ("logs/app.log", b"ok"),
+        ];
+        create_test_zip(&zip_file, files).expect("创建 ZIP 文件失败");
+
+        let summary = ZipHandler
+            .extract(&zip_file, &output_dir)
+            .await
+            .expect("解压 ZIP 文件失败");
+
+        assert_eq!(summary.files_extracted, 1);
+        assert_eq!(summary.quarantined.len(), 1);
+        assert_eq!(summary.quarantined[0].entry_name, "../../etc/passwd");
+        assert_eq!(summary.quarantined[0].size, 4);
+        assert!(!temp_dir.path().join("etc").exists());
+    }
+
+    #[tokio::test]
+    async fn test_extract_zip_with_reserved_names_and_long_paths() {
+        let temp_dir = TempDir::new().expect("创建临时目录失败");
+        let zip_file = temp_dir.path().join("windows.zip");
+        let output_dir = temp_dir.path().join("output");
+
+        // 嵌套目录使完整路径超过 Windows MAX_PATH（260）
+        let deep_dir = (0..12)
+            .map(|i| format!("nested_directory_level_{i:02}"))
+            .collect::<Vec<_>>()
+            .join("/");
+        let deep_file = format!("{deep_dir}/service.log");
+        assert!(deep_file.len() > 260, "前置条件：归档内路径超过 260 字符");
+
+        let files = vec![
+            ("aux.log", b"aux" as &[u8]),
+            ("logs/con/app.log", b"con"),
+            ("trailing. ", b"dots"),
+            (deep_file.as_str(), b"deep"),
+        ];
+        create_test_zip(&zip_file, files).expect("创建 ZIP 文件失败");
+
+        let handler = ZipHandler;
+        let summary = handler
+            .extract(&zip_file, &output_dir)
+            .await
+            .expect("解压 ZIP 文件失败");
+
+        assert_eq!(summary.files_extracted, 4, "errors: {:?}", summary.errors);
+        assert!(output_dir.join("_aux.log").exists());
+        assert!(output_dir.join("logs/_con/app.log").exists());
+        assert!(output_dir.join("trailing__").exists());
+        assert_eq!(
+            std::fs::read(output_dir.join(&deep_file)).unwrap(),
+            b"deep".to_vec()
+        );
+    }
+
     #[tokio::test]
     async fn test_extract_nonexistent_zip() {
         let temp_dir = TempDir::new().expect("创建临时目录失败");
@@ -429,6 +666,45 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_extract_encrypted_zip_requires_password() {
+        let temp_dir = TempDir::new().expect("创建临时目录失败");
+        let zip_file = temp_dir.path().join("secret.zip");
+        let output_dir = temp_dir.path().join("output");
+
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_file).unwrap());
+        let options: zip::write::FileOptions<'_, ()> =
+            FileOptions::default().with_deprecated_encryption(b"s3cret");
+        zip.start_file("app.log", options).unwrap();
+        zip.write_all(b"secret log line").unwrap();
+        zip.finish().unwrap();
+
+        let handler = ZipHandler;
+        let err = handler.extract(&zip_file, &output_dir).await.unwrap_err();
+        assert_eq!(err.category(), la_core::ErrorCategory::PasswordRequired);
+
+        assert_eq!(err.code(), "PASSWORD_REQUIRED");
+
+        let password = ArchivePassword::new("s3cret");
+        let summary = handler
+            .extract_with_password(
+                &zip_file,
+                &output_dir,
+                u64::MAX,
+                u64::MAX,
+                10,
+                None,
+                &password,
+            )
+            .await
+            .expect("使用正确密码解压失败");
+        assert_eq!(summary.files_extracted, 1);
+        assert_eq!(
+            std::fs::read(output_dir.join("app.log")).unwrap(),
+            b"secret log line".to_vec()
+        );
+    }
+
     #[tokio::test]
     async fn test_extract_zip_slip_protection() {
         let temp_dir = TempDir::new().expect("创建临时目录失败");
@@ -0,0 +1,103 @@
+use crate::archive_handler::{ArchiveHandler, ExtractionSummary};
+use crate::gz_handler::{decompressed_file_name, open_for_streaming, stream_decompress};
+use crate::tar_handler::has_tar_stem;
+use async_compression::tokio::bufread::ZstdDecoder;
+use async_trait::async_trait;
+use la_core::error::{AppError, Result};
+use std::path::Path;
+
+/**
+ * ZST文件处理器
+ *
+ * 处理单个 zstd 压缩文件（`.zst`），解压为单个文件；`.tar.zst` / `.tzst` 由 TarHandler 处理。
+ *
+ * zstd 解压速度快且帧内不保证记录原始大小，统一使用流式解压，
+ * 与 GzHandler 的流式模式共用单文件大小限制与解压炸弹检测。
+ */
+pub struct ZstdHandler;
+
+#[async_trait]
+impl ArchiveHandler for ZstdHandler {
+    fn can_handle(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|s| s.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("zst") && !has_tar_stem(path))
+            .unwrap_or(false)
+    }
+
+    async fn extract_with_limits(
+        &self,
+        source: &Path,
+        target_dir: &Path,
+        max_file_size: u64,
+        max_total_size: u64,
+        max_file_count: usize,
+    ) -> Result<ExtractionSummary> {
+        // 安全检查：文件数量限制（ZST只包含一个文件）
+        if max_file_count < 1 {
+            return Err(AppError::archive_error(
+                format!("Extraction would exceed file count limit of {max_file_count} files"),
+                Some(source.to_path_buf()),
+            ));
+        }
+
+        let reader = open_for_streaming(source, target_dir, "ZST").await?;
+        let output_path = target_dir.join(decompressed_file_name(source, "zst"));
+        stream_decompress(
+            ZstdDecoder::new(reader),
+            source,
+            output_path,
+            max_file_size.min(max_total_size),
+            "zstd",
+        )
+        .await
+    }
+
+    fn file_extensions(&self) -> Vec<&str> {
+        vec!["zst"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_zstd_handler_can_handle() {
+        let handler = ZstdHandler;
+        assert!(handler.can_handle(Path::new("app.log.zst")));
+        assert!(handler.can_handle(Path::new("APP.LOG.ZST")));
+        assert!(!handler.can_handle(Path::new("bundle.tar.zst")));
+        assert!(!handler.can_handle(Path::new("app.log.gz")));
+    }
+
+    #[tokio::test]
+    async fn test_extract_zst_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("app.log.zst");
+        let content = b"2024-01-01 ERROR timeout\n".repeat(100);
+        std::fs::write(&source, zstd::encode_all(&content[..], 3).unwrap()).unwrap();
+
+        let output_dir = temp_dir.path().join("out");
+        let summary = ZstdHandler.extract(&source, &output_dir).await.unwrap();
+
+        assert_eq!(summary.files_extracted, 1);
+        assert_eq!(std::fs::read(output_dir.join("app.log")).unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_extract_zst_enforces_size_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("big.log.zst");
+        std::fs::write(&source, zstd::encode_all(&[b'x'; 4096][..], 3).unwrap()).unwrap();
+
+        let output_dir = temp_dir.path().join("out");
+        let result = ZstdHandler
+            .extract_with_limits(&source, &output_dir, 1024, 1024 * 1024, 10)
+            .await;
+
+        assert!(result.is_err());
+        assert!(!output_dir.join("big.log").exists());
+    }
+}
@@ -4,6 +4,9 @@
 //! logic doesn't depend on the Tauri framework directly.
 
 use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::models::{SearchPhaseTimings, SearchResultSummary, SearchTruncation, TruncationReason};
 
 /// Summary statistics emitted when a search completes.
 #[derive(Debug, Clone)]
@@ -11,6 +14,80 @@ pub struct SearchSummary {
     pub total_count: usize,
     pub duration_ms: u64,
     pub was_truncated: bool,
+    /// 搜索被取消；`total_count` 为取消前已写入的部分结果数
+    pub was_cancelled: bool,
+    /// 达到超时后提前停止；`total_count` 为已找到的部分结果数
+    pub timed_out: bool,
+    /// 已完整扫描的文件数
+    pub files_scanned: usize,
+    /// 候选文件总数
+    pub files_total: usize,
+    /// 由 `SearchStatisticsCollector` 生成的关键词、文件与时间分布
+    pub statistics: SearchResultSummary,
+    pub phase_timings: SearchPhaseTimings,
+    /// 搜索提交时工作区的导入进度（百分比）；未在导入时为 None
+    pub import_progress: Option<u8>,
+}
+
+impl SearchSummary {
+    /// 转换为前端摘要结构，结果不完整时附带截断信息
+    pub fn to_result_summary(&self) -> SearchResultSummary {
+        let summary = SearchResultSummary {
+            total_matches: self.total_count,
+            search_duration_ms: self.duration_ms,
+            phase_timings: self.phase_timings,
+            import_progress: self.import_progress,
+            ..self.statistics.clone()
+        };
+        let reason = if self.was_cancelled {
+            TruncationReason::Cancelled
+        } else if self.timed_out {
+            TruncationReason::Timeout
+        } else if self.was_truncated {
+            TruncationReason::MaxResults
+        } else {
+            return summary;
+        };
+        summary.with_truncation(SearchTruncation::new(
+            reason,
+            self.files_scanned,
+            self.files_total,
+        ))
+    }
+}
+
+/// 导入期间检测到的安全告警（如压缩包内路径穿越条目被隔离）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityWarning {
+    pub workspace_id: String,
+    /// 固定为 "security"，便于前端按类别统一展示
+    pub category: &'static str,
+    /// 告警类型，如 "path_traversal"
+    pub kind: &'static str,
+    /// 所属压缩包的虚拟路径
+    pub archive_path: String,
+    /// 压缩包内的原始条目名
+    pub entry_name: String,
+    pub reason: String,
+}
+
+impl SecurityWarning {
+    pub fn path_traversal(
+        workspace_id: impl Into<String>,
+        archive_path: impl Into<String>,
+        entry_name: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            workspace_id: workspace_id.into(),
+            category: "security",
+            kind: "path_traversal",
+            archive_path: archive_path.into(),
+            entry_name: entry_name.into(),
+            reason: reason.into(),
+        }
+    }
 }
 
 /// Publisher for application events consumed by the frontend.
@@ -46,4 +123,7 @@ pub trait EventPublisher: Send + Sync {
 
     /// Emitted when post-import integrity verification finds issues.
     async fn emit_validation_report(&self, workspace_id: &str, report_json: &str);
+
+    /// Emitted when an import quarantines an unsafe archive entry.
+    async fn emit_security_warning(&self, warning: &SecurityWarning);
 }
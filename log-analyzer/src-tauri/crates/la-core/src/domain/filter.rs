@@ -22,6 +22,9 @@ pub struct LineMetadata {
     pub datetime: Option<chrono::NaiveDateTime>,
     /// Bitmask computed from the level (0 if unknown).
     pub level_mask: u8,
+    /// Position in the workspace level order; only computed when a
+    /// minimum-level filter is active.
+    pub level_rank: Option<usize>,
 }
 
 /// Trait for filtering search results by file path and line metadata.
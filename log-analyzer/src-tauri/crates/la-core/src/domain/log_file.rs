@@ -2,11 +2,24 @@
 //!
 //! Abstracts CAS storage and metadata database behind a single facade.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
 use crate::error::Result;
+use crate::models::LogEntry;
 use crate::storage_types::FileMetadata;
 
+/// Per-content parse overrides set by the user (encoding, timezone, parser).
+pub trait ContentOverrides: Send + Sync {
+    /// Decode raw bytes (a whole file or a single line) with the overridden encoding.
+    fn decode(&self, bytes: &[u8]) -> String;
+
+    /// Rewrite timestamps and levels of parsed entries with the parser and timezone overrides.
+    fn apply(&self, entries: &mut [LogEntry]);
+}
+
 /// Repository for reading log file metadata and content.
 #[async_trait]
 pub trait LogFileRepository: Send + Sync {
@@ -20,11 +33,51 @@ pub trait LogFileRepository: Send + Sync {
         file_pattern: Option<&str>,
     ) -> Result<Vec<FileMetadata>>;
 
+    /// Load serialized term sketches (`utils::TermBloom`) keyed by content hash.
+    ///
+    /// Hashes without a sketch are absent from the map and are always scanned.
+    async fn get_term_sketches(&self, _hashes: &[String]) -> Result<HashMap<String, Vec<u8>>> {
+        Ok(HashMap::new())
+    }
+
+    /// Load serialized sparse time indexes (`utils::TimeIndex`) keyed by content hash.
+    ///
+    /// Hashes without an index are absent from the map and are read from the start.
+    async fn get_time_indexes(&self, _hashes: &[String]) -> Result<HashMap<String, Vec<u8>>> {
+        Ok(HashMap::new())
+    }
+
+    /// Load parse overrides keyed by content hash.
+    ///
+    /// Hashes without overrides are absent from the map and are decoded automatically.
+    async fn get_content_overrides(
+        &self,
+        _hashes: &[String],
+    ) -> Result<HashMap<String, Arc<dyn ContentOverrides>>> {
+        Ok(HashMap::new())
+    }
+
+    /// Load non-zero per-file clock offsets (seconds) keyed by virtual path.
+    async fn get_time_offsets(&self) -> Result<HashMap<String, i64>> {
+        Ok(HashMap::new())
+    }
+
+    /// Load the workspace's maximum line length in bytes (`None` = no limit).
+    async fn get_max_line_length(&self) -> Result<Option<usize>> {
+        Ok(None)
+    }
+
+    /// Load the workspace's custom log level order (`None` = default order).
+    async fn get_level_order(&self) -> Result<Option<crate::utils::LevelOrder>> {
+        Ok(None)
+    }
+
     /// Read raw file content by SHA-256 hash (synchronous — called from spawn_blocking).
     fn read_content_sync(&self, hash: &str) -> Result<Vec<u8>>;
 
     /// Read line chunks by SHA-256 hash (synchronous — called from spawn_blocking).
     ///
+    /// Lines are decoded with `overrides` when given, otherwise as UTF-8 (lossy).
     /// The default implementation falls back to `read_content_sync` so tests and
     /// non-CAS adapters keep working. Production CAS overrides this to avoid
     /// loading very large files into memory before the first search results can
@@ -32,16 +85,20 @@ pub trait LogFileRepository: Send + Sync {
     fn read_line_chunks_sync(
         &self,
         hash: &str,
+        overrides: Option<&dyn ContentOverrides>,
         chunk_size: usize,
         visitor: &mut dyn FnMut(Vec<String>, usize) -> Result<bool>,
     ) -> Result<()> {
         let content = self.read_content_sync(hash)?;
-        let text = String::from_utf8_lossy(&content);
+        let text = match overrides {
+            Some(overrides) => overrides.decode(&content),
+            None => String::from_utf8_lossy(&content).into_owned(),
+        };
         let mut lines = Vec::with_capacity(chunk_size);
         let mut chunk_start_line = 1usize;
         let mut next_line = 1usize;
 
-        for line in text.lines() {
+        for line in crate::utils::split_lines(&text) {
             lines.push(line.to_string());
             if lines.len() >= chunk_size {
                 if !visitor(std::mem::take(&mut lines), chunk_start_line)? {
@@ -59,6 +116,38 @@ pub trait LogFileRepository: Send + Sync {
         Ok(())
     }
 
+    /// Read line chunks starting at 1-based `start_line`, whose first byte is at
+    /// `start_offset` in the content (a `utils::TimeIndex` checkpoint).
+    ///
+    /// Chunks carry their real line numbers. The default implementation reads
+    /// from the start and drops the earlier lines; production CAS seeks instead.
+    fn read_line_chunks_from_sync(
+        &self,
+        hash: &str,
+        overrides: Option<&dyn ContentOverrides>,
+        chunk_size: usize,
+        start_offset: u64,
+        start_line: usize,
+        visitor: &mut dyn FnMut(Vec<String>, usize) -> Result<bool>,
+    ) -> Result<()> {
+        let _ = start_offset;
+        self.read_line_chunks_sync(
+            hash,
+            overrides,
+            chunk_size,
+            &mut |lines, chunk_start_line| {
+                if chunk_start_line + lines.len() <= start_line {
+                    return Ok(true);
+                }
+                if chunk_start_line >= start_line {
+                    return visitor(lines, chunk_start_line);
+                }
+                let skipped = start_line - chunk_start_line;
+                visitor(lines.into_iter().skip(skipped).collect(), start_line)
+            },
+        )
+    }
+
     /// Check if a file exists in storage.
     fn file_exists_sync(&self, hash: &str) -> bool;
 }
@@ -24,9 +24,9 @@ pub mod workspace_paths;
 pub use event::EventPublisher;
 pub use extract::{ArchiveEntry, ArchiveExtractor, ExtractionPolicy, ExtractionSummary};
 pub use filter::{Filter, LineMetadata};
-pub use log_file::LogFileRepository;
+pub use log_file::{ContentOverrides, LogFileRepository};
 pub use result_store::{SearchResultPage, SearchResultRepository};
-pub use search::{ExecutionPlan, LogSearcher, MatchPlan};
+pub use search::{ExecutionPlan, LogSearcher, MatchBudget, MatchPlan};
 pub use task::{TaskHandle, TaskScheduler};
 pub use workspace::{WorkspaceInfo, WorkspaceRepository, WorkspaceStatus};
 pub use workspace_paths::WorkspacePaths;
@@ -4,7 +4,9 @@
 //! It is intentionally synchronous — heavy search work runs in spawn_blocking
 //! at the use case level.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
 use crate::error::Result;
 use crate::models::match_detail::MatchDetail;
@@ -41,6 +43,59 @@ pub struct ExecutionPlan {
     /// Typed handle to the adapter's compiled plan.
     /// Set by the adapter in build_plan(), used in match_content().
     pub plan: Option<Arc<dyn MatchPlan>>,
+    /// Matching time budget, set when the plan runs user-supplied regexes.
+    /// Shared by every file searched with this plan.
+    pub budget: Option<Arc<MatchBudget>>,
+}
+
+/// Time budget for matching with user-supplied regexes.
+///
+/// A single pathological pattern can pin a core for minutes; once a line or
+/// a file overruns its budget the searcher records the reason here and the
+/// search loop stops with a "regex too expensive" error.
+///
+/// Only time spent matching counts: reading, decoding and filtering lines do
+/// not, so a slow disk or a busy pool cannot trip the budget. A file searched
+/// in chunks accumulates its matching time across calls.
+#[derive(Debug)]
+pub struct MatchBudget {
+    pub per_line: Duration,
+    pub per_file: Duration,
+    exceeded: OnceLock<String>,
+    spent: Mutex<HashMap<String, Duration>>,
+}
+
+impl MatchBudget {
+    pub fn new(per_line: Duration, per_file: Duration) -> Self {
+        Self {
+            per_line,
+            per_file,
+            exceeded: OnceLock::new(),
+            spent: Mutex::default(),
+        }
+    }
+
+    /// Matching time already charged to `file` by earlier calls.
+    pub fn spent(&self, file: &str) -> Duration {
+        let spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+        spent.get(file).copied().unwrap_or_default()
+    }
+
+    /// Add `elapsed` matching time to `file`.
+    pub fn charge(&self, file: &str, elapsed: Duration) {
+        let mut spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+        *spent.entry(file.to_string()).or_default() += elapsed;
+    }
+
+    /// Record the first overrun; later ones are ignored.
+    pub fn record_exceeded(&self, reason: String) {
+        let _ = self.exceeded.set(reason);
+    }
+
+    /// Reason of the first overrun, if any.
+    pub fn exceeded(&self) -> Option<&str> {
+        self.exceeded.get().map(String::as_str)
+    }
 }
 
 impl std::fmt::Debug for ExecutionPlan {
@@ -50,6 +105,7 @@ impl std::fmt::Debug for ExecutionPlan {
             .field("engine_count", &self.engine_count)
             .field("steps", &self.steps)
             .field("plan", &self.plan.as_ref().map(|_| "Some(..)"))
+            .field("budget", &self.budget)
             .finish()
     }
 }
@@ -190,6 +190,8 @@ pub enum ErrorCategory {
     UnsupportedFormat,
     /// Archive file corrupted
     CorruptedArchive,
+    /// Encrypted archive without a (correct) password
+    PasswordRequired,
     /// Permission denied
     PermissionDenied,
     /// Zip bomb / malicious compression
@@ -265,6 +267,15 @@ impl AppError {
         }
     }
 
+    /// 创建加密压缩包缺少密码或密码错误的错误（错误码 `PASSWORD_REQUIRED`）
+    pub fn password_required(message: impl Into<String>, path: Option<PathBuf>) -> Self {
+        AppError::Archive {
+            category: ErrorCategory::PasswordRequired,
+            _message: message.into(),
+            _path: path,
+        }
+    }
+
     /// 创建验证错误
     pub fn validation_error(message: impl Into<String>) -> Self {
         AppError::Validation {
@@ -538,6 +549,10 @@ impl AppError {
         match self {
             AppError::Io(_) => "IO_ERROR".to_string(),
             AppError::Search { .. } => "SEARCH_ERROR".to_string(),
+            AppError::Archive {
+                category: ErrorCategory::PasswordRequired,
+                ..
+            } => "PASSWORD_REQUIRED".to_string(),
             AppError::Archive { .. } => "ARCHIVE_ERROR".to_string(),
             AppError::Validation { .. } => "VALIDATION_ERROR".to_string(),
             AppError::Security { .. } => "SECURITY_ERROR".to_string(),
@@ -566,6 +581,10 @@ impl AppError {
             AppError::Search { .. } => {
                 Some("Try simplifying your search query or checking the workspace status")
             }
+            AppError::Archive {
+                category: ErrorCategory::PasswordRequired,
+                ..
+            } => Some("Provide the archive password and import again"),
             AppError::Archive { .. } => {
                 Some("Ensure the archive file is not corrupted and is a supported format")
             }
@@ -631,6 +650,17 @@ mod tests {
         assert_eq!(error.code(), "VALIDATION_ERROR");
     }
 
+    #[test]
+    fn test_password_required_has_dedicated_code() {
+        let error = AppError::password_required("Archive is encrypted", None);
+        assert_eq!(error.category(), ErrorCategory::PasswordRequired);
+        assert_eq!(error.code(), "PASSWORD_REQUIRED");
+        assert_eq!(CommandError::from(error).code, "PASSWORD_REQUIRED");
+
+        let error = AppError::archive_error("Archive is truncated", None);
+        assert_eq!(error.code(), "ARCHIVE_ERROR");
+    }
+
     #[test]
     fn test_error_help() {
         let error = AppError::search_error("Query failed");
@@ -343,6 +343,10 @@ pub struct SearchConfig {
     #[serde(default = "default_10_usize")]
     pub max_concurrent_searches: usize,
 
+    /// 单个搜索来源（窗口 / 会话）可同时执行的搜索数，防止一个标签页占满全部槽位
+    #[serde(default = "default_4_usize")]
+    pub max_searches_per_origin: usize,
+
     #[serde(default = "default_true")]
     pub fuzzy_search_enabled: bool,
 
@@ -354,22 +358,32 @@ pub struct SearchConfig {
 
     #[serde(default = "default_1000_usize")]
     pub regex_cache_size: usize,
+
+    /// 单批搜索结果的序列化字节预算：长行结果按字节提前分批，短行结果攒满条数上限
+    #[serde(default = "default_search_batch_max_bytes")]
+    pub batch_max_bytes: usize,
 }
 
 fn default_10_u64() -> u64 {
     10
 }
 
+fn default_search_batch_max_bytes() -> usize {
+    1024 * 1024
+}
+
 impl Default for SearchConfig {
     fn default() -> Self {
         Self {
             max_results: 1000,
             timeout_seconds: 10,
             max_concurrent_searches: 10,
+            max_searches_per_origin: 4,
             fuzzy_search_enabled: true,
             case_sensitive: false,
             regex_enabled: true,
             regex_cache_size: 1000,
+            batch_max_bytes: default_search_batch_max_bytes(),
         }
     }
 }
@@ -398,10 +412,28 @@ impl ConfigValidator for SearchConfig {
             result.add_error(err.field, err.message, err.code);
         }
 
+        if let Some(err) = validate_range(
+            "max_searches_per_origin",
+            self.max_searches_per_origin,
+            1,
+            100,
+        ) {
+            result.add_error(err.field, err.message, err.code);
+        }
+
         if let Some(err) = validate_range("regex_cache_size", self.regex_cache_size, 1, 100000) {
             result.add_error(err.field, err.message, err.code);
         }
 
+        if let Some(err) = validate_range(
+            "batch_max_bytes",
+            self.batch_max_bytes,
+            16 * 1024,
+            64 * 1024 * 1024,
+        ) {
+            result.add_error(err.field, err.message, err.code);
+        }
+
         result
     }
 
@@ -421,10 +453,18 @@ impl ConfigValidator for SearchConfig {
             modified = true;
         }
 
+        if self.max_searches_per_origin == 0 || self.max_searches_per_origin > 100 {
+            modified = true;
+        }
+
         if self.regex_cache_size == 0 || self.regex_cache_size > 100000 {
             modified = true;
         }
 
+        if !(16 * 1024..=64 * 1024 * 1024).contains(&self.batch_max_bytes) {
+            modified = true;
+        }
+
         (result, !modified)
     }
 }
@@ -447,6 +487,34 @@ pub struct MonitoringConfig {
 
     #[serde(default = "default_5_usize")]
     pub max_log_files: usize,
+
+    /// 崩溃与错误上报（默认关闭，需用户显式开启）
+    #[serde(default)]
+    pub sentry: SentryConfig,
+
+    /// 资源监控采样间隔（秒）
+    #[serde(default = "default_30_u64")]
+    pub resource_check_interval_secs: u64,
+
+    /// 可用内存低于该值（MB）时释放缓存并暂停新的导入
+    #[serde(default = "default_512_u64")]
+    pub min_free_memory_mb: u64,
+
+    /// 数据目录所在磁盘可用空间低于该值（MB）时清理搜索缓存并暂停新的导入
+    #[serde(default = "default_1024_u64")]
+    pub min_free_disk_mb: u64,
+
+    /// 本地使用统计（默认关闭；只保存在本机，由用户显式导出）
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+}
+
+fn default_512_u64() -> u64 {
+    512
+}
+
+fn default_1024_u64() -> u64 {
+    1024
 }
 
 fn default_info_level() -> String {
@@ -469,6 +537,11 @@ impl Default for MonitoringConfig {
             tracing_enabled: true,
             log_file: "./logs/app.log".to_string(),
             max_log_files: 5,
+            sentry: SentryConfig::default(),
+            resource_check_interval_secs: 30,
+            min_free_memory_mb: 512,
+            min_free_disk_mb: 1024,
+            telemetry_enabled: false,
         }
     }
 }
@@ -492,6 +565,17 @@ impl ConfigValidator for MonitoringConfig {
             result.add_error(err.field, err.message, err.code);
         }
 
+        if let Some(err) = validate_range(
+            "resource_check_interval_secs",
+            self.resource_check_interval_secs,
+            1,
+            3600,
+        ) {
+            result.add_error(err.field, err.message, err.code);
+        }
+
+        result.merge(self.sentry.validate());
+
         result
     }
 
@@ -508,10 +592,110 @@ impl ConfigValidator for MonitoringConfig {
             modified = true;
         }
 
+        if self.resource_check_interval_secs == 0 || self.resource_check_interval_secs > 3600 {
+            modified = true;
+        }
+
+        if !self.sentry.validate_with_defaults().1 {
+            modified = true;
+        }
+
         (result, !modified)
     }
 }
 
+// ============ Sentry 配置 ============
+
+/// Sentry 错误上报配置
+///
+/// 仅当 `enabled` 为 true 且 `dsn` 非空时才会初始化客户端。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SentryConfig {
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    #[serde(default)]
+    pub dsn: String,
+
+    #[serde(default = "default_sentry_environment")]
+    pub environment: String,
+
+    /// 错误事件采样率（0.0 - 1.0）
+    #[serde(default = "default_1_0_f32")]
+    pub sample_rate: f32,
+
+    /// 性能追踪采样率（0.0 - 1.0）
+    #[serde(default = "default_0_0_f32")]
+    pub traces_sample_rate: f32,
+}
+
+fn default_sentry_environment() -> String {
+    "production".to_string()
+}
+
+fn default_1_0_f32() -> f32 {
+    1.0
+}
+
+fn default_0_0_f32() -> f32 {
+    0.0
+}
+
+impl Default for SentryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dsn: String::new(),
+            environment: "production".to_string(),
+            sample_rate: 1.0,
+            traces_sample_rate: 0.0,
+        }
+    }
+}
+
+impl SentryConfig {
+    /// 是否应初始化 Sentry 客户端
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.dsn.trim().is_empty()
+    }
+}
+
+impl ConfigValidator for SentryConfig {
+    fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+
+        if self.enabled {
+            let dsn = self.dsn.trim();
+            if dsn.is_empty() {
+                result.add_error("sentry.dsn", "启用 Sentry 时 DSN 不能为空", "required");
+            } else if !dsn.starts_with("https://") && !dsn.starts_with("http://") {
+                result.add_error(
+                    "sentry.dsn",
+                    "DSN 必须以 http:// 或 https:// 开头",
+                    "invalid_dsn",
+                );
+            }
+        }
+
+        for (name, value) in [
+            ("sentry.sample_rate", self.sample_rate),
+            ("sentry.traces_sample_rate", self.traces_sample_rate),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                result.add_error(name, "采样率必须在 0.0 到 1.0 之间", "out_of_range");
+            }
+        }
+
+        result
+    }
+
+    fn validate_with_defaults(&self) -> (ValidationResult, bool) {
+        let result = self.validate();
+        let valid = result.is_valid;
+        (result, valid)
+    }
+}
+
 // ============ 安全配置 ============
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -533,6 +717,11 @@ pub struct SecurityConfig {
 
     #[serde(default = "default_wildcard")]
     pub allowed_origins: Vec<String>,
+
+    /// 共享后端的租户密钥（租户 → API 密钥）。客户端以密钥打开会话，会话的租户由
+    /// 密钥决定；`api_key` 为不限租户的运维密钥
+    #[serde(default)]
+    pub tenant_keys: std::collections::BTreeMap<String, String>,
 }
 
 fn default_none<T>() -> Option<T> {
@@ -552,6 +741,7 @@ impl Default for SecurityConfig {
             rate_limit_per_minute: 100,
             cors_enabled: true,
             allowed_origins: vec!["*".to_string()],
+            tenant_keys: std::collections::BTreeMap::new(),
         }
     }
 }
@@ -578,6 +768,38 @@ impl ConfigValidator for SecurityConfig {
             }
         }
 
+        // 验证租户名与租户密钥
+        for (tenant, key) in &self.tenant_keys {
+            let field = format!("tenant_keys.{tenant}");
+            let valid_name = !tenant.is_empty()
+                && tenant.len() <= 64
+                && tenant
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_name {
+                result.add_error(
+                    field.clone(),
+                    "租户名只能包含 1-64 个字母、数字、'-' 或 '_'",
+                    "invalid_tenant",
+                );
+            }
+            if key.len() < 16 || key.len() > 256 {
+                result.add_error(
+                    field.clone(),
+                    "租户密钥长度应为 16-256 个字符",
+                    "tenant_key_length",
+                );
+            }
+            if self.api_key.as_deref() == Some(key.as_str())
+                || self
+                    .tenant_keys
+                    .iter()
+                    .any(|(t, k)| t != tenant && k == key)
+            {
+                result.add_error(field, "租户密钥不能与其他密钥相同", "duplicate_tenant_key");
+            }
+        }
+
         // 验证速率限制
         if let Some(err) = validate_range(
             "rate_limit_per_minute",
@@ -1141,6 +1363,15 @@ pub struct ArchiveConfig {
 
     #[serde(default = "default_64kb")]
     pub copy_buffer_size: u64,
+
+    /// 导入时只提取匹配的压缩包条目（glob，如 `**/*.log`）；为空表示全部。
+    /// 嵌套压缩包不受限制，导入时提供的列表优先
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+
+    /// 导入时不提取的压缩包条目（glob，如 `**/*.png`），优先于包含列表
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
 }
 
 // 辅助默认值函数
@@ -1197,6 +1428,8 @@ impl Default for ArchiveConfig {
             gz_streaming_threshold: 10 * 1024 * 1024,
             file_copy_timeout_seconds: 300,
             copy_buffer_size: 1024 * 1024, // 1MB (优化: 从 64KB 增大)
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
         }
     }
 }
@@ -2059,6 +2292,438 @@ impl ConfigValidator for FrontendConfig {
     }
 }
 
+// ============ 文件监听配置 ============
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchConfig {
+    /// 全局可同时使用原生（inotify / FSEvents / ReadDirectoryChangesW）监听的工作区数，
+    /// 超出后新的监听自动降级为轮询
+    #[serde(default = "default_8_usize")]
+    pub max_native_watchers: usize,
+
+    /// 轮询模式的扫描间隔（毫秒），检测到变更后回落到该值
+    #[serde(default = "default_2000_u64")]
+    pub poll_interval_ms: u64,
+
+    /// 轮询模式空闲时自适应放大的最大扫描间隔（毫秒）
+    #[serde(default = "default_30000_u64")]
+    pub max_poll_interval_ms: u64,
+
+    /// 检测到网络文件系统（NFS / SMB 等，原生事件不可靠）时直接使用轮询
+    #[serde(default = "default_true")]
+    pub poll_network_filesystems: bool,
+}
+
+fn default_8_usize() -> usize {
+    8
+}
+
+fn default_2000_u64() -> u64 {
+    2000
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            max_native_watchers: 8,
+            poll_interval_ms: 2000,
+            max_poll_interval_ms: 30_000,
+            poll_network_filesystems: true,
+        }
+    }
+}
+
+impl ConfigValidator for WatchConfig {
+    fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+
+        if let Some(err) = validate_range("max_native_watchers", self.max_native_watchers, 0, 256) {
+            result.add_error(err.field, err.message, err.code);
+        }
+
+        if let Some(err) = validate_range("poll_interval_ms", self.poll_interval_ms, 100, 600_000) {
+            result.add_error(err.field, err.message, err.code);
+        }
+
+        if self.max_poll_interval_ms < self.poll_interval_ms {
+            result.add_error(
+                "max_poll_interval_ms",
+                "max_poll_interval_ms must be >= poll_interval_ms",
+                "out_of_range",
+            );
+        }
+
+        result
+    }
+
+    fn validate_with_defaults(&self) -> (ValidationResult, bool) {
+        let result = self.validate();
+        let modified = self.max_native_watchers > 256
+            || self.poll_interval_ms < 100
+            || self.poll_interval_ms > 600_000
+            || self.max_poll_interval_ms < self.poll_interval_ms;
+        (result, !modified)
+    }
+}
+
+// ============ 外部链接模板配置 ============
+
+/// 外部链接模板：用正则从日志内容捕获字段（如工单号），按 URL 模板生成链接
+///
+/// `url_template` 中 `{0}` 为整个匹配，`{1}`、`{2}`… 为捕获组，`{name}` 为命名捕获组。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LinkTemplate {
+    pub id: String,
+    /// 展示名称，例如 "Jira"
+    pub label: String,
+    pub pattern: String,
+    pub url_template: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LinksConfig {
+    #[serde(default)]
+    pub templates: Vec<LinkTemplate>,
+}
+
+impl LinkTemplate {
+    /// 校验单个模板；返回第一个错误信息
+    pub fn check(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("link template id must not be empty".to_string());
+        }
+        if let Err(e) = regex::Regex::new(&self.pattern) {
+            return Err(format!(
+                "invalid pattern for link template '{}': {e}",
+                self.id
+            ));
+        }
+        let url = self.url_template.trim_start().to_ascii_lowercase();
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(format!(
+                "url_template for link template '{}' must start with http:// or https://",
+                self.id
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ConfigValidator for LinksConfig {
+    fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for template in &self.templates {
+            if let Err(message) = template.check() {
+                result.add_error("links.templates", message, "invalid_link_template");
+            } else if !seen.insert(template.id.as_str()) {
+                result.add_error(
+                    "links.templates",
+                    format!("duplicate link template id '{}'", template.id),
+                    "duplicate_link_template",
+                );
+            }
+        }
+
+        result
+    }
+
+    fn validate_with_defaults(&self) -> (ValidationResult, bool) {
+        let result = self.validate();
+        let valid = result.is_valid;
+        (result, valid)
+    }
+}
+
+// ============ 日志翻译配置 ============
+
+/// 日志翻译：对可见条目按需调用翻译提供者，译文与原文一并返回
+///
+/// `provider` 为 `dictionary` 时使用本地词典（`dictionary` 中的短语按最长匹配替换）；
+/// 其他名称指向运行时注册的外部翻译服务。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TranslationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_translation_provider")]
+    pub provider: String,
+    /// 目标语言（BCP 47，如 `en`）
+    #[serde(default = "default_translation_target")]
+    pub target_language: String,
+    /// 本地词典：原文短语 → 译文
+    #[serde(default)]
+    pub dictionary: std::collections::BTreeMap<String, String>,
+    /// 译文缓存条目数
+    #[serde(default = "default_translation_cache_entries")]
+    pub cache_entries: u64,
+}
+
+pub const DICTIONARY_TRANSLATION_PROVIDER: &str = "dictionary";
+
+fn default_translation_provider() -> String {
+    DICTIONARY_TRANSLATION_PROVIDER.to_string()
+}
+
+fn default_translation_target() -> String {
+    "en".to_string()
+}
+
+fn default_translation_cache_entries() -> u64 {
+    10_000
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_translation_provider(),
+            target_language: default_translation_target(),
+            dictionary: Default::default(),
+            cache_entries: default_translation_cache_entries(),
+        }
+    }
+}
+
+impl ConfigValidator for TranslationConfig {
+    fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+
+        if self.provider.trim().is_empty() {
+            result.add_error(
+                "translation.provider",
+                "translation provider must not be empty",
+                "empty_translation_provider",
+            );
+        }
+        if self.target_language.trim().is_empty() {
+            result.add_error(
+                "translation.target_language",
+                "target language must not be empty",
+                "empty_target_language",
+            );
+        }
+        if self.dictionary.keys().any(|k| k.trim().is_empty()) {
+            result.add_error(
+                "translation.dictionary",
+                "dictionary phrases must not be empty",
+                "empty_dictionary_phrase",
+            );
+        }
+        if !(1..=1_000_000).contains(&self.cache_entries) {
+            result.add_error(
+                "translation.cache_entries",
+                "cache_entries must be between 1 and 1000000",
+                "invalid_cache_entries",
+            );
+        }
+
+        result
+    }
+
+    fn validate_with_defaults(&self) -> (ValidationResult, bool) {
+        let result = self.validate();
+        let valid = result.is_valid;
+        (result, valid)
+    }
+}
+
+// ============ 导出目的地配置 ============
+
+/// 导出目的地：剪贴板上限与可选的自定义命令钩子
+///
+/// 命令钩子只能由配置定义，前端按名称选择，不能在导出请求中传入任意命令。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExportConfig {
+    /// 导出到剪贴板的最大字节数
+    #[serde(default = "default_clipboard_max_bytes")]
+    pub clipboard_max_bytes: u64,
+    /// SFTP 上传使用的客户端程序（OpenSSH `sftp`，以 BatchMode 运行，只支持密钥认证）
+    #[serde(default = "default_sftp_program")]
+    pub sftp_program: String,
+    /// 目的地执行超时（秒）
+    #[serde(default = "default_export_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub commands: Vec<ExportCommand>,
+}
+
+/// 导出命令钩子：以导出文件路径为参数执行外部程序
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExportCommand {
+    pub name: String,
+    pub program: String,
+    /// 参数中的 `{path}` 替换为导出文件路径，`{format}` 替换为 `csv` / `json`
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+fn default_clipboard_max_bytes() -> u64 {
+    1024 * 1024
+}
+
+fn default_sftp_program() -> String {
+    "sftp".to_string()
+}
+
+fn default_export_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            clipboard_max_bytes: default_clipboard_max_bytes(),
+            sftp_program: default_sftp_program(),
+            timeout_secs: default_export_timeout_secs(),
+            commands: Vec::new(),
+        }
+    }
+}
+
+impl ConfigValidator for ExportConfig {
+    fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+
+        if !(1024..=64 * 1024 * 1024).contains(&self.clipboard_max_bytes) {
+            result.add_error(
+                "export.clipboard_max_bytes",
+                "clipboard_max_bytes must be between 1 KiB and 64 MiB",
+                "invalid_clipboard_max_bytes",
+            );
+        }
+        if self.sftp_program.trim().is_empty() {
+            result.add_error(
+                "export.sftp_program",
+                "sftp_program must not be empty",
+                "empty_sftp_program",
+            );
+        }
+        if !(1..=3600).contains(&self.timeout_secs) {
+            result.add_error(
+                "export.timeout_secs",
+                "timeout_secs must be between 1 and 3600",
+                "invalid_export_timeout",
+            );
+        }
+        let mut seen = std::collections::HashSet::new();
+        for command in &self.commands {
+            if command.name.trim().is_empty() || command.program.trim().is_empty() {
+                result.add_error(
+                    "export.commands",
+                    "export command name and program must not be empty",
+                    "invalid_export_command",
+                );
+            } else if !seen.insert(command.name.as_str()) {
+                result.add_error(
+                    "export.commands",
+                    format!("duplicate export command '{}'", command.name),
+                    "duplicate_export_command",
+                );
+            }
+        }
+
+        result
+    }
+
+    fn validate_with_defaults(&self) -> (ValidationResult, bool) {
+        let result = self.validate();
+        let valid = result.is_valid;
+        (result, valid)
+    }
+}
+
+// ============ 缓存容量配置 ============
+
+/// 内存缓存（L1：各工作区的正则引擎与查询计划缓存）的容量策略
+///
+/// 开启自适应时，容量 = 可用内存 × `memory_percent`% ÷ 已打开工作区数 ÷ 实测单条平均大小，
+/// 限制在 `[min_entries, max_entries]` 内，并每隔 `reevaluate_interval_secs` 重新评估；
+/// 关闭时使用固定的 `search.regex_cache_size`。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    #[serde(default = "default_true")]
+    pub adaptive: bool,
+    /// L1 缓存合计可占用的可用内存比例（百分比）
+    #[serde(default = "default_cache_memory_percent")]
+    pub memory_percent: u64,
+    #[serde(default = "default_cache_min_entries")]
+    pub min_entries: usize,
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+    #[serde(default = "default_cache_reevaluate_interval_secs")]
+    pub reevaluate_interval_secs: u64,
+}
+
+fn default_cache_memory_percent() -> u64 {
+    2
+}
+
+fn default_cache_min_entries() -> usize {
+    100
+}
+
+fn default_cache_max_entries() -> usize {
+    20_000
+}
+
+fn default_cache_reevaluate_interval_secs() -> u64 {
+    300
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            adaptive: true,
+            memory_percent: default_cache_memory_percent(),
+            min_entries: default_cache_min_entries(),
+            max_entries: default_cache_max_entries(),
+            reevaluate_interval_secs: default_cache_reevaluate_interval_secs(),
+        }
+    }
+}
+
+impl ConfigValidator for CacheConfig {
+    fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+
+        if let Some(err) = validate_range("cache.memory_percent", self.memory_percent, 1, 50) {
+            result.add_error(err.field, err.message, err.code);
+        }
+        if let Some(err) = validate_range("cache.min_entries", self.min_entries, 1, 100_000) {
+            result.add_error(err.field, err.message, err.code);
+        }
+        if let Some(err) = validate_range("cache.max_entries", self.max_entries, 1, 1_000_000) {
+            result.add_error(err.field, err.message, err.code);
+        }
+        if self.min_entries > self.max_entries {
+            result.add_error(
+                "cache.min_entries",
+                "min_entries must not exceed max_entries",
+                "invalid_cache_bounds",
+            );
+        }
+        if let Some(err) = validate_range(
+            "cache.reevaluate_interval_secs",
+            self.reevaluate_interval_secs,
+            10,
+            86_400,
+        ) {
+            result.add_error(err.field, err.message, err.code);
+        }
+
+        result
+    }
+
+    fn validate_with_defaults(&self) -> (ValidationResult, bool) {
+        let result = self.validate();
+        let valid = result.is_valid;
+        (result, valid)
+    }
+}
+
 // ============ 统一应用配置根结构 ============
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -2106,6 +2771,21 @@ pub struct AppConfig {
 
     #[serde(default)]
     pub frontend: FrontendConfig,
+
+    #[serde(default)]
+    pub watch: WatchConfig,
+
+    #[serde(default)]
+    pub links: LinksConfig,
+
+    #[serde(default)]
+    pub translation: TranslationConfig,
+
+    #[serde(default)]
+    pub export: ExportConfig,
+
+    #[serde(default)]
+    pub cache: CacheConfig,
 }
 
 impl Default for AppConfig {
@@ -2125,6 +2805,11 @@ impl Default for AppConfig {
             database: DatabaseConfig::default(),
             rate_limit: RateLimitConfig::default(),
             frontend: FrontendConfig::default(),
+            watch: WatchConfig::default(),
+            links: LinksConfig::default(),
+            translation: TranslationConfig::default(),
+            export: ExportConfig::default(),
+            cache: CacheConfig::default(),
         }
     }
 }
@@ -2146,6 +2831,11 @@ impl ConfigValidator for AppConfig {
         result.merge(self.database.validate());
         result.merge(self.rate_limit.validate());
         result.merge(self.frontend.validate());
+        result.merge(self.watch.validate());
+        result.merge(self.links.validate());
+        result.merge(self.translation.validate());
+        result.merge(self.export.validate());
+        result.merge(self.cache.validate());
 
         result
     }
@@ -2170,6 +2860,11 @@ impl ConfigValidator for AppConfig {
             ("database", self.database.validate_with_defaults()),
             ("rate_limit", self.rate_limit.validate_with_defaults()),
             ("frontend", self.frontend.validate_with_defaults()),
+            ("watch", self.watch.validate_with_defaults()),
+            ("links", self.links.validate_with_defaults()),
+            ("translation", self.translation.validate_with_defaults()),
+            ("export", self.export.validate_with_defaults()),
+            ("cache", self.cache.validate_with_defaults()),
         ];
 
         for (name, (sub_result, is_valid)) in validations {
@@ -117,6 +117,156 @@ mod tests {
         assert!(!result.is_valid);
     }
 
+    #[test]
+    fn test_search_config_invalid_max_searches_per_origin() {
+        let config = SearchConfig {
+            max_searches_per_origin: 0,
+            ..Default::default()
+        };
+        let result = config.validate();
+        assert!(!result.is_valid);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.field == "max_searches_per_origin"));
+    }
+
+    #[test]
+    fn test_search_config_batch_max_bytes_range() {
+        let config: SearchConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.batch_max_bytes, 1024 * 1024);
+
+        let config = SearchConfig {
+            batch_max_bytes: 1024,
+            ..Default::default()
+        };
+        let result = config.validate();
+        assert!(result.errors.iter().any(|e| e.field == "batch_max_bytes"));
+        assert!(!config.validate_with_defaults().1);
+    }
+
+    // ============ WatchConfig 验证测试 ============
+
+    #[test]
+    fn test_watch_config_valid() {
+        let config = WatchConfig::default();
+        assert!(config.validate().is_valid);
+        assert!(config.validate_with_defaults().1);
+    }
+
+    #[test]
+    fn test_watch_config_invalid_poll_interval() {
+        let config = WatchConfig {
+            poll_interval_ms: 10,
+            ..Default::default()
+        };
+        assert!(!config.validate().is_valid);
+        assert!(!config.validate_with_defaults().1);
+    }
+
+    #[test]
+    fn test_watch_config_max_poll_interval_below_min() {
+        let config = WatchConfig {
+            poll_interval_ms: 5000,
+            max_poll_interval_ms: 1000,
+            ..Default::default()
+        };
+        assert!(!config.validate().is_valid);
+    }
+
+    // ============ LinksConfig 验证测试 ============
+
+    fn link_template(id: &str, pattern: &str, url: &str) -> LinkTemplate {
+        LinkTemplate {
+            id: id.to_string(),
+            label: "Jira".to_string(),
+            pattern: pattern.to_string(),
+            url_template: url.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_links_config_rejects_bad_templates() {
+        let valid = link_template(
+            "jira",
+            r"([A-Z]+-\d+)",
+            "https://jira.example.com/browse/{1}",
+        );
+        assert!(
+            LinksConfig {
+                templates: vec![valid.clone()],
+            }
+            .validate()
+            .is_valid
+        );
+
+        for invalid in [
+            link_template("", "x", "https://example.com"),
+            link_template("bad-regex", "(", "https://example.com"),
+            link_template("script", "x", "javascript:alert({0})"),
+        ] {
+            assert!(invalid.check().is_err(), "{invalid:?}");
+        }
+
+        let duplicated = LinksConfig {
+            templates: vec![valid.clone(), valid],
+        };
+        assert!(!duplicated.validate().is_valid);
+    }
+
+    // ============ ExportConfig 验证测试 ============
+
+    #[test]
+    fn test_export_config_rejects_bad_commands_and_limits() {
+        let command = ExportCommand {
+            name: "upload".to_string(),
+            program: "/usr/local/bin/upload-report".to_string(),
+            args: vec!["{path}".to_string()],
+        };
+        let config = ExportConfig {
+            commands: vec![command.clone()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_valid);
+
+        let duplicated = ExportConfig {
+            commands: vec![command.clone(), command],
+            ..Default::default()
+        };
+        assert!(!duplicated.validate().is_valid);
+
+        let tiny_clipboard = ExportConfig {
+            clipboard_max_bytes: 10,
+            ..Default::default()
+        };
+        assert!(!tiny_clipboard.validate().is_valid);
+    }
+
+    // ============ CacheConfig 验证测试 ============
+
+    #[test]
+    fn test_cache_config_rejects_inverted_bounds_and_large_share() {
+        assert!(CacheConfig::default().validate().is_valid);
+
+        let inverted = CacheConfig {
+            min_entries: 5_000,
+            max_entries: 1_000,
+            ..Default::default()
+        };
+        assert!(!inverted.validate().is_valid);
+
+        let greedy = CacheConfig {
+            memory_percent: 80,
+            ..Default::default()
+        };
+        assert!(!greedy.validate().is_valid);
+
+        // 旧配置文件没有 cache 节时使用默认值
+        let config: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.cache, CacheConfig::default());
+    }
+
     // ============ MonitoringConfig 验证测试 ============
 
     #[test]
@@ -137,6 +287,41 @@ mod tests {
         assert!(result.errors.iter().any(|e| e.field == "log_level"));
     }
 
+    #[test]
+    fn test_sentry_config_disabled_by_default() {
+        let config = SentryConfig::default();
+        assert!(!config.is_active());
+        assert!(config.validate().is_valid);
+    }
+
+    #[test]
+    fn test_sentry_config_requires_dsn_when_enabled() {
+        let config = SentryConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let result = config.validate();
+        assert!(!result.is_valid);
+        assert!(result.errors.iter().any(|e| e.field == "sentry.dsn"));
+    }
+
+    #[test]
+    fn test_sentry_config_invalid_sample_rate() {
+        let config = MonitoringConfig {
+            sentry: SentryConfig {
+                sample_rate: 1.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = config.validate();
+        assert!(!result.is_valid);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.field == "sentry.sample_rate"));
+    }
+
     // ============ SecurityConfig 验证测试 ============
 
     #[test]
@@ -157,6 +342,29 @@ mod tests {
         assert!(result.errors.iter().any(|e| e.field == "api_key"));
     }
 
+    #[test]
+    fn test_security_config_tenant_keys() {
+        let mut config = SecurityConfig {
+            api_key: Some("o".repeat(16)),
+            ..Default::default()
+        };
+        config.tenant_keys.insert("acme".into(), "a".repeat(16));
+        assert!(config.validate().is_valid);
+
+        config
+            .tenant_keys
+            .insert("bad tenant".into(), "b".repeat(16));
+        config.tenant_keys.insert("globex".into(), "o".repeat(16));
+        config.tenant_keys.insert("initech".into(), "short".into());
+        let result = config.validate();
+        for field in ["bad tenant", "globex", "initech"] {
+            assert!(result
+                .errors
+                .iter()
+                .any(|e| e.field == format!("tenant_keys.{field}")));
+        }
+    }
+
     #[test]
     fn test_security_config_invalid_origin() {
         let config = SecurityConfig {
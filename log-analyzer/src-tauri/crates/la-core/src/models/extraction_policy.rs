@@ -17,6 +17,18 @@ pub struct HandlersConfig {
     /// Enable GZ handler
     #[serde(default = "default_true")]
     pub gz: bool,
+    /// Enable ZST handler (single-file zstd; `.tar.zst` is handled by TAR)
+    #[serde(default = "default_true")]
+    pub zstd: bool,
+    /// Enable XZ handler (single-file xz; `.tar.xz` is handled by TAR)
+    #[serde(default = "default_true")]
+    pub xz: bool,
+    /// Enable BZ2 handler (single-file bzip2; `.tar.bz2` is handled by TAR)
+    #[serde(default = "default_true")]
+    pub bzip2: bool,
+    /// Enable LZ4 handler (single-file LZ4 frame format)
+    #[serde(default = "default_true")]
+    pub lz4: bool,
     /// Enable 7Z handler
     #[serde(default = "default_true")]
     pub sevenz: bool,
@@ -35,12 +47,33 @@ impl Default for HandlersConfig {
             zip: true,
             tar: true,
             gz: true,
+            zstd: true,
+            xz: true,
+            bzip2: true,
+            lz4: true,
             sevenz: true,
             rar: true,
         }
     }
 }
 
+/// How symbolic links found while importing a directory are treated.
+///
+/// Hard links are ordinary directory entries and are imported as regular
+/// files; identical content is deduplicated by the CAS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkPolicy {
+    /// Ignore symlinks entirely (default)
+    #[default]
+    Skip,
+    /// Follow links whose target stays inside the import root; links that
+    /// escape the root or revisit an already walked directory are skipped
+    FollowWithinRoot,
+    /// Do not follow; record the link and its target in the metadata store
+    RecordAsMetadata,
+}
+
 /// Complete extraction policy configuration loaded from TOML
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractionPolicy {
@@ -77,6 +110,16 @@ pub struct ExtractionConfig {
 
     /// Use enhanced extraction system (default: false for backward compatibility)
     pub use_enhanced_extraction: bool,
+
+    /// Only extract archive entries matching these globs (e.g. `**/*.log`);
+    /// empty extracts everything. Nested archives are always opened
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+
+    /// Never extract archive entries matching these globs; wins over
+    /// `include_patterns`
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
 }
 
 /// Security and zip bomb detection parameters
@@ -90,6 +133,10 @@ pub struct SecurityConfig {
 
     /// Enable zip bomb detection
     pub enable_zip_bomb_detection: bool,
+
+    /// Symlink handling for directory imports
+    #[serde(default)]
+    pub symlink_policy: SymlinkPolicy,
 }
 
 /// Path management configuration
@@ -154,11 +201,14 @@ impl Default for ExtractionPolicy {
                 concurrent_extractions: 0,          // Auto-detect
                 buffer_size: 65_536,                // 64KB
                 use_enhanced_extraction: false,     // Default to false for backward compatibility
+                include_patterns: Vec::new(),
+                exclude_patterns: Vec::new(),
             },
             security: SecurityConfig {
                 compression_ratio_threshold: 100.0,
                 exponential_backoff_threshold: 1_000_000.0,
                 enable_zip_bomb_detection: true,
+                symlink_policy: SymlinkPolicy::Skip,
             },
             paths: PathsConfig {
                 enable_long_paths: true,
@@ -402,4 +452,23 @@ mod tests {
         assert!(!policy.handlers.rar);
         assert!(policy.validate().is_ok());
     }
+
+    #[test]
+    fn test_symlink_policy_defaults_and_parse() {
+        assert_eq!(
+            ExtractionPolicy::default().security.symlink_policy,
+            SymlinkPolicy::Skip
+        );
+
+        let mut toml_str = toml::to_string(&ExtractionPolicy::default()).unwrap();
+        toml_str = toml_str.replace(
+            "symlink_policy = \"skip\"",
+            "symlink_policy = \"follow_within_root\"",
+        );
+        let policy = ExtractionPolicy::from_toml_str(&toml_str).unwrap();
+        assert_eq!(
+            policy.security.symlink_policy,
+            SymlinkPolicy::FollowWithinRoot
+        );
+    }
 }
@@ -2,8 +2,12 @@
 //!
 //! 本模块定义了搜索过滤条件和性能监控相关的数据结构。
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::utils::LevelOrder;
+
 /// 高级搜索过滤器
 ///
 /// 支持按时间范围、日志级别和文件模式等条件过滤搜索结果。
@@ -15,8 +19,30 @@ pub struct SearchFilters {
     pub time_end: Option<String>,
     /// 允许的日志级别列表
     pub levels: Vec<String>,
+    /// 最低日志级别（如 `WARN` 表示 `level >= WARN`），按工作区的级别顺序比较；为空表示不限
+    #[serde(default)]
+    pub min_level: Option<String>,
     /// 文件路径匹配模式
     pub file_pattern: Option<String>,
+    /// 只搜索匹配任一 glob 的虚拟路径（如 `**/app-server/**/*.log`），为空表示不限
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+    /// 排除匹配任一 glob 的虚拟路径
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
+    /// 跨文件去重窗口（条数）：时间戳与内容相同、来自不同文件的结果只保留一条，
+    /// 并在 `source_files` 中列出全部来源文件；为空表示不去重
+    #[serde(default)]
+    pub dedup_window: Option<usize>,
+    /// 文件时钟偏移（秒，按虚拟路径）；由服务端从元数据填充，时间过滤按校正后的时间比较
+    #[serde(skip)]
+    pub time_offsets: BTreeMap<String, i64>,
+    /// 单行最大字节数，超出部分截断并标记；由服务端从工作区设置填充，为空表示不截断
+    #[serde(skip)]
+    pub max_line_length: Option<usize>,
+    /// 工作区自定义的级别顺序；由服务端从工作区设置填充，为空时使用默认顺序
+    #[serde(skip)]
+    pub level_order: Option<LevelOrder>,
 }
 
 /// 性能监控指标
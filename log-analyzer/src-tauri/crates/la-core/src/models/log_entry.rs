@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use super::match_detail::MatchDetail;
@@ -20,6 +21,64 @@ pub struct LogEntry {
     /// 匹配的关键词列表（可选，用于统计面板）
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub matched_keywords: Option<Vec<String>>,
+    /// 所在文件的时钟偏移（秒，已计入时间过滤与排序；时间戳文本保持原样）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub time_offset_secs: Option<i64>,
+    /// 按外部链接模板从内容解析出的链接（返回前端前由服务端填充）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub links: Option<Vec<ExternalLink>>,
+    /// 内容的译文（可见条目按需翻译后由服务端填充，原文保留在 `content`）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub translation: Option<String>,
+    /// 跨文件去重后保留的代表条目：出现过该条目的全部文件（含自身所在文件）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub source_files: Option<Vec<String>>,
+    /// 超长行被截断时原始行的字节长度：`content` 为截断后的前缀加 [`TRUNCATION_MARKER`]，
+    /// 完整内容可按行号与字节偏移通过 `read_file_by_hash` 读取
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub original_length: Option<usize>,
+    /// 按工作区字段提取器从内容提取的字段（展示列，返回前端前由服务端填充）
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub fields: Option<BTreeMap<String, String>>,
+}
+
+/// 截断超长行时追加在内容末尾的标记
+pub const TRUNCATION_MARKER: &str = " …[truncated]";
+
+impl LogEntry {
+    /// 内容超过 `max_bytes` 字节时在字符边界截断并追加 [`TRUNCATION_MARKER`]
+    ///
+    /// 落在截断点之后的匹配位置被清除（匹配详情本身保留，供关键词统计），
+    /// 跨越截断点的位置收窄到截断点。返回是否发生了截断。
+    pub fn truncate_content(&mut self, max_bytes: usize) -> bool {
+        if self.content.len() <= max_bytes {
+            return false;
+        }
+        let mut cut = max_bytes;
+        while !self.content.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        self.original_length = Some(self.content.len());
+        self.content = format!("{}{TRUNCATION_MARKER}", &self.content[..cut]).into();
+        for detail in self.match_details.iter_mut().flatten() {
+            detail.match_position = detail
+                .match_position
+                .filter(|(start, _)| *start < cut)
+                .map(|(start, end)| (start, end.min(cut)));
+        }
+        true
+    }
+}
+
+/// 外部系统链接（如工单），由链接模板从日志内容解析得到
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalLink {
+    pub template_id: String,
+    pub label: String,
+    /// 捕获到的文本，例如 "OPS-1234"
+    pub text: String,
+    pub url: String,
 }
 
 /// 任务进度
@@ -42,3 +101,63 @@ pub struct FileChangeEvent {
     pub workspace_id: String, // 所属工作区
     pub timestamp: i64,       // 事件发生时间戳
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(content: &str, positions: &[(usize, usize)]) -> LogEntry {
+        LogEntry {
+            id: 0,
+            timestamp: "".into(),
+            level: "INFO".into(),
+            file: "a.log".into(),
+            real_path: "a.log".into(),
+            line: 1,
+            content: content.into(),
+            tags: vec![],
+            match_details: Some(
+                positions
+                    .iter()
+                    .map(|&p| MatchDetail {
+                        term_id: "t".into(),
+                        term_value: "x".into(),
+                        priority: 1,
+                        match_position: Some(p),
+                    })
+                    .collect(),
+            ),
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn truncate_content_cuts_on_char_boundary_and_clamps_matches() {
+        // "é" 占两个字节，截断点 4 落在第二个 "é" 中间，应回退到 3
+        let mut e = entry("aééxyz", &[(0, 1), (1, 5), (6, 8)]);
+        assert!(e.truncate_content(4));
+        assert_eq!(&*e.content, format!("aé{TRUNCATION_MARKER}"));
+        assert_eq!(e.original_length, Some(8));
+        let positions: Vec<_> = e
+            .match_details
+            .unwrap()
+            .iter()
+            .map(|d| d.match_position)
+            .collect();
+        assert_eq!(positions, vec![Some((0, 1)), Some((1, 3)), None]);
+    }
+
+    #[test]
+    fn truncate_content_leaves_short_lines_alone() {
+        let mut e = entry("short", &[]);
+        assert!(!e.truncate_content(5));
+        assert_eq!(&*e.content, "short");
+        assert_eq!(e.original_length, None);
+    }
+}
@@ -1,3 +1,5 @@
+// config/tests.rs 内嵌 `mod tests`
+#[allow(clippy::module_inception)]
 pub mod config;
 pub mod extraction_policy;
 pub mod filters;
@@ -12,10 +14,10 @@ pub mod validated;
 
 // 重新导出核心类型
 pub use config::{AppConfig, ConfigLoader, FileFilterConfig, FilterMode};
-pub use extraction_policy::{ExtractionPolicy, HandlersConfig};
+pub use extraction_policy::{ExtractionPolicy, HandlersConfig, SymlinkPolicy};
 pub use filters::{PerformanceMetrics, SearchFilters};
 pub use import_decision::{FileTypeInfo, ImportDecision, ImportDecisionDetails, RejectionReason};
-pub use log_entry::{FileChangeEvent, LogEntry, TaskProgress};
+pub use log_entry::{ExternalLink, FileChangeEvent, LogEntry, TaskProgress, TRUNCATION_MARKER};
 pub use match_detail::MatchDetail;
 pub use policy_manager::PolicyManager;
 pub use processing_report::{
@@ -23,4 +25,7 @@ pub use processing_report::{
     ProcessingStatistics, ProcessingStatus,
 };
 pub use search::*;
-pub use search_statistics::{KeywordStatistics, SearchResultSummary};
+pub use search_statistics::{
+    FileStatistics, KeywordStatistics, MatchTimeRange, ResultSource, SearchPhaseTimings,
+    SearchResultSummary, SearchStatisticsCollector, SearchTruncation, TruncationReason,
+};
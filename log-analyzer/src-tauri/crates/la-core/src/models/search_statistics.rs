@@ -1,5 +1,10 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::models::LogEntry;
+use crate::utils::TimestampParser;
+
 /// 关键词统计信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeywordStatistics {
@@ -30,6 +35,82 @@ impl KeywordStatistics {
     }
 }
 
+/// 结果不完整的原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TruncationReason {
+    /// 达到最大结果数
+    MaxResults,
+    /// 达到搜索超时
+    Timeout,
+    /// 用户取消
+    Cancelled,
+}
+
+/// 结构化截断信息：说明结果覆盖了多少数据
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTruncation {
+    pub reason: TruncationReason,
+    /// 已完整扫描的文件数
+    pub files_scanned: usize,
+    /// 候选文件总数
+    pub files_total: usize,
+    /// 统计数字（总数、关键词计数）是否只是下限
+    pub counts_are_lower_bound: bool,
+}
+
+impl SearchTruncation {
+    pub fn new(reason: TruncationReason, files_scanned: usize, files_total: usize) -> Self {
+        Self {
+            reason,
+            files_scanned,
+            files_total,
+            // 任何提前停止都意味着可能还有未计入的匹配
+            counts_are_lower_bound: true,
+        }
+    }
+}
+
+/// 单个文件的命中统计
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStatistics {
+    /// 文件虚拟路径
+    pub file: String,
+    pub match_count: usize,
+}
+
+/// 命中行的时间跨度（保留原始时间戳文本，比较时计入文件时钟偏移）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchTimeRange {
+    pub earliest: String,
+    pub latest: String,
+}
+
+/// 结果来源：内存结果会话（L1）、磁盘结果存储（L2）或本次新扫描
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultSource {
+    L1,
+    L2,
+    #[default]
+    Fresh,
+}
+
+/// 搜索各阶段耗时（毫秒）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchPhaseTimings {
+    /// 候选文件筛选（路径范围与关键词摘要剪枝）
+    pub file_selection_ms: u64,
+    /// 构建执行计划
+    pub planning_ms: u64,
+    /// 扫描文件内容并写入结果
+    pub scan_ms: u64,
+}
+
 /// 搜索结果摘要信息
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SearchResultSummary {
@@ -44,6 +125,22 @@ pub struct SearchResultSummary {
     pub search_duration_ms: u64,
     /// 是否因超限截断
     pub truncated: bool,
+    /// 结果不完整时的详细信息（超时 / 取消 / 超限）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<SearchTruncation>,
+    /// 按命中数降序的文件分布
+    #[serde(rename = "fileStats", default)]
+    pub file_stats: Vec<FileStatistics>,
+    /// 命中行的时间跨度；没有可解析时间戳时为 None
+    #[serde(rename = "timeRange", default)]
+    pub time_range: Option<MatchTimeRange>,
+    #[serde(default)]
+    pub source: ResultSource,
+    #[serde(rename = "phaseTimings", default)]
+    pub phase_timings: SearchPhaseTimings,
+    /// 搜索提交时工作区仍在导入：已导入的估算百分比（结果只覆盖已导入的文件）
+    #[serde(rename = "importProgress", default, skip_serializing_if = "Option::is_none")]
+    pub import_progress: Option<u8>,
 }
 
 impl SearchResultSummary {
@@ -59,6 +156,108 @@ impl SearchResultSummary {
             keyword_stats,
             search_duration_ms,
             truncated,
+            truncation: None,
+            file_stats: Vec::new(),
+            time_range: None,
+            source: ResultSource::Fresh,
+            phase_timings: SearchPhaseTimings::default(),
+            import_progress: None,
+        }
+    }
+
+    /// 附加截断信息（同时置位 `truncated`）
+    pub fn with_truncation(mut self, truncation: SearchTruncation) -> Self {
+        self.truncated = true;
+        self.truncation = Some(truncation);
+        self
+    }
+}
+
+/// 搜索过程中逐条累计命中统计，结束时生成摘要所需的关键词、文件与时间分布
+#[derive(Debug, Default)]
+pub struct SearchStatisticsCollector {
+    /// (关键词, 命中行数)，保持查询中的顺序
+    keywords: Vec<(String, usize)>,
+    files: HashMap<String, usize>,
+    earliest: Option<(chrono::NaiveDateTime, String)>,
+    latest: Option<(chrono::NaiveDateTime, String)>,
+}
+
+impl SearchStatisticsCollector {
+    /// `keywords` 为查询中启用的关键词；零命中的关键词也会出现在统计中
+    pub fn new<I, S>(keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut collected: Vec<(String, usize)> = Vec::new();
+        for keyword in keywords {
+            let keyword = keyword.into();
+            if !collected.iter().any(|(k, _)| *k == keyword) {
+                collected.push((keyword, 0));
+            }
+        }
+        Self {
+            keywords: collected,
+            ..Self::default()
+        }
+    }
+
+    pub fn record(&mut self, entry: &LogEntry) {
+        for matched in entry.matched_keywords.iter().flatten() {
+            match self.keywords.iter_mut().find(|(k, _)| k == matched) {
+                Some((_, count)) => *count += 1,
+                None => self.keywords.push((matched.clone(), 1)),
+            }
+        }
+
+        match self.files.get_mut(entry.file.as_ref()) {
+            Some(count) => *count += 1,
+            None => {
+                self.files.insert(entry.file.to_string(), 1);
+            }
+        }
+
+        let Some(parsed) = TimestampParser::parse_naive_datetime(&entry.timestamp) else {
+            return;
+        };
+        let at = parsed + chrono::Duration::seconds(entry.time_offset_secs.unwrap_or(0));
+        if self.earliest.as_ref().is_none_or(|(t, _)| at < *t) {
+            self.earliest = Some((at, entry.timestamp.to_string()));
+        }
+        if self.latest.as_ref().is_none_or(|(t, _)| at > *t) {
+            self.latest = Some((at, entry.timestamp.to_string()));
+        }
+    }
+
+    /// 生成摘要；耗时、来源与截断信息由调用方补充
+    pub fn finish(self, total_matches: usize) -> SearchResultSummary {
+        let keyword_stats = self
+            .keywords
+            .into_iter()
+            .map(|(keyword, count)| KeywordStatistics::new(keyword, count, total_matches))
+            .collect();
+
+        let mut file_stats: Vec<FileStatistics> = self
+            .files
+            .into_iter()
+            .map(|(file, match_count)| FileStatistics { file, match_count })
+            .collect();
+        file_stats.sort_by(|a, b| {
+            b.match_count
+                .cmp(&a.match_count)
+                .then_with(|| a.file.cmp(&b.file))
+        });
+
+        let time_range = match (self.earliest, self.latest) {
+            (Some((_, earliest)), Some((_, latest))) => Some(MatchTimeRange { earliest, latest }),
+            _ => None,
+        };
+
+        SearchResultSummary {
+            file_stats,
+            time_range,
+            ..SearchResultSummary::new(total_matches, keyword_stats, 0, false)
         }
     }
 }
@@ -100,5 +299,76 @@ mod tests {
         assert_eq!(summary.keyword_stats.len(), 0);
         assert_eq!(summary.search_duration_ms, 0);
         assert!(!summary.truncated);
+        assert!(summary.truncation.is_none());
+    }
+
+    #[test]
+    fn test_search_result_summary_with_truncation() {
+        let summary = SearchResultSummary::new(42, vec![], 10_000, false)
+            .with_truncation(SearchTruncation::new(TruncationReason::Timeout, 3, 10));
+
+        assert!(summary.truncated);
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["truncation"]["reason"], "timeout");
+        assert_eq!(json["truncation"]["filesScanned"], 3);
+        assert_eq!(json["truncation"]["filesTotal"], 10);
+        assert_eq!(json["truncation"]["countsAreLowerBound"], true);
+    }
+
+    fn entry(file: &str, timestamp: &str, offset: Option<i64>, keywords: &[&str]) -> LogEntry {
+        LogEntry {
+            id: 0,
+            timestamp: timestamp.into(),
+            level: "ERROR".into(),
+            file: file.into(),
+            real_path: file.into(),
+            line: 1,
+            content: "".into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: Some(keywords.iter().map(|k| k.to_string()).collect()),
+            time_offset_secs: offset,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn test_statistics_collector_summary() {
+        let mut collector = SearchStatisticsCollector::new(["error", "timeout", "disk"]);
+        collector.record(&entry("a.log", "2024-01-01 10:00:00", None, &["error"]));
+        collector.record(&entry(
+            "b.log",
+            "2024-01-01 09:00:00",
+            Some(7200),
+            &["error", "timeout"],
+        ));
+        collector.record(&entry("b.log", "not a time", None, &["timeout"]));
+        collector.record(&entry("b.log", "2024-01-01 09:30:00", None, &["error"]));
+
+        let summary = collector.finish(4);
+        let counts: Vec<_> = summary
+            .keyword_stats
+            .iter()
+            .map(|k| (k.keyword.as_str(), k.match_count))
+            .collect();
+        assert_eq!(counts, vec![("error", 3), ("timeout", 2), ("disk", 0)]);
+        assert_eq!(summary.file_stats[0].file, "b.log");
+        assert_eq!(summary.file_stats[0].match_count, 3);
+        // b.log 的 09:00 校正后为 11:00，最早是同文件未偏移的 09:30
+        let range = summary.time_range.unwrap();
+        assert_eq!(range.earliest, "2024-01-01 09:30:00");
+        assert_eq!(range.latest, "2024-01-01 09:00:00");
+        assert_eq!(summary.source, ResultSource::Fresh);
+
+        let json =
+            serde_json::to_value(SearchStatisticsCollector::new(Vec::<String>::new()).finish(0))
+                .unwrap();
+        assert_eq!(json["source"], "fresh");
+        assert!(json["timeRange"].is_null());
+        assert_eq!(json["phaseTimings"]["scanMs"], 0);
     }
 }
@@ -36,6 +36,42 @@ impl std::str::FromStr for AnalysisStatus {
     }
 }
 
+/// 文件搜索标记
+///
+/// - `Ignored`：不参与搜索与索引
+/// - `Pinned`：始终优先搜索（如主应用日志）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSearchFlag {
+    #[default]
+    Normal,
+    Ignored,
+    Pinned,
+}
+
+impl FileSearchFlag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileSearchFlag::Normal => "NORMAL",
+            FileSearchFlag::Ignored => "IGNORED",
+            FileSearchFlag::Pinned => "PINNED",
+        }
+    }
+}
+
+impl std::str::FromStr for FileSearchFlag {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "NORMAL" => Ok(FileSearchFlag::Normal),
+            "IGNORED" => Ok(FileSearchFlag::Ignored),
+            "PINNED" => Ok(FileSearchFlag::Pinned),
+            _ => Err(format!("Unknown file search flag: {s}")),
+        }
+    }
+}
+
 /// 文件元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileMetadata {
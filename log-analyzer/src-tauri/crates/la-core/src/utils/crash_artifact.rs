@@ -0,0 +1,424 @@
+//! 崩溃产物识别 — core dump、minidump 与 hprof
+//!
+//! 这些二进制文件不写入索引，而是在导入时按文件头识别并登记到产物目录
+//! （大小、构建 ID、崩溃时间），在文件树中标出，便于交给调试器或分析工具。
+//!
+//! - **ELF core dump**：`e_type == ET_CORE`；构建 ID 取自 core 中第一个带
+//!   `NT_GNU_BUILD_ID` 的映像（内核默认转储每个 ELF 映射的首页，见 `coredump_filter`）
+//! - **Minidump**（Breakpad / Crashpad / Windows）：头部的时间戳；构建 ID 取第一个模块的
+//!   CodeView 记录（PDB70 的 GUID+age 或 ELF 构建 ID）
+//! - **hprof**（Java 堆转储）：头部的毫秒时间戳，无构建 ID
+
+use serde::{Deserialize, Serialize};
+
+/// 构建 ID 的最大字节数（超出视为损坏）
+const MAX_BUILD_ID_BYTES: usize = 64;
+/// ELF core 中最多检查的程序头数
+const MAX_PROGRAM_HEADERS: usize = 4096;
+
+/// 崩溃产物类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrashArtifactKind {
+    Coredump,
+    Minidump,
+    Hprof,
+}
+
+impl CrashArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrashArtifactKind::Coredump => "coredump",
+            CrashArtifactKind::Minidump => "minidump",
+            CrashArtifactKind::Hprof => "hprof",
+        }
+    }
+}
+
+impl std::str::FromStr for CrashArtifactKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "coredump" => Ok(CrashArtifactKind::Coredump),
+            "minidump" => Ok(CrashArtifactKind::Minidump),
+            "hprof" => Ok(CrashArtifactKind::Hprof),
+            other => Err(format!("Unknown crash artifact kind: {other}")),
+        }
+    }
+}
+
+/// 从文件内容解析出的产物信息；无法解析的字段为 `None`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashArtifactInfo {
+    pub kind: CrashArtifactKind,
+    /// 十六进制构建 ID（minidump 的 PDB70 记录为 GUID 加 age）
+    pub build_id: Option<String>,
+    /// 崩溃时间（Unix 秒），文件中未记录时为 `None`
+    pub crashed_at: Option<i64>,
+}
+
+/// 按文件头识别崩溃产物；其他内容返回 `None`
+pub fn detect_crash_artifact(content: &[u8]) -> Option<CrashArtifactInfo> {
+    if content.starts_with(b"MDMP") {
+        return Some(parse_minidump(content));
+    }
+    if content.starts_with(b"JAVA PROFILE 1.0.") {
+        return Some(parse_hprof(content));
+    }
+    let elf = Elf::parse(content)?;
+    (elf.e_type == ET_CORE).then(|| CrashArtifactInfo {
+        kind: CrashArtifactKind::Coredump,
+        build_id: core_build_id(content, &elf),
+        crashed_at: None,
+    })
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// ============================================================================
+// hprof
+// ============================================================================
+
+fn parse_hprof(content: &[u8]) -> CrashArtifactInfo {
+    // "JAVA PROFILE 1.0.x\0"，随后是 u32 标识符长度与 u64 毫秒时间戳（大端）
+    let crashed_at = content
+        .iter()
+        .position(|&b| b == 0)
+        .and_then(|nul| content.get(nul + 5..nul + 13))
+        .and_then(|ms| ms.try_into().ok())
+        .map(|ms: [u8; 8]| u64::from_be_bytes(ms) / 1000)
+        .and_then(|secs| i64::try_from(secs).ok())
+        .filter(|&secs| secs > 0);
+    CrashArtifactInfo {
+        kind: CrashArtifactKind::Hprof,
+        build_id: None,
+        crashed_at,
+    }
+}
+
+// ============================================================================
+// Minidump
+// ============================================================================
+
+const MINIDUMP_MODULE_LIST_STREAM: u32 = 4;
+/// MINIDUMP_MODULE 中 CodeView 记录位置描述符的偏移
+const MINIDUMP_MODULE_CV_RECORD: usize = 76;
+
+fn parse_minidump(content: &[u8]) -> CrashArtifactInfo {
+    let crashed_at = u32_le(content, 20).filter(|&ts| ts > 0).map(i64::from);
+    CrashArtifactInfo {
+        kind: CrashArtifactKind::Minidump,
+        build_id: minidump_build_id(content),
+        crashed_at,
+    }
+}
+
+fn minidump_build_id(content: &[u8]) -> Option<String> {
+    let stream_count = u32_le(content, 8)? as usize;
+    let directory = u32_le(content, 12)? as usize;
+    let module_list = (0..stream_count.min(1024)).find_map(|i| {
+        let entry = directory + i * 12;
+        (u32_le(content, entry)? == MINIDUMP_MODULE_LIST_STREAM)
+            .then(|| u32_le(content, entry + 8))
+            .flatten()
+    })? as usize;
+
+    u32_le(content, module_list).filter(|&n| n > 0)?;
+    let module = module_list + 4;
+    let cv_size = u32_le(content, module + MINIDUMP_MODULE_CV_RECORD)? as usize;
+    let cv_rva = u32_le(content, module + MINIDUMP_MODULE_CV_RECORD + 4)? as usize;
+    let cv = content.get(cv_rva..cv_rva.checked_add(cv_size)?)?;
+
+    match cv.get(..4)? {
+        // PDB70：GUID（Data1/2/3 小端）+ age，按调试符号服务器的写法输出
+        b"RSDS" => {
+            let guid = cv.get(4..20)?;
+            let age = u32_le(cv, 20)?;
+            Some(format!(
+                "{:08X}{:04X}{:04X}{}{age:X}",
+                u32_le(guid, 0)?,
+                u16_le(guid, 4)?,
+                u16_le(guid, 6)?,
+                hex(&guid[8..16]).to_uppercase()
+            ))
+        }
+        // Breakpad 的 ELF 构建 ID 记录
+        b"BpEL" => {
+            let id = cv.get(4..)?;
+            (!id.is_empty() && id.len() <= MAX_BUILD_ID_BYTES).then(|| hex(id))
+        }
+        _ => None,
+    }
+}
+
+// ============================================================================
+// ELF core
+// ============================================================================
+
+const ET_CORE: u16 = 4;
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+const NT_GNU_BUILD_ID: u32 = 3;
+
+/// 只支持小端 ELF（x86、ARM、RISC-V 等主流平台）
+struct Elf {
+    is_64: bool,
+    e_type: u16,
+    phoff: usize,
+    phentsize: usize,
+    phnum: usize,
+}
+
+struct ProgramHeader {
+    p_type: u32,
+    offset: usize,
+    vaddr: u64,
+    filesz: usize,
+}
+
+impl Elf {
+    fn parse(content: &[u8]) -> Option<Self> {
+        if !content.starts_with(b"\x7fELF") || content.get(5) != Some(&1) {
+            return None;
+        }
+        let is_64 = match content.get(4)? {
+            1 => false,
+            2 => true,
+            _ => return None,
+        };
+        let e_type = u16_le(content, 16)?;
+        let (phoff, phentsize, phnum) = if is_64 {
+            (
+                u64::from_le_bytes(content.get(32..40)?.try_into().ok()?) as usize,
+                u16_le(content, 54)? as usize,
+                u16_le(content, 56)? as usize,
+            )
+        } else {
+            (
+                u32_le(content, 28)? as usize,
+                u16_le(content, 42)? as usize,
+                u16_le(content, 44)? as usize,
+            )
+        };
+        Some(Self {
+            is_64,
+            e_type,
+            phoff,
+            phentsize,
+            phnum: phnum.min(MAX_PROGRAM_HEADERS),
+        })
+    }
+
+    fn program_headers(&self, content: &[u8]) -> Vec<ProgramHeader> {
+        (0..self.phnum)
+            .map_while(|i| {
+                let at = self.phoff.checked_add(i.checked_mul(self.phentsize)?)?;
+                let u64_at = |o: usize| -> Option<u64> {
+                    Some(u64::from_le_bytes(
+                        content.get(at + o..at + o + 8)?.try_into().ok()?,
+                    ))
+                };
+                let p_type = u32_le(content, at)?;
+                Some(if self.is_64 {
+                    ProgramHeader {
+                        p_type,
+                        offset: u64_at(8)? as usize,
+                        vaddr: u64_at(16)?,
+                        filesz: u64_at(32)? as usize,
+                    }
+                } else {
+                    ProgramHeader {
+                        p_type,
+                        offset: u32_le(content, at + 4)? as usize,
+                        vaddr: u64::from(u32_le(content, at + 8)?),
+                        filesz: u32_le(content, at + 16)? as usize,
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+/// core 自身的 note 段，或转储的第一个 ELF 映像中的 `NT_GNU_BUILD_ID`
+fn core_build_id(content: &[u8], core: &Elf) -> Option<String> {
+    let segments = core.program_headers(content);
+    let in_file = |ph: &ProgramHeader| content.get(ph.offset..ph.offset.checked_add(ph.filesz)?);
+
+    segments
+        .iter()
+        .filter(|ph| ph.p_type == PT_NOTE)
+        .filter_map(in_file)
+        .find_map(gnu_build_id)
+        .or_else(|| {
+            segments
+                .iter()
+                .filter(|ph| ph.p_type == PT_LOAD)
+                .filter_map(|ph| Some((ph.vaddr, in_file(ph)?)))
+                .find_map(|(base, image)| mapped_image_build_id(image, base))
+        })
+}
+
+/// 映射到内存的 ELF 映像（首页）中的构建 ID；note 段按相对映射基址的虚拟地址定位
+fn mapped_image_build_id(image: &[u8], base: u64) -> Option<String> {
+    let elf = Elf::parse(image)?;
+    let headers = elf.program_headers(image);
+    let load_base = headers
+        .iter()
+        .find(|ph| ph.p_type == PT_LOAD)
+        .map(|ph| ph.vaddr)
+        .unwrap_or(0);
+    headers
+        .iter()
+        .filter(|ph| ph.p_type == PT_NOTE)
+        .find_map(|ph| {
+            // 位置无关映像的 vaddr 相对 0；固定地址映像的 vaddr 是绝对地址
+            let relative = if ph.vaddr >= base {
+                ph.vaddr - base
+            } else {
+                ph.vaddr.checked_sub(load_base)?
+            } as usize;
+            gnu_build_id(image.get(relative..relative.checked_add(ph.filesz)?)?)
+        })
+}
+
+/// 遍历 note 段（4 字节对齐），返回 GNU 构建 ID
+fn gnu_build_id(notes: &[u8]) -> Option<String> {
+    let mut at = 0;
+    while let (Some(namesz), Some(descsz), Some(n_type)) = (
+        u32_le(notes, at),
+        u32_le(notes, at + 4),
+        u32_le(notes, at + 8),
+    ) {
+        let name_start = at + 12;
+        let desc_start = name_start.checked_add((namesz as usize).div_ceil(4) * 4)?;
+        let desc_end = desc_start.checked_add(descsz as usize)?;
+        if n_type == NT_GNU_BUILD_ID && notes.get(name_start..name_start + 4) == Some(b"GNU\0") {
+            let id = notes.get(desc_start..desc_end)?;
+            return (!id.is_empty() && id.len() <= MAX_BUILD_ID_BYTES).then(|| hex(id));
+        }
+        at = desc_start.checked_add((descsz as usize).div_ceil(4) * 4)?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_id_note(id: &[u8]) -> Vec<u8> {
+        let mut note = Vec::new();
+        note.extend_from_slice(&4u32.to_le_bytes());
+        note.extend_from_slice(&(id.len() as u32).to_le_bytes());
+        note.extend_from_slice(&NT_GNU_BUILD_ID.to_le_bytes());
+        note.extend_from_slice(b"GNU\0");
+        note.extend_from_slice(id);
+        note
+    }
+
+    /// 64 位小端 ELF：文件头 + 程序头 (类型, 文件偏移, 虚拟地址, 大小)
+    fn elf64(e_type: u16, headers: &[(u32, u64, u64, u64)], total_len: usize) -> Vec<u8> {
+        let mut elf = vec![0u8; total_len.max(64 + headers.len() * 56)];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[4] = 2;
+        elf[5] = 1;
+        elf[16..18].copy_from_slice(&e_type.to_le_bytes());
+        elf[32..40].copy_from_slice(&64u64.to_le_bytes());
+        elf[54..56].copy_from_slice(&56u16.to_le_bytes());
+        elf[56..58].copy_from_slice(&(headers.len() as u16).to_le_bytes());
+        for (i, &(p_type, offset, vaddr, size)) in headers.iter().enumerate() {
+            let at = 64 + i * 56;
+            elf[at..at + 4].copy_from_slice(&p_type.to_le_bytes());
+            elf[at + 8..at + 16].copy_from_slice(&offset.to_le_bytes());
+            elf[at + 16..at + 24].copy_from_slice(&vaddr.to_le_bytes());
+            elf[at + 32..at + 40].copy_from_slice(&size.to_le_bytes());
+        }
+        elf
+    }
+
+    #[test]
+    fn test_core_dump_build_id_from_mapped_image() {
+        let id = [0xab, 0xcd, 0x01, 0x23, 0x45, 0x67, 0x89, 0xef];
+        let note = build_id_note(&id);
+        // 被转储的可执行文件首页：note 段位于映像偏移 0x100
+        let mut image = elf64(
+            3,
+            &[
+                (PT_LOAD, 0, 0, 0x1000),
+                (PT_NOTE, 0x100, 0x100, note.len() as u64),
+            ],
+            0x1000,
+        );
+        image[0x100..0x100 + note.len()].copy_from_slice(&note);
+
+        let mut core = elf64(ET_CORE, &[(PT_LOAD, 0x200, 0x5555_0000, 0x1000)], 0x200);
+        core.extend_from_slice(&image);
+
+        assert_eq!(
+            detect_crash_artifact(&core),
+            Some(CrashArtifactInfo {
+                kind: CrashArtifactKind::Coredump,
+                build_id: Some("abcd0123456789ef".to_string()),
+                crashed_at: None,
+            })
+        );
+        // 普通可执行文件不是崩溃产物
+        assert_eq!(detect_crash_artifact(&image), None);
+    }
+
+    #[test]
+    fn test_minidump_pdb70_and_timestamp() {
+        let mut dump = vec![0u8; 400];
+        dump[..4].copy_from_slice(b"MDMP");
+        dump[8..12].copy_from_slice(&1u32.to_le_bytes()); // stream count
+        dump[12..16].copy_from_slice(&32u32.to_le_bytes()); // directory rva
+        dump[20..24].copy_from_slice(&1_704_164_645u32.to_le_bytes());
+        // directory: ModuleListStream at 44
+        dump[32..36].copy_from_slice(&MINIDUMP_MODULE_LIST_STREAM.to_le_bytes());
+        dump[40..44].copy_from_slice(&44u32.to_le_bytes());
+        dump[44..48].copy_from_slice(&1u32.to_le_bytes());
+        let module = 48;
+        let cv_rva = 300u32;
+        dump[module + 76..module + 80].copy_from_slice(&24u32.to_le_bytes());
+        dump[module + 80..module + 84].copy_from_slice(&cv_rva.to_le_bytes());
+        let cv = 300;
+        dump[cv..cv + 4].copy_from_slice(b"RSDS");
+        dump[cv + 4..cv + 8].copy_from_slice(&0x1122_3344u32.to_le_bytes());
+        dump[cv + 8..cv + 10].copy_from_slice(&0x5566u16.to_le_bytes());
+        dump[cv + 10..cv + 12].copy_from_slice(&0x7788u16.to_le_bytes());
+        dump[cv + 12..cv + 20].copy_from_slice(&[0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff, 0x00]);
+        dump[cv + 20..cv + 24].copy_from_slice(&2u32.to_le_bytes());
+
+        let info = detect_crash_artifact(&dump).unwrap();
+        assert_eq!(info.kind, CrashArtifactKind::Minidump);
+        assert_eq!(info.crashed_at, Some(1_704_164_645));
+        assert_eq!(
+            info.build_id.as_deref(),
+            Some("112233445566778899AABBCCDDEEFF002")
+        );
+    }
+
+    #[test]
+    fn test_hprof_timestamp() {
+        let mut hprof = b"JAVA PROFILE 1.0.2\0".to_vec();
+        hprof.extend_from_slice(&8u32.to_be_bytes());
+        hprof.extend_from_slice(&1_704_164_645_123u64.to_be_bytes());
+        let info = detect_crash_artifact(&hprof).unwrap();
+        assert_eq!(info.kind, CrashArtifactKind::Hprof);
+        assert_eq!(info.crashed_at, Some(1_704_164_645));
+
+        assert_eq!(detect_crash_artifact(b"2024-01-02 INFO started\n"), None);
+        assert_eq!("minidump".parse(), Ok(CrashArtifactKind::Minidump));
+    }
+}
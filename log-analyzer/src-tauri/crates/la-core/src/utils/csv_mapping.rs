@@ -0,0 +1,367 @@
+//! CSV/TSV 日志 — 分隔符、表头与列映射
+//!
+//! 其他工具导出的 CSV 通常带时间列。[`detect_csv_mapping`] 在导入时识别分隔符与表头，
+//! 按列名或内容猜测时间、级别、消息列；用户可以按文件改写映射。索引时按映射从列中
+//! 取时间戳与级别（见 [`csv_row_metadata`]），表头行不写入索引。
+//!
+//! 每个物理行视为一条记录，不支持引号内换行。
+
+use serde::{Deserialize, Serialize};
+
+use super::log_parsing::normalize_level;
+use super::text_quality::split_lines;
+use super::timestamp_parser::TimestampParser;
+
+/// 识别时尝试的分隔符（按优先级）
+pub const CSV_DELIMITERS: [char; 3] = [',', '\t', ';'];
+/// 列数上限
+pub const MAX_CSV_COLUMNS: usize = 256;
+/// 识别时抽样的非空行数
+const SAMPLE_ROWS: usize = 50;
+/// 列数一致的行至少占这一比例才视为 CSV
+const MIN_CONSISTENCY: f32 = 0.8;
+
+const TIME_HEADERS: [&str; 8] = [
+    "timestamp",
+    "time",
+    "@timestamp",
+    "datetime",
+    "date",
+    "ts",
+    "eventtime",
+    "created_at",
+];
+const LEVEL_HEADERS: [&str; 6] = [
+    "level",
+    "severity",
+    "lvl",
+    "loglevel",
+    "log_level",
+    "priority",
+];
+const MESSAGE_HEADERS: [&str; 7] = [
+    "message",
+    "msg",
+    "text",
+    "content",
+    "log",
+    "description",
+    "event",
+];
+
+/// 单个文件的列映射；列号从 0 开始
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvMapping {
+    pub delimiter: char,
+    /// 第一行是表头（不写入索引）
+    pub has_header: bool,
+    /// 列名：表头中的名称，无表头时为 `column1`、`column2`……
+    pub columns: Vec<String>,
+    pub time_column: Option<usize>,
+    pub level_column: Option<usize>,
+    pub message_column: Option<usize>,
+    /// 作为附加字段展示的列
+    #[serde(default)]
+    pub extra_columns: Vec<usize>,
+}
+
+impl CsvMapping {
+    /// 校验分隔符与列号
+    pub fn validate(&self) -> Result<(), String> {
+        if matches!(self.delimiter, '"' | '\n' | '\r') {
+            return Err(format!("Invalid delimiter: {:?}", self.delimiter));
+        }
+        if self.columns.is_empty() || self.columns.len() > MAX_CSV_COLUMNS {
+            return Err(format!(
+                "A mapping needs 1-{MAX_CSV_COLUMNS} columns, got {}",
+                self.columns.len()
+            ));
+        }
+        let columns = self.columns.len();
+        let mapped = [self.time_column, self.level_column, self.message_column];
+        for index in mapped
+            .into_iter()
+            .flatten()
+            .chain(self.extra_columns.iter().copied())
+        {
+            if index >= columns {
+                return Err(format!(
+                    "Column {index} is out of range (the file has {columns} columns)"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 按映射取一行的时间、级别、消息与附加字段（键为列名，空值省略）
+    pub fn row_fields(&self, line: &str) -> Vec<(String, String)> {
+        let cells = split_csv_record(line, self.delimiter);
+        let mapped = [
+            ("time", self.time_column),
+            ("level", self.level_column),
+            ("message", self.message_column),
+        ];
+        mapped
+            .into_iter()
+            .filter_map(|(key, column)| Some((key.to_string(), column?)))
+            .chain(
+                self.extra_columns
+                    .iter()
+                    .filter_map(|&c| Some((self.columns.get(c)?.clone(), c))),
+            )
+            .filter_map(|(key, column)| {
+                let value = cells.get(column)?.trim();
+                (!value.is_empty()).then(|| (key, value.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// 按映射从一行中取时间戳与级别；无法识别的部分为空字符串 / `None`
+pub fn csv_row_metadata(line: &str, mapping: &CsvMapping) -> (String, Option<&'static str>) {
+    let cells = split_csv_record(line, mapping.delimiter);
+    let cell = |column: Option<usize>| column.and_then(|c| cells.get(c)).map(|v| v.trim());
+    let timestamp = cell(mapping.time_column)
+        .and_then(TimestampParser::parse_timestamp)
+        .unwrap_or_default();
+    let level = cell(mapping.level_column).and_then(normalize_level);
+    (timestamp, level)
+}
+
+/// 用映射列中的时间戳与级别覆盖已解析的条目；列为空或无法识别时保留原值
+pub fn apply_csv_metadata(entries: &mut [crate::models::LogEntry], mapping: &CsvMapping) {
+    for entry in entries {
+        let (timestamp, level) = csv_row_metadata(&entry.content, mapping);
+        if !timestamp.is_empty() {
+            entry.timestamp = timestamp.into();
+        }
+        if let Some(level) = level {
+            entry.level = level.into();
+        }
+    }
+}
+
+/// 按分隔符切分一条记录，支持双引号包围与 `""` 转义
+pub fn split_csv_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
+
+/// 行中未被引号包围的分隔符数加一
+pub(crate) fn csv_field_count(line: &str, delimiter: char) -> usize {
+    let mut in_quotes = false;
+    1 + line
+        .chars()
+        .filter(|&c| {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            }
+            c == delimiter && !in_quotes
+        })
+        .count()
+}
+
+/// 列数一致的行占比最高的分隔符：`(分隔符, 列数, 占比)`
+pub(crate) fn best_delimiter(lines: &[&str]) -> Option<(char, usize, f32)> {
+    if lines.is_empty() {
+        return None;
+    }
+    CSV_DELIMITERS
+        .into_iter()
+        .filter_map(|delimiter| {
+            let counts: Vec<usize> = lines
+                .iter()
+                .map(|l| csv_field_count(l, delimiter))
+                .collect();
+            let (columns, hits) = counts
+                .iter()
+                .map(|&count| (count, counts.iter().filter(|&&c| c == count).count()))
+                .max_by_key(|&(count, hits)| (hits, count))?;
+            (columns >= 2).then_some((delimiter, columns, hits as f32 / lines.len() as f32))
+        })
+        .fold(
+            None,
+            |best: Option<(char, usize, f32)>, candidate| match best {
+                Some(b) if b.2 >= candidate.2 => Some(b),
+                _ => Some(candidate),
+            },
+        )
+}
+
+/// 识别分隔符、表头与时间/级别/消息列；不像 CSV 时返回 `None`
+pub fn detect_csv_mapping(content: &str) -> Option<CsvMapping> {
+    let lines: Vec<&str> = split_lines(content)
+        .filter(|l| !l.trim().is_empty())
+        .take(SAMPLE_ROWS)
+        .collect();
+    let (delimiter, column_count, consistency) = best_delimiter(&lines)?;
+    if consistency < MIN_CONSISTENCY || column_count > MAX_CSV_COLUMNS {
+        return None;
+    }
+
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .map(|l| split_csv_record(l, delimiter))
+        .filter(|cells| cells.len() == column_count)
+        .collect();
+    let first = rows.first()?;
+    let is_value = |cell: &str| {
+        let cell = cell.trim();
+        cell.parse::<f64>().is_ok() || TimestampParser::parse_timestamp(cell).is_some()
+    };
+    let has_header = first
+        .iter()
+        .all(|cell| !cell.trim().is_empty() && !is_value(cell))
+        && rows
+            .get(1)
+            .is_none_or(|second| second.iter().any(|cell| is_value(cell)));
+
+    let columns: Vec<String> = if has_header {
+        first.iter().map(|c| c.trim().to_string()).collect()
+    } else {
+        (1..=column_count).map(|i| format!("column{i}")).collect()
+    };
+    let data: Vec<&Vec<String>> = rows.iter().skip(usize::from(has_header)).collect();
+
+    let by_name = |names: &[&str]| {
+        has_header
+            .then(|| {
+                columns.iter().position(|c| {
+                    let c = c.to_ascii_lowercase();
+                    names.contains(&c.as_str())
+                })
+            })
+            .flatten()
+    };
+    let by_content = |matches: &dyn Fn(&str) -> bool, skip: &[Option<usize>]| {
+        (0..column_count).find(|&c| {
+            !skip.contains(&Some(c))
+                && !data.is_empty()
+                && data.iter().all(|row| matches(row[c].trim()))
+        })
+    };
+
+    let time_column = by_name(&TIME_HEADERS).or_else(|| {
+        by_content(
+            &|cell| TimestampParser::parse_timestamp(cell).is_some(),
+            &[],
+        )
+    });
+    let level_column = by_name(&LEVEL_HEADERS)
+        .or_else(|| by_content(&|cell| normalize_level(cell).is_some(), &[time_column]));
+    let message_column = by_name(&MESSAGE_HEADERS).or_else(|| {
+        // 平均长度最长的剩余列
+        (0..column_count)
+            .filter(|c| ![time_column, level_column].contains(&Some(*c)))
+            .max_by_key(|&c| data.iter().map(|row| row[c].len()).sum::<usize>())
+    });
+    let extra_columns = (0..column_count)
+        .filter(|c| ![time_column, level_column, message_column].contains(&Some(*c)))
+        .collect();
+
+    Some(CsvMapping {
+        delimiter,
+        has_header,
+        columns,
+        time_column,
+        level_column,
+        message_column,
+        extra_columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_mapping_with_header() {
+        let content = "Timestamp,Severity,Host,Message\n\
+                       2024-01-02 03:04:05,WARNING,db1,\"disk, 91% full\"\n\
+                       2024-01-02 03:04:06,info,db2,ok\n";
+        let mapping = detect_csv_mapping(content).unwrap();
+        assert_eq!(mapping.delimiter, ',');
+        assert!(mapping.has_header);
+        assert_eq!(
+            mapping.columns,
+            vec!["Timestamp", "Severity", "Host", "Message"]
+        );
+        assert_eq!(
+            (
+                mapping.time_column,
+                mapping.level_column,
+                mapping.message_column
+            ),
+            (Some(0), Some(1), Some(3))
+        );
+        assert_eq!(mapping.extra_columns, vec![2]);
+
+        let row = "2024-01-02 03:04:05,WARNING,db1,\"disk, 91% full\"";
+        assert_eq!(
+            csv_row_metadata(row, &mapping),
+            ("2024-01-02 03:04:05".to_string(), Some("warn"))
+        );
+        let mut entries = crate::utils::parse_log_lines_as(
+            &[row.to_string()],
+            crate::utils::LogFormat::Csv,
+            "a.csv",
+            "cas://a",
+            0,
+            2,
+        );
+        apply_csv_metadata(&mut entries, &mapping);
+        assert_eq!(&*entries[0].level, "warn");
+        assert_eq!(
+            mapping.row_fields(row),
+            vec![
+                ("time".to_string(), "2024-01-02 03:04:05".to_string()),
+                ("level".to_string(), "WARNING".to_string()),
+                ("message".to_string(), "disk, 91% full".to_string()),
+                ("Host".to_string(), "db1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_headerless_tsv_by_content() {
+        let content = "web\t2024-01-02T03:04:05\tERROR\tconnection reset by peer\n\
+                       api\t2024-01-02T03:04:06\tINFO\trequest served\n";
+        let mapping = detect_csv_mapping(content).unwrap();
+        assert_eq!(mapping.delimiter, '\t');
+        assert!(!mapping.has_header);
+        assert_eq!(mapping.columns[0], "column1");
+        assert_eq!(mapping.time_column, Some(1));
+        assert_eq!(mapping.level_column, Some(2));
+        assert_eq!(mapping.message_column, Some(3));
+
+        assert!(detect_csv_mapping("2024-01-02 INFO started\nplain text line\n").is_none());
+    }
+
+    #[test]
+    fn test_validate_and_split() {
+        assert_eq!(
+            split_csv_record(r#"a,"b ""q"", c",d"#, ','),
+            vec!["a", r#"b "q", c"#, "d"]
+        );
+        let mut mapping = detect_csv_mapping("time,msg\n2024-01-02 03:04:05,x\n").unwrap();
+        assert!(mapping.validate().is_ok());
+        mapping.extra_columns = vec![5];
+        assert!(mapping.validate().unwrap_err().contains("out of range"));
+    }
+}
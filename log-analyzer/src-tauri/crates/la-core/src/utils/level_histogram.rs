@@ -0,0 +1,65 @@
+//! 按日期统计日志级别
+//!
+//! 导入时对每个文件完整扫描一遍，得到 (日期, 级别) → 行数，存入元数据，
+//! 工作区概览直接汇总这些计数，无需再读取文件内容。
+
+use std::collections::BTreeMap;
+
+use super::{parse_metadata, split_lines, TimestampParser};
+
+/// 某一天某个级别的行数；`day` 为 `YYYY-MM-DD`，无法解析时间戳的行记为空字符串
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyLevelCount {
+    pub day: String,
+    pub level: &'static str,
+    pub count: i64,
+}
+
+/// 统计文件内容的每日级别分布（级别为小写，与索引一致），按日期、级别排序
+///
+/// 只读取时间戳与级别关键字（均为 ASCII），非 UTF-8 内容按有损解码处理即可。
+pub fn daily_level_counts(content: &[u8]) -> Vec<DailyLevelCount> {
+    let text = String::from_utf8_lossy(content);
+    let mut counts: BTreeMap<(String, &'static str), i64> = BTreeMap::new();
+
+    for line in split_lines(&text).filter(|line| !line.trim().is_empty()) {
+        let (timestamp, level) = parse_metadata(line);
+        let day = TimestampParser::parse_naive_datetime(&timestamp)
+            .map(|dt| dt.date().format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        *counts.entry((day, level)).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|((day, level), count)| DailyLevelCount { day, level, count })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_grouped_by_day_and_level() {
+        let content = b"2024-01-15 10:00:00 ERROR boom\n\
+            2024-01-15 11:00:00 INFO ok\n\
+            2024-01-15 12:00:00 ERROR again\n\
+            2024-01-16 00:00:01 WARN slow\n\
+            continuation without timestamp\n\n";
+        let counts = daily_level_counts(content);
+        let rows: Vec<(&str, &str, i64)> = counts
+            .iter()
+            .map(|c| (c.day.as_str(), c.level, c.count))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("", "debug", 1),
+                ("2024-01-15", "error", 2),
+                ("2024-01-15", "info", 1),
+                ("2024-01-16", "warn", 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! 工作区自定义的日志级别顺序
+//!
+//! 不同产品的级别体系不同（如 `VERBOSE < DEBUG < INFO < NOTICE < WARN < ERROR < FATAL`）。
+//! [`LevelOrder`] 记录从低到高的级别名，供 `level >= WARN` 这类最低级别过滤在
+//! 逐行执行器与 Tantivy 索引查询两条路径上按同一顺序求值。
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::log_parsing::normalize_level;
+
+/// 行级别正则可识别、按原样写入索引的级别名（见 [`crate::utils::parse_metadata`]）
+const INDEXED_TOKENS: [&str; 4] = ["ERROR", "WARN", "INFO", "DEBUG"];
+
+/// 从低到高排列的级别名（统一为大写）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LevelOrder {
+    levels: Vec<String>,
+}
+
+impl Default for LevelOrder {
+    /// 默认顺序：`TRACE < DEBUG < INFO < WARN < ERROR`
+    fn default() -> Self {
+        Self {
+            levels: ["TRACE", "DEBUG", "INFO", "WARN", "ERROR"]
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl LevelOrder {
+    /// 解析 `VERBOSE < DEBUG < INFO` 或 `VERBOSE, DEBUG, INFO` 形式的级别顺序
+    ///
+    /// 级别名只能包含字母、数字和下划线，不区分大小写且不可重复，至少两个级别。
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut levels = Vec::new();
+        for raw in spec.split(['<', ',']) {
+            let name = raw.trim();
+            if name.is_empty() {
+                return Err(format!("Empty level name in '{spec}'"));
+            }
+            if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!(
+                    "Invalid level name '{name}': use letters, digits and '_' only"
+                ));
+            }
+            let name = name.to_ascii_uppercase();
+            if levels.contains(&name) {
+                return Err(format!("Level '{name}' appears more than once"));
+            }
+            levels.push(name);
+        }
+        if levels.len() < 2 {
+            return Err("A level order needs at least two levels".to_string());
+        }
+        Ok(Self { levels })
+    }
+
+    /// 从低到高的级别名
+    pub fn levels(&self) -> &[String] {
+        &self.levels
+    }
+
+    /// 以 `A < B < C` 形式输出，可被 [`LevelOrder::parse`] 读回
+    pub fn to_spec(&self) -> String {
+        self.levels.join(" < ")
+    }
+
+    /// 级别在顺序中的位置（越大越严重），不区分大小写；`warning` 视为 `WARN`
+    pub fn rank(&self, level: &str) -> Option<usize> {
+        let level = level.trim().to_ascii_uppercase();
+        let level = if level == "WARNING" { "WARN" } else { &level };
+        self.levels.iter().position(|l| l == level)
+    }
+
+    /// 日志行的级别位置
+    ///
+    /// 取行内第一个与级别名完全相同的大写单词（与默认的级别正则一致）；
+    /// 行内没有级别名时按解析器给出的 `parsed_level` 定位。
+    pub fn line_rank(&self, line: &str, parsed_level: &str) -> Option<usize> {
+        line.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .find_map(|word| self.levels.iter().position(|l| l == word))
+            .or_else(|| self.rank(parsed_level))
+    }
+
+    /// 位置不低于 `min_rank` 的级别在索引中可能的取值（小写）
+    ///
+    /// 索引只记录归一化后的 error / warn / info / debug：结构化日志按
+    /// `normalize_level` 映射，纯文本中无法识别的级别名落为 `debug`。
+    /// 结果是候选集合，自定义级别与默认级别不一一对应时会多于实际命中。
+    pub fn indexed_levels_at_least(&self, min_rank: usize) -> Vec<&'static str> {
+        let mut seen = HashSet::new();
+        let mut indexed = Vec::new();
+        for name in self.levels.iter().skip(min_rank) {
+            let mut candidates = vec![normalize_level(name)];
+            if !INDEXED_TOKENS.contains(&name.as_str()) {
+                candidates.push(Some("debug"));
+            }
+            for level in candidates.into_iter().flatten() {
+                if seen.insert(level) {
+                    indexed.push(level);
+                }
+            }
+        }
+        indexed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_order() {
+        let order =
+            LevelOrder::parse("verbose < DEBUG < INFO < NOTICE < WARN < ERROR < FATAL").unwrap();
+        assert_eq!(order.levels().len(), 7);
+        assert!(order.rank("notice").unwrap() > order.rank("INFO").unwrap());
+        assert!(order.rank("warning").unwrap() < order.rank("ERROR").unwrap());
+        assert_eq!(order.rank("TRACE"), None);
+        assert_eq!(LevelOrder::parse(&order.to_spec()).unwrap(), order);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_orders() {
+        assert!(LevelOrder::parse("INFO").is_err());
+        assert!(LevelOrder::parse("INFO < < WARN").is_err());
+        assert!(LevelOrder::parse("INFO < info").is_err());
+        assert!(LevelOrder::parse("INFO < WARN-1").is_err());
+    }
+
+    #[test]
+    fn test_line_rank_uses_first_level_word() {
+        let order = LevelOrder::parse("DEBUG < INFO < NOTICE < WARN < ERROR < FATAL").unwrap();
+        assert_eq!(
+            order.line_rank("2024-01-01 NOTICE disk at 80%", "debug"),
+            order.rank("NOTICE")
+        );
+        assert_eq!(
+            order.line_rank("2024-01-01 FATAL ERROR abort", "error"),
+            order.rank("FATAL")
+        );
+        // NOTICEABLE 不是级别名，回退到解析器给出的级别
+        assert_eq!(
+            order.line_rank("NOTICEABLE latency", "debug"),
+            order.rank("DEBUG")
+        );
+    }
+
+    #[test]
+    fn test_indexed_levels_cover_custom_levels() {
+        let order = LevelOrder::default();
+        let warn = order.rank("WARN").unwrap();
+        assert_eq!(order.indexed_levels_at_least(warn), vec!["warn", "error"]);
+
+        let custom = LevelOrder::parse("DEBUG < INFO < NOTICE < WARN < ERROR < FATAL").unwrap();
+        let indexed = custom.indexed_levels_at_least(custom.rank("ERROR").unwrap());
+        assert_eq!(indexed, vec!["error", "debug"]);
+    }
+}
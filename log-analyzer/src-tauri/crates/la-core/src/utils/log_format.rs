@@ -0,0 +1,356 @@
+//! 日志格式指纹 — 按内容抽样识别文件格式
+//!
+//! [`fingerprint_content`] 在导入时对每个内容对象的开头抽样，判断是纯文本、NDJSON、
+//! logfmt、Apache 访问日志、syslog、CSV、pcap/pcapng 抓包、HAR 还是二进制，并给出置信度。结果按内容哈希记录，
+//! 决定索引时的级别/时间戳解析方式（见 `parse_metadata_as`），用户可在文件树中改判。
+
+use std::fmt;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::csv_mapping::{best_delimiter, detect_csv_mapping, CsvMapping};
+use super::network_summary::{is_pcap, looks_like_har};
+use super::text_quality::split_lines;
+
+/// 抽样的字节数上限
+const SAMPLE_BYTES: usize = 64 * 1024;
+/// 抽样的非空行数上限
+const SAMPLE_LINES: usize = 200;
+/// 控制字符占比超过该值视为二进制
+const BINARY_CONTROL_RATIO: f32 = 0.1;
+/// 结构化格式至少要有这一比例的抽样行命中
+const MIN_STRUCTURED_RATIO: f32 = 0.6;
+
+/// 日志文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Plain,
+    Ndjson,
+    Logfmt,
+    Apache,
+    Syslog,
+    Csv,
+    /// pcap / pcapng 抓包（导入时另存连接摘要，见 `network_summary`）
+    Pcap,
+    /// HTTP Archive（导入时另存请求摘要）
+    Har,
+    Binary,
+}
+
+impl LogFormat {
+    pub const ALL: [LogFormat; 9] = [
+        LogFormat::Plain,
+        LogFormat::Ndjson,
+        LogFormat::Logfmt,
+        LogFormat::Apache,
+        LogFormat::Syslog,
+        LogFormat::Csv,
+        LogFormat::Pcap,
+        LogFormat::Har,
+        LogFormat::Binary,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Plain => "plain",
+            LogFormat::Ndjson => "ndjson",
+            LogFormat::Logfmt => "logfmt",
+            LogFormat::Apache => "apache",
+            LogFormat::Syslog => "syslog",
+            LogFormat::Csv => "csv",
+            LogFormat::Pcap => "pcap",
+            LogFormat::Har => "har",
+            LogFormat::Binary => "binary",
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LogFormat::ALL
+            .into_iter()
+            .find(|f| f.as_str() == s)
+            .ok_or_else(|| format!("Unknown log format: {s}"))
+    }
+}
+
+/// 格式识别结果，`confidence` 在 0..=1 之间
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatFingerprint {
+    pub format: LogFormat,
+    pub confidence: f32,
+    /// 识别为 CSV 时的分隔符、表头与列映射
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_mapping: Option<CsvMapping>,
+}
+
+/// 对内容开头抽样识别格式
+///
+/// 抓包按文件头、HAR 按开头的 JSON 结构直接判定；其余结构化格式按命中行占比打分，
+/// 同分时按 NDJSON、Apache、syslog、logfmt、CSV 的顺序取；最高分低于 60% 时判为纯文本，
+/// 置信度为未命中行的占比。
+pub fn fingerprint_content(content: &[u8]) -> FormatFingerprint {
+    let sample = &content[..content.len().min(SAMPLE_BYTES)];
+    if sample.is_empty() {
+        return FormatFingerprint {
+            format: LogFormat::Plain,
+            confidence: 0.0,
+            csv_mapping: None,
+        };
+    }
+
+    if is_pcap(content) {
+        return FormatFingerprint {
+            format: LogFormat::Pcap,
+            confidence: 1.0,
+            csv_mapping: None,
+        };
+    }
+
+    let control = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0c | 0x1b))
+        .count();
+    let control_ratio = control as f32 / sample.len() as f32;
+    if control_ratio > BINARY_CONTROL_RATIO {
+        return FormatFingerprint {
+            format: LogFormat::Binary,
+            confidence: (control_ratio * 3.0).min(1.0),
+            csv_mapping: None,
+        };
+    }
+
+    let text = String::from_utf8_lossy(sample);
+    if looks_like_har(&text) {
+        return FormatFingerprint {
+            format: LogFormat::Har,
+            confidence: 0.9,
+            csv_mapping: None,
+        };
+    }
+    let mut lines: Vec<&str> = split_lines(&text)
+        .filter(|l| !l.trim().is_empty())
+        .take(SAMPLE_LINES + 1)
+        .collect();
+    // 截断处的最后一行可能不完整
+    if content.len() > SAMPLE_BYTES && lines.len() > 1 {
+        lines.pop();
+    }
+    lines.truncate(SAMPLE_LINES);
+    if lines.is_empty() {
+        return FormatFingerprint {
+            format: LogFormat::Plain,
+            confidence: 0.0,
+            csv_mapping: None,
+        };
+    }
+
+    let ratio = |matches: fn(&str) -> bool| {
+        lines.iter().filter(|l| matches(l)).count() as f32 / lines.len() as f32
+    };
+    let scores = [
+        (LogFormat::Ndjson, ratio(is_ndjson_line)),
+        (LogFormat::Apache, ratio(is_apache_line)),
+        (LogFormat::Syslog, ratio(is_syslog_line)),
+        (LogFormat::Logfmt, ratio(is_logfmt_line)),
+        (LogFormat::Csv, csv_ratio(&lines)),
+    ];
+    let (format, score) = scores
+        .into_iter()
+        .fold((LogFormat::Plain, 0.0f32), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        });
+
+    if score >= MIN_STRUCTURED_RATIO {
+        FormatFingerprint {
+            format,
+            confidence: score,
+            csv_mapping: (format == LogFormat::Csv)
+                .then(|| detect_csv_mapping(&text))
+                .flatten(),
+        }
+    } else {
+        FormatFingerprint {
+            format: LogFormat::Plain,
+            confidence: 1.0 - score,
+            csv_mapping: None,
+        }
+    }
+}
+
+fn is_ndjson_line(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('{')
+        && line.ends_with('}')
+        && serde_json::from_str::<serde_json::Value>(line).is_ok_and(|v| v.is_object())
+}
+
+fn is_apache_line(line: &str) -> bool {
+    static APACHE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^\S+ \S+ \S+ \[[^\]]+\] "[^"]*" \d{3} (?:\d+|-)"#)
+            .expect("APACHE is a valid static regex pattern")
+    });
+    APACHE.is_match(line)
+}
+
+fn is_syslog_line(line: &str) -> bool {
+    static SYSLOG: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"^(?:<\d{1,3}>\d \S+ \S+ \S+ \S+ \S+ |(?:<\d{1,3}>)?[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2} \S+ [^\s:\[]+(?:\[\d+\])?: )",
+        )
+        .expect("SYSLOG is a valid static regex pattern")
+    });
+    SYSLOG.is_match(line)
+}
+
+fn is_logfmt_line(line: &str) -> bool {
+    static PAIR: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^[A-Za-z_][\w.\-]*=(?:"[^"]*"?|\S*)$"#)
+            .expect("PAIR is a valid static regex pattern")
+    });
+    let tokens = logfmt_tokens(line);
+    let pairs = tokens.iter().filter(|t| PAIR.is_match(t)).count();
+    pairs >= 2 && pairs * 4 >= tokens.len() * 3
+}
+
+/// 按空白切分，保留引号内的空白
+fn logfmt_tokens(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                start.get_or_insert(i);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if let Some(s) = start.take() {
+                    tokens.push(&line[s..i]);
+                }
+            }
+            _ => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&line[s..]);
+    }
+    tokens
+}
+
+/// 列数（至少 3 列）一致的行占比，取逗号、制表符、分号中最一致的分隔符
+fn csv_ratio(lines: &[&str]) -> f32 {
+    match best_delimiter(lines) {
+        Some((_, columns, ratio)) if columns >= 3 => ratio,
+        _ => 0.0,
+    }
+}
+
+/// 解析 logfmt 行中的 `key=value`（值去掉包围的引号）
+pub(crate) fn logfmt_pairs(line: &str) -> impl Iterator<Item = (&str, &str)> {
+    logfmt_tokens(line).into_iter().filter_map(|token| {
+        let (key, value) = token.split_once('=')?;
+        let value = value
+            .strip_prefix('"')
+            .map(|v| v.strip_suffix('"').unwrap_or(v))
+            .unwrap_or(value);
+        Some((key, value))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(content: &str) -> LogFormat {
+        fingerprint_content(content.as_bytes()).format
+    }
+
+    #[test]
+    fn test_fingerprint_formats() {
+        assert_eq!(
+            detect("{\"level\":\"info\",\"msg\":\"a\"}\n{\"level\":\"error\",\"msg\":\"b\"}\n"),
+            LogFormat::Ndjson
+        );
+        assert_eq!(
+            detect("ts=2024-01-01T00:00:00Z level=info msg=\"started app\"\nts=2024-01-01T00:00:01Z level=warn msg=slow\n"),
+            LogFormat::Logfmt
+        );
+        assert_eq!(
+            detect(
+                "127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] \"GET /a.gif HTTP/1.0\" 200 2326\n"
+            ),
+            LogFormat::Apache
+        );
+        assert_eq!(
+            detect("Oct 11 22:14:15 mymachine su[230]: 'su root' failed\n<34>1 2003-10-11T22:14:15.003Z host app 1 ID47 - msg\n"),
+            LogFormat::Syslog
+        );
+        assert_eq!(detect("a,b,c\n1,2,3\n4,\"5,6\",7\n"), LogFormat::Csv);
+        assert_eq!(detect("a\tb\tc\n1\t2\t3\n"), LogFormat::Csv);
+        let csv = fingerprint_content(b"time,level,msg\n2024-01-02 03:04:05,INFO,ok\n");
+        assert_eq!(csv.csv_mapping.unwrap().time_column, Some(0));
+        assert_eq!(
+            detect(
+                "2024-01-01 10:00:00 INFO started\n2024-01-01 10:00:01 ERROR failed, retrying\n"
+            ),
+            LogFormat::Plain
+        );
+
+        let binary = fingerprint_content(&[0u8, 1, 2, 3, 0, 0, b'a', 0, 5, 6]);
+        assert_eq!(binary.format, LogFormat::Binary);
+        assert!(binary.confidence > 0.5);
+    }
+
+    #[test]
+    fn test_confidence_reflects_mixed_content() {
+        let mixed = "{\"a\":1}\n{\"a\":2}\n{\"a\":3}\nplain line\n";
+        let fp = fingerprint_content(mixed.as_bytes());
+        assert_eq!(fp.format, LogFormat::Ndjson);
+        assert!((fp.confidence - 0.75).abs() < f32::EPSILON);
+
+        assert_eq!("logfmt".parse::<LogFormat>(), Ok(LogFormat::Logfmt));
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_parse_metadata_as_structured() {
+        use crate::utils::parse_metadata_as;
+
+        let (ts, level) = parse_metadata_as(
+            r#"{"time":"2024-01-02T03:04:05Z","level":"WARNING","msg":"x"}"#,
+            LogFormat::Ndjson,
+        );
+        assert_eq!((ts.as_str(), level), ("2024-01-02T03:04:05", "warn"));
+
+        let (ts, level) = parse_metadata_as(
+            r#"ts="2024-01-02 03:04:05" level=fatal msg=boom"#,
+            LogFormat::Logfmt,
+        );
+        assert_eq!((ts.as_str(), level), ("2024-01-02 03:04:05", "error"));
+
+        // 缺失的字段回退到通用解析
+        let (_, level) = parse_metadata_as(r#"{"msg":"ERROR in handler"}"#, LogFormat::Ndjson);
+        assert_eq!(level, "error");
+    }
+}
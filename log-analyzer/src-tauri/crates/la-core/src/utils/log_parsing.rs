@@ -7,6 +7,8 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use tracing::debug;
 
+use crate::utils::log_format::{logfmt_pairs, LogFormat};
+use crate::utils::text_quality::clean_line;
 use crate::utils::timestamp_parser::TimestampParser;
 
 /// 从日志行中提取时间戳和日志级别。
@@ -46,6 +48,71 @@ pub fn parse_metadata(line: &str) -> (String, &'static str) {
     (timestamp, level)
 }
 
+/// 按文件格式从日志行中提取时间戳和日志级别。
+///
+/// - **NDJSON**：读取 `level` / `lvl` / `severity` 与 `timestamp` / `time` / `ts` /
+///   `@timestamp` 字段（字符串值），级别不区分大小写
+/// - **logfmt**：读取同名的 `key=value` 对
+/// - 其他格式与缺失的字段回退到 [`parse_metadata`]
+pub fn parse_metadata_as(line: &str, format: LogFormat) -> (String, &'static str) {
+    const LEVEL_KEYS: [&str; 3] = ["level", "lvl", "severity"];
+    const TIME_KEYS: [&str; 4] = ["timestamp", "time", "ts", "@timestamp"];
+
+    let (level, time) = match format {
+        LogFormat::Ndjson => {
+            let Ok(serde_json::Value::Object(map)) =
+                serde_json::from_str::<serde_json::Value>(line.trim())
+            else {
+                return parse_metadata(line);
+            };
+            let field = |keys: &[&str]| {
+                keys.iter()
+                    .find_map(|k| map.get(*k).and_then(|v| v.as_str()).map(str::to_string))
+            };
+            (field(&LEVEL_KEYS), field(&TIME_KEYS))
+        }
+        LogFormat::Logfmt => {
+            let mut level = None;
+            let mut time = None;
+            for (key, value) in logfmt_pairs(line) {
+                if level.is_none() && LEVEL_KEYS.contains(&key) {
+                    level = Some(value.to_string());
+                } else if time.is_none() && TIME_KEYS.contains(&key) {
+                    time = Some(value.to_string());
+                }
+            }
+            (level, time)
+        }
+        _ => return parse_metadata(line),
+    };
+
+    let level = level.and_then(|l| normalize_level(&l));
+    let timestamp = time.and_then(|t| TimestampParser::parse_timestamp(&t));
+    match (timestamp, level) {
+        (Some(timestamp), Some(level)) => (timestamp, level),
+        (timestamp, level) => {
+            let (fallback_ts, fallback_level) = parse_metadata(line);
+            (
+                timestamp.unwrap_or(fallback_ts),
+                level.unwrap_or(fallback_level),
+            )
+        }
+    }
+}
+
+/// 把结构化日志中的级别名映射为索引使用的小写级别
+pub(crate) fn normalize_level(level: &str) -> Option<&'static str> {
+    match level.to_ascii_lowercase().as_str() {
+        "error" | "err" | "fatal" | "critical" | "crit" | "panic" | "alert" | "emerg" => {
+            Some("error")
+        }
+        "warn" | "warning" => Some("warn"),
+        "info" | "notice" | "information" => Some("info"),
+        "debug" | "trace" | "verbose" => Some("debug"),
+        _ => None,
+    }
+}
+
 /// 将日志行批量解析为 `LogEntry` 列表。
 ///
 /// # 参数
@@ -60,18 +127,39 @@ pub fn parse_metadata(line: &str) -> (String, &'static str) {
 ///
 /// - 无搜索上下文时，`match_details` 和 `matched_keywords` 均为 `None`。
 /// - ID 和行号按顺序自动递增。
+/// - 行内的 NUL 替换为 `␀`（见 [`clean_line`]），其余内容原样保留。
 pub fn parse_log_lines(
     lines: &[String],
     file_path: &str,
     real_path: &str,
     start_id: usize,
     start_line_number: usize,
+) -> Vec<crate::models::LogEntry> {
+    parse_log_lines_as(
+        lines,
+        LogFormat::Plain,
+        file_path,
+        real_path,
+        start_id,
+        start_line_number,
+    )
+}
+
+/// 与 [`parse_log_lines`] 相同，但按文件格式解析级别与时间戳（见 [`parse_metadata_as`]）。
+pub fn parse_log_lines_as(
+    lines: &[String],
+    format: LogFormat,
+    file_path: &str,
+    real_path: &str,
+    start_id: usize,
+    start_line_number: usize,
 ) -> Vec<crate::models::LogEntry> {
     lines
         .iter()
         .enumerate()
         .map(|(i, line)| {
-            let (timestamp, level) = parse_metadata(line);
+            let line = clean_line(line);
+            let (timestamp, level) = parse_metadata_as(&line, format);
             crate::models::LogEntry {
                 id: start_id + i,
                 timestamp: timestamp.into(),
@@ -79,10 +167,16 @@ pub fn parse_log_lines(
                 file: file_path.to_string().into(),
                 real_path: real_path.to_string().into(),
                 line: start_line_number + i,
-                content: line.clone().into(),
+                content: line.into_owned().into(),
                 tags: vec![],
                 match_details: None,
                 matched_keywords: None,
+                time_offset_secs: None,
+                links: None,
+                translation: None,
+                source_files: None,
+                original_length: None,
+                fields: None,
             }
         })
         .collect()
@@ -2,17 +2,37 @@
 //!
 //! 提供 la-core 内部使用的通用工具函数
 
+pub mod crash_artifact;
+pub mod csv_mapping;
+pub mod level_histogram;
+pub mod level_order;
+pub mod log_format;
 pub mod log_levels;
 pub mod log_parsing;
+pub mod network_summary;
 pub mod path;
 pub mod path_security;
+pub mod term_bloom;
+pub mod text_quality;
+pub mod time_index;
 pub mod timestamp_parser;
 pub mod validation;
 
+pub use crash_artifact::{detect_crash_artifact, CrashArtifactInfo, CrashArtifactKind};
+pub use csv_mapping::{
+    apply_csv_metadata, csv_row_metadata, detect_csv_mapping, split_csv_record, CsvMapping,
+};
+pub use level_histogram::{daily_level_counts, DailyLevelCount};
+pub use level_order::LevelOrder;
+pub use log_format::{fingerprint_content, FormatFingerprint, LogFormat};
 pub use log_levels::level_to_mask;
-pub use log_parsing::{parse_log_lines, parse_metadata};
+pub use log_parsing::{parse_log_lines, parse_log_lines_as, parse_metadata, parse_metadata_as};
+pub use network_summary::{summarize_har, summarize_network_capture, summarize_pcap};
 pub use path_security::{
     is_windows_reserved_name, validate_and_sanitize_archive_path, validate_and_sanitize_path,
-    PathValidationResult, SecurityConfig,
+    PathValidationResult, SecurityConfig, SymlinkDecision, SymlinkGuard,
 };
+pub use term_bloom::TermBloom;
+pub use text_quality::{assess_content, clean_line, split_lines, DataQuality};
+pub use time_index::{TimeIndex, TimeIndexPoint};
 pub use timestamp_parser::TimestampParser;
@@ -0,0 +1,661 @@
+//! 抓包与 HAR 摘要 — 把网络记录转换为可搜索的日志行
+//!
+//! 网络抓包常与日志一起提交。[`summarize_network_capture`] 把 pcap/pcapng 按连接、
+//! HAR 按请求汇总为每行一条的文本（时间、级别、端点、状态、耗时），导入时作为
+//! `<文件名>.summary.log` 与原文件并列保存，从而与应用日志按时间线对照。
+//!
+//! 时间一律为 UTC，格式为 `YYYY-MM-DD HH:MM:SS.mmm`。解析只依赖字节格式，
+//! 不重组 TCP 流，也不解析应用层协议。
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+
+use super::log_format::{fingerprint_content, LogFormat};
+
+/// 单个抓包最多汇总的连接数，超出的连接只计数
+pub const MAX_SUMMARY_CONNECTIONS: usize = 100_000;
+
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+/// 内容为抓包或 HAR 时返回摘要文本，其他格式返回 `Ok(None)`；文件损坏或为空时返回错误
+pub fn summarize_network_capture(content: &[u8]) -> Result<Option<String>, String> {
+    let lines = match fingerprint_content(content).format {
+        LogFormat::Pcap => summarize_pcap(content)?,
+        LogFormat::Har => summarize_har(content)?,
+        _ => return Ok(None),
+    };
+    if lines.is_empty() {
+        return Err("Capture contains no summarizable records".to_string());
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    Ok(Some(text))
+}
+
+/// 是否以 pcap 或 pcapng 文件头开始
+pub(crate) fn is_pcap(content: &[u8]) -> bool {
+    let Some(magic) = content.get(..4) else {
+        return false;
+    };
+    let pcap_magics: [[u8; 4]; 4] = [
+        [0xd4, 0xc3, 0xb2, 0xa1],
+        [0xa1, 0xb2, 0xc3, 0xd4],
+        [0x4d, 0x3c, 0xb2, 0xa1],
+        [0xa1, 0xb2, 0x3c, 0x4d],
+    ];
+    if content.len() >= 24 && pcap_magics.iter().any(|m| m == magic) {
+        return true;
+    }
+    magic == PCAPNG_SECTION_HEADER.to_le_bytes()
+        && content.get(8..12).is_some_and(|bom| {
+            bom == PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes()
+                || bom == PCAPNG_BYTE_ORDER_MAGIC.to_be_bytes()
+        })
+}
+
+/// 是否像 HAR（顶层为 `log` 对象、带 `entries` 的 JSON）；只检查抽样开头
+pub(crate) fn looks_like_har(sample: &str) -> bool {
+    let head = sample.trim_start_matches('\u{feff}').trim_start();
+    head.starts_with('{')
+        && head.contains("\"log\"")
+        && head.contains("\"entries\"")
+        && (head.contains("\"startedDateTime\"") || head.contains("\"creator\""))
+}
+
+// ============================================================================
+// HAR
+// ============================================================================
+
+/// 每个请求一行：`时间 级别 方法 URL -> 状态 状态文本 time=耗时ms [server=地址]`
+///
+/// 状态 0（请求失败或被拦截）与 5xx 记为 ERROR，4xx 记为 WARN。
+pub fn summarize_har(content: &[u8]) -> Result<Vec<String>, String> {
+    let har: serde_json::Value =
+        serde_json::from_slice(content).map_err(|e| format!("Invalid HAR file: {e}"))?;
+    let entries = har
+        .pointer("/log/entries")
+        .and_then(|e| e.as_array())
+        .ok_or("Invalid HAR file: missing log.entries")?;
+
+    let mut requests: Vec<(DateTime<Utc>, String)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let started = DateTime::parse_from_rfc3339(entry.get("startedDateTime")?.as_str()?)
+                .ok()?
+                .with_timezone(&Utc);
+            let text = |pointer: &str| entry.pointer(pointer).and_then(|v| v.as_str());
+            let status = entry
+                .pointer("/response/status")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            let level = match status {
+                0 | 500.. => "ERROR",
+                400..=499 => "WARN",
+                _ => "INFO",
+            };
+
+            let mut line = format!(
+                "{} {level} {} {} -> {status}",
+                format_time(started),
+                text("/request/method").unwrap_or("-"),
+                text("/request/url").unwrap_or("-"),
+            );
+            if let Some(status_text) = text("/response/statusText").filter(|s| !s.is_empty()) {
+                let _ = write!(line, " {status_text}");
+            }
+            if let Some(ms) = entry.get("time").and_then(|v| v.as_f64()) {
+                let _ = write!(line, " time={ms:.0}ms");
+            }
+            if let Some(server) = text("/serverIPAddress").filter(|s| !s.is_empty()) {
+                let _ = write!(line, " server={server}");
+            }
+            Some((started, line))
+        })
+        .collect();
+
+    requests.sort_by_key(|(started, _)| *started);
+    Ok(requests.into_iter().map(|(_, line)| line).collect())
+}
+
+// ============================================================================
+// pcap / pcapng
+// ============================================================================
+
+/// 每个连接（协议 + 两端地址/端口）一行：
+/// `时间 级别 协议 发起方 -> 接收方 packets=包数 bytes=字节数 duration=秒 [flags=...]`
+///
+/// 发起方取第一个包的源地址；出现 RST 的 TCP 连接记为 WARN。支持 Ethernet（含 VLAN）、
+/// Linux cooked（SLL/SLL2）、BSD loopback 与裸 IP 链路层，其他链路层的包忽略。
+pub fn summarize_pcap(content: &[u8]) -> Result<Vec<String>, String> {
+    let mut table = ConnectionTable::default();
+    if content.get(..4) == Some(&PCAPNG_SECTION_HEADER.to_le_bytes()) {
+        read_pcapng(content, &mut table)?;
+    } else {
+        read_pcap(content, &mut table)?;
+    }
+    Ok(table.into_lines())
+}
+
+#[derive(Clone, Copy)]
+enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    fn u16(self, bytes: &[u8], at: usize) -> Option<u16> {
+        let b: [u8; 2] = bytes.get(at..at + 2)?.try_into().ok()?;
+        Some(match self {
+            Endian::Little => u16::from_le_bytes(b),
+            Endian::Big => u16::from_be_bytes(b),
+        })
+    }
+
+    fn u32(self, bytes: &[u8], at: usize) -> Option<u32> {
+        let b: [u8; 4] = bytes.get(at..at + 4)?.try_into().ok()?;
+        Some(match self {
+            Endian::Little => u32::from_le_bytes(b),
+            Endian::Big => u32::from_be_bytes(b),
+        })
+    }
+}
+
+fn read_pcap(content: &[u8], table: &mut ConnectionTable) -> Result<(), String> {
+    let (endian, nanos) = match content.get(..4) {
+        Some([0xd4, 0xc3, 0xb2, 0xa1]) => (Endian::Little, false),
+        Some([0xa1, 0xb2, 0xc3, 0xd4]) => (Endian::Big, false),
+        Some([0x4d, 0x3c, 0xb2, 0xa1]) => (Endian::Little, true),
+        Some([0xa1, 0xb2, 0x3c, 0x4d]) => (Endian::Big, true),
+        _ => return Err("Not a pcap file".to_string()),
+    };
+    let link_type = endian.u32(content, 20).ok_or("Truncated pcap header")?;
+
+    let mut offset = 24;
+    while let (Some(sec), Some(frac), Some(captured), Some(original)) = (
+        endian.u32(content, offset),
+        endian.u32(content, offset + 4),
+        endian.u32(content, offset + 8),
+        endian.u32(content, offset + 12),
+    ) {
+        let start = offset + 16;
+        let Some(data) = content.get(start..start + captured as usize) else {
+            break; // 截断的最后一个包
+        };
+        let frac_nanos = if nanos {
+            frac as i64
+        } else {
+            frac as i64 * 1_000
+        };
+        table.add(
+            sec as i64 * 1_000_000_000 + frac_nanos,
+            link_type,
+            data,
+            original,
+        );
+        offset = start + captured as usize;
+    }
+    Ok(())
+}
+
+fn read_pcapng(content: &[u8], table: &mut ConnectionTable) -> Result<(), String> {
+    // (链路层类型, 每秒的时间单位数)
+    let mut interfaces: Vec<(u32, u64)> = Vec::new();
+    let mut endian = Endian::Little;
+    let mut offset = 0;
+
+    while offset + 12 <= content.len() {
+        // 块类型 0x0A0D0D0A 是回文，与字节序无关
+        if content[offset..offset + 4] == PCAPNG_SECTION_HEADER.to_le_bytes() {
+            endian = match content.get(offset + 8..offset + 12) {
+                Some(bom) if bom == PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes() => Endian::Little,
+                Some(bom) if bom == PCAPNG_BYTE_ORDER_MAGIC.to_be_bytes() => Endian::Big,
+                _ => return Err("Invalid pcapng section header".to_string()),
+            };
+            interfaces.clear();
+        }
+        let block_type = endian.u32(content, offset).unwrap_or_default();
+        let block_len = endian.u32(content, offset + 4).unwrap_or_default() as usize;
+        if block_len < 12 || offset + block_len > content.len() {
+            break; // 截断或损坏的块
+        }
+        let body = &content[offset + 8..offset + block_len - 4];
+
+        match block_type {
+            // Interface Description Block
+            1 => {
+                let link_type = endian.u16(body, 0).unwrap_or_default() as u32;
+                interfaces.push((link_type, pcapng_ts_resolution(body, endian)));
+            }
+            // Enhanced Packet Block
+            6 => {
+                let (Some(interface), Some(high), Some(low), Some(captured), Some(original)) = (
+                    endian.u32(body, 0),
+                    endian.u32(body, 4),
+                    endian.u32(body, 8),
+                    endian.u32(body, 12),
+                    endian.u32(body, 16),
+                ) else {
+                    break;
+                };
+                let Some(&(link_type, units)) = interfaces.get(interface as usize) else {
+                    offset += block_len;
+                    continue;
+                };
+                if let Some(data) = body.get(20..20 + captured as usize) {
+                    let ticks = ((high as u64) << 32) | low as u64;
+                    let nanos = (ticks as u128 * 1_000_000_000 / units as u128) as i64;
+                    table.add(nanos, link_type, data, original);
+                }
+            }
+            _ => {}
+        }
+        offset += block_len;
+    }
+    Ok(())
+}
+
+/// 接口描述块中 `if_tsresol` 选项给出的时间精度，缺省为微秒
+fn pcapng_ts_resolution(body: &[u8], endian: Endian) -> u64 {
+    const DEFAULT_UNITS: u64 = 1_000_000;
+    let mut at = 8;
+    while let (Some(code), Some(len)) = (endian.u16(body, at), endian.u16(body, at + 2)) {
+        if code == 0 {
+            break;
+        }
+        if code == 9 && len == 1 {
+            let Some(&value) = body.get(at + 4) else {
+                break;
+            };
+            let exponent = u32::from(value & 0x7f);
+            let base: u64 = if value & 0x80 == 0 { 10 } else { 2 };
+            return base.checked_pow(exponent).unwrap_or(DEFAULT_UNITS);
+        }
+        at += 4 + (len as usize).div_ceil(4) * 4;
+    }
+    DEFAULT_UNITS
+}
+
+/// 链路层之上的 IP 包：返回 (以太类型, 负载)
+fn strip_link_layer(link_type: u32, data: &[u8]) -> Option<(u16, &[u8])> {
+    match link_type {
+        // Ethernet，含 802.1Q VLAN 标签
+        1 => {
+            let mut ether_type = u16::from_be_bytes(data.get(12..14)?.try_into().ok()?);
+            let mut start = 14;
+            while ether_type == 0x8100 || ether_type == 0x88a8 {
+                ether_type = u16::from_be_bytes(data.get(start + 2..start + 4)?.try_into().ok()?);
+                start += 4;
+            }
+            Some((ether_type, data.get(start..)?))
+        }
+        // BSD loopback：4 字节地址族
+        0 | 108 => {
+            let version = data.get(4)? >> 4;
+            Some((if version == 6 { 0x86dd } else { 0x0800 }, data.get(4..)?))
+        }
+        // 裸 IP
+        101 | 228 | 229 => {
+            let version = data.first()? >> 4;
+            Some((if version == 6 { 0x86dd } else { 0x0800 }, data))
+        }
+        // Linux cooked capture v1 / v2
+        113 => Some((
+            u16::from_be_bytes(data.get(14..16)?.try_into().ok()?),
+            data.get(16..)?,
+        )),
+        276 => Some((
+            u16::from_be_bytes(data.get(0..2)?.try_into().ok()?),
+            data.get(20..)?,
+        )),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Endpoint {
+    addr: IpAddr,
+    port: Option<u16>,
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.addr, self.port) {
+            (IpAddr::V6(addr), Some(port)) => write!(f, "[{addr}]:{port}"),
+            (addr, Some(port)) => write!(f, "{addr}:{port}"),
+            (addr, None) => write!(f, "{addr}"),
+        }
+    }
+}
+
+struct Packet {
+    protocol: u8,
+    src: Endpoint,
+    dst: Endpoint,
+    tcp_flags: u8,
+}
+
+fn parse_ip(ether_type: u16, ip: &[u8]) -> Option<Packet> {
+    let (protocol, src, dst, transport) = match ether_type {
+        0x0800 => {
+            let header_len = usize::from(ip.first()? & 0x0f) * 4;
+            let src: [u8; 4] = ip.get(12..16)?.try_into().ok()?;
+            let dst: [u8; 4] = ip.get(16..20)?.try_into().ok()?;
+            // 非首个分片没有传输层头
+            let fragment_offset = u16::from_be_bytes(ip.get(6..8)?.try_into().ok()?) & 0x1fff;
+            let transport = if fragment_offset == 0 {
+                ip.get(header_len..).unwrap_or_default()
+            } else {
+                &[]
+            };
+            (*ip.get(9)?, IpAddr::from(src), IpAddr::from(dst), transport)
+        }
+        0x86dd => {
+            let src: [u8; 16] = ip.get(8..24)?.try_into().ok()?;
+            let dst: [u8; 16] = ip.get(24..40)?.try_into().ok()?;
+            (
+                *ip.get(6)?,
+                IpAddr::from(src),
+                IpAddr::from(dst),
+                ip.get(40..).unwrap_or_default(),
+            )
+        }
+        _ => return None,
+    };
+
+    let port = |at: usize| -> Option<u16> {
+        Some(u16::from_be_bytes(
+            transport.get(at..at + 2)?.try_into().ok()?,
+        ))
+    };
+    let (src_port, dst_port) = match protocol {
+        6 | 17 | 132 => (port(0), port(2)),
+        _ => (None, None),
+    };
+    Some(Packet {
+        protocol,
+        src: Endpoint {
+            addr: src,
+            port: src_port,
+        },
+        dst: Endpoint {
+            addr: dst,
+            port: dst_port,
+        },
+        tcp_flags: if protocol == 6 {
+            transport.get(13).copied().unwrap_or_default()
+        } else {
+            0
+        },
+    })
+}
+
+struct Connection {
+    protocol: u8,
+    initiator: Endpoint,
+    responder: Endpoint,
+    first_nanos: i64,
+    last_nanos: i64,
+    packets: u64,
+    bytes: u64,
+    tcp_flags: u8,
+}
+
+#[derive(Default)]
+struct ConnectionTable {
+    connections: Vec<Connection>,
+    index: HashMap<(u8, Endpoint, Endpoint), usize>,
+    dropped: usize,
+}
+
+impl ConnectionTable {
+    fn add(&mut self, nanos: i64, link_type: u32, data: &[u8], original_len: u32) {
+        let Some(packet) =
+            strip_link_layer(link_type, data).and_then(|(ether_type, ip)| parse_ip(ether_type, ip))
+        else {
+            return;
+        };
+        let key = if packet.src <= packet.dst {
+            (packet.protocol, packet.src, packet.dst)
+        } else {
+            (packet.protocol, packet.dst, packet.src)
+        };
+
+        let connection = match self.index.get(&key) {
+            Some(&i) => &mut self.connections[i],
+            None if self.connections.len() >= MAX_SUMMARY_CONNECTIONS => {
+                self.dropped += 1;
+                return;
+            }
+            None => {
+                self.index.insert(key, self.connections.len());
+                self.connections.push(Connection {
+                    protocol: packet.protocol,
+                    initiator: packet.src,
+                    responder: packet.dst,
+                    first_nanos: nanos,
+                    last_nanos: nanos,
+                    packets: 0,
+                    bytes: 0,
+                    tcp_flags: 0,
+                });
+                self.connections
+                    .last_mut()
+                    .expect("connection was just pushed")
+            }
+        };
+        connection.first_nanos = connection.first_nanos.min(nanos);
+        connection.last_nanos = connection.last_nanos.max(nanos);
+        connection.packets += 1;
+        connection.bytes += u64::from(original_len);
+        connection.tcp_flags |= packet.tcp_flags;
+    }
+
+    fn into_lines(mut self) -> Vec<String> {
+        self.connections.sort_by_key(|c| c.first_nanos);
+        let mut lines: Vec<String> = self
+            .connections
+            .iter()
+            .map(|c| {
+                let rst = c.tcp_flags & 0x04 != 0;
+                let mut line = format!(
+                    "{} {} {} {} -> {} packets={} bytes={} duration={:.3}s",
+                    DateTime::from_timestamp_nanos(c.first_nanos).format("%Y-%m-%d %H:%M:%S%.3f"),
+                    if rst { "WARN" } else { "INFO" },
+                    protocol_name(c.protocol),
+                    c.initiator,
+                    c.responder,
+                    c.packets,
+                    c.bytes,
+                    (c.last_nanos - c.first_nanos) as f64 / 1e9,
+                );
+                let flags: Vec<&str> = [(0x02, "SYN"), (0x01, "FIN"), (0x04, "RST")]
+                    .into_iter()
+                    .filter(|(bit, _)| c.tcp_flags & bit != 0)
+                    .map(|(_, name)| name)
+                    .collect();
+                if !flags.is_empty() {
+                    let _ = write!(line, " flags={}", flags.join(","));
+                }
+                line
+            })
+            .collect();
+        if self.dropped > 0 {
+            lines.push(format!(
+                "{} more packets in connections beyond the first {MAX_SUMMARY_CONNECTIONS} were not summarized",
+                self.dropped
+            ));
+        }
+        lines
+    }
+}
+
+fn protocol_name(protocol: u8) -> String {
+    match protocol {
+        1 => "ICMP".to_string(),
+        6 => "TCP".to_string(),
+        17 => "UDP".to_string(),
+        58 => "ICMPv6".to_string(),
+        132 => "SCTP".to_string(),
+        other => format!("IP/{other}"),
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ethernet + IPv4 + TCP 帧
+    fn tcp_frame(src: [u8; 4], sport: u16, dst: [u8; 4], dport: u16, flags: u8) -> Vec<u8> {
+        let mut frame = vec![0u8; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+        let mut ip = vec![0x45, 0, 0, 40, 0, 0, 0x40, 0, 64, 6, 0, 0];
+        ip.extend_from_slice(&src);
+        ip.extend_from_slice(&dst);
+        let mut tcp = vec![0u8; 20];
+        tcp[0..2].copy_from_slice(&sport.to_be_bytes());
+        tcp[2..4].copy_from_slice(&dport.to_be_bytes());
+        tcp[13] = flags;
+        frame.extend(ip);
+        frame.extend(tcp);
+        frame
+    }
+
+    fn pcap_file(packets: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+        let mut file = vec![0xd4, 0xc3, 0xb2, 0xa1, 2, 0, 4, 0];
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+        for (sec, usec, data) in packets {
+            file.extend_from_slice(&sec.to_le_bytes());
+            file.extend_from_slice(&usec.to_le_bytes());
+            file.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            file.extend_from_slice(data);
+        }
+        file
+    }
+
+    #[test]
+    fn test_pcap_connections() {
+        let client = [10, 0, 0, 1];
+        let server = [10, 0, 0, 2];
+        let file = pcap_file(&[
+            (
+                1_704_164_645,
+                0,
+                tcp_frame(client, 51000, server, 443, 0x02),
+            ),
+            (
+                1_704_164_645,
+                500_000,
+                tcp_frame(server, 443, client, 51000, 0x12),
+            ),
+            (
+                1_704_164_646,
+                250_000,
+                tcp_frame(client, 51000, server, 443, 0x04),
+            ),
+            (1_704_164_647, 0, tcp_frame(client, 51001, server, 80, 0x02)),
+        ]);
+        assert_eq!(fingerprint_content(&file).format, LogFormat::Pcap);
+
+        let text = summarize_network_capture(&file).unwrap().unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "2024-01-02 03:04:05.000 WARN TCP 10.0.0.1:51000 -> 10.0.0.2:443 packets=3 bytes=162 duration=1.250s flags=SYN,RST",
+                "2024-01-02 03:04:07.000 INFO TCP 10.0.0.1:51001 -> 10.0.0.2:80 packets=1 bytes=54 duration=0.000s flags=SYN",
+            ]
+        );
+        let (timestamp, level) = crate::utils::parse_metadata(lines[0]);
+        assert_eq!(
+            (timestamp.as_str(), level),
+            ("2024-01-02 03:04:05.000", "warn")
+        );
+    }
+
+    #[test]
+    fn test_pcapng_enhanced_packets() {
+        let frame = tcp_frame([192, 168, 1, 5], 5353, [192, 168, 1, 9], 22, 0x02);
+        let mut file = Vec::new();
+        // Section Header Block
+        file.extend_from_slice(&PCAPNG_SECTION_HEADER.to_le_bytes());
+        file.extend_from_slice(&28u32.to_le_bytes());
+        file.extend_from_slice(&PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes());
+        file.extend_from_slice(&[1, 0, 0, 0]);
+        file.extend_from_slice(&u64::MAX.to_le_bytes());
+        file.extend_from_slice(&28u32.to_le_bytes());
+        // Interface Description Block，if_tsresol = 10^-9
+        file.extend_from_slice(&1u32.to_le_bytes());
+        file.extend_from_slice(&32u32.to_le_bytes());
+        file.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        file.extend_from_slice(&[9, 0, 1, 0, 9, 0, 0, 0]);
+        file.extend_from_slice(&[0, 0, 0, 0]);
+        file.extend_from_slice(&32u32.to_le_bytes());
+        // Enhanced Packet Block
+        let ticks: u64 = 1_704_164_645_123_000_000;
+        let padded = frame.len().div_ceil(4) * 4;
+        let block_len = (32 + padded) as u32;
+        file.extend_from_slice(&6u32.to_le_bytes());
+        file.extend_from_slice(&block_len.to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes());
+        file.extend_from_slice(&((ticks >> 32) as u32).to_le_bytes());
+        file.extend_from_slice(&(ticks as u32).to_le_bytes());
+        file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        file.extend_from_slice(&frame);
+        file.resize(file.len() + padded - frame.len(), 0);
+        file.extend_from_slice(&block_len.to_le_bytes());
+
+        assert!(is_pcap(&file));
+        assert_eq!(
+            summarize_pcap(&file).unwrap(),
+            vec!["2024-01-02 03:04:05.123 INFO TCP 192.168.1.5:5353 -> 192.168.1.9:22 packets=1 bytes=54 duration=0.000s flags=SYN"]
+        );
+    }
+
+    #[test]
+    fn test_har_requests() {
+        let har = r#"{
+  "log": {
+    "version": "1.2",
+    "creator": {"name": "browser", "version": "1"},
+    "entries": [
+      {
+        "startedDateTime": "2024-01-02T04:04:06.500+01:00",
+        "time": 1203.4,
+        "request": {"method": "POST", "url": "https://api.example.com/orders"},
+        "response": {"status": 503, "statusText": "Service Unavailable"},
+        "serverIPAddress": "203.0.113.7"
+      },
+      {
+        "startedDateTime": "2024-01-02T03:04:05.000Z",
+        "time": 12,
+        "request": {"method": "GET", "url": "https://example.com/"},
+        "response": {"status": 200, "statusText": "OK"}
+      }
+    ]
+  }
+}"#;
+        assert_eq!(fingerprint_content(har.as_bytes()).format, LogFormat::Har);
+        assert_eq!(
+            summarize_har(har.as_bytes()).unwrap(),
+            vec![
+                "2024-01-02 03:04:05.000 INFO GET https://example.com/ -> 200 OK time=12ms",
+                "2024-01-02 03:04:06.500 ERROR POST https://api.example.com/orders -> 503 Service Unavailable time=1203ms server=203.0.113.7",
+            ]
+        );
+
+        assert_eq!(summarize_network_capture(b"plain log line\n"), Ok(None));
+        assert!(summarize_har(b"{\"log\":{}}").is_err());
+    }
+}
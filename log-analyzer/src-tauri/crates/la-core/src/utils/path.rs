@@ -15,3 +15,134 @@ pub fn normalize_path_separator(path: &str) -> String {
         path.to_string()
     }
 }
+
+/// 转换为 Windows 扩展长度路径（`\\?\` 前缀）
+///
+/// 带前缀的路径不受 MAX_PATH（260）限制，也不会被系统按设备名
+/// （`aux.log` 等）解析。前缀路径不会再做 `.` / `..` 处理，因此这里先做
+/// 词法规范化。仅处理绝对路径；非 Windows 平台原样返回。
+///
+/// 用于实际 I/O 调用（创建目录、写文件）；展示给用户的路径应使用原始路径
+/// 或经 [`strip_extended_length_prefix`] 还原。
+pub fn to_extended_length_path(path: &std::path::Path) -> std::path::PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        std::path::PathBuf::from(extended_length_windows(&path.to_string_lossy()))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        path.to_path_buf()
+    }
+}
+
+/// 去除扩展长度前缀，还原为便于展示的普通路径
+pub fn strip_extended_length_prefix(path: &str) -> String {
+    if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Windows 扩展长度路径转换（平台无关的字符串实现，便于在所有平台测试）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn extended_length_windows(path: &str) -> String {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return path.to_string();
+    }
+    let path = path.replace('/', "\\");
+
+    let (prefix, rest, root_len) = if let Some(unc) = path.strip_prefix(r"\\") {
+        // \\server\share\...：server 与 share 构成根
+        (r"\\?\UNC\", unc, 2)
+    } else {
+        let bytes = path.as_bytes();
+        let is_drive_absolute = bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && bytes[2] == b'\\';
+        if !is_drive_absolute {
+            return path;
+        }
+        (r"\\?\", path.as_str(), 1)
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('\\') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                if components.len() > root_len {
+                    components.pop();
+                }
+            }
+            other => components.push(other),
+        }
+    }
+
+    let mut result = format!("{prefix}{}", components.join("\\"));
+    if components.len() <= root_len {
+        result.push('\\');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_length_drive_path() {
+        assert_eq!(
+            extended_length_windows(r"C:\logs\.\a\..\b/aux.log"),
+            r"\\?\C:\logs\b\aux.log"
+        );
+        assert_eq!(extended_length_windows(r"C:\"), r"\\?\C:\");
+        assert_eq!(extended_length_windows(r"C:\..\x"), r"\\?\C:\x");
+    }
+
+    #[test]
+    fn test_extended_length_unc_path() {
+        assert_eq!(
+            extended_length_windows(r"\\server\share\dir\..\app.log"),
+            r"\\?\UNC\server\share\app.log"
+        );
+        assert_eq!(
+            extended_length_windows(r"\\server\share\..\..\x"),
+            r"\\?\UNC\server\share\x"
+        );
+    }
+
+    #[test]
+    fn test_extended_length_keeps_verbatim_and_relative() {
+        assert_eq!(extended_length_windows(r"\\?\C:\a"), r"\\?\C:\a");
+        assert_eq!(extended_length_windows(r"relative\a"), r"relative\a");
+    }
+
+    #[test]
+    fn test_extended_length_long_path() {
+        let deep = (0..40)
+            .map(|i| format!("directory_{i:02}"))
+            .collect::<Vec<_>>()
+            .join("\\");
+        let path = format!(r"C:\workspace\{deep}\app.log");
+        assert!(path.len() > 260);
+        let extended = extended_length_windows(&path);
+        assert_eq!(extended, format!(r"\\?\{path}"));
+        assert_eq!(strip_extended_length_prefix(&extended), path);
+    }
+
+    #[test]
+    fn test_strip_extended_length_prefix() {
+        assert_eq!(
+            strip_extended_length_prefix(r"\\?\UNC\server\share\a.log"),
+            r"\\server\share\a.log"
+        );
+        assert_eq!(
+            strip_extended_length_prefix("/var/log/a.log"),
+            "/var/log/a.log"
+        );
+    }
+}
@@ -3,11 +3,13 @@
 //! 提供全面的路径安全检查功能,防止路径穿越、文件系统错误和恶意压缩包攻击。
 //! 支持Windows保留字符过滤、保留文件名检测、路径长度限制等安全措施。
 
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
 use tracing::warn;
 use unicode_normalization::UnicodeNormalization;
 
-#[cfg(test)]
-use std::path::Path;
+use crate::models::extraction_policy::SymlinkPolicy;
 
 /// 路径组件验证结果
 #[derive(Debug, Clone, PartialEq)]
@@ -41,10 +43,11 @@ impl Default for SecurityConfig {
     }
 }
 
-/// Windows保留文件名列表
+/// Windows保留文件名列表(含 COM0/LPT0、上标数字与控制台设备名)
 const WINDOWS_RESERVED_NAMES: &[&str] = &[
-    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
-    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6",
+    "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³", "CONIN$", "CONOUT$",
 ];
 
 /// Windows保留字符列表
@@ -139,6 +142,18 @@ pub fn validate_and_sanitize_path(
         }
     }
 
+    // Windows: 结尾的点和空格会被系统静默去除(导致重名或打开失败)，替换为下划线
+    let mut has_trailing_dots = false;
+    if config.windows_compatible {
+        let trimmed_len = sanitized.trim_end_matches(['.', ' ']).len();
+        if trimmed_len < sanitized.len() {
+            has_trailing_dots = true;
+            let trailing = sanitized.len() - trimmed_len;
+            sanitized.truncate(trimmed_len);
+            sanitized.push_str(&"_".repeat(trailing));
+        }
+    }
+
     // Windows: 检查保留文件名
     let needs_prefix = if config.windows_compatible {
         is_windows_reserved_name(&sanitized)
@@ -160,7 +175,12 @@ pub fn validate_and_sanitize_path(
     };
 
     // 判断是否需要清理
-    if has_control_chars || has_reserved_chars || needs_prefix || needs_truncation {
+    if has_control_chars
+        || has_reserved_chars
+        || has_trailing_dots
+        || needs_prefix
+        || needs_truncation
+    {
         PathValidationResult::RequiresSanitization(component.to_string(), sanitized)
     } else if sanitized == component {
         PathValidationResult::Valid(sanitized)
@@ -242,6 +262,9 @@ pub fn validate_and_sanitize_archive_path(
 
 /// 检查是否为Windows保留文件名
 ///
+/// Windows 按第一个点之前的部分(忽略结尾空格)判断设备名，
+/// 因此 `aux.log`、`aux.tar.gz`、`NUL .txt` 都会被解析为设备。
+///
 /// # Arguments
 ///
 /// * `name` - 要检查的文件名(不含路径)
@@ -250,15 +273,10 @@ pub fn validate_and_sanitize_archive_path(
 ///
 /// 如果是保留文件名返回true,否则返回false
 pub fn is_windows_reserved_name(name: &str) -> bool {
-    // 移除扩展名
-    let name_without_ext = if let Some(pos) = name.rfind('.') {
-        &name[..pos]
-    } else {
-        name
-    };
+    let base = name.split('.').next().unwrap_or(name).trim_end_matches(' ');
 
     // 大小写不敏感比较
-    let upper_name = name_without_ext.to_uppercase();
+    let upper_name = base.to_uppercase();
     WINDOWS_RESERVED_NAMES.contains(&upper_name.as_str())
 }
 
@@ -327,6 +345,102 @@ fn truncate_long_component(component: &str, max_len: usize) -> String {
     result
 }
 
+/// 符号链接检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymlinkDecision {
+    /// 跳过该链接,附带原因
+    Skip { reason: String },
+    /// 跟随链接,target 为规范化后的目标路径(保证位于根目录内)
+    Follow { target: PathBuf },
+    /// 不跟随,仅作为元数据记录
+    Record { target: PathBuf, within_root: bool },
+}
+
+/// 目录导入时的符号链接守卫
+///
+/// 按 [`SymlinkPolicy`] 决定每个链接的处理方式,并记录已遍历目录的规范路径,
+/// 用于检测符号链接环路以及指向同一目录的重复链接。
+/// 硬链接在文件系统层面就是普通文件,不经过此守卫,重复内容由 CAS 去重。
+#[derive(Debug)]
+pub struct SymlinkGuard {
+    root: PathBuf,
+    policy: SymlinkPolicy,
+    visited: HashSet<PathBuf>,
+}
+
+impl SymlinkGuard {
+    /// 创建守卫;根目录无法规范化时退回原始路径
+    pub fn new(root: &Path, policy: SymlinkPolicy) -> Self {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut visited = HashSet::new();
+        visited.insert(root.clone());
+        Self {
+            root,
+            policy,
+            visited,
+        }
+    }
+
+    pub fn policy(&self) -> SymlinkPolicy {
+        self.policy
+    }
+
+    /// 判断单个符号链接的处理方式
+    pub fn check(&self, link: &Path) -> SymlinkDecision {
+        if self.policy == SymlinkPolicy::Skip {
+            return SymlinkDecision::Skip {
+                reason: "symlink policy is skip".to_string(),
+            };
+        }
+
+        let raw_target = match std::fs::read_link(link) {
+            Ok(target) => target,
+            Err(e) => {
+                return SymlinkDecision::Skip {
+                    reason: format!("无法读取符号链接: {e}"),
+                }
+            }
+        };
+        let joined = match link.parent() {
+            Some(parent) if raw_target.is_relative() => parent.join(&raw_target),
+            _ => raw_target.clone(),
+        };
+
+        let resolved = joined.canonicalize();
+        let within_root = resolved
+            .as_ref()
+            .map(|target| target.starts_with(&self.root))
+            .unwrap_or(false);
+
+        match self.policy {
+            SymlinkPolicy::Skip => unreachable!(),
+            SymlinkPolicy::RecordAsMetadata => SymlinkDecision::Record {
+                target: resolved.unwrap_or(raw_target),
+                within_root,
+            },
+            SymlinkPolicy::FollowWithinRoot => match resolved {
+                Err(e) => SymlinkDecision::Skip {
+                    reason: format!("符号链接目标不可访问: {e}"),
+                },
+                Ok(target) if !within_root => SymlinkDecision::Skip {
+                    reason: format!("符号链接指向根目录之外: {}", target.display()),
+                },
+                Ok(target) => SymlinkDecision::Follow { target },
+            },
+        }
+    }
+
+    /// 登记即将进入的目录(按规范路径)
+    ///
+    /// 返回 false 表示该目录已遍历过(符号链接环路或重复链接),应剪枝。
+    pub fn enter_dir(&mut self, dir: &Path) -> bool {
+        match dir.canonicalize() {
+            Ok(canonical) => self.visited.insert(canonical),
+            Err(_) => false,
+        }
+    }
+}
+
 /// 检查路径深度
 ///
 /// # Arguments
@@ -350,7 +464,6 @@ pub fn check_path_depth(path: &Path, max_depth: usize) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_validate_normal_path() {
@@ -393,6 +506,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_trailing_dots_and_spaces() {
+        let config = SecurityConfig::default();
+        match validate_and_sanitize_path("report. ", &config) {
+            PathValidationResult::RequiresSanitization(_, sanitized) => {
+                assert_eq!(sanitized, "report__");
+            }
+            other => panic!("Expected RequiresSanitization, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_archive_path_with_reserved_names() {
+        let config = SecurityConfig::default();
+        match validate_and_sanitize_archive_path("logs/aux.log/con/app.log", &config) {
+            PathValidationResult::RequiresSanitization(_, sanitized) => {
+                assert_eq!(sanitized, "logs/_aux.log/_con/app.log");
+            }
+            other => panic!("Expected RequiresSanitization, got: {other:?}"),
+        }
+    }
+
     #[test]
     fn test_validate_control_characters() {
         let config = SecurityConfig::default();
@@ -427,6 +562,11 @@ mod tests {
         assert!(is_windows_reserved_name("con"));
         assert!(is_windows_reserved_name("CON.txt"));
         assert!(is_windows_reserved_name("COM1"));
+        assert!(is_windows_reserved_name("aux.tar.gz"));
+        assert!(is_windows_reserved_name("nul .log"));
+        assert!(is_windows_reserved_name("COM0.log"));
+        assert!(is_windows_reserved_name("conout$"));
+        assert!(!is_windows_reserved_name("auxiliary.log"));
         assert!(!is_windows_reserved_name("CONFIG"));
         assert!(!is_windows_reserved_name("normal"));
     }
@@ -562,4 +702,70 @@ mod tests {
             }
         }
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_guard_follow_within_root() {
+        use std::os::unix::fs::symlink;
+
+        let outside = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("logs")).unwrap();
+        symlink(root.join("logs"), root.join("inner")).unwrap();
+        symlink(outside.path(), root.join("escape")).unwrap();
+        symlink(root, root.join("logs").join("loop")).unwrap();
+        symlink(root.join("missing"), root.join("broken")).unwrap();
+
+        let mut guard = SymlinkGuard::new(root, SymlinkPolicy::FollowWithinRoot);
+        assert!(matches!(
+            guard.check(&root.join("inner")),
+            SymlinkDecision::Follow { .. }
+        ));
+        assert!(matches!(
+            guard.check(&root.join("escape")),
+            SymlinkDecision::Skip { .. }
+        ));
+        assert!(matches!(
+            guard.check(&root.join("broken")),
+            SymlinkDecision::Skip { .. }
+        ));
+
+        // 环路: logs/loop -> root,root 已登记
+        assert!(matches!(
+            guard.check(&root.join("logs").join("loop")),
+            SymlinkDecision::Follow { .. }
+        ));
+        assert!(!guard.enter_dir(&root.join("logs").join("loop")));
+        assert!(guard.enter_dir(&root.join("logs")));
+        assert!(!guard.enter_dir(&root.join("inner")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_guard_skip_and_record() {
+        use std::os::unix::fs::symlink;
+
+        let outside = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        symlink(outside.path(), dir.path().join("escape")).unwrap();
+
+        let guard = SymlinkGuard::new(dir.path(), SymlinkPolicy::Skip);
+        assert!(matches!(
+            guard.check(&dir.path().join("escape")),
+            SymlinkDecision::Skip { .. }
+        ));
+
+        let guard = SymlinkGuard::new(dir.path(), SymlinkPolicy::RecordAsMetadata);
+        match guard.check(&dir.path().join("escape")) {
+            SymlinkDecision::Record {
+                target,
+                within_root,
+            } => {
+                assert!(!within_root);
+                assert_eq!(target, outside.path().canonicalize().unwrap());
+            }
+            other => panic!("unexpected decision: {other:?}"),
+        }
+    }
 }
@@ -0,0 +1,179 @@
+//! 文件内容三元组布隆过滤器
+//!
+//! 导入时对每个文件的全部字节三元组（ASCII 小写化）建立布隆过滤器，存入元数据。
+//! 查询时若字面量关键词的某个三元组不在过滤器中，该文件必然不包含此关键词，可直接跳过。
+//!
+//! - 三元组覆盖任意子串查询（关键词长度 ≥ 3），与搜索的子串匹配语义一致
+//! - ASCII 小写化后，大小写敏感与不敏感的查询都可安全判断
+//! - 只对合法 UTF-8 内容构建：其他编码在搜索时会先转码，原始字节的三元组不可靠
+//!
+//! 序列化格式：`[b'T', 版本, k, 0, 位数组（小端 u64）...]`
+
+/// 每个不同三元组分配的位数（k = 6 时误判率约 1%）
+const BITS_PER_TRIGRAM: usize = 10;
+const HASH_COUNT: u8 = 6;
+const MIN_BITS: usize = 1 << 10;
+const MAX_BITS: usize = 1 << 20;
+/// 不同三元组过多时过滤器接近饱和，不再有筛选价值
+const MAX_DISTINCT_TRIGRAMS: usize = MAX_BITS / 5;
+const FORMAT_MAGIC: u8 = b'T';
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 4;
+
+/// 文件内容的三元组布隆过滤器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermBloom {
+    words: Vec<u64>,
+    hash_count: u8,
+}
+
+impl TermBloom {
+    /// 由文件内容构建；内容不是 UTF-8、过短或三元组过多时返回 `None`（该文件不参与跳过）
+    pub fn from_content(content: &[u8]) -> Option<Self> {
+        if content.len() < 3 || std::str::from_utf8(content).is_err() {
+            return None;
+        }
+
+        // 三元组空间为 2^24，用 2MB 位图精确去重，内存与文件大小无关
+        let mut seen = vec![0u64; (1 << 24) / 64];
+        let mut distinct = 0usize;
+        for window in content.windows(3) {
+            let trigram = pack_trigram(window);
+            let (word, bit) = ((trigram >> 6) as usize, trigram & 63);
+            if seen[word] & (1 << bit) == 0 {
+                seen[word] |= 1 << bit;
+                distinct += 1;
+            }
+        }
+        if distinct > MAX_DISTINCT_TRIGRAMS {
+            return None;
+        }
+
+        let bits = (distinct * BITS_PER_TRIGRAM)
+            .next_power_of_two()
+            .clamp(MIN_BITS, MAX_BITS);
+        let mut bloom = Self {
+            words: vec![0u64; bits / 64],
+            hash_count: HASH_COUNT,
+        };
+        for (word_index, word) in seen.iter().enumerate() {
+            let mut remaining = *word;
+            while remaining != 0 {
+                let bit = remaining.trailing_zeros();
+                remaining &= remaining - 1;
+                bloom.insert(((word_index as u32) << 6) | bit);
+            }
+        }
+        Some(bloom)
+    }
+
+    /// 文件是否可能包含 `term`（按 ASCII 大小写不敏感判断）
+    ///
+    /// 长度不足 3 字节的关键词无法判断，始终返回 `true`。
+    pub fn may_contain(&self, term: &str) -> bool {
+        let bytes = term.as_bytes();
+        if bytes.len() < 3 {
+            return true;
+        }
+        bytes
+            .windows(3)
+            .all(|window| self.contains_trigram(pack_trigram(window)))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.words.len() * 8);
+        out.extend_from_slice(&[FORMAT_MAGIC, FORMAT_VERSION, self.hash_count, 0]);
+        for word in &self.words {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+
+    /// 解析序列化数据；格式不识别时返回 `None`
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (header, body) = bytes.split_at_checked(HEADER_LEN)?;
+        if header[0] != FORMAT_MAGIC || header[1] != FORMAT_VERSION || header[2] == 0 {
+            return None;
+        }
+        let word_count = body.len() / 8;
+        if body.len() % 8 != 0 || !word_count.is_power_of_two() {
+            return None;
+        }
+        let words = body
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap_or_default()))
+            .collect();
+        Some(Self {
+            words,
+            hash_count: header[2],
+        })
+    }
+
+    fn bit_positions(&self, trigram: u32) -> impl Iterator<Item = usize> {
+        bit_positions(self.hash_count, self.words.len() * 64, trigram)
+    }
+
+    fn insert(&mut self, trigram: u32) {
+        for pos in bit_positions(self.hash_count, self.words.len() * 64, trigram) {
+            self.words[pos / 64] |= 1 << (pos % 64);
+        }
+    }
+
+    fn contains_trigram(&self, trigram: u32) -> bool {
+        self.bit_positions(trigram)
+            .all(|pos| self.words[pos / 64] & (1 << (pos % 64)) != 0)
+    }
+}
+
+/// 双重哈希得到 k 个位下标；`bits` 为 2 的幂
+fn bit_positions(hash_count: u8, bits: usize, trigram: u32) -> impl Iterator<Item = usize> {
+    let hash = mix64(trigram as u64);
+    let h1 = hash as u32 as usize;
+    let h2 = ((hash >> 32) as u32 | 1) as usize;
+    let mask = bits - 1;
+    (0..hash_count as usize).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) & mask)
+}
+
+fn pack_trigram(window: &[u8]) -> u32 {
+    ((window[0].to_ascii_lowercase() as u32) << 16)
+        | ((window[1].to_ascii_lowercase() as u32) << 8)
+        | window[2].to_ascii_lowercase() as u32
+}
+
+/// splitmix64 终混函数
+fn mix64(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_every_substring_case_insensitively() {
+        let content = b"2024-01-15 10:30:00 ERROR connection timeout on worker-7\n";
+        let bloom = TermBloom::from_content(content).unwrap();
+        assert!(bloom.may_contain("timeout"));
+        assert!(bloom.may_contain("Connection Timeout"));
+        assert!(bloom.may_contain("worker-7"));
+        assert!(bloom.may_contain("ok"));
+        assert!(!bloom.may_contain("deadlock detected"));
+    }
+
+    #[test]
+    fn test_round_trip_bytes() {
+        let bloom = TermBloom::from_content("服务 started\nshutdown".as_bytes()).unwrap();
+        let restored = TermBloom::from_bytes(&bloom.to_bytes()).unwrap();
+        assert_eq!(bloom, restored);
+        assert!(restored.may_contain("服务"));
+        assert!(TermBloom::from_bytes(b"junk").is_none());
+    }
+
+    #[test]
+    fn test_non_utf8_content_is_not_summarized() {
+        assert!(TermBloom::from_content(b"\xc4\xe3\xba\xc3 gbk text").is_none());
+        assert!(TermBloom::from_content(b"ab").is_none());
+    }
+}
@@ -0,0 +1,159 @@
+//! 文本质量 — NUL 字节、混合换行与无效 UTF-8
+//!
+//! 各扫描路径统一用 [`split_lines`] 切分行（`\r\n`、`\n` 与单独的 `\r` 都是行结束符），
+//! 用 [`clean_line`] 把 NUL 替换为可见的 `␀`，保证索引、搜索与读取时的行号一致。
+//! [`assess_content`] 在导入时统计每个内容对象的问题，按内容哈希记录，
+//! 有问题的文件在虚拟文件树中带数据质量提示。
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+/// NUL 字节在行内容中的替代字符（U+2400 SYMBOL FOR NULL）
+pub const NUL_REPLACEMENT: char = '\u{2400}';
+
+/// 单个内容对象的数据质量统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataQuality {
+    /// 无效 UTF-8 序列数（解码时各替换为一个 U+FFFD）
+    pub invalid_sequences: u64,
+    pub nul_bytes: u64,
+    /// 以 `\r\n` 结尾的行数
+    pub crlf_lines: u64,
+    /// 以 `\n` 结尾的行数
+    pub lf_lines: u64,
+    /// 以单独的 `\r` 结尾的行数
+    pub cr_lines: u64,
+}
+
+impl DataQuality {
+    /// 同一文件中出现了不止一种换行符
+    pub fn mixed_line_endings(&self) -> bool {
+        [self.crlf_lines, self.lf_lines, self.cr_lines]
+            .iter()
+            .filter(|n| **n > 0)
+            .count()
+            > 1
+    }
+
+    /// 是否需要向用户提示
+    pub fn has_warnings(&self) -> bool {
+        self.invalid_sequences > 0
+            || self.nul_bytes > 0
+            || self.cr_lines > 0
+            || self.mixed_line_endings()
+    }
+}
+
+/// 统计内容中的无效 UTF-8 序列、NUL 字节与各类换行符
+pub fn assess_content(content: &[u8]) -> DataQuality {
+    let mut quality = DataQuality::default();
+
+    let mut i = 0;
+    while i < content.len() {
+        match content[i] {
+            0 => quality.nul_bytes += 1,
+            b'\n' => quality.lf_lines += 1,
+            b'\r' if content.get(i + 1) == Some(&b'\n') => {
+                quality.crlf_lines += 1;
+                i += 1;
+            }
+            b'\r' => quality.cr_lines += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut rest = content;
+    while let Err(e) = std::str::from_utf8(rest) {
+        quality.invalid_sequences += 1;
+        let skip = e.valid_up_to() + e.error_len().unwrap_or(rest.len() - e.valid_up_to());
+        rest = &rest[skip..];
+    }
+
+    quality
+}
+
+/// 按 `\r\n`、`\n` 或单独的 `\r` 切分行；与 `str::lines` 一样不产生末尾的空行
+pub fn split_lines(text: &str) -> SplitLines<'_> {
+    SplitLines { rest: text }
+}
+
+/// [`split_lines`] 返回的迭代器
+pub struct SplitLines<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for SplitLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        match self.rest.find(['\n', '\r']) {
+            Some(pos) => {
+                let line = &self.rest[..pos];
+                let terminator = if self.rest[pos..].starts_with("\r\n") {
+                    2
+                } else {
+                    1
+                };
+                self.rest = &self.rest[pos + terminator..];
+                Some(line)
+            }
+            None => Some(std::mem::take(&mut self.rest)),
+        }
+    }
+}
+
+/// 把行内的 NUL 替换为 [`NUL_REPLACEMENT`]，无 NUL 时不分配
+pub fn clean_line(line: &str) -> Cow<'_, str> {
+    if line.contains('\0') {
+        Cow::Owned(line.replace('\0', "\u{2400}"))
+    } else {
+        Cow::Borrowed(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_lines_handles_crlf_lf_and_bare_cr() {
+        let lines: Vec<_> = split_lines("a\r\nb\nc\rd\r\r\ne").collect();
+        assert_eq!(lines, vec!["a", "b", "c", "d", "", "e"]);
+        assert_eq!(split_lines("x\n").collect::<Vec<_>>(), vec!["x"]);
+        assert_eq!(split_lines("\n\n").collect::<Vec<_>>(), vec!["", ""]);
+        assert_eq!(split_lines("").count(), 0);
+    }
+
+    #[test]
+    fn test_assess_content_counts_problems() {
+        let quality = assess_content(b"ok\r\nnul\0here\nbad \xff\xfe end\rlast \xe4\xb8");
+        assert_eq!(
+            quality,
+            DataQuality {
+                invalid_sequences: 3,
+                nul_bytes: 1,
+                crlf_lines: 1,
+                lf_lines: 1,
+                cr_lines: 1,
+            }
+        );
+        assert!(quality.mixed_line_endings());
+        assert!(quality.has_warnings());
+
+        let clean = assess_content("2024-01-01 INFO 世界\n".as_bytes());
+        assert!(!clean.has_warnings());
+        assert_eq!(clean.lf_lines, 1);
+    }
+
+    #[test]
+    fn test_clean_line_replaces_nul_only_when_present() {
+        assert!(matches!(clean_line("plain"), Cow::Borrowed("plain")));
+        assert_eq!(clean_line("a\0b"), "a\u{2400}b");
+    }
+}
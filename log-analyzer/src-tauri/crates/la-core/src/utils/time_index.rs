@@ -0,0 +1,264 @@
+//! 文件内容的稀疏时间索引
+//!
+//! 导入时每隔约 [`STRIDE_BYTES`] 字节在行首记录一个检查点（行号、字节偏移、此前所有行的
+//! 最大时间戳），存入元数据。跳转到某一时间或按起始时间过滤时，从最后一个
+//! “此前没有任何行达到目标时间”的检查点开始读取，而不必从文件开头扫描。
+//!
+//! - 记录的是前缀最大值，时间乱序的日志同样安全：检查点之前的行必然早于目标时间
+//! - 时间戳按秒向下取整，比较用严格小于，亚秒精度的过滤不会漏行
+//! - 只对合法 UTF-8 内容构建：其他编码在读取时会先转码，原始字节偏移不可靠
+//!
+//! 序列化格式：`[b'S', 版本, 0, 0, 首个时间戳（i64 小端）, 检查点（行号 u64、偏移 u64、
+//! 前缀最大时间戳 i64，均为小端）...]`，没有任何时间戳的前缀记为 `i64::MIN`
+
+use crate::utils::text_quality::split_lines;
+use crate::utils::timestamp_parser::TimestampParser;
+
+/// 相邻检查点的最小间隔（字节）
+pub const STRIDE_BYTES: usize = 64 * 1024;
+/// 检查点数量上限，超大文件按比例放宽间隔
+const MAX_POINTS: usize = 4096;
+const FORMAT_MAGIC: u8 = b'S';
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 12;
+const POINT_LEN: usize = 24;
+
+/// 检查点：从这里开始读取时，之前的行都早于 `max_before` 之后的时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeIndexPoint {
+    /// 检查点所在行（从 1 开始）
+    pub line: usize,
+    /// 该行在内容中的字节偏移
+    pub offset: u64,
+    /// 此前所有行的最大时间戳（Unix 秒）；此前没有时间戳时为 `None`
+    pub max_before: Option<i64>,
+}
+
+impl TimeIndexPoint {
+    /// 文件开头
+    pub const START: Self = Self {
+        line: 1,
+        offset: 0,
+        max_before: None,
+    };
+}
+
+/// 文件内容的稀疏时间索引
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeIndex {
+    first_timestamp: i64,
+    points: Vec<TimeIndexPoint>,
+}
+
+impl TimeIndex {
+    /// 由文件内容构建；内容不是 UTF-8、不足两个检查点或没有任何时间戳时返回 `None`
+    pub fn from_content(content: &[u8]) -> Option<Self> {
+        if content.len() <= STRIDE_BYTES {
+            return None;
+        }
+        let text = std::str::from_utf8(content).ok()?;
+        Self::from_text_with(text, line_timestamp)
+    }
+
+    /// 由 UTF-8 内容构建，每行的时间戳由 `timestamp` 给出（如按用户设置的解析器与时区覆盖）；
+    /// 不足两个检查点或没有任何时间戳时返回 `None`
+    pub fn from_text_with(
+        text: &str,
+        mut timestamp: impl FnMut(&str) -> Option<i64>,
+    ) -> Option<Self> {
+        if text.len() <= STRIDE_BYTES {
+            return None;
+        }
+        let stride = STRIDE_BYTES.max(text.len() / MAX_POINTS);
+
+        let mut points = Vec::new();
+        let mut first_timestamp = None;
+        let mut max_seen: Option<i64> = None;
+        let mut next_point_at = 0usize;
+        for (index, line) in split_lines(text).enumerate() {
+            let offset = line.as_ptr() as usize - text.as_ptr() as usize;
+            if offset >= next_point_at {
+                points.push(TimeIndexPoint {
+                    line: index + 1,
+                    offset: offset as u64,
+                    max_before: max_seen,
+                });
+                next_point_at = offset + stride;
+            }
+            if let Some(ts) = timestamp(line) {
+                first_timestamp.get_or_insert(ts);
+                max_seen = Some(max_seen.map_or(ts, |m| m.max(ts)));
+            }
+        }
+
+        if points.len() < 2 {
+            return None;
+        }
+        Some(Self {
+            first_timestamp: first_timestamp?,
+            points,
+        })
+    }
+
+    /// 由外部按行首记录的检查点构建（如 gzip 分帧时每帧一个）；检查点需按行号严格递增，
+    /// 第一个时间戳缺失或不足两个检查点时返回 `None`
+    pub fn from_points(first_timestamp: Option<i64>, points: Vec<TimeIndexPoint>) -> Option<Self> {
+        if points.len() < 2 || points.windows(2).any(|w| w[0].line >= w[1].line) {
+            return None;
+        }
+        Some(Self {
+            first_timestamp: first_timestamp?,
+            points,
+        })
+    }
+
+    /// 文件中第一个时间戳（Unix 秒），用于把 `14:32` 这类时刻换算到文件所在日期
+    pub fn first_timestamp(&self) -> i64 {
+        self.first_timestamp
+    }
+
+    /// 检查点（按行号升序）
+    pub fn points(&self) -> &[TimeIndexPoint] {
+        &self.points
+    }
+
+    /// 第一个不早于 `timestamp`（Unix 秒）的行必然位于返回的检查点或其后
+    pub fn seek(&self, timestamp: i64) -> TimeIndexPoint {
+        // 前缀最大值单调不减，满足条件的检查点是一段前缀
+        let count = self
+            .points
+            .partition_point(|p| p.max_before.is_none_or(|max| max < timestamp));
+        count
+            .checked_sub(1)
+            .map_or(TimeIndexPoint::START, |i| self.points[i])
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.points.len() * POINT_LEN);
+        bytes.extend_from_slice(&[FORMAT_MAGIC, FORMAT_VERSION, 0, 0]);
+        bytes.extend_from_slice(&self.first_timestamp.to_le_bytes());
+        for point in &self.points {
+            bytes.extend_from_slice(&(point.line as u64).to_le_bytes());
+            bytes.extend_from_slice(&point.offset.to_le_bytes());
+            bytes.extend_from_slice(&point.max_before.unwrap_or(i64::MIN).to_le_bytes());
+        }
+        bytes
+    }
+
+    /// 解析 [`Self::to_bytes`] 的输出；格式不符时返回 `None`（按无索引处理）
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_LEN
+            || bytes[0] != FORMAT_MAGIC
+            || bytes[1] != FORMAT_VERSION
+            || (bytes.len() - HEADER_LEN) % POINT_LEN != 0
+        {
+            return None;
+        }
+        let word = |chunk: &[u8], at: usize| {
+            i64::from_le_bytes(chunk[at..at + 8].try_into().expect("8-byte slice"))
+        };
+        let first_timestamp = word(bytes, 4);
+        let points: Vec<TimeIndexPoint> = bytes[HEADER_LEN..]
+            .chunks_exact(POINT_LEN)
+            .map(|chunk| {
+                let max_before = word(chunk, 16);
+                TimeIndexPoint {
+                    line: word(chunk, 0) as usize,
+                    offset: word(chunk, 8) as u64,
+                    max_before: (max_before != i64::MIN).then_some(max_before),
+                }
+            })
+            .collect();
+        if points.is_empty() || points.windows(2).any(|w| w[0].line >= w[1].line) {
+            return None;
+        }
+        Some(Self {
+            first_timestamp,
+            points,
+        })
+    }
+}
+
+/// 行内时间戳（Unix 秒，按 UTC 解释），与搜索时间过滤使用同一解析规则
+pub fn line_timestamp(line: &str) -> Option<i64> {
+    let raw = TimestampParser::parse_timestamp(line)?;
+    TimestampParser::parse_naive_datetime(&raw).map(|dt| dt.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每行 `2024-01-01 HH:MM:SS INFO ...`，按秒递增
+    fn sample_log(lines: usize) -> String {
+        (0..lines)
+            .map(|i| {
+                format!(
+                    "2024-01-01 {:02}:{:02}:{:02} INFO request {i} handled with some padding text\n",
+                    i / 3600,
+                    i / 60 % 60,
+                    i % 60
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_small_or_untimed_content_has_no_index() {
+        assert!(TimeIndex::from_content(sample_log(10).as_bytes()).is_none());
+        let untimed = "no timestamp here\n".repeat(STRIDE_BYTES / 10);
+        assert!(TimeIndex::from_content(untimed.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_seek_lands_before_first_matching_line() {
+        let content = sample_log(20_000);
+        let index = TimeIndex::from_content(content.as_bytes()).unwrap();
+        assert!(index.points().len() > 2);
+        assert_eq!(
+            index.first_timestamp(),
+            line_timestamp("2024-01-01 00:00:00").unwrap()
+        );
+
+        let target = line_timestamp("2024-01-01 03:00:00").unwrap();
+        let point = index.seek(target);
+        assert!(point.line > 1);
+        assert!(point.line <= 3 * 3600 + 1);
+        // 偏移指向检查点所在行的行首
+        let line = content[point.offset as usize..].lines().next().unwrap();
+        assert_eq!(line, content.lines().nth(point.line - 1).unwrap());
+
+        assert_eq!(index.seek(i64::MIN), TimeIndexPoint::START);
+    }
+
+    #[test]
+    fn test_seek_never_skips_out_of_order_lines() {
+        let mut content = sample_log(10_000);
+        // 文件开头混入一条很晚的时间戳：之后的检查点都不能跳过它
+        content.insert_str(0, "2024-01-02 00:00:00 ERROR late line\n");
+        let index = TimeIndex::from_content(content.as_bytes()).unwrap();
+        let target = line_timestamp("2024-01-01 02:00:00").unwrap();
+        assert_eq!(index.seek(target).line, 1);
+    }
+
+    #[test]
+    fn test_custom_timestamps_shift_checkpoints() {
+        let content = sample_log(5_000);
+        let plain = TimeIndex::from_content(content.as_bytes()).unwrap();
+        let shifted =
+            TimeIndex::from_text_with(&content, |line| line_timestamp(line).map(|ts| ts - 3600))
+                .unwrap();
+        assert_eq!(shifted.first_timestamp(), plain.first_timestamp() - 3600);
+        for (a, b) in shifted.points().iter().zip(plain.points()) {
+            assert_eq!((a.line, a.offset), (b.line, b.offset));
+            assert_eq!(a.max_before, b.max_before.map(|ts| ts - 3600));
+        }
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let index = TimeIndex::from_content(sample_log(5_000).as_bytes()).unwrap();
+        assert_eq!(TimeIndex::from_bytes(&index.to_bytes()), Some(index));
+        assert!(TimeIndex::from_bytes(b"garbage").is_none());
+    }
+}
@@ -12,17 +12,26 @@ use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tantivy::{
-    collector::{Collector, Count, TopDocs},
-    query::{BooleanQuery, Occur, Query, QueryParser, TermQuery},
+    collector::{Collector, Count, SegmentCollector, TopDocs},
+    query::{BooleanQuery, Occur, Query, QueryParser, TermQuery, Weight},
     schema::Field,
-    DocId, Index, IndexReader, Score, SegmentOrdinal, SegmentReader, Term,
+    DocId, DocSet, Index, IndexReader, Score, SegmentOrdinal, SegmentReader, Term, TERMINATED,
 };
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 use crate::{SearchError, SearchResult};
 
-/// A custom collector wrapper that supports cancellation
+/// 每收集多少个文档检查一次取消状态
+///
+/// `is_cancelled` 只是一次原子读，256 个文档的间隔在大段上也能让取消在 ~100ms 内生效。
+pub const CANCEL_CHECK_INTERVAL: u32 = 256;
+
+/// A custom collector wrapper that supports cooperative cancellation
+///
+/// 取消后不再返回错误，而是停止收集：已经收集到的文档仍通过 `harvest` /
+/// `merge_fruits` 返回，调用方可据 `token.is_cancelled()` 判断结果是否不完整。
+/// 取消后也不再遍历倒排表：当前段在下一个检查点停止，其余段不再进入。
 pub struct CancellableCollector<C> {
     inner: C,
     token: CancellationToken,
@@ -43,15 +52,12 @@ impl<C: Collector> Collector for CancellableCollector<C> {
         segment_id: SegmentOrdinal,
         reader: &SegmentReader,
     ) -> tantivy::Result<Self::Child> {
-        if self.token.is_cancelled() {
-            return Err(tantivy::TantivyError::InternalError(
-                "Search cancelled".to_string(),
-            ));
-        }
         let child = self.inner.for_segment(segment_id, reader)?;
         Ok(CancellableChildCollector {
             inner: child,
             token: self.token.clone(),
+            since_check: 0,
+            stopped: self.token.is_cancelled(),
         })
     }
 
@@ -59,9 +65,43 @@ impl<C: Collector> Collector for CancellableCollector<C> {
         self.inner.requires_scoring()
     }
 
+    /// 逐个推进打分器而不是整段 `for_each`，取消后立即停止遍历，
+    /// 取消延迟与段大小、剩余段数无关
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> tantivy::Result<<Self::Child as SegmentCollector>::Fruit> {
+        let mut child = self.for_segment(segment_ord, reader)?;
+        if child.stopped {
+            return Ok(child.harvest());
+        }
+
+        let requires_scoring = self.requires_scoring();
+        let alive_bitset = reader.alive_bitset();
+        let mut scorer = weight.scorer(reader, 1.0)?;
+        let mut doc = scorer.doc();
+        while doc != TERMINATED {
+            if alive_bitset.is_none_or(|alive| alive.is_alive(doc)) {
+                let score = if requires_scoring {
+                    scorer.score()
+                } else {
+                    0.0
+                };
+                child.collect(doc, score);
+                if child.stopped {
+                    break;
+                }
+            }
+            doc = scorer.advance();
+        }
+        Ok(child.harvest())
+    }
+
     fn merge_fruits(
         &self,
-        fruits: Vec<<Self::Child as tantivy::collector::SegmentCollector>::Fruit>,
+        fruits: Vec<<Self::Child as SegmentCollector>::Fruit>,
     ) -> tantivy::Result<Self::Fruit> {
         self.inner.merge_fruits(fruits)
     }
@@ -70,31 +110,33 @@ impl<C: Collector> Collector for CancellableCollector<C> {
 pub struct CancellableChildCollector<C> {
     inner: C,
     token: CancellationToken,
+    /// 距上次检查取消状态已收集的文档数
+    since_check: u32,
+    /// 已取消：丢弃后续文档
+    stopped: bool,
 }
 
-impl<C: tantivy::collector::SegmentCollector> tantivy::collector::SegmentCollector
-    for CancellableChildCollector<C>
-{
+impl<C: SegmentCollector> SegmentCollector for CancellableChildCollector<C> {
     type Fruit = C::Fruit;
 
     /// 文档收集时的取消检查
-    /// 使用更细粒度的检查策略：
-    /// - 每 256 个文档检查一次取消状态（平衡开销和响应性）
-    /// - 对于高分文档（score > 0.9）立即检查，确保能快速响应
+    ///
+    /// 按实际收集的文档数（而非 DocId）计数，每 `CANCEL_CHECK_INTERVAL` 个检查一次；
+    /// 稀疏命中时 DocId 很少落在固定倍数上，按 DocId 取模会导致长时间不检查。
     fn collect(&mut self, doc: DocId, score: Score) {
-        // 细粒度取消检查：每 256 个文档检查一次，比原来的 1024 更频繁
-        const CANCEL_CHECK_INTERVAL: u32 = 256;
-        // 高分文档阈值：对于高相关性文档立即检查取消状态
-        const HIGH_SCORE_THRESHOLD: f32 = 0.9;
-
-        let should_check_cancel =
-            doc.is_multiple_of(CANCEL_CHECK_INTERVAL) || score > HIGH_SCORE_THRESHOLD;
-
-        if should_check_cancel && self.token.is_cancelled() {
-            // 取消时停止收集当前段，但保留已收集的结果
-            // 通过 inner.harvest() 返回的部分结果可以被上层使用
+        if self.stopped {
             return;
         }
+
+        self.since_check += 1;
+        if self.since_check >= CANCEL_CHECK_INTERVAL {
+            self.since_check = 0;
+            if self.token.is_cancelled() {
+                // 停止收集当前段，已收集的结果通过 harvest() 保留
+                self.stopped = true;
+                return;
+            }
+        }
         self.inner.collect(doc, score);
     }
 
@@ -398,18 +440,6 @@ impl BooleanQueryProcessor {
             return Err(SearchError::QueryError("Search cancelled".to_string()));
         }
 
-        // Get total count first
-        let count_collector = CancellableCollector::new(Count, token.clone());
-        let total_count = match searcher.search(&query, &count_collector) {
-            Ok(count) => count,
-            Err(e) => {
-                if token.is_cancelled() {
-                    return Err(SearchError::QueryError("Search cancelled".to_string()));
-                }
-                return Err(SearchError::IndexError(e.to_string()));
-            }
-        };
-
         // Adjust limit based on early termination strategy
         // NOTE: Remove artificial limit to prevent result truncation
         // The early termination should be based on query cost estimation,
@@ -422,19 +452,19 @@ impl BooleanQueryProcessor {
             limit
         };
 
-        // Execute query with top docs collector
-        let top_docs_collector = TopDocs::with_limit(effective_limit);
-        let cancellable_top_docs = CancellableCollector::new(top_docs_collector, token.clone());
+        // 单次遍历同时计数与收集 top docs；取消时两者都是已遍历部分的结果
+        let collector =
+            CancellableCollector::new((Count, TopDocs::with_limit(effective_limit)), token.clone());
+        let (total_count, top_docs) = searcher
+            .search(&query, &collector)
+            .map_err(|e| SearchError::IndexError(e.to_string()))?;
 
-        let top_docs = match searcher.search(&query, &cancellable_top_docs) {
-            Ok(docs) => docs,
-            Err(e) => {
-                if token.is_cancelled() {
-                    return Err(SearchError::QueryError("Search cancelled".to_string()));
-                }
-                return Err(SearchError::IndexError(e.to_string()));
-            }
-        };
+        if token.is_cancelled() {
+            debug!(
+                collected = top_docs.len(),
+                "Multi-keyword search cancelled, returning partial results"
+            );
+        }
 
         let doc_addresses: Vec<tantivy::DocAddress> = top_docs
             .into_iter()
@@ -632,6 +662,122 @@ mod tests {
         // Depending on where it stops, it could be QueryError or IndexError wrapped cancellation
     }
 
+    /// 依次产出段内全部文档、推进到 `cancel_at` 时取消令牌的打分器，记录总推进次数
+    struct CancellingWeight {
+        token: CancellationToken,
+        cancel_at: usize,
+        advanced: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    struct CancellingScorer {
+        doc: DocId,
+        max_doc: DocId,
+        token: CancellationToken,
+        cancel_at: usize,
+        advanced: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl DocSet for CancellingScorer {
+        fn advance(&mut self) -> DocId {
+            let advanced = self
+                .advanced
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                + 1;
+            if advanced == self.cancel_at {
+                self.token.cancel();
+            }
+            self.doc = if self.doc + 1 < self.max_doc {
+                self.doc + 1
+            } else {
+                TERMINATED
+            };
+            self.doc
+        }
+
+        fn doc(&self) -> DocId {
+            self.doc
+        }
+
+        fn size_hint(&self) -> u32 {
+            self.max_doc
+        }
+    }
+
+    impl tantivy::query::Scorer for CancellingScorer {
+        fn score(&mut self) -> Score {
+            1.0
+        }
+    }
+
+    impl Weight for CancellingWeight {
+        fn scorer(
+            &self,
+            reader: &SegmentReader,
+            _boost: Score,
+        ) -> tantivy::Result<Box<dyn tantivy::query::Scorer>> {
+            Ok(Box::new(CancellingScorer {
+                doc: 0,
+                max_doc: reader.max_doc(),
+                token: self.token.clone(),
+                cancel_at: self.cancel_at,
+                advanced: Arc::clone(&self.advanced),
+            }))
+        }
+
+        fn explain(
+            &self,
+            _reader: &SegmentReader,
+            _doc: DocId,
+        ) -> tantivy::Result<tantivy::query::Explanation> {
+            unreachable!("not used by collectors")
+        }
+    }
+
+    #[test]
+    fn test_cancellation_stops_segment_scan() {
+        const DOCS_PER_SEGMENT: usize = 20_000;
+        const CANCEL_AT: usize = 1_000;
+
+        let mut schema_builder = Schema::builder();
+        let content_field = schema_builder.add_text_field("content", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        writer.set_merge_policy(Box::new(tantivy::indexer::NoMergePolicy));
+        for _ in 0..3 {
+            for _ in 0..DOCS_PER_SEGMENT {
+                writer
+                    .add_document(tantivy::doc!(content_field => "error"))
+                    .unwrap();
+            }
+            writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+
+        let token = CancellationToken::new();
+        let weight = CancellingWeight {
+            token: token.clone(),
+            cancel_at: CANCEL_AT,
+            advanced: Arc::default(),
+        };
+        let collector = CancellableCollector::new(Count, token.clone());
+        let fruits = searcher
+            .segment_readers()
+            .iter()
+            .enumerate()
+            .map(|(ord, reader)| collector.collect_segment(&weight, ord as SegmentOrdinal, reader))
+            .collect::<tantivy::Result<Vec<_>>>()
+            .unwrap();
+        let count = collector.merge_fruits(fruits).unwrap();
+
+        // 取消后当前段在下一个检查点停止，其余段不再遍历；之前收集的文档保留
+        let advanced = weight.advanced.load(std::sync::atomic::Ordering::Relaxed);
+        assert!(token.is_cancelled());
+        assert!(advanced < CANCEL_AT + CANCEL_CHECK_INTERVAL as usize);
+        assert!(count >= CANCEL_AT);
+        assert!(count < CANCEL_AT + CANCEL_CHECK_INTERVAL as usize);
+    }
+
     #[test]
     fn test_query_plan_creation() {
         let (processor, _temp_dir) = create_test_processor();
@@ -357,6 +357,45 @@ impl DiskResultStore {
         self.sessions.len()
     }
 
+    /// 进行中会话的写缓冲区占用（字节）；已完成的会话只剩磁盘文件
+    pub fn buffered_bytes(&self) -> u64 {
+        self.sessions
+            .iter()
+            .filter_map(|session| {
+                session.writer.lock().as_ref().map(|writer| {
+                    (writer.data_writer.capacity() + writer.index_writer.capacity()) as u64
+                })
+            })
+            .sum()
+    }
+
+    /// 驱逐已完成的会话，仅保留最新的 `keep` 个（进行中的会话不受影响）
+    ///
+    /// 供资源紧张时释放磁盘空间使用，返回被驱逐的会话数。
+    pub fn evict_completed_sessions(&self, keep: usize) -> usize {
+        let mut completed: Vec<(String, std::time::Instant)> = self
+            .sessions
+            .iter()
+            .filter(|entry| entry.is_complete.load(Ordering::Acquire))
+            .map(|entry| (entry.key().clone(), entry.created_at))
+            .collect();
+
+        if completed.len() <= keep {
+            return 0;
+        }
+
+        // 新的在前，跳过需要保留的部分
+        completed.sort_by(|a, b| b.1.cmp(&a.1));
+        let mut evicted = 0;
+        for (id, _) in completed.into_iter().skip(keep) {
+            if self.remove_session(&id) {
+                evicted += 1;
+            }
+        }
+        tracing::debug!(evicted, keep, "已驱逐已完成的搜索会话");
+        evicted
+    }
+
     // ─── 内部方法 ────────────────────────────────────────────────────────────
 
     fn evict_oldest_session(&self) {
@@ -420,6 +459,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         }
     }
 
@@ -474,6 +519,39 @@ mod tests {
         assert!(!store.has_session("session-x"));
     }
 
+    #[test]
+    fn test_evict_completed_sessions_keeps_newest_and_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskResultStore::new(dir.path().to_path_buf(), 10).unwrap();
+
+        for id in ["old", "mid", "new"] {
+            store.create_session(id).unwrap();
+            store.complete_session(id).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        store.create_session("running").unwrap();
+
+        assert_eq!(store.evict_completed_sessions(1), 2);
+        assert!(store.has_session("new"));
+        assert!(store.has_session("running"));
+        assert!(!store.has_session("old"));
+        assert!(!store.has_session("mid"));
+        assert!(!dir.path().join("old.ndjson").exists());
+    }
+
+    #[test]
+    fn test_buffered_bytes_only_counts_running_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskResultStore::new(dir.path().to_path_buf(), 10).unwrap();
+
+        store.create_session("done").unwrap();
+        store.complete_session("done").unwrap();
+        assert_eq!(store.buffered_bytes(), 0);
+
+        store.create_session("running").unwrap();
+        assert_eq!(store.buffered_bytes(), 320 * 1024);
+    }
+
     #[test]
     fn test_read_during_write() {
         // 验证搜索进行中（is_complete=false）读取的正确性
@@ -106,6 +106,12 @@ pub struct HighlightingEngine {
 const DEFAULT_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(1000).unwrap();
 const DEFAULT_QUERY_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(100).unwrap();
 
+/// 片段缓存单条的固定开销（键值结构体 + LRU 节点）
+const SNIPPET_ENTRY_OVERHEAD: usize =
+    std::mem::size_of::<SnippetCacheKey>() + std::mem::size_of::<CachedSnippet>() + 48;
+/// 已解析查询的估算大小（trait 对象无法精确计量）
+const QUERY_CACHE_ENTRY_ESTIMATE: usize = 1024;
+
 impl HighlightingEngine {
     /// Create a new highlighting engine
     pub fn new(
@@ -486,6 +492,28 @@ impl HighlightingEngine {
         (cache.len(), cache.cap().get())
     }
 
+    /// Estimated heap held by the snippet and parsed-query caches: (entries, bytes)
+    ///
+    /// Parsed queries are opaque trait objects, so each one is counted at a
+    /// fixed per-entry estimate.
+    pub fn cache_memory(&self) -> (usize, u64) {
+        let snippets = self.snippet_cache.read();
+        let snippet_bytes: usize = snippets
+            .iter()
+            .map(|(key, value)| {
+                SNIPPET_ENTRY_OVERHEAD
+                    + key.doc_id.capacity()
+                    + key.field_name.capacity()
+                    + value.content.capacity()
+            })
+            .sum();
+        let queries = self.query_cache.read().len();
+        (
+            snippets.len() + queries,
+            (snippet_bytes + queries * QUERY_CACHE_ENTRY_ESTIMATE) as u64,
+        )
+    }
+
     /// Optimize highlighting for large documents
     pub fn highlight_large_document(
         &self,
@@ -10,8 +10,11 @@ pub mod schema;
 pub use boolean_query_processor::BooleanQueryProcessor;
 pub use disk_result_store::{DiskResultStore, SearchPageResult};
 pub use highlighting_engine::{HighlightingConfig, HighlightingEngine, HighlightingStats};
-pub use manager::{parse_log_timestamp_to_unix, SearchEngineManager};
-pub use schema::LogSchema;
+pub use manager::{
+    parse_log_timestamp_to_unix, IndexMemoryUsage, IndexSnapshot, MatchDirection, OccurrenceEdge,
+    SearchEngineManager,
+};
+pub use schema::{LogSchema, INDEX_SCHEMA_VERSION};
 
 use thiserror::Error;
 
@@ -66,6 +69,32 @@ impl SearchError {
         }
     }
 
+    /// 索引本身不可用（格式版本不受支持、schema 不符或数据损坏）：丢弃重建可以恢复
+    ///
+    /// 锁冲突、权限与 I/O 错误不在此列，丢弃索引无济于事。
+    pub fn is_incompatible_index(&self) -> bool {
+        use tantivy::directory::error::OpenReadError;
+        use tantivy::TantivyError;
+        matches!(
+            self,
+            SearchError::TantivyError(
+                TantivyError::IncompatibleIndex(_)
+                    | TantivyError::OpenReadError(OpenReadError::IncompatibleIndex(_))
+                    | TantivyError::SchemaError(_)
+                    | TantivyError::FieldNotFound(_)
+                    | TantivyError::DataCorruption(_)
+            )
+        )
+    }
+
+    /// 索引目录被其他进程（或同进程的另一写入者）锁定
+    pub fn is_lock_busy(&self) -> bool {
+        matches!(
+            self,
+            SearchError::TantivyError(tantivy::TantivyError::LockFailure(..))
+        )
+    }
+
     /// 判断是否为致命错误（不应继续执行）
     pub fn is_fatal(&self) -> bool {
         matches!(
@@ -7,7 +7,7 @@
 //! - Query parsing and execution
 
 use parking_lot::{Mutex, RwLock};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Bound;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -30,6 +30,22 @@ use crate::{
 };
 use la_core::models::config::SearchConfig as AppSearchConfig;
 use la_core::models::LogEntry;
+use la_core::utils::LevelOrder;
+
+/// Which end of the time axis [`SearchEngineManager::find_occurrence`] looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccurrenceEdge {
+    First,
+    Last,
+}
+
+/// Which way [`SearchEngineManager::find_adjacent_match`] moves from a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchDirection {
+    Next,
+    Previous,
+}
 
 const INDEX_TIMESTAMP_FORMATS: &[&str] = &[
     "%Y-%m-%dT%H:%M:%S%.f",
@@ -107,6 +123,8 @@ pub struct SearchResults {
     pub total_count: usize,
     pub query_time_ms: u64,
     pub was_timeout: bool,
+    /// 搜索被取消：entries / total_count 只覆盖取消前已遍历的部分
+    pub was_cancelled: bool,
 }
 
 impl SearchResults {
@@ -118,6 +136,7 @@ impl SearchResults {
             total_count: 0,
             query_time_ms: 0,
             was_timeout: false,
+            was_cancelled: false,
         }
     }
 
@@ -129,6 +148,55 @@ impl SearchResults {
     }
 }
 
+/// A point-in-time view of the index.
+///
+/// Holding the underlying `Searcher` keeps its segments alive, so readers of
+/// the snapshot keep seeing the same documents even after later commits and
+/// merges until the snapshot is dropped.
+#[derive(Clone)]
+pub struct IndexSnapshot {
+    searcher: tantivy::Searcher,
+}
+
+impl IndexSnapshot {
+    /// Searcher generation the snapshot is pinned to
+    pub fn generation(&self) -> u64 {
+        self.searcher.generation().generation_id()
+    }
+
+    /// Number of documents visible in the snapshot
+    pub fn num_docs(&self) -> u64 {
+        self.searcher.num_docs()
+    }
+
+    pub fn searcher(&self) -> &tantivy::Searcher {
+        &self.searcher
+    }
+}
+
+impl std::fmt::Debug for IndexSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexSnapshot")
+            .field("generation", &self.generation())
+            .field("num_docs", &self.num_docs())
+            .finish()
+    }
+}
+
+/// Estimated memory held by one search engine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexMemoryUsage {
+    /// Size of the segments opened by the reader. They are memory-mapped, so
+    /// this is an upper bound on what can be paged in rather than heap.
+    pub reader_bytes: u64,
+    pub segments: usize,
+    pub num_docs: u64,
+    /// Memory budget of the index writer (allocated up front by Tantivy)
+    pub writer_heap_bytes: u64,
+    pub highlight_cache_entries: usize,
+    pub highlight_cache_bytes: u64,
+}
+
 /// Search results with highlighting metadata
 #[derive(Debug, Clone)]
 pub struct SearchResultsWithHighlighting {
@@ -137,6 +205,7 @@ pub struct SearchResultsWithHighlighting {
     pub query_time_ms: u64,
     pub highlight_time_ms: u64,
     pub was_timeout: bool,
+    pub was_cancelled: bool,
 }
 
 /// Search configuration
@@ -405,12 +474,14 @@ impl SearchEngineManager {
     /// - `content_query_str` — 用户输入的原始查询字符串（如 "error | timeout"）
     /// - `time_range` — 可选的时间范围过滤 (start_timestamp, end_timestamp)，Unix 秒
     /// - `levels` — 可选的日志级别集合，如 {"ERROR", "WARN"}
+    /// - `min_level` — 可选的最低级别 (级别顺序, 最低级别的位置)，即 `level >= X`
     /// - `file_pattern` — 可选的文件路径 GLOB 模式，如 "logs/app*.log"
     pub fn build_tantivy_query(
         &self,
         content_query_str: &str,
         time_range: Option<(i64, i64)>,
         levels: Option<&HashSet<String>>,
+        min_level: Option<(&LevelOrder, usize)>,
         file_pattern: Option<&str>,
     ) -> SearchResult<Box<dyn Query>> {
         use tantivy::schema::IndexRecordOption;
@@ -449,6 +520,22 @@ impl SearchEngineManager {
             }
         }
 
+        // 3b. 最低级别过滤 → 按级别顺序换算为索引中的级别取值，多个 TermQuery 用 OR 组合
+        if let Some((order, min_rank)) = min_level {
+            let level_clauses: Vec<(Occur, Box<dyn Query>)> = order
+                .indexed_levels_at_least(min_rank)
+                .into_iter()
+                .map(|lvl| {
+                    let term = Term::from_field_text(self.schema.level, lvl);
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(level_clauses))));
+        }
+
         // 4. 文件路径过滤 → RegexQuery on schema.file_path
         if let Some(pattern) = file_pattern {
             if !pattern.is_empty() {
@@ -517,41 +604,27 @@ impl SearchEngineManager {
         let handle = tokio::task::spawn_blocking(move || -> SearchResult<SearchResults> {
             let searcher = reader.searcher();
 
-            // Get total count with cancellation support
-            let count_collector = crate::boolean_query_processor::CancellableCollector::new(
-                Count,
-                token_clone.clone(),
-            );
-            let total_count = match searcher.search(&*query, &count_collector) {
-                Ok(count) => count,
-                Err(e) => {
-                    if token_clone.is_cancelled() {
-                        return Err(SearchError::QueryError("Search cancelled".to_string()));
-                    }
-                    return Err(SearchError::IndexError(e.to_string()));
-                }
-            };
-
             if token_clone.is_cancelled() {
                 return Err(SearchError::QueryError("Search cancelled".to_string()));
             }
 
-            // Get top documents with cancellation support
-            let top_docs_collector = TopDocs::with_limit(limit);
-            let cancellable_top_docs = crate::boolean_query_processor::CancellableCollector::new(
-                top_docs_collector,
+            // 单次遍历同时计数与收集 top docs；收集器内协作式检查取消，
+            // 取消后保留已收集的部分结果
+            let collector = crate::boolean_query_processor::CancellableCollector::new(
+                (Count, TopDocs::with_limit(limit)),
                 token_clone.clone(),
             );
-
-            let top_docs = match searcher.search(&*query, &cancellable_top_docs) {
-                Ok(docs) => docs,
-                Err(e) => {
-                    if token_clone.is_cancelled() {
-                        return Err(SearchError::QueryError("Search cancelled".to_string()));
-                    }
-                    return Err(SearchError::IndexError(e.to_string()));
-                }
-            };
+            let (total_count, top_docs) = searcher
+                .search(&*query, &collector)
+                .map_err(|e| SearchError::IndexError(e.to_string()))?;
+            let was_cancelled = token_clone.is_cancelled();
+
+            if was_cancelled {
+                debug!(
+                    collected = top_docs.len(),
+                    "Search cancelled, returning partial results"
+                );
+            }
 
             // Convert documents to LogEntry, capturing DocAddress for each
             let mut entries = Vec::with_capacity(top_docs.len());
@@ -575,6 +648,7 @@ impl SearchEngineManager {
                 total_count,
                 query_time_ms: 0, // Will be set by caller
                 was_timeout: false,
+                was_cancelled,
             })
         });
 
@@ -591,6 +665,18 @@ impl SearchEngineManager {
 }
 
 /// 独立函数版的 document_to_log_entry，可在 spawn_blocking 闭包中使用（无 &self）。
+/// Drop the sort keys and number entries by their position in the result
+fn number_entries(entries: Vec<(i64, LogEntry)>) -> Vec<LogEntry> {
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(id, (_, mut entry))| {
+            entry.id = id;
+            entry
+        })
+        .collect()
+}
+
 fn document_to_log_entry_inner(schema: &LogSchema, doc: &TantivyDocument) -> Option<LogEntry> {
     document_to_log_entry_impl(schema, doc)
 }
@@ -693,6 +779,12 @@ fn document_to_log_entry_impl(schema: &LogSchema, doc: &TantivyDocument) -> Opti
         tags: vec![],
         match_details: None,
         matched_keywords: None,
+        time_offset_secs: None,
+        links: None,
+        translation: None,
+        source_files: None,
+        original_length: None,
+        fields: None,
     })
 }
 
@@ -831,10 +923,11 @@ impl SearchEngineManager {
                     &keywords_owned,
                     require_all,
                     limit,
-                    token_inner,
+                    token_inner.clone(),
                 )?;
 
                 let searcher = reader.searcher();
+                let was_cancelled = token_inner.as_ref().is_some_and(|t| t.is_cancelled());
                 let mut entries = Vec::with_capacity(doc_addresses.len());
                 let mut addresses = Vec::with_capacity(doc_addresses.len());
 
@@ -852,6 +945,7 @@ impl SearchEngineManager {
                     total_count,
                     query_time_ms: 0,
                     was_timeout: false,
+                    was_cancelled,
                 })
             }),
         )
@@ -975,6 +1069,7 @@ impl SearchEngineManager {
             query_time_ms: search_results.query_time_ms,
             highlight_time_ms: total_time.as_millis() as u64,
             was_timeout: search_results.was_timeout,
+            was_cancelled: search_results.was_cancelled,
         })
     }
 
@@ -997,6 +1092,35 @@ impl SearchEngineManager {
         }
     }
 
+    /// Pin the currently visible index generation
+    pub fn snapshot(&self) -> IndexSnapshot {
+        IndexSnapshot {
+            searcher: self.reader.searcher(),
+        }
+    }
+
+    /// Estimate the memory held by the reader, writer and highlighting caches
+    pub fn memory_usage(&self) -> IndexMemoryUsage {
+        let searcher = self.reader.searcher();
+        let reader_bytes = searcher
+            .space_usage()
+            .map(|usage| usage.total().get_bytes())
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "Failed to compute index space usage");
+                0
+            });
+        let (highlight_cache_entries, highlight_cache_bytes) =
+            self.highlighting_engine.cache_memory();
+        IndexMemoryUsage {
+            reader_bytes,
+            segments: searcher.segment_readers().len(),
+            num_docs: searcher.num_docs(),
+            writer_heap_bytes: self.config.writer_heap_size as u64,
+            highlight_cache_entries,
+            highlight_cache_bytes,
+        }
+    }
+
     /// Get search statistics
     pub fn get_stats(&self) -> SearchStats {
         self.stats.read().clone()
@@ -1075,6 +1199,547 @@ impl SearchEngineManager {
         Ok((min_timestamp, max_timestamp, total_count))
     }
 
+    /// Get the earliest real timestamp (unix seconds) in the index
+    ///
+    /// Unlike the minimum from [`Self::get_time_range`], lines without a
+    /// parsable timestamp (indexed at 0) are ignored. `None` when no entry
+    /// has a timestamp.
+    pub fn get_earliest_timestamp(&self) -> SearchResult<Option<i64>> {
+        use tantivy::Order;
+
+        let query = RangeQuery::new(
+            Bound::Included(Term::from_field_i64(self.schema.timestamp, 1)),
+            Bound::Included(Term::from_field_i64(self.schema.timestamp, i64::MAX)),
+        );
+        let searcher = self.reader.searcher();
+        let collector = TopDocs::with_limit(1).order_by_fast_field::<i64>("timestamp", Order::Asc);
+        let top_docs = searcher.search(&query, &collector)?;
+        Ok(top_docs.first().map(|(timestamp, _)| *timestamp))
+    }
+
+    /// Get the newest indexed entries, optionally restricted to levels
+    ///
+    /// Ordered by the timestamp fast field (newest first), so no content query
+    /// is needed. `levels` are matched exactly against the indexed level
+    /// (lowercase, e.g. `error`); an empty slice matches every level.
+    pub fn get_recent_entries(
+        &self,
+        levels: &[String],
+        limit: usize,
+    ) -> SearchResult<Vec<LogEntry>> {
+        use tantivy::query::AllQuery;
+        use tantivy::schema::IndexRecordOption;
+        use tantivy::Order;
+
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let query: Box<dyn Query> = if levels.is_empty() {
+            Box::new(AllQuery)
+        } else {
+            let level_clauses: Vec<(Occur, Box<dyn Query>)> = levels
+                .iter()
+                .map(|lvl| {
+                    let term = Term::from_field_text(self.schema.level, lvl);
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            Box::new(BooleanQuery::new(level_clauses))
+        };
+
+        let searcher = self.reader.searcher();
+        let collector =
+            TopDocs::with_limit(limit).order_by_fast_field::<i64>("timestamp", Order::Desc);
+        let top_docs = searcher.search(&*query, &collector)?;
+
+        let mut entries = Vec::with_capacity(top_docs.len());
+        for (_timestamp, doc_address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            if let Some(mut entry) = document_to_log_entry_inner(&self.schema, &doc) {
+                entry.id = entries.len();
+                entries.push(entry);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Get entries whose timestamp falls in `[start, end]` (unix seconds)
+    ///
+    /// Ordered globally by time, ties broken by file and line, so entries from
+    /// several files interleave as they happened. An empty `file_paths` slice
+    /// covers every file.
+    ///
+    /// `time_offsets` holds per-file clock corrections keyed by file path: an
+    /// entry is placed at `timestamp + offset`, both for the window bounds and
+    /// for ordering, and reports the applied offset in `time_offset_secs`.
+    pub fn get_entries_in_time_window(
+        &self,
+        file_paths: &[String],
+        time_offsets: &HashMap<String, i64>,
+        start: i64,
+        end: i64,
+        limit: usize,
+    ) -> SearchResult<Vec<LogEntry>> {
+        let entries = self.time_window(
+            file_paths,
+            time_offsets,
+            start,
+            end,
+            limit,
+            tantivy::Order::Asc,
+        )?;
+        Ok(number_entries(entries))
+    }
+
+    /// Get entries within `window` seconds of `center`, split evenly around it
+    ///
+    /// Up to `limit / 2` entries are taken from just before `center` (the
+    /// newest ones) and the rest from `center` onwards, so a dense stretch on
+    /// one side cannot push the other side out of the result; a side with
+    /// fewer entries leaves its share to the other. Ordering and
+    /// `time_offsets` behave as in [`Self::get_entries_in_time_window`].
+    pub fn get_entries_around_time(
+        &self,
+        file_paths: &[String],
+        time_offsets: &HashMap<String, i64>,
+        center: i64,
+        window: i64,
+        limit: usize,
+    ) -> SearchResult<Vec<LogEntry>> {
+        use tantivy::Order;
+
+        if limit == 0 || window < 0 {
+            return Ok(Vec::new());
+        }
+        let (start, end) = (center.saturating_sub(window), center.saturating_add(window));
+        let before_of = |limit| {
+            self.time_window(
+                file_paths,
+                time_offsets,
+                start,
+                center.saturating_sub(1),
+                limit,
+                Order::Desc,
+            )
+        };
+
+        let mut before = before_of(limit / 2)?;
+        let after = self.time_window(
+            file_paths,
+            time_offsets,
+            center,
+            end,
+            limit - before.len(),
+            Order::Asc,
+        )?;
+        if before.len() == limit / 2 && before.len() + after.len() < limit {
+            before = before_of(limit - after.len())?;
+        }
+
+        before.reverse();
+        before.extend(after);
+        Ok(number_entries(before))
+    }
+
+    /// Entries in `[start, end]` across the offset groups, sorted by corrected
+    /// time (then file and line) in `order` and truncated to `limit`
+    fn time_window(
+        &self,
+        file_paths: &[String],
+        time_offsets: &HashMap<String, i64>,
+        start: i64,
+        end: i64,
+        limit: usize,
+        order: tantivy::Order,
+    ) -> SearchResult<Vec<(i64, LogEntry)>> {
+        if limit == 0 || start > end {
+            return Ok(Vec::new());
+        }
+
+        // Files sharing an offset share one range query over their local clock.
+        let mut groups: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+        let corrected: Vec<String> = time_offsets
+            .iter()
+            .filter(|(path, offset)| {
+                **offset != 0 && (file_paths.is_empty() || file_paths.contains(path))
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &corrected {
+            groups
+                .entry(time_offsets[path])
+                .or_default()
+                .push(path.clone());
+        }
+        let uncorrected: Vec<String> = file_paths
+            .iter()
+            .filter(|path| !corrected.contains(path))
+            .cloned()
+            .collect();
+
+        let mut entries = Vec::new();
+        if file_paths.is_empty() || !uncorrected.is_empty() {
+            // Whole workspace: everything except the corrected files
+            let excluded = if file_paths.is_empty() {
+                corrected.as_slice()
+            } else {
+                &[]
+            };
+            entries.extend(self.time_window_group(
+                &uncorrected,
+                excluded,
+                0,
+                start,
+                end,
+                limit,
+                order.clone(),
+            )?);
+        }
+        for (offset, paths) in &groups {
+            entries.extend(self.time_window_group(
+                paths,
+                &[],
+                *offset,
+                start,
+                end,
+                limit,
+                order.clone(),
+            )?);
+        }
+
+        entries.sort_by(|(ts_a, a), (ts_b, b)| {
+            let ordering = ts_a
+                .cmp(ts_b)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line));
+            match order {
+                tantivy::Order::Asc => ordering,
+                tantivy::Order::Desc => ordering.reverse(),
+            }
+        });
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// Get the sorted timestamps (unix seconds) of one file within `[start, end]`
+    ///
+    /// Reads only the timestamp fast field, so it stays cheap for large files.
+    /// Lines without a parsable timestamp are indexed at 0 and never returned.
+    pub fn get_file_timestamps(
+        &self,
+        file_path: &str,
+        start: i64,
+        end: i64,
+    ) -> SearchResult<Vec<i64>> {
+        use tantivy::collector::DocSetCollector;
+        use tantivy::schema::IndexRecordOption;
+
+        let start = start.max(1);
+        if start > end {
+            return Ok(Vec::new());
+        }
+
+        let start_term = Term::from_field_i64(self.schema.timestamp, start);
+        let end_term = Term::from_field_i64(self.schema.timestamp, end);
+        let file_term = Term::from_field_text(self.schema.file_path, file_path);
+        let query = BooleanQuery::new(vec![
+            (
+                Occur::Must,
+                Box::new(RangeQuery::new(
+                    Bound::Included(start_term),
+                    Bound::Included(end_term),
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(file_term, IndexRecordOption::Basic)),
+            ),
+        ]);
+
+        let searcher = self.reader.searcher();
+        let docs = searcher.search(&query, &DocSetCollector)?;
+
+        let columns = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment| segment.fast_fields().i64("timestamp"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut timestamps: Vec<i64> = docs
+            .into_iter()
+            .filter_map(|address| columns[address.segment_ord as usize].first(address.doc_id))
+            .collect();
+        timestamps.sort_unstable();
+        Ok(timestamps)
+    }
+
+    /// Find the earliest or latest entry matching `query_str`
+    ///
+    /// Binary-searches the timestamp axis instead of sorting all matches: each
+    /// probe only counts matches within half of the remaining range, so the
+    /// matching second is found in ~log2(span) probes without loading any
+    /// documents. Ties within that second are broken by file and line.
+    ///
+    /// Files with a clock offset in `time_offsets` are searched on their own
+    /// local clock and compared after correction, like
+    /// [`Self::get_entries_in_time_window`].
+    pub fn find_occurrence(
+        &self,
+        query_str: &str,
+        time_offsets: &HashMap<String, i64>,
+        edge: OccurrenceEdge,
+    ) -> SearchResult<Option<LogEntry>> {
+        let content = self.parse_query(query_str)?;
+        let (min_ts, max_ts, total) = self.get_time_range()?;
+        if total == 0 {
+            return Ok(None);
+        }
+
+        let mut groups: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+        for (path, offset) in time_offsets.iter().filter(|(_, offset)| **offset != 0) {
+            groups.entry(*offset).or_default().push(path.clone());
+        }
+        let corrected: Vec<String> = groups.values().flatten().cloned().collect();
+
+        let mut found: Vec<(i64, LogEntry)> = Vec::new();
+        found.extend(self.occurrence_in_group(
+            &content,
+            &[],
+            &corrected,
+            0,
+            min_ts,
+            max_ts,
+            edge,
+        )?);
+        for (offset, paths) in &groups {
+            found.extend(self.occurrence_in_group(
+                &content,
+                paths,
+                &[],
+                *offset,
+                min_ts,
+                max_ts,
+                edge,
+            )?);
+        }
+
+        let key = |(ts, entry): &(i64, LogEntry)| (*ts, entry.file.clone(), entry.line);
+        let best = match edge {
+            OccurrenceEdge::First => found.into_iter().min_by_key(key),
+            OccurrenceEdge::Last => found.into_iter().max_by_key(key),
+        };
+        Ok(best.map(|(_, entry)| entry))
+    }
+
+    /// Find the nearest line after (or before) `line` in one file that matches `query_str`
+    ///
+    /// Intersects the query with the file's `file_path` postings and a range on
+    /// the `line_number` fast field, then takes the single closest hit ordered
+    /// by line number, so stepping through matches never loads the rest of the
+    /// file's results.
+    pub fn find_adjacent_match(
+        &self,
+        query_str: &str,
+        file_path: &str,
+        line: usize,
+        direction: MatchDirection,
+    ) -> SearchResult<Option<LogEntry>> {
+        use tantivy::schema::IndexRecordOption;
+        use tantivy::Order;
+
+        let line = line as u64;
+        let (range, order) = match direction {
+            MatchDirection::Next => (
+                RangeQuery::new(
+                    Bound::Excluded(Term::from_field_u64(self.schema.line_number, line)),
+                    Bound::Unbounded,
+                ),
+                Order::Asc,
+            ),
+            MatchDirection::Previous if line <= 1 => return Ok(None),
+            MatchDirection::Previous => (
+                RangeQuery::new(
+                    Bound::Unbounded,
+                    Bound::Excluded(Term::from_field_u64(self.schema.line_number, line)),
+                ),
+                Order::Desc,
+            ),
+        };
+        let file_term = Term::from_field_text(self.schema.file_path, file_path);
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, self.parse_query(query_str)?),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(file_term, IndexRecordOption::Basic)),
+            ),
+            (Occur::Must, Box::new(range)),
+        ]);
+
+        let searcher = self.reader.searcher();
+        let collector = TopDocs::with_limit(1).order_by_fast_field::<u64>("line_number", order);
+        let Some((_, address)) = searcher.search(&query, &collector)?.into_iter().next() else {
+            return Ok(None);
+        };
+        let doc: TantivyDocument = searcher.doc(address)?;
+        Ok(document_to_log_entry_inner(&self.schema, &doc))
+    }
+
+    /// Binary search within one offset group; returns `(corrected timestamp, entry)`
+    #[allow(clippy::too_many_arguments)]
+    fn occurrence_in_group(
+        &self,
+        content: &dyn Query,
+        file_paths: &[String],
+        excluded: &[String],
+        offset: i64,
+        min_ts: i64,
+        max_ts: i64,
+        edge: OccurrenceEdge,
+    ) -> SearchResult<Option<(i64, LogEntry)>> {
+        use tantivy::collector::DocSetCollector;
+        use tantivy::schema::IndexRecordOption;
+
+        let searcher = self.reader.searcher();
+        let file_term = |path: &String| -> Box<dyn Query> {
+            let term = Term::from_field_text(self.schema.file_path, path);
+            Box::new(TermQuery::new(term, IndexRecordOption::Basic))
+        };
+        let query_in = |start: i64, end: i64| -> BooleanQuery {
+            let start_term = Term::from_field_i64(self.schema.timestamp, start);
+            let end_term = Term::from_field_i64(self.schema.timestamp, end);
+            let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![
+                (Occur::Must, content.box_clone()),
+                (
+                    Occur::Must,
+                    Box::new(RangeQuery::new(
+                        Bound::Included(start_term),
+                        Bound::Included(end_term),
+                    )),
+                ),
+            ];
+            if !file_paths.is_empty() {
+                let file_clauses: Vec<(Occur, Box<dyn Query>)> = file_paths
+                    .iter()
+                    .map(|path| (Occur::Should, file_term(path)))
+                    .collect();
+                clauses.push((Occur::Must, Box::new(BooleanQuery::new(file_clauses))));
+            }
+            clauses.extend(
+                excluded
+                    .iter()
+                    .map(|path| (Occur::MustNot, file_term(path))),
+            );
+            BooleanQuery::new(clauses)
+        };
+        let any_in = |start: i64, end: i64| -> SearchResult<bool> {
+            Ok(searcher.search(&query_in(start, end), &Count)? > 0)
+        };
+
+        // The index stores local timestamps, so the index range bounds every group
+        let (mut lo, mut hi) = (min_ts, max_ts);
+        if !any_in(lo, hi)? {
+            return Ok(None);
+        }
+        while lo < hi {
+            match edge {
+                OccurrenceEdge::First => {
+                    let mid = lo + (hi - lo) / 2;
+                    if any_in(lo, mid)? {
+                        hi = mid;
+                    } else {
+                        lo = mid + 1;
+                    }
+                }
+                OccurrenceEdge::Last => {
+                    let mid = lo + (hi - lo + 1) / 2;
+                    if any_in(mid, hi)? {
+                        lo = mid;
+                    } else {
+                        hi = mid - 1;
+                    }
+                }
+            }
+        }
+
+        let mut entries = Vec::new();
+        for address in searcher.search(&query_in(lo, lo), &DocSetCollector)? {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            if let Some(mut entry) = document_to_log_entry_inner(&self.schema, &doc) {
+                entry.time_offset_secs = (offset != 0).then_some(offset);
+                entries.push(entry);
+            }
+        }
+        let key = |entry: &LogEntry| (entry.file.clone(), entry.line);
+        let entry = match edge {
+            OccurrenceEdge::First => entries.into_iter().min_by_key(key),
+            OccurrenceEdge::Last => entries.into_iter().max_by_key(key),
+        };
+        Ok(entry.map(|entry| (lo.saturating_add(offset), entry)))
+    }
+
+    /// One range query for files sharing `offset`; returns `(corrected timestamp, entry)`
+    ///
+    /// An empty `file_paths` slice means every file not listed in `excluded`.
+    #[allow(clippy::too_many_arguments)]
+    fn time_window_group(
+        &self,
+        file_paths: &[String],
+        excluded: &[String],
+        offset: i64,
+        start: i64,
+        end: i64,
+        limit: usize,
+        order: tantivy::Order,
+    ) -> SearchResult<Vec<(i64, LogEntry)>> {
+        use tantivy::schema::IndexRecordOption;
+
+        let file_term = |path: &String| -> Box<dyn Query> {
+            let term = Term::from_field_text(self.schema.file_path, path);
+            Box::new(TermQuery::new(term, IndexRecordOption::Basic))
+        };
+
+        let start_term = Term::from_field_i64(self.schema.timestamp, start.saturating_sub(offset));
+        let end_term = Term::from_field_i64(self.schema.timestamp, end.saturating_sub(offset));
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(
+            Occur::Must,
+            Box::new(RangeQuery::new(
+                Bound::Included(start_term),
+                Bound::Included(end_term),
+            )),
+        )];
+        if !file_paths.is_empty() {
+            let file_clauses: Vec<(Occur, Box<dyn Query>)> = file_paths
+                .iter()
+                .map(|path| (Occur::Should, file_term(path)))
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(file_clauses))));
+        }
+        clauses.extend(
+            excluded
+                .iter()
+                .map(|path| (Occur::MustNot, file_term(path))),
+        );
+        let query = BooleanQuery::new(clauses);
+
+        let searcher = self.reader.searcher();
+        let collector = TopDocs::with_limit(limit).order_by_fast_field::<i64>("timestamp", order);
+        let top_docs = searcher.search(&query, &collector)?;
+
+        let mut entries = Vec::with_capacity(top_docs.len());
+        for (timestamp, doc_address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            if let Some(mut entry) = document_to_log_entry_inner(&self.schema, &doc) {
+                entry.time_offset_secs = (offset != 0).then_some(offset);
+                entries.push((timestamp.saturating_add(offset), entry));
+            }
+        }
+        Ok(entries)
+    }
+
     /// Delete all documents for a specific file
     ///
     /// This is used when a file is deleted or removed from the workspace.
@@ -1195,6 +1860,26 @@ mod tests {
         // If we get here, creation was successful
     }
 
+    #[test]
+    fn test_open_errors_distinguish_lock_from_corruption() {
+        let (manager, temp_dir) = create_test_manager();
+        let config = SearchConfig {
+            index_path: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        // 写入锁仍被第一个管理器持有
+        let busy = SearchEngineManager::new(config.clone()).err().unwrap();
+        assert!(busy.is_lock_busy());
+        assert!(!busy.is_incompatible_index());
+
+        drop(manager);
+        std::fs::write(temp_dir.path().join("meta.json"), b"{not json").unwrap();
+        let corrupt = SearchEngineManager::new(config).err().unwrap();
+        assert!(corrupt.is_incompatible_index());
+        assert!(!corrupt.is_lock_busy());
+    }
+
     #[tokio::test]
     async fn test_empty_search() {
         let (manager, _temp_dir) = create_test_manager();
@@ -1229,6 +1914,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_memory_usage_counts_segments_and_writer_heap() {
+        let (manager, _temp_dir) = create_test_manager();
+        let empty = manager.memory_usage();
+        assert_eq!(empty.segments, 0);
+        assert_eq!(empty.writer_heap_bytes, 50_000_000);
+
+        let entry = la_core::models::LogEntry {
+            id: 1,
+            timestamp: "2024-01-01 00:00:00".into(),
+            level: "ERROR".into(),
+            file: "logs/app.log".into(),
+            real_path: "cas://a".into(),
+            line: 1,
+            content: "disk full".into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        };
+        manager.add_document(&entry).unwrap();
+        manager.commit().unwrap();
+
+        let usage = manager.memory_usage();
+        assert_eq!(usage.segments, 1);
+        assert_eq!(usage.num_docs, 1);
+        assert!(usage.reader_bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn test_build_tantivy_query_min_level_uses_level_order() {
+        let (manager, _temp_dir) = create_test_manager();
+        for (line, level) in [(1, "debug"), (2, "info"), (3, "warn"), (4, "error")] {
+            let entry = la_core::models::LogEntry {
+                id: line,
+                timestamp: "2024-01-01 00:00:00".into(),
+                level: level.into(),
+                file: "logs/app.log".into(),
+                real_path: "cas://a".into(),
+                line,
+                content: "request failed".into(),
+                tags: vec![],
+                match_details: None,
+                matched_keywords: None,
+                time_offset_secs: None,
+                links: None,
+                translation: None,
+                source_files: None,
+                original_length: None,
+                fields: None,
+            };
+            manager.add_document(&entry).unwrap();
+        }
+        manager.commit().unwrap();
+
+        let search = |order: &LevelOrder, min: &str| {
+            let rank = order.rank(min).unwrap();
+            manager
+                .build_tantivy_query("request", None, None, Some((order, rank)), None)
+                .unwrap()
+        };
+        let default_order = LevelOrder::default();
+        let results = manager
+            .search_with_query(search(&default_order, "WARN"), None, None, None)
+            .await
+            .unwrap();
+        let mut lines: Vec<usize> = results.entries.iter().map(|e| e.line).collect();
+        lines.sort_unstable();
+        assert_eq!(lines, vec![3, 4]);
+
+        // 自定义顺序中 ERROR 之上的 FATAL 在纯文本索引中落为 debug，作为候选保留
+        let custom = LevelOrder::parse("DEBUG < INFO < NOTICE < WARN < ERROR < FATAL").unwrap();
+        let results = manager
+            .search_with_query(search(&custom, "ERROR"), None, None, None)
+            .await
+            .unwrap();
+        let mut lines: Vec<usize> = results.entries.iter().map(|e| e.line).collect();
+        lines.sort_unstable();
+        assert_eq!(lines, vec![1, 4]);
+    }
+
     #[test]
     fn test_parse_log_timestamp_to_unix_accepts_common_formats() {
         let iso = parse_log_timestamp_to_unix("2024-01-01T00:00:00").unwrap();
@@ -1257,6 +2028,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         };
         let entry2 = la_core::models::LogEntry {
             id: 2,
@@ -1269,6 +2046,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         };
 
         manager.add_document(&entry1).unwrap();
@@ -1281,6 +2064,334 @@ mod tests {
         assert_eq!(total, 2);
     }
 
+    #[tokio::test]
+    async fn test_snapshot_is_unaffected_by_later_commits() {
+        let (manager, _temp_dir) = create_test_manager();
+        let entry = |id: usize| la_core::models::LogEntry {
+            id,
+            timestamp: "2024-01-01 00:00:00".into(),
+            level: "INFO".into(),
+            file: "logs/app.log".into(),
+            real_path: "cas://a".into(),
+            line: id,
+            content: format!("entry {id}").into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        };
+
+        manager.add_document(&entry(1)).unwrap();
+        manager.commit().unwrap();
+        let pinned = manager.snapshot();
+
+        manager.add_document(&entry(2)).unwrap();
+        manager.commit().unwrap();
+
+        assert_eq!(pinned.num_docs(), 1);
+        assert_eq!(manager.snapshot().num_docs(), 2);
+        assert_ne!(pinned.generation(), manager.snapshot().generation());
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_entries_orders_by_timestamp() {
+        let (manager, _temp_dir) = create_test_manager();
+
+        for (line, (timestamp, level)) in [
+            ("2024-01-01 00:00:00", "error"),
+            ("2024-01-01 00:10:00", "info"),
+            ("2024-01-01 00:05:00", "error"),
+            ("2024-01-01 00:01:00", "warn"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let entry = la_core::models::LogEntry {
+                id: line,
+                timestamp: timestamp.into(),
+                level: level.into(),
+                file: "logs/app.log".into(),
+                real_path: "cas://a".into(),
+                line: line + 1,
+                content: format!("entry {line}").into(),
+                tags: vec![],
+                match_details: None,
+                matched_keywords: None,
+                time_offset_secs: None,
+                links: None,
+                translation: None,
+                source_files: None,
+                original_length: None,
+                fields: None,
+            };
+            manager.add_document(&entry).unwrap();
+        }
+        manager.commit().unwrap();
+
+        let errors = manager
+            .get_recent_entries(&["error".to_string()], 10)
+            .unwrap();
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 1]);
+
+        let newest = manager.get_recent_entries(&[], 2).unwrap();
+        let lines: Vec<usize> = newest.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![2, 3]);
+        assert_eq!(newest[1].id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_entries_in_time_window_interleaves_files() {
+        let (manager, _temp_dir) = create_test_manager();
+
+        for (file, line, timestamp) in [
+            ("svc-a.log", 1, "2024-01-01 00:00:00"),
+            ("svc-a.log", 2, "2024-01-01 00:00:10"),
+            ("svc-b.log", 1, "2024-01-01 00:00:05"),
+            ("svc-b.log", 2, "2024-01-01 00:00:10"),
+            ("svc-c.log", 1, "2024-01-01 00:00:06"),
+            ("svc-a.log", 3, "2024-01-01 00:01:00"),
+            ("svc-a.log", 4, ""),
+        ] {
+            let entry = la_core::models::LogEntry {
+                id: 0,
+                timestamp: timestamp.into(),
+                level: "info".into(),
+                file: file.into(),
+                real_path: "cas://a".into(),
+                line,
+                content: format!("{file}:{line}").into(),
+                tags: vec![],
+                match_details: None,
+                matched_keywords: None,
+                time_offset_secs: None,
+                links: None,
+                translation: None,
+                source_files: None,
+                original_length: None,
+                fields: None,
+            };
+            manager.add_document(&entry).unwrap();
+        }
+        manager.commit().unwrap();
+
+        let base = 1_704_067_200;
+        let files = vec!["svc-a.log".to_string(), "svc-b.log".to_string()];
+        let entries = manager
+            .get_entries_in_time_window(&files, &HashMap::new(), base, base + 30, 100)
+            .unwrap();
+        let order: Vec<&str> = entries.iter().map(|e| &*e.content).collect();
+        assert_eq!(
+            order,
+            vec!["svc-a.log:1", "svc-b.log:1", "svc-a.log:2", "svc-b.log:2"]
+        );
+        assert_eq!(entries[3].id, 3);
+
+        let all = manager
+            .get_entries_in_time_window(&[], &HashMap::new(), base + 5, base + 6, 100)
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        // svc-c runs 4s behind: local 00:00:06 becomes 00:00:10, after svc-b:2
+        let offsets = HashMap::from([("svc-c.log".to_string(), 4)]);
+        let corrected = manager
+            .get_entries_in_time_window(&[], &offsets, base + 10, base + 10, 100)
+            .unwrap();
+        let order: Vec<&str> = corrected.iter().map(|e| &*e.content).collect();
+        assert_eq!(order, vec!["svc-a.log:2", "svc-b.log:2", "svc-c.log:1"]);
+        assert_eq!(corrected[2].time_offset_secs, Some(4));
+        assert_eq!(corrected[0].time_offset_secs, None);
+
+        let timestamps = manager
+            .get_file_timestamps("svc-a.log", base, base + 30)
+            .unwrap();
+        assert_eq!(timestamps, vec![base, base + 10]);
+
+        // The line without a timestamp (indexed at 0) is not a real sample
+        let timestamps = manager
+            .get_file_timestamps("svc-a.log", 0, base + 30)
+            .unwrap();
+        assert_eq!(timestamps, vec![base, base + 10]);
+        assert_eq!(manager.get_earliest_timestamp().unwrap(), Some(base));
+    }
+
+    #[tokio::test]
+    async fn test_get_entries_around_time_splits_limit_around_center() {
+        let (manager, _temp_dir) = create_test_manager();
+
+        // One line a second before 00:00:10, a few lines from it onwards
+        let lines = (1..=9)
+            .map(|line| (line, format!("2024-01-01 00:00:0{}", line - 1)))
+            .chain([
+                (10, "2024-01-01 00:00:10".to_string()),
+                (11, "2024-01-01 00:00:12".to_string()),
+                (12, "2024-01-01 00:00:15".to_string()),
+            ]);
+        for (line, timestamp) in lines {
+            let entry = la_core::models::LogEntry {
+                id: 0,
+                timestamp: timestamp.into(),
+                level: "info".into(),
+                file: "svc.log".into(),
+                real_path: "cas://a".into(),
+                line,
+                content: format!("line {line}").into(),
+                tags: vec![],
+                match_details: None,
+                matched_keywords: None,
+                time_offset_secs: None,
+                links: None,
+                translation: None,
+                source_files: None,
+                original_length: None,
+                fields: None,
+            };
+            manager.add_document(&entry).unwrap();
+        }
+        manager.commit().unwrap();
+
+        let base = 1_704_067_200;
+        let line_numbers =
+            |entries: Vec<LogEntry>| -> Vec<usize> { entries.iter().map(|e| e.line).collect() };
+
+        // Half the limit before the center (the newest lines), the rest after
+        let around = manager
+            .get_entries_around_time(&[], &HashMap::new(), base + 10, 10, 6)
+            .unwrap();
+        assert_eq!(around[5].id, 5);
+        assert_eq!(line_numbers(around), vec![7, 8, 9, 10, 11, 12]);
+
+        // A sparse side leaves its share to the other
+        let sparse_after = manager
+            .get_entries_around_time(&[], &HashMap::new(), base + 12, 10, 6)
+            .unwrap();
+        assert_eq!(line_numbers(sparse_after), vec![7, 8, 9, 10, 11, 12]);
+        let sparse_before = manager
+            .get_entries_around_time(&[], &HashMap::new(), base, 10, 6)
+            .unwrap();
+        assert_eq!(line_numbers(sparse_before), vec![1, 2, 3, 4, 5, 6]);
+
+        // The plain window keeps the oldest lines and misses the center
+        let window = manager
+            .get_entries_in_time_window(&[], &HashMap::new(), base, base + 20, 6)
+            .unwrap();
+        assert_eq!(line_numbers(window), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_find_occurrence_binary_searches_time_axis() {
+        let (manager, _temp_dir) = create_test_manager();
+
+        for (file, line, timestamp, content) in [
+            ("svc-a.log", 1, "2024-01-01 00:00:00", "service started"),
+            ("svc-a.log", 2, "2024-01-01 00:05:00", "connection timeout"),
+            ("svc-b.log", 7, "2024-01-01 00:05:00", "upstream timeout"),
+            ("svc-b.log", 8, "2024-01-01 02:00:00", "request timeout"),
+            ("svc-c.log", 1, "2024-01-01 00:04:00", "read timeout"),
+            ("svc-a.log", 3, "2024-01-01 03:00:00", "shutdown"),
+        ] {
+            let entry = la_core::models::LogEntry {
+                id: 0,
+                timestamp: timestamp.into(),
+                level: "error".into(),
+                file: file.into(),
+                real_path: "cas://a".into(),
+                line,
+                content: content.into(),
+                tags: vec![],
+                match_details: None,
+                matched_keywords: None,
+                time_offset_secs: None,
+                links: None,
+                translation: None,
+                source_files: None,
+                original_length: None,
+                fields: None,
+            };
+            manager.add_document(&entry).unwrap();
+        }
+        manager.commit().unwrap();
+
+        let none = HashMap::new();
+        let first = manager
+            .find_occurrence("timeout", &none, OccurrenceEdge::First)
+            .unwrap()
+            .unwrap();
+        assert_eq!(&*first.content, "read timeout");
+        let last = manager
+            .find_occurrence("timeout", &none, OccurrenceEdge::Last)
+            .unwrap()
+            .unwrap();
+        assert_eq!(&*last.content, "request timeout");
+
+        // svc-c runs 2 minutes behind: corrected 00:06:00, so the tie at 00:05:00 wins by file
+        let offsets = HashMap::from([("svc-c.log".to_string(), 120)]);
+        let first = manager
+            .find_occurrence("timeout", &offsets, OccurrenceEdge::First)
+            .unwrap()
+            .unwrap();
+        assert_eq!(&*first.content, "connection timeout");
+        assert_eq!(first.time_offset_secs, None);
+
+        assert!(manager
+            .find_occurrence("missing", &none, OccurrenceEdge::First)
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_adjacent_match_steps_within_one_file() {
+        let (manager, _temp_dir) = create_test_manager();
+
+        for (file, line, content) in [
+            ("app.log", 3, "connection timeout"),
+            ("app.log", 10, "all good"),
+            ("app.log", 12, "read timeout"),
+            ("app.log", 40, "write timeout"),
+            ("other.log", 11, "upstream timeout"),
+        ] {
+            let entry = la_core::models::LogEntry {
+                id: 0,
+                timestamp: "2024-01-01 00:00:00".into(),
+                level: "error".into(),
+                file: file.into(),
+                real_path: "cas://a".into(),
+                line,
+                content: content.into(),
+                tags: vec![],
+                match_details: None,
+                matched_keywords: None,
+                time_offset_secs: None,
+                links: None,
+                translation: None,
+                source_files: None,
+                original_length: None,
+                fields: None,
+            };
+            manager.add_document(&entry).unwrap();
+        }
+        manager.commit().unwrap();
+
+        let adjacent = |line, direction| {
+            manager
+                .find_adjacent_match("timeout", "app.log", line, direction)
+                .unwrap()
+                .map(|entry| entry.line)
+        };
+        assert_eq!(adjacent(3, MatchDirection::Next), Some(12));
+        assert_eq!(adjacent(12, MatchDirection::Next), Some(40));
+        assert_eq!(adjacent(40, MatchDirection::Next), None);
+        assert_eq!(adjacent(40, MatchDirection::Previous), Some(12));
+        assert_eq!(adjacent(11, MatchDirection::Previous), Some(3));
+        assert_eq!(adjacent(3, MatchDirection::Previous), None);
+        assert_eq!(adjacent(1, MatchDirection::Previous), None);
+    }
+
     /// Test delete_file_documents functionality
     #[tokio::test]
     async fn test_delete_file_documents() {
@@ -1300,6 +2411,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         };
 
         let entry2 = la_core::models::LogEntry {
@@ -1313,6 +2430,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         };
 
         let entry3 = la_core::models::LogEntry {
@@ -1326,6 +2449,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         };
 
         // Add documents to index
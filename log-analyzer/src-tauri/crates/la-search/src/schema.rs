@@ -12,6 +12,12 @@ use tantivy::schema::{
 };
 use tantivy::tokenizer::TextAnalyzer;
 
+/// Version of the on-disk index layout produced by [`LogSchema`].
+///
+/// Bump whenever fields, field options or tokenizers change so that existing
+/// workspaces are rebuilt instead of being queried with a mismatched schema.
+pub const INDEX_SCHEMA_VERSION: i32 = 1;
+
 /// Schema definition for log entries in the search index
 #[derive(Clone, Debug)]
 pub struct LogSchema {
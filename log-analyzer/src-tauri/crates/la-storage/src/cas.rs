@@ -20,7 +20,15 @@
 //!
 //! The first 2 characters of the hash are used as a directory name
 //! to avoid having too many files in a single directory.
-
+//!
+//! Large objects also get a `<hash>.chunks` sidecar with per-chunk checksums
+//! (see [`crate::chunk_checksums`]) so corruption can be located and detected
+//! mid-read.
+
+use crate::chunk_checksums::{
+    self, describe_ranges, ChunkHasher, ChunkManifest, CorruptedRange, CorruptionLog,
+    VerifyingReader, CHUNKED_OBJECT_MIN_BYTES, CHUNK_SIZE,
+};
 use async_trait::async_trait;
 use la_core::error::{AppError, Result};
 use la_core::traits::ContentStorage;
@@ -161,6 +169,10 @@ fn get_available_space(_path: &Path) -> u64 {
     0 // Unknown, skip check
 }
 
+/// Estimated size of one existence cache entry: 64-byte hex key, `String`
+/// header and moka's per-entry bookkeeping
+const EXISTENCE_CACHE_ENTRY_BYTES: u64 = 64 + 24 + 96;
+
 /// Content-Addressable Storage manager
 ///
 /// Provides Git-style content storage with SHA-256 hashing.
@@ -178,6 +190,8 @@ pub struct ContentAddressableStorage {
     /// In-memory LRU cache for object existence checks (performance optimization)
     /// Limits memory usage by evicting least recently used entries
     existence_cache: Arc<Cache<String, ()>>,
+    /// Objects whose chunk checksums failed since startup
+    corruption: CorruptionLog,
 }
 
 fn is_valid_content_hash(hash: &str) -> bool {
@@ -228,6 +242,85 @@ impl Drop for TempFileGuard {
     }
 }
 
+/// Write buffer of [`CasObjectWriter`]
+const OBJECT_WRITER_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// Incremental writer for a single CAS object, see
+/// [`ContentAddressableStorage::object_writer_sync`]
+pub struct CasObjectWriter<'a> {
+    cas: &'a ContentAddressableStorage,
+    file: std::io::BufWriter<std::fs::File>,
+    guard: TempFileGuard,
+    hasher: Sha256,
+    chunks: ChunkHasher,
+    bytes_written: u64,
+}
+
+impl CasObjectWriter<'_> {
+    /// Bytes written so far
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Move the written content into place and return its hash
+    ///
+    /// Content that is already stored is deduplicated: the temp file is
+    /// dropped and the existing object is kept.
+    pub fn finish(self) -> Result<String> {
+        let Self {
+            cas,
+            file,
+            guard,
+            hasher,
+            chunks,
+            ..
+        } = self;
+        file.into_inner().map_err(|e| e.into_error()).map_err(|e| {
+            AppError::io_error(
+                format!("Temp file flush failed: {e}"),
+                Some(guard.path.clone()),
+            )
+        })?;
+
+        let hash = format!("{:x}", hasher.finalize());
+        let object_path = cas.get_object_path(&hash);
+        if object_path.exists() {
+            cas.existence_cache.insert(hash.clone(), ());
+            return Ok(hash);
+        }
+        if let Some(parent) = object_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                AppError::io_error(
+                    format!("Failed to create object directory: {e}"),
+                    Some(parent.to_path_buf()),
+                )
+            })?;
+        }
+        std::fs::rename(&guard.path, &object_path).map_err(|e| {
+            AppError::io_error(format!("CAS rename failed: {e}"), Some(object_path.clone()))
+        })?;
+        let _ = guard.keep();
+
+        cas.save_chunk_manifest_sync(&hash, chunks.finish());
+        cas.existence_cache.insert(hash.clone(), ());
+        Ok(hash)
+    }
+}
+
+impl std::io::Write for CasObjectWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = std::io::Write::write(&mut self.file, buf)?;
+        self.hasher.update(&buf[..written]);
+        self.chunks.update(&buf[..written]);
+        self.bytes_written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::Write::flush(&mut self.file)
+    }
+}
+
 impl ContentAddressableStorage {
     /// Create a new CAS instance
     ///
@@ -257,6 +350,7 @@ impl ContentAddressableStorage {
                     .time_to_idle(Duration::from_secs(300))
                     .build(),
             ),
+            corruption: CorruptionLog::default(),
         }
     }
 
@@ -469,6 +563,7 @@ impl ContentAddressableStorage {
             // Copy using async I/O with buffer
             let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
             let mut total_bytes = 0u64;
+            let mut chunks = ChunkHasher::new();
 
             loop {
                 let bytes_read = src_file.read(&mut buffer).await.map_err(|e| {
@@ -491,6 +586,7 @@ impl ContentAddressableStorage {
                             Some(object_path.clone()),
                         )
                     })?;
+                chunks.update(&buffer[..bytes_read]);
 
                 total_bytes += bytes_read as u64;
             }
@@ -502,6 +598,7 @@ impl ContentAddressableStorage {
                     Some(object_path.clone()),
                 )
             })?;
+            self.save_chunk_manifest(&hash, chunks.finish()).await;
 
             Ok::<u64, AppError>(total_bytes)
         })
@@ -733,6 +830,11 @@ impl ContentAddressableStorage {
             }
         }
 
+        if content.len() as u64 >= CHUNKED_OBJECT_MIN_BYTES {
+            self.save_chunk_manifest(&hash, ChunkManifest::compute(content))
+                .await;
+        }
+
         // Cache the newly created object
         self.existence_cache.insert(hash.clone(), ());
 
@@ -958,6 +1060,7 @@ impl ContentAddressableStorage {
         })?;
 
         let mut hasher = Sha256::new();
+        let mut chunks = ChunkHasher::new();
         let mut buf = vec![0u8; 1024 * 1024]; // 1MB buffer
 
         loop {
@@ -969,6 +1072,7 @@ impl ContentAddressableStorage {
                 break;
             }
             hasher.update(&buf[..n]);
+            chunks.update(&buf[..n]);
             tmp_file.write_all(&buf[..n]).await.map_err(|e| {
                 AppError::io_error(
                     format!("Temp file write failed: {e}"),
@@ -998,6 +1102,7 @@ impl ContentAddressableStorage {
 
         match fs::rename(&tmp_path, &object_path).await {
             Ok(_) => {
+                self.save_chunk_manifest(&hash, chunks.finish()).await;
                 self.existence_cache.insert(hash.clone(), ());
                 Ok(hash)
             }
@@ -1016,6 +1121,45 @@ impl ContentAddressableStorage {
         }
     }
 
+    /// Start a CAS object whose content is produced incrementally (sync version)
+    ///
+    /// Bytes written to the returned [`CasObjectWriter`] are hashed on the fly
+    /// and land in a temp file under the CAS root; [`CasObjectWriter::finish`]
+    /// renames it into `objects/`. Archive entries decoded in `spawn_blocking`
+    /// are stored this way instead of being extracted to a scratch directory
+    /// first, so each entry is written to disk once instead of twice.
+    ///
+    /// Dropping the writer without calling `finish` discards the temp file.
+    pub fn object_writer_sync(&self) -> Result<CasObjectWriter<'_>> {
+        let temp_dir = self.workspace_dir.join("tmp");
+        std::fs::create_dir_all(&temp_dir).map_err(|e| {
+            AppError::io_error(
+                format!("Failed to create temp directory: {e}"),
+                Some(temp_dir.clone()),
+            )
+        })?;
+        let temp_path = temp_dir.join(format!(
+            ".tmp.{}.{}.tmp",
+            uuid::Uuid::new_v4(),
+            std::process::id()
+        ));
+        let file = std::fs::File::create(&temp_path).map_err(|e| {
+            AppError::io_error(
+                format!("Failed to create temp file: {e}"),
+                Some(temp_path.clone()),
+            )
+        })?;
+
+        Ok(CasObjectWriter {
+            cas: self,
+            file: std::io::BufWriter::with_capacity(OBJECT_WRITER_BUFFER_BYTES, file),
+            guard: TempFileGuard::new(temp_path),
+            hasher: Sha256::new(),
+            chunks: ChunkHasher::new(),
+            bytes_written: 0,
+        })
+    }
+
     /// Read content via memory-mapped I/O (sync version)
     ///
     /// Uses mmap for large files to avoid loading entire content into Vec<u8>.
@@ -1197,6 +1341,272 @@ impl ContentAddressableStorage {
         Ok(computed_hash == hash)
     }
 
+    /// Chunk manifest of an object; `None` for small objects and objects stored
+    /// before chunk checksums existed
+    pub fn chunk_manifest(&self, hash: &str) -> Option<ChunkManifest> {
+        if !is_valid_content_hash(hash) {
+            return None;
+        }
+        let path = chunk_checksums::manifest_path(&self.get_object_path(hash));
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!(hash = %hash, error = %e, "Failed to read chunk manifest");
+                return None;
+            }
+        };
+        match serde_json::from_slice(&bytes) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                warn!(hash = %hash, error = %e, "Ignoring unreadable chunk manifest");
+                None
+            }
+        }
+    }
+
+    /// Write the chunk manifest of a newly stored object (large objects only).
+    ///
+    /// A missing manifest only disables chunk checks, so failures are logged.
+    async fn save_chunk_manifest(&self, hash: &str, manifest: ChunkManifest) {
+        if manifest.object_size < CHUNKED_OBJECT_MIN_BYTES {
+            return;
+        }
+        let path = chunk_checksums::manifest_path(&self.get_object_path(hash));
+        let result = match serde_json::to_vec(&manifest) {
+            Ok(json) => fs::write(&path, json).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            warn!(hash = %hash, error = %e, "Failed to write chunk manifest");
+        }
+    }
+
+    /// Sync counterpart of [`Self::save_chunk_manifest`]
+    fn save_chunk_manifest_sync(&self, hash: &str, manifest: ChunkManifest) {
+        if manifest.object_size < CHUNKED_OBJECT_MIN_BYTES {
+            return;
+        }
+        let path = chunk_checksums::manifest_path(&self.get_object_path(hash));
+        let result = match serde_json::to_vec(&manifest) {
+            Ok(json) => std::fs::write(&path, json).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            warn!(hash = %hash, error = %e, "Failed to write chunk manifest");
+        }
+    }
+
+    /// Estimated heap held by the existence cache: (entries, bytes)
+    pub fn existence_cache_usage(&self) -> (u64, u64) {
+        // Entry counts are updated lazily by moka's maintenance tasks
+        self.existence_cache.run_pending_tasks();
+        let entries = self.existence_cache.entry_count();
+        (entries, entries * EXISTENCE_CACHE_ENTRY_BYTES)
+    }
+
+    /// Objects whose chunk checks failed since this instance was created
+    pub fn corruption_log(&self) -> &CorruptionLog {
+        &self.corruption
+    }
+
+    fn corruption_error(&self, hash: &str, ranges: &[CorruptedRange]) -> AppError {
+        AppError::io_error(
+            format!(
+                "CAS object {hash} is corrupted at {}",
+                describe_ranges(ranges)
+            ),
+            Some(self.get_object_path(hash)),
+        )
+    }
+
+    /// Read content and check it against the chunk manifest, if any (sync version)
+    ///
+    /// Corrupted ranges are recorded in [`Self::corruption_log`] and named in
+    /// the returned error.
+    pub fn read_content_verified_sync(&self, hash: &str) -> Result<Vec<u8>> {
+        let content = self.read_content_sync(hash)?;
+        if let Some(manifest) = self.chunk_manifest(hash) {
+            let corrupted = chunk_checksums::find_corrupted(&manifest, &content);
+            if !corrupted.is_empty() {
+                self.corruption.record(hash, &corrupted);
+                return Err(self.corruption_error(hash, &corrupted));
+            }
+        }
+        Ok(content)
+    }
+
+    /// Open an object for streaming (sync version)
+    ///
+    /// Objects with a chunk manifest are read through a [`VerifyingReader`]:
+    /// the first corrupted chunk fails the read before any of its bytes are
+    /// returned, and is recorded in [`Self::corruption_log`].
+    pub fn open_verified_sync(&self, hash: &str) -> Result<Box<dyn std::io::Read + Send>> {
+        if !is_valid_content_hash(hash) {
+            return Err(AppError::validation_error(format!(
+                "Invalid content hash format: {hash}"
+            )));
+        }
+        let object_path = self.get_object_path(hash);
+        let file = std::fs::File::open(&object_path).map_err(|e| {
+            AppError::io_error(
+                format!("Failed to open object {hash}: {e}"),
+                Some(object_path.clone()),
+            )
+        })?;
+        Ok(match self.chunk_manifest(hash) {
+            Some(manifest) => Box::new(VerifyingReader::new(
+                file,
+                hash,
+                manifest,
+                self.corruption.clone(),
+            )),
+            None => Box::new(file),
+        })
+    }
+
+    /// Check every chunk of a stored object (sync version)
+    ///
+    /// # Returns
+    ///
+    /// Corrupted ranges (empty when intact), or `None` when the object has no
+    /// chunk manifest
+    pub fn scan_chunks_sync(&self, hash: &str) -> Result<Option<Vec<CorruptedRange>>> {
+        use std::io::Read;
+
+        let Some(manifest) = self.chunk_manifest(hash) else {
+            return Ok(None);
+        };
+        let object_path = self.get_object_path(hash);
+        let mut file = std::fs::File::open(&object_path).map_err(|e| {
+            AppError::io_error(
+                format!("Failed to open object {hash}: {e}"),
+                Some(object_path.clone()),
+            )
+        })?;
+        let mut chunks = ChunkHasher::new();
+        let mut buffer = vec![0u8; CHUNK_SIZE as usize];
+        loop {
+            let n = file.read(&mut buffer).map_err(|e| {
+                AppError::io_error(
+                    format!("Failed to read object {hash}: {e}"),
+                    Some(object_path.clone()),
+                )
+            })?;
+            if n == 0 {
+                break;
+            }
+            chunks.update(&buffer[..n]);
+        }
+
+        let corrupted = chunk_checksums::compare(&manifest, &chunks.finish());
+        if corrupted.is_empty() {
+            self.corruption.clear(hash);
+        } else {
+            self.corruption.record(hash, &corrupted);
+        }
+        Ok(Some(corrupted))
+    }
+
+    /// Replace a corrupted object with content from `reader` (e.g. re-extracted
+    /// from its parent archive)
+    ///
+    /// The content is written to a temp file and only renamed over the object
+    /// when it hashes to `hash`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the object was restored, `false` if the content did not match
+    pub async fn restore_object<R>(&self, hash: &str, mut reader: R) -> Result<bool>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        if !is_valid_content_hash(hash) {
+            return Err(AppError::validation_error(format!(
+                "Invalid content hash format: {hash}"
+            )));
+        }
+
+        let temp_dir = self.workspace_dir.join("tmp");
+        fs::create_dir_all(&temp_dir).await.map_err(|e| {
+            AppError::io_error(
+                format!("Failed to create temp directory: {e}"),
+                Some(temp_dir.clone()),
+            )
+        })?;
+        let temp_path = temp_dir.join(format!("restore_{}", uuid::Uuid::new_v4()));
+        let mut temp_file = fs::File::create(&temp_path).await.map_err(|e| {
+            AppError::io_error(
+                format!("Failed to create temp file: {e}"),
+                Some(temp_path.clone()),
+            )
+        })?;
+        let temp_guard = TempFileGuard::new(temp_path.clone());
+
+        let mut hasher = Sha256::new();
+        let mut chunks = ChunkHasher::new();
+        let mut buf = vec![0u8; CHUNK_SIZE as usize];
+        loop {
+            let n = reader
+                .read(&mut buf)
+                .await
+                .map_err(|e| AppError::io_error(format!("Stream read failed: {e}"), None))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            chunks.update(&buf[..n]);
+            temp_file.write_all(&buf[..n]).await.map_err(|e| {
+                AppError::io_error(
+                    format!("Temp file write failed: {e}"),
+                    Some(temp_path.clone()),
+                )
+            })?;
+        }
+        temp_file.sync_all().await.map_err(|e| {
+            AppError::io_error(
+                format!("Temp file sync failed: {e}"),
+                Some(temp_path.clone()),
+            )
+        })?;
+        drop(temp_file);
+
+        let computed = format!("{:x}", hasher.finalize());
+        if computed != hash {
+            warn!(
+                hash = %hash,
+                computed = %computed,
+                "Restored content does not match the object hash, keeping the object"
+            );
+            return Ok(false);
+        }
+
+        let object_path = self.get_object_path(hash);
+        if let Some(parent) = object_path.parent() {
+            fs::create_dir_all(parent).await.map_err(|e| {
+                AppError::io_error(
+                    format!("Failed to create object directory: {e}"),
+                    Some(parent.to_path_buf()),
+                )
+            })?;
+        }
+        fs::rename(&temp_path, &object_path).await.map_err(|e| {
+            AppError::io_error(
+                format!("Failed to replace object {hash}: {e}"),
+                Some(object_path.clone()),
+            )
+        })?;
+        let _ = temp_guard.keep();
+
+        self.save_chunk_manifest(hash, chunks.finish()).await;
+        self.corruption.clear(hash);
+        self.existence_cache.insert(hash.to_string(), ());
+        info!(hash = %hash, path = %object_path.display(), "Restored corrupted CAS object");
+        Ok(true)
+    }
+
     /// Store file with disk space pre-check (safe storage)
     ///
     /// This method performs disk space validation before attempting to store a file.
@@ -1346,6 +1756,7 @@ impl ContentAddressableStorage {
 
         // Single-pass: read, hash, and write simultaneously
         let mut hasher = Sha256::new();
+        let mut chunks = ChunkHasher::new();
         let mut buffer = vec![0u8; BUFFER_SIZE];
         let mut total_bytes = 0u64;
 
@@ -1364,6 +1775,7 @@ impl ContentAddressableStorage {
 
                 // Update hash
                 hasher.update(&buffer[..bytes_read]);
+                chunks.update(&buffer[..bytes_read]);
 
                 // Write to temp file
                 temp_file
@@ -1486,6 +1898,7 @@ impl ContentAddressableStorage {
                 // Success! Prevent RAII guard from cleaning up the temp file
                 // since it's now the permanent file
                 let _ = temp_guard.keep();
+                self.save_chunk_manifest(&hash, chunks.finish()).await;
             }
             Err(e) => {
                 // Rename failed, temp file will be cleaned up by RAII guard (BUG-007 fix)
@@ -1722,6 +2135,36 @@ mod tests {
         assert!(cas.exists(&hash1), "Content should exist");
     }
 
+    #[test]
+    fn test_object_writer_stores_and_deduplicates() {
+        use std::io::Write;
+
+        let temp_dir = TempDir::new().unwrap();
+        let cas = ContentAddressableStorage::new(temp_dir.path().join("workspace"));
+        let content = b"line 1\nline 2\n";
+
+        let mut writer = cas.object_writer_sync().unwrap();
+        writer.write_all(&content[..4]).unwrap();
+        writer.write_all(&content[4..]).unwrap();
+        assert_eq!(writer.bytes_written(), content.len() as u64);
+        let hash = writer.finish().unwrap();
+        assert_eq!(hash, ContentAddressableStorage::compute_hash(content));
+        assert_eq!(cas.read_content_sync(&hash).unwrap(), content);
+
+        let mut again = cas.object_writer_sync().unwrap();
+        again.write_all(content).unwrap();
+        assert_eq!(again.finish().unwrap(), hash);
+
+        // Abandoned writers leave no temp file behind
+        let mut abandoned = cas.object_writer_sync().unwrap();
+        abandoned.write_all(b"partial").unwrap();
+        drop(abandoned);
+        let leftovers = std::fs::read_dir(temp_dir.path().join("workspace/tmp"))
+            .unwrap()
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
     #[test]
     fn test_hash_empty_content() {
         let hash = ContentAddressableStorage::compute_hash(b"");
@@ -1761,6 +2204,10 @@ mod tests {
             !cas.exists("nonexistent_hash"),
             "Nonexistent content should not exist"
         );
+
+        let (entries, bytes) = cas.existence_cache_usage();
+        assert_eq!(entries, 1);
+        assert_eq!(bytes, EXISTENCE_CACHE_ENTRY_BYTES);
     }
 
     #[tokio::test]
@@ -2091,4 +2538,41 @@ mod tests {
         assert!(!cas.exists_async(&malicious_hash).await);
         assert!(cas.read_content_sync(&malicious_hash).is_err());
     }
+
+    #[tokio::test]
+    async fn test_chunk_checksums_locate_corruption_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let cas = ContentAddressableStorage::new(temp_dir.path().to_path_buf());
+
+        let content: Vec<u8> = (0..CHUNKED_OBJECT_MIN_BYTES as usize + 10)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let hash = cas.store_content(&content).await.unwrap();
+        let small = cas.store_content(b"small object").await.unwrap();
+        assert_eq!(cas.chunk_manifest(&hash).unwrap().checksums.len(), 5);
+        assert!(cas.chunk_manifest(&small).is_none());
+        assert_eq!(cas.scan_chunks_sync(&hash).unwrap(), Some(vec![]));
+
+        let mut rotten = content.clone();
+        rotten[CHUNK_SIZE as usize * 2 + 3] ^= 0xff;
+        std::fs::write(cas.get_object_path(&hash), &rotten).unwrap();
+
+        let error = cas.read_content_verified_sync(&hash).unwrap_err();
+        assert!(error.to_string().contains("chunk 2"), "{error}");
+        let expected = vec![CorruptedRange {
+            chunk_index: 2,
+            offset: CHUNK_SIZE * 2,
+            length: CHUNK_SIZE,
+        }];
+        assert_eq!(cas.corruption_log().get(&hash), Some(expected.clone()));
+        assert_eq!(cas.scan_chunks_sync(&hash).unwrap(), Some(expected));
+
+        // Wrong content never replaces the object
+        assert!(!cas.restore_object(&hash, &b"other"[..]).await.unwrap());
+        assert!(cas.read_content_verified_sync(&hash).is_err());
+
+        assert!(cas.restore_object(&hash, &content[..]).await.unwrap());
+        assert!(cas.corruption_log().get(&hash).is_none());
+        assert_eq!(cas.read_content_verified_sync(&hash).unwrap(), content);
+    }
 }
@@ -0,0 +1,412 @@
+//! Per-chunk checksums for large CAS objects
+//!
+//! Re-hashing a whole object only says *that* it is corrupt, and only after
+//! reading all of it. Objects of at least [`CHUNKED_OBJECT_MIN_BYTES`] get a
+//! sidecar next to the object listing the SHA-256 of every [`CHUNK_SIZE`] chunk:
+//!
+//! ```text
+//! objects/
+//!   a3/
+//!     f2e1d4c5b6a7...          (object)
+//!     f2e1d4c5b6a7....chunks   (JSON chunk manifest)
+//! ```
+//!
+//! - [`VerifyingReader`] checks each chunk before handing out its bytes, so a
+//!   streaming scan stops at the first rotten chunk instead of searching garbage
+//! - [`find_corrupted`] reports every corrupted range of an in-memory copy
+//! - Objects stored before the sidecar existed (or below the size threshold)
+//!   have no manifest and are read unchecked
+//! - Detected corruption is recorded in a [`CorruptionLog`] so it can be
+//!   repaired later (e.g. by re-extracting the parent archive)
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Size of a checksummed chunk (1 MiB)
+pub const CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// Objects smaller than this are cheap to re-hash and get no manifest (4 MiB)
+pub const CHUNKED_OBJECT_MIN_BYTES: u64 = 4 * CHUNK_SIZE;
+
+const MANIFEST_SUFFIX: &str = ".chunks";
+
+/// Sidecar path of the chunk manifest for `object_path`
+pub fn manifest_path(object_path: &Path) -> PathBuf {
+    let mut path = object_path.as_os_str().to_owned();
+    path.push(MANIFEST_SUFFIX);
+    PathBuf::from(path)
+}
+
+/// Checksums of every chunk of one object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkManifest {
+    pub chunk_size: u64,
+    pub object_size: u64,
+    /// Lowercase hex SHA-256 per chunk; the last chunk may be short
+    pub checksums: Vec<String>,
+}
+
+impl ChunkManifest {
+    /// Manifest of an in-memory object
+    pub fn compute(content: &[u8]) -> Self {
+        let mut hasher = ChunkHasher::new();
+        hasher.update(content);
+        hasher.finish()
+    }
+
+    /// Byte range covered by chunk `index`
+    fn chunk_range(&self, index: usize) -> (u64, u64) {
+        let offset = index as u64 * self.chunk_size;
+        (offset, self.chunk_size.min(self.object_size - offset))
+    }
+
+    fn is_consistent(&self) -> bool {
+        self.chunk_size > 0
+            && self.checksums.len() as u64 == self.object_size.div_ceil(self.chunk_size)
+    }
+}
+
+/// A corrupted byte range `[offset, offset + length)` of an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptedRange {
+    pub chunk_index: usize,
+    pub offset: u64,
+    pub length: u64,
+}
+
+impl fmt::Display for CorruptedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bytes {}..{} (chunk {})",
+            self.offset,
+            self.offset + self.length,
+            self.chunk_index
+        )
+    }
+}
+
+/// Human-readable list of ranges, e.g. for error messages
+pub fn describe_ranges(ranges: &[CorruptedRange]) -> String {
+    ranges
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Error carried inside the `io::Error` returned by [`VerifyingReader`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkCorruption {
+    pub hash: String,
+    pub range: CorruptedRange,
+}
+
+impl fmt::Display for ChunkCorruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CAS object {} is corrupted at {}", self.hash, self.range)
+    }
+}
+
+impl std::error::Error for ChunkCorruption {}
+
+impl ChunkCorruption {
+    /// The corruption behind an I/O error from [`VerifyingReader`], if any
+    pub fn from_io_error(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+/// Incremental per-chunk hasher, fed while an object is being written
+pub struct ChunkHasher {
+    current: Sha256,
+    filled: u64,
+    total: u64,
+    checksums: Vec<String>,
+}
+
+impl Default for ChunkHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkHasher {
+    pub fn new() -> Self {
+        Self {
+            current: Sha256::new(),
+            filled: 0,
+            total: 0,
+            checksums: Vec::new(),
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = ((CHUNK_SIZE - self.filled) as usize).min(data.len());
+            self.current.update(&data[..take]);
+            self.filled += take as u64;
+            self.total += take as u64;
+            data = &data[take..];
+            if self.filled == CHUNK_SIZE {
+                self.finish_chunk();
+            }
+        }
+    }
+
+    fn finish_chunk(&mut self) {
+        let digest = std::mem::take(&mut self.current).finalize();
+        self.checksums.push(format!("{digest:x}"));
+        self.filled = 0;
+    }
+
+    pub fn finish(mut self) -> ChunkManifest {
+        if self.filled > 0 {
+            self.finish_chunk();
+        }
+        ChunkManifest {
+            chunk_size: CHUNK_SIZE,
+            object_size: self.total,
+            checksums: self.checksums,
+        }
+    }
+}
+
+/// Chunks of `actual` that differ from `expected`.
+///
+/// A size mismatch reports the chunks past the shorter of the two lengths;
+/// an inconsistent `expected` manifest reports nothing.
+pub fn compare(expected: &ChunkManifest, actual: &ChunkManifest) -> Vec<CorruptedRange> {
+    if !expected.is_consistent() || expected.chunk_size != actual.chunk_size {
+        return Vec::new();
+    }
+    let mut corrupted: Vec<CorruptedRange> = expected
+        .checksums
+        .iter()
+        .enumerate()
+        .filter(|(index, checksum)| actual.checksums.get(*index) != Some(checksum))
+        .map(|(index, _)| {
+            let (offset, length) = expected.chunk_range(index);
+            CorruptedRange {
+                chunk_index: index,
+                offset,
+                length,
+            }
+        })
+        .collect();
+    if actual.object_size > expected.object_size {
+        corrupted.push(CorruptedRange {
+            chunk_index: expected.checksums.len(),
+            offset: expected.object_size,
+            length: actual.object_size - expected.object_size,
+        });
+    }
+    corrupted
+}
+
+/// Every corrupted range of an in-memory copy of the object
+pub fn find_corrupted(manifest: &ChunkManifest, content: &[u8]) -> Vec<CorruptedRange> {
+    compare(manifest, &ChunkManifest::compute(content))
+}
+
+/// Objects found corrupted since startup, keyed by hash
+#[derive(Debug, Clone, Default)]
+pub struct CorruptionLog(Arc<DashMap<String, Vec<CorruptedRange>>>);
+
+impl CorruptionLog {
+    /// Record `ranges` for `hash`, merged with ranges seen earlier
+    pub fn record(&self, hash: &str, ranges: &[CorruptedRange]) {
+        let mut entry = self.0.entry(hash.to_string()).or_default();
+        for range in ranges {
+            if !entry.contains(range) {
+                entry.push(*range);
+            }
+        }
+        entry.sort_by_key(|range| range.offset);
+    }
+
+    pub fn get(&self, hash: &str) -> Option<Vec<CorruptedRange>> {
+        self.0.get(hash).map(|ranges| ranges.clone())
+    }
+
+    pub fn clear(&self, hash: &str) {
+        self.0.remove(hash);
+    }
+
+    /// All recorded objects, sorted by hash
+    pub fn snapshot(&self) -> Vec<(String, Vec<CorruptedRange>)> {
+        let mut all: Vec<_> = self
+            .0
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        all.sort_by(|a, b| a.0.cmp(&b.0));
+        all
+    }
+}
+
+/// Reader that verifies each chunk against the manifest before returning it.
+///
+/// The first corrupted chunk fails the read with `ErrorKind::InvalidData`
+/// wrapping a [`ChunkCorruption`]; bytes of that chunk are never returned.
+pub struct VerifyingReader<R> {
+    inner: R,
+    hash: String,
+    manifest: ChunkManifest,
+    log: CorruptionLog,
+    chunk: Vec<u8>,
+    pos: usize,
+    next_index: usize,
+}
+
+impl<R: Read> VerifyingReader<R> {
+    pub fn new(inner: R, hash: &str, manifest: ChunkManifest, log: CorruptionLog) -> Self {
+        Self {
+            inner,
+            hash: hash.to_string(),
+            chunk: Vec::with_capacity(manifest.chunk_size as usize),
+            manifest,
+            log,
+            pos: 0,
+            next_index: 0,
+        }
+    }
+
+    fn corrupted(&self, range: CorruptedRange) -> io::Error {
+        self.log.record(&self.hash, &[range]);
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            ChunkCorruption {
+                hash: self.hash.clone(),
+                range,
+            },
+        )
+    }
+
+    /// Read and verify the next chunk; returns `false` at a verified EOF
+    fn fill_chunk(&mut self) -> io::Result<bool> {
+        self.chunk.clear();
+        self.pos = 0;
+        (&mut self.inner)
+            .take(self.manifest.chunk_size)
+            .read_to_end(&mut self.chunk)?;
+
+        let index = self.next_index;
+        let Some(expected) = self.manifest.checksums.get(index) else {
+            if self.chunk.is_empty() {
+                return Ok(false);
+            }
+            // Longer than when it was stored
+            return Err(self.corrupted(CorruptedRange {
+                chunk_index: index,
+                offset: self.manifest.object_size,
+                length: self.chunk.len() as u64,
+            }));
+        };
+        let (offset, length) = self.manifest.chunk_range(index);
+        if self.chunk.len() as u64 != length
+            || format!("{:x}", Sha256::digest(&self.chunk)) != *expected
+        {
+            return Err(self.corrupted(CorruptedRange {
+                chunk_index: index,
+                offset,
+                length,
+            }));
+        }
+        self.next_index += 1;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for VerifyingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.chunk.len() && !self.fill_chunk()? {
+            return Ok(0);
+        }
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_hasher_matches_whole_buffer_manifest() {
+        let data = content(CHUNK_SIZE as usize * 2 + 10);
+        let mut hasher = ChunkHasher::new();
+        for piece in data.chunks(300_001) {
+            hasher.update(piece);
+        }
+        let manifest = hasher.finish();
+        assert_eq!(manifest, ChunkManifest::compute(&data));
+        assert_eq!(manifest.checksums.len(), 3);
+        assert_eq!(manifest.object_size, data.len() as u64);
+        assert!(find_corrupted(&manifest, &data).is_empty());
+    }
+
+    #[test]
+    fn test_find_corrupted_reports_rotten_and_missing_chunks() {
+        let data = content(CHUNK_SIZE as usize * 3 + 5);
+        let manifest = ChunkManifest::compute(&data);
+
+        let mut rotten = data.clone();
+        rotten[CHUNK_SIZE as usize + 7] ^= 0xff;
+        assert_eq!(
+            find_corrupted(&manifest, &rotten),
+            vec![CorruptedRange {
+                chunk_index: 1,
+                offset: CHUNK_SIZE,
+                length: CHUNK_SIZE,
+            }]
+        );
+
+        let truncated = &data[..CHUNK_SIZE as usize * 2 + 1];
+        let ranges = find_corrupted(&manifest, truncated);
+        assert_eq!(
+            ranges.iter().map(|r| r.chunk_index).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(ranges[1].length, 5);
+    }
+
+    #[test]
+    fn test_verifying_reader_stops_before_corrupted_chunk() {
+        let data = content(CHUNK_SIZE as usize * 3);
+        let manifest = ChunkManifest::compute(&data);
+        let log = CorruptionLog::default();
+
+        let mut intact = Vec::new();
+        VerifyingReader::new(&data[..], "aa", manifest.clone(), log.clone())
+            .read_to_end(&mut intact)
+            .unwrap();
+        assert_eq!(intact, data);
+
+        let mut rotten = data.clone();
+        rotten[CHUNK_SIZE as usize * 2] ^= 0x01;
+        let mut reader = VerifyingReader::new(&rotten[..], "bb", manifest, log.clone());
+        let mut out = Vec::new();
+        let error = reader.read_to_end(&mut out).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let corruption = ChunkCorruption::from_io_error(&error).unwrap();
+        assert_eq!(corruption.range.chunk_index, 2);
+        // Only the verified chunks were handed out
+        assert_eq!(out, &data[..CHUNK_SIZE as usize * 2]);
+        assert_eq!(log.get("bb").unwrap(), vec![corruption.range]);
+        assert!(log.get("aa").is_none());
+    }
+}
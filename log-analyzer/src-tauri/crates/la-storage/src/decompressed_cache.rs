@@ -0,0 +1,329 @@
+//! Decompressed-view cache for gzip CAS objects
+//!
+//! Nested archives past the extraction depth limit (and `.gz` files whose
+//! extraction was skipped) are stored in CAS as raw gzip. Searching or
+//! previewing them means a full gunzip on every read, so this cache keeps
+//! decompressed copies on disk:
+//!
+//! ```text
+//! views/
+//!   <sha256 of the compressed object>
+//! ```
+//!
+//! - Entries are evicted least-recently-used once the total size exceeds the cap
+//! - The entry just written is never evicted by its own insertion, so a single
+//!   view larger than the cap is served once and dropped on the next insertion
+//! - The index is rebuilt from the directory on startup (oldest mtime first)
+//! - Objects that are not gzip are remembered so later lookups skip the header read
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, warn};
+
+use crate::cas::ContentAddressableStorage;
+use crate::chunk_checksums::ChunkCorruption;
+
+/// Default size cap for a workspace's decompressed views (1 GiB)
+pub const DEFAULT_VIEW_CACHE_BYTES: u64 = 1024 * 1024 * 1024;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// Snapshot of cache usage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewCacheStats {
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+struct ViewEntry {
+    size: u64,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct ViewIndex {
+    entries: HashMap<String, ViewEntry>,
+    /// Hashes known not to be gzip (CAS content is immutable per hash)
+    plain: HashSet<String>,
+    total_bytes: u64,
+    clock: u64,
+}
+
+impl ViewIndex {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+/// Size-capped, LRU on-disk cache of decompressed gzip objects
+pub struct DecompressedViewCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<ViewIndex>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl DecompressedViewCache {
+    /// Open (or create) the cache directory and rebuild the index from its contents
+    pub fn new(dir: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        let mut existing = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            // Leftovers from an interrupted decompression
+            if name.ends_with(TEMP_SUFFIX) {
+                let _ = fs::remove_file(entry.path());
+                continue;
+            }
+            existing.push((name, meta.len(), meta.modified().ok()));
+        }
+        existing.sort_by_key(|(_, _, modified)| *modified);
+
+        let mut index = ViewIndex::default();
+        for (hash, size, _) in existing {
+            let last_used = index.tick();
+            index.total_bytes += size;
+            index.entries.insert(hash, ViewEntry { size, last_used });
+        }
+
+        let cache = Self {
+            dir,
+            max_bytes,
+            index: Mutex::new(index),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        };
+        // The cap may have shrunk since the views were written
+        cache.evict_to_cap(None);
+        Ok(cache)
+    }
+
+    /// Path of the decompressed view of `hash`, decompressing on first use.
+    ///
+    /// Returns `Ok(None)` when the object is not gzip or is not valid gzip,
+    /// in which case callers should read the raw object.
+    pub fn view_path(
+        &self,
+        cas: &ContentAddressableStorage,
+        hash: &str,
+    ) -> io::Result<Option<PathBuf>> {
+        if !is_valid_hash(hash) {
+            return Ok(None);
+        }
+        let view_path = self.dir.join(hash);
+
+        {
+            let mut index = self.index.lock();
+            if index.plain.contains(hash) {
+                return Ok(None);
+            }
+            let now = index.tick();
+            if let Some(entry) = index.entries.get_mut(hash) {
+                if view_path.exists() {
+                    entry.last_used = now;
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(Some(view_path));
+                }
+                // Removed behind our back; rebuild it below
+                let size = entry.size;
+                index.entries.remove(hash);
+                index.total_bytes = index.total_bytes.saturating_sub(size);
+            }
+        }
+
+        let object_path = cas.get_object_path(hash);
+        if !is_gzip(&object_path)? {
+            self.index.lock().plain.insert(hash.to_string());
+            return Ok(None);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let object = cas
+            .open_verified_sync(hash)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let size = match self.decompress(object, &view_path) {
+            Ok(size) => size,
+            // Rotten chunk in the compressed object, not a format problem
+            Err(e) if ChunkCorruption::from_io_error(&e).is_some() => return Err(e),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                debug!(hash = %hash, error = %e, "Object has gzip magic but is not valid gzip");
+                self.index.lock().plain.insert(hash.to_string());
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+
+        {
+            let mut index = self.index.lock();
+            let last_used = index.tick();
+            // A concurrent reader may have inserted the same view already
+            if let Some(previous) = index
+                .entries
+                .insert(hash.to_string(), ViewEntry { size, last_used })
+            {
+                index.total_bytes = index.total_bytes.saturating_sub(previous.size);
+            }
+            index.total_bytes += size;
+        }
+        self.evict_to_cap(Some(hash));
+
+        Ok(Some(view_path))
+    }
+
+    /// Remove all views; returns the number of bytes freed
+    pub fn clear(&self) -> u64 {
+        let mut index = self.index.lock();
+        let mut freed = 0;
+        for (hash, entry) in index.entries.drain() {
+            if fs::remove_file(self.dir.join(&hash)).is_ok() {
+                freed += entry.size;
+            }
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        index.total_bytes = 0;
+        freed
+    }
+
+    pub fn stats(&self) -> ViewCacheStats {
+        let index = self.index.lock();
+        ViewCacheStats {
+            entries: index.entries.len(),
+            total_bytes: index.total_bytes,
+            max_bytes: self.max_bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Decompress into a temp file and rename it into place; returns the view size
+    fn decompress(&self, object: Box<dyn Read + Send>, view_path: &Path) -> io::Result<u64> {
+        let temp_path = self.dir.join(format!(
+            "{}.{}{TEMP_SUFFIX}",
+            view_path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            uuid::Uuid::new_v4().simple()
+        ));
+        let _guard = scopeguard::guard(temp_path.clone(), |p| {
+            let _ = fs::remove_file(p);
+        });
+
+        let mut decoder = flate2::read::MultiGzDecoder::new(io::BufReader::new(object));
+        let mut out = io::BufWriter::new(File::create(&temp_path)?);
+        let size = io::copy(&mut decoder, &mut out)?;
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temp_path, view_path)?;
+        Ok(size)
+    }
+
+    /// Evict least-recently-used views until under the cap, sparing `keep`
+    fn evict_to_cap(&self, keep: Option<&str>) {
+        let mut index = self.index.lock();
+        while index.total_bytes > self.max_bytes {
+            let Some(victim) = index
+                .entries
+                .iter()
+                .filter(|(hash, _)| Some(hash.as_str()) != keep)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(hash, _)| hash.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = index.entries.remove(&victim) {
+                index.total_bytes = index.total_bytes.saturating_sub(entry.size);
+            }
+            // On Windows a view still open by a reader cannot be removed; the
+            // orphan is picked up again on the next startup scan.
+            if let Err(e) = fs::remove_file(self.dir.join(&victim)) {
+                warn!(hash = %victim, error = %e, "Failed to remove evicted decompressed view");
+            }
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn is_valid_hash(hash: &str) -> bool {
+    !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_gzip(path: &Path) -> io::Result<bool> {
+    let mut magic = [0u8; 2];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == GZIP_MAGIC),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn gzip(content: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_view_cache_hits_and_evicts_lru() {
+        let temp = TempDir::new().unwrap();
+        let cas = ContentAddressableStorage::new(temp.path().to_path_buf());
+        let plain = cas.store_content(b"plain text\n").await.unwrap();
+        let a = cas.store_content(&gzip(&[b'a'; 600])).await.unwrap();
+        let b = cas.store_content(&gzip(&[b'b'; 600])).await.unwrap();
+
+        let cache = DecompressedViewCache::new(temp.path().join("views"), 1000).unwrap();
+        assert!(cache.view_path(&cas, &plain).unwrap().is_none());
+
+        let view_a = cache.view_path(&cas, &a).unwrap().unwrap();
+        assert_eq!(fs::read(&view_a).unwrap(), vec![b'a'; 600]);
+        assert_eq!(cache.view_path(&cas, &a).unwrap(), Some(view_a.clone()));
+
+        // Inserting b exceeds the cap and evicts a
+        let view_b = cache.view_path(&cas, &b).unwrap().unwrap();
+        assert!(view_b.exists());
+        assert!(!view_a.exists());
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.total_bytes, 600);
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 2, 1));
+
+        // Index survives a restart
+        drop(cache);
+        let cache = DecompressedViewCache::new(temp.path().join("views"), 1000).unwrap();
+        assert_eq!(cache.stats().total_bytes, 600);
+        assert_eq!(cache.clear(), 600);
+        assert!(!view_b.exists());
+    }
+}
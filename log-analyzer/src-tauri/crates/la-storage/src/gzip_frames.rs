@@ -0,0 +1,514 @@
+//! Random access into large gzip CAS objects
+//!
+//! Nested archives past the extraction depth limit (and `.gz` files whose
+//! extraction was skipped) are stored in CAS as raw gzip, and a gzip stream
+//! can only be decompressed from its start. zran-style access points resume
+//! inflate at an arbitrary bit offset with a primed 32 KiB window, which the
+//! pure-Rust deflate backend does not expose. Instead, large gzip objects get
+//! a *frame copy* at import: the same content recompressed as independent gzip
+//! members of about [`FRAME_BYTES`] each, every one starting at a line start,
+//! plus an index of where each frame begins:
+//!
+//! ```text
+//! frames/
+//!   <sha256 of the compressed object>        concatenated gzip members
+//!   <sha256 of the compressed object>.idx    FrameIndex::to_bytes
+//! ```
+//!
+//! - A time/offset-bounded scan seeks to the frame holding its start offset and
+//!   decompresses from there; fetching one line decompresses only its frame
+//! - The frames are written first and the index last, so an index on disk
+//!   always describes a complete frame file
+//! - A time index with one checkpoint per frame is built in the same pass,
+//!   since the object's own bytes are not text
+//! - Lines are counted like `split_lines`: `\r\n`, `\n` or a lone `\r`
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::{GzDecoder, MultiGzDecoder};
+use flate2::write::GzEncoder;
+use la_core::utils::time_index::line_timestamp;
+use la_core::utils::{split_lines, TimeIndex, TimeIndexPoint};
+use tracing::debug;
+
+use crate::cas::ContentAddressableStorage;
+use crate::chunk_checksums::ChunkCorruption;
+
+/// Uncompressed size of a frame before it is cut at the next line end
+pub const FRAME_BYTES: usize = 1024 * 1024;
+/// Compressed objects smaller than this are served by the decompressed view cache
+pub const MIN_FRAMED_OBJECT_BYTES: u64 = 1024 * 1024;
+/// A single line longer than this aborts framing (frames must start at a line)
+const MAX_FRAME_BYTES: usize = 64 * 1024 * 1024;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const INDEX_SUFFIX: &str = ".idx";
+const TEMP_SUFFIX: &str = ".tmp";
+const FORMAT_MAGIC: u8 = b'F';
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 12;
+const FRAME_LEN: usize = 24;
+
+/// Where a frame starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GzipFrame {
+    /// Offset of the frame's gzip member in the frame file
+    pub compressed_offset: u64,
+    /// Uncompressed offset of the frame's first byte
+    pub offset: u64,
+    /// 1-based line number of the frame's first line
+    pub line: u64,
+}
+
+/// Frame boundaries of one object, ordered by offset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameIndex {
+    uncompressed_len: u64,
+    frames: Vec<GzipFrame>,
+}
+
+impl FrameIndex {
+    pub fn uncompressed_len(&self) -> u64 {
+        self.uncompressed_len
+    }
+
+    pub fn frames(&self) -> &[GzipFrame] {
+        &self.frames
+    }
+
+    /// The frame holding uncompressed byte `offset`
+    pub fn frame_at_offset(&self, offset: u64) -> GzipFrame {
+        let count = self.frames.partition_point(|f| f.offset <= offset);
+        self.frames[count.saturating_sub(1)]
+    }
+
+    /// The frame holding 1-based `line`
+    pub fn frame_at_line(&self, line: u64) -> GzipFrame {
+        let count = self.frames.partition_point(|f| f.line <= line);
+        self.frames[count.saturating_sub(1)]
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.frames.len() * FRAME_LEN);
+        bytes.extend_from_slice(&[FORMAT_MAGIC, FORMAT_VERSION, 0, 0]);
+        bytes.extend_from_slice(&self.uncompressed_len.to_le_bytes());
+        for frame in &self.frames {
+            bytes.extend_from_slice(&frame.compressed_offset.to_le_bytes());
+            bytes.extend_from_slice(&frame.offset.to_le_bytes());
+            bytes.extend_from_slice(&frame.line.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Parse the output of [`Self::to_bytes`]; `None` when malformed
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_LEN
+            || bytes[0] != FORMAT_MAGIC
+            || bytes[1] != FORMAT_VERSION
+            || (bytes.len() - HEADER_LEN) % FRAME_LEN != 0
+        {
+            return None;
+        }
+        let word = |chunk: &[u8], at: usize| {
+            u64::from_le_bytes(chunk[at..at + 8].try_into().expect("8-byte slice"))
+        };
+        let frames: Vec<GzipFrame> = bytes[HEADER_LEN..]
+            .chunks_exact(FRAME_LEN)
+            .map(|chunk| GzipFrame {
+                compressed_offset: word(chunk, 0),
+                offset: word(chunk, 8),
+                line: word(chunk, 16),
+            })
+            .collect();
+        let ordered = frames.windows(2).all(|w| {
+            w[0].compressed_offset < w[1].compressed_offset
+                && w[0].offset < w[1].offset
+                && w[0].line < w[1].line
+        });
+        if frames.first() != Some(&FIRST_FRAME) || !ordered {
+            return None;
+        }
+        Some(Self {
+            uncompressed_len: word(bytes, 4),
+            frames,
+        })
+    }
+}
+
+const FIRST_FRAME: GzipFrame = GzipFrame {
+    compressed_offset: 0,
+    offset: 0,
+    line: 1,
+};
+
+/// Result of framing an object
+#[derive(Debug, Clone)]
+pub struct FramedObject {
+    pub index: FrameIndex,
+    /// One checkpoint per frame; `None` when the content has no timestamps,
+    /// is not UTF-8, or fits in a single frame
+    pub time_index: Option<TimeIndex>,
+}
+
+/// Frame copies of a workspace's large gzip objects
+pub struct GzipFrameStore {
+    dir: PathBuf,
+}
+
+impl GzipFrameStore {
+    /// The store next to a workspace's CAS objects
+    pub fn for_cas(cas: &ContentAddressableStorage) -> Self {
+        Self {
+            dir: cas.objects_dir().with_file_name("frames"),
+        }
+    }
+
+    /// Whether `hash` is a gzip object large enough to be worth framing
+    pub fn should_frame(cas: &ContentAddressableStorage, hash: &str) -> io::Result<bool> {
+        if cas.object_size_sync(hash) < MIN_FRAMED_OBJECT_BYTES {
+            return Ok(false);
+        }
+        is_gzip(&cas.get_object_path(hash))
+    }
+
+    /// Build the frame copy of gzip object `hash`.
+    ///
+    /// Returns `Ok(None)` when the object is not valid gzip or has a line
+    /// longer than the frame limit; no files are left behind in that case.
+    pub fn build(
+        &self,
+        cas: &ContentAddressableStorage,
+        hash: &str,
+    ) -> io::Result<Option<FramedObject>> {
+        if !is_gzip(&cas.get_object_path(hash))? {
+            return Ok(None);
+        }
+        let object = cas
+            .open_verified_sync(hash)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        fs::create_dir_all(&self.dir)?;
+        let frames_path = self.dir.join(hash);
+        let temp_path = self.dir.join(format!(
+            "{hash}.{}{TEMP_SUFFIX}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let _guard = scopeguard::guard(temp_path.clone(), |p| {
+            let _ = fs::remove_file(p);
+        });
+
+        let mut out = io::BufWriter::new(File::create(&temp_path)?);
+        let framed = match write_frames(MultiGzDecoder::new(BufReader::new(object)), &mut out) {
+            Ok(framed) => framed,
+            // Rotten chunk in the compressed object, not a format problem
+            Err(e) if ChunkCorruption::from_io_error(&e).is_some() => return Err(e),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::InvalidData
+                        | io::ErrorKind::InvalidInput
+                        | io::ErrorKind::UnexpectedEof
+                ) =>
+            {
+                debug!(hash = %hash, error = %e, "Gzip object cannot be framed");
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let Some(framed) = framed else {
+            debug!(hash = %hash, "Gzip object has a line longer than the frame limit");
+            return Ok(None);
+        };
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temp_path, &frames_path)?;
+        write_atomic(&self.index_path(hash), &framed.index.to_bytes())?;
+        Ok(Some(framed))
+    }
+
+    /// Frame index of `hash`, if it has a frame copy
+    pub fn index(&self, hash: &str) -> Option<FrameIndex> {
+        let bytes = fs::read(self.index_path(hash)).ok()?;
+        FrameIndex::from_bytes(&bytes)
+    }
+
+    /// Decompressed content of `hash` from uncompressed byte `offset` on,
+    /// decompressing only from the frame holding `offset`
+    pub fn open_at(&self, hash: &str, offset: u64) -> io::Result<Option<Box<dyn Read + Send>>> {
+        let Some(index) = self.index(hash) else {
+            return Ok(None);
+        };
+        let frame = index.frame_at_offset(offset);
+        let mut reader = MultiGzDecoder::new(self.open_frame_file(hash, frame)?);
+        io::copy(
+            &mut (&mut reader).take(offset - frame.offset),
+            &mut io::sink(),
+        )?;
+        Ok(Some(Box::new(reader)))
+    }
+
+    /// The decompressed frame holding 1-based `line` and the frame's first line number
+    pub fn read_frame_at_line(&self, hash: &str, line: u64) -> io::Result<Option<(u64, Vec<u8>)>> {
+        let Some(index) = self.index(hash) else {
+            return Ok(None);
+        };
+        let frame = index.frame_at_line(line);
+        let mut content = Vec::new();
+        GzDecoder::new(self.open_frame_file(hash, frame)?).read_to_end(&mut content)?;
+        Ok(Some((frame.line, content)))
+    }
+
+    /// Remove the frame copy of `hash`
+    pub fn remove(&self, hash: &str) {
+        let _ = fs::remove_file(self.index_path(hash));
+        let _ = fs::remove_file(self.dir.join(hash));
+    }
+
+    fn index_path(&self, hash: &str) -> PathBuf {
+        self.dir.join(format!("{hash}{INDEX_SUFFIX}"))
+    }
+
+    fn open_frame_file(&self, hash: &str, frame: GzipFrame) -> io::Result<BufReader<File>> {
+        let mut file = File::open(self.dir.join(hash))?;
+        file.seek(SeekFrom::Start(frame.compressed_offset))?;
+        Ok(BufReader::new(file))
+    }
+}
+
+fn is_gzip(path: &Path) -> io::Result<bool> {
+    let mut magic = [0u8; 2];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == GZIP_MAGIC),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let temp_path = path.with_extension(format!("idx{TEMP_SUFFIX}"));
+    fs::write(&temp_path, bytes)?;
+    fs::rename(&temp_path, path)
+}
+
+/// Accumulates the index and per-frame time checkpoints while frames are written
+struct FrameWriter {
+    frames: Vec<GzipFrame>,
+    points: Vec<TimeIndexPoint>,
+    compressed_len: u64,
+    uncompressed_len: u64,
+    next_line: u64,
+    first_timestamp: Option<i64>,
+    max_seen: Option<i64>,
+    utf8: bool,
+}
+
+impl FrameWriter {
+    fn write(&mut self, frame: &[u8], out: &mut impl Write) -> io::Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(frame)?;
+        let member = encoder.finish()?;
+        out.write_all(&member)?;
+
+        self.frames.push(GzipFrame {
+            compressed_offset: self.compressed_len,
+            offset: self.uncompressed_len,
+            line: self.next_line,
+        });
+        self.points.push(TimeIndexPoint {
+            line: self.next_line as usize,
+            offset: self.uncompressed_len,
+            max_before: self.max_seen,
+        });
+        match std::str::from_utf8(frame) {
+            Ok(text) if self.utf8 => {
+                for line in split_lines(text) {
+                    if let Some(ts) = line_timestamp(line) {
+                        self.first_timestamp.get_or_insert(ts);
+                        self.max_seen = Some(self.max_seen.map_or(ts, |m| m.max(ts)));
+                    }
+                }
+            }
+            _ => self.utf8 = false,
+        }
+        self.compressed_len += member.len() as u64;
+        self.uncompressed_len += frame.len() as u64;
+        self.next_line += count_lines(frame);
+        Ok(())
+    }
+}
+
+/// Recompress `content` into line-aligned frames; `None` when a line is
+/// longer than [`MAX_FRAME_BYTES`]
+fn write_frames(mut content: impl Read, out: &mut impl Write) -> io::Result<Option<FramedObject>> {
+    let mut writer = FrameWriter {
+        frames: Vec::new(),
+        points: Vec::new(),
+        compressed_len: 0,
+        uncompressed_len: 0,
+        next_line: 1,
+        first_timestamp: None,
+        max_seen: None,
+        utf8: true,
+    };
+    let mut pending = Vec::with_capacity(FRAME_BYTES * 2);
+    let mut buf = vec![0u8; 256 * 1024];
+    loop {
+        let read = content.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        pending.extend_from_slice(&buf[..read]);
+        while pending.len() >= FRAME_BYTES {
+            // Cut after the last line end so the next frame starts a line
+            let Some(end) = pending.iter().rposition(|&b| b == b'\n') else {
+                if pending.len() > MAX_FRAME_BYTES {
+                    return Ok(None);
+                }
+                break;
+            };
+            writer.write(&pending[..=end], out)?;
+            pending.drain(..=end);
+        }
+    }
+    if !pending.is_empty() || writer.frames.is_empty() {
+        writer.write(&pending, out)?;
+    }
+    out.flush()?;
+
+    let time_index = if writer.utf8 {
+        TimeIndex::from_points(writer.first_timestamp, writer.points)
+    } else {
+        None
+    };
+    Ok(Some(FramedObject {
+        index: FrameIndex {
+            uncompressed_len: writer.uncompressed_len,
+            frames: writer.frames,
+        },
+        time_index,
+    }))
+}
+
+/// Number of lines ended in `bytes`, plus one for a trailing unterminated line
+fn count_lines(bytes: &[u8]) -> u64 {
+    let mut lines = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'\n' => lines += 1,
+            b'\r' if bytes.get(i + 1) != Some(&b'\n') => lines += 1,
+            _ => {}
+        }
+    }
+    if bytes.last().is_some_and(|&b| b != b'\n' && b != b'\r') {
+        lines += 1;
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// `lines` timestamped lines, one second apart
+    fn sample_log(lines: usize) -> String {
+        (0..lines)
+            .map(|i| {
+                format!(
+                    "2024-01-01 {:02}:{:02}:{:02} INFO request {i} handled with padding\n",
+                    i / 3600,
+                    i / 60 % 60,
+                    i % 60
+                )
+            })
+            .collect()
+    }
+
+    fn gzip(content: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_count_lines_matches_split_lines() {
+        for text in ["a\nb\n", "a\r\nb", "a\rb\r\n\nc", "", "\n"] {
+            assert_eq!(
+                count_lines(text.as_bytes()),
+                split_lines(text).count() as u64,
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_frames_start_at_lines_and_round_trip() {
+        let content = sample_log(60_000);
+        let mut out = Vec::new();
+        let framed = write_frames(content.as_bytes(), &mut out).unwrap().unwrap();
+        let index = framed.index;
+        assert!(index.frames().len() > 2);
+        assert_eq!(index.uncompressed_len(), content.len() as u64);
+
+        // The frame file is one valid multi-member gzip stream
+        let mut all = String::new();
+        MultiGzDecoder::new(&out[..])
+            .read_to_string(&mut all)
+            .unwrap();
+        assert_eq!(all, content);
+
+        for frame in index.frames() {
+            let first = content[frame.offset as usize..].lines().next().unwrap();
+            assert_eq!(Some(first), content.lines().nth(frame.line as usize - 1));
+        }
+        assert_eq!(
+            FrameIndex::from_bytes(&index.to_bytes()),
+            Some(index.clone())
+        );
+        assert!(FrameIndex::from_bytes(b"garbage").is_none());
+
+        let time_index = framed.time_index.unwrap();
+        assert_eq!(time_index.points().len(), index.frames().len());
+        let target = line_timestamp("2024-01-01 10:00:00").unwrap();
+        assert!(time_index.seek(target).line > 1);
+    }
+
+    #[tokio::test]
+    async fn test_store_reads_from_offset_and_line() {
+        let temp = TempDir::new().unwrap();
+        let cas = ContentAddressableStorage::new(temp.path().to_path_buf());
+        let content = sample_log(60_000);
+        let hash = cas.store_content(&gzip(content.as_bytes())).await.unwrap();
+        let plain = cas.store_content(b"plain\n").await.unwrap();
+
+        assert!(!GzipFrameStore::should_frame(&cas, &plain).unwrap());
+        let store = GzipFrameStore::for_cas(&cas);
+        assert!(store.open_at(&hash, 0).unwrap().is_none());
+        let framed = store.build(&cas, &hash).unwrap().unwrap();
+        assert_eq!(store.index(&hash), Some(framed.index.clone()));
+
+        let offset = content.len() as u64 / 2;
+        let mut rest = Vec::new();
+        store
+            .open_at(&hash, offset)
+            .unwrap()
+            .unwrap()
+            .read_to_end(&mut rest)
+            .unwrap();
+        assert_eq!(rest, content.as_bytes()[offset as usize..]);
+
+        let (first_line, frame) = store.read_frame_at_line(&hash, 40_000).unwrap().unwrap();
+        let frame = String::from_utf8(frame).unwrap();
+        let wanted = content.lines().nth(40_000 - 1).unwrap();
+        assert_eq!(
+            frame.lines().nth((40_000 - first_line) as usize),
+            Some(wanted)
+        );
+        assert!(frame.len() < content.len() / 2);
+
+        assert!(store.build(&cas, &plain).unwrap().is_none());
+        assert!(store.index(&plain).is_none());
+        store.remove(&hash);
+        assert!(store.index(&hash).is_none());
+    }
+}
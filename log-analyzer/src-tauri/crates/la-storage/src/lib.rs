@@ -1,12 +1,24 @@
 // la-storage: CAS 内容寻址存储 + SQLite 元数据
 pub mod cas;
+pub mod chunk_checksums;
+pub mod decompressed_cache;
+pub mod gzip_frames;
 pub mod integrity;
 pub mod metadata_store;
 
 // 重新导出核心类型
-pub use cas::ContentAddressableStorage;
+pub use cas::{CasObjectWriter, ContentAddressableStorage};
+pub use chunk_checksums::{ChunkCorruption, ChunkManifest, CorruptedRange, CorruptionLog};
+pub use decompressed_cache::{DecompressedViewCache, ViewCacheStats, DEFAULT_VIEW_CACHE_BYTES};
+pub use gzip_frames::{FrameIndex, FramedObject, GzipFrame, GzipFrameStore};
 pub use integrity::{
     verify_after_import, verify_file_integrity, verify_workspace_integrity, InvalidFileInfo,
     ValidationReport,
 };
-pub use metadata_store::{ArchiveMetadata, FileMetadata, IndexState, IndexedFile, MetadataStore};
+pub use metadata_store::{
+    ArchiveMetadata, CoverageGap, CrashArtifact, DayOverview, DedupBucket, DedupReport,
+    FailedEntryRecord, FileFormat, FileMetadata, FileOverrides, FileOverview, FileSearchFlag,
+    FlaggedFile, IndexState, IndexedFile, LevelCounts, MetadataQueryResult, MetadataStore,
+    PostExtractRunRecord, QuarantinedEntryRecord, SkippedEntryRecord, SymlinkRecord,
+    WorkspaceMetadataEntry, WorkspaceOverview, METADATA_SCHEMA_VERSION,
+};
@@ -0,0 +1,98 @@
+//! Crash artifact catalog.
+//!
+//! Core dumps, minidumps and hprof heap dumps are recognized at import (see
+//! `la_core::utils::detect_crash_artifact`) and recorded per content hash
+//! instead of being indexed; the catalog joins them back to their virtual
+//! paths.
+
+use std::collections::HashMap;
+
+use la_core::error::{AppError, Result};
+use la_core::utils::{CrashArtifactInfo, CrashArtifactKind};
+use sqlx::{Row, SqlitePool};
+
+use super::types::CrashArtifact;
+
+/// Record the parsed artifact metadata for a content hash (UPSERT).
+pub(crate) async fn set_crash_artifact(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    info: &CrashArtifactInfo,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO crash_artifacts (sha256_hash, kind, build_id, crashed_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET
+            kind = excluded.kind,
+            build_id = excluded.build_id,
+            crashed_at = excluded.crashed_at
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(info.kind.as_str())
+    .bind(&info.build_id)
+    .bind(info.crashed_at)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store crash artifact: {e}")))?;
+
+    Ok(())
+}
+
+/// Artifact metadata keyed by content hash. Rows with an unknown kind
+/// (written by a newer version) are skipped.
+pub(crate) async fn get_crash_artifact_infos(
+    pool: &SqlitePool,
+) -> Result<HashMap<String, CrashArtifactInfo>> {
+    let rows = sqlx::query("SELECT sha256_hash, kind, build_id, crashed_at FROM crash_artifacts")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to load crash artifacts: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| Some((row.get("sha256_hash"), row_to_info(&row)?)))
+        .collect())
+}
+
+/// Every file whose content is a cataloged artifact, oldest crash first.
+/// Artifacts without a recorded crash time sort by file modification time.
+pub(crate) async fn get_crash_artifacts(pool: &SqlitePool) -> Result<Vec<CrashArtifact>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT f.virtual_path, f.sha256_hash, f.size, f.modified_time,
+               c.kind, c.build_id, c.crashed_at
+        FROM crash_artifacts c
+        JOIN files f ON f.sha256_hash = c.sha256_hash
+        ORDER BY COALESCE(c.crashed_at, f.modified_time), f.virtual_path
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to list crash artifacts: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(CrashArtifact {
+                virtual_path: row.get("virtual_path"),
+                sha256_hash: row.get("sha256_hash"),
+                size: row.get("size"),
+                modified_time: row.get("modified_time"),
+                info: row_to_info(&row)?,
+            })
+        })
+        .collect())
+}
+
+fn row_to_info(row: &sqlx::sqlite::SqliteRow) -> Option<CrashArtifactInfo> {
+    Some(CrashArtifactInfo {
+        kind: row
+            .get::<String, _>("kind")
+            .parse::<CrashArtifactKind>()
+            .ok()?,
+        build_id: row.get("build_id"),
+        crashed_at: row.get("crashed_at"),
+    })
+}
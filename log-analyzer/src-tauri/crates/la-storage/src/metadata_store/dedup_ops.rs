@@ -0,0 +1,138 @@
+//! CAS deduplication references and reporting.
+//!
+//! Every file insert also records `virtual_path -> sha256_hash` in
+//! `file_refs`, including paths whose content was already stored. The report
+//! is computed from those stored hashes; no content is re-read.
+
+use la_core::error::{AppError, Result};
+use la_core::storage_types::FileMetadata;
+use sqlx::{Row, SqliteExecutor, SqlitePool};
+
+use super::types::{DedupBucket, DedupReport, DuplicatedObject};
+
+/// Maximum virtual paths listed per duplicated object.
+const MAX_PATHS_PER_OBJECT: usize = 10;
+
+/// Record a logical file reference (UPSERT on virtual path).
+pub(crate) async fn record_file_ref<'e, E>(
+    executor: E,
+    metadata: &FileMetadata,
+    now: i64,
+) -> Result<()>
+where
+    E: SqliteExecutor<'e>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO file_refs (virtual_path, sha256_hash, size, created_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(virtual_path) DO UPDATE SET
+            sha256_hash = excluded.sha256_hash,
+            size = excluded.size
+        "#,
+    )
+    .bind(&metadata.virtual_path)
+    .bind(&metadata.sha256_hash)
+    .bind(metadata.size)
+    .bind(now)
+    .execute(executor)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to record file reference: {e}")))?;
+
+    Ok(())
+}
+
+/// Compute the dedup report; `top_n` limits the duplicated-object list.
+pub(crate) async fn get_dedup_report(pool: &SqlitePool, top_n: usize) -> Result<DedupReport> {
+    let totals = sqlx::query(
+        r#"
+        SELECT
+            COUNT(*) AS logical_files,
+            COALESCE(SUM(size), 0) AS logical_bytes,
+            COUNT(DISTINCT sha256_hash) AS unique_objects
+        FROM file_refs
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to compute dedup totals: {e}")))?;
+
+    let stored_bytes: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(size), 0) FROM (SELECT MAX(size) AS size FROM file_refs GROUP BY sha256_hash)",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to compute stored bytes: {e}")))?;
+
+    let distribution = sqlx::query(
+        r#"
+        SELECT ref_count, COUNT(*) AS objects
+        FROM (SELECT COUNT(*) AS ref_count FROM file_refs GROUP BY sha256_hash)
+        GROUP BY ref_count
+        ORDER BY ref_count
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to compute dedup distribution: {e}")))?
+    .into_iter()
+    .map(|r| DedupBucket {
+        ref_count: r.get("ref_count"),
+        objects: r.get("objects"),
+    })
+    .collect();
+
+    let top_rows = sqlx::query(
+        r#"
+        SELECT sha256_hash, MAX(size) AS size, COUNT(*) AS ref_count
+        FROM file_refs
+        GROUP BY sha256_hash
+        HAVING COUNT(*) > 1
+        ORDER BY (COUNT(*) - 1) * MAX(size) DESC, sha256_hash
+        LIMIT ?
+        "#,
+    )
+    .bind(top_n as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to list duplicated files: {e}")))?;
+
+    let mut top_duplicates = Vec::with_capacity(top_rows.len());
+    for row in top_rows {
+        let sha256_hash: String = row.get("sha256_hash");
+        let size: i64 = row.get("size");
+        let ref_count: i64 = row.get("ref_count");
+        let virtual_paths: Vec<String> = sqlx::query_scalar(
+            "SELECT virtual_path FROM file_refs WHERE sha256_hash = ? ORDER BY virtual_path LIMIT ?",
+        )
+        .bind(&sha256_hash)
+        .bind(MAX_PATHS_PER_OBJECT as i64)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to list duplicate paths: {e}")))?;
+
+        top_duplicates.push(DuplicatedObject {
+            bytes_saved: size * (ref_count - 1),
+            sha256_hash,
+            size,
+            ref_count,
+            virtual_paths,
+        });
+    }
+
+    let logical_bytes: i64 = totals.get("logical_bytes");
+    Ok(DedupReport {
+        logical_files: totals.get("logical_files"),
+        unique_objects: totals.get("unique_objects"),
+        logical_bytes,
+        stored_bytes,
+        bytes_saved: (logical_bytes - stored_bytes).max(0),
+        dedup_ratio: if stored_bytes > 0 {
+            logical_bytes as f64 / stored_bytes as f64
+        } else {
+            1.0
+        },
+        distribution,
+        top_duplicates,
+    })
+}
@@ -0,0 +1,98 @@
+//! Archive entries that failed to extract or import.
+//!
+//! Transient IO errors while unpacking or storing an entry do not abort the
+//! archive; the entry is recorded here against its parent archive so it can
+//! be retried later from the archive copy kept in CAS.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::FailedEntryRecord;
+
+/// Record `(entry_name, error)` pairs for an archive, replacing earlier errors
+/// for the same entries.
+pub(crate) async fn record_failed_entries(
+    pool: &SqlitePool,
+    archive_id: i64,
+    entries: &[(String, String)],
+) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let failed_at = chrono::Utc::now().timestamp();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to begin transaction: {e}")))?;
+
+    for (entry_name, error) in entries {
+        sqlx::query(
+            r#"
+            INSERT INTO failed_entries (archive_id, entry_name, error, failed_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(archive_id, entry_name) DO UPDATE SET
+                error = excluded.error,
+                failed_at = excluded.failed_at
+            "#,
+        )
+        .bind(archive_id)
+        .bind(entry_name)
+        .bind(error)
+        .bind(failed_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to record failed entry: {e}")))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to commit failed entries: {e}")))?;
+
+    Ok(())
+}
+
+/// All failed entries, grouped by archive, with the archive's virtual path.
+pub(crate) async fn get_failed_entries(pool: &SqlitePool) -> Result<Vec<FailedEntryRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT f.archive_id, a.virtual_path AS archive_virtual_path,
+               f.entry_name, f.error, f.failed_at
+        FROM failed_entries f
+        LEFT JOIN archives a ON a.id = f.archive_id
+        ORDER BY f.archive_id, f.entry_name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to get failed entries: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| FailedEntryRecord {
+            archive_id: r.get("archive_id"),
+            archive_virtual_path: r
+                .try_get::<Option<String>, _>("archive_virtual_path")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            entry_name: r.get("entry_name"),
+            error: r.get("error"),
+            failed_at: r.get("failed_at"),
+        })
+        .collect())
+}
+
+/// Forget a failed entry once it has been imported.
+pub(crate) async fn remove_failed_entry(
+    pool: &SqlitePool,
+    archive_id: i64,
+    entry_name: &str,
+) -> Result<()> {
+    sqlx::query("DELETE FROM failed_entries WHERE archive_id = ? AND entry_name = ?")
+        .bind(archive_id)
+        .bind(entry_name)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to remove failed entry: {e}")))?;
+    Ok(())
+}
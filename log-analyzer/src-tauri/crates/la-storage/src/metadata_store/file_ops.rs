@@ -4,11 +4,12 @@
 //! insert, query, update stats, batch operations, and FTS search.
 
 use la_core::error::{AppError, Result};
-use la_core::storage_types::FileMetadata;
+use la_core::storage_types::{FileMetadata, FileSearchFlag};
 use sqlx::{Row, SqlitePool};
 use tracing::{debug, info};
 
-use super::types::{parse_analysis_status, MAX_BATCH_SIZE};
+use super::dedup_ops;
+use super::types::{parse_analysis_status, FlaggedFile, MAX_BATCH_SIZE};
 
 /// Insert file metadata with CAS deduplication (within a transaction).
 pub(crate) async fn insert_file(pool: &SqlitePool, metadata: &FileMetadata) -> Result<i64> {
@@ -42,6 +43,7 @@ pub(crate) async fn insert_file(pool: &SqlitePool, metadata: &FileMetadata) -> R
     .execute(&mut *tx)
     .await
     .map_err(|e| AppError::database_error(format!("Failed to insert file: {e}")))?;
+    dedup_ops::record_file_ref(&mut *tx, metadata, chrono::Utc::now().timestamp()).await?;
 
     let id = sqlx::query_as::<_, (i64,)>("SELECT id FROM files WHERE sha256_hash = ? LIMIT 1")
         .bind(&metadata.sha256_hash)
@@ -165,6 +167,34 @@ pub(crate) async fn get_all_files(pool: &SqlitePool) -> Result<Vec<FileMetadata>
         .collect())
 }
 
+/// Files whose name (case-insensitive) is one of `names`, shallowest first.
+pub(crate) async fn get_files_by_name(
+    pool: &SqlitePool,
+    names: &[&str],
+) -> Result<Vec<FileMetadata>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let sql = format!(
+        "SELECT * FROM files WHERE LOWER(original_name) IN ({placeholders}) \
+         ORDER BY depth_level, LENGTH(virtual_path), virtual_path"
+    );
+    let mut query = sqlx::query(&sql);
+    for name in names {
+        query = query.bind(name.to_lowercase());
+    }
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to query files by name: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r: sqlx::sqlite::SqliteRow| row_to_file_metadata(&r))
+        .collect())
+}
+
 /// Count total files.
 pub(crate) async fn count_files(pool: &SqlitePool) -> Result<i64> {
     let row = sqlx::query("SELECT COUNT(*) as count FROM files")
@@ -252,6 +282,9 @@ pub(crate) async fn get_ready_files(pool: &SqlitePool) -> Result<Vec<FileMetadat
 }
 
 /// Get files with pruning filters (time range, level mask, file pattern).
+///
+/// This is the search scan list: ignored files are excluded and pinned files
+/// come first.
 pub(crate) async fn get_files_with_pruning(
     pool: &SqlitePool,
     time_start: Option<i64>,
@@ -261,19 +294,22 @@ pub(crate) async fn get_files_with_pruning(
 ) -> Result<Vec<FileMetadata>> {
     let requires_stats = time_start.is_some() || time_end.is_some() || level_mask.is_some();
     let mut sql = if requires_stats {
-        String::from("SELECT * FROM files WHERE analysis_status = 'READY'")
+        String::from(
+            "SELECT * FROM files WHERE search_flag != 'IGNORED' AND analysis_status = 'READY'",
+        )
     } else {
-        String::from("SELECT * FROM files WHERE 1=1")
+        String::from("SELECT * FROM files WHERE search_flag != 'IGNORED'")
     };
 
+    // 时间裁剪按校正后的时间比较（文件时钟偏移量加到统计范围上）
     if let (Some(_start), Some(_end)) = (time_start, time_end) {
         sql.push_str(
-            " AND (min_timestamp IS NULL OR max_timestamp IS NULL OR (min_timestamp <= ? AND max_timestamp >= ?))",
+            " AND (min_timestamp IS NULL OR max_timestamp IS NULL OR (min_timestamp + time_offset_secs <= ? AND max_timestamp + time_offset_secs >= ?))",
         );
     } else if let Some(_start) = time_start {
-        sql.push_str(" AND (max_timestamp IS NULL OR max_timestamp >= ?)");
+        sql.push_str(" AND (max_timestamp IS NULL OR max_timestamp + time_offset_secs >= ?)");
     } else if let Some(_end) = time_end {
-        sql.push_str(" AND (min_timestamp IS NULL OR min_timestamp <= ?)");
+        sql.push_str(" AND (min_timestamp IS NULL OR min_timestamp + time_offset_secs <= ?)");
     }
 
     if let Some(_mask) = level_mask {
@@ -284,7 +320,10 @@ pub(crate) async fn get_files_with_pruning(
         sql.push_str(" AND virtual_path GLOB ?");
     }
 
-    sql.push_str(" ORDER BY virtual_path");
+    // 哈希作为最后的比较键，保证同路径文件的顺序稳定（搜索结果顺序依赖于此）
+    sql.push_str(
+        " ORDER BY CASE WHEN search_flag = 'PINNED' THEN 0 ELSE 1 END, virtual_path, sha256_hash",
+    );
 
     let mut query = sqlx::query(&sql);
 
@@ -314,6 +353,94 @@ pub(crate) async fn get_files_with_pruning(
         .collect())
 }
 
+/// Set the search flag of a file.
+///
+/// Returns the previous flag, or `None` if no file has this virtual path.
+pub(crate) async fn set_file_search_flag(
+    pool: &SqlitePool,
+    virtual_path: &str,
+    flag: FileSearchFlag,
+) -> Result<Option<FileSearchFlag>> {
+    let previous = sqlx::query("SELECT search_flag FROM files WHERE virtual_path = ?")
+        .bind(virtual_path)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to query search flag: {e}")))?;
+
+    let Some(row) = previous else {
+        return Ok(None);
+    };
+    let previous: FileSearchFlag = row
+        .get::<String, _>("search_flag")
+        .parse()
+        .unwrap_or_default();
+
+    sqlx::query("UPDATE files SET search_flag = ? WHERE virtual_path = ?")
+        .bind(flag.as_str())
+        .bind(virtual_path)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to update search flag: {e}")))?;
+
+    Ok(Some(previous))
+}
+
+/// Get all files that are ignored or pinned, ordered by virtual path.
+pub(crate) async fn get_flagged_files(pool: &SqlitePool) -> Result<Vec<FlaggedFile>> {
+    let rows = sqlx::query(
+        "SELECT virtual_path, sha256_hash, search_flag FROM files WHERE search_flag != 'NORMAL' ORDER BY virtual_path",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to query flagged files: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| FlaggedFile {
+            virtual_path: r.get("virtual_path"),
+            sha256_hash: r.get("sha256_hash"),
+            flag: r
+                .get::<String, _>("search_flag")
+                .parse()
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Set the clock offset of a file (seconds added to its parsed timestamps).
+///
+/// Returns `false` if no file has this virtual path.
+pub(crate) async fn set_file_time_offset(
+    pool: &SqlitePool,
+    virtual_path: &str,
+    offset_secs: i64,
+) -> Result<bool> {
+    let result = sqlx::query("UPDATE files SET time_offset_secs = ? WHERE virtual_path = ?")
+        .bind(offset_secs)
+        .bind(virtual_path)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to update time offset: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Get non-zero clock offsets keyed by virtual path.
+pub(crate) async fn get_file_time_offsets(
+    pool: &SqlitePool,
+) -> Result<std::collections::HashMap<String, i64>> {
+    let rows =
+        sqlx::query("SELECT virtual_path, time_offset_secs FROM files WHERE time_offset_secs != 0")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to query time offsets: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| (r.get("virtual_path"), r.get("time_offset_secs")))
+        .collect())
+}
+
 /// Search files using FTS5.
 pub(crate) async fn search_files(pool: &SqlitePool, query: &str) -> Result<Vec<FileMetadata>> {
     let rows = sqlx::query(
@@ -381,6 +508,7 @@ pub(crate) async fn insert_files_batch(
         .execute(&mut *tx)
         .await
         .map_err(|e| AppError::database_error(format!("Failed to insert file: {e}")))?;
+        dedup_ops::record_file_ref(&mut *tx, &metadata, chrono::Utc::now().timestamp()).await?;
 
         let id = sqlx::query_as::<_, (i64,)>("SELECT id FROM files WHERE sha256_hash = ? LIMIT 1")
             .bind(&metadata.sha256_hash)
@@ -489,6 +617,9 @@ pub(crate) async fn insert_files_batch_optimized(
         .map_err(|e| AppError::database_error(format!("Failed to execute batch insert: {e}")))?;
 
     let ids: Vec<i64> = rows.iter().map(|row| row.get("id")).collect();
+    for metadata in &files {
+        dedup_ops::record_file_ref(&mut *tx, metadata, now).await?;
+    }
 
     tx.commit()
         .await
@@ -550,6 +681,160 @@ pub(crate) async fn clear_all_files(pool: &SqlitePool) -> Result<()> {
         }
     }
 
+    if let Err(e) = sqlx::query("DELETE FROM file_refs WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete file references: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM term_sketches WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete term sketches: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM time_indexes WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete time indexes: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM level_histograms WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete level histograms: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM data_quality WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete data quality: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM file_formats WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete file formats: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM crash_artifacts WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete crash artifacts: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM quarantined_entries WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete quarantined entries: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM skipped_entries WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete skipped entries: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM failed_entries WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete failed entries: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM entry_raw_names WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete raw entry names: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM post_extract_runs WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete post-extraction runs: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM workspace_metadata WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete workspace metadata: {e}"
+            )));
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM symlinks WHERE 1=1")
+        .execute(pool)
+        .await
+    {
+        if !e.to_string().contains("no such table") {
+            return Err(AppError::database_error(format!(
+                "Failed to delete symlinks: {e}"
+            )));
+        }
+    }
+
     info!("Cleared all files and archives");
     Ok(())
 }
@@ -594,6 +879,7 @@ pub(crate) async fn insert_file_tx(
     .execute(&mut **tx)
     .await
     .map_err(|e| AppError::database_error(format!("Failed to insert file in transaction: {e}")))?;
+    dedup_ops::record_file_ref(&mut **tx, metadata, chrono::Utc::now().timestamp()).await?;
 
     let id = sqlx::query_as::<_, (i64,)>("SELECT id FROM files WHERE sha256_hash = ? LIMIT 1")
         .bind(&metadata.sha256_hash)
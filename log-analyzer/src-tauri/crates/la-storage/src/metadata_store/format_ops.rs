@@ -0,0 +1,168 @@
+//! Per-content format fingerprints.
+//!
+//! The format detected at import (see `la_core::utils::fingerprint_content`)
+//! and an optional user override, keyed by content hash like data quality, so
+//! every virtual path sharing a CAS object shares them. CSV/TSV content also
+//! keeps its detected column mapping and an optional mapping override, stored
+//! as JSON. Encoding, timezone and parser overrides are plain text columns.
+
+use std::collections::HashMap;
+
+use la_core::error::{AppError, Result};
+use la_core::utils::{CsvMapping, FormatFingerprint, LogFormat};
+use sqlx::{Row, SqlitePool};
+
+use super::types::{FileFormat, FileOverrides};
+
+/// Store the detected format for a content hash, keeping any override (UPSERT).
+pub(crate) async fn set_format_fingerprint(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    fingerprint: &FormatFingerprint,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO file_formats (sha256_hash, format, confidence, detected_csv_mapping)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET
+            format = excluded.format,
+            confidence = excluded.confidence,
+            detected_csv_mapping = excluded.detected_csv_mapping
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(fingerprint.format.as_str())
+    .bind(fingerprint.confidence as f64)
+    .bind(mapping_json(fingerprint.csv_mapping.as_ref())?)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store format fingerprint: {e}")))?;
+
+    Ok(())
+}
+
+/// Set or clear (`None`) the user's format override for a content hash.
+///
+/// Content not fingerprinted yet is recorded as plain with zero confidence.
+pub(crate) async fn set_format_override(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    format: Option<LogFormat>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO file_formats (sha256_hash, format, confidence, override_format)
+        VALUES (?, 'plain', 0, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET
+            override_format = excluded.override_format
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(format.map(|f| f.as_str()))
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store format override: {e}")))?;
+
+    Ok(())
+}
+
+/// Set or clear (`None`) the user's CSV column mapping for a content hash.
+///
+/// Content not fingerprinted yet is recorded as plain with zero confidence.
+pub(crate) async fn set_csv_mapping_override(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    mapping: Option<&CsvMapping>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO file_formats (sha256_hash, format, confidence, csv_mapping_override)
+        VALUES (?, 'plain', 0, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET
+            csv_mapping_override = excluded.csv_mapping_override
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(mapping_json(mapping)?)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store CSV mapping: {e}")))?;
+
+    Ok(())
+}
+
+/// Replace all of the user's overrides (format, encoding, timezone, parser) for a
+/// content hash; `None` fields are cleared. The CSV mapping override is kept.
+///
+/// Content not fingerprinted yet is recorded as plain with zero confidence.
+pub(crate) async fn set_file_overrides(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    overrides: &FileOverrides,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO file_formats (sha256_hash, format, confidence, override_format,
+            encoding_override, timezone_override, parser_override)
+        VALUES (?, 'plain', 0, ?, ?, ?, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET
+            override_format = excluded.override_format,
+            encoding_override = excluded.encoding_override,
+            timezone_override = excluded.timezone_override,
+            parser_override = excluded.parser_override
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(overrides.format.map(|f| f.as_str()))
+    .bind(overrides.encoding.as_deref())
+    .bind(overrides.timezone.as_deref())
+    .bind(overrides.parser.as_deref())
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store file overrides: {e}")))?;
+
+    Ok(())
+}
+
+/// Formats of every fingerprinted content hash. Unknown format names (written
+/// by a newer version) are read as plain and unreadable mappings as absent.
+pub(crate) async fn get_file_formats(pool: &SqlitePool) -> Result<HashMap<String, FileFormat>> {
+    let rows = sqlx::query(
+        "SELECT sha256_hash, format, confidence, override_format, detected_csv_mapping, \
+             csv_mapping_override, encoding_override, timezone_override, parser_override \
+             FROM file_formats",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to load file formats: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let format = FileFormat {
+                detected: row.get::<String, _>("format").parse().unwrap_or_default(),
+                confidence: row.get::<f64, _>("confidence") as f32,
+                override_format: row
+                    .get::<Option<String>, _>("override_format")
+                    .and_then(|f| f.parse().ok()),
+                detected_csv_mapping: parse_mapping(row.get("detected_csv_mapping")),
+                csv_mapping_override: parse_mapping(row.get("csv_mapping_override")),
+                encoding_override: row.get("encoding_override"),
+                timezone_override: row.get("timezone_override"),
+                parser_override: row.get("parser_override"),
+            };
+            (row.get("sha256_hash"), format)
+        })
+        .collect())
+}
+
+fn mapping_json(mapping: Option<&CsvMapping>) -> Result<Option<String>> {
+    mapping
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| AppError::database_error(format!("Failed to serialize CSV mapping: {e}")))
+}
+
+fn parse_mapping(json: Option<String>) -> Option<CsvMapping> {
+    json.and_then(|j| serde_json::from_str(&j).ok())
+}
@@ -13,16 +13,21 @@ use super::types::{IndexState, IndexedFile};
 pub(crate) async fn save_index_state(pool: &SqlitePool, state: &IndexState) -> Result<()> {
     sqlx::query(
         r#"
-        INSERT INTO index_state (workspace_id, last_commit_time, index_version)
-        VALUES (?, ?, ?)
+        INSERT INTO index_state
+            (workspace_id, last_commit_time, index_version, schema_version, app_version)
+        VALUES (?, ?, ?, ?, ?)
         ON CONFLICT(workspace_id) DO UPDATE SET
             last_commit_time = excluded.last_commit_time,
-            index_version = excluded.index_version
+            index_version = excluded.index_version,
+            schema_version = excluded.schema_version,
+            app_version = excluded.app_version
         "#,
     )
     .bind(&state.workspace_id)
     .bind(state.last_commit_time)
     .bind(state.index_version)
+    .bind(state.schema_version)
+    .bind(&state.app_version)
     .execute(pool)
     .await
     .map_err(|e| AppError::database_error(format!("Failed to save index state: {e}")))?;
@@ -42,17 +47,21 @@ pub(crate) async fn load_index_state(
     pool: &SqlitePool,
     workspace_id: &str,
 ) -> Result<Option<IndexState>> {
-    let row =
-        sqlx::query("SELECT workspace_id, last_commit_time, index_version FROM index_state WHERE workspace_id = ?")
-            .bind(workspace_id)
-            .fetch_optional(pool)
-            .await
-            .map_err(|e| AppError::database_error(format!("Failed to load index state: {e}")))?;
+    let row = sqlx::query(
+        "SELECT workspace_id, last_commit_time, index_version, schema_version, app_version \
+         FROM index_state WHERE workspace_id = ?",
+    )
+    .bind(workspace_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to load index state: {e}")))?;
 
     Ok(row.map(|r: sqlx::sqlite::SqliteRow| IndexState {
         workspace_id: r.get("workspace_id"),
         last_commit_time: r.get("last_commit_time"),
         index_version: r.get("index_version"),
+        schema_version: r.get("schema_version"),
+        app_version: r.get("app_version"),
     }))
 }
 
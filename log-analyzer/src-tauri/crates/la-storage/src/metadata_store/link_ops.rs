@@ -0,0 +1,52 @@
+//! Symlink metadata operations.
+//!
+//! Directory imports running with the `record_as_metadata` symlink policy do
+//! not follow links; they store the link location and its target here.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::SymlinkRecord;
+
+/// Record a symlink (UPSERT on virtual path).
+pub(crate) async fn record_symlink(pool: &SqlitePool, record: &SymlinkRecord) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO symlinks (virtual_path, target, within_root, created_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(virtual_path) DO UPDATE SET
+            target = excluded.target,
+            within_root = excluded.within_root,
+            created_at = excluded.created_at
+        "#,
+    )
+    .bind(&record.virtual_path)
+    .bind(&record.target)
+    .bind(record.within_root)
+    .bind(record.created_at)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to record symlink: {e}")))?;
+
+    Ok(())
+}
+
+/// Get all recorded symlinks, ordered by virtual path.
+pub(crate) async fn get_symlinks(pool: &SqlitePool) -> Result<Vec<SymlinkRecord>> {
+    let rows = sqlx::query(
+        "SELECT virtual_path, target, within_root, created_at FROM symlinks ORDER BY virtual_path",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to get symlinks: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| SymlinkRecord {
+            virtual_path: r.get("virtual_path"),
+            target: r.get("target"),
+            within_root: r.get("within_root"),
+            created_at: r.get("created_at"),
+        })
+        .collect())
+}
@@ -5,17 +5,51 @@
 //!
 //! ## Module structure
 //!
-//! - `types` — shared type definitions (IndexState, IndexedFile)
+//! - `types` — shared type definitions (IndexState, IndexedFile, FlaggedFile)
 //! - `schema` — database schema initialization and migrations
 //! - `file_ops` — file metadata CRUD operations
 //! - `archive_ops` — archive metadata CRUD operations
 //! - `index_ops` — incremental indexing state management
+//! - `dedup_ops` — logical file references and dedup reporting
+//! - `link_ops` — symlinks recorded during directory import
+//! - `quarantine_ops` — archive entries quarantined for path traversal
+//! - `skip_ops` — archive entries left out by selective extraction
+//! - `failure_ops` — archive entries that failed to extract, kept for retry
+//! - `raw_name_ops` — raw bytes of archive entry names decoded from GBK / Shift-JIS
+//! - `workspace_meta_ops` — workspace key/value metadata (bundle manifests)
+//! - `settings_ops` — per-workspace settings that survive re-imports
+//! - `secret_ops` — per-workspace secrets, hidden from the query console
+//! - `owner_ops` — the tenant that owns the workspace
+//! - `post_extract_ops` — results of post-extraction hooks
+//! - `sketch_ops` — per-content term sketches for query-time file skipping
+//! - `time_index_ops` — per-content sparse time indexes for seeking by time
+//! - `format_ops` — per-content format fingerprints and user overrides
+//! - `query_ops` — read-only ad-hoc SQL for the query console
+//! - `artifact_ops` — catalog of crash artifacts (core dumps, minidumps, hprof)
 
 mod archive_ops;
+mod artifact_ops;
+mod dedup_ops;
+mod failure_ops;
 mod file_ops;
+mod format_ops;
 mod index_ops;
+mod link_ops;
+mod overview_ops;
+mod owner_ops;
+mod post_extract_ops;
+mod quality_ops;
+mod quarantine_ops;
+mod query_ops;
+mod raw_name_ops;
 mod schema;
+mod secret_ops;
+mod settings_ops;
+mod sketch_ops;
+mod skip_ops;
+mod time_index_ops;
 mod types;
+mod workspace_meta_ops;
 
 use async_trait::async_trait;
 use la_core::error::{AppError, Result};
@@ -28,8 +62,19 @@ use std::time::Duration;
 use tracing::info;
 
 // ── Re-exports ──
-pub use la_core::storage_types::AnalysisStatus;
-pub use types::{IndexState, IndexedFile};
+pub use la_core::storage_types::{AnalysisStatus, FileSearchFlag};
+pub use types::{
+    CoverageGap, CrashArtifact, DayOverview, DedupBucket, DedupReport, DuplicatedObject,
+    FailedEntryRecord, FileFormat, FileOverrides, FileOverview, FlaggedFile, IndexState,
+    IndexedFile, LevelCounts, MetadataQueryResult, PostExtractRunRecord, QuarantinedEntryRecord,
+    SkippedEntryRecord, SymlinkRecord, WorkspaceMetadataEntry, WorkspaceOverview,
+};
+
+/// Latest metadata schema version (the highest `migrate_schema_vN` applied on open).
+///
+/// Recorded in [`IndexState::schema_version`] so an older app can tell that a
+/// workspace was written by a newer one.
+pub const METADATA_SCHEMA_VERSION: i32 = 24;
 
 /// SQLite metadata store manager.
 ///
@@ -37,6 +82,8 @@ pub use types::{IndexState, IndexedFile};
 /// For the trait-based interface, see the `MetadataStorage` impl below.
 pub struct MetadataStore {
     pub(crate) pool: SqlitePool,
+    /// Tenant that owns the workspace; `None` for local (unscoped) workspaces
+    tenant: Option<String>,
 }
 
 impl MetadataStore {
@@ -87,8 +134,58 @@ impl MetadataStore {
         schema::init_schema(&pool).await?;
         schema::migrate_schema_v2(&pool).await?;
         schema::migrate_schema_v3(&pool).await?;
+        schema::migrate_schema_v4(&pool).await?;
+        schema::migrate_schema_v5(&pool).await?;
+        schema::migrate_schema_v6(&pool).await?;
+        schema::migrate_schema_v7(&pool).await?;
+        schema::migrate_schema_v8(&pool).await?;
+        schema::migrate_schema_v9(&pool).await?;
+        schema::migrate_schema_v10(&pool).await?;
+        schema::migrate_schema_v11(&pool).await?;
+        schema::migrate_schema_v12(&pool).await?;
+        schema::migrate_schema_v13(&pool).await?;
+        schema::migrate_schema_v14(&pool).await?;
+        schema::migrate_schema_v15(&pool).await?;
+        schema::migrate_schema_v16(&pool).await?;
+        schema::migrate_schema_v17(&pool).await?;
+        schema::migrate_schema_v18(&pool).await?;
+        schema::migrate_schema_v19(&pool).await?;
+        schema::migrate_schema_v20(&pool).await?;
+        schema::migrate_schema_v21(&pool).await?;
+        schema::migrate_schema_v22(&pool).await?;
+        schema::migrate_schema_v23(&pool).await?;
+        schema::migrate_schema_v24(&pool).await?;
+
+        let tenant = owner_ops::get_owner(&pool).await?;
+        Ok(Self { pool, tenant })
+    }
+
+    /// Open the metadata store of a workspace in `tenant`'s namespace.
+    ///
+    /// The first scoped open records `tenant` as the owner. Opening a workspace
+    /// owned by another tenant, or an owned workspace without a tenant, fails
+    /// with a security error so a misplaced or copied database is never served
+    /// across tenants.
+    pub async fn new_scoped(workspace_dir: &Path, tenant: Option<&str>) -> Result<Self> {
+        let mut store = Self::new(workspace_dir).await?;
+        let owner = match tenant {
+            Some(tenant) => Some(owner_ops::claim_owner(&store.pool, tenant).await?),
+            None => store.tenant.clone(),
+        };
+        if owner.as_deref() != tenant {
+            store.pool.close().await;
+            return Err(AppError::security_error(format!(
+                "Workspace at {} belongs to a different tenant",
+                workspace_dir.display()
+            )));
+        }
+        store.tenant = owner;
+        Ok(store)
+    }
 
-        Ok(Self { pool })
+    /// Tenant that owns the workspace; `None` for local workspaces.
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
     }
 
     /// Close the database and perform WAL checkpoint.
@@ -112,6 +209,11 @@ impl MetadataStore {
         file_ops::get_file_by_virtual_path(&self.pool, virtual_path).await
     }
 
+    /// Files whose name (case-insensitive) is one of `names`, shallowest first.
+    pub async fn get_files_by_name(&self, names: &[&str]) -> Result<Vec<FileMetadata>> {
+        file_ops::get_files_by_name(&self.pool, names).await
+    }
+
     pub async fn get_file_by_hash(&self, hash: &str) -> Result<Option<FileMetadata>> {
         file_ops::get_file_by_hash(&self.pool, hash).await
     }
@@ -192,6 +294,26 @@ impl MetadataStore {
             .await
     }
 
+    pub async fn set_file_search_flag(
+        &self,
+        virtual_path: &str,
+        flag: FileSearchFlag,
+    ) -> Result<Option<FileSearchFlag>> {
+        file_ops::set_file_search_flag(&self.pool, virtual_path, flag).await
+    }
+
+    pub async fn get_flagged_files(&self) -> Result<Vec<FlaggedFile>> {
+        file_ops::get_flagged_files(&self.pool).await
+    }
+
+    pub async fn set_file_time_offset(&self, virtual_path: &str, offset_secs: i64) -> Result<bool> {
+        file_ops::set_file_time_offset(&self.pool, virtual_path, offset_secs).await
+    }
+
+    pub async fn get_file_time_offsets(&self) -> Result<std::collections::HashMap<String, i64>> {
+        file_ops::get_file_time_offsets(&self.pool).await
+    }
+
     pub async fn search_files(&self, query: &str) -> Result<Vec<FileMetadata>> {
         file_ops::search_files(&self.pool, query).await
     }
@@ -271,6 +393,277 @@ impl MetadataStore {
     pub async fn clear_indexed_files(&self, workspace_id: &str) -> Result<()> {
         index_ops::clear_indexed_files(&self.pool, workspace_id).await
     }
+
+    // ── Symlink operations (delegated to link_ops) ──
+
+    pub async fn record_symlink(&self, record: &SymlinkRecord) -> Result<()> {
+        link_ops::record_symlink(&self.pool, record).await
+    }
+
+    pub async fn get_symlinks(&self) -> Result<Vec<SymlinkRecord>> {
+        link_ops::get_symlinks(&self.pool).await
+    }
+
+    // ── Dedup reporting (delegated to dedup_ops) ──
+
+    pub async fn get_dedup_report(&self, top_n: usize) -> Result<DedupReport> {
+        dedup_ops::get_dedup_report(&self.pool, top_n).await
+    }
+
+    // ── Quarantine operations (delegated to quarantine_ops) ──
+
+    pub async fn record_quarantined_entry(&self, record: &QuarantinedEntryRecord) -> Result<i64> {
+        quarantine_ops::record_quarantined_entry(&self.pool, record).await
+    }
+
+    pub async fn get_quarantined_entries(&self, since: i64) -> Result<Vec<QuarantinedEntryRecord>> {
+        quarantine_ops::get_quarantined_entries(&self.pool, since).await
+    }
+
+    // ── Skipped entries (delegated to skip_ops) ──
+
+    pub async fn record_skipped_entries(
+        &self,
+        archive_id: i64,
+        entries: &[(String, i64)],
+    ) -> Result<()> {
+        skip_ops::record_skipped_entries(&self.pool, archive_id, entries).await
+    }
+
+    pub async fn get_skipped_entries(&self) -> Result<Vec<SkippedEntryRecord>> {
+        skip_ops::get_skipped_entries(&self.pool).await
+    }
+
+    // ── Failed entries (delegated to failure_ops) ──
+
+    pub async fn record_failed_entries(
+        &self,
+        archive_id: i64,
+        entries: &[(String, String)],
+    ) -> Result<()> {
+        failure_ops::record_failed_entries(&self.pool, archive_id, entries).await
+    }
+
+    pub async fn get_failed_entries(&self) -> Result<Vec<FailedEntryRecord>> {
+        failure_ops::get_failed_entries(&self.pool).await
+    }
+
+    pub async fn remove_failed_entry(&self, archive_id: i64, entry_name: &str) -> Result<()> {
+        failure_ops::remove_failed_entry(&self.pool, archive_id, entry_name).await
+    }
+
+    // ── Raw entry names (delegated to raw_name_ops) ──
+
+    pub async fn record_raw_entry_names(
+        &self,
+        archive_id: i64,
+        entries: &[(String, Vec<u8>)],
+    ) -> Result<()> {
+        raw_name_ops::record_raw_entry_names(&self.pool, archive_id, entries).await
+    }
+
+    pub async fn get_raw_entry_name(
+        &self,
+        archive_id: i64,
+        entry_name: &str,
+    ) -> Result<Option<Vec<u8>>> {
+        raw_name_ops::get_raw_entry_name(&self.pool, archive_id, entry_name).await
+    }
+
+    // ── Workspace metadata (delegated to workspace_meta_ops) ──
+
+    pub async fn set_workspace_metadata(
+        &self,
+        entries: &[(String, String)],
+        source: &str,
+    ) -> Result<()> {
+        workspace_meta_ops::set_workspace_metadata(&self.pool, entries, source).await
+    }
+
+    pub async fn get_workspace_metadata(&self) -> Result<Vec<WorkspaceMetadataEntry>> {
+        workspace_meta_ops::get_workspace_metadata(&self.pool).await
+    }
+
+    // ── Query console (delegated to query_ops) ──
+
+    /// Run user-provided SQL on a separate read-only connection.
+    ///
+    /// Anything other than reading is rejected by an authorizer; at most
+    /// `max_rows` rows are returned and the query is interrupted after `timeout`.
+    pub async fn run_read_only_query(
+        &self,
+        sql: &str,
+        max_rows: usize,
+        timeout: Duration,
+    ) -> Result<MetadataQueryResult> {
+        query_ops::run_read_only_query(&self.pool, sql, max_rows, timeout).await
+    }
+
+    // ── Workspace settings (delegated to settings_ops) ──
+
+    pub async fn get_workspace_setting(&self, key: &str) -> Result<Option<String>> {
+        settings_ops::get_workspace_setting(&self.pool, key).await
+    }
+
+    pub async fn set_workspace_setting(&self, key: &str, value: &str) -> Result<()> {
+        settings_ops::set_workspace_setting(&self.pool, key, value).await
+    }
+
+    // ── Workspace secrets (delegated to secret_ops) ──
+
+    pub async fn get_workspace_secret(&self, key: &str) -> Result<Option<String>> {
+        secret_ops::get_workspace_secret(&self.pool, key).await
+    }
+
+    pub async fn set_workspace_secret(&self, key: &str, value: &str) -> Result<()> {
+        secret_ops::set_workspace_secret(&self.pool, key, value).await
+    }
+
+    pub async fn delete_workspace_secret(&self, key: &str) -> Result<bool> {
+        secret_ops::delete_workspace_secret(&self.pool, key).await
+    }
+
+    // ── Post-extraction hook runs (delegated to post_extract_ops) ──
+
+    pub async fn record_post_extract_runs(
+        &self,
+        archive_id: i64,
+        runs: &[(String, i64, Option<String>)],
+    ) -> Result<()> {
+        post_extract_ops::record_post_extract_runs(&self.pool, archive_id, runs).await
+    }
+
+    pub async fn get_post_extract_runs(&self) -> Result<Vec<PostExtractRunRecord>> {
+        post_extract_ops::get_post_extract_runs(&self.pool).await
+    }
+
+    // ── Term sketches (delegated to sketch_ops) ──
+
+    pub async fn set_term_sketch(&self, sha256_hash: &str, sketch: &[u8]) -> Result<()> {
+        sketch_ops::set_term_sketch(&self.pool, sha256_hash, sketch).await
+    }
+
+    pub async fn delete_term_sketch(&self, sha256_hash: &str) -> Result<()> {
+        sketch_ops::delete_term_sketch(&self.pool, sha256_hash).await
+    }
+
+    pub async fn get_term_sketches(
+        &self,
+        hashes: &[String],
+    ) -> Result<std::collections::HashMap<String, Vec<u8>>> {
+        sketch_ops::get_term_sketches(&self.pool, hashes).await
+    }
+
+    // ── Time indexes (delegated to time_index_ops) ──
+
+    pub async fn set_time_index(&self, sha256_hash: &str, time_index: &[u8]) -> Result<()> {
+        time_index_ops::set_time_index(&self.pool, sha256_hash, time_index).await
+    }
+
+    pub async fn delete_time_index(&self, sha256_hash: &str) -> Result<()> {
+        time_index_ops::delete_time_index(&self.pool, sha256_hash).await
+    }
+
+    pub async fn get_time_indexes(
+        &self,
+        hashes: &[String],
+    ) -> Result<std::collections::HashMap<String, Vec<u8>>> {
+        time_index_ops::get_time_indexes(&self.pool, hashes).await
+    }
+
+    // ── Data quality (delegated to quality_ops) ──
+
+    pub async fn set_data_quality(
+        &self,
+        sha256_hash: &str,
+        quality: &la_core::utils::DataQuality,
+    ) -> Result<()> {
+        quality_ops::set_data_quality(&self.pool, sha256_hash, quality).await
+    }
+
+    /// Content hashes with invalid UTF-8, NUL bytes, bare CR or mixed line endings
+    pub async fn get_data_quality_warnings(
+        &self,
+    ) -> Result<std::collections::HashMap<String, la_core::utils::DataQuality>> {
+        quality_ops::get_data_quality_warnings(&self.pool).await
+    }
+
+    // ── Format fingerprints (delegated to format_ops) ──
+
+    pub async fn set_format_fingerprint(
+        &self,
+        sha256_hash: &str,
+        fingerprint: &la_core::utils::FormatFingerprint,
+    ) -> Result<()> {
+        format_ops::set_format_fingerprint(&self.pool, sha256_hash, fingerprint).await
+    }
+
+    /// Override the detected format of a content hash; `None` restores detection
+    pub async fn set_format_override(
+        &self,
+        sha256_hash: &str,
+        format: Option<la_core::utils::LogFormat>,
+    ) -> Result<()> {
+        format_ops::set_format_override(&self.pool, sha256_hash, format).await
+    }
+
+    /// Override the detected CSV column mapping of a content hash; `None` restores detection
+    pub async fn set_csv_mapping_override(
+        &self,
+        sha256_hash: &str,
+        mapping: Option<&la_core::utils::CsvMapping>,
+    ) -> Result<()> {
+        format_ops::set_csv_mapping_override(&self.pool, sha256_hash, mapping).await
+    }
+
+    /// Replace the format, encoding, timezone and parser overrides of a content hash
+    pub async fn set_file_overrides(
+        &self,
+        sha256_hash: &str,
+        overrides: &FileOverrides,
+    ) -> Result<()> {
+        format_ops::set_file_overrides(&self.pool, sha256_hash, overrides).await
+    }
+
+    pub async fn get_file_formats(&self) -> Result<std::collections::HashMap<String, FileFormat>> {
+        format_ops::get_file_formats(&self.pool).await
+    }
+
+    // ── Crash artifacts (delegated to artifact_ops) ──
+
+    pub async fn set_crash_artifact(
+        &self,
+        sha256_hash: &str,
+        info: &la_core::utils::CrashArtifactInfo,
+    ) -> Result<()> {
+        artifact_ops::set_crash_artifact(&self.pool, sha256_hash, info).await
+    }
+
+    /// Artifact metadata keyed by content hash (for the virtual tree)
+    pub async fn get_crash_artifact_infos(
+        &self,
+    ) -> Result<std::collections::HashMap<String, la_core::utils::CrashArtifactInfo>> {
+        artifact_ops::get_crash_artifact_infos(&self.pool).await
+    }
+
+    /// Every imported file whose content is a cataloged crash artifact
+    pub async fn get_crash_artifacts(&self) -> Result<Vec<CrashArtifact>> {
+        artifact_ops::get_crash_artifacts(&self.pool).await
+    }
+
+    // ── Workspace overview (delegated to overview_ops) ──
+
+    pub async fn set_level_histogram(
+        &self,
+        sha256_hash: &str,
+        counts: &[la_core::utils::DailyLevelCount],
+    ) -> Result<()> {
+        overview_ops::set_level_histogram(&self.pool, sha256_hash, counts).await
+    }
+
+    pub async fn get_workspace_overview(&self) -> Result<WorkspaceOverview> {
+        overview_ops::get_workspace_overview(&self.pool).await
+    }
 }
 
 // ── Static transaction helpers ──
@@ -0,0 +1,159 @@
+//! Workspace overview: level counts and time coverage.
+//!
+//! Each CAS object gets a `(day, level) -> lines` histogram when it is
+//! imported (`level_histograms`, keyed by content hash like term sketches).
+//! The overview sums those histograms over the logical files, so it never
+//! touches file content. File time ranges include the per-file clock offset;
+//! day buckets use the timestamps as written.
+
+use chrono::NaiveDate;
+use la_core::error::{AppError, Result};
+use la_core::utils::DailyLevelCount;
+use sqlx::{Row, SqlitePool};
+
+use super::types::{CoverageGap, DayOverview, FileOverview, LevelCounts, WorkspaceOverview};
+
+/// Replace the histogram stored for a content hash.
+pub(crate) async fn set_level_histogram(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    counts: &[DailyLevelCount],
+) -> Result<()> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to begin transaction: {e}")))?;
+
+    sqlx::query("DELETE FROM level_histograms WHERE sha256_hash = ?")
+        .bind(sha256_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to clear level histogram: {e}")))?;
+
+    for count in counts {
+        sqlx::query(
+            "INSERT INTO level_histograms (sha256_hash, day, level, count) VALUES (?, ?, ?, ?)",
+        )
+        .bind(sha256_hash)
+        .bind(&count.day)
+        .bind(count.level)
+        .bind(count.count)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to store level histogram: {e}")))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to commit level histogram: {e}")))?;
+
+    Ok(())
+}
+
+/// Aggregate the overview for every file in the workspace.
+pub(crate) async fn get_workspace_overview(pool: &SqlitePool) -> Result<WorkspaceOverview> {
+    let file_rows = sqlx::query(
+        r#"
+        SELECT
+            f.virtual_path,
+            f.min_timestamp + f.time_offset_secs AS min_timestamp,
+            f.max_timestamp + f.time_offset_secs AS max_timestamp,
+            h.level,
+            SUM(h.count) AS lines
+        FROM files f
+        LEFT JOIN level_histograms h ON h.sha256_hash = f.sha256_hash
+        GROUP BY f.virtual_path, h.level
+        ORDER BY f.virtual_path
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to compute file overview: {e}")))?;
+
+    let mut overview = WorkspaceOverview::default();
+    for row in file_rows {
+        let virtual_path: String = row.get("virtual_path");
+        if overview.files.last().map(|f| &f.virtual_path) != Some(&virtual_path) {
+            let min_timestamp: Option<i64> = row.get("min_timestamp");
+            let max_timestamp: Option<i64> = row.get("max_timestamp");
+            overview.earliest_timestamp = min_option(overview.earliest_timestamp, min_timestamp);
+            overview.latest_timestamp = overview.latest_timestamp.max(max_timestamp);
+            overview.files.push(FileOverview {
+                virtual_path,
+                min_timestamp,
+                max_timestamp,
+                counts: LevelCounts::default(),
+            });
+        }
+        if let Some(level) = row.get::<Option<String>, _>("level") {
+            let lines: i64 = row.get("lines");
+            if let Some(file) = overview.files.last_mut() {
+                file.counts.add(&level, lines);
+            }
+            overview.totals.add(&level, lines);
+        }
+    }
+
+    let day_rows = sqlx::query(
+        r#"
+        SELECT h.day, h.level, SUM(h.count) AS lines
+        FROM files f
+        JOIN level_histograms h ON h.sha256_hash = f.sha256_hash
+        WHERE h.day != ''
+        GROUP BY h.day, h.level
+        ORDER BY h.day
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to compute daily overview: {e}")))?;
+
+    for row in day_rows {
+        let day: String = row.get("day");
+        let level: String = row.get("level");
+        if overview.days.last().map(|d| &d.day) != Some(&day) {
+            overview.days.push(DayOverview {
+                day,
+                counts: LevelCounts::default(),
+            });
+        }
+        if let Some(entry) = overview.days.last_mut() {
+            entry.counts.add(&level, row.get("lines"));
+        }
+    }
+
+    overview.gaps = coverage_gaps(&overview.days);
+    Ok(overview)
+}
+
+fn min_option(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Runs of missing days between consecutive covered days (input ascending).
+fn coverage_gaps(days: &[DayOverview]) -> Vec<CoverageGap> {
+    let parsed: Vec<NaiveDate> = days
+        .iter()
+        .filter_map(|d| NaiveDate::parse_from_str(&d.day, "%Y-%m-%d").ok())
+        .collect();
+
+    parsed
+        .windows(2)
+        .filter_map(|pair| {
+            let missing = (pair[1] - pair[0]).num_days() - 1;
+            if missing <= 0 {
+                return None;
+            }
+            let start = pair[0].succ_opt()?;
+            let end = pair[1].pred_opt()?;
+            Some(CoverageGap {
+                start_day: start.format("%Y-%m-%d").to_string(),
+                end_day: end.format("%Y-%m-%d").to_string(),
+                days: missing,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,33 @@
+//! Tenant ownership of the workspace.
+//!
+//! A workspace opened by a tenant-scoped caller records that tenant once;
+//! later opens under a different tenant (or none) are refused. See
+//! [`MetadataStore::new_scoped`](super::MetadataStore::new_scoped).
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+pub(crate) async fn get_owner(pool: &SqlitePool) -> Result<Option<String>> {
+    let row = sqlx::query("SELECT tenant FROM workspace_owner WHERE id = 1")
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to get workspace owner: {e}")))?;
+
+    Ok(row.map(|r| r.get("tenant")))
+}
+
+/// Record `tenant` as the owner unless one is already recorded; returns the owner.
+pub(crate) async fn claim_owner(pool: &SqlitePool, tenant: &str) -> Result<String> {
+    sqlx::query(
+        "INSERT INTO workspace_owner (id, tenant, claimed_at) VALUES (1, ?, ?) ON CONFLICT(id) DO NOTHING",
+    )
+    .bind(tenant)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to claim workspace owner: {e}")))?;
+
+    get_owner(pool)
+        .await?
+        .ok_or_else(|| AppError::database_error("Workspace owner missing after claim"))
+}
@@ -0,0 +1,83 @@
+//! Results of post-extraction hooks.
+//!
+//! Each extracted archive may run a configured list of hooks (decompress
+//! `.gz` singletons, split giant files, plugins) before its entries are
+//! stored. One row per hook run forms the import report for the workspace.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::PostExtractRunRecord;
+
+/// Record `(hook, files_affected, error)` runs for an archive in one transaction.
+pub(crate) async fn record_post_extract_runs(
+    pool: &SqlitePool,
+    archive_id: i64,
+    runs: &[(String, i64, Option<String>)],
+) -> Result<()> {
+    if runs.is_empty() {
+        return Ok(());
+    }
+    let ran_at = chrono::Utc::now().timestamp();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to begin transaction: {e}")))?;
+
+    for (hook, files_affected, error) in runs {
+        sqlx::query(
+            r#"
+            INSERT INTO post_extract_runs (archive_id, hook, files_affected, error, ran_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(archive_id)
+        .bind(hook)
+        .bind(files_affected)
+        .bind(error)
+        .bind(ran_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            AppError::database_error(format!("Failed to record post-extraction run: {e}"))
+        })?;
+    }
+
+    tx.commit().await.map_err(|e| {
+        AppError::database_error(format!("Failed to commit post-extraction runs: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// All hook runs in execution order, with the archive's virtual path.
+pub(crate) async fn get_post_extract_runs(pool: &SqlitePool) -> Result<Vec<PostExtractRunRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.archive_id, a.virtual_path AS archive_virtual_path,
+               r.hook, r.files_affected, r.error, r.ran_at
+        FROM post_extract_runs r
+        LEFT JOIN archives a ON a.id = r.archive_id
+        ORDER BY r.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to get post-extraction runs: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| PostExtractRunRecord {
+            archive_id: r.get("archive_id"),
+            archive_virtual_path: r
+                .try_get::<Option<String>, _>("archive_virtual_path")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            hook: r.get("hook"),
+            files_affected: r.get("files_affected"),
+            error: r.get("error"),
+            ran_at: r.get("ran_at"),
+        })
+        .collect())
+}
@@ -0,0 +1,78 @@
+//! Per-content data quality.
+//!
+//! Counts of invalid UTF-8 sequences, NUL bytes and line endings recorded at
+//! import (see `la_core::utils::assess_content`), keyed by content hash like
+//! term sketches, so every virtual path sharing a CAS object shares them.
+
+use std::collections::HashMap;
+
+use la_core::error::{AppError, Result};
+use la_core::utils::DataQuality;
+use sqlx::{Row, SqlitePool};
+
+/// Store the data quality for a content hash (UPSERT).
+pub(crate) async fn set_data_quality(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    quality: &DataQuality,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO data_quality
+            (sha256_hash, invalid_sequences, nul_bytes, crlf_lines, lf_lines, cr_lines)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET
+            invalid_sequences = excluded.invalid_sequences,
+            nul_bytes = excluded.nul_bytes,
+            crlf_lines = excluded.crlf_lines,
+            lf_lines = excluded.lf_lines,
+            cr_lines = excluded.cr_lines
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(quality.invalid_sequences as i64)
+    .bind(quality.nul_bytes as i64)
+    .bind(quality.crlf_lines as i64)
+    .bind(quality.lf_lines as i64)
+    .bind(quality.cr_lines as i64)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store data quality: {e}")))?;
+
+    Ok(())
+}
+
+/// Content hashes whose data quality warrants a warning
+/// (see `DataQuality::has_warnings`).
+pub(crate) async fn get_data_quality_warnings(
+    pool: &SqlitePool,
+) -> Result<HashMap<String, DataQuality>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT sha256_hash, invalid_sequences, nul_bytes, crlf_lines, lf_lines, cr_lines
+        FROM data_quality
+        WHERE invalid_sequences > 0
+            OR nul_bytes > 0
+            OR cr_lines > 0
+            OR (crlf_lines > 0 AND lf_lines > 0)
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to load data quality: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let count = |column: &str| row.get::<i64, _>(column).max(0) as u64;
+            let quality = DataQuality {
+                invalid_sequences: count("invalid_sequences"),
+                nul_bytes: count("nul_bytes"),
+                crlf_lines: count("crlf_lines"),
+                lf_lines: count("lf_lines"),
+                cr_lines: count("cr_lines"),
+            };
+            (row.get("sha256_hash"), quality)
+        })
+        .collect())
+}
@@ -0,0 +1,73 @@
+//! Quarantined archive entry operations.
+//!
+//! Entries whose names would escape the extraction directory (e.g.
+//! `../../etc/passwd`) are never written to disk; the extraction handlers
+//! quarantine them and the processor records them here against the parent
+//! archive.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::QuarantinedEntryRecord;
+
+/// Record a quarantined entry. Returns the new row id.
+pub(crate) async fn record_quarantined_entry(
+    pool: &SqlitePool,
+    record: &QuarantinedEntryRecord,
+) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO quarantined_entries (archive_id, entry_name, reason, size, detected_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(record.archive_id)
+    .bind(&record.entry_name)
+    .bind(&record.reason)
+    .bind(record.size)
+    .bind(record.detected_at)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to record quarantined entry: {e}")))?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Get quarantined entries detected at or after `since` (unix seconds),
+/// joined with the parent archive's virtual path.
+pub(crate) async fn get_quarantined_entries(
+    pool: &SqlitePool,
+    since: i64,
+) -> Result<Vec<QuarantinedEntryRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT q.id, q.archive_id, a.virtual_path AS archive_virtual_path,
+               q.entry_name, q.reason, q.size, q.detected_at
+        FROM quarantined_entries q
+        LEFT JOIN archives a ON a.id = q.archive_id
+        WHERE q.detected_at >= ?
+        ORDER BY q.id
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to get quarantined entries: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| QuarantinedEntryRecord {
+            id: r.get("id"),
+            archive_id: r.get("archive_id"),
+            archive_virtual_path: r
+                .try_get::<Option<String>, _>("archive_virtual_path")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            entry_name: r.get("entry_name"),
+            reason: r.get("reason"),
+            size: r.get("size"),
+            detected_at: r.get("detected_at"),
+        })
+        .collect())
+}
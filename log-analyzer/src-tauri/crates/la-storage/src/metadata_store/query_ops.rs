@@ -0,0 +1,183 @@
+//! Read-only ad-hoc SQL over the metadata database (query console).
+//!
+//! Each query runs on its own connection opened with `SQLITE_OPEN_READONLY`.
+//! An authorizer callback additionally rejects every statement action other
+//! than reading (no writes, DDL, PRAGMA, ATTACH or transactions) as well as
+//! reads of the secrets table, and a progress handler interrupts the query
+//! once its time budget is spent.
+
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::time::{Duration, Instant};
+
+use futures::TryStreamExt;
+use la_core::error::{AppError, Result};
+use libsqlite3_sys as ffi;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, ConnectOptions, Connection, Executor, Row, SqliteConnection, SqlitePool};
+use sqlx::{TypeInfo, ValueRef};
+
+use super::secret_ops::SECRETS_TABLE;
+use super::types::MetadataQueryResult;
+
+/// SQLite VM instructions between deadline checks
+const PROGRESS_CHECK_OPS: c_int = 1_000;
+
+/// Allow only the actions a plain `SELECT` (including CTEs and functions) needs,
+/// and no reads of the secrets table.
+extern "C" fn authorize_read_only(
+    _user_data: *mut c_void,
+    action: c_int,
+    arg1: *const c_char,
+    _arg2: *const c_char,
+    _database: *const c_char,
+    _trigger: *const c_char,
+) -> c_int {
+    match action {
+        ffi::SQLITE_READ if reads_secrets(arg1) => ffi::SQLITE_DENY,
+        ffi::SQLITE_SELECT | ffi::SQLITE_READ | ffi::SQLITE_FUNCTION | ffi::SQLITE_RECURSIVE => {
+            ffi::SQLITE_OK
+        }
+        _ => ffi::SQLITE_DENY,
+    }
+}
+
+/// Whether `table` (the first authorizer argument of `SQLITE_READ`) is the secrets table
+fn reads_secrets(table: *const c_char) -> bool {
+    if table.is_null() {
+        return false;
+    }
+    // SAFETY: SQLite passes a NUL-terminated table name valid for the callback.
+    let table = unsafe { CStr::from_ptr(table) };
+    table
+        .to_str()
+        .is_ok_and(|name| name.eq_ignore_ascii_case(SECRETS_TABLE))
+}
+
+pub(crate) async fn run_read_only_query(
+    pool: &SqlitePool,
+    sql: &str,
+    max_rows: usize,
+    timeout: Duration,
+) -> Result<MetadataQueryResult> {
+    let options = (*pool.connect_options())
+        .clone()
+        .read_only(true)
+        .create_if_missing(false);
+    let mut conn = options.connect().await.map_err(|e| {
+        AppError::database_error(format!("Failed to open read-only connection: {e}"))
+    })?;
+
+    let started = Instant::now();
+    let deadline = started + timeout;
+    {
+        let mut handle = conn.lock_handle().await.map_err(|e| {
+            AppError::database_error(format!("Failed to lock query connection: {e}"))
+        })?;
+        // SAFETY: the raw handle is valid while locked, and the authorizer is a
+        // plain function without user data that lives as long as the program.
+        unsafe {
+            ffi::sqlite3_set_authorizer(
+                handle.as_raw_handle().as_ptr(),
+                Some(authorize_read_only),
+                std::ptr::null_mut(),
+            );
+        }
+        handle.set_progress_handler(PROGRESS_CHECK_OPS, move || Instant::now() < deadline);
+    }
+
+    let collected = collect_rows(&mut conn, sql, max_rows).await;
+    let _ = conn.close().await;
+    let (columns, rows, truncated) = collected.map_err(|e| {
+        if Instant::now() >= deadline {
+            AppError::validation_error(format!(
+                "Query exceeded the {}ms time limit",
+                timeout.as_millis()
+            ))
+        } else {
+            AppError::validation_error(format!("Query failed: {e}"))
+        }
+    })?;
+
+    Ok(MetadataQueryResult {
+        columns,
+        rows,
+        truncated,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+type CollectedRows = (Vec<String>, Vec<Vec<serde_json::Value>>, bool);
+
+async fn collect_rows(
+    conn: &mut SqliteConnection,
+    sql: &str,
+    max_rows: usize,
+) -> std::result::Result<CollectedRows, sqlx::Error> {
+    let mut columns: Option<Vec<String>> = None;
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    {
+        let mut stream = sqlx::query(sql).fetch(&mut *conn);
+        while let Some(row) = stream.try_next().await? {
+            if rows.len() == max_rows {
+                truncated = true;
+                break;
+            }
+            if columns.is_none() {
+                columns = Some(column_names(&row));
+            }
+            rows.push(row_values(&row));
+        }
+    }
+
+    let columns = match columns {
+        Some(columns) => columns,
+        // 无结果行时从语句描述中取列名
+        None => conn
+            .describe(sql)
+            .await?
+            .columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect(),
+    };
+    Ok((columns, rows, truncated))
+}
+
+fn column_names(row: &SqliteRow) -> Vec<String> {
+    row.columns().iter().map(|c| c.name().to_string()).collect()
+}
+
+fn row_values(row: &SqliteRow) -> Vec<serde_json::Value> {
+    use serde_json::Value;
+
+    (0..row.len())
+        .map(|i| {
+            let Ok(raw) = row.try_get_raw(i) else {
+                return Value::Null;
+            };
+            if raw.is_null() {
+                return Value::Null;
+            }
+            // 按存储类型解码，忽略列声明类型
+            match raw.type_info().name() {
+                "INTEGER" => row
+                    .try_get_unchecked::<i64, _>(i)
+                    .map(Value::from)
+                    .unwrap_or(Value::Null),
+                "REAL" => row
+                    .try_get_unchecked::<f64, _>(i)
+                    .map(Value::from)
+                    .unwrap_or(Value::Null),
+                "BLOB" => row
+                    .try_get_unchecked::<Vec<u8>, _>(i)
+                    .map(|bytes| Value::String(bytes.iter().map(|b| format!("{b:02x}")).collect()))
+                    .unwrap_or(Value::Null),
+                _ => row
+                    .try_get_unchecked::<String, _>(i)
+                    .map(Value::String)
+                    .unwrap_or(Value::Null),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! Raw name bytes of archive entries with legacy-encoded names.
+//!
+//! ZIP and RAR archives created on Chinese or Japanese Windows store entry
+//! names in the local code page (GBK, Shift-JIS). Files are imported under the
+//! decoded name; the original bytes are kept here, keyed by that name, so an
+//! entry can still be located in the archive when it is retried or re-extracted.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+/// Record `(entry_name, raw_name)` pairs for an archive, replacing earlier rows.
+pub(crate) async fn record_raw_entry_names(
+    pool: &SqlitePool,
+    archive_id: i64,
+    entries: &[(String, Vec<u8>)],
+) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to begin transaction: {e}")))?;
+
+    for (entry_name, raw_name) in entries {
+        sqlx::query(
+            r#"
+            INSERT INTO entry_raw_names (archive_id, entry_name, raw_name)
+            VALUES (?, ?, ?)
+            ON CONFLICT(archive_id, entry_name) DO UPDATE SET raw_name = excluded.raw_name
+            "#,
+        )
+        .bind(archive_id)
+        .bind(entry_name)
+        .bind(raw_name)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to record raw entry name: {e}")))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to commit raw entry names: {e}")))?;
+
+    Ok(())
+}
+
+/// Raw name bytes of an entry, if its name was decoded from a legacy encoding.
+pub(crate) async fn get_raw_entry_name(
+    pool: &SqlitePool,
+    archive_id: i64,
+    entry_name: &str,
+) -> Result<Option<Vec<u8>>> {
+    let row =
+        sqlx::query("SELECT raw_name FROM entry_raw_names WHERE archive_id = ? AND entry_name = ?")
+            .bind(archive_id)
+            .bind(entry_name)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to get raw entry name: {e}")))?;
+
+    Ok(row.map(|r| r.get("raw_name")))
+}
@@ -25,6 +25,8 @@ pub(crate) async fn init_schema(pool: &SqlitePool) -> Result<()> {
             max_timestamp INTEGER,
             level_mask INTEGER,
             created_at INTEGER NOT NULL,
+            search_flag TEXT NOT NULL DEFAULT 'NORMAL',
+            time_offset_secs INTEGER NOT NULL DEFAULT 0,
             FOREIGN KEY (parent_archive_id) REFERENCES archives(id) ON DELETE CASCADE
         )
         "#,
@@ -217,3 +219,489 @@ pub(crate) async fn migrate_schema_v3(pool: &SqlitePool) -> Result<()> {
 
     Ok(())
 }
+
+/// v4: symlinks recorded (not followed) during directory import.
+pub(crate) async fn migrate_schema_v4(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS symlinks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            virtual_path TEXT NOT NULL UNIQUE,
+            target TEXT NOT NULL,
+            within_root INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create symlinks table: {e}")))?;
+
+    Ok(())
+}
+
+/// v5: archive entries quarantined for path traversal.
+pub(crate) async fn migrate_schema_v5(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS quarantined_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            archive_id INTEGER NOT NULL,
+            entry_name TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            size INTEGER NOT NULL DEFAULT 0,
+            detected_at INTEGER NOT NULL,
+            FOREIGN KEY (archive_id) REFERENCES archives(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create quarantined_entries table: {e}"))
+    })?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_quarantined_archive ON quarantined_entries(archive_id)",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create quarantine index: {e}")))?;
+
+    Ok(())
+}
+
+/// v6: every logical file path and the CAS object it maps to.
+///
+/// `files` keeps one row per hash, so duplicate paths are dropped there;
+/// `file_refs` keeps them for dedup reporting.
+pub(crate) async fn migrate_schema_v6(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS file_refs (
+            virtual_path TEXT PRIMARY KEY,
+            sha256_hash TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create file_refs table: {e}")))?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_file_refs_hash ON file_refs(sha256_hash)")
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to create file_refs index: {e}")))?;
+
+    // Backfill from existing workspaces (duplicates imported before v6 are not recoverable)
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO file_refs (virtual_path, sha256_hash, size, created_at)
+        SELECT virtual_path, sha256_hash, size, created_at FROM files
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to backfill file_refs: {e}")))?;
+
+    Ok(())
+}
+
+/// v7: per-file search flag (NORMAL / IGNORED / PINNED).
+pub(crate) async fn migrate_schema_v7(pool: &SqlitePool) -> Result<()> {
+    let sql = "ALTER TABLE files ADD COLUMN search_flag TEXT NOT NULL DEFAULT 'NORMAL'";
+    if let Err(e) = sqlx::query(sql).execute(pool).await {
+        let msg = e.to_string().to_lowercase();
+        if !msg.contains("duplicate column") {
+            return Err(AppError::database_error(format!(
+                "Failed to add search_flag column: {e}"
+            )));
+        }
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_files_search_flag ON files(search_flag)")
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            AppError::database_error(format!("Failed to create search_flag index: {e}"))
+        })?;
+
+    Ok(())
+}
+
+/// Migrate to v8: per-content term sketches used to skip files at query time.
+///
+/// Kept out of `files` so row scans never load the blobs.
+pub(crate) async fn migrate_schema_v8(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS term_sketches (
+            sha256_hash TEXT PRIMARY KEY NOT NULL,
+            sketch BLOB NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create term_sketches table: {e}")))?;
+
+    Ok(())
+}
+
+/// Migrate to v9: per-file clock offset (seconds added to every parsed timestamp).
+pub(crate) async fn migrate_schema_v9(pool: &SqlitePool) -> Result<()> {
+    let sql = "ALTER TABLE files ADD COLUMN time_offset_secs INTEGER NOT NULL DEFAULT 0";
+    if let Err(e) = sqlx::query(sql).execute(pool).await {
+        let msg = e.to_string().to_lowercase();
+        if !msg.contains("duplicate column") {
+            return Err(AppError::database_error(format!(
+                "Failed to add time_offset_secs column: {e}"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrate to v10: per-content daily level histograms for the workspace overview.
+pub(crate) async fn migrate_schema_v10(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS level_histograms (
+            sha256_hash TEXT NOT NULL,
+            day TEXT NOT NULL,
+            level TEXT NOT NULL,
+            count INTEGER NOT NULL,
+            PRIMARY KEY (sha256_hash, day, level)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create level_histograms table: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// Migrate to v11: archive entries left out by a selective-extraction include list.
+pub(crate) async fn migrate_schema_v11(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS skipped_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            archive_id INTEGER NOT NULL,
+            entry_name TEXT NOT NULL,
+            size INTEGER NOT NULL DEFAULT 0,
+            skipped_at INTEGER NOT NULL,
+            FOREIGN KEY (archive_id) REFERENCES archives(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create skipped_entries table: {e}"))
+    })?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_skipped_archive ON skipped_entries(archive_id)")
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            AppError::database_error(format!("Failed to create skipped_entries index: {e}"))
+        })?;
+
+    Ok(())
+}
+
+/// Migrate to v12: workspace metadata extracted from bundle manifests.
+pub(crate) async fn migrate_schema_v12(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspace_metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            source TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create workspace_metadata table: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// Migrate to v13: per-workspace settings and post-extraction hook results.
+pub(crate) async fn migrate_schema_v13(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspace_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create workspace_settings table: {e}"))
+    })?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS post_extract_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            archive_id INTEGER NOT NULL,
+            hook TEXT NOT NULL,
+            files_affected INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            ran_at INTEGER NOT NULL,
+            FOREIGN KEY (archive_id) REFERENCES archives(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create post_extract_runs table: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// Migrate to v14: per-content data quality (invalid UTF-8, NUL bytes, line endings).
+pub(crate) async fn migrate_schema_v14(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS data_quality (
+            sha256_hash TEXT PRIMARY KEY NOT NULL,
+            invalid_sequences INTEGER NOT NULL DEFAULT 0,
+            nul_bytes INTEGER NOT NULL DEFAULT 0,
+            crlf_lines INTEGER NOT NULL DEFAULT 0,
+            lf_lines INTEGER NOT NULL DEFAULT 0,
+            cr_lines INTEGER NOT NULL DEFAULT 0
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create data_quality table: {e}")))?;
+
+    Ok(())
+}
+
+/// Migrate to v15: record which metadata schema and app version last wrote the index state.
+pub(crate) async fn migrate_schema_v15(pool: &SqlitePool) -> Result<()> {
+    for (col, typ) in [
+        ("schema_version", "INTEGER NOT NULL DEFAULT 0"),
+        ("app_version", "TEXT"),
+    ] {
+        let sql = format!("ALTER TABLE index_state ADD COLUMN {col} {typ}");
+        if let Err(e) = sqlx::query(&sql).execute(pool).await {
+            let msg = e.to_string().to_lowercase();
+            if !msg.contains("duplicate column") {
+                return Err(AppError::database_error(format!(
+                    "Failed to add index_state.{col} column: {e}"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrate to v16: per-content format fingerprint and user override.
+pub(crate) async fn migrate_schema_v16(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS file_formats (
+            sha256_hash TEXT PRIMARY KEY NOT NULL,
+            format TEXT NOT NULL,
+            confidence REAL NOT NULL DEFAULT 0,
+            override_format TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create file_formats table: {e}")))?;
+
+    Ok(())
+}
+
+/// Migrate to v17: detected CSV column mapping and the user's mapping override.
+pub(crate) async fn migrate_schema_v17(pool: &SqlitePool) -> Result<()> {
+    for col in ["detected_csv_mapping", "csv_mapping_override"] {
+        let sql = format!("ALTER TABLE file_formats ADD COLUMN {col} TEXT");
+        if let Err(e) = sqlx::query(&sql).execute(pool).await {
+            let msg = e.to_string().to_lowercase();
+            if !msg.contains("duplicate column") {
+                return Err(AppError::database_error(format!(
+                    "Failed to add file_formats.{col} column: {e}"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrate to v18: catalog of crash artifacts (core dumps, minidumps, hprof) per content hash.
+pub(crate) async fn migrate_schema_v18(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS crash_artifacts (
+            sha256_hash TEXT PRIMARY KEY NOT NULL,
+            kind TEXT NOT NULL,
+            build_id TEXT,
+            crashed_at INTEGER
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create crash_artifacts table: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// Migrate to v19: per-content sparse time indexes (timestamp → line offset).
+///
+/// Kept out of `files` so row scans never load the blobs.
+pub(crate) async fn migrate_schema_v19(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS time_indexes (
+            sha256_hash TEXT PRIMARY KEY NOT NULL,
+            time_index BLOB NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create time_indexes table: {e}")))?;
+
+    Ok(())
+}
+
+/// Migrate to v20: archive entries that failed to extract or import.
+///
+/// One row per `(archive, entry)`; a retry overwrites the error or deletes the row.
+pub(crate) async fn migrate_schema_v20(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS failed_entries (
+            archive_id INTEGER NOT NULL,
+            entry_name TEXT NOT NULL,
+            error TEXT NOT NULL,
+            failed_at INTEGER NOT NULL,
+            PRIMARY KEY (archive_id, entry_name),
+            FOREIGN KEY (archive_id) REFERENCES archives(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to create failed_entries table: {e}")))?;
+
+    Ok(())
+}
+
+/// Migrate to v21: raw name bytes of archive entries whose names were not UTF-8.
+///
+/// Entries are stored under the decoded name; the raw bytes let later lookups
+/// (retry, re-extraction) find the entry even if the decoding changes.
+pub(crate) async fn migrate_schema_v21(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS entry_raw_names (
+            archive_id INTEGER NOT NULL,
+            entry_name TEXT NOT NULL,
+            raw_name BLOB NOT NULL,
+            PRIMARY KEY (archive_id, entry_name),
+            FOREIGN KEY (archive_id) REFERENCES archives(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create entry_raw_names table: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// Migrate to v22: per-content encoding, timezone and parser overrides on `file_formats`.
+pub(crate) async fn migrate_schema_v22(pool: &SqlitePool) -> Result<()> {
+    for col in ["encoding_override", "timezone_override", "parser_override"] {
+        let sql = format!("ALTER TABLE file_formats ADD COLUMN {col} TEXT");
+        if let Err(e) = sqlx::query(&sql).execute(pool).await {
+            let msg = e.to_string().to_lowercase();
+            if !msg.contains("duplicate column") {
+                return Err(AppError::database_error(format!(
+                    "Failed to add file_formats.{col} column: {e}"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrate to v23: the tenant that owns the workspace.
+///
+/// A single row, written once when a tenant-scoped caller first opens the
+/// workspace. Kept out of `workspace_settings` so no settings command can
+/// rewrite it.
+pub(crate) async fn migrate_schema_v23(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspace_owner (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            tenant TEXT NOT NULL,
+            claimed_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create workspace_owner table: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// Migrate to v24: per-workspace secrets (webhook signing keys).
+///
+/// Kept out of `workspace_settings`, which the query console can read; the
+/// console's authorizer refuses reads of this table.
+pub(crate) async fn migrate_schema_v24(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspace_secrets (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        AppError::database_error(format!("Failed to create workspace_secrets table: {e}"))
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Per-workspace secrets (e.g. webhook signing keys).
+//!
+//! Stored apart from `workspace_settings`: settings are listed back to the UI
+//! and readable from the query console, secrets are only ever read by the
+//! backend that uses them. Like settings, they survive re-imports.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+/// Table the query console authorizer refuses to read.
+pub(crate) const SECRETS_TABLE: &str = "workspace_secrets";
+
+pub(crate) async fn get_workspace_secret(pool: &SqlitePool, key: &str) -> Result<Option<String>> {
+    let row = sqlx::query("SELECT value FROM workspace_secrets WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to get workspace secret: {e}")))?;
+
+    Ok(row.map(|r| r.get("value")))
+}
+
+pub(crate) async fn set_workspace_secret(pool: &SqlitePool, key: &str, value: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO workspace_secrets (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET
+            value = excluded.value,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to set workspace secret: {e}")))?;
+
+    Ok(())
+}
+
+/// Remove a secret; returns whether one was stored.
+pub(crate) async fn delete_workspace_secret(pool: &SqlitePool, key: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM workspace_secrets WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to delete workspace secret: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
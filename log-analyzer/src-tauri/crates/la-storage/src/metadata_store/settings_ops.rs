@@ -0,0 +1,38 @@
+//! Per-workspace settings.
+//!
+//! Small JSON or scalar values keyed by name (e.g. post-extraction hooks).
+//! Unlike imported data, settings are kept when the workspace is cleared
+//! and re-imported.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+pub(crate) async fn get_workspace_setting(pool: &SqlitePool, key: &str) -> Result<Option<String>> {
+    let row = sqlx::query("SELECT value FROM workspace_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to get workspace setting: {e}")))?;
+
+    Ok(row.map(|r| r.get("value")))
+}
+
+pub(crate) async fn set_workspace_setting(pool: &SqlitePool, key: &str, value: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO workspace_settings (key, value, updated_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT(key) DO UPDATE SET
+            value = excluded.value,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to set workspace setting: {e}")))?;
+
+    Ok(())
+}
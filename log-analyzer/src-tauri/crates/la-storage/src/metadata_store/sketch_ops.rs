@@ -0,0 +1,76 @@
+//! Per-content term sketches.
+//!
+//! A sketch is an opaque blob (see `la_core::utils::TermBloom`) keyed by the
+//! content hash, so every virtual path sharing the same CAS object shares it.
+//! Files without a sketch are never skipped.
+
+use std::collections::HashMap;
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::MAX_BATCH_SIZE;
+
+/// Store the sketch for a content hash (UPSERT).
+pub(crate) async fn set_term_sketch(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    sketch: &[u8],
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO term_sketches (sha256_hash, sketch)
+        VALUES (?, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET sketch = excluded.sketch
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(sketch)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store term sketch: {e}")))?;
+
+    Ok(())
+}
+
+/// Remove the sketch for a content hash, so the file is never skipped.
+pub(crate) async fn delete_term_sketch(pool: &SqlitePool, sha256_hash: &str) -> Result<()> {
+    sqlx::query("DELETE FROM term_sketches WHERE sha256_hash = ?")
+        .bind(sha256_hash)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to delete term sketch: {e}")))?;
+
+    Ok(())
+}
+
+/// Load sketches for the given content hashes; hashes without one are absent.
+pub(crate) async fn get_term_sketches(
+    pool: &SqlitePool,
+    hashes: &[String],
+) -> Result<HashMap<String, Vec<u8>>> {
+    let mut sketches = HashMap::new();
+
+    for chunk in hashes.chunks(MAX_BATCH_SIZE) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "SELECT sha256_hash, sketch FROM term_sketches WHERE sha256_hash IN ({placeholders})"
+        );
+
+        let mut query = sqlx::query(&sql);
+        for hash in chunk {
+            query = query.bind(hash);
+        }
+
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to load term sketches: {e}")))?;
+
+        for row in rows {
+            sketches.insert(row.get("sha256_hash"), row.get("sketch"));
+        }
+    }
+
+    Ok(sketches)
+}
@@ -0,0 +1,79 @@
+//! Archive entries skipped by selective extraction.
+//!
+//! When an import carries an include list for an archive, entries that do
+//! not match are never written to disk. They are recorded here against the
+//! parent archive so the user can see what was left out and re-import it.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::SkippedEntryRecord;
+
+/// Record the `(entry_name, size)` pairs skipped for an archive in one transaction.
+pub(crate) async fn record_skipped_entries(
+    pool: &SqlitePool,
+    archive_id: i64,
+    entries: &[(String, i64)],
+) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let skipped_at = chrono::Utc::now().timestamp();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to begin transaction: {e}")))?;
+
+    for (entry_name, size) in entries {
+        sqlx::query(
+            r#"
+            INSERT INTO skipped_entries (archive_id, entry_name, size, skipped_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(archive_id)
+        .bind(entry_name)
+        .bind(size)
+        .bind(skipped_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to record skipped entry: {e}")))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to commit skipped entries: {e}")))?;
+
+    Ok(())
+}
+
+/// All skipped entries, grouped by archive, with the archive's virtual path.
+pub(crate) async fn get_skipped_entries(pool: &SqlitePool) -> Result<Vec<SkippedEntryRecord>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT s.archive_id, a.virtual_path AS archive_virtual_path,
+               s.entry_name, s.size, s.skipped_at
+        FROM skipped_entries s
+        LEFT JOIN archives a ON a.id = s.archive_id
+        ORDER BY s.archive_id, s.entry_name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to get skipped entries: {e}")))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| SkippedEntryRecord {
+            archive_id: r.get("archive_id"),
+            archive_virtual_path: r
+                .try_get::<Option<String>, _>("archive_virtual_path")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            entry_name: r.get("entry_name"),
+            size: r.get("size"),
+            skipped_at: r.get("skipped_at"),
+        })
+        .collect())
+}
@@ -0,0 +1,75 @@
+//! Per-content sparse time indexes.
+//!
+//! An index is an opaque blob (see `la_core::utils::TimeIndex`) keyed by the
+//! content hash, like term sketches. Files without one are read from the start.
+
+use std::collections::HashMap;
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::MAX_BATCH_SIZE;
+
+/// Store the time index for a content hash (UPSERT).
+pub(crate) async fn set_time_index(
+    pool: &SqlitePool,
+    sha256_hash: &str,
+    time_index: &[u8],
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO time_indexes (sha256_hash, time_index)
+        VALUES (?, ?)
+        ON CONFLICT(sha256_hash) DO UPDATE SET time_index = excluded.time_index
+        "#,
+    )
+    .bind(sha256_hash)
+    .bind(time_index)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::database_error(format!("Failed to store time index: {e}")))?;
+
+    Ok(())
+}
+
+/// Remove the time index for a content hash, e.g. when overrides make it stale.
+pub(crate) async fn delete_time_index(pool: &SqlitePool, sha256_hash: &str) -> Result<()> {
+    sqlx::query("DELETE FROM time_indexes WHERE sha256_hash = ?")
+        .bind(sha256_hash)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to delete time index: {e}")))?;
+
+    Ok(())
+}
+
+/// Load time indexes for the given content hashes; hashes without one are absent.
+pub(crate) async fn get_time_indexes(
+    pool: &SqlitePool,
+    hashes: &[String],
+) -> Result<HashMap<String, Vec<u8>>> {
+    let mut indexes = HashMap::new();
+
+    for chunk in hashes.chunks(MAX_BATCH_SIZE) {
+        let placeholders = vec!["?"; chunk.len()].join(", ");
+        let sql = format!(
+            "SELECT sha256_hash, time_index FROM time_indexes WHERE sha256_hash IN ({placeholders})"
+        );
+
+        let mut query = sqlx::query(&sql);
+        for hash in chunk {
+            query = query.bind(hash);
+        }
+
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to load time indexes: {e}")))?;
+
+        for row in rows {
+            indexes.insert(row.get("sha256_hash"), row.get("time_index"));
+        }
+    }
+
+    Ok(indexes)
+}
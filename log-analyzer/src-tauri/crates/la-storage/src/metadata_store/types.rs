@@ -5,7 +5,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::Row;
 
-use la_core::storage_types::AnalysisStatus;
+use la_core::storage_types::{AnalysisStatus, FileSearchFlag};
+use la_core::utils::{CrashArtifactInfo, CsvMapping, LogFormat};
 
 /// Parse analysis_status from a database row
 pub(crate) fn parse_analysis_status(row: &sqlx::sqlite::SqliteRow) -> AnalysisStatus {
@@ -20,7 +21,12 @@ pub(crate) fn parse_analysis_status(row: &sqlx::sqlite::SqliteRow) -> AnalysisSt
 pub struct IndexState {
     pub workspace_id: String,
     pub last_commit_time: i64,
+    /// Search index schema version the index was built with (0 = rebuild pending)
     pub index_version: i32,
+    /// Highest metadata schema version that has written this database (0 = unknown)
+    pub schema_version: i32,
+    /// App version that wrote `schema_version`
+    pub app_version: Option<String>,
 }
 
 /// Indexed file tracking for incremental indexing
@@ -34,5 +40,276 @@ pub struct IndexedFile {
     pub hash: String, // SHA-256
 }
 
+/// Symlink recorded (not followed) during directory import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymlinkRecord {
+    pub virtual_path: String,
+    pub target: String,
+    /// Whether the resolved target lies inside the import root
+    pub within_root: bool,
+    pub created_at: i64,
+}
+
+/// Archive entry quarantined for path traversal during extraction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedEntryRecord {
+    /// Row id (ignored on insert)
+    pub id: i64,
+    pub archive_id: i64,
+    /// Virtual path of the parent archive (filled on read)
+    pub archive_virtual_path: String,
+    /// Raw entry name as stored in the archive
+    pub entry_name: String,
+    pub reason: String,
+    pub size: i64,
+    pub detected_at: i64,
+}
+
+/// Archive entry left out by a selective-extraction include list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEntryRecord {
+    pub archive_id: i64,
+    /// Virtual path of the parent archive
+    pub archive_virtual_path: String,
+    /// Entry path inside the archive
+    pub entry_name: String,
+    pub size: i64,
+    pub skipped_at: i64,
+}
+
+/// Archive entry that failed to extract or import and can be retried
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedEntryRecord {
+    pub archive_id: i64,
+    /// Virtual path of the parent archive
+    pub archive_virtual_path: String,
+    /// Entry path inside the archive
+    pub entry_name: String,
+    /// Last error seen for the entry
+    pub error: String,
+    pub failed_at: i64,
+}
+
+/// Result of one post-extraction hook run on an archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostExtractRunRecord {
+    pub archive_id: i64,
+    /// Virtual path of the archive the hook ran on
+    pub archive_virtual_path: String,
+    pub hook: String,
+    pub files_affected: i64,
+    pub error: Option<String>,
+    pub ran_at: i64,
+}
+
+/// Workspace-level key/value metadata (e.g. device serial from a bundle manifest)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMetadataEntry {
+    pub key: String,
+    pub value: String,
+    /// Where the value came from, e.g. the manifest's virtual path
+    pub source: String,
+    pub updated_at: i64,
+}
+
+/// CAS deduplication report for a workspace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupReport {
+    /// Logical files (virtual paths) imported
+    pub logical_files: i64,
+    /// Distinct CAS objects backing them
+    pub unique_objects: i64,
+    /// Bytes the logical files would occupy without dedup
+    pub logical_bytes: i64,
+    /// Bytes actually stored in CAS
+    pub stored_bytes: i64,
+    pub bytes_saved: i64,
+    /// logical_bytes / stored_bytes (1.0 when nothing is stored)
+    pub dedup_ratio: f64,
+    /// How many objects are referenced by N logical files
+    pub distribution: Vec<DedupBucket>,
+    /// Objects with the largest savings, descending
+    pub top_duplicates: Vec<DuplicatedObject>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupBucket {
+    pub ref_count: i64,
+    pub objects: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatedObject {
+    pub sha256_hash: String,
+    pub size: i64,
+    pub ref_count: i64,
+    pub bytes_saved: i64,
+    /// Up to 10 of the virtual paths sharing this object
+    pub virtual_paths: Vec<String>,
+}
+
+/// A file whose search flag is not `Normal`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedFile {
+    pub virtual_path: String,
+    pub sha256_hash: String,
+    pub flag: FileSearchFlag,
+}
+
+/// Line counts per log level
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelCounts {
+    pub error: i64,
+    pub warn: i64,
+    pub info: i64,
+    pub debug: i64,
+    pub total: i64,
+}
+
+impl LevelCounts {
+    /// Add `count` lines of `level` (lowercase); unknown levels only count toward `total`
+    pub fn add(&mut self, level: &str, count: i64) {
+        match level {
+            "error" => self.error += count,
+            "warn" => self.warn += count,
+            "info" => self.info += count,
+            "debug" => self.debug += count,
+            _ => {}
+        }
+        self.total += count;
+    }
+}
+
+/// Per-file entry of the workspace overview
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOverview {
+    pub virtual_path: String,
+    pub min_timestamp: Option<i64>,
+    pub max_timestamp: Option<i64>,
+    pub counts: LevelCounts,
+}
+
+/// Per-day entry of the workspace overview (`day` is `YYYY-MM-DD`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayOverview {
+    pub day: String,
+    pub counts: LevelCounts,
+}
+
+/// A run of consecutive days without any timestamped line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageGap {
+    pub start_day: String,
+    pub end_day: String,
+    pub days: i64,
+}
+
+/// Level and time-coverage statistics for the whole workspace
+///
+/// Built from the per-content histograms recorded at import time, so no file
+/// content is read. Files whose histogram is not computed yet have zero counts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceOverview {
+    pub earliest_timestamp: Option<i64>,
+    pub latest_timestamp: Option<i64>,
+    pub totals: LevelCounts,
+    /// Files ordered by virtual path
+    pub files: Vec<FileOverview>,
+    /// Days that have at least one timestamped line, ascending
+    pub days: Vec<DayOverview>,
+    /// Empty days between the first and the last covered day
+    pub gaps: Vec<CoverageGap>,
+}
+
+/// Result of a read-only ad-hoc query against the metadata database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataQueryResult {
+    pub columns: Vec<String>,
+    /// Integers, reals and text as JSON values, BLOBs as lowercase hex, NULL as null
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// The query produced more rows than the row limit
+    pub truncated: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Detected format of a content object and the user's overrides, if any
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileFormat {
+    pub detected: LogFormat,
+    pub confidence: f32,
+    pub override_format: Option<LogFormat>,
+    /// Column mapping detected for CSV/TSV content
+    #[serde(default)]
+    pub detected_csv_mapping: Option<CsvMapping>,
+    #[serde(default)]
+    pub csv_mapping_override: Option<CsvMapping>,
+    /// Encoding label (e.g. `gbk`) forced instead of BOM/UTF-8 detection
+    #[serde(default)]
+    pub encoding_override: Option<String>,
+    /// Fixed UTC offset (e.g. `+08:00`) applied to timestamps without one
+    #[serde(default)]
+    pub timezone_override: Option<String>,
+    /// Regex with named `timestamp` / `level` groups that replaces field extraction
+    #[serde(default)]
+    pub parser_override: Option<String>,
+}
+
+impl FileFormat {
+    /// The format used for parsing: the override when set, otherwise the detected one
+    pub fn effective(&self) -> LogFormat {
+        self.override_format.unwrap_or(self.detected)
+    }
+
+    /// The column mapping used when the effective format is CSV
+    pub fn effective_csv_mapping(&self) -> Option<&CsvMapping> {
+        self.csv_mapping_override
+            .as_ref()
+            .or(self.detected_csv_mapping.as_ref())
+    }
+}
+
+/// User overrides for one content object; `None` fields restore auto-detection
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOverrides {
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
+    pub format: Option<LogFormat>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub parser: Option<String>,
+}
+
+/// A crash artifact in the catalog and the file it was imported as
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashArtifact {
+    pub virtual_path: String,
+    pub sha256_hash: String,
+    pub size: i64,
+    /// File modification time (Unix seconds); the fallback when no crash time is recorded
+    pub modified_time: i64,
+    #[serde(flatten)]
+    pub info: CrashArtifactInfo,
+}
+
 /// Maximum batch insert size to prevent SQL injection and memory overflow
 pub(crate) const MAX_BATCH_SIZE: usize = 1000;
@@ -0,0 +1,75 @@
+//! Workspace-level key/value metadata.
+//!
+//! Filled from bundle manifests (`manifest.json`, `info.txt`) found during
+//! import: device serial, firmware version, collection time and any other
+//! scalar fields. Keys are unique; a later import overwrites earlier values.
+
+use la_core::error::{AppError, Result};
+use sqlx::{Row, SqlitePool};
+
+use super::types::WorkspaceMetadataEntry;
+
+/// Upsert `(key, value)` pairs from one source in a single transaction.
+pub(crate) async fn set_workspace_metadata(
+    pool: &SqlitePool,
+    entries: &[(String, String)],
+    source: &str,
+) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let updated_at = chrono::Utc::now().timestamp();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to begin transaction: {e}")))?;
+
+    for (key, value) in entries {
+        sqlx::query(
+            r#"
+            INSERT INTO workspace_metadata (key, value, source, updated_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET
+                value = excluded.value,
+                source = excluded.source,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .bind(source)
+        .bind(updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to set workspace metadata: {e}")))?;
+    }
+
+    tx.commit().await.map_err(|e| {
+        AppError::database_error(format!("Failed to commit workspace metadata: {e}"))
+    })?;
+
+    Ok(())
+}
+
+/// All workspace metadata, ordered by key.
+pub(crate) async fn get_workspace_metadata(
+    pool: &SqlitePool,
+) -> Result<Vec<WorkspaceMetadataEntry>> {
+    let rows =
+        sqlx::query("SELECT key, value, source, updated_at FROM workspace_metadata ORDER BY key")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                AppError::database_error(format!("Failed to get workspace metadata: {e}"))
+            })?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| WorkspaceMetadataEntry {
+            key: r.get("key"),
+            value: r.get("value"),
+            source: r.get("source"),
+            updated_at: r.get("updated_at"),
+        })
+        .collect())
+}
@@ -839,6 +839,8 @@ async fn test_save_and_load_index_state() {
         workspace_id: workspace_id.to_string(),
         last_commit_time: 1234567890,
         index_version: 1,
+        schema_version: METADATA_SCHEMA_VERSION,
+        app_version: Some("1.2.3".to_string()),
     };
 
     // Save state
@@ -851,6 +853,8 @@ async fn test_save_and_load_index_state() {
     assert_eq!(loaded.workspace_id, workspace_id);
     assert_eq!(loaded.last_commit_time, 1234567890);
     assert_eq!(loaded.index_version, 1);
+    assert_eq!(loaded.schema_version, METADATA_SCHEMA_VERSION);
+    assert_eq!(loaded.app_version.as_deref(), Some("1.2.3"));
 }
 
 /// Test load non-existent index state returns None
@@ -878,6 +882,8 @@ async fn test_update_index_state() {
         workspace_id: workspace_id.to_string(),
         last_commit_time: 1000,
         index_version: 1,
+        schema_version: 14,
+        app_version: None,
     };
 
     // Save initial state
@@ -888,6 +894,8 @@ async fn test_update_index_state() {
         workspace_id: workspace_id.to_string(),
         last_commit_time: 2000,
         index_version: 2,
+        schema_version: 15,
+        app_version: Some("1.2.3".to_string()),
     };
     store.save_index_state(&state2).await.unwrap();
 
@@ -895,6 +903,7 @@ async fn test_update_index_state() {
     let loaded = store.load_index_state(workspace_id).await.unwrap().unwrap();
     assert_eq!(loaded.last_commit_time, 2000);
     assert_eq!(loaded.index_version, 2);
+    assert_eq!(loaded.schema_version, 15);
 }
 
 /// Test save and load indexed file
@@ -1266,3 +1275,999 @@ mod property_tests {
         });
     }
 }
+
+// ========== Symlink Metadata Tests ==========
+
+/// Test recording symlinks is idempotent per virtual path
+#[tokio::test]
+async fn test_record_and_get_symlinks() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    let mut record = SymlinkRecord {
+        virtual_path: "logs/current".to_string(),
+        target: "/var/log/app/2024".to_string(),
+        within_root: false,
+        created_at: 1,
+    };
+    store.record_symlink(&record).await.unwrap();
+    record.within_root = true;
+    record.created_at = 2;
+    store.record_symlink(&record).await.unwrap();
+
+    let links = store.get_symlinks().await.unwrap();
+    assert_eq!(links, vec![record]);
+}
+
+// ========== Quarantine Tests ==========
+
+/// Test quarantined entries are linked to their parent archive
+#[tokio::test]
+async fn test_record_and_get_quarantined_entries() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    let archive_id = store
+        .insert_archive(&ArchiveMetadata {
+            id: 0,
+            sha256_hash: "quarantine_archive_hash".to_string(),
+            virtual_path: "uploads/evil.zip".to_string(),
+            original_name: "evil.zip".to_string(),
+            archive_type: "zip".to_string(),
+            parent_archive_id: None,
+            depth_level: 0,
+            extraction_status: "completed".to_string(),
+        })
+        .await
+        .unwrap();
+
+    for (name, detected_at) in [("../../etc/passwd", 100), ("../old.log", 50)] {
+        store
+            .record_quarantined_entry(&QuarantinedEntryRecord {
+                id: 0,
+                archive_id,
+                archive_virtual_path: String::new(),
+                entry_name: name.to_string(),
+                reason: "path traversal".to_string(),
+                size: 4,
+                detected_at,
+            })
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(store.get_quarantined_entries(0).await.unwrap().len(), 2);
+    let recent = store.get_quarantined_entries(100).await.unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].entry_name, "../../etc/passwd");
+    assert_eq!(recent[0].archive_virtual_path, "uploads/evil.zip");
+}
+
+/// Test entries skipped by selective extraction are listed per archive
+#[tokio::test]
+async fn test_record_and_get_skipped_entries() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    let archive_id = store
+        .insert_archive(&ArchiveMetadata {
+            id: 0,
+            sha256_hash: "selected_archive_hash".to_string(),
+            virtual_path: "bundle/support.tar.gz".to_string(),
+            original_name: "support.tar.gz".to_string(),
+            archive_type: "gz".to_string(),
+            parent_archive_id: None,
+            depth_level: 0,
+            extraction_status: "completed".to_string(),
+        })
+        .await
+        .unwrap();
+
+    store.record_skipped_entries(archive_id, &[]).await.unwrap();
+    store
+        .record_skipped_entries(
+            archive_id,
+            &[
+                ("db/slow.log".to_string(), 40),
+                ("cache/redis.log".to_string(), 10),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let skipped = store.get_skipped_entries().await.unwrap();
+    let names: Vec<&str> = skipped.iter().map(|e| e.entry_name.as_str()).collect();
+    assert_eq!(names, vec!["cache/redis.log", "db/slow.log"]);
+    assert_eq!(skipped[0].archive_virtual_path, "bundle/support.tar.gz");
+    assert_eq!(skipped[1].size, 40);
+
+    store.clear_all().await.unwrap();
+    assert!(store.get_skipped_entries().await.unwrap().is_empty());
+}
+
+/// Test failed entries are upserted per archive and removed after a retry
+#[tokio::test]
+async fn test_record_retry_and_remove_failed_entries() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    let archive_id = store
+        .insert_archive(&ArchiveMetadata {
+            id: 0,
+            sha256_hash: "flaky_archive_hash".to_string(),
+            virtual_path: "bundle/node1.zip".to_string(),
+            original_name: "node1.zip".to_string(),
+            archive_type: "zip".to_string(),
+            parent_archive_id: None,
+            depth_level: 0,
+            extraction_status: "completed".to_string(),
+        })
+        .await
+        .unwrap();
+
+    store
+        .record_failed_entries(
+            archive_id,
+            &[
+                ("logs/app.log".to_string(), "Input/output error".to_string()),
+                ("logs/gc.log".to_string(), "Input/output error".to_string()),
+            ],
+        )
+        .await
+        .unwrap();
+    store
+        .record_failed_entries(
+            archive_id,
+            &[(
+                "logs/app.log".to_string(),
+                "No space left on device".to_string(),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let failed = store.get_failed_entries().await.unwrap();
+    assert_eq!(failed.len(), 2);
+    assert_eq!(failed[0].entry_name, "logs/app.log");
+    assert_eq!(failed[0].error, "No space left on device");
+    assert_eq!(failed[0].archive_virtual_path, "bundle/node1.zip");
+
+    store
+        .remove_failed_entry(archive_id, "logs/app.log")
+        .await
+        .unwrap();
+    let failed = store.get_failed_entries().await.unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].entry_name, "logs/gc.log");
+
+    store.clear_all().await.unwrap();
+    assert!(store.get_failed_entries().await.unwrap().is_empty());
+}
+
+/// Test raw entry name bytes are kept per archive under the decoded name
+#[tokio::test]
+async fn test_record_and_get_raw_entry_names() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    let archive_id = store
+        .insert_archive(&ArchiveMetadata {
+            id: 0,
+            sha256_hash: "gbk_archive_hash".to_string(),
+            virtual_path: "bundle/日志.zip".to_string(),
+            original_name: "日志.zip".to_string(),
+            archive_type: "zip".to_string(),
+            parent_archive_id: None,
+            depth_level: 0,
+            extraction_status: "completed".to_string(),
+        })
+        .await
+        .unwrap();
+
+    let raw = b"\xc4\xe3\xba\xc3.log".to_vec();
+    store
+        .record_raw_entry_names(archive_id, &[("你好.log".to_string(), raw.clone())])
+        .await
+        .unwrap();
+    assert_eq!(
+        store
+            .get_raw_entry_name(archive_id, "你好.log")
+            .await
+            .unwrap(),
+        Some(raw)
+    );
+    assert_eq!(
+        store
+            .get_raw_entry_name(archive_id, "app.log")
+            .await
+            .unwrap(),
+        None
+    );
+
+    store.clear_all().await.unwrap();
+    assert_eq!(
+        store
+            .get_raw_entry_name(archive_id, "你好.log")
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+// ========== Workspace Metadata Tests ==========
+
+#[tokio::test]
+async fn test_workspace_metadata_and_manifest_lookup() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    for (hash, path, name, depth) in [
+        (
+            "deep_manifest",
+            "b/x/nested/manifest.json",
+            "manifest.json",
+            2,
+        ),
+        ("top_manifest", "b/MANIFEST.JSON", "MANIFEST.JSON", 0),
+        ("app_log", "b/app.log", "app.log", 0),
+    ] {
+        store
+            .insert_file(&FileMetadata {
+                id: 0,
+                sha256_hash: hash.to_string(),
+                virtual_path: path.to_string(),
+                original_name: name.to_string(),
+                size: 10,
+                modified_time: 0,
+                mime_type: None,
+                parent_archive_id: None,
+                depth_level: depth,
+                min_timestamp: None,
+                max_timestamp: None,
+                level_mask: None,
+                analysis_status: AnalysisStatus::Ready,
+            })
+            .await
+            .unwrap();
+    }
+    let found = store
+        .get_files_by_name(&["manifest.json", "info.txt"])
+        .await
+        .unwrap();
+    let paths: Vec<&str> = found.iter().map(|f| f.virtual_path.as_str()).collect();
+    assert_eq!(paths, vec!["b/MANIFEST.JSON", "b/x/nested/manifest.json"]);
+
+    store
+        .set_workspace_metadata(
+            &[
+                ("serial".to_string(), "SN-1".to_string()),
+                ("firmware".to_string(), "1.0".to_string()),
+            ],
+            "b/MANIFEST.JSON",
+        )
+        .await
+        .unwrap();
+    store
+        .set_workspace_metadata(&[("firmware".to_string(), "2.0".to_string())], "b/info.txt")
+        .await
+        .unwrap();
+
+    let metadata = store.get_workspace_metadata().await.unwrap();
+    let pairs: Vec<(&str, &str, &str)> = metadata
+        .iter()
+        .map(|m| (m.key.as_str(), m.value.as_str(), m.source.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("firmware", "2.0", "b/info.txt"),
+            ("serial", "SN-1", "b/MANIFEST.JSON"),
+        ]
+    );
+
+    store.clear_all().await.unwrap();
+    assert!(store.get_workspace_metadata().await.unwrap().is_empty());
+}
+
+// ========== Workspace Settings / Post-Extraction Runs ==========
+
+#[tokio::test]
+async fn test_settings_survive_clear_and_hook_runs_do_not() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    assert_eq!(store.get_workspace_setting("hooks").await.unwrap(), None);
+    store.set_workspace_setting("hooks", "[1]").await.unwrap();
+    store.set_workspace_setting("hooks", "[2]").await.unwrap();
+
+    let archive_id = store
+        .insert_archive(&ArchiveMetadata {
+            id: 0,
+            sha256_hash: "hooked_archive".to_string(),
+            virtual_path: "bundle.zip".to_string(),
+            original_name: "bundle.zip".to_string(),
+            archive_type: "zip".to_string(),
+            parent_archive_id: None,
+            depth_level: 0,
+            extraction_status: "completed".to_string(),
+        })
+        .await
+        .unwrap();
+    store
+        .record_post_extract_runs(
+            archive_id,
+            &[
+                ("decompressGz".to_string(), 3, None),
+                ("plugin:x".to_string(), 0, Some("boom".to_string())),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let runs = store.get_post_extract_runs().await.unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].hook, "decompressGz");
+    assert_eq!(runs[0].files_affected, 3);
+    assert_eq!(runs[1].error.as_deref(), Some("boom"));
+    assert_eq!(runs[1].archive_virtual_path, "bundle.zip");
+
+    store.clear_all().await.unwrap();
+    assert!(store.get_post_extract_runs().await.unwrap().is_empty());
+    assert_eq!(
+        store
+            .get_workspace_setting("hooks")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("[2]")
+    );
+}
+
+// ========== Dedup Report Tests ==========
+
+fn dedup_test_file(virtual_path: &str, hash: &str, size: i64) -> FileMetadata {
+    FileMetadata {
+        id: 0,
+        sha256_hash: hash.to_string(),
+        virtual_path: virtual_path.to_string(),
+        original_name: virtual_path.rsplit('/').next().unwrap().to_string(),
+        size,
+        modified_time: 0,
+        mime_type: None,
+        parent_archive_id: None,
+        depth_level: 0,
+        min_timestamp: None,
+        max_timestamp: None,
+        level_mask: None,
+        analysis_status: AnalysisStatus::Pending,
+    }
+}
+
+/// Duplicate paths are counted even though `files` keeps one row per hash
+#[tokio::test]
+async fn test_dedup_report_counts_logical_references() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    store
+        .insert_file(&dedup_test_file("a/app.log", "hash_big", 1000))
+        .await
+        .unwrap();
+    store
+        .insert_files_batch(vec![
+            dedup_test_file("b/app.log", "hash_big", 1000),
+            dedup_test_file("c/app.log", "hash_big", 1000),
+            dedup_test_file("a/small.log", "hash_small", 10),
+            dedup_test_file("b/small.log", "hash_small", 10),
+            dedup_test_file("unique.log", "hash_unique", 5),
+        ])
+        .await
+        .unwrap();
+
+    let report = store.get_dedup_report(1).await.unwrap();
+    assert_eq!(report.logical_files, 6);
+    assert_eq!(report.unique_objects, 3);
+    assert_eq!(report.logical_bytes, 3025);
+    assert_eq!(report.stored_bytes, 1015);
+    assert_eq!(report.bytes_saved, 2010);
+    assert_eq!(
+        report.distribution,
+        vec![
+            DedupBucket {
+                ref_count: 1,
+                objects: 1
+            },
+            DedupBucket {
+                ref_count: 2,
+                objects: 1
+            },
+            DedupBucket {
+                ref_count: 3,
+                objects: 1
+            },
+        ]
+    );
+    assert_eq!(report.top_duplicates.len(), 1);
+    assert_eq!(report.top_duplicates[0].sha256_hash, "hash_big");
+    assert_eq!(report.top_duplicates[0].bytes_saved, 2000);
+    assert_eq!(
+        report.top_duplicates[0].virtual_paths,
+        vec!["a/app.log", "b/app.log", "c/app.log"]
+    );
+}
+
+/// Ignored files drop out of the scan list, pinned files come first
+#[tokio::test]
+async fn test_search_flags_shape_scan_list() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    store
+        .insert_files_batch(vec![
+            dedup_test_file("a/app.log", "hash_a", 10),
+            dedup_test_file("b/noise.log", "hash_b", 10),
+            dedup_test_file("c/main.log", "hash_c", 10),
+        ])
+        .await
+        .unwrap();
+
+    let previous = store
+        .set_file_search_flag("b/noise.log", FileSearchFlag::Ignored)
+        .await
+        .unwrap();
+    assert_eq!(previous, Some(FileSearchFlag::Normal));
+    store
+        .set_file_search_flag("c/main.log", FileSearchFlag::Pinned)
+        .await
+        .unwrap();
+    assert_eq!(
+        store
+            .set_file_search_flag("missing.log", FileSearchFlag::Pinned)
+            .await
+            .unwrap(),
+        None
+    );
+
+    let scan: Vec<String> = store
+        .get_files_with_pruning(None, None, None, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|f| f.virtual_path)
+        .collect();
+    assert_eq!(scan, vec!["c/main.log", "a/app.log"]);
+
+    let flagged = store.get_flagged_files().await.unwrap();
+    assert_eq!(flagged.len(), 2);
+    assert_eq!(flagged[0].virtual_path, "b/noise.log");
+    assert_eq!(flagged[0].flag, FileSearchFlag::Ignored);
+    assert_eq!(flagged[1].flag, FileSearchFlag::Pinned);
+}
+
+/// Term sketches are keyed by content hash, deleted one by one and cleared with the workspace
+#[tokio::test]
+async fn test_term_sketches_round_trip() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    store.set_term_sketch("hash_a", b"first").await.unwrap();
+    store.set_term_sketch("hash_a", b"second").await.unwrap();
+    store.set_term_sketch("hash_b", b"other").await.unwrap();
+
+    let sketches = store
+        .get_term_sketches(&["hash_a".to_string(), "hash_missing".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(sketches.len(), 1);
+    assert_eq!(sketches["hash_a"], b"second");
+
+    store.delete_term_sketch("hash_a").await.unwrap();
+    assert!(store
+        .get_term_sketches(&["hash_a".to_string()])
+        .await
+        .unwrap()
+        .is_empty());
+
+    store.clear_all().await.unwrap();
+    assert!(store
+        .get_term_sketches(&["hash_b".to_string()])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// Only content with data quality problems is listed, and it is cleared with the workspace
+#[tokio::test]
+async fn test_data_quality_warnings() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    let clean = la_core::utils::assess_content(b"a\nb\n");
+    let mixed = la_core::utils::assess_content(b"a\r\nb\n");
+    let broken = la_core::utils::assess_content(b"a\0\xff\n");
+    store.set_data_quality("hash_clean", &clean).await.unwrap();
+    store.set_data_quality("hash_mixed", &mixed).await.unwrap();
+    store
+        .set_data_quality("hash_broken", &broken)
+        .await
+        .unwrap();
+
+    let warnings = store.get_data_quality_warnings().await.unwrap();
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings["hash_mixed"], mixed);
+    assert_eq!(warnings["hash_broken"].nul_bytes, 1);
+    assert_eq!(warnings["hash_broken"].invalid_sequences, 1);
+
+    store.clear_all().await.unwrap();
+    assert!(store.get_data_quality_warnings().await.unwrap().is_empty());
+}
+
+/// Clock offsets shift time pruning and are listed when non-zero
+#[tokio::test]
+async fn test_time_offsets_shift_pruning() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    store
+        .insert_files_batch(vec![
+            dedup_test_file("device.log", "hash_a", 10),
+            dedup_test_file("server.log", "hash_b", 10),
+        ])
+        .await
+        .unwrap();
+    store
+        .update_file_ready("device.log", Some(1000), Some(2000), Some(1))
+        .await
+        .unwrap();
+    store
+        .update_file_ready("server.log", Some(1000), Some(2000), Some(1))
+        .await
+        .unwrap();
+
+    // 设备时钟慢 1 小时：校正后覆盖 4600..5600
+    assert!(store
+        .set_file_time_offset("device.log", 3600)
+        .await
+        .unwrap());
+    assert!(!store.set_file_time_offset("missing.log", 5).await.unwrap());
+
+    let scan: Vec<String> = store
+        .get_files_with_pruning(Some(5000), Some(5100), None, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|f| f.virtual_path)
+        .collect();
+    assert_eq!(scan, vec!["device.log"]);
+
+    let offsets = store.get_file_time_offsets().await.unwrap();
+    assert_eq!(offsets.len(), 1);
+    assert_eq!(offsets["device.log"], 3600);
+}
+
+/// Overview sums stored histograms per file and per day and reports empty days
+#[tokio::test]
+async fn test_workspace_overview_from_histograms() {
+    use la_core::utils::daily_level_counts;
+
+    let (store, _temp_dir) = create_test_store().await;
+
+    store
+        .insert_files_batch(vec![
+            dedup_test_file("a.log", "hash_a", 10),
+            dedup_test_file("b.log", "hash_b", 10),
+            dedup_test_file("pending.log", "hash_c", 10),
+        ])
+        .await
+        .unwrap();
+    store
+        .update_file_ready("a.log", Some(1000), Some(2000), Some(9))
+        .await
+        .unwrap();
+    store
+        .set_level_histogram(
+            "hash_a",
+            &daily_level_counts(
+                b"2024-01-01 10:00:00 ERROR x\n2024-01-01 11:00:00 INFO y\nno timestamp\n",
+            ),
+        )
+        .await
+        .unwrap();
+    store
+        .set_level_histogram(
+            "hash_b",
+            &daily_level_counts(b"2024-01-01 09:00:00 ERROR z\n2024-01-04 09:00:00 WARN w\n"),
+        )
+        .await
+        .unwrap();
+
+    let overview = store.get_workspace_overview().await.unwrap();
+    assert_eq!(overview.earliest_timestamp, Some(1000));
+    assert_eq!(overview.latest_timestamp, Some(2000));
+    assert_eq!(overview.totals.total, 5);
+    assert_eq!(overview.totals.error, 2);
+
+    let paths: Vec<&str> = overview
+        .files
+        .iter()
+        .map(|f| f.virtual_path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["a.log", "b.log", "pending.log"]);
+    assert_eq!(overview.files[0].counts.total, 3);
+    assert_eq!(overview.files[0].counts.debug, 1);
+    assert_eq!(overview.files[2].counts.total, 0);
+
+    let days: Vec<(&str, i64)> = overview
+        .days
+        .iter()
+        .map(|d| (d.day.as_str(), d.counts.total))
+        .collect();
+    assert_eq!(days, vec![("2024-01-01", 3), ("2024-01-04", 1)]);
+    assert_eq!(overview.gaps.len(), 1);
+    assert_eq!(overview.gaps[0].start_day, "2024-01-02");
+    assert_eq!(overview.gaps[0].end_day, "2024-01-03");
+    assert_eq!(overview.gaps[0].days, 2);
+}
+
+/// Query console: reads work, writes and PRAGMAs are rejected, limits apply
+#[tokio::test]
+async fn test_run_read_only_query() {
+    let (store, _temp_dir) = create_test_store().await;
+    store.set_workspace_setting("theme", "dark").await.unwrap();
+    let timeout = std::time::Duration::from_secs(5);
+
+    let result = store
+        .run_read_only_query("SELECT key, value FROM workspace_settings", 100, timeout)
+        .await
+        .unwrap();
+    assert_eq!(result.columns, vec!["key", "value"]);
+    assert_eq!(
+        result.rows,
+        vec![vec![serde_json::json!("theme"), serde_json::json!("dark")]]
+    );
+    assert!(!result.truncated);
+
+    let empty = store
+        .run_read_only_query("SELECT key FROM workspace_settings WHERE 0", 100, timeout)
+        .await
+        .unwrap();
+    assert_eq!(empty.columns, vec!["key"]);
+    assert!(empty.rows.is_empty());
+
+    let limited = store
+        .run_read_only_query(
+            "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c LIMIT 10) SELECT x, x * 0.5, NULL, x'ff' FROM c",
+            3,
+            timeout,
+        )
+        .await
+        .unwrap();
+    assert_eq!(limited.rows.len(), 3);
+    assert!(limited.truncated);
+    assert_eq!(
+        limited.rows[0],
+        vec![
+            serde_json::json!(1),
+            serde_json::json!(0.5),
+            serde_json::json!(null),
+            serde_json::json!("ff")
+        ]
+    );
+
+    for sql in [
+        "DELETE FROM workspace_settings",
+        "UPDATE workspace_settings SET value = 'x'",
+        "PRAGMA journal_mode = DELETE",
+        "ATTACH DATABASE ':memory:' AS other",
+        "CREATE TABLE t (x)",
+    ] {
+        assert!(
+            store.run_read_only_query(sql, 100, timeout).await.is_err(),
+            "{sql} should be rejected"
+        );
+    }
+    assert_eq!(
+        store
+            .get_workspace_setting("theme")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("dark")
+    );
+
+    store
+        .set_workspace_secret("webhook:ci", "s3cret")
+        .await
+        .unwrap();
+    for sql in [
+        "SELECT value FROM workspace_secrets",
+        "SELECT count(*) FROM workspace_secrets",
+        "SELECT * FROM (SELECT value FROM Workspace_Secrets)",
+    ] {
+        assert!(
+            store.run_read_only_query(sql, 100, timeout).await.is_err(),
+            "{sql} should not read secrets"
+        );
+    }
+
+    let endless = store
+        .run_read_only_query(
+            "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT count(*) FROM c",
+            1,
+            std::time::Duration::from_millis(50),
+        )
+        .await;
+    assert!(endless.unwrap_err().to_string().contains("time limit"));
+}
+
+/// Secrets are stored apart from settings and survive clearing the workspace
+#[tokio::test]
+async fn test_workspace_secrets() {
+    let (store, _temp_dir) = create_test_store().await;
+    assert_eq!(
+        store.get_workspace_secret("webhook:ci").await.unwrap(),
+        None
+    );
+
+    store
+        .set_workspace_secret("webhook:ci", "one")
+        .await
+        .unwrap();
+    store
+        .set_workspace_secret("webhook:ci", "two")
+        .await
+        .unwrap();
+    assert_eq!(
+        store
+            .get_workspace_secret("webhook:ci")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("two")
+    );
+    assert_eq!(
+        store.get_workspace_setting("webhook:ci").await.unwrap(),
+        None
+    );
+
+    store.clear_all().await.unwrap();
+    assert!(store.delete_workspace_secret("webhook:ci").await.unwrap());
+    assert!(!store.delete_workspace_secret("webhook:ci").await.unwrap());
+    assert_eq!(
+        store.get_workspace_secret("webhook:ci").await.unwrap(),
+        None
+    );
+}
+
+/// Re-fingerprinting keeps the user's override, and both are cleared with the workspace
+#[tokio::test]
+async fn test_file_formats_and_overrides() {
+    use la_core::utils::{FormatFingerprint, LogFormat};
+
+    let (store, _temp_dir) = create_test_store().await;
+    let detected = FormatFingerprint {
+        format: LogFormat::Csv,
+        confidence: 0.7,
+        csv_mapping: None,
+    };
+    store
+        .set_format_fingerprint("hash_a", &detected)
+        .await
+        .unwrap();
+    store
+        .set_format_override("hash_a", Some(LogFormat::Plain))
+        .await
+        .unwrap();
+    store
+        .set_format_fingerprint("hash_a", &detected)
+        .await
+        .unwrap();
+    // 尚未识别的内容也可以先改判
+    store
+        .set_format_override("hash_b", Some(LogFormat::Ndjson))
+        .await
+        .unwrap();
+
+    let formats = store.get_file_formats().await.unwrap();
+    assert_eq!(formats["hash_a"].detected, LogFormat::Csv);
+    assert_eq!(formats["hash_a"].effective(), LogFormat::Plain);
+    assert_eq!(formats["hash_b"].effective(), LogFormat::Ndjson);
+
+    store.set_format_override("hash_a", None).await.unwrap();
+    let formats = store.get_file_formats().await.unwrap();
+    assert_eq!(formats["hash_a"].effective(), LogFormat::Csv);
+
+    store.clear_all().await.unwrap();
+    assert!(store.get_file_formats().await.unwrap().is_empty());
+}
+
+/// The CSV mapping override wins over detection and survives re-fingerprinting
+#[tokio::test]
+async fn test_csv_mapping_override() {
+    use la_core::utils::fingerprint_content;
+
+    let (store, _temp_dir) = create_test_store().await;
+    let fingerprint =
+        fingerprint_content(b"time,level,host,msg\n2024-01-02 03:04:05,INFO,db1,ok\n");
+    let detected = fingerprint.csv_mapping.clone().unwrap();
+    store
+        .set_format_fingerprint("hash_a", &fingerprint)
+        .await
+        .unwrap();
+
+    let mut custom = detected.clone();
+    custom.message_column = Some(2);
+    store
+        .set_csv_mapping_override("hash_a", Some(&custom))
+        .await
+        .unwrap();
+    store
+        .set_format_fingerprint("hash_a", &fingerprint)
+        .await
+        .unwrap();
+
+    let formats = store.get_file_formats().await.unwrap();
+    assert_eq!(
+        formats["hash_a"].detected_csv_mapping.as_ref(),
+        Some(&detected)
+    );
+    assert_eq!(formats["hash_a"].effective_csv_mapping(), Some(&custom));
+
+    store
+        .set_csv_mapping_override("hash_a", None)
+        .await
+        .unwrap();
+    let formats = store.get_file_formats().await.unwrap();
+    assert_eq!(formats["hash_a"].effective_csv_mapping(), Some(&detected));
+}
+
+/// Setting overrides replaces all four at once and keeps the detected format
+#[tokio::test]
+async fn test_file_overrides_round_trip() {
+    use la_core::utils::{FormatFingerprint, LogFormat};
+
+    let (store, _temp_dir) = create_test_store().await;
+    store
+        .set_format_fingerprint(
+            "hash_a",
+            &FormatFingerprint {
+                format: LogFormat::Plain,
+                confidence: 0.4,
+                csv_mapping: None,
+            },
+        )
+        .await
+        .unwrap();
+    let overrides = FileOverrides {
+        encoding: Some("gbk".into()),
+        format: Some(LogFormat::Syslog),
+        timezone: Some("+08:00".into()),
+        parser: Some(r"^(?P<timestamp>\S+) (?P<level>\w+)".into()),
+    };
+    store
+        .set_file_overrides("hash_a", &overrides)
+        .await
+        .unwrap();
+
+    let formats = store.get_file_formats().await.unwrap();
+    let format = &formats["hash_a"];
+    assert_eq!(format.detected, LogFormat::Plain);
+    assert_eq!(format.effective(), LogFormat::Syslog);
+    assert_eq!(format.encoding_override.as_deref(), Some("gbk"));
+    assert_eq!(format.timezone_override.as_deref(), Some("+08:00"));
+    assert_eq!(format.parser_override, overrides.parser);
+
+    store
+        .set_file_overrides("hash_a", &FileOverrides::default())
+        .await
+        .unwrap();
+    let formats = store.get_file_formats().await.unwrap();
+    assert_eq!(formats["hash_a"].effective(), LogFormat::Plain);
+    assert_eq!(formats["hash_a"].encoding_override, None);
+    assert_eq!(formats["hash_a"].parser_override, None);
+}
+
+/// Cataloged artifacts are listed by crash time (falling back to mtime) with their paths
+#[tokio::test]
+async fn test_crash_artifact_catalog() {
+    use la_core::utils::{CrashArtifactInfo, CrashArtifactKind};
+
+    let (store, _temp_dir) = create_test_store().await;
+    for (hash, path, modified_time) in [
+        ("core_hash", "host/core.1234", 300),
+        ("dump_hash", "client/crash.dmp", 100),
+    ] {
+        store
+            .insert_file(&FileMetadata {
+                id: 0,
+                sha256_hash: hash.to_string(),
+                virtual_path: path.to_string(),
+                original_name: path.rsplit('/').next().unwrap().to_string(),
+                size: 4096,
+                modified_time,
+                mime_type: None,
+                parent_archive_id: None,
+                depth_level: 0,
+                min_timestamp: None,
+                max_timestamp: None,
+                level_mask: None,
+                analysis_status: AnalysisStatus::Pending,
+            })
+            .await
+            .unwrap();
+    }
+    let core = CrashArtifactInfo {
+        kind: CrashArtifactKind::Coredump,
+        build_id: Some("abcd".to_string()),
+        crashed_at: None,
+    };
+    let minidump = CrashArtifactInfo {
+        kind: CrashArtifactKind::Minidump,
+        build_id: None,
+        crashed_at: Some(200),
+    };
+    store.set_crash_artifact("core_hash", &core).await.unwrap();
+    store
+        .set_crash_artifact("dump_hash", &minidump)
+        .await
+        .unwrap();
+
+    let artifacts = store.get_crash_artifacts().await.unwrap();
+    let paths: Vec<&str> = artifacts.iter().map(|a| a.virtual_path.as_str()).collect();
+    assert_eq!(paths, vec!["client/crash.dmp", "host/core.1234"]);
+    assert_eq!(artifacts[1].info, core);
+    assert_eq!(
+        store.get_crash_artifact_infos().await.unwrap()["dump_hash"],
+        minidump
+    );
+
+    store.clear_all().await.unwrap();
+    assert!(store.get_crash_artifact_infos().await.unwrap().is_empty());
+}
+
+/// Time indexes are keyed by content hash and cleared with the workspace
+#[tokio::test]
+async fn test_time_indexes_round_trip() {
+    let (store, _temp_dir) = create_test_store().await;
+
+    store.set_time_index("hash_a", b"first").await.unwrap();
+    store.set_time_index("hash_a", b"second").await.unwrap();
+    store.set_time_index("hash_b", b"other").await.unwrap();
+
+    let indexes = store
+        .get_time_indexes(&["hash_a".to_string(), "hash_missing".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(indexes.len(), 1);
+    assert_eq!(indexes["hash_a"], b"second");
+
+    store.delete_time_index("hash_a").await.unwrap();
+    assert!(store
+        .get_time_indexes(&["hash_a".to_string()])
+        .await
+        .unwrap()
+        .is_empty());
+
+    store.clear_all().await.unwrap();
+    assert!(store
+        .get_time_indexes(&["hash_b".to_string()])
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// The first scoped open claims the workspace; other tenants are refused
+#[tokio::test]
+async fn test_scoped_open_records_and_enforces_owner() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let store = MetadataStore::new_scoped(temp_dir.path(), Some("acme"))
+        .await
+        .unwrap();
+    assert_eq!(store.tenant(), Some("acme"));
+    store.clear_all().await.unwrap();
+    store.close().await;
+
+    let reopened = MetadataStore::new(temp_dir.path()).await.unwrap();
+    assert_eq!(reopened.tenant(), Some("acme"));
+    reopened.close().await;
+
+    assert!(MetadataStore::new_scoped(temp_dir.path(), Some("globex"))
+        .await
+        .is_err());
+    assert!(MetadataStore::new_scoped(temp_dir.path(), None)
+        .await
+        .is_err());
+
+    let local = TempDir::new().unwrap();
+    let store = MetadataStore::new_scoped(local.path(), None).await.unwrap();
+    assert_eq!(store.tenant(), None);
+}
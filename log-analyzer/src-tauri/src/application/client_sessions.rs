@@ -0,0 +1,501 @@
+//! ClientSessionManager — per-client resource ownership for shared-backend use.
+//!
+//! When several clients (WebSocket connections or desktop windows) talk to one
+//! backend, every resource a client creates is recorded against its session:
+//! - identity it authenticated as, and the tenant it is scoped to
+//! - follow-query subscriptions attached to workspaces
+//! - searches it started (each owns a cancellation token and a pinned snapshot
+//!   in `SearchSessionManager`)
+//! - its own rate-limit bucket
+//!
+//! Clients heartbeat while connected. A session that has not been seen for
+//! `CLIENT_IDLE_TIMEOUT` is reaped; the manager only hands back the
+//! [`ClientResources`] it tracked, and the caller releases them.
+//!
+//! Sessions are authenticated with an API key from the security config: a
+//! tenant key scopes the session to that tenant, the operator key (`api_key`)
+//! opens an unscoped session. The tenant is never taken from the client. With
+//! `auth_enabled` (server mode) a key is required, and callers without a
+//! session are rejected (see `workspace_guard::caller_tenant`); otherwise
+//! keyless sessions are local and unscoped.
+//!
+//! A session is fixed to its tenant for its lifetime. Searches it starts are
+//! visible only to sessions of the same tenant; unscoped sessions (the desktop
+//! app, server operators) see everything.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use la_core::models::config::SecurityConfig;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+
+/// Sessions without a heartbeat for this long are torn down.
+pub const CLIENT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// How often idle sessions are looked for.
+pub const CLIENT_REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+struct ClientSession {
+    identity: Option<String>,
+    tenant: Option<String>,
+    opened_at: i64,
+    last_seen: Instant,
+    searches: BTreeSet<String>,
+    /// (workspace_id, query_id)
+    follow_queries: BTreeSet<(String, String)>,
+    bucket: Option<Arc<DefaultDirectRateLimiter>>,
+}
+
+/// Description of a client session, for the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientSessionInfo {
+    pub client_id: String,
+    pub identity: Option<String>,
+    pub tenant: Option<String>,
+    /// Unix milliseconds
+    pub opened_at: i64,
+    pub idle_ms: u64,
+    pub searches: usize,
+    pub follow_queries: usize,
+    pub rate_limited: bool,
+}
+
+/// Resources a closed or reaped session still held; the caller releases them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ClientResources {
+    pub client_id: String,
+    pub searches: Vec<String>,
+    /// (workspace_id, query_id)
+    pub follow_queries: Vec<(String, String)>,
+}
+
+/// Who an API key authenticates as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub identity: Option<String>,
+    /// `None` for operator and local sessions
+    pub tenant: Option<String>,
+}
+
+/// Identity recorded for sessions opened with the operator key.
+pub const OPERATOR_IDENTITY: &str = "operator";
+
+#[derive(Default)]
+struct AuthConfig {
+    required: bool,
+    operator_key: Option<String>,
+    /// tenant → key
+    tenant_keys: BTreeMap<String, String>,
+}
+
+/// Central owner of client session bookkeeping.
+#[derive(Default)]
+pub struct ClientSessionManager {
+    sessions: Mutex<HashMap<String, ClientSession>>,
+    /// `None` disables per-client rate limiting
+    rate_per_minute: RwLock<Option<NonZeroU32>>,
+    auth: RwLock<AuthConfig>,
+}
+
+impl ClientSessionManager {
+    /// Apply the security config. Keys take effect for sessions opened
+    /// afterwards; only those sessions pick up a changed rate limit.
+    pub fn configure(&self, config: &SecurityConfig) {
+        let rate = config
+            .rate_limit_enabled
+            .then(|| u32::try_from(config.rate_limit_per_minute).ok())
+            .flatten()
+            .and_then(NonZeroU32::new);
+        *self.rate_per_minute.write() = rate;
+        *self.auth.write() = AuthConfig {
+            required: config.auth_enabled,
+            operator_key: config.api_key.clone().filter(|key| !key.is_empty()),
+            tenant_keys: config.tenant_keys.clone(),
+        };
+    }
+
+    /// Whether the backend runs in server mode, where every caller must hold
+    /// an authenticated session.
+    pub fn auth_required(&self) -> bool {
+        self.auth.read().required
+    }
+
+    /// Resolve an API key to the identity and tenant it grants.
+    ///
+    /// Without a key the session is local and unscoped, which server mode
+    /// refuses. Every configured key is compared in constant time.
+    pub fn authenticate(&self, api_key: Option<&str>) -> Result<Credential, String> {
+        let auth = self.auth.read();
+        let Some(api_key) = api_key.filter(|key| !key.is_empty()) else {
+            if auth.required {
+                return Err("An API key is required to open a session".to_string());
+            }
+            return Ok(Credential {
+                identity: None,
+                tenant: None,
+            });
+        };
+        let mut credential = None;
+        if auth
+            .operator_key
+            .as_deref()
+            .is_some_and(|key| keys_match(key, api_key))
+        {
+            credential = Some(Credential {
+                identity: Some(OPERATOR_IDENTITY.to_string()),
+                tenant: None,
+            });
+        }
+        for (tenant, key) in &auth.tenant_keys {
+            if keys_match(key, api_key) && credential.is_none() {
+                credential = Some(Credential {
+                    identity: Some(format!("tenant:{tenant}")),
+                    tenant: Some(tenant.clone()),
+                });
+            }
+        }
+        credential.ok_or_else(|| "Invalid API key".to_string())
+    }
+
+    /// Open a session, or refresh an existing one (a reconnect within the idle
+    /// timeout keeps everything the client already owns).
+    ///
+    /// `identity` and `tenant` come from [`authenticate`](Self::authenticate).
+    /// Fails if the session exists and is scoped to a different tenant.
+    pub fn open(
+        &self,
+        client_id: &str,
+        identity: Option<String>,
+        tenant: Option<String>,
+    ) -> Result<ClientSessionInfo, String> {
+        let rate = *self.rate_per_minute.read();
+        let mut sessions = self.sessions.lock();
+        if let Some(existing) = sessions.get(client_id) {
+            if existing.tenant != tenant {
+                return Err(format!(
+                    "Client '{client_id}' is already open for a different tenant"
+                ));
+            }
+        }
+        let session = sessions
+            .entry(client_id.to_string())
+            .or_insert_with(|| ClientSession {
+                identity: None,
+                tenant: tenant.clone(),
+                opened_at: chrono::Utc::now().timestamp_millis(),
+                last_seen: Instant::now(),
+                searches: BTreeSet::new(),
+                follow_queries: BTreeSet::new(),
+                bucket: rate
+                    .map(|per_minute| Arc::new(RateLimiter::direct(Quota::per_minute(per_minute)))),
+            });
+        session.last_seen = Instant::now();
+        if identity.is_some() {
+            session.identity = identity;
+        }
+        Ok(describe(client_id, session))
+    }
+
+    /// Tenant the client's session is scoped to; `None` without a session or tenant.
+    pub fn tenant_of(&self, client_id: &str) -> Option<String> {
+        self.sessions.lock().get(client_id)?.tenant.clone()
+    }
+
+    /// `Some(tenant)` if the client has an open session (`Some(None)` when it
+    /// is unscoped), `None` without a session.
+    pub fn scope_of(&self, client_id: &str) -> Option<Option<String>> {
+        Some(self.sessions.lock().get(client_id)?.tenant.clone())
+    }
+
+    /// Whether a caller scoped to `tenant` may read a search's results.
+    ///
+    /// Unscoped callers see every search; scoped callers only searches started
+    /// by a session of the same tenant.
+    pub fn search_visible_to(&self, search_id: &str, tenant: Option<&str>) -> bool {
+        let Some(tenant) = tenant else {
+            return true;
+        };
+        self.sessions.lock().values().any(|session| {
+            session.tenant.as_deref() == Some(tenant) && session.searches.contains(search_id)
+        })
+    }
+
+    /// Record that the client is still connected; false if it has no session.
+    pub fn heartbeat(&self, client_id: &str) -> bool {
+        self.with_session(client_id, |session| session.last_seen = Instant::now())
+    }
+
+    /// Take one request from the client's bucket; clients without a session
+    /// or without a bucket are never limited.
+    pub fn check_rate(&self, client_id: &str) -> bool {
+        let bucket = self
+            .sessions
+            .lock()
+            .get(client_id)
+            .and_then(|session| session.bucket.clone());
+        bucket.is_none_or(|bucket| bucket.check().is_ok())
+    }
+
+    /// Attribute a search to the client; false if it has no session.
+    pub fn track_search(&self, client_id: &str, search_id: &str) -> bool {
+        self.with_session(client_id, |session| {
+            session.searches.insert(search_id.to_string());
+        })
+    }
+
+    /// Stop tracking a search that was closed explicitly.
+    pub fn forget_search(&self, search_id: &str) {
+        for session in self.sessions.lock().values_mut() {
+            session.searches.remove(search_id);
+        }
+    }
+
+    /// Attribute a follow query to the client; false if it has no session.
+    pub fn track_follow_query(&self, client_id: &str, workspace_id: &str, query_id: &str) -> bool {
+        self.with_session(client_id, |session| {
+            session
+                .follow_queries
+                .insert((workspace_id.to_string(), query_id.to_string()));
+        })
+    }
+
+    /// Stop tracking a follow query that was detached explicitly.
+    pub fn forget_follow_query(&self, workspace_id: &str, query_id: &str) {
+        let key = (workspace_id.to_string(), query_id.to_string());
+        for session in self.sessions.lock().values_mut() {
+            session.follow_queries.remove(&key);
+        }
+    }
+
+    /// End a session and return what it still held.
+    pub fn close(&self, client_id: &str) -> Option<ClientResources> {
+        let session = self.sessions.lock().remove(client_id)?;
+        Some(into_resources(client_id.to_string(), session))
+    }
+
+    /// End every session idle for longer than `timeout`.
+    pub fn reap_idle(&self, timeout: Duration) -> Vec<ClientResources> {
+        let mut sessions = self.sessions.lock();
+        let idle: Vec<String> = sessions
+            .iter()
+            .filter(|(_, session)| session.last_seen.elapsed() >= timeout)
+            .map(|(client_id, _)| client_id.clone())
+            .collect();
+        idle.into_iter()
+            .filter_map(|client_id| {
+                let session = sessions.remove(&client_id)?;
+                Some(into_resources(client_id, session))
+            })
+            .collect()
+    }
+
+    /// Open sessions visible to a caller scoped to `tenant` (all of them when
+    /// unscoped), sorted by client id.
+    pub fn list(&self, tenant: Option<&str>) -> Vec<ClientSessionInfo> {
+        let sessions = self.sessions.lock();
+        let mut infos: Vec<_> = sessions
+            .iter()
+            .filter(|(_, session)| tenant.is_none() || session.tenant.as_deref() == tenant)
+            .map(|(client_id, session)| describe(client_id, session))
+            .collect();
+        infos.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+        infos
+    }
+
+    fn with_session(&self, client_id: &str, f: impl FnOnce(&mut ClientSession)) -> bool {
+        match self.sessions.lock().get_mut(client_id) {
+            Some(session) => {
+                f(session);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn describe(client_id: &str, session: &ClientSession) -> ClientSessionInfo {
+    ClientSessionInfo {
+        client_id: client_id.to_string(),
+        identity: session.identity.clone(),
+        tenant: session.tenant.clone(),
+        opened_at: session.opened_at,
+        idle_ms: session.last_seen.elapsed().as_millis() as u64,
+        searches: session.searches.len(),
+        follow_queries: session.follow_queries.len(),
+        rate_limited: session.bucket.is_some(),
+    }
+}
+
+/// Constant-time key comparison, so response timing does not reveal how many
+/// leading bytes matched.
+fn keys_match(expected: &str, provided: &str) -> bool {
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn into_resources(client_id: String, session: ClientSession) -> ClientResources {
+    ClientResources {
+        client_id,
+        searches: session.searches.into_iter().collect(),
+        follow_queries: session.follow_queries.into_iter().collect(),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_returns_tracked_resources() {
+        let manager = ClientSessionManager::default();
+        manager
+            .open("alice", Some("alice@example.com".into()), None)
+            .unwrap();
+        assert!(manager.track_search("alice", "s-1"));
+        assert!(manager.track_search("alice", "s-2"));
+        assert!(manager.track_follow_query("alice", "ws-1", "errors"));
+        assert!(!manager.track_search("ghost", "s-3"));
+
+        manager.forget_search("s-2");
+        let info = manager.open("alice", None, None).unwrap();
+        assert_eq!(info.identity.as_deref(), Some("alice@example.com"));
+        assert_eq!(info.searches, 1);
+
+        let resources = manager.close("alice").unwrap();
+        assert_eq!(
+            resources,
+            ClientResources {
+                client_id: "alice".into(),
+                searches: vec!["s-1".into()],
+                follow_queries: vec![("ws-1".into(), "errors".into())],
+            }
+        );
+        assert!(manager.close("alice").is_none());
+        assert!(!manager.heartbeat("alice"));
+    }
+
+    #[test]
+    fn test_reap_idle_only_takes_stale_sessions() {
+        let manager = ClientSessionManager::default();
+        manager.open("stale", None, None).unwrap();
+        manager.track_search("stale", "s-1");
+        std::thread::sleep(Duration::from_millis(30));
+        manager.open("fresh", None, None).unwrap();
+
+        let reaped = manager.reap_idle(Duration::from_millis(20));
+        assert_eq!(reaped.len(), 1);
+        assert_eq!(reaped[0].client_id, "stale");
+        assert_eq!(reaped[0].searches, vec!["s-1".to_string()]);
+        assert_eq!(manager.list(None).len(), 1);
+        assert_eq!(manager.list(None)[0].client_id, "fresh");
+    }
+
+    #[test]
+    fn test_rate_bucket_per_client() {
+        let manager = ClientSessionManager::default();
+        manager.configure(&SecurityConfig {
+            rate_limit_per_minute: 2,
+            ..SecurityConfig::default()
+        });
+        manager.open("a", None, None).unwrap();
+        manager.open("b", None, None).unwrap();
+
+        assert!(manager.check_rate("a"));
+        assert!(manager.check_rate("a"));
+        assert!(!manager.check_rate("a"));
+        // Buckets are per client; callers without a session are not limited
+        assert!(manager.check_rate("b"));
+        assert!(manager.check_rate("unknown"));
+
+        manager.configure(&SecurityConfig {
+            rate_limit_enabled: false,
+            ..SecurityConfig::default()
+        });
+        manager.open("c", None, None).unwrap();
+        assert!(
+            !manager
+                .list(None)
+                .iter()
+                .find(|s| s.client_id == "c")
+                .unwrap()
+                .rate_limited
+        );
+    }
+
+    #[test]
+    fn test_tenant_scopes_sessions_and_searches() {
+        let manager = ClientSessionManager::default();
+        manager.open("a1", None, Some("acme".into())).unwrap();
+        manager.open("a2", None, Some("acme".into())).unwrap();
+        manager.open("g1", None, Some("globex".into())).unwrap();
+        manager.open("ops", None, None).unwrap();
+        manager.track_search("a1", "s-acme");
+        manager.track_search("ops", "s-ops");
+
+        assert_eq!(manager.tenant_of("a2").as_deref(), Some("acme"));
+        assert!(manager.tenant_of("ops").is_none());
+        // Reconnecting cannot switch tenants, or drop the tenant
+        assert!(manager.open("a1", None, Some("globex".into())).is_err());
+        assert!(manager.open("a1", None, None).is_err());
+        assert!(manager.open("a1", None, Some("acme".into())).is_ok());
+
+        assert!(manager.search_visible_to("s-acme", Some("acme")));
+        assert!(!manager.search_visible_to("s-acme", Some("globex")));
+        assert!(!manager.search_visible_to("s-ops", Some("acme")));
+        assert!(manager.search_visible_to("s-acme", None));
+
+        let acme: Vec<_> = manager
+            .list(Some("acme"))
+            .into_iter()
+            .map(|s| s.client_id)
+            .collect();
+        assert_eq!(acme, vec!["a1".to_string(), "a2".to_string()]);
+        assert_eq!(manager.list(None).len(), 4);
+    }
+
+    #[test]
+    fn test_authenticate_binds_tenant_to_key() {
+        let manager = ClientSessionManager::default();
+        let mut config = SecurityConfig {
+            api_key: Some("operator-key-0123456789".into()),
+            ..SecurityConfig::default()
+        };
+        config
+            .tenant_keys
+            .insert("acme".into(), "acme-key-0123456789".into());
+        manager.configure(&config);
+
+        // Local mode: keyless sessions are unscoped, wrong keys are still rejected
+        assert_eq!(manager.authenticate(None).unwrap().tenant, None);
+        assert!(manager.authenticate(Some("guess")).is_err());
+        let acme = manager.authenticate(Some("acme-key-0123456789")).unwrap();
+        assert_eq!(acme.tenant.as_deref(), Some("acme"));
+        let operator = manager
+            .authenticate(Some("operator-key-0123456789"))
+            .unwrap();
+        assert_eq!(operator.identity.as_deref(), Some(OPERATOR_IDENTITY));
+        assert_eq!(operator.tenant, None);
+
+        config.auth_enabled = true;
+        manager.configure(&config);
+        assert!(manager.auth_required());
+        assert!(manager.authenticate(None).is_err());
+        assert!(manager.scope_of("nobody").is_none());
+        manager.open("a1", acme.identity, acme.tenant).unwrap();
+        assert_eq!(manager.scope_of("a1"), Some(Some("acme".to_string())));
+    }
+}
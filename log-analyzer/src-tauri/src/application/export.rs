@@ -1,19 +1,47 @@
 //! ExportUseCase — application-layer export data transformation.
 //!
-//! Pure functions that convert search results to CSV/JSON strings.
-//! File I/O and path validation remain in the command layer.
+//! Pure functions that convert search results and workspace statistics to
+//! CSV/JSON strings. File I/O and path validation remain in the command layer.
 
 use la_core::models::LogEntry;
+use la_storage::{DedupReport, LevelCounts, WorkspaceOverview};
+use serde_json::{json, Map, Value};
+
+/// Statistics tables available for export, in output order.
+pub const STATS_TABLES: &[&str] = &[
+    "levelsByDay",
+    "levelsByFile",
+    "coverageGaps",
+    "dedupDistribution",
+    "topDuplicates",
+];
+
+const LEVEL_COLUMNS: [&str; 5] = ["error", "warn", "info", "debug", "total"];
+
+/// One aggregated table: fixed columns, one JSON scalar per cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsTable {
+    pub name: &'static str,
+    pub columns: Vec<&'static str>,
+    pub rows: Vec<Vec<Value>>,
+}
 
 /// Convert search results to CSV format (UTF-8 BOM + quoted fields).
 pub fn transform_csv(entries: &[LogEntry]) -> String {
-    let mut output = String::from("\u{FEFF}ID,Timestamp,Level,File,Line,Content\n");
+    let mut output = String::from("\u{FEFF}ID,Timestamp,Level,File,Line,Content,Links\n");
     for entry in entries {
         let content = entry.content.replace('\"', "\"\"");
         let file = entry.file.replace('\"', "\"\"");
+        let links = entry
+            .links
+            .iter()
+            .flatten()
+            .map(|link| link.url.replace('\"', "\"\""))
+            .collect::<Vec<_>>()
+            .join(" ");
         output.push_str(&format!(
-            "{},\"{}\",{},\"{}\",{},\"{}\"\n",
-            entry.id, entry.timestamp, entry.level, file, entry.line, content
+            "{},\"{}\",{},\"{}\",{},\"{}\",\"{}\"\n",
+            entry.id, entry.timestamp, entry.level, file, entry.line, content, links
         ));
     }
     output
@@ -30,3 +58,276 @@ pub fn transform_json(entries: &[LogEntry]) -> String {
     });
     serde_json::to_string_pretty(&data).unwrap_or_default()
 }
+
+/// Build the requested statistics tables (all of `STATS_TABLES` when `names`
+/// is empty). Unknown names are returned as the error.
+pub fn build_stats_tables(
+    names: &[String],
+    overview: &WorkspaceOverview,
+    dedup: &DedupReport,
+) -> Result<Vec<StatsTable>, String> {
+    if let Some(unknown) = names.iter().find(|n| !STATS_TABLES.contains(&n.as_str())) {
+        return Err(unknown.clone());
+    }
+    let wanted = |name: &str| names.is_empty() || names.iter().any(|n| n == name);
+    let with_levels = |lead: &[&'static str]| -> Vec<&'static str> {
+        lead.iter().copied().chain(LEVEL_COLUMNS).collect()
+    };
+
+    let mut tables = Vec::new();
+    if wanted("levelsByDay") {
+        tables.push(StatsTable {
+            name: "levelsByDay",
+            columns: with_levels(&["day"]),
+            rows: overview
+                .days
+                .iter()
+                .map(|d| level_row(vec![json!(d.day)], &d.counts))
+                .collect(),
+        });
+    }
+    if wanted("levelsByFile") {
+        tables.push(StatsTable {
+            name: "levelsByFile",
+            columns: with_levels(&["file", "firstTimestamp", "lastTimestamp"]),
+            rows: overview
+                .files
+                .iter()
+                .map(|f| {
+                    let lead = vec![
+                        json!(f.virtual_path),
+                        timestamp_cell(f.min_timestamp),
+                        timestamp_cell(f.max_timestamp),
+                    ];
+                    level_row(lead, &f.counts)
+                })
+                .collect(),
+        });
+    }
+    if wanted("coverageGaps") {
+        tables.push(StatsTable {
+            name: "coverageGaps",
+            columns: vec!["startDay", "endDay", "days"],
+            rows: overview
+                .gaps
+                .iter()
+                .map(|g| vec![json!(g.start_day), json!(g.end_day), json!(g.days)])
+                .collect(),
+        });
+    }
+    if wanted("dedupDistribution") {
+        tables.push(StatsTable {
+            name: "dedupDistribution",
+            columns: vec!["refCount", "objects"],
+            rows: dedup
+                .distribution
+                .iter()
+                .map(|b| vec![json!(b.ref_count), json!(b.objects)])
+                .collect(),
+        });
+    }
+    if wanted("topDuplicates") {
+        tables.push(StatsTable {
+            name: "topDuplicates",
+            columns: vec!["sha256", "size", "refCount", "bytesSaved", "files"],
+            rows: dedup
+                .top_duplicates
+                .iter()
+                .map(|o| {
+                    vec![
+                        json!(o.sha256_hash),
+                        json!(o.size),
+                        json!(o.ref_count),
+                        json!(o.bytes_saved),
+                        json!(o.virtual_paths.join(" ")),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    Ok(tables)
+}
+
+fn level_row(mut lead: Vec<Value>, counts: &LevelCounts) -> Vec<Value> {
+    lead.extend(
+        [
+            counts.error,
+            counts.warn,
+            counts.info,
+            counts.debug,
+            counts.total,
+        ]
+        .map(Value::from),
+    );
+    lead
+}
+
+fn timestamp_cell(ts: Option<i64>) -> Value {
+    ts.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| Value::String(dt.to_rfc3339()))
+        .unwrap_or(Value::Null)
+}
+
+/// Convert one statistics table to CSV (UTF-8 BOM, strings quoted, nulls empty).
+pub fn stats_table_csv(table: &StatsTable) -> String {
+    rows_csv(&table.columns, &table.rows)
+}
+
+/// Convert arbitrary rows of JSON scalars to CSV in the same format as
+/// [`stats_table_csv`]. Header cells are quoted only when they need it.
+pub fn rows_csv<S: AsRef<str>>(columns: &[S], rows: &[Vec<Value>]) -> String {
+    let header: Vec<String> = columns
+        .iter()
+        .map(|c| {
+            let c = c.as_ref();
+            if c.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", c.replace('"', "\"\""))
+            } else {
+                c.to_string()
+            }
+        })
+        .collect();
+    let mut output = format!("\u{FEFF}{}\n", header.join(","));
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| match cell {
+                Value::Null => String::new(),
+                Value::String(s) => format!("\"{}\"", s.replace('\"', "\"\"")),
+                other => other.to_string(),
+            })
+            .collect();
+        output.push_str(&cells.join(","));
+        output.push('\n');
+    }
+    output
+}
+
+/// Convert statistics tables to pretty-printed JSON, one array of row
+/// objects per table name.
+pub fn stats_tables_json(workspace_id: &str, tables: &[StatsTable]) -> String {
+    let tables: Map<String, Value> = tables
+        .iter()
+        .map(|table| {
+            let rows = table
+                .rows
+                .iter()
+                .map(|row| {
+                    let object: Map<String, Value> = table
+                        .columns
+                        .iter()
+                        .map(|c| c.to_string())
+                        .zip(row.iter().cloned())
+                        .collect();
+                    Value::Object(object)
+                })
+                .collect();
+            (table.name.to_string(), Value::Array(rows))
+        })
+        .collect();
+    let data = json!({
+        "metadata": {
+            "exportTime": chrono::Utc::now().to_rfc3339(),
+            "workspaceId": workspace_id,
+        },
+        "tables": tables,
+    });
+    serde_json::to_string_pretty(&data).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use la_storage::{CoverageGap, DayOverview, DedupBucket, FileOverview};
+
+    fn overview() -> WorkspaceOverview {
+        let counts = LevelCounts {
+            error: 2,
+            warn: 1,
+            info: 7,
+            debug: 0,
+            total: 10,
+        };
+        WorkspaceOverview {
+            earliest_timestamp: Some(0),
+            latest_timestamp: Some(86_400),
+            totals: counts.clone(),
+            files: vec![FileOverview {
+                virtual_path: "app/\"quoted\".log".into(),
+                min_timestamp: Some(0),
+                max_timestamp: None,
+                counts: counts.clone(),
+            }],
+            days: vec![DayOverview {
+                day: "1970-01-01".into(),
+                counts,
+            }],
+            gaps: vec![CoverageGap {
+                start_day: "1970-01-02".into(),
+                end_day: "1970-01-03".into(),
+                days: 2,
+            }],
+        }
+    }
+
+    fn dedup() -> DedupReport {
+        DedupReport {
+            logical_files: 3,
+            unique_objects: 2,
+            logical_bytes: 30,
+            stored_bytes: 20,
+            bytes_saved: 10,
+            dedup_ratio: 1.5,
+            distribution: vec![DedupBucket {
+                ref_count: 2,
+                objects: 1,
+            }],
+            top_duplicates: vec![],
+        }
+    }
+
+    #[test]
+    fn selects_tables_and_rejects_unknown_names() {
+        let all = build_stats_tables(&[], &overview(), &dedup()).unwrap();
+        let names: Vec<_> = all.iter().map(|t| t.name).collect();
+        assert_eq!(names, STATS_TABLES);
+
+        let one = build_stats_tables(&["coverageGaps".into()], &overview(), &dedup()).unwrap();
+        assert_eq!(one.len(), 1);
+        assert_eq!(
+            one[0].rows,
+            vec![vec![json!("1970-01-02"), json!("1970-01-03"), json!(2)]]
+        );
+
+        let err = build_stats_tables(&["clusters".into()], &overview(), &dedup()).unwrap_err();
+        assert_eq!(err, "clusters");
+    }
+
+    #[test]
+    fn stats_csv_quotes_strings_and_leaves_nulls_empty() {
+        let tables = build_stats_tables(&["levelsByFile".into()], &overview(), &dedup()).unwrap();
+        let csv = stats_table_csv(&tables[0]);
+        let lines: Vec<_> = csv.trim_start_matches('\u{FEFF}').lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "file,firstTimestamp,lastTimestamp,error,warn,info,debug,total",
+                "\"app/\"\"quoted\"\".log\",\"1970-01-01T00:00:00+00:00\",,2,1,7,0,10",
+            ]
+        );
+
+        let json: Value = serde_json::from_str(&stats_tables_json("ws", &tables)).unwrap();
+        assert_eq!(json["tables"]["levelsByFile"][0]["error"], json!(2));
+        assert_eq!(json["metadata"]["workspaceId"], json!("ws"));
+    }
+
+    #[test]
+    fn rows_csv_quotes_header_cells_only_when_needed() {
+        let csv = rows_csv(
+            &["id", "count(*)", "a,b"],
+            &[vec![json!(1), json!(2.5), json!("x")]],
+        );
+        let lines: Vec<_> = csv.trim_start_matches('\u{FEFF}').lines().collect();
+        assert_eq!(lines, vec!["id,count(*),\"a,b\"", "1,2.5,\"x\""]);
+    }
+}
@@ -6,18 +6,27 @@
 //! - **WorkspaceService** trait family: per-workspace service composition (SearchService, ImportService, WatchService)
 //! - Dead use cases removed in P6: ImportUseCase (replaced by ImportService), WorkspaceUseCase + RuntimeWorkspaceRepository (never wired)
 
+pub mod client_sessions;
 pub mod config;
 pub mod export;
+pub mod result_order;
 pub mod search;
 pub mod search_batch;
+pub mod search_concurrency;
+pub mod search_dedup;
 pub mod search_session;
 pub mod virtual_tree;
 pub mod watch;
 pub mod workspace_service;
 
+pub use client_sessions::ClientSessionManager;
 pub use config::ConfigUseCase;
-pub use export::{transform_csv, transform_json};
+pub use export::{
+    build_stats_tables, rows_csv, stats_table_csv, stats_tables_json, transform_csv,
+    transform_json, StatsTable, STATS_TABLES,
+};
 pub use search::SearchUseCase;
+pub use search_concurrency::{ConcurrentSearchManager, SearchConcurrencySnapshot, SearchPermit};
 pub use search_session::SearchSessionManager;
 pub use workspace_service::{
     ImportOptions, ImportResult, ImportService, SearchService, WatchService, WorkspaceService,
@@ -113,6 +122,7 @@ mod tests {
         async fn emit_import_complete(&self, _task_id: &str) {}
         async fn emit_import_error(&self, _error: &str) {}
         async fn emit_validation_report(&self, _workspace_id: &str, _report_json: &str) {}
+        async fn emit_security_warning(&self, _warning: &la_core::domain::event::SecurityWarning) {}
     }
 
     #[tokio::test]
@@ -164,11 +174,11 @@ mod tests {
                 100,
                 "test-search".into(),
                 token,
+                None,
             )
             .await;
 
-        // The search runs in spawn_blocking, so it completes asynchronously.
-        // Just verify no immediate error.
+        // execute() resolves once the blocking search has finished.
         assert!(
             result.is_ok(),
             "SearchUseCase::execute should not error: {:?}",
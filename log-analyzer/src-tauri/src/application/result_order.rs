@@ -0,0 +1,182 @@
+//! ResultOrder — deterministic merge of concurrently scanned files.
+//!
+//! Small files are matched in parallel, one window of files at a time. The
+//! per-file results are merged here, so the order never depends on thread
+//! count or scheduling.
+//!
+//! # Guarantees
+//!
+//! - The same query over the same workspace contents yields the same result
+//!   sequence on every run. Pagination offsets and result diffs are stable.
+//! - Windows follow the file list order: pinned files first, then virtual
+//!   path, then content hash. A large file is streamed whole, in line order,
+//!   between the windows around it.
+//! - Within a window, entries are ordered by [`OrderKey`]: corrected time
+//!   (timestamp plus the file's clock offset), then file content hash, then
+//!   line number. Entries without a parseable timestamp sort before
+//!   timestamped ones.
+//! - Lines of one file never move relative to each other. The merge always
+//!   takes the smallest *head* among the files, so a line whose timestamp is
+//!   out of order (or missing, like a stack-trace continuation) stays next to
+//!   its neighbours.
+//! - Files with identical content share a hash; equal keys fall back to the
+//!   file list order.
+
+use chrono::NaiveDateTime;
+use la_core::models::LogEntry;
+use la_core::utils::TimestampParser;
+
+/// Sort key of one result: (corrected time, file hash, line).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OrderKey<'a> {
+    pub time: Option<NaiveDateTime>,
+    pub file_hash: &'a str,
+    pub line: usize,
+}
+
+impl<'a> OrderKey<'a> {
+    pub fn of(entry: &LogEntry, file_hash: &'a str) -> Self {
+        let time = TimestampParser::parse_naive_datetime(&entry.timestamp)
+            .map(|at| at + chrono::Duration::seconds(entry.time_offset_secs.unwrap_or(0)));
+        Self {
+            time,
+            file_hash,
+            line: entry.line,
+        }
+    }
+}
+
+/// Merge the results of several files, each in its own line order, into one
+/// sequence ordered as described in the module docs.
+///
+/// `runs` is in file list order; each item is the file's content hash and its
+/// entries.
+pub fn merge_file_results(runs: Vec<(&str, Vec<LogEntry>)>) -> Vec<LogEntry> {
+    let total = runs.iter().map(|(_, entries)| entries.len()).sum();
+    let mut runs: Vec<_> = runs
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(hash, entries)| {
+            let mut entries = entries.into_iter().peekable();
+            let key = entries.peek().map(|e| OrderKey::of(e, hash));
+            (hash, key, entries)
+        })
+        .collect();
+
+    if runs.len() == 1 {
+        return runs
+            .pop()
+            .map(|(_, _, entries)| entries.collect())
+            .unwrap_or_default();
+    }
+
+    let mut merged = Vec::with_capacity(total);
+    // min_by_key keeps the first of equal keys, i.e. the earlier file
+    while let Some(next) = runs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (_, key, _))| key.map(|key| (i, key)))
+        .min_by_key(|(_, key)| *key)
+        .map(|(i, _)| i)
+    {
+        let (hash, key, entries) = &mut runs[next];
+        merged.extend(entries.next());
+        *key = entries.peek().map(|e| OrderKey::of(e, hash));
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file: &str, line: usize, timestamp: &str) -> LogEntry {
+        LogEntry {
+            id: line,
+            timestamp: timestamp.into(),
+            level: "INFO".into(),
+            file: file.into(),
+            real_path: file.into(),
+            line,
+            content: format!("{file}:{line}").into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    fn positions(entries: &[LogEntry]) -> Vec<(&str, usize)> {
+        entries.iter().map(|e| (&*e.file, e.line)).collect()
+    }
+
+    #[test]
+    fn interleaves_files_by_time_then_hash_then_line() {
+        let a = vec![
+            entry("a.log", 1, "2024-01-01 10:00:00"),
+            entry("a.log", 2, "2024-01-01 10:00:02"),
+        ];
+        let b = vec![
+            entry("b.log", 1, "2024-01-01 10:00:01"),
+            entry("b.log", 2, "2024-01-01 10:00:02"),
+        ];
+
+        // b.log's hash sorts first, so it wins the 10:00:02 tie
+        let merged = merge_file_results(vec![("h2", a.clone()), ("h1", b.clone())]);
+        assert_eq!(
+            positions(&merged),
+            vec![("a.log", 1), ("b.log", 1), ("b.log", 2), ("a.log", 2)]
+        );
+
+        // Independent of the order the files were handed in
+        let swapped = merge_file_results(vec![("h1", b), ("h2", a)]);
+        assert_eq!(positions(&swapped), positions(&merged));
+    }
+
+    #[test]
+    fn keeps_line_order_within_a_file() {
+        let a = vec![
+            entry("a.log", 1, "2024-01-01 10:00:05"),
+            entry("a.log", 2, ""),
+            entry("a.log", 3, "2024-01-01 10:00:00"),
+        ];
+        let b = vec![entry("b.log", 1, "2024-01-01 10:00:03")];
+
+        let merged = merge_file_results(vec![("h1", a), ("h2", b)]);
+        assert_eq!(
+            positions(&merged),
+            vec![("b.log", 1), ("a.log", 1), ("a.log", 2), ("a.log", 3)]
+        );
+    }
+
+    #[test]
+    fn clock_offsets_and_identical_content_are_ordered() {
+        let mut late = entry("late.log", 1, "2024-01-01 10:00:10");
+        late.time_offset_secs = Some(-20);
+        let early = entry("early.log", 1, "2024-01-01 10:00:00");
+        let copy_a = entry("copy-a.log", 1, "2024-01-01 10:00:00");
+        let copy_b = entry("copy-b.log", 1, "2024-01-01 10:00:00");
+
+        let merged = merge_file_results(vec![
+            ("h9", vec![early]),
+            ("h5", vec![copy_b]),
+            ("h5", vec![copy_a]),
+            ("h1", vec![late]),
+            ("h0", vec![]),
+        ]);
+        assert_eq!(
+            positions(&merged),
+            vec![
+                ("late.log", 1),
+                ("copy-b.log", 1),
+                ("copy-a.log", 1),
+                ("early.log", 1)
+            ]
+        );
+    }
+}
@@ -12,16 +12,28 @@
 //! - 纯批量逻辑提取为 SearchBatch 模块
 //! - 删除 application/search_executor.rs
 
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use la_core::domain::event::EventPublisher;
-use la_core::domain::{ExecutionPlan, LogFileRepository, LogSearcher, SearchResultRepository};
+use la_core::domain::{
+    ContentOverrides, ExecutionPlan, LogFileRepository, LogSearcher, SearchResultRepository,
+};
 use la_core::error::Result;
-use la_core::models::{LogEntry, SearchFilters, SearchQuery};
+use la_core::models::{
+    LogEntry, QueryOperator, SearchFilters, SearchPhaseTimings, SearchQuery, SearchResultSummary,
+    SearchStatisticsCollector, SearchTerm,
+};
 use la_core::storage_types::FileMetadata;
+use la_core::utils::{TimeIndex, TimeIndexPoint};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::application::search_batch::{BatchAction, SearchBatch};
+use crate::application::result_order::merge_file_results;
+use crate::application::search_batch::{BatchAction, SearchBatch, DEFAULT_BATCH_MAX_BYTES};
+use crate::application::search_dedup::{SearchDedup, DEFAULT_DEDUP_WINDOW};
+use crate::application::search_session::SearchSnapshot;
 use crate::services::search_filters::CompiledSearchFilters;
 use crate::utils::encoding::decode_log_content;
 
@@ -39,6 +51,13 @@ pub(crate) struct SearchOutcome {
     pub(crate) total_count: usize,
     pub(crate) duration_ms: u64,
     pub(crate) was_truncated: bool,
+    /// 扫描因取消或超时提前停止
+    pub(crate) was_cancelled: bool,
+    pub(crate) files_scanned: usize,
+    pub(crate) files_total: usize,
+    /// 关键词、文件与时间分布（仅统计写入结果的条目）
+    pub(crate) statistics: SearchResultSummary,
+    pub(crate) phase_timings: SearchPhaseTimings,
 }
 
 /// The application use case for executing a log search.
@@ -48,6 +67,7 @@ pub struct SearchUseCase {
     events: Arc<dyn EventPublisher>,
     searcher: Arc<dyn LogSearcher>,
     thread_pool: Arc<rayon::ThreadPool>,
+    batch_max_bytes: usize,
 }
 
 impl SearchUseCase {
@@ -64,27 +84,41 @@ impl SearchUseCase {
             events,
             searcher,
             thread_pool,
+            batch_max_bytes: DEFAULT_BATCH_MAX_BYTES,
         }
     }
 
-    /// Execute a search query asynchronously.
+    /// Serialized-size budget of one result batch (`search.batch_max_bytes`).
+    pub fn with_batch_budget(mut self, batch_max_bytes: usize) -> Self {
+        self.batch_max_bytes = batch_max_bytes;
+        self
+    }
+
+    /// Pin the data a search will run against: candidate files after metadata
+    /// pruning, per-file clock offsets, per-content parse overrides, the line
+    /// length limit, the level order and the current index generation.
     ///
-    /// CPU-intensive work runs on `spawn_blocking`; this method returns
-    /// immediately. Progress and results are reported via EventPublisher.
-    #[allow(clippy::too_many_arguments)]
-    pub async fn execute(
+    /// CAS objects are immutable per hash, so scanning the pinned file list
+    /// yields the same results no matter what the watcher commits meanwhile.
+    pub async fn pin_snapshot(
         &self,
         workspace_id: &str,
-        query: &la_core::models::SearchQuery,
         filters: &la_core::models::SearchFilters,
-        max_results: usize,
-        search_id: String,
-        cancellation_token: tokio_util::sync::CancellationToken,
-    ) -> Result<()> {
-        let compiled_filters = CompiledSearchFilters::compile(filters)
+        index: Option<la_search::IndexSnapshot>,
+    ) -> Result<SearchSnapshot> {
+        // 最低级别按工作区级别顺序校验；读取失败时使用默认顺序
+        let level_order = match self.log_files.get_level_order().await {
+            Ok(order) => order,
+            Err(e) => {
+                tracing::warn!(workspace_id = %workspace_id, error = %e, "Failed to load level order");
+                None
+            }
+        };
+        let mut filters = filters.clone();
+        filters.level_order = level_order.clone();
+        let compiled_filters = CompiledSearchFilters::compile(&filters)
             .map_err(|e| la_core::error::AppError::validation_error(e.message))?;
 
-        // 1. Get candidate files
         let files = self
             .log_files
             .get_files_with_filters(
@@ -98,6 +132,131 @@ impl SearchUseCase {
             )
             .await?;
 
+        // 时钟偏移按文件校正时间过滤；读取失败时按未校正处理
+        let time_offsets = match self.log_files.get_time_offsets().await {
+            Ok(offsets) => offsets.into_iter().collect(),
+            Err(e) => {
+                tracing::warn!(workspace_id = %workspace_id, error = %e, "Failed to load time offsets");
+                Default::default()
+            }
+        };
+
+        // 超长行截断按工作区设置；读取失败时不截断
+        let max_line_length = match self.log_files.get_max_line_length().await {
+            Ok(limit) => limit,
+            Err(e) => {
+                tracing::warn!(workspace_id = %workspace_id, error = %e, "Failed to load max line length");
+                None
+            }
+        };
+
+        // 用户设置的编码、解析器与时区覆盖；读取失败时按自动识别处理
+        let hashes: Vec<String> = files.iter().map(|f| f.sha256_hash.clone()).collect();
+        let content_overrides = match self.log_files.get_content_overrides(&hashes).await {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                tracing::warn!(workspace_id = %workspace_id, error = %e, "Failed to load file overrides");
+                HashMap::new()
+            }
+        };
+
+        Ok(SearchSnapshot::new(files, time_offsets, index)
+            .with_max_line_length(max_line_length)
+            .with_level_order(level_order)
+            .with_content_overrides(content_overrides))
+    }
+
+    /// Execute a search query asynchronously against freshly pinned data.
+    ///
+    /// See [`Self::execute_pinned`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute(
+        &self,
+        workspace_id: &str,
+        query: &la_core::models::SearchQuery,
+        filters: &la_core::models::SearchFilters,
+        max_results: usize,
+        search_id: String,
+        cancellation_token: tokio_util::sync::CancellationToken,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let snapshot = self.pin_snapshot(workspace_id, filters, None).await?;
+        self.execute_pinned(
+            &snapshot,
+            query,
+            filters,
+            max_results,
+            search_id,
+            cancellation_token,
+            timeout,
+        )
+        .await
+    }
+
+    /// Execute a search query asynchronously over a pinned snapshot.
+    ///
+    /// CPU-intensive work runs on `spawn_blocking`; this method resolves once
+    /// the search has finished so callers can hold resources (cancellation
+    /// token, concurrency slot) for its whole lifetime. Progress and results
+    /// are reported via EventPublisher.
+    ///
+    /// When `timeout` elapses the scan stops like a cancellation: results found
+    /// so far stay in the result session and the summary carries truncation info.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_pinned(
+        &self,
+        snapshot: &SearchSnapshot,
+        query: &la_core::models::SearchQuery,
+        filters: &la_core::models::SearchFilters,
+        max_results: usize,
+        search_id: String,
+        cancellation_token: tokio_util::sync::CancellationToken,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let mut filters_owned = filters.clone();
+        filters_owned.time_offsets = snapshot.time_offsets().clone();
+        filters_owned.max_line_length = snapshot.max_line_length();
+        filters_owned.level_order = snapshot.level_order().cloned();
+        let compiled_filters = CompiledSearchFilters::compile(&filters_owned)
+            .map_err(|e| la_core::error::AppError::validation_error(e.message))?;
+
+        // 1. Candidate files come from the pinned snapshot
+        let selection_started = std::time::Instant::now();
+        let files = snapshot.files().to_vec();
+
+        // 1b. 虚拟路径 glob 范围只依赖元数据，在读取任何文件内容前裁剪扫描列表
+        let files = if compiled_filters.has_path_scope() {
+            let before = files.len();
+            let scoped: Vec<_> = files
+                .into_iter()
+                .filter(|f| compiled_filters.matches_path_scope(&f.virtual_path))
+                .collect();
+            tracing::debug!(
+                search_id = %search_id,
+                before,
+                after = scoped.len(),
+                "Applied virtual-path scope to scan list"
+            );
+            scoped
+        } else {
+            files
+        };
+
+        // 1c. 字面量关键词不可能出现在某文件时（三元组摘要判定），不读取其内容
+        let files = self
+            .skip_files_by_term_sketch(&search_id, query, files)
+            .await;
+
+        // 1d. 有起始时间时，大文件从稀疏时间索引的检查点开始读取
+        let scan_starts = match compiled_filters.time_start {
+            Some(start) => {
+                self.time_scan_starts(&search_id, start, &filters_owned.time_offsets, &files)
+                    .await
+            }
+            None => HashMap::new(),
+        };
+        let file_selection_ms = selection_started.elapsed().as_millis() as u64;
+
         // 2. Create result session. WorkspaceService may pre-create the
         // session before returning search_id so the frontend can safely request
         // page 0 immediately; in that case reuse it instead of truncating.
@@ -111,15 +270,36 @@ impl SearchUseCase {
         // 4. Spawn blocking search
         let sid = search_id.clone();
         let query_owned = query.clone();
-        let filters_owned = filters.clone();
         let files_owned = files.clone();
         let log_files = Arc::clone(&self.log_files);
         let results = Arc::clone(&self.results);
         let events = Arc::clone(&self.events);
         let searcher = Arc::clone(&self.searcher);
         let thread_pool = Arc::clone(&self.thread_pool);
+        let batch_max_bytes = self.batch_max_bytes;
+        let import_progress = snapshot.import_progress();
+        let content_overrides = snapshot.content_overrides().clone();
+
+        // 超时通过子 token 停止扫描，与用户取消共用同一套协作式检查
+        let scan_token = cancellation_token.child_token();
+        let timed_out = Arc::new(AtomicBool::new(false));
+        if let Some(limit) = timeout {
+            let token = scan_token.clone();
+            let flag = Arc::clone(&timed_out);
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(limit) => {
+                        flag.store(true, Ordering::Release);
+                        token.cancel();
+                    }
+                    _ = token.cancelled() => {}
+                }
+            });
+        }
 
-        tokio::task::spawn_blocking(move || {
+        let handle = tokio::task::spawn_blocking(move || {
+            // 搜索结束时停止超时计时器
+            let _stop_timer = scan_token.clone().drop_guard();
             let outcome = Self::run_blocking(
                 &log_files,
                 &results,
@@ -130,9 +310,16 @@ impl SearchUseCase {
                 &query_owned,
                 &filters_owned,
                 &files_owned,
+                &content_overrides,
+                &scan_starts,
                 max_results,
-                cancellation_token,
+                batch_max_bytes,
+                scan_token,
             );
+            let was_cancelled = cancellation_token.is_cancelled();
+            // 计时器可能在扫描刚好完成后才触发，只有确实提前停止才算超时
+            let timed_out =
+                outcome.was_cancelled && !was_cancelled && timed_out.load(Ordering::Acquire);
 
             let _ = results.complete_session(&sid);
             tokio::spawn(async move {
@@ -143,13 +330,31 @@ impl SearchUseCase {
                             total_count: outcome.total_count,
                             duration_ms: outcome.duration_ms,
                             was_truncated: outcome.was_truncated,
+                            was_cancelled,
+                            timed_out,
+                            files_scanned: outcome.files_scanned,
+                            files_total: outcome.files_total,
+                            statistics: outcome.statistics,
+                            phase_timings: SearchPhaseTimings {
+                                file_selection_ms,
+                                ..outcome.phase_timings
+                            },
+                            import_progress,
                         },
                     )
                     .await;
+                // 已收集的部分结果已写入结果会话，前端仍可分页读取
+                if was_cancelled {
+                    events.emit_search_cancelled(&sid).await;
+                } else if timed_out {
+                    events.emit_search_timeout(&sid).await;
+                }
             });
         });
 
-        Ok(())
+        handle
+            .await
+            .map_err(|e| la_core::error::AppError::search_error(format!("Search task failed: {e}")))
     }
 
     /// 阻塞搜索循环 —— 在 spawn_blocking 中调用。
@@ -164,7 +369,10 @@ impl SearchUseCase {
         query: &SearchQuery,
         filters: &SearchFilters,
         files: &[FileMetadata],
+        content_overrides: &HashMap<String, Arc<dyn ContentOverrides>>,
+        scan_starts: &HashMap<String, TimeIndexPoint>,
         max_results: usize,
+        batch_max_bytes: usize,
         cancellation_token: tokio_util::sync::CancellationToken,
     ) -> SearchOutcome {
         let start = std::time::Instant::now();
@@ -181,13 +389,37 @@ impl SearchUseCase {
                     total_count: 0,
                     duration_ms: start.elapsed().as_millis() as u64,
                     was_truncated: false,
+                    was_cancelled: false,
+                    files_scanned: 0,
+                    files_total: files.len(),
+                    statistics: SearchResultSummary::default(),
+                    phase_timings: SearchPhaseTimings {
+                        planning_ms: start.elapsed().as_millis() as u64,
+                        ..Default::default()
+                    },
                 };
             }
         };
+        let planning_ms = start.elapsed().as_millis() as u64;
 
         // ── Search loop ──
-        let mut batch = SearchBatch::new(BATCH_SIZE);
+        let keywords = query
+            .terms
+            .iter()
+            .filter(|t| t.enabled)
+            .map(|t| t.value.clone());
+        let mut batch = SearchBatch::new(BATCH_SIZE)
+            .with_byte_budget(batch_max_bytes)
+            .with_statistics(SearchStatisticsCollector::new(keywords))
+            .with_dedup(filters.dedup_window.map(|window| {
+                SearchDedup::new(if window == 0 {
+                    DEFAULT_DEDUP_WINDOW
+                } else {
+                    window
+                })
+            }));
         let mut was_truncated = false;
+        let mut files_scanned = 0usize;
 
         'outer: for file_batch in files.chunks(FILE_CHUNK_SIZE) {
             if cancellation_token.is_cancelled() {
@@ -204,6 +436,7 @@ impl SearchUseCase {
                 if fm.size >= LARGE_FILE_STREAM_THRESHOLD_BYTES {
                     if !flush_small_files(
                         &small_files,
+                        content_overrides,
                         log_files,
                         searcher,
                         thread_pool,
@@ -216,6 +449,7 @@ impl SearchUseCase {
                         max_results,
                         &mut was_truncated,
                         &cancellation_token,
+                        &mut files_scanned,
                     ) {
                         break 'outer;
                     }
@@ -224,7 +458,12 @@ impl SearchUseCase {
                     if !search_large_file_in_chunks(
                         log_files,
                         searcher,
+                        thread_pool,
                         fm,
+                        content_overrides
+                            .get(&fm.sha256_hash)
+                            .map(|overrides| overrides.as_ref()),
+                        scan_starts.get(&fm.virtual_path).copied(),
                         &plan,
                         filters,
                         results,
@@ -237,6 +476,7 @@ impl SearchUseCase {
                     ) {
                         break 'outer;
                     }
+                    files_scanned += 1;
                 } else {
                     small_files.push(fm);
                 }
@@ -244,6 +484,7 @@ impl SearchUseCase {
 
             if !flush_small_files(
                 &small_files,
+                content_overrides,
                 log_files,
                 searcher,
                 thread_pool,
@@ -256,11 +497,30 @@ impl SearchUseCase {
                 max_results,
                 &mut was_truncated,
                 &cancellation_token,
+                &mut files_scanned,
             ) {
                 break 'outer;
             }
         }
 
+        // 正则超出时间预算：保留已得结果并报告原因
+        if let Some(reason) = budget_exceeded(&plan) {
+            tracing::warn!(search_id = %search_id, reason, "Search stopped by regex time budget");
+            emit_error(events, search_id, reason.to_string());
+        }
+
+        // 去重窗口中尚未释放的条目
+        if let BatchAction::Truncate(_) = batch.release_held(max_results) {
+            was_truncated = true;
+        }
+        if batch.duplicates_removed() > 0 {
+            tracing::debug!(
+                search_id = %search_id,
+                removed = batch.duplicates_removed(),
+                "Collapsed cross-file duplicate results"
+            );
+        }
+
         // Final flush
         if !batch.is_empty() {
             if let Err(e) = results.append_entries(search_id, &batch.take()) {
@@ -270,10 +530,172 @@ impl SearchUseCase {
             }
         }
 
+        let duration_ms = start.elapsed().as_millis() as u64;
+        let total_count = batch.total();
         SearchOutcome {
-            total_count: batch.total(),
-            duration_ms: start.elapsed().as_millis() as u64,
+            total_count,
+            duration_ms,
             was_truncated,
+            was_cancelled: cancellation_token.is_cancelled(),
+            files_scanned,
+            files_total: files.len(),
+            statistics: batch.into_statistics().finish(total_count),
+            phase_timings: SearchPhaseTimings {
+                file_selection_ms: 0,
+                planning_ms,
+                scan_ms: duration_ms.saturating_sub(planning_ms),
+            },
+        }
+    }
+
+    /// 大文件的扫描起点（按虚拟路径）：时间索引中此前没有任何行达到起始时间的最后一个检查点。
+    /// 起始时间按文件时钟偏移换算到文件本地时间；索引缺失或读取失败时从头扫描
+    async fn time_scan_starts(
+        &self,
+        search_id: &str,
+        time_start: chrono::NaiveDateTime,
+        time_offsets: &BTreeMap<String, i64>,
+        files: &[FileMetadata],
+    ) -> HashMap<String, TimeIndexPoint> {
+        let large: Vec<&FileMetadata> = files
+            .iter()
+            .filter(|f| f.size >= LARGE_FILE_STREAM_THRESHOLD_BYTES)
+            .collect();
+        if large.is_empty() {
+            return HashMap::new();
+        }
+
+        let mut hashes: Vec<String> = large.iter().map(|f| f.sha256_hash.clone()).collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        let indexes = match self.log_files.get_time_indexes(&hashes).await {
+            Ok(indexes) => indexes,
+            Err(e) => {
+                tracing::warn!(search_id = %search_id, error = %e, "Failed to load time indexes");
+                return HashMap::new();
+            }
+        };
+        let indexes: HashMap<&str, TimeIndex> = indexes
+            .iter()
+            .filter_map(|(hash, bytes)| Some((hash.as_str(), TimeIndex::from_bytes(bytes)?)))
+            .collect();
+
+        let start = time_start.and_utc().timestamp();
+        let starts: HashMap<String, TimeIndexPoint> = large
+            .into_iter()
+            .filter_map(|f| {
+                let index = indexes.get(f.sha256_hash.as_str())?;
+                let offset = time_offsets.get(&f.virtual_path).copied().unwrap_or(0);
+                let point = index.seek(start.saturating_sub(offset));
+                (point.line > 1).then(|| (f.virtual_path.clone(), point))
+            })
+            .collect();
+        tracing::debug!(
+            search_id = %search_id,
+            files = starts.len(),
+            "Seeking large files via time indexes"
+        );
+        starts
+    }
+
+    /// 用导入时记录的三元组摘要剔除不可能命中的文件；摘要缺失或读取失败时保留文件
+    async fn skip_files_by_term_sketch(
+        &self,
+        search_id: &str,
+        query: &SearchQuery,
+        files: Vec<FileMetadata>,
+    ) -> Vec<FileMetadata> {
+        let Some(requirement) = SketchRequirement::from_query(query) else {
+            return files;
+        };
+
+        let mut hashes: Vec<String> = files.iter().map(|f| f.sha256_hash.clone()).collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        let sketches = match self.log_files.get_term_sketches(&hashes).await {
+            Ok(sketches) => sketches,
+            Err(e) => {
+                tracing::warn!(search_id = %search_id, error = %e, "Failed to load term sketches");
+                return files;
+            }
+        };
+        if sketches.is_empty() {
+            return files;
+        }
+
+        let verdicts: std::collections::HashMap<&str, bool> = sketches
+            .iter()
+            .filter_map(|(hash, bytes)| {
+                let bloom = la_core::utils::TermBloom::from_bytes(bytes)?;
+                Some((hash.as_str(), requirement.may_match(&bloom)))
+            })
+            .collect();
+
+        let before = files.len();
+        let kept: Vec<FileMetadata> = files
+            .into_iter()
+            .filter(|f| {
+                verdicts
+                    .get(f.sha256_hash.as_str())
+                    .copied()
+                    .unwrap_or(true)
+            })
+            .collect();
+        tracing::debug!(
+            search_id = %search_id,
+            before,
+            after = kept.len(),
+            "Skipped files via term sketches"
+        );
+        kept
+    }
+}
+
+/// 可由三元组摘要判定的关键词条件
+#[derive(Debug, PartialEq)]
+enum SketchRequirement {
+    /// AND：每个关键词都必须可能出现
+    All(Vec<String>),
+    /// OR：至少一个关键词可能出现
+    Any(Vec<String>),
+}
+
+impl SketchRequirement {
+    /// 只使用非正则、单模式、≥3 字节的关键词；大小写不敏感时还要求是 ASCII
+    /// （摘要只做了 ASCII 小写化）。NOT 查询不做跳过。
+    fn from_query(query: &SearchQuery) -> Option<Self> {
+        let usable = |term: &SearchTerm| {
+            !term.is_regex
+                && !term.value.contains('|')
+                && term.value.len() >= 3
+                && (term.case_sensitive || term.value.is_ascii())
+        };
+        let mut enabled = query.terms.iter().filter(|t| t.enabled).peekable();
+        enabled.peek()?;
+
+        match query.global_operator {
+            QueryOperator::And => {
+                let values: Vec<String> = enabled
+                    .filter(|t| usable(t))
+                    .map(|t| t.value.clone())
+                    .collect();
+                (!values.is_empty()).then_some(Self::All(values))
+            }
+            QueryOperator::Or => {
+                let terms: Vec<_> = enabled.collect();
+                terms
+                    .iter()
+                    .all(|t| usable(t))
+                    .then(|| Self::Any(terms.iter().map(|t| t.value.clone()).collect()))
+            }
+            QueryOperator::Not => None,
+        }
+    }
+
+    fn may_match(&self, bloom: &la_core::utils::TermBloom) -> bool {
+        match self {
+            Self::All(values) => values.iter().all(|v| bloom.may_contain(v)),
+            Self::Any(values) => values.iter().any(|v| bloom.may_contain(v)),
         }
     }
 }
@@ -281,6 +703,7 @@ impl SearchUseCase {
 #[allow(clippy::too_many_arguments)]
 fn flush_small_files(
     files: &[&FileMetadata],
+    content_overrides: &HashMap<String, Arc<dyn ContentOverrides>>,
     log_files: &Arc<dyn LogFileRepository>,
     searcher: &Arc<dyn LogSearcher>,
     thread_pool: &Arc<rayon::ThreadPool>,
@@ -293,45 +716,64 @@ fn flush_small_files(
     max_results: usize,
     was_truncated: &mut bool,
     cancellation_token: &tokio_util::sync::CancellationToken,
+    files_scanned: &mut usize,
 ) -> bool {
     if files.is_empty() {
         return true;
     }
 
-    let chunk_results: Vec<Vec<LogEntry>> = thread_pool.install(|| {
+    // None = 因取消 / 超时未扫描
+    let chunk_results: Vec<Option<Vec<LogEntry>>> = thread_pool.install(|| {
         files
             .par_iter()
             .map(|fm| {
                 if cancellation_token.is_cancelled() {
-                    return Vec::new();
+                    return None;
                 }
-                search_one_file(log_files, searcher, fm, plan, filters)
+                let overrides = content_overrides
+                    .get(&fm.sha256_hash)
+                    .map(|overrides| overrides.as_ref());
+                Some(search_one_file(
+                    log_files, searcher, fm, overrides, plan, filters,
+                ))
             })
             .collect()
     });
 
-    for file_results in chunk_results {
-        if !consume_search_entries(
-            file_results,
-            results,
-            events,
-            search_id,
-            batch,
-            max_results,
-            was_truncated,
-        ) {
-            return false;
-        }
+    // 并行扫描的结果按（时间, 文件哈希, 行号）合并，顺序与调度无关
+    let runs: Vec<(&str, Vec<LogEntry>)> = files
+        .iter()
+        .zip(chunk_results)
+        .filter_map(|(fm, entries)| Some((fm.sha256_hash.as_str(), entries?)))
+        .collect();
+    let scanned = runs.len();
+    let keep_searching = consume_search_entries(
+        merge_file_results(runs),
+        results,
+        events,
+        search_id,
+        batch,
+        max_results,
+        was_truncated,
+    );
+    *files_scanned += scanned;
+    if !keep_searching {
+        return false;
     }
 
-    true
+    budget_exceeded(plan).is_none()
 }
 
+/// 大文件分块并行扫描：按行边界流式读取，每攒满一个窗口（线程数 × 2 块）就在
+/// 搜索线程池上并行匹配，再按块顺序合并结果，保证输出顺序与单线程扫描一致。
 #[allow(clippy::too_many_arguments)]
 fn search_large_file_in_chunks(
     log_files: &Arc<dyn LogFileRepository>,
     searcher: &Arc<dyn LogSearcher>,
+    thread_pool: &Arc<rayon::ThreadPool>,
     fm: &FileMetadata,
+    overrides: Option<&dyn ContentOverrides>,
+    scan_start: Option<TimeIndexPoint>,
     plan: &ExecutionPlan,
     filters: &SearchFilters,
     results: &Arc<dyn SearchResultRepository>,
@@ -344,90 +786,158 @@ fn search_large_file_in_chunks(
 ) -> bool {
     let hash = &fm.sha256_hash;
     let real_path = format!("cas://{hash}");
+    let window = (thread_pool.current_num_threads() * 2).max(2);
+    let mut pending: Vec<(Vec<String>, usize)> = Vec::with_capacity(window);
     let mut keep_searching = true;
+
+    let mut scan_window = |pending: &mut Vec<(Vec<String>, usize)>, batch: &mut SearchBatch| {
+        // None = 因取消 / 超时未扫描
+        let chunk_results: Vec<Option<Vec<LogEntry>>> = thread_pool.install(|| {
+            pending
+                .par_iter()
+                .map(|(lines, start_line)| {
+                    if cancellation_token.is_cancelled() {
+                        return None;
+                    }
+                    Some(match_line_chunk(
+                        searcher,
+                        &fm.virtual_path,
+                        &real_path,
+                        overrides,
+                        plan,
+                        filters,
+                        lines,
+                        *start_line,
+                    ))
+                })
+                .collect()
+        });
+        pending.clear();
+
+        for entries in chunk_results {
+            let Some(mut entries) = entries else {
+                return false;
+            };
+            let id_offset = batch.total();
+            for entry in &mut entries {
+                entry.id += id_offset;
+            }
+            if !consume_search_entries(
+                entries,
+                results,
+                events,
+                search_id,
+                batch,
+                max_results,
+                was_truncated,
+            ) {
+                return false;
+            }
+        }
+        // 文件级预算由匹配器跨块累计匹配耗时
+        budget_exceeded(plan).is_none()
+    };
+
     let mut visitor = |chunk_lines: Vec<String>, chunk_start_line: usize| {
         if cancellation_token.is_cancelled() {
             keep_searching = false;
             return Ok(false);
         }
 
-        keep_searching = search_line_chunk(
-            searcher,
-            &fm.virtual_path,
-            &real_path,
-            plan,
-            filters,
-            &chunk_lines,
-            chunk_start_line,
-            results,
-            events,
-            search_id,
-            batch,
-            max_results,
-            was_truncated,
-        );
+        pending.push((chunk_lines, chunk_start_line));
+        if pending.len() >= window {
+            keep_searching = scan_window(&mut pending, batch);
+        }
         Ok(keep_searching)
     };
 
-    match log_files.read_line_chunks_sync(hash, SEARCH_LINE_CHUNK_SIZE, &mut visitor) {
-        Ok(()) => keep_searching,
-        Err(_) => true,
+    // 读取中途失败（如块校验发现损坏）时，已读出的行仍照常匹配
+    let read = match scan_start {
+        // 检查点之前的行都早于起始时间，不会通过时间过滤
+        Some(point) => log_files.read_line_chunks_from_sync(
+            hash,
+            overrides,
+            SEARCH_LINE_CHUNK_SIZE,
+            point.offset,
+            point.line,
+            &mut visitor,
+        ),
+        None => {
+            log_files.read_line_chunks_sync(hash, overrides, SEARCH_LINE_CHUNK_SIZE, &mut visitor)
+        }
+    };
+    // 读取失败只结束本文件；是否继续扫描其余文件仍由结果上限、预算与取消决定
+    if let Err(e) = read {
+        tracing::warn!(search_id = %search_id, file = %fm.virtual_path, error = %e, "Stopped scanning unreadable file");
     }
+
+    if keep_searching && !pending.is_empty() {
+        keep_searching = scan_window(&mut pending, batch);
+    }
+
+    keep_searching
 }
 
+/// 正则匹配超出时间预算的原因（计划未设置预算时为 None）
+fn budget_exceeded(plan: &ExecutionPlan) -> Option<&str> {
+    plan.budget.as_ref().and_then(|budget| budget.exceeded())
+}
+
+/// 扫描一个小文件；有用户覆盖时按覆盖的编码解码，并对匹配结果应用解析器与时区覆盖
 fn search_one_file(
     log_files: &Arc<dyn LogFileRepository>,
     searcher: &Arc<dyn LogSearcher>,
     fm: &FileMetadata,
+    overrides: Option<&dyn ContentOverrides>,
     plan: &ExecutionPlan,
     filters: &SearchFilters,
 ) -> Vec<LogEntry> {
     let hash = &fm.sha256_hash;
     let content = match log_files.read_content_sync(hash) {
         Ok(bytes) => bytes,
-        Err(_) => return Vec::new(),
+        Err(e) => {
+            tracing::warn!(file = %fm.virtual_path, error = %e, "Skipped unreadable file");
+            return Vec::new();
+        }
     };
 
-    let (text, _) = decode_log_content(&content);
+    let text = match overrides {
+        Some(overrides) => overrides.decode(&content),
+        None => decode_log_content(&content).0,
+    };
     let mut entries = searcher.match_content(&text, &fm.virtual_path, plan, filters, 0);
+    if let Some(overrides) = overrides {
+        overrides.apply(&mut entries);
+    }
     for entry in &mut entries {
         entry.real_path = format!("cas://{hash}").into();
     }
     entries
 }
 
+/// 匹配一个行块；返回条目的 id 从 0 开始，由调用方按合并顺序偏移
 #[allow(clippy::too_many_arguments)]
-fn search_line_chunk(
+fn match_line_chunk(
     searcher: &Arc<dyn LogSearcher>,
     virtual_path: &str,
     real_path: &str,
+    overrides: Option<&dyn ContentOverrides>,
     plan: &ExecutionPlan,
     filters: &SearchFilters,
     lines: &[String],
     start_line: usize,
-    results: &Arc<dyn SearchResultRepository>,
-    events: &Arc<dyn EventPublisher>,
-    search_id: &str,
-    batch: &mut SearchBatch,
-    max_results: usize,
-    was_truncated: &mut bool,
-) -> bool {
+) -> Vec<LogEntry> {
     let text = lines.join("\n");
-    let mut entries = searcher.match_content(&text, virtual_path, plan, filters, batch.total());
+    let mut entries = searcher.match_content(&text, virtual_path, plan, filters, 0);
+    if let Some(overrides) = overrides {
+        overrides.apply(&mut entries);
+    }
     let line_offset = start_line.saturating_sub(1);
     for entry in &mut entries {
         entry.line += line_offset;
         entry.real_path = real_path.to_string().into();
     }
-    consume_search_entries(
-        entries,
-        results,
-        events,
-        search_id,
-        batch,
-        max_results,
-        was_truncated,
-    )
+    entries
 }
 
 fn consume_search_entries(
@@ -458,9 +968,11 @@ fn flush_search_batch(
     search_id: &str,
     batch: &mut SearchBatch,
 ) -> bool {
-    if let Err(e) = results.append_entries(search_id, &batch.take()) {
-        emit_error(events, search_id, e.to_string());
-        return false;
+    for entries in batch.take_batches() {
+        if let Err(e) = results.append_entries(search_id, &entries) {
+            emit_error(events, search_id, e.to_string());
+            return false;
+        }
     }
     emit_progress(events, search_id, batch.total());
     true
@@ -538,6 +1050,7 @@ mod tests {
                 plan: Some(Arc::new(StubMatchPlan {
                     matches_per_line: self.matches_per_line,
                 })),
+                budget: None,
             })
         }
 
@@ -564,6 +1077,12 @@ mod tests {
                             tags: vec![],
                             match_details: None,
                             matched_keywords: None,
+                            time_offset_secs: None,
+                            links: None,
+                            translation: None,
+                            source_files: None,
+                            original_length: None,
+                            fields: None,
                         });
                     }
                 }
@@ -655,6 +1174,7 @@ mod tests {
         fn read_line_chunks_sync(
             &self,
             _hash: &str,
+            _overrides: Option<&dyn ContentOverrides>,
             chunk_size: usize,
             visitor: &mut dyn FnMut(Vec<String>, usize) -> Result<bool>,
         ) -> Result<()> {
@@ -683,6 +1203,50 @@ mod tests {
         }
     }
 
+    /// 每个文件读出一块后报告读取失败，记录被读取的文件
+    struct FailingReadLogFiles {
+        lines: Vec<String>,
+        reads: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl LogFileRepository for FailingReadLogFiles {
+        async fn get_files_with_filters(
+            &self,
+            _ws: &str,
+            _ts: Option<i64>,
+            _te: Option<i64>,
+            _lm: Option<u8>,
+            _fp: Option<&str>,
+        ) -> Result<Vec<FileMetadata>> {
+            Ok(Vec::new())
+        }
+
+        fn read_content_sync(&self, hash: &str) -> Result<Vec<u8>> {
+            self.reads.lock().unwrap().push(hash.to_string());
+            Ok(self.lines.join("\n").into_bytes())
+        }
+
+        fn read_line_chunks_sync(
+            &self,
+            hash: &str,
+            _overrides: Option<&dyn ContentOverrides>,
+            _chunk_size: usize,
+            visitor: &mut dyn FnMut(Vec<String>, usize) -> Result<bool>,
+        ) -> Result<()> {
+            self.reads.lock().unwrap().push(hash.to_string());
+            visitor(self.lines.clone(), 1)?;
+            Err(la_core::error::AppError::archive_error(
+                "Corrupted chunk",
+                None,
+            ))
+        }
+
+        fn file_exists_sync(&self, _hash: &str) -> bool {
+            true
+        }
+    }
+
     // ── Stub SearchResultRepository that captures appends ──
 
     struct CapturingResults {
@@ -763,6 +1327,7 @@ mod tests {
         async fn emit_import_complete(&self, _task_id: &str) {}
         async fn emit_import_error(&self, _error: &str) {}
         async fn emit_validation_report(&self, _ws: &str, _report_json: &str) {}
+        async fn emit_security_warning(&self, _warning: &la_core::domain::event::SecurityWarning) {}
     }
 
     // ── Test helpers ──
@@ -859,7 +1424,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &[],
+            &HashMap::new(),
+            &HashMap::new(),
             1000,
+            DEFAULT_BATCH_MAX_BYTES,
             tokio_util::sync::CancellationToken::new(),
         );
 
@@ -888,7 +1456,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &files,
+            &HashMap::new(),
+            &HashMap::new(),
             1000,
+            DEFAULT_BATCH_MAX_BYTES,
             tokio_util::sync::CancellationToken::new(),
         );
 
@@ -897,6 +1468,41 @@ mod tests {
         assert_eq!(results.entries.lock().unwrap().len(), 3);
     }
 
+    #[test]
+    fn stopped_scan_reports_files_scanned() {
+        let (use_case, _results, _events) = make_test_use_case("line1\nline2\n", 1);
+        let files = make_test_files();
+
+        let run = |token: tokio_util::sync::CancellationToken| {
+            SearchUseCase::run_blocking(
+                &use_case.log_files,
+                &use_case.results,
+                &use_case.events,
+                &use_case.searcher,
+                &use_case.thread_pool,
+                "search-stopped",
+                &make_query(),
+                &SearchFilters::default(),
+                &files,
+                &HashMap::new(),
+                &HashMap::new(),
+                1000,
+                DEFAULT_BATCH_MAX_BYTES,
+                token,
+            )
+        };
+
+        let complete = run(tokio_util::sync::CancellationToken::new());
+        assert!(!complete.was_cancelled);
+        assert_eq!((complete.files_scanned, complete.files_total), (1, 1));
+
+        let token = tokio_util::sync::CancellationToken::new();
+        token.cancel();
+        let stopped = run(token);
+        assert!(stopped.was_cancelled);
+        assert_eq!((stopped.files_scanned, stopped.files_total), (0, 1));
+    }
+
     // ===================================================================
     // Test 3: Batch flush boundary
     // ===================================================================
@@ -919,7 +1525,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &files,
+            &HashMap::new(),
+            &HashMap::new(),
             20_000,
+            DEFAULT_BATCH_MAX_BYTES,
             tokio_util::sync::CancellationToken::new(),
         );
 
@@ -981,7 +1590,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &files,
+            &HashMap::new(),
+            &HashMap::new(),
             20_000,
+            DEFAULT_BATCH_MAX_BYTES,
             tokio_util::sync::CancellationToken::new(),
         );
 
@@ -996,6 +1608,163 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_error_after_result_cap_stops_scanning_other_files() {
+        let log_files = Arc::new(FailingReadLogFiles {
+            lines: (0..100).map(|i| format!("line {i}")).collect(),
+            reads: Mutex::new(Vec::new()),
+        });
+        let (use_case, results, _events) = make_test_use_case("", 1);
+        let mut files = make_test_files();
+        files[0].size = 512 * 1024;
+        files.push(FileMetadata {
+            id: 2,
+            sha256_hash: "def456".into(),
+            virtual_path: "other.log".into(),
+            ..files[0].clone()
+        });
+
+        let outcome = SearchUseCase::run_blocking(
+            &(log_files.clone() as Arc<dyn LogFileRepository>),
+            &use_case.results,
+            &use_case.events,
+            &use_case.searcher,
+            &use_case.thread_pool,
+            "search-read-error",
+            &make_query(),
+            &SearchFilters::default(),
+            &files,
+            &HashMap::new(),
+            &HashMap::new(),
+            10,
+            DEFAULT_BATCH_MAX_BYTES,
+            tokio_util::sync::CancellationToken::new(),
+        );
+
+        // 读取失败前的行仍参与匹配，达到上限后不再读取下一个文件
+        assert_eq!(outcome.total_count, 10);
+        assert_eq!(results.entries.lock().unwrap().len(), 10);
+        assert_eq!(*log_files.reads.lock().unwrap(), vec!["abc123".to_string()]);
+    }
+
+    #[test]
+    fn large_file_scan_starts_at_time_index_checkpoint() {
+        let lines: Vec<String> = (0..10_000).map(|i| format!("line {i}")).collect();
+        let results = Arc::new(CapturingResults::new());
+        let log_files: Arc<dyn LogFileRepository> = Arc::new(StreamingProbeLogFiles {
+            lines,
+            append_count: results.append_count.clone(),
+            saw_append_before_eof: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            chunks_read: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        });
+        let searcher: Arc<dyn LogSearcher> = Arc::new(StubSearcher {
+            plan_id: 42,
+            matches_per_line: 1,
+        });
+        let use_case = SearchUseCase::new(
+            log_files,
+            results.clone() as Arc<dyn SearchResultRepository>,
+            Arc::new(CapturingEvents::new()) as Arc<dyn EventPublisher>,
+            searcher,
+            Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(1)
+                    .build()
+                    .unwrap(),
+            ),
+        );
+        let mut files = make_test_files();
+        files[0].sha256_hash = "streaming-hash".into();
+        files[0].virtual_path = "large.log".into();
+        files[0].size = 512 * 1024;
+        let scan_starts = HashMap::from([(
+            "large.log".to_string(),
+            TimeIndexPoint {
+                line: 6_001,
+                offset: 0,
+                max_before: Some(0),
+            },
+        )]);
+
+        let outcome = SearchUseCase::run_blocking(
+            &use_case.log_files,
+            &use_case.results,
+            &use_case.events,
+            &use_case.searcher,
+            &use_case.thread_pool,
+            "search-seek",
+            &make_query(),
+            &SearchFilters::default(),
+            &files,
+            &HashMap::new(),
+            &scan_starts,
+            20_000,
+            DEFAULT_BATCH_MAX_BYTES,
+            tokio_util::sync::CancellationToken::new(),
+        );
+
+        assert_eq!(outcome.total_count, 4_000);
+        let captured = results.entries.lock().unwrap();
+        assert_eq!(captured[0].line, 6_001);
+        assert_eq!(&*captured[0].content, "line 6000");
+    }
+
+    #[test]
+    fn large_file_parallel_chunks_keep_line_order() {
+        let lines: Vec<String> = (0..10_000).map(|i| format!("line {i}")).collect();
+        let results = Arc::new(CapturingResults::new());
+        let log_files: Arc<dyn LogFileRepository> = Arc::new(StreamingProbeLogFiles {
+            lines,
+            append_count: results.append_count.clone(),
+            saw_append_before_eof: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            chunks_read: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+        });
+        let searcher: Arc<dyn LogSearcher> = Arc::new(StubSearcher {
+            plan_id: 42,
+            matches_per_line: 1,
+        });
+        let thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(4)
+                .build()
+                .unwrap(),
+        );
+        let use_case = SearchUseCase::new(
+            log_files,
+            results.clone() as Arc<dyn SearchResultRepository>,
+            Arc::new(CapturingEvents::new()) as Arc<dyn EventPublisher>,
+            searcher,
+            thread_pool,
+        );
+        let mut files = make_test_files();
+        files[0].sha256_hash = "streaming-hash".into();
+        files[0].size = 512 * 1024;
+
+        let outcome = SearchUseCase::run_blocking(
+            &use_case.log_files,
+            &use_case.results,
+            &use_case.events,
+            &use_case.searcher,
+            &use_case.thread_pool,
+            "search-parallel",
+            &make_query(),
+            &SearchFilters::default(),
+            &files,
+            &HashMap::new(),
+            &HashMap::new(),
+            20_000,
+            DEFAULT_BATCH_MAX_BYTES,
+            tokio_util::sync::CancellationToken::new(),
+        );
+
+        assert_eq!(outcome.total_count, 10_000);
+        let captured = results.entries.lock().unwrap();
+        assert!(captured
+            .iter()
+            .enumerate()
+            .all(|(i, e)| e.line == i + 1 && e.id == i));
+    }
+
     #[tokio::test]
     async fn final_flush_emits_progress_for_small_result_set() {
         let (use_case, _results, events) = make_test_use_case("line1\nline2\nline3\n", 1);
@@ -1011,7 +1780,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &files,
+            &HashMap::new(),
+            &HashMap::new(),
             1000,
+            DEFAULT_BATCH_MAX_BYTES,
             tokio_util::sync::CancellationToken::new(),
         );
 
@@ -1042,7 +1814,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &files,
+            &HashMap::new(),
+            &HashMap::new(),
             3,
+            DEFAULT_BATCH_MAX_BYTES,
             tokio_util::sync::CancellationToken::new(),
         );
 
@@ -1074,7 +1849,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &files,
+            &HashMap::new(),
+            &HashMap::new(),
             10000,
+            DEFAULT_BATCH_MAX_BYTES,
             token,
         );
 
@@ -1143,7 +1921,10 @@ mod tests {
             &make_query(),
             &SearchFilters::default(),
             &files,
+            &HashMap::new(),
+            &HashMap::new(),
             1000,
+            DEFAULT_BATCH_MAX_BYTES,
             tokio_util::sync::CancellationToken::new(),
         );
 
@@ -1156,4 +1937,38 @@ mod tests {
         }
         assert_eq!(*events.error_count.lock().unwrap(), 1);
     }
+
+    #[test]
+    fn sketch_requirement_only_uses_decidable_terms() {
+        let bloom =
+            la_core::utils::TermBloom::from_content(b"2024-01-15 ERROR connection timeout\n")
+                .unwrap();
+
+        let mut query = make_query();
+        query.global_operator = QueryOperator::And;
+        query.terms[0].value = "Timeout".into();
+        let mut regex_term = query.terms[0].clone();
+        regex_term.is_regex = true;
+        regex_term.value = "dead.*lock".into();
+        query.terms.push(regex_term);
+        let requirement = SketchRequirement::from_query(&query).unwrap();
+        assert_eq!(requirement, SketchRequirement::All(vec!["Timeout".into()]));
+        assert!(requirement.may_match(&bloom));
+
+        query.terms[0].value = "deadlock".into();
+        assert!(!SketchRequirement::from_query(&query)
+            .unwrap()
+            .may_match(&bloom));
+
+        // OR 只有在所有关键词都可判定时才能跳过
+        query.global_operator = QueryOperator::Or;
+        assert_eq!(SketchRequirement::from_query(&query), None);
+        query.terms.pop();
+        query.terms[0].value = "ab".into();
+        assert_eq!(SketchRequirement::from_query(&query), None);
+
+        query.global_operator = QueryOperator::Not;
+        query.terms[0].value = "deadlock".into();
+        assert_eq!(SketchRequirement::from_query(&query), None);
+    }
 }
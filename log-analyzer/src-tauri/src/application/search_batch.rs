@@ -2,15 +2,71 @@
 //!
 //! Extracted from SearchExecutor so that the decision of when to flush,
 //! truncate, or continue becomes testable pure logic without async or I/O.
+//!
+//! Batches are bounded by estimated serialized size as well as entry count:
+//! a few thousand long stack-trace lines can be megabytes, while the same
+//! count of short lines is tiny. The entry target adapts to a running average
+//! of observed entry sizes so the byte budget is reached in either case.
+
+use la_core::models::{LogEntry, SearchStatisticsCollector};
+
+use crate::application::search_dedup::SearchDedup;
+
+/// Default serialized-size budget of one batch (`search.batch_max_bytes`).
+pub const DEFAULT_BATCH_MAX_BYTES: usize = 1024 * 1024;
 
-use la_core::models::LogEntry;
+/// Field names, quoting and the numeric fields of one serialized entry.
+const ENTRY_OVERHEAD_BYTES: usize = 160;
+
+/// Cheap estimate of an entry's JSON size, without serializing it.
+pub fn estimated_entry_bytes(entry: &LogEntry) -> usize {
+    let keywords: usize = entry
+        .matched_keywords
+        .iter()
+        .flatten()
+        .map(|k| k.len() + 3)
+        .sum();
+    let tags: usize = entry.tags.iter().map(|t| t.len() + 3).sum();
+    ENTRY_OVERHEAD_BYTES
+        + entry.timestamp.len()
+        + entry.level.len()
+        + entry.file.len()
+        + entry.real_path.len()
+        + entry.content.len()
+        + keywords
+        + tags
+        + entry.match_details.as_ref().map_or(0, |d| d.len() * 80)
+        + entry.translation.as_ref().map_or(0, String::len)
+}
+
+/// Split entries into consecutive groups of at most `max_bytes` (estimated)
+/// each; an entry larger than the budget travels alone.
+pub fn split_by_byte_budget(entries: Vec<LogEntry>, max_bytes: usize) -> Vec<Vec<LogEntry>> {
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    let mut current_bytes = 0;
+    for entry in entries {
+        let bytes = estimated_entry_bytes(&entry);
+        if !current.is_empty() && current_bytes + bytes > max_bytes {
+            groups.push(std::mem::take(&mut current));
+            current_bytes = 0;
+        }
+        current_bytes += bytes;
+        current.push(entry);
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
 
 /// Action returned by `SearchBatch::accumulate` after ingesting a chunk.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BatchAction {
     /// All entries fit; no flush needed yet.
     Continue,
-    /// Buffer reached batch size — caller should flush and keep looping.
+    /// Buffer reached its entry target or byte budget — caller should flush
+    /// and keep looping.
     Flush,
     /// Max results hit after taking `n` entries — caller should flush and stop.
     Truncate(usize),
@@ -23,7 +79,16 @@ pub enum BatchAction {
 pub struct SearchBatch {
     buffer: Vec<LogEntry>,
     total: usize,
+    /// Upper bound on entries per batch, however short they are.
     batch_size: usize,
+    max_bytes: usize,
+    buffer_bytes: usize,
+    /// Exponential moving average of estimated entry size; 0 until the first entry.
+    avg_entry_bytes: usize,
+    /// Statistics over every entry actually taken (not the truncated tail).
+    statistics: SearchStatisticsCollector,
+    /// Cross-file deduplication; entries pass through it before being counted.
+    dedup: Option<SearchDedup>,
 }
 
 impl SearchBatch {
@@ -32,7 +97,39 @@ impl SearchBatch {
             buffer: Vec::new(),
             total: 0,
             batch_size,
+            max_bytes: DEFAULT_BATCH_MAX_BYTES,
+            buffer_bytes: 0,
+            avg_entry_bytes: 0,
+            statistics: SearchStatisticsCollector::default(),
+            dedup: None,
+        }
+    }
+
+    /// Flush once the buffered entries are estimated to exceed `max_bytes`.
+    pub fn with_byte_budget(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes.max(1);
+        self
+    }
+
+    /// Track per-term counts starting from the query's keywords.
+    pub fn with_statistics(mut self, statistics: SearchStatisticsCollector) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    /// Collapse cross-file duplicates before entries are counted or buffered.
+    pub fn with_dedup(mut self, dedup: Option<SearchDedup>) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Entries per batch given the sizes seen so far: the byte budget divided
+    /// by the average entry size, capped at `batch_size`.
+    pub fn target_entries(&self) -> usize {
+        if self.avg_entry_bytes == 0 {
+            return self.batch_size;
         }
+        (self.max_bytes / self.avg_entry_bytes).clamp(1, self.batch_size.max(1))
     }
 
     /// Ingest a chunk of entries and decide the next action.
@@ -43,10 +140,32 @@ impl SearchBatch {
     ///
     /// # Returns
     /// * `Continue` — entries added, no flush needed
-    /// * `Flush` — buffer full, caller should `take()` and flush
+    /// * `Flush` — buffer full, caller should `take_batches()` and flush
     /// * `Truncate(n)` — only `n` entries were taken (max_results hit),
-    ///   caller should `take()` and flush, then stop
+    ///   caller should `take_batches()` and flush, then stop
     pub fn accumulate(&mut self, entries: Vec<LogEntry>, max_results: usize) -> BatchAction {
+        let entries = match &mut self.dedup {
+            Some(dedup) => dedup.push(entries),
+            None => entries,
+        };
+        self.take_entries(entries, max_results)
+    }
+
+    /// Release entries still held for deduplication (end of the scan) and
+    /// decide the next action like `accumulate`.
+    pub fn release_held(&mut self, max_results: usize) -> BatchAction {
+        match self.dedup.as_mut().map(SearchDedup::drain) {
+            Some(held) if !held.is_empty() => self.take_entries(held, max_results),
+            _ => BatchAction::Continue,
+        }
+    }
+
+    /// Duplicates dropped so far.
+    pub fn duplicates_removed(&self) -> usize {
+        self.dedup.as_ref().map_or(0, SearchDedup::removed)
+    }
+
+    fn take_entries(&mut self, mut entries: Vec<LogEntry>, max_results: usize) -> BatchAction {
         let remaining = max_results.saturating_sub(self.total);
 
         if remaining == 0 {
@@ -59,32 +178,60 @@ impl SearchBatch {
             };
         }
 
-        if entries.len() > remaining {
-            // Only some entries fit before hitting max_results.
-            let to_take = remaining;
-            self.buffer.extend(entries.into_iter().take(to_take));
-            self.total += to_take;
-            return BatchAction::Truncate(to_take);
-        }
-
-        // All entries fit.
+        // Only some entries may fit before hitting max_results.
+        let truncated = entries.len() > remaining;
+        entries.truncate(remaining);
         let added = entries.len();
+        for entry in &entries {
+            self.statistics.record(entry);
+            self.observe(estimated_entry_bytes(entry));
+        }
         self.buffer.extend(entries);
         self.total += added;
 
+        if truncated {
+            return BatchAction::Truncate(added);
+        }
+
         if self.total >= max_results {
             return BatchAction::Flush;
         }
 
-        if self.buffer.len() >= self.batch_size {
+        if self.buffer.len() >= self.target_entries() || self.buffer_bytes >= self.max_bytes {
             return BatchAction::Flush;
         }
 
         BatchAction::Continue
     }
 
+    fn observe(&mut self, bytes: usize) {
+        self.buffer_bytes += bytes;
+        self.avg_entry_bytes = if self.avg_entry_bytes == 0 {
+            bytes
+        } else {
+            (self.avg_entry_bytes * 7 + bytes) / 8
+        };
+    }
+
+    /// Drain the buffer as groups that each respect the byte budget and the
+    /// current entry target. A single file chunk can overshoot both, so the
+    /// caller writes each group separately.
+    pub fn take_batches(&mut self) -> Vec<Vec<LogEntry>> {
+        let target = self.target_entries();
+        let mut batches = Vec::new();
+        for mut group in split_by_byte_budget(self.take(), self.max_bytes) {
+            while group.len() > target {
+                let rest = group.split_off(target);
+                batches.push(std::mem::replace(&mut group, rest));
+            }
+            batches.push(group);
+        }
+        batches
+    }
+
     /// Take the current buffer (draining it) for flushing.
     pub fn take(&mut self) -> Vec<LogEntry> {
+        self.buffer_bytes = 0;
         std::mem::take(&mut self.buffer)
     }
 
@@ -97,6 +244,11 @@ impl SearchBatch {
     pub fn is_empty(&self) -> bool {
         self.buffer.is_empty()
     }
+
+    /// Consume the batch and return the statistics collected so far.
+    pub fn into_statistics(self) -> SearchStatisticsCollector {
+        self.statistics
+    }
 }
 
 #[cfg(test)]
@@ -117,6 +269,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         }
     }
 
@@ -192,4 +350,80 @@ mod tests {
         assert_eq!(action, BatchAction::Flush);
         assert_eq!(batch.take().len(), 5);
     }
+
+    fn long_entry(id: usize, len: usize) -> LogEntry {
+        LogEntry {
+            content: Arc::from("x".repeat(len)),
+            ..make_entry(id)
+        }
+    }
+
+    #[test]
+    fn byte_budget_flushes_long_lines_early() {
+        let mut batch = SearchBatch::new(256).with_byte_budget(10_000);
+        let action = batch.accumulate((0..5).map(|i| long_entry(i, 3_000)).collect(), 1000);
+        assert_eq!(action, BatchAction::Flush);
+        // ~3.2KB per entry → 3 per batch
+        assert_eq!(batch.target_entries(), 3);
+
+        let batches = batch.take_batches();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 2]);
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn short_lines_fill_entry_target() {
+        let mut batch = SearchBatch::new(8).with_byte_budget(1_000_000);
+        assert_eq!(
+            batch.accumulate(make_entries(5), 1000),
+            BatchAction::Continue
+        );
+        assert_eq!(batch.target_entries(), 8);
+        assert_eq!(batch.accumulate(make_entries(5), 1000), BatchAction::Flush);
+        let batches = batch.take_batches();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![8, 2]);
+    }
+
+    #[test]
+    fn split_keeps_oversized_entry_alone() {
+        let entries = vec![long_entry(0, 10), long_entry(1, 5_000), long_entry(2, 10)];
+        let groups = split_by_byte_budget(entries, 1_000);
+        assert_eq!(
+            groups.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![1, 1, 1]
+        );
+        assert_eq!(groups[1][0].id, 1);
+    }
+
+    #[test]
+    fn statistics_skip_truncated_entries() {
+        let mut batch =
+            SearchBatch::new(10).with_statistics(SearchStatisticsCollector::new(["error"]));
+        let mut entries = make_entries(15);
+        for entry in &mut entries {
+            entry.matched_keywords = Some(vec!["error".to_string()]);
+        }
+        batch.accumulate(entries, 10);
+        let summary = batch.into_statistics().finish(10);
+        assert_eq!(summary.keyword_stats[0].match_count, 10);
+        assert_eq!(summary.file_stats[0].match_count, 10);
+    }
+
+    #[test]
+    fn dedup_holds_entries_until_released() {
+        let mut batch = SearchBatch::new(10).with_dedup(Some(SearchDedup::new(100)));
+        let mut entries = make_entries(3);
+        entries[0].file = Arc::from("journal.1");
+        entries[1].file = Arc::from("journal");
+        entries[2].file = Arc::from("journal");
+        entries[2].content = Arc::from("other");
+
+        assert_eq!(batch.accumulate(entries, 1000), BatchAction::Continue);
+        assert_eq!(batch.total(), 0);
+        assert_eq!(batch.release_held(1000), BatchAction::Continue);
+        assert_eq!(batch.total(), 2);
+        assert_eq!(batch.duplicates_removed(), 1);
+        let taken = batch.take();
+        assert_eq!(taken[0].source_files.as_ref().map(Vec::len), Some(2));
+    }
 }
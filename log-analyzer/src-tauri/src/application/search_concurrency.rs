@@ -0,0 +1,314 @@
+//! ConcurrentSearchManager — fair scheduling of concurrent searches.
+//!
+//! Searches are grouped by *origin* (window label, or an explicit
+//! user/session id supplied by the frontend). Two limits apply:
+//! - a global cap on in-flight searches (`search.max_concurrent_searches`)
+//! - a per-origin cap (`search.max_searches_per_origin`)
+//!
+//! When slots are exhausted, waiting searches queue per origin and freed slots
+//! are handed out round-robin across origins, so one busy tab cannot starve
+//! the others. Within an origin, searches run in submission order.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+#[derive(Default)]
+struct OriginState {
+    active: usize,
+    waiters: VecDeque<oneshot::Sender<SearchPermit>>,
+}
+
+struct Inner {
+    max_total: usize,
+    max_per_origin: usize,
+    active_total: usize,
+    origins: HashMap<String, OriginState>,
+    /// Origins with queued searches, in round-robin order.
+    rotation: VecDeque<String>,
+}
+
+impl Inner {
+    fn release(&mut self, origin: &str) {
+        self.active_total = self.active_total.saturating_sub(1);
+        if let Some(state) = self.origins.get_mut(origin) {
+            state.active = state.active.saturating_sub(1);
+            if state.active == 0 && state.waiters.is_empty() {
+                self.origins.remove(origin);
+            }
+        }
+    }
+}
+
+/// Global scheduler shared by all workspaces.
+#[derive(Clone)]
+pub struct ConcurrentSearchManager {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// Slot held for the lifetime of a running search; dropping it frees the slot.
+pub struct SearchPermit {
+    slot: Option<(Arc<Mutex<Inner>>, String)>,
+}
+
+impl Drop for SearchPermit {
+    fn drop(&mut self) {
+        if let Some((inner, origin)) = self.slot.take() {
+            let mut guard = inner.lock();
+            guard.release(&origin);
+            dispatch(&inner, &mut guard);
+        }
+    }
+}
+
+/// Per-origin usage, as reported by `get_active_searches_count`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginSearchUsage {
+    pub origin: String,
+    pub active: usize,
+    pub queued: usize,
+}
+
+/// Snapshot of scheduler state.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchConcurrencySnapshot {
+    pub active: usize,
+    pub queued: usize,
+    pub max_concurrent: usize,
+    pub max_per_origin: usize,
+    pub origins: Vec<OriginSearchUsage>,
+}
+
+/// Hand freed slots to queued searches, round-robin across origins.
+fn dispatch(inner: &Arc<Mutex<Inner>>, state: &mut Inner) {
+    let mut skipped = 0;
+    while state.active_total < state.max_total && skipped < state.rotation.len() {
+        let Some(origin) = state.rotation.pop_front() else {
+            break;
+        };
+        let max_per_origin = state.max_per_origin;
+        let Some(origin_state) = state.origins.get_mut(&origin) else {
+            continue;
+        };
+
+        if origin_state.active >= max_per_origin {
+            // At its own cap; keep its place and let other origins go first.
+            state.rotation.push_back(origin);
+            skipped += 1;
+            continue;
+        }
+
+        let Some(waiter) = origin_state.waiters.pop_front() else {
+            continue;
+        };
+        let has_more = !origin_state.waiters.is_empty();
+
+        let permit = SearchPermit {
+            slot: Some((Arc::clone(inner), origin.clone())),
+        };
+        origin_state.active += 1;
+        state.active_total += 1;
+
+        if let Err(mut permit) = waiter.send(permit) {
+            // Waiter gave up (search cancelled while queued); undo without re-entering the lock.
+            permit.slot = None;
+            state.release(&origin);
+        }
+
+        if has_more {
+            state.rotation.push_back(origin);
+        }
+        skipped = 0;
+    }
+}
+
+impl ConcurrentSearchManager {
+    pub fn new(max_total: usize, max_per_origin: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                max_total: max_total.max(1),
+                max_per_origin: max_per_origin.max(1),
+                active_total: 0,
+                origins: HashMap::new(),
+                rotation: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Update limits (e.g. after config load); raised limits take effect immediately.
+    pub fn set_limits(&self, max_total: usize, max_per_origin: usize) {
+        let mut guard = self.inner.lock();
+        guard.max_total = max_total.max(1);
+        guard.max_per_origin = max_per_origin.max(1);
+        dispatch(&self.inner, &mut guard);
+    }
+
+    /// Wait for a slot for `origin`.
+    ///
+    /// Dropping the returned future while queued removes the search from the
+    /// queue without consuming a slot.
+    pub async fn acquire(&self, origin: &str) -> SearchPermit {
+        let receiver = {
+            let mut guard = self.inner.lock();
+            let state = &mut *guard;
+            let origin_state = state.origins.entry(origin.to_string()).or_default();
+            let others_waiting = !origin_state.waiters.is_empty();
+            let can_start = !others_waiting
+                && state.active_total < state.max_total
+                && origin_state.active < state.max_per_origin;
+
+            if can_start {
+                origin_state.active += 1;
+                state.active_total += 1;
+                return SearchPermit {
+                    slot: Some((Arc::clone(&self.inner), origin.to_string())),
+                };
+            }
+
+            let (tx, rx) = oneshot::channel();
+            origin_state.waiters.push_back(tx);
+            if !state.rotation.iter().any(|o| o == origin) {
+                state.rotation.push_back(origin.to_string());
+            }
+            // Earlier waiters of this origin may have been abandoned; let dispatch skip them.
+            dispatch(&self.inner, state);
+            rx
+        };
+
+        match receiver.await {
+            Ok(permit) => permit,
+            // Sender is only dropped by the manager itself, which never happens while it is alive.
+            Err(_) => SearchPermit { slot: None },
+        }
+    }
+
+    /// Current usage, overall and per origin.
+    pub fn snapshot(&self) -> SearchConcurrencySnapshot {
+        let guard = self.inner.lock();
+        let mut origins: Vec<OriginSearchUsage> = guard
+            .origins
+            .iter()
+            .map(|(origin, state)| OriginSearchUsage {
+                origin: origin.clone(),
+                active: state.active,
+                queued: state.waiters.iter().filter(|w| !w.is_closed()).count(),
+            })
+            .filter(|u| u.active > 0 || u.queued > 0)
+            .collect();
+        origins.sort_by(|a, b| a.origin.cmp(&b.origin));
+
+        SearchConcurrencySnapshot {
+            active: guard.active_total,
+            queued: origins.iter().map(|u| u.queued).sum(),
+            max_concurrent: guard.max_total,
+            max_per_origin: guard.max_per_origin,
+            origins,
+        }
+    }
+}
+
+impl Default for ConcurrentSearchManager {
+    fn default() -> Self {
+        let config = la_core::models::config::SearchConfig::default();
+        Self::new(
+            config.max_concurrent_searches,
+            config.max_searches_per_origin,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn pending<F: std::future::Future>(fut: F) -> bool {
+        tokio::time::timeout(Duration::from_millis(20), fut)
+            .await
+            .is_err()
+    }
+
+    #[tokio::test]
+    async fn test_per_origin_limit() {
+        let manager = ConcurrentSearchManager::new(10, 2);
+        let _a1 = manager.acquire("tab-a").await;
+        let _a2 = manager.acquire("tab-a").await;
+        assert!(pending(manager.acquire("tab-a")).await);
+        let _b1 = manager.acquire("tab-b").await;
+
+        let snapshot = manager.snapshot();
+        assert_eq!(snapshot.active, 3);
+        assert_eq!(
+            snapshot.origins[0],
+            OriginSearchUsage {
+                origin: "tab-a".into(),
+                active: 2,
+                queued: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_freed_slots_round_robin_across_origins() {
+        let manager = ConcurrentSearchManager::new(1, 10);
+        let first = manager.acquire("tab-a").await;
+
+        let a2 = tokio::spawn({
+            let m = manager.clone();
+            async move { m.acquire("tab-a").await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let a3 = tokio::spawn({
+            let m = manager.clone();
+            async move { m.acquire("tab-a").await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let b1 = tokio::spawn({
+            let m = manager.clone();
+            async move { m.acquire("tab-b").await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(manager.snapshot().queued, 3);
+
+        // tab-a queued first, then tab-b gets the next slot before tab-a's second waiter.
+        drop(first);
+        let a2 = a2.await.unwrap();
+        drop(a2);
+        let b1 = b1.await.unwrap();
+        assert!(!a3.is_finished());
+        drop(b1);
+        let _a3 = a3.await.unwrap();
+        assert_eq!(manager.snapshot().active, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_leak_slot() {
+        let manager = ConcurrentSearchManager::new(1, 1);
+        let first = manager.acquire("tab-a").await;
+        assert!(pending(manager.acquire("tab-b")).await);
+
+        drop(first);
+        assert_eq!(manager.snapshot().active, 0);
+        let _next = manager.acquire("tab-c").await;
+        assert_eq!(manager.snapshot().active, 1);
+    }
+
+    #[tokio::test]
+    async fn test_raising_limits_wakes_waiters() {
+        let manager = ConcurrentSearchManager::new(1, 1);
+        let _first = manager.acquire("tab-a").await;
+        let waiter = tokio::spawn({
+            let m = manager.clone();
+            async move { m.acquire("tab-b").await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        manager.set_limits(2, 1);
+        let _second = waiter.await.unwrap();
+        assert_eq!(manager.snapshot().active, 2);
+    }
+}
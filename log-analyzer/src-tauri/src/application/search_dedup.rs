@@ -0,0 +1,191 @@
+//! SearchDedup — cross-file duplicate removal for search results.
+//!
+//! Rotated or copied logs (`journal` and `journal.1`) often overlap, so the
+//! same line is matched once per file. Entries with the same timestamp text
+//! and content hash in *different* files collapse into the first one seen,
+//! which is annotated with every file the line appeared in.
+//!
+//! Entries are held in a bounded window before being released to the batch,
+//! so a representative can still be annotated when its duplicates arrive
+//! later in the stream. Duplicates further apart than the window survive.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use la_core::models::LogEntry;
+
+/// Default number of entries kept for duplicate lookup.
+pub const DEFAULT_DEDUP_WINDOW: usize = 10_000;
+
+/// Largest window a search may request.
+pub const MAX_DEDUP_WINDOW: usize = 100_000;
+
+type DedupKey = (Arc<str>, u64);
+
+/// Stateful sliding-window deduplicator.
+pub struct SearchDedup {
+    window: usize,
+    pending: VecDeque<LogEntry>,
+    /// Key → sequence number of its representative in `pending`.
+    index: HashMap<DedupKey, u64>,
+    /// Sequence number of `pending.front()`.
+    front_seq: u64,
+    removed: usize,
+}
+
+impl SearchDedup {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.clamp(1, MAX_DEDUP_WINDOW),
+            pending: VecDeque::new(),
+            index: HashMap::new(),
+            front_seq: 0,
+            removed: 0,
+        }
+    }
+
+    /// Feed entries in stream order; returns the entries that left the window.
+    pub fn push(&mut self, entries: Vec<LogEntry>) -> Vec<LogEntry> {
+        let mut released = Vec::new();
+        for entry in entries {
+            let key = dedup_key(&entry);
+            if let Some(&seq) = self.index.get(&key) {
+                let representative = &mut self.pending[(seq - self.front_seq) as usize];
+                if representative.file != entry.file {
+                    let files = representative
+                        .source_files
+                        .get_or_insert_with(|| vec![representative.file.to_string()]);
+                    if !files.iter().any(|f| **f == *entry.file) {
+                        files.push(entry.file.to_string());
+                    }
+                    self.removed += 1;
+                    continue;
+                }
+            }
+
+            // Repeated lines inside one file are real; the newest becomes the
+            // representative for later files.
+            self.index
+                .insert(key, self.front_seq + self.pending.len() as u64);
+            self.pending.push_back(entry);
+            if self.pending.len() > self.window {
+                released.extend(self.pop_front());
+            }
+        }
+        released
+    }
+
+    /// Release everything still held (end of search).
+    pub fn drain(&mut self) -> Vec<LogEntry> {
+        let mut released = Vec::with_capacity(self.pending.len());
+        while let Some(entry) = self.pop_front() {
+            released.push(entry);
+        }
+        released
+    }
+
+    /// Duplicates dropped so far.
+    pub fn removed(&self) -> usize {
+        self.removed
+    }
+
+    fn pop_front(&mut self) -> Option<LogEntry> {
+        let entry = self.pending.pop_front()?;
+        let key = dedup_key(&entry);
+        if self.index.get(&key) == Some(&self.front_seq) {
+            self.index.remove(&key);
+        }
+        self.front_seq += 1;
+        Some(entry)
+    }
+}
+
+fn dedup_key(entry: &LogEntry) -> DedupKey {
+    let mut hasher = DefaultHasher::new();
+    entry.content.hash(&mut hasher);
+    (Arc::clone(&entry.timestamp), hasher.finish())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file: &str, line: usize, timestamp: &str, content: &str) -> LogEntry {
+        LogEntry {
+            id: line,
+            timestamp: timestamp.into(),
+            level: "INFO".into(),
+            file: file.into(),
+            real_path: file.into(),
+            line,
+            content: content.into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn collapses_overlap_between_rotated_files() {
+        let mut dedup = SearchDedup::new(100);
+        let mut out = dedup.push(vec![
+            entry("journal.1", 1, "10:00:00", "boot"),
+            entry("journal.1", 2, "10:00:01", "disk error"),
+        ]);
+        out.extend(dedup.push(vec![
+            entry("journal", 1, "10:00:01", "disk error"),
+            entry("journal", 2, "10:00:02", "recovered"),
+        ]));
+        out.extend(dedup.drain());
+
+        assert_eq!(out.len(), 3);
+        assert_eq!(dedup.removed(), 1);
+        assert_eq!(
+            out[1].source_files.as_deref(),
+            Some(&["journal.1".to_string(), "journal".to_string()][..])
+        );
+        assert!(out[0].source_files.is_none());
+        assert!(out[2].source_files.is_none());
+    }
+
+    #[test]
+    fn keeps_repeats_within_one_file_and_beyond_window() {
+        let mut dedup = SearchDedup::new(1);
+        let mut out = dedup.push(vec![
+            entry("a.log", 1, "10:00:00", "retry"),
+            entry("a.log", 2, "10:00:00", "retry"),
+        ]);
+        assert_eq!(out.len(), 1);
+        // a.log:2 is still in the window and absorbs b.log
+        out.extend(dedup.push(vec![entry("b.log", 1, "10:00:00", "retry")]));
+        // a.log:2 leaves the window, so c.log's copy is kept
+        out.extend(dedup.push(vec![
+            entry("c.log", 1, "10:00:05", "other"),
+            entry("c.log", 2, "10:00:00", "retry"),
+        ]));
+        out.extend(dedup.drain());
+
+        let files: Vec<_> = out.iter().map(|e| (&*e.file, e.line)).collect();
+        assert_eq!(
+            files,
+            vec![("a.log", 1), ("a.log", 2), ("c.log", 1), ("c.log", 2)]
+        );
+        assert_eq!(
+            out[1].source_files.as_deref(),
+            Some(&["a.log".to_string(), "b.log".to_string()][..])
+        );
+        assert_eq!(dedup.removed(), 1);
+    }
+}
@@ -1,22 +1,220 @@
 //! SearchSessionManager — owns the backend lifecycle of a search session.
 //!
-//! A search session has two runtime resources:
+//! A search session has three runtime resources:
 //! - A `DiskResultStore` session where paginated results are written/read.
 //! - A `CancellationToken` used to abort an in-flight search.
+//! - A pinned `SearchSnapshot` of the data the query runs against, held until
+//!   the session is closed or has been idle for `SNAPSHOT_IDLE_TTL`.
 //!
 //! This module centralises all `search_id`-based operations so that commands
 //! no longer need to iterate workspaces (for cancellation) or read through a
 //! workspace service (for paging). The manager is owned by the backend app
 //! state and shared with each `WorkspaceServiceImpl`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use la_core::domain::ContentOverrides;
 use la_core::error::{AppError, Result};
-use la_search::{DiskResultStore, SearchPageResult};
+use la_core::models::LogEntry;
+use la_core::storage_types::FileMetadata;
+use la_core::utils::LevelOrder;
+use la_search::{DiskResultStore, IndexSnapshot, SearchPageResult};
 use parking_lot::Mutex;
+use serde::Serialize;
 use tokio_util::sync::CancellationToken;
 
+/// Snapshots not paged for this long are released on the next pin.
+pub const SNAPSHOT_IDLE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// One time-offset map entry besides the path itself: `String` header,
+/// offset and B-tree node share
+const TIME_OFFSET_ENTRY_OVERHEAD: usize = 24 + 8 + 16;
+
+// ============================================================================
+// SearchSnapshot
+// ============================================================================
+
+/// The data a search session was run against.
+///
+/// Captured when the search is submitted (before it queues for a concurrency
+/// slot), so files the watcher commits afterwards never leak into the session's
+/// results, pages or exports.
+#[derive(Clone)]
+pub struct SearchSnapshot {
+    files: Vec<FileMetadata>,
+    time_offsets: BTreeMap<String, i64>,
+    index: Option<IndexSnapshot>,
+    max_line_length: Option<usize>,
+    level_order: Option<LevelOrder>,
+    content_overrides: HashMap<String, Arc<dyn ContentOverrides>>,
+    import_progress: Option<u8>,
+    pinned_at: i64,
+}
+
+impl SearchSnapshot {
+    pub fn new(
+        files: Vec<FileMetadata>,
+        time_offsets: BTreeMap<String, i64>,
+        index: Option<IndexSnapshot>,
+    ) -> Self {
+        Self {
+            files,
+            time_offsets,
+            index,
+            max_line_length: None,
+            level_order: None,
+            content_overrides: HashMap::new(),
+            import_progress: None,
+            pinned_at: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    /// Line length limit in force when the search was submitted
+    pub fn with_max_line_length(mut self, max_line_length: Option<usize>) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Workspace level order in force when the search was submitted
+    pub fn with_level_order(mut self, level_order: Option<LevelOrder>) -> Self {
+        self.level_order = level_order;
+        self
+    }
+
+    /// Per-content parse overrides (by hash) in force when the search was submitted
+    pub fn with_content_overrides(
+        mut self,
+        content_overrides: HashMap<String, Arc<dyn ContentOverrides>>,
+    ) -> Self {
+        self.content_overrides = content_overrides;
+        self
+    }
+
+    /// Import progress (percent) when the snapshot was pinned mid-import;
+    /// the snapshot then only covers files imported so far
+    pub fn with_import_progress(mut self, import_progress: Option<u8>) -> Self {
+        self.import_progress = import_progress;
+        self
+    }
+
+    pub fn import_progress(&self) -> Option<u8> {
+        self.import_progress
+    }
+
+    /// Candidate files after metadata pruning
+    pub fn files(&self) -> &[FileMetadata] {
+        &self.files
+    }
+
+    pub fn time_offsets(&self) -> &BTreeMap<String, i64> {
+        &self.time_offsets
+    }
+
+    pub fn max_line_length(&self) -> Option<usize> {
+        self.max_line_length
+    }
+
+    pub fn level_order(&self) -> Option<&LevelOrder> {
+        self.level_order.as_ref()
+    }
+
+    pub fn content_overrides(&self) -> &HashMap<String, Arc<dyn ContentOverrides>> {
+        &self.content_overrides
+    }
+
+    /// Index generation pinned alongside the file list
+    pub fn index(&self) -> Option<&IndexSnapshot> {
+        self.index.as_ref()
+    }
+}
+
+/// Description of a pinned snapshot, for the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSnapshotInfo {
+    pub search_id: String,
+    pub file_count: usize,
+    pub index_generation: Option<u64>,
+    pub indexed_docs: Option<u64>,
+    /// Workspace import progress (percent) when pinned mid-import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import_progress: Option<u8>,
+    /// Unix milliseconds
+    pub pinned_at: i64,
+}
+
+struct PinnedSnapshot {
+    snapshot: Arc<SearchSnapshot>,
+    last_access: Instant,
+}
+
+// ============================================================================
+// FrozenSearchView
+// ============================================================================
+
+/// Pre-refresh data view of a workspace.
+///
+/// While a refresh rewrites metadata and commits index segments, searches
+/// submitted in the meantime pin this frozen snapshot (all searchable files,
+/// unpruned, plus the index generation at freeze time) instead of the live,
+/// half-refreshed state. Dropping the [`FrozenViewGuard`] publishes the
+/// refreshed data to new searches; sessions already pinned keep their view.
+#[derive(Clone, Default)]
+pub struct FrozenSearchView {
+    inner: Arc<Mutex<Option<FrozenSnapshot>>>,
+    next_generation: Arc<AtomicU64>,
+}
+
+struct FrozenSnapshot {
+    generation: u64,
+    snapshot: Arc<SearchSnapshot>,
+}
+
+impl FrozenSearchView {
+    /// Freeze `snapshot` until the returned guard is dropped.
+    pub fn freeze(&self, snapshot: SearchSnapshot) -> FrozenViewGuard {
+        let generation = self.next_generation.fetch_add(1, AtomicOrdering::Relaxed);
+        *self.inner.lock() = Some(FrozenSnapshot {
+            generation,
+            snapshot: Arc::new(snapshot),
+        });
+        FrozenViewGuard {
+            view: self.clone(),
+            generation,
+        }
+    }
+
+    /// The frozen snapshot, if a refresh is in progress.
+    pub fn current(&self) -> Option<Arc<SearchSnapshot>> {
+        self.inner
+            .lock()
+            .as_ref()
+            .map(|frozen| Arc::clone(&frozen.snapshot))
+    }
+}
+
+/// Keeps a [`FrozenSearchView`] frozen; unfreezes on drop unless a newer
+/// freeze has replaced it.
+pub struct FrozenViewGuard {
+    view: FrozenSearchView,
+    generation: u64,
+}
+
+impl Drop for FrozenViewGuard {
+    fn drop(&mut self) {
+        let mut inner = self.view.inner.lock();
+        if inner
+            .as_ref()
+            .is_some_and(|frozen| frozen.generation == self.generation)
+        {
+            *inner = None;
+        }
+    }
+}
+
 // ============================================================================
 // SearchSessionManager
 // ============================================================================
@@ -26,6 +224,7 @@ use tokio_util::sync::CancellationToken;
 pub struct SearchSessionManager {
     disk_result_store: Arc<DiskResultStore>,
     sessions: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    snapshots: Arc<Mutex<HashMap<String, PinnedSnapshot>>>,
 }
 
 impl SearchSessionManager {
@@ -34,6 +233,7 @@ impl SearchSessionManager {
         Self {
             disk_result_store,
             sessions: Arc::new(Mutex::new(HashMap::new())),
+            snapshots: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -47,6 +247,20 @@ impl SearchSessionManager {
             .map_err(|e| AppError::io_error(format!("Failed to create search session: {e}"), None))
     }
 
+    /// Create a completed session holding previously saved results, so they
+    /// page exactly like a finished search.
+    pub fn restore_session(&self, search_id: &str, entries: &[LogEntry]) -> Result<()> {
+        self.create_session(search_id)?;
+        let store = &self.disk_result_store;
+        store
+            .append_entries(search_id, entries)
+            .and_then(|_| store.complete_session(search_id))
+            .map_err(|e| {
+                store.remove_session(search_id);
+                AppError::io_error(format!("Failed to restore search results: {e}"), None)
+            })
+    }
+
     /// Register a cancellation token for an active search session.
     pub fn register_token(&self, search_id: &str, token: CancellationToken) {
         self.sessions.lock().insert(search_id.to_string(), token);
@@ -66,6 +280,87 @@ impl SearchSessionManager {
         }
     }
 
+    /// Pin the snapshot a session runs against and release idle ones.
+    pub fn pin_snapshot(&self, search_id: &str, snapshot: SearchSnapshot) -> Arc<SearchSnapshot> {
+        self.release_idle_snapshots(SNAPSHOT_IDLE_TTL);
+        let snapshot = Arc::new(snapshot);
+        self.snapshots.lock().insert(
+            search_id.to_string(),
+            PinnedSnapshot {
+                snapshot: Arc::clone(&snapshot),
+                last_access: Instant::now(),
+            },
+        );
+        snapshot
+    }
+
+    /// Describe the snapshot pinned for a session, if it is still held.
+    pub fn snapshot_info(&self, search_id: &str) -> Option<SearchSnapshotInfo> {
+        let snapshots = self.snapshots.lock();
+        let snapshot = &snapshots.get(search_id)?.snapshot;
+        Some(SearchSnapshotInfo {
+            search_id: search_id.to_string(),
+            file_count: snapshot.files.len(),
+            index_generation: snapshot.index.as_ref().map(IndexSnapshot::generation),
+            indexed_docs: snapshot.index.as_ref().map(IndexSnapshot::num_docs),
+            import_progress: snapshot.import_progress,
+            pinned_at: snapshot.pinned_at,
+        })
+    }
+
+    /// Estimated heap held by the file lists and time-offset maps of pinned
+    /// snapshots: (paths, bytes). Index segments pinned by a snapshot are
+    /// memory-mapped and not counted here.
+    pub fn snapshot_memory(&self) -> (u64, u64) {
+        let snapshots = self.snapshots.lock();
+        let mut paths = 0;
+        let mut bytes = 0;
+        for pinned in snapshots.values() {
+            let snapshot = &pinned.snapshot;
+            paths += (snapshot.files.len() + snapshot.time_offsets.len()) as u64;
+            bytes += snapshot
+                .files
+                .iter()
+                .map(|file| {
+                    std::mem::size_of::<FileMetadata>()
+                        + file.sha256_hash.len()
+                        + file.virtual_path.len()
+                        + file.original_name.len()
+                        + file.mime_type.as_ref().map_or(0, String::len)
+                })
+                .sum::<usize>() as u64;
+            bytes += snapshot
+                .time_offsets
+                .keys()
+                .map(|path| TIME_OFFSET_ENTRY_OVERHEAD + path.len())
+                .sum::<usize>() as u64;
+        }
+        (paths, bytes)
+    }
+
+    /// Release snapshots idle for longer than `ttl`, or whose result session
+    /// has already been evicted; returns how many were released.
+    pub fn release_idle_snapshots(&self, ttl: Duration) -> usize {
+        let mut snapshots = self.snapshots.lock();
+        let before = snapshots.len();
+        snapshots.retain(|search_id, pinned| {
+            pinned.last_access.elapsed() < ttl && self.disk_result_store.has_session(search_id)
+        });
+        before - snapshots.len()
+    }
+
+    /// Close a session: cancel it if still running, release its snapshot and
+    /// delete its results. Returns whether anything was held for it.
+    pub fn close_session(&self, search_id: &str) -> bool {
+        let token = self.sessions.lock().remove(search_id);
+        if let Some(token) = &token {
+            token.cancel();
+        }
+        let snapshot = self.snapshots.lock().remove(search_id);
+        let results = self.disk_result_store.remove_session(search_id);
+        token.is_some() || snapshot.is_some() || results
+    }
+
     /// Read a page of results for the given search session.
     pub fn fetch_search_page(
         &self,
@@ -81,6 +376,10 @@ impl SearchSessionManager {
             )));
         }
 
+        if let Some(pinned) = self.snapshots.lock().get_mut(search_id) {
+            pinned.last_access = Instant::now();
+        }
+
         self.disk_result_store
             .read_page(search_id, offset, limit)
             .map_err(|e| AppError::io_error(format!("Failed to read search page: {e}"), None))
@@ -94,6 +393,11 @@ impl SearchSessionManager {
         self.sessions.lock().remove(search_id);
     }
 
+    /// Whether the search is still queued or running (its token is held).
+    pub fn is_running(&self, search_id: &str) -> bool {
+        self.sessions.lock().contains_key(search_id)
+    }
+
     /// Returns the number of active cancellation tokens.
     #[cfg(test)]
     pub(crate) fn active_token_count(&self) -> usize {
@@ -124,6 +428,12 @@ mod tests {
             tags: vec![],
             match_details: None,
             matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
         }
     }
 
@@ -156,6 +466,19 @@ mod tests {
         assert_eq!(page.next_offset, Some(2));
     }
 
+    #[test]
+    fn restore_session_pages_like_a_finished_search() {
+        let (mgr, _dir) = make_manager();
+        let entries: Vec<LogEntry> = (0..3).map(|i| make_entry(i, "saved")).collect();
+
+        mgr.restore_session("restored", &entries).unwrap();
+
+        let page = mgr.fetch_search_page("restored", 0, 10).unwrap();
+        assert_eq!(page.entries.len(), 3);
+        assert!(page.is_complete);
+        assert!(!mgr.is_running("restored"));
+    }
+
     #[test]
     fn fetch_page_for_unknown_session_fails() {
         let (mgr, _dir) = make_manager();
@@ -191,10 +514,70 @@ mod tests {
         mgr.create_session(search_id).unwrap();
         mgr.register_token(search_id, CancellationToken::new());
 
+        assert!(mgr.is_running(search_id));
         mgr.cleanup_token(search_id);
         assert_eq!(mgr.active_token_count(), 0);
+        assert!(!mgr.is_running(search_id));
 
         // Result session must remain so pagination still works.
         assert!(mgr.disk_result_store.has_session(search_id));
     }
+
+    #[test]
+    fn snapshot_is_held_until_session_closes() {
+        let (mgr, _dir) = make_manager();
+        let search_id = "session-4";
+
+        mgr.create_session(search_id).unwrap();
+        mgr.register_token(search_id, CancellationToken::new());
+        let offsets = BTreeMap::from([("a.log".to_string(), 3)]);
+        mgr.pin_snapshot(search_id, SearchSnapshot::new(vec![], offsets, None));
+
+        let (paths, bytes) = mgr.snapshot_memory();
+        assert_eq!(paths, 1);
+        assert_eq!(bytes, (TIME_OFFSET_ENTRY_OVERHEAD + "a.log".len()) as u64);
+
+        // Paging keeps the snapshot alive past the idle sweep
+        mgr.fetch_search_page(search_id, 0, 10).unwrap();
+        assert_eq!(mgr.release_idle_snapshots(Duration::from_secs(60)), 0);
+        let info = mgr.snapshot_info(search_id).unwrap();
+        assert_eq!(info.file_count, 0);
+        assert_eq!(info.index_generation, None);
+
+        assert!(mgr.close_session(search_id));
+        assert!(mgr.snapshot_info(search_id).is_none());
+        assert!(!mgr.disk_result_store.has_session(search_id));
+        assert_eq!(mgr.active_token_count(), 0);
+        assert!(!mgr.close_session(search_id));
+    }
+
+    #[test]
+    fn idle_snapshots_are_released() {
+        let (mgr, _dir) = make_manager();
+        mgr.create_session("idle").unwrap();
+        mgr.pin_snapshot("idle", SearchSnapshot::new(vec![], BTreeMap::new(), None));
+        // Result session evicted elsewhere: snapshot goes on the next sweep
+        mgr.pin_snapshot("orphan", SearchSnapshot::new(vec![], BTreeMap::new(), None));
+
+        assert_eq!(mgr.release_idle_snapshots(Duration::from_secs(60)), 1);
+        assert!(mgr.snapshot_info("idle").is_some());
+        assert_eq!(mgr.release_idle_snapshots(Duration::ZERO), 1);
+        assert!(mgr.snapshot_info("idle").is_none());
+    }
+
+    #[test]
+    fn frozen_view_is_released_by_its_own_guard_only() {
+        let view = FrozenSearchView::default();
+        assert!(view.current().is_none());
+
+        let first = view.freeze(SearchSnapshot::new(vec![], BTreeMap::new(), None));
+        let offsets = BTreeMap::from([("a.log".to_string(), 1)]);
+        let second = view.freeze(SearchSnapshot::new(vec![], offsets, None));
+        // A stale guard must not unfreeze the newer view
+        drop(first);
+        assert_eq!(view.current().unwrap().time_offsets().len(), 1);
+
+        drop(second);
+        assert!(view.current().is_none());
+    }
 }
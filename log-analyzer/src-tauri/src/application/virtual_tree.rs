@@ -3,7 +3,10 @@
 //! Encapsulates the tree-building algorithm that constructs a hierarchical
 //! `VirtualTreeNode` representation from flat metadata (archive + file lists).
 
-use la_storage::MetadataStore;
+use std::collections::HashMap;
+
+use la_core::utils::{CrashArtifactInfo, DataQuality};
+use la_storage::{FileFormat, MetadataStore};
 use serde::{Deserialize, Serialize};
 
 /// Virtual file tree node
@@ -18,6 +21,23 @@ pub enum VirtualTreeNode {
         size: i64,
         #[serde(rename = "mimeType")]
         mime_type: Option<String>,
+        /// 内容含无效 UTF-8、NUL 字节、单独 `\r` 或混合换行时的统计，用于提示
+        #[serde(
+            rename = "dataQuality",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        data_quality: Option<DataQuality>,
+        /// 导入时识别的格式与置信度，以及用户改判的格式
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<Box<FileFormat>>,
+        /// 崩溃产物（core dump、minidump、hprof）的类型、构建 ID 与崩溃时间
+        #[serde(
+            rename = "crashArtifact",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        crash_artifact: Option<Box<CrashArtifactInfo>>,
     },
     #[serde(rename = "archive")]
     Archive {
@@ -27,19 +47,57 @@ pub enum VirtualTreeNode {
         #[serde(rename = "archiveType")]
         archive_type: String,
         children: Vec<VirtualTreeNode>,
+        /// 选择性解压跳过的条目（未导入，前端置灰显示）
+        #[serde(
+            rename = "skippedEntries",
+            default,
+            skip_serializing_if = "Vec::is_empty"
+        )]
+        skipped_entries: Vec<SkippedTreeEntry>,
     },
 }
 
+/// 压缩包中被跳过、未导入的条目
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SkippedTreeEntry {
+    pub name: String,
+    pub path: String,
+    /// 条目声明的解压大小；流式导入时未知，为 0
+    pub size: i64,
+}
+
 /// Build hierarchical tree structure from flat data
 pub async fn build_tree_structure(
     archives: &[la_storage::ArchiveMetadata],
     files: &[la_storage::FileMetadata],
-    _metadata_store: &MetadataStore,
+    metadata_store: &MetadataStore,
 ) -> Result<Vec<VirtualTreeNode>, String> {
     let mut tree = Vec::new();
 
+    // 数据质量提示与格式指纹按内容哈希记录；读取失败时不附带
+    let hints = ContentHints {
+        quality: metadata_store
+            .get_data_quality_warnings()
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Failed to load data quality warnings");
+                HashMap::new()
+            }),
+        formats: metadata_store.get_file_formats().await.unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to load file formats");
+            HashMap::new()
+        }),
+        artifacts: metadata_store
+            .get_crash_artifact_infos()
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Failed to load crash artifacts");
+                HashMap::new()
+            }),
+        skipped: skipped_by_archive(metadata_store).await,
+    };
+
     // 预构建索引：parent_archive_id -> 子 archive/file 列表，O(n) → O(1) 查找
-    use std::collections::HashMap;
     let mut archive_children: HashMap<i64, Vec<&la_storage::ArchiveMetadata>> = HashMap::new();
     for a in archives {
         if let Some(parent_id) = a.parent_archive_id {
@@ -67,29 +125,84 @@ pub async fn build_tree_structure(
 
     // Add root archives with their children
     for archive in root_archives {
-        let node = build_archive_node_indexed(archive, &archive_children, &file_children).await?;
+        let node =
+            build_archive_node_indexed(archive, &archive_children, &file_children, &hints).await?;
         tree.push(node);
     }
 
     // Add root files
     for file in root_files {
-        tree.push(VirtualTreeNode::File {
-            name: file.original_name.clone(),
-            path: file.virtual_path.clone(),
-            hash: file.sha256_hash.clone(),
-            size: file.size,
-            mime_type: file.mime_type.clone(),
-        });
+        tree.push(file_node(file, &hints));
     }
 
     Ok(tree)
 }
 
+/// 按内容哈希附加到文件节点的提示，以及按压缩包 ID 附加的跳过条目
+struct ContentHints {
+    quality: HashMap<String, DataQuality>,
+    formats: HashMap<String, FileFormat>,
+    artifacts: HashMap<String, CrashArtifactInfo>,
+    skipped: HashMap<i64, Vec<la_storage::SkippedEntryRecord>>,
+}
+
+/// 跳过的条目按所属压缩包分组；读取失败时不附带
+async fn skipped_by_archive(
+    metadata_store: &MetadataStore,
+) -> HashMap<i64, Vec<la_storage::SkippedEntryRecord>> {
+    let entries = metadata_store
+        .get_skipped_entries()
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to load skipped entries");
+            Vec::new()
+        });
+    let mut grouped: HashMap<i64, Vec<_>> = HashMap::new();
+    for entry in entries {
+        grouped.entry(entry.archive_id).or_default().push(entry);
+    }
+    grouped
+}
+
+fn skipped_node(
+    archive: &la_storage::ArchiveMetadata,
+    entry: &la_storage::SkippedEntryRecord,
+) -> SkippedTreeEntry {
+    let entry_name = entry.entry_name.trim_end_matches('/');
+    SkippedTreeEntry {
+        name: entry_name
+            .rsplit('/')
+            .next()
+            .unwrap_or(entry_name)
+            .to_string(),
+        path: format!("{}/{}", archive.virtual_path, entry_name),
+        size: entry.size,
+    }
+}
+
+fn file_node(file: &la_storage::FileMetadata, hints: &ContentHints) -> VirtualTreeNode {
+    VirtualTreeNode::File {
+        name: file.original_name.clone(),
+        path: file.virtual_path.clone(),
+        hash: file.sha256_hash.clone(),
+        size: file.size,
+        mime_type: file.mime_type.clone(),
+        data_quality: hints.quality.get(&file.sha256_hash).cloned(),
+        format: hints.formats.get(&file.sha256_hash).cloned().map(Box::new),
+        crash_artifact: hints
+            .artifacts
+            .get(&file.sha256_hash)
+            .cloned()
+            .map(Box::new),
+    }
+}
+
 /// Build archive node with pre-built HashMap indexes, O(n) total instead of O(n²)
 fn build_archive_node_indexed<'a>(
     archive: &'a la_storage::ArchiveMetadata,
-    archive_children: &'a std::collections::HashMap<i64, Vec<&'a la_storage::ArchiveMetadata>>,
-    file_children: &'a std::collections::HashMap<i64, Vec<&'a la_storage::FileMetadata>>,
+    archive_children: &'a HashMap<i64, Vec<&'a la_storage::ArchiveMetadata>>,
+    file_children: &'a HashMap<i64, Vec<&'a la_storage::FileMetadata>>,
+    hints: &'a ContentHints,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<VirtualTreeNode, String>> + Send + 'a>>
 {
     Box::pin(async move {
@@ -98,22 +211,20 @@ fn build_archive_node_indexed<'a>(
         // O(1) HashMap lookup instead of O(n) linear scan
         if let Some(child_archives) = archive_children.get(&archive.id) {
             for child_archive in child_archives {
-                let child_node =
-                    build_archive_node_indexed(child_archive, archive_children, file_children)
-                        .await?;
+                let child_node = build_archive_node_indexed(
+                    child_archive,
+                    archive_children,
+                    file_children,
+                    hints,
+                )
+                .await?;
                 children.push(child_node);
             }
         }
 
         if let Some(child_files) = file_children.get(&archive.id) {
             for file in child_files {
-                children.push(VirtualTreeNode::File {
-                    name: file.original_name.clone(),
-                    path: file.virtual_path.clone(),
-                    hash: file.sha256_hash.clone(),
-                    size: file.size,
-                    mime_type: file.mime_type.clone(),
-                });
+                children.push(file_node(file, hints));
             }
         }
 
@@ -123,6 +234,11 @@ fn build_archive_node_indexed<'a>(
             hash: archive.sha256_hash.clone(),
             archive_type: archive.archive_type.clone(),
             children,
+            skipped_entries: hints
+                .skipped
+                .get(&archive.id)
+                .map(|entries| entries.iter().map(|e| skipped_node(archive, e)).collect())
+                .unwrap_or_default(),
         })
     })
 }
@@ -139,12 +255,16 @@ mod tests {
             hash: "abc123".to_string(),
             size: 1024,
             mime_type: Some("text/plain".to_string()),
+            data_quality: None,
+            format: None,
+            crash_artifact: None,
         };
 
         let json = serde_json::to_string(&file_node)
             .expect("VirtualTreeNode::File should always be serializable");
         assert!(json.contains("\"type\":\"file\""));
         assert!(json.contains("\"name\":\"test.log\""));
+        assert!(!json.contains("dataQuality"));
     }
 
     #[test]
@@ -155,11 +275,42 @@ mod tests {
             hash: "def456".to_string(),
             archive_type: "zip".to_string(),
             children: vec![],
+            skipped_entries: vec![],
         };
 
         let json = serde_json::to_string(&archive_node)
             .expect("VirtualTreeNode::Archive should always be serializable");
         assert!(json.contains("\"type\":\"archive\""));
         assert!(json.contains("\"archiveType\":\"zip\""));
+        assert!(!json.contains("skippedEntries"));
+    }
+
+    #[test]
+    fn test_skipped_entry_node_path() {
+        let archive = la_storage::ArchiveMetadata {
+            id: 7,
+            sha256_hash: "abc".to_string(),
+            virtual_path: "import/bundle.tar.gz".to_string(),
+            original_name: "bundle.tar.gz".to_string(),
+            archive_type: "tar.gz".to_string(),
+            parent_archive_id: None,
+            depth_level: 0,
+            extraction_status: "completed".to_string(),
+        };
+        let entry = la_storage::SkippedEntryRecord {
+            archive_id: 7,
+            archive_virtual_path: archive.virtual_path.clone(),
+            entry_name: "node1/bin/server".to_string(),
+            size: 0,
+            skipped_at: 0,
+        };
+        assert_eq!(
+            skipped_node(&archive, &entry),
+            SkippedTreeEntry {
+                name: "server".to_string(),
+                path: "import/bundle.tar.gz/node1/bin/server".to_string(),
+                size: 0,
+            }
+        );
     }
 }
@@ -31,6 +31,10 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 
+use crate::application::search_session::FrozenViewGuard;
+use crate::monitoring::MemoryComponent;
+use crate::services::watcher_budget::WatcherStatus;
+
 // 保留 re-exports 以保持向后兼容（workspace_repo、cleanup_workspace_resources 等引用）
 pub use la_search::SearchEngineManager;
 pub use la_storage::ContentAddressableStorage;
@@ -59,17 +63,24 @@ pub trait SearchService: Send + Sync {
     /// - `raw_terms`: 原始搜索词（用于高亮显示）
     /// - `filters`: 搜索过滤器（时间范围、日志级别、文件路径等）
     /// - `max_results`: 最大结果数上限
+    /// - `origin`: 搜索来源（窗口 / 会话），用于跨来源的公平调度与配额
+    /// - `timeout`: 搜索超时；到期后停止扫描并返回已找到的部分结果
+    /// - `batch_max_bytes`: 单批结果的序列化字节预算（`search.batch_max_bytes`）
     ///
     /// CancellationToken 由实现层内部创建和管理，外部通过 cancel_search() 取消。
     ///
     /// # 返回
     /// 搜索会话 ID，前端可用此 ID 获取结果和进度。
+    #[allow(clippy::too_many_arguments)]
     async fn search(
         &self,
         query: SearchQuery,
         raw_terms: Vec<String>,
         filters: SearchFilters,
         max_results: usize,
+        origin: String,
+        timeout: Option<std::time::Duration>,
+        batch_max_bytes: usize,
     ) -> Result<String>;
 
     /// 获取搜索结果分页。
@@ -86,6 +97,12 @@ pub trait SearchService: Send + Sync {
     ///
     /// 取消指定 search_id 的搜索会话（如果存在）。
     async fn cancel_search(&self, search_id: &str) -> Result<()>;
+
+    /// 冻结当前搜索视图（刷新期间使用）。
+    ///
+    /// 守卫存活期间新提交的搜索基于冻结时的文件列表与索引代次，看不到刷新
+    /// 中途写入的元数据和索引段；释放守卫即向之后的搜索发布刷新结果。
+    async fn freeze_search_view(&self) -> Result<FrozenViewGuard>;
 }
 
 // ============================================================================
@@ -121,6 +138,13 @@ pub trait ImportService: Send + Sync {
         task_id: &str,
         cancellation_token: CancellationToken,
     ) -> Result<ImportResult>;
+
+    /// 进行中导入的估算完成百分比；未在导入时为 `None`。
+    ///
+    /// 导入按文件流水线进行，已处理的文件在导入期间即可搜索。
+    fn import_progress(&self) -> Option<u8> {
+        None
+    }
 }
 
 /// 导入返回结果。
@@ -139,6 +163,21 @@ pub struct ImportOptions {
     pub extract_archives: bool,
     /// 是否跳过已存在的文件
     pub skip_existing: bool,
+    /// 选择性解压：按压缩包列出要提取的条目，未列出的压缩包完整提取
+    #[serde(default)]
+    pub selection: Vec<la_archive::ArchiveSelection>,
+    /// 作用于其他压缩包的包含 / 排除列表；`None` 表示使用配置中的默认列表
+    #[serde(default)]
+    pub entry_filter: Option<la_archive::EntryFilter>,
+    /// 解压后处理钩子；`None` 表示沿用工作区已保存的设置
+    #[serde(default)]
+    pub post_extract_hooks: Option<Vec<la_archive::PostExtractHook>>,
+    /// 加密压缩包（ZIP、RAR）的密码
+    #[serde(default)]
+    pub password: Option<la_archive::ArchivePassword>,
+    /// 导入目录时预先扫描的结果；提供时处理阶段回放其目录项，不再遍历一次
+    #[serde(skip)]
+    pub prescanned: Option<Arc<la_archive::ScannedDirectory>>,
 }
 
 // ============================================================================
@@ -155,13 +194,29 @@ pub trait WatchService: Send + Sync {
     ///
     /// # 参数
     /// - `watch_path`: 要监听的文件系统路径（文件或目录）
-    async fn start_watch(&self, watch_path: &str) -> Result<()>;
+    ///
+    /// 原生监听超出全局预算或触发系统上限时自动降级为轮询，返回实际采用的模式。
+    async fn start_watch(&self, watch_path: &str) -> Result<WatcherStatus>;
 
     /// 停止文件监听。
     async fn stop_watch(&self) -> Result<()>;
 
     /// 获取监听状态。
     async fn is_watching(&self) -> Result<bool>;
+
+    /// 当前监听模式（未监听时为 None）。
+    fn watch_status(&self) -> Option<WatcherStatus>;
+
+    /// 挂载跟随查询：监听写入的新条目命中时以 `WorkspaceEvent::NewLogs` 推送。
+    ///
+    /// 同 id 的查询会被替换；挂载与是否正在监听无关。
+    fn attach_follow_query(&self, query: &SearchQuery) -> Result<()>;
+
+    /// 卸载跟随查询，返回是否存在。
+    fn detach_follow_query(&self, query_id: &str) -> bool;
+
+    /// 已挂载的跟随查询 id。
+    fn follow_query_ids(&self) -> Vec<String>;
 }
 
 // ============================================================================
@@ -197,10 +252,40 @@ pub trait WorkspaceService: SearchService + ImportService + WatchService + Send
     /// 获取 SearchEngineManager 实例（供 workspace_repo、cleanup 等使用）。
     fn search_engine(&self) -> &Arc<la_search::SearchEngineManager>;
 
+    /// 获取解压视图缓存（搜索与预览读取 gzip 对象时复用）。
+    fn views(&self) -> &Arc<la_storage::DecompressedViewCache>;
+
     /// 关闭所有数据库连接（MetadataStore + SearchEngine）。
     ///
     /// 在 workspace 关闭/删除/应用退出时调用，确保 WAL checkpoint。
     async fn close_databases(&self);
+
+    /// 释放可重建的内存缓存（正则引擎、查询计划等）。
+    ///
+    /// 由资源监控在内存不足时调用，不影响正确性，只影响后续首次搜索的速度。
+    fn release_caches(&self) {}
+
+    /// 释放可重建的磁盘缓存（解压视图），返回释放的字节数。
+    ///
+    /// 由资源监控在磁盘空间不足时调用。
+    fn release_disk_caches(&self) -> u64 {
+        0
+    }
+
+    /// 正则引擎缓存的估算占用：(条目数, 字节)，供自适应缓存容量计算单条平均大小。
+    fn engine_cache_usage(&self) -> (u64, u64) {
+        (0, 0)
+    }
+
+    /// 调整内存缓存容量。
+    ///
+    /// 由自适应缓存容量评估在可用内存变化时调用。
+    fn resize_caches(&self, _capacity: usize) {}
+
+    /// 估算该工作区持有的内存（索引、缓存等），供内存占用分析使用。
+    fn memory_usage(&self) -> Vec<MemoryComponent> {
+        Vec::new()
+    }
 }
 
 // ============================================================================
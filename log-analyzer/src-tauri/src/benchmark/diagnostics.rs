@@ -0,0 +1,445 @@
+//! 诊断基准
+//!
+//! 在用户机器上测量导入 / 搜索热路径依赖的几项基础能力：
+//! - 磁盘顺序写入 / 读取吞吐（写入含 fsync，反映真实磁盘；读取可能命中页缓存，
+//!   仅作相对参考）
+//! - SHA-256 哈希吞吐（CAS 写入瓶颈）
+//! - 关键词与正则匹配吞吐（搜索瓶颈，使用生产匹配引擎）
+//! - SQLite 事务内批量插入速率（元数据写入瓶颈）
+//!
+//! 结果写入 `{data_dir}/benchmark_baseline.json` 作为基线，下次运行时一并返回，
+//! 便于对比；同时根据结果与当前配置给出调优建议。
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use la_core::error::{AppError, Result};
+use la_core::models::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqlitePoolOptions;
+use tracing::{info, warn};
+
+use crate::services::RegexEngine;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+/// 基线文件名
+const BASELINE_FILE: &str = "benchmark_baseline.json";
+
+/// 低于该写入吞吐（MB/s，含 fsync）视为机械硬盘 / 网络盘，降低并行度。
+/// 读取吞吐刚写完就测，多半来自页缓存，不能用来判断磁盘快慢
+const SLOW_DISK_MB_PER_SEC: f64 = 150.0;
+
+/// 基准规模
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkOptions {
+    pub disk_bytes: usize,
+    pub hash_bytes: usize,
+    pub match_bytes: usize,
+    pub sqlite_rows: usize,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        Self {
+            disk_bytes: 64 * 1024 * 1024,
+            hash_bytes: 64 * 1024 * 1024,
+            match_bytes: 16 * 1024 * 1024,
+            sqlite_rows: 20_000,
+        }
+    }
+}
+
+/// 基准测量结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkResults {
+    pub cpu_count: usize,
+    pub disk_write_mb_per_sec: f64,
+    pub disk_read_mb_per_sec: f64,
+    pub sha256_mb_per_sec: f64,
+    pub keyword_match_mb_per_sec: f64,
+    pub regex_match_mb_per_sec: f64,
+    pub sqlite_inserts_per_sec: f64,
+    /// 测量时间（RFC 3339）
+    pub measured_at: String,
+    pub total_duration_ms: u64,
+}
+
+/// 配置调优建议
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TuningSuggestion {
+    /// 配置项路径（如 `archive.max_parallel_files`）
+    pub setting: String,
+    pub current: u64,
+    pub suggested: u64,
+    pub reason: String,
+}
+
+/// `run_diagnostics_benchmark` 返回结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub results: BenchmarkResults,
+    /// 上一次保存的基线（首次运行为 None）
+    pub baseline: Option<BenchmarkResults>,
+    pub suggestions: Vec<TuningSuggestion>,
+}
+
+fn mb_per_sec(bytes: usize, elapsed: Duration) -> f64 {
+    bytes as f64 / MB / elapsed.as_secs_f64().max(1e-6)
+}
+
+fn per_sec(count: usize, elapsed: Duration) -> f64 {
+    count as f64 / elapsed.as_secs_f64().max(1e-6)
+}
+
+/// 生成约 `bytes` 字节的合成日志文本
+fn synthetic_log(bytes: usize) -> String {
+    const LEVELS: [&str; 4] = ["INFO", "DEBUG", "WARN", "ERROR"];
+    let mut text = String::with_capacity(bytes + 256);
+    let mut i = 0usize;
+    while text.len() < bytes {
+        let level = LEVELS[i % LEVELS.len()];
+        let _ = std::fmt::Write::write_fmt(
+            &mut text,
+            format_args!(
+                "2024-01-15 10:{:02}:{:02}.{:03} {level} [worker-{}] request id={} user={} latency_ms={} status={}\n",
+                (i / 60) % 60,
+                i % 60,
+                i % 1000,
+                i % 16,
+                i,
+                i % 977,
+                i % 1500,
+                if i % 97 == 0 { "timeout" } else { "ok" },
+            ),
+        );
+        i += 1;
+    }
+    text
+}
+
+/// 磁盘顺序写 + 读，返回 (写 MB/s, 读 MB/s)
+fn bench_disk(dir: &Path, bytes: usize) -> Result<(f64, f64)> {
+    let path = dir.join("disk.bin");
+    let chunk = vec![0xA5u8; 1024 * 1024];
+
+    let start = Instant::now();
+    {
+        let mut file = std::fs::File::create(&path)
+            .map_err(|e| AppError::io_error(e.to_string(), Some(path.clone())))?;
+        let mut written = 0;
+        while written < bytes {
+            let n = chunk.len().min(bytes - written);
+            file.write_all(&chunk[..n])
+                .map_err(|e| AppError::io_error(e.to_string(), Some(path.clone())))?;
+            written += n;
+        }
+        file.sync_all()
+            .map_err(|e| AppError::io_error(e.to_string(), Some(path.clone())))?;
+    }
+    let write = mb_per_sec(bytes, start.elapsed());
+
+    let start = Instant::now();
+    let mut file = std::fs::File::open(&path)
+        .map_err(|e| AppError::io_error(e.to_string(), Some(path.clone())))?;
+    let mut buf = vec![0u8; 1024 * 1024];
+    let mut read = 0;
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| AppError::io_error(e.to_string(), Some(path.clone())))?;
+        if n == 0 {
+            break;
+        }
+        read += n;
+    }
+    let read_speed = mb_per_sec(read, start.elapsed());
+
+    let _ = std::fs::remove_file(&path);
+    Ok((write, read_speed))
+}
+
+fn bench_sha256(bytes: usize) -> f64 {
+    let chunk = vec![0x5Au8; 1024 * 1024];
+    let start = Instant::now();
+    let mut hasher = Sha256::new();
+    let mut hashed = 0;
+    while hashed < bytes {
+        let n = chunk.len().min(bytes - hashed);
+        hasher.update(&chunk[..n]);
+        hashed += n;
+    }
+    std::hint::black_box(hasher.finalize());
+    mb_per_sec(bytes, start.elapsed())
+}
+
+/// 返回 (关键词 MB/s, 正则 MB/s)
+fn bench_matching(bytes: usize) -> Result<(f64, f64)> {
+    let text = synthetic_log(bytes);
+    let keyword = RegexEngine::new("ERROR|timeout|worker-7", false)
+        .map_err(|e| AppError::search_error(e.to_string()))?;
+    let regex = RegexEngine::new(r"latency_ms=1\d{3}\b", true)
+        .map_err(|e| AppError::search_error(e.to_string()))?;
+
+    let measure = |engine: &RegexEngine| {
+        let start = Instant::now();
+        let hits = text.lines().filter(|line| engine.is_match(line)).count();
+        std::hint::black_box(hits);
+        mb_per_sec(text.len(), start.elapsed())
+    };
+
+    Ok((measure(&keyword), measure(&regex)))
+}
+
+async fn bench_sqlite(dir: &Path, rows: usize) -> Result<f64> {
+    let db_url = format!("sqlite://{}?mode=rwc", dir.join("bench.db").display());
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&db_url)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to open benchmark database: {e}")))?;
+
+    let db_err = |e: sqlx::Error| AppError::database_error(format!("Benchmark query failed: {e}"));
+
+    sqlx::query("PRAGMA journal_mode = WAL")
+        .execute(&pool)
+        .await
+        .map_err(db_err)?;
+    sqlx::query(
+        "CREATE TABLE bench (id INTEGER PRIMARY KEY, hash TEXT NOT NULL, path TEXT NOT NULL, size INTEGER NOT NULL)",
+    )
+    .execute(&pool)
+    .await
+    .map_err(db_err)?;
+
+    let start = Instant::now();
+    let mut tx = pool.begin().await.map_err(db_err)?;
+    for i in 0..rows {
+        sqlx::query("INSERT INTO bench (hash, path, size) VALUES (?, ?, ?)")
+            .bind(format!("{i:064x}"))
+            .bind(format!("logs/app-{i}.log"))
+            .bind(i as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_err)?;
+    }
+    tx.commit().await.map_err(db_err)?;
+    let rate = per_sec(rows, start.elapsed());
+
+    pool.close().await;
+    Ok(rate)
+}
+
+/// 根据基准结果与当前配置给出调优建议（仅返回与当前值不同的项）
+pub fn suggest_tuning(results: &BenchmarkResults, config: &AppConfig) -> Vec<TuningSuggestion> {
+    let cpus = results.cpu_count.max(1) as u64;
+    let slow_disk = results.disk_write_mb_per_sec < SLOW_DISK_MB_PER_SEC;
+    let mut suggestions = Vec::new();
+
+    let mut push = |setting: &str, current: u64, suggested: u64, reason: String| {
+        if current != suggested {
+            suggestions.push(TuningSuggestion {
+                setting: setting.to_string(),
+                current,
+                suggested,
+                reason,
+            });
+        }
+    };
+
+    // 导入并行度：慢盘上并行只会加剧寻道；快盘按 CPU 数（哈希是 CPU 密集）
+    let parallel_files = if slow_disk {
+        2
+    } else {
+        (cpus / 2).clamp(2, 16)
+    };
+    push(
+        "archive.max_parallel_files",
+        config.archive.max_parallel_files as u64,
+        parallel_files,
+        if slow_disk {
+            format!(
+                "磁盘写入仅 {:.0} MB/s，降低并行度以减少随机 IO",
+                results.disk_write_mb_per_sec
+            )
+        } else {
+            format!("磁盘较快，按 {cpus} 个 CPU 核心设置并行导入数")
+        },
+    );
+
+    // 目录批大小：SQLite 写入越快，单批可以越大
+    let batch_size = match results.sqlite_inserts_per_sec {
+        r if r >= 50_000.0 => 50,
+        r if r >= 10_000.0 => 20,
+        _ => 10,
+    };
+    push(
+        "archive.directory_batch_size",
+        config.archive.directory_batch_size as u64,
+        batch_size,
+        format!(
+            "SQLite 插入速率约 {:.0} 行/秒",
+            results.sqlite_inserts_per_sec
+        ),
+    );
+
+    // 并发搜索数：搜索为 CPU 密集，超过核心数只会互相抢占
+    let max_searches = config.search.max_concurrent_searches as u64;
+    if max_searches > cpus {
+        push(
+            "search.max_concurrent_searches",
+            max_searches,
+            cpus.max(2),
+            format!("并发搜索数超过 CPU 核心数（{cpus}），会互相抢占线程"),
+        );
+    }
+
+    suggestions
+}
+
+fn baseline_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(BASELINE_FILE)
+}
+
+/// 读取已保存的基线
+pub fn load_baseline(data_dir: &Path) -> Option<BenchmarkResults> {
+    let content = std::fs::read_to_string(baseline_path(data_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_baseline(data_dir: &Path, results: &BenchmarkResults) -> Result<()> {
+    let path = baseline_path(data_dir);
+    let json = serde_json::to_string_pretty(results)
+        .map_err(|e| AppError::io_error(format!("Failed to serialize baseline: {e}"), None))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|_| std::fs::rename(&tmp, &path))
+        .map_err(|e| AppError::io_error(format!("Failed to save baseline: {e}"), Some(path)))
+}
+
+/// 运行全部基准，保存基线并返回报告
+pub async fn run_diagnostics(
+    data_dir: &Path,
+    config: &AppConfig,
+    options: BenchmarkOptions,
+) -> Result<BenchmarkReport> {
+    let bench_root = data_dir.join("benchmark");
+    std::fs::create_dir_all(&bench_root)
+        .map_err(|e| AppError::io_error(e.to_string(), Some(bench_root.clone())))?;
+    let scratch = tempfile::TempDir::new_in(&bench_root)
+        .map_err(|e| AppError::io_error(e.to_string(), Some(bench_root.clone())))?;
+
+    let started = Instant::now();
+
+    let scratch_path = scratch.path().to_path_buf();
+    let (disk, sha256, matching) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let disk = bench_disk(&scratch_path, options.disk_bytes)?;
+        let sha256 = bench_sha256(options.hash_bytes);
+        let matching = bench_matching(options.match_bytes)?;
+        Ok((disk, sha256, matching))
+    })
+    .await
+    .map_err(|e| AppError::io_error(format!("Benchmark task panicked: {e}"), None))??;
+
+    let sqlite = bench_sqlite(scratch.path(), options.sqlite_rows).await?;
+
+    let results = BenchmarkResults {
+        cpu_count: num_cpus::get(),
+        disk_write_mb_per_sec: disk.0,
+        disk_read_mb_per_sec: disk.1,
+        sha256_mb_per_sec: sha256,
+        keyword_match_mb_per_sec: matching.0,
+        regex_match_mb_per_sec: matching.1,
+        sqlite_inserts_per_sec: sqlite,
+        measured_at: chrono::Utc::now().to_rfc3339(),
+        total_duration_ms: started.elapsed().as_millis() as u64,
+    };
+
+    let baseline = load_baseline(data_dir);
+    if let Err(e) = save_baseline(data_dir, &results) {
+        warn!(error = %e, "Failed to save benchmark baseline");
+    }
+
+    info!(
+        duration_ms = results.total_duration_ms,
+        disk_read = results.disk_read_mb_per_sec,
+        sha256 = results.sha256_mb_per_sec,
+        sqlite = results.sqlite_inserts_per_sec,
+        "Diagnostics benchmark completed"
+    );
+
+    let suggestions = suggest_tuning(&results, config);
+    Ok(BenchmarkReport {
+        results,
+        baseline,
+        suggestions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(disk_write: f64, sqlite: f64, cpus: usize) -> BenchmarkResults {
+        BenchmarkResults {
+            cpu_count: cpus,
+            disk_write_mb_per_sec: disk_write,
+            // 页缓存命中时读取远快于磁盘本身
+            disk_read_mb_per_sec: 5_000.0,
+            sha256_mb_per_sec: 500.0,
+            keyword_match_mb_per_sec: 1000.0,
+            regex_match_mb_per_sec: 300.0,
+            sqlite_inserts_per_sec: sqlite,
+            measured_at: String::new(),
+            total_duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_suggest_tuning_slow_disk_lowers_parallelism() {
+        let config = AppConfig::default();
+        let suggestions = suggest_tuning(&results(80.0, 5_000.0, 8), &config);
+        let parallel = suggestions
+            .iter()
+            .find(|s| s.setting == "archive.max_parallel_files")
+            .unwrap();
+        assert_eq!(parallel.suggested, 2);
+    }
+
+    #[test]
+    fn test_suggest_tuning_skips_unchanged_settings() {
+        let mut config = AppConfig::default();
+        config.archive.max_parallel_files = 4;
+        config.archive.directory_batch_size = 50;
+        config.search.max_concurrent_searches = 4;
+        let suggestions = suggest_tuning(&results(2_000.0, 100_000.0, 8), &config);
+        assert!(suggestions.is_empty(), "{suggestions:?}");
+    }
+
+    #[tokio::test]
+    async fn test_run_diagnostics_saves_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = BenchmarkOptions {
+            disk_bytes: 256 * 1024,
+            hash_bytes: 256 * 1024,
+            match_bytes: 64 * 1024,
+            sqlite_rows: 100,
+        };
+        let config = AppConfig::default();
+
+        let first = run_diagnostics(dir.path(), &config, options).await.unwrap();
+        assert!(first.baseline.is_none());
+        assert!(first.results.sqlite_inserts_per_sec > 0.0);
+
+        // 浮点吞吐经 JSON 往返可能差最后一位，按测量时间识别基线
+        let second = run_diagnostics(dir.path(), &config, options).await.unwrap();
+        let baseline = second.baseline.unwrap();
+        assert_eq!(baseline.measured_at, first.results.measured_at);
+        assert_eq!(baseline.cpu_count, first.results.cpu_count);
+    }
+}
@@ -0,0 +1,10 @@
+//! 本机性能基准
+//!
+//! - `diagnostics`：面向用户的自检基准（磁盘、哈希、模式匹配、SQLite 写入），
+//!   结果保存为基线并据此给出配置调优建议
+//! - `relevance`：离线 A/B 评估，回放录制查询比较两套引擎配置的延迟与结果差异
+
+pub mod diagnostics;
+pub mod relevance;
+
+pub use diagnostics::{run_diagnostics, BenchmarkReport, BenchmarkResults, TuningSuggestion};
@@ -0,0 +1,481 @@
+//! 搜索引擎 A/B 离线评估
+//!
+//! 把录制的真实查询（前端搜索历史导出）分别交给两套引擎配置执行，
+//! 比较延迟与结果集差异，用于在发布前验证分词 / 计划器改动的影响。
+//!
+//! 手动运行：
+//!
+//! ```text
+//! LA_RELEVANCE_QUERIES=history.json LA_RELEVANCE_CORPUS=/path/to/logs \
+//!     cargo test --lib relevance_ab_from_env -- --ignored --nocapture
+//! ```
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use la_core::domain::LogSearcher;
+use la_core::error::{AppError, Result};
+use la_core::models::search::{QueryMetadata, QueryOperator, SearchTerm, TermSource};
+use la_core::models::{SearchFilters, SearchQuery};
+use serde::{Deserialize, Serialize};
+
+use crate::infrastructure::QueryEngineLogSearcher;
+use crate::services::looks_like_regex_pattern;
+use crate::utils::encoding::decode_log_content;
+
+/// 录制的查询
+///
+/// 兼容两种格式：纯字符串，或带大小写标志的对象。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum RecordedQuery {
+    Plain(String),
+    Detailed {
+        query: String,
+        #[serde(default, rename = "caseSensitive")]
+        case_sensitive: bool,
+    },
+}
+
+impl RecordedQuery {
+    pub fn text(&self) -> &str {
+        match self {
+            RecordedQuery::Plain(q) => q,
+            RecordedQuery::Detailed { query, .. } => query,
+        }
+    }
+
+    pub fn case_sensitive(&self) -> bool {
+        matches!(
+            self,
+            RecordedQuery::Detailed {
+                case_sensitive: true,
+                ..
+            }
+        )
+    }
+}
+
+/// 查询分词方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryTokenizer {
+    /// 与 `search_logs` 一致：按顶层 `|` 切分
+    Pipe,
+    /// 按空白切分
+    Whitespace,
+    /// 整个查询作为单一词项
+    Whole,
+}
+
+/// 一套引擎配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineVariant {
+    pub name: String,
+    pub tokenizer: QueryTokenizer,
+    /// 词项之间的组合方式
+    pub operator: QueryOperator,
+    /// 是否对词项做正则自动识别
+    pub regex_detection: bool,
+    /// 覆盖录制查询的大小写设置
+    pub case_sensitive: Option<bool>,
+    pub regex_cache_size: usize,
+}
+
+impl EngineVariant {
+    /// 当前生产配置（与 `search_logs` 行为一致）
+    pub fn production() -> Self {
+        Self {
+            name: "production".to_string(),
+            tokenizer: QueryTokenizer::Pipe,
+            operator: QueryOperator::Or,
+            regex_detection: true,
+            case_sensitive: None,
+            regex_cache_size: 1000,
+        }
+    }
+
+    fn tokenize(&self, query: &str) -> Vec<String> {
+        match self.tokenizer {
+            QueryTokenizer::Pipe => crate::commands::search::query::split_query_by_pipe(query),
+            QueryTokenizer::Whitespace => query.split_whitespace().map(str::to_string).collect(),
+            QueryTokenizer::Whole => {
+                let q = query.trim();
+                if q.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![q.to_string()]
+                }
+            }
+        }
+    }
+
+    fn build_query(&self, recorded: &RecordedQuery) -> Option<SearchQuery> {
+        let case_sensitive = self
+            .case_sensitive
+            .unwrap_or_else(|| recorded.case_sensitive());
+        let terms: Vec<SearchTerm> = self
+            .tokenize(recorded.text())
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| SearchTerm {
+                id: format!("term_{i}"),
+                is_regex: self.regex_detection && looks_like_regex_pattern(&value),
+                value,
+                operator: self.operator.clone(),
+                source: TermSource::User,
+                preset_group_id: None,
+                priority: 1,
+                enabled: true,
+                case_sensitive,
+            })
+            .collect();
+
+        if terms.is_empty() {
+            return None;
+        }
+
+        Some(SearchQuery {
+            id: "relevance-ab".to_string(),
+            terms,
+            global_operator: self.operator.clone(),
+            filters: None,
+            metadata: QueryMetadata {
+                created_at: 0,
+                last_modified: 0,
+                execution_count: 0,
+                label: None,
+            },
+        })
+    }
+}
+
+/// 语料中的一个文件
+#[derive(Debug, Clone)]
+pub struct CorpusFile {
+    pub virtual_path: String,
+    pub content: String,
+}
+
+/// 单个配置对单条查询的执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantRun {
+    pub matches: usize,
+    /// 多次运行中的最短耗时（微秒）
+    pub latency_us: u64,
+    pub error: Option<String>,
+}
+
+/// 单条查询的 A/B 对比
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryComparison {
+    pub query: String,
+    pub a: VariantRun,
+    pub b: VariantRun,
+    pub only_in_a: usize,
+    pub only_in_b: usize,
+    /// 结果集 Jaccard 相似度（两边均为空时为 1.0）
+    pub jaccard: f64,
+}
+
+/// 延迟分布（微秒）
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub max_us: u64,
+    pub total_us: u64,
+}
+
+impl LatencyStats {
+    fn from_samples(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let pick = |p: f64| samples[((samples.len() - 1) as f64 * p).round() as usize];
+        Self {
+            p50_us: pick(0.50),
+            p95_us: pick(0.95),
+            max_us: *samples.last().unwrap_or(&0),
+            total_us: samples.iter().sum(),
+        }
+    }
+}
+
+/// 评估报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelevanceReport {
+    pub variant_a: String,
+    pub variant_b: String,
+    pub query_count: usize,
+    pub identical_results: usize,
+    pub differing_results: usize,
+    pub errors: usize,
+    pub mean_jaccard: f64,
+    pub latency_a: LatencyStats,
+    pub latency_b: LatencyStats,
+    /// 结果不一致或出错的查询，按相似度升序
+    pub differences: Vec<QueryComparison>,
+}
+
+/// 读取录制的查询（JSON 数组）
+pub fn load_recorded_queries(path: &Path) -> Result<Vec<RecordedQuery>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::io_error(e.to_string(), Some(path.to_path_buf())))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::validation_error(format!("Invalid recorded queries file: {e}")))
+}
+
+/// 递归读取目录下的所有文件作为评估语料
+pub fn load_corpus(dir: &Path) -> Result<Vec<CorpusFile>> {
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(dir).follow_links(false) {
+        let entry =
+            entry.map_err(|e| AppError::io_error(e.to_string(), Some(dir.to_path_buf())))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let bytes = std::fs::read(entry.path())
+            .map_err(|e| AppError::io_error(e.to_string(), Some(entry.path().to_path_buf())))?;
+        let (content, _) = decode_log_content(&bytes);
+        let virtual_path = entry
+            .path()
+            .strip_prefix(dir)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        files.push(CorpusFile {
+            virtual_path,
+            content,
+        });
+    }
+    Ok(files)
+}
+
+type ResultKey = (String, usize);
+
+fn run_variant(
+    searcher: &QueryEngineLogSearcher,
+    variant: &EngineVariant,
+    recorded: &RecordedQuery,
+    corpus: &[CorpusFile],
+    runs: usize,
+) -> (VariantRun, HashSet<ResultKey>) {
+    let Some(query) = variant.build_query(recorded) else {
+        return (
+            VariantRun {
+                matches: 0,
+                latency_us: 0,
+                error: Some("empty query".to_string()),
+            },
+            HashSet::new(),
+        );
+    };
+
+    let filters = SearchFilters::default();
+    let mut best = Duration::MAX;
+    let mut keys = HashSet::new();
+
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let plan = match searcher.build_plan(&query) {
+            Ok(plan) => plan,
+            Err(e) => {
+                return (
+                    VariantRun {
+                        matches: 0,
+                        latency_us: 0,
+                        error: Some(e.to_string()),
+                    },
+                    HashSet::new(),
+                )
+            }
+        };
+        keys.clear();
+        for file in corpus {
+            for entry in
+                searcher.match_content(&file.content, &file.virtual_path, &plan, &filters, 0)
+            {
+                keys.insert((file.virtual_path.clone(), entry.line));
+            }
+        }
+        best = best.min(start.elapsed());
+    }
+
+    (
+        VariantRun {
+            matches: keys.len(),
+            latency_us: best.as_micros() as u64,
+            error: None,
+        },
+        keys,
+    )
+}
+
+/// 用两套配置回放查询并比较结果
+///
+/// `runs` 为每条查询每套配置的重复次数，延迟取最短一次以降低噪声。
+pub fn compare_variants(
+    queries: &[RecordedQuery],
+    corpus: &[CorpusFile],
+    a: &EngineVariant,
+    b: &EngineVariant,
+    runs: usize,
+) -> RelevanceReport {
+    let searcher_a = QueryEngineLogSearcher::new(a.regex_cache_size);
+    let searcher_b = QueryEngineLogSearcher::new(b.regex_cache_size);
+
+    let mut comparisons = Vec::with_capacity(queries.len());
+    for recorded in queries {
+        let (run_a, keys_a) = run_variant(&searcher_a, a, recorded, corpus, runs);
+        let (run_b, keys_b) = run_variant(&searcher_b, b, recorded, corpus, runs);
+
+        let intersection = keys_a.intersection(&keys_b).count();
+        let union = keys_a.len() + keys_b.len() - intersection;
+        let jaccard = if union == 0 {
+            1.0
+        } else {
+            intersection as f64 / union as f64
+        };
+
+        comparisons.push(QueryComparison {
+            query: recorded.text().to_string(),
+            only_in_a: keys_a.len() - intersection,
+            only_in_b: keys_b.len() - intersection,
+            jaccard,
+            a: run_a,
+            b: run_b,
+        });
+    }
+
+    let ok = |c: &&QueryComparison| c.a.error.is_none() && c.b.error.is_none();
+    let errors = comparisons.iter().filter(|c| !ok(c)).count();
+    let identical_results = comparisons
+        .iter()
+        .filter(ok)
+        .filter(|c| c.only_in_a == 0 && c.only_in_b == 0)
+        .count();
+    let mean_jaccard = if comparisons.is_empty() {
+        1.0
+    } else {
+        comparisons.iter().map(|c| c.jaccard).sum::<f64>() / comparisons.len() as f64
+    };
+    let latency_a = LatencyStats::from_samples(
+        comparisons
+            .iter()
+            .filter(ok)
+            .map(|c| c.a.latency_us)
+            .collect(),
+    );
+    let latency_b = LatencyStats::from_samples(
+        comparisons
+            .iter()
+            .filter(ok)
+            .map(|c| c.b.latency_us)
+            .collect(),
+    );
+
+    let mut differences: Vec<QueryComparison> = comparisons
+        .into_iter()
+        .filter(|c| c.a.error.is_some() || c.b.error.is_some() || c.only_in_a + c.only_in_b > 0)
+        .collect();
+    differences.sort_by(|x, y| x.jaccard.total_cmp(&y.jaccard));
+
+    RelevanceReport {
+        variant_a: a.name.clone(),
+        variant_b: b.name.clone(),
+        query_count: queries.len(),
+        identical_results,
+        differing_results: queries.len() - identical_results - errors,
+        errors,
+        mean_jaccard,
+        latency_a,
+        latency_b,
+        differences,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<CorpusFile> {
+        vec![CorpusFile {
+            virtual_path: "app.log".to_string(),
+            content: "2024-01-01 10:00:00 ERROR disk full\n\
+                      2024-01-01 10:00:01 INFO request ok\n\
+                      2024-01-01 10:00:02 WARN disk slow\n"
+                .to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_recorded_query_formats() {
+        let queries: Vec<RecordedQuery> =
+            serde_json::from_str(r#"["error", {"query": "Disk", "caseSensitive": true}]"#).unwrap();
+        assert_eq!(queries[0].text(), "error");
+        assert!(!queries[0].case_sensitive());
+        assert!(queries[1].case_sensitive());
+    }
+
+    #[test]
+    fn test_identical_variants_have_no_differences() {
+        let queries = vec![RecordedQuery::Plain("disk|ERROR".to_string())];
+        let v = EngineVariant::production();
+        let report = compare_variants(&queries, &corpus(), &v, &v, 1);
+        assert_eq!(report.identical_results, 1);
+        assert!(report.differences.is_empty());
+        assert_eq!(report.mean_jaccard, 1.0);
+    }
+
+    #[test]
+    fn test_tokenizer_change_is_reported() {
+        let queries = vec![RecordedQuery::Plain("disk full".to_string())];
+        let a = EngineVariant::production();
+        let b = EngineVariant {
+            name: "whitespace-or".to_string(),
+            tokenizer: QueryTokenizer::Whitespace,
+            ..EngineVariant::production()
+        };
+        let report = compare_variants(&queries, &corpus(), &a, &b, 1);
+        assert_eq!(report.differing_results, 1);
+        let diff = &report.differences[0];
+        assert_eq!(diff.a.matches, 1);
+        assert_eq!(diff.b.matches, 2);
+        assert_eq!(diff.only_in_b, 1);
+    }
+
+    #[test]
+    fn test_latency_stats_percentiles() {
+        let stats = LatencyStats::from_samples((1..=100).collect());
+        assert_eq!(stats.p50_us, 51);
+        assert_eq!(stats.p95_us, 95);
+        assert_eq!(stats.max_us, 100);
+    }
+
+    #[test]
+    #[ignore = "Offline harness - set LA_RELEVANCE_QUERIES and LA_RELEVANCE_CORPUS"]
+    fn relevance_ab_from_env() {
+        let queries = std::env::var("LA_RELEVANCE_QUERIES").expect("LA_RELEVANCE_QUERIES");
+        let corpus_dir = std::env::var("LA_RELEVANCE_CORPUS").expect("LA_RELEVANCE_CORPUS");
+        let queries = load_recorded_queries(Path::new(&queries)).unwrap();
+        let corpus = load_corpus(Path::new(&corpus_dir)).unwrap();
+
+        let a = EngineVariant::production();
+        let b = EngineVariant {
+            name: "whitespace-and".to_string(),
+            tokenizer: QueryTokenizer::Whitespace,
+            operator: QueryOperator::And,
+            ..EngineVariant::production()
+        };
+        let report = compare_variants(&queries, &corpus, &a, &b, 3);
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    }
+}
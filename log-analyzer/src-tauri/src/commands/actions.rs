@@ -0,0 +1,754 @@
+//! 操作注册表
+//!
+//! 枚举前端可调用的操作（名称、标题、分类、参数），供命令面板与脚本宏使用：
+//! `list_actions` 返回注册表，`invoke_action` 按名称分发到对应命令，
+//! 前端不必硬编码命令名。
+//!
+//! 参数与直接 `invoke` 命令时相同（camelCase 键）；分发时仍经过命令自身的
+//! 校验与工作区/租户守卫。上传分块、客户端会话、状态同步与表单校验等
+//! 传输层命令不作为操作暴露（见 [`NON_ACTION_COMMANDS`]）。
+
+use la_core::error::CommandError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, State, Window};
+
+use crate::commands::{
+    analysis, analysis_macro, config, diagnostics, export, import, log_config, search,
+    virtual_tree, watch, workspace, workspace_share, workspace_template,
+};
+use crate::models::AppState;
+use crate::utils::workspace_guard::require_unscoped_caller;
+
+/// 已注册但不作为操作暴露的命令
+pub const NON_ACTION_COMMANDS: &[&str] = &[
+    "list_actions",
+    "invoke_action",
+    "start_macro_recording",
+    "get_macro_recording",
+    "stop_macro_recording",
+    "discard_macro_recording",
+    "list_macros",
+    "save_macro",
+    "delete_macro",
+    "replay_macro",
+    "assistant_rpc",
+    "remote_workspace_call",
+    "begin_upload",
+    "append_chunk",
+    "finish_upload",
+    "cancel_upload",
+    "init_state_sync",
+    "open_client_session",
+    "client_heartbeat",
+    "close_client_session",
+    "list_client_sessions",
+    "report_frontend_error",
+    "validate_workspace_id_format",
+    "validate_path_security",
+    "validate_workspace_config_cmd",
+    "validate_search_query_cmd",
+    "validate_archive_config_cmd",
+];
+
+/// 操作描述
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionDescriptor {
+    /// 操作名（与命令名相同）
+    pub name: &'static str,
+    /// 面板中展示的标题
+    pub title: &'static str,
+    pub category: &'static str,
+    pub args: Vec<ActionArg>,
+}
+
+/// 操作参数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionArg {
+    /// 参数键（camelCase，与 `invoke` 时相同）
+    pub name: String,
+    /// JSON 类型：`string`、`integer`、`number`、`boolean`、`array`、`object`
+    #[serde(rename = "type")]
+    pub value_type: &'static str,
+    /// Rust 侧类型名（不含模块路径），用于区分不同结构的 `object`
+    pub type_name: String,
+    pub required: bool,
+}
+
+impl ActionArg {
+    fn new(param: &str, rust_type: &str) -> Self {
+        let rust_type: String = rust_type.chars().filter(|c| !c.is_whitespace()).collect();
+        let (inner, required) = match rust_type
+            .strip_prefix("Option<")
+            .and_then(|t| t.strip_suffix('>'))
+        {
+            Some(inner) => (inner, false),
+            None => (rust_type.as_str(), true),
+        };
+        Self {
+            name: param_key(param),
+            value_type: json_type(inner),
+            type_name: strip_paths(inner),
+            required,
+        }
+    }
+}
+
+/// 命令参数名转换为 `invoke` 使用的 camelCase 键（与 Tauri 的转换一致）
+fn param_key(param: &str) -> String {
+    let mut key = String::with_capacity(param.len());
+    let mut upper_next = false;
+    for c in param.trim_start_matches('_').chars() {
+        if c == '_' {
+            upper_next = true;
+        } else if upper_next {
+            key.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            key.push(c);
+        }
+    }
+    key
+}
+
+fn json_type(rust_type: &str) -> &'static str {
+    let base = rust_type.split('<').next().unwrap_or(rust_type);
+    match base.rsplit("::").next().unwrap_or(base) {
+        "String" | "str" | "LogFormat" | "FileSearchFlag" | "MatchDirection" => "string",
+        "bool" => "boolean",
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+            "integer"
+        }
+        "f32" | "f64" => "number",
+        "Vec" => "array",
+        _ => "object",
+    }
+}
+
+/// `la_core::models::SearchQuery` → `SearchQuery`，泛型参数同样处理
+fn strip_paths(rust_type: &str) -> String {
+    let mut out = String::with_capacity(rust_type.len());
+    let mut segment = String::new();
+    for c in rust_type.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            out.push_str(segment.rsplit("::").next().unwrap_or(&segment));
+            segment.clear();
+            out.push(c);
+        }
+    }
+    out.push_str(segment.rsplit("::").next().unwrap_or(&segment));
+    out
+}
+
+/// 一次分发的参数；逐个取出后未消费的键视为错误
+struct ActionArgs {
+    action: String,
+    values: Map<String, Value>,
+}
+
+impl ActionArgs {
+    fn new(action: &str, args: Option<Value>) -> Result<Self, CommandError> {
+        let values = match args {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(values)) => values,
+            Some(_) => {
+                return Err(CommandError::new(
+                    "VALIDATION_ERROR",
+                    format!("Arguments for action '{action}' must be an object"),
+                ))
+            }
+        };
+        Ok(Self {
+            action: action.to_string(),
+            values,
+        })
+    }
+
+    fn take<T: DeserializeOwned>(&mut self, param: &str) -> Result<T, CommandError> {
+        let key = param_key(param);
+        let value = self.values.remove(&key);
+        let missing = value.is_none();
+        serde_json::from_value(value.unwrap_or(Value::Null)).map_err(|e| {
+            let message = if missing {
+                format!("Missing argument '{key}' for action '{}'", self.action)
+            } else {
+                format!("Invalid argument '{key}' for action '{}': {e}", self.action)
+            };
+            CommandError::new("VALIDATION_ERROR", message)
+        })
+    }
+
+    fn finish(self) -> Result<(), CommandError> {
+        if self.values.is_empty() {
+            return Ok(());
+        }
+        let mut unknown: Vec<&str> = self.values.keys().map(String::as_str).collect();
+        unknown.sort_unstable();
+        Err(CommandError::new(
+            "VALIDATION_ERROR",
+            format!(
+                "Unknown argument(s) for action '{}': {}",
+                self.action,
+                unknown.join(", ")
+            ),
+        )
+        .with_help("Use list_actions to see the accepted arguments"))
+    }
+}
+
+fn action_result<T: Serialize, E: Into<CommandError>>(
+    result: Result<T, E>,
+) -> Result<Value, CommandError> {
+    let value = result.map_err(Into::into)?;
+    serde_json::to_value(value).map_err(|e| CommandError::new("SERIALIZATION_ERROR", e.to_string()))
+}
+
+/// 声明操作表，生成注册表与分发函数
+///
+/// 每项为 `名称 "标题" (参数: 类型, ...) => 命令调用;`，调用表达式中可使用
+/// 参数以及首行声明的 `app`、`state`、`window`。
+macro_rules! action_table {
+    (
+        dispatch($args:ident, $app:ident, $state:ident, $window:ident);
+        $( $category:literal => {
+            $( $name:ident $title:literal ( $( $arg:ident : $ty:ty ),* ) => $call:expr; )*
+        } )*
+    ) => {
+        /// 全部操作的描述（按分类声明顺序）
+        pub fn action_descriptors() -> Vec<ActionDescriptor> {
+            vec![$($(
+                ActionDescriptor {
+                    name: stringify!($name),
+                    title: $title,
+                    category: $category,
+                    args: vec![$( ActionArg::new(stringify!($arg), stringify!($ty)) ),*],
+                },
+            )*)*]
+        }
+
+        #[allow(non_snake_case)]
+        async fn dispatch(
+            name: &str,
+            mut $args: ActionArgs,
+            $app: AppHandle,
+            $state: State<'_, AppState>,
+            $window: Window,
+        ) -> Result<Value, CommandError> {
+            match name {
+                $($(
+                    stringify!($name) => {
+                        $( let $arg: $ty = $args.take(stringify!($arg))?; )*
+                        $args.finish()?;
+                        crate::monitoring::telemetry().record_command(name);
+                        action_result($call.await)
+                    }
+                )*)*
+                _ => Err(CommandError::new("NOT_FOUND", format!("Unknown action: {name}"))
+                    .with_help("Use list_actions to see the available actions")),
+            }
+        }
+    };
+}
+
+action_table! {
+    dispatch(args, app, state, window);
+
+    "config" => {
+        load_config "Load configuration" () => config::load_config(app);
+        save_config "Save configuration" (config: la_core::models::config::AppConfig)
+            => config::save_config(app, config);
+        get_file_filter_config "Show file filter settings" () => config::get_file_filter_config(app);
+        save_file_filter_config "Save file filter settings"
+            (filter_config: la_core::models::config::FileFilterConfig)
+            => config::save_file_filter_config(app, filter_config);
+        get_search_config "Show search settings" () => config::get_search_config(app);
+        save_search_config "Save search settings"
+            (search_config: la_core::models::config::SearchConfig)
+            => config::save_search_config(app, search_config);
+        get_task_manager_config "Show task manager settings" ()
+            => config::get_task_manager_config(app);
+        save_task_manager_config "Save task manager settings"
+            (task_manager_config: la_core::models::config::TaskManagerConfig)
+            => config::save_task_manager_config(app, task_manager_config);
+        get_link_templates "List link templates" () => config::get_link_templates(app);
+        register_link_template "Register link template"
+            (template: la_core::models::config::LinkTemplate)
+            => config::register_link_template(app, template);
+        remove_link_template "Remove link template" (id: String)
+            => config::remove_link_template(app, id);
+    }
+
+    "workspace" => {
+        create_workspace "Create workspace" (name: String, path: String, client_id: Option<String>)
+            => workspace::create_workspace(name, path, client_id, app, state);
+        load_workspace "Open workspace" (workspace_id: String, client_id: Option<String>)
+            => workspace::load_workspace(app, workspace_id, client_id, state);
+        refresh_workspace "Refresh workspace"
+            (workspace_id: String, path: Option<String>, client_id: Option<String>, wait_secs: Option<u64>)
+            => workspace::refresh_workspace(app, workspace_id, path, client_id, wait_secs, state);
+        preview_refresh "Preview workspace refresh"
+            (workspace_id: String, client_id: Option<String>, path: Option<String>, verify_hashes: Option<bool>)
+            => workspace::preview_refresh(app, workspace_id, client_id, path, verify_hashes, state);
+        repair_corrupted_objects "Repair corrupted objects"
+            (workspace_id: String, client_id: Option<String>, full_scan: Option<bool>)
+            => workspace::repair_corrupted_objects(app, workspace_id, client_id, full_scan, state);
+        get_failed_entries "Show failed archive entries"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_failed_entries(workspace_id, client_id, app, state);
+        retry_failed_extractions "Retry failed archive entries"
+            (workspace_id: String, client_id: Option<String>, wait_secs: Option<u64>)
+            => workspace::retry_failed_extractions(app, workspace_id, client_id, wait_secs, state);
+        delete_workspace "Delete workspace"
+            (workspace_id: String, client_id: Option<String>, wait_secs: Option<u64>)
+            => workspace::delete_workspace(workspace_id, client_id, wait_secs, state, app);
+        cancel_task "Cancel task" (task_id: String) => workspace::cancel_task(task_id, state);
+        get_workspace_status "Show workspace status"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_workspace_status(workspace_id, client_id, app, state);
+        get_workspace_time_range "Show workspace time range"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_workspace_time_range(app, workspace_id, client_id, state);
+        get_recent_entries "Show recent entries"
+            (workspace_id: String, client_id: Option<String>, levels: Option<Vec<String>>, limit: Option<usize>)
+            => workspace::get_recent_entries(app, workspace_id, client_id, levels, limit, state);
+        get_entries_around_time "Show entries around a time"
+            (workspace_id: String, client_id: Option<String>, timestamp: String, files: Vec<String>, window_secs: Option<i64>, limit: Option<usize>)
+            => workspace::get_entries_around_time(app, workspace_id, client_id, timestamp, files, window_secs, limit, state);
+        get_workspace_overview "Show workspace overview"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_workspace_overview(workspace_id, client_id, app, state);
+        get_dedup_report "Show duplicate content report"
+            (workspace_id: String, client_id: Option<String>, top_n: Option<usize>)
+            => workspace::get_dedup_report(workspace_id, client_id, top_n, app, state);
+        get_skipped_entries "Show skipped archive entries"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_skipped_entries(workspace_id, client_id, app, state);
+        get_workspace_manifest "Show bundle manifest"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_workspace_manifest(workspace_id, client_id, app, state);
+        get_post_extract_hooks "Show post-extract hooks"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_post_extract_hooks(workspace_id, client_id, app, state);
+        set_post_extract_hooks "Set post-extract hooks"
+            (workspace_id: String, client_id: Option<String>, hooks: Vec<la_archive::PostExtractHook>)
+            => workspace::set_post_extract_hooks(workspace_id, client_id, hooks, app, state);
+        get_post_extract_report "Show post-extract report"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_post_extract_report(workspace_id, client_id, app, state);
+        get_workspace_read_only "Show read-only state"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_workspace_read_only(workspace_id, client_id, app, state);
+        set_workspace_read_only "Set read-only state"
+            (workspace_id: String, client_id: Option<String>, read_only: bool)
+            => workspace::set_workspace_read_only(workspace_id, client_id, read_only, app, state);
+        get_max_line_length "Show max line length" (workspace_id: String, client_id: Option<String>)
+            => workspace::get_max_line_length(workspace_id, client_id, app, state);
+        set_max_line_length "Set max line length"
+            (workspace_id: String, client_id: Option<String>, max_line_length: Option<usize>)
+            => workspace::set_max_line_length(workspace_id, client_id, max_line_length, app, state);
+        get_level_order "Show log level order" (workspace_id: String, client_id: Option<String>)
+            => workspace::get_level_order(workspace_id, client_id, app, state);
+        set_level_order "Set log level order"
+            (workspace_id: String, client_id: Option<String>, level_order: Option<String>)
+            => workspace::set_level_order(workspace_id, client_id, level_order, app, state);
+        get_search_defaults "Show workspace search defaults"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_search_defaults(workspace_id, client_id, app, state);
+        set_search_defaults "Set workspace search defaults"
+            (workspace_id: String, client_id: Option<String>, defaults: search::WorkspaceSearchDefaults)
+            => workspace::set_search_defaults(workspace_id, client_id, defaults, app, state);
+        get_field_extractors "List field extractors"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_field_extractors(workspace_id, client_id, app, state);
+        save_field_extractor "Save field extractor"
+            (workspace_id: String, client_id: Option<String>, extractor: crate::services::field_extractors::FieldExtractor)
+            => workspace::save_field_extractor(workspace_id, client_id, extractor, app, state);
+        delete_field_extractor "Delete field extractor"
+            (workspace_id: String, client_id: Option<String>, name: String)
+            => workspace::delete_field_extractor(workspace_id, client_id, name, app, state);
+        test_extractor "Preview field extractor"
+            (extractor: crate::services::field_extractors::FieldExtractor, sample_lines: Vec<String>)
+            => workspace::test_extractor(extractor, sample_lines);
+        extract_entry_fields "Extract fields from entries"
+            (workspace_id: String, client_id: Option<String>, entries: Vec<la_core::models::LogEntry>)
+            => workspace::extract_entry_fields(workspace_id, client_id, entries, app, state);
+        get_webhooks "List webhooks" (workspace_id: String, client_id: Option<String>)
+            => workspace::get_webhooks(workspace_id, client_id, app, state);
+        save_webhook "Save webhook"
+            (workspace_id: String, client_id: Option<String>, webhook: crate::services::webhooks::Webhook)
+            => workspace::save_webhook(workspace_id, client_id, webhook, app, state);
+        delete_webhook "Delete webhook"
+            (workspace_id: String, client_id: Option<String>, id: String)
+            => workspace::delete_webhook(workspace_id, client_id, id, app, state);
+        test_webhook "Send test webhook"
+            (workspace_id: String, client_id: Option<String>, id: String)
+            => workspace::test_webhook(workspace_id, client_id, id, app, state);
+        get_workspace_leases "Show workspace leases" (client_id: Option<String>)
+            => workspace::get_workspace_leases(client_id, app, state);
+        set_file_search_flag "Set file search flag"
+            (workspace_id: String, client_id: Option<String>, virtual_path: String, flag: la_storage::FileSearchFlag)
+            => workspace::set_file_search_flag(workspace_id, client_id, virtual_path, flag, app, state);
+        get_file_search_flags "List ignored and pinned files"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_file_search_flags(workspace_id, client_id, app, state);
+        set_file_format "Set file format"
+            (workspace_id: String, client_id: Option<String>, virtual_path: String, format: Option<la_core::utils::LogFormat>)
+            => workspace::set_file_format(workspace_id, client_id, virtual_path, format, app, state);
+        get_csv_mapping "Show CSV column mapping"
+            (workspace_id: String, client_id: Option<String>, virtual_path: String)
+            => workspace::get_csv_mapping(workspace_id, client_id, virtual_path, app, state);
+        set_csv_mapping "Set CSV column mapping"
+            (workspace_id: String, client_id: Option<String>, virtual_path: String, mapping: Option<la_core::utils::CsvMapping>)
+            => workspace::set_csv_mapping(workspace_id, client_id, virtual_path, mapping, app, state);
+        set_file_overrides "Set file encoding/format/timezone/parser overrides"
+            (workspace_id: String, client_id: Option<String>, file_hash: String, overrides: la_storage::FileOverrides)
+            => workspace::set_file_overrides(workspace_id, client_id, file_hash, overrides, app, state);
+        get_crash_artifacts "List crash artifacts" (workspace_id: String, client_id: Option<String>)
+            => workspace::get_crash_artifacts(workspace_id, client_id, app, state);
+        open_artifact_folder "Reveal artifact in folder"
+            (workspace_id: String, client_id: Option<String>, virtual_path: String)
+            => workspace::open_artifact_folder(workspace_id, client_id, virtual_path, app, state);
+        set_file_time_offset "Set file time offset"
+            (workspace_id: String, client_id: Option<String>, virtual_path: String, offset_secs: i64)
+            => workspace::set_file_time_offset(workspace_id, client_id, virtual_path, offset_secs, app, state);
+        get_file_time_offsets "List file time offsets"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace::get_file_time_offsets(workspace_id, client_id, app, state);
+        estimate_clock_skew "Estimate clock skew"
+            (workspace_id: String, client_id: Option<String>, marker: String, reference_path: String, files: Vec<String>)
+            => workspace::estimate_clock_skew(workspace_id, client_id, marker, reference_path, files, app, state);
+    }
+
+    "template" => {
+        list_workspace_templates "List workspace templates" ()
+            => workspace_template::list_workspace_templates(app);
+        save_workspace_template "Save workspace template"
+            (template: crate::services::workspace_templates::WorkspaceTemplate)
+            => workspace_template::save_workspace_template(template, app);
+        delete_workspace_template "Delete workspace template" (id: String)
+            => workspace_template::delete_workspace_template(id, app);
+        capture_workspace_template "Save workspace as template"
+            (workspace_id: String, client_id: Option<String>, id: String, name: String, description: Option<String>)
+            => workspace_template::capture_workspace_template(workspace_id, client_id, id, name, description, app, state);
+        export_workspace_template "Export workspace template" (id: String)
+            => workspace_template::export_workspace_template(id, app);
+        import_workspace_template "Import workspace template"
+            (json: String, overwrite: Option<bool>)
+            => workspace_template::import_workspace_template(json, overwrite, app);
+        create_workspace_from_template "Create workspace from template"
+            (name: String, path: String, template_id: String, client_id: Option<String>)
+            => workspace_template::create_workspace_from_template(name, path, template_id, client_id, app, state);
+    }
+
+    "share" => {
+        start_workspace_share "Share workspace read-only"
+            (workspace_id: String, client_id: Option<String>, host: Option<String>, port: Option<u16>)
+            => workspace_share::start_workspace_share(workspace_id, client_id, host, port, app, state);
+        stop_workspace_share "Stop sharing workspace"
+            (workspace_id: String, client_id: Option<String>)
+            => workspace_share::stop_workspace_share(workspace_id, client_id, app, state);
+        list_workspace_shares "List workspace shares" (client_id: Option<String>)
+            => workspace_share::list_workspace_shares(client_id, state);
+        mount_remote_workspace "Mount remote workspace"
+            (url: String, token: String, name: Option<String>)
+            => workspace_share::mount_remote_workspace(url, token, name, app);
+        open_remote_workspace "Open workspace by URL"
+            (url: String, token: Option<String>, name: Option<String>)
+            => workspace_share::open_remote_workspace(url, token, name, app);
+        list_remote_workspaces "List remote workspaces" ()
+            => workspace_share::list_remote_workspaces(app);
+        unmount_remote_workspace "Unmount remote workspace" (remote_id: String)
+            => workspace_share::unmount_remote_workspace(remote_id, app);
+        refresh_remote_workspace "Refresh remote workspace" (remote_id: String)
+            => workspace_share::refresh_remote_workspace(remote_id, app);
+    }
+
+    "analysis" => {
+        find_silences "Find silences"
+            (workspace_id: String, client_id: Option<String>, files: Vec<String>, start: Option<String>, end: Option<String>, factor: Option<f64>, min_gap_secs: Option<i64>, context: Option<usize>)
+            => analysis::find_silences(app, workspace_id, client_id, files, start, end, factor, min_gap_secs, context, state);
+        find_first_occurrence "Find first occurrence"
+            (workspace_id: String, client_id: Option<String>, query: String, context: Option<usize>)
+            => analysis::find_first_occurrence(app, workspace_id, client_id, query, context, state);
+        get_adjacent_match "Jump to next or previous match in file"
+            (workspace_id: String, client_id: Option<String>, file_hash: String, line: usize, direction: la_search::MatchDirection, query: String)
+            => analysis::get_adjacent_match(app, workspace_id, client_id, file_hash, line, direction, query, state);
+        get_keyword_cooccurrence "Show keyword co-occurrence"
+            (search_id: String, terms: Vec<String>, window_secs: Option<i64>, client_id: Option<String>)
+            => analysis::get_keyword_cooccurrence(search_id, terms, window_secs, client_id, state);
+        diff_search_results "Diff search results"
+            (workspace_id: Option<String>, left: analysis::DiffSide, right: analysis::DiffSide, max_entries: Option<usize>, client_id: Option<String>)
+            => analysis::diff_search_results(app, window, workspace_id, left, right, max_entries, client_id, state);
+        run_metadata_query "Run metadata query"
+            (workspace_id: String, client_id: Option<String>, sql: String, max_rows: Option<usize>, timeout_ms: Option<u64>, format: Option<String>)
+            => analysis::run_metadata_query(app, workspace_id, sql, max_rows, timeout_ms, format, client_id, state);
+    }
+
+    "watch" => {
+        start_watch "Start watching"
+            (workspaceId: String, clientId: Option<String>, path: String, _autoSearch: Option<bool>)
+            => watch::start_watch(app, workspaceId, clientId, path, _autoSearch, state);
+        stop_watch "Stop watching" (workspaceId: String, clientId: Option<String>)
+            => watch::stop_watch(app, workspaceId, clientId, state);
+        get_watcher_budget "Show watcher budget" () => watch::get_watcher_budget(state);
+        attach_follow_query "Follow query"
+            (workspaceId: String, clientId: Option<String>, query: la_core::models::SearchQuery)
+            => watch::attach_follow_query(app, workspaceId, query, clientId, state);
+        detach_follow_query "Stop following query"
+            (workspaceId: String, clientId: Option<String>, queryId: String)
+            => watch::detach_follow_query(app, workspaceId, clientId, queryId, state);
+    }
+
+    "file" => {
+        read_file_by_hash "Read file"
+            (workspaceId: String, clientId: Option<String>, hash: String, line: Option<usize>, offset: Option<usize>, length: Option<usize>)
+            => virtual_tree::read_file_by_hash(app, workspaceId, clientId, hash, line, offset, length, state);
+        seek_file_time "Jump to time in file"
+            (workspaceId: String, clientId: Option<String>, hash: String, time: String)
+            => virtual_tree::seek_file_time(app, workspaceId, clientId, hash, time, state);
+    }
+
+    "search" => {
+        search_logs "Search logs"
+            (query: String, structuredQuery: Option<la_core::models::SearchQuery>, workspaceId: Option<String>, maxResults: Option<usize>, caseSensitive: Option<bool>, filters: Option<la_core::models::SearchFilters>, origin: Option<String>, clientId: Option<String>)
+            => search::search_logs(app, query, structuredQuery, workspaceId, maxResults, caseSensitive, filters, origin, clientId, window, state);
+        cancel_search "Cancel search" (searchId: String, clientId: Option<String>)
+            => search::cancel_search(searchId, clientId, state);
+        fetch_search_page "Fetch search results page"
+            (searchId: String, offset: usize, limit: usize, clientId: Option<String>)
+            => search::fetch_search_page(state, searchId, offset, limit, clientId);
+        get_search_snapshot "Show search snapshot" (searchId: String, clientId: Option<String>)
+            => search::get_search_snapshot(state, searchId, clientId);
+        translate_entries "Translate entries" (entries: Vec<la_core::models::LogEntry>)
+            => search::translate_entries(state, entries);
+        lint_search_query "Check search query"
+            (query: String, structuredQuery: Option<la_core::models::SearchQuery>, filters: Option<la_core::models::SearchFilters>, workspaceId: Option<String>, caseSensitive: Option<bool>, clientId: Option<String>)
+            => search::lint_search_query(app, query, structuredQuery, filters, workspaceId, caseSensitive, clientId, state);
+        close_search_session "Close search session" (searchId: String, clientId: Option<String>)
+            => search::close_search_session(state, searchId, clientId);
+        get_active_searches_count "Show active searches" () => search::get_active_searches_count(state);
+        save_search_results "Save search results"
+            (searchId: String, workspaceId: Option<String>, query: String, filters: Option<la_core::models::SearchFilters>, summary: Option<la_core::models::SearchResultSummary>, name: Option<String>, clientId: Option<String>)
+            => search::save_search_results(state, searchId, workspaceId, query, filters, summary, name, clientId);
+        list_saved_results "List saved search results"
+            (workspaceId: Option<String>, clientId: Option<String>)
+            => search::list_saved_results(state, workspaceId, clientId);
+        open_saved_results "Open saved search results"
+            (savedId: String, workspaceId: Option<String>, clientId: Option<String>)
+            => search::open_saved_results(state, savedId, workspaceId, clientId);
+    }
+
+    "import" => {
+        import_folder "Import folder or archive"
+            (path: String, workspace_id: String, selection: Option<Vec<la_archive::ArchiveSelection>>, filter: Option<la_archive::EntryFilter>, post_extract_hooks: Option<Vec<la_archive::PostExtractHook>>, password: Option<String>, client_id: Option<String>)
+            => import::import_folder(app, path, workspace_id, selection, filter, post_extract_hooks, password, client_id, state);
+        import_from_url "Import from URL"
+            (url: String, workspace_id: String, expectedSha256: Option<String>, client_id: Option<String>)
+            => import::import_from_url(app, url, workspace_id, expectedSha256, client_id, state);
+        preview_import "Preview import" (path: String) => import::preview_import(app, path);
+        quick_scan_archive "Quick scan archive"
+            (path: String, query: String, structuredQuery: Option<la_core::models::SearchQuery>, maxResults: Option<usize>, filters: Option<la_core::models::SearchFilters>)
+            => import::quick_scan_archive(app, path, query, structuredQuery, maxResults, filters, state);
+        promote_quick_scan "Import scanned archive"
+            (path: String, workspace_id: String, client_id: Option<String>)
+            => import::promote_quick_scan(app, path, workspace_id, client_id, state);
+        check_rar_support "Check RAR support" () => import::check_rar_support();
+    }
+
+    "export" => {
+        export_results "Export results"
+            (results: Vec<la_core::models::LogEntry>, format: String, savePath: String, options: Option<crate::services::export_destinations::ExportOptions>)
+            => export::export_results(app, results, format, savePath, options);
+        export_statistics "Export statistics"
+            (workspace_id: String, client_id: Option<String>, tables: Option<Vec<String>>, top_n: Option<usize>, format: String, savePath: String, options: Option<crate::services::export_destinations::ExportOptions>)
+            => export::export_statistics(app, state, workspace_id, client_id, tables, top_n, format, savePath, options);
+    }
+
+    "logging" => {
+        get_current_log_config "Show log settings" () => log_config::get_current_log_config();
+        set_log_level "Set log level" (level: String) => log_config::set_log_level(level);
+        set_module_level "Set module log level" (module: String, level: String)
+            => log_config::set_module_level(module, level);
+        reset_log_configuration "Reset log settings" () => log_config::reset_log_configuration();
+        get_recommended_production_config "Show production log preset" ()
+            => log_config::get_recommended_production_config();
+        get_recommended_debug_config "Show debug log preset" ()
+            => log_config::get_recommended_debug_config();
+        load_log_config "Load log settings from file" (path: String)
+            => log_config::load_log_config(app, path);
+        save_log_config "Save log settings to file"
+            (path: String, config: crate::utils::log_config::LogConfig)
+            => log_config::save_log_config(app, path, config);
+        get_available_log_levels "List log levels" () => log_config::get_available_log_levels();
+        apply_log_preset "Apply log preset" (preset: String) => log_config::apply_log_preset(preset);
+    }
+
+    "diagnostics" => {
+        get_error_groups "Show error groups" (limit: Option<u32>)
+            => diagnostics::get_error_groups(limit, state);
+        clear_error_groups "Clear error groups" () => diagnostics::clear_error_groups(state);
+        get_backend_logs "Show backend logs"
+            (filter: Option<crate::monitoring::BackendLogFilter>)
+            => diagnostics::get_backend_logs(filter);
+        export_backend_logs "Export backend logs"
+            (filter: Option<crate::monitoring::BackendLogFilter>, format: Option<String>, savePath: String)
+            => diagnostics::export_backend_logs(app, filter, format, savePath);
+        list_crash_reports "List crash reports" () => diagnostics::list_crash_reports(app);
+        delete_crash_reports "Delete crash reports" (ids: Vec<String>)
+            => diagnostics::delete_crash_reports(app, ids);
+        run_startup_check "Run startup check" () => diagnostics::run_startup_check(app, state);
+        cleanup_orphaned_temp_dirs "Clean up temporary directories" ()
+            => diagnostics::cleanup_orphaned_temp_dirs(app, state);
+        get_memory_breakdown "Show memory breakdown" () => diagnostics::get_memory_breakdown(state);
+        get_telemetry_report "Show usage statistics" () => diagnostics::get_telemetry_report();
+        set_telemetry_enabled "Enable or disable usage statistics" (enabled: bool)
+            => diagnostics::set_telemetry_enabled(app, enabled);
+        export_telemetry "Export usage statistics" (savePath: String)
+            => diagnostics::export_telemetry(app, savePath);
+        clear_telemetry "Clear usage statistics" () => diagnostics::clear_telemetry();
+        run_diagnostics_benchmark "Run performance benchmark" ()
+            => diagnostics::run_diagnostics_benchmark(app);
+    }
+}
+
+/// 列出可调用的操作
+#[tauri::command]
+pub async fn list_actions() -> Result<Vec<ActionDescriptor>, CommandError> {
+    Ok(action_descriptors())
+}
+
+/// 影响整个后端的操作分类；服务器模式下只有操作员会话可以调用
+const OPERATOR_CATEGORIES: &[&str] = &["config", "logging", "diagnostics"];
+
+/// 按名称分发操作（`invoke_action` 与宏回放共用）
+pub(crate) async fn dispatch_action(
+    action: &str,
+    mut args: Option<Value>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<Value, CommandError> {
+    let operator_only = action_descriptors()
+        .iter()
+        .any(|d| d.name == action && OPERATOR_CATEGORIES.contains(&d.category));
+    if operator_only {
+        // 这些命令本身不带会话参数：在此取出 `clientId` 校验调用方
+        let client_id = match args.as_mut() {
+            Some(Value::Object(values)) => values.remove("clientId"),
+            _ => None,
+        };
+        require_unscoped_caller(&state, client_id.as_ref().and_then(Value::as_str))?;
+    }
+    let args = ActionArgs::new(action, args)?;
+    dispatch(action, args, app, state, window).await
+}
+
+/// 按名称调用操作，`args` 为与直接调用命令时相同的参数对象，返回命令结果的 JSON
+///
+/// 正在录制分析宏时，成功的调用被记为宏的一步。
+#[tauri::command]
+pub async fn invoke_action(
+    action: String,
+    args: Option<Value>,
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<Value, CommandError> {
+    let recorded_args = analysis_macro::is_recording().then(|| args.clone());
+    let result = dispatch_action(&action, args, app, state, window).await?;
+    if let Some(args) = recorded_args {
+        analysis_macro::record_action(&action, args);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn arg_keys_match_invoke_naming() {
+        assert_eq!(param_key("workspace_id"), "workspaceId");
+        assert_eq!(param_key("workspaceId"), "workspaceId");
+        assert_eq!(param_key("_autoSearch"), "autoSearch");
+        assert_eq!(param_key("sql"), "sql");
+    }
+
+    #[test]
+    fn arg_types_are_described() {
+        let arg = ActionArg::new("max_rows", "Option < usize >");
+        assert_eq!(
+            arg,
+            ActionArg {
+                name: "maxRows".to_string(),
+                value_type: "integer",
+                type_name: "usize".to_string(),
+                required: false,
+            }
+        );
+
+        let arg = ActionArg::new("selection", "Option<Vec<la_archive::ArchiveSelection>>");
+        assert_eq!(arg.value_type, "array");
+        assert_eq!(arg.type_name, "Vec<ArchiveSelection>");
+
+        let arg = ActionArg::new("format", "Option<la_core::utils::LogFormat>");
+        assert_eq!(arg.value_type, "string");
+        assert!(ActionArg::new("query", "la_core::models::SearchQuery").required);
+    }
+
+    #[test]
+    fn args_are_taken_and_checked() {
+        let mut args = ActionArgs::new(
+            "load_workspace",
+            Some(serde_json::json!({ "workspaceId": "ws-1", "limit": 5 })),
+        )
+        .unwrap();
+        let workspace_id: String = args.take("workspace_id").unwrap();
+        assert_eq!(workspace_id, "ws-1");
+        let missing: Option<String> = args.take("client_id").unwrap();
+        assert!(missing.is_none());
+        let err = args.take::<String>("path").unwrap_err();
+        assert!(err.message.contains("Missing argument 'path'"));
+        assert!(args.finish().unwrap_err().message.contains("limit"));
+
+        assert!(ActionArgs::new("load_workspace", Some(serde_json::json!([1]))).is_err());
+    }
+
+    #[test]
+    fn registry_covers_every_registered_command() {
+        let descriptors = action_descriptors();
+        let mut names = HashSet::new();
+        for descriptor in &descriptors {
+            assert!(
+                names.insert(descriptor.name),
+                "duplicate {}",
+                descriptor.name
+            );
+            assert!(!descriptor.title.is_empty());
+        }
+
+        let main = include_str!("../main.rs");
+        let handlers = main
+            .split("generate_handler![")
+            .nth(1)
+            .and_then(|rest| rest.split("])").next())
+            .unwrap();
+        for line in handlers.lines() {
+            let command = line.trim().trim_end_matches(',');
+            if command.is_empty() || command.starts_with("//") {
+                continue;
+            }
+            assert!(
+                names.contains(command) || NON_ACTION_COMMANDS.contains(&command),
+                "command {command} is neither an action nor listed in NON_ACTION_COMMANDS"
+            );
+        }
+    }
+}
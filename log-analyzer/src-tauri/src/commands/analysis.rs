@@ -0,0 +1,697 @@
+//! 分析命令
+//!
+//! 基于索引时间戳的跨文件分析（不读取文件内容），以及基于搜索结果集的统计。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use la_core::error::CommandError;
+use la_core::models::{LogEntry, SearchFilters};
+use la_storage::MetadataQueryResult;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State, Window};
+
+use crate::application::rows_csv;
+use crate::application::search_session::SearchSessionManager;
+use crate::commands::analysis_macro::wait_for_search;
+use crate::commands::workspace::format_index_timestamps;
+use crate::models::AppState;
+use crate::services::cooccurrence::{self, CooccurrenceCounter, CooccurrenceMatrix};
+use crate::services::search_diff::{self, SearchDiff};
+use crate::services::silence_detection::{self, Silence};
+
+/// 单次最多返回的静默时段数（按时长降序截取）
+const MAX_SILENCES: usize = 200;
+/// 静默前后附带的条目数上限
+const MAX_CONTEXT_ENTRIES: usize = 20;
+const DEFAULT_CONTEXT_ENTRIES: usize = 3;
+/// 读取静默边界所在那一秒的条目上限
+const BOUNDARY_SCAN_LIMIT: usize = 1000;
+/// 首次/末次出现前后查找上下文的时间范围（±秒）
+const OCCURRENCE_CONTEXT_WINDOW_SECS: i64 = 300;
+/// 读取上下文时间范围内的条目上限
+const OCCURRENCE_CONTEXT_SCAN_LIMIT: usize = 5000;
+/// 共现统计最多读取的结果条目数
+const MAX_COOCCURRENCE_ENTRIES: usize = 500_000;
+/// 共现统计按页读取结果会话
+const COOCCURRENCE_PAGE_SIZE: usize = 10_000;
+/// 共现时间窗口上限（秒）
+const MAX_COOCCURRENCE_WINDOW_SECS: i64 = 24 * 3600;
+/// 搜索结果对比每侧最多读取的条目数（与单次搜索的结果上限一致）
+const MAX_DIFF_ENTRIES: usize = crate::commands::search::MAX_SEARCH_RESULTS;
+/// 搜索结果对比按页读取结果会话
+const DIFF_PAGE_SIZE: usize = 10_000;
+/// 元数据查询默认/最大返回行数
+const DEFAULT_QUERY_ROWS: usize = 1000;
+const MAX_QUERY_ROWS: usize = 10_000;
+/// 元数据查询默认/最长执行时间（毫秒）
+const DEFAULT_QUERY_TIMEOUT_MS: u64 = 5000;
+const MAX_QUERY_TIMEOUT_MS: u64 = 30_000;
+
+/// 静默时段及其前后的日志
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceReport {
+    #[serde(flatten)]
+    pub silence: Silence,
+    /// 静默开始前的最后几条日志
+    pub before: Vec<LogEntry>,
+    /// 静默结束后的最初几条日志
+    pub after: Vec<LogEntry>,
+}
+
+/// 查找时间范围内各文件的可疑静默（超过自身典型输出间隔的空白时段）
+///
+/// - `files` 为虚拟路径，为空时覆盖所有参与搜索的文件
+/// - `start` / `end` 接受与 `get_entries_around_time` 相同的时间格式，缺省为索引的整体范围
+/// - `factor`：间隔超过典型间隔的多少倍视为静默（默认 10）
+/// - `min_gap_secs`：短于该秒数的间隔不报告（默认 60）
+///
+/// 结果按静默时长降序排列，时间均已计入文件时钟偏移。
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn find_silences(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    files: Vec<String>,
+    start: Option<String>,
+    end: Option<String>,
+    factor: Option<f64>,
+    min_gap_secs: Option<i64>,
+    context: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SilenceReport>, CommandError> {
+    let parse_bound = |value: Option<String>| -> Result<Option<i64>, CommandError> {
+        value
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| {
+                la_search::parse_log_timestamp_to_unix(&v).ok_or_else(|| {
+                    CommandError::new("VALIDATION_ERROR", format!("Unrecognized timestamp: {v}"))
+                })
+            })
+            .transpose()
+    };
+    let start = parse_bound(start)?;
+    let end = parse_bound(end)?;
+    let factor = factor.unwrap_or(silence_detection::DEFAULT_CADENCE_FACTOR);
+    if !factor.is_finite() || factor < 1.0 {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            "factor must be a number >= 1",
+        ));
+    }
+    let min_gap_secs = min_gap_secs
+        .unwrap_or(silence_detection::DEFAULT_MIN_GAP_SECS)
+        .max(0);
+    let context = context
+        .unwrap_or(DEFAULT_CONTEXT_ENTRIES)
+        .min(MAX_CONTEXT_ENTRIES);
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata_store = service.metadata_store();
+
+    let offsets = metadata_store
+        .get_file_time_offsets()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    let files = if files.is_empty() {
+        metadata_store
+            .get_files_with_pruning(start, end, None, None)
+            .await
+            .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+            .into_iter()
+            .map(|f| f.virtual_path)
+            .collect()
+    } else {
+        files
+    };
+
+    let manager: Arc<la_search::SearchEngineManager> = Arc::clone(service.search_engine());
+    let links = state.search.links().resolver();
+    let mut reports = tokio::task::spawn_blocking(move || {
+        let (index_start, index_end, _) = manager.get_time_range()?;
+        // 无时间戳的行以 0 入索引，缺省起点取最早的真实时间戳
+        let start = match start {
+            Some(start) => start,
+            None => manager.get_earliest_timestamp()?.unwrap_or(index_start),
+        };
+        let end = end.unwrap_or(index_end);
+
+        let mut silences = Vec::new();
+        for file in &files {
+            let offset = offsets.get(file).copied().unwrap_or(0);
+            let timestamps: Vec<i64> = manager
+                .get_file_timestamps(file, start - offset, end - offset)?
+                .into_iter()
+                .map(|ts| ts + offset)
+                .collect();
+            silences.extend(silence_detection::find_silences(
+                file,
+                &timestamps,
+                factor,
+                min_gap_secs,
+            ));
+        }
+        silences.sort_by(|a, b| {
+            b.duration_secs
+                .cmp(&a.duration_secs)
+                .then_with(|| a.virtual_path.cmp(&b.virtual_path))
+        });
+        silences.truncate(MAX_SILENCES);
+
+        silences
+            .into_iter()
+            .map(|silence| silence_report(&manager, &offsets, silence, context))
+            .collect::<Result<Vec<_>, _>>()
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Silence detection failed: {e}")))?
+    .map_err(|e: la_search::SearchError| {
+        CommandError::new("SEARCH_ERROR", format!("Failed to read index: {e}"))
+    })?;
+
+    for report in &mut reports {
+        links.annotate(&mut report.before);
+        links.annotate(&mut report.after);
+    }
+    Ok(reports)
+}
+
+/// 取静默两端那一秒内的条目：开始端保留最后 `context` 条，结束端保留最前 `context` 条
+fn silence_report(
+    manager: &la_search::SearchEngineManager,
+    offsets: &HashMap<String, i64>,
+    silence: Silence,
+    context: usize,
+) -> Result<SilenceReport, la_search::SearchError> {
+    if context == 0 {
+        return Ok(SilenceReport {
+            silence,
+            before: Vec::new(),
+            after: Vec::new(),
+        });
+    }
+
+    let file = [silence.virtual_path.clone()];
+    let mut before = manager.get_entries_in_time_window(
+        &file,
+        offsets,
+        silence.start,
+        silence.start,
+        BOUNDARY_SCAN_LIMIT,
+    )?;
+    before.drain(..before.len().saturating_sub(context));
+    let mut after =
+        manager.get_entries_in_time_window(&file, offsets, silence.end, silence.end, context)?;
+    format_index_timestamps(&mut before);
+    format_index_timestamps(&mut after);
+
+    Ok(SilenceReport {
+        silence,
+        before,
+        after,
+    })
+}
+
+/// 某条命中日志及其在同一文件中的前后几行
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OccurrenceReport {
+    pub entry: LogEntry,
+    pub before: Vec<LogEntry>,
+    pub after: Vec<LogEntry>,
+}
+
+/// 查询最早与最晚的命中；没有命中时均为 None
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OccurrenceRange {
+    pub first: Option<OccurrenceReport>,
+    pub last: Option<OccurrenceReport>,
+}
+
+/// 查找查询最早（及最晚）出现的日志，附带同一文件中的前后 `context` 行
+///
+/// 在索引的时间轴上二分查找，无需对整次搜索结果排序即可回答“这个错误最早什么时候出现”。
+/// `query` 使用与索引搜索相同的语法；时间已计入文件时钟偏移。
+#[tauri::command]
+pub async fn find_first_occurrence(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    query: String,
+    context: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<OccurrenceRange, CommandError> {
+    if query.trim().is_empty() {
+        return Err(CommandError::new("VALIDATION_ERROR", "Query is empty"));
+    }
+    let context = context
+        .unwrap_or(DEFAULT_CONTEXT_ENTRIES)
+        .min(MAX_CONTEXT_ENTRIES);
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let offsets = service
+        .metadata_store()
+        .get_file_time_offsets()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+
+    let manager: Arc<la_search::SearchEngineManager> = Arc::clone(service.search_engine());
+    let links = state.search.links().resolver();
+    let mut range = tokio::task::spawn_blocking(move || {
+        let report = |edge| {
+            manager
+                .find_occurrence(&query, &offsets, edge)?
+                .map(|entry| occurrence_report(&manager, &offsets, entry, context))
+                .transpose()
+        };
+        Ok::<_, la_search::SearchError>(OccurrenceRange {
+            first: report(la_search::OccurrenceEdge::First)?,
+            last: report(la_search::OccurrenceEdge::Last)?,
+        })
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Occurrence lookup failed: {e}")))?
+    .map_err(|e| CommandError::new("SEARCH_ERROR", format!("Failed to search index: {e}")))?;
+
+    for report in [&mut range.first, &mut range.last].into_iter().flatten() {
+        links.annotate(std::slice::from_mut(&mut report.entry));
+        links.annotate(&mut report.before);
+        links.annotate(&mut report.after);
+    }
+    Ok(range)
+}
+
+/// 在同一文件中查找 `line` 之后（`next`）或之前（`previous`）最近的命中行，供文件查看器
+/// 逐个跳转（F3 / Shift+F3），无需重新传输整次搜索结果
+///
+/// `query` 使用与索引搜索相同的语法；没有更多命中时返回 None。
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn get_adjacent_match(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    file_hash: String,
+    line: usize,
+    direction: la_search::MatchDirection,
+    query: String,
+    state: State<'_, AppState>,
+) -> Result<Option<LogEntry>, CommandError> {
+    if query.trim().is_empty() {
+        return Err(CommandError::new("VALIDATION_ERROR", "Query is empty"));
+    }
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata = service.metadata_store();
+    let file = metadata
+        .get_file_by_hash(&file_hash)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .ok_or_else(|| {
+            CommandError::new("NOT_FOUND", format!("File {file_hash} not found"))
+                .with_help("The workspace may have been refreshed; reopen the file")
+        })?;
+    let offset = metadata
+        .get_file_time_offsets()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .get(&file.virtual_path)
+        .copied()
+        .filter(|offset| *offset != 0);
+
+    let manager: Arc<la_search::SearchEngineManager> = Arc::clone(service.search_engine());
+    let found = tokio::task::spawn_blocking(move || {
+        manager.find_adjacent_match(&query, &file.virtual_path, line, direction)
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Match lookup failed: {e}")))?
+    .map_err(|e| CommandError::new("SEARCH_ERROR", format!("Failed to search index: {e}")))?;
+
+    Ok(found.map(|mut entry| {
+        entry.time_offset_secs = offset;
+        let entries = std::slice::from_mut(&mut entry);
+        format_index_timestamps(entries);
+        state.search.links().annotate(entries);
+        entry
+    }))
+}
+
+/// 在命中所在文件中取前后各 `context` 行（按行号，限定在命中时间前后一段范围内）
+fn occurrence_report(
+    manager: &la_search::SearchEngineManager,
+    offsets: &HashMap<String, i64>,
+    mut entry: LogEntry,
+    context: usize,
+) -> Result<OccurrenceReport, la_search::SearchError> {
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    let at = entry.timestamp.parse::<i64>().ok().filter(|_| context > 0);
+    if let Some(at) = at {
+        let at = at + entry.time_offset_secs.unwrap_or(0);
+        let file = [entry.file.to_string()];
+        let mut nearby = manager.get_entries_in_time_window(
+            &file,
+            offsets,
+            at - OCCURRENCE_CONTEXT_WINDOW_SECS,
+            at + OCCURRENCE_CONTEXT_WINDOW_SECS,
+            OCCURRENCE_CONTEXT_SCAN_LIMIT,
+        )?;
+        nearby.sort_by_key(|e| e.line);
+        before = nearby
+            .iter()
+            .filter(|e| e.line < entry.line)
+            .cloned()
+            .collect();
+        before.drain(..before.len().saturating_sub(context));
+        after = nearby
+            .into_iter()
+            .filter(|e| e.line > entry.line)
+            .take(context)
+            .collect();
+    }
+
+    format_index_timestamps(std::slice::from_mut(&mut entry));
+    format_index_timestamps(&mut before);
+    format_index_timestamps(&mut after);
+    Ok(OccurrenceReport {
+        entry,
+        before,
+        after,
+    })
+}
+
+/// 统计一次搜索结果中查询关键词两两共现的次数
+///
+/// - `terms` 为矩阵的行列（通常是查询中启用的关键词），最多 32 个
+/// - `window_secs` 给出时额外统计时间窗口内的共现（命中 A 的行前后若干秒内出现 B）
+///
+/// 结果集超过 50 万条时只统计前 50 万条，并置 `truncated`。
+#[tauri::command]
+pub async fn get_keyword_cooccurrence(
+    search_id: String,
+    terms: Vec<String>,
+    window_secs: Option<i64>,
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CooccurrenceMatrix, CommandError> {
+    crate::commands::search::ensure_search_visible(&state, &search_id, client_id.as_deref())?;
+    if terms.is_empty() {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            "At least one term is required",
+        ));
+    }
+    if terms.len() > cooccurrence::MAX_TERMS {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            format!(
+                "Too many terms ({}, max {})",
+                terms.len(),
+                cooccurrence::MAX_TERMS
+            ),
+        ));
+    }
+    if let Some(window) = window_secs {
+        if !(0..=MAX_COOCCURRENCE_WINDOW_SECS).contains(&window) {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                format!("window_secs must be between 0 and {MAX_COOCCURRENCE_WINDOW_SECS}"),
+            ));
+        }
+    }
+
+    let manager = state.get_search_session_manager().ok_or_else(|| {
+        CommandError::new("NOT_FOUND", "Search session manager not initialized")
+            .with_help("Import a workspace first")
+    })?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut counter = CooccurrenceCounter::new(terms, window_secs);
+        let mut offset = 0;
+        let mut truncated = false;
+        loop {
+            let limit = COOCCURRENCE_PAGE_SIZE.min(MAX_COOCCURRENCE_ENTRIES - offset);
+            let page = manager.fetch_search_page(&search_id, offset, limit)?;
+            for entry in &page.entries {
+                counter.record(entry);
+            }
+            offset += page.entries.len();
+            if !page.has_more || page.entries.is_empty() {
+                break;
+            }
+            if offset >= MAX_COOCCURRENCE_ENTRIES {
+                truncated = true;
+                break;
+            }
+        }
+        let mut matrix = counter.finish();
+        matrix.truncated = truncated;
+        Ok::<_, la_core::error::AppError>(matrix)
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Co-occurrence analysis failed: {e}")))?
+    .map_err(CommandError::from)
+}
+
+/// 对比的一侧：已有的搜索会话，或现场执行的一次查询
+///
+/// 同一查询配不同的 `filters.timeRange` 即可对比两个时间段；搜索会话固定了创建时的数据快照，
+/// 重新导入前后各搜索一次即可对比两个快照。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DiffSide {
+    pub search_id: Option<String>,
+    pub query: Option<String>,
+    pub filters: Option<SearchFilters>,
+    pub case_sensitive: Option<bool>,
+}
+
+/// 对比两次搜索的结果：各自独有的条目与按模式的数量变化
+///
+/// - `left` / `right` 各自给出 `searchId` 或 `query`（二者选一）；查询在 `workspace_id`
+///   上执行，结束后关闭其搜索会话，已有的会话保持不变
+/// - `max_entries` 为每侧列出的独有条目数（默认 200，上限 5000）
+///
+/// 每侧最多读取 10 万条结果，超出时只对比前一部分并置 `truncated`。
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn diff_search_results(
+    app: AppHandle,
+    window: Window,
+    workspace_id: Option<String>,
+    left: DiffSide,
+    right: DiffSide,
+    max_entries: Option<usize>,
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SearchDiff, CommandError> {
+    let max_listed = max_entries.unwrap_or(search_diff::DEFAULT_LISTED_ENTRIES);
+    if !(1..=search_diff::MAX_LISTED_ENTRIES).contains(&max_listed) {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            format!(
+                "max_entries must be between 1 and {}",
+                search_diff::MAX_LISTED_ENTRIES
+            ),
+        ));
+    }
+    for (name, side) in [("left", &left), ("right", &right)] {
+        if side.search_id.is_some() == side.query.is_some() {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                format!("The {name} side needs exactly one of searchId or query"),
+            )
+            .with_help("Pass an existing search session id, or a query to run"));
+        }
+    }
+
+    let mut truncated = false;
+    let mut sides = Vec::with_capacity(2);
+    for side in [left, right] {
+        let (entries, side_truncated) = read_diff_side(
+            side,
+            &app,
+            &window,
+            workspace_id.as_deref(),
+            client_id.as_deref(),
+            &state,
+        )
+        .await?;
+        truncated |= side_truncated;
+        sides.push(entries);
+    }
+    let right = sides.pop().unwrap_or_default();
+    let left = sides.pop().unwrap_or_default();
+
+    let mut diff =
+        tokio::task::spawn_blocking(move || search_diff::diff_entries(&left, &right, max_listed))
+            .await
+            .map_err(|e| CommandError::new("TASK_ERROR", format!("Search diff failed: {e}")))?;
+    diff.truncated = truncated;
+    state.search.links().annotate(&mut diff.only_left);
+    state.search.links().annotate(&mut diff.only_right);
+    Ok(diff)
+}
+
+/// 读取一侧的结果（最多 [`MAX_DIFF_ENTRIES`] 条），返回是否被截断
+async fn read_diff_side(
+    side: DiffSide,
+    app: &AppHandle,
+    window: &Window,
+    workspace_id: Option<&str>,
+    client_id: Option<&str>,
+    state: &State<'_, AppState>,
+) -> Result<(Vec<LogEntry>, bool), CommandError> {
+    let manager = || {
+        state.get_search_session_manager().ok_or_else(|| {
+            CommandError::new("NOT_FOUND", "Search session manager not initialized")
+                .with_help("Import a workspace first")
+        })
+    };
+
+    if let Some(search_id) = side.search_id {
+        crate::commands::search::ensure_search_visible(state, &search_id, client_id)?;
+        let manager = manager()?;
+        return tokio::task::spawn_blocking(move || read_session_entries(&manager, &search_id))
+            .await
+            .map_err(|e| CommandError::new("TASK_ERROR", format!("Search diff failed: {e}")))?
+            .map_err(CommandError::from);
+    }
+
+    let search_id = crate::commands::search::search_logs(
+        app.clone(),
+        side.query.unwrap_or_default(),
+        None,
+        workspace_id.map(str::to_string),
+        Some(MAX_DIFF_ENTRIES),
+        side.case_sensitive,
+        side.filters,
+        None,
+        client_id.map(str::to_string),
+        window.clone(),
+        state.clone(),
+    )
+    .await?;
+    let result = async {
+        wait_for_search(state, &search_id).await?;
+        let manager = manager()?;
+        let id = search_id.clone();
+        tokio::task::spawn_blocking(move || read_session_entries(&manager, &id))
+            .await
+            .map_err(|e| CommandError::new("TASK_ERROR", format!("Search diff failed: {e}")))?
+            .map_err(CommandError::from)
+    }
+    .await;
+    if let Ok(manager) = manager() {
+        state.sync.clients().forget_search(&search_id);
+        manager.close_session(&search_id);
+    }
+    result
+}
+
+/// 按页读取搜索会话的全部结果（最多 [`MAX_DIFF_ENTRIES`] 条），返回是否被截断
+pub(crate) fn read_session_entries(
+    manager: &SearchSessionManager,
+    search_id: &str,
+) -> Result<(Vec<LogEntry>, bool), la_core::error::AppError> {
+    let mut entries = Vec::new();
+    loop {
+        let limit = DIFF_PAGE_SIZE.min(MAX_DIFF_ENTRIES - entries.len());
+        let page = manager.fetch_search_page(search_id, entries.len(), limit)?;
+        let done = !page.has_more || page.entries.is_empty();
+        entries.extend(page.entries);
+        if done {
+            return Ok((entries, false));
+        }
+        if entries.len() >= MAX_DIFF_ENTRIES {
+            return Ok((entries, true));
+        }
+    }
+}
+
+/// 元数据查询结果；`format` 为 `csv` 时额外附带 CSV 文本
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataQueryResponse {
+    #[serde(flatten)]
+    pub result: MetadataQueryResult,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv: Option<String>,
+}
+
+/// 在工作区 `metadata.db` 上执行只读 SQL（查询控制台）
+///
+/// 使用独立的只读连接，授权回调拒绝一切写入、PRAGMA 与 ATTACH；
+/// 最多返回 `max_rows` 行（默认 1000，上限 10000，超出置 `truncated`），
+/// 超过 `timeout_ms`（默认 5 秒，上限 30 秒）的查询会被中断。
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn run_metadata_query(
+    app: AppHandle,
+    workspace_id: String,
+    sql: String,
+    max_rows: Option<usize>,
+    timeout_ms: Option<u64>,
+    format: Option<String>,
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<MetadataQueryResponse, CommandError> {
+    if sql.trim().is_empty() {
+        return Err(CommandError::new("VALIDATION_ERROR", "Query is empty"));
+    }
+    let as_csv = match format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        other => {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                format!("Unsupported format: {other}"),
+            )
+            .with_help("Use json or csv"))
+        }
+    };
+    let max_rows = max_rows
+        .unwrap_or(DEFAULT_QUERY_ROWS)
+        .clamp(1, MAX_QUERY_ROWS);
+    let timeout = std::time::Duration::from_millis(
+        timeout_ms
+            .unwrap_or(DEFAULT_QUERY_TIMEOUT_MS)
+            .clamp(1, MAX_QUERY_TIMEOUT_MS),
+    );
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let result = service
+        .metadata_store()
+        .run_read_only_query(&sql, max_rows, timeout)
+        .await
+        .map_err(CommandError::from)?;
+    let csv = as_csv.then(|| rows_csv(&result.columns, &result.rows));
+    Ok(MetadataQueryResponse { result, csv })
+}
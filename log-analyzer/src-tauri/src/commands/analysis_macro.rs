@@ -0,0 +1,317 @@
+//! 分析宏命令
+//!
+//! 录制经 `invoke_action` 执行的操作、管理已保存的宏，并对其他工作区回放
+//! （见 `services::analysis_macros`）。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use la_core::error::CommandError;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State, Window};
+use tracing::{info, warn};
+
+use crate::commands::actions::{action_descriptors, dispatch_action};
+use crate::models::AppState;
+use crate::services::analysis_macros::{
+    load_macros, prepare_step_args, save_macros, upsert_macro, AnalysisMacro, MacroRecording,
+    MacroReport, ReplayContext, MACROS_FILE, RECORDING,
+};
+
+/// 回放中等待一次搜索完成的最长时间
+const SEARCH_WAIT_TIMEOUT: Duration = Duration::from_secs(600);
+const SEARCH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 回放时为 `export_results` 读取的搜索结果上限
+const MAX_EXPORT_ENTRIES: usize = 100_000;
+const EXPORT_PAGE_SIZE: usize = 10_000;
+
+/// 串行化宏文件的读改写
+static MACROS_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn macros_path(app: &AppHandle) -> Result<PathBuf, CommandError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(MACROS_FILE))
+        .map_err(|e| {
+            CommandError::new(
+                "CONFIG_ERROR",
+                format!("Failed to resolve data directory: {e}"),
+            )
+        })
+}
+
+async fn read_macros(app: &AppHandle) -> Result<Vec<AnalysisMacro>, CommandError> {
+    let path = macros_path(app)?;
+    tokio::task::spawn_blocking(move || load_macros(&path))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Task panicked: {e}")))?
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))
+}
+
+/// 校验后保存宏；同 id 的宏被替换
+async fn store_macro(
+    app: &AppHandle,
+    analysis_macro: AnalysisMacro,
+) -> Result<AnalysisMacro, CommandError> {
+    let names = action_names();
+    analysis_macro
+        .check(|action| names.contains_key(action))
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+
+    let _guard = MACROS_LOCK.lock().await;
+    let mut macros = read_macros(app).await?;
+    upsert_macro(&mut macros, analysis_macro.clone())
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let path = macros_path(app)?;
+    tokio::task::spawn_blocking(move || save_macros(&path, &macros))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Task panicked: {e}")))?
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))?;
+    Ok(analysis_macro)
+}
+
+/// 操作名 → 接受的参数键
+fn action_names() -> HashMap<&'static str, Vec<String>> {
+    action_descriptors()
+        .into_iter()
+        .map(|d| (d.name, d.args.into_iter().map(|a| a.name).collect()))
+        .collect()
+}
+
+pub(crate) fn is_recording() -> bool {
+    RECORDING.lock().is_some()
+}
+
+/// 把一次成功的 `invoke_action` 调用记入当前录制
+pub(crate) fn record_action(action: &str, args: Option<Value>) {
+    let mut recording = RECORDING.lock();
+    let Some(recording) = recording.as_mut() else {
+        return;
+    };
+    let args = match args {
+        Some(Value::Object(args)) => args,
+        _ => Default::default(),
+    };
+    if let Err(e) = recording.record(action, args) {
+        warn!(macro_id = %recording.id, action, error = %e, "Macro step not recorded");
+    }
+}
+
+/// 开始录制分析宏；同一时间只能有一个录制
+#[tauri::command]
+pub async fn start_macro_recording(
+    id: String,
+    name: String,
+    description: Option<String>,
+) -> Result<MacroRecording, CommandError> {
+    let mut current = RECORDING.lock();
+    if let Some(active) = current.as_ref() {
+        return Err(CommandError::new(
+            "MACRO_RECORDING_ACTIVE",
+            format!("Macro '{}' is already being recorded", active.id),
+        )
+        .with_help("Stop or discard the current recording first"));
+    }
+    let recording = MacroRecording {
+        id,
+        name,
+        description: description.unwrap_or_default(),
+        steps: Vec::new(),
+    };
+    *current = Some(recording.clone());
+    info!(macro_id = %recording.id, "Macro recording started");
+    Ok(recording)
+}
+
+/// 当前录制（未录制时为 `None`）
+#[tauri::command]
+pub async fn get_macro_recording() -> Result<Option<MacroRecording>, CommandError> {
+    Ok(RECORDING.lock().clone())
+}
+
+/// 结束录制并保存宏
+#[tauri::command]
+pub async fn stop_macro_recording(app: AppHandle) -> Result<AnalysisMacro, CommandError> {
+    let recording = RECORDING.lock().take().ok_or_else(|| {
+        CommandError::new("NOT_FOUND", "No macro is being recorded")
+            .with_help("Start a recording with start_macro_recording")
+    })?;
+    let saved = store_macro(&app, recording.finish()).await?;
+    info!(macro_id = %saved.id, steps = saved.steps.len(), "Macro recorded");
+    Ok(saved)
+}
+
+/// 放弃当前录制；返回是否有录制
+#[tauri::command]
+pub async fn discard_macro_recording() -> Result<bool, CommandError> {
+    Ok(RECORDING.lock().take().is_some())
+}
+
+/// 列出已保存的分析宏
+#[tauri::command]
+pub async fn list_macros(app: AppHandle) -> Result<Vec<AnalysisMacro>, CommandError> {
+    read_macros(&app).await
+}
+
+/// 保存（或编辑后覆盖）分析宏
+#[tauri::command]
+pub async fn save_macro(
+    analysis_macro: AnalysisMacro,
+    app: AppHandle,
+) -> Result<AnalysisMacro, CommandError> {
+    store_macro(&app, analysis_macro).await
+}
+
+/// 删除分析宏；返回是否存在
+#[tauri::command]
+pub async fn delete_macro(id: String, app: AppHandle) -> Result<bool, CommandError> {
+    let _guard = MACROS_LOCK.lock().await;
+    let mut macros = read_macros(&app).await?;
+    let before = macros.len();
+    macros.retain(|m| m.id != id);
+    if macros.len() == before {
+        return Ok(false);
+    }
+    let path = macros_path(&app)?;
+    tokio::task::spawn_blocking(move || save_macros(&path, &macros))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Task panicked: {e}")))?
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))?;
+    Ok(true)
+}
+
+/// 对指定工作区回放分析宏，返回汇总报告
+///
+/// 每一步经 `invoke_action` 的分发执行，工作区与客户端按回放上下文替换；
+/// 搜索步骤等待搜索结束后记录命中数。`stop_on_error` 为 `true` 时首个失败后停止。
+#[tauri::command]
+pub async fn replay_macro(
+    macro_id: String,
+    workspace_id: String,
+    client_id: Option<String>,
+    stop_on_error: Option<bool>,
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<MacroReport, CommandError> {
+    let analysis_macro = read_macros(&app)
+        .await?
+        .into_iter()
+        .find(|m| m.id == macro_id)
+        .ok_or_else(|| CommandError::new("NOT_FOUND", format!("Macro not found: {macro_id}")))?;
+    let actions = action_names();
+    analysis_macro
+        .check(|action| actions.contains_key(action))
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let stop_on_error = stop_on_error.unwrap_or(false);
+    let started = Instant::now();
+    let mut report = MacroReport::new(&analysis_macro, &workspace_id);
+    let mut ctx = ReplayContext {
+        workspace_id: workspace_id.clone(),
+        client_id,
+        last_search_id: None,
+    };
+
+    for (index, step) in analysis_macro.steps.iter().enumerate() {
+        if stop_on_error && report.failed > 0 {
+            report.skipped = analysis_macro.steps.len() - index;
+            break;
+        }
+        let step_started = Instant::now();
+        let mut args = prepare_step_args(step, &actions[step.action.as_str()], &ctx);
+        if step.action == "export_results" {
+            if let Some(search_id) = &ctx.last_search_id {
+                if args
+                    .get("results")
+                    .and_then(Value::as_array)
+                    .is_none_or(Vec::is_empty)
+                {
+                    match collect_search_entries(&state, search_id) {
+                        Ok(entries) => {
+                            args.insert("results".to_string(), entries);
+                        }
+                        Err(e) => {
+                            report.push(&step.action, step_started, Err(e.message));
+                            continue;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut outcome = dispatch_action(
+            &step.action,
+            Some(Value::Object(args)),
+            app.clone(),
+            state.clone(),
+            window.clone(),
+        )
+        .await;
+        if step.action == "search_logs" {
+            if let Ok(Value::String(search_id)) = &outcome {
+                ctx.last_search_id = Some(search_id.clone());
+                outcome = wait_for_search(&state, search_id).await;
+            }
+        }
+        report.push(&step.action, step_started, outcome.map_err(|e| e.message));
+    }
+
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    info!(
+        macro_id = %macro_id,
+        workspace_id = %workspace_id,
+        succeeded = report.succeeded,
+        failed = report.failed,
+        "Macro replayed"
+    );
+    Ok(report)
+}
+
+/// 等待搜索结束，返回搜索 ID 与命中数
+pub(crate) async fn wait_for_search(
+    state: &AppState,
+    search_id: &str,
+) -> Result<Value, CommandError> {
+    let manager = state
+        .get_search_session_manager()
+        .ok_or_else(|| CommandError::new("NOT_FOUND", "Search session manager not initialized"))?;
+    let deadline = Instant::now() + SEARCH_WAIT_TIMEOUT;
+    while manager.is_running(search_id) && Instant::now() < deadline {
+        tokio::time::sleep(SEARCH_POLL_INTERVAL).await;
+    }
+    let page = manager.fetch_search_page(search_id, 0, 0)?;
+    Ok(serde_json::json!({
+        "searchId": search_id,
+        "totalCount": page.total_count,
+        "isComplete": page.is_complete,
+    }))
+}
+
+/// 读取搜索结果（最多 [`MAX_EXPORT_ENTRIES`] 条）作为导出条目
+fn collect_search_entries(state: &AppState, search_id: &str) -> Result<Value, CommandError> {
+    let manager = state
+        .get_search_session_manager()
+        .ok_or_else(|| CommandError::new("NOT_FOUND", "Search session manager not initialized"))?;
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while entries.len() < MAX_EXPORT_ENTRIES {
+        let limit = EXPORT_PAGE_SIZE.min(MAX_EXPORT_ENTRIES - entries.len());
+        let page = manager.fetch_search_page(search_id, offset, limit)?;
+        entries.extend(page.entries);
+        match page.next_offset {
+            Some(next) if page.has_more => offset = next,
+            _ => break,
+        }
+    }
+    serde_json::to_value(entries)
+        .map_err(|e| CommandError::new("SERIALIZATION_ERROR", e.to_string()))
+}
@@ -0,0 +1,277 @@
+//! 助手工具服务
+//!
+//! 以 MCP 兼容的 JSON-RPC 2.0 向 LLM 助手暴露只读工具（协议与 Schema 见
+//! `services::assistant_tools`）。两种接入方式：
+//! - `assistant_rpc` 命令：前端或插件转发单条 JSON-RPC 消息
+//! - `--mcp-stdio` 启动参数：在 stdin / stdout 上按行收发 JSON-RPC，日志改写到 stderr；
+//!   配合 `--mcp-workspace=<id>` 只允许访问该工作区
+
+use la_core::error::CommandError;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager, State, Window};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{info, warn};
+
+use crate::application::{build_stats_tables, stats_tables_json};
+use crate::commands::actions::dispatch_action;
+use crate::commands::analysis_macro::wait_for_search;
+use crate::models::AppState;
+use crate::services::assistant_tools::{
+    self, AssistantTool, RpcRequest, StdioOptions, INVALID_PARAMS, METHOD_NOT_FOUND,
+};
+
+/// 搜索工具默认返回的条数
+const DEFAULT_SEARCH_RESULTS: usize = 50;
+/// 统计工具默认的 Top N
+const DEFAULT_TOP_N: usize = 20;
+/// 助手发起的搜索使用的来源，与用户在界面上的搜索互不取消
+const SEARCH_ORIGIN: &str = "assistant";
+
+/// 处理一条 JSON-RPC 消息；通知（无 `id`）返回 `None`
+#[tauri::command]
+pub async fn assistant_rpc(
+    request: Value,
+    app: AppHandle,
+    window: Window,
+) -> Result<Option<Value>, CommandError> {
+    let line = serde_json::to_string(&request)
+        .map_err(|e| CommandError::new("SERIALIZATION_ERROR", e.to_string()))?;
+    Ok(handle_message(&line, &app, &window, None).await)
+}
+
+async fn handle_message(
+    line: &str,
+    app: &AppHandle,
+    window: &Window,
+    scope: Option<&str>,
+) -> Option<Value> {
+    match assistant_tools::parse_request(line) {
+        Ok(request) => handle_request(request, app, window, scope).await,
+        Err(response) => Some(response),
+    }
+}
+
+async fn handle_request(
+    request: RpcRequest,
+    app: &AppHandle,
+    window: &Window,
+    scope: Option<&str>,
+) -> Option<Value> {
+    let id = request.id?;
+    let response = match request.method.as_str() {
+        "initialize" => assistant_tools::success(id, assistant_tools::initialize_result()),
+        "ping" => assistant_tools::success(id, json!({})),
+        "tools/list" => assistant_tools::success(id, assistant_tools::tools_list_result()),
+        "tools/call" => match assistant_tools::parse_tool_call(&request.params) {
+            Ok((tool, args)) => {
+                let outcome = match scope {
+                    Some(scope)
+                        if args.get("workspaceId").and_then(Value::as_str) != Some(scope) =>
+                    {
+                        Err(format!("This server only serves workspace {scope}"))
+                    }
+                    _ => call_tool(tool, args, app, window)
+                        .await
+                        .map_err(|e| e.message),
+                };
+                assistant_tools::success(id, assistant_tools::tool_result(outcome))
+            }
+            Err(message) => assistant_tools::failure(id, INVALID_PARAMS, message),
+        },
+        other => assistant_tools::failure(id, METHOD_NOT_FOUND, format!("Unknown method: {other}")),
+    };
+    Some(response)
+}
+
+async fn call_tool(
+    tool: &AssistantTool,
+    mut args: Map<String, Value>,
+    app: &AppHandle,
+    window: &Window,
+) -> Result<Value, CommandError> {
+    let state = app.state::<AppState>();
+    match (tool.name, tool.action) {
+        ("search_logs", _) => search(args, app, window, state, SEARCH_ORIGIN).await,
+        ("get_statistics", _) => statistics(args, app, state).await,
+        (_, Some(action)) => {
+            // `files` 为空表示覆盖所有文件，工具参数中可省略
+            if matches!(action, "get_entries_around_time" | "find_silences") {
+                args.entry("files").or_insert_with(|| json!([]));
+            }
+            dispatch_action(
+                action,
+                Some(Value::Object(args)),
+                app.clone(),
+                state,
+                window.clone(),
+            )
+            .await
+        }
+        (name, None) => Err(CommandError::new(
+            "NOT_FOUND",
+            format!("Tool {name} has no handler"),
+        )),
+    }
+}
+
+/// 发起搜索、等待完成并返回前 `maxResults` 条，随后关闭搜索会话
+///
+/// `origin` 区分搜索来源：同一来源的新搜索会取消其未完成的旧搜索。
+pub(crate) async fn search(
+    mut args: Map<String, Value>,
+    app: &AppHandle,
+    window: &Window,
+    state: State<'_, AppState>,
+    origin: &str,
+) -> Result<Value, CommandError> {
+    let max_results = args
+        .get("maxResults")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_SEARCH_RESULTS, |n| n as usize);
+    args.insert("maxResults".to_string(), json!(max_results));
+    args.insert("origin".to_string(), json!(origin));
+
+    let search_id = match dispatch_action(
+        "search_logs",
+        Some(Value::Object(args)),
+        app.clone(),
+        state.clone(),
+        window.clone(),
+    )
+    .await?
+    {
+        Value::String(id) => id,
+        other => {
+            return Err(CommandError::new(
+                "SEARCH_ERROR",
+                format!("Unexpected search result: {other}"),
+            ))
+        }
+    };
+
+    let result = collect_search(&state, &search_id, max_results).await;
+    let close_args = json!({ "searchId": search_id });
+    if let Err(e) = dispatch_action(
+        "close_search_session",
+        Some(close_args),
+        app.clone(),
+        state,
+        window.clone(),
+    )
+    .await
+    {
+        warn!(search_id = %search_id, error = %e, "Failed to close assistant search session");
+    }
+    result
+}
+
+async fn collect_search(
+    state: &AppState,
+    search_id: &str,
+    max_results: usize,
+) -> Result<Value, CommandError> {
+    let summary = wait_for_search(state, search_id).await?;
+    let manager = state
+        .get_search_session_manager()
+        .ok_or_else(|| CommandError::new("NOT_FOUND", "Search session manager not initialized"))?;
+    let page = manager.fetch_search_page(search_id, 0, max_results)?;
+    Ok(json!({
+        "totalCount": summary["totalCount"],
+        "isComplete": summary["isComplete"],
+        "returned": page.entries.len(),
+        "entries": page.entries,
+    }))
+}
+
+/// 与 `export_statistics` 相同的统计表，直接以 JSON 返回
+async fn statistics(
+    args: Map<String, Value>,
+    app: &AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Value, CommandError> {
+    let workspace_id = args["workspaceId"].as_str().unwrap_or_default().to_string();
+    let client_id = args.get("clientId").and_then(Value::as_str);
+    let tables: Vec<String> = args
+        .get("tables")
+        .and_then(Value::as_array)
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let top_n = args
+        .get("topN")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_TOP_N, |n| n as usize);
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        app,
+        &state,
+        &workspace_id,
+        client_id,
+    )
+    .await?;
+    let store = service.metadata_store();
+    let overview = store.get_workspace_overview().await.map_err(|e| {
+        CommandError::new(
+            "DATABASE_ERROR",
+            format!("Failed to compute workspace overview: {e}"),
+        )
+    })?;
+    let dedup = store.get_dedup_report(top_n).await.map_err(|e| {
+        CommandError::new(
+            "DATABASE_ERROR",
+            format!("Failed to compute dedup report: {e}"),
+        )
+    })?;
+    let tables = build_stats_tables(&tables, &overview, &dedup).map_err(|name| {
+        CommandError::new(
+            "VALIDATION_ERROR",
+            format!("Unknown statistics table: {name}"),
+        )
+    })?;
+    serde_json::from_str(&stats_tables_json(&workspace_id, &tables))
+        .map_err(|e| CommandError::new("SERIALIZATION_ERROR", e.to_string()))
+}
+
+/// 在 stdin / stdout 上运行工具服务，stdin 关闭时退出
+pub fn spawn_stdio_server(app: AppHandle, options: StdioOptions) {
+    tauri::async_runtime::spawn(async move {
+        let Some(webview) = app.get_webview_window("main") else {
+            warn!("Assistant tool server needs the main window; not started");
+            return;
+        };
+        let window = webview.as_ref().window();
+        info!(workspace = ?options.workspace, "Assistant tool server listening on stdio");
+
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!(error = %e, "Assistant tool server failed to read stdin");
+                    break;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some(response) =
+                handle_message(&line, &app, &window, options.workspace.as_deref()).await
+            else {
+                continue;
+            };
+            let mut out = response.to_string();
+            out.push('\n');
+            if stdout.write_all(out.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+                break;
+            }
+        }
+        info!("Assistant tool server stopped");
+    });
+}
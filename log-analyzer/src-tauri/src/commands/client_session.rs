@@ -0,0 +1,97 @@
+//! 客户端会话命令
+//!
+//! 共享后端模式下，每个客户端（WebSocket 连接 / 窗口）以 `clientId` 打开会话并定期心跳。
+//! 携带 `clientId` 发起的搜索与跟随查询记在该会话名下，会话关闭或心跳超时后统一释放。
+//!
+//! 多个团队共用一个后端时，会话以 API 密钥打开：租户密钥把会话限定在该租户，只能打开、
+//! 列出本租户的工作区、搜索结果与会话（见 `workspace_guard::authorize_workspace`）；
+//! 租户由密钥决定，不接受客户端自报。开启 `security.auth_enabled`（服务器模式）后
+//! 必须提供密钥，未打开会话的调用一律拒绝。
+//!
+//! # 前后端集成规范
+//!
+//! 为保持与 JavaScript camelCase 惯例一致，Tauri 命令参数使用 camelCase 命名。
+
+use la_core::error::CommandError;
+use tauri::State;
+
+use crate::application::client_sessions::{ClientSessionInfo, CLIENT_IDLE_TIMEOUT};
+use crate::infrastructure::client_session_reaper::release_client_resources;
+use crate::models::AppState;
+use crate::utils::workspace_guard::caller_tenant;
+
+/// 客户端 ID 最大长度
+const MAX_CLIENT_ID_LEN: usize = 128;
+
+fn validate_client_id(client_id: &str) -> Result<(), CommandError> {
+    if client_id.trim().is_empty() || client_id.len() > MAX_CLIENT_ID_LEN {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            format!("Client ID must be 1-{MAX_CLIENT_ID_LEN} characters"),
+        ));
+    }
+    Ok(())
+}
+
+/// 打开（或重连）客户端会话；会话的身份与租户由 `apiKey` 决定（打开后不可更换）
+#[tauri::command]
+pub async fn open_client_session(
+    #[allow(non_snake_case)] clientId: String,
+    #[allow(non_snake_case)] apiKey: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ClientSessionInfo, CommandError> {
+    validate_client_id(&clientId)?;
+    let clients = state.sync.clients();
+    let credential = clients.authenticate(apiKey.as_deref()).map_err(|e| {
+        CommandError::new("UNAUTHORIZED", e)
+            .with_help("Use the operator key or a tenant key from the security settings")
+    })?;
+    clients
+        .open(&clientId, credential.identity, credential.tenant)
+        .map_err(|e| {
+            CommandError::new("VALIDATION_ERROR", e)
+                .with_help("Close the session or use a different client ID")
+        })
+}
+
+/// 客户端心跳；超过空闲超时未心跳的会话会被回收
+#[tauri::command]
+pub async fn client_heartbeat(
+    #[allow(non_snake_case)] clientId: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    if state.sync.clients().heartbeat(&clientId) {
+        return Ok(());
+    }
+    Err(CommandError::new(
+        "NOT_FOUND",
+        format!("No session open for client '{clientId}'"),
+    )
+    .with_help(format!(
+        "Sessions expire after {}s without a heartbeat; open a new one",
+        CLIENT_IDLE_TIMEOUT.as_secs()
+    )))
+}
+
+/// 关闭客户端会话并释放其搜索、快照与跟随查询；返回会话是否存在
+#[tauri::command]
+pub async fn close_client_session(
+    #[allow(non_snake_case)] clientId: String,
+    state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    let Some(resources) = state.sync.clients().close(&clientId) else {
+        return Ok(false);
+    };
+    release_client_resources(&state, &resources);
+    Ok(true)
+}
+
+/// 当前打开的客户端会话；租户会话只能看到本租户的会话
+#[tauri::command]
+pub async fn list_client_sessions(
+    #[allow(non_snake_case)] clientId: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ClientSessionInfo>, CommandError> {
+    let tenant = caller_tenant(&state, clientId.as_deref())?;
+    Ok(state.sync.clients().list(tenant.as_deref()))
+}
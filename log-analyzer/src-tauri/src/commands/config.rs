@@ -7,14 +7,15 @@
 
 use std::sync::Arc;
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
 use la_core::models::config::{
-    AppConfig, ConfigValidator, FileFilterConfig, SearchConfig, TaskManagerConfig,
+    AppConfig, ConfigValidator, FileFilterConfig, LinkTemplate, SearchConfig, TaskManagerConfig,
 };
 
 use crate::adapters::tauri_config::TauriAppConfigProvider;
 use crate::application::ConfigUseCase;
+use crate::models::AppState;
 
 fn use_case(app: AppHandle) -> ConfigUseCase<TauriAppConfigProvider> {
     ConfigUseCase::new(Arc::new(TauriAppConfigProvider(app)))
@@ -29,9 +30,33 @@ pub async fn load_config(app: AppHandle) -> Result<AppConfig, String> {
 
 #[tauri::command]
 pub async fn save_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
+    let (max_total, max_per_origin) = (
+        config.search.max_concurrent_searches,
+        config.search.max_searches_per_origin,
+    );
+    let watch_config = config.watch.clone();
+    let links_config = config.links.clone();
+    let translation_config = config.translation.clone();
+    let telemetry_enabled = config.monitoring.telemetry_enabled;
+    let security_config = config.security.clone();
+    let handle = app.clone();
     tokio::task::spawn_blocking(move || use_case(app).save(&config).map_err(|e| e.to_string()))
         .await
-        .map_err(|e| format!("Task panicked: {e}"))?
+        .map_err(|e| format!("Task panicked: {e}"))??;
+
+    // 并发限制与监听预算即时生效，无需重启
+    let state = handle.state::<AppState>();
+    state
+        .search
+        .concurrency()
+        .set_limits(max_total, max_per_origin);
+    state.workspace.watcher_budget().configure(&watch_config);
+    state.search.links().configure(&links_config);
+    state.search.translation().configure(&translation_config);
+    // 密钥变更后新会话按新密钥认证，已打开的会话保持原租户
+    state.sync.clients().configure(&security_config);
+    crate::monitoring::telemetry().set_enabled(telemetry_enabled);
+    Ok(())
 }
 
 #[tauri::command]
@@ -103,6 +128,41 @@ pub async fn save_task_manager_config(
     save_config(app, config).await
 }
 
+#[tauri::command]
+pub async fn get_link_templates(app: AppHandle) -> Result<Vec<LinkTemplate>, String> {
+    let config = load_config(app).await?;
+    Ok(config.links.templates)
+}
+
+/// 注册外部链接模板；同 id 的模板被替换
+#[tauri::command]
+pub async fn register_link_template(app: AppHandle, template: LinkTemplate) -> Result<(), String> {
+    template
+        .check()
+        .map_err(|e| format!("链接模板验证失败: {e}"))?;
+
+    let mut config = load_config(app.clone()).await?;
+    let templates = &mut config.links.templates;
+    match templates.iter_mut().find(|t| t.id == template.id) {
+        Some(existing) => *existing = template,
+        None => templates.push(template),
+    }
+    save_config(app, config).await
+}
+
+/// 删除外部链接模板；返回是否存在
+#[tauri::command]
+pub async fn remove_link_template(app: AppHandle, id: String) -> Result<bool, String> {
+    let mut config = load_config(app.clone()).await?;
+    let before = config.links.templates.len();
+    config.links.templates.retain(|t| t.id != id);
+    if config.links.templates.len() == before {
+        return Ok(false);
+    }
+    save_config(app, config).await?;
+    Ok(true)
+}
+
 fn format_validation_errors(
     prefix: &str,
     errors: &[la_core::models::config::FieldValidationError],
@@ -0,0 +1,343 @@
+//! 诊断命令
+//!
+//! 前端错误上报、诊断页面查询、后端日志查看与导出、崩溃报告、启动自检、内存占用估算、
+//! 本地使用统计的查看与导出，以及本机性能基准接口。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use la_core::error::CommandError;
+use tauri::{AppHandle, Manager, State};
+
+use crate::benchmark::diagnostics::BenchmarkOptions;
+use crate::benchmark::{run_diagnostics, BenchmarkReport};
+use crate::models::AppState;
+use crate::monitoring::backend_logs::backend_logs;
+use crate::monitoring::memory_usage::{global_components, process_resident_bytes};
+use crate::monitoring::telemetry::telemetry;
+use crate::monitoring::{
+    BackendLogFilter, BackendLogRecord, CrashReportSummary, ErrorGroup, ErrorReportOutcome,
+    ErrorReportStore, FrontendErrorReport, MemoryBreakdown, TelemetryReport,
+};
+use crate::services::startup_check::{
+    cleanup_orphaned_temp, run_startup_checks, StartupReport, TempCleanupSummary,
+};
+use crate::utils::load_app_config;
+
+/// `get_error_groups` 默认返回的错误组数量
+const DEFAULT_ERROR_GROUP_LIMIT: u32 = 100;
+
+/// `get_error_groups` 允许的最大返回数量
+const MAX_ERROR_GROUP_LIMIT: u32 = 1_000;
+
+/// 防止基准并发运行（并发会互相干扰测量结果）
+static BENCHMARK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn error_report_store(
+    state: &State<'_, AppState>,
+) -> Result<std::sync::Arc<ErrorReportStore>, CommandError> {
+    state.monitoring.error_reports().ok_or_else(|| {
+        CommandError::new("DIAGNOSTICS_UNAVAILABLE", "错误上报存储未初始化")
+            .with_help("请检查应用数据目录是否可写后重启应用")
+    })
+}
+
+/// 上报前端错误
+///
+/// 按指纹去重后持久化，并关联上报时间前后的后端错误。
+#[tauri::command]
+pub async fn report_frontend_error(
+    report: FrontendErrorReport,
+    state: State<'_, AppState>,
+) -> Result<ErrorReportOutcome, CommandError> {
+    let store = error_report_store(&state)?;
+    store.record(&report).await.map_err(CommandError::from)
+}
+
+/// 获取错误组列表（按最近出现时间倒序）
+///
+/// # 参数
+/// - `limit`: 返回的最大错误组数量，默认 100，上限 1000
+#[tauri::command]
+pub async fn get_error_groups(
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ErrorGroup>, CommandError> {
+    let store = error_report_store(&state)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_ERROR_GROUP_LIMIT)
+        .clamp(1, MAX_ERROR_GROUP_LIMIT);
+    store
+        .get_error_groups(limit)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// 清空所有错误组
+#[tauri::command]
+pub async fn clear_error_groups(state: State<'_, AppState>) -> Result<(), CommandError> {
+    let store = error_report_store(&state)?;
+    store.clear().await.map_err(CommandError::from)
+}
+
+/// 获取最近的后端日志（内存环形缓冲区，按时间顺序）
+///
+/// 级别受日志配置的全局过滤器限制；`filter` 为空时返回缓冲区中的全部记录。
+#[tauri::command]
+pub async fn get_backend_logs(
+    filter: Option<BackendLogFilter>,
+) -> Result<Vec<BackendLogRecord>, CommandError> {
+    backend_logs(&filter.unwrap_or_default()).map_err(|e| {
+        CommandError::new("VALIDATION_ERROR", e)
+            .with_help("Use one of: error, warn, info, debug, trace")
+    })
+}
+
+/// 把后端日志导出到下载目录，便于附在问题报告中
+///
+/// `format` 为 `text`（默认，每行一条）或 `json`。返回写入的文件路径。
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export_backend_logs(
+    app: AppHandle,
+    filter: Option<BackendLogFilter>,
+    format: Option<String>,
+    savePath: String,
+) -> Result<String, CommandError> {
+    let records = backend_logs(&filter.unwrap_or_default())
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let content = match format.as_deref().unwrap_or("text") {
+        "text" => records
+            .iter()
+            .map(|r| r.to_line() + "\n")
+            .collect::<String>(),
+        "json" => serde_json::to_string_pretty(&records)
+            .map_err(|e| CommandError::new("EXPORT_ERROR", e.to_string()))?,
+        other => {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                format!("Unsupported format: {other}"),
+            )
+            .with_help("Use 'text' or 'json'"))
+        }
+    };
+
+    let path = crate::commands::export::resolve_save_path(&app, &savePath)?;
+    tokio::fs::write(&path, content).await.map_err(|e| {
+        CommandError::new(
+            "IO_ERROR",
+            format!("Failed to write {}: {e}", path.display()),
+        )
+    })?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 查看本地使用统计（内容与 `export_telemetry` 导出的完全一致）
+#[tauri::command]
+pub async fn get_telemetry_report() -> Result<TelemetryReport, CommandError> {
+    Ok(telemetry().report())
+}
+
+/// 开启或关闭本地使用统计并写入配置；关闭时清空已有统计
+#[tauri::command]
+pub async fn set_telemetry_enabled(app: AppHandle, enabled: bool) -> Result<(), CommandError> {
+    let mut config = crate::commands::config::load_config(app.clone())
+        .await
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))?;
+    config.monitoring.telemetry_enabled = enabled;
+    crate::commands::config::save_config(app, config)
+        .await
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))
+}
+
+/// 把本地使用统计导出为 JSON，由用户自行决定是否附在反馈中。返回写入的文件路径。
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn export_telemetry(app: AppHandle, savePath: String) -> Result<String, CommandError> {
+    let report = telemetry().report();
+    if !report.enabled {
+        return Err(
+            CommandError::new("VALIDATION_ERROR", "Usage statistics are disabled")
+                .with_help("Enable usage statistics with set_telemetry_enabled first"),
+        );
+    }
+    let content = serde_json::to_string_pretty(&report)
+        .map_err(|e| CommandError::new("EXPORT_ERROR", e.to_string()))?;
+
+    let path = crate::commands::export::resolve_save_path(&app, &savePath)?;
+    tokio::fs::write(&path, content).await.map_err(|e| {
+        CommandError::new(
+            "IO_ERROR",
+            format!("Failed to write {}: {e}", path.display()),
+        )
+    })?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 清空本地使用统计（保持开启状态，从零重新统计）
+#[tauri::command]
+pub async fn clear_telemetry() -> Result<(), CommandError> {
+    tokio::task::spawn_blocking(|| telemetry().clear())
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", e.to_string()))
+}
+
+fn crash_report_dir(app: &AppHandle) -> Result<std::path::PathBuf, CommandError> {
+    let data_dir = app.path().app_data_dir().map_err(|e| {
+        CommandError::new(
+            "APP_DATA_DIR_UNAVAILABLE",
+            format!("无法获取应用数据目录: {e}"),
+        )
+    })?;
+    Ok(data_dir.join(crate::monitoring::CRASH_REPORT_DIR))
+}
+
+/// 列出之前运行留下的崩溃报告（新的在前）
+///
+/// 启动时调用，有报告时提示用户打开（`path` 为报告 JSON 文件）或随问题一起发送。
+#[tauri::command]
+pub async fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReportSummary>, CommandError> {
+    let dir = crash_report_dir(&app)?;
+    tokio::task::spawn_blocking(move || crate::monitoring::crash_reports::list_crash_reports(&dir))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", e.to_string()))?
+        .map_err(|e| CommandError::new("IO_ERROR", format!("读取崩溃报告失败: {e}")))
+}
+
+/// 删除已处理的崩溃报告，返回删除数量
+#[tauri::command]
+pub async fn delete_crash_reports(app: AppHandle, ids: Vec<String>) -> Result<usize, CommandError> {
+    let dir = crash_report_dir(&app)?;
+    crate::monitoring::crash_reports::delete_crash_reports(&dir, &ids)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))
+}
+
+/// 已在本进程中打开的工作区（跳过其索引锁与临时目录）
+fn open_workspace_ids(state: &State<'_, AppState>) -> std::collections::HashSet<String> {
+    state.workspace_ids().into_iter().collect()
+}
+
+/// 启动自检
+///
+/// 检查配置文件、各工作区数据库、中断的导入、残留临时目录、索引锁与崩溃报告。
+/// 每个问题附带可直接调用的恢复命令，供前端恢复向导展示。
+#[tauri::command]
+pub async fn run_startup_check(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<StartupReport, CommandError> {
+    let data_dir = app.path().app_data_dir().map_err(|e| {
+        CommandError::new(
+            "APP_DATA_DIR_UNAVAILABLE",
+            format!("无法获取应用数据目录: {e}"),
+        )
+    })?;
+    let config_path = app
+        .path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join("config.json"));
+
+    let report = run_startup_checks(
+        config_path.as_deref(),
+        &data_dir,
+        &open_workspace_ids(&state),
+    )
+    .await;
+    if !report.is_healthy() {
+        tracing::warn!(
+            issues = report.issues.len(),
+            "Startup self-check found problems"
+        );
+    }
+    Ok(report)
+}
+
+/// 清理中断的导入留下的临时文件（工作区 `tmp/`、`extracted/` 与下载 / 快速扫描暂存）
+///
+/// 已打开的工作区不清理；持有租约（导入 / 刷新 / 删除进行中）时拒绝执行。
+#[tauri::command]
+pub async fn cleanup_orphaned_temp_dirs(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<TempCleanupSummary, CommandError> {
+    if !state.sync.leases().snapshot().is_empty() {
+        return Err(
+            CommandError::new("WORKSPACE_BUSY", "An import or refresh is in progress")
+                .with_help("Run the cleanup after it finishes"),
+        );
+    }
+    let data_dir = app.path().app_data_dir().map_err(|e| {
+        CommandError::new(
+            "APP_DATA_DIR_UNAVAILABLE",
+            format!("无法获取应用数据目录: {e}"),
+        )
+    })?;
+    let skip = open_workspace_ids(&state);
+
+    let summary = tokio::task::spawn_blocking(move || cleanup_orphaned_temp(&data_dir, &skip))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", e.to_string()))?;
+    tracing::info!(
+        removed = summary.removed_entries,
+        freed_bytes = summary.freed_bytes,
+        "Orphaned temp dirs cleaned"
+    );
+    Ok(summary)
+}
+
+/// 获取内存占用估算
+///
+/// 汇总各工作区的索引与缓存、搜索结果缓冲、快照路径表与事件历史，
+/// 按估算占用从大到小返回，并附上进程常驻内存作对照。
+#[tauri::command]
+pub async fn get_memory_breakdown(
+    state: State<'_, AppState>,
+) -> Result<MemoryBreakdown, CommandError> {
+    let services = state.all_workspace_services();
+    let sessions = state.get_search_session_manager();
+    let results = state.get_disk_result_store();
+    let translation = state.search.translation();
+
+    // 索引段大小需要读取段元数据，放到阻塞线程执行
+    tokio::task::spawn_blocking(move || {
+        let mut components: Vec<_> = services
+            .iter()
+            .flat_map(|service| service.memory_usage())
+            .collect();
+        components.extend(global_components(
+            sessions.as_ref(),
+            results.as_deref(),
+            &translation,
+        ));
+        MemoryBreakdown::new(components, process_resident_bytes())
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", e.to_string()))
+}
+
+/// 运行本机性能基准
+///
+/// 测量磁盘吞吐、哈希、模式匹配与 SQLite 插入速率，保存为基线，
+/// 并返回上一次基线与配置调优建议。同一时刻只允许一个基准运行。
+#[tauri::command]
+pub async fn run_diagnostics_benchmark(app: AppHandle) -> Result<BenchmarkReport, CommandError> {
+    if BENCHMARK_RUNNING
+        .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+        .is_err()
+    {
+        return Err(CommandError::new("BENCHMARK_RUNNING", "基准测试正在运行"));
+    }
+    scopeguard::defer! { BENCHMARK_RUNNING.store(false, Ordering::Release); }
+
+    let data_dir = app.path().app_data_dir().map_err(|e| {
+        CommandError::new(
+            "APP_DATA_DIR_UNAVAILABLE",
+            format!("无法获取应用数据目录: {e}"),
+        )
+    })?;
+    let config = load_app_config(&app).unwrap_or_default();
+
+    run_diagnostics(&data_dir, &config, BenchmarkOptions::default())
+        .await
+        .map_err(CommandError::from)
+}
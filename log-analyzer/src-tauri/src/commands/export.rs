@@ -1,22 +1,145 @@
-//! 导出命令实现（CSV / JSON）
+//! 导出命令实现（CSV / JSON）：原始搜索结果与工作区统计表
 //!
-//! 路径安全验证 + I/O 在命令层，数据变换委托给 ExportUseCase。
+//! 路径安全验证 + I/O 在命令层，数据变换委托给 ExportUseCase，
+//! 剪贴板 / SFTP / 命令钩子目的地见 `services::export_destinations`。
+
+use std::path::{Path, PathBuf};
 
 use la_core::error::CommandError;
+use la_core::models::config::ExportConfig;
 use la_core::models::LogEntry;
-use tauri::{command, AppHandle, Manager};
+use la_core::utils::path::to_extended_length_path;
+use la_core::utils::{validate_and_sanitize_path, PathValidationResult, SecurityConfig};
+use tauri::{command, AppHandle, Manager, State};
 
-use crate::application::{transform_csv, transform_json};
+use crate::application::{
+    build_stats_tables, stats_table_csv, stats_tables_json, transform_csv, transform_json,
+    STATS_TABLES,
+};
+use crate::models::AppState;
+use crate::services::export_destinations::{self, ExportDestination, ExportOptions};
 
 #[command]
 pub async fn export_results(
     app: AppHandle,
-    results: Vec<LogEntry>,
+    mut results: Vec<LogEntry>,
     format: String,
     #[allow(non_snake_case)] savePath: String,
+    options: Option<ExportOptions>,
 ) -> Result<String, CommandError> {
-    let save_path = std::path::Path::new(&savePath);
-    for component in save_path.components() {
+    let final_path = resolve_save_path(&app, &savePath)?;
+
+    // 导出的报告带上可点击的外部链接
+    app.state::<crate::models::AppState>()
+        .search
+        .links()
+        .annotate(&mut results);
+
+    let export_config = crate::utils::load_app_config(&app)
+        .unwrap_or_default()
+        .export;
+    let destination = options.unwrap_or_default().destination;
+
+    tokio::task::spawn_blocking(move || -> Result<String, CommandError> {
+        let content = render(&results, &format)?;
+        deliver(content, destination, &final_path, &format, &export_config)
+    })
+    .await
+    .map_err(|e| CommandError::new("EXPORT_PANICKED", format!("Export panicked: {e}")))?
+}
+
+/// 导出工作区统计表（而非原始日志行）
+///
+/// - `tables`：`levelsByDay`（按天级别直方图）、`levelsByFile`（按文件级别分布）、
+///   `coverageGaps`、`dedupDistribution`、`topDuplicates`（节省最多的前 N 个重复对象）；
+///   为空时导出全部
+/// - CSV 每个文件只能包含一张表；JSON 按表名输出行对象数组
+/// - `top_n` 为 `topDuplicates` 的行数（默认 20，上限 500）
+///
+/// 统计均来自导入时记录的直方图与哈希，不读取文件内容。目的地与 `export_results` 相同。
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn export_statistics(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    workspace_id: String,
+    client_id: Option<String>,
+    tables: Option<Vec<String>>,
+    top_n: Option<usize>,
+    format: String,
+    #[allow(non_snake_case)] savePath: String,
+    options: Option<ExportOptions>,
+) -> Result<String, CommandError> {
+    const DEFAULT_TOP_N: usize = 20;
+    const MAX_TOP_N: usize = 500;
+
+    let tables = tables.unwrap_or_default();
+    let single_table = tables.len() == 1 || (tables.is_empty() && STATS_TABLES.len() == 1);
+    if format == "csv" && !single_table {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            "CSV statistics export takes exactly one table".to_string(),
+        )
+        .with_help(format!("Pick one of: {}", STATS_TABLES.join(", "))));
+    }
+    let final_path = resolve_save_path(&app, &savePath)?;
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let store = service.metadata_store();
+    let overview = store.get_workspace_overview().await.map_err(|e| {
+        CommandError::new(
+            "DATABASE_ERROR",
+            format!("Failed to compute workspace overview: {e}"),
+        )
+    })?;
+    let dedup = store
+        .get_dedup_report(top_n.unwrap_or(DEFAULT_TOP_N).min(MAX_TOP_N))
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to compute dedup report: {e}"),
+            )
+        })?;
+    let tables = build_stats_tables(&tables, &overview, &dedup).map_err(|name| {
+        CommandError::new(
+            "VALIDATION_ERROR",
+            format!("Unknown statistics table: {name}"),
+        )
+        .with_help(format!("Pick from: {}", STATS_TABLES.join(", ")))
+    })?;
+
+    let export_config = crate::utils::load_app_config(&app)
+        .unwrap_or_default()
+        .export;
+    let destination = options.unwrap_or_default().destination;
+
+    tokio::task::spawn_blocking(move || -> Result<String, CommandError> {
+        let content = match format.as_str() {
+            "csv" => stats_table_csv(&tables[0]).into_bytes(),
+            "json" => stats_tables_json(&workspace_id, &tables).into_bytes(),
+            _ => {
+                return Err(CommandError::new(
+                    "UNSUPPORTED_EXPORT_FORMAT",
+                    format!("Unsupported format: {format}"),
+                ))
+            }
+        };
+        deliver(content, destination, &final_path, &format, &export_config)
+    })
+    .await
+    .map_err(|e| CommandError::new("EXPORT_PANICKED", format!("Export panicked: {e}")))?
+}
+
+/// 把相对导出路径解析到下载目录内，拒绝绝对路径、路径遍历与越界
+pub(crate) fn resolve_save_path(app: &AppHandle, save_path: &str) -> Result<PathBuf, CommandError> {
+    for component in std::path::Path::new(save_path).components() {
         match component {
             std::path::Component::Normal(_) | std::path::Component::CurDir => {}
             std::path::Component::RootDir | std::path::Component::Prefix(_) => {
@@ -34,12 +157,28 @@ pub async fn export_results(
         }
     }
 
-    let safe = crate::utils::validation::prevent_path_traversal(&savePath)
+    let safe = crate::utils::validation::prevent_path_traversal(save_path)
         .map_err(|e| CommandError::new("EXPORT_PATH_UNSAFE", format!("导出路径不安全: {e}")))?;
     let download_dir = app.path().download_dir().map_err(|e| {
         CommandError::new("DOWNLOAD_DIR_UNAVAILABLE", format!("无法获取下载目录: {e}"))
     })?;
-    let final_path = download_dir.join(&safe);
+    // 各组件按 Windows 规则清理（保留设备名 aux.csv、结尾点/空格等），各平台结果一致
+    let mut final_path = download_dir.clone();
+    for component in safe
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+    {
+        match validate_and_sanitize_path(component, &SecurityConfig::default()) {
+            PathValidationResult::Valid(name)
+            | PathValidationResult::RequiresSanitization(_, name) => final_path.push(name),
+            PathValidationResult::Unsafe(reason) => {
+                return Err(CommandError::new(
+                    "EXPORT_PATH_UNSAFE",
+                    format!("导出路径不安全: {reason}"),
+                ));
+            }
+        }
+    }
 
     if let Ok(canonical_final) = dunce::canonicalize(&final_path) {
         let cd = dunce::canonicalize(&download_dir).unwrap_or(download_dir);
@@ -51,32 +190,82 @@ pub async fn export_results(
         }
     }
 
-    let path_str = final_path.to_string_lossy().to_string();
+    Ok(final_path)
+}
 
-    tokio::task::spawn_blocking(move || -> Result<String, CommandError> {
-        match format.as_str() {
-            "csv" => {
-                let csv = transform_csv(&results);
-                let mut f = std::fs::File::create(&path_str)
-                    .map_err(|e| CommandError::new("IO_ERROR", e.to_string()))?;
-                std::io::Write::write_all(&mut f, &[0xEFu8, 0xBB, 0xBF])
-                    .map_err(|e| CommandError::new("IO_ERROR", e.to_string()))?;
-                std::io::Write::write_all(&mut f, csv.as_bytes())
-                    .map_err(|e| CommandError::new("IO_ERROR", e.to_string()))?;
-                Ok(path_str)
-            }
-            "json" => {
-                let json = transform_json(&results);
-                std::fs::write(&path_str, json)
-                    .map_err(|e| CommandError::new("IO_ERROR", e.to_string()))?;
-                Ok(path_str)
-            }
-            _ => Err(CommandError::new(
-                "UNSUPPORTED_EXPORT_FORMAT",
-                format!("Unsupported format: {format}"),
-            )),
+/// 把渲染好的内容交给目的地，返回导出位置（剪贴板目的地返回文本本身）
+fn deliver(
+    content: Vec<u8>,
+    destination: ExportDestination,
+    final_path: &Path,
+    format: &str,
+    export_config: &ExportConfig,
+) -> Result<String, CommandError> {
+    let file_name = final_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("export.{format}"));
+    let delivery_failed = |e: String| CommandError::new("EXPORT_DELIVERY_FAILED", e);
+    match destination {
+        ExportDestination::File => {
+            // 实际写入使用 \\?\ 前缀，避免超长下载目录路径写入失败
+            std::fs::write(to_extended_length_path(final_path), content)
+                .map_err(|e| CommandError::new("IO_ERROR", e.to_string()))?;
+            Ok(final_path.to_string_lossy().to_string())
         }
-    })
-    .await
-    .map_err(|e| CommandError::new("EXPORT_PANICKED", format!("Export panicked: {e}")))?
+        ExportDestination::Clipboard => {
+            export_destinations::clipboard_text(content, export_config.clipboard_max_bytes).map_err(
+                |e| {
+                    CommandError::new("EXPORT_TOO_LARGE", e)
+                        .with_help("Narrow the results or export to a file instead")
+                },
+            )
+        }
+        ExportDestination::Sftp {
+            host,
+            port,
+            user,
+            remote_path,
+        } => {
+            export_destinations::validate_sftp_target(&host, user.as_deref(), &remote_path)
+                .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+            export_destinations::upload_sftp(
+                &content,
+                &file_name,
+                &host,
+                port,
+                user.as_deref(),
+                &remote_path,
+                export_config,
+            )
+            .map_err(delivery_failed)
+        }
+        ExportDestination::Command { name } => {
+            let hook = export_config
+                .commands
+                .iter()
+                .find(|c| c.name == name)
+                .ok_or_else(|| {
+                    CommandError::new(
+                        "UNKNOWN_EXPORT_COMMAND",
+                        format!("Export command '{name}' is not configured"),
+                    )
+                    .with_help("Define it under export.commands in the settings file")
+                })?;
+            export_destinations::run_command_hook(&content, &file_name, format, hook, export_config)
+                .map_err(delivery_failed)
+        }
+    }
+}
+
+/// 渲染导出内容（CSV 自带 UTF-8 BOM）
+fn render(results: &[LogEntry], format: &str) -> Result<Vec<u8>, CommandError> {
+    match format {
+        "csv" => Ok(transform_csv(results).into_bytes()),
+        "json" => Ok(transform_json(results).into_bytes()),
+        _ => Err(CommandError::new(
+            "UNSUPPORTED_EXPORT_FORMAT",
+            format!("Unsupported format: {format}"),
+        )),
+    }
 }
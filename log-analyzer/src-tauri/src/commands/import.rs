@@ -12,9 +12,16 @@
 use serde::Serialize;
 use tauri::{command, AppHandle, State};
 
+use crate::application::workspace_service::ImportOptions;
 use crate::infrastructure::import_pipeline::run_import;
 use crate::infrastructure::{TauriEventPublisher, TauriWorkspacePaths};
 use crate::models::AppState;
+use crate::state_sync::LeaseOperation;
+use crate::utils::workspace_guard::{acquire_workspace_lease, claim_workspace};
+use la_archive::{
+    ArchivePassword, ArchiveSelection, EntryFilter, EntrySelection, ExtractionSelection,
+    PostExtractHook,
+};
 use std::sync::Arc;
 
 // ============================================================================
@@ -52,13 +59,93 @@ fn get_rar_support_info() -> RarSupportInfo {
 // 简化版 import_folder 命令
 // ============================================================================
 
+/// 导入文件夹或压缩包
+///
+/// `selection` 为可选的选择性解压列表：对列出的压缩包只提取 `include` 命中的条目，
+/// 其余条目不落盘、不建索引，记录在 `get_skipped_entries` 中。
+///
+/// `filter` 为作用于所有未单独列出的压缩包（含嵌套压缩包）的包含 / 排除 glob 列表，
+/// 如只提取 `**/*.log` 与 `**/var/log/**`；被跳过的条目同样记录在 `get_skipped_entries` 中，
+/// 并以灰色节点出现在虚拟文件树里。省略时使用配置 `archive.include_patterns` /
+/// `archive.exclude_patterns`。
+///
+/// `post_extract_hooks` 为该工作区的解压后处理钩子，提供时保存为工作区设置，
+/// 省略时沿用已保存的设置（见 `set_post_extract_hooks`）。
+///
+/// `password` 用于解密加密的 ZIP、RAR 压缩包；缺少密码或密码错误时导入失败，
+/// 错误为 JSON 格式的 `CommandError`，`code` 为 `PASSWORD_REQUIRED`。
+///
+/// 导入期间持有工作区的操作租约（`client_id` 记为持有者），与其他导入、
+/// 刷新、删除互斥，冲突时返回 `WORKSPACE_BUSY`。
+///
+/// 租户会话导入新工作区时将其归属该租户；不能导入到其他租户（或无租户）的已有工作区。
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn import_folder(
     app: AppHandle,
     path: String,
     workspace_id: String,
+    selection: Option<Vec<ArchiveSelection>>,
+    filter: Option<EntryFilter>,
+    post_extract_hooks: Option<Vec<PostExtractHook>>,
+    password: Option<String>,
+    client_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
+    let _lease = acquire_workspace_lease(
+        &state,
+        &workspace_id,
+        LeaseOperation::Import,
+        client_id.clone(),
+        None,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    claim_workspace(&app, &state, &workspace_id, client_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    import_folder_leased(
+        app,
+        path,
+        workspace_id,
+        selection,
+        filter,
+        post_extract_hooks,
+        password,
+        &state,
+    )
+    .await
+}
+
+/// `import_folder` 的主体；调用方须已持有该工作区的操作租约
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn import_folder_leased(
+    app: AppHandle,
+    path: String,
+    workspace_id: String,
+    selection: Option<Vec<ArchiveSelection>>,
+    filter: Option<EntryFilter>,
+    post_extract_hooks: Option<Vec<PostExtractHook>>,
+    password: Option<String>,
+    state: &AppState,
+) -> Result<String, String> {
+    let selection = selection.unwrap_or_default();
+    // 提前编译，模式错误时不创建导入任务
+    ExtractionSelection::new(&selection)?;
+    if let Some(filter) = &filter {
+        EntrySelection::filter(&filter.include, &filter.exclude)?;
+    }
+    if let Some(hooks) = &post_extract_hooks {
+        la_archive::post_extract::validate_hooks(hooks)?;
+    }
+    let options = ImportOptions {
+        selection,
+        entry_filter: filter,
+        post_extract_hooks,
+        password: password.filter(|p| !p.is_empty()).map(ArchivePassword::new),
+        ..ImportOptions::default()
+    };
+
     let event_publisher = Arc::new(TauriEventPublisher {
         app_handle: app.clone(),
     });
@@ -69,11 +156,441 @@ pub async fn import_folder(
         &workspace_paths,
         &config_provider,
         &app,
-        &state,
+        state,
         &workspace_id,
         &path,
+        options,
+    )
+    .await
+}
+
+/// 从 HTTPS 地址下载压缩包并导入工作区
+///
+/// 下载进度通过独立的 "Download" 任务上报；同一地址（不含查询串）的未完成下载会续传。
+/// 提供 `expectedSha256` 时校验通过才导入。下载文件放在应用数据目录的
+/// `downloads/` 下，导入成功后删除。返回导入任务 ID。
+#[tauri::command]
+pub async fn import_from_url(
+    app: AppHandle,
+    url: String,
+    workspace_id: String,
+    #[allow(non_snake_case)] expectedSha256: Option<String>,
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    use crate::infrastructure::url_download;
+    use la_core::domain::TaskHandle;
+    use sha2::{Digest, Sha256};
+    use tauri::Manager;
+
+    crate::utils::validation::validate_workspace_id(&workspace_id)?;
+    let parsed = url_download::parse_download_url(&url)?;
+    let expected = expectedSha256
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(url_download::normalize_sha256)
+        .transpose()?;
+    // 先确认调用方可以写入该工作区，再开始下载
+    claim_workspace(&app, &state, &workspace_id, client_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 同一对象的预签名链接每次查询串不同，用不含查询串的地址定位续传目录
+    let mut resume_key = parsed.clone();
+    resume_key.set_query(None);
+    resume_key.set_fragment(None);
+    let key = format!("{:x}", Sha256::digest(resume_key.as_str().as_bytes()));
+    let file_name = url_download::download_file_name(&parsed);
+    // 导入源必须是目录：以文件名为导入根，虚拟路径形如 `<文件名>/<文件名>/...`
+    let download_root = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {e}"))?
+        .join("downloads")
+        .join(&key[..16]);
+    let import_dir = download_root.join(&file_name);
+    tokio::fs::create_dir_all(&import_dir)
+        .await
+        .map_err(|e| format!("Failed to create download dir: {e}"))?;
+    let dest = import_dir.join(&file_name);
+
+    let scheduler = state
+        .get_task_scheduler()
+        .ok_or("Task manager not initialized")?;
+    let task_manager = state
+        .get_task_manager_clone()
+        .ok_or("Task manager not initialized")?;
+    let task_id = uuid::Uuid::new_v4().to_string();
+    let handle = TaskHandle::new(&task_id);
+    scheduler
+        .create(&task_id, "Download", &file_name, Some(&workspace_id))
+        .await
+        .map_err(|e| format!("Failed to create task: {e}"))?;
+
+    if !dest.exists() {
+        let progress_scheduler = Arc::clone(&scheduler);
+        let progress_handle = handle.clone();
+        let on_progress = move |done: u64, total: Option<u64>| {
+            let percent = total
+                .filter(|t| *t > 0)
+                .map(|t| (done.saturating_mul(100) / t).min(99) as u8)
+                .unwrap_or(0);
+            let message = match total {
+                Some(t) => format!("Downloading {done} / {t} bytes"),
+                None => format!("Downloading {done} bytes"),
+            };
+            let scheduler = Arc::clone(&progress_scheduler);
+            let handle = progress_handle.clone();
+            tokio::spawn(async move {
+                let _ = scheduler.update(&handle, percent, &message).await;
+            });
+        };
+        // 取消下载任务（`cancel_task`）时中止下载，已下载部分保留供续传
+        let cancel = task_manager.cancellation_token(&task_id);
+        if let Err(e) = url_download::download_with_resume(
+            &parsed,
+            &dest,
+            expected.as_deref(),
+            &cancel,
+            on_progress,
+        )
+        .await
+        {
+            let _ = scheduler.fail(&handle, &e).await;
+            return Err(e);
+        }
+    } else if let Some(expected) = expected.as_deref() {
+        // 上次下载完成但导入失败：复用前重新校验
+        let path = dest.clone();
+        let actual = tokio::task::spawn_blocking(move || url_download::sha256_file(&path))
+            .await
+            .map_err(|e| format!("Checksum task failed: {e}"))?
+            .map_err(|e| format!("Failed to hash download: {e}"))?;
+        if actual != expected {
+            let _ = tokio::fs::remove_file(&dest).await;
+            let msg = format!("SHA-256 mismatch: expected {expected}, got {actual}");
+            let _ = scheduler.fail(&handle, &msg).await;
+            return Err(msg);
+        }
+    }
+    let _ = scheduler.update(&handle, 100, "Download complete").await;
+    let _ = scheduler.complete(&handle).await;
+
+    let import_task_id = import_folder(
+        app,
+        import_dir.to_string_lossy().to_string(),
+        workspace_id,
+        None,
+        None,
+        None,
+        None,
+        client_id,
+        state,
+    )
+    .await?;
+
+    if let Err(e) = tokio::fs::remove_dir_all(&download_root).await {
+        tracing::warn!(path = %download_root.display(), error = %e, "Failed to remove downloaded archive");
+    }
+    Ok(import_task_id)
+}
+
+/// 开始分块上传本地大文件，返回上传 ID
+///
+/// 用于通过文件对话框导入的超大压缩包：分块经 `append_chunk` 直接写入暂存文件，
+/// 边写边计算 SHA-256，`finish_upload` 校验后导入工作区。
+#[tauri::command]
+pub async fn begin_upload(
+    app: AppHandle,
+    workspace_id: String,
+    #[allow(non_snake_case)] fileName: String,
+    #[allow(non_snake_case)] totalSize: u64,
+    #[allow(non_snake_case)] expectedSha256: Option<String>,
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    use crate::infrastructure::url_download::normalize_sha256;
+    use crate::services::chunked_upload::UploadRequest;
+    use la_core::utils::{validate_and_sanitize_path, PathValidationResult, SecurityConfig};
+
+    crate::utils::validation::validate_workspace_id(&workspace_id)?;
+    let file_name = match validate_and_sanitize_path(fileName.trim(), &SecurityConfig::default()) {
+        PathValidationResult::Valid(name) | PathValidationResult::RequiresSanitization(_, name)
+            if !name.is_empty() && name != "." && name != ".." =>
+        {
+            name
+        }
+        _ => return Err(format!("Invalid file name: {fileName}")),
+    };
+    let expected_sha256 = expectedSha256
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(normalize_sha256)
+        .transpose()?;
+    // 先确认调用方可以写入该工作区，再接收数据
+    claim_workspace(&app, &state, &workspace_id, client_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let uploads = state
+        .workspace
+        .uploads()
+        .ok_or("Upload receiver not initialized")?;
+    uploads
+        .begin(UploadRequest {
+            workspace_id,
+            file_name,
+            total_size: totalSize,
+            expected_sha256,
+            client_id,
+        })
+        .await
+}
+
+/// 追加一个分块，返回已接收的总字节数
+///
+/// 请求体为原始字节（`invoke('append_chunk', bytes, { headers })`），避免 JSON 数组编码；
+/// 上传 ID 与分块起始偏移分别放在 `x-upload-id`、`x-upload-offset` 请求头中。
+/// 重发已写入的分块会被忽略。
+#[tauri::command]
+pub async fn append_chunk(
+    request: tauri::ipc::Request<'_>,
+    state: State<'_, AppState>,
+) -> Result<u64, String> {
+    let header = |name: &str| -> Result<&str, String> {
+        request
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| format!("Missing {name} header"))
+    };
+    let upload_id = header("x-upload-id")?;
+    let offset: u64 = header("x-upload-offset")?
+        .parse()
+        .map_err(|_| "Invalid x-upload-offset header".to_string())?;
+    let tauri::ipc::InvokeBody::Raw(data) = request.body() else {
+        return Err("Chunk body must be raw bytes".to_string());
+    };
+
+    let uploads = state
+        .workspace
+        .uploads()
+        .ok_or("Upload receiver not initialized")?;
+    uploads.append(upload_id, offset, data).await
+}
+
+/// 结束分块上传：校验长度与校验和后导入工作区，返回导入任务 ID
+///
+/// 校验失败时删除暂存文件；导入结束后（无论成败）暂存文件都会被删除。
+#[tauri::command]
+pub async fn finish_upload(
+    app: AppHandle,
+    #[allow(non_snake_case)] uploadId: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let uploads = state
+        .workspace
+        .uploads()
+        .ok_or("Upload receiver not initialized")?;
+    let upload = uploads.finish(&uploadId).await?;
+    tracing::info!(
+        workspace_id = %upload.workspace_id,
+        file = %upload.file_name,
+        size = upload.size,
+        sha256 = %upload.sha256,
+        "Chunked upload received"
+    );
+
+    let result = import_folder(
+        app,
+        upload.import_dir.to_string_lossy().to_string(),
+        upload.workspace_id.clone(),
+        None,
+        None,
+        None,
+        None,
+        upload.client_id.clone(),
+        state,
     )
+    .await;
+    if let Err(e) = tokio::fs::remove_dir_all(&upload.upload_dir).await {
+        tracing::warn!(path = %upload.upload_dir.display(), error = %e, "Failed to remove uploaded archive");
+    }
+    result
+}
+
+/// 取消分块上传并删除暂存文件
+#[tauri::command]
+pub async fn cancel_upload(
+    #[allow(non_snake_case)] uploadId: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if let Some(uploads) = state.workspace.uploads() {
+        uploads.cancel(&uploadId).await;
+    }
+    Ok(())
+}
+
+/// 快速扫描：不导入，边解压边搜索本地压缩包
+///
+/// 结果为近似结果（嵌套压缩包不展开，达到 `maxResults` 或搜索超时即停止），
+/// 确认有价值后可用 `promote_quick_scan` 导入为完整工作区。
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn quick_scan_archive(
+    app: AppHandle,
+    path: String,
+    query: String,
+    structuredQuery: Option<la_core::models::SearchQuery>,
+    maxResults: Option<usize>,
+    filters: Option<la_core::models::SearchFilters>,
+    state: State<'_, AppState>,
+) -> Result<crate::services::quick_scan::QuickScanResult, la_core::error::CommandError> {
+    use crate::commands::search::query::resolve_search_query;
+    use crate::commands::search::{load_search_runtime_config, validate_search_params};
+    use crate::infrastructure::searcher::QueryEngineLogSearcher;
+    use la_core::error::CommandError;
+
+    validate_search_params(&query)?;
+    let archive = std::path::PathBuf::from(&path);
+    if !archive.is_file() {
+        return Err(CommandError::new(
+            "NOT_FOUND",
+            format!("Archive not found: {path}"),
+        ));
+    }
+    if !la_archive::can_stream(&archive) {
+        return Err(CommandError::new(
+            "UNSUPPORTED_FORMAT",
+            "Quick scan does not support this archive format",
+        )
+        .with_help("Import the archive into a workspace instead"));
+    }
+
+    let rc = load_search_runtime_config(&app);
+    let max_results = maxResults.unwrap_or(rc.default_max_results).min(100_000);
+    let filters = filters.unwrap_or_default();
+    let (_, search_query) = resolve_search_query(
+        &query,
+        structuredQuery,
+        rc.case_sensitive,
+        "quick_scan_archive",
+    )?;
+    let deadline =
+        std::time::Instant::now() + std::time::Duration::from_secs(rc.timeout_seconds.max(1));
+
+    let mut result = tokio::task::spawn_blocking(move || {
+        let searcher = QueryEngineLogSearcher::new(64);
+        crate::services::quick_scan::quick_scan(
+            &archive,
+            &searcher,
+            &search_query,
+            &filters,
+            max_results,
+            Some(deadline),
+        )
+    })
     .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Quick scan failed: {e}")))?
+    .map_err(|e| CommandError::new("SEARCH_ERROR", e))?;
+
+    state.search.links().annotate(&mut result.entries);
+    Ok(result)
+}
+
+/// 导入预览：不导入，快速统计导入源的文件数、大小与压缩包嵌套深度
+///
+/// 只读取压缩包目录，并按本机基准基线估算导入耗时（没有基线时使用默认吞吐，
+/// 可先运行 `run_diagnostics_benchmark` 得到更准确的估算）。
+#[tauri::command]
+pub async fn preview_import(
+    app: AppHandle,
+    path: String,
+) -> Result<crate::services::import_preview::ImportPreview, la_core::error::CommandError> {
+    use la_core::error::CommandError;
+    use tauri::Manager;
+
+    let source = std::path::PathBuf::from(&path);
+    if !source.exists() {
+        return Err(CommandError::new(
+            "NOT_FOUND",
+            format!("Path not found: {path}"),
+        ));
+    }
+    let config = crate::utils::load_app_config(&app).unwrap_or_default();
+    let baseline = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| crate::benchmark::diagnostics::load_baseline(&dir));
+
+    tokio::task::spawn_blocking(move || {
+        crate::services::import_preview::preview_import(&source, &config, baseline.as_ref())
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Import preview failed: {e}")))?
+    .map_err(|e| CommandError::new("PREVIEW_ERROR", e))
+}
+
+/// 把快速扫描过的压缩包导入为完整工作区，返回导入任务 ID
+///
+/// 压缩包以硬链接（跨设备时复制）暂存到 `quick-scan/` 下再导入，
+/// 避免把同目录的其他文件一并导入；导入结束后删除暂存目录。
+#[tauri::command]
+pub async fn promote_quick_scan(
+    app: AppHandle,
+    path: String,
+    workspace_id: String,
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    use tauri::Manager;
+
+    crate::utils::validation::validate_workspace_id(&workspace_id)?;
+    let archive = std::path::PathBuf::from(&path);
+    if !archive.is_file() {
+        return Err(format!("Archive not found: {path}"));
+    }
+    let file_name = archive
+        .file_name()
+        .ok_or_else(|| format!("Invalid archive path: {path}"))?
+        .to_os_string();
+
+    // 与 URL 导入相同的布局：以文件名为导入根
+    let staging_root = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {e}"))?
+        .join("quick-scan")
+        .join(uuid::Uuid::new_v4().to_string());
+    let import_dir = staging_root.join(&file_name);
+    tokio::fs::create_dir_all(&import_dir)
+        .await
+        .map_err(|e| format!("Failed to create staging dir: {e}"))?;
+    let staged = import_dir.join(&file_name);
+    if tokio::fs::hard_link(&archive, &staged).await.is_err() {
+        if let Err(e) = tokio::fs::copy(&archive, &staged).await {
+            let _ = tokio::fs::remove_dir_all(&staging_root).await;
+            return Err(format!("Failed to stage archive: {e}"));
+        }
+    }
+
+    let result = import_folder(
+        app,
+        import_dir.to_string_lossy().to_string(),
+        workspace_id,
+        None,
+        None,
+        None,
+        None,
+        client_id,
+        state,
+    )
+    .await;
+    if let Err(e) = tokio::fs::remove_dir_all(&staging_root).await {
+        tracing::warn!(path = %staging_root.display(), error = %e, "Failed to remove quick scan staging dir");
+    }
+    result
 }
 
 /// 检查 RAR 支持状态（无 sidecar 依赖）
@@ -7,11 +7,24 @@
 //! - 日志配置管理（运行时调整日志级别与预设）
 //! - 实时文件监听
 //! - 虚拟文件树
-//! - 状态同步
+//! - 状态同步与客户端会话
 //! - 参数验证
+//! - 诊断（前端错误上报、本机性能基准）
+//! - 日志分析（静默检测）
 //! - 全局配置管理
+//! - 工作区模板
+//! - 操作注册表（命令面板与脚本宏）
+//! - 分析宏（录制与回放）
+//! - 助手工具服务（MCP / JSON-RPC）
+//! - 工作区只读共享与远程工作区挂载
 
+pub mod actions;
+pub mod analysis;
+pub mod analysis_macro;
+pub mod assistant;
+pub mod client_session;
 pub mod config;
+pub mod diagnostics;
 pub mod export;
 pub mod import;
 pub mod log_config;
@@ -21,6 +34,8 @@ pub mod validation;
 pub mod virtual_tree;
 pub mod watch;
 pub mod workspace;
+pub mod workspace_share;
+pub mod workspace_template;
 
 // TauriAppConfigProvider 已移至 adapters::tauri_config 模块
 // 保留 re-export 以保持向后兼容
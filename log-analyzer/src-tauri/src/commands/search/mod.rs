@@ -10,15 +10,23 @@
 
 pub(crate) mod query;
 
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
-use tauri::{command, AppHandle, State};
+use tauri::{command, AppHandle, State, Window};
 
 use la_core::error::CommandError;
-use la_core::models::{LogEntry, SearchFilters, SearchQuery};
+use la_core::models::{LogEntry, SearchFilters, SearchQuery, SearchResultSummary};
 
+use crate::application::search_concurrency::SearchConcurrencySnapshot;
+use crate::application::search_session::SearchSnapshotInfo;
 use crate::application::workspace_service::WorkspaceServiceRef;
 use crate::commands::search::query::resolve_search_query;
 use crate::models::AppState;
+use crate::services::query_lint::QueryLintReport;
+use crate::services::saved_results::{self, SavedResultsInfo};
+use crate::services::QueryPlanner;
+use crate::utils::workspace_guard::{authorize_workspace, caller_tenant};
 
 // ============================================================================
 // 公共类型
@@ -49,6 +57,8 @@ pub struct BinarySearchRequest {
 pub(crate) struct SearchRuntimeConfig {
     pub(crate) default_max_results: usize,
     pub(crate) case_sensitive: bool,
+    pub(crate) timeout_seconds: u64,
+    pub(crate) batch_max_bytes: usize,
 }
 
 impl Default for SearchRuntimeConfig {
@@ -56,6 +66,59 @@ impl Default for SearchRuntimeConfig {
         Self {
             default_max_results: 100_000,
             case_sensitive: false,
+            timeout_seconds: la_core::models::config::SearchConfig::default().timeout_seconds,
+            batch_max_bytes: la_core::models::config::SearchConfig::default().batch_max_bytes,
+        }
+    }
+}
+
+/// 工作区设置中保存搜索默认值的键
+pub(crate) const SEARCH_DEFAULTS_SETTING: &str = "search_defaults";
+
+/// 单次搜索结果上限的硬上限
+pub(crate) const MAX_SEARCH_RESULTS: usize = 100_000;
+
+/// 工作区级搜索默认值，覆盖应用配置；未设置的字段沿用应用配置
+///
+/// 优先级：命令参数（`maxResults`、`caseSensitive`、结构化查询中逐项的大小写）
+/// > 工作区默认值 > 应用配置。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSearchDefaults {
+    #[serde(default)]
+    pub case_sensitive: Option<bool>,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+impl SearchRuntimeConfig {
+    pub(crate) fn with_workspace_defaults(mut self, defaults: &WorkspaceSearchDefaults) -> Self {
+        if let Some(case_sensitive) = defaults.case_sensitive {
+            self.case_sensitive = case_sensitive;
+        }
+        if let Some(max_results) = defaults.max_results {
+            self.default_max_results = max_results;
+        }
+        self
+    }
+}
+
+/// 读取工作区搜索默认值；未设置或读取失败时返回空默认值
+pub(crate) async fn load_workspace_search_defaults(
+    metadata_store: &la_storage::MetadataStore,
+) -> WorkspaceSearchDefaults {
+    match metadata_store
+        .get_workspace_setting(SEARCH_DEFAULTS_SETTING)
+        .await
+    {
+        Ok(Some(json)) if !json.is_empty() => serde_json::from_str(&json).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Invalid workspace search defaults, ignoring");
+            WorkspaceSearchDefaults::default()
+        }),
+        Ok(_) => WorkspaceSearchDefaults::default(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load workspace search defaults");
+            WorkspaceSearchDefaults::default()
         }
     }
 }
@@ -66,6 +129,8 @@ pub(crate) fn load_search_runtime_config(app: &AppHandle) -> SearchRuntimeConfig
         Some(c) => SearchRuntimeConfig {
             default_max_results: c.search.max_results,
             case_sensitive: c.search.case_sensitive,
+            timeout_seconds: c.search.timeout_seconds,
+            batch_max_bytes: c.search.batch_max_bytes,
         },
         None => SearchRuntimeConfig::default(),
     }
@@ -125,6 +190,27 @@ pub(crate) fn resolve_workspace_id(
     }
 }
 
+/// 租户会话只能访问本租户会话发起的搜索；不可见时与会话不存在返回相同错误
+pub(crate) fn ensure_search_visible(
+    state: &AppState,
+    search_id: &str,
+    client_id: Option<&str>,
+) -> Result<(), CommandError> {
+    let tenant = caller_tenant(state, client_id)?;
+    if state
+        .sync
+        .clients()
+        .search_visible_to(search_id, tenant.as_deref())
+    {
+        return Ok(());
+    }
+    Err(CommandError::new(
+        "NOT_FOUND",
+        format!("Search session '{search_id}' not found"),
+    )
+    .with_help("The session was closed or expired; run the search again"))
+}
+
 // ============================================================================
 // Tauri 命令 — 搜索管理
 // ============================================================================
@@ -132,8 +218,10 @@ pub(crate) fn resolve_workspace_id(
 #[command]
 pub async fn cancel_search(
     #[allow(non_snake_case)] searchId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), CommandError> {
+    ensure_search_visible(&state, &searchId, clientId.as_deref())?;
     let manager = state.get_search_session_manager().ok_or_else(|| {
         CommandError::new("NOT_FOUND", "Search session manager not initialized")
             .with_help("Import a workspace first")
@@ -150,15 +238,290 @@ pub async fn fetch_search_page(
     #[allow(non_snake_case)] searchId: String,
     offset: usize,
     limit: usize,
+    #[allow(non_snake_case)] clientId: Option<String>,
 ) -> Result<la_search::SearchPageResult, CommandError> {
+    ensure_search_visible(&state, &searchId, clientId.as_deref())?;
     let manager = state.get_search_session_manager().ok_or_else(|| {
         CommandError::new("NOT_FOUND", "Search session manager not initialized")
             .with_help("Import a workspace first")
     })?;
 
-    manager
-        .fetch_search_page(&searchId, offset, limit)
-        .map_err(|e| e.into())
+    let mut page = manager.fetch_search_page(&searchId, offset, limit)?;
+    state.search.links().annotate(&mut page.entries);
+    Ok(page)
+}
+
+/// 单次最多翻译的条目数（前端只提交当前可见的条目）
+const MAX_TRANSLATE_ENTRIES: usize = 1000;
+
+/// 翻译可见条目：返回填充了 `translation` 的同一批条目（原文保留在 `content`）
+#[command]
+pub async fn translate_entries(
+    state: State<'_, AppState>,
+    mut entries: Vec<LogEntry>,
+) -> Result<Vec<LogEntry>, CommandError> {
+    let translation = state.search.translation();
+    if !translation.is_enabled() {
+        return Err(
+            CommandError::new("TRANSLATION_DISABLED", "Log translation is not enabled")
+                .with_help("Enable translation and choose a provider in settings"),
+        );
+    }
+    if entries.len() > MAX_TRANSLATE_ENTRIES {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            format!(
+                "Too many entries to translate ({}, max {MAX_TRANSLATE_ENTRIES})",
+                entries.len()
+            ),
+        )
+        .with_help("Translate only the visible entries"));
+    }
+
+    // 外部提供者可能较慢，放到阻塞线程执行
+    tokio::task::spawn_blocking(move || {
+        translation.annotate(&mut entries);
+        entries
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Translation task failed: {e}")))
+}
+
+/// 扫描前检查查询：致命问题返回错误，其余以 lint 报告返回（含可一键应用的改写）
+#[allow(clippy::too_many_arguments)]
+#[command]
+#[allow(non_snake_case)]
+pub async fn lint_search_query(
+    app: AppHandle,
+    query: String,
+    structuredQuery: Option<SearchQuery>,
+    filters: Option<SearchFilters>,
+    workspaceId: Option<String>,
+    caseSensitive: Option<bool>,
+    clientId: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<QueryLintReport, CommandError> {
+    validate_search_params(&query)?;
+    let mut rc = load_search_runtime_config(&app);
+    if let Some(workspace) = workspaceId.and_then(|id| state.get_workspace_service(&id)) {
+        authorize_workspace(&state, &workspace, clientId.as_deref()).await?;
+        rc = rc.with_workspace_defaults(
+            &load_workspace_search_defaults(workspace.metadata_store()).await,
+        );
+    }
+    let (_, mut sq) = resolve_search_query(
+        &query,
+        structuredQuery,
+        caseSensitive.unwrap_or(rc.case_sensitive),
+        "lint_search_query",
+    )?;
+
+    // 命令参数中的级别过滤优先于查询自带的过滤
+    if let Some(levels) = filters.map(|f| f.levels).filter(|l| !l.is_empty()) {
+        sq.filters
+            .get_or_insert(la_core::models::search::SearchFilters {
+                levels: None,
+                time_range: None,
+                file_pattern: None,
+            })
+            .levels = Some(levels);
+    }
+
+    QueryPlanner::validate_query(&sq).map_err(|e| {
+        CommandError::new("VALIDATION_ERROR", e.to_string())
+            .with_help("Fix the highlighted term before searching")
+    })
+}
+
+/// 搜索会话固定的数据快照（文件数、索引代次、固定时间）
+#[command]
+pub async fn get_search_snapshot(
+    state: State<'_, AppState>,
+    #[allow(non_snake_case)] searchId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
+) -> Result<SearchSnapshotInfo, CommandError> {
+    ensure_search_visible(&state, &searchId, clientId.as_deref())?;
+    let manager = state.get_search_session_manager().ok_or_else(|| {
+        CommandError::new("NOT_FOUND", "Search session manager not initialized")
+            .with_help("Import a workspace first")
+    })?;
+
+    manager.snapshot_info(&searchId).ok_or_else(|| {
+        CommandError::new(
+            "NOT_FOUND",
+            format!("No snapshot pinned for search '{searchId}'"),
+        )
+        .with_help("The session was closed or expired; run the search again")
+    })
+}
+
+/// 关闭搜索会话：取消进行中的搜索，释放固定的快照并删除结果
+#[command]
+pub async fn close_search_session(
+    state: State<'_, AppState>,
+    #[allow(non_snake_case)] searchId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
+) -> Result<bool, CommandError> {
+    ensure_search_visible(&state, &searchId, clientId.as_deref())?;
+    let manager = state.get_search_session_manager().ok_or_else(|| {
+        CommandError::new("NOT_FOUND", "Search session manager not initialized")
+            .with_help("Import a workspace first")
+    })?;
+
+    state.sync.clients().forget_search(&searchId);
+    Ok(manager.close_session(&searchId))
+}
+
+/// 当前搜索并发情况（总数与按来源细分）
+#[command]
+pub async fn get_active_searches_count(
+    state: State<'_, AppState>,
+) -> Result<SearchConcurrencySnapshot, CommandError> {
+    Ok(state.search.concurrency().snapshot())
+}
+
+// ============================================================================
+// Tauri 命令 — 保存的搜索结果
+// ============================================================================
+
+/// 打开保存的结果：新建的结果会话 ID 与保存时的清单
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedSavedResults {
+    pub search_id: String,
+    pub saved: SavedResultsInfo,
+}
+
+async fn saved_results_dir(
+    state: &AppState,
+    workspace_id: Option<String>,
+    client_id: Option<&str>,
+) -> Result<(String, PathBuf), CommandError> {
+    let ws_id = resolve_workspace_id(workspace_id, state)?;
+    let workspace = get_workspace_service_or_error(state, &ws_id).await?;
+    authorize_workspace(state, &workspace, client_id).await?;
+    let dir = workspace
+        .workspace_dir()
+        .join(saved_results::SAVED_RESULTS_DIR);
+    Ok((ws_id, dir))
+}
+
+/// 保存搜索会话的完整结果（压缩）连同查询与摘要，之后可用 `open_saved_results`
+/// 在不重新搜索的情况下重新打开
+///
+/// `summary` 为搜索完成事件中的摘要；未提供时由结果重新统计。结果超过单次搜索上限时
+/// 只保存前一部分并置位 `summary.truncated`。
+#[command]
+#[allow(non_snake_case, clippy::too_many_arguments)]
+pub async fn save_search_results(
+    state: State<'_, AppState>,
+    searchId: String,
+    workspaceId: Option<String>,
+    query: String,
+    filters: Option<SearchFilters>,
+    summary: Option<SearchResultSummary>,
+    name: Option<String>,
+    clientId: Option<String>,
+) -> Result<SavedResultsInfo, CommandError> {
+    ensure_search_visible(&state, &searchId, clientId.as_deref())?;
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| query.clone());
+    if name.chars().count() > saved_results::MAX_NAME_LEN {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            format!(
+                "Name too long (max {} characters)",
+                saved_results::MAX_NAME_LEN
+            ),
+        ));
+    }
+    let manager = state.get_search_session_manager().ok_or_else(|| {
+        CommandError::new("NOT_FOUND", "Search session manager not initialized")
+            .with_help("Import a workspace first")
+    })?;
+    if manager.is_running(&searchId) {
+        return Err(
+            CommandError::new("VALIDATION_ERROR", "The search is still running")
+                .with_help("Save the results after the search completes"),
+        );
+    }
+    let (workspace_id, dir) = saved_results_dir(&state, workspaceId, clientId.as_deref()).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let (entries, truncated) =
+            crate::commands::analysis::read_session_entries(&manager, &searchId)?;
+        let mut summary = summary.unwrap_or_else(|| saved_results::summarize(&entries));
+        summary.truncated |= truncated;
+        let info = SavedResultsInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            workspace_id,
+            query,
+            filters,
+            summary,
+            entry_count: entries.len(),
+            saved_at: chrono::Utc::now().timestamp(),
+        };
+        saved_results::save_results(&dir, &info, &entries)
+            .map_err(|e| CommandError::new("IO_ERROR", e))?;
+        Ok::<_, CommandError>(info)
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Saving results failed: {e}")))?
+}
+
+/// 工作区中保存的搜索结果（按保存时间从新到旧）
+#[command]
+#[allow(non_snake_case)]
+pub async fn list_saved_results(
+    state: State<'_, AppState>,
+    workspaceId: Option<String>,
+    clientId: Option<String>,
+) -> Result<Vec<SavedResultsInfo>, CommandError> {
+    let (_, dir) = saved_results_dir(&state, workspaceId, clientId.as_deref()).await?;
+    tokio::task::spawn_blocking(move || saved_results::list_saved_results(&dir))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Listing results failed: {e}")))?
+        .map_err(|e| CommandError::new("IO_ERROR", e))
+}
+
+/// 重新打开保存的结果：载入为已完成的结果会话，按返回的 `searchId` 用
+/// `fetch_search_page` 分页读取，不访问源文件
+#[command]
+#[allow(non_snake_case)]
+pub async fn open_saved_results(
+    state: State<'_, AppState>,
+    savedId: String,
+    workspaceId: Option<String>,
+    clientId: Option<String>,
+) -> Result<OpenedSavedResults, CommandError> {
+    let (_, dir) = saved_results_dir(&state, workspaceId, clientId.as_deref()).await?;
+    let manager = state.get_search_session_manager().ok_or_else(|| {
+        CommandError::new("NOT_FOUND", "Search session manager not initialized")
+            .with_help("Import a workspace first")
+    })?;
+
+    let search_id = uuid::Uuid::new_v4().to_string();
+    let id = search_id.clone();
+    let saved = tokio::task::spawn_blocking(move || {
+        let (saved, entries) = saved_results::load_saved_results(&dir, &savedId)
+            .map_err(|e| CommandError::new("IO_ERROR", e))?
+            .ok_or_else(|| {
+                CommandError::new("NOT_FOUND", format!("Saved results '{savedId}' not found"))
+                    .with_help("List the saved results of this workspace")
+            })?;
+        manager.restore_session(&id, &entries)?;
+        Ok::<_, CommandError>(saved)
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Opening results failed: {e}")))??;
+
+    if let Some(client_id) = &clientId {
+        state.sync.clients().track_search(client_id, &search_id);
+    }
+    Ok(OpenedSavedResults { search_id, saved })
 }
 
 // ============================================================================
@@ -166,39 +529,113 @@ pub async fn fetch_search_page(
 // ============================================================================
 
 #[tauri::command]
-#[allow(non_snake_case)]
+#[allow(non_snake_case, clippy::too_many_arguments)]
 pub async fn search_logs(
     app: AppHandle,
     query: String,
     structuredQuery: Option<SearchQuery>,
     workspaceId: Option<String>,
     maxResults: Option<usize>,
+    caseSensitive: Option<bool>,
     filters: Option<SearchFilters>,
+    origin: Option<String>,
+    clientId: Option<String>,
+    window: Window,
     state: State<'_, AppState>,
 ) -> Result<String, CommandError> {
     // ── 1. Validate ──
     validate_search_params(&query)?;
+    let clients = state.sync.clients();
+    if let Some(client_id) = &clientId {
+        if !clients.check_rate(client_id) {
+            return Err(CommandError::new(
+                "RATE_LIMITED",
+                format!("Too many searches from client '{client_id}'"),
+            )
+            .with_help("Wait a moment before searching again"));
+        }
+    }
+
+    // ── 2. Get WorkspaceService (pure lookup — workspace must be pre-created at import time) ──
+    let ws_id = resolve_workspace_id(workspaceId, &state)?;
+    let workspace = get_workspace_service_or_error(&state, &ws_id).await?;
+    authorize_workspace(&state, &workspace, clientId.as_deref()).await?;
 
-    // ── 2. Load config ──
-    let rc = load_search_runtime_config(&app);
+    // ── 3. Load config (app config overlaid with workspace defaults) ──
+    let rc = load_search_runtime_config(&app)
+        .with_workspace_defaults(&load_workspace_search_defaults(workspace.metadata_store()).await);
 
-    // ── 3. Resolve params ──
-    let mr = maxResults.unwrap_or(rc.default_max_results).min(100_000);
+    // ── 4. Resolve params — explicit arguments win over defaults ──
+    let mr = maxResults
+        .unwrap_or(rc.default_max_results)
+        .min(MAX_SEARCH_RESULTS);
     let f = filters.unwrap_or_default();
-    let (raw_terms, sq) =
-        resolve_search_query(&query, structuredQuery, rc.case_sensitive, "search_logs")?;
-    let ws_id = resolve_workspace_id(workspaceId, &state)?;
+    let (raw_terms, sq) = resolve_search_query(
+        &query,
+        structuredQuery,
+        caseSensitive.unwrap_or(rc.case_sensitive),
+        "search_logs",
+    )?;
+    // 未指定来源（用户 / 会话）时按客户端区分，桌面端无客户端 ID 时按窗口区分
+    let origin = origin
+        .filter(|o| !o.trim().is_empty())
+        .or_else(|| clientId.clone())
+        .unwrap_or_else(|| window.label().to_string());
 
-    // ── 4. Get WorkspaceService (pure lookup — workspace must be pre-created at import time) ──
-    let workspace = get_workspace_service_or_error(&state, &ws_id).await?;
+    let timeout = std::time::Duration::from_secs(rc.timeout_seconds.max(1));
 
     // ── 5. Execute search via WorkspaceService ──
     // CancellationToken lifecycle is managed by WorkspaceServiceImpl internally;
     // cancel_search goes through service.cancel_search() — no global HashMap needed.
-    let search_id = workspace.search(sq, raw_terms, f, mr).await.map_err(|e| {
-        CommandError::new("SEARCH_ERROR", format!("Failed to start search: {e}"))
-            .with_help("Try again with a simpler query")
-    })?;
+    let search_id = workspace
+        .search(
+            sq,
+            raw_terms,
+            f,
+            mr,
+            origin,
+            Some(timeout),
+            rc.batch_max_bytes,
+        )
+        .await
+        .map_err(|e| {
+            CommandError::new("SEARCH_ERROR", format!("Failed to start search: {e}"))
+                .with_help("Try again with a simpler query")
+        })?;
+
+    // 记在客户端会话名下，断开后由会话回收释放
+    if let Some(client_id) = &clientId {
+        clients.track_search(client_id, &search_id);
+    }
 
     Ok(search_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_defaults_override_only_set_fields() {
+        let base = SearchRuntimeConfig {
+            default_max_results: 5_000,
+            case_sensitive: false,
+            ..SearchRuntimeConfig::default()
+        };
+
+        let rc = base
+            .clone()
+            .with_workspace_defaults(&WorkspaceSearchDefaults {
+                case_sensitive: Some(true),
+                max_results: None,
+            });
+        assert!(rc.case_sensitive);
+        assert_eq!(rc.default_max_results, 5_000);
+
+        let defaults: WorkspaceSearchDefaults =
+            serde_json::from_str(r#"{"maxResults":200}"#).unwrap();
+        let rc = base.with_workspace_defaults(&defaults);
+        assert!(!rc.case_sensitive);
+        assert_eq!(rc.default_max_results, 200);
+    }
+}
@@ -1,21 +1,35 @@
 //! Virtual File Tree Commands
 //!
-//! Provides commands for accessing the virtual file tree structure
-//! and retrieving file content by hash from the Content-Addressable Storage.
+//! Provides commands for accessing the virtual file tree structure,
+//! retrieving file content by hash from the Content-Addressable Storage and
+//! finding the line for a time within a file. Both honour the per-content
+//! encoding, parser and timezone overrides.
 //!
 //! # P7 Consolidation
 //!
-//! Both commands now go through [`require_cas_workspace`], using the
+//! All commands now go through [`require_tenant_workspace`], using the
 //! pre-assembled WorkspaceService instead of creating standalone CAS /
 //! MetadataStore instances. This closes the last remaining bypass of the
 //! WorkspaceService seam in the command layer.
 
+use std::sync::Arc;
+
+use la_core::domain::{ContentOverrides, LogFileRepository};
+use la_core::utils::time_index::line_timestamp;
+use la_core::utils::{TimeIndex, TimeIndexPoint, TimestampParser};
+use la_storage::chunk_checksums::describe_ranges;
+use la_storage::{ContentAddressableStorage, DecompressedViewCache, GzipFrameStore, MetadataStore};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, State};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::application::virtual_tree::{build_tree_structure, VirtualTreeNode};
+use crate::infrastructure::CasLogFileRepository;
 use crate::models::AppState;
+use crate::services::file_overrides::CompiledOverrides;
+
+/// Lines read per chunk while looking for the first line at a time
+const SEEK_LINE_CHUNK_SIZE: usize = 4096;
 
 /// File content response
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,6 +39,121 @@ pub struct FileContentResponse {
     pub size: usize,
 }
 
+/// Where the viewer should jump for a requested time
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileTimeSeek {
+    /// First line (1-based) whose timestamp is at or after the time; `None`
+    /// when no line is that late
+    pub line: Option<usize>,
+    /// Whether the file's sparse time index let the scan skip ahead
+    pub indexed: bool,
+}
+
+/// Time to seek to: a full date-time, or a time of day on the file's first date
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SeekTime {
+    At(i64),
+    TimeOfDay(chrono::NaiveTime),
+}
+
+impl SeekTime {
+    fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if let Some(dt) = TimestampParser::parse_naive_datetime(value) {
+            return Ok(Self::At(dt.and_utc().timestamp()));
+        }
+        ["%H:%M:%S", "%H:%M"]
+            .iter()
+            .find_map(|format| chrono::NaiveTime::parse_from_str(value, format).ok())
+            .map(Self::TimeOfDay)
+            .ok_or_else(|| {
+                format!("Invalid time '{value}': use '14:32', '14:32:05' or '2024-01-15 14:32'")
+            })
+    }
+
+    /// Unix seconds, taking a time of day on the date of `first_timestamp`
+    fn resolve(self, first_timestamp: i64) -> i64 {
+        match self {
+            Self::At(ts) => ts,
+            Self::TimeOfDay(time) => chrono::DateTime::from_timestamp(first_timestamp, 0)
+                .map(|dt| dt.date_naive().and_time(time).and_utc().timestamp())
+                .unwrap_or(first_timestamp),
+        }
+    }
+}
+
+/// Scan from the time index checkpoint (or the start) for the first line at or
+/// after `seek`. Without an index a time of day uses the first timestamp seen.
+/// Lines are decoded and timestamped with `overrides` when set.
+fn first_line_at(
+    repo: &CasLogFileRepository,
+    hash: &str,
+    overrides: Option<&CompiledOverrides>,
+    index: Option<&TimeIndex>,
+    seek: SeekTime,
+) -> la_core::error::Result<Option<usize>> {
+    let mut target = match (index, seek) {
+        (Some(index), _) => Some(seek.resolve(index.first_timestamp())),
+        (None, SeekTime::At(ts)) => Some(ts),
+        (None, SeekTime::TimeOfDay(_)) => None,
+    };
+    let start = match (index, target) {
+        (Some(index), Some(target)) => index.seek(target),
+        _ => TimeIndexPoint::START,
+    };
+
+    let mut found = None;
+    repo.read_line_chunks_from_sync(
+        hash,
+        overrides.map(|o| o as &dyn ContentOverrides),
+        SEEK_LINE_CHUNK_SIZE,
+        start.offset,
+        start.line,
+        &mut |lines, chunk_start_line| {
+            for (i, line) in lines.iter().enumerate() {
+                let ts = match overrides {
+                    Some(overrides) => overrides.line_timestamp(line),
+                    None => line_timestamp(line),
+                };
+                let Some(ts) = ts else {
+                    continue;
+                };
+                let target = *target.get_or_insert_with(|| seek.resolve(ts));
+                if ts >= target {
+                    found = Some(chunk_start_line + i);
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        },
+    )?;
+    Ok(found)
+}
+
+/// Parse overrides recorded for `hash`; `None` when unset or unreadable.
+async fn content_overrides(metadata: &MetadataStore, hash: &str) -> Option<CompiledOverrides> {
+    match metadata.get_file_formats().await {
+        Ok(formats) => formats
+            .get(hash)
+            .map(CompiledOverrides::from_format)
+            .filter(|overrides| !overrides.is_empty()),
+        Err(e) => {
+            warn!(hash = %hash, error = %e, "Failed to load file overrides");
+            None
+        }
+    }
+}
+
+/// Decode content with the encoding override, otherwise require UTF-8.
+fn decode_content(bytes: Vec<u8>, overrides: Option<&CompiledOverrides>) -> Result<String, String> {
+    match overrides {
+        Some(overrides) => Ok(overrides.decode(&bytes)),
+        None => {
+            String::from_utf8(bytes).map_err(|e| format!("File content is not valid UTF-8: {e}"))
+        }
+    }
+}
+
 fn validate_file_hash(hash: &str) -> Result<(), String> {
     if hash.len() != 64 || !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
         return Err("Invalid file hash format".to_string());
@@ -32,15 +161,118 @@ fn validate_file_hash(hash: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Byte range `[offset, offset + length)` of 1-based `line`, with both ends
+/// moved back to char boundaries. Returns the slice and the full line length.
+fn line_slice(
+    content: &str,
+    line: usize,
+    offset: usize,
+    length: Option<usize>,
+) -> Result<(String, usize), String> {
+    let index = line
+        .checked_sub(1)
+        .ok_or_else(|| "Line numbers start at 1".to_string())?;
+    let text = la_core::utils::split_lines(content)
+        .nth(index)
+        .ok_or_else(|| format!("Line {line} is out of range"))?;
+    let floor = |i: usize| {
+        let mut i = i.min(text.len());
+        while !text.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    };
+    let start = floor(offset);
+    let end = length.map_or(text.len(), |len| floor(offset.saturating_add(len)));
+    Ok((text[start..end.max(start)].to_string(), text.len()))
+}
+
+/// Raw bytes of an object, checked against its chunk checksums when it has
+/// them. gzip objects are read through the cached decompressed view so
+/// previews don't decompress them every time.
+async fn read_object(
+    cas: &Arc<ContentAddressableStorage>,
+    views: &Arc<DecompressedViewCache>,
+    hash: &str,
+) -> Result<Vec<u8>, String> {
+    let cas = cas.clone();
+    let views = views.clone();
+    let hash = hash.to_string();
+    tokio::task::spawn_blocking(move || {
+        let view = views.view_path(&cas, &hash).unwrap_or_else(|e| {
+            warn!(hash = %hash, error = %e, "Failed to build decompressed view");
+            None
+        });
+        match view {
+            Some(path) => std::fs::read(&path).map_err(|e| e.to_string()),
+            None => cas
+                .read_content_verified_sync(&hash)
+                .map_err(|e| e.to_string()),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// One line of a framed gzip object (see `la_storage::gzip_frames`),
+/// decompressing only the frame holding it. `None` when the object has no
+/// frame copy, in which case the whole object is read as usual.
+async fn read_framed_line(
+    cas: &ContentAddressableStorage,
+    hash: &str,
+    overrides: Option<&CompiledOverrides>,
+    line: usize,
+    offset: usize,
+    length: Option<usize>,
+) -> Result<Option<(String, usize)>, String> {
+    let frames = GzipFrameStore::for_cas(cas);
+    let task_hash = hash.to_string();
+    let frame =
+        tokio::task::spawn_blocking(move || frames.read_frame_at_line(&task_hash, line as u64))
+            .await
+            .map_err(|e| e.to_string())?;
+    let (first_line, bytes) = match frame {
+        Ok(Some(frame)) => frame,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            warn!(hash = %hash, error = %e, "Failed to read gzip frame copy");
+            return Ok(None);
+        }
+    };
+    let text = decode_content(bytes, overrides)?;
+    if line == 0 {
+        return Err("Line numbers start at 1".to_string());
+    }
+    // 帧内的行号（帧从 `first_line` 开始）
+    let relative = line + 1 - first_line as usize;
+    line_slice(&text, relative, offset, length)
+        .map(Some)
+        .map_err(|_| format!("Line {line} is out of range"))
+}
+
 /// Read file content by SHA-256 hash.
 ///
 /// Uses the workspace's pre-assembled CAS instance (via WorkspaceService),
 /// rather than creating a standalone ContentAddressableStorage.
+///
+/// With `line` set only that line is returned, optionally limited to the byte
+/// range `offset..offset + length`; `size` is then the full line length. This
+/// is how the rest of a search result truncated by the workspace's max line
+/// length is fetched.
+///
+/// Large objects are checked chunk by chunk; a corrupted object is
+/// re-extracted from its parent archive when possible, otherwise the error
+/// names the corrupted byte ranges.
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn read_file_by_hash(
     app: AppHandle,
     #[allow(non_snake_case)] workspaceId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
     hash: String,
+    line: Option<usize>,
+    offset: Option<usize>,
+    length: Option<usize>,
     state: State<'_, AppState>,
 ) -> Result<FileContentResponse, String> {
     validate_file_hash(&hash)?;
@@ -52,10 +284,14 @@ pub async fn read_file_by_hash(
     );
 
     // ── Acquire workspace service (validates ID, resolves dir, checks CAS format) ──
-    let (service, _workspace_dir) =
-        crate::utils::workspace_guard::require_cas_workspace(&app, &state, &workspaceId)
-            .await
-            .map_err(|e| e.to_string())?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspaceId,
+        clientId.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
 
     let cas = service.cas();
 
@@ -64,14 +300,61 @@ pub async fn read_file_by_hash(
         return Err(format!("File not found: {hash}"));
     }
 
-    let content_bytes = cas
-        .read_content(&hash)
-        .await
-        .map_err(|e| format!("Failed to read file: {e}"))?;
+    let overrides = content_overrides(service.metadata_store(), &hash).await;
 
-    let size = content_bytes.len();
-    let content = String::from_utf8(content_bytes)
-        .map_err(|e| format!("File content is not valid UTF-8: {e}"))?;
+    if let Some(line) = line {
+        if let Some((content, size)) = read_framed_line(
+            cas,
+            &hash,
+            overrides.as_ref(),
+            line,
+            offset.unwrap_or(0),
+            length,
+        )
+        .await?
+        {
+            return Ok(FileContentResponse {
+                content,
+                hash,
+                size,
+            });
+        }
+    }
+
+    let content_bytes = match read_object(cas, service.views(), &hash).await {
+        Ok(bytes) => bytes,
+        // 块校验发现损坏：尝试从父压缩包重新解压后再读一次
+        Err(e) => {
+            let Some(ranges) = cas.corruption_log().get(&hash) else {
+                return Err(format!("Failed to read file: {e}"));
+            };
+            let ranges = describe_ranges(&ranges);
+            warn!(hash = %hash, ranges = %ranges, "Corrupted CAS object, re-extracting from parent archive");
+            let attempt = la_archive::reextract_object(cas, service.metadata_store(), &hash)
+                .await
+                .map_err(|e| {
+                    format!("File content is corrupted at {ranges}; re-extraction failed: {e}")
+                })?;
+            if !attempt.restored {
+                return Err(format!(
+                    "File content is corrupted at {ranges}; re-extraction from the parent archive failed: {}",
+                    attempt.reason.unwrap_or_default()
+                ));
+            }
+            read_object(cas, service.views(), &hash)
+                .await
+                .map_err(|e| format!("Failed to read file: {e}"))?
+        }
+    };
+
+    let content = decode_content(content_bytes, overrides.as_ref())?;
+    let (content, size) = match line {
+        Some(line) => line_slice(&content, line, offset.unwrap_or(0), length)?,
+        None => {
+            let size = content.len();
+            (content, size)
+        }
+    };
 
     debug!(
         hash = %hash,
@@ -86,6 +369,64 @@ pub async fn read_file_by_hash(
     })
 }
 
+/// Find the line the viewer should jump to for a time such as `14:32`.
+///
+/// A bare time of day is taken on the date of the file's first timestamp.
+/// Files imported with a sparse time index are read from the last checkpoint
+/// before the time instead of from the start.
+#[tauri::command]
+pub async fn seek_file_time(
+    app: AppHandle,
+    #[allow(non_snake_case)] workspaceId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
+    hash: String,
+    time: String,
+    state: State<'_, AppState>,
+) -> Result<FileTimeSeek, String> {
+    validate_file_hash(&hash)?;
+    let seek = SeekTime::parse(&time)?;
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspaceId,
+        clientId.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if !service.cas().exists(&hash) {
+        return Err(format!("File not found: {hash}"));
+    }
+
+    let repo = CasLogFileRepository {
+        metadata: service.metadata_store().clone(),
+        cas: service.cas().clone(),
+        views: Some(service.views().clone()),
+    };
+    // 索引缺失或读取失败时从头扫描
+    let index = match repo.get_time_indexes(std::slice::from_ref(&hash)).await {
+        Ok(mut indexes) => indexes
+            .remove(&hash)
+            .and_then(|bytes| TimeIndex::from_bytes(&bytes)),
+        Err(e) => {
+            warn!(hash = %hash, error = %e, "Failed to load time index");
+            None
+        }
+    };
+
+    let overrides = content_overrides(service.metadata_store(), &hash).await;
+
+    let indexed = index.is_some();
+    let line = tokio::task::spawn_blocking(move || {
+        first_line_at(&repo, &hash, overrides.as_ref(), index.as_ref(), seek)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to read file: {e}"))?;
+
+    Ok(FileTimeSeek { line, indexed })
+}
+
 /// Get virtual file tree structure.
 ///
 /// Uses the workspace's pre-assembled MetadataStore (via WorkspaceService),
@@ -94,6 +435,7 @@ pub async fn read_file_by_hash(
 pub async fn get_virtual_file_tree(
     app: AppHandle,
     #[allow(non_snake_case)] workspaceId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<Vec<VirtualTreeNode>, String> {
     info!(
@@ -102,10 +444,14 @@ pub async fn get_virtual_file_tree(
     );
 
     // ── Acquire workspace service ──
-    let (service, _workspace_dir) =
-        crate::utils::workspace_guard::require_cas_workspace(&app, &state, &workspaceId)
-            .await
-            .map_err(|e| e.to_string())?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspaceId,
+        clientId.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
 
     let metadata_store = service.metadata_store();
 
@@ -136,6 +482,39 @@ pub async fn get_virtual_file_tree(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_line_slice_returns_char_aligned_range_and_line_length() {
+        let content = "first\nabcé€xyz\nlast";
+        assert_eq!(
+            line_slice(content, 2, 0, Some(4)).unwrap(),
+            ("abc".to_string(), 11)
+        );
+        assert_eq!(
+            line_slice(content, 2, 3, None).unwrap(),
+            ("é€xyz".to_string(), 11)
+        );
+        assert_eq!(line_slice(content, 2, 50, Some(5)).unwrap().0, "");
+        assert!(line_slice(content, 0, 0, None).is_err());
+        assert!(line_slice(content, 4, 0, None).is_err());
+    }
+
+    #[test]
+    fn test_seek_time_accepts_time_of_day_and_date_time() {
+        let first = line_timestamp("2024-01-15 08:00:00").unwrap();
+        let seek = SeekTime::parse("14:32").unwrap();
+        assert_eq!(
+            seek.resolve(first),
+            line_timestamp("2024-01-15 14:32:00").unwrap()
+        );
+        assert_eq!(
+            SeekTime::parse("2024-01-16 09:00:00")
+                .unwrap()
+                .resolve(first),
+            line_timestamp("2024-01-16 09:00:00").unwrap()
+        );
+        assert!(SeekTime::parse("half past two").is_err());
+    }
+
     #[test]
     fn test_validate_file_hash_accepts_sha256() {
         let hash = "a3".repeat(32);
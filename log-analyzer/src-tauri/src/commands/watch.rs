@@ -8,29 +8,38 @@
 //! - StopOnlyCas / StopOnlyMeta / StopOnlyEvents 存根（stop_watch 不再需要外部依赖注入）
 //! - WatchUseCase 创建（逻辑已内联到 WorkspaceServiceImpl）
 
+use la_core::models::SearchQuery;
 use tauri::{AppHandle, State};
 
 use crate::models::AppState;
+use crate::services::watcher_budget::{WatcherBudgetSnapshot, WatcherStatus};
 use crate::utils::validation::validate_path_param;
 
 /// Start watching a workspace directory for file changes.
 ///
 /// Thin glue: validates parameters, looks up the workspace service, delegates.
+/// Returns the mode actually used; native watching downgrades to polling when
+/// the global watcher budget or OS watch limits are exhausted.
 #[tauri::command]
 pub async fn start_watch(
     app: AppHandle,
     #[allow(non_snake_case)] workspaceId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
     path: String,
     #[allow(non_snake_case)] _autoSearch: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<WatcherStatus, String> {
     validate_path_param(&path, "path")?;
 
     // ── Acquire workspace service (validates ID, resolves dir, checks CAS format) ──
-    let (workspace, _workspace_dir) =
-        crate::utils::workspace_guard::require_cas_workspace(&app, &state, &workspaceId)
-            .await
-            .map_err(|e| e.to_string())?;
+    let (workspace, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspaceId,
+        clientId.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
 
     // ── Delegate to service (watcher state lives inside the instance) ──
     workspace
@@ -46,13 +55,102 @@ pub async fn start_watch(
 pub async fn stop_watch(
     app: AppHandle,
     #[allow(non_snake_case)] workspaceId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     // ── Acquire workspace service ──
-    let (workspace, _workspace_dir) =
-        crate::utils::workspace_guard::require_cas_workspace(&app, &state, &workspaceId)
-            .await
-            .map_err(|e| e.to_string())?;
+    let (workspace, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspaceId,
+        clientId.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
 
     workspace.stop_watch().await.map_err(|e| e.to_string())
 }
+
+/// Global watcher budget: native/polling counts, estimated OS handle usage
+/// and the per-workspace mode (with fallback reason, detected network
+/// filesystem and current adaptive poll interval).
+#[tauri::command]
+pub async fn get_watcher_budget(
+    state: State<'_, AppState>,
+) -> Result<WatcherBudgetSnapshot, String> {
+    let mut snapshot = state.workspace.watcher_budget().snapshot();
+    for status in &mut snapshot.watchers {
+        if let Some(live) = state
+            .workspace
+            .get(&status.workspace_id)
+            .and_then(|svc| svc.watch_status())
+        {
+            *status = live;
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Attach a follow query to a workspace.
+///
+/// Every entry the watcher writes to the index is matched against attached
+/// queries; hits are pushed as `workspace-event` `NewLogs` with the query id.
+/// A query with the same id replaces the previous one. With `clientId` the
+/// query belongs to that client session and is detached when the session ends;
+/// a tenant-scoped client can only attach to its tenant's workspaces.
+#[tauri::command]
+pub async fn attach_follow_query(
+    app: AppHandle,
+    #[allow(non_snake_case)] workspaceId: String,
+    query: SearchQuery,
+    #[allow(non_snake_case)] clientId: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let (workspace, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspaceId,
+        clientId.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    workspace
+        .attach_follow_query(&query)
+        .map_err(|e| e.to_string())?;
+    if let Some(client_id) = &clientId {
+        state
+            .sync
+            .clients()
+            .track_follow_query(client_id, &workspaceId, &query.id);
+    }
+    Ok(workspace.follow_query_ids())
+}
+
+/// Detach a follow query; returns the remaining attached query ids.
+#[tauri::command]
+pub async fn detach_follow_query(
+    app: AppHandle,
+    #[allow(non_snake_case)] workspaceId: String,
+    #[allow(non_snake_case)] clientId: Option<String>,
+    #[allow(non_snake_case)] queryId: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let (workspace, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspaceId,
+        clientId.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if !workspace.detach_follow_query(&queryId) {
+        return Err(format!("Follow query not found: {queryId}"));
+    }
+    state
+        .sync
+        .clients()
+        .forget_follow_query(&workspaceId, &queryId);
+    Ok(workspace.follow_query_ids())
+}
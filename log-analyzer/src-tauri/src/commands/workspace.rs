@@ -29,15 +29,30 @@
 
 use std::{fs, path::Path, sync::Arc};
 
+use la_archive::internal::file_type_filter::FileTypeFilter;
+use la_archive::RepairAttempt;
 use la_core::error::{AppError, CommandError};
+use la_storage::CorruptedRange;
 use tauri::{AppHandle, Manager, State};
 use tracing::{error, info, warn};
 
 use crate::application::workspace_service::WorkspaceServiceRef;
-use crate::commands::import::import_folder;
+use crate::commands::import::{import_folder, import_folder_leased};
+use crate::infrastructure::index_compat::IndexCompatibility;
+use crate::infrastructure::workspace_service_factory::get_or_create_workspace_service;
 use crate::models::AppState;
+use crate::services::field_extractors::{
+    self, ExtractionPreview, FieldExtractor, FieldExtractorSet,
+};
+use crate::services::import_preview::estimate_import_duration;
+use crate::services::refresh_preview::{self, KnownEntry, RefreshPreview};
+use crate::services::webhooks::{self, Webhook, WebhookInfo};
+use crate::state_sync::LeaseOperation;
 use crate::utils::validation::validate_workspace_id;
-use crate::utils::workspace_paths::{build_workspace_id, resolve_workspace_dir};
+use crate::utils::workspace_guard::caller_tenant;
+use crate::utils::workspace_paths::{
+    build_workspace_id, resolve_workspace_dir, tenant_of_workspace_dir,
+};
 
 /// 关闭工作区数据库连接（MetadataStore + SearchEngine）。
 /// 委托给 WorkspaceService::close_databases()。
@@ -53,6 +68,8 @@ pub struct WorkspaceLoadResponse {
     pub success: bool,
     /// Number of files loaded
     pub file_count: usize,
+    /// Whether the index/metadata was written by a compatible app version
+    pub index_compatibility: IndexCompatibility,
 }
 
 /// 加载工作区索引
@@ -68,17 +85,39 @@ pub struct WorkspaceLoadResponse {
 pub async fn load_workspace(
     app: AppHandle,
     workspace_id: String,
+    client_id: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<WorkspaceLoadResponse, CommandError> {
     // ── Acquire workspace service (validates ID, resolves dir, checks CAS, creates service) ──
-    let (service, _workspace_dir) =
-        crate::utils::workspace_guard::require_cas_workspace(&app, &state, &workspace_id).await?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
 
     let file_count =
         service.metadata_store().count_files().await.map_err(|e| {
             CommandError::new("DATABASE_ERROR", format!("Failed to count files: {e}"))
         })? as usize;
 
+    let index_state = service
+        .metadata_store()
+        .load_index_state(&workspace_id)
+        .await
+        .map_err(|e| {
+            CommandError::new("DATABASE_ERROR", format!("Failed to load index state: {e}"))
+        })?;
+    let index_compatibility = IndexCompatibility::assess(index_state.as_ref());
+    if index_compatibility != IndexCompatibility::Compatible {
+        warn!(
+            workspace_id = %workspace_id,
+            compatibility = ?index_compatibility,
+            "Workspace loaded with index compatibility issue"
+        );
+    }
+
     // Broadcast workspace loaded event
     let state_sync_opt = state.get_state_sync();
     if let Some(state_sync) = state_sync_opt {
@@ -98,6 +137,7 @@ pub async fn load_workspace(
     Ok(WorkspaceLoadResponse {
         success: true,
         file_count,
+        index_compatibility,
     })
 }
 
@@ -105,13 +145,45 @@ pub async fn load_workspace(
 ///
 /// 注意：CAS架构下，刷新操作等同于重新导入
 /// 因为CAS自动处理去重，重新导入是最简单可靠的方式
+///
+/// 刷新期间持有工作区的操作租约；已有导入 / 刷新 / 删除在进行时返回
+/// `WORKSPACE_BUSY`，传入 `wait_secs` 则排队等待至多该秒数。
+///
+/// 搜索不受刷新阻塞：刷新期间提交的搜索基于刷新前冻结的文件列表与索引代次，
+/// 刷新结束后提交的搜索才会看到新数据。
+///
+/// 租户会话只能刷新本租户的工作区（不存在时按导入处理并归属该租户）。
 #[tauri::command]
 pub async fn refresh_workspace(
     app: AppHandle,
     workspace_id: String,
     path: Option<String>,
+    client_id: Option<String>,
+    wait_secs: Option<u64>,
     state: State<'_, AppState>,
 ) -> Result<String, CommandError> {
+    crate::utils::workspace_guard::ensure_writable(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let _lease = crate::utils::workspace_guard::acquire_workspace_lease(
+        &state,
+        &workspace_id,
+        LeaseOperation::Refresh,
+        client_id.clone(),
+        wait_secs,
+    )
+    .await?;
+    crate::utils::workspace_guard::claim_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
     let path = resolve_refresh_source_path(&app, &workspace_id, path)?;
 
     info!(
@@ -132,7 +204,7 @@ pub async fn refresh_workspace(
     // Check if workspace exists and is CAS format
     if !workspace_dir.exists() {
         info!("Workspace not found, performing fresh import");
-        return import_folder(app, path, workspace_id, state)
+        return import_folder_leased(app, path, workspace_id, None, None, None, None, &state)
             .await
             .map_err(|e| CommandError::new("IMPORT_ERROR", e));
     }
@@ -142,7 +214,7 @@ pub async fn refresh_workspace(
 
     if !metadata_db.exists() || !objects_dir.exists() {
         info!("Workspace is not CAS format, performing fresh import");
-        return import_folder(app, path, workspace_id, state)
+        return import_folder_leased(app, path, workspace_id, None, None, None, None, &state)
             .await
             .map_err(|e| CommandError::new("IMPORT_ERROR", e));
     }
@@ -151,11 +223,344 @@ pub async fn refresh_workspace(
     // CAS handles deduplication automatically, so re-importing is safe and simple
     info!("CAS workspace detected, re-importing for refresh");
 
-    import_folder(app, path, workspace_id, state)
+    // 刷新期间新提交的搜索沿用刷新前的数据视图，导入结束（成功或失败）后再发布
+    let service =
+        get_or_create_workspace_service(&app, &state, &workspace_id, &workspace_dir).await?;
+    let _frozen_view = match service.freeze_search_view().await {
+        Ok(guard) => Some(guard),
+        Err(e) => {
+            warn!(
+                workspace_id = %workspace_id,
+                error = %e,
+                "Failed to freeze search view; searches will see the refresh in progress"
+            );
+            None
+        }
+    };
+
+    import_folder_leased(app, path, workspace_id, None, None, None, None, &state)
         .await
         .map_err(|e| CommandError::new("IMPORT_ERROR", e))
 }
 
+/// 刷新预览：比较源目录与工作区元数据，报告刷新将新增、变更、删除的文件
+///
+/// 不解压、不建索引；文件按大小与修改时间比较，只有修改时间变化的文件才计算哈希确认，
+/// `verify_hashes` 为 true 时对所有文件计算哈希。`path` 缺省为工作区保存的源路径，
+/// 与 `refresh_workspace` 相同。结果附带按本机基准估算的完整刷新耗时。
+#[tauri::command]
+pub async fn preview_refresh(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    path: Option<String>,
+    verify_hashes: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<RefreshPreview, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let path = resolve_refresh_source_path(&app, &workspace_id, path)?;
+    let source = std::path::PathBuf::from(&path);
+    if !source.exists() {
+        return Err(CommandError::new("NOT_FOUND", "Source path does not exist")
+            .with_help("The source folder may have been moved or deleted"));
+    }
+
+    let store = service.metadata_store();
+    let files = store.get_all_files().await.map_err(|e| {
+        CommandError::new(
+            "DATABASE_ERROR",
+            format!("Failed to list workspace files: {e}"),
+        )
+    })?;
+    let archives = store.get_all_archives().await.map_err(|e| {
+        CommandError::new(
+            "DATABASE_ERROR",
+            format!("Failed to list workspace archives: {e}"),
+        )
+    })?;
+    let known = KnownEntry::from_metadata(&files, &archives);
+
+    let config = crate::utils::load_app_config(&app).unwrap_or_default();
+    let baseline = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .and_then(|dir| crate::benchmark::diagnostics::load_baseline(&dir));
+
+    let preview = tokio::task::spawn_blocking(move || {
+        let filter = (config.file_filter.enabled || config.file_filter.binary_detection_enabled)
+            .then(|| FileTypeFilter::new(config.file_filter.clone()));
+        let mut preview = refresh_preview::preview_refresh(
+            &source,
+            known,
+            filter.as_ref(),
+            verify_hashes.unwrap_or(false),
+        )?;
+        preview.estimated_duration_ms = estimate_import_duration(
+            preview.source_bytes,
+            preview.source_bytes,
+            preview.new_count + preview.changed_count + preview.unchanged_count,
+            baseline.as_ref(),
+        )
+        .as_millis() as u64;
+        Ok::<_, String>(preview)
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Refresh preview failed: {e}")))?
+    .map_err(|e| CommandError::new("PREVIEW_ERROR", e))?;
+
+    info!(
+        workspace_id = %workspace_id,
+        new = preview.new_count,
+        changed = preview.changed_count,
+        deleted = preview.deleted_count,
+        "Refresh preview computed"
+    );
+    Ok(preview)
+}
+
+/// 一个损坏对象的检查与修复结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptedObjectReport {
+    pub hash: String,
+    /// 引用该对象的文件
+    pub virtual_paths: Vec<String>,
+    pub ranges: Vec<CorruptedRange>,
+    pub repair: RepairAttempt,
+}
+
+/// `repair_corrupted_objects` 的结果
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectRepairReport {
+    /// 本次逐块校验的对象数（未全量扫描时为 0）
+    pub checked: usize,
+    pub corrupted: Vec<CorruptedObjectReport>,
+}
+
+/// 修复损坏的 CAS 对象
+///
+/// 默认只处理预览、搜索扫描时块校验已发现的损坏；`full_scan` 为 true 时先对所有带
+/// 块校验清单的对象逐块校验。损坏对象尝试从父压缩包重新解压，无法修复的附带原因。
+#[tauri::command]
+pub async fn repair_corrupted_objects(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    full_scan: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ObjectRepairReport, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let cas = service.cas().clone();
+    let store = service.metadata_store();
+    let files = store.get_all_files().await.map_err(|e| {
+        CommandError::new(
+            "DATABASE_ERROR",
+            format!("Failed to list workspace files: {e}"),
+        )
+    })?;
+
+    let mut checked = 0;
+    if full_scan.unwrap_or(false) {
+        let archives = store.get_all_archives().await.map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to list workspace archives: {e}"),
+            )
+        })?;
+        let mut hashes: Vec<String> = files
+            .iter()
+            .map(|f| f.sha256_hash.clone())
+            .chain(archives.into_iter().map(|a| a.sha256_hash))
+            .collect();
+        hashes.sort();
+        hashes.dedup();
+        let scan_cas = cas.clone();
+        checked = tokio::task::spawn_blocking(move || {
+            hashes
+                .iter()
+                .filter(|hash| match scan_cas.scan_chunks_sync(hash) {
+                    Ok(scanned) => scanned.is_some(),
+                    Err(e) => {
+                        warn!(hash = %hash, error = %e, "Failed to check object chunks");
+                        false
+                    }
+                })
+                .count()
+        })
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Chunk check failed: {e}")))?;
+    }
+
+    let mut corrupted = Vec::new();
+    for (hash, ranges) in cas.corruption_log().snapshot() {
+        let repair = la_archive::reextract_object(&cas, store, &hash).await?;
+        let virtual_paths = files
+            .iter()
+            .filter(|f| f.sha256_hash == hash)
+            .map(|f| f.virtual_path.clone())
+            .collect();
+        corrupted.push(CorruptedObjectReport {
+            hash,
+            virtual_paths,
+            ranges,
+            repair,
+        });
+    }
+
+    info!(
+        workspace_id = %workspace_id,
+        checked,
+        corrupted = corrupted.len(),
+        repaired = corrupted.iter().filter(|c| c.repair.restored).count(),
+        "Corrupted object repair finished"
+    );
+    Ok(ObjectRepairReport { checked, corrupted })
+}
+
+/// 列出解压或导入失败、可重试的压缩包条目（按压缩包分组排序）
+#[tauri::command]
+pub async fn get_failed_entries(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_storage::FailedEntryRecord>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .get_failed_entries()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to load failed entries: {e}"),
+            )
+        })
+}
+
+/// 重试导入时失败的压缩包条目
+///
+/// 只处理 `get_failed_entries` 中记录的条目：从 CAS 中保存的父压缩包重新取出并导入，
+/// 成功的条目建立索引并移出失败列表，仍失败的更新错误信息。重试期间持有工作区的
+/// 导入租约，已有导入 / 刷新 / 删除在进行时返回 `WORKSPACE_BUSY`。
+#[tauri::command]
+pub async fn retry_failed_extractions(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    wait_secs: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<la_archive::RetryReport, CommandError> {
+    let (service, workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let _lease = crate::utils::workspace_guard::acquire_workspace_lease(
+        &state,
+        &workspace_id,
+        LeaseOperation::Import,
+        client_id,
+        wait_secs,
+    )
+    .await?;
+    let store = Arc::clone(service.metadata_store());
+    let cas = Arc::clone(service.cas());
+
+    let hooks = store
+        .get_workspace_setting(la_archive::post_extract::POST_EXTRACT_HOOKS_SETTING)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let context =
+        la_archive::CasProcessingContext::new(workspace_dir, Arc::clone(&cas), Arc::clone(&store))
+            .with_post_extract_hooks(hooks);
+    let provider = crate::adapters::tauri_config::TauriAppConfigProvider(app.clone());
+    let task_id = format!("retry-{}", uuid::Uuid::new_v4());
+    let report =
+        la_archive::retry_failed_entries(&context, &provider, &task_id, &workspace_id).await?;
+
+    // 恢复的条目可能是嵌套压缩包，其下的文件一并建立索引
+    if !report.recovered.is_empty() {
+        let files: Vec<_> = store
+            .get_all_files()
+            .await
+            .map_err(|e| {
+                CommandError::new(
+                    "DATABASE_ERROR",
+                    format!("Failed to list workspace files: {e}"),
+                )
+            })?
+            .into_iter()
+            .filter(|f| {
+                report.recovered.iter().any(|path| {
+                    f.virtual_path == *path
+                        || f.virtual_path
+                            .strip_prefix(path.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+            })
+            .collect();
+        let formats =
+            crate::infrastructure::workspace_service_impl::load_file_formats(&store).await;
+        let search_manager = Arc::clone(service.search_engine());
+        tokio::task::spawn_blocking(move || -> Result<(), String> {
+            let (_, _, mut next_id) = search_manager
+                .get_time_range()
+                .map_err(|e| format!("Failed to read index size: {e}"))?;
+            for file in &files {
+                next_id += crate::infrastructure::workspace_service_impl::index_file_documents(
+                    &search_manager,
+                    &cas,
+                    file,
+                    formats.get(&file.sha256_hash),
+                    next_id,
+                )?
+                .documents;
+            }
+            search_manager
+                .commit()
+                .map_err(|e| format!("Failed to commit search index: {e}"))
+        })
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Index update panicked: {e}")))?
+        .map_err(|e| CommandError::new("SEARCH_ERROR", e))?;
+    }
+
+    info!(
+        workspace_id = %workspace_id,
+        retried = report.retried,
+        recovered = report.recovered.len(),
+        still_failed = report.still_failed.len(),
+        "Failed archive entry retry finished"
+    );
+    Ok(report)
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct StoredWorkspaceConfig {
     id: String,
@@ -320,6 +725,9 @@ async fn cleanup_workspace_resources(
             "No workspace service found, skipping watcher stop"
         );
     }
+    if crate::commands::workspace_share::stop_share(workspace_id) {
+        info!(workspace_id = %workspace_id, "Workspace share stopped");
+    }
 
     // ===== 步骤2: 清除搜索缓存 =====
     // 优化决策: 不主动清理搜索缓存,依赖LRU自动淘汰机制
@@ -550,9 +958,14 @@ async fn cleanup_workspace_resources(
 /// 删除工作区命令
 ///
 /// Tauri命令接口,用于删除工作区及其所有相关资源。
+///
+/// 删除期间持有工作区的操作租约，不会与进行中的导入 / 刷新交错执行。
+/// 租户会话只能删除本租户的工作区。
 #[tauri::command]
 pub async fn delete_workspace(
     workspace_id: String,
+    client_id: Option<String>,
+    wait_secs: Option<u64>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<(), CommandError> {
@@ -563,6 +976,21 @@ pub async fn delete_workspace(
 
     // 参数验证
     validate_workspace_id(&workspace_id).map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    crate::utils::workspace_guard::ensure_writable(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let _lease = crate::utils::workspace_guard::acquire_workspace_lease(
+        &state,
+        &workspace_id,
+        LeaseOperation::Delete,
+        client_id,
+        wait_secs,
+    )
+    .await?;
 
     // 执行清理
     cleanup_workspace_resources(&workspace_id, &state, &app).await?;
@@ -594,7 +1022,7 @@ pub async fn delete_workspace(
 
 /// 取消任务命令
 ///
-/// 将任务状态设置为 Stopped
+/// 将任务状态设置为 Stopped；登记了取消令牌的任务（如导入前的目录扫描）随之停止
 #[tauri::command]
 pub async fn cancel_task(task_id: String, state: State<'_, AppState>) -> Result<(), CommandError> {
     info!(
@@ -632,20 +1060,29 @@ pub struct WorkspaceStatusResponse {
     pub status: String,
     pub size: String,
     pub files: usize,
+    /// 导入进行中时的估算完成百分比（已导入的文件可以搜索）
+    #[serde(rename = "importProgress", skip_serializing_if = "Option::is_none")]
+    pub import_progress: Option<u8>,
 }
 
 /// 获取工作区状态命令
 ///
-/// 返回工作区的详细信息
+/// 返回工作区的详细信息；导入进行中时状态为 `PROCESSING` 并附带导入进度
 #[tauri::command]
 pub async fn get_workspace_status(
     workspace_id: String,
+    client_id: Option<String>,
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<WorkspaceStatusResponse, CommandError> {
     // ── Acquire workspace service (validates ID, resolves dir, checks CAS, creates service) ──
-    let (service, _workspace_dir) =
-        crate::utils::workspace_guard::require_cas_workspace(&app, &state, &workspace_id).await?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
 
     let file_count: i64 = service.metadata_store().count_files().await.unwrap_or(0);
 
@@ -665,63 +1102,1724 @@ pub async fn get_workspace_status(
         format!("{size_mb}MB")
     };
 
+    let import_progress = service.import_progress();
     Ok(WorkspaceStatusResponse {
         id: workspace_id.clone(),
         name: resolve_workspace_display_name(&app, &workspace_id)
             .unwrap_or_else(|| workspace_id.clone()),
-        status: "READY".to_string(),
+        status: if import_progress.is_some() {
+            "PROCESSING"
+        } else {
+            "READY"
+        }
+        .to_string(),
         size: size_str,
         files: file_count as usize,
+        import_progress,
     })
 }
 
-/// 获取工作区日志时间范围
+/// 工作区 CAS 去重报告响应
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DedupReportResponse {
+    pub workspace_id: String,
+    #[serde(flatten)]
+    pub report: la_storage::DedupReport,
+}
+
+/// 获取工作区去重报告命令
 ///
-/// 从 Tantivy 索引中查询最早和最晚的日志时间戳
+/// 基于已存储的哈希统计每个 CAS 对象对应的逻辑文件数、去重节省的字节数，
+/// 以及节省最多的重复文件（默认前 20 个）
 #[tauri::command]
-pub async fn get_workspace_time_range(
+pub async fn get_dedup_report(
+    workspace_id: String,
+    client_id: Option<String>,
+    top_n: Option<usize>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DedupReportResponse, CommandError> {
+    const DEFAULT_TOP_N: usize = 20;
+    const MAX_TOP_N: usize = 500;
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let top_n = top_n.unwrap_or(DEFAULT_TOP_N).min(MAX_TOP_N);
+    let report = service
+        .metadata_store()
+        .get_dedup_report(top_n)
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to compute dedup report: {e}"),
+            )
+        })?;
+
+    Ok(DedupReportResponse {
+        workspace_id,
+        report,
+    })
+}
+
+/// 列出选择性解压时未被包含列表选中的压缩包条目（按压缩包分组排序）
+#[tauri::command]
+pub async fn get_skipped_entries(
+    workspace_id: String,
+    client_id: Option<String>,
     app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_storage::SkippedEntryRecord>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .get_skipped_entries()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to load skipped entries: {e}"),
+            )
+        })
+}
+
+/// 工作区元数据（来自支持包清单）、标签与建议名称
+///
+/// 导入时识别 `manifest.json` / `info.txt`，没有清单时返回空元数据且无建议名称。
+#[tauri::command]
+pub async fn get_workspace_manifest(
     workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<la_core::models::search::WorkspaceTimeRange, CommandError> {
-    use chrono::DateTime;
-    use la_core::models::search::WorkspaceTimeRange;
+) -> Result<crate::services::bundle_manifest::ManifestInfo, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
 
-    // ── Acquire workspace service ──
-    let (service, _workspace_dir) =
-        crate::utils::workspace_guard::require_cas_workspace(&app, &state, &workspace_id).await?;
-    let manager: Arc<la_search::SearchEngineManager> = Arc::clone(service.search_engine());
-    let (min_ts, max_ts, total_logs) = manager.get_time_range().map_err(|e| {
-        CommandError::new(
-            "SEARCH_ERROR",
-            format!("Failed to get time range from index: {e}"),
-        )
-    })?;
+    let metadata = service
+        .metadata_store()
+        .get_workspace_metadata()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to load workspace metadata: {e}"),
+            )
+        })?;
+    Ok(crate::services::bundle_manifest::ManifestInfo::from_metadata(metadata))
+}
 
-    // Convert timestamps to ISO 8601 format
-    let min_timestamp = if min_ts > 0 {
-        Some(
-            DateTime::from_timestamp(min_ts, 0)
-                .map_or_else(|| min_ts.to_string(), |dt| dt.to_rfc3339()),
-        )
-    } else {
-        None
+/// 解压后处理钩子设置
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostExtractHooksResponse {
+    pub hooks: Vec<la_archive::PostExtractHook>,
+    /// 可在 `plugin` 钩子中使用的已注册插件
+    pub available_plugins: Vec<String>,
+}
+
+/// 读取工作区的解压后处理钩子
+#[tauri::command]
+pub async fn get_post_extract_hooks(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PostExtractHooksResponse, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let saved = service
+        .metadata_store()
+        .get_workspace_setting(la_archive::post_extract::POST_EXTRACT_HOOKS_SETTING)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    let hooks = saved
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    Ok(PostExtractHooksResponse {
+        hooks,
+        available_plugins: la_archive::post_extract::registered_plugins(),
+    })
+}
+
+/// 保存工作区的解压后处理钩子，从下一次导入或刷新起生效
+#[tauri::command]
+pub async fn set_post_extract_hooks(
+    workspace_id: String,
+    client_id: Option<String>,
+    hooks: Vec<la_archive::PostExtractHook>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    la_archive::post_extract::validate_hooks(&hooks)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let json = serde_json::to_string(&hooks)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e.to_string()))?;
+    service
+        .metadata_store()
+        .set_workspace_setting(la_archive::post_extract::POST_EXTRACT_HOOKS_SETTING, &json)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))
+}
+
+/// 导入报告：各压缩包解压后处理钩子的执行结果（按执行顺序）
+#[tauri::command]
+pub async fn get_post_extract_report(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_storage::PostExtractRunRecord>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .get_post_extract_runs()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to load post-extraction report: {e}"),
+            )
+        })
+}
+
+/// 各工作区正在进行的导入 / 刷新 / 删除（持有者与开始时间）
+///
+/// 供新连接的客户端获取初始状态，之后的变化通过 `LeaseChanged` 事件推送。
+#[tauri::command]
+pub async fn get_workspace_leases(
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<std::collections::HashMap<String, crate::state_sync::OperationLease>, CommandError> {
+    let leases = state.sync.leases().snapshot();
+    let Some(tenant) = caller_tenant(&state, client_id.as_deref())? else {
+        return Ok(leases);
     };
+    // 租户会话只看到本租户命名空间中工作区的租约
+    Ok(leases
+        .into_iter()
+        .filter(|(workspace_id, _)| {
+            resolve_workspace_dir(&app, workspace_id)
+                .is_ok_and(|dir| tenant_of_workspace_dir(&dir).as_deref() == Some(tenant.as_str()))
+        })
+        .collect())
+}
 
-    let max_timestamp = if max_ts > 0 {
-        Some(
-            DateTime::from_timestamp(max_ts, 0)
-                .map_or_else(|| max_ts.to_string(), |dt| dt.to_rfc3339()),
+/// 工作区是否为只读（已归档的证据工作区）
+#[tauri::command]
+pub async fn get_workspace_read_only(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    crate::utils::workspace_guard::is_read_only(&service).await
+}
+
+/// 设置或清除工作区只读标记
+///
+/// 只读工作区拒绝刷新、监听、删除以及文件标记 / 时钟偏移 / 解压钩子等写操作，
+/// 调查结束后归档的证据不会再被修改；搜索与分析不受影响。开启时停止正在进行的监听。
+#[tauri::command]
+pub async fn set_workspace_read_only(
+    workspace_id: String,
+    client_id: Option<String>,
+    read_only: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .set_workspace_setting(
+            crate::utils::workspace_guard::READ_ONLY_SETTING,
+            if read_only { "true" } else { "false" },
         )
-    } else {
-        None
-    };
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
 
-    Ok(WorkspaceTimeRange {
-        min_timestamp,
-        max_timestamp,
-        total_logs,
-    })
+    if read_only {
+        if let Err(e) = service.stop_watch().await {
+            warn!(workspace_id = %workspace_id, error = %e, "停止只读工作区的监听失败");
+        }
+    }
+
+    info!(
+        workspace_id = %workspace_id,
+        read_only,
+        "工作区只读标记已更新"
+    );
+    Ok(())
+}
+
+/// 工作区的单行最大字节数（未设置时为 `None`，不截断）
+#[tauri::command]
+pub async fn get_max_line_length(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<usize>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let value = service
+        .metadata_store()
+        .get_workspace_setting(crate::infrastructure::log_file_repo::MAX_LINE_LENGTH_SETTING)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    Ok(value
+        .and_then(|v| v.parse().ok())
+        .filter(|limit| *limit > 0))
+}
+
+/// 设置工作区的单行最大字节数；传 `None` 取消限制
+///
+/// 超长行（如压缩成一行的 JSON）在搜索结果中截断为前缀加标记，匹配仍在完整行上进行，
+/// 完整内容可用 `read_file_by_hash` 按行号与字节偏移读取。从下一次搜索起生效。
+#[tauri::command]
+pub async fn set_max_line_length(
+    workspace_id: String,
+    client_id: Option<String>,
+    max_line_length: Option<usize>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    const MIN_LINE_LENGTH: usize = 256;
+    const MAX_LINE_LENGTH: usize = 16 * 1024 * 1024;
+
+    if let Some(limit) = max_line_length {
+        if !(MIN_LINE_LENGTH..=MAX_LINE_LENGTH).contains(&limit) {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                format!(
+                    "max_line_length must be between {MIN_LINE_LENGTH} and {MAX_LINE_LENGTH} bytes"
+                ),
+            ));
+        }
+    }
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .set_workspace_setting(
+            crate::infrastructure::log_file_repo::MAX_LINE_LENGTH_SETTING,
+            &max_line_length.map(|l| l.to_string()).unwrap_or_default(),
+        )
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))
+}
+
+/// 工作区自定义的日志级别顺序（从低到高）；未设置时为 `None`，使用默认顺序
+#[tauri::command]
+pub async fn get_level_order(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<Vec<String>>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let value = service
+        .metadata_store()
+        .get_workspace_setting(crate::infrastructure::log_file_repo::LEVEL_ORDER_SETTING)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    Ok(value
+        .and_then(|v| la_core::utils::LevelOrder::parse(&v).ok())
+        .map(|order| order.levels().to_vec()))
+}
+
+/// 设置工作区的日志级别顺序，如 `VERBOSE < DEBUG < INFO < NOTICE < WARN < ERROR < FATAL`；
+/// 传 `None` 恢复默认顺序 `TRACE < DEBUG < INFO < WARN < ERROR`
+///
+/// 搜索过滤器的 `min_level`（`level >= WARN`）按此顺序比较，从下一次搜索起生效。
+#[tauri::command]
+pub async fn set_level_order(
+    workspace_id: String,
+    client_id: Option<String>,
+    level_order: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let order = level_order
+        .as_deref()
+        .map(str::trim)
+        .filter(|spec| !spec.is_empty())
+        .map(la_core::utils::LevelOrder::parse)
+        .transpose()
+        .map_err(|e| {
+            CommandError::new("VALIDATION_ERROR", e)
+                .with_help("List levels from lowest to highest, e.g. 'DEBUG < INFO < WARN < ERROR'")
+        })?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .set_workspace_setting(
+            crate::infrastructure::log_file_repo::LEVEL_ORDER_SETTING,
+            &order.map(|o| o.to_spec()).unwrap_or_default(),
+        )
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))
+}
+
+/// 工作区搜索默认值（大小写敏感、结果上限），未设置的字段为 `null`
+#[tauri::command]
+pub async fn get_search_defaults(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<crate::commands::search::WorkspaceSearchDefaults, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    Ok(crate::commands::search::load_workspace_search_defaults(service.metadata_store()).await)
+}
+
+/// 保存工作区搜索默认值；字段为 `null` 时沿用应用配置
+///
+/// 从下一次搜索起生效，`search_logs` / `lint_search_query` 的显式参数仍优先。
+#[tauri::command]
+pub async fn set_search_defaults(
+    workspace_id: String,
+    client_id: Option<String>,
+    defaults: crate::commands::search::WorkspaceSearchDefaults,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    use crate::commands::search::{MAX_SEARCH_RESULTS, SEARCH_DEFAULTS_SETTING};
+
+    if let Some(max_results) = defaults.max_results {
+        if !(1..=MAX_SEARCH_RESULTS).contains(&max_results) {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                format!("maxResults must be between 1 and {MAX_SEARCH_RESULTS}"),
+            ));
+        }
+    }
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let json = serde_json::to_string(&defaults)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e.to_string()))?;
+    service
+        .metadata_store()
+        .set_workspace_setting(SEARCH_DEFAULTS_SETTING, &json)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))
+}
+
+/// 读取工作区保存的字段提取器；无效的已保存内容按空列表处理
+pub(crate) async fn load_field_extractors(
+    store: &la_storage::MetadataStore,
+) -> Result<Vec<FieldExtractor>, CommandError> {
+    let saved = store
+        .get_workspace_setting(field_extractors::FIELD_EXTRACTORS_SETTING)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    Ok(saved
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+/// 读取工作区的字段提取器（按保存顺序）
+#[tauri::command]
+pub async fn get_field_extractors(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<FieldExtractor>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    load_field_extractors(service.metadata_store()).await
+}
+
+/// 新增或按名称替换一个字段提取器（替换时保留原位置），返回保存后的列表
+#[tauri::command]
+pub async fn save_field_extractor(
+    workspace_id: String,
+    client_id: Option<String>,
+    extractor: FieldExtractor,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<FieldExtractor>, CommandError> {
+    field_extractors::validate_extractor(&extractor)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let store = service.metadata_store();
+
+    let mut extractors = load_field_extractors(store).await?;
+    match extractors.iter_mut().find(|e| e.name == extractor.name) {
+        Some(existing) => *existing = extractor,
+        None => extractors.push(extractor),
+    }
+    field_extractors::validate_extractors(&extractors)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+
+    let json = serde_json::to_string(&extractors)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e.to_string()))?;
+    store
+        .set_workspace_setting(field_extractors::FIELD_EXTRACTORS_SETTING, &json)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    Ok(extractors)
+}
+
+/// 按名称删除字段提取器；返回是否存在
+#[tauri::command]
+pub async fn delete_field_extractor(
+    workspace_id: String,
+    client_id: Option<String>,
+    name: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let store = service.metadata_store();
+
+    let mut extractors = load_field_extractors(store).await?;
+    let before = extractors.len();
+    extractors.retain(|e| e.name != name);
+    if extractors.len() == before {
+        return Ok(false);
+    }
+    let json = serde_json::to_string(&extractors)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e.to_string()))?;
+    store
+        .set_workspace_setting(field_extractors::FIELD_EXTRACTORS_SETTING, &json)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    Ok(true)
+}
+
+/// 用样例行试运行一个提取器（无需保存），返回每行的提取结果
+#[tauri::command]
+pub async fn test_extractor(
+    extractor: FieldExtractor,
+    sample_lines: Vec<String>,
+) -> Result<Vec<ExtractionPreview>, CommandError> {
+    if sample_lines.len() > field_extractors::MAX_SAMPLE_LINES {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            format!(
+                "Too many sample lines ({}, max {})",
+                sample_lines.len(),
+                field_extractors::MAX_SAMPLE_LINES
+            ),
+        ));
+    }
+    field_extractors::validate_extractor(&extractor)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let extractor = FieldExtractor {
+        enabled: true,
+        ..extractor
+    };
+    let set = FieldExtractorSet::compile(std::slice::from_ref(&extractor))
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    Ok(set.preview(&sample_lines))
+}
+
+/// 按工作区启用的提取器为可见条目填充 `fields`（展示列），返回同一批条目
+#[tauri::command]
+pub async fn extract_entry_fields(
+    workspace_id: String,
+    client_id: Option<String>,
+    mut entries: Vec<la_core::models::LogEntry>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_core::models::LogEntry>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let extractors = load_field_extractors(service.metadata_store()).await?;
+    // 已保存的规则在保存时校验过；编译失败说明设置被外部改写，按无提取器处理
+    let set = FieldExtractorSet::compile(&extractors).unwrap_or_else(|e| {
+        warn!(workspace_id = %workspace_id, error = %e, "Ignoring invalid field extractors");
+        FieldExtractorSet::default()
+    });
+    set.annotate(&mut entries);
+    annotate_csv_fields(service.metadata_store(), &mut entries).await;
+    Ok(entries)
+}
+
+/// 读取工作区登记的 Webhook（不含签名密钥，只返回 `hasSecret`）
+#[tauri::command]
+pub async fn get_webhooks(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<WebhookInfo>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    webhooks::list_webhooks(service.metadata_store())
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))
+}
+
+/// 新增或按 id 替换一个 Webhook，返回保存后的列表；`secret` 省略时保留已有密钥，
+/// 为空字符串时删除密钥
+#[tauri::command]
+pub async fn save_webhook(
+    workspace_id: String,
+    client_id: Option<String>,
+    webhook: Webhook,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<WebhookInfo>, CommandError> {
+    webhooks::validate_webhook(&webhook).map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let store = service.metadata_store();
+
+    webhooks::save_webhook(store, webhook)
+        .await
+        .map_err(|e| match e {
+            webhooks::SaveError::Invalid(e) => CommandError::new("VALIDATION_ERROR", e),
+            webhooks::SaveError::Storage(e) => CommandError::new("DATABASE_ERROR", e.to_string()),
+        })?;
+    webhooks::list_webhooks(store)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))
+}
+
+/// 按 id 删除 Webhook 及其密钥；返回是否存在
+#[tauri::command]
+pub async fn delete_webhook(
+    workspace_id: String,
+    client_id: Option<String>,
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    webhooks::delete_webhook(service.metadata_store(), &id)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))
+}
+
+/// 立即向一个 Webhook 投递测试事件（`data.test = true`，事件类型取其首个订阅），
+/// 返回接收方的 HTTP 状态码
+#[tauri::command]
+pub async fn test_webhook(
+    workspace_id: String,
+    client_id: Option<String>,
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<u16, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let webhook = webhooks::load_webhooks(service.metadata_store())
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .into_iter()
+        .find(|w| w.id == id)
+        .ok_or_else(|| CommandError::new("NOT_FOUND", format!("Webhook not found: {id}")))?;
+    let event =
+        webhook.events.first().copied().ok_or_else(|| {
+            CommandError::new("VALIDATION_ERROR", "Webhook subscribes to no events")
+        })?;
+    let payload =
+        webhooks::WebhookPayload::new(&workspace_id, event, serde_json::json!({ "test": true }));
+    webhooks::deliver(&webhook, &payload).await.map_err(|e| {
+        CommandError::new("WEBHOOK_DELIVERY_FAILED", e)
+            .with_help("Check that the URL is reachable and returns a 2xx status")
+    })
+}
+
+/// 为 CSV/TSV 文件中的条目按列映射补充字段（提取器已提取的同名字段优先）
+async fn annotate_csv_fields(
+    store: &la_storage::MetadataStore,
+    entries: &mut [la_core::models::LogEntry],
+) {
+    let formats = crate::infrastructure::workspace_service_impl::load_file_formats(store).await;
+    for entry in entries {
+        let Some(mapping) = entry
+            .real_path
+            .strip_prefix("cas://")
+            .and_then(|hash| formats.get(hash))
+            .filter(|f| f.effective() == la_core::utils::LogFormat::Csv)
+            .and_then(|f| f.effective_csv_mapping())
+        else {
+            continue;
+        };
+        let row = mapping.row_fields(&entry.content);
+        if row.is_empty() {
+            continue;
+        }
+        let fields = entry.fields.get_or_insert_with(Default::default);
+        for (name, value) in row {
+            fields.entry(name).or_insert(value);
+        }
+    }
+}
+
+/// 设置文件搜索标记命令
+///
+/// - `ignored`：从搜索扫描列表移除，并删除该文件的索引文档
+/// - `pinned`：搜索时优先扫描
+/// - `normal`：恢复默认；从 ignored 恢复时重新建立该文件的索引
+#[tauri::command]
+pub async fn set_file_search_flag(
+    workspace_id: String,
+    client_id: Option<String>,
+    virtual_path: String,
+    flag: la_storage::FileSearchFlag,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<la_storage::FlaggedFile, CommandError> {
+    use la_storage::FileSearchFlag;
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata_store = Arc::clone(service.metadata_store());
+
+    let file = metadata_store
+        .get_file_by_virtual_path(&virtual_path)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", format!("Failed to query file: {e}")))?
+        .ok_or_else(|| CommandError::new("NOT_FOUND", format!("File not found: {virtual_path}")))?;
+
+    let previous = metadata_store
+        .set_file_search_flag(&virtual_path, flag)
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to update search flag: {e}"),
+            )
+        })?
+        .unwrap_or_default();
+
+    let was_ignored = previous == FileSearchFlag::Ignored;
+    let is_ignored = flag == FileSearchFlag::Ignored;
+    if was_ignored != is_ignored {
+        let format =
+            crate::infrastructure::workspace_service_impl::load_file_formats(&metadata_store)
+                .await
+                .remove(&file.sha256_hash);
+        let search_manager = Arc::clone(service.search_engine());
+        let cas = Arc::clone(service.cas());
+        let indexed_file = file.clone();
+        let result = tokio::task::spawn_blocking(move || -> Result<(), String> {
+            if is_ignored {
+                search_manager
+                    .delete_file_documents(&indexed_file.virtual_path)
+                    .map_err(|e| format!("Failed to remove indexed documents: {e}"))?;
+            } else {
+                let (_, _, total_docs) = search_manager
+                    .get_time_range()
+                    .map_err(|e| format!("Failed to read index size: {e}"))?;
+                crate::infrastructure::workspace_service_impl::index_file_documents(
+                    &search_manager,
+                    &cas,
+                    &indexed_file,
+                    format.as_ref(),
+                    total_docs,
+                )?;
+                search_manager
+                    .commit()
+                    .map_err(|e| format!("Failed to commit search index: {e}"))?;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Index update panicked: {e}")))?;
+
+        if let Err(e) = result {
+            warn!(
+                workspace_id = %workspace_id,
+                virtual_path = %virtual_path,
+                error = %e,
+                "搜索标记已更新，但索引同步失败"
+            );
+            return Err(CommandError::new("SEARCH_ERROR", e));
+        }
+    }
+
+    info!(
+        workspace_id = %workspace_id,
+        virtual_path = %virtual_path,
+        previous = previous.as_str(),
+        flag = flag.as_str(),
+        "文件搜索标记已更新"
+    );
+
+    Ok(la_storage::FlaggedFile {
+        virtual_path,
+        sha256_hash: file.sha256_hash,
+        flag,
+    })
+}
+
+/// 列出工作区中被忽略或置顶的文件
+#[tauri::command]
+pub async fn get_file_search_flags(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_storage::FlaggedFile>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .get_flagged_files()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to query file search flags: {e}"),
+            )
+        })
+}
+
+/// 改判文件格式（`format` 为 `None` 时恢复自动识别），返回更新后的格式
+///
+/// 格式按内容记录，内容相同的文件一起改判；未被忽略的这些文件按新格式重建索引。
+#[tauri::command]
+pub async fn set_file_format(
+    workspace_id: String,
+    client_id: Option<String>,
+    virtual_path: String,
+    format: Option<la_core::utils::LogFormat>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<la_storage::FileFormat, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata_store = Arc::clone(service.metadata_store());
+
+    let file = metadata_store
+        .get_file_by_virtual_path(&virtual_path)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", format!("Failed to query file: {e}")))?
+        .ok_or_else(|| CommandError::new("NOT_FOUND", format!("File not found: {virtual_path}")))?;
+
+    metadata_store
+        .set_format_override(&file.sha256_hash, format)
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to update file format: {e}"),
+            )
+        })?;
+    let updated = reindex_same_content(&service, &file)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                workspace_id = %workspace_id,
+                virtual_path = %virtual_path,
+                error = %e.message,
+                "文件格式已更新，但索引同步失败"
+            );
+        })?;
+
+    info!(
+        workspace_id = %workspace_id,
+        virtual_path = %virtual_path,
+        format = updated.effective().as_str(),
+        "文件格式已更新"
+    );
+    Ok(updated)
+}
+
+/// 按内容哈希当前记录的格式、列映射与解析覆盖，重建与 `file` 内容相同、未被忽略的文件的索引，
+/// 并按新的解析结果刷新这些文件的时间范围、级别掩码与内容的稀疏时间索引、三元组摘要，
+/// 返回该内容的格式记录
+pub(crate) async fn reindex_same_content(
+    service: &WorkspaceServiceRef,
+    file: &la_storage::FileMetadata,
+) -> Result<la_storage::FileFormat, CommandError> {
+    let metadata_store = service.metadata_store();
+    let updated = metadata_store
+        .get_file_formats()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .remove(&file.sha256_hash)
+        .ok_or_else(|| CommandError::new("DATABASE_ERROR", "File format was not recorded"))?;
+
+    // 扫描列表已排除被忽略的文件
+    let same_content: Vec<_> = metadata_store
+        .get_files_with_pruning(None, None, None, None)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .into_iter()
+        .filter(|f| f.sha256_hash == file.sha256_hash)
+        .collect();
+    let paths: Vec<String> = same_content
+        .iter()
+        .map(|f| f.virtual_path.clone())
+        .collect();
+    let search_manager = Arc::clone(service.search_engine());
+    let cas = Arc::clone(service.cas());
+    let format = updated.clone();
+    let hash = file.sha256_hash.clone();
+    let (indexed, time_index, bloom) = tokio::task::spawn_blocking(move || -> Result<_, String> {
+        let mut indexed = None;
+        for indexed_file in &same_content {
+            search_manager
+                .delete_file_documents(&indexed_file.virtual_path)
+                .map_err(|e| format!("Failed to remove indexed documents: {e}"))?;
+            let (_, _, total_docs) = search_manager
+                .get_time_range()
+                .map_err(|e| format!("Failed to read index size: {e}"))?;
+            indexed = Some(
+                crate::infrastructure::workspace_service_impl::index_file_documents(
+                    &search_manager,
+                    &cas,
+                    indexed_file,
+                    Some(&format),
+                    total_docs,
+                )?,
+            );
+        }
+        search_manager
+            .commit()
+            .map_err(|e| format!("Failed to commit search index: {e}"))?;
+
+        // gzip 对象的时间索引按分帧记录（见 `store_gzip_frames`），不在这里重建
+        let content = cas
+            .read_content_sync(&hash)
+            .map_err(|e| format!("Failed to read CAS content: {e}"))?;
+        let overrides = crate::services::file_overrides::CompiledOverrides::from_format(&format);
+        let time_index =
+            (!content.starts_with(&[0x1f, 0x8b])).then(|| overrides.time_index(&content));
+        // 导入时按原始字节构建的摘要在编码覆盖后不再可靠，按解码后的文本重建
+        let bloom = overrides.term_sketch(&content);
+        Ok((indexed, time_index, bloom))
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Index update panicked: {e}")))?
+    .map_err(|e| CommandError::new("SEARCH_ERROR", e))?;
+
+    // 元数据裁剪与按时间跳转使用覆盖后的时间戳与级别
+    if let Some(indexed) = indexed {
+        for path in &paths {
+            metadata_store
+                .update_file_stats(
+                    path,
+                    indexed.min_timestamp,
+                    indexed.max_timestamp,
+                    indexed.level_mask,
+                )
+                .await
+                .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+        }
+    }
+    let stored = match time_index {
+        Some(Some(index)) => {
+            metadata_store
+                .set_time_index(&file.sha256_hash, &index.to_bytes())
+                .await
+        }
+        // 覆盖后没有可靠的检查点：删除旧索引，按时间跳转时从头读取
+        Some(None) => metadata_store.delete_time_index(&file.sha256_hash).await,
+        None => Ok(()),
+    };
+    stored.map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    // 无法构建摘要时删除旧摘要，搜索不再据此跳过该文件
+    let stored = match bloom {
+        Some(bloom) => {
+            metadata_store
+                .set_term_sketch(&file.sha256_hash, &bloom.to_bytes())
+                .await
+        }
+        None => metadata_store.delete_term_sketch(&file.sha256_hash).await,
+    };
+    stored.map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+
+    Ok(updated)
+}
+
+/// 读取文件当前使用的 CSV 列映射（改写优先，其次为导入时识别的映射）
+///
+/// 文件不是 CSV/TSV 或未识别出映射时返回 `None`。
+#[tauri::command]
+pub async fn get_csv_mapping(
+    workspace_id: String,
+    client_id: Option<String>,
+    virtual_path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<la_core::utils::CsvMapping>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata_store = service.metadata_store();
+
+    let file = metadata_store
+        .get_file_by_virtual_path(&virtual_path)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", format!("Failed to query file: {e}")))?
+        .ok_or_else(|| CommandError::new("NOT_FOUND", format!("File not found: {virtual_path}")))?;
+
+    let formats = metadata_store
+        .get_file_formats()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    Ok(formats
+        .get(&file.sha256_hash)
+        .filter(|f| f.effective() == la_core::utils::LogFormat::Csv)
+        .and_then(|f| f.effective_csv_mapping().cloned()))
+}
+
+/// 改写文件的 CSV 列映射（`mapping` 为 `None` 时恢复自动识别），返回更新后的格式
+///
+/// 映射按内容记录，内容相同的文件一起改写并重建索引。文件尚未按 CSV 解析时一并改判为 CSV。
+#[tauri::command]
+pub async fn set_csv_mapping(
+    workspace_id: String,
+    client_id: Option<String>,
+    virtual_path: String,
+    mapping: Option<la_core::utils::CsvMapping>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<la_storage::FileFormat, CommandError> {
+    if let Some(mapping) = &mapping {
+        mapping.validate().map_err(|e| {
+            CommandError::new("VALIDATION_ERROR", e)
+                .with_help("Column indexes start at 0 and must be within the column list")
+        })?;
+    }
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata_store = service.metadata_store();
+
+    let file = metadata_store
+        .get_file_by_virtual_path(&virtual_path)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", format!("Failed to query file: {e}")))?
+        .ok_or_else(|| CommandError::new("NOT_FOUND", format!("File not found: {virtual_path}")))?;
+
+    metadata_store
+        .set_csv_mapping_override(&file.sha256_hash, mapping.as_ref())
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to update CSV mapping: {e}"),
+            )
+        })?;
+    let is_csv = metadata_store
+        .get_file_formats()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .get(&file.sha256_hash)
+        .is_some_and(|f| f.effective() == la_core::utils::LogFormat::Csv);
+    if mapping.is_some() && !is_csv {
+        metadata_store
+            .set_format_override(&file.sha256_hash, Some(la_core::utils::LogFormat::Csv))
+            .await
+            .map_err(|e| {
+                CommandError::new(
+                    "DATABASE_ERROR",
+                    format!("Failed to update file format: {e}"),
+                )
+            })?;
+    }
+
+    let updated = reindex_same_content(&service, &file)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                workspace_id = %workspace_id,
+                virtual_path = %virtual_path,
+                error = %e.message,
+                "CSV 列映射已更新，但索引同步失败"
+            );
+        })?;
+
+    info!(
+        workspace_id = %workspace_id,
+        virtual_path = %virtual_path,
+        custom = mapping.is_some(),
+        "CSV 列映射已更新"
+    );
+    Ok(updated)
+}
+
+/// 设置内容的编码、格式、时区与解析器覆盖（各项为空时恢复自动识别），返回更新后的格式
+///
+/// 覆盖按内容哈希记录并整体替换，内容相同的文件一起按新设置重建索引。
+#[tauri::command]
+pub async fn set_file_overrides(
+    workspace_id: String,
+    client_id: Option<String>,
+    file_hash: String,
+    overrides: la_storage::FileOverrides,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<la_storage::FileFormat, CommandError> {
+    let overrides = crate::services::file_overrides::normalize(overrides);
+    crate::services::file_overrides::CompiledOverrides::compile(&overrides).map_err(|e| {
+        CommandError::new("VALIDATION_ERROR", e).with_help(
+            "Use an encoding label such as gbk, a fixed offset such as +08:00, and a parser \
+             regex with (?P<timestamp>...) or (?P<level>...) groups",
+        )
+    })?;
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata_store = service.metadata_store();
+
+    let file = metadata_store
+        .get_file_by_hash(&file_hash)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", format!("Failed to query file: {e}")))?
+        .ok_or_else(|| CommandError::new("NOT_FOUND", format!("File not found: {file_hash}")))?;
+
+    metadata_store
+        .set_file_overrides(&file.sha256_hash, &overrides)
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to update file overrides: {e}"),
+            )
+        })?;
+    let updated = reindex_same_content(&service, &file)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                workspace_id = %workspace_id,
+                file_hash = %file_hash,
+                error = %e.message,
+                "文件解析覆盖已更新，但索引同步失败"
+            );
+        })?;
+
+    info!(
+        workspace_id = %workspace_id,
+        file_hash = %file_hash,
+        encoding = ?overrides.encoding,
+        format = updated.effective().as_str(),
+        timezone = ?overrides.timezone,
+        custom_parser = overrides.parser.is_some(),
+        "文件解析覆盖已更新"
+    );
+    Ok(updated)
+}
+
+/// 列出工作区中的崩溃产物（core dump、minidump、hprof），按崩溃时间排序
+///
+/// 产物在导入时按文件头识别并登记，不写入搜索索引。
+#[tauri::command]
+pub async fn get_crash_artifacts(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_storage::CrashArtifact>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .get_crash_artifacts()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to list crash artifacts: {e}"),
+            )
+        })
+}
+
+/// 在系统文件管理器中显示崩溃产物所在的文件夹
+///
+/// 显示的是工作区中按内容哈希命名的存储副本，可直接交给调试器或分析工具。
+#[tauri::command]
+pub async fn open_artifact_folder(
+    workspace_id: String,
+    client_id: Option<String>,
+    virtual_path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let artifact = service
+        .metadata_store()
+        .get_crash_artifacts()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .into_iter()
+        .find(|a| a.virtual_path == virtual_path)
+        .ok_or_else(|| {
+            CommandError::new(
+                "NOT_FOUND",
+                format!("Not a cataloged crash artifact: {virtual_path}"),
+            )
+            .with_help("Only core dumps, minidumps and hprof files can be revealed")
+        })?;
+
+    let object_path = service.cas().get_object_path(&artifact.sha256_hash);
+    tauri_plugin_opener::reveal_item_in_dir(&object_path).map_err(|e| {
+        CommandError::new("IO_ERROR", format!("Failed to open containing folder: {e}"))
+    })?;
+
+    info!(
+        workspace_id = %workspace_id,
+        virtual_path = %virtual_path,
+        kind = artifact.info.kind.as_str(),
+        "已打开崩溃产物所在文件夹"
+    );
+    Ok(())
+}
+
+/// 获取工作区概览：按文件、按天的级别计数，整体时间范围与无日志的日期区间
+///
+/// 计数在导入时已按内容记录，这里只做汇总，不读取文件内容。
+#[tauri::command]
+pub async fn get_workspace_overview(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<la_storage::WorkspaceOverview, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .get_workspace_overview()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to compute workspace overview: {e}"),
+            )
+        })
+}
+
+/// 设置文件时钟偏移（秒）
+///
+/// 偏移加到该文件的时间戳上参与时间过滤与跨文件排序，时间戳文本保持原样；传 0 清除偏移。
+#[tauri::command]
+pub async fn set_file_time_offset(
+    workspace_id: String,
+    client_id: Option<String>,
+    virtual_path: String,
+    offset_secs: i64,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    let updated = service
+        .metadata_store()
+        .set_file_time_offset(&virtual_path, offset_secs)
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to update time offset: {e}"),
+            )
+        })?;
+    if !updated {
+        return Err(CommandError::new(
+            "NOT_FOUND",
+            format!("File not found: {virtual_path}"),
+        ));
+    }
+
+    info!(
+        workspace_id = %workspace_id,
+        virtual_path = %virtual_path,
+        offset_secs,
+        "文件时钟偏移已更新"
+    );
+    Ok(())
+}
+
+/// 列出设置了非零时钟偏移的文件（虚拟路径 → 秒）
+#[tauri::command]
+pub async fn get_file_time_offsets(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<std::collections::HashMap<String, i64>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+
+    service
+        .metadata_store()
+        .get_file_time_offsets()
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "DATABASE_ERROR",
+                format!("Failed to query time offsets: {e}"),
+            )
+        })
+}
+
+/// 按共同锚点估算文件间时钟偏移
+///
+/// 在参考文件与各目标文件中查找第一个包含 `marker` 且带时间戳的行，
+/// 建议偏移 = 参考时间 − 目标时间。只返回建议值，需通过 `set_file_time_offset` 应用。
+#[tauri::command]
+pub async fn estimate_clock_skew(
+    workspace_id: String,
+    client_id: Option<String>,
+    marker: String,
+    reference_path: String,
+    files: Vec<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::services::clock_skew::ClockSkewEstimate>, CommandError> {
+    use crate::services::clock_skew;
+
+    if marker.trim().is_empty() {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            "Marker must not be empty",
+        ));
+    }
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let metadata_store = service.metadata_store();
+    let cas = service.cas();
+
+    let read_text = |virtual_path: String| async move {
+        let file = metadata_store
+            .get_file_by_virtual_path(&virtual_path)
+            .await
+            .map_err(|e| CommandError::new("DATABASE_ERROR", format!("Failed to query file: {e}")))?
+            .ok_or_else(|| {
+                CommandError::new("NOT_FOUND", format!("File not found: {virtual_path}"))
+            })?;
+        let bytes = cas.read_content(&file.sha256_hash).await.map_err(|e| {
+            CommandError::new("IO_ERROR", format!("Failed to read {virtual_path}: {e}"))
+        })?;
+        let (text, _) = crate::utils::encoding::decode_log_content(&bytes);
+        Ok::<_, CommandError>(text)
+    };
+
+    let reference_text = read_text(reference_path.clone()).await?;
+    let reference = clock_skew::find_marker_timestamp(&reference_text, &marker);
+    let reference_unix = reference.as_ref().map(|(_, _, unix)| *unix);
+
+    let mut estimates = vec![clock_skew::ClockSkewEstimate {
+        virtual_path: reference_path,
+        marker_line: reference.as_ref().map(|(line, _, _)| *line),
+        marker_timestamp: reference.map(|(_, timestamp, _)| timestamp),
+        suggested_offset_secs: reference_unix.map(|_| 0),
+    }];
+    for virtual_path in files {
+        let text = read_text(virtual_path.clone()).await?;
+        estimates.push(clock_skew::estimate(
+            reference_unix,
+            virtual_path,
+            &text,
+            &marker,
+        ));
+    }
+    Ok(estimates)
+}
+
+/// 获取工作区日志时间范围
+///
+/// 从 Tantivy 索引中查询最早和最晚的日志时间戳
+#[tauri::command]
+pub async fn get_workspace_time_range(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<la_core::models::search::WorkspaceTimeRange, CommandError> {
+    use chrono::DateTime;
+    use la_core::models::search::WorkspaceTimeRange;
+
+    // ── Acquire workspace service ──
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let manager: Arc<la_search::SearchEngineManager> = Arc::clone(service.search_engine());
+    let (min_ts, max_ts, total_logs) = manager.get_time_range().map_err(|e| {
+        CommandError::new(
+            "SEARCH_ERROR",
+            format!("Failed to get time range from index: {e}"),
+        )
+    })?;
+
+    // Convert timestamps to ISO 8601 format
+    let min_timestamp = if min_ts > 0 {
+        Some(
+            DateTime::from_timestamp(min_ts, 0)
+                .map_or_else(|| min_ts.to_string(), |dt| dt.to_rfc3339()),
+        )
+    } else {
+        None
+    };
+
+    let max_timestamp = if max_ts > 0 {
+        Some(
+            DateTime::from_timestamp(max_ts, 0)
+                .map_or_else(|| max_ts.to_string(), |dt| dt.to_rfc3339()),
+        )
+    } else {
+        None
+    };
+
+    Ok(WorkspaceTimeRange {
+        min_timestamp,
+        max_timestamp,
+        total_logs,
+    })
+}
+
+/// 最近条目默认返回数量
+const RECENT_ENTRIES_DEFAULT_LIMIT: usize = 50;
+/// 最近条目最大返回数量
+const RECENT_ENTRIES_MAX_LIMIT: usize = 1000;
+
+/// 获取工作区最新的日志条目（默认只取 ERROR）
+///
+/// 直接按索引时间戳倒序读取，无需用户查询，用于打开工作区时的“最新错误”面板。
+/// `levels` 为空数组时不限级别。
+#[tauri::command]
+pub async fn get_recent_entries(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    levels: Option<Vec<String>>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_core::models::LogEntry>, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let manager: Arc<la_search::SearchEngineManager> = Arc::clone(service.search_engine());
+
+    // 索引中的级别为小写（见 parse_metadata）
+    let levels: Vec<String> = levels
+        .unwrap_or_else(|| vec!["error".to_string()])
+        .iter()
+        .map(|level| level.trim().to_lowercase())
+        .filter(|level| !level.is_empty())
+        .collect();
+    let limit = limit
+        .unwrap_or(RECENT_ENTRIES_DEFAULT_LIMIT)
+        .min(RECENT_ENTRIES_MAX_LIMIT);
+
+    let mut entries =
+        tokio::task::spawn_blocking(move || manager.get_recent_entries(&levels, limit))
+            .await
+            .map_err(|e| {
+                CommandError::new("TASK_ERROR", format!("Recent entries task failed: {e}"))
+            })?
+            .map_err(|e| {
+                CommandError::new(
+                    "SEARCH_ERROR",
+                    format!("Failed to read recent entries from index: {e}"),
+                )
+            })?;
+
+    format_index_timestamps(&mut entries);
+    state.search.links().annotate(&mut entries);
+    Ok(entries)
+}
+
+/// 时间对齐视图默认窗口（±秒）
+const TIME_WINDOW_DEFAULT_SECS: i64 = 5;
+/// 时间对齐视图最大窗口（±秒）
+const TIME_WINDOW_MAX_SECS: i64 = 3600;
+/// 时间对齐视图默认返回数量
+const TIME_WINDOW_DEFAULT_LIMIT: usize = 500;
+/// 时间对齐视图最大返回数量
+const TIME_WINDOW_MAX_LIMIT: usize = 5000;
+
+/// 获取多个文件在某一时刻前后 ±N 秒内的条目，按时间全局交错排序
+///
+/// 用于并排查看“14:32:07 时各服务在做什么”。`limit` 前后各占一半，
+/// 一侧条目不足时余量留给另一侧。`files` 为虚拟路径，为空时覆盖整个工作区；
+/// `timestamp` 接受 Unix 秒/毫秒、RFC3339 及常见日志时间格式。
+/// 设置了时钟偏移的文件按校正后的时间参与窗口与排序。
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn get_entries_around_time(
+    app: AppHandle,
+    workspace_id: String,
+    client_id: Option<String>,
+    timestamp: String,
+    files: Vec<String>,
+    window_secs: Option<i64>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<la_core::models::LogEntry>, CommandError> {
+    let center = la_search::parse_log_timestamp_to_unix(&timestamp).ok_or_else(|| {
+        CommandError::new(
+            "VALIDATION_ERROR",
+            format!("Unrecognized timestamp: {timestamp}"),
+        )
+    })?;
+    let window = window_secs
+        .unwrap_or(TIME_WINDOW_DEFAULT_SECS)
+        .clamp(0, TIME_WINDOW_MAX_SECS);
+    let limit = limit
+        .unwrap_or(TIME_WINDOW_DEFAULT_LIMIT)
+        .min(TIME_WINDOW_MAX_LIMIT);
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let manager: Arc<la_search::SearchEngineManager> = Arc::clone(service.search_engine());
+    let offsets = service
+        .metadata_store()
+        .get_file_time_offsets()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+
+    let mut entries = tokio::task::spawn_blocking(move || {
+        manager.get_entries_around_time(&files, &offsets, center, window, limit)
+    })
+    .await
+    .map_err(|e| CommandError::new("TASK_ERROR", format!("Time window task failed: {e}")))?
+    .map_err(|e| {
+        CommandError::new(
+            "SEARCH_ERROR",
+            format!("Failed to read entries around {timestamp}: {e}"),
+        )
+    })?;
+
+    format_index_timestamps(&mut entries);
+    state.search.links().annotate(&mut entries);
+    Ok(entries)
+}
+
+/// 索引存储的是 Unix 秒，转换为 ISO 8601 便于展示
+pub(crate) fn format_index_timestamps(entries: &mut [la_core::models::LogEntry]) {
+    use chrono::DateTime;
+
+    for entry in entries {
+        if let Some(dt) = entry
+            .timestamp
+            .parse::<i64>()
+            .ok()
+            .filter(|ts| *ts > 0)
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        {
+            entry.timestamp = dt.to_rfc3339().into();
+        }
+    }
 }
 
 /// 创建工作区命令（import_folder 的语义化别名）
@@ -731,6 +2829,7 @@ pub async fn get_workspace_time_range(
 pub async fn create_workspace(
     name: String,
     path: String,
+    client_id: Option<String>,
     app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<String, CommandError> {
@@ -755,6 +2854,11 @@ pub async fn create_workspace(
         app,
         canonical_path.to_string_lossy().into_owned(),
         workspace_id,
+        None,
+        None,
+        None,
+        None,
+        client_id,
         state,
     )
     .await
@@ -0,0 +1,565 @@
+//! 工作区只读共享命令
+//!
+//! 共享端：在本机监听 HTTP，把 `services::workspace_share` 路由出的只读操作转发给
+//! 本地命令执行。挂载端：保存远程工作区的地址与令牌，经 `services::remote_workspace`
+//! 的客户端（带本地缓存）把操作请求转发给共享端；可按共享链接直接打开。
+//!
+//! - 监听地址与端口默认取配置中的 `server`，连接数上限为 `server.max_connections`，
+//!   读取请求的超时为 `server.timeout_seconds`
+//! - 每个工作区同时只有一个共享；删除工作区时自动停止
+//! - 共享端的搜索按来源 IP 区分来源：同一队友的新搜索取消其旧搜索，不影响其他人
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use la_core::error::CommandError;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, State, Window};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::commands::actions::dispatch_action;
+use crate::commands::assistant::search;
+use crate::commands::virtual_tree::get_virtual_file_tree;
+use crate::models::AppState;
+use crate::services::remote_workspace::{fetch_info, RemoteCacheStats, RemoteWorkspaceClient};
+use crate::services::workspace_share::{
+    encode_response, error_status, generate_token, info_json, load_remotes, normalize_remote_url,
+    parse_head, parse_share_link, route, save_remotes, scope_args, share_link, token_matches,
+    upsert_remote, HttpError, RemoteWorkspace, RequestHead, Route, ShareInfo, MAX_HEAD_BYTES,
+    REMOTES_FILE,
+};
+use crate::utils::workspace_guard::{caller_tenant, require_tenant_workspace, workspace_tenant};
+
+/// 接受连接失败后的退避，避免文件句柄耗尽时空转
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+struct ActiveShare {
+    info: ShareInfo,
+    cancel: CancellationToken,
+}
+
+/// 正在进行的共享（按工作区 id）
+static SHARES: Lazy<Mutex<HashMap<String, ActiveShare>>> = Lazy::new(Default::default);
+
+/// 串行化挂载文件的读改写
+static REMOTES_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// 挂载的远程工作区客户端（按挂载 id），各自持有缓存
+static CLIENTS: Lazy<Mutex<HashMap<String, Arc<RemoteWorkspaceClient>>>> =
+    Lazy::new(Default::default);
+
+/// 开始以只读方式共享工作区；已在共享时返回现有的共享信息
+#[tauri::command]
+pub async fn start_workspace_share(
+    workspace_id: String,
+    client_id: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ShareInfo, CommandError> {
+    require_tenant_workspace(&app, &state, &workspace_id, client_id.as_deref()).await?;
+    if let Some(share) = SHARES.lock().get(&workspace_id) {
+        return Ok(share.info.clone());
+    }
+
+    let Some(webview) = app.get_webview_window("main") else {
+        return Err(CommandError::new(
+            "RUNTIME_ERROR",
+            "Workspace sharing needs the main window",
+        ));
+    };
+    let window = webview.as_ref().window();
+
+    let server = crate::utils::load_app_config(&app)
+        .unwrap_or_default()
+        .server;
+    let host = host.filter(|h| !h.trim().is_empty()).unwrap_or(server.host);
+    let port = port.unwrap_or(server.port);
+    let listener = TcpListener::bind((host.as_str(), port))
+        .await
+        .map_err(|e| {
+            CommandError::new(
+                "IO_ERROR",
+                format!("Failed to listen on {host}:{port}: {e}"),
+            )
+            .with_help("Choose another port or stop the program using it")
+        })?;
+    let addr = listener
+        .local_addr()
+        .map_err(|e| CommandError::new("IO_ERROR", e.to_string()))?;
+
+    let url = format!("http://{addr}");
+    let token = generate_token();
+    let info = ShareInfo {
+        workspace_id: workspace_id.clone(),
+        link: share_link(&url, &token),
+        url,
+        token,
+        started_at: chrono::Utc::now().timestamp(),
+    };
+    let cancel = CancellationToken::new();
+    {
+        let mut shares = SHARES.lock();
+        // 并发的两次开始共享：后到的放弃自己的监听
+        if let Some(share) = shares.get(&workspace_id) {
+            return Ok(share.info.clone());
+        }
+        shares.insert(
+            workspace_id.clone(),
+            ActiveShare {
+                info: info.clone(),
+                cancel: cancel.clone(),
+            },
+        );
+    }
+
+    let limits = ServerLimits {
+        connections: Arc::new(Semaphore::new(server.max_connections.max(1))),
+        read_timeout: Duration::from_secs(server.timeout_seconds.max(1)),
+    };
+    tauri::async_runtime::spawn(serve(listener, app, window, info.clone(), cancel, limits));
+    info!(workspace_id = %workspace_id, url = %info.url, "Workspace share started");
+    Ok(info)
+}
+
+/// 停止共享；工作区没有在共享时返回 `false`
+#[tauri::command]
+pub async fn stop_workspace_share(
+    workspace_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, CommandError> {
+    require_tenant_workspace(&app, &state, &workspace_id, client_id.as_deref()).await?;
+    Ok(stop_share(&workspace_id))
+}
+
+/// 列出本机正在进行的共享；租户会话只能看到本租户工作区的共享
+#[tauri::command]
+pub async fn list_workspace_shares(
+    client_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ShareInfo>, CommandError> {
+    let tenant = caller_tenant(&state, client_id.as_deref())?;
+    let mut shares: Vec<ShareInfo> = SHARES
+        .lock()
+        .values()
+        .filter(|share| {
+            tenant.is_none()
+                || state
+                    .get_workspace_service(&share.info.workspace_id)
+                    .is_some_and(|service| workspace_tenant(&service) == tenant)
+        })
+        .map(|share| share.info.clone())
+        .collect();
+    shares.sort_by(|a, b| a.workspace_id.cmp(&b.workspace_id));
+    Ok(shares)
+}
+
+/// 停止工作区的共享（删除工作区时调用）
+pub(crate) fn stop_share(workspace_id: &str) -> bool {
+    let Some(share) = SHARES.lock().remove(workspace_id) else {
+        return false;
+    };
+    share.cancel.cancel();
+    true
+}
+
+struct ServerLimits {
+    connections: Arc<Semaphore>,
+    read_timeout: Duration,
+}
+
+async fn serve(
+    listener: TcpListener,
+    app: AppHandle,
+    window: Window,
+    share: ShareInfo,
+    cancel: CancellationToken,
+    limits: ServerLimits,
+) {
+    loop {
+        let (mut stream, peer) = tokio::select! {
+            _ = cancel.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!(error = %e, "Workspace share failed to accept a connection");
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            },
+        };
+
+        let Ok(permit) = Arc::clone(&limits.connections).try_acquire_owned() else {
+            debug!(peer = %peer, "Workspace share is busy; rejecting connection");
+            tauri::async_runtime::spawn(async move {
+                let busy = HttpError::new(503, "RATE_LIMITED", "Too many concurrent requests");
+                let _ = stream.write_all(&busy.to_response()).await;
+            });
+            continue;
+        };
+        let (app, window, share) = (app.clone(), window.clone(), share.clone());
+        let read_timeout = limits.read_timeout;
+        tauri::async_runtime::spawn(async move {
+            handle_connection(stream, peer, &app, &window, &share, read_timeout).await;
+            drop(permit);
+        });
+    }
+    info!(workspace_id = %share.workspace_id, "Workspace share stopped");
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    peer: SocketAddr,
+    app: &AppHandle,
+    window: &Window,
+    share: &ShareInfo,
+    read_timeout: Duration,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let response = match tokio::time::timeout(read_timeout, read_request(reader)).await {
+        Err(_) => {
+            HttpError::new(408, "TIMEOUT_ERROR", "Timed out reading the request").to_response()
+        }
+        Ok(Err(e)) => e.to_response(),
+        Ok(Ok((head, body))) => respond(head, &body, peer, app, window, share).await,
+    };
+    if let Err(e) = writer.write_all(&response).await {
+        debug!(peer = %peer, error = %e, "Failed to write workspace share response");
+    }
+    let _ = writer.shutdown().await;
+}
+
+/// 读取请求头（以空行结束）与 `Content-Length` 指定长度的请求体
+async fn read_request(stream: OwnedReadHalf) -> Result<(RequestHead, Vec<u8>), HttpError> {
+    let read_error = |e: std::io::Error| {
+        HttpError::new(
+            400,
+            "VALIDATION_ERROR",
+            format!("Failed to read request: {e}"),
+        )
+    };
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    loop {
+        let start = head.len();
+        let read = (&mut reader)
+            .take((MAX_HEAD_BYTES + 1 - start) as u64)
+            .read_line(&mut head)
+            .await
+            .map_err(read_error)?;
+        if head.len() > MAX_HEAD_BYTES {
+            return Err(HttpError::new(
+                431,
+                "VALIDATION_ERROR",
+                "Request headers are too large",
+            ));
+        }
+        if read == 0 {
+            return Err(HttpError::new(
+                400,
+                "VALIDATION_ERROR",
+                "Connection closed before the request was complete",
+            ));
+        }
+        if head[start..].trim_end_matches(['\r', '\n']).is_empty() {
+            head.truncate(start);
+            break;
+        }
+    }
+
+    let head = parse_head(&head)?;
+    let mut body = vec![0; head.content_length];
+    reader.read_exact(&mut body).await.map_err(read_error)?;
+    Ok((head, body))
+}
+
+async fn respond(
+    head: RequestHead,
+    body: &[u8],
+    peer: SocketAddr,
+    app: &AppHandle,
+    window: &Window,
+    share: &ShareInfo,
+) -> Vec<u8> {
+    if !token_matches(&share.token, head.token.as_deref()) {
+        warn!(peer = %peer, "Rejected workspace share request with an invalid token");
+        return HttpError::unauthorized().to_response();
+    }
+    let result = match route(&head.method, &head.path) {
+        Err(e) => return e.to_response(),
+        Ok(Route::Info) => Ok(info_json(&share.workspace_id)),
+        Ok(Route::Action(action)) => match scope_args(body, &share.workspace_id) {
+            Err(e) => return e.to_response(),
+            Ok(args) => {
+                debug!(peer = %peer, action, "Workspace share request");
+                run_action(action, args, peer, app, window).await
+            }
+        },
+    };
+    match result {
+        Ok(value) => encode_response(200, &value),
+        Err(e) => encode_response(
+            error_status(&e.code),
+            &serde_json::to_value(e).unwrap_or(Value::Null),
+        ),
+    }
+}
+
+/// 执行共享端收到的操作。操作（搜索、操作表）中含开始共享命令本身，直接 await
+/// 会让各 future 的类型引用自身，因此返回装箱的 future 截断这一环
+fn run_action<'a>(
+    action: &'a str,
+    args: Map<String, Value>,
+    peer: SocketAddr,
+    app: &'a AppHandle,
+    window: &'a Window,
+) -> BoxFuture<'a, Result<Value, CommandError>> {
+    Box::pin(async move {
+        let state = app.state::<AppState>();
+        match action {
+            "search_logs" => {
+                search(args, app, window, state, &format!("share:{}", peer.ip())).await
+            }
+            // 虚拟文件树不在操作表中，直接调用命令
+            "get_virtual_file_tree" => {
+                let workspace_id = args
+                    .get("workspaceId")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                let client_id = args
+                    .get("clientId")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                let tree = get_virtual_file_tree(app.clone(), workspace_id, client_id, state)
+                    .await
+                    .map_err(|e| CommandError::new("DATABASE_ERROR", e))?;
+                serde_json::to_value(tree)
+                    .map_err(|e| CommandError::new("SERIALIZATION_ERROR", e.to_string()))
+            }
+            _ => {
+                dispatch_action(
+                    action,
+                    Some(Value::Object(args)),
+                    app.clone(),
+                    state,
+                    window.clone(),
+                )
+                .await
+            }
+        }
+    })
+}
+
+fn remotes_path(app: &AppHandle) -> Result<PathBuf, CommandError> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(REMOTES_FILE))
+        .map_err(|e| {
+            CommandError::new(
+                "CONFIG_ERROR",
+                format!("Failed to resolve config directory: {e}"),
+            )
+        })
+}
+
+async fn read_remotes(app: &AppHandle) -> Result<Vec<RemoteWorkspace>, CommandError> {
+    let path = remotes_path(app)?;
+    tokio::task::spawn_blocking(move || load_remotes(&path))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Task panicked: {e}")))?
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))
+}
+
+async fn write_remotes(app: &AppHandle, remotes: Vec<RemoteWorkspace>) -> Result<(), CommandError> {
+    let path = remotes_path(app)?;
+    tokio::task::spawn_blocking(move || save_remotes(&path, &remotes))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Task panicked: {e}")))?
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))
+}
+
+/// 取得挂载的客户端；挂载记录变化（如更新令牌）后换用新的客户端与缓存
+async fn client_for(
+    app: &AppHandle,
+    remote_id: &str,
+) -> Result<Arc<RemoteWorkspaceClient>, CommandError> {
+    let remote = read_remotes(app)
+        .await?
+        .into_iter()
+        .find(|r| r.id == remote_id)
+        .ok_or_else(|| {
+            CommandError::new(
+                "NOT_FOUND",
+                format!("Remote workspace {remote_id} not found"),
+            )
+        })?;
+    let mut clients = CLIENTS.lock();
+    if let Some(client) = clients.get(remote_id) {
+        if *client.remote() == remote {
+            return Ok(Arc::clone(client));
+        }
+    }
+    let client = Arc::new(RemoteWorkspaceClient::new(remote));
+    clients.insert(remote_id.to_string(), Arc::clone(&client));
+    Ok(client)
+}
+
+/// 校验共享端并保存挂载记录
+async fn mount(
+    app: &AppHandle,
+    url: String,
+    token: String,
+    name: Option<String>,
+) -> Result<RemoteWorkspace, CommandError> {
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err(CommandError::new(
+            "VALIDATION_ERROR",
+            "Share token is required",
+        ));
+    }
+
+    let info = fetch_info(&url, &token).await?;
+    let workspace_id = info
+        .get("workspaceId")
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            CommandError::new("NETWORK_ERROR", "Remote server did not report a workspace")
+        })?
+        .to_string();
+    let remote = RemoteWorkspace {
+        id: format!("remote-{}", &generate_token()[..12]),
+        name: name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| workspace_id.clone()),
+        url,
+        token,
+        workspace_id,
+    };
+
+    let _guard = REMOTES_LOCK.lock().await;
+    let mut remotes = read_remotes(app).await?;
+    let remote = upsert_remote(&mut remotes, remote)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    write_remotes(app, remotes).await?;
+    info!(remote_id = %remote.id, url = %remote.url, "Mounted remote workspace");
+    Ok(remote)
+}
+
+/// 挂载远程工作区：校验地址与令牌并记录共享的工作区 id
+#[tauri::command]
+pub async fn mount_remote_workspace(
+    url: String,
+    token: String,
+    name: Option<String>,
+    app: AppHandle,
+) -> Result<RemoteWorkspace, CommandError> {
+    let url = normalize_remote_url(&url).map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    mount(&app, url, token, name).await
+}
+
+/// 按地址打开远程工作区的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWorkspaceOpened {
+    pub remote: RemoteWorkspace,
+    /// 远程工作区的虚拟文件树
+    pub tree: Value,
+}
+
+/// 按共享链接（或地址加令牌）打开远程工作区：挂载后加载虚拟文件树
+///
+/// 同一地址上的同一工作区重复打开时沿用原有挂载。
+#[tauri::command]
+pub async fn open_remote_workspace(
+    url: String,
+    token: Option<String>,
+    name: Option<String>,
+    app: AppHandle,
+) -> Result<RemoteWorkspaceOpened, CommandError> {
+    let (url, link_token) =
+        parse_share_link(&url).map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let token = token
+        .filter(|t| !t.trim().is_empty())
+        .or(link_token)
+        .unwrap_or_default();
+    let remote = mount(&app, url, token, name).await?;
+    let tree = client_for(&app, &remote.id).await?.virtual_tree().await?;
+    Ok(RemoteWorkspaceOpened { remote, tree })
+}
+
+/// 列出已挂载的远程工作区
+#[tauri::command]
+pub async fn list_remote_workspaces(app: AppHandle) -> Result<Vec<RemoteWorkspace>, CommandError> {
+    read_remotes(&app).await
+}
+
+/// 取消挂载；不存在时返回 `false`
+#[tauri::command]
+pub async fn unmount_remote_workspace(
+    remote_id: String,
+    app: AppHandle,
+) -> Result<bool, CommandError> {
+    let _guard = REMOTES_LOCK.lock().await;
+    CLIENTS.lock().remove(&remote_id);
+    let mut remotes = read_remotes(&app).await?;
+    let before = remotes.len();
+    remotes.retain(|r| r.id != remote_id);
+    if remotes.len() == before {
+        return Ok(false);
+    }
+    write_remotes(&app, remotes).await?;
+    Ok(true)
+}
+
+/// 丢弃远程工作区缓存的查询结果（共享端导入新文件后刷新），返回缓存占用
+#[tauri::command]
+pub async fn refresh_remote_workspace(
+    remote_id: String,
+    app: AppHandle,
+) -> Result<RemoteCacheStats, CommandError> {
+    let client = client_for(&app, &remote_id).await?;
+    client.invalidate_queries();
+    Ok(client.cache_stats())
+}
+
+/// 在远程工作区上调用只读操作，参数与本地操作相同（`workspaceId` 由共享端决定）
+#[tauri::command]
+pub async fn remote_workspace_call(
+    remote_id: String,
+    action: String,
+    args: Option<Value>,
+    app: AppHandle,
+) -> Result<Value, CommandError> {
+    let args = match args {
+        Some(Value::Object(args)) => args,
+        None | Some(Value::Null) => Map::new(),
+        Some(_) => {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                "Arguments must be a JSON object",
+            ))
+        }
+    };
+    client_for(&app, &remote_id)
+        .await?
+        .call(&action, args)
+        .await
+}
@@ -0,0 +1,386 @@
+//! 工作区模板命令
+//!
+//! 模板的增删查、导入导出，以及从模板创建工作区（见 `services::workspace_templates`）。
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use la_core::error::CommandError;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, warn};
+
+use crate::commands::import::import_folder;
+use crate::commands::workspace::{load_field_extractors, reindex_same_content};
+use crate::models::AppState;
+use crate::services::workspace_templates::{
+    load_templates, save_templates, upsert_template, TemplatePathRules, WorkspaceTemplate,
+    TEMPLATES_FILE,
+};
+use crate::utils::validation::validate_workspace_id;
+use crate::utils::workspace_paths::build_workspace_id;
+
+/// 串行化模板文件的读改写
+static TEMPLATES_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// 从模板创建工作区的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateWorkspaceResult {
+    pub workspace_id: String,
+    pub task_id: String,
+    /// 按忽略规则标记为忽略的文件数
+    pub ignored_files: usize,
+    /// 按格式规则改判格式的文件数
+    pub reformatted_files: usize,
+}
+
+fn templates_path(app: &AppHandle) -> Result<PathBuf, CommandError> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(TEMPLATES_FILE))
+        .map_err(|e| {
+            CommandError::new(
+                "CONFIG_ERROR",
+                format!("Failed to resolve config directory: {e}"),
+            )
+        })
+}
+
+async fn read_templates(app: &AppHandle) -> Result<Vec<WorkspaceTemplate>, CommandError> {
+    let path = templates_path(app)?;
+    tokio::task::spawn_blocking(move || load_templates(&path))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Task panicked: {e}")))?
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))
+}
+
+async fn write_templates(
+    app: &AppHandle,
+    templates: Vec<WorkspaceTemplate>,
+) -> Result<(), CommandError> {
+    let path = templates_path(app)?;
+    tokio::task::spawn_blocking(move || save_templates(&path, &templates))
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Task panicked: {e}")))?
+        .map_err(|e| CommandError::new("CONFIG_ERROR", e))
+}
+
+/// 校验后保存模板；`overwrite` 为 `false` 时同 id 模板已存在返回 `ALREADY_EXISTS`
+async fn store_template(
+    app: &AppHandle,
+    template: WorkspaceTemplate,
+    overwrite: bool,
+) -> Result<WorkspaceTemplate, CommandError> {
+    template
+        .check()
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+
+    let _guard = TEMPLATES_LOCK.lock().await;
+    let mut templates = read_templates(app).await?;
+    if !overwrite && templates.iter().any(|t| t.id == template.id) {
+        return Err(CommandError::new(
+            "ALREADY_EXISTS",
+            format!("Workspace template '{}' already exists", template.id),
+        )
+        .with_help("Import with overwrite enabled to replace it"));
+    }
+    upsert_template(&mut templates, template.clone(), overwrite)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    write_templates(app, templates).await?;
+    Ok(template)
+}
+
+async fn find_template(app: &AppHandle, id: &str) -> Result<WorkspaceTemplate, CommandError> {
+    read_templates(app)
+        .await?
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| {
+            CommandError::new("NOT_FOUND", format!("Workspace template not found: {id}"))
+        })
+}
+
+/// 列出已保存的工作区模板
+#[tauri::command]
+pub async fn list_workspace_templates(
+    app: AppHandle,
+) -> Result<Vec<WorkspaceTemplate>, CommandError> {
+    read_templates(&app).await
+}
+
+/// 保存工作区模板；同 id 的模板被替换
+#[tauri::command]
+pub async fn save_workspace_template(
+    template: WorkspaceTemplate,
+    app: AppHandle,
+) -> Result<WorkspaceTemplate, CommandError> {
+    store_template(&app, template, true).await
+}
+
+/// 删除工作区模板；返回是否存在
+#[tauri::command]
+pub async fn delete_workspace_template(id: String, app: AppHandle) -> Result<bool, CommandError> {
+    let _guard = TEMPLATES_LOCK.lock().await;
+    let mut templates = read_templates(&app).await?;
+    let before = templates.len();
+    templates.retain(|t| t.id != id);
+    if templates.len() == before {
+        return Ok(false);
+    }
+    write_templates(&app, templates).await?;
+    Ok(true)
+}
+
+/// 以现有工作区的设置（解压后处理钩子、字段提取器、搜索默认值、单行长度上限）
+/// 保存为模板；同 id 的模板被替换
+///
+/// 文件级的忽略标记与格式改判依赖具体路径，不会带入模板，可在保存后补充路径规则。
+#[tauri::command]
+pub async fn capture_workspace_template(
+    workspace_id: String,
+    client_id: Option<String>,
+    id: String,
+    name: String,
+    description: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceTemplate, CommandError> {
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_tenant_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    let store = service.metadata_store();
+
+    let post_extract_hooks = store
+        .get_workspace_setting(la_archive::post_extract::POST_EXTRACT_HOOKS_SETTING)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let search_defaults = crate::commands::search::load_workspace_search_defaults(store).await;
+    let max_line_length = store
+        .get_workspace_setting(crate::infrastructure::log_file_repo::MAX_LINE_LENGTH_SETTING)
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?
+        .and_then(|v| v.parse().ok())
+        .filter(|limit: &usize| *limit > 0);
+
+    let template = WorkspaceTemplate {
+        id,
+        name,
+        description: description.unwrap_or_default(),
+        post_extract_hooks,
+        field_extractors: load_field_extractors(store).await?,
+        search_defaults: (search_defaults != Default::default()).then_some(search_defaults),
+        max_line_length,
+        ignore_patterns: Vec::new(),
+        format_rules: Vec::new(),
+    };
+    store_template(&app, template, true).await
+}
+
+/// 导出模板为 JSON 文本
+#[tauri::command]
+pub async fn export_workspace_template(id: String, app: AppHandle) -> Result<String, CommandError> {
+    let template = find_template(&app, &id).await?;
+    serde_json::to_string_pretty(&template)
+        .map_err(|e| CommandError::new("SERIALIZATION_ERROR", e.to_string()))
+}
+
+/// 从 `export_workspace_template` 导出的 JSON 导入模板
+///
+/// 同 id 的模板已存在时，`overwrite` 为 `true` 才替换，否则返回 `ALREADY_EXISTS`。
+#[tauri::command]
+pub async fn import_workspace_template(
+    json: String,
+    overwrite: Option<bool>,
+    app: AppHandle,
+) -> Result<WorkspaceTemplate, CommandError> {
+    let template: WorkspaceTemplate = serde_json::from_str(&json).map_err(|e| {
+        CommandError::new(
+            "VALIDATION_ERROR",
+            format!("Invalid workspace template: {e}"),
+        )
+    })?;
+    store_template(&app, template, overwrite.unwrap_or(false)).await
+}
+
+/// 按模板创建工作区
+///
+/// 解压后处理钩子随导入生效；导入完成后写入其余工作区设置，再把忽略规则与格式规则
+/// 应用到命中的文件（忽略的文件移出索引，改判格式的内容重建索引）。
+#[tauri::command]
+pub async fn create_workspace_from_template(
+    name: String,
+    path: String,
+    template_id: String,
+    client_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<TemplateWorkspaceResult, CommandError> {
+    let template = find_template(&app, &template_id).await?;
+    // 模板可能来自其他机器（例如插件未注册），导入前重新校验
+    template
+        .check()
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let rules = TemplatePathRules::compile(&template)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+
+    let canonical_path = crate::utils::validation::validate_import_source_path(&path, "path")
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    let workspace_id = build_workspace_id(&name);
+    validate_workspace_id(&workspace_id).map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+
+    info!(
+        workspace_id = %workspace_id,
+        template_id = %template_id,
+        "Creating workspace from template"
+    );
+    let hooks =
+        (!template.post_extract_hooks.is_empty()).then(|| template.post_extract_hooks.clone());
+    let task_id = import_folder(
+        app.clone(),
+        canonical_path.to_string_lossy().into_owned(),
+        workspace_id.clone(),
+        None,
+        None,
+        hooks,
+        None,
+        client_id.clone(),
+        state.clone(),
+    )
+    .await
+    .map_err(|e| CommandError::new("IMPORT_ERROR", e))?;
+
+    let (service, _workspace_dir) = crate::utils::workspace_guard::require_writable_workspace(
+        &app,
+        &state,
+        &workspace_id,
+        client_id.as_deref(),
+    )
+    .await?;
+    apply_template_settings(service.metadata_store(), &template).await?;
+
+    let (ignored_files, reformatted_files) = if template.has_path_rules() {
+        apply_path_rules(&service, &rules).await.inspect_err(|e| {
+            warn!(
+                workspace_id = %workspace_id,
+                error = %e.message,
+                "工作区已创建，但模板路径规则应用失败"
+            )
+        })?
+    } else {
+        (0, 0)
+    };
+
+    info!(
+        workspace_id = %workspace_id,
+        template_id = %template_id,
+        ignored_files,
+        reformatted_files,
+        "工作区已按模板创建"
+    );
+    Ok(TemplateWorkspaceResult {
+        workspace_id,
+        task_id,
+        ignored_files,
+        reformatted_files,
+    })
+}
+
+async fn apply_template_settings(
+    store: &la_storage::MetadataStore,
+    template: &WorkspaceTemplate,
+) -> Result<(), CommandError> {
+    let mut settings = Vec::new();
+    if !template.field_extractors.is_empty() {
+        settings.push((
+            crate::services::field_extractors::FIELD_EXTRACTORS_SETTING,
+            serde_json::to_string(&template.field_extractors),
+        ));
+    }
+    if let Some(defaults) = &template.search_defaults {
+        settings.push((
+            crate::commands::search::SEARCH_DEFAULTS_SETTING,
+            serde_json::to_string(defaults),
+        ));
+    }
+    for (key, json) in settings {
+        let json = json.map_err(|e| CommandError::new("SERIALIZATION_ERROR", e.to_string()))?;
+        store
+            .set_workspace_setting(key, &json)
+            .await
+            .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    }
+    if let Some(limit) = template.max_line_length {
+        store
+            .set_workspace_setting(
+                crate::infrastructure::log_file_repo::MAX_LINE_LENGTH_SETTING,
+                &limit.to_string(),
+            )
+            .await
+            .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// 应用忽略规则与格式规则，返回（忽略的文件数，改判格式的文件数）
+async fn apply_path_rules(
+    service: &crate::application::workspace_service::WorkspaceServiceRef,
+    rules: &TemplatePathRules,
+) -> Result<(usize, usize), CommandError> {
+    use la_storage::FileSearchFlag;
+
+    let metadata_store = Arc::clone(service.metadata_store());
+    let files = metadata_store
+        .get_all_files()
+        .await
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+
+    let mut ignored = Vec::new();
+    let mut reformatted = 0;
+    let mut reformatted_hashes = HashSet::new();
+    for file in &files {
+        if rules.is_ignored(&file.virtual_path) {
+            metadata_store
+                .set_file_search_flag(&file.virtual_path, FileSearchFlag::Ignored)
+                .await
+                .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+            ignored.push(file.virtual_path.clone());
+        } else if let Some(format) = rules.format_for(&file.virtual_path) {
+            reformatted += 1;
+            if reformatted_hashes.insert(file.sha256_hash.clone()) {
+                metadata_store
+                    .set_format_override(&file.sha256_hash, Some(format))
+                    .await
+                    .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+                reindex_same_content(service, file).await?;
+            }
+        }
+    }
+
+    let ignored_count = ignored.len();
+    if !ignored.is_empty() {
+        let search_manager = Arc::clone(service.search_engine());
+        tokio::task::spawn_blocking(move || -> Result<(), String> {
+            for virtual_path in &ignored {
+                search_manager
+                    .delete_file_documents(virtual_path)
+                    .map_err(|e| format!("Failed to remove indexed documents: {e}"))?;
+            }
+            search_manager
+                .commit()
+                .map_err(|e| format!("Failed to commit search index: {e}"))
+        })
+        .await
+        .map_err(|e| CommandError::new("TASK_ERROR", format!("Index update panicked: {e}")))?
+        .map_err(|e| CommandError::new("SEARCH_ERROR", e))?;
+    }
+
+    Ok((ignored_count, reformatted))
+}
@@ -0,0 +1,47 @@
+//! 客户端会话回收：释放断开或超时客户端遗留的搜索与跟随查询。
+//!
+//! `ClientSessionManager` 只负责记账；真正的资源分布在 `SearchSessionManager`
+//! （取消令牌、固定快照、结果）和各工作区服务（跟随查询）中，由这里逐项释放。
+//! 限流桶随会话本身一起丢弃。
+
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::application::client_sessions::{
+    ClientResources, CLIENT_IDLE_TIMEOUT, CLIENT_REAP_INTERVAL,
+};
+use crate::models::AppState;
+
+/// 释放一个已关闭会话遗留的资源
+pub fn release_client_resources(state: &AppState, resources: &ClientResources) {
+    if let Some(manager) = state.get_search_session_manager() {
+        for search_id in &resources.searches {
+            manager.close_session(search_id);
+        }
+    }
+    for (workspace_id, query_id) in &resources.follow_queries {
+        if let Some(workspace) = state.get_workspace_service(workspace_id) {
+            workspace.detach_follow_query(query_id);
+        }
+    }
+    info!(
+        client_id = %resources.client_id,
+        searches = resources.searches.len(),
+        follow_queries = resources.follow_queries.len(),
+        "Released client session resources"
+    );
+}
+
+/// 后台定期回收超过 `CLIENT_IDLE_TIMEOUT` 未心跳的客户端会话
+pub fn spawn_client_session_reaper(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CLIENT_REAP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+            for resources in state.sync.clients().reap_idle(CLIENT_IDLE_TIMEOUT) {
+                release_client_resources(&state, &resources);
+            }
+        }
+    });
+}
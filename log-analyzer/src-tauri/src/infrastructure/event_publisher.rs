@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use tauri::Emitter;
 
-use la_core::domain::event::{EventPublisher, SearchSummary};
+use la_core::domain::event::{EventPublisher, SearchSummary, SecurityWarning};
 
 /// Adapter that delegates to Tauri's event system.
 #[derive(Clone)]
@@ -28,6 +28,20 @@ impl EventPublisher for TauriEventPublisher {
     }
 
     async fn emit_search_complete(&self, search_id: &str, summary: SearchSummary) {
+        let outcome = if summary.was_cancelled {
+            "cancelled"
+        } else if summary.timed_out {
+            "timedOut"
+        } else if summary.was_truncated {
+            "truncated"
+        } else {
+            "completed"
+        };
+        crate::monitoring::telemetry().record_operation(
+            "search",
+            std::time::Duration::from_millis(summary.duration_ms),
+            outcome,
+        );
         let _ = self.app_handle.emit(
             "search-complete",
             serde_json::json!({
@@ -39,8 +53,11 @@ impl EventPublisher for TauriEventPublisher {
             "search-summary",
             serde_json::json!({
                 "search_id": search_id,
+                "summary": summary.to_result_summary(),
                 "duration_ms": summary.duration_ms,
                 "was_truncated": summary.was_truncated,
+                "was_cancelled": summary.was_cancelled,
+                "timed_out": summary.timed_out,
             }),
         );
     }
@@ -85,6 +102,10 @@ impl EventPublisher for TauriEventPublisher {
             );
         }
     }
+
+    async fn emit_security_warning(&self, warning: &SecurityWarning) {
+        let _ = self.app_handle.emit("security-warning", warning);
+    }
 }
 
 impl TauriEventPublisher {
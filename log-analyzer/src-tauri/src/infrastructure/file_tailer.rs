@@ -5,10 +5,13 @@
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
 use la_core::error::{AppError, Result};
+use la_core::utils::split_lines;
+
+use crate::utils::encoding::decode_log_content;
 
 /// Result of a tail operation.
 #[derive(Debug, PartialEq, Eq)]
@@ -98,17 +101,11 @@ fn read_file_from_offset(path: &Path, offset: u64) -> Result<(Vec<String>, u64,
     file.seek(SeekFrom::Start(start_offset))
         .map_err(AppError::Io)?;
 
-    let reader = BufReader::with_capacity(65536, file);
-    let mut lines = Vec::new();
-    for line_result in reader.lines() {
-        match line_result {
-            Ok(line) => lines.push(line),
-            Err(e) => {
-                tracing::warn!(error = %e, "Error reading line, continuing");
-                continue;
-            }
-        }
-    }
+    // 整段读取后有损解码：含无效字节的行保留（以 U+FFFD 替换），不会被跳过而打乱行号
+    let mut bytes = Vec::with_capacity((file_size - start_offset) as usize);
+    file.read_to_end(&mut bytes).map_err(AppError::Io)?;
+    let (text, _) = decode_log_content(&bytes);
+    let lines = split_lines(&text).map(str::to_string).collect();
 
     Ok((lines, file_size, start_offset))
 }
@@ -221,6 +218,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn invalid_utf8_and_bare_cr_lines_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mixed.log");
+        std::fs::write(&path, b"ok\rbad \xff\r\nlast\n").unwrap();
+
+        let mut tailer = FileTailer::new(dir.path().to_path_buf());
+        tailer.on_create(&path);
+        let result = tailer.tail(&path).unwrap();
+
+        assert_eq!(result.lines, vec!["ok", "bad \u{FFFD}", "last"]);
+    }
+
     #[test]
     fn truncated_file_is_reread_from_beginning() {
         let dir = tempfile::tempdir().unwrap();
@@ -1,7 +1,7 @@
 //! ImportPipeline — 导入生命周期编排器。
 //!
 //! 将 `import_folder` Tauri 命令中原本混杂的验证、目录创建、
-//! TaskScheduler 生命周期、WorkspaceService 创建、导入调用、失败清理、
+//! 目录扫描、TaskScheduler 生命周期、WorkspaceService 创建、导入调用、失败清理、
 //! 后台完整性验证和 Tantivy 段合并抽取到本模块，使命令层保持薄。
 //!
 //! # 职责边界
@@ -20,19 +20,27 @@ use uuid::Uuid;
 use crate::application::workspace_service::ImportOptions;
 use crate::infrastructure::workspace_service_factory::get_or_create_workspace_service;
 use crate::models::AppState;
+use crate::services::webhooks::WebhookEventKind;
 use crate::utils::canonicalize_path;
 use crate::utils::validation::{validate_import_source_path, validate_workspace_id};
+use la_archive::{scan_import_directory, ScanProgress, ScannedDirectory};
 use la_core::domain::event::EventPublisher;
 use la_core::domain::{TaskHandle, WorkspacePaths};
-use la_core::error::AppError;
+use la_core::error::{AppError, CommandError, ErrorCategory};
 use la_core::traits::AppConfigProvider;
 use la_storage::verify_after_import;
 
+/// 资源不足时等待恢复的最长时间
+const RESOURCE_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+/// 目录扫描进度的上报间隔
+const SCAN_PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
 /// 运行导入生命周期，返回 TaskScheduler 创建的任务 ID。
 ///
 /// 通过 trait 引用接收基础设施依赖，不绑定 Tauri 具体类型，可独立测试。
 ///
 /// `event_publisher` 使用 `Arc` 以支持后台任务的 fire-and-forget 事件发送。
+#[allow(clippy::too_many_arguments)]
 pub async fn run_import(
     event_publisher: Arc<dyn EventPublisher>,
     workspace_paths: &dyn WorkspacePaths,
@@ -41,6 +49,7 @@ pub async fn run_import(
     state: &AppState,
     workspace_id: &str,
     path: &str,
+    mut options: ImportOptions,
 ) -> Result<String, String> {
     validate_workspace_id(workspace_id)?;
 
@@ -55,6 +64,26 @@ pub async fn run_import(
         }
     };
 
+    let target_name = canonical_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+        .to_string();
+
+    // ── 目录扫描（独立任务，可取消；取消时不创建工作区目录）──
+    if canonical_path.is_dir() {
+        options.prescanned = Some(Arc::new(
+            scan_source_directory(
+                state,
+                config_provider,
+                workspace_id,
+                &canonical_path,
+                &target_name,
+            )
+            .await?,
+        ));
+    }
+
     let workspace_dir = workspace_paths.workspace_data_dir(workspace_id)?;
     fs::create_dir_all(&workspace_dir).map_err(|e| {
         AppError::io_error(
@@ -64,12 +93,6 @@ pub async fn run_import(
         .to_string()
     })?;
 
-    let target_name = canonical_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(path)
-        .to_string();
-
     // ── TaskScheduler 任务创建 ──
     let task_id = Uuid::new_v4().to_string();
     let scheduler = state
@@ -89,6 +112,7 @@ pub async fn run_import(
         &workspace_dir,
     )
     .await
+    .map_err(|e| e.message)
     .inspect_err(|e| {
         let publisher = Arc::clone(&event_publisher);
         let error_msg = e.clone();
@@ -98,15 +122,31 @@ pub async fn run_import(
     })?;
 
     // ── 更新任务进度 ──
-    let _ = scheduler.update(&handle, 10, "Scanning...").await;
+    let _ = scheduler.update(&handle, 10, "Importing...").await;
+
+    // ── 资源不足时暂停，等待恢复后再开始导入 ──
+    let resource_gate = state.monitoring.resource_gate();
+    if resource_gate.pressure().is_under_pressure() {
+        let _ = scheduler
+            .update(&handle, 10, "Waiting for system resources...")
+            .await;
+        if !resource_gate.wait_for_capacity(RESOURCE_WAIT_TIMEOUT).await {
+            let msg = "Import aborted: system memory or disk space is too low".to_string();
+            warn!(workspace_id = %workspace_id, "{msg}");
+            let _ = scheduler.fail(&handle, &msg).await;
+            event_publisher.emit_import_error(&msg).await;
+            return Err(msg);
+        }
+        let _ = scheduler.update(&handle, 10, "Importing...").await;
+    }
 
     // ── 调用 ImportService ──
     let cancel_token = tokio_util::sync::CancellationToken::new();
 
-    let _import_result = match service
+    let import_result = match service
         .import_file(
             &canonical_path,
-            ImportOptions::default(),
+            options,
             config_provider,
             &task_id,
             cancel_token,
@@ -129,14 +169,43 @@ pub async fn run_import(
             }
             let msg = format!("Failed to import: {e}");
             let _ = scheduler.fail(&handle, &msg).await;
+            // 缺少密码时返回结构化错误，前端按 `PASSWORD_REQUIRED` 提示输入密码后重试
+            if e.category() == ErrorCategory::PasswordRequired {
+                return Err(serde_json::to_string(&CommandError::from(e)).unwrap_or(msg));
+            }
             return Err(msg);
         }
     };
 
+    // ── 支持包清单 → 工作区元数据（失败不影响导入结果）──
+    match crate::services::bundle_manifest::apply_bundle_manifest(
+        service.metadata_store(),
+        service.cas(),
+    )
+    .await
+    {
+        Ok(Some(manifest)) => {
+            info!(workspace_id = %workspace_id, manifest = %manifest, "Bundle manifest detected")
+        }
+        Ok(None) => {}
+        Err(e) => warn!(workspace_id = %workspace_id, error = %e, "Failed to read bundle manifest"),
+    }
+
     // ── 完成 ──
     let _ = scheduler.update(&handle, 100, "Import complete").await;
     let _ = scheduler.complete(&handle).await;
     event_publisher.emit_import_complete(&task_id).await;
+    crate::services::webhooks::notify(
+        &tokio::runtime::Handle::current(),
+        Arc::clone(service.metadata_store()),
+        workspace_id,
+        WebhookEventKind::ImportComplete,
+        serde_json::json!({
+            "taskId": task_id,
+            "source": import_result.root_name,
+            "filesImported": import_result.files_imported,
+        }),
+    );
 
     // ── 完整性验证（后台执行）──
     let verify_publisher = Arc::clone(&event_publisher);
@@ -196,6 +265,83 @@ pub async fn run_import(
     Ok(task_id)
 }
 
+/// 以独立的 "Scan" 任务遍历导入目录，返回扫描结果（文件数作为导入进度的分母，
+/// 目录项由处理阶段回放）
+///
+/// 任务消息实时显示已访问的目录数与发现的文件数；任务被取消（`cancel_task`）时
+/// 扫描在下一个目录项处停止，导入不再继续。
+async fn scan_source_directory(
+    state: &AppState,
+    config_provider: &dyn AppConfigProvider,
+    workspace_id: &str,
+    root: &Path,
+    target_name: &str,
+) -> Result<ScannedDirectory, String> {
+    let scheduler = state
+        .get_task_scheduler()
+        .ok_or("Task manager not initialized")?;
+    let task_manager = state
+        .get_task_manager_clone()
+        .ok_or("Task manager not initialized")?;
+    let task_id = Uuid::new_v4().to_string();
+    let handle = TaskHandle::new(&task_id);
+    scheduler
+        .create(&task_id, "Scan", target_name, Some(workspace_id))
+        .await
+        .map_err(|e| format!("Failed to create task: {e}"))?;
+    let cancel = task_manager.cancellation_token(&task_id);
+
+    let progress = Arc::new(ScanProgress::new());
+    let scan = scan_import_directory(root, config_provider, Arc::clone(&progress), cancel.clone());
+    tokio::pin!(scan);
+    let mut ticker = tokio::time::interval(SCAN_PROGRESS_INTERVAL);
+    let result = loop {
+        tokio::select! {
+            result = &mut scan => break result,
+            _ = ticker.tick() => {
+                // 已取消的任务不再上报进度，避免把状态改回运行中
+                if cancel.is_cancelled() {
+                    continue;
+                }
+                let (directories, files) = progress.counts();
+                let message = format!("Scanning: {directories} directories, {files} files");
+                let _ = scheduler.update(&handle, 0, &message).await;
+            }
+        }
+    };
+
+    match result {
+        Ok(scanned) => {
+            info!(
+                workspace_id = %workspace_id,
+                directories = scanned.summary.directories,
+                files = scanned.summary.files,
+                unreadable = scanned.summary.errors,
+                "Import directory scan completed"
+            );
+            let _ = scheduler.complete(&handle).await;
+            Ok(scanned)
+        }
+        Err(e) if e.category() == ErrorCategory::CancellationRequested => {
+            let (directories, files) = progress.counts();
+            info!(
+                workspace_id = %workspace_id,
+                directories,
+                files,
+                "Import directory scan cancelled"
+            );
+            let _ = scheduler.cancel(&handle).await;
+            Err("Import cancelled during directory scan".to_string())
+        }
+        Err(e) => {
+            let msg = format!("Directory scan failed: {e}");
+            warn!(workspace_id = %workspace_id, "{msg}");
+            let _ = scheduler.fail(&handle, &msg).await;
+            Err(msg)
+        }
+    }
+}
+
 /// 便捷函数：从字符串路径构造 [`Path`] 后运行导入生命周期。
 ///
 /// 主要用于测试或需要显式传入 [`Path`] 的场景。
@@ -217,6 +363,7 @@ pub async fn run_import_with_path(
         state,
         workspace_id,
         &path_str,
+        ImportOptions::default(),
     )
     .await
 }
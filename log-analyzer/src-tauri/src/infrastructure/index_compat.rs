@@ -0,0 +1,156 @@
+//! 索引兼容性检查
+//!
+//! `IndexState` 记录搜索索引的 schema 版本（`index_version`）以及写入过元数据库的
+//! 最高 schema 版本与应用版本。打开工作区时据此判断：
+//! - 索引版本与当前 [`INDEX_SCHEMA_VERSION`] 不一致（包括由更新版本构建的索引）：
+//!   丢弃索引目录并在后台从 CAS 透明重建；
+//! - 元数据库由更新版本的应用写入：照常打开，但通过 `load_workspace` 报告
+//!   `needsUpgrade`，由前端提示用户升级。
+
+use std::path::Path;
+
+use la_search::INDEX_SCHEMA_VERSION;
+use la_storage::{IndexState, MetadataStore, METADATA_SCHEMA_VERSION};
+use serde::Serialize;
+
+/// `load_workspace` 返回的索引兼容性状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum IndexCompatibility {
+    Compatible,
+    /// 搜索索引正在（或将要）按当前 schema 重建，期间搜索结果可能不完整
+    #[serde(rename_all = "camelCase")]
+    Reindexing {
+        found_index_version: i32,
+        expected_index_version: i32,
+    },
+    /// 元数据库由更新版本的应用写入，部分数据可能无法识别
+    #[serde(rename_all = "camelCase")]
+    NeedsUpgrade {
+        found_schema_version: i32,
+        supported_schema_version: i32,
+        written_by: Option<String>,
+    },
+}
+
+impl IndexCompatibility {
+    /// 根据记录的索引状态判断兼容性；没有记录时视为兼容（打开时会写入当前版本）
+    pub fn assess(state: Option<&IndexState>) -> Self {
+        let Some(state) = state else {
+            return Self::Compatible;
+        };
+        if state.schema_version > METADATA_SCHEMA_VERSION {
+            return Self::NeedsUpgrade {
+                found_schema_version: state.schema_version,
+                supported_schema_version: METADATA_SCHEMA_VERSION,
+                written_by: state.app_version.clone(),
+            };
+        }
+        if state.index_version != INDEX_SCHEMA_VERSION {
+            return Self::Reindexing {
+                found_index_version: state.index_version,
+                expected_index_version: INDEX_SCHEMA_VERSION,
+            };
+        }
+        Self::Compatible
+    }
+}
+
+/// 记录的索引版本与当前不一致时需要重建
+pub fn index_needs_rebuild(state: Option<&IndexState>) -> bool {
+    state.is_some_and(|s| s.index_version != INDEX_SCHEMA_VERSION)
+}
+
+/// 删除搜索索引目录（随后由 SearchEngineManager 重新创建空索引）
+pub fn discard_search_index(index_path: &Path) -> Result<(), String> {
+    match std::fs::remove_dir_all(index_path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!(
+            "Failed to remove incompatible search index {}: {e}",
+            index_path.display()
+        )),
+    }
+}
+
+/// 打开工作区时写入版本信息
+///
+/// `index_version` 为 `None` 时保留已记录的值（重建完成前不更新）。
+/// `schema_version` 只增不减，并保留写入最高版本的应用版本号，
+/// 这样旧版本打开后不会抹掉“由更新版本写入”的记录。
+pub async fn record_versions(
+    metadata_store: &MetadataStore,
+    workspace_id: &str,
+    previous: Option<&IndexState>,
+    index_version: Option<i32>,
+) -> Result<(), String> {
+    let (schema_version, app_version) = match previous {
+        Some(p) if p.schema_version > METADATA_SCHEMA_VERSION => {
+            (p.schema_version, p.app_version.clone())
+        }
+        _ => (
+            METADATA_SCHEMA_VERSION,
+            Some(env!("CARGO_PKG_VERSION").to_string()),
+        ),
+    };
+    let state = IndexState {
+        workspace_id: workspace_id.to_string(),
+        last_commit_time: previous
+            .map(|p| p.last_commit_time)
+            .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+        index_version: index_version
+            .or(previous.map(|p| p.index_version))
+            .unwrap_or(INDEX_SCHEMA_VERSION),
+        schema_version,
+        app_version,
+    };
+    metadata_store
+        .save_index_state(&state)
+        .await
+        .map_err(|e| format!("Failed to record index state: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(index_version: i32, schema_version: i32) -> IndexState {
+        IndexState {
+            workspace_id: "ws".to_string(),
+            last_commit_time: 0,
+            index_version,
+            schema_version,
+            app_version: Some("9.9.9".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_assess_reports_reindex_and_upgrade() {
+        assert_eq!(
+            IndexCompatibility::assess(None),
+            IndexCompatibility::Compatible
+        );
+        let current = state(INDEX_SCHEMA_VERSION, METADATA_SCHEMA_VERSION);
+        assert_eq!(
+            IndexCompatibility::assess(Some(&current)),
+            IndexCompatibility::Compatible
+        );
+        assert!(!index_needs_rebuild(Some(&current)));
+
+        let newer_index = state(INDEX_SCHEMA_VERSION + 1, METADATA_SCHEMA_VERSION);
+        assert!(index_needs_rebuild(Some(&newer_index)));
+        assert_eq!(
+            IndexCompatibility::assess(Some(&newer_index)),
+            IndexCompatibility::Reindexing {
+                found_index_version: INDEX_SCHEMA_VERSION + 1,
+                expected_index_version: INDEX_SCHEMA_VERSION,
+            }
+        );
+
+        let newer_schema = state(INDEX_SCHEMA_VERSION, METADATA_SCHEMA_VERSION + 1);
+        let json = serde_json::to_value(IndexCompatibility::assess(Some(&newer_schema))).unwrap();
+        assert_eq!(json["status"], "needsUpgrade");
+        assert_eq!(json["foundSchemaVersion"], METADATA_SCHEMA_VERSION + 1);
+        assert_eq!(json["writtenBy"], "9.9.9");
+    }
+}
@@ -1,71 +1,111 @@
 //! LogFileRepository adapter — wraps MetadataStore + CAS behind the trait.
 
-use std::io::BufRead;
+use std::collections::HashMap;
+use std::io::{BufRead, Read, Seek};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use la_core::domain::LogFileRepository;
+use la_core::domain::{ContentOverrides, LogFileRepository};
 use la_core::error::Result;
 use la_core::storage_types::FileMetadata;
-use la_storage::{ContentAddressableStorage, MetadataStore};
+use la_core::utils::{split_lines, LevelOrder};
+use la_storage::{ContentAddressableStorage, DecompressedViewCache, GzipFrameStore, MetadataStore};
 
+use crate::services::file_overrides::CompiledOverrides;
 use crate::utils::encoding::decode_log_content;
 
+/// Workspace setting holding the maximum line length in bytes (absent = no limit)
+pub const MAX_LINE_LENGTH_SETTING: &str = "max_line_length";
+
+/// Workspace setting holding the custom log level order, e.g. `DEBUG < INFO < WARN`
+pub const LEVEL_ORDER_SETTING: &str = "level_order";
+
 /// Adapter that delegates to MetadataStore (for queries) and CAS (for content).
 pub struct CasLogFileRepository {
     pub metadata: Arc<MetadataStore>,
     pub cas: Arc<ContentAddressableStorage>,
+    /// gzip objects are read through their frame copy or cached decompressed
+    /// view when set
+    pub views: Option<Arc<DecompressedViewCache>>,
 }
 
-#[async_trait]
-impl LogFileRepository for CasLogFileRepository {
-    async fn get_files_with_filters(
-        &self,
-        workspace_id: &str,
-        time_start: Option<i64>,
-        time_end: Option<i64>,
-        level_mask: Option<u8>,
-        file_pattern: Option<&str>,
-    ) -> Result<Vec<FileMetadata>> {
-        // MetadataStore has its own error type; map it
-        self.metadata
-            .get_files_with_pruning(time_start, time_end, level_mask, file_pattern)
-            .await
-            .map_err(|e| {
-                la_core::error::AppError::database_error(format!(
-                    "Failed to get files for workspace {workspace_id}: {e}"
-                ))
-            })
+impl CasLogFileRepository {
+    /// Decompressed view of a gzip object; `None` means read the raw object.
+    fn view_path(&self, hash: &str) -> Option<PathBuf> {
+        let views = self.views.as_ref()?;
+        match views.view_path(&self.cas, hash) {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::warn!(hash = %hash, error = %e, "Failed to build decompressed view");
+                None
+            }
+        }
     }
 
-    fn read_content_sync(&self, hash: &str) -> Result<Vec<u8>> {
-        self.cas.read_content_sync(hash).map_err(|e| {
-            la_core::error::AppError::io_error(
-                format!("Failed to read CAS content for hash {hash}: {e}"),
-                None,
-            )
-        })
+    /// Decompressed content of a framed gzip object from `offset`, decompressing
+    /// only from the frame holding it; `None` means no frame copy is available.
+    fn open_frames_at(&self, hash: &str, offset: u64) -> Option<Box<dyn std::io::Read + Send>> {
+        self.views.as_ref()?;
+        match GzipFrameStore::for_cas(&self.cas).open_at(hash, offset) {
+            Ok(reader) => reader,
+            Err(e) => {
+                tracing::warn!(hash = %hash, error = %e, "Failed to read gzip frame copy");
+                None
+            }
+        }
     }
 
-    fn read_line_chunks_sync(
+    /// Stream line chunks from byte `start_offset`, which must be the start of
+    /// 1-based line `start_line` (`0` / `1` for the whole object). Each line is
+    /// decoded with `overrides` when given.
+    fn stream_line_chunks(
         &self,
         hash: &str,
+        overrides: Option<&dyn ContentOverrides>,
         chunk_size: usize,
+        start_offset: u64,
+        start_line: usize,
         visitor: &mut dyn FnMut(Vec<String>, usize) -> Result<bool>,
     ) -> Result<()> {
-        let object_path = self.cas.get_object_path(hash);
-        let file = std::fs::File::open(&object_path).map_err(|e| {
-            la_core::error::AppError::io_error(
-                format!("Failed to open CAS content for hash {hash}: {e}"),
-                Some(object_path.clone()),
-            )
-        })?;
-        let mut reader = std::io::BufReader::with_capacity(256 * 1024, file);
+        let framed = self.open_frames_at(hash, start_offset);
+        let (object_path, source): (PathBuf, Box<dyn std::io::Read + Send>) = match framed {
+            Some(source) => (self.cas.get_object_path(hash), source),
+            None => match self.view_path(hash) {
+                Some(view) => {
+                    let file = Self::open_at(hash, &view, start_offset)?;
+                    (view, Box::new(file))
+                }
+                // 没有块校验清单的对象可直接定位
+                None if start_offset > 0 && self.cas.chunk_manifest(hash).is_none() => {
+                    let object_path = self.cas.get_object_path(hash);
+                    let file = Self::open_at(hash, &object_path, start_offset)?;
+                    (object_path, Box::new(file))
+                }
+                // 逐块校验：损坏的块在交给匹配之前就让读取失败；
+                // 校验需要完整的块，定位时读过并丢弃起点之前的字节
+                None => {
+                    let object_path = self.cas.get_object_path(hash);
+                    let mut source = self.cas.open_verified_sync(hash)?;
+                    if start_offset > 0 {
+                        std::io::copy(&mut (&mut source).take(start_offset), &mut std::io::sink())
+                            .map_err(|e| {
+                                la_core::error::AppError::io_error(
+                                    format!("Failed to seek CAS content for hash {hash}: {e}"),
+                                    Some(object_path.clone()),
+                                )
+                            })?;
+                    }
+                    (object_path, source)
+                }
+            },
+        };
+        let mut reader = std::io::BufReader::with_capacity(256 * 1024, source);
         let mut line_bytes = Vec::with_capacity(1024);
         let mut lines = Vec::with_capacity(chunk_size);
-        let mut chunk_start_line = 1usize;
-        let mut next_line = 1usize;
+        let mut chunk_start_line = start_line;
+        let mut next_line = start_line;
 
         loop {
             line_bytes.clear();
@@ -79,18 +119,19 @@ impl LogFileRepository for CasLogFileRepository {
                 break;
             }
 
-            while matches!(line_bytes.last(), Some(b'\n' | b'\r')) {
-                line_bytes.pop();
-            }
-
-            let (line, _) = decode_log_content(&line_bytes);
-            lines.push(line);
+            // 单独的 `\r` 也是行结束符，与索引及其它扫描路径的行号保持一致
+            let text = match overrides {
+                Some(overrides) => overrides.decode(&line_bytes),
+                None => decode_log_content(&line_bytes).0,
+            };
+            lines.extend(split_lines(&text).map(str::to_string));
 
             if lines.len() >= chunk_size {
-                if !visitor(std::mem::take(&mut lines), chunk_start_line)? {
+                let chunk = std::mem::take(&mut lines);
+                next_line += chunk.len();
+                if !visitor(chunk, chunk_start_line)? {
                     return Ok(());
                 }
-                next_line += chunk_size;
                 chunk_start_line = next_line;
             }
         }
@@ -102,6 +143,140 @@ impl LogFileRepository for CasLogFileRepository {
         Ok(())
     }
 
+    /// Open a plain file positioned at `offset`.
+    fn open_at(hash: &str, path: &Path, offset: u64) -> Result<std::fs::File> {
+        let io_error = |e: std::io::Error| {
+            la_core::error::AppError::io_error(
+                format!("Failed to open CAS content for hash {hash}: {e}"),
+                Some(path.to_path_buf()),
+            )
+        };
+        let mut file = std::fs::File::open(path).map_err(io_error)?;
+        if offset > 0 {
+            file.seek(std::io::SeekFrom::Start(offset))
+                .map_err(io_error)?;
+        }
+        Ok(file)
+    }
+}
+
+#[async_trait]
+impl LogFileRepository for CasLogFileRepository {
+    async fn get_files_with_filters(
+        &self,
+        workspace_id: &str,
+        time_start: Option<i64>,
+        time_end: Option<i64>,
+        level_mask: Option<u8>,
+        file_pattern: Option<&str>,
+    ) -> Result<Vec<FileMetadata>> {
+        // MetadataStore has its own error type; map it
+        self.metadata
+            .get_files_with_pruning(time_start, time_end, level_mask, file_pattern)
+            .await
+            .map_err(|e| {
+                la_core::error::AppError::database_error(format!(
+                    "Failed to get files for workspace {workspace_id}: {e}"
+                ))
+            })
+    }
+
+    async fn get_term_sketches(&self, hashes: &[String]) -> Result<HashMap<String, Vec<u8>>> {
+        self.metadata.get_term_sketches(hashes).await
+    }
+
+    async fn get_time_indexes(&self, hashes: &[String]) -> Result<HashMap<String, Vec<u8>>> {
+        self.metadata.get_time_indexes(hashes).await
+    }
+
+    async fn get_content_overrides(
+        &self,
+        hashes: &[String],
+    ) -> Result<HashMap<String, Arc<dyn ContentOverrides>>> {
+        let formats = self.metadata.get_file_formats().await?;
+        Ok(hashes
+            .iter()
+            .filter_map(|hash| {
+                let overrides = CompiledOverrides::from_format(formats.get(hash)?);
+                (!overrides.is_empty()).then(|| {
+                    (
+                        hash.clone(),
+                        Arc::new(overrides) as Arc<dyn ContentOverrides>,
+                    )
+                })
+            })
+            .collect())
+    }
+
+    async fn get_time_offsets(&self) -> Result<HashMap<String, i64>> {
+        self.metadata.get_file_time_offsets().await
+    }
+
+    async fn get_max_line_length(&self) -> Result<Option<usize>> {
+        let value = self
+            .metadata
+            .get_workspace_setting(MAX_LINE_LENGTH_SETTING)
+            .await?;
+        Ok(value
+            .and_then(|v| v.parse().ok())
+            .filter(|limit| *limit > 0))
+    }
+
+    async fn get_level_order(&self) -> Result<Option<LevelOrder>> {
+        let value = self
+            .metadata
+            .get_workspace_setting(LEVEL_ORDER_SETTING)
+            .await?;
+        Ok(value.and_then(|v| LevelOrder::parse(&v).ok()))
+    }
+
+    fn read_content_sync(&self, hash: &str) -> Result<Vec<u8>> {
+        if let Some(view) = self.view_path(hash) {
+            return std::fs::read(&view).map_err(|e| {
+                la_core::error::AppError::io_error(
+                    format!("Failed to read decompressed view for hash {hash}: {e}"),
+                    Some(view.clone()),
+                )
+            });
+        }
+        // 带块校验清单的大对象读取时逐块校验，损坏范围记入 CAS 的损坏日志
+        self.cas.read_content_verified_sync(hash).map_err(|e| {
+            la_core::error::AppError::io_error(
+                format!("Failed to read CAS content for hash {hash}: {e}"),
+                None,
+            )
+        })
+    }
+
+    fn read_line_chunks_sync(
+        &self,
+        hash: &str,
+        overrides: Option<&dyn ContentOverrides>,
+        chunk_size: usize,
+        visitor: &mut dyn FnMut(Vec<String>, usize) -> Result<bool>,
+    ) -> Result<()> {
+        self.stream_line_chunks(hash, overrides, chunk_size, 0, 1, visitor)
+    }
+
+    fn read_line_chunks_from_sync(
+        &self,
+        hash: &str,
+        overrides: Option<&dyn ContentOverrides>,
+        chunk_size: usize,
+        start_offset: u64,
+        start_line: usize,
+        visitor: &mut dyn FnMut(Vec<String>, usize) -> Result<bool>,
+    ) -> Result<()> {
+        self.stream_line_chunks(
+            hash,
+            overrides,
+            chunk_size,
+            start_offset,
+            start_line,
+            visitor,
+        )
+    }
+
     fn file_exists_sync(&self, hash: &str) -> bool {
         self.cas.exists(hash)
     }
@@ -122,10 +297,14 @@ mod tests {
             .store_content(b"one\ntwo\nthree\nfour\nfive\n")
             .await
             .unwrap();
-        let repo = CasLogFileRepository { metadata, cas };
+        let repo = CasLogFileRepository {
+            metadata,
+            cas,
+            views: None,
+        };
 
         let mut chunks = Vec::new();
-        repo.read_line_chunks_sync(&hash, 2, &mut |lines, start_line| {
+        repo.read_line_chunks_sync(&hash, None, 2, &mut |lines, start_line| {
             chunks.push((start_line, lines));
             Ok(true)
         })
@@ -139,4 +318,218 @@ mod tests {
         );
         assert_eq!(chunks[2], (5, vec!["five".to_string()]));
     }
+
+    #[tokio::test]
+    async fn read_line_chunks_from_sync_seeks_to_line_offset() {
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let hash = cas
+            .store_content(b"one\ntwo\nthree\nfour\nfive\n")
+            .await
+            .unwrap();
+        let repo = CasLogFileRepository {
+            metadata,
+            cas,
+            views: None,
+        };
+
+        let mut chunks = Vec::new();
+        repo.read_line_chunks_from_sync(&hash, None, 2, 8, 3, &mut |lines, start_line| {
+            chunks.push((start_line, lines));
+            Ok(true)
+        })
+        .unwrap();
+
+        assert_eq!(
+            chunks,
+            vec![
+                (3, vec!["three".to_string(), "four".to_string()]),
+                (5, vec!["five".to_string()]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn read_line_chunks_sync_splits_bare_cr_and_decodes_invalid_bytes() {
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let hash = cas
+            .store_content(b"one\rtwo\r\nbad \xff\nfour\n")
+            .await
+            .unwrap();
+        let repo = CasLogFileRepository {
+            metadata,
+            cas,
+            views: None,
+        };
+
+        let mut chunks = Vec::new();
+        repo.read_line_chunks_sync(&hash, None, 2, &mut |lines, start_line| {
+            chunks.push((start_line, lines));
+            Ok(true)
+        })
+        .unwrap();
+
+        assert_eq!(
+            chunks,
+            vec![
+                (1, vec!["one".to_string(), "two".to_string()]),
+                (3, vec!["bad \u{FFFD}".to_string(), "four".to_string()]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn read_line_chunks_sync_decodes_with_encoding_override() {
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        // GBK 编码的 "你好"，自动识别会按 UTF-8 失败
+        let hash = cas.store_content(b"\xc4\xe3\xba\xc3\nok\n").await.unwrap();
+        let plain = cas.store_content(b"plain\n").await.unwrap();
+        metadata
+            .set_file_overrides(
+                &hash,
+                &la_storage::FileOverrides {
+                    encoding: Some("gbk".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        // 全部恢复自动识别的内容不返回覆盖
+        metadata
+            .set_file_overrides(&plain, &la_storage::FileOverrides::default())
+            .await
+            .unwrap();
+        let repo = CasLogFileRepository {
+            metadata,
+            cas,
+            views: None,
+        };
+
+        let overrides = repo
+            .get_content_overrides(&[hash.clone(), plain.clone()])
+            .await
+            .unwrap();
+        assert_eq!(overrides.len(), 1);
+
+        let mut lines = Vec::new();
+        repo.read_line_chunks_sync(
+            &hash,
+            overrides.get(&hash).map(|o| o.as_ref()),
+            10,
+            &mut |chunk, _| {
+                lines.extend(chunk);
+                Ok(true)
+            },
+        )
+        .unwrap();
+        assert_eq!(lines, vec!["你好".to_string(), "ok".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn read_line_chunks_sync_stops_at_corrupted_chunk() {
+        use la_storage::chunk_checksums::{CHUNKED_OBJECT_MIN_BYTES, CHUNK_SIZE};
+
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let content = b"2024-01-01 INFO ok\n".repeat(CHUNKED_OBJECT_MIN_BYTES as usize / 19 + 1);
+        let hash = cas.store_content(&content).await.unwrap();
+        let mut rotten = content.clone();
+        rotten[CHUNK_SIZE as usize * 2 + 5] = b'#';
+        std::fs::write(cas.get_object_path(&hash), &rotten).unwrap();
+        let repo = CasLogFileRepository {
+            metadata,
+            cas: cas.clone(),
+            views: None,
+        };
+
+        let mut lines_seen = 0;
+        let error = repo
+            .read_line_chunks_sync(&hash, None, 10_000, &mut |lines, _| {
+                lines_seen += lines.len();
+                Ok(true)
+            })
+            .unwrap_err();
+
+        assert!(error.to_string().contains("chunk 2"), "{error}");
+        assert!(lines_seen < content.len() / 19);
+        assert_eq!(cas.corruption_log().get(&hash).unwrap()[0].chunk_index, 2);
+        assert!(repo.read_content_sync(&hash).is_err());
+    }
+
+    #[tokio::test]
+    async fn gzip_objects_are_read_through_decompressed_view() {
+        use std::io::Write;
+
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(b"alpha\nbeta\n").unwrap();
+        let hash = cas.store_content(&encoder.finish().unwrap()).await.unwrap();
+        let views =
+            Arc::new(DecompressedViewCache::new(workspace_dir.join("views"), 1024).unwrap());
+        let repo = CasLogFileRepository {
+            metadata,
+            cas,
+            views: Some(views.clone()),
+        };
+
+        assert_eq!(repo.read_content_sync(&hash).unwrap(), b"alpha\nbeta\n");
+        let mut lines = Vec::new();
+        repo.read_line_chunks_sync(&hash, None, 10, &mut |chunk, _| {
+            lines.extend(chunk);
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(lines, vec!["alpha".to_string(), "beta".to_string()]);
+        assert_eq!(views.stats().misses, 1);
+        assert_eq!(views.stats().hits, 1);
+    }
+
+    #[tokio::test]
+    async fn framed_gzip_objects_seek_without_decompressed_view() {
+        use std::io::Write;
+
+        let temp = TempDir::new().unwrap();
+        let workspace_dir = temp.path().join("workspace");
+        let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.clone()));
+        let metadata = Arc::new(MetadataStore::new(&workspace_dir).await.unwrap());
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(b"one\ntwo\nthree\nfour\n").unwrap();
+        let hash = cas.store_content(&encoder.finish().unwrap()).await.unwrap();
+        GzipFrameStore::for_cas(&cas)
+            .build(&cas, &hash)
+            .unwrap()
+            .unwrap();
+        let views =
+            Arc::new(DecompressedViewCache::new(workspace_dir.join("views"), 1024).unwrap());
+        let repo = CasLogFileRepository {
+            metadata,
+            cas,
+            views: Some(views.clone()),
+        };
+
+        let mut chunks = Vec::new();
+        repo.read_line_chunks_from_sync(&hash, None, 10, 8, 3, &mut |lines, start_line| {
+            chunks.push((start_line, lines));
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(
+            chunks,
+            vec![(3, vec!["three".to_string(), "four".to_string()])]
+        );
+        assert_eq!(views.stats().misses, 0);
+    }
 }
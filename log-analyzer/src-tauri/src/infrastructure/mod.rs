@@ -1,14 +1,17 @@
 //! Infrastructure adapters — implement domain traits for concrete types.
 
 pub mod archive_extractor;
+pub mod client_session_reaper;
 pub mod event_publisher;
 pub mod file_tailer;
 pub mod import_pipeline;
+pub mod index_compat;
 pub mod log_file_repo;
 pub mod notify_watcher;
 pub mod result_store;
 pub mod searcher;
 pub mod task_scheduler;
+pub mod url_download;
 pub mod watcher_runner;
 pub mod workspace_paths_adapter;
 pub mod workspace_repo;
@@ -5,16 +5,25 @@
 //! 通过 MatchPlan trait 调用——无全局缓存，无 Any 向下转型，编译期类型安全。
 
 use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use parking_lot::Mutex;
 
 use la_core::domain::filter::{Filter, LineMetadata};
-use la_core::domain::{ExecutionPlan, LogSearcher};
+use la_core::domain::{ExecutionPlan, LogSearcher, MatchBudget};
 use la_core::error::Result;
 use la_core::models::{LogEntry, SearchFilters, SearchQuery};
+use la_core::utils::{clean_line, split_lines};
 
 use crate::services::query_planner::QueryPlanner;
 use crate::services::search_filters::{CompiledSearchFilters, ParsedLineMetadata};
+use crate::services::EngineType;
+
+/// 含正则词时单行匹配的时间预算
+const REGEX_LINE_BUDGET: Duration = Duration::from_millis(250);
+/// 含正则词时单个文件匹配的时间预算（只计匹配耗时，分块搜索时跨块累计）
+const REGEX_FILE_BUDGET: Duration = Duration::from_secs(60);
 
 /// Domain LogSearcher implementation using the production regex/query engine.
 pub struct QueryEngineLogSearcher {
@@ -27,6 +36,26 @@ impl QueryEngineLogSearcher {
             planner: Mutex::new(QueryPlanner::new(regex_cache_size.max(1))),
         }
     }
+
+    /// 清空已编译的正则引擎与查询计划缓存
+    pub fn clear_caches(&self) {
+        self.planner.lock().clear_caches();
+    }
+
+    /// 正则引擎与查询计划缓存的估算占用：(条目数, 字节)
+    pub fn cache_usage(&self) -> (u64, u64) {
+        self.planner.lock().cache_usage()
+    }
+
+    /// 正则引擎缓存的估算占用：(条目数, 字节)
+    pub fn engine_cache_usage(&self) -> (u64, u64) {
+        self.planner.lock().engine_cache_usage()
+    }
+
+    /// 调整正则引擎缓存容量（保留已编译的引擎）
+    pub fn resize_engine_cache(&self, capacity: usize) {
+        self.planner.lock().set_engine_capacity(capacity);
+    }
 }
 
 impl LogSearcher for QueryEngineLogSearcher {
@@ -34,11 +63,20 @@ impl LogSearcher for QueryEngineLogSearcher {
         let service_plan = self.planner.lock().build(query)?;
         let engine_count = service_plan.engines.len();
         let steps = service_plan.execution_term_ids().to_vec();
+        // 关键词引擎是线性且极快的，只有正则引擎才需要计时
+        let has_regex = service_plan.engines.iter().any(|compiled| {
+            matches!(
+                compiled.engine.engine_type(),
+                EngineType::Standard | EngineType::Fancy
+            )
+        });
         let plan = ExecutionPlan {
             id: 0,
             engine_count,
             steps,
-            plan: Some(std::sync::Arc::new(service_plan)),
+            plan: Some(Arc::new(service_plan)),
+            budget: has_regex
+                .then(|| Arc::new(MatchBudget::new(REGEX_LINE_BUDGET, REGEX_FILE_BUDGET))),
         };
         Ok(plan)
     }
@@ -52,8 +90,16 @@ impl LogSearcher for QueryEngineLogSearcher {
         global_offset: usize,
     ) -> Vec<LogEntry> {
         // Compile filters via the concrete adapter, then use the Filter trait.
+        let time_offset = filters
+            .time_offsets
+            .get(virtual_path)
+            .copied()
+            .filter(|offset| *offset != 0);
         let compiled: Box<dyn Filter> = match CompiledSearchFilters::compile(filters) {
-            Ok(f) => Box::new(f),
+            Ok(f) => match time_offset {
+                Some(offset) => Box::new(f.shifted_by(offset)),
+                None => Box::new(f),
+            },
             Err(_) => return Vec::new(),
         };
 
@@ -66,9 +112,25 @@ impl LogSearcher for QueryEngineLogSearcher {
             None => return Vec::new(),
         };
 
+        let budget = plan.budget.as_deref();
+        if budget.is_some_and(|b| b.exceeded().is_some()) {
+            return Vec::new();
+        }
+        // 只累计匹配本身的耗时；此前的块已计入的耗时一并计算
+        let mut spent = budget.map_or(Duration::ZERO, |b| b.spent(virtual_path));
+        let spent_before = spent;
+
         let has_time = compiled.has_time_filter();
+        // 最低级别过滤按工作区级别顺序识别每行的级别
+        let level_order = filters
+            .min_level
+            .as_ref()
+            .map(|_| filters.level_order.clone().unwrap_or_default());
         let mut entries = Vec::new();
-        for (index, line) in content.lines().enumerate() {
+        for (index, line) in split_lines(content).enumerate() {
+            // NUL 替换在匹配之前进行，匹配位置与返回的内容一致
+            let line = clean_line(line);
+            let line = line.as_ref();
             let metadata = ParsedLineMetadata::parse(line, has_time);
             if !compiled.matches_line(&LineMetadata {
                 timestamp: metadata.timestamp.clone(),
@@ -76,11 +138,39 @@ impl LogSearcher for QueryEngineLogSearcher {
                 level_normalized: metadata.level_normalized,
                 datetime: metadata.datetime,
                 level_mask: metadata.level_mask,
+                level_rank: level_order
+                    .as_ref()
+                    .and_then(|order| order.line_rank(line, metadata.level)),
             }) {
                 continue;
             }
 
-            if let Some(details) = match_plan.match_line(line) {
+            let line_started = budget.map(|_| Instant::now());
+            let matched = match_plan.match_line(line);
+            if let (Some(budget), Some(line_started)) = (budget, line_started) {
+                let line_elapsed = line_started.elapsed();
+                spent += line_elapsed;
+                if line_elapsed > budget.per_line {
+                    budget.record_exceeded(format!(
+                        "regex too expensive: line {} of {} took {} ms (limit {} ms)",
+                        index + 1,
+                        virtual_path,
+                        line_elapsed.as_millis(),
+                        budget.per_line.as_millis()
+                    ));
+                    break;
+                }
+                if spent > budget.per_file {
+                    budget.record_exceeded(format!(
+                        "regex too expensive: matching {} exceeded {} s",
+                        virtual_path,
+                        budget.per_file.as_secs()
+                    ));
+                    break;
+                }
+            }
+
+            if let Some(details) = matched {
                 let keywords = details
                     .iter()
                     .map(|detail| detail.term_value.clone())
@@ -88,7 +178,7 @@ impl LogSearcher for QueryEngineLogSearcher {
                     .into_iter()
                     .collect::<Vec<_>>();
 
-                entries.push(LogEntry {
+                let mut entry = LogEntry {
                     id: global_offset + entries.len(),
                     timestamp: metadata.timestamp.into(),
                     level: metadata.level.into(),
@@ -107,10 +197,24 @@ impl LogSearcher for QueryEngineLogSearcher {
                     } else {
                         Some(keywords)
                     },
-                });
+                    time_offset_secs: time_offset,
+                    links: None,
+                    translation: None,
+                    source_files: None,
+                    original_length: None,
+                    fields: None,
+                };
+                // 匹配在完整行上进行，只截断返回给前端的内容
+                if let Some(max) = filters.max_line_length {
+                    entry.truncate_content(max);
+                }
+                entries.push(entry);
             }
         }
 
+        if let Some(budget) = budget {
+            budget.charge(virtual_path, spent - spent_before);
+        }
         entries
     }
 }
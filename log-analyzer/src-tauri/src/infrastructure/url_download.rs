@@ -0,0 +1,334 @@
+//! HTTPS 下载（断点续传 + SHA-256 校验）
+//!
+//! 支持包常以预签名链接提供。下载先写入 `<文件名>.part`，再次下载同一地址时
+//! 用 `Range` 请求从已有长度继续；服务端不支持续传（返回 200）时从头下载。
+//! 校验通过后才重命名为最终文件名，校验失败删除临时文件，避免坏数据被续传复用。
+//!
+//! - 重定向的每一跳都必须是 https，不会被降级到明文地址
+//! - 开始下载时记下响应的校验标识（强 ETag，否则 Last-Modified），续传时以
+//!   `If-Range` 发送：远端内容已变化时服务端返回完整内容，从头下载；没有记下
+//!   标识时不续传，避免把不同版本的内容拼在一起
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use reqwest::header::{HeaderMap, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::redirect;
+use reqwest::{StatusCode, Url};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
+
+use la_core::utils::{validate_and_sanitize_path, PathValidationResult, SecurityConfig};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// 两次进度回调的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+const FALLBACK_FILE_NAME: &str = "download";
+/// 最多跟随的重定向次数（与 reqwest 默认一致）
+const MAX_REDIRECTS: usize = 10;
+
+/// 解析并校验下载地址：只接受 https
+pub fn parse_download_url(raw: &str) -> Result<Url, String> {
+    let url = Url::parse(raw.trim()).map_err(|e| format!("Invalid URL: {e}"))?;
+    if url.scheme() != "https" {
+        return Err(format!(
+            "Only https:// URLs are supported, got {}://",
+            url.scheme()
+        ));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err("URL has no host".to_string());
+    }
+    Ok(url)
+}
+
+/// 由地址路径的最后一段得到安全的本地文件名（忽略查询串中的签名参数）
+pub fn download_file_name(url: &Url) -> String {
+    let last = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .map(|s| {
+            percent_encoding::percent_decode_str(s)
+                .decode_utf8_lossy()
+                .into_owned()
+        })
+        .unwrap_or_default();
+
+    match validate_and_sanitize_path(last.trim(), &SecurityConfig::default()) {
+        PathValidationResult::Valid(name) | PathValidationResult::RequiresSanitization(_, name)
+            if !name.is_empty() && name != "." && name != ".." =>
+        {
+            name
+        }
+        _ => FALLBACK_FILE_NAME.to_string(),
+    }
+}
+
+/// 规范化期望的校验和（十六进制，忽略大小写与空白）
+pub fn normalize_sha256(raw: &str) -> Result<String, String> {
+    let hex = raw.trim().to_ascii_lowercase();
+    if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("Expected SHA-256 must be 64 hexadecimal characters".to_string());
+    }
+    Ok(hex)
+}
+
+/// 计算文件的 SHA-256（十六进制小写）
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 重定向的下一跳：只允许 https 且不超过 [`MAX_REDIRECTS`] 跳
+fn check_redirect(next: &Url, previous_hops: usize) -> Result<(), String> {
+    if next.scheme() != "https" {
+        return Err(format!(
+            "Refusing to follow redirect to non-https URL ({}://)",
+            next.scheme()
+        ));
+    }
+    if previous_hops >= MAX_REDIRECTS {
+        return Err(format!("Too many redirects (more than {MAX_REDIRECTS})"));
+    }
+    Ok(())
+}
+
+/// 响应中可用于 `If-Range` 的校验标识：强 ETag 优先（弱 ETag 不能用于 `If-Range`），
+/// 否则 Last-Modified
+fn resume_validator(headers: &HeaderMap) -> Option<String> {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+        .map(str::to_string)
+}
+
+/// `Content-Range: bytes 100-199/1000` 中的总长度
+fn content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// 下载到 `dest`；`on_progress(已下载字节, 总字节)` 按间隔回调
+pub async fn download_with_resume(
+    url: &Url,
+    dest: &Path,
+    expected_sha256: Option<&str>,
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<(), String> {
+    let partial = partial_path(dest);
+    let validator_file = validator_path(dest);
+    let validator = tokio::fs::read_to_string(&validator_file)
+        .await
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    // 没有记下校验标识的临时文件无法确认与远端是同一版本，从头下载
+    let mut downloaded = match validator {
+        Some(_) => tokio::fs::metadata(&partial)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0),
+        None => 0,
+    };
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .redirect(redirect::Policy::custom(|attempt| {
+            match check_redirect(attempt.url(), attempt.previous().len()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        }))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let mut request = client.get(url.clone());
+    if let Some(validator) = validator.as_deref().filter(|_| downloaded > 0) {
+        request = request
+            .header(RANGE, format!("bytes={downloaded}-"))
+            .header(IF_RANGE, validator);
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Download request failed: {e}"))?;
+
+    let status = response.status();
+    let total = match status {
+        StatusCode::PARTIAL_CONTENT => response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(content_range_total),
+        // 已有的临时文件即为完整内容
+        StatusCode::RANGE_NOT_SATISFIABLE if downloaded > 0 => Some(downloaded),
+        s if s.is_success() => {
+            // 从头下载（含远端内容已变化的情况）：改记本次响应的校验标识
+            downloaded = 0;
+            let _ = tokio::fs::remove_file(&validator_file).await;
+            if let Some(validator) = resume_validator(response.headers()) {
+                tokio::fs::write(&validator_file, validator)
+                    .await
+                    .map_err(|e| format!("Failed to write {}: {e}", validator_file.display()))?;
+            }
+            response.content_length()
+        }
+        s => return Err(format!("Download failed with HTTP status {s}")),
+    };
+
+    if status != StatusCode::RANGE_NOT_SATISFIABLE {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(downloaded > 0)
+            .truncate(downloaded == 0)
+            .open(&partial)
+            .await
+            .map_err(|e| format!("Failed to open {}: {e}", partial.display()))?;
+
+        let mut last_report = Instant::now();
+        on_progress(downloaded, total);
+        loop {
+            let chunk = tokio::select! {
+                _ = cancel.cancelled() => {
+                    let _ = file.flush().await;
+                    return Err("Download cancelled".to_string());
+                }
+                chunk = response.chunk() => chunk.map_err(|e| format!("Download interrupted: {e}"))?,
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {}: {e}", partial.display()))?;
+            downloaded += chunk.len() as u64;
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                on_progress(downloaded, total);
+                last_report = Instant::now();
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to flush {}: {e}", partial.display()))?;
+        on_progress(downloaded, total);
+    }
+
+    if let Some(total) = total.filter(|t| downloaded < *t) {
+        return Err(format!(
+            "Download incomplete: {downloaded} of {total} bytes (retry to resume)"
+        ));
+    }
+
+    if let Some(expected) = expected_sha256 {
+        let path = partial.clone();
+        let actual = tokio::task::spawn_blocking(move || sha256_file(&path))
+            .await
+            .map_err(|e| format!("Checksum task failed: {e}"))?
+            .map_err(|e| format!("Failed to hash download: {e}"))?;
+        if actual != expected {
+            let _ = tokio::fs::remove_file(&partial).await;
+            let _ = tokio::fs::remove_file(&validator_file).await;
+            return Err(format!(
+                "SHA-256 mismatch: expected {expected}, got {actual}"
+            ));
+        }
+    }
+
+    tokio::fs::rename(&partial, dest)
+        .await
+        .map_err(|e| format!("Failed to finalize download: {e}"))?;
+    let _ = tokio::fs::remove_file(&validator_file).await;
+    Ok(())
+}
+
+fn partial_path(dest: &Path) -> PathBuf {
+    sibling_path(dest, ".part")
+}
+
+/// 记录临时文件对应的远端校验标识
+fn validator_path(dest: &Path) -> PathBuf {
+    sibling_path(dest, ".part.validator")
+}
+
+fn sibling_path(dest: &Path, suffix: &str) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    dest.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_https_urls_are_accepted() {
+        assert!(parse_download_url("https://bucket.example.com/a.zip?X-Sig=1").is_ok());
+        assert!(parse_download_url("http://example.com/a.zip").is_err());
+        assert!(parse_download_url("file:///etc/passwd").is_err());
+        assert!(parse_download_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_redirects_must_stay_on_https() {
+        let https = Url::parse("https://cdn.example.com/a.zip").unwrap();
+        assert!(check_redirect(&https, 0).is_ok());
+        assert!(check_redirect(&https, MAX_REDIRECTS).is_err());
+        let http = Url::parse("http://cdn.example.com/a.zip").unwrap();
+        assert!(check_redirect(&http, 0).is_err());
+    }
+
+    #[test]
+    fn test_resume_validator_prefers_strong_etag() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(resume_validator(&headers), None);
+        headers.insert(
+            LAST_MODIFIED,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        headers.insert(ETAG, "W/\"weak\"".parse().unwrap());
+        assert_eq!(
+            resume_validator(&headers).as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        headers.insert(ETAG, "\"abc\"".parse().unwrap());
+        assert_eq!(resume_validator(&headers).as_deref(), Some("\"abc\""));
+    }
+
+    #[test]
+    fn test_file_name_ignores_query_and_unsafe_segments() {
+        let url =
+            parse_download_url("https://s3.example.com/cases/bundle%201.tar.gz?sig=x").unwrap();
+        assert_eq!(download_file_name(&url), "bundle 1.tar.gz");
+
+        let url = parse_download_url("https://example.com/").unwrap();
+        assert_eq!(download_file_name(&url), FALLBACK_FILE_NAME);
+    }
+
+    #[test]
+    fn test_checksum_helpers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.bin");
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(normalize_sha256(
+            " BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD "
+        )
+        .is_ok());
+        assert!(normalize_sha256("abc").is_err());
+        assert_eq!(content_range_total("bytes 100-199/1000"), Some(1000));
+        assert_eq!(content_range_total("bytes */*"), None);
+    }
+}
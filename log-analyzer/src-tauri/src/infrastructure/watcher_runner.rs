@@ -12,8 +12,15 @@ use tauri::Emitter;
 use tokio::runtime::Handle as TokioHandle;
 use tracing::warn;
 
+use crate::application::search_batch::{split_by_byte_budget, DEFAULT_BATCH_MAX_BYTES};
 use crate::application::watch::{WatchEvent, WatchEventKind};
 use crate::infrastructure::file_tailer::FileTailer;
+use crate::services::follow_query::FollowQueries;
+use crate::services::webhooks::{self, WebhookEventKind, MAX_ALERT_SAMPLES};
+use crate::state_sync::FileIndexDelta;
+
+/// 持续出错时监听错误 Webhook 的最小通知间隔
+const WATCH_ERROR_NOTIFY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
 
 /// 文件监听后台运行器。
 ///
@@ -28,21 +35,30 @@ pub(crate) struct WatcherRunner {
     /// Whether the watcher should continue running.
     is_active: bool,
     workspace_id: String,
+    /// 挂载的跟随查询（与 WorkspaceServiceImpl 共享，可在监听期间增删）
+    follow_queries: FollowQueries,
     runtime: TokioHandle,
     /// Watch 模式广播用 AppHandle（构造时由工厂注入）
     app_handle: tauri::AppHandle,
     /// FilesUpdated 广播 debounce：记录上次广播的时刻
     last_broadcast: std::time::Instant,
+    /// 读取 Webhook 设置的元数据库
+    webhook_store: Arc<la_storage::MetadataStore>,
+    /// 监听错误 Webhook 节流：记录上次通知的时刻
+    last_error_notified: Option<std::time::Instant>,
 }
 
 impl WatcherRunner {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         cas: Arc<dyn ContentStorage>,
         metadata: Arc<dyn MetadataStorage>,
         search_engine: Arc<la_search::SearchEngineManager>,
         watched_path: std::path::PathBuf,
         workspace_id: String,
+        follow_queries: FollowQueries,
         app_handle: tauri::AppHandle,
+        webhook_store: Arc<la_storage::MetadataStore>,
     ) -> Self {
         Self {
             cas,
@@ -51,9 +67,12 @@ impl WatcherRunner {
             tailer: FileTailer::new(watched_path),
             is_active: true,
             workspace_id,
+            follow_queries,
             runtime: TokioHandle::current(),
             app_handle,
             last_broadcast: std::time::Instant::now(),
+            webhook_store,
+            last_error_notified: None,
         }
     }
 
@@ -82,6 +101,8 @@ impl WatcherRunner {
             WatchEventKind::Other => return,
         };
 
+        let mut deltas = Vec::new();
+        let mut new_lines = 0;
         for path in &event.paths {
             if path.to_str().is_none() {
                 warn!(path = ?path, "Skipping path with non-UTF-8 chars");
@@ -90,23 +111,34 @@ impl WatcherRunner {
 
             match event_type {
                 "created" => self.on_create(path),
-                "modified" => self.on_modify(path),
+                "modified" => {
+                    if let Some((delta, lines)) = self.on_modify(path) {
+                        deltas.push(delta);
+                        new_lines += lines;
+                    }
+                }
                 _ => {}
             }
         }
+
+        // 同一事件涉及的文件一次提交
+        if !deltas.is_empty() {
+            self.commit_index(deltas, new_lines);
+        }
     }
 
     fn on_create(&mut self, path: &Path) {
         self.tailer.on_create(path);
     }
 
-    fn on_modify(&mut self, path: &Path) {
+    /// 读取新增行并写入索引（不提交）；返回该文件的索引增量与新增行数
+    fn on_modify(&mut self, path: &Path) -> Option<(FileIndexDelta, usize)> {
         let start_line_number = self.tailer.line_count(path) + 1;
 
         match self.tailer.tail(path) {
             Ok(result) => {
                 if result.lines.is_empty() {
-                    return;
+                    return None;
                 }
 
                 let new_line_count = result.lines.len();
@@ -120,8 +152,11 @@ impl WatcherRunner {
                     start_line_number,
                 );
 
-                // 更新搜索索引与存储（前端通过 workspace-event 通道获知变更）
-                self.update_search_index(&new_entries);
+                // 写入搜索索引，由 handle_event 统一提交（前端通过 workspace-event 通道获知变更）
+                let indexed = self.add_to_search_index(&new_entries);
+
+                // 跟随查询：命中的新条目立即推送
+                self.broadcast_follow_matches(&new_entries);
 
                 // Store in CAS + metadata
                 self.store_to_cas(&path.to_string_lossy(), &virtual_path);
@@ -129,18 +164,26 @@ impl WatcherRunner {
                 // Update line count
                 self.tailer.add_lines(path, new_line_count);
 
-                // Watch 模式：广播 FilesUpdated（debounce 5 秒，累计行数）
-                self.broadcast_files_updated(new_line_count);
+                indexed.then(|| {
+                    let delta = FileIndexDelta {
+                        virtual_path,
+                        new_entries: new_entries.len() as u64,
+                    };
+                    (delta, new_line_count)
+                })
             }
             Err(e) => {
                 warn!(error = %e, file = %path.display(), "Failed to read file incrementally");
+                self.notify_watch_error("read", &e.to_string(), Some(path));
+                None
             }
         }
     }
 
-    fn update_search_index(&self, entries: &[la_core::models::LogEntry]) {
+    /// 返回是否有条目写入索引
+    fn add_to_search_index(&mut self, entries: &[la_core::models::LogEntry]) -> bool {
         if entries.is_empty() {
-            return;
+            return false;
         }
         if let Err(e) = self.search_engine.add_documents(entries) {
             warn!(
@@ -148,13 +191,31 @@ impl WatcherRunner {
                 workspace_id = %self.workspace_id,
                 "Failed to add watch documents to search index"
             );
+            self.notify_watch_error("index", &e.to_string(), None);
+            return false;
         }
+        true
+    }
+
+    /// 提交索引并广播 IndexCommitted（每次提交）与 FilesUpdated（debounce）
+    fn commit_index(&mut self, deltas: Vec<FileIndexDelta>, new_lines: usize) {
         if let Err(e) = self.search_engine.commit() {
             warn!(
                 error = %e, workspace_id = %self.workspace_id,
                 "Failed to commit search index after watch update"
             );
+            self.notify_watch_error("commit", &e.to_string(), None);
+            return;
         }
+
+        let event = crate::state_sync::models::WorkspaceEvent::IndexCommitted {
+            workspace_id: self.workspace_id.clone(),
+            files: deltas,
+        };
+        self.emit_workspace_event(event);
+
+        // Watch 模式：广播 FilesUpdated（debounce 5 秒）
+        self.broadcast_files_updated(new_lines);
     }
 
     fn store_to_cas(&self, file_path: &str, virtual_path: &str) {
@@ -200,6 +261,30 @@ impl WatcherRunner {
         });
     }
 
+    /// 用挂载的跟随查询匹配新条目，按查询 id 推送 NewLogs 事件。
+    ///
+    /// 命中较多或行较长时按字节预算拆成多个事件，避免单个 IPC 负载过大。
+    fn broadcast_follow_matches(&self, entries: &[la_core::models::LogEntry]) {
+        for (query_id, matched) in self.follow_queries.evaluate(entries) {
+            self.notify_webhooks(
+                WebhookEventKind::AlertFired,
+                serde_json::json!({
+                    "queryId": query_id,
+                    "matchCount": matched.len(),
+                    "entries": &matched[..matched.len().min(MAX_ALERT_SAMPLES)],
+                }),
+            );
+            for entries in split_by_byte_budget(matched, DEFAULT_BATCH_MAX_BYTES) {
+                let event = crate::state_sync::models::WorkspaceEvent::NewLogs {
+                    workspace_id: self.workspace_id.clone(),
+                    query_id: query_id.clone(),
+                    entries,
+                };
+                self.emit_workspace_event(event);
+            }
+        }
+    }
+
     /// Watch 模式广播 FilesUpdated 事件（带 5 秒 debounce）。
     ///
     /// 新内容已写入 Tantivy 索引后调用，告知前端有新日志到达，
@@ -215,6 +300,40 @@ impl WatcherRunner {
             workspace_id: self.workspace_id.clone(),
             new_lines: new_lines as u64,
         };
+        self.emit_workspace_event(event);
+    }
+
+    fn notify_webhooks(&self, kind: WebhookEventKind, data: serde_json::Value) {
+        webhooks::notify(
+            &self.runtime,
+            Arc::clone(&self.webhook_store),
+            &self.workspace_id,
+            kind,
+            data,
+        );
+    }
+
+    /// 通知监听错误；持续出错时最多每 [`WATCH_ERROR_NOTIFY_INTERVAL`] 一次
+    fn notify_watch_error(&mut self, stage: &str, error: &str, file: Option<&Path>) {
+        let now = std::time::Instant::now();
+        if self
+            .last_error_notified
+            .is_some_and(|last| now.duration_since(last) < WATCH_ERROR_NOTIFY_INTERVAL)
+        {
+            return;
+        }
+        self.last_error_notified = Some(now);
+        self.notify_webhooks(
+            WebhookEventKind::WatchError,
+            serde_json::json!({
+                "stage": stage,
+                "error": error,
+                "file": file.map(|p| p.to_string_lossy().into_owned()),
+            }),
+        );
+    }
+
+    fn emit_workspace_event(&self, event: crate::state_sync::models::WorkspaceEvent) {
         let app = self.app_handle.clone();
         // 非阻塞：在异步运行时中发射，不阻塞监听事件循环
         self.runtime.spawn(async move {
@@ -227,7 +346,7 @@ impl WatcherRunner {
                     tokio::time::sleep(std::time::Duration::from_millis(10)).await;
                 }
             }
-            warn!("Failed to emit FilesUpdated after 3 attempts");
+            warn!("Failed to emit workspace event after 3 attempts");
         });
     }
 }
@@ -5,7 +5,7 @@ use std::path::PathBuf;
 use la_core::domain::WorkspacePaths;
 use tauri::Manager;
 
-use crate::utils::workspace_paths::preferred_workspace_dir_from_root;
+use crate::utils::workspace_paths::resolve_workspace_dir_from_root;
 
 /// Adapter that resolves workspace directories via Tauri's path resolver.
 pub struct TauriWorkspacePaths {
@@ -25,6 +25,6 @@ impl TauriWorkspacePaths {
 
 impl WorkspacePaths for TauriWorkspacePaths {
     fn workspace_data_dir(&self, workspace_id: &str) -> std::result::Result<PathBuf, String> {
-        preferred_workspace_dir_from_root(&self.app_data_dir, workspace_id)
+        resolve_workspace_dir_from_root(&self.app_data_dir, workspace_id)
     }
 }
@@ -6,7 +6,7 @@
 use std::sync::Arc;
 
 use la_search::{DiskResultStore, SearchEngineManager};
-use la_storage::{ContentAddressableStorage, MetadataStore};
+use la_storage::{ContentAddressableStorage, DecompressedViewCache, MetadataStore};
 
 /// Persistent storage + search index for a single workspace.
 #[derive(Clone)]
@@ -15,6 +15,7 @@ pub struct WorkspaceRepo {
     metadata_store: Arc<MetadataStore>,
     search_engine: Arc<SearchEngineManager>,
     disk_result_store: Arc<DiskResultStore>,
+    views: Arc<DecompressedViewCache>,
 }
 
 impl WorkspaceRepo {
@@ -23,12 +24,14 @@ impl WorkspaceRepo {
         metadata_store: Arc<MetadataStore>,
         search_engine: Arc<SearchEngineManager>,
         disk_result_store: Arc<DiskResultStore>,
+        views: Arc<DecompressedViewCache>,
     ) -> Self {
         Self {
             cas,
             metadata_store,
             search_engine,
             disk_result_store,
+            views,
         }
     }
 
@@ -47,4 +50,8 @@ impl WorkspaceRepo {
     pub fn disk_result_store(&self) -> &Arc<DiskResultStore> {
         &self.disk_result_store
     }
+
+    pub fn views(&self) -> &Arc<DecompressedViewCache> {
+        &self.views
+    }
 }
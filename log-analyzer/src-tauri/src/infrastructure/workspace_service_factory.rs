@@ -6,13 +6,21 @@
 use std::path::Path;
 use std::sync::Arc;
 
+use la_core::error::CommandError;
 use tauri::AppHandle;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::application::workspace_service::WorkspaceServiceRef;
+use crate::infrastructure::index_compat::{
+    discard_search_index, index_needs_rebuild, record_versions,
+};
+use crate::infrastructure::workspace_service_impl::rebuild_search_index_inner;
 use crate::infrastructure::{TauriEventPublisher, WorkspaceRepo, WorkspaceServiceImpl};
 use crate::models::AppState;
-use la_storage::{ContentAddressableStorage, MetadataStore};
+use crate::utils::workspace_paths::tenant_of_workspace_dir;
+use la_storage::{
+    ContentAddressableStorage, DecompressedViewCache, MetadataStore, DEFAULT_VIEW_CACHE_BYTES,
+};
 
 const SEARCH_INDEX_DIR_NAME: &str = "search_index";
 const SEARCH_INDEX_WRITER_HEAP_BYTES: usize = 50_000_000;
@@ -38,27 +46,46 @@ pub(crate) fn load_workspace_search_config(
 ///
 /// 优先从已有的 workspace service 获取，否则新建。
 /// 接受预加载的 SearchConfig 以避免重复读取 config.json。
-pub(crate) fn ensure_search_engine_manager_with_config(
+/// 返回原始错误，供调用方区分索引不兼容与锁冲突、权限等暂时性错误。
+fn open_search_engine_manager(
     state: &AppState,
     workspace_id: &str,
     workspace_dir: &Path,
     search_config: &la_core::models::config::SearchConfig,
-) -> Result<Arc<la_search::SearchEngineManager>, String> {
+) -> la_search::SearchResult<Arc<la_search::SearchEngineManager>> {
     if let Some(service) = state.get_workspace_service(workspace_id) {
         return Ok(Arc::clone(service.search_engine()));
     }
 
     let index_path = workspace_dir.join(SEARCH_INDEX_DIR_NAME);
-    let manager = Arc::new(
-        la_search::SearchEngineManager::with_app_config(
-            search_config.clone(),
-            index_path,
-            SEARCH_INDEX_WRITER_HEAP_BYTES,
-        )
-        .map_err(|e| format!("Failed to initialize search engine: {e}"))?,
-    );
+    la_search::SearchEngineManager::with_app_config(
+        search_config.clone(),
+        index_path,
+        SEARCH_INDEX_WRITER_HEAP_BYTES,
+    )
+    .map(Arc::new)
+}
 
-    Ok(manager)
+/// 索引无法打开且不属于格式问题时的错误：保留索引目录，由调用方稍后重试
+fn index_open_error(workspace_id: &str, error: &la_search::SearchError) -> CommandError {
+    warn!(
+        workspace_id = %workspace_id,
+        error = %error,
+        "Search index could not be opened; leaving it in place"
+    );
+    if error.is_lock_busy() {
+        CommandError::new(
+            "WORKSPACE_BUSY",
+            format!("Search index of workspace '{workspace_id}' is locked: {error}"),
+        )
+        .with_help("Close other instances of the application using this workspace and retry")
+    } else {
+        CommandError::new(
+            "INDEX_UNAVAILABLE",
+            format!("Search index of workspace '{workspace_id}' could not be opened: {error}"),
+        )
+        .with_help("Check that the workspace directory is readable and writable, then retry")
+    }
 }
 
 // ============================================================================
@@ -68,34 +95,171 @@ pub(crate) fn ensure_search_engine_manager_with_config(
 /// 获取或创建工作区服务实例。
 ///
 /// 如果服务已存在于 AppState 中则直接返回，否则创建新实例并存储。
+///
+/// 搜索索引只在记录的版本不一致或索引本身不兼容 / 损坏时丢弃重建；被锁定时返回
+/// `WORKSPACE_BUSY`，权限与 I/O 错误返回 `INDEX_UNAVAILABLE`，索引保持原样。
+/// 其余失败返回 `RUNTIME_ERROR`。
 pub(crate) async fn get_or_create_workspace_service(
     app: &AppHandle,
     state: &AppState,
     workspace_id: &str,
     workspace_dir: &Path,
-) -> Result<WorkspaceServiceRef, String> {
-    // 优先返回已存在的服务
+) -> Result<WorkspaceServiceRef, CommandError> {
+    create_workspace_service(app, state, workspace_id, workspace_dir)
+        .await
+        .map_err(|e| match e {
+            ServiceInitError::Index(e) => e,
+            ServiceInitError::Other(e) => CommandError::new(
+                "RUNTIME_ERROR",
+                format!("Failed to initialize workspace: {e}"),
+            ),
+        })
+}
+
+enum ServiceInitError {
+    Index(CommandError),
+    Other(String),
+}
+
+impl From<String> for ServiceInitError {
+    fn from(e: String) -> Self {
+        Self::Other(e)
+    }
+}
+
+impl From<&str> for ServiceInitError {
+    fn from(e: &str) -> Self {
+        Self::Other(e.to_string())
+    }
+}
+
+async fn create_workspace_service(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    workspace_dir: &Path,
+) -> Result<WorkspaceServiceRef, ServiceInitError> {
+    // 优先返回已存在的服务；工作区 id 在所有租户命名空间中唯一，
+    // 目录不一致说明缓存的服务属于另一个命名空间，不能复用
     if let Some(service) = state.get_workspace_service(workspace_id) {
+        if service.workspace_dir().as_path() != workspace_dir {
+            return Err(format!(
+                "Workspace '{workspace_id}' is registered under a different namespace"
+            )
+            .into());
+        }
         return Ok(service);
     }
 
     // 创建各运行时组件
     let cas = Arc::new(ContentAddressableStorage::new(workspace_dir.to_path_buf()));
 
+    // 元数据库按目录布局所在的租户命名空间打开，归属不符时拒绝
+    let tenant = tenant_of_workspace_dir(workspace_dir);
     let metadata_store = Arc::new(
-        MetadataStore::new(workspace_dir)
+        MetadataStore::new_scoped(workspace_dir, tenant.as_deref())
             .await
             .map_err(|e| format!("Failed to open metadata store: {e}"))?,
     );
 
+    // 索引由不同 schema 版本构建时丢弃并在服务创建后重建
+    let index_state = metadata_store
+        .load_index_state(workspace_id)
+        .await
+        .map_err(|e| format!("Failed to load index state: {e}"))?;
+    let index_path = workspace_dir.join(SEARCH_INDEX_DIR_NAME);
+    let mut reindex = index_needs_rebuild(index_state.as_ref());
+    if reindex {
+        warn!(
+            workspace_id = %workspace_id,
+            found = index_state.as_ref().map(|s| s.index_version),
+            expected = la_search::INDEX_SCHEMA_VERSION,
+            "Search index schema version mismatch; rebuilding index"
+        );
+        discard_search_index(&index_path)?;
+    }
+
     // 预加载配置以避免 ensure_search_engine_manager 重复读取 config.json
     let search_config = load_workspace_search_config(app);
-    let search_manager = ensure_search_engine_manager_with_config(
-        state,
+    let search_manager =
+        match open_search_engine_manager(state, workspace_id, workspace_dir, &search_config) {
+            Ok(manager) => manager,
+            // 索引格式不受支持或已损坏（如未记录版本的更新格式）同样丢弃重建；
+            // 锁冲突、权限与 I/O 错误时丢弃无济于事，原样保留并报告
+            Err(e) if e.is_incompatible_index() => {
+                warn!(
+                    workspace_id = %workspace_id,
+                    error = %e,
+                    "Search index format is incompatible; rebuilding index"
+                );
+                discard_search_index(&index_path)?;
+                reindex = true;
+                open_search_engine_manager(state, workspace_id, workspace_dir, &search_config)
+                    .map_err(|e| ServiceInitError::Index(index_open_error(workspace_id, &e)))?
+            }
+            Err(e) => return Err(ServiceInitError::Index(index_open_error(workspace_id, &e))),
+        };
+
+    // 重建完成前保留旧的索引版本（无记录时写 0），中途退出后下次打开会再次重建
+    let recorded_index_version = if !reindex {
+        Some(la_search::INDEX_SCHEMA_VERSION)
+    } else if index_needs_rebuild(index_state.as_ref()) {
+        None
+    } else {
+        Some(0)
+    };
+    record_versions(
+        &metadata_store,
         workspace_id,
-        workspace_dir,
-        &search_config,
-    )?;
+        index_state.as_ref(),
+        recorded_index_version,
+    )
+    .await?;
+
+    if reindex {
+        let metadata_store = Arc::clone(&metadata_store);
+        let cas = Arc::clone(&cas);
+        let search_manager = Arc::clone(&search_manager);
+        let workspace_id = workspace_id.to_string();
+        tokio::spawn(async move {
+            let rebuilt =
+                rebuild_search_index_inner(Arc::clone(&metadata_store), cas, search_manager).await;
+            match rebuilt {
+                Ok(documents) => {
+                    let state = metadata_store.load_index_state(&workspace_id).await;
+                    let recorded = match state {
+                        Ok(state) => {
+                            record_versions(
+                                &metadata_store,
+                                &workspace_id,
+                                state.as_ref(),
+                                Some(la_search::INDEX_SCHEMA_VERSION),
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e.to_string()),
+                    };
+                    if let Err(e) = recorded {
+                        warn!(
+                            workspace_id = %workspace_id,
+                            error = %e,
+                            "Failed to record rebuilt index version"
+                        );
+                    }
+                    info!(
+                        workspace_id = %workspace_id,
+                        documents,
+                        "Search index rebuilt for current schema"
+                    );
+                }
+                Err(e) => warn!(
+                    workspace_id = %workspace_id,
+                    error = %e,
+                    "Search index rebuild failed; will retry on next open"
+                ),
+            }
+        });
+    }
 
     let disk_result_store = state
         .get_disk_result_store()
@@ -104,9 +268,26 @@ pub(crate) async fn get_or_create_workspace_service(
         .get_search_session_manager()
         .ok_or("Search session manager not initialized")?;
     let thread_pool = state.get_search_thread_pool();
-    let regex_cache_size = search_config.regex_cache_size.max(1);
+    // 自适应缓存容量评估过后以其结果为准，否则使用配置的固定容量
+    let regex_cache_size = state
+        .monitoring
+        .cache_sizer()
+        .capacity()
+        .unwrap_or(search_config.regex_cache_size)
+        .max(1);
+
+    let views = Arc::new(
+        DecompressedViewCache::new(workspace_dir.join("views"), DEFAULT_VIEW_CACHE_BYTES)
+            .map_err(|e| format!("Failed to open decompressed view cache: {e}"))?,
+    );
 
-    let repo = WorkspaceRepo::new(cas, metadata_store, search_manager, disk_result_store);
+    let repo = WorkspaceRepo::new(
+        cas,
+        metadata_store,
+        search_manager,
+        disk_result_store,
+        views,
+    );
 
     let service = Arc::new(WorkspaceServiceImpl::new(
         workspace_id.to_string(),
@@ -118,6 +299,8 @@ pub(crate) async fn get_or_create_workspace_service(
         thread_pool,
         regex_cache_size,
         search_session_manager,
+        state.search.concurrency(),
+        state.workspace.watcher_budget(),
         app.clone(),
     ));
 
@@ -20,17 +20,23 @@ use parking_lot::Mutex;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::application::search_session::SearchSessionManager;
+use crate::application::search_concurrency::ConcurrentSearchManager;
+use crate::application::search_session::{FrozenSearchView, SearchSessionManager};
 use la_core::domain::event::EventPublisher;
 
 use crate::application::workspace_service::WorkspaceService;
 use crate::infrastructure::{QueryEngineLogSearcher, WorkspaceRepo};
+use crate::monitoring::{MemoryCategory, MemoryComponent};
 use crate::services::file_watcher::WatcherState;
+use crate::services::follow_query::FollowQueries;
+use crate::services::watcher_budget::WatcherBudget;
 
 mod import;
 mod search;
 mod watch;
 
+pub(crate) use import::{index_file_documents, load_file_formats, rebuild_search_index_inner};
+
 // ============================================================================
 // WorkspaceServiceImpl
 // ============================================================================
@@ -57,10 +63,20 @@ pub struct WorkspaceServiceImpl {
     searcher: Arc<QueryEngineLogSearcher>,
     /// P8: 搜索会话生命周期由后端全局 SearchSessionManager 统一管理
     search_session_manager: SearchSessionManager,
+    /// 刷新期间冻结的搜索视图
+    frozen_view: FrozenSearchView,
+    /// 全局搜索并发调度（跨工作区共享）
+    search_concurrency: ConcurrentSearchManager,
     /// 文件监听器状态（P5：从 AppState::watchers 移入实例）
     watcher_state: Arc<Mutex<Option<WatcherState>>>,
+    /// 全局监听预算（跨工作区共享）
+    watcher_budget: Arc<WatcherBudget>,
+    /// 挂载的跟随查询（与监听线程共享，停止监听后保留）
+    follow_queries: FollowQueries,
     /// Watch 模式 FilesUpdated 广播用（传递给 WatcherRunner）
     app_handle: tauri::AppHandle,
+    /// 进行中导入的文件计数（渐进式导入期间供搜索摘要与状态查询使用）
+    import_progress: Mutex<Option<Arc<la_archive::ImportProgress>>>,
 }
 
 impl WorkspaceServiceImpl {
@@ -73,6 +89,8 @@ impl WorkspaceServiceImpl {
     /// - `event_publisher`: 事件发射器 trait 对象
     /// - `thread_pool`: 全局共享的 Rayon 线程池
     /// - `regex_cache_size`: 正则缓存大小（传递给 QueryEngineLogSearcher）
+    /// - `search_concurrency`: 全局共享的搜索并发调度器
+    /// - `watcher_budget`: 全局共享的文件监听预算
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         workspace_id: String,
@@ -82,6 +100,8 @@ impl WorkspaceServiceImpl {
         thread_pool: Arc<rayon::ThreadPool>,
         regex_cache_size: usize,
         search_session_manager: SearchSessionManager,
+        search_concurrency: ConcurrentSearchManager,
+        watcher_budget: Arc<WatcherBudget>,
         app_handle: tauri::AppHandle,
     ) -> Self {
         Self {
@@ -92,8 +112,13 @@ impl WorkspaceServiceImpl {
             thread_pool,
             searcher: Arc::new(QueryEngineLogSearcher::new(regex_cache_size)),
             search_session_manager,
+            frozen_view: FrozenSearchView::default(),
+            search_concurrency,
             watcher_state: Arc::new(Mutex::new(None)),
+            watcher_budget,
+            follow_queries: FollowQueries::default(),
             app_handle,
+            import_progress: Mutex::new(None),
         }
     }
 }
@@ -124,8 +149,64 @@ impl WorkspaceService for WorkspaceServiceImpl {
         self.repo.search_engine()
     }
 
+    fn views(&self) -> &Arc<la_storage::DecompressedViewCache> {
+        self.repo.views()
+    }
+
     async fn close_databases(&self) {
         self.repo.metadata_store().close().await;
         self.repo.search_engine().close().await;
     }
+
+    fn release_caches(&self) {
+        self.searcher.clear_caches();
+    }
+
+    fn release_disk_caches(&self) -> u64 {
+        self.repo.views().clear()
+    }
+
+    fn engine_cache_usage(&self) -> (u64, u64) {
+        self.searcher.engine_cache_usage()
+    }
+
+    fn resize_caches(&self, capacity: usize) {
+        self.searcher.resize_engine_cache(capacity);
+    }
+
+    fn memory_usage(&self) -> Vec<MemoryComponent> {
+        let id = self.workspace_id.as_str();
+        let index = self.repo.search_engine().memory_usage();
+        let (existence_entries, existence_bytes) = self.repo.cas().existence_cache_usage();
+        let (planner_entries, planner_bytes) = self.searcher.cache_usage();
+        vec![
+            MemoryComponent::new(MemoryCategory::Index, "tantivy.reader", index.reader_bytes)
+                .with_entries(index.num_docs)
+                .for_workspace(id)
+                .mapped(),
+            MemoryComponent::new(
+                MemoryCategory::Index,
+                "tantivy.writer",
+                index.writer_heap_bytes,
+            )
+            .for_workspace(id),
+            MemoryComponent::new(
+                MemoryCategory::Cache,
+                "tantivy.highlight_cache",
+                index.highlight_cache_bytes,
+            )
+            .with_entries(index.highlight_cache_entries as u64)
+            .for_workspace(id),
+            MemoryComponent::new(
+                MemoryCategory::Cache,
+                "cas.existence_cache",
+                existence_bytes,
+            )
+            .with_entries(existence_entries)
+            .for_workspace(id),
+            MemoryComponent::new(MemoryCategory::Cache, "query_planner.cache", planner_bytes)
+                .with_entries(planner_entries)
+                .for_workspace(id),
+        ]
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -5,10 +6,20 @@ use async_trait::async_trait;
 use tokio_util::sync::CancellationToken;
 
 use crate::application::workspace_service::{ImportOptions, ImportResult, ImportService};
-use crate::utils::encoding::decode_log_content;
-use la_archive::processor::process_path_with_cas;
-use la_core::error::AppError;
+use crate::services::file_overrides::CompiledOverrides;
+use la_archive::post_extract::{validate_hooks, POST_EXTRACT_HOOKS_SETTING};
+use la_archive::processor::{
+    process_path_with_cas_and_checkpoints, resolve_entry_filter, store_gzip_frames,
+    store_network_summary,
+};
+use la_archive::{CasProcessingContext, ExtractionSelection, PostExtractHook};
+use la_core::domain::event::SecurityWarning;
+use la_core::error::{AppError, ErrorCategory};
 use la_core::traits::AppConfigProvider;
+use la_core::utils::{
+    apply_csv_metadata, assess_content, daily_level_counts, detect_crash_artifact,
+    detect_csv_mapping, fingerprint_content, split_lines, LogFormat, TermBloom, TimeIndex,
+};
 
 use super::WorkspaceServiceImpl;
 const SEARCH_INDEX_COMMIT_EVERY_FILES: usize = 25;
@@ -18,7 +29,101 @@ pub(super) fn compute_file_stats(content: &[u8]) -> (Option<i64>, Option<i64>, O
     crate::utils::log_stats::compute_file_stats(content)
 }
 
-async fn rebuild_search_index_inner(
+/// [`index_file_documents`] 的结果：写入的文档数，以及写入索引的时间范围与级别
+///
+/// 与 `compute_file_stats` 不同，这里的统计已应用用户覆盖，覆盖变更后用它刷新 `files` 表。
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IndexedFile {
+    pub documents: usize,
+    pub min_timestamp: Option<i64>,
+    pub max_timestamp: Option<i64>,
+    pub level_mask: Option<u8>,
+}
+
+/// 将单个文件的内容写入搜索索引（不提交），返回写入的文档数与统计
+///
+/// 级别与时间戳按文件格式解析（见 `la_core::utils::parse_metadata_as`）；`format` 为
+/// `None`（导入后的格式指纹尚未写入）时就地识别。CSV/TSV 按列映射取时间戳与级别，
+/// 表头行不写入索引。用户设置的编码、时区与解析器覆盖（见 [`CompiledOverrides`]）在
+/// 解码与解析时应用。崩溃产物（core dump、minidump、hprof）只登记到产物目录，不写入索引。
+pub(crate) fn index_file_documents(
+    search_manager: &la_search::SearchEngineManager,
+    cas: &la_storage::ContentAddressableStorage,
+    file: &la_storage::FileMetadata,
+    format: Option<&la_storage::FileFormat>,
+    start_id: usize,
+) -> std::result::Result<IndexedFile, String> {
+    let content = cas
+        .read_content_sync(&file.sha256_hash)
+        .map_err(|e| format!("Failed to read CAS content for {}: {e}", file.virtual_path))?;
+    if detect_crash_artifact(&content).is_some() {
+        return Ok(IndexedFile::default());
+    }
+    let overrides = format
+        .map(CompiledOverrides::from_format)
+        .unwrap_or_default();
+    let (format, csv_mapping) = match format {
+        Some(stored) => (stored.effective(), stored.effective_csv_mapping().cloned()),
+        None => {
+            let fingerprint = fingerprint_content(&content);
+            (fingerprint.format, fingerprint.csv_mapping)
+        }
+    };
+    let content_str = overrides.decode(&content);
+    let real_path = format!("cas://{}", file.sha256_hash);
+
+    // 改判为 CSV 但导入时未识别出映射的内容，按当前内容识别
+    let csv_mapping = (format == LogFormat::Csv)
+        .then(|| csv_mapping.or_else(|| detect_csv_mapping(&content_str)))
+        .flatten();
+    let header_lines = csv_mapping
+        .as_ref()
+        .map_or(0, |mapping| usize::from(mapping.has_header));
+
+    let lines: Vec<&str> = split_lines(&content_str).skip(header_lines).collect();
+    let mut indexed = IndexedFile::default();
+
+    for (chunk_index, chunk) in lines.chunks(1024).enumerate() {
+        let line_buffer: Vec<String> = chunk.iter().map(|line| line.to_string()).collect();
+        let mut entries = la_core::utils::parse_log_lines_as(
+            &line_buffer,
+            format,
+            &file.virtual_path,
+            &real_path,
+            start_id + indexed.documents,
+            header_lines + chunk_index * 1024 + 1,
+        );
+        if let Some(mapping) = &csv_mapping {
+            apply_csv_metadata(&mut entries, mapping);
+        }
+        overrides.apply(&mut entries);
+        for entry in &entries {
+            search_manager
+                .add_document(entry)
+                .map_err(|e| format!("Failed to add indexed document: {e}"))?;
+            indexed.record(entry);
+        }
+    }
+
+    Ok(indexed)
+}
+
+impl IndexedFile {
+    fn record(&mut self, entry: &la_core::models::LogEntry) {
+        self.documents += 1;
+        if let Some(ts) = la_search::parse_log_timestamp_to_unix(&entry.timestamp) {
+            self.min_timestamp = Some(self.min_timestamp.map_or(ts, |m| m.min(ts)));
+            self.max_timestamp = Some(self.max_timestamp.map_or(ts, |m| m.max(ts)));
+        }
+        if !entry.level.is_empty() {
+            let mask = self.level_mask.unwrap_or(0) | la_core::utils::level_to_mask(&entry.level);
+            self.level_mask = Some(mask);
+        }
+    }
+}
+
+/// 索引为空时从元数据与 CAS 重建搜索索引，返回写入的文档数（非空时跳过并返回 0）
+pub(crate) async fn rebuild_search_index_inner(
     metadata_store: Arc<la_storage::MetadataStore>,
     cas: Arc<la_storage::ContentAddressableStorage>,
     search_manager: Arc<la_search::SearchEngineManager>,
@@ -33,10 +138,12 @@ async fn rebuild_search_index_inner(
         return Ok(0);
     }
 
+    // 扫描列表已排除被忽略的文件
     let files = metadata_store
-        .get_all_files()
+        .get_files_with_pruning(None, None, None, None)
         .await
         .map_err(|e| format!("Failed to enumerate imported files for indexing: {e}"))?;
+    let formats = load_file_formats(&metadata_store).await;
 
     tokio::task::spawn_blocking(move || -> std::result::Result<usize, String> {
         search_manager
@@ -46,52 +153,10 @@ async fn rebuild_search_index_inner(
         let mut indexed_lines = 0usize;
 
         for (file_index, file) in files.into_iter().enumerate() {
-            let content = cas.read_content_sync(&file.sha256_hash).map_err(|e| {
-                format!("Failed to read CAS content for {}: {e}", file.virtual_path)
-            })?;
-            let (content_str, _) = decode_log_content(&content);
-            let real_path = format!("cas://{}", file.sha256_hash);
-
-            let mut line_buffer = Vec::with_capacity(1024);
-            let mut start_line_number = 1usize;
-
-            for line in content_str.lines() {
-                line_buffer.push(line.to_string());
-
-                if line_buffer.len() >= 1024 {
-                    let entries = la_core::utils::parse_log_lines(
-                        &line_buffer,
-                        &file.virtual_path,
-                        &real_path,
-                        indexed_lines,
-                        start_line_number,
-                    );
-                    for entry in &entries {
-                        search_manager
-                            .add_document(entry)
-                            .map_err(|e| format!("Failed to add indexed document: {e}"))?;
-                    }
-                    indexed_lines += entries.len();
-                    start_line_number += line_buffer.len();
-                    line_buffer.clear();
-                }
-            }
-
-            if !line_buffer.is_empty() {
-                let entries = la_core::utils::parse_log_lines(
-                    &line_buffer,
-                    &file.virtual_path,
-                    &real_path,
-                    indexed_lines,
-                    start_line_number,
-                );
-                for entry in &entries {
-                    search_manager
-                        .add_document(entry)
-                        .map_err(|e| format!("Failed to add indexed document: {e}"))?;
-                }
-                indexed_lines += entries.len();
-            }
+            let format = formats.get(&file.sha256_hash);
+            indexed_lines +=
+                index_file_documents(&search_manager, &cas, &file, format, indexed_lines)?
+                    .documents;
 
             if (file_index + 1) % SEARCH_INDEX_COMMIT_EVERY_FILES == 0 {
                 search_manager
@@ -110,12 +175,99 @@ async fn rebuild_search_index_inner(
     .map_err(|e| format!("Search index rebuild task panicked: {e}"))?
 }
 
+/// 按内容哈希读取各文件的格式与列映射；读取失败时返回空表，由索引时就地识别
+pub(crate) async fn load_file_formats(
+    metadata_store: &la_storage::MetadataStore,
+) -> HashMap<String, la_storage::FileFormat> {
+    match metadata_store.get_file_formats().await {
+        Ok(formats) => formats,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load file formats, detecting while indexing");
+            HashMap::new()
+        }
+    }
+}
+
+impl WorkspaceServiceImpl {
+    /// 本次导入使用的解压后处理钩子：显式提供时保存为工作区设置，否则读取已保存的设置
+    async fn resolve_post_extract_hooks(
+        &self,
+        requested: Option<Vec<PostExtractHook>>,
+    ) -> la_core::error::Result<Vec<PostExtractHook>> {
+        let metadata_store = self.repo.metadata_store();
+        if let Some(hooks) = requested {
+            validate_hooks(&hooks).map_err(AppError::validation_error)?;
+            let json = serde_json::to_string(&hooks)
+                .map_err(|e| AppError::validation_error(format!("Invalid hooks: {e}")))?;
+            metadata_store
+                .set_workspace_setting(POST_EXTRACT_HOOKS_SETTING, &json)
+                .await?;
+            return Ok(hooks);
+        }
+
+        let saved = metadata_store
+            .get_workspace_setting(POST_EXTRACT_HOOKS_SETTING)
+            .await?;
+        Ok(saved
+            .and_then(|json| {
+                serde_json::from_str(&json)
+                    .inspect_err(|e| {
+                        tracing::warn!(
+                            workspace_id = %self.workspace_id,
+                            error = %e,
+                            "Ignoring unreadable post-extraction hook settings"
+                        )
+                    })
+                    .ok()
+            })
+            .unwrap_or_default())
+    }
+
+    /// 为本次导入中被隔离的压缩包条目发送安全告警
+    async fn report_quarantined_entries(&self, since: i64) {
+        let entries = match self
+            .repo
+            .metadata_store()
+            .get_quarantined_entries(since)
+            .await
+        {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!(
+                    workspace_id = %self.workspace_id,
+                    error = %e,
+                    "Failed to load quarantined archive entries"
+                );
+                return;
+            }
+        };
+
+        for entry in entries {
+            tracing::warn!(
+                workspace_id = %self.workspace_id,
+                archive = %entry.archive_virtual_path,
+                entry = %entry.entry_name,
+                reason = %entry.reason,
+                "Archive entry quarantined for path traversal"
+            );
+            self.event_publisher
+                .emit_security_warning(&SecurityWarning::path_traversal(
+                    self.workspace_id.clone(),
+                    entry.archive_virtual_path,
+                    entry.entry_name,
+                    entry.reason,
+                ))
+                .await;
+        }
+    }
+}
+
 #[async_trait]
 impl ImportService for WorkspaceServiceImpl {
     async fn import_file(
         &self,
         source_path: &std::path::Path,
-        _options: ImportOptions,
+        options: ImportOptions,
         config_provider: &dyn AppConfigProvider,
         task_id: &str,
         cancellation_token: CancellationToken,
@@ -126,26 +278,72 @@ impl ImportService for WorkspaceServiceImpl {
             .to_string_lossy()
             .to_string();
 
-        process_path_with_cas(
+        let filter = resolve_entry_filter(config_provider, options.entry_filter)
+            .await
+            .map_err(AppError::validation_error)?;
+        let selection = ExtractionSelection::new(&options.selection)
+            .map_err(AppError::validation_error)?
+            .with_filter(filter);
+        let selection = (!selection.is_empty()).then(|| Arc::new(selection));
+
+        let post_extract_hooks = self
+            .resolve_post_extract_hooks(options.post_extract_hooks)
+            .await?;
+        let context = CasProcessingContext::new(
+            self.workspace_dir.clone(),
+            Arc::clone(self.repo.cas()),
+            self.repo.metadata_store().clone(),
+        )
+        .with_selection(selection)
+        .with_post_extract_hooks(post_extract_hooks)
+        .with_password(options.password);
+
+        // 已处理的文件在导入期间即可搜索，进度供搜索摘要标注“已导入 N%”
+        let progress = Arc::new(la_archive::ImportProgress::new());
+        let prescanned = if source_path.is_dir() {
+            options.prescanned.inspect(|scanned| {
+                progress.discover(scanned.summary.files);
+            })
+        } else {
+            progress.discover(1);
+            None
+        };
+        *self.import_progress.lock() = Some(Arc::clone(&progress));
+        let context = context
+            .with_progress(Arc::clone(&progress))
+            .with_prescanned(prescanned);
+
+        let import_started_at = chrono::Utc::now().timestamp();
+        let processed = process_path_with_cas_and_checkpoints(
             source_path,
             &root_name,
-            &self.workspace_dir,
-            self.repo.cas(),
-            self.repo.metadata_store().clone(),
+            &context,
             config_provider,
             task_id,
             &self.workspace_id,
             None,
             0,
         )
-        .await
-        .map_err(|e| {
+        .await;
+        {
+            let mut current = self.import_progress.lock();
+            if current.as_ref().is_some_and(|p| Arc::ptr_eq(p, &progress)) {
+                *current = None;
+            }
+        }
+        processed.map_err(|e| {
+            // 保留密码错误的类别，前端据此提示输入密码
+            if e.category() == ErrorCategory::PasswordRequired {
+                return e;
+            }
             AppError::archive_error(
                 format!("Import failed: {e}"),
                 Some(source_path.to_path_buf()),
             )
         })?;
 
+        self.report_quarantined_entries(import_started_at).await;
+
         let metadata_store = self.repo.metadata_store().clone();
         let cas = Arc::clone(self.repo.cas());
         let workspace_id = self.workspace_id.clone();
@@ -172,6 +370,89 @@ impl ImportService for WorkspaceServiceImpl {
                         match cas.read_content(&file.sha256_hash).await {
                             Ok(content) => {
                                 let (min_ts, max_ts, level_mask) = compute_file_stats(&content);
+                                if let Some(bloom) = TermBloom::from_content(&content) {
+                                    if let Err(e) = metadata_store
+                                        .set_term_sketch(&file.sha256_hash, &bloom.to_bytes())
+                                        .await
+                                    {
+                                        tracing::warn!(
+                                            hash = %file.sha256_hash,
+                                            error = %e,
+                                            "Failed to store term sketch in fallback"
+                                        );
+                                    }
+                                }
+                                if let Some(time_index) = TimeIndex::from_content(&content) {
+                                    if let Err(e) = metadata_store
+                                        .set_time_index(&file.sha256_hash, &time_index.to_bytes())
+                                        .await
+                                    {
+                                        tracing::warn!(
+                                            hash = %file.sha256_hash,
+                                            error = %e,
+                                            "Failed to store time index in fallback"
+                                        );
+                                    }
+                                }
+                                store_gzip_frames(&cas, &metadata_store, &file.sha256_hash).await;
+                                if let Err(e) = metadata_store
+                                    .set_level_histogram(
+                                        &file.sha256_hash,
+                                        &daily_level_counts(&content),
+                                    )
+                                    .await
+                                {
+                                    tracing::warn!(
+                                        hash = %file.sha256_hash,
+                                        error = %e,
+                                        "Failed to store level histogram in fallback"
+                                    );
+                                }
+                                if let Err(e) = metadata_store
+                                    .set_data_quality(&file.sha256_hash, &assess_content(&content))
+                                    .await
+                                {
+                                    tracing::warn!(
+                                        hash = %file.sha256_hash,
+                                        error = %e,
+                                        "Failed to store data quality in fallback"
+                                    );
+                                }
+                                if let Err(e) = metadata_store
+                                    .set_format_fingerprint(
+                                        &file.sha256_hash,
+                                        &fingerprint_content(&content),
+                                    )
+                                    .await
+                                {
+                                    tracing::warn!(
+                                        hash = %file.sha256_hash,
+                                        error = %e,
+                                        "Failed to store format fingerprint in fallback"
+                                    );
+                                }
+                                if let Err(e) =
+                                    store_network_summary(&cas, &metadata_store, &file, &content)
+                                        .await
+                                {
+                                    tracing::warn!(
+                                        virtual_path = %file.virtual_path,
+                                        error = %e,
+                                        "Failed to store network capture summary in fallback"
+                                    );
+                                }
+                                if let Some(artifact) = detect_crash_artifact(&content) {
+                                    if let Err(e) = metadata_store
+                                        .set_crash_artifact(&file.sha256_hash, &artifact)
+                                        .await
+                                    {
+                                        tracing::warn!(
+                                            hash = %file.sha256_hash,
+                                            error = %e,
+                                            "Failed to catalog crash artifact in fallback"
+                                        );
+                                    }
+                                }
                                 if let Err(e) = metadata_store
                                     .update_file_ready(
                                         &file.virtual_path,
@@ -252,4 +533,11 @@ impl ImportService for WorkspaceServiceImpl {
             files_imported,
         })
     }
+
+    fn import_progress(&self) -> Option<u8> {
+        self.import_progress
+            .lock()
+            .as_ref()
+            .map(|progress| progress.percent())
+    }
 }
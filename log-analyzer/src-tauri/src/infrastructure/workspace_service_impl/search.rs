@@ -3,7 +3,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio_util::sync::CancellationToken;
 
-use crate::application::workspace_service::SearchService;
+use crate::application::search_session::FrozenViewGuard;
+use crate::application::workspace_service::{ImportService, SearchService};
 use crate::application::SearchUseCase;
 use crate::infrastructure::{CasLogFileRepository, DiskResultStoreRepo, QueryEngineLogSearcher};
 use la_core::error::{AppError, Result};
@@ -19,10 +20,30 @@ impl SearchService for WorkspaceServiceImpl {
         _raw_terms: Vec<String>,
         filters: SearchFilters,
         max_results: usize,
+        origin: String,
+        timeout: Option<std::time::Duration>,
+        batch_max_bytes: usize,
     ) -> Result<String> {
         let search_id = uuid::Uuid::new_v4().to_string();
         let cancellation_token = CancellationToken::new();
 
+        let use_case = self.search_use_case().with_batch_budget(batch_max_bytes);
+
+        // 提交时固定数据快照：排队期间及分页过程中监听器提交的新数据不会混入本次结果。
+        // 刷新进行中时沿用刷新前冻结的视图（未按过滤条件裁剪，文件与行过滤在扫描时生效）
+        let snapshot = match self.frozen_view.current() {
+            Some(frozen) => (*frozen).clone(),
+            // 导入进行中时只覆盖已导入的文件，摘要中标注导入进度
+            None => use_case
+                .pin_snapshot(
+                    &self.workspace_id,
+                    &filters,
+                    Some(self.repo.search_engine().snapshot()),
+                )
+                .await?
+                .with_import_progress(self.import_progress()),
+        };
+
         self.search_session_manager
             .create_session(&search_id)
             .map_err(|e| {
@@ -30,37 +51,36 @@ impl SearchService for WorkspaceServiceImpl {
             })?;
         self.search_session_manager
             .register_token(&search_id, cancellation_token.clone());
+        let snapshot = self
+            .search_session_manager
+            .pin_snapshot(&search_id, snapshot);
 
-        let log_files = Arc::new(CasLogFileRepository {
-            metadata: self.repo.metadata_store().clone(),
-            cas: self.repo.cas().clone(),
-        });
-        let results = Arc::new(DiskResultStoreRepo {
-            store: self.repo.disk_result_store().clone(),
-        });
-        let searcher: Arc<QueryEngineLogSearcher> = Arc::clone(&self.searcher);
-
-        let use_case = SearchUseCase::new(
-            log_files,
-            results,
-            self.event_publisher.clone(),
-            searcher,
-            self.thread_pool.clone(),
-        );
-
-        let workspace_id = self.workspace_id.clone();
         let search_id_clone = search_id.clone();
         let session_manager = self.search_session_manager.clone();
+        let concurrency = self.search_concurrency.clone();
+        let events = self.event_publisher.clone();
 
         tokio::spawn(async move {
+            // 排队等待并发槽位；排队期间被取消则直接结束
+            let _permit = tokio::select! {
+                permit = concurrency.acquire(&origin) => permit,
+                _ = cancellation_token.cancelled() => {
+                    session_manager.cleanup_token(&search_id_clone);
+                    tracing::debug!(search_id = %search_id_clone, origin = %origin, "Search cancelled while queued");
+                    events.emit_search_cancelled(&search_id_clone).await;
+                    return;
+                }
+            };
+
             let result = use_case
-                .execute(
-                    &workspace_id,
+                .execute_pinned(
+                    &snapshot,
                     &query,
                     &filters,
                     max_results,
                     search_id_clone.clone(),
                     cancellation_token,
+                    timeout,
                 )
                 .await;
 
@@ -91,4 +111,43 @@ impl SearchService for WorkspaceServiceImpl {
     async fn cancel_search(&self, search_id: &str) -> Result<()> {
         self.search_session_manager.cancel_search(search_id)
     }
+
+    async fn freeze_search_view(&self) -> Result<FrozenViewGuard> {
+        let snapshot = self
+            .search_use_case()
+            .pin_snapshot(
+                &self.workspace_id,
+                &SearchFilters::default(),
+                Some(self.repo.search_engine().snapshot()),
+            )
+            .await?;
+        tracing::debug!(
+            workspace_id = %self.workspace_id,
+            files = snapshot.files().len(),
+            "Froze search view for refresh"
+        );
+        Ok(self.frozen_view.freeze(snapshot))
+    }
+}
+
+impl WorkspaceServiceImpl {
+    fn search_use_case(&self) -> SearchUseCase {
+        let log_files = Arc::new(CasLogFileRepository {
+            metadata: self.repo.metadata_store().clone(),
+            cas: self.repo.cas().clone(),
+            views: Some(self.repo.views().clone()),
+        });
+        let results = Arc::new(DiskResultStoreRepo {
+            store: self.repo.disk_result_store().clone(),
+        });
+        let searcher: Arc<QueryEngineLogSearcher> = Arc::clone(&self.searcher);
+
+        SearchUseCase::new(
+            log_files,
+            results,
+            self.event_publisher.clone(),
+            searcher,
+            self.thread_pool.clone(),
+        )
+    }
 }
@@ -3,18 +3,23 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use notify::Watcher;
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::application::watch::{WatchEvent, WatchEventKind};
 use crate::application::workspace_service::WatchService;
 use crate::infrastructure::watcher_runner::WatcherRunner;
-use crate::services::file_watcher::WatcherState;
+use crate::services::file_watcher::{WatchBackend, WatcherState};
+use crate::services::polling_watcher::{detect_network_filesystem, PollingScanner};
+use crate::services::watcher_budget::{
+    estimate_watch_handles, is_os_limit_error, WatchMode, WatcherStatus,
+};
 use la_core::error::{AppError, Result};
+use la_core::models::SearchQuery;
 
 use super::WorkspaceServiceImpl;
 #[async_trait]
 impl WatchService for WorkspaceServiceImpl {
-    async fn start_watch(&self, watch_path: &str) -> Result<()> {
+    async fn start_watch(&self, watch_path: &str) -> Result<WatcherStatus> {
         let watch_path_buf = PathBuf::from(watch_path);
         if !watch_path_buf.exists() {
             return Err(AppError::validation_error(format!(
@@ -31,42 +36,71 @@ impl WatchService for WorkspaceServiceImpl {
             }
         }
 
-        let (tx, notify_rx) =
-            crossbeam::channel::unbounded::<std::result::Result<notify::Event, notify::Error>>();
         let (watch_tx, rx) = crossbeam::channel::unbounded::<WatchEvent>();
 
-        let mut watcher = notify::recommended_watcher(tx)
-            .map_err(|e| AppError::io_error(format!("Failed to create file watcher: {e}"), None))?;
-
-        watcher
-            .watch(&watch_path_buf, notify::RecursiveMode::Recursive)
-            .map_err(|e| AppError::io_error(format!("Failed to start watching path: {e}"), None))?;
-
-        std::thread::spawn(move || {
-            for res in notify_rx {
-                let event = match res {
-                    Ok(e) => {
-                        let kind = match e.kind {
-                            notify::EventKind::Create(_) => WatchEventKind::Create,
-                            notify::EventKind::Modify(_) => WatchEventKind::Modify,
-                            notify::EventKind::Remove(_) => WatchEventKind::Remove,
-                            _ => WatchEventKind::Other,
-                        };
-                        WatchEvent {
-                            kind,
-                            paths: e.paths,
-                        }
-                    }
-                    Err(err) => {
-                        tracing::warn!(error = %err, "NotifyWatcher: event error, skipping");
-                        continue;
-                    }
-                };
-                if watch_tx.send(event).is_err() {
-                    break;
+        // 网络文件系统检测与目录计数都可能较慢（远程 stat / 大目录树）
+        let (estimated_handles, network_fs) = {
+            let path = watch_path_buf.clone();
+            let detect_network = self.watcher_budget.poll_network_filesystems();
+            tokio::task::spawn_blocking(move || {
+                match detect_network
+                    .then(|| detect_network_filesystem(&path))
+                    .flatten()
+                {
+                    Some(fs_type) => (0, Some(fs_type)),
+                    None => (estimate_watch_handles(&path), None),
+                }
+            })
+            .await
+            .map_err(|e| AppError::io_error(format!("Watch setup task failed: {e}"), None))?
+        };
+        let mut status = match &network_fs {
+            Some(fs_type) => self
+                .watcher_budget
+                .register_network(&self.workspace_id, fs_type),
+            None => self
+                .watcher_budget
+                .reserve(&self.workspace_id, estimated_handles),
+        };
+
+        let mut backend = None;
+        if status.mode == WatchMode::Native {
+            match create_native_watcher(watch_tx.clone(), &watch_path_buf) {
+                Ok(watcher) => backend = Some(WatchBackend::Native(watcher)),
+                Err(e) if is_os_limit_error(&e) => {
+                    status = self
+                        .watcher_budget
+                        .downgrade(&self.workspace_id, format!("OS watch limit reached: {e}"));
+                }
+                Err(e) => {
+                    self.watcher_budget.release(&self.workspace_id);
+                    return Err(AppError::io_error(
+                        format!("Failed to start watching path: {e}"),
+                        Some(watch_path_buf),
+                    ));
                 }
             }
-        });
+        }
+
+        let backend = match backend {
+            Some(backend) => backend,
+            None => {
+                warn!(
+                    workspace_id = %self.workspace_id,
+                    reason = status.fallback_reason.as_deref().unwrap_or_default(),
+                    "Native file watching unavailable, falling back to polling"
+                );
+                let (min_interval, max_interval) = self.watcher_budget.poll_intervals();
+                status.poll_interval_ms = Some(min_interval.as_millis() as u64);
+                WatchBackend::Polling(PollingScanner::start(
+                    watch_path_buf.clone(),
+                    min_interval,
+                    max_interval,
+                    watch_tx,
+                ))
+            }
+        };
+        status.network_filesystem = network_fs;
 
         let runner = WatcherRunner::new(
             self.repo.cas().clone(),
@@ -74,17 +108,22 @@ impl WatchService for WorkspaceServiceImpl {
             Arc::clone(self.repo.search_engine()),
             watch_path_buf,
             self.workspace_id.clone(),
+            self.follow_queries.clone(),
             self.app_handle.clone(),
+            Arc::clone(self.repo.metadata_store()),
         );
         let handle = std::thread::spawn(move || runner.run(rx));
 
         *self.watcher_state.lock() = Some(WatcherState {
             is_active: true,
+            mode: status.mode,
+            fallback_reason: status.fallback_reason.clone(),
+            network_filesystem: status.network_filesystem.clone(),
             thread_handle: Arc::new(parking_lot::Mutex::new(Some(handle))),
-            watcher: Arc::new(parking_lot::Mutex::new(Some(watcher))),
+            watcher: Arc::new(parking_lot::Mutex::new(Some(backend))),
         });
 
-        Ok(())
+        Ok(status)
     }
 
     async fn stop_watch(&self) -> Result<()> {
@@ -104,6 +143,7 @@ impl WatchService for WorkspaceServiceImpl {
         drop(state);
 
         drop(watcher_opt);
+        self.watcher_budget.release(&self.workspace_id);
 
         if let Some(handle) = thread_handle {
             if handle.join().is_err() {
@@ -118,4 +158,76 @@ impl WatchService for WorkspaceServiceImpl {
         let guard = self.watcher_state.lock();
         Ok(guard.as_ref().map(|w| w.is_active).unwrap_or(false))
     }
+
+    fn watch_status(&self) -> Option<WatcherStatus> {
+        let guard = self.watcher_state.lock();
+        guard.as_ref().map(|w| WatcherStatus {
+            workspace_id: self.workspace_id.clone(),
+            mode: w.mode,
+            fallback_reason: w.fallback_reason.clone(),
+            estimated_handles: self
+                .watcher_budget
+                .status(&self.workspace_id)
+                .map(|s| s.estimated_handles)
+                .unwrap_or(0),
+            network_filesystem: w.network_filesystem.clone(),
+            poll_interval_ms: match w.watcher.lock().as_ref() {
+                Some(WatchBackend::Polling(scanner)) => {
+                    Some(scanner.current_interval().as_millis() as u64)
+                }
+                _ => None,
+            },
+        })
+    }
+
+    fn attach_follow_query(&self, query: &SearchQuery) -> Result<()> {
+        self.follow_queries.attach(query)
+    }
+
+    fn detach_follow_query(&self, query_id: &str) -> bool {
+        self.follow_queries.detach(query_id)
+    }
+
+    fn follow_query_ids(&self) -> Vec<String> {
+        self.follow_queries.query_ids()
+    }
+}
+
+/// 创建原生监听，并在独立线程中把 notify 事件转换为 WatchEvent
+fn create_native_watcher(
+    watch_tx: crossbeam::channel::Sender<WatchEvent>,
+    path: &std::path::Path,
+) -> notify::Result<Box<dyn Watcher + Send>> {
+    let (tx, notify_rx) =
+        crossbeam::channel::unbounded::<std::result::Result<notify::Event, notify::Error>>();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(path, notify::RecursiveMode::Recursive)?;
+
+    std::thread::spawn(move || {
+        for res in notify_rx {
+            let event = match res {
+                Ok(e) => {
+                    let kind = match e.kind {
+                        notify::EventKind::Create(_) => WatchEventKind::Create,
+                        notify::EventKind::Modify(_) => WatchEventKind::Modify,
+                        notify::EventKind::Remove(_) => WatchEventKind::Remove,
+                        _ => WatchEventKind::Other,
+                    };
+                    WatchEvent {
+                        kind,
+                        paths: e.paths,
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(error = %err, "NotifyWatcher: event error, skipping");
+                    continue;
+                }
+            };
+            if watch_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(Box::new(watcher))
 }
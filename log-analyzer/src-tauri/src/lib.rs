@@ -13,8 +13,10 @@ pub mod infrastructure;
 
 // 核心模块
 pub mod adapters;
+pub mod benchmark;
 pub mod commands;
 pub mod models;
+pub mod monitoring;
 pub mod utils;
 
 // 业务服务与引擎（直接使用 la_search / la_storage crate）
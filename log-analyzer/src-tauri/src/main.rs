@@ -8,10 +8,19 @@
 
 // 导入 log_analyzer 库的模块（所有命令均在 commands/ 层定义）
 use log_analyzer::commands::{
-    config::*, export::*, import::*, log_config::*, search::*, state_sync::*, validation::*,
-    virtual_tree::*, watch::*, workspace::*,
+    actions::*, analysis::*, analysis_macro::*, assistant::*, client_session::*, config::*,
+    diagnostics::*, export::*, import::*, log_config::*, search::*, state_sync::*, validation::*,
+    virtual_tree::*, watch::*, workspace::*, workspace_share::*, workspace_template::*,
 };
+use log_analyzer::infrastructure::client_session_reaper::spawn_client_session_reaper;
 use log_analyzer::models::AppState;
+use log_analyzer::monitoring::telemetry::instrument;
+use log_analyzer::monitoring::{
+    init_sentry, shutdown_sentry, spawn_cache_sizer, spawn_resource_monitor, spawn_telemetry_saver,
+    telemetry, ErrorReportStore,
+};
+use log_analyzer::services::assistant_tools::stdio_options;
+use log_analyzer::services::chunked_upload::UploadManager;
 use log_analyzer::task_manager::{TaskManager, TauriEventEmitter};
 use log_analyzer::utils::load_app_config;
 use std::sync::Arc;
@@ -23,9 +32,13 @@ use tracing::info;
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 初始化日志系统
     // 使用条件编译：debug 模式启用 DEBUG 级别，release 模式启用 INFO 级别
-    init_logging_with_profile();
+    // `--mcp-stdio` 时 stdout 专用于助手工具协议，日志改写到 stderr
+    let assistant_stdio = stdio_options(std::env::args());
+    init_logging_with_profile(assistant_stdio.is_some());
 
-    fn init_logging_with_profile() {
+    fn init_logging_with_profile(log_to_stderr: bool) {
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::util::SubscriberInitExt;
         use tracing_subscriber::EnvFilter;
 
         let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
@@ -58,13 +71,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         });
 
-        tracing_subscriber::fmt()
-            .with_env_filter(filter)
-            .with_target(true)
-            .with_thread_ids(false)
-            .with_thread_names(false)
-            .with_file(false)
-            .with_line_number(false)
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_target(true)
+                    .with_thread_ids(false)
+                    .with_thread_names(false)
+                    .with_file(false)
+                    .with_line_number(false)
+                    .with_writer(if log_to_stderr {
+                        tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stderr)
+                    } else {
+                        tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stdout)
+                    }),
+            )
+            // 捕获 ERROR 事件，供前端错误上报做时间窗口关联
+            .with(log_analyzer::monitoring::BackendErrorLayer::new())
+            // 最近的后端日志，供 get_backend_logs / export_backend_logs 使用
+            .with(log_analyzer::monitoring::BackendLogLayer::new())
+            // Sentry 未初始化时该层为空操作
+            .with(sentry::integrations::tracing::layer())
             .init();
     }
 
@@ -100,24 +127,113 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // 设置到 AppState
             app_state.init_task_manager(task_manager);
 
+            // 可选的 Sentry 错误上报（默认关闭）
+            if let Some(config) = app_config.as_ref() {
+                init_sentry(&config.monitoring.sentry);
+                app_state.search.concurrency().set_limits(
+                    config.search.max_concurrent_searches,
+                    config.search.max_searches_per_origin,
+                );
+                app_state
+                    .workspace
+                    .watcher_budget()
+                    .configure(&config.watch);
+                app_state.search.links().configure(&config.links);
+                app_state.sync.clients().configure(&config.security);
+                app_state
+                    .search
+                    .translation()
+                    .configure(&config.translation);
+            }
+
             info!("✅ TaskManager 初始化成功");
 
             // M4 Fix: Initialize DiskResultStore at app data dir (persistent)
             // instead of the OS temp directory (volatile, may be cleaned by system)
             if let Ok(app_data_dir) = app.path().app_data_dir() {
-                if let Err(e) = app_state.init_disk_result_store_at(app_data_dir) {
+                if let Err(e) = app_state.init_disk_result_store_at(app_data_dir.clone()) {
                     tracing::error!(error = %e, "DiskResultStore init failure");
                     let _ = app
                         .handle()
                         .emit("import-error", &format!("Search cache init failed: {e}"));
                 }
+
+                // panic 时写入崩溃报告；在 Sentry 之后安装，先写报告再交给 Sentry
+                let handle = app.handle().clone();
+                log_analyzer::monitoring::install_panic_hook(
+                    app_data_dir.join(log_analyzer::monitoring::CRASH_REPORT_DIR),
+                    Box::new(move || {
+                        let state = handle.state::<AppState>();
+                        serde_json::json!({
+                            "workspaceIds": state.workspace_ids(),
+                            "operations": state.sync.leases().snapshot(),
+                            "searches": state.search.concurrency().snapshot(),
+                        })
+                    }),
+                );
+
+                // 前端错误上报存储（失败不影响主流程，仅诊断功能不可用）
+                let handle = app.handle().clone();
+                let data_dir = app_data_dir.clone();
+                tauri::async_runtime::spawn(async move {
+                    match ErrorReportStore::new(&data_dir).await {
+                        Ok(store) => handle
+                            .state::<AppState>()
+                            .monitoring
+                            .init_error_reports(store),
+                        Err(e) => tracing::warn!(error = %e, "Error report store init failed"),
+                    }
+                });
+
+                // 分块上传暂存目录：上次运行残留的未完成上传无法续传，启动时清空
+                let upload_root = app_data_dir.join("uploads");
+                if upload_root.exists() {
+                    if let Err(e) = std::fs::remove_dir_all(&upload_root) {
+                        tracing::warn!(error = %e, "Failed to clear upload staging dir");
+                    }
+                }
+                app_state
+                    .workspace
+                    .init_uploads(UploadManager::new(upload_root));
+
+                // 本地使用统计（默认关闭，只在用户导出时离开本机）
+                telemetry().init(
+                    &app_data_dir,
+                    app_config
+                        .as_ref()
+                        .is_some_and(|config| config.monitoring.telemetry_enabled),
+                );
+                spawn_telemetry_saver();
+
+                // 内存 / 磁盘资源监控
+                let monitoring_config = app_config
+                    .as_ref()
+                    .map(|config| config.monitoring.clone())
+                    .unwrap_or_default();
+                spawn_resource_monitor(app.handle().clone(), monitoring_config, app_data_dir);
+            }
+
+            // 按可用内存调整各工作区的正则引擎缓存容量
+            let cache_config = app_config
+                .as_ref()
+                .map(|config| config.cache.clone())
+                .unwrap_or_default();
+            spawn_cache_sizer(app.handle().clone(), cache_config);
+
+            spawn_client_session_reaper(app.handle().clone());
+
+            if let Some(options) = assistant_stdio {
+                spawn_stdio_server(app.handle().clone(), options);
             }
 
             info!("✅ 应用初始化完成");
             Ok(())
         })
         // 注册所有命令
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(instrument(tauri::generate_handler![
+            // ===== 操作注册表 =====
+            list_actions,
+            invoke_action,
             // ===== 配置管理 =====
             load_config,
             save_config,
@@ -127,30 +243,135 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             save_search_config,
             get_task_manager_config,
             save_task_manager_config,
+            get_link_templates,
+            register_link_template,
+            remove_link_template,
             // ===== 工作区管理 =====
             create_workspace,
             load_workspace,
             refresh_workspace,
+            preview_refresh,
+            repair_corrupted_objects,
+            get_failed_entries,
+            retry_failed_extractions,
             delete_workspace,
             cancel_task,
             get_workspace_status,
             get_workspace_time_range,
+            get_recent_entries,
+            get_entries_around_time,
+            find_silences,
+            find_first_occurrence,
+            get_adjacent_match,
+            get_keyword_cooccurrence,
+            diff_search_results,
+            run_metadata_query,
+            get_workspace_overview,
+            get_dedup_report,
+            get_skipped_entries,
+            get_workspace_manifest,
+            get_post_extract_hooks,
+            set_post_extract_hooks,
+            get_post_extract_report,
+            get_workspace_read_only,
+            set_workspace_read_only,
+            get_max_line_length,
+            set_max_line_length,
+            get_level_order,
+            set_level_order,
+            get_search_defaults,
+            set_search_defaults,
+            get_field_extractors,
+            save_field_extractor,
+            delete_field_extractor,
+            test_extractor,
+            extract_entry_fields,
+            get_webhooks,
+            save_webhook,
+            delete_webhook,
+            test_webhook,
+            get_workspace_leases,
+            set_file_search_flag,
+            get_file_search_flags,
+            set_file_format,
+            get_csv_mapping,
+            set_csv_mapping,
+            set_file_overrides,
+            get_crash_artifacts,
+            open_artifact_folder,
+            set_file_time_offset,
+            get_file_time_offsets,
+            estimate_clock_skew,
+            // ===== 工作区模板 =====
+            list_workspace_templates,
+            save_workspace_template,
+            delete_workspace_template,
+            capture_workspace_template,
+            export_workspace_template,
+            import_workspace_template,
+            create_workspace_from_template,
+            // ===== 工作区共享 =====
+            start_workspace_share,
+            stop_workspace_share,
+            list_workspace_shares,
+            mount_remote_workspace,
+            open_remote_workspace,
+            list_remote_workspaces,
+            unmount_remote_workspace,
+            refresh_remote_workspace,
+            remote_workspace_call,
+            // ===== 分析宏 =====
+            start_macro_recording,
+            get_macro_recording,
+            stop_macro_recording,
+            discard_macro_recording,
+            list_macros,
+            save_macro,
+            delete_macro,
+            replay_macro,
+            // ===== 助手工具服务 =====
+            assistant_rpc,
             // ===== 文件监听 =====
             start_watch,
             stop_watch,
+            get_watcher_budget,
+            attach_follow_query,
+            detach_follow_query,
             // ===== 虚拟文件树 =====
             read_file_by_hash,
+            seek_file_time,
             // ===== 日志搜索 =====
             search_logs,
             cancel_search,
             fetch_search_page,
+            get_search_snapshot,
+            translate_entries,
+            lint_search_query,
+            close_search_session,
+            get_active_searches_count,
+            save_search_results,
+            list_saved_results,
+            open_saved_results,
             // ===== 导入 =====
             import_folder,
+            import_from_url,
+            begin_upload,
+            append_chunk,
+            finish_upload,
+            cancel_upload,
+            preview_import,
+            quick_scan_archive,
+            promote_quick_scan,
             check_rar_support,
             // ===== 导出 =====
             export_results,
+            export_statistics,
             // ===== 状态同步 =====
             init_state_sync,
+            open_client_session,
+            client_heartbeat,
+            close_client_session,
+            list_client_sessions,
             // ===== 日志配置 =====
             get_current_log_config,
             set_log_level,
@@ -168,7 +389,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             validate_workspace_config_cmd,
             validate_search_query_cmd,
             validate_archive_config_cmd,
-        ])
+            // ===== 诊断 =====
+            report_frontend_error,
+            get_error_groups,
+            get_backend_logs,
+            export_backend_logs,
+            list_crash_reports,
+            delete_crash_reports,
+            run_startup_check,
+            cleanup_orphaned_temp_dirs,
+            clear_error_groups,
+            get_memory_breakdown,
+            run_diagnostics_benchmark,
+            // ===== 使用统计 =====
+            get_telemetry_report,
+            set_telemetry_enabled,
+            export_telemetry,
+            clear_telemetry,
+        ]))
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
@@ -214,6 +452,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     });
                 });
 
+                // 3. 刷新 Sentry 发送队列，保存使用统计
+                shutdown_sentry();
+                telemetry().save();
+
                 info!("应用退出清理完成");
             }
         });
@@ -10,10 +10,17 @@ use std::sync::Arc;
 
 use parking_lot::{Mutex, RwLock};
 
+use crate::application::client_sessions::ClientSessionManager;
+use crate::application::search_concurrency::ConcurrentSearchManager;
 use crate::application::search_session::SearchSessionManager;
 use crate::application::workspace_service::WorkspaceServiceRef;
 use crate::infrastructure::TaskManagerAdapter;
-use crate::state_sync::StateSync;
+use crate::monitoring::{CacheSizer, ErrorReportStore, ResourceGate};
+use crate::services::chunked_upload::UploadManager;
+use crate::services::external_links::ExternalLinkRegistry;
+use crate::services::translation::TranslationRegistry;
+use crate::services::watcher_budget::WatcherBudget;
+use crate::state_sync::{StateSync, WorkspaceLeases};
 use crate::task_manager::TaskManager;
 use la_core::domain::TaskScheduler;
 use la_search::DiskResultStore;
@@ -25,6 +32,8 @@ use la_search::DiskResultStore;
 #[derive(Default)]
 pub struct WorkspaceRegistry {
     services: Arc<Mutex<HashMap<String, WorkspaceServiceRef>>>,
+    watcher_budget: Arc<WatcherBudget>,
+    uploads: RwLock<Option<Arc<UploadManager>>>,
 }
 
 impl WorkspaceRegistry {
@@ -43,12 +52,24 @@ impl WorkspaceRegistry {
     pub fn ids(&self) -> Vec<String> {
         self.services.lock().keys().cloned().collect()
     }
+    pub fn watcher_budget(&self) -> Arc<WatcherBudget> {
+        Arc::clone(&self.watcher_budget)
+    }
+    pub fn init_uploads(&self, manager: UploadManager) {
+        *self.uploads.write() = Some(Arc::new(manager));
+    }
+    pub fn uploads(&self) -> Option<Arc<UploadManager>> {
+        self.uploads.read().clone()
+    }
 }
 
 pub struct SearchRegistry {
     disk_result_store: RwLock<Option<Arc<DiskResultStore>>>,
     search_session_manager: RwLock<Option<SearchSessionManager>>,
     thread_pool: Arc<rayon::ThreadPool>,
+    concurrency: ConcurrentSearchManager,
+    links: Arc<ExternalLinkRegistry>,
+    translation: Arc<TranslationRegistry>,
 }
 
 impl Default for SearchRegistry {
@@ -62,6 +83,9 @@ impl Default for SearchRegistry {
                     .build()
                     .expect("Failed to create search thread pool"),
             ),
+            concurrency: ConcurrentSearchManager::default(),
+            links: Arc::default(),
+            translation: Arc::default(),
         }
     }
 }
@@ -96,6 +120,15 @@ impl SearchRegistry {
     pub fn get_thread_pool(&self) -> Arc<rayon::ThreadPool> {
         Arc::clone(&self.thread_pool)
     }
+    pub fn concurrency(&self) -> ConcurrentSearchManager {
+        self.concurrency.clone()
+    }
+    pub fn links(&self) -> Arc<ExternalLinkRegistry> {
+        Arc::clone(&self.links)
+    }
+    pub fn translation(&self) -> Arc<TranslationRegistry> {
+        Arc::clone(&self.translation)
+    }
     pub fn cleanup_disk_result_store(&self) {
         if let Some(store) = self.disk_result_store.read().as_ref() {
             store.cleanup_all();
@@ -128,6 +161,8 @@ impl TaskRegistry {
 #[derive(Default)]
 pub struct SyncRegistry {
     sync: Arc<Mutex<Option<StateSync>>>,
+    leases: Arc<WorkspaceLeases>,
+    clients: Arc<ClientSessionManager>,
 }
 
 impl SyncRegistry {
@@ -140,6 +175,34 @@ impl SyncRegistry {
     pub fn arc(&self) -> Arc<Mutex<Option<StateSync>>> {
         Arc::clone(&self.sync)
     }
+    pub fn leases(&self) -> Arc<WorkspaceLeases> {
+        Arc::clone(&self.leases)
+    }
+    pub fn clients(&self) -> Arc<ClientSessionManager> {
+        Arc::clone(&self.clients)
+    }
+}
+
+#[derive(Default)]
+pub struct MonitoringRegistry {
+    error_reports: RwLock<Option<Arc<ErrorReportStore>>>,
+    resource_gate: Arc<ResourceGate>,
+    cache_sizer: Arc<CacheSizer>,
+}
+
+impl MonitoringRegistry {
+    pub fn init_error_reports(&self, store: ErrorReportStore) {
+        *self.error_reports.write() = Some(Arc::new(store));
+    }
+    pub fn error_reports(&self) -> Option<Arc<ErrorReportStore>> {
+        self.error_reports.read().clone()
+    }
+    pub fn resource_gate(&self) -> Arc<ResourceGate> {
+        Arc::clone(&self.resource_gate)
+    }
+    pub fn cache_sizer(&self) -> Arc<CacheSizer> {
+        Arc::clone(&self.cache_sizer)
+    }
 }
 
 // ============================================================================
@@ -151,6 +214,7 @@ pub struct AppState {
     pub search: SearchRegistry,
     pub task: TaskRegistry,
     pub sync: SyncRegistry,
+    pub monitoring: MonitoringRegistry,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -161,6 +225,7 @@ impl Default for AppState {
             search: SearchRegistry::default(),
             task: TaskRegistry::default(),
             sync: SyncRegistry::default(),
+            monitoring: MonitoringRegistry::default(),
         }
     }
 }
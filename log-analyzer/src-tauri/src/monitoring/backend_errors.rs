@@ -0,0 +1,158 @@
+//! 后端错误捕获
+//!
+//! `BackendErrorLayer` 挂在 tracing 订阅器上，把 ERROR 级别事件写入一个
+//! 有界的内存环形缓冲区。前端错误上报时按时间窗口从中取出并发生的后端错误，
+//! 写入出现记录，便于在诊断页面关联前后端问题。
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// 环形缓冲区容量（超过后丢弃最旧的记录）
+const MAX_BACKEND_ERRORS: usize = 512;
+
+static BACKEND_ERRORS: Lazy<Mutex<VecDeque<BackendErrorRecord>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_BACKEND_ERRORS)));
+
+/// 一条被捕获的后端错误
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendErrorRecord {
+    /// 发生时间（Unix 毫秒）
+    pub timestamp_ms: i64,
+    /// tracing target（通常为模块路径）
+    pub target: String,
+    /// 事件消息及字段
+    pub message: String,
+}
+
+/// 记录一条后端错误（供 Layer 与测试使用）
+pub fn record_backend_error(record: BackendErrorRecord) {
+    let mut buffer = BACKEND_ERRORS.lock();
+    if buffer.len() >= MAX_BACKEND_ERRORS {
+        buffer.pop_front();
+    }
+    buffer.push_back(record);
+}
+
+/// 缓冲区的估算占用：(记录数, 字节)
+pub fn backend_errors_memory() -> (u64, u64) {
+    let buffer = BACKEND_ERRORS.lock();
+    let text: usize = buffer
+        .iter()
+        .map(|r| r.target.capacity() + r.message.capacity())
+        .sum();
+    let slots = buffer.capacity() * std::mem::size_of::<BackendErrorRecord>();
+    (buffer.len() as u64, (slots + text) as u64)
+}
+
+/// 返回 `[center_ms - window_ms, center_ms + window_ms]` 区间内的后端错误
+pub fn backend_errors_around(center_ms: i64, window_ms: i64) -> Vec<BackendErrorRecord> {
+    let start = center_ms.saturating_sub(window_ms);
+    let end = center_ms.saturating_add(window_ms);
+    BACKEND_ERRORS
+        .lock()
+        .iter()
+        .filter(|r| r.timestamp_ms >= start && r.timestamp_ms <= end)
+        .cloned()
+        .collect()
+}
+
+/// 捕获 ERROR 级别事件的 tracing Layer
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BackendErrorLayer;
+
+impl BackendErrorLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S: Subscriber> Layer<S> for BackendErrorLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if *metadata.level() != Level::ERROR {
+            return;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        record_backend_error(BackendErrorRecord {
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            target: metadata.target().to_string(),
+            message: visitor.finish(),
+        });
+    }
+}
+
+/// 把 `message` 字段放在最前，其余字段以 `key=value` 追加
+#[derive(Default)]
+pub(super) struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    pub(super) fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else if self.message.is_empty() {
+            self.fields
+        } else {
+            format!("{} {}", self.message, self.fields)
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            if !self.fields.is_empty() {
+                self.fields.push(' ');
+            }
+            let _ = write!(self.fields, "{}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_errors_around_filters_by_window() {
+        let base = 9_000_000_000_000;
+        record_backend_error(BackendErrorRecord {
+            timestamp_ms: base,
+            target: "test".to_string(),
+            message: "inside".to_string(),
+        });
+        record_backend_error(BackendErrorRecord {
+            timestamp_ms: base + 60_000,
+            target: "test".to_string(),
+            message: "outside".to_string(),
+        });
+
+        let found = backend_errors_around(base + 1_000, 5_000);
+        assert!(found.iter().any(|r| r.message == "inside"));
+        assert!(!found.iter().any(|r| r.message == "outside"));
+    }
+}
@@ -0,0 +1,212 @@
+//! 后端日志环形缓冲区
+//!
+//! `BackendLogLayer` 挂在 tracing 订阅器上，把通过全局过滤器的事件（不限级别）
+//! 写入有界的内存环形缓冲区。用户提交问题时可以直接在应用内查看或导出最近的
+//! 后端日志，无需到控制台查找输出。
+
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use super::backend_errors::MessageVisitor;
+
+/// 环形缓冲区容量（超过后丢弃最旧的记录）
+pub const MAX_BACKEND_LOGS: usize = 5_000;
+
+static BACKEND_LOGS: Lazy<Mutex<VecDeque<BackendLogRecord>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_BACKEND_LOGS)));
+
+/// 单调递增的记录序号，供前端增量拉取
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(1);
+
+/// 一条后端日志
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendLogRecord {
+    pub seq: u64,
+    /// 发生时间（Unix 毫秒）
+    pub timestamp_ms: i64,
+    /// `ERROR` / `WARN` / `INFO` / `DEBUG` / `TRACE`
+    pub level: String,
+    /// tracing target（通常为模块路径）
+    pub target: String,
+    /// 事件消息及字段
+    pub message: String,
+}
+
+impl BackendLogRecord {
+    /// 单行文本形式，用于导出
+    pub fn to_line(&self) -> String {
+        let time = chrono::DateTime::from_timestamp_millis(self.timestamp_ms)
+            .map(|t| t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+            .unwrap_or_else(|| self.timestamp_ms.to_string());
+        format!("{time} {:>5} {}: {}", self.level, self.target, self.message)
+    }
+}
+
+/// `get_backend_logs` 的过滤条件，均为可选
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendLogFilter {
+    /// 最低级别（如 `warn` 返回 WARN 与 ERROR）
+    #[serde(default)]
+    pub min_level: Option<String>,
+    /// target 前缀（如 `log_analyzer::commands`）
+    #[serde(default)]
+    pub target: Option<String>,
+    /// 消息包含的文本（不区分大小写）
+    #[serde(default)]
+    pub contains: Option<String>,
+    /// 只返回序号大于该值的记录（增量拉取）
+    #[serde(default)]
+    pub after_seq: Option<u64>,
+    /// 最多返回最近的多少条
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// 记录一条后端日志（供 Layer 与测试使用）
+pub fn record_backend_log(level: &Level, target: &str, message: String) {
+    let record = BackendLogRecord {
+        seq: NEXT_SEQ.fetch_add(1, Ordering::Relaxed),
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message,
+    };
+    let mut buffer = BACKEND_LOGS.lock();
+    if buffer.len() >= MAX_BACKEND_LOGS {
+        buffer.pop_front();
+    }
+    buffer.push_back(record);
+}
+
+/// 缓冲区的估算占用：(记录数, 字节)
+pub fn backend_logs_memory() -> (u64, u64) {
+    let buffer = BACKEND_LOGS.lock();
+    let text: usize = buffer
+        .iter()
+        .map(|r| r.level.capacity() + r.target.capacity() + r.message.capacity())
+        .sum();
+    let slots = buffer.capacity() * std::mem::size_of::<BackendLogRecord>();
+    (buffer.len() as u64, (slots + text) as u64)
+}
+
+/// 按过滤条件返回缓冲区中的日志（按时间顺序，`limit` 取最近的部分）
+pub fn backend_logs(filter: &BackendLogFilter) -> Result<Vec<BackendLogRecord>, String> {
+    let min_level = filter
+        .min_level
+        .as_deref()
+        .map(|l| Level::from_str(l).map_err(|_| format!("Invalid log level: {l}")))
+        .transpose()?;
+    let contains = filter.contains.as_deref().map(str::to_lowercase);
+
+    let buffer = BACKEND_LOGS.lock();
+    let mut records: Vec<BackendLogRecord> = buffer
+        .iter()
+        .filter(|r| filter.after_seq.is_none_or(|seq| r.seq > seq))
+        .filter(|r| {
+            // tracing 中越详细的级别越“大”：TRACE > DEBUG > INFO > WARN > ERROR
+            min_level.is_none_or(|min| Level::from_str(&r.level).is_ok_and(|level| level <= min))
+        })
+        .filter(|r| {
+            filter
+                .target
+                .as_deref()
+                .is_none_or(|prefix| r.target.starts_with(prefix))
+        })
+        .filter(|r| {
+            contains
+                .as_deref()
+                .is_none_or(|needle| r.message.to_lowercase().contains(needle))
+        })
+        .cloned()
+        .collect();
+    drop(buffer);
+
+    if let Some(limit) = filter.limit {
+        let skip = records.len().saturating_sub(limit);
+        records.drain(..skip);
+    }
+    Ok(records)
+}
+
+/// 记录全部事件的 tracing Layer（级别由订阅器的全局过滤器决定）
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BackendLogLayer;
+
+impl BackendLogLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S: Subscriber> Layer<S> for BackendLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        record_backend_log(metadata.level(), metadata.target(), visitor.finish());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_logs_filters_by_level_target_and_text() {
+        let target = "backend_logs_test::filters";
+        record_backend_log(&Level::DEBUG, target, "cache warmed".to_string());
+        record_backend_log(&Level::WARN, target, "Slow QUERY took 3s".to_string());
+        record_backend_log(&Level::ERROR, "elsewhere", "slow query failed".to_string());
+
+        let scoped = |filter: BackendLogFilter| {
+            backend_logs(&BackendLogFilter {
+                target: Some(target.to_string()),
+                ..filter
+            })
+            .unwrap()
+        };
+
+        let warnings = scoped(BackendLogFilter {
+            min_level: Some("warn".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].level, "WARN");
+
+        let matching = scoped(BackendLogFilter {
+            contains: Some("slow query".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(matching.len(), 1);
+
+        let all = scoped(BackendLogFilter::default());
+        let newer = scoped(BackendLogFilter {
+            after_seq: Some(all[0].seq),
+            ..Default::default()
+        });
+        assert_eq!(newer.len(), all.len() - 1);
+        assert_eq!(
+            scoped(BackendLogFilter {
+                limit: Some(1),
+                ..Default::default()
+            }),
+            vec![all.last().unwrap().clone()]
+        );
+
+        assert!(backend_logs(&BackendLogFilter {
+            min_level: Some("loud".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! 自适应缓存容量
+//!
+//! 各工作区的正则引擎缓存（L1）不再使用固定条目数，而是按可用内存与实测的单条平均大小
+//! 确定容量：启动时评估一次，之后按 `cache.reevaluate_interval_secs` 周期重新评估，
+//! 小内存笔记本与大内存工作站都能得到合适的容量。计算规则见 [`CacheConfig`]。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use la_core::models::config::CacheConfig;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use super::resource_monitor::available_memory_bytes;
+use crate::models::AppState;
+
+/// 缓存中尚无条目时使用的单条大小估值
+const DEFAULT_ENTRY_BYTES: u64 = 16 * 1024;
+
+/// 新容量与当前容量相差不超过该比例时不调整，避免反复重建缓存
+const RESIZE_TOLERANCE_PERCENT: usize = 20;
+
+/// 按内存预算计算单个缓存的容量
+pub fn compute_capacity(
+    config: &CacheConfig,
+    available_memory: u64,
+    caches: usize,
+    average_entry_bytes: u64,
+) -> usize {
+    let budget = available_memory / 100 * config.memory_percent;
+    let per_cache = budget / caches.max(1) as u64;
+    let entries = per_cache / average_entry_bytes.max(1);
+    usize::try_from(entries)
+        .unwrap_or(usize::MAX)
+        .clamp(config.min_entries, config.max_entries)
+}
+
+/// 由各缓存的 (条目数, 字节) 计算单条平均大小；全部为空时使用默认估值
+pub fn average_entry_bytes(usages: impl IntoIterator<Item = (u64, u64)>) -> u64 {
+    let (entries, bytes) = usages
+        .into_iter()
+        .fold((0u64, 0u64), |(e, b), (entries, bytes)| {
+            (e + entries, b + bytes)
+        });
+    bytes
+        .checked_div(entries)
+        .map_or(DEFAULT_ENTRY_BYTES, |average| average.max(1))
+}
+
+/// 当前生效的缓存容量（新建工作区时使用）；尚未评估时为 `None`
+#[derive(Debug, Default)]
+pub struct CacheSizer {
+    capacity: AtomicUsize,
+}
+
+impl CacheSizer {
+    pub fn capacity(&self) -> Option<usize> {
+        match self.capacity.load(Ordering::Acquire) {
+            0 => None,
+            capacity => Some(capacity),
+        }
+    }
+
+    /// 记录新的容量；首次评估或与当前容量相差超过容差时返回 `true`
+    pub fn update(&self, capacity: usize) -> bool {
+        let current = self.capacity.load(Ordering::Acquire);
+        let tolerance = current * RESIZE_TOLERANCE_PERCENT / 100;
+        if current != 0 && capacity.abs_diff(current) <= tolerance {
+            return false;
+        }
+        self.capacity.store(capacity, Ordering::Release);
+        true
+    }
+}
+
+/// 启动后台缓存容量评估循环；关闭自适应时不启动
+pub fn spawn_cache_sizer(app: AppHandle, config: CacheConfig) {
+    if !config.adaptive {
+        return;
+    }
+    let interval = Duration::from_secs(config.reevaluate_interval_secs.max(1));
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            let Ok(Some(available)) = tokio::task::spawn_blocking(available_memory_bytes).await
+            else {
+                continue;
+            };
+
+            let state = app.state::<AppState>();
+            let services = state.all_workspace_services();
+            let average =
+                average_entry_bytes(services.iter().map(|service| service.engine_cache_usage()));
+            let capacity = compute_capacity(&config, available, services.len(), average);
+            if !state.monitoring.cache_sizer().update(capacity) {
+                continue;
+            }
+
+            for service in &services {
+                service.resize_caches(capacity);
+            }
+            info!(
+                capacity,
+                workspaces = services.len(),
+                average_entry_bytes = average,
+                available_memory_mb = available / (1024 * 1024),
+                "Resized regex engine caches"
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_capacity_scales_with_memory_within_bounds() {
+        let config = CacheConfig::default();
+
+        // 2% × 2 GB ÷ 16 KiB ≈ 2621 条
+        let laptop = compute_capacity(&config, 2 * GB, 1, DEFAULT_ENTRY_BYTES);
+        assert_eq!(laptop, 2621);
+        // 预算在工作区间平分
+        assert_eq!(
+            compute_capacity(&config, 2 * GB, 2, DEFAULT_ENTRY_BYTES),
+            1310
+        );
+        // 大内存机器受 max_entries 限制，极小内存受 min_entries 限制
+        assert_eq!(
+            compute_capacity(&config, 128 * GB, 1, DEFAULT_ENTRY_BYTES),
+            config.max_entries
+        );
+        assert_eq!(
+            compute_capacity(&config, 64 * 1024 * 1024, 4, DEFAULT_ENTRY_BYTES),
+            config.min_entries
+        );
+    }
+
+    #[test]
+    fn test_average_entry_bytes_falls_back_when_empty() {
+        assert_eq!(average_entry_bytes([]), DEFAULT_ENTRY_BYTES);
+        assert_eq!(average_entry_bytes([(0, 0), (4, 4096), (4, 12_288)]), 2048);
+    }
+
+    #[test]
+    fn test_sizer_ignores_small_changes() {
+        let sizer = CacheSizer::default();
+        assert_eq!(sizer.capacity(), None);
+        assert!(sizer.update(1000));
+        assert!(!sizer.update(1150));
+        assert_eq!(sizer.capacity(), Some(1000));
+        assert!(sizer.update(700));
+        assert_eq!(sizer.capacity(), Some(700));
+    }
+}
@@ -0,0 +1,240 @@
+//! 崩溃报告
+//!
+//! `install_panic_hook` 在原有 panic hook（含 Sentry）之前把结构化的崩溃包写入
+//! 应用数据目录的 `crash-reports/`：panic 消息与位置、backtrace、环形缓冲区中最近的
+//! 后端日志，以及由调用方提供的运行时上下文（进行中的操作、工作区 ID 等）。
+//! 下次启动时前端通过 `list_crash_reports` 列出并提示用户查看或发送。
+//!
+//! 只覆盖 Rust panic；进程被信号终止等原生崩溃不会生成报告。
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::backend_logs::{backend_logs, BackendLogFilter};
+use super::BackendLogRecord;
+
+/// 崩溃报告目录（相对应用数据目录）
+pub const CRASH_REPORT_DIR: &str = "crash-reports";
+
+/// 最多保留的崩溃报告数，超出时删除最旧的
+const MAX_CRASH_REPORTS: usize = 20;
+
+/// 崩溃包中附带的最近后端日志条数
+const RECENT_LOG_LIMIT: usize = 500;
+
+/// 收集日志与上下文的最长等待时间。panic 可能发生在持有状态锁时，
+/// 在独立线程中收集并限时，避免 hook 自身死锁
+const CONTEXT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 运行时上下文提供者（在 panic 时调用）
+pub type CrashContextProvider = dyn Fn() -> serde_json::Value + Send + Sync;
+
+/// 一份崩溃报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    /// 发生时间（Unix 毫秒）
+    pub timestamp_ms: i64,
+    pub app_version: String,
+    pub os: String,
+    pub thread: String,
+    pub message: String,
+    /// `file:line:column`
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub recent_logs: Vec<BackendLogRecord>,
+    /// 调用方提供的上下文；收集超时或失败时为 `null`
+    pub context: serde_json::Value,
+}
+
+/// `list_crash_reports` 返回的摘要
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub timestamp_ms: i64,
+    pub message: String,
+    pub path: String,
+}
+
+/// 安装 panic hook，保留之前的 hook 并在写入崩溃包后调用
+pub fn install_panic_hook(crash_dir: PathBuf, context: Box<CrashContextProvider>) {
+    let context: std::sync::Arc<CrashContextProvider> = context.into();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = build_report(info, context.clone());
+        if let Err(e) = write_crash_report(&crash_dir, &report) {
+            eprintln!("Failed to write crash report: {e}");
+        }
+        previous(info);
+    }));
+}
+
+fn build_report(
+    info: &std::panic::PanicHookInfo<'_>,
+    context: std::sync::Arc<CrashContextProvider>,
+) -> CrashReport {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string());
+    let timestamp_ms = chrono::Utc::now().timestamp_millis();
+
+    let (tx, rx) = mpsc::channel();
+    let _ = std::thread::Builder::new()
+        .name("crash-context".to_string())
+        .spawn(move || {
+            let logs = backend_logs(&BackendLogFilter {
+                limit: Some(RECENT_LOG_LIMIT),
+                ..Default::default()
+            })
+            .unwrap_or_default();
+            let _ = tx.send((logs, context()));
+        });
+    let (recent_logs, context) = rx
+        .recv_timeout(CONTEXT_TIMEOUT)
+        .unwrap_or((Vec::new(), serde_json::Value::Null));
+
+    CrashReport {
+        id: format!("crash-{timestamp_ms}-{}", std::process::id()),
+        timestamp_ms,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("<unnamed>")
+            .to_string(),
+        message,
+        location: info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        recent_logs,
+        context,
+    }
+}
+
+/// 写入崩溃报告并清理超出保留数量的旧报告
+pub fn write_crash_report(crash_dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(crash_dir)?;
+    let path = crash_dir.join(format!("{}.json", report.id));
+    let json = serde_json::to_vec_pretty(report).map_err(std::io::Error::other)?;
+    std::fs::write(&path, json)?;
+
+    let mut ids = report_ids(crash_dir)?;
+    if ids.len() > MAX_CRASH_REPORTS {
+        ids.sort_by_key(|(_, timestamp)| *timestamp);
+        for (id, _) in &ids[..ids.len() - MAX_CRASH_REPORTS] {
+            let _ = std::fs::remove_file(crash_dir.join(format!("{id}.json")));
+        }
+    }
+    Ok(path)
+}
+
+/// 列出崩溃报告（新的在前）；无法解析的文件跳过
+pub fn list_crash_reports(crash_dir: &Path) -> std::io::Result<Vec<CrashReportSummary>> {
+    if !crash_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reports: Vec<CrashReportSummary> = report_ids(crash_dir)?
+        .into_iter()
+        .filter_map(|(id, _)| {
+            let path = crash_dir.join(format!("{id}.json"));
+            let report: CrashReport = serde_json::from_slice(&std::fs::read(&path).ok()?).ok()?;
+            Some(CrashReportSummary {
+                id: report.id,
+                timestamp_ms: report.timestamp_ms,
+                message: report.message,
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.timestamp_ms));
+    Ok(reports)
+}
+
+/// 删除指定的崩溃报告（用户查看或发送后），返回实际删除的数量
+pub fn delete_crash_reports(crash_dir: &Path, ids: &[String]) -> Result<usize, String> {
+    let mut deleted = 0;
+    for id in ids {
+        if parse_report_id(id).is_none() {
+            return Err(format!("Invalid crash report id: {id}"));
+        }
+        match std::fs::remove_file(crash_dir.join(format!("{id}.json"))) {
+            Ok(()) => deleted += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to delete crash report {id}: {e}")),
+        }
+    }
+    Ok(deleted)
+}
+
+/// 目录中的报告 ID 及其时间戳
+fn report_ids(crash_dir: &Path) -> std::io::Result<Vec<(String, i64)>> {
+    let mut ids = Vec::new();
+    for entry in std::fs::read_dir(crash_dir)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        if let Some(id) = name.strip_suffix(".json") {
+            if let Some(timestamp) = parse_report_id(id) {
+                ids.push((id.to_string(), timestamp));
+            }
+        }
+    }
+    Ok(ids)
+}
+
+/// `crash-<timestamp_ms>-<pid>`，返回时间戳；其他格式（含路径分隔符）返回 `None`
+fn parse_report_id(id: &str) -> Option<i64> {
+    let (timestamp, pid) = id.strip_prefix("crash-")?.split_once('-')?;
+    pid.parse::<u32>().ok()?;
+    timestamp.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(timestamp_ms: i64) -> CrashReport {
+        CrashReport {
+            id: format!("crash-{timestamp_ms}-42"),
+            timestamp_ms,
+            app_version: "0.0.0".to_string(),
+            os: "test".to_string(),
+            thread: "main".to_string(),
+            message: format!("boom {timestamp_ms}"),
+            location: Some("src/main.rs:1:1".to_string()),
+            backtrace: String::new(),
+            recent_logs: Vec::new(),
+            context: serde_json::json!({ "workspaceIds": ["ws-1"] }),
+        }
+    }
+
+    #[test]
+    fn test_crash_reports_are_listed_newest_first_and_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        for timestamp in 0..(MAX_CRASH_REPORTS as i64 + 2) {
+            write_crash_report(dir.path(), &report(timestamp)).unwrap();
+        }
+        std::fs::write(dir.path().join("notes.json"), "{}").unwrap();
+
+        let listed = list_crash_reports(dir.path()).unwrap();
+        assert_eq!(listed.len(), MAX_CRASH_REPORTS);
+        assert_eq!(listed[0].timestamp_ms, MAX_CRASH_REPORTS as i64 + 1);
+        assert_eq!(listed.last().unwrap().timestamp_ms, 2);
+
+        let deleted =
+            delete_crash_reports(dir.path(), &[listed[0].id.clone(), "crash-1-42".into()]).unwrap();
+        assert_eq!(deleted, 1);
+        assert!(delete_crash_reports(dir.path(), &["../secrets".into()]).is_err());
+        assert_eq!(
+            list_crash_reports(dir.path()).unwrap().len(),
+            MAX_CRASH_REPORTS - 1
+        );
+    }
+}
@@ -0,0 +1,586 @@
+//! 前端错误上报存储
+//!
+//! 前端上报的错误按指纹（错误类型 + 归一化消息 + 归一化栈顶帧）去重：
+//! - `error_groups` 每个指纹一行，维护首次/最近出现时间与累计次数
+//! - `error_occurrences` 保存每次出现的栈、面包屑与时间窗口内关联的后端错误
+//!
+//! 打包后的前端资源文件名带有构建哈希（如 `index-3f2a9c1b.js`），行列号也会
+//! 随构建变化。归一化时去掉这些易变部分，保证同一处错误跨版本得到相同指纹。
+
+use std::path::Path;
+use std::time::Duration;
+
+use la_core::error::{AppError, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Row, SqlitePool};
+use tracing::{debug, info};
+
+use super::backend_errors::{backend_errors_around, BackendErrorRecord};
+
+/// 每个错误组保留的最大出现记录数（超出后删除最旧的）
+const MAX_OCCURRENCES_PER_GROUP: i64 = 50;
+
+/// `get_error_groups` 中每组附带的最近出现记录数
+const RECENT_OCCURRENCES_PER_GROUP: i64 = 5;
+
+/// 每次出现最多保存的面包屑数量
+const MAX_BREADCRUMBS: usize = 100;
+
+/// 参与指纹计算的栈顶帧数量
+const FINGERPRINT_FRAMES: usize = 3;
+
+/// 前端错误与后端错误关联的时间窗口（毫秒）
+pub const CORRELATION_WINDOW_MS: i64 = 5_000;
+
+static BUILD_HASH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[-.][0-9a-zA-Z_]{8,}\.(js|mjs|css)").unwrap());
+static LINE_COL_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r":\d+(:\d+)?").unwrap());
+static QUERY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\?[^\s):]*").unwrap());
+static ORIGIN_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(https?|tauri|asset)://[^/\s)]+").unwrap());
+static UUID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}")
+        .unwrap()
+});
+static HEX_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(0x)?[0-9a-fA-F]{12,}\b").unwrap());
+static NUMBER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// 前端操作面包屑
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Breadcrumb {
+    pub timestamp_ms: i64,
+    pub category: String,
+    pub message: String,
+}
+
+/// 前端上报的错误
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontendErrorReport {
+    pub message: String,
+    /// 错误类型（如 `TypeError`），缺省为 `Error`
+    #[serde(default)]
+    pub error_type: Option<String>,
+    #[serde(default)]
+    pub stack: Option<String>,
+    /// React ErrorBoundary 提供的组件栈
+    #[serde(default)]
+    pub component_stack: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub workspace_id: Option<String>,
+    /// 错误发生时间（Unix 毫秒），缺省为接收时间
+    #[serde(default)]
+    pub timestamp_ms: Option<i64>,
+    #[serde(default)]
+    pub breadcrumbs: Vec<Breadcrumb>,
+    /// 任意附加上下文
+    #[serde(default)]
+    pub context: Option<serde_json::Value>,
+}
+
+/// 单次错误出现记录
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorOccurrence {
+    pub id: i64,
+    pub occurred_at_ms: i64,
+    pub stack: Option<String>,
+    pub component_stack: Option<String>,
+    pub url: Option<String>,
+    pub user_agent: Option<String>,
+    pub workspace_id: Option<String>,
+    pub breadcrumbs: Vec<Breadcrumb>,
+    pub context: Option<serde_json::Value>,
+    pub correlated_backend_errors: Vec<BackendErrorRecord>,
+}
+
+/// 按指纹聚合的错误组
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorGroup {
+    pub fingerprint: String,
+    pub error_type: String,
+    pub message: String,
+    pub first_seen_ms: i64,
+    pub last_seen_ms: i64,
+    pub occurrence_count: i64,
+    pub recent_occurrences: Vec<ErrorOccurrence>,
+}
+
+/// 上报结果
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorReportOutcome {
+    pub fingerprint: String,
+    pub occurrence_count: i64,
+    /// 是否为首次出现的新错误组
+    pub is_new_group: bool,
+    pub correlated_backend_errors: usize,
+}
+
+/// 归一化错误消息：去除 UUID、长十六进制串与数字
+pub fn normalize_message(message: &str) -> String {
+    let s = UUID_RE.replace_all(message.trim(), "<uuid>");
+    let s = HEX_RE.replace_all(&s, "<hex>");
+    NUMBER_RE.replace_all(&s, "<n>").into_owned()
+}
+
+/// 归一化单个栈帧：去除源地址、查询串、构建哈希与行列号
+pub fn normalize_frame(frame: &str) -> String {
+    let s = ORIGIN_RE.replace_all(frame.trim(), "");
+    let s = QUERY_RE.replace_all(&s, "");
+    let s = BUILD_HASH_RE.replace_all(&s, ".$1");
+    LINE_COL_RE.replace_all(&s, "").into_owned()
+}
+
+/// 计算错误指纹
+pub fn compute_fingerprint(error_type: &str, message: &str, stack: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(error_type.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(normalize_message(message).as_bytes());
+
+    if let Some(stack) = stack {
+        // 只取栈帧行（首行通常是 "TypeError: message"，已参与计算）
+        let frames = stack
+            .lines()
+            .map(str::trim)
+            .filter(|l| l.starts_with("at ") || l.contains('@'))
+            .take(FINGERPRINT_FRAMES);
+        for frame in frames {
+            hasher.update(b"\n");
+            hasher.update(normalize_frame(frame).as_bytes());
+        }
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// 前端错误上报存储（应用数据目录下的 `diagnostics.db`）
+pub struct ErrorReportStore {
+    pool: SqlitePool,
+}
+
+impl ErrorReportStore {
+    /// 在 `data_dir/diagnostics.db` 打开（或创建）存储
+    pub async fn new(data_dir: &Path) -> Result<Self> {
+        tokio::fs::create_dir_all(data_dir).await.map_err(|e| {
+            AppError::io_error(
+                format!("Failed to create diagnostics directory: {e}"),
+                Some(data_dir.to_path_buf()),
+            )
+        })?;
+
+        let db_path = data_dir.join("diagnostics.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let pool: SqlitePool = SqlitePoolOptions::new()
+            .min_connections(1)
+            .max_connections(2)
+            .acquire_timeout(Duration::from_secs(10))
+            .connect(&db_url)
+            .await
+            .map_err(|e| {
+                AppError::database_error(format!("Failed to connect to diagnostics database: {e}"))
+            })?;
+
+        for pragma in &["PRAGMA journal_mode = WAL", "PRAGMA busy_timeout = 5000"] {
+            sqlx::query(pragma).execute(&pool).await.map_err(|e| {
+                AppError::database_error(format!("Failed to set PRAGMA '{pragma}': {e}"))
+            })?;
+        }
+
+        init_schema(&pool).await?;
+        info!(path = %db_path.display(), "Error report store initialized");
+
+        Ok(Self { pool })
+    }
+
+    /// 记录一次前端错误，返回所属错误组信息
+    pub async fn record(&self, report: &FrontendErrorReport) -> Result<ErrorReportOutcome> {
+        if report.message.trim().is_empty() {
+            return Err(AppError::validation_error("Error message cannot be empty"));
+        }
+
+        let error_type = report
+            .error_type
+            .as_deref()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or("Error");
+        let fingerprint = compute_fingerprint(error_type, &report.message, report.stack.as_deref());
+        let occurred_at = report
+            .timestamp_ms
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+
+        let correlated = backend_errors_around(occurred_at, CORRELATION_WINDOW_MS);
+        let breadcrumbs: Vec<&Breadcrumb> = report
+            .breadcrumbs
+            .iter()
+            .rev()
+            .take(MAX_BREADCRUMBS)
+            .rev()
+            .collect();
+
+        let breadcrumbs_json = serde_json::to_string(&breadcrumbs)
+            .map_err(|e| AppError::validation_error(format!("Invalid breadcrumbs: {e}")))?;
+        let correlated_json = serde_json::to_string(&correlated).map_err(|e| {
+            AppError::validation_error(format!("Failed to serialize backend errors: {e}"))
+        })?;
+        let context_json = report.context.as_ref().map(serde_json::Value::to_string);
+
+        let mut tx =
+            self.pool.begin().await.map_err(|e| {
+                AppError::database_error(format!("Failed to begin transaction: {e}"))
+            })?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO error_groups (
+                fingerprint, error_type, message, first_seen, last_seen, occurrence_count
+            ) VALUES (?, ?, ?, ?, ?, 0)
+            "#,
+        )
+        .bind(&fingerprint)
+        .bind(error_type)
+        .bind(report.message.trim())
+        .bind(occurred_at)
+        .bind(occurred_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to insert error group: {e}")))?
+        .rows_affected();
+
+        sqlx::query(
+            r#"
+            UPDATE error_groups
+            SET occurrence_count = occurrence_count + 1,
+                first_seen = MIN(first_seen, ?),
+                last_seen = MAX(last_seen, ?)
+            WHERE fingerprint = ?
+            "#,
+        )
+        .bind(occurred_at)
+        .bind(occurred_at)
+        .bind(&fingerprint)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to update error group: {e}")))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO error_occurrences (
+                fingerprint, occurred_at, stack, component_stack, url, user_agent,
+                workspace_id, breadcrumbs, context, correlated_backend_errors
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&fingerprint)
+        .bind(occurred_at)
+        .bind(&report.stack)
+        .bind(&report.component_stack)
+        .bind(&report.url)
+        .bind(&report.user_agent)
+        .bind(&report.workspace_id)
+        .bind(&breadcrumbs_json)
+        .bind(&context_json)
+        .bind(&correlated_json)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to insert error occurrence: {e}")))?;
+
+        // 只保留最近 MAX_OCCURRENCES_PER_GROUP 条出现记录
+        sqlx::query(
+            r#"
+            DELETE FROM error_occurrences
+            WHERE fingerprint = ?
+              AND id NOT IN (
+                SELECT id FROM error_occurrences
+                WHERE fingerprint = ?
+                ORDER BY occurred_at DESC, id DESC
+                LIMIT ?
+              )
+            "#,
+        )
+        .bind(&fingerprint)
+        .bind(&fingerprint)
+        .bind(MAX_OCCURRENCES_PER_GROUP)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to prune error occurrences: {e}")))?;
+
+        let occurrence_count: i64 =
+            sqlx::query_scalar("SELECT occurrence_count FROM error_groups WHERE fingerprint = ?")
+                .bind(&fingerprint)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    AppError::database_error(format!("Failed to read occurrence count: {e}"))
+                })?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to commit transaction: {e}")))?;
+
+        debug!(
+            fingerprint = %fingerprint,
+            occurrence_count,
+            correlated = correlated.len(),
+            "Frontend error recorded"
+        );
+
+        Ok(ErrorReportOutcome {
+            fingerprint,
+            occurrence_count,
+            is_new_group: inserted > 0,
+            correlated_backend_errors: correlated.len(),
+        })
+    }
+
+    /// 按最近出现时间倒序列出错误组
+    pub async fn get_error_groups(&self, limit: u32) -> Result<Vec<ErrorGroup>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT fingerprint, error_type, message, first_seen, last_seen, occurrence_count
+            FROM error_groups
+            ORDER BY last_seen DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to query error groups: {e}")))?;
+
+        let mut groups = Vec::with_capacity(rows.len());
+        for row in rows {
+            let fingerprint: String = row.get("fingerprint");
+            let recent_occurrences = self
+                .get_occurrences(&fingerprint, RECENT_OCCURRENCES_PER_GROUP)
+                .await?;
+            groups.push(ErrorGroup {
+                fingerprint,
+                error_type: row.get("error_type"),
+                message: row.get("message"),
+                first_seen_ms: row.get("first_seen"),
+                last_seen_ms: row.get("last_seen"),
+                occurrence_count: row.get("occurrence_count"),
+                recent_occurrences,
+            });
+        }
+
+        Ok(groups)
+    }
+
+    /// 删除所有错误组与出现记录
+    pub async fn clear(&self) -> Result<()> {
+        for sql in ["DELETE FROM error_occurrences", "DELETE FROM error_groups"] {
+            sqlx::query(sql).execute(&self.pool).await.map_err(|e| {
+                AppError::database_error(format!("Failed to clear error reports: {e}"))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// 关闭连接池
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    async fn get_occurrences(&self, fingerprint: &str, limit: i64) -> Result<Vec<ErrorOccurrence>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, occurred_at, stack, component_stack, url, user_agent,
+                   workspace_id, breadcrumbs, context, correlated_backend_errors
+            FROM error_occurrences
+            WHERE fingerprint = ?
+            ORDER BY occurred_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(fingerprint)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to query error occurrences: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let breadcrumbs: String = row.get("breadcrumbs");
+                let context: Option<String> = row.get("context");
+                let correlated: String = row.get("correlated_backend_errors");
+                ErrorOccurrence {
+                    id: row.get("id"),
+                    occurred_at_ms: row.get("occurred_at"),
+                    stack: row.get("stack"),
+                    component_stack: row.get("component_stack"),
+                    url: row.get("url"),
+                    user_agent: row.get("user_agent"),
+                    workspace_id: row.get("workspace_id"),
+                    breadcrumbs: serde_json::from_str(&breadcrumbs).unwrap_or_default(),
+                    context: context.and_then(|c| serde_json::from_str(&c).ok()),
+                    correlated_backend_errors: serde_json::from_str(&correlated)
+                        .unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+}
+
+async fn init_schema(pool: &SqlitePool) -> Result<()> {
+    let statements = [
+        r#"
+        CREATE TABLE IF NOT EXISTS error_groups (
+            fingerprint TEXT PRIMARY KEY,
+            error_type TEXT NOT NULL,
+            message TEXT NOT NULL,
+            first_seen INTEGER NOT NULL,
+            last_seen INTEGER NOT NULL,
+            occurrence_count INTEGER NOT NULL DEFAULT 0
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS error_occurrences (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            fingerprint TEXT NOT NULL,
+            occurred_at INTEGER NOT NULL,
+            stack TEXT,
+            component_stack TEXT,
+            url TEXT,
+            user_agent TEXT,
+            workspace_id TEXT,
+            breadcrumbs TEXT NOT NULL DEFAULT '[]',
+            context TEXT,
+            correlated_backend_errors TEXT NOT NULL DEFAULT '[]',
+            FOREIGN KEY (fingerprint) REFERENCES error_groups(fingerprint) ON DELETE CASCADE
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_error_groups_last_seen ON error_groups(last_seen)",
+        "CREATE INDEX IF NOT EXISTS idx_error_occurrences_fp ON error_occurrences(fingerprint, occurred_at)",
+    ];
+
+    for sql in statements {
+        sqlx::query(sql).execute(pool).await.map_err(|e| {
+            AppError::database_error(format!("Failed to initialize diagnostics schema: {e}"))
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::backend_errors::record_backend_error;
+
+    fn report(message: &str, stack: &str) -> FrontendErrorReport {
+        FrontendErrorReport {
+            message: message.to_string(),
+            error_type: Some("TypeError".to_string()),
+            stack: Some(stack.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_build_hash_and_line_numbers() {
+        let a = compute_fingerprint(
+            "TypeError",
+            "Cannot read properties of undefined (reading 'id')",
+            Some("TypeError: x\n    at render (http://localhost:1420/assets/index-3f2a9c1b.js:12:345)"),
+        );
+        let b = compute_fingerprint(
+            "TypeError",
+            "Cannot read properties of undefined (reading 'id')",
+            Some("TypeError: x\n    at render (tauri://localhost/assets/index-8d7e6f5a.js:98:7)"),
+        );
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_fingerprint_distinguishes_frames() {
+        let a = compute_fingerprint("Error", "boom", Some("    at foo (app.js:1:1)"));
+        let b = compute_fingerprint("Error", "boom", Some("    at bar (app.js:1:1)"));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_normalize_message_masks_ids() {
+        assert_eq!(
+            normalize_message("Workspace 42 not found: 123e4567-e89b-12d3-a456-426614174000"),
+            "Workspace <n> not found: <uuid>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_dedupes_by_fingerprint() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ErrorReportStore::new(dir.path()).await.unwrap();
+
+        let first = store
+            .record(&report("boom 1", "    at foo (app.js:1:1)"))
+            .await
+            .unwrap();
+        let second = store
+            .record(&report("boom 2", "    at foo (app.js:7:9)"))
+            .await
+            .unwrap();
+
+        assert!(first.is_new_group);
+        assert!(!second.is_new_group);
+        assert_eq!(first.fingerprint, second.fingerprint);
+        assert_eq!(second.occurrence_count, 2);
+
+        let groups = store.get_error_groups(10).await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].occurrence_count, 2);
+        assert_eq!(groups[0].recent_occurrences.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_record_correlates_backend_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ErrorReportStore::new(dir.path()).await.unwrap();
+        let at = 8_000_000_000_000;
+
+        record_backend_error(BackendErrorRecord {
+            timestamp_ms: at - 1_000,
+            target: "log_analyzer::commands::search".to_string(),
+            message: "search failed".to_string(),
+        });
+
+        let mut r = report("render failed", "    at Results (app.js:1:1)");
+        r.timestamp_ms = Some(at);
+        r.breadcrumbs = vec![Breadcrumb {
+            timestamp_ms: at - 2_000,
+            category: "ui.click".to_string(),
+            message: "search button".to_string(),
+        }];
+        let outcome = store.record(&r).await.unwrap();
+        assert_eq!(outcome.correlated_backend_errors, 1);
+
+        let groups = store.get_error_groups(10).await.unwrap();
+        let occurrence = &groups[0].recent_occurrences[0];
+        assert_eq!(occurrence.breadcrumbs.len(), 1);
+        assert_eq!(
+            occurrence.correlated_backend_errors[0].message,
+            "search failed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_rejects_empty_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ErrorReportStore::new(dir.path()).await.unwrap();
+        assert!(store.record(&report("  ", "")).await.is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! 内存占用估算
+//!
+//! `get_memory_breakdown` 的数据来源。各组件按自身结构估算占用（条目数 × 单条大小、
+//! 缓冲区容量、索引段大小），用于定位大块内存的去向，并非精确计量：
+//! - 索引：Tantivy reader 打开的段（内存映射）、writer 内存预算、高亮缓存
+//! - 缓存：CAS 存在性缓存、正则引擎与查询计划缓存、译文缓存
+//! - 路径表：固定搜索快照中的文件列表与时间偏移表
+//! - 结果缓冲：进行中搜索会话的写缓冲区
+//! - 事件历史：后端日志与错误环形缓冲区
+//!
+//! 进程常驻内存（RSS）减去堆内估算即“未归属”部分，包含已换入的索引映射页、
+//! 分配器碎片、线程栈与未建模的结构。
+
+use serde::Serialize;
+
+use crate::application::search_session::SearchSessionManager;
+use crate::services::translation::TranslationRegistry;
+use la_search::DiskResultStore;
+
+use super::backend_errors::backend_errors_memory;
+use super::backend_logs::backend_logs_memory;
+
+/// 组件类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryCategory {
+    Index,
+    Cache,
+    PathMap,
+    ResultBuffer,
+    EventHistory,
+}
+
+/// 单个组件的估算占用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryComponent {
+    pub category: MemoryCategory,
+    /// 组件名（如 `tantivy.reader`）
+    pub name: String,
+    /// 所属工作区；全局组件为 `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<u64>,
+    pub estimated_bytes: u64,
+    /// 内存映射（按需换入），不计入堆内估算
+    pub mapped: bool,
+}
+
+impl MemoryComponent {
+    pub fn new(category: MemoryCategory, name: &str, estimated_bytes: u64) -> Self {
+        Self {
+            category,
+            name: name.to_string(),
+            workspace_id: None,
+            entries: None,
+            estimated_bytes,
+            mapped: false,
+        }
+    }
+
+    pub fn with_entries(mut self, entries: u64) -> Self {
+        self.entries = Some(entries);
+        self
+    }
+
+    pub fn for_workspace(mut self, workspace_id: &str) -> Self {
+        self.workspace_id = Some(workspace_id.to_string());
+        self
+    }
+
+    pub fn mapped(mut self) -> Self {
+        self.mapped = true;
+        self
+    }
+}
+
+/// 按类别汇总
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryTotal {
+    pub category: MemoryCategory,
+    pub estimated_bytes: u64,
+}
+
+/// `get_memory_breakdown` 的返回值
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryBreakdown {
+    /// 按估算占用从大到小排列
+    pub components: Vec<MemoryComponent>,
+    /// 各类别堆内合计（从大到小，不含内存映射）
+    pub categories: Vec<CategoryTotal>,
+    pub estimated_heap_bytes: u64,
+    pub mapped_bytes: u64,
+    /// 进程常驻内存；平台不支持时为 `None`
+    pub process_resident_bytes: Option<u64>,
+    /// RSS 中未归属到任何组件的部分
+    pub unattributed_bytes: Option<u64>,
+}
+
+impl MemoryBreakdown {
+    pub fn new(mut components: Vec<MemoryComponent>, process_resident_bytes: Option<u64>) -> Self {
+        components.sort_by_key(|c| std::cmp::Reverse(c.estimated_bytes));
+
+        let mut categories: Vec<CategoryTotal> = Vec::new();
+        let mut estimated_heap_bytes = 0;
+        let mut mapped_bytes = 0;
+        for component in &components {
+            if component.mapped {
+                mapped_bytes += component.estimated_bytes;
+                continue;
+            }
+            estimated_heap_bytes += component.estimated_bytes;
+            match categories
+                .iter_mut()
+                .find(|total| total.category == component.category)
+            {
+                Some(total) => total.estimated_bytes += component.estimated_bytes,
+                None => categories.push(CategoryTotal {
+                    category: component.category,
+                    estimated_bytes: component.estimated_bytes,
+                }),
+            }
+        }
+        categories.sort_by_key(|c| std::cmp::Reverse(c.estimated_bytes));
+
+        Self {
+            components,
+            categories,
+            estimated_heap_bytes,
+            mapped_bytes,
+            process_resident_bytes,
+            unattributed_bytes: process_resident_bytes
+                .map(|rss| rss.saturating_sub(estimated_heap_bytes)),
+        }
+    }
+}
+
+/// 不属于单个工作区的组件
+pub fn global_components(
+    sessions: Option<&SearchSessionManager>,
+    results: Option<&DiskResultStore>,
+    translation: &TranslationRegistry,
+) -> Vec<MemoryComponent> {
+    let mut components = Vec::new();
+
+    let (entries, bytes) = translation.cache_usage();
+    components.push(
+        MemoryComponent::new(MemoryCategory::Cache, "translation.cache", bytes)
+            .with_entries(entries),
+    );
+    if let Some(results) = results {
+        components.push(
+            MemoryComponent::new(
+                MemoryCategory::ResultBuffer,
+                "search.result_writers",
+                results.buffered_bytes(),
+            )
+            .with_entries(results.active_session_count() as u64),
+        );
+    }
+    if let Some(sessions) = sessions {
+        let (paths, bytes) = sessions.snapshot_memory();
+        components.push(
+            MemoryComponent::new(MemoryCategory::PathMap, "search.pinned_snapshots", bytes)
+                .with_entries(paths),
+        );
+    }
+
+    let (entries, bytes) = backend_logs_memory();
+    components.push(
+        MemoryComponent::new(MemoryCategory::EventHistory, "backend_logs", bytes)
+            .with_entries(entries),
+    );
+    let (entries, bytes) = backend_errors_memory();
+    components.push(
+        MemoryComponent::new(MemoryCategory::EventHistory, "backend_errors", bytes)
+            .with_entries(entries),
+    );
+    components
+}
+
+/// 进程常驻内存（字节）
+#[cfg(target_os = "linux")]
+pub fn process_resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find(|line| line.starts_with("VmRSS:"))?
+        .split_whitespace()
+        .nth(1)?
+        .parse::<u64>()
+        .ok()
+        .map(|kb| kb * 1024)
+}
+
+/// 进程常驻内存（字节，工作集）
+#[cfg(target_os = "windows")]
+pub fn process_resident_bytes() -> Option<u64> {
+    use windows_sys::Win32::System::ProcessStatus::{
+        GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    // SAFETY: PROCESS_MEMORY_COUNTERS 为纯数据结构，cb 按 API 要求设置
+    unsafe {
+        let mut counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+        counters.cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        if GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) != 0 {
+            Some(counters.WorkingSetSize as u64)
+        } else {
+            None
+        }
+    }
+}
+
+/// 进程常驻内存（字节）
+#[cfg(target_os = "macos")]
+pub fn process_resident_bytes() -> Option<u64> {
+    // SAFETY: rusage_info_v2 为纯数据结构，缓冲区类型与 flavor 匹配
+    unsafe {
+        let mut info: libc::rusage_info_v2 = std::mem::zeroed();
+        let ret = libc::proc_pid_rusage(
+            std::process::id() as libc::c_int,
+            libc::RUSAGE_INFO_V2,
+            (&mut info as *mut libc::rusage_info_v2).cast(),
+        );
+        (ret == 0).then_some(info.ri_resident_size)
+    }
+}
+
+/// 进程常驻内存（字节）
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn process_resident_bytes() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown_sorts_and_excludes_mapped_from_heap() {
+        let breakdown = MemoryBreakdown::new(
+            vec![
+                MemoryComponent::new(MemoryCategory::Cache, "small", 10),
+                MemoryComponent::new(MemoryCategory::Index, "tantivy.reader", 5_000).mapped(),
+                MemoryComponent::new(MemoryCategory::Index, "tantivy.writer", 1_000)
+                    .for_workspace("ws"),
+                MemoryComponent::new(MemoryCategory::Cache, "large", 300).with_entries(3),
+            ],
+            Some(2_000),
+        );
+
+        let names: Vec<&str> = breakdown
+            .components
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["tantivy.reader", "tantivy.writer", "large", "small"]
+        );
+        assert_eq!(breakdown.estimated_heap_bytes, 1_310);
+        assert_eq!(breakdown.mapped_bytes, 5_000);
+        assert_eq!(breakdown.unattributed_bytes, Some(690));
+        assert_eq!(
+            breakdown.categories,
+            [
+                CategoryTotal {
+                    category: MemoryCategory::Index,
+                    estimated_bytes: 1_000,
+                },
+                CategoryTotal {
+                    category: MemoryCategory::Cache,
+                    estimated_bytes: 310,
+                },
+            ]
+        );
+    }
+}
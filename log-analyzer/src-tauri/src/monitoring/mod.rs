@@ -0,0 +1,32 @@
+//! 运行时监控与诊断
+//!
+//! 提供面向诊断页面的后端能力：
+//! - 前端错误上报（按指纹去重、持久化到 SQLite）
+//! - 后端错误捕获（tracing Layer，用于与前端错误做时间窗口关联）
+//! - 后端日志环形缓冲区（最近的 tracing 事件，可在应用内查看与导出）
+//! - panic 时写入崩溃报告（消息、backtrace、最近日志与运行时上下文）
+//! - 可选的 Sentry 错误上报（由配置开启）
+//! - 系统资源监控（内存 / 磁盘不足时释放缓存、暂停导入并提醒用户）
+//! - 自适应缓存容量（按可用内存周期性调整各工作区的正则引擎缓存）
+//! - 内存占用估算（索引、缓存、路径表、结果缓冲与事件历史各占多少）
+//! - 可选的本地使用统计（命令调用次数与操作耗时，只在用户导出时离开本机）
+
+pub mod backend_errors;
+pub mod backend_logs;
+pub mod cache_sizing;
+pub mod crash_reports;
+pub mod error_reports;
+pub mod memory_usage;
+pub mod resource_monitor;
+pub mod sentry_config;
+pub mod telemetry;
+
+pub use backend_errors::{BackendErrorLayer, BackendErrorRecord};
+pub use backend_logs::{BackendLogFilter, BackendLogLayer, BackendLogRecord};
+pub use cache_sizing::{spawn_cache_sizer, CacheSizer};
+pub use crash_reports::{install_panic_hook, CrashReportSummary, CRASH_REPORT_DIR};
+pub use error_reports::{ErrorGroup, ErrorReportOutcome, ErrorReportStore, FrontendErrorReport};
+pub use memory_usage::{MemoryBreakdown, MemoryCategory, MemoryComponent};
+pub use resource_monitor::{spawn_resource_monitor, ResourceGate, ResourcePressure};
+pub use sentry_config::{init_sentry, shutdown_sentry};
+pub use telemetry::{spawn_telemetry_saver, telemetry, TelemetryReport};
@@ -0,0 +1,375 @@
+//! 系统资源监控
+//!
+//! 周期性采样可用内存与数据目录所在磁盘的可用空间，跨越阈值时主动响应，
+//! 而不是等到被操作系统杀死：
+//! - 内存不足：释放各工作区的正则引擎 / 查询计划缓存
+//! - 磁盘不足：清理已完成的磁盘搜索结果缓存与解压视图缓存
+//! - 任一不足：暂停新的导入（`ResourceGate::wait_for_capacity`）
+//! - 状态变化时发送 `resource-pressure` 事件提醒用户
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use la_core::models::config::MonitoringConfig;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use crate::models::AppState;
+
+/// 资源紧张时保留的已完成搜索会话数
+const KEEP_SEARCH_SESSIONS_UNDER_PRESSURE: usize = 2;
+
+const MB: u64 = 1024 * 1024;
+
+/// 一次资源采样
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceSample {
+    /// 可用内存（字节），平台不支持时为 None
+    pub available_memory: Option<u64>,
+    /// 数据目录所在磁盘可用空间（字节），获取失败时为 None
+    pub available_disk: Option<u64>,
+}
+
+/// 资源阈值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceThresholds {
+    pub min_free_memory: u64,
+    pub min_free_disk: u64,
+}
+
+impl ResourceThresholds {
+    pub fn from_config(config: &MonitoringConfig) -> Self {
+        Self {
+            min_free_memory: config.min_free_memory_mb.saturating_mul(MB),
+            min_free_disk: config.min_free_disk_mb.saturating_mul(MB),
+        }
+    }
+}
+
+/// 资源压力状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcePressure {
+    pub memory_low: bool,
+    pub disk_low: bool,
+}
+
+impl ResourcePressure {
+    pub fn is_under_pressure(&self) -> bool {
+        self.memory_low || self.disk_low
+    }
+}
+
+/// 根据采样与阈值计算压力状态；无法采样的资源视为充足
+pub fn evaluate_pressure(
+    sample: &ResourceSample,
+    thresholds: &ResourceThresholds,
+) -> ResourcePressure {
+    ResourcePressure {
+        memory_low: sample
+            .available_memory
+            .is_some_and(|free| free < thresholds.min_free_memory),
+        disk_low: sample
+            .available_disk
+            .is_some_and(|free| free < thresholds.min_free_disk),
+    }
+}
+
+/// `resource-pressure` 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourcePressureEvent {
+    #[serde(flatten)]
+    pressure: ResourcePressure,
+    available_memory_mb: Option<u64>,
+    available_disk_mb: Option<u64>,
+    min_free_memory_mb: u64,
+    min_free_disk_mb: u64,
+    /// 已执行的响应动作
+    actions: Vec<&'static str>,
+}
+
+/// 导入闸门：资源紧张时阻塞新的导入，恢复后唤醒
+#[derive(Default)]
+pub struct ResourceGate {
+    memory_low: AtomicBool,
+    disk_low: AtomicBool,
+    released: Notify,
+}
+
+impl ResourceGate {
+    pub fn pressure(&self) -> ResourcePressure {
+        ResourcePressure {
+            memory_low: self.memory_low.load(Ordering::Acquire),
+            disk_low: self.disk_low.load(Ordering::Acquire),
+        }
+    }
+
+    pub fn set_pressure(&self, pressure: ResourcePressure) {
+        self.memory_low
+            .store(pressure.memory_low, Ordering::Release);
+        self.disk_low.store(pressure.disk_low, Ordering::Release);
+        if !pressure.is_under_pressure() {
+            self.released.notify_waiters();
+        }
+    }
+
+    /// 等待资源恢复，最多等待 `timeout`；返回资源是否已恢复
+    pub async fn wait_for_capacity(&self, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                let released = self.released.notified();
+                if !self.pressure().is_under_pressure() {
+                    return;
+                }
+                released.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+}
+
+/// 当前可用内存（字节）
+#[cfg(target_os = "linux")]
+pub fn available_memory_bytes() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    meminfo
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))?
+        .split_whitespace()
+        .nth(1)?
+        .parse::<u64>()
+        .ok()
+        .map(|kb| kb * 1024)
+}
+
+/// 当前可用内存（字节）
+#[cfg(target_os = "windows")]
+pub fn available_memory_bytes() -> Option<u64> {
+    use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    // SAFETY: MEMORYSTATUSEX 为纯数据结构，dwLength 按 API 要求设置
+    unsafe {
+        let mut status: MEMORYSTATUSEX = std::mem::zeroed();
+        status.dwLength = std::mem::size_of::<MEMORYSTATUSEX>() as u32;
+        if GlobalMemoryStatusEx(&mut status) != 0 {
+            Some(status.ullAvailPhys)
+        } else {
+            None
+        }
+    }
+}
+
+/// 当前可用内存（字节）
+///
+/// 按空闲、非活跃与可清除页估算：macOS 会把空闲内存尽量用作文件缓存，
+/// 只看空闲页会长期误报内存不足。
+#[cfg(target_os = "macos")]
+pub fn available_memory_bytes() -> Option<u64> {
+    // SAFETY: vm_statistics64 只含整数字段，全零为合法值
+    let mut stats: libc::vm_statistics64 = unsafe { std::mem::zeroed() };
+    let mut count = libc::HOST_VM_INFO64_COUNT;
+    // SAFETY: 缓冲区为 vm_statistics64，count 为其按 integer_t 计的长度
+    let ret = unsafe {
+        #[allow(deprecated)]
+        let host = libc::mach_host_self();
+        libc::host_statistics64(
+            host,
+            libc::HOST_VM_INFO64,
+            (&mut stats as *mut libc::vm_statistics64).cast(),
+            &mut count,
+        )
+    };
+    if ret != libc::KERN_SUCCESS {
+        return None;
+    }
+
+    let mut page_size: u64 = 0;
+    let mut size = std::mem::size_of::<u64>();
+    // SAFETY: 传入的缓冲区与长度匹配
+    let ret = unsafe {
+        libc::sysctlbyname(
+            c"hw.pagesize".as_ptr(),
+            (&mut page_size as *mut u64).cast(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+
+    let pages = u64::from(stats.free_count)
+        + u64::from(stats.inactive_count)
+        + u64::from(stats.purgeable_count);
+    Some(pages.saturating_mul(page_size))
+}
+
+/// 当前可用内存（字节）
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn available_memory_bytes() -> Option<u64> {
+    None
+}
+
+fn take_sample(data_dir: &std::path::Path) -> ResourceSample {
+    ResourceSample {
+        available_memory: available_memory_bytes(),
+        available_disk: fs4::available_space(data_dir).ok(),
+    }
+}
+
+/// 对新进入的压力状态执行响应动作，返回已执行的动作名
+fn respond(
+    state: &AppState,
+    previous: ResourcePressure,
+    current: ResourcePressure,
+) -> Vec<&'static str> {
+    let mut actions = Vec::new();
+
+    if current.memory_low && !previous.memory_low {
+        for service in state.all_workspace_services() {
+            service.release_caches();
+        }
+        actions.push("release_caches");
+    }
+
+    if current.disk_low && !previous.disk_low {
+        if let Some(store) = state.get_disk_result_store() {
+            let evicted = store.evict_completed_sessions(KEEP_SEARCH_SESSIONS_UNDER_PRESSURE);
+            info!(
+                evicted,
+                "Evicted search result sessions due to low disk space"
+            );
+        }
+        actions.push("evict_search_cache");
+
+        let freed: u64 = state
+            .all_workspace_services()
+            .iter()
+            .map(|service| service.release_disk_caches())
+            .sum();
+        info!(
+            freed_mb = freed / MB,
+            "Cleared decompressed view caches due to low disk space"
+        );
+        actions.push("clear_decompressed_views");
+    }
+
+    if current.is_under_pressure() {
+        actions.push("pause_imports");
+    }
+
+    actions
+}
+
+/// 启动后台资源监控循环
+pub fn spawn_resource_monitor(app: AppHandle, config: MonitoringConfig, data_dir: PathBuf) {
+    let thresholds = ResourceThresholds::from_config(&config);
+    let interval = Duration::from_secs(config.resource_check_interval_secs.max(1));
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            let dir = data_dir.clone();
+            let Ok(sample) = tokio::task::spawn_blocking(move || take_sample(&dir)).await else {
+                continue;
+            };
+
+            let state = app.state::<AppState>();
+            let gate = state.monitoring.resource_gate();
+            let previous = gate.pressure();
+            let current = evaluate_pressure(&sample, &thresholds);
+            if current == previous {
+                continue;
+            }
+
+            let actions = respond(&state, previous, current);
+            gate.set_pressure(current);
+
+            if current.is_under_pressure() {
+                warn!(
+                    memory_low = current.memory_low,
+                    disk_low = current.disk_low,
+                    available_memory_mb = sample.available_memory.map(|b| b / MB),
+                    available_disk_mb = sample.available_disk.map(|b| b / MB),
+                    "System resources low, imports paused"
+                );
+            } else {
+                info!("System resources recovered, imports resumed");
+            }
+
+            let _ = app.emit(
+                "resource-pressure",
+                ResourcePressureEvent {
+                    pressure: current,
+                    available_memory_mb: sample.available_memory.map(|b| b / MB),
+                    available_disk_mb: sample.available_disk.map(|b| b / MB),
+                    min_free_memory_mb: config.min_free_memory_mb,
+                    min_free_disk_mb: config.min_free_disk_mb,
+                    actions,
+                },
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn thresholds() -> ResourceThresholds {
+        ResourceThresholds {
+            min_free_memory: 512 * MB,
+            min_free_disk: 1024 * MB,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_pressure() {
+        let sample = ResourceSample {
+            available_memory: Some(100 * MB),
+            available_disk: Some(10_000 * MB),
+        };
+        let pressure = evaluate_pressure(&sample, &thresholds());
+        assert!(pressure.memory_low);
+        assert!(!pressure.disk_low);
+    }
+
+    #[test]
+    fn test_evaluate_pressure_unknown_is_ok() {
+        let pressure = evaluate_pressure(&ResourceSample::default(), &thresholds());
+        assert!(!pressure.is_under_pressure());
+    }
+
+    #[tokio::test]
+    async fn test_gate_waits_until_released() {
+        let gate = Arc::new(ResourceGate::default());
+        gate.set_pressure(ResourcePressure {
+            memory_low: true,
+            disk_low: false,
+        });
+        assert!(!gate.wait_for_capacity(Duration::from_millis(20)).await);
+
+        let waiter = {
+            let gate = Arc::clone(&gate);
+            tokio::spawn(async move { gate.wait_for_capacity(Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        gate.set_pressure(ResourcePressure::default());
+        assert!(waiter.await.unwrap());
+    }
+
+    #[test]
+    fn test_available_memory_reported_on_linux() {
+        if cfg!(target_os = "linux") {
+            assert!(available_memory_bytes().is_some_and(|b| b > 0));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Sentry 错误上报
+//!
+//! 由 `monitoring.sentry` 配置控制，默认关闭。启用后：
+//! - tracing 的 ERROR 事件上报为 Sentry 事件，WARN/INFO 作为面包屑
+//! - 事件字段中的工作区 / 任务 / 搜索标识提升为标签，便于在 Sentry 中筛选
+//! - 默认集成中的 panic hook 捕获崩溃，并在退出前刷新发送队列
+
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+
+use la_core::models::config::SentryConfig;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use sentry::protocol::{Context, Event};
+use tracing::{info, warn};
+
+/// 提升为 Sentry 标签的 tracing 字段
+const TAG_FIELDS: &[&str] = &["workspace_id", "task_id", "task_type", "search_id"];
+
+/// sentry-tracing 存放事件字段的 context 名称
+const TRACING_FIELDS_CONTEXT: &str = "Rust Tracing Fields";
+
+/// 退出时刷新发送队列的最长等待时间
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 保持客户端存活；drop 时刷新并关闭传输
+static SENTRY_GUARD: Lazy<Mutex<Option<sentry::ClientInitGuard>>> = Lazy::new(|| Mutex::new(None));
+
+/// 按配置初始化 Sentry，返回是否已启用
+///
+/// 未启用或 DSN 无效时不做任何事；重复调用会替换之前的客户端。
+pub fn init_sentry(config: &SentryConfig) -> bool {
+    if !config.is_active() {
+        return false;
+    }
+
+    let dsn = match config.dsn.trim().parse::<sentry::types::Dsn>() {
+        Ok(dsn) => dsn,
+        Err(e) => {
+            warn!(error = %e, "Invalid Sentry DSN, error reporting disabled");
+            return false;
+        }
+    };
+
+    let guard = sentry::init(sentry::ClientOptions {
+        dsn: Some(dsn),
+        release: sentry::release_name!(),
+        environment: Some(Cow::Owned(config.environment.clone())),
+        sample_rate: config.sample_rate.clamp(0.0, 1.0),
+        traces_sample_rate: config.traces_sample_rate.clamp(0.0, 1.0),
+        attach_stacktrace: true,
+        send_default_pii: false,
+        before_send: Some(Arc::new(|event| Some(promote_context_tags(event)))),
+        ..Default::default()
+    });
+
+    // sentry::init 绑定的是当前线程的 Hub；同时绑定进程 Hub，
+    // 确保之后新建线程（tokio worker、rayon）继承同一客户端
+    if let Some(client) = sentry::Hub::current().client() {
+        sentry::Hub::main().bind_client(Some(client));
+    }
+
+    *SENTRY_GUARD.lock() = Some(guard);
+    info!(environment = %config.environment, "Sentry error reporting enabled");
+    true
+}
+
+/// 刷新待发送事件并关闭客户端（应用退出时调用）
+pub fn shutdown_sentry() {
+    if let Some(guard) = SENTRY_GUARD.lock().take() {
+        guard.flush(Some(FLUSH_TIMEOUT));
+    }
+}
+
+/// 把 tracing 字段中的工作区 / 任务标识提升为事件标签
+fn promote_context_tags(mut event: Event<'static>) -> Event<'static> {
+    let Some(Context::Other(fields)) = event.contexts.get(TRACING_FIELDS_CONTEXT) else {
+        return event;
+    };
+
+    let tags: Vec<(String, String)> = TAG_FIELDS
+        .iter()
+        .filter_map(|name| {
+            let value = match fields.get(*name)? {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            Some((name.to_string(), value))
+        })
+        .collect();
+
+    for (key, value) in tags {
+        event.tags.entry(key).or_insert(value);
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_init_sentry_disabled_by_default() {
+        assert!(!init_sentry(&SentryConfig::default()));
+    }
+
+    #[test]
+    fn test_init_sentry_rejects_invalid_dsn() {
+        let config = SentryConfig {
+            enabled: true,
+            dsn: "not a dsn".to_string(),
+            ..Default::default()
+        };
+        assert!(!init_sentry(&config));
+    }
+
+    #[test]
+    fn test_promote_context_tags() {
+        let mut fields = BTreeMap::new();
+        fields.insert("workspace_id".to_string(), "ws-1".into());
+        fields.insert("task_id".to_string(), "task-9".into());
+        fields.insert("error".to_string(), "boom".into());
+
+        let mut event = Event::default();
+        event
+            .contexts
+            .insert(TRACING_FIELDS_CONTEXT.to_string(), Context::Other(fields));
+
+        let event = promote_context_tags(event);
+        assert_eq!(
+            event.tags.get("workspace_id").map(String::as_str),
+            Some("ws-1")
+        );
+        assert_eq!(
+            event.tags.get("task_id").map(String::as_str),
+            Some("task-9")
+        );
+        assert!(!event.tags.contains_key("error"));
+    }
+}
@@ -0,0 +1,298 @@
+//! 本地使用统计（可选）
+//!
+//! 由 `monitoring.telemetry_enabled` 控制，默认关闭。启用后只记录：
+//! - 每个命令的调用次数（命令名，不含参数）
+//! - 搜索、导入等操作的耗时分布与结果（完成 / 失败 / 取消等）
+//!
+//! 不记录工作区、路径、查询内容或任何标识符，也不生成安装 ID。统计保存在应用数据目录的
+//! [`TELEMETRY_FILE`] 中，从不自动发送；用户可以先查看报告内容，再显式导出附在反馈里。
+//! 关闭时清空已有统计并删除文件。
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// 统计文件名（位于应用数据目录）
+pub const TELEMETRY_FILE: &str = "telemetry.json";
+
+/// 报告格式版本，字段含义变化时递增
+const SCHEMA_VERSION: u32 = 1;
+
+/// 耗时分桶的上界（毫秒）；超过最后一个上界的计入额外的溢出桶
+pub const DURATION_BUCKETS_MS: [u64; 6] = [10, 100, 1_000, 10_000, 60_000, 600_000];
+
+/// 有新统计时写盘的间隔
+const SAVE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// 单个操作的耗时分布与结果计数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStats {
+    pub count: u64,
+    /// 结果 → 次数（如 `completed`、`failed`、`cancelled`）
+    pub outcomes: BTreeMap<String, u64>,
+    /// 按 [`DURATION_BUCKETS_MS`] 分桶的次数，最后一项为溢出桶
+    pub duration_buckets: Vec<u64>,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+impl OperationStats {
+    fn record(&mut self, duration: Duration, outcome: &str) {
+        let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        if self.duration_buckets.len() != DURATION_BUCKETS_MS.len() + 1 {
+            self.duration_buckets = vec![0; DURATION_BUCKETS_MS.len() + 1];
+        }
+        let bucket = DURATION_BUCKETS_MS
+            .iter()
+            .position(|&upper| ms <= upper)
+            .unwrap_or(DURATION_BUCKETS_MS.len());
+        self.duration_buckets[bucket] += 1;
+        self.count += 1;
+        *self.outcomes.entry(outcome.to_string()).or_default() += 1;
+        self.total_ms = self.total_ms.saturating_add(ms);
+        self.max_ms = self.max_ms.max(ms);
+    }
+}
+
+/// 累计的统计数据（即 [`TELEMETRY_FILE`] 的内容）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryData {
+    /// 开始统计的日期（只精确到天）
+    pub since: Option<String>,
+    /// 命令名 → 调用次数
+    pub commands: BTreeMap<String, u64>,
+    /// 操作名 → 耗时与结果
+    pub operations: BTreeMap<String, OperationStats>,
+}
+
+/// 查看与导出的报告：统计数据加上应用版本与平台
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryReport {
+    pub schema_version: u32,
+    pub app_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub enabled: bool,
+    pub generated_on: String,
+    #[serde(flatten)]
+    pub data: TelemetryData,
+}
+
+/// 使用统计收集器
+#[derive(Default)]
+pub struct Telemetry {
+    enabled: AtomicBool,
+    dirty: AtomicBool,
+    data: Mutex<TelemetryData>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl Telemetry {
+    /// 设置统计文件所在目录并按配置启用；启用时载入之前的统计
+    pub fn init(&self, data_dir: &Path, enabled: bool) {
+        *self.path.lock() = Some(data_dir.join(TELEMETRY_FILE));
+        self.set_enabled(enabled);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// 开启或关闭统计；关闭时清空内存中的统计并删除文件
+    pub fn set_enabled(&self, enabled: bool) {
+        let was_enabled = self.enabled.swap(enabled, Ordering::Relaxed);
+        if enabled && !was_enabled {
+            *self.data.lock() = self.load().unwrap_or_default();
+            info!("Local usage telemetry enabled");
+        } else if !enabled {
+            self.clear();
+            if was_enabled {
+                info!("Local usage telemetry disabled and cleared");
+            }
+        }
+    }
+
+    /// 记录一次命令调用
+    pub fn record_command(&self, command: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut data = self.data.lock();
+        data.since.get_or_insert_with(today);
+        *data.commands.entry(command.to_string()).or_default() += 1;
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// 记录一次操作的耗时与结果
+    pub fn record_operation(&self, operation: &str, duration: Duration, outcome: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut data = self.data.lock();
+        data.since.get_or_insert_with(today);
+        data.operations
+            .entry(operation.to_string())
+            .or_default()
+            .record(duration, outcome);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// 当前统计的完整报告（与导出内容一致）
+    pub fn report(&self) -> TelemetryReport {
+        TelemetryReport {
+            schema_version: SCHEMA_VERSION,
+            app_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            enabled: self.is_enabled(),
+            generated_on: today(),
+            data: self.data.lock().clone(),
+        }
+    }
+
+    /// 清空统计并删除文件
+    pub fn clear(&self) {
+        *self.data.lock() = TelemetryData::default();
+        self.dirty.store(false, Ordering::Relaxed);
+        if let Some(path) = self.path.lock().as_ref() {
+            if let Err(e) = std::fs::remove_file(path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!(error = %e, "Failed to delete telemetry file");
+                }
+            }
+        }
+    }
+
+    /// 有未保存的统计时写盘（先写临时文件再替换，避免写到一半的文件）
+    pub fn save(&self) {
+        if !self.is_enabled() || !self.dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let Some(path) = self.path.lock().clone() else {
+            return;
+        };
+        let content = match serde_json::to_vec_pretty(&*self.data.lock()) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize telemetry");
+                return;
+            }
+        };
+        let tmp = path.with_extension("json.tmp");
+        if let Err(e) = std::fs::write(&tmp, content).and_then(|_| std::fs::rename(&tmp, &path)) {
+            warn!(error = %e, "Failed to save telemetry");
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn load(&self) -> Option<TelemetryData> {
+        let path = self.path.lock().clone()?;
+        let content = std::fs::read(&path).ok()?;
+        serde_json::from_slice(&content)
+            .inspect_err(|e| warn!(error = %e, "Ignoring unreadable telemetry file"))
+            .ok()
+    }
+}
+
+fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
+
+static TELEMETRY: Lazy<Telemetry> = Lazy::new(Telemetry::default);
+
+/// 进程内的使用统计收集器
+pub fn telemetry() -> &'static Telemetry {
+    &TELEMETRY
+}
+
+/// 包装命令处理器：命令被识别时记录一次调用
+pub fn instrument<R, F>(
+    handler: F,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: tauri::Runtime,
+    F: Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let handled = handler(invoke);
+        if handled {
+            telemetry().record_command(&command);
+        }
+        handled
+    }
+}
+
+/// 启动后台写盘循环
+pub fn spawn_telemetry_saver() {
+    tauri::async_runtime::spawn(async {
+        let mut ticker = tokio::time::interval(SAVE_INTERVAL);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let _ = tokio::task::spawn_blocking(|| telemetry().save()).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_fall_into_buckets() {
+        let mut stats = OperationStats::default();
+        stats.record(Duration::from_millis(5), "completed");
+        stats.record(Duration::from_millis(100), "completed");
+        stats.record(Duration::from_secs(3600), "timedOut");
+        assert_eq!(stats.duration_buckets, vec![1, 1, 0, 0, 0, 0, 1]);
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.outcomes["completed"], 2);
+        assert_eq!(stats.max_ms, 3_600_000);
+    }
+
+    #[test]
+    fn records_only_while_enabled_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let telemetry = Telemetry::default();
+        telemetry.init(dir.path(), false);
+        telemetry.record_command("search_logs");
+        assert!(telemetry.report().data.commands.is_empty());
+
+        telemetry.set_enabled(true);
+        telemetry.record_command("search_logs");
+        telemetry.record_command("search_logs");
+        telemetry.record_operation("search", Duration::from_millis(42), "completed");
+        telemetry.save();
+
+        let reloaded = Telemetry::default();
+        reloaded.init(dir.path(), true);
+        let report = reloaded.report();
+        assert_eq!(report.data.commands["search_logs"], 2);
+        assert_eq!(report.data.operations["search"].count, 1);
+        assert!(report.data.since.is_some());
+    }
+
+    #[test]
+    fn disabling_clears_statistics_and_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let telemetry = Telemetry::default();
+        telemetry.init(dir.path(), true);
+        telemetry.record_command("load_config");
+        telemetry.save();
+        assert!(dir.path().join(TELEMETRY_FILE).exists());
+
+        telemetry.set_enabled(false);
+        assert_eq!(telemetry.report().data, TelemetryData::default());
+        assert!(!dir.path().join(TELEMETRY_FILE).exists());
+    }
+}
@@ -59,6 +59,12 @@ pub mod strategies {
                     tags: vec![],
                     match_details: None,
                     matched_keywords: None,
+                    time_offset_secs: None,
+                    links: None,
+                    translation: None,
+                    source_files: None,
+                    original_length: None,
+                    fields: None,
                 },
             )
     }
@@ -204,6 +210,12 @@ pub mod strategies {
                     tags: vec![],
                     match_details: None,
                     matched_keywords: None,
+                    time_offset_secs: None,
+                    links: None,
+                    translation: None,
+                    source_files: None,
+                    original_length: None,
+                    fields: None,
                 }
             })
     }
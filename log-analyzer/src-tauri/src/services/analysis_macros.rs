@@ -0,0 +1,456 @@
+//! 分析宏
+//!
+//! 把一次排查中经 `invoke_action` 执行的操作（搜索、过滤、导出……）录制为命名宏，
+//! 保存在应用数据目录的 [`MACROS_FILE`] 中，之后可对其他工作区回放并得到汇总报告。
+//!
+//! 回放时按参数名替换上下文相关的值：
+//! - `workspaceId` 替换为目标工作区，`clientId` 替换为回放调用方
+//! - `searchId` 替换为本次回放中最近一次搜索的 ID
+//! - 字符串参数中的 `{workspaceId}` 替换为目标工作区（如导出路径）
+//! - `export_results` 的 `results` 在本次回放已有搜索时替换为该搜索的结果
+
+use std::path::Path;
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 应用数据目录下保存宏的文件名
+pub const MACROS_FILE: &str = "analysis_macros.json";
+/// 最多保存的宏数
+pub const MAX_MACROS: usize = 128;
+/// 单个宏的步骤数上限
+pub const MAX_STEPS: usize = 100;
+/// 报告中每个步骤结果保留的数组元素数
+pub const MAX_REPORT_ITEMS: usize = 50;
+/// 字符串参数中替换为目标工作区的占位符
+pub const WORKSPACE_PLACEHOLDER: &str = "{workspaceId}";
+const MAX_ID_LEN: usize = 64;
+
+/// 分析宏
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisMacro {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<MacroStep>,
+}
+
+/// 宏中的一步：操作名与参数（与 `invoke_action` 相同）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroStep {
+    pub action: String,
+    #[serde(default)]
+    pub args: Map<String, Value>,
+}
+
+impl AnalysisMacro {
+    /// 校验宏；`is_action` 判断操作名是否已注册
+    pub fn check(&self, is_action: impl Fn(&str) -> bool) -> Result<(), String> {
+        if !is_valid_macro_id(&self.id) {
+            return Err(format!(
+                "Invalid macro id '{}': use 1-{MAX_ID_LEN} letters, digits, '_', '-' or '.'",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err(format!("Macro '{}' must have a name", self.id));
+        }
+        if self.steps.is_empty() {
+            return Err(format!("Macro '{}' has no steps", self.id));
+        }
+        if self.steps.len() > MAX_STEPS {
+            return Err(format!(
+                "Too many steps ({}, max {MAX_STEPS})",
+                self.steps.len()
+            ));
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            if !is_action(&step.action) {
+                return Err(format!(
+                    "Step {}: unknown action '{}'",
+                    index + 1,
+                    step.action
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_valid_macro_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// 回放上下文
+#[derive(Debug, Clone, Default)]
+pub struct ReplayContext {
+    pub workspace_id: String,
+    pub client_id: Option<String>,
+    /// 本次回放中最近一次搜索的 ID
+    pub last_search_id: Option<String>,
+}
+
+/// 按回放上下文生成一步的实际参数
+///
+/// `accepted` 为该操作接受的参数键；只为操作接受的键注入上下文值。
+pub fn prepare_step_args(
+    step: &MacroStep,
+    accepted: &[String],
+    ctx: &ReplayContext,
+) -> Map<String, Value> {
+    let mut args = step.args.clone();
+    for value in args.values_mut() {
+        replace_placeholder(value, &ctx.workspace_id);
+    }
+    let accepts = |key: &str| accepted.iter().any(|a| a == key);
+    if accepts("workspaceId") {
+        args.insert(
+            "workspaceId".to_string(),
+            Value::String(ctx.workspace_id.clone()),
+        );
+    }
+    if let (true, Some(search_id)) = (accepts("searchId"), &ctx.last_search_id) {
+        args.insert("searchId".to_string(), Value::String(search_id.clone()));
+    }
+    if let (true, Some(client_id)) = (accepts("clientId"), &ctx.client_id) {
+        args.insert("clientId".to_string(), Value::String(client_id.clone()));
+    }
+    args
+}
+
+fn replace_placeholder(value: &mut Value, workspace_id: &str) {
+    match value {
+        Value::String(s) if s.contains(WORKSPACE_PLACEHOLDER) => {
+            *s = s.replace(WORKSPACE_PLACEHOLDER, workspace_id);
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|v| replace_placeholder(v, workspace_id)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|v| replace_placeholder(v, workspace_id)),
+        _ => {}
+    }
+}
+
+/// 报告中保留的步骤结果：过长的数组只保留前 [`MAX_REPORT_ITEMS`] 个元素
+pub fn summarize_result(value: Value) -> (Value, Option<usize>) {
+    match value {
+        Value::Array(mut items) if items.len() > MAX_REPORT_ITEMS => {
+            let total = items.len();
+            items.truncate(MAX_REPORT_ITEMS);
+            (Value::Array(items), Some(total))
+        }
+        other => (other, None),
+    }
+}
+
+/// 单步回放结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroStepReport {
+    pub index: usize,
+    pub action: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// 结果被截断时的原始元素数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_items: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 宏回放报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroReport {
+    pub macro_id: String,
+    pub macro_name: String,
+    pub workspace_id: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 因 `stopOnError` 未执行的步骤数
+    pub skipped: usize,
+    pub steps: Vec<MacroStepReport>,
+}
+
+impl MacroReport {
+    pub fn new(analysis_macro: &AnalysisMacro, workspace_id: &str) -> Self {
+        Self {
+            macro_id: analysis_macro.id.clone(),
+            macro_name: analysis_macro.name.clone(),
+            workspace_id: workspace_id.to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            duration_ms: 0,
+            succeeded: 0,
+            failed: 0,
+            skipped: 0,
+            steps: Vec::new(),
+        }
+    }
+
+    /// 记录一步的结果
+    pub fn push(&mut self, action: &str, started: Instant, outcome: Result<Value, String>) {
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let index = self.steps.len();
+        let step = match outcome {
+            Ok(value) => {
+                self.succeeded += 1;
+                let (result, total_items) = summarize_result(value);
+                MacroStepReport {
+                    index,
+                    action: action.to_string(),
+                    ok: true,
+                    duration_ms,
+                    result: Some(result),
+                    total_items,
+                    error: None,
+                }
+            }
+            Err(error) => {
+                self.failed += 1;
+                MacroStepReport {
+                    index,
+                    action: action.to_string(),
+                    ok: false,
+                    duration_ms,
+                    result: None,
+                    total_items: None,
+                    error: Some(error),
+                }
+            }
+        };
+        self.steps.push(step);
+    }
+}
+
+/// 进行中的录制
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroRecording {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<MacroStep>,
+}
+
+impl MacroRecording {
+    /// 追加一步；已有搜索步骤时，`export_results` 的结果由回放填充，不保存原始条目
+    pub fn record(&mut self, action: &str, mut args: Map<String, Value>) -> Result<(), String> {
+        if self.steps.len() >= MAX_STEPS {
+            return Err(format!("Macro recording is full (max {MAX_STEPS} steps)"));
+        }
+        let searched = self.steps.iter().any(|s| s.action == "search_logs");
+        if action == "export_results" && searched {
+            args.insert("results".to_string(), Value::Array(Vec::new()));
+        }
+        self.steps.push(MacroStep {
+            action: action.to_string(),
+            args,
+        });
+        Ok(())
+    }
+
+    pub fn finish(self) -> AnalysisMacro {
+        AnalysisMacro {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            steps: self.steps,
+        }
+    }
+}
+
+/// 当前录制（同一时间只有一个）
+pub static RECORDING: Lazy<Mutex<Option<MacroRecording>>> = Lazy::new(|| Mutex::new(None));
+
+/// 读取宏列表；文件不存在时返回空列表
+pub fn load_macros(path: &Path) -> Result<Vec<AnalysisMacro>, String> {
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            serde_json::from_slice(&bytes).map_err(|e| format!("Failed to parse macros: {e}"))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read macros: {e}")),
+    }
+}
+
+/// 保存宏列表（先写临时文件再替换）
+pub fn save_macros(path: &Path, macros: &[AnalysisMacro]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create data directory: {e}"))?;
+    }
+    let json = serde_json::to_vec_pretty(macros)
+        .map_err(|e| format!("Failed to serialize macros: {e}"))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write macros: {e}"))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to save macros: {e}"))
+}
+
+/// 插入或替换同 id 的宏
+pub fn upsert_macro(
+    macros: &mut Vec<AnalysisMacro>,
+    analysis_macro: AnalysisMacro,
+) -> Result<(), String> {
+    match macros.iter().position(|m| m.id == analysis_macro.id) {
+        Some(index) => macros[index] = analysis_macro,
+        None if macros.len() >= MAX_MACROS => {
+            return Err(format!("Too many macros (max {MAX_MACROS})"))
+        }
+        None => macros.push(analysis_macro),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn step(action: &str, args: Value) -> MacroStep {
+        MacroStep {
+            action: action.to_string(),
+            args: args.as_object().cloned().unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn macros_are_validated_against_known_actions() {
+        let mut m = AnalysisMacro {
+            id: "triage".to_string(),
+            name: "Triage".to_string(),
+            description: String::new(),
+            steps: vec![step("search_logs", json!({ "query": "ERROR" }))],
+        };
+        assert!(m.check(|a| a == "search_logs").is_ok());
+        assert!(m.check(|_| false).unwrap_err().contains("unknown action"));
+
+        m.steps.clear();
+        assert!(m.check(|_| true).unwrap_err().contains("no steps"));
+        m.id = "bad id".to_string();
+        assert!(m.check(|_| true).unwrap_err().contains("Invalid macro id"));
+    }
+
+    #[test]
+    fn step_args_follow_the_replay_context() {
+        let ctx = ReplayContext {
+            workspace_id: "ws-new".to_string(),
+            client_id: Some("client-1".to_string()),
+            last_search_id: Some("search-9".to_string()),
+        };
+        let accepted: Vec<String> = ["searchId", "offset", "limit", "clientId"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let args = prepare_step_args(
+            &step(
+                "fetch_search_page",
+                json!({ "searchId": "search-1", "offset": 0, "limit": 100 }),
+            ),
+            &accepted,
+            &ctx,
+        );
+        assert_eq!(args["searchId"], "search-9");
+        assert_eq!(args["clientId"], "client-1");
+        assert!(!args.contains_key("workspaceId"));
+
+        let accepted = vec!["workspaceId".to_string(), "savePath".to_string()];
+        let args = prepare_step_args(
+            &step(
+                "export_statistics",
+                json!({ "workspaceId": "ws-old", "savePath": "/tmp/{workspaceId}-stats.csv" }),
+            ),
+            &accepted,
+            &ctx,
+        );
+        assert_eq!(args["workspaceId"], "ws-new");
+        assert_eq!(args["savePath"], "/tmp/ws-new-stats.csv");
+    }
+
+    #[test]
+    fn recording_drops_exported_entries_after_a_search() {
+        let mut recording = MacroRecording {
+            id: "r".to_string(),
+            name: "R".to_string(),
+            description: String::new(),
+            steps: Vec::new(),
+        };
+        let export = json!({ "results": [{ "content": "x" }], "format": "csv" });
+        recording
+            .record("export_results", export.as_object().cloned().unwrap())
+            .unwrap();
+        recording.record("search_logs", Map::new()).unwrap();
+        recording
+            .record("export_results", export.as_object().cloned().unwrap())
+            .unwrap();
+
+        let m = recording.finish();
+        assert_eq!(m.steps[0].args["results"].as_array().unwrap().len(), 1);
+        assert!(m.steps[2].args["results"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn report_truncates_long_results() {
+        let m = AnalysisMacro {
+            id: "m".to_string(),
+            name: "M".to_string(),
+            description: String::new(),
+            steps: Vec::new(),
+        };
+        let mut report = MacroReport::new(&m, "ws");
+        report.push(
+            "get_recent_entries",
+            Instant::now(),
+            Ok(json!(vec![1; 120])),
+        );
+        report.push("load_workspace", Instant::now(), Err("boom".to_string()));
+
+        assert_eq!((report.succeeded, report.failed), (1, 1));
+        assert_eq!(report.steps[0].total_items, Some(120));
+        assert_eq!(
+            report.steps[0]
+                .result
+                .as_ref()
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            MAX_REPORT_ITEMS
+        );
+        assert_eq!(report.steps[1].error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn macros_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(MACROS_FILE);
+        let mut macros = load_macros(&path).unwrap();
+        upsert_macro(
+            &mut macros,
+            AnalysisMacro {
+                id: "triage".to_string(),
+                name: "Triage".to_string(),
+                description: String::new(),
+                steps: vec![step("search_logs", json!({ "query": "ERROR" }))],
+            },
+        )
+        .unwrap();
+        save_macros(&path, &macros).unwrap();
+        assert_eq!(load_macros(&path).unwrap(), macros);
+    }
+}
@@ -0,0 +1,595 @@
+//! 助手工具协议
+//!
+//! 以 MCP（Model Context Protocol）兼容的 JSON-RPC 2.0 暴露一组只读工具：
+//! 搜索、工作区概览与统计、重复内容模式、首次出现、静默区间等，供基于 LLM
+//! 的助手调用。本模块只负责协议与校验，工具的执行见 `commands::assistant`。
+//!
+//! 安全约束：
+//! - 工具均为只读，不暴露导入、删除、配置写入等操作
+//! - 参数按工具的 JSON Schema 严格校验：未知字段、类型不符、越界一律拒绝
+//! - 结果序列化后超过 [`MAX_RESULT_BYTES`] 时裁剪最长的数组 / 字符串，并附带说明
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::application::STATS_TABLES;
+
+/// 支持的 MCP 协议版本
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+/// 单次工具结果（序列化后）的字节上限
+pub const MAX_RESULT_BYTES: usize = 64 * 1024;
+/// 返回条目类工具的条数上限
+pub const MAX_TOOL_ENTRIES: u64 = 200;
+/// 统计类工具的 Top N 上限
+pub const MAX_TOOL_TOP_N: u64 = 50;
+/// 以 stdio 运行工具服务的启动参数
+pub const STDIO_FLAG: &str = "--mcp-stdio";
+/// 把 stdio 工具服务限定到一个工作区：`--mcp-workspace=<id>`
+pub const WORKSPACE_FLAG: &str = "--mcp-workspace=";
+
+// JSON-RPC 错误码
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// 一个助手工具
+#[derive(Debug, Clone)]
+pub struct AssistantTool {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// 直接转发的操作名；`None` 表示由工具服务特殊处理
+    pub action: Option<&'static str>,
+    pub input_schema: Value,
+}
+
+fn workspace_id() -> Value {
+    json!({ "type": "string", "minLength": 1, "description": "Workspace id" })
+}
+
+fn bounded(min: u64, max: u64, description: &str) -> Value {
+    json!({ "type": "integer", "minimum": min, "maximum": max, "description": description })
+}
+
+fn object_schema(properties: Value, required: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// 全部工具（按 `tools/list` 返回顺序）
+pub static TOOLS: Lazy<Vec<AssistantTool>> = Lazy::new(|| {
+    vec![
+        AssistantTool {
+            name: "search_logs",
+            description: "Search a workspace and return the first matching entries with the total match count.",
+            action: None,
+            input_schema: object_schema(
+                json!({
+                    "workspaceId": workspace_id(),
+                    "query": { "type": "string", "minLength": 1, "description": "Keywords separated by |, or a regex" },
+                    "maxResults": bounded(1, MAX_TOOL_ENTRIES, "Entries to return (default 50)"),
+                    "caseSensitive": { "type": "boolean" },
+                    "filters": { "type": "object", "description": "Search filters: levels, timeRange, filePattern" },
+                }),
+                &["workspaceId", "query"],
+            ),
+        },
+        AssistantTool {
+            name: "get_workspace_overview",
+            description: "Summarize a workspace: files, sizes, level counts and covered time range.",
+            action: Some("get_workspace_overview"),
+            input_schema: object_schema(json!({ "workspaceId": workspace_id() }), &["workspaceId"]),
+        },
+        AssistantTool {
+            name: "get_workspace_time_range",
+            description: "Earliest and latest timestamps in a workspace.",
+            action: Some("get_workspace_time_range"),
+            input_schema: object_schema(json!({ "workspaceId": workspace_id() }), &["workspaceId"]),
+        },
+        AssistantTool {
+            name: "get_statistics",
+            description: "Aggregated tables: levels by day and file, coverage gaps, duplicate distribution.",
+            action: None,
+            input_schema: object_schema(
+                json!({
+                    "workspaceId": workspace_id(),
+                    "tables": { "type": "array", "items": { "type": "string", "enum": STATS_TABLES } },
+                    "topN": bounded(1, MAX_TOOL_TOP_N, "Rows for top-N tables (default 20)"),
+                }),
+                &["workspaceId"],
+            ),
+        },
+        AssistantTool {
+            name: "get_duplicate_patterns",
+            description: "Most repeated log content across files, a quick view of recurring patterns.",
+            action: Some("get_dedup_report"),
+            input_schema: object_schema(
+                json!({
+                    "workspaceId": workspace_id(),
+                    "topN": bounded(1, MAX_TOOL_TOP_N, "Patterns to return"),
+                }),
+                &["workspaceId"],
+            ),
+        },
+        AssistantTool {
+            name: "get_recent_entries",
+            description: "Newest entries, optionally limited to some levels.",
+            action: Some("get_recent_entries"),
+            input_schema: object_schema(
+                json!({
+                    "workspaceId": workspace_id(),
+                    "levels": { "type": "array", "items": { "type": "string" } },
+                    "limit": bounded(1, MAX_TOOL_ENTRIES, "Entries to return"),
+                }),
+                &["workspaceId"],
+            ),
+        },
+        AssistantTool {
+            name: "get_entries_around_time",
+            description: "Entries from all (or some) files around a timestamp, ordered by time.",
+            action: Some("get_entries_around_time"),
+            input_schema: object_schema(
+                json!({
+                    "workspaceId": workspace_id(),
+                    "timestamp": { "type": "string", "minLength": 1 },
+                    "files": { "type": "array", "items": { "type": "string" } },
+                    "windowSecs": bounded(1, 86_400, "Seconds on each side"),
+                    "limit": bounded(1, MAX_TOOL_ENTRIES, "Entries to return"),
+                }),
+                &["workspaceId", "timestamp"],
+            ),
+        },
+        AssistantTool {
+            name: "find_first_occurrence",
+            description: "Earliest entry matching a query, with surrounding context lines.",
+            action: Some("find_first_occurrence"),
+            input_schema: object_schema(
+                json!({
+                    "workspaceId": workspace_id(),
+                    "query": { "type": "string", "minLength": 1 },
+                    "context": bounded(0, 20, "Context lines"),
+                }),
+                &["workspaceId", "query"],
+            ),
+        },
+        AssistantTool {
+            name: "find_silences",
+            description: "Gaps where files stopped logging for unusually long.",
+            action: Some("find_silences"),
+            input_schema: object_schema(
+                json!({
+                    "workspaceId": workspace_id(),
+                    "files": { "type": "array", "items": { "type": "string" } },
+                    "start": { "type": "string" },
+                    "end": { "type": "string" },
+                    "factor": { "type": "number" },
+                    "minGapSecs": bounded(1, 86_400 * 30, "Minimum gap in seconds"),
+                    "context": bounded(0, 20, "Context lines"),
+                }),
+                &["workspaceId"],
+            ),
+        },
+    ]
+});
+
+pub fn find_tool(name: &str) -> Option<&'static AssistantTool> {
+    TOOLS.iter().find(|tool| tool.name == name)
+}
+
+/// 按工具 Schema 校验参数（仅支持本模块用到的 JSON Schema 子集）
+pub fn validate_arguments(schema: &Value, args: &Value) -> Result<(), String> {
+    validate_value(schema, args, "arguments")
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!(
+                "{path} must be one of {}",
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("object") => {
+            let object = value
+                .as_object()
+                .ok_or_else(|| format!("{path} must be an object"))?;
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if object.get(required).is_none_or(Value::is_null) {
+                    return Err(format!("{path}.{required} is required"));
+                }
+            }
+            let Some(properties) = properties else {
+                return Ok(());
+            };
+            let strict = schema.get("additionalProperties") == Some(&Value::Bool(false));
+            for (key, item) in object {
+                match properties.get(key) {
+                    // 可选参数传 null 等同于未传
+                    Some(_) if item.is_null() => {}
+                    Some(item_schema) => {
+                        validate_value(item_schema, item, &format!("{path}.{key}"))?
+                    }
+                    None if strict => return Err(format!("{path}.{key} is not a known argument")),
+                    None => {}
+                }
+            }
+            Ok(())
+        }
+        Some("array") => {
+            let items = value
+                .as_array()
+                .ok_or_else(|| format!("{path} must be an array"))?;
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{path}[{i}]"))?;
+                }
+            }
+            Ok(())
+        }
+        Some("string") => {
+            let s = value
+                .as_str()
+                .ok_or_else(|| format!("{path} must be a string"))?;
+            let min = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0);
+            if (s.chars().count() as u64) < min {
+                return Err(format!("{path} must not be empty"));
+            }
+            Ok(())
+        }
+        Some("integer") => {
+            let n = value
+                .as_i64()
+                .ok_or_else(|| format!("{path} must be an integer"))?;
+            let min = schema.get("minimum").and_then(Value::as_i64);
+            let max = schema.get("maximum").and_then(Value::as_i64);
+            if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) {
+                return Err(format!(
+                    "{path} must be between {} and {}",
+                    min.map_or("-inf".to_string(), |m| m.to_string()),
+                    max.map_or("inf".to_string(), |m| m.to_string())
+                ));
+            }
+            Ok(())
+        }
+        Some("number") if !value.is_number() => Err(format!("{path} must be a number")),
+        Some("boolean") if !value.is_boolean() => Err(format!("{path} must be a boolean")),
+        _ => Ok(()),
+    }
+}
+
+/// 把结果裁剪到 `max_bytes` 以内：反复减半最长的数组，没有可裁剪的数组时截断最长
+/// 的字符串。返回是否发生裁剪。
+pub fn cap_result(value: &mut Value, max_bytes: usize) -> bool {
+    let mut truncated = false;
+    while serialized_len(value) > max_bytes {
+        let target = longest(value, &|v| v.as_array().map(Vec::len).filter(|&n| n > 1))
+            .or_else(|| longest(value, &|v| v.as_str().map(str::len).filter(|&n| n > 1)));
+        match target.and_then(|pointer| value.pointer_mut(&pointer)) {
+            Some(Value::Array(items)) => items.truncate(items.len() / 2),
+            Some(Value::String(s)) => {
+                let cut = (0..=s.len() / 2)
+                    .rev()
+                    .find(|&i| s.is_char_boundary(i))
+                    .unwrap_or(0);
+                s.truncate(cut);
+                s.push('…');
+            }
+            _ => break,
+        }
+        truncated = true;
+    }
+    truncated
+}
+
+fn serialized_len(value: &Value) -> usize {
+    serde_json::to_vec(value).map_or(0, |bytes| bytes.len())
+}
+
+/// 按 `size` 找出最大的节点，返回其 JSON Pointer
+fn longest(value: &Value, size: &dyn Fn(&Value) -> Option<usize>) -> Option<String> {
+    fn walk(
+        value: &Value,
+        pointer: &mut String,
+        size: &dyn Fn(&Value) -> Option<usize>,
+        best: &mut Option<(usize, String)>,
+    ) {
+        if let Some(n) = size(value) {
+            if best.as_ref().is_none_or(|(max, _)| n > *max) {
+                *best = Some((n, pointer.clone()));
+            }
+        }
+        let len = pointer.len();
+        match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    pointer.push_str(&format!("/{i}"));
+                    walk(item, pointer, size, best);
+                    pointer.truncate(len);
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map {
+                    pointer.push('/');
+                    pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                    walk(item, pointer, size, best);
+                    pointer.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut best = None;
+    walk(value, &mut String::new(), size, &mut best);
+    best.map(|(_, pointer)| pointer)
+}
+
+/// JSON-RPC 请求；没有 `id` 的是通知，不回复
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    #[serde(default)]
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// 解析一行 JSON-RPC 消息；失败时返回应回复的错误响应
+pub fn parse_request(line: &str) -> Result<RpcRequest, Value> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| failure(Value::Null, PARSE_ERROR, format!("Parse error: {e}")))?;
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let request: RpcRequest = serde_json::from_value(value)
+        .map_err(|e| failure(id.clone(), INVALID_REQUEST, format!("Invalid request: {e}")))?;
+    if request.jsonrpc != "2.0" {
+        return Err(failure(id, INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+    Ok(request)
+}
+
+pub fn success(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+pub fn failure(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message.into() } })
+}
+
+pub fn initialize_result() -> Value {
+    json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "log-analyzer", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+pub fn tools_list_result() -> Value {
+    let tools: Vec<Value> = TOOLS
+        .iter()
+        .map(|tool| {
+            json!({
+                "name": tool.name,
+                "description": tool.description,
+                "inputSchema": tool.input_schema,
+            })
+        })
+        .collect();
+    json!({ "tools": tools })
+}
+
+/// `tools/call` 的参数 → (工具, 已校验的参数)
+pub fn parse_tool_call(
+    params: &Value,
+) -> Result<(&'static AssistantTool, Map<String, Value>), String> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or("tools/call requires a tool name")?;
+    let tool = find_tool(name).ok_or_else(|| format!("Unknown tool: {name}"))?;
+    let args = match params.get("arguments") {
+        None | Some(Value::Null) => Value::Object(Map::new()),
+        Some(args) => args.clone(),
+    };
+    validate_arguments(&tool.input_schema, &args)?;
+    let Value::Object(mut args) = args else {
+        unreachable!("validated as an object");
+    };
+    args.retain(|_, v| !v.is_null());
+    Ok((tool, args))
+}
+
+/// 工具执行结果 → MCP `tools/call` 结果（JSON 文本内容，超限时裁剪）
+pub fn tool_result(outcome: Result<Value, String>) -> Value {
+    match outcome {
+        Ok(mut value) => {
+            let truncated = cap_result(&mut value, MAX_RESULT_BYTES);
+            let text = serde_json::to_string(&value).unwrap_or_default();
+            let mut content = vec![json!({ "type": "text", "text": text })];
+            if truncated {
+                content.push(json!({
+                    "type": "text",
+                    "text": format!(
+                        "Result truncated to {} KiB; narrow the query or lower the limits for complete data.",
+                        MAX_RESULT_BYTES / 1024
+                    ),
+                }));
+            }
+            json!({ "content": content, "isError": false })
+        }
+        Err(message) => json!({
+            "content": [{ "type": "text", "text": message }],
+            "isError": true,
+        }),
+    }
+}
+
+/// stdio 工具服务的启动选项；未传 [`STDIO_FLAG`] 时为 `None`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StdioOptions {
+    /// 限定可访问的工作区
+    pub workspace: Option<String>,
+}
+
+pub fn stdio_options(args: impl IntoIterator<Item = String>) -> Option<StdioOptions> {
+    let mut enabled = false;
+    let mut workspace = None;
+    for arg in args {
+        if arg == STDIO_FLAG {
+            enabled = true;
+        } else if let Some(id) = arg.strip_prefix(WORKSPACE_FLAG) {
+            workspace = Some(id.to_string()).filter(|id| !id.is_empty());
+        }
+    }
+    enabled.then_some(StdioOptions { workspace })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tools_have_strict_object_schemas_with_workspace() {
+        for tool in TOOLS.iter() {
+            let schema = &tool.input_schema;
+            assert_eq!(schema["type"], "object", "{}", tool.name);
+            assert_eq!(schema["additionalProperties"], false, "{}", tool.name);
+            assert!(
+                schema["required"]
+                    .as_array()
+                    .unwrap()
+                    .contains(&json!("workspaceId")),
+                "{}",
+                tool.name
+            );
+        }
+        let listed = tools_list_result();
+        assert_eq!(listed["tools"].as_array().unwrap().len(), TOOLS.len());
+        assert!(listed["tools"][0]["inputSchema"].is_object());
+    }
+
+    #[test]
+    fn forwarded_tools_name_registered_actions() {
+        let actions: Vec<&str> = crate::commands::actions::action_descriptors()
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        for tool in TOOLS.iter() {
+            if let Some(action) = tool.action {
+                assert!(actions.contains(&action), "{action}");
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_missing_and_out_of_range_arguments() {
+        let call =
+            |args: Value| parse_tool_call(&json!({ "name": "search_logs", "arguments": args }));
+
+        let (tool, args) = call(json!({ "workspaceId": "ws", "query": "error", "maxResults": 10, "caseSensitive": null }))
+            .unwrap();
+        assert_eq!(tool.name, "search_logs");
+        assert!(!args.contains_key("caseSensitive"));
+
+        assert!(call(json!({ "workspaceId": "ws" }))
+            .unwrap_err()
+            .contains("query"));
+        assert!(
+            call(json!({ "workspaceId": "ws", "query": "e", "sql": "drop" }))
+                .unwrap_err()
+                .contains("not a known argument")
+        );
+        assert!(
+            call(json!({ "workspaceId": "ws", "query": "e", "maxResults": 5000 }))
+                .unwrap_err()
+                .contains("between")
+        );
+        assert!(call(json!({ "workspaceId": 1, "query": "e" })).is_err());
+        assert!(call(json!("ws")).is_err());
+
+        let stats = parse_tool_call(&json!({
+            "name": "get_statistics",
+            "arguments": { "workspaceId": "ws", "tables": ["levelsByDay", "nope"] },
+        }));
+        assert!(stats.unwrap_err().contains("must be one of"));
+        assert!(parse_tool_call(&json!({ "name": "delete_workspace" })).is_err());
+    }
+
+    #[test]
+    fn caps_large_results() {
+        let mut value = json!({
+            "totalCount": 5000,
+            "entries": (0..5000).map(|i| json!({ "line": i, "content": "x".repeat(40) })).collect::<Vec<_>>(),
+        });
+        assert!(cap_result(&mut value, 4096));
+        assert!(serialized_len(&value) <= 4096);
+        assert_eq!(value["totalCount"], 5000);
+        assert!(!value["entries"].as_array().unwrap().is_empty());
+
+        let mut long = json!({ "a/b": { "content": "é".repeat(10_000) } });
+        assert!(cap_result(&mut long, 1024));
+        assert!(serialized_len(&long) <= 1024);
+
+        let mut small = json!({ "ok": true });
+        assert!(!cap_result(&mut small, 1024));
+
+        let oversized = json!({ "entries": vec!["x".repeat(1024); 128] });
+        let result = tool_result(Ok(oversized));
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["content"].as_array().unwrap().len(), 2);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.len() <= MAX_RESULT_BYTES);
+        assert_eq!(tool_result(Err("boom".into()))["isError"], true);
+    }
+
+    #[test]
+    fn parses_requests_and_reports_protocol_errors() {
+        let request = parse_request(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#).unwrap();
+        assert_eq!(request.method, "tools/list");
+        assert_eq!(request.id, Some(json!(1)));
+
+        let notification =
+            parse_request(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).unwrap();
+        assert!(notification.id.is_none());
+
+        let err = parse_request("{not json").unwrap_err();
+        assert_eq!(err["error"]["code"], PARSE_ERROR);
+        let err = parse_request(r#"{"jsonrpc":"1.0","id":7,"method":"x"}"#).unwrap_err();
+        assert_eq!(
+            (err["id"].clone(), err["error"]["code"].clone()),
+            (json!(7), json!(INVALID_REQUEST))
+        );
+
+        assert_eq!(initialize_result()["protocolVersion"], PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn stdio_options_from_args() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(stdio_options(args(&["app"])), None);
+        assert_eq!(
+            stdio_options(args(&["app", STDIO_FLAG])),
+            Some(StdioOptions { workspace: None })
+        );
+        assert_eq!(
+            stdio_options(args(&["app", "--mcp-workspace=ws-1", STDIO_FLAG])),
+            Some(StdioOptions {
+                workspace: Some("ws-1".to_string())
+            })
+        );
+    }
+}
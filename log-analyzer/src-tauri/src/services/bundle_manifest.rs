@@ -0,0 +1,318 @@
+//! 支持包清单识别
+//!
+//! 设备支持包通常附带 `manifest.json` 或 `info.txt`，记录设备序列号、固件版本
+//! 与采集时间。导入完成后取最浅层的清单文件解析，写入工作区元数据，
+//! 并据此生成建议的工作区名称与标签。
+//!
+//! - JSON：嵌套对象按 `a.b` 展平，只保留标量值
+//! - 文本：逐行解析 `key: value` 或 `key = value`
+//!
+//! 序列号 / 固件 / 采集时间按常见字段名（忽略大小写与分隔符）识别，
+//! 统一存为 `serial`、`firmware`、`collected_at`，其余字段存为 `manifest.<原键名>`。
+
+use std::collections::BTreeMap;
+
+use la_storage::{ContentAddressableStorage, MetadataStore, WorkspaceMetadataEntry};
+use serde::Serialize;
+
+/// 识别为清单的文件名（忽略大小写）
+pub const MANIFEST_FILE_NAMES: &[&str] = &["manifest.json", "info.txt"];
+/// 超过该大小的同名文件不视为清单
+const MAX_MANIFEST_BYTES: i64 = 1024 * 1024;
+/// 单个清单最多保留的其他字段数
+const MAX_EXTRA_FIELDS: usize = 64;
+const MAX_VALUE_LEN: usize = 256;
+
+pub const KEY_SERIAL: &str = "serial";
+pub const KEY_FIRMWARE: &str = "firmware";
+pub const KEY_COLLECTED_AT: &str = "collected_at";
+const EXTRA_KEY_PREFIX: &str = "manifest.";
+
+const SERIAL_ALIASES: &[&str] = &[
+    "serial",
+    "serialnumber",
+    "serialno",
+    "deviceserial",
+    "deviceserialnumber",
+    "sn",
+];
+const FIRMWARE_ALIASES: &[&str] = &[
+    "firmware",
+    "firmwareversion",
+    "fwversion",
+    "fw",
+    "softwareversion",
+    "swversion",
+];
+const COLLECTED_AT_ALIASES: &[&str] = &[
+    "collectiontime",
+    "collectedat",
+    "collectiondate",
+    "capturetime",
+    "createdat",
+    "generatedat",
+    "timestamp",
+];
+
+/// 从清单中解析出的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BundleManifest {
+    pub serial: Option<String>,
+    pub firmware: Option<String>,
+    pub collected_at: Option<String>,
+    /// 其他标量字段（展平后的原键名）
+    pub fields: BTreeMap<String, String>,
+}
+
+impl BundleManifest {
+    /// 按文件名选择解析方式；无法解析或没有任何字段时返回 `None`
+    pub fn parse(file_name: &str, content: &str) -> Option<Self> {
+        let mut pairs = Vec::new();
+        if file_name.to_lowercase().ends_with(".json") {
+            let value: serde_json::Value = serde_json::from_str(content).ok()?;
+            flatten_json("", &value, &mut pairs);
+        } else {
+            pairs = content
+                .lines()
+                .filter_map(|line| {
+                    let line = line.trim();
+                    if line.starts_with('#') {
+                        return None;
+                    }
+                    let (key, value) = line.split_once([':', '='])?;
+                    Some((key.trim().to_string(), unquote(value.trim()).to_string()))
+                })
+                .collect();
+        }
+
+        let mut manifest = Self::default();
+        for (key, value) in pairs {
+            if key.is_empty() || value.is_empty() {
+                continue;
+            }
+            let value: String = value.chars().take(MAX_VALUE_LEN).collect();
+            let normalized = normalize_key(&key);
+            let slot = if SERIAL_ALIASES.contains(&normalized.as_str()) {
+                &mut manifest.serial
+            } else if FIRMWARE_ALIASES.contains(&normalized.as_str()) {
+                &mut manifest.firmware
+            } else if COLLECTED_AT_ALIASES.contains(&normalized.as_str()) {
+                &mut manifest.collected_at
+            } else {
+                if manifest.fields.len() < MAX_EXTRA_FIELDS {
+                    manifest.fields.entry(key).or_insert(value);
+                }
+                continue;
+            };
+            // 同一字段出现多次时保留第一个（通常是最外层）
+            slot.get_or_insert(value);
+        }
+
+        (manifest != Self::default()).then_some(manifest)
+    }
+
+    /// 写入工作区元数据的键值对
+    pub fn to_metadata(&self) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        for (key, value) in [
+            (KEY_SERIAL, &self.serial),
+            (KEY_FIRMWARE, &self.firmware),
+            (KEY_COLLECTED_AT, &self.collected_at),
+        ] {
+            if let Some(value) = value {
+                out.push((key.to_string(), value.clone()));
+            }
+        }
+        out.extend(
+            self.fields
+                .iter()
+                .map(|(k, v)| (format!("{EXTRA_KEY_PREFIX}{k}"), v.clone())),
+        );
+        out
+    }
+}
+
+/// 清单信息：元数据、标签与建议名称
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestInfo {
+    pub metadata: Vec<WorkspaceMetadataEntry>,
+    /// 如 `serial:SN123`、`firmware:4.2.1`
+    pub tags: Vec<String>,
+    /// 如 `SN123 fw4.2.1 2026-03-01`；没有序列号与固件版本时为空
+    pub proposed_name: Option<String>,
+}
+
+impl ManifestInfo {
+    pub fn from_metadata(metadata: Vec<WorkspaceMetadataEntry>) -> Self {
+        let get = |key: &str| {
+            metadata
+                .iter()
+                .find(|m| m.key == key)
+                .map(|m| m.value.as_str())
+        };
+        let serial = get(KEY_SERIAL);
+        let firmware = get(KEY_FIRMWARE);
+        let collected_date = get(KEY_COLLECTED_AT).map(collection_date);
+
+        let tags = [("serial", serial), ("firmware", firmware)]
+            .into_iter()
+            .filter_map(|(tag, value)| value.map(|v| format!("{tag}:{v}")))
+            .chain(collected_date.as_ref().map(|d| format!("collected:{d}")))
+            .collect();
+
+        let proposed_name = if serial.is_some() || firmware.is_some() {
+            let parts: Vec<String> = [
+                serial.map(str::to_string),
+                firmware.map(|f| format!("fw{}", f.trim_start_matches(['v', 'V']))),
+                collected_date,
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            Some(parts.join(" "))
+        } else {
+            None
+        };
+
+        Self {
+            metadata,
+            tags,
+            proposed_name,
+        }
+    }
+}
+
+/// 查找并解析工作区中最浅层的清单，写入元数据；返回所用清单的虚拟路径
+pub async fn apply_bundle_manifest(
+    metadata_store: &MetadataStore,
+    cas: &ContentAddressableStorage,
+) -> la_core::error::Result<Option<String>> {
+    let candidates = metadata_store
+        .get_files_by_name(MANIFEST_FILE_NAMES)
+        .await?;
+    for file in candidates
+        .into_iter()
+        .filter(|f| f.size <= MAX_MANIFEST_BYTES)
+    {
+        let content = cas.read_content(&file.sha256_hash).await?;
+        let Some(manifest) =
+            BundleManifest::parse(&file.original_name, &String::from_utf8_lossy(&content))
+        else {
+            continue;
+        };
+        metadata_store
+            .set_workspace_metadata(&manifest.to_metadata(), &file.virtual_path)
+            .await?;
+        return Ok(Some(file.virtual_path));
+    }
+    Ok(None)
+}
+
+fn flatten_json(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, String)>) {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_json(&key, value, out);
+            }
+        }
+        Value::String(s) => out.push((prefix.to_string(), s.trim().to_string())),
+        Value::Number(n) => out.push((prefix.to_string(), n.to_string())),
+        Value::Bool(b) => out.push((prefix.to_string(), b.to_string())),
+        Value::Array(_) | Value::Null => {}
+    }
+}
+
+/// 取最后一段并去掉分隔符：`device.Serial_Number` → `serialnumber`
+fn normalize_key(key: &str) -> String {
+    key.rsplit('.')
+        .next()
+        .unwrap_or(key)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// 采集时间的日期部分；纯数字按 Unix 秒（或毫秒）解释
+fn collection_date(raw: &str) -> String {
+    let unix = match raw.parse::<i64>() {
+        Ok(n) if n > 100_000_000_000 => Some(n / 1000),
+        Ok(n) => Some(n),
+        Err(_) => la_search::parse_log_timestamp_to_unix(raw),
+    };
+    unix.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_nested_json_manifest() {
+        let manifest = BundleManifest::parse(
+            "manifest.json",
+            r#"{"device": {"serialNumber": "SN-42", "model": "X1"},
+                "firmware_version": "v4.2.1",
+                "collection_time": "2026-03-01T08:00:00Z",
+                "files": ["a.log"]}"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.serial.as_deref(), Some("SN-42"));
+        assert_eq!(manifest.firmware.as_deref(), Some("v4.2.1"));
+        assert_eq!(
+            manifest.fields.get("device.model").map(String::as_str),
+            Some("X1")
+        );
+        assert!(manifest
+            .to_metadata()
+            .contains(&("manifest.device.model".to_string(), "X1".to_string())));
+
+        assert!(BundleManifest::parse("manifest.json", "not json").is_none());
+        assert!(BundleManifest::parse("manifest.json", "{}").is_none());
+    }
+
+    #[test]
+    fn test_parses_text_manifest_and_proposes_name() {
+        let manifest = BundleManifest::parse(
+            "info.txt",
+            "# collected by agent\nSerial No: SN-7\nFW = 1.0.3\nCollected At: \"1772352000\"\n",
+        )
+        .unwrap();
+        let metadata = manifest
+            .to_metadata()
+            .into_iter()
+            .map(|(key, value)| WorkspaceMetadataEntry {
+                key,
+                value,
+                source: "b/info.txt".into(),
+                updated_at: 0,
+            })
+            .collect();
+
+        let info = ManifestInfo::from_metadata(metadata);
+        assert_eq!(
+            info.proposed_name.as_deref(),
+            Some("SN-7 fw1.0.3 2026-03-01")
+        );
+        assert_eq!(
+            info.tags,
+            vec!["serial:SN-7", "firmware:1.0.3", "collected:2026-03-01"]
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! 分块上传接收
+//!
+//! 通过文件对话框选择的超大压缩包（20 GB 以上）无法一次性经 IPC 传输。
+//! 前端按顺序发送分块，后端直接追加写入暂存文件并同步计算 SHA-256，
+//! 完成后只需核对长度与校验和，无需再读一遍文件。
+//!
+//! 分块携带起始偏移：重发已写入的分块会被忽略（便于前端超时重试），
+//! 偏移跳跃则拒绝，保证暂存文件与哈希状态一致。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+/// 同时进行的上传数上限
+const MAX_ACTIVE_UPLOADS: usize = 8;
+
+/// `begin_upload` 的参数
+#[derive(Debug, Clone)]
+pub struct UploadRequest {
+    pub workspace_id: String,
+    pub file_name: String,
+    pub total_size: u64,
+    /// 规范化后的十六进制 SHA-256（可选）
+    pub expected_sha256: Option<String>,
+    /// 发起上传的客户端会话，导入时沿用其租户范围
+    pub client_id: Option<String>,
+}
+
+/// 已完成并通过校验的上传
+#[derive(Debug, Clone)]
+pub struct CompletedUpload {
+    pub workspace_id: String,
+    pub file_name: String,
+    /// 上传专属目录（包含 `file_name` 子目录），导入后整体删除
+    pub upload_dir: PathBuf,
+    /// 作为导入源的目录
+    pub import_dir: PathBuf,
+    pub sha256: String,
+    pub size: u64,
+    pub client_id: Option<String>,
+}
+
+struct UploadSession {
+    request: UploadRequest,
+    upload_dir: PathBuf,
+    import_dir: PathBuf,
+    staging_path: PathBuf,
+    file: tokio::fs::File,
+    hasher: Sha256,
+    received: u64,
+}
+
+/// 进行中的上传会话
+pub struct UploadManager {
+    root: PathBuf,
+    sessions: Mutex<HashMap<String, Arc<tokio::sync::Mutex<UploadSession>>>>,
+}
+
+impl UploadManager {
+    /// `root` 为暂存根目录，每个上传使用其下的 `<upload_id>/` 子目录
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 创建暂存文件并返回上传 ID
+    pub async fn begin(&self, request: UploadRequest) -> Result<String, String> {
+        if self.sessions.lock().len() >= MAX_ACTIVE_UPLOADS {
+            return Err(format!(
+                "Too many active uploads (max {MAX_ACTIVE_UPLOADS})"
+            ));
+        }
+
+        let upload_id = uuid::Uuid::new_v4().to_string();
+        // 导入源必须是目录：以文件名为导入根，与 URL 导入的布局一致
+        let upload_dir = self.root.join(&upload_id);
+        let import_dir = upload_dir.join(&request.file_name);
+        let staging_path = import_dir.join(&request.file_name);
+        tokio::fs::create_dir_all(&import_dir)
+            .await
+            .map_err(|e| format!("Failed to create upload dir: {e}"))?;
+        let file = tokio::fs::File::create(&staging_path)
+            .await
+            .map_err(|e| format!("Failed to create staging file: {e}"))?;
+
+        let session = UploadSession {
+            request,
+            upload_dir,
+            import_dir,
+            staging_path,
+            file,
+            hasher: Sha256::new(),
+            received: 0,
+        };
+        self.sessions.lock().insert(
+            upload_id.clone(),
+            Arc::new(tokio::sync::Mutex::new(session)),
+        );
+        Ok(upload_id)
+    }
+
+    /// 追加一个分块，返回已接收的总字节数
+    pub async fn append(&self, upload_id: &str, offset: u64, data: &[u8]) -> Result<u64, String> {
+        let session = self.session(upload_id)?;
+        let mut session = session.lock().await;
+
+        let end = offset.saturating_add(data.len() as u64);
+        if end <= session.received {
+            // 重发的分块已写入
+            return Ok(session.received);
+        }
+        if offset != session.received {
+            return Err(format!(
+                "Unexpected chunk offset {offset}, expected {}",
+                session.received
+            ));
+        }
+        if end > session.request.total_size {
+            return Err(format!(
+                "Chunk exceeds declared size: {end} > {}",
+                session.request.total_size
+            ));
+        }
+
+        session
+            .file
+            .write_all(data)
+            .await
+            .map_err(|e| format!("Failed to write chunk: {e}"))?;
+        session.hasher.update(data);
+        session.received = end;
+        Ok(end)
+    }
+
+    /// 结束上传：核对长度与校验和，失败时删除暂存文件
+    pub async fn finish(&self, upload_id: &str) -> Result<CompletedUpload, String> {
+        let session = self
+            .sessions
+            .lock()
+            .remove(upload_id)
+            .ok_or_else(|| format!("Unknown upload: {upload_id}"))?;
+        let mut session = session.lock().await;
+
+        let result = Self::verify(&mut session).await;
+        if result.is_err() {
+            Self::remove_dir(&session.upload_dir).await;
+        }
+        result
+    }
+
+    /// 放弃上传并删除暂存文件；未知 ID 视为已取消
+    pub async fn cancel(&self, upload_id: &str) {
+        let Some(session) = self.sessions.lock().remove(upload_id) else {
+            return;
+        };
+        let session = session.lock().await;
+        Self::remove_dir(&session.upload_dir).await;
+    }
+
+    fn session(&self, upload_id: &str) -> Result<Arc<tokio::sync::Mutex<UploadSession>>, String> {
+        self.sessions
+            .lock()
+            .get(upload_id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown upload: {upload_id}"))
+    }
+
+    async fn verify(session: &mut UploadSession) -> Result<CompletedUpload, String> {
+        session
+            .file
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to flush staging file: {e}"))?;
+        session
+            .file
+            .sync_all()
+            .await
+            .map_err(|e| format!("Failed to sync staging file: {e}"))?;
+
+        if session.received != session.request.total_size {
+            return Err(format!(
+                "Upload incomplete: {} of {} bytes",
+                session.received, session.request.total_size
+            ));
+        }
+        let sha256 = format!("{:x}", std::mem::take(&mut session.hasher).finalize());
+        if let Some(expected) = &session.request.expected_sha256 {
+            if *expected != sha256 {
+                return Err(format!(
+                    "SHA-256 mismatch: expected {expected}, got {sha256}"
+                ));
+            }
+        }
+
+        tracing::debug!(
+            path = %session.staging_path.display(),
+            size = session.received,
+            "Chunked upload completed"
+        );
+        Ok(CompletedUpload {
+            workspace_id: session.request.workspace_id.clone(),
+            file_name: session.request.file_name.clone(),
+            upload_dir: session.upload_dir.clone(),
+            import_dir: session.import_dir.clone(),
+            sha256,
+            size: session.received,
+            client_id: session.request.client_id.clone(),
+        })
+    }
+
+    async fn remove_dir(dir: &Path) {
+        if let Err(e) = tokio::fs::remove_dir_all(dir).await {
+            tracing::warn!(path = %dir.display(), error = %e, "Failed to remove upload staging dir");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(total_size: u64, expected_sha256: Option<&str>) -> UploadRequest {
+        UploadRequest {
+            workspace_id: "ws".into(),
+            file_name: "bundle.tar.gz".into(),
+            total_size,
+            expected_sha256: expected_sha256.map(str::to_string),
+            client_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chunks_are_hashed_and_retries_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = UploadManager::new(dir.path().to_path_buf());
+        let id = manager
+            .begin(request(
+                3,
+                Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(manager.append(&id, 0, b"ab").await.unwrap(), 2);
+        // 重发同一分块
+        assert_eq!(manager.append(&id, 0, b"ab").await.unwrap(), 2);
+        assert!(manager.append(&id, 5, b"x").await.is_err());
+        assert!(manager.append(&id, 2, b"cd").await.is_err());
+        assert_eq!(manager.append(&id, 2, b"c").await.unwrap(), 3);
+
+        let done = manager.finish(&id).await.unwrap();
+        assert_eq!(done.size, 3);
+        assert_eq!(
+            std::fs::read(done.import_dir.join("bundle.tar.gz")).unwrap(),
+            b"abc"
+        );
+        assert!(manager.append(&id, 3, b"d").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_or_mismatched_upload_is_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = UploadManager::new(dir.path().to_path_buf());
+
+        let id = manager.begin(request(4, None)).await.unwrap();
+        manager.append(&id, 0, b"abc").await.unwrap();
+        assert!(manager.finish(&id).await.is_err());
+        assert!(!dir.path().join(&id).exists());
+
+        let id = manager
+            .begin(request(3, Some(&"0".repeat(64))))
+            .await
+            .unwrap();
+        manager.append(&id, 0, b"abc").await.unwrap();
+        assert!(manager.finish(&id).await.unwrap_err().contains("mismatch"));
+        assert!(!dir.path().join(&id).exists());
+    }
+}
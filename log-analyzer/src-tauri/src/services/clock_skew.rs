@@ -0,0 +1,73 @@
+//! 文件间时钟偏移估算
+//!
+//! 不同主机的时钟常有数秒到数分钟的偏差，直接按时间戳合并会得到错误的先后顺序。
+//! 若同一事件（如请求 ID、部署标记）同时出现在多个文件中，可把它当作对齐锚点：
+//! 以参考文件中锚点行的时间为准，其他文件的偏移 = 参考时间 − 本文件锚点时间。
+
+use la_core::utils::parse_metadata;
+use serde::Serialize;
+
+/// 单个文件的偏移估算结果
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockSkewEstimate {
+    pub virtual_path: String,
+    /// 锚点所在行号（1 起）；未找到带时间戳的锚点行时为 `None`
+    pub marker_line: Option<usize>,
+    /// 锚点行的原始时间戳文本
+    pub marker_timestamp: Option<String>,
+    /// 建议偏移（秒），加到本文件时间戳上即与参考文件对齐
+    pub suggested_offset_secs: Option<i64>,
+}
+
+/// 查找第一个包含 `marker` 且能解析出时间戳的行，返回 (行号, 时间戳文本, Unix 秒)
+pub fn find_marker_timestamp(content: &str, marker: &str) -> Option<(usize, String, i64)> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains(marker))
+        .find_map(|(index, line)| {
+            let (timestamp, _) = parse_metadata(line);
+            let unix = la_search::parse_log_timestamp_to_unix(&timestamp)?;
+            Some((index + 1, timestamp, unix))
+        })
+}
+
+/// 以参考文件锚点时间估算目标文件偏移；参考锚点缺失时所有建议偏移均为 `None`
+pub fn estimate(
+    reference_unix: Option<i64>,
+    virtual_path: String,
+    content: &str,
+    marker: &str,
+) -> ClockSkewEstimate {
+    let found = find_marker_timestamp(content, marker);
+    ClockSkewEstimate {
+        virtual_path,
+        marker_line: found.as_ref().map(|(line, _, _)| *line),
+        suggested_offset_secs: reference_unix
+            .zip(found.as_ref().map(|(_, _, unix)| *unix))
+            .map(|(reference, local)| reference - local),
+        marker_timestamp: found.map(|(_, timestamp, _)| timestamp),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_aligns_marker_with_reference() {
+        let reference =
+            "2024-01-15 10:00:00 INFO start\n2024-01-15 10:00:30 INFO req-42 accepted\n";
+        let lagging = "2024-01-15 09:59:00 INFO boot\nreq-42 without timestamp\n2024-01-15 09:59:45 INFO req-42 handled\n";
+
+        let (_, _, reference_unix) = find_marker_timestamp(reference, "req-42").unwrap();
+        let result = estimate(Some(reference_unix), "b.log".into(), lagging, "req-42");
+        assert_eq!(result.marker_line, Some(3));
+        assert_eq!(result.suggested_offset_secs, Some(45));
+
+        let missing = estimate(Some(reference_unix), "c.log".into(), "no marker", "req-42");
+        assert_eq!(missing.marker_line, None);
+        assert_eq!(missing.suggested_offset_secs, None);
+    }
+}
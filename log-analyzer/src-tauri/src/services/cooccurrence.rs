@@ -0,0 +1,220 @@
+//! 关键词共现统计：哪些关键词总在同一行或同一时间段内一起出现
+//!
+//! 故障期间相关的错误往往成簇出现（连接超时紧跟着重试耗尽）。基于一次搜索的结果集，
+//! 统计查询关键词两两之间的共现次数：
+//!
+//! - 同行：两个关键词命中同一行
+//! - 时间窗口：命中 A 的行前后 `window_secs` 秒内存在命中 B 的行（时间已计入文件时钟偏移）
+
+use std::collections::HashMap;
+
+use la_core::models::LogEntry;
+use la_core::utils::TimestampParser;
+use serde::Serialize;
+
+/// 矩阵最多包含的关键词数
+pub const MAX_TERMS: usize = 32;
+
+/// 共现矩阵；`same_line[i][j]` 与 `within_window[i][j]` 的行列顺序与 `terms` 一致
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CooccurrenceMatrix {
+    pub terms: Vec<String>,
+    /// 每个关键词命中的行数
+    pub term_counts: Vec<usize>,
+    /// 同时命中两个关键词的行数（对称，对角线等于 `term_counts`）
+    pub same_line: Vec<Vec<usize>>,
+    /// 命中行 i 的前后窗口内存在命中 j 的行的次数（不对称）；未指定窗口时为 None
+    pub within_window: Option<Vec<Vec<usize>>>,
+    pub window_secs: Option<i64>,
+    /// 参与统计的条目数
+    pub entries_analyzed: usize,
+    /// 时间戳无法解析、未参与窗口统计的条目数
+    pub entries_without_time: usize,
+    /// 结果集超过读取上限，只统计了前一部分
+    pub truncated: bool,
+}
+
+/// 逐条累计共现；关键词集合在创建时确定
+pub struct CooccurrenceCounter {
+    terms: Vec<String>,
+    index: HashMap<String, usize>,
+    term_counts: Vec<usize>,
+    same_line: Vec<Vec<usize>>,
+    window_secs: Option<i64>,
+    /// (校正后的 Unix 秒, 命中的关键词下标)
+    timed: Vec<(i64, Vec<usize>)>,
+    entries_analyzed: usize,
+    entries_without_time: usize,
+}
+
+impl CooccurrenceCounter {
+    /// 重复的关键词只保留第一次出现，超过 [`MAX_TERMS`] 的部分被忽略
+    pub fn new(terms: Vec<String>, window_secs: Option<i64>) -> Self {
+        let mut unique = Vec::new();
+        let mut index = HashMap::new();
+        for term in terms {
+            if unique.len() == MAX_TERMS {
+                break;
+            }
+            if !index.contains_key(&term) {
+                index.insert(term.clone(), unique.len());
+                unique.push(term);
+            }
+        }
+        let n = unique.len();
+        Self {
+            terms: unique,
+            index,
+            term_counts: vec![0; n],
+            same_line: vec![vec![0; n]; n],
+            window_secs: window_secs.map(|w| w.max(0)),
+            timed: Vec::new(),
+            entries_analyzed: 0,
+            entries_without_time: 0,
+        }
+    }
+
+    pub fn record(&mut self, entry: &LogEntry) {
+        self.entries_analyzed += 1;
+        let mut hits: Vec<usize> = entry
+            .matched_keywords
+            .iter()
+            .flatten()
+            .filter_map(|k| self.index.get(k).copied())
+            .collect();
+        hits.sort_unstable();
+        hits.dedup();
+        if hits.is_empty() {
+            return;
+        }
+
+        for &a in &hits {
+            self.term_counts[a] += 1;
+            for &b in &hits {
+                self.same_line[a][b] += 1;
+            }
+        }
+
+        if self.window_secs.is_none() {
+            return;
+        }
+        match TimestampParser::parse_naive_datetime(&entry.timestamp) {
+            Some(at) => {
+                let secs = at.and_utc().timestamp() + entry.time_offset_secs.unwrap_or(0);
+                self.timed.push((secs, hits));
+            }
+            None => self.entries_without_time += 1,
+        }
+    }
+
+    pub fn finish(self) -> CooccurrenceMatrix {
+        let within_window = self
+            .window_secs
+            .map(|window| window_counts(self.terms.len(), &self.timed, window));
+        CooccurrenceMatrix {
+            terms: self.terms,
+            term_counts: self.term_counts,
+            same_line: self.same_line,
+            within_window,
+            window_secs: self.window_secs,
+            entries_analyzed: self.entries_analyzed,
+            entries_without_time: self.entries_without_time,
+            truncated: false,
+        }
+    }
+}
+
+/// 对每条命中 i 的行，检查 `[t - window, t + window]` 内是否有命中 j 的行
+fn window_counts(n: usize, timed: &[(i64, Vec<usize>)], window: i64) -> Vec<Vec<usize>> {
+    let mut times: Vec<Vec<i64>> = vec![Vec::new(); n];
+    for (secs, hits) in timed {
+        for &term in hits {
+            times[term].push(*secs);
+        }
+    }
+    for list in &mut times {
+        list.sort_unstable();
+    }
+
+    let mut counts = vec![vec![0; n]; n];
+    for (secs, hits) in timed {
+        let (lo, hi) = (secs.saturating_sub(window), secs.saturating_add(window));
+        for (j, list) in times.iter().enumerate() {
+            let first = list.partition_point(|&t| t < lo);
+            if list.get(first).is_some_and(|&t| t <= hi) {
+                for &i in hits {
+                    counts[i][j] += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, keywords: &[&str]) -> LogEntry {
+        LogEntry {
+            id: 0,
+            timestamp: timestamp.into(),
+            level: "ERROR".into(),
+            file: "app.log".into(),
+            real_path: "app.log".into(),
+            line: 1,
+            content: "".into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: Some(keywords.iter().map(|k| k.to_string()).collect()),
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn test_same_line_and_window_counts() {
+        let terms = ["timeout", "retry", "disk"].map(String::from).to_vec();
+        let mut counter = CooccurrenceCounter::new(terms, Some(5));
+        counter.record(&entry("2024-01-01 10:00:00", &["timeout", "retry"]));
+        counter.record(&entry("2024-01-01 10:00:03", &["retry"]));
+        counter.record(&entry("2024-01-01 10:00:30", &["timeout"]));
+        counter.record(&entry("2024-01-01 11:00:00", &["disk"]));
+        counter.record(&entry("garbled", &["disk", "timeout"]));
+
+        let matrix = counter.finish();
+        assert_eq!(matrix.term_counts, vec![3, 2, 2]);
+        assert_eq!(matrix.same_line[0][1], 1);
+        assert_eq!(matrix.same_line[1][0], 1);
+        assert_eq!(matrix.same_line[0][2], 1);
+        assert_eq!(matrix.same_line[1][2], 0);
+        assert_eq!(matrix.entries_analyzed, 5);
+        assert_eq!(matrix.entries_without_time, 1);
+
+        let window = matrix.within_window.unwrap();
+        // 10:00:00 的 timeout 附近有 retry；10:00:30 的没有
+        assert_eq!(window[0][1], 1);
+        // 两条 retry 附近都有 timeout（10:00:00）
+        assert_eq!(window[1][0], 2);
+        assert_eq!(window[2][0], 0);
+        assert_eq!(window[2][2], 1);
+    }
+
+    #[test]
+    fn test_duplicate_terms_and_no_window() {
+        let terms = ["error", "error", "warn"].map(String::from).to_vec();
+        let mut counter = CooccurrenceCounter::new(terms, None);
+        counter.record(&entry("2024-01-01 10:00:00", &["error", "other"]));
+
+        let matrix = counter.finish();
+        assert_eq!(matrix.terms, vec!["error", "warn"]);
+        assert_eq!(matrix.same_line, vec![vec![1, 0], vec![0, 0]]);
+        assert!(matrix.within_window.is_none());
+        assert_eq!(matrix.entries_without_time, 0);
+    }
+}
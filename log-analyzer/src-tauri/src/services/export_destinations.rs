@@ -0,0 +1,273 @@
+//! 导出目的地
+//!
+//! 导出内容渲染为 CSV / JSON 后交给目的地：
+//! - `file`：写入下载目录（默认）
+//! - `clipboard`：文本返回前端写入系统剪贴板，受 `export.clipboard_max_bytes` 限制
+//! - `sftp`：调用 OpenSSH `sftp` 以 BatchMode 上传，只支持密钥 / agent 认证
+//! - `command`：执行配置中按名称定义的命令钩子，参数 `{path}` 为导出文件路径
+//!
+//! SFTP 与命令钩子先把内容写入临时目录（文件名取自 `savePath`），执行结束后删除。
+//! 以下函数均为同步阻塞调用，调用方应放在 `spawn_blocking` 中。
+
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use la_core::models::config::{ExportCommand, ExportConfig};
+use serde::Deserialize;
+
+/// 失败时附带的 stderr 末尾长度
+const STDERR_TAIL_BYTES: usize = 2048;
+
+/// 导出目的地
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ExportDestination {
+    #[default]
+    File,
+    Clipboard,
+    #[serde(rename_all = "camelCase")]
+    Sftp {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+        #[serde(default)]
+        user: Option<String>,
+        /// 远端完整路径（含文件名）
+        remote_path: String,
+    },
+    Command {
+        name: String,
+    },
+}
+
+/// `export_results` 的可选参数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    #[serde(default)]
+    pub destination: ExportDestination,
+}
+
+/// 校验 SFTP 目标，避免主机名 / 用户名被解释为 ssh 选项或注入批处理命令
+pub fn validate_sftp_target(
+    host: &str,
+    user: Option<&str>,
+    remote_path: &str,
+) -> Result<(), String> {
+    let valid_name = |s: &str| {
+        !s.is_empty()
+            && !s.starts_with('-')
+            && s.chars().all(|c| {
+                c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':' | '[' | ']')
+            })
+    };
+    if !valid_name(host) {
+        return Err(format!("Invalid SFTP host: {host}"));
+    }
+    if let Some(user) = user {
+        if !valid_name(user) || user.contains([':', '[', ']']) {
+            return Err(format!("Invalid SFTP user: {user}"));
+        }
+    }
+    if remote_path.trim().is_empty()
+        || remote_path.ends_with('/')
+        || remote_path.contains(['\n', '\r', '"'])
+    {
+        return Err("SFTP remote path must be a file path without quotes or newlines".to_string());
+    }
+    Ok(())
+}
+
+/// 剪贴板文本：去掉 CSV 的 BOM，超过上限时报错
+pub fn clipboard_text(content: Vec<u8>, max_bytes: u64) -> Result<String, String> {
+    if content.len() as u64 > max_bytes {
+        return Err(format!(
+            "Export is {} bytes, clipboard limit is {max_bytes} bytes",
+            content.len()
+        ));
+    }
+    let text = String::from_utf8(content).map_err(|e| e.to_string())?;
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// 上传到 SFTP，返回 `sftp://` 位置
+pub fn upload_sftp(
+    content: &[u8],
+    file_name: &str,
+    host: &str,
+    port: Option<u16>,
+    user: Option<&str>,
+    remote_path: &str,
+    config: &ExportConfig,
+) -> Result<String, String> {
+    validate_sftp_target(host, user, remote_path)?;
+    let staging = stage(content, file_name)?;
+    let local = staging.path().join(file_name);
+
+    let target = match user {
+        Some(user) => format!("{user}@{host}"),
+        None => host.to_string(),
+    };
+    let mut command = Command::new(&config.sftp_program);
+    command.args(["-b", "-", "-o", "BatchMode=yes"]);
+    if let Some(port) = port {
+        command.args(["-P", &port.to_string()]);
+    }
+    command.arg(&target);
+    let batch = format!("put \"{}\" \"{remote_path}\"\n", local.display());
+    run(command, Some(batch.as_bytes()), config.timeout_secs)?;
+
+    let port = port.map(|p| format!(":{p}")).unwrap_or_default();
+    Ok(format!(
+        "sftp://{target}{port}/{}",
+        remote_path.trim_start_matches('/')
+    ))
+}
+
+/// 执行命令钩子，返回钩子名称
+pub fn run_command_hook(
+    content: &[u8],
+    file_name: &str,
+    format: &str,
+    hook: &ExportCommand,
+    config: &ExportConfig,
+) -> Result<String, String> {
+    let staging = stage(content, file_name)?;
+    let local = staging.path().join(file_name);
+    let local = local.to_string_lossy();
+
+    let mut command = Command::new(&hook.program);
+    command.args(
+        hook.args
+            .iter()
+            .map(|arg| arg.replace("{path}", &local).replace("{format}", format)),
+    );
+    run(command, None, config.timeout_secs)?;
+    Ok(format!("command:{}", hook.name))
+}
+
+fn stage(content: &[u8], file_name: &str) -> Result<tempfile::TempDir, String> {
+    let dir = tempfile::tempdir().map_err(|e| format!("Failed to create staging dir: {e}"))?;
+    std::fs::write(dir.path().join(file_name), content)
+        .map_err(|e| format!("Failed to stage export: {e}"))?;
+    Ok(dir)
+}
+
+/// 运行外部程序并等待结束；超时后终止。非零退出码时返回 stderr 末尾
+fn run(mut command: Command, stdin: Option<&[u8]>, timeout_secs: u64) -> Result<(), String> {
+    let program = Path::new(command.get_program())
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let mut child = command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {program}: {e}"))?;
+
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input)
+            .map_err(|e| format!("Failed to write to {program}: {e}"))?;
+    }
+    // stderr 在独立线程读取，避免输出填满管道后子进程阻塞
+    let stderr = child.stderr.take().map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            buf
+        })
+    });
+
+    let status = wait_with_timeout(&mut child, Duration::from_secs(timeout_secs))
+        .map_err(|e| format!("Failed to wait for {program}: {e}"))?;
+    let stderr = stderr
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    match status {
+        Some(status) if status.success() => Ok(()),
+        Some(status) => {
+            let stderr = String::from_utf8_lossy(&stderr);
+            let tail_start = stderr
+                .char_indices()
+                .map(|(i, _)| i)
+                .find(|&i| stderr.len() - i <= STDERR_TAIL_BYTES)
+                .unwrap_or(stderr.len());
+            Err(format!(
+                "{program} exited with {status}: {}",
+                stderr[tail_start..].trim()
+            ))
+        }
+        None => Err(format!("{program} timed out after {timeout_secs} s")),
+    }
+}
+
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Duration,
+) -> std::io::Result<Option<std::process::ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sftp_target_rejects_option_and_batch_injection() {
+        assert!(validate_sftp_target("logs.example.com", Some("ops"), "/srv/in/r.csv").is_ok());
+        assert!(validate_sftp_target("-oProxyCommand=x", None, "/r.csv").is_err());
+        assert!(validate_sftp_target("host", Some("a b"), "/r.csv").is_err());
+        assert!(validate_sftp_target("host", None, "/r.csv\"\n!rm -rf /").is_err());
+        assert!(validate_sftp_target("host", None, "/srv/in/").is_err());
+    }
+
+    #[test]
+    fn test_clipboard_text_is_bounded_and_strips_bom() {
+        let csv = "\u{feff}a,b\n".as_bytes().to_vec();
+        assert_eq!(clipboard_text(csv.clone(), 1024).unwrap(), "a,b\n");
+        assert!(clipboard_text(csv, 4).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_hook_receives_staged_path() {
+        let out = tempfile::tempdir().unwrap();
+        let copy = out.path().join("copy.json");
+        let hook = ExportCommand {
+            name: "copy".into(),
+            program: "cp".into(),
+            args: vec!["{path}".into(), copy.to_string_lossy().to_string()],
+        };
+        let config = ExportConfig::default();
+        let location = run_command_hook(b"[]", "results.json", "json", &hook, &config).unwrap();
+        assert_eq!(location, "command:copy");
+        assert_eq!(std::fs::read(&copy).unwrap(), b"[]");
+
+        let failing = ExportCommand {
+            name: "fail".into(),
+            program: "false".into(),
+            args: vec![],
+        };
+        assert!(run_command_hook(b"[]", "r.json", "json", &failing, &config).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! 外部链接解析
+//!
+//! 按配置中的链接模板（`links.templates`）从日志内容捕获字段并生成 URL，
+//! 在条目返回前端或导出前填充到 `LogEntry::links`。模板保存后立即生效。
+
+use std::sync::Arc;
+
+use la_core::models::config::{LinkTemplate, LinksConfig};
+use la_core::models::{ExternalLink, LogEntry};
+use parking_lot::RwLock;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::{Captures, Regex};
+
+/// 每条日志最多解析的链接数，避免异常内容产生大量链接
+const MAX_LINKS_PER_ENTRY: usize = 16;
+/// 捕获值编码时保留 RFC 3986 的非保留字符
+const CAPTURE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+struct CompiledTemplate {
+    template: LinkTemplate,
+    regex: Regex,
+}
+
+/// 已编译的启用模板集合
+#[derive(Default)]
+pub struct LinkResolver {
+    templates: Vec<CompiledTemplate>,
+}
+
+impl LinkResolver {
+    /// 编译启用的模板；无效模板记录警告后跳过
+    pub fn from_config(config: &LinksConfig) -> Self {
+        let templates = config
+            .templates
+            .iter()
+            .filter(|t| t.enabled)
+            .filter_map(|template| match Regex::new(&template.pattern) {
+                Ok(regex) => Some(CompiledTemplate {
+                    template: template.clone(),
+                    regex,
+                }),
+                Err(e) => {
+                    tracing::warn!(id = %template.id, error = %e, "Skipping invalid link template");
+                    None
+                }
+            })
+            .collect();
+        Self { templates }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// 解析一段内容中的所有链接（按模板顺序，同一 URL 只保留一次）
+    pub fn resolve(&self, content: &str) -> Vec<ExternalLink> {
+        let mut links: Vec<ExternalLink> = Vec::new();
+        for compiled in &self.templates {
+            for caps in compiled.regex.captures_iter(content) {
+                if links.len() >= MAX_LINKS_PER_ENTRY {
+                    return links;
+                }
+                let text = caps
+                    .get(1)
+                    .or_else(|| caps.get(0))
+                    .map(|m| m.as_str().to_string())
+                    .unwrap_or_default();
+                let url = expand_url(&compiled.template.url_template, &caps);
+                if links.iter().any(|l| l.url == url) {
+                    continue;
+                }
+                links.push(ExternalLink {
+                    template_id: compiled.template.id.clone(),
+                    label: compiled.template.label.clone(),
+                    text,
+                    url,
+                });
+            }
+        }
+        links
+    }
+
+    /// 为条目填充链接；没有命中的条目保持 `None`
+    pub fn annotate(&self, entries: &mut [LogEntry]) {
+        if self.is_empty() {
+            return;
+        }
+        for entry in entries {
+            let links = self.resolve(&entry.content);
+            entry.links = (!links.is_empty()).then_some(links);
+        }
+    }
+}
+
+/// 替换 `{0}`、`{1}`、`{name}` 占位符；捕获值做 URL 编码，未知占位符原样保留
+fn expand_url(template: &str, caps: &Captures<'_>) -> String {
+    let mut out = String::with_capacity(template.len() + 16);
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            out.push_str(&rest[open..]);
+            return out;
+        };
+        let key = &after[..close];
+        let value = match key.parse::<usize>() {
+            Ok(index) => caps.get(index),
+            Err(_) => caps.name(key),
+        };
+        match value {
+            Some(m) => out.extend(utf8_percent_encode(m.as_str(), CAPTURE_ENCODE_SET)),
+            None => out.push_str(&rest[open..open + close + 2]),
+        }
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 全局链接模板注册表（配置保存后替换整个解析器）
+#[derive(Default)]
+pub struct ExternalLinkRegistry {
+    resolver: RwLock<Arc<LinkResolver>>,
+}
+
+impl ExternalLinkRegistry {
+    pub fn configure(&self, config: &LinksConfig) {
+        *self.resolver.write() = Arc::new(LinkResolver::from_config(config));
+    }
+
+    pub fn resolver(&self) -> Arc<LinkResolver> {
+        Arc::clone(&self.resolver.read())
+    }
+
+    pub fn annotate(&self, entries: &mut [LogEntry]) {
+        self.resolver().annotate(entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(id: &str, pattern: &str, url: &str) -> LinkTemplate {
+        LinkTemplate {
+            id: id.into(),
+            label: id.to_uppercase(),
+            pattern: pattern.into(),
+            url_template: url.into(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_resolve_expands_captures() {
+        let resolver = LinkResolver::from_config(&LinksConfig {
+            templates: vec![
+                template(
+                    "jira",
+                    r"\b([A-Z]+-\d+)\b",
+                    "https://jira.example.com/browse/{1}",
+                ),
+                template(
+                    "trace",
+                    r"trace=(?P<id>[0-9a-f]+)",
+                    "https://tracing.example.com/trace/{id}?q={0}",
+                ),
+            ],
+        });
+
+        let links = resolver.resolve("OPS-12 retried, see OPS-12 and DB-7 trace=ab12");
+        let urls: Vec<&str> = links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://jira.example.com/browse/OPS-12",
+                "https://jira.example.com/browse/DB-7",
+                "https://tracing.example.com/trace/ab12?q=trace%3Dab12",
+            ]
+        );
+        assert_eq!(links[0].text, "OPS-12");
+        assert_eq!(links[2].label, "TRACE");
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_templates_are_skipped() {
+        let mut disabled = template("off", "x", "https://example.com/{0}");
+        disabled.enabled = false;
+        let resolver = LinkResolver::from_config(&LinksConfig {
+            templates: vec![disabled, template("bad", "(", "https://example.com/")],
+        });
+        assert!(resolver.is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+//! 字段提取器
+//!
+//! 工作区级的命名提取规则（正则命名捕获组、JSON 指针、key=value 对），
+//! 保存在工作区设置 [`FIELD_EXTRACTORS_SETTING`] 中。条目返回前端前按规则
+//! 从内容提取字段填充到 `LogEntry::fields`，作为展示列使用。
+
+use std::collections::BTreeMap;
+
+use la_core::models::LogEntry;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// 工作区设置键：字段提取器列表（JSON）
+pub const FIELD_EXTRACTORS_SETTING: &str = "field_extractors";
+/// 每个工作区最多保存的提取器数
+pub const MAX_EXTRACTORS: usize = 32;
+/// `test_extractor` 单次最多预览的样例行数
+pub const MAX_SAMPLE_LINES: usize = 200;
+/// 每条日志最多提取的字段数，避免异常内容产生大量字段
+const MAX_FIELDS_PER_ENTRY: usize = 64;
+const MAX_NAME_LEN: usize = 64;
+const MAX_VALUE_LEN: usize = 1024;
+/// 编译后正则的大小上限（字节）
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// 命名字段提取器
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldExtractor {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: ExtractorKind,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 提取方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ExtractorKind {
+    /// 正则：每个命名捕获组产生一个同名字段
+    Regex { pattern: String },
+    /// JSON：字段名 → JSON 指针（RFC 6901）；行内 JSON 从第一个 `{` 开始解析
+    Json { pointers: BTreeMap<String, String> },
+    /// key=value 对：`pair_delimiter` 分隔各对，`kv_separator` 分隔键与值
+    KeyValue {
+        pair_delimiter: String,
+        kv_separator: String,
+    },
+}
+
+/// 单行样例的提取结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionPreview {
+    pub line: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+enum CompiledKind {
+    Regex(Regex),
+    Json(BTreeMap<String, String>),
+    KeyValue {
+        pair_delimiter: String,
+        kv_separator: String,
+    },
+}
+
+/// 已编译的启用提取器集合（按保存顺序，同名字段先到先得）
+#[derive(Default)]
+pub struct FieldExtractorSet {
+    extractors: Vec<CompiledKind>,
+}
+
+impl FieldExtractorSet {
+    /// 编译启用的提取器；规则无效时返回错误信息
+    pub fn compile(extractors: &[FieldExtractor]) -> Result<Self, String> {
+        let extractors = extractors
+            .iter()
+            .filter(|e| e.enabled)
+            .map(compile_one)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { extractors })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extractors.is_empty()
+    }
+
+    /// 从一行内容提取字段
+    pub fn extract(&self, content: &str) -> BTreeMap<String, String> {
+        let mut fields = BTreeMap::new();
+        for extractor in &self.extractors {
+            match extractor {
+                CompiledKind::Regex(regex) => {
+                    if let Some(caps) = regex.captures(content) {
+                        for name in regex.capture_names().flatten() {
+                            if let Some(m) = caps.name(name) {
+                                insert_field(&mut fields, name, m.as_str());
+                            }
+                        }
+                    }
+                }
+                CompiledKind::Json(pointers) => {
+                    let Some(value) = parse_embedded_json(content) else {
+                        continue;
+                    };
+                    for (name, pointer) in pointers {
+                        if let Some(found) = value.pointer(pointer) {
+                            match found {
+                                serde_json::Value::Null => {}
+                                serde_json::Value::String(s) => insert_field(&mut fields, name, s),
+                                other => insert_field(&mut fields, name, &other.to_string()),
+                            }
+                        }
+                    }
+                }
+                CompiledKind::KeyValue {
+                    pair_delimiter,
+                    kv_separator,
+                } => {
+                    for pair in content.split(pair_delimiter.as_str()) {
+                        let Some((key, value)) = pair.split_once(kv_separator.as_str()) else {
+                            continue;
+                        };
+                        let key = key.trim();
+                        if is_valid_field_name(key) {
+                            insert_field(&mut fields, key, strip_quotes(value.trim()));
+                        }
+                    }
+                }
+            }
+        }
+        fields
+    }
+
+    /// 为条目填充字段；没有提取到字段的条目保持 `None`
+    pub fn annotate(&self, entries: &mut [LogEntry]) {
+        if self.is_empty() {
+            return;
+        }
+        for entry in entries {
+            let fields = self.extract(&entry.content);
+            entry.fields = (!fields.is_empty()).then_some(fields);
+        }
+    }
+
+    /// 对样例行逐行提取（`test_extractor` 预览）
+    pub fn preview(&self, lines: &[String]) -> Vec<ExtractionPreview> {
+        lines
+            .iter()
+            .map(|line| ExtractionPreview {
+                line: line.clone(),
+                fields: self.extract(line),
+            })
+            .collect()
+    }
+}
+
+/// 校验提取器列表：名称唯一、数量受限、规则可编译
+pub fn validate_extractors(extractors: &[FieldExtractor]) -> Result<(), String> {
+    if extractors.len() > MAX_EXTRACTORS {
+        return Err(format!(
+            "Too many extractors ({}, max {MAX_EXTRACTORS})",
+            extractors.len()
+        ));
+    }
+    let mut names = std::collections::HashSet::new();
+    for extractor in extractors {
+        validate_extractor(extractor)?;
+        if !names.insert(extractor.name.as_str()) {
+            return Err(format!("Duplicate extractor name: {}", extractor.name));
+        }
+    }
+    Ok(())
+}
+
+/// 校验单个提取器（含禁用的提取器）
+pub fn validate_extractor(extractor: &FieldExtractor) -> Result<(), String> {
+    if !is_valid_field_name(&extractor.name) {
+        return Err(format!(
+            "Invalid extractor name '{}': use 1-{MAX_NAME_LEN} letters, digits, '_', '-' or '.'",
+            extractor.name
+        ));
+    }
+    compile_one(extractor).map(|_| ())
+}
+
+fn compile_one(extractor: &FieldExtractor) -> Result<CompiledKind, String> {
+    let name = &extractor.name;
+    match &extractor.kind {
+        ExtractorKind::Regex { pattern } => {
+            let regex = RegexBuilder::new(pattern)
+                .size_limit(REGEX_SIZE_LIMIT)
+                .build()
+                .map_err(|e| format!("Extractor '{name}': invalid regex: {e}"))?;
+            if regex.capture_names().flatten().next().is_none() {
+                return Err(format!(
+                    "Extractor '{name}': pattern has no named capture groups, e.g. (?P<user>\\w+)"
+                ));
+            }
+            if let Some(bad) = regex
+                .capture_names()
+                .flatten()
+                .find(|group| !is_valid_field_name(group))
+            {
+                return Err(format!("Extractor '{name}': invalid field name '{bad}'"));
+            }
+            Ok(CompiledKind::Regex(regex))
+        }
+        ExtractorKind::Json { pointers } => {
+            if pointers.is_empty() {
+                return Err(format!(
+                    "Extractor '{name}': at least one pointer is required"
+                ));
+            }
+            for (field, pointer) in pointers {
+                if !is_valid_field_name(field) {
+                    return Err(format!("Extractor '{name}': invalid field name '{field}'"));
+                }
+                if !pointer.is_empty() && !pointer.starts_with('/') {
+                    return Err(format!(
+                        "Extractor '{name}': JSON pointer '{pointer}' must start with '/'"
+                    ));
+                }
+            }
+            Ok(CompiledKind::Json(pointers.clone()))
+        }
+        ExtractorKind::KeyValue {
+            pair_delimiter,
+            kv_separator,
+        } => {
+            if pair_delimiter.is_empty() || kv_separator.is_empty() {
+                return Err(format!(
+                    "Extractor '{name}': pair delimiter and key/value separator must not be empty"
+                ));
+            }
+            if pair_delimiter == kv_separator {
+                return Err(format!(
+                    "Extractor '{name}': pair delimiter and key/value separator must differ"
+                ));
+            }
+            Ok(CompiledKind::KeyValue {
+                pair_delimiter: pair_delimiter.clone(),
+                kv_separator: kv_separator.clone(),
+            })
+        }
+    }
+}
+
+fn is_valid_field_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+fn insert_field(fields: &mut BTreeMap<String, String>, name: &str, value: &str) {
+    if fields.len() >= MAX_FIELDS_PER_ENTRY || fields.contains_key(name) {
+        return;
+    }
+    let mut end = value.len().min(MAX_VALUE_LEN);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    fields.insert(name.to_string(), value[..end].to_string());
+}
+
+fn parse_embedded_json(content: &str) -> Option<serde_json::Value> {
+    let start = content.find('{')?;
+    let end = content.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str(&content[start..=end]).ok()
+}
+
+fn strip_quotes(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extractor(name: &str, kind: ExtractorKind) -> FieldExtractor {
+        FieldExtractor {
+            name: name.into(),
+            kind,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn extracts_regex_json_and_key_value_fields() {
+        let set = FieldExtractorSet::compile(&[
+            extractor(
+                "req",
+                ExtractorKind::Regex {
+                    pattern: r"req=(?P<request_id>\w+)".into(),
+                },
+            ),
+            extractor(
+                "payload",
+                ExtractorKind::Json {
+                    pointers: BTreeMap::from([
+                        ("user".into(), "/user/name".into()),
+                        ("status".into(), "/status".into()),
+                    ]),
+                },
+            ),
+            extractor(
+                "kv",
+                ExtractorKind::KeyValue {
+                    pair_delimiter: " ".into(),
+                    kv_separator: "=".into(),
+                },
+            ),
+        ])
+        .unwrap();
+
+        let fields = set.extract(r#"req=ab12 host="db1" {"user":{"name":"ann"},"status":404}"#);
+        assert_eq!(fields["request_id"], "ab12");
+        assert_eq!(fields["user"], "ann");
+        assert_eq!(fields["status"], "404");
+        assert_eq!(fields["req"], "ab12");
+        assert_eq!(fields["host"], "db1");
+
+        let preview = set.preview(&["nothing here".to_string()]);
+        assert!(preview[0].fields.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_extractors() {
+        let no_groups = extractor(
+            "bad",
+            ExtractorKind::Regex {
+                pattern: r"\d+".into(),
+            },
+        );
+        assert!(validate_extractor(&no_groups)
+            .unwrap_err()
+            .contains("named capture"));
+
+        let bad_pointer = extractor(
+            "json",
+            ExtractorKind::Json {
+                pointers: BTreeMap::from([("user".into(), "user".into())]),
+            },
+        );
+        assert!(validate_extractor(&bad_pointer).is_err());
+
+        let kv = extractor(
+            "kv",
+            ExtractorKind::KeyValue {
+                pair_delimiter: ";".into(),
+                kv_separator: ":".into(),
+            },
+        );
+        assert!(validate_extractors(&[kv.clone(), kv])
+            .unwrap_err()
+            .contains("Duplicate"));
+
+        let json: FieldExtractor = serde_json::from_str(
+            r#"{"name":"kv","kind":"keyValue","pairDelimiter":",","kvSeparator":"="}"#,
+        )
+        .unwrap();
+        assert!(json.enabled);
+        assert!(matches!(json.kind, ExtractorKind::KeyValue { .. }));
+    }
+}
@@ -0,0 +1,372 @@
+//! 单个文件的解析覆盖
+//!
+//! 自动识别出错时（编码、格式、时区、字段位置），用户可以按内容哈希设置覆盖，
+//! 保存在 `file_formats` 表中（见 `MetadataStore::set_file_overrides`），由索引
+//! 流水线在解码与解析时应用：
+//! - 编码：`encoding_rs` 标签（如 `gbk`、`shift_jis`），替代 UTF-8 / GBK 自动回退
+//! - 格式：替代导入时识别的 [`LogFormat`](la_core::utils::LogFormat)
+//! - 时区：固定偏移（`+08:00`、`-0530`、`UTC`），用于不带时区的时间戳
+//! - 解析器：带 `timestamp` / `level` 命名捕获组的正则，匹配的行以捕获结果为准
+//!
+//! 扫描搜索、大文件流式读取与文件查看通过 [`ContentOverrides`] 使用同一套覆盖；
+//! 覆盖变更后按覆盖后的时间戳与级别刷新文件统计和稀疏时间索引，并按解码后的文本
+//! 重建三元组摘要。
+
+use chrono::{FixedOffset, SecondsFormat};
+use encoding_rs::Encoding;
+use la_core::domain::ContentOverrides;
+use la_core::models::LogEntry;
+use la_core::utils::time_index::line_timestamp;
+use la_core::utils::{TermBloom, TimeIndex, TimestampParser};
+use la_storage::{FileFormat, FileOverrides};
+use regex::{Regex, RegexBuilder};
+
+use crate::utils::encoding::decode_log_content;
+
+/// 解析器正则编译后的大小上限（字节）
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// 已校验、可直接应用的覆盖
+#[derive(Debug, Default)]
+pub struct CompiledOverrides {
+    encoding: Option<&'static Encoding>,
+    timezone: Option<FixedOffset>,
+    parser: Option<Regex>,
+}
+
+impl CompiledOverrides {
+    /// 校验并编译用户提交的覆盖；任一项无效时返回错误信息
+    pub fn compile(overrides: &FileOverrides) -> Result<Self, String> {
+        let encoding = overrides
+            .encoding
+            .as_deref()
+            .map(|label| {
+                Encoding::for_label(label.trim().as_bytes())
+                    .ok_or_else(|| format!("Unknown encoding '{label}'"))
+            })
+            .transpose()?;
+        let timezone = overrides
+            .timezone
+            .as_deref()
+            .map(|tz| parse_timezone(tz).ok_or_else(|| format!("Invalid timezone '{tz}'")))
+            .transpose()?;
+        let parser = overrides
+            .parser
+            .as_deref()
+            .map(compile_parser)
+            .transpose()?;
+        Ok(Self {
+            encoding,
+            timezone,
+            parser,
+        })
+    }
+
+    /// 已保存的覆盖；无法编译的项（如由更新版本写入）被忽略
+    pub fn from_format(format: &FileFormat) -> Self {
+        let encoding = format
+            .encoding_override
+            .as_deref()
+            .and_then(|label| Encoding::for_label(label.trim().as_bytes()));
+        let timezone = format.timezone_override.as_deref().and_then(parse_timezone);
+        let parser = format
+            .parser_override
+            .as_deref()
+            .and_then(|pattern| compile_parser(pattern).ok());
+        Self {
+            encoding,
+            timezone,
+            parser,
+        }
+    }
+
+    /// 没有任何可应用的覆盖（按自动识别解码与解析）
+    pub fn is_empty(&self) -> bool {
+        self.encoding.is_none() && self.timezone.is_none() && self.parser.is_none()
+    }
+
+    /// 按覆盖的编码解码内容，未覆盖时自动识别
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self.encoding {
+            Some(encoding) => encoding.decode_with_bom_removal(bytes).0.into_owned(),
+            None => decode_log_content(bytes).0,
+        }
+    }
+
+    /// 对解析结果应用解析器与时区覆盖
+    pub fn apply(&self, entries: &mut [LogEntry]) {
+        if self.parser.is_none() && self.timezone.is_none() {
+            return;
+        }
+        for entry in entries {
+            if let Some(caps) = self
+                .parser
+                .as_ref()
+                .and_then(|p| p.captures(&entry.content))
+            {
+                if let Some(timestamp) = caps.name("timestamp") {
+                    entry.timestamp = timestamp.as_str().trim().into();
+                }
+                if let Some(level) = caps.name("level") {
+                    entry.level = level.as_str().trim().to_uppercase().into();
+                }
+            }
+            if let Some(offset) = self.timezone {
+                if let Some(localized) = localize_timestamp(&entry.timestamp, offset) {
+                    entry.timestamp = localized.into();
+                }
+            }
+        }
+    }
+
+    /// 一行按解析器与时区覆盖后的时间戳（Unix 秒）；未覆盖时与导入时的规则相同
+    pub fn line_timestamp(&self, line: &str) -> Option<i64> {
+        if self.parser.is_none() && self.timezone.is_none() {
+            return line_timestamp(line);
+        }
+        let raw = self
+            .parser
+            .as_ref()
+            .and_then(|p| p.captures(line))
+            .and_then(|caps| caps.name("timestamp"))
+            .map(|timestamp| timestamp.as_str().trim().to_string())
+            .or_else(|| TimestampParser::parse_timestamp(line))?;
+        let raw = match self.timezone {
+            Some(offset) => localize_timestamp(&raw, offset).unwrap_or(raw),
+            None => raw,
+        };
+        la_search::parse_log_timestamp_to_unix(&raw).or_else(|| {
+            TimestampParser::parse_naive_datetime(&raw).map(|dt| dt.and_utc().timestamp())
+        })
+    }
+
+    /// 按覆盖后的时间戳构建稀疏时间索引
+    ///
+    /// 检查点记录原始字节偏移，覆盖的编码改变了内容（或内容不是 UTF-8）时返回 `None`。
+    pub fn time_index(&self, content: &[u8]) -> Option<TimeIndex> {
+        let text = std::str::from_utf8(content).ok()?;
+        if self
+            .encoding
+            .is_some_and(|encoding| encoding.decode_with_bom_removal(content).0 != text)
+        {
+            return None;
+        }
+        TimeIndex::from_text_with(text, |line| self.line_timestamp(line))
+    }
+
+    /// 按覆盖解码后的文本构建三元组摘要，与搜索时看到的内容一致
+    ///
+    /// gzip 对象与无法构建摘要的内容返回 `None`（该文件不参与跳过）。
+    pub fn term_sketch(&self, content: &[u8]) -> Option<TermBloom> {
+        if content.starts_with(&[0x1f, 0x8b]) {
+            return None;
+        }
+        TermBloom::from_content(self.decode(content).as_bytes())
+    }
+}
+
+impl ContentOverrides for CompiledOverrides {
+    fn decode(&self, bytes: &[u8]) -> String {
+        CompiledOverrides::decode(self, bytes)
+    }
+
+    fn apply(&self, entries: &mut [LogEntry]) {
+        CompiledOverrides::apply(self, entries)
+    }
+}
+
+/// 去掉空白项：空字符串表示恢复自动识别
+pub fn normalize(overrides: FileOverrides) -> FileOverrides {
+    let clean = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+    FileOverrides {
+        encoding: clean(overrides.encoding),
+        format: overrides.format,
+        timezone: clean(overrides.timezone),
+        parser: clean(overrides.parser),
+    }
+}
+
+/// 解析固定时区偏移：`UTC` / `Z`、`+08:00`、`+0800`、`+8`
+pub fn parse_timezone(value: &str) -> Option<FixedOffset> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("utc") || value.eq_ignore_ascii_case("z") {
+        return FixedOffset::east_opt(0);
+    }
+    let value = value
+        .strip_prefix("UTC")
+        .or_else(|| value.strip_prefix("GMT"))
+        .unwrap_or(value);
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    if hours.is_empty() || hours.len() > 2 || minutes.len() > 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+fn compile_parser(pattern: &str) -> Result<Regex, String> {
+    let regex = RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid parser regex: {e}"))?;
+    if !regex
+        .capture_names()
+        .flatten()
+        .any(|name| name == "timestamp" || name == "level")
+    {
+        return Err(
+            "Parser regex needs a named group (?P<timestamp>...) or (?P<level>...)".to_string(),
+        );
+    }
+    Ok(regex)
+}
+
+/// 不带时区的时间戳按 `offset` 解释并改写为 RFC 3339；已带时区或无法解析时返回 `None`
+fn localize_timestamp(timestamp: &str, offset: FixedOffset) -> Option<String> {
+    let trimmed = timestamp.trim();
+    if trimmed.is_empty() || chrono::DateTime::parse_from_rfc3339(trimmed).is_ok() {
+        return None;
+    }
+    let naive = TimestampParser::parse_naive_datetime(trimmed)?;
+    let local = naive.and_local_timezone(offset).single()?;
+    Some(local.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(content: &str, timestamp: &str) -> LogEntry {
+        LogEntry {
+            id: 0,
+            timestamp: timestamp.into(),
+            level: "INFO".into(),
+            file: "app.log".into(),
+            real_path: "app.log".into(),
+            line: 1,
+            content: content.into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn parses_fixed_offsets() {
+        let east = FixedOffset::east_opt;
+        assert_eq!(parse_timezone("UTC"), east(0));
+        assert_eq!(parse_timezone("+08:00"), east(8 * 3600));
+        assert_eq!(parse_timezone("-0530"), east(-(5 * 3600 + 30 * 60)));
+        assert_eq!(parse_timezone("UTC+9"), east(9 * 3600));
+        assert_eq!(parse_timezone("Asia/Shanghai"), None);
+        assert_eq!(parse_timezone("+25:00"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_overrides() {
+        let compile = |overrides: FileOverrides| CompiledOverrides::compile(&overrides);
+        assert!(compile(FileOverrides {
+            encoding: Some("no-such-charset".into()),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(compile(FileOverrides {
+            parser: Some(r"^(\S+) (\w+)".into()),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(compile(FileOverrides {
+            encoding: Some("GBK".into()),
+            timezone: Some("+08:00".into()),
+            parser: Some(r"^(?P<timestamp>\S+ \S+) \[(?P<level>\w+)\]".into()),
+            ..Default::default()
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn applies_encoding_parser_and_timezone() {
+        let overrides = CompiledOverrides::compile(&FileOverrides {
+            encoding: Some("gbk".into()),
+            timezone: Some("+08:00".into()),
+            parser: Some(r"^(?P<timestamp>\S+ \S+) <(?P<level>\w+)>".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(overrides.decode(b"\xc4\xe3\xba\xc3"), "你好");
+
+        let mut entries = vec![
+            entry("2024-03-01 10:00:00 <warn> disk", ""),
+            entry("no header here", "2024-03-01 11:00:00"),
+            entry("already zoned", "2024-03-01T12:00:00Z"),
+        ];
+        overrides.apply(&mut entries);
+        assert_eq!(&*entries[0].timestamp, "2024-03-01T10:00:00+08:00");
+        assert_eq!(&*entries[0].level, "WARN");
+        assert_eq!(&*entries[1].timestamp, "2024-03-01T11:00:00+08:00");
+        assert_eq!(&*entries[2].timestamp, "2024-03-01T12:00:00Z");
+    }
+
+    #[test]
+    fn line_timestamps_follow_parser_and_timezone() {
+        let overrides = CompiledOverrides::compile(&FileOverrides {
+            timezone: Some("+08:00".into()),
+            parser: Some(r"^\[(?P<timestamp>[^\]]+)\]".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        let utc = |value: &str| line_timestamp(value).unwrap();
+        assert_eq!(
+            overrides.line_timestamp("[2024-03-01 10:00:00] 2023-01-01 00:00:00 boot"),
+            Some(utc("2024-03-01 02:00:00"))
+        );
+        assert_eq!(
+            overrides.line_timestamp("2024-03-01 10:00:00 no brackets"),
+            Some(utc("2024-03-01 02:00:00"))
+        );
+        assert!(CompiledOverrides::default().is_empty());
+        assert_eq!(
+            CompiledOverrides::default().line_timestamp("2024-03-01 10:00:00 x"),
+            Some(utc("2024-03-01 10:00:00"))
+        );
+    }
+
+    #[test]
+    fn term_sketch_follows_encoding_override() {
+        let content: Vec<u8> = "2024-03-01 10:00:00 ERROR connection timeout\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        // 无 BOM 的 UTF-16 也是合法 UTF-8，按原始字节构建的摘要不含关键词
+        let raw = TermBloom::from_content(&content).unwrap();
+        assert!(!raw.may_contain("timeout"));
+
+        let overrides = CompiledOverrides::compile(&FileOverrides {
+            encoding: Some("utf-16le".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        let sketch = overrides.term_sketch(&content).unwrap();
+        assert!(sketch.may_contain("timeout"));
+        assert!(overrides.term_sketch(&[0x1f, 0x8b, 0x08, 0x00]).is_none());
+    }
+}
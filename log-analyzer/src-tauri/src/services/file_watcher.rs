@@ -2,6 +2,7 @@
 //!
 //! P7 修剪后仅保留：
 //! - `WatcherState`：单个工作区监听线程的生命周期句柄（由 WorkspaceServiceImpl 持有）
+//!   及当前监听模式（原生 / 轮询，见 `services::watcher_budget`）
 //! - TimestampParser 回归测试（实现位于 la_core::utils）
 //!
 //! 历史职责已迁移或删除：
@@ -12,19 +13,36 @@
 
 use std::sync::Arc;
 
+use crate::services::polling_watcher::PollingScanner;
+use crate::services::watcher_budget::WatchMode;
+
+/// 实际使用的监听后端
+pub enum WatchBackend {
+    /// notify 原生后端（inotify / FSEvents / ReadDirectoryChangesW）
+    Native(Box<dyn notify::Watcher + Send>),
+    /// mtime / size 轮询扫描
+    Polling(PollingScanner),
+}
+
 /// 文件监听器状态
 ///
 /// 仅保留生命周期管理字段：file_offsets / line_counts 由 FileTailer 持有，
 /// workspace_id / watched_path 构造后从未被读取，均已移除。
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct WatcherState {
     pub is_active: bool,
+    /// 当前监听模式（超出全局预算或触发系统上限时为轮询）
+    pub mode: WatchMode,
+    /// 降级为轮询的原因
+    pub fallback_reason: Option<String>,
+    /// 检测到的网络文件系统类型（轮询延迟高于原生监听的常见原因）
+    pub network_filesystem: Option<String>,
     /// 监听线程的 JoinHandle，用于确保正确退出并清理资源
     /// 使用 parking_lot::Mutex 避免 poison 问题（B-M3）
     pub thread_handle: Arc<parking_lot::Mutex<Option<std::thread::JoinHandle<()>>>>,
     /// 底层文件监听器，存放在这里确保其生命周期与状态同步
     /// 使用 parking_lot::Mutex 避免 poison 问题（B-M3）
-    pub watcher: Arc<parking_lot::Mutex<Option<notify::RecommendedWatcher>>>,
+    pub watcher: Arc<parking_lot::Mutex<Option<WatchBackend>>>,
 }
 
 #[cfg(test)]
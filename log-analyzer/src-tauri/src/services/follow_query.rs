@@ -0,0 +1,189 @@
+//! 跟随查询（Watch 模式的 `tail -f | grep`）
+//!
+//! 工作区可挂载若干已保存的 `SearchQuery`。监听器每写入一批新条目，
+//! 就逐条用各查询的执行计划匹配，命中的条目按查询 id 分组推送给前端。
+//!
+//! 挂载状态属于工作区而非某次监听：停止后重新开始监听，已挂载的查询继续生效。
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use la_core::error::Result;
+use la_core::models::{LogEntry, SearchQuery};
+
+use crate::services::query_planner::{ExecutionPlan, QueryPlanner};
+
+/// 单个跟随查询：预编译的执行计划与级别过滤
+struct FollowQuery {
+    query_id: String,
+    plan: ExecutionPlan,
+    /// 小写级别集合；为空表示不限级别
+    levels: HashSet<String>,
+}
+
+impl FollowQuery {
+    fn matches(&self, entry: &LogEntry) -> Option<LogEntry> {
+        if !self.levels.is_empty() && !self.levels.contains(&entry.level.to_lowercase()) {
+            return None;
+        }
+        let details = self.plan.match_with_details(&entry.content)?;
+        let mut matched = entry.clone();
+        matched.match_details = (!details.is_empty()).then_some(details);
+        Some(matched)
+    }
+}
+
+/// 工作区挂载的跟随查询集合（克隆共享同一份状态）
+#[derive(Clone, Default)]
+pub struct FollowQueries {
+    queries: Arc<RwLock<Vec<FollowQuery>>>,
+}
+
+impl FollowQueries {
+    /// 挂载查询；同 id 的查询会被替换。查询无效时返回校验错误。
+    pub fn attach(&self, query: &SearchQuery) -> Result<()> {
+        let plan = QueryPlanner::with_default_capacity().build(query)?;
+        let levels = query
+            .filters
+            .as_ref()
+            .and_then(|f| f.levels.as_ref())
+            .map(|levels| levels.iter().map(|l| l.to_lowercase()).collect())
+            .unwrap_or_default();
+
+        let follow = FollowQuery {
+            query_id: query.id.clone(),
+            plan,
+            levels,
+        };
+        let mut queries = self.queries.write();
+        queries.retain(|q| q.query_id != query.id);
+        queries.push(follow);
+        Ok(())
+    }
+
+    /// 卸载查询；返回是否存在
+    pub fn detach(&self, query_id: &str) -> bool {
+        let mut queries = self.queries.write();
+        let before = queries.len();
+        queries.retain(|q| q.query_id != query_id);
+        queries.len() != before
+    }
+
+    /// 已挂载的查询 id（按挂载顺序）
+    pub fn query_ids(&self) -> Vec<String> {
+        self.queries
+            .read()
+            .iter()
+            .map(|q| q.query_id.clone())
+            .collect()
+    }
+
+    /// 用每个查询匹配新条目，返回 (查询 id, 命中条目)；无命中的查询不出现
+    pub fn evaluate(&self, entries: &[LogEntry]) -> Vec<(String, Vec<LogEntry>)> {
+        let queries = self.queries.read();
+        queries
+            .iter()
+            .filter_map(|query| {
+                let matched: Vec<LogEntry> =
+                    entries.iter().filter_map(|e| query.matches(e)).collect();
+                (!matched.is_empty()).then(|| (query.query_id.clone(), matched))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use la_core::models::search::{
+        QueryMetadata, QueryOperator, SearchFilters, SearchTerm, TermSource,
+    };
+
+    fn query(id: &str, value: &str, levels: Option<Vec<String>>) -> SearchQuery {
+        SearchQuery {
+            id: id.to_string(),
+            terms: vec![SearchTerm {
+                id: "t1".to_string(),
+                value: value.to_string(),
+                operator: QueryOperator::And,
+                source: TermSource::User,
+                preset_group_id: None,
+                is_regex: false,
+                priority: 1,
+                enabled: true,
+                case_sensitive: false,
+            }],
+            global_operator: QueryOperator::And,
+            filters: levels.map(|levels| SearchFilters {
+                levels: Some(levels),
+                time_range: None,
+                file_pattern: None,
+            }),
+            metadata: QueryMetadata {
+                created_at: 0,
+                last_modified: 0,
+                execution_count: 0,
+                label: None,
+            },
+        }
+    }
+
+    fn entry(line: usize, level: &str, content: &str) -> LogEntry {
+        LogEntry {
+            id: line,
+            timestamp: "2024-01-01 00:00:00".into(),
+            level: level.into(),
+            file: "app.log".into(),
+            real_path: "/logs/app.log".into(),
+            line,
+            content: content.into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn test_new_entries_grouped_by_query() {
+        let follow = FollowQueries::default();
+        follow.attach(&query("timeouts", "timeout", None)).unwrap();
+        follow
+            .attach(&query("errors", "failed", Some(vec!["ERROR".to_string()])))
+            .unwrap();
+
+        let entries = vec![
+            entry(1, "info", "request timeout, retrying"),
+            entry(2, "error", "upload failed"),
+            entry(3, "warn", "upload failed once"),
+        ];
+        let matches = follow.evaluate(&entries);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].0, "timeouts");
+        assert_eq!(matches[0].1[0].line, 1);
+        assert!(matches[0].1[0].match_details.is_some());
+        assert_eq!(matches[1].0, "errors");
+        assert_eq!(matches[1].1.len(), 1);
+        assert_eq!(matches[1].1[0].line, 2);
+    }
+
+    #[test]
+    fn test_attach_replaces_and_detach_removes() {
+        let follow = FollowQueries::default();
+        follow.attach(&query("q", "alpha", None)).unwrap();
+        follow.attach(&query("q", "beta", None)).unwrap();
+        assert_eq!(follow.query_ids(), vec!["q".to_string()]);
+        assert!(follow.evaluate(&[entry(1, "info", "alpha")]).is_empty());
+
+        assert!(follow.detach("q"));
+        assert!(!follow.detach("q"));
+        assert!(follow.query_ids().is_empty());
+    }
+}
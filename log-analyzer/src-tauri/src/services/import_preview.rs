@@ -0,0 +1,691 @@
+//! 导入预览：正式导入前快速估算规模与耗时
+//!
+//! 只读取压缩包的目录信息（ZIP 中央目录、TAR 头、GZ 尾部 / ZST 帧头的原始大小、7Z / RAR
+//! 文件列表），不写 CAS、不建索引。单文件 XZ / BZ2 / LZ4 不记录原始大小，按假定压缩比估算。较小的嵌套压缩包读入内存后继续展开以得到
+//! 嵌套深度；过大的嵌套包和无法读取目录的格式按假定压缩比估算，并标记为估算值。
+//!
+//! 耗时按本机基准基线（`benchmark_baseline.json`）的磁盘、哈希与 SQLite 吞吐估算，
+//! 没有基线时使用保守的默认吞吐。
+
+use std::io::{self, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use la_archive::entry_names::zip_entry_name;
+use la_archive::internal::file_type_filter::FileTypeFilter;
+use la_core::models::config::AppConfig;
+use serde::Serialize;
+
+use crate::benchmark::BenchmarkResults;
+
+/// 最多统计的文件 / 条目数，超出后停止并标记 `truncated`
+pub const MAX_PREVIEW_ENTRIES: usize = 200_000;
+
+/// 读入内存继续展开的嵌套压缩包大小上限
+const NESTED_INSPECT_MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+/// 无法读取原始大小时假定的压缩比
+const ASSUMED_COMPRESSION_RATIO: u64 = 8;
+
+/// 没有基线时使用的吞吐（MB/s）与 SQLite 插入速率，取机械硬盘量级
+const FALLBACK_DISK_READ_MB_PER_SEC: f64 = 120.0;
+const FALLBACK_DISK_WRITE_MB_PER_SEC: f64 = 80.0;
+const FALLBACK_SHA256_MB_PER_SEC: f64 = 300.0;
+const FALLBACK_SQLITE_INSERTS_PER_SEC: f64 = 5_000.0;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+/// 无法导入的源文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedSource {
+    /// 相对导入根的路径；压缩包内条目为 `<压缩包>/<条目路径>`
+    pub path: String,
+    pub reason: String,
+}
+
+/// `preview_import` 返回结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub source_path: PathBuf,
+    /// 将被导入的日志文件数（含压缩包内条目）
+    pub file_count: usize,
+    /// 压缩包数（含嵌套）
+    pub archive_count: usize,
+    /// 被文件类型过滤规则跳过的文件数
+    pub filtered_count: usize,
+    /// 源文件在磁盘上的总大小
+    pub compressed_bytes: u64,
+    /// 解压后的总大小估算
+    pub uncompressed_bytes: u64,
+    /// 最深的压缩包嵌套层数（没有压缩包为 0）
+    pub max_nesting_depth: usize,
+    pub unsupported: Vec<UnsupportedSource>,
+    /// 部分大小按假定压缩比估算，而不是读自压缩包目录
+    pub sizes_estimated: bool,
+    /// 条目数超过 [`MAX_PREVIEW_ENTRIES`]，只统计了前一部分
+    pub truncated: bool,
+    pub estimated_duration_ms: u64,
+    /// 估算所用基线的测量时间；None 表示没有基线，使用默认吞吐
+    pub baseline_measured_at: Option<String>,
+    /// 超出导入限制等提示
+    pub warnings: Vec<String>,
+}
+
+/// 按基线吞吐估算导入耗时：读取源文件、写入与哈希解压后的内容、逐文件写元数据
+pub fn estimate_import_duration(
+    compressed_bytes: u64,
+    uncompressed_bytes: u64,
+    file_count: usize,
+    baseline: Option<&BenchmarkResults>,
+) -> Duration {
+    let (read, write, sha256, inserts) = baseline.map_or(
+        (
+            FALLBACK_DISK_READ_MB_PER_SEC,
+            FALLBACK_DISK_WRITE_MB_PER_SEC,
+            FALLBACK_SHA256_MB_PER_SEC,
+            FALLBACK_SQLITE_INSERTS_PER_SEC,
+        ),
+        |b| {
+            (
+                b.disk_read_mb_per_sec,
+                b.disk_write_mb_per_sec,
+                b.sha256_mb_per_sec,
+                b.sqlite_inserts_per_sec,
+            )
+        },
+    );
+    let compressed_mb = compressed_bytes as f64 / MB;
+    let uncompressed_mb = uncompressed_bytes as f64 / MB;
+    let secs = compressed_mb / read.max(1e-3)
+        + uncompressed_mb / write.max(1e-3)
+        + uncompressed_mb / sha256.max(1e-3)
+        + file_count as f64 / inserts.max(1e-3);
+    Duration::from_secs_f64(secs)
+}
+
+/// 扫描导入源（目录、压缩包或单个文件）并估算耗时
+pub fn preview_import(
+    source: &Path,
+    config: &AppConfig,
+    baseline: Option<&BenchmarkResults>,
+) -> Result<ImportPreview, String> {
+    if !source.exists() {
+        return Err(format!("Path not found: {}", source.display()));
+    }
+    let filter = (config.file_filter.enabled || config.file_filter.binary_detection_enabled)
+        .then(|| FileTypeFilter::new(config.file_filter.clone()));
+    let mut scanner = Scanner {
+        filter: filter.as_ref(),
+        max_depth: config.archive.max_extraction_depth,
+        preview: ImportPreview {
+            source_path: source.to_path_buf(),
+            ..Default::default()
+        },
+        entries: 0,
+    };
+
+    if source.is_dir() {
+        for entry in walkdir::WalkDir::new(source).follow_links(false) {
+            let entry = entry.map_err(|e| format!("Failed to walk directory: {e}"))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let label = entry
+                .path()
+                .strip_prefix(source)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            if !scanner.visit_disk_file(entry.path(), &label) {
+                break;
+            }
+        }
+    } else {
+        let label = source
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        scanner.visit_disk_file(source, &label);
+    }
+
+    let mut preview = scanner.preview;
+    preview.estimated_duration_ms = estimate_import_duration(
+        preview.compressed_bytes,
+        preview.uncompressed_bytes,
+        preview.file_count,
+        baseline,
+    )
+    .as_millis() as u64;
+    preview.baseline_measured_at = baseline.map(|b| b.measured_at.clone());
+
+    if preview.max_nesting_depth > config.archive.max_extraction_depth {
+        preview.warnings.push(format!(
+            "Archives are nested {} levels deep; only {} levels will be extracted",
+            preview.max_nesting_depth, config.archive.max_extraction_depth
+        ));
+    }
+    if preview.uncompressed_bytes > config.archive.max_workspace_size {
+        preview.warnings.push(format!(
+            "Estimated size {} bytes exceeds the workspace limit of {} bytes",
+            preview.uncompressed_bytes, config.archive.max_workspace_size
+        ));
+    }
+    Ok(preview)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceKind {
+    Plain,
+    Zip,
+    Tar,
+    TarGz,
+    TarZst,
+    TarXz,
+    TarBz2,
+    Gz,
+    Zst,
+    Xz,
+    Bz2,
+    Lz4,
+    SevenZ,
+    Rar,
+    Unsupported(&'static str),
+}
+
+/// 导入时按压缩包展开的文件名
+pub(crate) fn is_archive_name(name: &str) -> bool {
+    matches!(
+        classify(name),
+        SourceKind::Zip
+            | SourceKind::Tar
+            | SourceKind::TarGz
+            | SourceKind::TarZst
+            | SourceKind::TarXz
+            | SourceKind::TarBz2
+            | SourceKind::Gz
+            | SourceKind::Zst
+            | SourceKind::Xz
+            | SourceKind::Bz2
+            | SourceKind::Lz4
+            | SourceKind::SevenZ
+            | SourceKind::Rar
+    )
+}
+
+fn classify(name: &str) -> SourceKind {
+    let lower = name.to_lowercase();
+    if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        SourceKind::TarGz
+    } else if lower.ends_with(".tar.zst") || lower.ends_with(".tzst") {
+        SourceKind::TarZst
+    } else if lower.ends_with(".tar.xz") || lower.ends_with(".txz") {
+        SourceKind::TarXz
+    } else if [".tar.bz2", ".tbz2", ".tbz"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+    {
+        SourceKind::TarBz2
+    } else if lower.ends_with(".zip") {
+        SourceKind::Zip
+    } else if lower.ends_with(".tar") {
+        SourceKind::Tar
+    } else if lower.ends_with(".gz") {
+        SourceKind::Gz
+    } else if lower.ends_with(".zst") {
+        SourceKind::Zst
+    } else if lower.ends_with(".xz") {
+        SourceKind::Xz
+    } else if lower.ends_with(".bz2") {
+        SourceKind::Bz2
+    } else if lower.ends_with(".lz4") {
+        SourceKind::Lz4
+    } else if lower.ends_with(".7z") {
+        SourceKind::SevenZ
+    } else if lower.ends_with(".rar") {
+        if cfg!(feature = "rar-support") {
+            SourceKind::Rar
+        } else {
+            SourceKind::Unsupported("RAR support is not compiled into this build")
+        }
+    } else if lower.ends_with(".lzma") {
+        SourceKind::Unsupported("Compression format is not supported")
+    } else {
+        SourceKind::Plain
+    }
+}
+
+struct Scanner<'a> {
+    filter: Option<&'a FileTypeFilter>,
+    max_depth: usize,
+    preview: ImportPreview,
+    entries: usize,
+}
+
+impl Scanner<'_> {
+    /// 统计一个磁盘文件；达到条目上限时返回 false
+    fn visit_disk_file(&mut self, path: &Path, label: &str) -> bool {
+        if !self.count_entry() {
+            return false;
+        }
+        let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+        self.preview.compressed_bytes += size;
+
+        let kind = classify(label);
+        let result = match kind {
+            SourceKind::Plain => {
+                if self.filter.is_some_and(|f| !f.should_import_file(path)) {
+                    self.preview.filtered_count += 1;
+                } else {
+                    self.preview.file_count += 1;
+                    self.preview.uncompressed_bytes += size;
+                }
+                return true;
+            }
+            SourceKind::Unsupported(reason) => {
+                self.unsupported(label, reason);
+                return true;
+            }
+            SourceKind::Gz => {
+                self.enter_archive(1);
+                gz_original_size(path).map(|original| {
+                    self.preview.file_count += 1;
+                    self.preview.uncompressed_bytes += original;
+                })
+            }
+            SourceKind::Zst => {
+                self.enter_archive(1);
+                zst_original_size(path).map(|original| {
+                    self.preview.file_count += 1;
+                    // 帧头未记录原始大小时按假定压缩比估算
+                    self.preview.uncompressed_bytes += original.unwrap_or_else(|| {
+                        self.preview.sizes_estimated = true;
+                        size * ASSUMED_COMPRESSION_RATIO
+                    });
+                })
+            }
+            SourceKind::Xz | SourceKind::Bz2 | SourceKind::Lz4 => {
+                self.enter_archive(1);
+                self.preview.file_count += 1;
+                self.preview.sizes_estimated = true;
+                self.preview.uncompressed_bytes += size * ASSUMED_COMPRESSION_RATIO;
+                Ok(())
+            }
+            SourceKind::Rar => {
+                self.enter_archive(1);
+                self.inspect_rar(path, label)
+            }
+            _ => {
+                self.enter_archive(1);
+                std::fs::File::open(path)
+                    .and_then(|file| self.inspect_archive(kind, io::BufReader::new(file), label, 1))
+            }
+        };
+        if let Err(e) = result {
+            self.unsupported(label, &format!("Failed to read archive: {e}"));
+        }
+        self.entries < MAX_PREVIEW_ENTRIES
+    }
+
+    /// 展开 ZIP / TAR / TAR.GZ / TAR.ZST / TAR.XZ / TAR.BZ2 / 7Z；`depth` 为该压缩包自身的嵌套层数
+    fn inspect_archive<R: Read + Seek>(
+        &mut self,
+        kind: SourceKind,
+        mut reader: R,
+        label: &str,
+        depth: usize,
+    ) -> io::Result<()> {
+        match kind {
+            SourceKind::Zip => {
+                let mut archive = zip::ZipArchive::new(reader).map_err(io::Error::other)?;
+                for i in 0..archive.len() {
+                    let (name, size, is_dir) = {
+                        let entry = archive.by_index_raw(i).map_err(io::Error::other)?;
+                        let (name, _) = zip_entry_name(entry.name(), entry.name_raw());
+                        (name, entry.size(), entry.is_dir())
+                    };
+                    if is_dir {
+                        continue;
+                    }
+                    let keep_going =
+                        self.visit_entry(&format!("{label}/{name}"), size, depth, || {
+                            let mut entry = archive.by_index(i).map_err(io::Error::other)?;
+                            let mut bytes = Vec::with_capacity(size as usize);
+                            entry.read_to_end(&mut bytes)?;
+                            Ok(bytes)
+                        });
+                    if !keep_going {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            SourceKind::Tar => self.inspect_tar(reader, label, depth),
+            SourceKind::TarGz => self.inspect_tar(GzDecoder::new(reader), label, depth),
+            SourceKind::TarZst => {
+                self.inspect_tar(zstd::stream::read::Decoder::new(reader)?, label, depth)
+            }
+            SourceKind::TarXz => self.inspect_tar(
+                xz2::read::XzDecoder::new_multi_decoder(reader),
+                label,
+                depth,
+            ),
+            SourceKind::TarBz2 => {
+                self.inspect_tar(bzip2::read::MultiBzDecoder::new(reader), label, depth)
+            }
+            SourceKind::SevenZ => {
+                let len = reader.seek(io::SeekFrom::End(0))?;
+                reader.rewind()?;
+                let archive =
+                    sevenz_rust::Archive::read(&mut reader, len, &[]).map_err(io::Error::other)?;
+                for entry in archive
+                    .files
+                    .iter()
+                    .filter(|e| e.has_stream && !e.is_directory)
+                {
+                    // 7Z 是固实压缩，不单独解出嵌套包
+                    if !self.visit_entry(
+                        &format!("{label}/{}", entry.name),
+                        entry.size,
+                        depth,
+                        || Err(io::Error::other("not inspected")),
+                    ) {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn inspect_tar<R: Read>(&mut self, reader: R, label: &str, depth: usize) -> io::Result<()> {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path()?.to_string_lossy().to_string();
+            let size = entry.size();
+            if !self.visit_entry(&format!("{label}/{name}"), size, depth, || {
+                let mut bytes = Vec::with_capacity(size as usize);
+                entry.read_to_end(&mut bytes)?;
+                Ok(bytes)
+            }) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "rar-support")]
+    fn inspect_rar(&mut self, path: &Path, label: &str) -> io::Result<()> {
+        let archive = unrar::Archive::new(path)
+            .open_for_listing()
+            .map_err(io::Error::other)?;
+        for header in archive {
+            let header = header.map_err(io::Error::other)?;
+            if !header.is_file() {
+                continue;
+            }
+            let name = header.filename.to_string_lossy().replace('\\', "/");
+            if !self.visit_entry(&format!("{label}/{name}"), header.unpacked_size, 1, || {
+                Err(io::Error::other("not inspected"))
+            }) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "rar-support"))]
+    fn inspect_rar(&mut self, _path: &Path, _label: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// 统计压缩包内的一个条目；`read` 在需要展开嵌套包时读出其内容
+    fn visit_entry(
+        &mut self,
+        label: &str,
+        size: u64,
+        depth: usize,
+        read: impl FnOnce() -> io::Result<Vec<u8>>,
+    ) -> bool {
+        if !self.count_entry() {
+            return false;
+        }
+        let kind = classify(label);
+        match kind {
+            SourceKind::Plain => {
+                self.preview.file_count += 1;
+                self.preview.uncompressed_bytes += size;
+            }
+            SourceKind::Unsupported(reason) => self.unsupported(label, reason),
+            _ => {
+                let nested = depth + 1;
+                self.enter_archive(nested);
+                let inspectable = kind != SourceKind::Rar
+                    && size <= NESTED_INSPECT_MAX_BYTES
+                    && nested <= self.max_depth;
+                if inspectable {
+                    if let Err(e) =
+                        read().and_then(|bytes| self.inspect_bytes(kind, bytes, label, nested))
+                    {
+                        self.unsupported(label, &format!("Failed to read archive: {e}"));
+                    }
+                } else {
+                    // 未展开的嵌套包按一个文件、假定压缩比计入
+                    self.preview.sizes_estimated = true;
+                    self.preview.file_count += 1;
+                    self.preview.uncompressed_bytes += size * ASSUMED_COMPRESSION_RATIO;
+                }
+            }
+        }
+        self.entries < MAX_PREVIEW_ENTRIES
+    }
+
+    fn inspect_bytes(
+        &mut self,
+        kind: SourceKind,
+        bytes: Vec<u8>,
+        label: &str,
+        depth: usize,
+    ) -> io::Result<()> {
+        // 单文件压缩：嵌套包已在内存中，直接解压计数得到原始大小
+        let mut decoder: Box<dyn Read + '_> = match kind {
+            SourceKind::Gz => Box::new(GzDecoder::new(&bytes[..])),
+            SourceKind::Zst => Box::new(zstd::stream::read::Decoder::new(&bytes[..])?),
+            SourceKind::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(&bytes[..])),
+            SourceKind::Bz2 => Box::new(bzip2::read::MultiBzDecoder::new(&bytes[..])),
+            SourceKind::Lz4 => Box::new(lz4::Decoder::new(&bytes[..])?),
+            _ => return self.inspect_archive(kind, Cursor::new(bytes), label, depth),
+        };
+        let original = io::copy(&mut decoder, &mut io::sink())?;
+        self.preview.file_count += 1;
+        self.preview.uncompressed_bytes += original;
+        Ok(())
+    }
+
+    fn enter_archive(&mut self, depth: usize) {
+        self.preview.archive_count += 1;
+        self.preview.max_nesting_depth = self.preview.max_nesting_depth.max(depth);
+    }
+
+    fn unsupported(&mut self, path: &str, reason: &str) {
+        self.preview.unsupported.push(UnsupportedSource {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    fn count_entry(&mut self) -> bool {
+        if self.entries >= MAX_PREVIEW_ENTRIES {
+            self.preview.truncated = true;
+            return false;
+        }
+        self.entries += 1;
+        true
+    }
+}
+
+/// GZ 尾部 ISIZE 字段：原始大小对 2^32 取模，超过 4 GiB 的单文件会偏小
+fn gz_original_size(path: &Path) -> io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.seek(io::SeekFrom::End(0))?;
+    if len < 18 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated gzip"));
+    }
+    file.seek(io::SeekFrom::End(-4))?;
+    let mut isize = [0u8; 4];
+    file.read_exact(&mut isize)?;
+    Ok(u32::from_le_bytes(isize) as u64)
+}
+
+/// 从首个 zstd 帧头读取原始大小；流式压缩未写入大小时返回 `None`
+fn zst_original_size(path: &Path) -> io::Result<Option<u64>> {
+    let mut header = Vec::with_capacity(18);
+    std::fs::File::open(path)?
+        .take(18)
+        .read_to_end(&mut header)?;
+    zstd::zstd_safe::get_frame_content_size(&header)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid zstd frame header"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_bytes(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_preview_counts_nested_archives_and_unsupported_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = zip_bytes(&[("db.log", b"0123456789")]);
+        let outer = zip_bytes(&[("api/app.log", b"hello world\n"), ("inner.zip", &inner)]);
+        std::fs::write(dir.path().join("bundle.zip"), &outer).unwrap();
+        std::fs::write(dir.path().join("plain.log"), b"abc\n").unwrap();
+        std::fs::write(dir.path().join("old.log.lzma"), b"lzma").unwrap();
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(&[b'x'; 1000]).unwrap();
+        std::fs::write(dir.path().join("rotated.log.gz"), gz.finish().unwrap()).unwrap();
+
+        let mut config = AppConfig::default();
+        config.file_filter.enabled = false;
+        config.file_filter.binary_detection_enabled = false;
+        let preview = preview_import(dir.path(), &config, None).unwrap();
+
+        // app.log、db.log、plain.log、rotated.log
+        assert_eq!(preview.file_count, 4);
+        assert_eq!(preview.archive_count, 3);
+        assert_eq!(preview.max_nesting_depth, 2);
+        assert_eq!(preview.uncompressed_bytes, 12 + 10 + 4 + 1000);
+        assert!(!preview.sizes_estimated);
+        assert_eq!(preview.unsupported.len(), 1);
+        assert_eq!(preview.unsupported[0].path, "old.log.lzma");
+        assert!(preview.baseline_measured_at.is_none());
+        assert!(preview.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_preview_reads_zstd_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = [b'x'; 1000];
+        std::fs::write(
+            dir.path().join("app.log.zst"),
+            zstd::bulk::compress(&content, 3).unwrap(),
+        )
+        .unwrap();
+
+        let mut tar = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(12);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, "api/app.log", &b"hello world\n"[..])
+            .unwrap();
+        let tar_bytes = tar.into_inner().unwrap();
+        std::fs::write(
+            dir.path().join("bundle.tar.zst"),
+            zstd::encode_all(&tar_bytes[..], 3).unwrap(),
+        )
+        .unwrap();
+
+        let mut config = AppConfig::default();
+        config.file_filter.enabled = false;
+        config.file_filter.binary_detection_enabled = false;
+        let preview = preview_import(dir.path(), &config, None).unwrap();
+
+        assert_eq!(preview.file_count, 2);
+        assert_eq!(preview.archive_count, 2);
+        assert_eq!(preview.uncompressed_bytes, 1000 + 12);
+        assert!(!preview.sizes_estimated);
+        assert!(preview.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_preview_reads_xz_and_bzip2_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tar = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(12);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, "var/log/syslog", &b"hello world\n"[..])
+            .unwrap();
+        let mut xz = xz2::write::XzEncoder::new(Vec::new(), 6);
+        xz.write_all(&tar.into_inner().unwrap()).unwrap();
+        std::fs::write(dir.path().join("journal.tar.xz"), xz.finish().unwrap()).unwrap();
+
+        let mut bz2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        bz2.write_all(&[b'x'; 1000]).unwrap();
+        std::fs::write(dir.path().join("messages.1.bz2"), bz2.finish().unwrap()).unwrap();
+
+        let mut config = AppConfig::default();
+        config.file_filter.enabled = false;
+        config.file_filter.binary_detection_enabled = false;
+        let preview = preview_import(dir.path(), &config, None).unwrap();
+
+        assert_eq!(preview.file_count, 2);
+        assert_eq!(preview.archive_count, 2);
+        // 单文件 BZ2 没有原始大小记录，按假定压缩比估算
+        assert!(preview.sizes_estimated);
+        assert!(preview.uncompressed_bytes > 12);
+        assert!(preview.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_duration_uses_baseline_throughput() {
+        let baseline = BenchmarkResults {
+            cpu_count: 8,
+            disk_write_mb_per_sec: 100.0,
+            disk_read_mb_per_sec: 200.0,
+            sha256_mb_per_sec: 400.0,
+            keyword_match_mb_per_sec: 0.0,
+            regex_match_mb_per_sec: 0.0,
+            sqlite_inserts_per_sec: 1_000.0,
+            measured_at: "2024-01-01T00:00:00Z".into(),
+            total_duration_ms: 0,
+        };
+        let mb = 1024 * 1024;
+        // 200 MB / 200 + 400 MB / 100 + 400 MB / 400 + 1000 / 1000 = 1 + 4 + 1 + 1
+        let estimate = estimate_import_duration(200 * mb, 400 * mb, 1_000, Some(&baseline));
+        assert_eq!(estimate.as_secs(), 7);
+
+        let fallback = estimate_import_duration(200 * mb, 400 * mb, 1_000, None);
+        assert!(fallback > estimate);
+    }
+}
@@ -1,7 +1,33 @@
+pub mod analysis_macros;
+pub mod assistant_tools;
+pub mod bundle_manifest;
+pub mod chunked_upload;
+pub mod clock_skew;
+pub mod cooccurrence;
+pub mod export_destinations;
+pub mod external_links;
+pub mod field_extractors;
+pub mod file_overrides;
 pub mod file_watcher;
+pub mod follow_query;
+pub mod import_preview;
+pub mod polling_watcher;
+pub mod query_lint;
 pub mod query_planner;
+pub mod quick_scan;
+pub mod refresh_preview;
 pub mod regex_engine;
+pub mod remote_workspace;
+pub mod saved_results;
+pub mod search_diff;
 pub mod search_filters;
+pub mod silence_detection;
+pub mod startup_check;
+pub mod translation;
+pub mod watcher_budget;
+pub mod webhooks;
+pub mod workspace_share;
+pub mod workspace_templates;
 
 #[cfg(test)]
 mod error_handling_property_tests;
@@ -16,7 +42,7 @@ mod concurrency_property_tests;
 // querier/searcher 类型
 pub use query_planner::ExecutionPlan;
 // query_planner: export standalone validation for frontend type-ahead
-pub use query_planner::compute_query_cache_key;
+pub use query_planner::compute_query_fingerprint;
 pub use query_planner::QueryPlanner;
 // regex：仅 export commands/search/query.rs 需要的
 pub use regex_engine::looks_like_regex_pattern;
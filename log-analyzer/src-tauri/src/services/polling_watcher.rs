@@ -0,0 +1,395 @@
+//! 轮询文件扫描器（原生监听的后备方案）
+//!
+//! `notify` 的原生后端在 SMB / NFS 等网络文件系统上收不到其他主机写入产生的事件，
+//! 超出监听预算时也无法使用原生监听。此时改为周期扫描：
+//! - 对比每个文件的 mtime / size 快照，产生 Create / Modify / Remove 事件（不读取内容）
+//! - 扫描间隔自适应：检测到变更后回落到最小间隔，连续空闲时逐步翻倍直到上限
+//! - 当前间隔可随时读取，供前端解释为何延迟与原生监听不同
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crossbeam::channel::{RecvTimeoutError, Sender};
+
+use crate::application::watch::{WatchEvent, WatchEventKind};
+
+/// 已知的网络 / 远程文件系统类型（Linux `/proc/self/mounts`、macOS `f_fstypename`）
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb",
+    "smb2",
+    "smb3",
+    "smbfs",
+    "afpfs",
+    "webdav",
+    "davfs",
+    "9p",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+/// 自适应扫描间隔
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveInterval {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl AdaptiveInterval {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        let max = max.max(min);
+        Self {
+            min,
+            max,
+            current: min,
+        }
+    }
+
+    pub fn current(&self) -> Duration {
+        self.current
+    }
+
+    /// 根据本轮是否有变更计算下一轮间隔
+    pub fn on_scan(&mut self, changed: bool) -> Duration {
+        self.current = if changed {
+            self.min
+        } else {
+            (self.current * 2).min(self.max)
+        };
+        self.current
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    size: u64,
+}
+
+type Snapshot = HashMap<PathBuf, FileStamp>;
+
+/// 遍历 `root` 生成快照；每个目录项前检查 `stop`，已停止时返回 `None`
+/// （网络盘上一次遍历可能长达数分钟，不能等它走完才退出）
+fn scan(root: &Path, stop: &AtomicBool) -> Option<Snapshot> {
+    let mut snapshot = Snapshot::new();
+    for entry in walkdir::WalkDir::new(root).follow_links(false) {
+        if stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else { continue };
+        snapshot.insert(
+            entry.into_path(),
+            FileStamp {
+                modified: meta.modified().ok(),
+                size: meta.len(),
+            },
+        );
+    }
+    Some(snapshot)
+}
+
+/// 对比两次快照，按事件类型聚合变更路径
+fn diff(old: &Snapshot, new: &Snapshot) -> Vec<WatchEvent> {
+    let mut created = Vec::new();
+    let mut modified = Vec::new();
+    for (path, stamp) in new {
+        match old.get(path) {
+            None => created.push(path.clone()),
+            Some(prev) if prev != stamp => modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    let removed: Vec<PathBuf> = old
+        .keys()
+        .filter(|p| !new.contains_key(*p))
+        .cloned()
+        .collect();
+
+    [
+        (WatchEventKind::Create, created),
+        (WatchEventKind::Modify, modified),
+        (WatchEventKind::Remove, removed),
+    ]
+    .into_iter()
+    .filter(|(_, paths)| !paths.is_empty())
+    .map(|(kind, mut paths)| {
+        paths.sort();
+        WatchEvent { kind, paths }
+    })
+    .collect()
+}
+
+/// 后台轮询扫描器；drop 时停止扫描线程并等待其退出（进行中的遍历在下一个目录项处中止）
+pub struct PollingScanner {
+    stop_tx: Option<Sender<()>>,
+    stopped: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+    current_interval_ms: Arc<AtomicU64>,
+}
+
+impl PollingScanner {
+    /// 以 `root` 当前状态为基线开始扫描，变更事件发送到 `events`
+    pub fn start(
+        root: PathBuf,
+        min_interval: Duration,
+        max_interval: Duration,
+        events: Sender<WatchEvent>,
+    ) -> Self {
+        let (stop_tx, stop_rx) = crossbeam::channel::bounded::<()>(0);
+        let mut interval = AdaptiveInterval::new(min_interval, max_interval);
+        let current_interval_ms = Arc::new(AtomicU64::new(interval.current().as_millis() as u64));
+        let current = Arc::clone(&current_interval_ms);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stop = Arc::clone(&stopped);
+
+        let handle = std::thread::spawn(move || {
+            let Some(mut snapshot) = scan(&root, &stop) else {
+                return;
+            };
+            // 停止通道断开（或收到信号）时退出，超时则进行一轮扫描
+            while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval.current()) {
+                let Some(next) = scan(&root, &stop) else {
+                    return;
+                };
+                let changes = diff(&snapshot, &next);
+                snapshot = next;
+
+                let changed = !changes.is_empty();
+                for event in changes {
+                    if events.send(event).is_err() {
+                        return;
+                    }
+                }
+                let next_interval = interval.on_scan(changed);
+                current.store(next_interval.as_millis() as u64, Ordering::Relaxed);
+            }
+        });
+
+        Self {
+            stop_tx: Some(stop_tx),
+            stopped,
+            handle: Some(handle),
+            current_interval_ms,
+        }
+    }
+
+    /// 当前扫描间隔
+    pub fn current_interval(&self) -> Duration {
+        Duration::from_millis(self.current_interval_ms.load(Ordering::Relaxed))
+    }
+}
+
+impl Drop for PollingScanner {
+    fn drop(&mut self) {
+        // 标记停止以中止进行中的遍历，断开停止通道以唤醒等待中的扫描线程
+        self.stopped.store(true, Ordering::Relaxed);
+        self.stop_tx.take();
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                tracing::error!("Polling scanner thread panicked");
+            }
+        }
+    }
+}
+
+fn is_network_fs_type(fs_type: &str) -> bool {
+    let fs_type = fs_type.to_ascii_lowercase();
+    NETWORK_FS_TYPES.contains(&fs_type.as_str())
+}
+
+/// 从 `/proc/self/mounts` 内容中找出 `path` 所在挂载点，若为网络文件系统则返回其类型
+fn network_fs_from_mounts(mounts: &str, path: &Path) -> Option<String> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            // 挂载点中的空格被转义为 \040
+            let mount_point = PathBuf::from(fields.next()?.replace("\\040", " "));
+            let fs_type = fields.next()?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.components().count(), fs_type.to_string()))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, fs_type)| fs_type)
+        .filter(|fs_type| is_network_fs_type(fs_type))
+}
+
+/// 检测 `path` 是否位于网络文件系统上，返回文件系统类型
+#[cfg(target_os = "linux")]
+pub fn detect_network_filesystem(path: &Path) -> Option<String> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    network_fs_from_mounts(&mounts, &path)
+}
+
+/// 检测 `path` 是否位于网络文件系统上，返回文件系统类型
+#[cfg(target_os = "macos")]
+pub fn detect_network_filesystem(path: &Path) -> Option<String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statfs 为纯数据结构，c_path 为合法的 NUL 结尾字符串
+    let fs_type = unsafe {
+        let mut stat: libc::statfs = std::mem::zeroed();
+        if libc::statfs(c_path.as_ptr(), &mut stat) != 0 {
+            return None;
+        }
+        std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr())
+            .to_string_lossy()
+            .into_owned()
+    };
+    is_network_fs_type(&fs_type).then_some(fs_type)
+}
+
+/// 检测 `path` 是否位于网络文件系统上（UNC 路径或映射的网络驱动器）
+#[cfg(target_os = "windows")]
+pub fn detect_network_filesystem(path: &Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::{Component, Prefix};
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+
+    /// GetDriveTypeW 返回值：远程（网络）驱动器
+    const DRIVE_REMOTE: u32 = 4;
+
+    match path.components().next()? {
+        Component::Prefix(prefix) => match prefix.kind() {
+            Prefix::UNC(..) | Prefix::VerbatimUNC(..) => Some("unc".to_string()),
+            Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => {
+                let root: Vec<u16> = std::ffi::OsStr::new(&format!("{}:\\", letter as char))
+                    .encode_wide()
+                    .chain(std::iter::once(0))
+                    .collect();
+                // SAFETY: root 为 NUL 结尾的宽字符串
+                let drive_type = unsafe { GetDriveTypeW(root.as_ptr()) };
+                (drive_type == DRIVE_REMOTE).then(|| "remote".to_string())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 检测 `path` 是否位于网络文件系统上（不支持的平台始终返回 None）
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn detect_network_filesystem(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_interval_backs_off_and_resets() {
+        let mut interval = AdaptiveInterval::new(Duration::from_secs(1), Duration::from_secs(5));
+        assert_eq!(interval.on_scan(false), Duration::from_secs(2));
+        assert_eq!(interval.on_scan(false), Duration::from_secs(4));
+        assert_eq!(interval.on_scan(false), Duration::from_secs(5));
+        assert_eq!(interval.on_scan(true), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_diff_detects_create_modify_remove() {
+        let stamp = |size| FileStamp {
+            modified: None,
+            size,
+        };
+        let old: Snapshot = [
+            (PathBuf::from("a.log"), stamp(1)),
+            (PathBuf::from("b.log"), stamp(1)),
+        ]
+        .into();
+        let new: Snapshot = [
+            (PathBuf::from("a.log"), stamp(2)),
+            (PathBuf::from("c.log"), stamp(1)),
+        ]
+        .into();
+
+        let events = diff(&old, &new);
+        let kinds: Vec<_> = events.iter().map(|e| (e.kind, e.paths.clone())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (WatchEventKind::Create, vec![PathBuf::from("c.log")]),
+                (WatchEventKind::Modify, vec![PathBuf::from("a.log")]),
+                (WatchEventKind::Remove, vec![PathBuf::from("b.log")]),
+            ]
+        );
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_network_fs_from_mounts_uses_longest_mount_point() {
+        let mounts = "\
+/dev/sda1 / ext4 rw 0 0
+server:/export /mnt/logs nfs4 rw 0 0
+//host/share /mnt/logs/win\\040share cifs rw 0 0
+/dev/sdb1 /mnt/logs/local ext4 rw 0 0
+";
+        assert_eq!(
+            network_fs_from_mounts(mounts, Path::new("/mnt/logs/app")),
+            Some("nfs4".to_string())
+        );
+        assert_eq!(
+            network_fs_from_mounts(mounts, Path::new("/mnt/logs/win share/x")),
+            Some("cifs".to_string())
+        );
+        assert_eq!(
+            network_fs_from_mounts(mounts, Path::new("/mnt/logs/local/x")),
+            None
+        );
+        assert_eq!(network_fs_from_mounts(mounts, Path::new("/home")), None);
+    }
+
+    #[test]
+    fn test_scanner_emits_events() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("existing.log"), "x").unwrap();
+
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let scanner = PollingScanner::start(
+            dir.path().to_path_buf(),
+            Duration::from_millis(20),
+            Duration::from_millis(40),
+            tx,
+        );
+        std::thread::sleep(Duration::from_millis(30));
+        std::fs::write(dir.path().join("new.log"), "y").unwrap();
+
+        let event = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert_eq!(event.kind, WatchEventKind::Create);
+        assert_eq!(event.paths, vec![dir.path().join("new.log")]);
+
+        drop(scanner);
+        // 扫描线程退出后发送端被释放
+        assert!(rx.recv_timeout(Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_scan_stops_mid_walk() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.log"), "x").unwrap();
+
+        let stop = AtomicBool::new(false);
+        let snapshot = scan(dir.path(), &stop).unwrap();
+        assert!(snapshot.contains_key(&dir.path().join("app.log")));
+
+        stop.store(true, Ordering::Relaxed);
+        assert!(scan(dir.path(), &stop).is_none());
+    }
+}
@@ -0,0 +1,433 @@
+//! 查询检查（lint）与改写建议
+//!
+//! 在扫描之前找出“能跑但不划算”的查询：重复或被蕴含的关键词、与级别过滤矛盾的组合、
+//! 大小写设置导致导入时的关键词摘要无法剪枝、实际不含元字符的正则等。
+//! 检查结果都不是错误；可安全自动修正的部分汇总为 `rewritten_query`。
+
+use la_core::models::search::{QueryOperator, SearchQuery, SearchTerm};
+use la_core::utils::level_to_mask;
+use serde::Serialize;
+
+/// 关键词摘要只收录 ≥3 字节的词（与导入时的三元组摘要一致）
+const SKETCH_MIN_TERM_BYTES: usize = 3;
+
+/// 在正则中有特殊含义的字符（含 `.` 与 `$`，与前端的启发式检测不同）
+const REGEX_SYNTAX_CHARS: &[char] = &[
+    '\\', '.', '+', '*', '?', '(', ')', '|', '[', ']', '{', '}', '^', '$',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryLintKind {
+    /// 与前面的关键词完全相同
+    DuplicateTerm,
+    /// 被另一个关键词蕴含，删除不改变结果
+    RedundantTerm,
+    /// 与级别过滤矛盾，几乎不可能命中
+    AlwaysFalse,
+    /// 大小写设置与导入时的关键词摘要（仅 ASCII 小写化）不匹配
+    CaseSensitivity,
+    /// 标记为正则但不含任何元字符
+    LiteralRegex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryLintSeverity {
+    Info,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLint {
+    pub kind: QueryLintKind,
+    pub severity: QueryLintSeverity,
+    /// 涉及的关键词 id
+    pub term_ids: Vec<String>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLintReport {
+    pub lints: Vec<QueryLint>,
+    /// 应用所有可安全自动修正的建议后的查询；无可修正项时为 None
+    pub rewritten_query: Option<SearchQuery>,
+}
+
+impl QueryLintReport {
+    pub fn is_clean(&self) -> bool {
+        self.lints.is_empty()
+    }
+}
+
+/// 检查查询；级别过滤取自 `query.filters.levels`
+pub fn lint_query(query: &SearchQuery) -> QueryLintReport {
+    let levels: &[String] = query
+        .filters
+        .as_ref()
+        .and_then(|f| f.levels.as_deref())
+        .unwrap_or_default();
+
+    let terms: Vec<&SearchTerm> = query.terms.iter().filter(|t| t.enabled).collect();
+    let mut lints = Vec::new();
+    let mut dropped = vec![false; terms.len()];
+    let mut rewrites: Vec<(usize, TermRewrite)> = Vec::new();
+
+    lint_duplicates(&terms, &mut dropped, &mut lints);
+    lint_redundant(&query.global_operator, &terms, &mut dropped, &mut lints);
+
+    for (index, term) in terms.iter().enumerate() {
+        if dropped[index] {
+            continue;
+        }
+        if query.global_operator == QueryOperator::And {
+            lint_level_conflict(term, levels, &mut lints);
+        }
+        if let Some(rewrite) = lint_case_sensitivity(term, &mut lints) {
+            rewrites.push((index, rewrite));
+        }
+        if let Some(rewrite) = lint_literal_regex(term, &mut lints) {
+            rewrites.push((index, rewrite));
+        }
+    }
+
+    let rewritten_query = (dropped.contains(&true) || !rewrites.is_empty()).then(|| {
+        let mut rewritten = query.clone();
+        let mut kept = Vec::with_capacity(rewritten.terms.len());
+        let mut enabled_index = 0;
+        for mut term in rewritten.terms.drain(..) {
+            if !term.enabled {
+                kept.push(term);
+                continue;
+            }
+            let index = enabled_index;
+            enabled_index += 1;
+            if dropped[index] {
+                continue;
+            }
+            for (_, rewrite) in rewrites.iter().filter(|(i, _)| *i == index) {
+                rewrite.apply(&mut term);
+            }
+            kept.push(term);
+        }
+        rewritten.terms = kept;
+        rewritten
+    });
+
+    QueryLintReport {
+        lints,
+        rewritten_query,
+    }
+}
+
+enum TermRewrite {
+    CaseSensitive,
+    PlainKeyword,
+}
+
+impl TermRewrite {
+    fn apply(&self, term: &mut SearchTerm) {
+        match self {
+            Self::CaseSensitive => term.case_sensitive = true,
+            Self::PlainKeyword => term.is_regex = false,
+        }
+    }
+}
+
+fn lint_duplicates(terms: &[&SearchTerm], dropped: &mut [bool], lints: &mut Vec<QueryLint>) {
+    for later in 1..terms.len() {
+        let duplicate_of = (0..later)
+            .find(|&earlier| !dropped[earlier] && same_term(terms[earlier], terms[later]));
+        if let Some(earlier) = duplicate_of {
+            dropped[later] = true;
+            lints.push(QueryLint {
+                kind: QueryLintKind::DuplicateTerm,
+                severity: QueryLintSeverity::Warning,
+                term_ids: vec![terms[earlier].id.clone(), terms[later].id.clone()],
+                message: format!("Term '{}' appears more than once", terms[later].value),
+                suggestion: Some(format!("Remove term {}", terms[later].id)),
+            });
+        }
+    }
+}
+
+/// AND 下较短的词被较长的词蕴含；OR / NOT 下较长的词被较短的词覆盖
+fn lint_redundant(
+    operator: &QueryOperator,
+    terms: &[&SearchTerm],
+    dropped: &mut [bool],
+    lints: &mut Vec<QueryLint>,
+) {
+    for redundant in 0..terms.len() {
+        if dropped[redundant] {
+            continue;
+        }
+        let covering = (0..terms.len()).find(|&other| {
+            if other == redundant || dropped[other] {
+                return false;
+            }
+            match operator {
+                QueryOperator::And => implies(terms[other], terms[redundant]),
+                QueryOperator::Or | QueryOperator::Not => implies(terms[redundant], terms[other]),
+            }
+        });
+        let Some(other) = covering else {
+            continue;
+        };
+        dropped[redundant] = true;
+        let reason = match operator {
+            QueryOperator::And => format!(
+                "every line containing '{}' also contains '{}'",
+                terms[other].value, terms[redundant].value
+            ),
+            QueryOperator::Or | QueryOperator::Not => format!(
+                "every line containing '{}' already matches '{}'",
+                terms[redundant].value, terms[other].value
+            ),
+        };
+        lints.push(QueryLint {
+            kind: QueryLintKind::RedundantTerm,
+            severity: QueryLintSeverity::Warning,
+            term_ids: vec![terms[redundant].id.clone(), terms[other].id.clone()],
+            message: format!(
+                "Term '{}' does not change the result: {reason}",
+                terms[redundant].value
+            ),
+            suggestion: Some(format!("Remove term {}", terms[redundant].id)),
+        });
+    }
+}
+
+/// 行按第一个级别关键词归类，搜索某个级别名却只允许其他级别，几乎不会命中
+fn lint_level_conflict(term: &SearchTerm, levels: &[String], lints: &mut Vec<QueryLint>) {
+    if term.is_regex || levels.is_empty() {
+        return;
+    }
+    let term_mask = level_to_mask(&term.value);
+    if term_mask == 0 {
+        return;
+    }
+    let allowed = levels
+        .iter()
+        .fold(0, |mask, level| mask | level_to_mask(level));
+    if allowed & term_mask != 0 {
+        return;
+    }
+    lints.push(QueryLint {
+        kind: QueryLintKind::AlwaysFalse,
+        severity: QueryLintSeverity::Warning,
+        term_ids: vec![term.id.clone()],
+        message: format!(
+            "Term '{}' names a log level, but the level filter only allows {}; lines are classified by their level keyword, so this combination almost never matches",
+            term.value,
+            levels.join(", ")
+        ),
+        suggestion: Some(format!(
+            "Add {} to the level filter, or remove term {}",
+            term.value.trim().to_ascii_uppercase(),
+            term.id
+        )),
+    });
+}
+
+/// 导入时的关键词摘要只做 ASCII 小写化：不区分大小写的非 ASCII 词无法用它跳过文件。
+/// 不含大小写字母的文字（如中文）改为区分大小写不影响结果，可自动修正。
+fn lint_case_sensitivity(term: &SearchTerm, lints: &mut Vec<QueryLint>) -> Option<TermRewrite> {
+    if term.is_regex
+        || term.case_sensitive
+        || term.value.is_ascii()
+        || term.value.len() < SKETCH_MIN_TERM_BYTES
+    {
+        return None;
+    }
+    let has_case = term
+        .value
+        .chars()
+        .any(|c| !c.is_ascii() && (c.is_lowercase() || c.is_uppercase()));
+    lints.push(QueryLint {
+        kind: QueryLintKind::CaseSensitivity,
+        severity: QueryLintSeverity::Info,
+        term_ids: vec![term.id.clone()],
+        message: format!(
+            "Case-insensitive term '{}' contains non-ASCII text, so the workspace term index cannot skip files and every file is scanned",
+            term.value
+        ),
+        suggestion: Some(if has_case {
+            format!(
+                "Enable case sensitivity for term {} if the exact casing is known",
+                term.id
+            )
+        } else {
+            format!(
+                "Enable case sensitivity for term {} (it has no letter case, results are unchanged)",
+                term.id
+            )
+        }),
+    });
+    (!has_case).then_some(TermRewrite::CaseSensitive)
+}
+
+fn lint_literal_regex(term: &SearchTerm, lints: &mut Vec<QueryLint>) -> Option<TermRewrite> {
+    if !term.is_regex || term.value.contains(REGEX_SYNTAX_CHARS) {
+        return None;
+    }
+    lints.push(QueryLint {
+        kind: QueryLintKind::LiteralRegex,
+        severity: QueryLintSeverity::Info,
+        term_ids: vec![term.id.clone()],
+        message: format!(
+            "Term '{}' is marked as regex but contains no regex syntax",
+            term.value
+        ),
+        suggestion: Some(format!(
+            "Search term {} as a plain keyword (faster engine)",
+            term.id
+        )),
+    });
+    Some(TermRewrite::PlainKeyword)
+}
+
+fn same_term(a: &SearchTerm, b: &SearchTerm) -> bool {
+    a.is_regex == b.is_regex
+        && a.case_sensitive == b.case_sensitive
+        && if a.case_sensitive {
+            a.value == b.value
+        } else {
+            a.value.to_lowercase() == b.value.to_lowercase()
+        }
+}
+
+/// 命中 `a` 的行是否必然命中 `b`（仅判断纯关键词的子串关系）
+fn implies(a: &SearchTerm, b: &SearchTerm) -> bool {
+    let plain = |t: &SearchTerm| !t.is_regex && !t.value.contains('|');
+    if !plain(a) || !plain(b) || a.value.len() <= b.value.len() {
+        return false;
+    }
+    if b.case_sensitive {
+        a.case_sensitive && a.value.contains(b.value.as_str())
+    } else {
+        a.value.to_lowercase().contains(&b.value.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use la_core::models::search::{QueryMetadata, SearchFilters, TermSource};
+
+    fn term(id: &str, value: &str, is_regex: bool, case_sensitive: bool) -> SearchTerm {
+        SearchTerm {
+            id: id.to_string(),
+            value: value.to_string(),
+            operator: QueryOperator::And,
+            source: TermSource::User,
+            preset_group_id: None,
+            is_regex,
+            priority: 1,
+            enabled: true,
+            case_sensitive,
+        }
+    }
+
+    fn query(operator: QueryOperator, terms: Vec<SearchTerm>, levels: &[&str]) -> SearchQuery {
+        SearchQuery {
+            id: "q".to_string(),
+            terms,
+            global_operator: operator,
+            filters: (!levels.is_empty()).then(|| SearchFilters {
+                levels: Some(levels.iter().map(|l| l.to_string()).collect()),
+                time_range: None,
+                file_pattern: None,
+            }),
+            metadata: QueryMetadata {
+                created_at: 0,
+                last_modified: 0,
+                execution_count: 0,
+                label: None,
+            },
+        }
+    }
+
+    fn kinds(report: &QueryLintReport) -> Vec<QueryLintKind> {
+        report.lints.iter().map(|l| l.kind).collect()
+    }
+
+    #[test]
+    fn test_redundant_and_duplicate_terms_are_rewritten() {
+        let and = lint_query(&query(
+            QueryOperator::And,
+            vec![
+                term("t1", "timeout", false, false),
+                term("t2", "connection timeout", false, false),
+                term("t3", "Connection Timeout", false, false),
+            ],
+            &[],
+        ));
+        assert_eq!(
+            kinds(&and),
+            vec![QueryLintKind::DuplicateTerm, QueryLintKind::RedundantTerm]
+        );
+        let rewritten = and.rewritten_query.unwrap();
+        let ids: Vec<_> = rewritten.terms.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["t2"]);
+
+        // OR 下保留较短的词
+        let or = lint_query(&query(
+            QueryOperator::Or,
+            vec![
+                term("t1", "connection timeout", false, false),
+                term("t2", "timeout", false, false),
+            ],
+            &[],
+        ));
+        assert_eq!(or.lints[0].term_ids, vec!["t1", "t2"]);
+        assert_eq!(or.rewritten_query.unwrap().terms[0].id, "t2");
+
+        // 区分大小写的短词不被不区分大小写的长词蕴含
+        let mixed = lint_query(&query(
+            QueryOperator::And,
+            vec![
+                term("t1", "Timeout", false, true),
+                term("t2", "connection timeout", false, false),
+            ],
+            &[],
+        ));
+        assert!(mixed.is_clean());
+        assert!(mixed.rewritten_query.is_none());
+    }
+
+    #[test]
+    fn test_level_conflict_case_and_literal_regex() {
+        let report = lint_query(&query(
+            QueryOperator::And,
+            vec![
+                term("t1", "ERROR", false, true),
+                term("t2", "磁盘已满", false, false),
+                term("t3", "disk-full", true, false),
+            ],
+            &["info"],
+        ));
+        assert_eq!(
+            kinds(&report),
+            vec![
+                QueryLintKind::AlwaysFalse,
+                QueryLintKind::CaseSensitivity,
+                QueryLintKind::LiteralRegex
+            ]
+        );
+        let rewritten = report.rewritten_query.unwrap();
+        assert!(rewritten.terms[1].case_sensitive);
+        assert!(!rewritten.terms[2].is_regex);
+
+        // 级别过滤包含该级别时不报
+        let allowed = lint_query(&query(
+            QueryOperator::And,
+            vec![term("t1", "error", false, false)],
+            &["ERROR", "WARN"],
+        ));
+        assert!(allowed.is_clean());
+    }
+}
@@ -1,10 +1,15 @@
-use crate::services::regex_engine::{EngineType, MatchResult, RegexEngine};
+use crate::services::query_lint::{lint_query, QueryLintReport};
+use crate::services::regex_engine::{
+    build_limited_fancy_regex, build_limited_regex, check_regex_safety, extract_required_literals,
+    map_fancy_regex_error, map_regex_error, needs_lookaround, EngineError, EngineType, MatchResult,
+    MultiKeywordMatcher, RegexEngine,
+};
 use la_core::domain::MatchPlan;
 use la_core::error::{AppError, Result};
 use la_core::models::match_detail::MatchDetail;
 use la_core::models::search::*;
+use moka::policy::EvictionPolicy;
 use moka::sync::Cache;
-use regex::Regex;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
@@ -18,35 +23,30 @@ fn hash_query_operator(op: &QueryOperator, hasher: &mut DefaultHasher) {
     }
 }
 
-/// 计算查询缓存键
+/// 计划缓存容量（按最近使用淘汰）
+const PLAN_CACHE_CAPACITY: u64 = 256;
+/// 单个编译后正则引擎的估算占用（含 DFA 缓存）
+const ENGINE_ESTIMATE_BYTES: u64 = 16 * 1024;
+/// 单个缓存执行计划的估算占用
+const PLAN_ESTIMATE_BYTES: u64 = 2 * 1024;
+
+/// 计算归一化的查询指纹
 ///
-/// 统一缓存键生成逻辑，确保等价的查询产生相同的缓存键。
-pub fn compute_query_cache_key(query: &SearchQuery) -> u64 {
+/// 只包含影响执行计划的字段：全局运算符与启用的搜索词（按 id 排序）。
+/// 查询 id、过滤器、元数据和禁用的搜索词不参与，因此重复或只调整过滤条件的搜索共享同一计划。
+pub fn compute_query_fingerprint(query: &SearchQuery) -> u64 {
     let mut hasher = DefaultHasher::new();
-    query.id.hash(&mut hasher);
     hash_query_operator(&query.global_operator, &mut hasher);
 
-    if let Some(filters) = &query.filters {
-        if let Some(time_range) = &filters.time_range {
-            time_range.start.hash(&mut hasher);
-            time_range.end.hash(&mut hasher);
-        }
-        filters.levels.hash(&mut hasher);
-        filters.file_pattern.hash(&mut hasher);
-    }
-
-    let mut sorted_terms: Vec<_> = query.terms.iter().collect();
+    let mut sorted_terms: Vec<_> = query.terms.iter().filter(|t| t.enabled).collect();
     sorted_terms.sort_unstable_by(|a, b| a.id.cmp(&b.id));
 
     for term in sorted_terms {
         term.id.hash(&mut hasher);
         term.value.hash(&mut hasher);
         hash_query_operator(&term.operator, &mut hasher);
-        term.source.hash(&mut hasher);
-        term.preset_group_id.hash(&mut hasher);
         term.is_regex.hash(&mut hasher);
         term.priority.hash(&mut hasher);
-        term.enabled.hash(&mut hasher);
         term.case_sensitive.hash(&mut hasher);
     }
 
@@ -58,7 +58,7 @@ pub fn compute_query_cache_key(query: &SearchQuery) -> u64 {
  *
  * 负责构建搜索查询的执行计划。包含两层缓存：
  * - engine_cache: RegexEngine 编译缓存
- * - plan_cache: ExecutionPlan 缓存
+ * - plan_cache: ExecutionPlan 的 LRU 缓存，键为归一化的查询指纹
  *
  * 设计决策：使用混合引擎策略自动选择最佳匹配算法：
  * - AhoCorasick: 简单关键词搜索，O(n) 线性复杂度
@@ -122,7 +122,7 @@ impl QueryPlanner {
     pub fn new(max_capacity: usize) -> Self {
         Self {
             engine_cache: Cache::new(max_capacity as u64),
-            plan_cache: Cache::new(1000),
+            plan_cache: Self::new_plan_cache(),
         }
     }
 
@@ -132,8 +132,69 @@ impl QueryPlanner {
     pub fn with_default_capacity() -> Self {
         Self {
             engine_cache: Cache::new(1000),
-            plan_cache: Cache::new(1000),
+            plan_cache: Self::new_plan_cache(),
+        }
+    }
+
+    fn new_plan_cache() -> Cache<u64, Arc<ExecutionPlan>> {
+        Cache::builder()
+            .max_capacity(PLAN_CACHE_CAPACITY)
+            .eviction_policy(EvictionPolicy::lru())
+            .build()
+    }
+
+    /**
+     * 清空引擎与计划缓存（资源紧张时释放内存）
+     */
+    pub fn clear_caches(&self) {
+        self.engine_cache.invalidate_all();
+        self.plan_cache.invalidate_all();
+    }
+
+    /**
+     * 引擎与计划缓存的估算占用：(条目数, 字节)
+     *
+     * 编译后的正则与执行计划无法精确计量，按单条固定估值计算。
+     */
+    pub fn cache_usage(&self) -> (u64, u64) {
+        let (engines, engine_bytes) = self.engine_cache_usage();
+        self.plan_cache.run_pending_tasks();
+        let plans = self.plan_cache.entry_count();
+        (engines + plans, engine_bytes + plans * PLAN_ESTIMATE_BYTES)
+    }
+
+    /**
+     * 引擎缓存的估算占用：(条目数, 字节)
+     */
+    pub fn engine_cache_usage(&self) -> (u64, u64) {
+        self.engine_cache.run_pending_tasks();
+        let key_bytes: usize = self.engine_cache.iter().map(|(key, _)| key.len()).sum();
+        let engines = self.engine_cache.entry_count();
+        (engines, key_bytes as u64 + engines * ENGINE_ESTIMATE_BYTES)
+    }
+
+    /**
+     * 引擎缓存当前容量
+     */
+    pub fn engine_capacity(&self) -> u64 {
+        self.engine_cache.policy().max_capacity().unwrap_or(0)
+    }
+
+    /**
+     * 调整引擎缓存容量
+     *
+     * moka 缓存创建后容量不可变：新建缓存并迁移已编译的引擎，超出新容量的部分由淘汰策略移除。
+     */
+    pub fn set_engine_capacity(&mut self, capacity: usize) {
+        let capacity = capacity.max(1) as u64;
+        if self.engine_capacity() == capacity {
+            return;
+        }
+        let resized = Cache::new(capacity);
+        for (key, engine) in self.engine_cache.iter() {
+            resized.insert(key.as_ref().clone(), engine);
         }
+        self.engine_cache = resized;
     }
 
     /**
@@ -142,9 +203,12 @@ impl QueryPlanner {
      * 验证查询，检查计划缓存，委托给 build_plan 构建实际计划。
      */
     pub fn build(&mut self, query: &SearchQuery) -> Result<ExecutionPlan> {
-        self.validate_query(query)?;
+        let lints = Self::validate_query(query)?;
+        if !lints.is_clean() {
+            tracing::debug!(lints = lints.lints.len(), "Query has lint findings");
+        }
 
-        let cache_key = compute_query_cache_key(query);
+        let cache_key = compute_query_fingerprint(query);
         if let Some(cached_plan) = self.plan_cache.get(&cache_key) {
             return Ok((*cached_plan).clone());
         }
@@ -160,10 +224,11 @@ impl QueryPlanner {
     /**
      * 验证搜索查询
      *
-     * 将原 QueryValidator::validate 的逻辑内联到 QueryPlanner，
-     * 使验证成为 build 的私有步骤，不暴露为独立操作。
+     * 将原 QueryValidator::validate 的逻辑内联到 QueryPlanner。
+     * 致命问题返回错误；非致命问题（冗余词、矛盾组合等）以 lint 报告返回，
+     * 前端可在扫描前展示并应用改写建议。
      */
-    fn validate_query(&self, query: &SearchQuery) -> Result<()> {
+    pub fn validate_query(query: &SearchQuery) -> Result<QueryLintReport> {
         if query.terms.is_empty() {
             return Err(AppError::validation_error("Query is empty"));
         }
@@ -178,13 +243,14 @@ impl QueryPlanner {
             Self::validate_term(term)?;
         }
 
-        Ok(())
+        Ok(lint_query(query))
     }
 
     /// Validate a single search term.
     ///
-    /// Checks for empty value, excessive length (>100 chars), and regex syntax
-    /// validity (backreferences, lookaround). Callable independently for
+    /// Checks for empty value, excessive length (>100 chars), regex syntax
+    /// validity (backreferences, lookaround), and regex cost (counted
+    /// repetition, backtracking risk, compiled size). Callable independently for
     /// frontend type-ahead pre-validation without importing QueryPlanner.
     pub fn validate_term(term: &SearchTerm) -> Result<()> {
         if term.value.is_empty() {
@@ -209,18 +275,24 @@ impl QueryPlanner {
             )));
             }
 
-            let has_lookaround = term.value.contains("(?=")
-                || term.value.contains("(?!")
-                || term.value.contains("(?<=")
-                || term.value.contains("(?<!");
-            if has_lookaround {
-                fancy_regex::Regex::new(&term.value).map_err(|e| {
-                    AppError::validation_error(format!("Term {} has invalid regex: {}", term.id, e))
-                })?;
+            let term_error = |e: EngineError| match e {
+                EngineError::TooExpensive(reason) => AppError::validation_error(format!(
+                    "Term {} regex is too expensive: {}",
+                    term.id, reason
+                )),
+                other => AppError::validation_error(format!(
+                    "Term {} has invalid regex: {}",
+                    term.id, other
+                )),
+            };
+
+            check_regex_safety(&term.value).map_err(term_error)?;
+            if needs_lookaround(&term.value) {
+                build_limited_fancy_regex(&term.value)
+                    .map_err(|e| term_error(map_fancy_regex_error(e)))?;
             } else {
-                Regex::new(&term.value).map_err(|e| {
-                    AppError::validation_error(format!("Term {} has invalid regex: {}", term.id, e))
-                })?;
+                build_limited_regex(&term.value, true)
+                    .map_err(|e| term_error(map_regex_error(e)))?;
             }
         }
 
@@ -318,14 +390,84 @@ impl QueryPlanner {
         }
 
         let fast_or_engine = self.build_fast_or_engine(&enabled_terms, &strategy)?;
+        let keyword_matcher = Self::build_keyword_matcher(&enabled_terms, &engines, &strategy)?;
+        let regex_prefilters = Self::build_regex_prefilters(&enabled_terms, &engines);
 
-        Ok(ExecutionPlan::new(
+        let plan = ExecutionPlan::new(
             strategy,
             engines,
             enabled_terms.len(),
             terms_list,
             fast_or_engine,
-        ))
+        );
+        let plan = match keyword_matcher {
+            Some((matcher, covered)) => plan.with_keyword_matcher(matcher, covered),
+            None => plan,
+        };
+        Ok(plan.with_regex_prefilters(regex_prefilters))
+    }
+
+    /// 为正则词提取必含字面量，用多模式自动机预筛候选行，只有命中的行才交给正则引擎
+    fn build_regex_prefilters(
+        enabled_terms: &[&SearchTerm],
+        engines: &[CompiledEngine],
+    ) -> std::collections::BTreeMap<String, Arc<MultiKeywordMatcher>> {
+        enabled_terms
+            .iter()
+            .zip(engines)
+            .filter(|(term, compiled)| {
+                term.is_regex
+                    && matches!(
+                        compiled.engine.engine_type(),
+                        EngineType::Standard | EngineType::Fancy
+                    )
+            })
+            .filter_map(|(term, _)| {
+                let (pattern, _) = Self::engine_pattern(term);
+                let literals = extract_required_literals(&pattern)?;
+                let keywords: Vec<(&str, bool)> =
+                    literals.iter().map(|l| (l.as_str(), false)).collect();
+                let matcher = MultiKeywordMatcher::new(&keywords).ok()?;
+                Some((term.id.clone(), Arc::new(matcher)))
+            })
+            .collect()
+    }
+
+    /// AND / NOT 查询中的字面量关键词合并为一个多模式自动机，单遍扫描代替逐词扫描。
+    ///
+    /// 只接管本来就按字面量匹配的词（Memchr / 单模式 AhoCorasick 引擎），
+    /// 正则等其余词仍逐个执行，匹配语义不变。
+    fn build_keyword_matcher(
+        enabled_terms: &[&SearchTerm],
+        engines: &[CompiledEngine],
+        strategy: &SearchStrategy,
+    ) -> Result<Option<(Arc<MultiKeywordMatcher>, Vec<String>)>> {
+        if *strategy == SearchStrategy::Or {
+            return Ok(None);
+        }
+
+        let mut keywords = Vec::new();
+        let mut covered = Vec::new();
+        for (term, compiled) in enabled_terms.iter().zip(engines) {
+            let literal_engine = match compiled.engine.as_ref() {
+                RegexEngine::Memchr(_) => true,
+                RegexEngine::AhoCorasick(e) => e.pattern_count() == 1,
+                _ => false,
+            };
+            if term.is_regex || !literal_engine || regex::escape(&term.value) != term.value {
+                continue;
+            }
+            keywords.push((term.value.as_str(), !term.case_sensitive));
+            covered.push(term.id.clone());
+        }
+
+        if keywords.len() < 2 {
+            return Ok(None);
+        }
+
+        let matcher = MultiKeywordMatcher::new(&keywords)
+            .map_err(|e| AppError::validation_error(format!("Engine error: {e}")))?;
+        Ok(Some((Arc::new(matcher), covered)))
     }
 
     fn build_fast_or_engine(
@@ -379,7 +521,32 @@ impl QueryPlanner {
      * * `Err(AppError)` - 编译失败
      */
     fn get_or_compile_engine(&mut self, term: &SearchTerm) -> Result<Arc<RegexEngine>> {
-        let (pattern, use_ci) = if term.is_regex {
+        let (pattern, use_ci) = Self::engine_pattern(term);
+
+        let cache_key = format!(
+            "{}|{}|{}|{}",
+            pattern, term.is_regex, term.case_sensitive, use_ci
+        );
+
+        if let Some(cached) = self.engine_cache.get(&cache_key) {
+            return Ok(Arc::clone(&cached));
+        }
+
+        let engine = if use_ci {
+            RegexEngine::new_with_case(&pattern, term.is_regex, true)
+        } else {
+            RegexEngine::new(&pattern, term.is_regex)
+        }
+        .map_err(|e| AppError::validation_error(format!("Engine error: {e}")))?;
+
+        let result = Arc::new(engine);
+        self.engine_cache.insert(cache_key, Arc::clone(&result));
+        Ok(result)
+    }
+
+    /// 引擎实际编译的模式，以及是否交给引擎做大小写不敏感匹配
+    fn engine_pattern(term: &SearchTerm) -> (String, bool) {
+        if term.is_regex {
             let pattern = if term.case_sensitive || Self::regex_has_inline_case_flag(&term.value) {
                 term.value.clone()
             } else {
@@ -403,27 +570,7 @@ impl QueryPlanner {
                     (escaped, true)
                 }
             }
-        };
-
-        let cache_key = format!(
-            "{}|{}|{}|{}",
-            pattern, term.is_regex, term.case_sensitive, use_ci
-        );
-
-        if let Some(cached) = self.engine_cache.get(&cache_key) {
-            return Ok(Arc::clone(&cached));
         }
-
-        let engine = if use_ci {
-            RegexEngine::new_with_case(&pattern, term.is_regex, true)
-        } else {
-            RegexEngine::new(&pattern, term.is_regex)
-        }
-        .map_err(|e| AppError::validation_error(format!("Engine error: {e}")))?;
-
-        let result = Arc::new(engine);
-        self.engine_cache.insert(cache_key, Arc::clone(&result));
-        Ok(result)
     }
 }
 
@@ -441,6 +588,12 @@ pub struct ExecutionPlan {
     /// 比 HashMap 的 SipHash 开销更低（search query term 数通常 ≤ 10）
     engine_map: std::collections::BTreeMap<String, Arc<RegexEngine>>,
     execution_order: Vec<String>,
+    /// AND / NOT 下合并的字面量关键词；被接管的 term 不再出现在 residual_order 中
+    keyword_matcher: Option<Arc<MultiKeywordMatcher>>,
+    keyword_term_ids: Vec<String>,
+    residual_order: Vec<String>,
+    /// 正则词的必含字面量预筛（term_id → 自动机）
+    regex_prefilters: std::collections::BTreeMap<String, Arc<MultiKeywordMatcher>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -476,10 +629,57 @@ impl ExecutionPlan {
             terms,
             fast_or_engine,
             engine_map,
+            residual_order: execution_order.clone(),
             execution_order,
+            keyword_matcher: None,
+            keyword_term_ids: Vec::new(),
+            regex_prefilters: std::collections::BTreeMap::new(),
+        }
+    }
+
+    /// 设置正则词的字面量预筛
+    pub fn with_regex_prefilters(
+        mut self,
+        prefilters: std::collections::BTreeMap<String, Arc<MultiKeywordMatcher>>,
+    ) -> Self {
+        self.regex_prefilters = prefilters;
+        self
+    }
+
+    /// 单个 term 是否匹配：有预筛时先排除不含必含字面量的行
+    fn term_is_match(&self, term_id: &str, line: &str) -> bool {
+        if let Some(prefilter) = self.regex_prefilters.get(term_id) {
+            if !prefilter.matches_any(line) {
+                return false;
+            }
+        }
+        match self.engine_map.get(term_id) {
+            Some(engine) => Self::engine_is_match(engine, line),
+            None => false,
         }
     }
 
+    /// 由多模式自动机一次性匹配 `term_ids` 对应的字面量词
+    pub fn with_keyword_matcher(
+        mut self,
+        matcher: Arc<MultiKeywordMatcher>,
+        term_ids: Vec<String>,
+    ) -> Self {
+        self.keyword_matcher = Some(matcher);
+        self.keyword_term_ids = term_ids;
+        self.rebuild_residual_order();
+        self
+    }
+
+    fn rebuild_residual_order(&mut self) {
+        self.residual_order = self
+            .execution_order
+            .iter()
+            .filter(|id| !self.keyword_term_ids.contains(id))
+            .cloned()
+            .collect();
+    }
+
     fn build_execution_order(engines: &[CompiledEngine], terms: &[PlanTerm]) -> Vec<String> {
         let term_lengths = terms
             .iter()
@@ -562,6 +762,7 @@ impl ExecutionPlan {
             })
         });
         self.execution_order = Self::build_execution_order(&self.engines, &self.terms);
+        self.rebuild_residual_order();
     }
 
     // ── Matching methods (moved from QueryPlanBuilder) ──
@@ -570,12 +771,13 @@ impl ExecutionPlan {
     pub fn matches_line(&self, line: &str) -> bool {
         match self.strategy {
             SearchStrategy::And => {
-                for term_id in self.execution_term_ids() {
-                    let term_matches = if let Some(engine) = self.get_engine_for_term(term_id) {
-                        Self::engine_is_match(&engine, line)
-                    } else {
-                        false
-                    };
+                if let Some(matcher) = &self.keyword_matcher {
+                    if !matcher.matches_all(line) {
+                        return false;
+                    }
+                }
+                for term_id in &self.residual_order {
+                    let term_matches = self.term_is_match(term_id, line);
                     if !term_matches {
                         return false;
                     }
@@ -587,11 +789,7 @@ impl ExecutionPlan {
                     return Self::engine_is_match(engine.as_ref(), line);
                 }
                 for term_id in self.execution_term_ids() {
-                    let term_matches = if let Some(engine) = self.get_engine_for_term(term_id) {
-                        Self::engine_is_match(&engine, line)
-                    } else {
-                        false
-                    };
+                    let term_matches = self.term_is_match(term_id, line);
                     if term_matches {
                         return true;
                     }
@@ -599,12 +797,13 @@ impl ExecutionPlan {
                 false
             }
             SearchStrategy::Not => {
-                for term_id in self.execution_term_ids() {
-                    let term_matches = if let Some(engine) = self.get_engine_for_term(term_id) {
-                        Self::engine_is_match(&engine, line)
-                    } else {
-                        false
-                    };
+                if let Some(matcher) = &self.keyword_matcher {
+                    if matcher.matches_any(line) {
+                        return false;
+                    }
+                }
+                for term_id in &self.residual_order {
+                    let term_matches = self.term_is_match(term_id, line);
                     if term_matches {
                         return false;
                     }
@@ -994,6 +1193,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_term_rejects_expensive_regex() {
+        let mut term = create_test_term(r"\d{5000}", QueryOperator::And);
+        term.is_regex = true;
+
+        let error = QueryPlanner::validate_term(&term).unwrap_err();
+        assert!(
+            error.to_string().contains("regex is too expensive"),
+            "got: {error}"
+        );
+
+        term.value = r"(\w+\.)+com".to_string();
+        assert!(QueryPlanner::validate_term(&term).is_ok());
+    }
+
     #[test]
     fn test_build_plan_structure() {
         let mut planner = QueryPlanner::new(100);
@@ -1183,6 +1397,77 @@ mod tests {
             .is_match("warning: service degraded"));
     }
 
+    fn build_test_plan(terms: Vec<SearchTerm>, operator: QueryOperator) -> ExecutionPlan {
+        let mut planner = QueryPlanner::new(100);
+        let query = SearchQuery {
+            id: "test".to_string(),
+            terms,
+            global_operator: operator,
+            filters: None,
+            metadata: QueryMetadata {
+                created_at: 0,
+                last_modified: 0,
+                execution_count: 0,
+                label: None,
+            },
+        };
+        planner.build(&query).unwrap()
+    }
+
+    fn keyword_term(id: &str, value: &str, is_regex: bool) -> SearchTerm {
+        SearchTerm {
+            id: id.to_string(),
+            is_regex,
+            ..create_test_term(value, QueryOperator::And)
+        }
+    }
+
+    #[test]
+    fn test_and_plan_merges_literal_terms_into_keyword_matcher() {
+        let plan = build_test_plan(
+            vec![
+                keyword_term("1", "timeout", false),
+                keyword_term("2", "worker", false),
+                keyword_term("3", r"id=\d+", true),
+            ],
+            QueryOperator::And,
+        );
+
+        assert!(plan.keyword_matcher.is_some());
+        assert_eq!(plan.residual_order, vec!["3".to_string()]);
+        assert!(plan.matches_line("Timeout on worker id=42"));
+        assert!(!plan.matches_line("Timeout on worker id=x"));
+        assert!(!plan.matches_line("Timeout id=42"));
+    }
+
+    #[test]
+    fn test_not_plan_keyword_matcher_rejects_any_literal() {
+        let plan = build_test_plan(
+            vec![
+                keyword_term("1", "debug", false),
+                keyword_term("2", "trace", false),
+            ],
+            QueryOperator::Not,
+        );
+
+        assert!(plan.keyword_matcher.is_some());
+        assert!(!plan.matches_line("TRACE span closed"));
+        assert!(plan.matches_line("ERROR disk full"));
+    }
+
+    #[test]
+    fn test_regex_term_gets_literal_prefilter() {
+        let plan = build_test_plan(
+            vec![keyword_term("1", r"timeout after \d+ms", true)],
+            QueryOperator::And,
+        );
+
+        assert!(plan.regex_prefilters.contains_key("1"));
+        assert!(plan.matches_line("Timeout after 300ms"));
+        assert!(!plan.matches_line("timeout after soon"));
+        assert!(!plan.matches_line("connection reset"));
+    }
+
     // ========== 自动单词边界检测测试 ==========
     // 【修改】2025-01-30: 由于移除了自动单词边界检测，这些测试已更新
 
@@ -1475,4 +1760,87 @@ mod tests {
             matches.len()
         );
     }
+
+    #[test]
+    fn test_plan_cache_keyed_by_normalized_fingerprint() {
+        let mut planner = QueryPlanner::new(100);
+        let mut disabled = keyword_term("2", "debug", false);
+        disabled.enabled = false;
+        let query = SearchQuery {
+            id: "first".to_string(),
+            terms: vec![keyword_term("1", "timeout", false), disabled],
+            global_operator: QueryOperator::And,
+            filters: None,
+            metadata: QueryMetadata {
+                created_at: 0,
+                last_modified: 0,
+                execution_count: 0,
+                label: None,
+            },
+        };
+
+        // 查询 id、过滤器与禁用的搜索词不影响指纹
+        let refined = SearchQuery {
+            id: "second".to_string(),
+            terms: vec![keyword_term("1", "timeout", false)],
+            filters: Some(SearchFilters {
+                levels: Some(vec!["ERROR".to_string()]),
+                time_range: None,
+                file_pattern: None,
+            }),
+            ..query.clone()
+        };
+        assert_eq!(
+            compute_query_fingerprint(&query),
+            compute_query_fingerprint(&refined)
+        );
+
+        planner.build(&query).unwrap();
+        planner.build(&refined).unwrap();
+        planner.plan_cache.run_pending_tasks();
+        assert_eq!(planner.plan_cache.entry_count(), 1);
+        assert!(planner
+            .plan_cache
+            .contains_key(&compute_query_fingerprint(&refined)));
+    }
+
+    #[test]
+    fn test_cache_usage_counts_engines_and_plans() {
+        let mut planner = QueryPlanner::new(100);
+        assert_eq!(planner.cache_usage(), (0, 0));
+
+        let query = SearchQuery {
+            id: "usage".to_string(),
+            terms: vec![keyword_term("1", "timeout", false)],
+            global_operator: QueryOperator::And,
+            filters: None,
+            metadata: QueryMetadata {
+                created_at: 0,
+                last_modified: 0,
+                execution_count: 0,
+                label: None,
+            },
+        };
+        planner.build(&query).unwrap();
+
+        let (entries, bytes) = planner.cache_usage();
+        assert_eq!(entries, 2);
+        assert!(bytes >= ENGINE_ESTIMATE_BYTES + PLAN_ESTIMATE_BYTES);
+    }
+
+    #[test]
+    fn test_set_engine_capacity_keeps_compiled_engines() {
+        let mut planner = QueryPlanner::new(100);
+        let engine = planner
+            .get_or_compile_engine(&keyword_term("1", "timeout", false))
+            .unwrap();
+
+        planner.set_engine_capacity(5_000);
+        assert_eq!(planner.engine_capacity(), 5_000);
+        assert_eq!(planner.engine_cache_usage().0, 1);
+        let cached = planner
+            .get_or_compile_engine(&keyword_term("1", "timeout", false))
+            .unwrap();
+        assert!(Arc::ptr_eq(&engine, &cached));
+    }
 }
@@ -0,0 +1,203 @@
+//! 快速扫描：不导入直接搜索压缩包
+//!
+//! 边解压边按行分批匹配，不写 CAS、不建索引，适合先确认一个支持包里有没有
+//! 要找的内容再决定是否完整导入。结果是近似的：嵌套压缩包不展开，达到结果
+//! 上限或超时即停止；时间过滤与排序按条目内原始顺序，不做跨文件合并。
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use la_archive::{stream_entries, Visit};
+use la_core::domain::LogSearcher;
+use la_core::models::{LogEntry, SearchFilters, SearchQuery};
+use serde::Serialize;
+
+/// 每批交给匹配器的行数
+const BATCH_LINES: usize = 8192;
+
+/// 快速扫描结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickScanResult {
+    pub archive_path: PathBuf,
+    /// 命中的行；`file` 为 `<压缩包名>/<条目路径>`，行号相对条目
+    pub entries: Vec<LogEntry>,
+    pub entries_scanned: usize,
+    /// 解压后扫描的字节数
+    pub bytes_scanned: u64,
+    /// 达到结果上限提前结束
+    pub truncated: bool,
+    /// 超时提前结束
+    pub timed_out: bool,
+    /// 未展开的嵌套压缩包（完整导入后才会被搜索）
+    pub nested_archives: Vec<String>,
+}
+
+/// 扫描压缩包（或普通文件）中的所有条目
+pub fn quick_scan(
+    path: &Path,
+    searcher: &dyn LogSearcher,
+    query: &SearchQuery,
+    filters: &SearchFilters,
+    max_results: usize,
+    deadline: Option<Instant>,
+) -> Result<QuickScanResult, String> {
+    let plan = searcher
+        .build_plan(query)
+        .map_err(|e| format!("Invalid query: {e}"))?;
+    let archive_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    let mut bytes_scanned = 0u64;
+    let mut truncated = false;
+    let mut timed_out = false;
+
+    let stats = stream_entries(path, |name, reader| {
+        let virtual_path = format!("{archive_name}/{name}");
+        let mut reader = BufReader::new(reader as &mut dyn Read);
+        let mut batch = String::new();
+        let mut batch_lines = 0usize;
+        let mut batch_start = 0usize;
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read > 0 {
+                bytes_scanned += read as u64;
+                batch.push_str(&String::from_utf8_lossy(&line));
+                batch_lines += 1;
+            }
+            if batch_lines == 0 || (read > 0 && batch_lines < BATCH_LINES) {
+                if read == 0 {
+                    break;
+                }
+                continue;
+            }
+
+            for mut entry in
+                searcher.match_content(&batch, &virtual_path, &plan, filters, entries.len())
+            {
+                entry.line += batch_start;
+                entry.id = entries.len();
+                entries.push(entry);
+                if entries.len() >= max_results {
+                    truncated = true;
+                    return Ok(Visit::Stop);
+                }
+            }
+            batch_start += batch_lines;
+            batch.clear();
+            batch_lines = 0;
+
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                timed_out = true;
+                return Ok(Visit::Stop);
+            }
+            if read == 0 {
+                break;
+            }
+        }
+        Ok(Visit::Continue)
+    })
+    .map_err(|e| e.to_string())?;
+
+    Ok(QuickScanResult {
+        archive_path: path.to_path_buf(),
+        entries,
+        entries_scanned: stats.entries_visited,
+        bytes_scanned,
+        truncated,
+        timed_out,
+        nested_archives: stats.nested_archives,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::searcher::QueryEngineLogSearcher;
+    use la_core::models::{QueryMetadata, QueryOperator, SearchTerm, TermSource};
+    use std::io::Write;
+
+    fn query(value: &str) -> SearchQuery {
+        SearchQuery {
+            id: "q".into(),
+            terms: vec![SearchTerm {
+                id: "t".into(),
+                value: value.into(),
+                operator: QueryOperator::And,
+                source: TermSource::User,
+                preset_group_id: None,
+                is_regex: false,
+                priority: 1,
+                enabled: true,
+                case_sensitive: false,
+            }],
+            global_operator: QueryOperator::And,
+            filters: None,
+            metadata: QueryMetadata {
+                created_at: 0,
+                last_modified: 0,
+                execution_count: 0,
+                label: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_scans_zip_entries_with_entry_relative_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("bundle.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("api/app.log", options).unwrap();
+        zip.write_all(b"ok\nERROR timeout\nok\n").unwrap();
+        zip.start_file("db/db.log", options).unwrap();
+        zip.write_all(b"timeout waiting for lock").unwrap();
+        zip.finish().unwrap();
+
+        let searcher = QueryEngineLogSearcher::new(8);
+        let result = quick_scan(
+            &archive,
+            &searcher,
+            &query("timeout"),
+            &SearchFilters::default(),
+            10,
+            None,
+        )
+        .unwrap();
+
+        let hits: Vec<_> = result
+            .entries
+            .iter()
+            .map(|e| (e.file.to_string(), e.line))
+            .collect();
+        assert_eq!(
+            hits,
+            vec![
+                ("bundle.zip/api/app.log".to_string(), 2),
+                ("bundle.zip/db/db.log".to_string(), 1),
+            ]
+        );
+        assert_eq!(result.entries_scanned, 2);
+        assert!(!result.truncated);
+
+        let result = quick_scan(
+            &archive,
+            &searcher,
+            &query("timeout"),
+            &SearchFilters::default(),
+            1,
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.entries.len(), 1);
+        assert!(result.truncated);
+    }
+}
@@ -0,0 +1,424 @@
+//! 刷新预览：比较源目录与工作区元数据，报告刷新将带来的变化
+//!
+//! 不解压、不写 CAS、不建索引。源文件按导入时的虚拟路径（`<源目录名>/<相对路径>`）
+//! 与元数据中的顶层文件 / 压缩包对应：
+//!
+//! - 普通文件：大小不同即为变更；大小与修改时间都相同视为未变；只有修改时间不同时
+//!   计算 SHA-256 与已导入的哈希比较（`verify_hashes` 时对所有文件计算）
+//! - 压缩包：元数据不记录大小与修改时间，总是按哈希比较
+//! - 元数据中属于该源、但源目录中已不存在的记为删除；压缩包内的条目随压缩包一起比较，
+//!   不单独列出
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use la_archive::internal::file_type_filter::FileTypeFilter;
+use la_archive::processor::NETWORK_SUMMARY_SUFFIX;
+use la_core::storage_types::{ArchiveMetadata, FileMetadata};
+use serde::Serialize;
+
+use crate::infrastructure::url_download::sha256_file;
+use crate::services::import_preview::is_archive_name;
+
+/// 最多比较的源文件数，超出后停止并标记 `truncated`
+pub const MAX_REFRESH_PREVIEW_FILES: usize = 200_000;
+/// 每类变化最多列出的文件数（计数不受限制）
+pub const MAX_LISTED_CHANGES: usize = 1000;
+
+/// 已导入的顶层条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownEntry {
+    pub virtual_path: String,
+    pub sha256_hash: String,
+    /// 压缩包为 None
+    pub size: Option<u64>,
+    /// 未知（0）或压缩包为 None
+    pub modified_time: Option<i64>,
+    pub is_archive: bool,
+}
+
+impl KnownEntry {
+    /// 属于本源的顶层条目：文件与压缩包本身，不含压缩包内的条目与网络抓包摘要
+    pub fn from_metadata(files: &[FileMetadata], archives: &[ArchiveMetadata]) -> Vec<Self> {
+        let paths: HashSet<&str> = files.iter().map(|f| f.virtual_path.as_str()).collect();
+        let files = files
+            .iter()
+            .filter(|f| f.parent_archive_id.is_none())
+            .filter(|f| {
+                f.virtual_path
+                    .strip_suffix(NETWORK_SUMMARY_SUFFIX)
+                    .is_none_or(|original| !paths.contains(original))
+            })
+            .map(|f| Self {
+                virtual_path: f.virtual_path.clone(),
+                sha256_hash: f.sha256_hash.clone(),
+                size: Some(f.size.max(0) as u64),
+                modified_time: Some(f.modified_time).filter(|&t| t > 0),
+                is_archive: false,
+            });
+        let archives = archives
+            .iter()
+            .filter(|a| a.parent_archive_id.is_none())
+            .map(|a| Self {
+                virtual_path: a.virtual_path.clone(),
+                sha256_hash: a.sha256_hash.clone(),
+                size: None,
+                modified_time: None,
+                is_archive: true,
+            });
+        files.chain(archives).collect()
+    }
+}
+
+/// 一个变化的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshChange {
+    pub virtual_path: String,
+    pub is_archive: bool,
+    /// 源文件大小；删除的文件为 None
+    pub size: Option<u64>,
+    /// 已导入时的大小；新文件与压缩包为 None
+    pub previous_size: Option<u64>,
+    pub modified_time: Option<i64>,
+    pub previous_modified_time: Option<i64>,
+}
+
+/// `preview_refresh` 返回结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshPreview {
+    pub source_path: PathBuf,
+    pub new_count: usize,
+    pub changed_count: usize,
+    pub deleted_count: usize,
+    pub unchanged_count: usize,
+    /// 只有修改时间变化、内容相同的文件数（已计入 `unchanged_count`）
+    pub touched_count: usize,
+    /// 被文件类型过滤规则跳过的源文件数
+    pub filtered_count: usize,
+    /// 为比较内容计算了哈希的文件数
+    pub hashed_count: usize,
+    /// 新增与变更文件的总大小
+    pub changed_bytes: u64,
+    /// 源文件总大小（刷新会重新读取全部源文件）
+    pub source_bytes: u64,
+    /// 各类最多列出 [`MAX_LISTED_CHANGES`] 个
+    pub new_files: Vec<RefreshChange>,
+    pub changed_files: Vec<RefreshChange>,
+    pub deleted_files: Vec<RefreshChange>,
+    /// 源文件超过 [`MAX_REFRESH_PREVIEW_FILES`]，只比较了前一部分（此时不报告删除）
+    pub truncated: bool,
+    /// 完整刷新的耗时估算，由调用方填写
+    pub estimated_duration_ms: u64,
+}
+
+impl RefreshPreview {
+    /// 源目录与工作区一致，刷新不会带来变化
+    pub fn is_up_to_date(&self) -> bool {
+        self.new_count == 0 && self.changed_count == 0 && self.deleted_count == 0
+    }
+
+    fn push(list: &mut Vec<RefreshChange>, change: RefreshChange) {
+        if list.len() < MAX_LISTED_CHANGES {
+            list.push(change);
+        }
+    }
+}
+
+fn modified_secs(metadata: &std::fs::Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+/// 比较源（目录或单个文件）与已导入的条目
+pub fn preview_refresh(
+    source: &Path,
+    known: Vec<KnownEntry>,
+    filter: Option<&FileTypeFilter>,
+    verify_hashes: bool,
+) -> Result<RefreshPreview, String> {
+    if !source.exists() {
+        return Err(format!("Path not found: {}", source.display()));
+    }
+    let root_name = source
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let root_prefix = format!("{root_name}/");
+    let mut known: HashMap<String, KnownEntry> = known
+        .into_iter()
+        .filter(|k| k.virtual_path == root_name || k.virtual_path.starts_with(&root_prefix))
+        .map(|k| (k.virtual_path.clone(), k))
+        .collect();
+
+    let mut preview = RefreshPreview {
+        source_path: source.to_path_buf(),
+        ..Default::default()
+    };
+    let mut visit = |path: &Path, virtual_path: String| -> Result<(), String> {
+        let metadata = path
+            .metadata()
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let size = metadata.len();
+        let modified_time = modified_secs(&metadata);
+        preview.source_bytes += size;
+        let is_archive = is_archive_name(&virtual_path);
+        if !is_archive && filter.is_some_and(|f| !f.should_import_file(path)) {
+            preview.filtered_count += 1;
+            return Ok(());
+        }
+
+        let change = |previous: Option<&KnownEntry>| RefreshChange {
+            virtual_path: virtual_path.clone(),
+            is_archive,
+            size: Some(size),
+            previous_size: previous.and_then(|k| k.size),
+            modified_time,
+            previous_modified_time: previous.and_then(|k| k.modified_time),
+        };
+        let Some(previous) = known.remove(&virtual_path) else {
+            preview.new_count += 1;
+            preview.changed_bytes += size;
+            RefreshPreview::push(&mut preview.new_files, change(None));
+            return Ok(());
+        };
+
+        let size_differs = previous.size.is_some_and(|s| s != size);
+        let metadata_matches = previous.size == Some(size)
+            && previous.modified_time.is_some()
+            && previous.modified_time == modified_time;
+        let changed = if size_differs {
+            true
+        } else if metadata_matches && !verify_hashes {
+            false
+        } else {
+            preview.hashed_count += 1;
+            let hash =
+                sha256_file(path).map_err(|e| format!("Failed to hash {}: {e}", path.display()))?;
+            let changed = !hash.eq_ignore_ascii_case(&previous.sha256_hash);
+            if !changed && !metadata_matches && !previous.is_archive {
+                preview.touched_count += 1;
+            }
+            changed
+        };
+        if changed {
+            preview.changed_count += 1;
+            preview.changed_bytes += size;
+            RefreshPreview::push(&mut preview.changed_files, change(Some(&previous)));
+        } else {
+            preview.unchanged_count += 1;
+        }
+        Ok(())
+    };
+
+    let mut truncated = false;
+    if source.is_dir() {
+        let mut files = 0;
+        for entry in walkdir::WalkDir::new(source).follow_links(false) {
+            let entry = entry.map_err(|e| format!("Failed to walk directory: {e}"))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            if files == MAX_REFRESH_PREVIEW_FILES {
+                truncated = true;
+                break;
+            }
+            files += 1;
+            let relative = entry
+                .path()
+                .strip_prefix(source)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            visit(entry.path(), format!("{root_prefix}{relative}"))?;
+        }
+    } else {
+        visit(source, root_name.clone())?;
+    }
+
+    preview.truncated = truncated;
+    if !truncated {
+        let mut deleted: Vec<KnownEntry> = known.into_values().collect();
+        deleted.sort_by(|a, b| a.virtual_path.cmp(&b.virtual_path));
+        preview.deleted_count = deleted.len();
+        for entry in deleted {
+            RefreshPreview::push(
+                &mut preview.deleted_files,
+                RefreshChange {
+                    virtual_path: entry.virtual_path,
+                    is_archive: entry.is_archive,
+                    size: None,
+                    previous_size: entry.size,
+                    modified_time: None,
+                    previous_modified_time: entry.modified_time,
+                },
+            );
+        }
+    }
+    preview
+        .new_files
+        .sort_by(|a, b| a.virtual_path.cmp(&b.virtual_path));
+    preview
+        .changed_files
+        .sort_by(|a, b| a.virtual_path.cmp(&b.virtual_path));
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(dir: &Path, root: &str, name: &str) -> KnownEntry {
+        let path = dir.join(name);
+        let metadata = path.metadata().unwrap();
+        let is_archive = is_archive_name(name);
+        KnownEntry {
+            virtual_path: format!("{root}/{name}"),
+            sha256_hash: sha256_file(&path).unwrap(),
+            size: (!is_archive).then_some(metadata.len()),
+            modified_time: modified_secs(&metadata).filter(|_| !is_archive),
+            is_archive,
+        }
+    }
+
+    #[test]
+    fn test_reports_new_changed_deleted_and_touched() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("logs");
+        std::fs::create_dir_all(source.join("sub")).unwrap();
+        for (name, content) in [
+            ("same.log", "a\n"),
+            ("grown.log", "a\n"),
+            ("touched.log", "t\n"),
+            ("sub/edited.log", "x\n"),
+            ("bundle.zip", "zip"),
+        ] {
+            std::fs::write(source.join(name), content).unwrap();
+        }
+        let mut entries: Vec<KnownEntry> =
+            ["same.log", "grown.log", "sub/edited.log", "bundle.zip"]
+                .iter()
+                .map(|name| known(&source, "logs", name))
+                .collect();
+        // 修改时间不同、内容相同
+        let mut touched = known(&source, "logs", "touched.log");
+        touched.modified_time = touched.modified_time.map(|t| t - 100);
+        entries.push(touched);
+        // 同样大小、不同内容，修改时间也不同
+        let mut edited = entries.remove(2);
+        edited.sha256_hash = "0".repeat(64);
+        edited.modified_time = edited.modified_time.map(|t| t - 100);
+        entries.push(edited);
+        entries.push(KnownEntry {
+            virtual_path: "logs/gone.log".into(),
+            sha256_hash: "f".repeat(64),
+            size: Some(3),
+            modified_time: None,
+            is_archive: false,
+        });
+        // 其他来源的条目不参与比较
+        entries.push(KnownEntry {
+            virtual_path: "other/app.log".into(),
+            sha256_hash: "e".repeat(64),
+            size: Some(3),
+            modified_time: None,
+            is_archive: false,
+        });
+
+        std::fs::write(source.join("grown.log"), "a\nb\n").unwrap();
+        std::fs::write(source.join("new.log"), "n\n").unwrap();
+
+        let preview = preview_refresh(&source, entries, None, false).unwrap();
+        let paths = |list: &[RefreshChange]| {
+            list.iter()
+                .map(|c| c.virtual_path.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(&preview.new_files), vec!["logs/new.log"]);
+        assert_eq!(
+            paths(&preview.changed_files),
+            vec!["logs/grown.log", "logs/sub/edited.log"]
+        );
+        assert_eq!(paths(&preview.deleted_files), vec!["logs/gone.log"]);
+        assert_eq!(preview.unchanged_count, 3);
+        assert_eq!(preview.touched_count, 1);
+        // touched、edited 与压缩包
+        assert_eq!(preview.hashed_count, 3);
+        assert_eq!(preview.changed_bytes, 4 + 2 + 2);
+        assert!(!preview.is_up_to_date());
+        assert_eq!(preview.changed_files[0].previous_size, Some(2));
+    }
+
+    #[test]
+    fn test_single_file_source_and_verify_hashes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("app.log");
+        std::fs::write(&file, "line\n").unwrap();
+        let metadata = file.metadata().unwrap();
+        let entry = KnownEntry {
+            virtual_path: "app.log".into(),
+            sha256_hash: sha256_file(&file).unwrap(),
+            size: Some(metadata.len()),
+            modified_time: modified_secs(&metadata),
+            is_archive: false,
+        };
+
+        let preview = preview_refresh(&file, vec![entry.clone()], None, false).unwrap();
+        assert!(preview.is_up_to_date());
+        assert_eq!(preview.hashed_count, 0);
+
+        let stale = KnownEntry {
+            sha256_hash: "0".repeat(64),
+            ..entry
+        };
+        let verified = preview_refresh(&file, vec![stale], None, true).unwrap();
+        assert_eq!(verified.changed_count, 1);
+        assert_eq!(verified.hashed_count, 1);
+    }
+
+    #[test]
+    fn test_known_entries_skip_archive_members_and_summaries() {
+        let file = |path: &str, parent: Option<i64>| FileMetadata {
+            id: 0,
+            sha256_hash: "h".into(),
+            virtual_path: path.into(),
+            original_name: path.into(),
+            size: 10,
+            modified_time: 0,
+            mime_type: None,
+            parent_archive_id: parent,
+            depth_level: 0,
+            min_timestamp: None,
+            max_timestamp: None,
+            level_mask: None,
+            analysis_status: la_core::storage_types::AnalysisStatus::Ready,
+        };
+        let files = vec![
+            file("logs/a.log", None),
+            file("logs/capture.pcap", None),
+            file("logs/capture.pcap.summary.log", None),
+            file("logs/b.zip/inner.log", Some(1)),
+        ];
+        let archives = vec![ArchiveMetadata {
+            id: 1,
+            sha256_hash: "z".into(),
+            virtual_path: "logs/b.zip".into(),
+            original_name: "b.zip".into(),
+            archive_type: "zip".into(),
+            parent_archive_id: None,
+            depth_level: 0,
+            extraction_status: "completed".into(),
+        }];
+
+        let entries = KnownEntry::from_metadata(&files, &archives);
+        let paths: Vec<&str> = entries.iter().map(|e| e.virtual_path.as_str()).collect();
+        assert_eq!(paths, vec!["logs/a.log", "logs/capture.pcap", "logs/b.zip"]);
+        assert_eq!(entries[0].modified_time, None);
+        assert!(entries[2].is_archive);
+    }
+}
@@ -27,8 +27,19 @@ pub enum EngineError {
     MatchError(String),
     #[error("Unsupported pattern for engine: {0}")]
     UnsupportedPattern(String),
+    #[error("Regex too expensive: {0}")]
+    TooExpensive(String),
 }
 
+/// 编译后正则程序的大小上限（超出即视为过于昂贵，拒绝编译）
+pub const REGEX_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+/// 惰性 DFA 缓存上限
+pub const REGEX_DFA_SIZE_LIMIT: usize = 4 * 1024 * 1024;
+/// fancy-regex 单次匹配的回溯步数上限
+pub const REGEX_BACKTRACK_LIMIT: usize = 100_000;
+/// 计数重复 `{n}` / `{n,m}` 允许的最大次数
+pub const MAX_REGEX_REPEAT: u32 = 1000;
+
 #[derive(Debug, Clone)]
 pub struct EngineInfo {
     pub engine_type: EngineType,
@@ -128,12 +139,13 @@ impl RegexEngine {
         if needs_lookaround(pattern) {
             // ReDoS check: fancy-regex uses backtracking for look-around,
             // reject patterns with nested quantifiers or overlapping alternation.
-            check_redos_risk(pattern)?;
+            check_regex_safety(pattern)?;
             return FancyEngine::new(pattern).map(RegexEngine::Fancy);
         }
 
         // 2. 如果标记为正则表达式且非简单关键词，使用 StandardEngine
         if is_regex && !is_simple_keyword(pattern) {
+            check_regex_safety(pattern)?;
             return StandardEngine::new(pattern).map(RegexEngine::Standard);
         }
 
@@ -297,6 +309,156 @@ impl<'a> Iterator for AhoCorasickMatches<'a> {
     }
 }
 
+// ========== MultiKeywordMatcher：多关键词单遍匹配 ==========
+
+/// 将多个字面量关键词编译进同一个 Aho-Corasick 自动机（DFA + Teddy SIMD 预过滤），
+/// 一次扫描即可得到命中的关键词集合，替代逐个关键词重复扫描整行。
+///
+/// 大小写敏感与不敏感的关键词分别编译；相同关键词只占一个槽位。
+#[derive(Clone)]
+pub struct MultiKeywordMatcher {
+    automata: Vec<(Arc<AhoCorasick>, Vec<usize>)>,
+    slot_count: usize,
+}
+
+impl MultiKeywordMatcher {
+    /// `keywords`：(关键词, 是否大小写不敏感)
+    pub fn new(keywords: &[(&str, bool)]) -> Result<Self, EngineError> {
+        if keywords.iter().any(|(k, _)| k.is_empty()) {
+            return Err(EngineError::CompilationError(
+                "Empty keyword in multi-keyword matcher".to_string(),
+            ));
+        }
+
+        let mut slots: std::collections::HashMap<(String, bool), usize> =
+            std::collections::HashMap::new();
+        let mut automata = Vec::new();
+
+        for case_insensitive in [false, true] {
+            let mut patterns = Vec::new();
+            let mut pattern_slots = Vec::new();
+            for (keyword, ci) in keywords.iter().filter(|(_, ci)| *ci == case_insensitive) {
+                let key = if *ci {
+                    keyword.to_ascii_lowercase()
+                } else {
+                    keyword.to_string()
+                };
+                let next = slots.len();
+                let slot = *slots.entry((key.clone(), *ci)).or_insert(next);
+                if slot == next {
+                    patterns.push(key);
+                    pattern_slots.push(slot);
+                }
+            }
+            if patterns.is_empty() {
+                continue;
+            }
+
+            let ac = AhoCorasickBuilder::new()
+                .ascii_case_insensitive(case_insensitive)
+                .kind(Some(aho_corasick::AhoCorasickKind::DFA))
+                .build(&patterns)
+                .map_err(|e| EngineError::CompilationError(e.to_string()))?;
+            automata.push((Arc::new(ac), pattern_slots));
+        }
+
+        Ok(Self {
+            automata,
+            slot_count: slots.len(),
+        })
+    }
+
+    /// 去重后的关键词数量
+    pub fn keyword_count(&self) -> usize {
+        self.slot_count
+    }
+
+    /// 文本是否包含任一关键词
+    pub fn matches_any(&self, text: &str) -> bool {
+        self.automata.iter().any(|(ac, _)| ac.is_match(text))
+    }
+
+    /// 文本是否包含全部关键词（集齐后提前结束扫描）
+    pub fn matches_all(&self, text: &str) -> bool {
+        if self.slot_count <= 128 {
+            let full = if self.slot_count == 128 {
+                u128::MAX
+            } else {
+                (1u128 << self.slot_count) - 1
+            };
+            let mut seen = 0u128;
+            self.visit_hits(text, |slot| {
+                seen |= 1u128 << slot;
+                seen == full
+            });
+            seen == full
+        } else {
+            let mut seen = vec![false; self.slot_count];
+            let mut remaining = self.slot_count;
+            self.visit_hits(text, |slot| {
+                if !seen[slot] {
+                    seen[slot] = true;
+                    remaining -= 1;
+                }
+                remaining == 0
+            });
+            remaining == 0
+        }
+    }
+
+    /// 依次回调命中的关键词槽位，回调返回 true 时停止
+    fn visit_hits(&self, text: &str, mut on_hit: impl FnMut(usize) -> bool) {
+        for (ac, pattern_slots) in &self.automata {
+            for mat in ac.find_overlapping_iter(text) {
+                if on_hit(pattern_slots[mat.pattern().as_usize()]) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// 从正则中提取"必含字面量"集合：任何匹配都以其中之一开头（或结尾）。
+///
+/// 无法得到有限且有效的集合时（如 `.*`、`\w+` 开头结尾，或含前瞻后瞻无法解析）返回 `None`。
+/// 返回的字面量已展开大小写变体，需按大小写敏感方式匹配。
+pub fn extract_required_literals(pattern: &str) -> Option<Vec<String>> {
+    use regex_syntax::hir::literal::{ExtractKind, Extractor};
+
+    let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+
+    for kind in [ExtractKind::Prefix, ExtractKind::Suffix] {
+        let mut seq = Extractor::new().kind(kind.clone()).extract(&hir);
+        match kind {
+            ExtractKind::Prefix => seq.optimize_for_prefix_by_preference(),
+            _ => seq.optimize_for_suffix_by_preference(),
+        }
+        let Some(literals) = seq.literals() else {
+            continue;
+        };
+        if literals.is_empty() || literals.iter().any(|l| l.as_bytes().is_empty()) {
+            continue;
+        }
+        let literals: Option<Vec<String>> = literals
+            .iter()
+            .map(|l| String::from_utf8(l.as_bytes().to_vec()).ok())
+            .collect();
+        if let Some(literals) = literals {
+            return Some(literals);
+        }
+    }
+
+    None
+}
+
+impl fmt::Debug for MultiKeywordMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiKeywordMatcher")
+            .field("keyword_count", &self.slot_count)
+            .finish()
+    }
+}
+
 // ========== StandardEngine ==========
 
 #[derive(Clone)]
@@ -316,13 +478,11 @@ impl StandardEngine {
         // 但某些 ASCII 模式（含 . 如 error.*timeout）会导致 ". 可匹配无效 UTF-8" 错误，
         // 此时回退到默认的 unicode=true 行为
         let regex = if pattern.is_ascii() {
-            RegexBuilder::new(pattern)
-                .unicode(false)
-                .build()
-                .or_else(|_| Regex::new(pattern))
-                .map_err(|e| EngineError::CompilationError(e.to_string()))?
+            build_limited_regex(pattern, false)
+                .or_else(|_| build_limited_regex(pattern, true))
+                .map_err(map_regex_error)?
         } else {
-            Regex::new(pattern).map_err(|e| EngineError::CompilationError(e.to_string()))?
+            build_limited_regex(pattern, true).map_err(map_regex_error)?
         };
 
         Ok(Self {
@@ -444,8 +604,7 @@ impl FancyEngine {
         if pattern.trim().is_empty() {
             return Err(EngineError::CompilationError("Empty pattern".to_string()));
         }
-        let regex = fancy_regex::Regex::new(pattern)
-            .map_err(|e| EngineError::CompilationError(e.to_string()))?;
+        let regex = build_limited_fancy_regex(pattern).map_err(map_fancy_regex_error)?;
         Ok(Self {
             regex,
             pattern: Arc::from(pattern),
@@ -627,19 +786,118 @@ pub fn needs_lookaround(pattern: &str) -> bool {
         || pattern.contains("(?<!")
 }
 
+/// 按统一的大小上限编译标准正则（线性时间引擎，只需限制程序大小）
+pub fn build_limited_regex(pattern: &str, unicode: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .unicode(unicode)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .build()
+}
+
+/// 按统一的大小与回溯上限编译 fancy-regex（超出回溯上限时匹配返回错误而不是卡死）
+pub fn build_limited_fancy_regex(pattern: &str) -> Result<fancy_regex::Regex, fancy_regex::Error> {
+    fancy_regex::RegexBuilder::new(pattern)
+        .backtrack_limit(REGEX_BACKTRACK_LIMIT)
+        .delegate_size_limit(REGEX_SIZE_LIMIT)
+        .delegate_dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .build()
+}
+
+pub fn map_regex_error(error: regex::Error) -> EngineError {
+    match error {
+        regex::Error::CompiledTooBig(limit) => EngineError::TooExpensive(format!(
+            "compiled program exceeds the {limit} byte size limit"
+        )),
+        other => EngineError::CompilationError(other.to_string()),
+    }
+}
+
+pub fn map_fancy_regex_error(error: fancy_regex::Error) -> EngineError {
+    if let fancy_regex::Error::CompileError(ref inner) = error {
+        if let fancy_regex::CompileError::InnerError(ref build) = **inner {
+            if let Some(limit) = build.size_limit() {
+                return EngineError::TooExpensive(format!(
+                    "compiled program exceeds the {limit} byte size limit"
+                ));
+            }
+        }
+    }
+    EngineError::CompilationError(error.to_string())
+}
+
+/// 用户正则的静态安全检查（在编译前调用）
+///
+/// - 所有正则：计数重复不得超过 [`MAX_REGEX_REPEAT`]
+/// - 需要回溯的正则（前瞻/后瞻）：额外拒绝嵌套量词与重叠分支。
+///   标准引擎是线性时间的，`(\w+\.)+com` 之类的嵌套量词对它无害，不做拦截
+pub fn check_regex_safety(pattern: &str) -> Result<(), EngineError> {
+    if let Some(count) = max_counted_repetition(pattern) {
+        if count > MAX_REGEX_REPEAT {
+            return Err(EngineError::TooExpensive(format!(
+                "counted repetition {{{count}}} exceeds the limit of {MAX_REGEX_REPEAT}"
+            )));
+        }
+    }
+
+    if needs_lookaround(pattern) {
+        check_redos_risk(pattern)?;
+    }
+
+    Ok(())
+}
+
+/// 模式中最大的计数重复次数（`{n}`、`{n,}`、`{n,m}` 取 n / m 的最大值），忽略转义的 `\{`
+fn max_counted_repetition(pattern: &str) -> Option<u32> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut max: Option<u32> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                i += 2;
+                continue;
+            }
+            '{' => {
+                let close = chars[i + 1..].iter().position(|&c| c == '}');
+                if let Some(offset) = close {
+                    let body: String = chars[i + 1..i + 1 + offset].iter().collect();
+                    let bounds: Vec<&str> = body.split(',').collect();
+                    if bounds.len() <= 2
+                        && !bounds[0].is_empty()
+                        && bounds.iter().all(|b| b.chars().all(|c| c.is_ascii_digit()))
+                    {
+                        let count = bounds
+                            .iter()
+                            .filter(|b| !b.is_empty())
+                            // 超出 u32 的次数同样视为超限
+                            .map(|b| b.parse::<u32>().unwrap_or(u32::MAX))
+                            .max();
+                        max = max.max(count);
+                    }
+                    i += offset + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    max
+}
+
 /// Check for ReDoS (Regular Expression Denial of Service) risk patterns.
 ///
 /// fancy_regex uses a backtracking engine for look-around assertions,
 /// which can suffer from catastrophic backtracking with exponential runtime.
 fn check_redos_risk(pattern: &str) -> Result<(), EngineError> {
     if has_nested_quantifiers(pattern) {
-        return Err(EngineError::CompilationError(
+        return Err(EngineError::TooExpensive(
             "Regex contains nested quantifiers (e.g., (a+)+) which may cause excessive backtracking".to_string(),
         ));
     }
 
     if has_overlapping_alternation(pattern) {
-        return Err(EngineError::CompilationError(
+        return Err(EngineError::TooExpensive(
             "Regex contains overlapping alternation under quantifier which may cause excessive backtracking".to_string(),
         ));
     }
@@ -746,6 +1004,52 @@ pub fn is_multi_keyword(pattern: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_extract_required_literals() {
+        let literals = extract_required_literals(r"timeout after \d+ms").unwrap();
+        assert!(literals.iter().all(|l| l.starts_with("timeout")));
+
+        let literals = extract_required_literals(r"(?:conn|socket) reset").unwrap();
+        assert!(literals.iter().any(|l| l.starts_with("conn")));
+        assert!(literals.iter().any(|l| l.starts_with("socket")));
+
+        // 前缀无字面量时退回后缀
+        let literals = extract_required_literals(r"\d+ms elapsed").unwrap();
+        assert!(literals.iter().all(|l| l.ends_with("elapsed")));
+
+        assert!(extract_required_literals(r"\w+").is_none());
+        assert!(extract_required_literals(r"(?<=a)b").is_none());
+    }
+
+    #[test]
+    fn test_multi_keyword_matcher_all_and_any() {
+        let matcher =
+            MultiKeywordMatcher::new(&[("timeout", true), ("worker-7", false), ("TIMEOUT", true)])
+                .unwrap();
+        assert_eq!(matcher.keyword_count(), 2);
+        assert!(matcher.matches_all("Timeout waiting for worker-7"));
+        assert!(!matcher.matches_all("Timeout waiting for WORKER-7"));
+        assert!(matcher.matches_any("WORKER-7 timeout"));
+        assert!(!matcher.matches_any("all good"));
+    }
+
+    #[test]
+    fn test_multi_keyword_matcher_overlapping_keywords() {
+        let matcher = MultiKeywordMatcher::new(&[("error", false), ("err", false)]).unwrap();
+        assert!(matcher.matches_all("fatal error"));
+        assert!(!matcher.matches_all("fatal err"));
+    }
+
+    #[test]
+    fn test_multi_keyword_matcher_many_keywords() {
+        let keywords: Vec<String> = (0..200).map(|i| format!("kw{i:03}")).collect();
+        let pairs: Vec<(&str, bool)> = keywords.iter().map(|k| (k.as_str(), false)).collect();
+        let matcher = MultiKeywordMatcher::new(&pairs).unwrap();
+        let all = keywords.join(" ");
+        assert!(matcher.matches_all(&all));
+        assert!(!matcher.matches_all(&keywords[..199].join(" ")));
+    }
+
     #[test]
     fn test_simple_keyword_detection() {
         assert!(is_simple_keyword("error"));
@@ -796,6 +1100,24 @@ mod tests {
         assert!(result.is_ok(), "Safe look-around should be accepted");
     }
 
+    #[test]
+    fn test_regex_safety_rejects_expensive_patterns() {
+        let error = RegexEngine::new(r"\d{5000}", true).unwrap_err();
+        assert!(matches!(error, EngineError::TooExpensive(_)), "{error}");
+        assert!(error.to_string().starts_with("Regex too expensive"));
+
+        // 编译后超出大小上限
+        let Err(error) = StandardEngine::new(r"(?u)\w{1000}") else {
+            panic!("oversized regex should be rejected");
+        };
+        assert!(matches!(error, EngineError::TooExpensive(_)), "{error}");
+
+        // 转义的花括号不是计数重复；线性引擎允许嵌套量词
+        assert!(check_regex_safety(r"\{5000\}").is_ok());
+        assert!(RegexEngine::new(r"(\w+\.)+com", true).is_ok());
+        assert!(RegexEngine::new(r"\d{1,3}\.\d{1,3}", true).is_ok());
+    }
+
     #[test]
     fn test_aho_corasiick_engine() {
         let engine = AhoCorasickEngine::new("error|warning|info").unwrap();
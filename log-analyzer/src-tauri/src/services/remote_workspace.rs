@@ -0,0 +1,298 @@
+//! 远程工作区客户端
+//!
+//! 通过 HTTP 访问其他实例共享的工作区（协议见 `services::workspace_share`），
+//! 提供与本地工作区相同的搜索、虚拟文件树与文件读取接口，并在本机缓存：
+//!
+//! - 文件内容按哈希寻址、不会变化，读取结果（含按时间跳转）按字节数 LRU 缓存，不过期
+//! - 搜索、文件树、概览等查询结果缓存 [`QUERY_TTL`]：共享端可能继续导入，不能长期复用
+//! - 失败的请求不缓存；共享端返回的 `CommandError` 原样交给调用方
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use la_core::error::CommandError;
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tracing::{debug, warn};
+
+use crate::services::workspace_share::{RemoteWorkspace, SHARED_ACTIONS};
+
+/// 请求共享端的超时（搜索大工作区可能较慢）
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+/// 查询结果的缓存时长
+pub const QUERY_TTL: Duration = Duration::from_secs(60);
+/// 文件内容缓存的字节上限
+const FILE_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+/// 查询结果缓存的字节上限
+const QUERY_CACHE_BYTES: u64 = 16 * 1024 * 1024;
+/// 结果按内容哈希寻址、可以长期缓存的操作
+const CONTENT_ADDRESSED_ACTIONS: &[&str] = &["read_file_by_hash", "seek_file_time"];
+
+static CLIENT: Lazy<Option<reqwest::Client>> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .inspect_err(|e| warn!(error = %e, "Failed to create remote workspace HTTP client"))
+        .ok()
+});
+
+/// 缓存的响应；`bytes` 为响应体长度，用作缓存权重
+#[derive(Clone)]
+struct CachedResponse {
+    value: Arc<Value>,
+    bytes: u32,
+}
+
+/// 远程工作区缓存的占用情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCacheStats {
+    pub files: u64,
+    pub file_bytes: u64,
+    pub queries: u64,
+    pub query_bytes: u64,
+}
+
+/// 一个挂载的远程工作区
+pub struct RemoteWorkspaceClient {
+    remote: RemoteWorkspace,
+    files: Cache<String, CachedResponse>,
+    queries: Cache<String, CachedResponse>,
+}
+
+impl RemoteWorkspaceClient {
+    pub fn new(remote: RemoteWorkspace) -> Self {
+        let weigher = |_: &String, cached: &CachedResponse| cached.bytes;
+        Self {
+            remote,
+            files: Cache::builder()
+                .max_capacity(FILE_CACHE_BYTES)
+                .weigher(weigher)
+                .build(),
+            queries: Cache::builder()
+                .max_capacity(QUERY_CACHE_BYTES)
+                .weigher(weigher)
+                .time_to_live(QUERY_TTL)
+                .build(),
+        }
+    }
+
+    pub fn remote(&self) -> &RemoteWorkspace {
+        &self.remote
+    }
+
+    /// 调用共享端的只读操作；参数与本地操作相同，`workspaceId` 固定为共享的工作区
+    pub async fn call(
+        &self,
+        action: &str,
+        mut args: Map<String, Value>,
+    ) -> Result<Value, CommandError> {
+        if !SHARED_ACTIONS.contains(&action) {
+            return Err(CommandError::new(
+                "VALIDATION_ERROR",
+                format!("Action {action} is not available on remote workspaces"),
+            ));
+        }
+        args.insert("workspaceId".to_string(), json!(self.remote.workspace_id));
+
+        let cache = if CONTENT_ADDRESSED_ACTIONS.contains(&action) {
+            &self.files
+        } else {
+            &self.queries
+        };
+        let key = cache_key(action, &args);
+        if let Some(cached) = cache.get(&key) {
+            debug!(remote_id = %self.remote.id, action, "Remote workspace cache hit");
+            return Ok((*cached.value).clone());
+        }
+
+        let body = Value::Object(args);
+        let bytes = request(&self.remote.url, &self.remote.token, action, Some(&body)).await?;
+        let value: Value = parse_body(&self.remote.url, &bytes)?;
+        cache.insert(
+            key,
+            CachedResponse {
+                value: Arc::new(value.clone()),
+                bytes: u32::try_from(bytes.len()).unwrap_or(u32::MAX),
+            },
+        );
+        Ok(value)
+    }
+
+    /// 搜索并返回前 `maxResults` 条结果（共享端等待搜索完成后一次性返回）
+    pub async fn search(&self, args: Map<String, Value>) -> Result<Value, CommandError> {
+        self.call("search_logs", args).await
+    }
+
+    /// 虚拟文件树
+    pub async fn virtual_tree(&self) -> Result<Value, CommandError> {
+        self.call("get_virtual_file_tree", Map::new()).await
+    }
+
+    /// 按哈希读取文件内容，参数含义与本地 `read_file_by_hash` 相同
+    pub async fn read_file(
+        &self,
+        hash: &str,
+        line: Option<usize>,
+        offset: Option<usize>,
+        length: Option<usize>,
+    ) -> Result<Value, CommandError> {
+        let mut args = Map::new();
+        args.insert("hash".to_string(), json!(hash));
+        for (key, value) in [("line", line), ("offset", offset), ("length", length)] {
+            if let Some(value) = value {
+                args.insert(key.to_string(), json!(value));
+            }
+        }
+        self.call("read_file_by_hash", args).await
+    }
+
+    /// 丢弃缓存的查询结果（共享端导入新文件后使用）；文件内容缓存不受影响
+    pub fn invalidate_queries(&self) {
+        self.queries.invalidate_all();
+    }
+
+    pub fn cache_stats(&self) -> RemoteCacheStats {
+        self.files.run_pending_tasks();
+        self.queries.run_pending_tasks();
+        RemoteCacheStats {
+            files: self.files.entry_count(),
+            file_bytes: self.files.weighted_size(),
+            queries: self.queries.entry_count(),
+            query_bytes: self.queries.weighted_size(),
+        }
+    }
+}
+
+/// 缓存键：操作名 + 参数 JSON（`serde_json::Map` 按键排序，同样的参数得到同样的键）
+fn cache_key(action: &str, args: &Map<String, Value>) -> String {
+    format!("{action}:{}", Value::Object(args.clone()))
+}
+
+/// 查询共享端的 `GET /api/info`
+pub async fn fetch_info(url: &str, token: &str) -> Result<Value, CommandError> {
+    let bytes = request(url, token, "info", None).await?;
+    parse_body(url, &bytes)
+}
+
+/// 向共享端发送请求并返回成功响应的响应体；`body` 为 `None` 时发送 GET
+async fn request(
+    url: &str,
+    token: &str,
+    endpoint: &str,
+    body: Option<&Value>,
+) -> Result<Vec<u8>, CommandError> {
+    let client = CLIENT
+        .as_ref()
+        .ok_or_else(|| CommandError::new("NETWORK_ERROR", "HTTP client is unavailable"))?;
+    let endpoint_url = format!("{url}/api/{endpoint}");
+    let request = match body {
+        Some(body) => client
+            .post(&endpoint_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string()),
+        None => client.get(&endpoint_url),
+    };
+    let response = request.bearer_auth(token).send().await.map_err(|e| {
+        CommandError::new("NETWORK_ERROR", format!("Failed to reach {url}: {e}"))
+            .with_help("Check that the sharing machine is reachable and still sharing")
+    })?;
+    let status = response.status();
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| CommandError::new("NETWORK_ERROR", format!("Failed to read response: {e}")))?;
+    if status.is_success() {
+        return Ok(bytes.to_vec());
+    }
+    // 共享端的错误响应体即 CommandError，原样返回
+    Err(serde_json::from_slice(&bytes).unwrap_or_else(|_| {
+        CommandError::new("NETWORK_ERROR", format!("Remote server returned {status}"))
+    }))
+}
+
+fn parse_body(url: &str, bytes: &[u8]) -> Result<Value, CommandError> {
+    serde_json::from_slice(bytes).map_err(|e| {
+        CommandError::new("NETWORK_ERROR", format!("Invalid response from {url}: {e}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> RemoteWorkspaceClient {
+        RemoteWorkspaceClient::new(RemoteWorkspace {
+            id: "remote-1".to_string(),
+            name: "prod".to_string(),
+            // 不可达的地址：命中缓存的调用不会发出请求
+            url: "http://127.0.0.1:9".to_string(),
+            token: "t".to_string(),
+            workspace_id: "ws-1".to_string(),
+        })
+    }
+
+    fn cached(value: Value) -> CachedResponse {
+        CachedResponse {
+            bytes: value.to_string().len() as u32,
+            value: Arc::new(value),
+        }
+    }
+
+    #[test]
+    fn cache_keys_ignore_argument_order() {
+        let mut a = Map::new();
+        a.insert("query".to_string(), json!("error"));
+        a.insert("maxResults".to_string(), json!(10));
+        let mut b = Map::new();
+        b.insert("maxResults".to_string(), json!(10));
+        b.insert("query".to_string(), json!("error"));
+        assert_eq!(cache_key("search_logs", &a), cache_key("search_logs", &b));
+        assert_ne!(
+            cache_key("search_logs", &a),
+            cache_key("get_workspace_overview", &a)
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_results_are_served_locally() {
+        let client = client();
+        let mut args = Map::new();
+        args.insert("hash".to_string(), json!("abc"));
+        args.insert("workspaceId".to_string(), json!("ws-1"));
+        client.files.insert(
+            cache_key("read_file_by_hash", &args),
+            cached(json!({"content": "line"})),
+        );
+        let content = client.read_file("abc", None, None, None).await.unwrap();
+        assert_eq!(content["content"], "line");
+
+        let tree_key = cache_key("get_virtual_file_tree", &{
+            let mut args = Map::new();
+            args.insert("workspaceId".to_string(), json!("ws-1"));
+            args
+        });
+        client.queries.insert(tree_key, cached(json!([])));
+        assert_eq!(client.virtual_tree().await.unwrap(), json!([]));
+
+        // 查询缓存失效后需要重新请求（地址不可达，返回网络错误）；文件缓存保留
+        client.invalidate_queries();
+        assert_eq!(
+            client.virtual_tree().await.unwrap_err().code,
+            "NETWORK_ERROR"
+        );
+        assert!(client.read_file("abc", None, None, None).await.is_ok());
+        assert_eq!(client.cache_stats().files, 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_actions_that_are_not_shared() {
+        let error = client()
+            .call("delete_workspace", Map::new())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, "VALIDATION_ERROR");
+    }
+}
@@ -0,0 +1,239 @@
+//! 保存的搜索结果
+//!
+//! 把一次搜索的完整结果连同查询与摘要保存到工作区目录下的 [`SAVED_RESULTS_DIR`]，
+//! 之后无需重新搜索即可重新打开（源文件所在磁盘已卸载时也可查看）。每份结果两个文件：
+//! - `{id}.ndjson.gz` — gzip 压缩的结果，每行一条 JSON 序列化的 LogEntry
+//! - `{id}.json`      — [`SavedResultsInfo`]（查询、过滤条件、摘要、条目数）
+//!
+//! 先写结果再写清单，列表只读取清单，写入中断留下的孤立结果文件不会出现在列表中。
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use la_core::models::{LogEntry, SearchFilters, SearchResultSummary, SearchStatisticsCollector};
+use serde::{Deserialize, Serialize};
+
+/// 工作区目录下保存结果的子目录名
+pub const SAVED_RESULTS_DIR: &str = "saved_results";
+/// 名称长度上限（字符）
+pub const MAX_NAME_LEN: usize = 200;
+
+/// 保存的结果清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedResultsInfo {
+    pub id: String,
+    pub name: String,
+    pub workspace_id: String,
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<SearchFilters>,
+    pub summary: SearchResultSummary,
+    pub entry_count: usize,
+    /// 保存时间（Unix 秒）
+    pub saved_at: i64,
+}
+
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
+
+fn paths(dir: &Path, id: &str) -> Result<(PathBuf, PathBuf), String> {
+    if !is_valid_id(id) {
+        return Err(format!("Invalid saved results id '{id}'"));
+    }
+    Ok((
+        dir.join(format!("{id}.ndjson.gz")),
+        dir.join(format!("{id}.json")),
+    ))
+}
+
+/// 由结果条目生成摘要（调用方未提供搜索时的摘要时使用）
+pub fn summarize(entries: &[LogEntry]) -> SearchResultSummary {
+    let mut collector = SearchStatisticsCollector::new(Vec::<String>::new());
+    for entry in entries {
+        collector.record(entry);
+    }
+    collector.finish(entries.len())
+}
+
+/// 保存结果与清单
+pub fn save_results(
+    dir: &Path,
+    info: &SavedResultsInfo,
+    entries: &[LogEntry],
+) -> Result<(), String> {
+    let (data_path, info_path) = paths(dir, &info.id)?;
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create saved results directory: {e}"))?;
+
+    let write_entries = || -> std::io::Result<()> {
+        let file = File::create(&data_path)?;
+        let mut writer = BufWriter::new(GzEncoder::new(file, flate2::Compression::default()));
+        for entry in entries {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .finish()?
+            .sync_all()
+    };
+    if let Err(e) = write_entries() {
+        let _ = std::fs::remove_file(&data_path);
+        return Err(format!("Failed to write saved results: {e}"));
+    }
+
+    let json = serde_json::to_vec_pretty(info)
+        .map_err(|e| format!("Failed to serialize saved results info: {e}"))?;
+    let tmp = info_path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, &info_path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&data_path);
+            format!("Failed to write saved results info: {e}")
+        })
+}
+
+/// 列出保存的结果（按保存时间从新到旧）；目录不存在时返回空列表
+pub fn list_saved_results(dir: &Path) -> Result<Vec<SavedResultsInfo>, String> {
+    let read_dir = match std::fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read saved results directory: {e}")),
+    };
+
+    let mut saved = Vec::new();
+    for dir_entry in read_dir.flatten() {
+        let path = dir_entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        match std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                serde_json::from_slice::<SavedResultsInfo>(&bytes).map_err(|e| e.to_string())
+            }) {
+            Ok(info) => saved.push(info),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Skipping unreadable saved results")
+            }
+        }
+    }
+    saved.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then_with(|| a.id.cmp(&b.id)));
+    Ok(saved)
+}
+
+/// 读取保存的结果；不存在时返回 `None`
+pub fn load_saved_results(
+    dir: &Path,
+    id: &str,
+) -> Result<Option<(SavedResultsInfo, Vec<LogEntry>)>, String> {
+    let (data_path, info_path) = paths(dir, id)?;
+    let info: SavedResultsInfo = match std::fs::read(&info_path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Failed to parse saved results info: {e}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read saved results info: {e}")),
+    };
+
+    let file = File::open(&data_path).map_err(|e| format!("Failed to open saved results: {e}"))?;
+    let mut entries = Vec::with_capacity(info.entry_count);
+    for line in BufReader::new(GzDecoder::new(file)).lines() {
+        let line = line.map_err(|e| format!("Failed to read saved results: {e}"))?;
+        if line.is_empty() {
+            continue;
+        }
+        entries.push(
+            serde_json::from_str(&line)
+                .map_err(|e| format!("Failed to parse saved results: {e}"))?,
+        );
+    }
+    Ok(Some((info, entries)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: usize, file: &str, content: &str) -> LogEntry {
+        LogEntry {
+            id,
+            timestamp: format!("2024-01-01 00:00:0{id}").into(),
+            level: "ERROR".into(),
+            file: file.into(),
+            real_path: file.into(),
+            line: id,
+            content: content.into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: Some(vec!["timeout".to_string()]),
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    fn info(id: &str, saved_at: i64, entries: &[LogEntry]) -> SavedResultsInfo {
+        SavedResultsInfo {
+            id: id.to_string(),
+            name: format!("results {id}"),
+            workspace_id: "ws".to_string(),
+            query: "timeout".to_string(),
+            filters: None,
+            summary: summarize(entries),
+            entry_count: entries.len(),
+            saved_at,
+        }
+    }
+
+    #[test]
+    fn saved_results_round_trip_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries = vec![
+            entry(1, "a.log", "timeout talking to db"),
+            entry(2, "b.log", "timeout again"),
+        ];
+        save_results(dir.path(), &info("first", 10, &entries), &entries).unwrap();
+        save_results(
+            dir.path(),
+            &info("second", 20, &entries[..1]),
+            &entries[..1],
+        )
+        .unwrap();
+
+        let listed = list_saved_results(dir.path()).unwrap();
+        let ids: Vec<&str> = listed.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["second", "first"]);
+        assert_eq!(listed[1].summary.total_matches, 2);
+        assert_eq!(listed[1].summary.file_stats.len(), 2);
+
+        let (loaded, loaded_entries) = load_saved_results(dir.path(), "first").unwrap().unwrap();
+        assert_eq!(loaded.name, "results first");
+        assert_eq!(loaded.entry_count, 2);
+        assert_eq!(loaded_entries.len(), 2);
+        assert_eq!(&*loaded_entries[1].content, "timeout again");
+        assert!(load_saved_results(dir.path(), "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_ids_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_saved_results(dir.path(), "../escape").is_err());
+        assert!(save_results(dir.path(), &info("a/b", 0, &[]), &[]).is_err());
+        assert!(list_saved_results(&dir.path().join("absent"))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+//! 搜索结果对比：两次搜索各自独有的条目与按模式的数量变化
+//!
+//! 用于确认修复是否真的消除了某类错误：同一查询在修复前后（或两个快照、两个时间段）
+//! 各搜索一次，对比结果集。
+//!
+//! - 条目按（文件, 内容）计数匹配：一侧出现 3 次、另一侧 1 次的行记为 2 条独有
+//! - 模式为内容归一化（UUID、长十六进制串、数字替换为占位符）后的模板，
+//!   按两侧数量变化的绝对值降序排列
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use la_core::models::LogEntry;
+use serde::Serialize;
+
+use crate::monitoring::error_reports::normalize_message;
+
+/// 每侧默认列出的独有条目数
+pub const DEFAULT_LISTED_ENTRIES: usize = 200;
+/// 每侧最多列出的独有条目数
+pub const MAX_LISTED_ENTRIES: usize = 5000;
+/// 最多返回的模式数
+pub const MAX_PATTERNS: usize = 200;
+/// 参与模式归一化的内容前缀长度（字符）
+const PATTERN_PREFIX_CHARS: usize = 500;
+
+/// 一个模式在两侧的数量
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternDelta {
+    pub pattern: String,
+    pub left_count: usize,
+    pub right_count: usize,
+    /// `right_count - left_count`：负数表示右侧减少
+    pub delta: i64,
+    /// 该模式的一条原始内容
+    pub example: String,
+}
+
+/// 对比结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDiff {
+    pub left_count: usize,
+    pub right_count: usize,
+    /// 两侧都出现的条目数
+    pub common_count: usize,
+    pub only_left_count: usize,
+    pub only_right_count: usize,
+    /// 仅左侧出现的条目（按左侧结果顺序，最多 `max_listed` 条）
+    pub only_left: Vec<LogEntry>,
+    /// 仅右侧出现的条目（按右侧结果顺序，最多 `max_listed` 条）
+    pub only_right: Vec<LogEntry>,
+    /// 按 |delta| 降序，最多 [`MAX_PATTERNS`] 个
+    pub patterns: Vec<PatternDelta>,
+    /// 模式总数（含未返回的部分）
+    pub pattern_count: usize,
+    /// 至少一侧的结果集超过读取上限，只对比了前一部分
+    pub truncated: bool,
+}
+
+fn entry_key(entry: &LogEntry) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    entry.file.hash(&mut hasher);
+    entry.content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 内容 → 模式模板
+pub fn pattern_of(content: &str) -> String {
+    let prefix: String = content.chars().take(PATTERN_PREFIX_CHARS).collect();
+    normalize_message(&prefix)
+}
+
+/// `entries` 中在 `other` 里找不到对应的条目（按计数匹配，靠前的先匹配）
+fn unmatched<'a>(entries: &'a [LogEntry], other: &[LogEntry]) -> Vec<&'a LogEntry> {
+    let mut available: HashMap<u64, usize> = HashMap::new();
+    for entry in other {
+        *available.entry(entry_key(entry)).or_default() += 1;
+    }
+    entries
+        .iter()
+        .filter(|entry| match available.get_mut(&entry_key(entry)) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+/// 对比两个结果集，每侧最多列出 `max_listed` 条独有条目
+pub fn diff_entries(left: &[LogEntry], right: &[LogEntry], max_listed: usize) -> SearchDiff {
+    let only_left = unmatched(left, right);
+    let only_right = unmatched(right, left);
+
+    let mut patterns: HashMap<String, PatternDelta> = HashMap::new();
+    for (entries, is_left) in [(left, true), (right, false)] {
+        for entry in entries {
+            let pattern = pattern_of(&entry.content);
+            let delta = patterns
+                .entry(pattern.clone())
+                .or_insert_with(|| PatternDelta {
+                    pattern,
+                    left_count: 0,
+                    right_count: 0,
+                    delta: 0,
+                    example: entry.content.to_string(),
+                });
+            if is_left {
+                delta.left_count += 1;
+            } else {
+                delta.right_count += 1;
+            }
+        }
+    }
+    let mut patterns: Vec<PatternDelta> = patterns
+        .into_values()
+        .map(|mut p| {
+            p.delta = p.right_count as i64 - p.left_count as i64;
+            p
+        })
+        .collect();
+    patterns.sort_by(|a, b| {
+        b.delta
+            .abs()
+            .cmp(&a.delta.abs())
+            .then_with(|| a.pattern.cmp(&b.pattern))
+    });
+    let pattern_count = patterns.len();
+    patterns.truncate(MAX_PATTERNS);
+
+    SearchDiff {
+        left_count: left.len(),
+        right_count: right.len(),
+        common_count: left.len() - only_left.len(),
+        only_left_count: only_left.len(),
+        only_right_count: only_right.len(),
+        only_left: only_left.into_iter().take(max_listed).cloned().collect(),
+        only_right: only_right.into_iter().take(max_listed).cloned().collect(),
+        patterns,
+        pattern_count,
+        truncated: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file: &str, line: usize, content: &str) -> LogEntry {
+        LogEntry {
+            id: line,
+            timestamp: "".into(),
+            level: "ERROR".into(),
+            file: file.into(),
+            real_path: file.into(),
+            line,
+            content: content.into(),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    #[test]
+    fn test_only_each_side_counts_duplicates() {
+        let left = vec![
+            entry("app.log", 1, "timeout after 30s"),
+            entry("app.log", 2, "timeout after 30s"),
+            entry("app.log", 3, "disk full"),
+            entry("db.log", 1, "disk full"),
+        ];
+        // 重新导入后行号变化不影响匹配；不同文件的相同内容不算同一条
+        let right = vec![
+            entry("app.log", 10, "timeout after 30s"),
+            entry("app.log", 11, "disk full"),
+            entry("app.log", 12, "connection reset"),
+        ];
+
+        let diff = diff_entries(&left, &right, 10);
+        assert_eq!(diff.common_count, 2);
+        assert_eq!(diff.only_left_count, 2);
+        assert_eq!(
+            diff.only_left
+                .iter()
+                .map(|e| (&*e.file, e.line))
+                .collect::<Vec<_>>(),
+            vec![("app.log", 2), ("db.log", 1)]
+        );
+        assert_eq!(diff.only_right_count, 1);
+        assert_eq!(&*diff.only_right[0].content, "connection reset");
+
+        let listed = diff_entries(&left, &right, 1);
+        assert_eq!(listed.only_left.len(), 1);
+        assert_eq!(listed.only_left_count, 2);
+    }
+
+    #[test]
+    fn test_pattern_deltas_sorted_by_change() {
+        let left = vec![
+            entry("a.log", 1, "request 1 failed: timeout"),
+            entry("a.log", 2, "request 2 failed: timeout"),
+            entry("a.log", 3, "request 3 failed: timeout"),
+            entry("a.log", 4, "cache miss for 0xdeadbeefcafe1234"),
+        ];
+        let right = vec![
+            entry("a.log", 5, "cache miss for 0x0123456789abcdef"),
+            entry("a.log", 6, "shutdown"),
+        ];
+
+        let diff = diff_entries(&left, &right, 10);
+        assert_eq!(diff.pattern_count, 3);
+        let first = &diff.patterns[0];
+        assert_eq!(first.pattern, "request <n> failed: timeout");
+        assert_eq!(
+            (first.left_count, first.right_count, first.delta),
+            (3, 0, -3)
+        );
+        assert_eq!(first.example, "request 1 failed: timeout");
+
+        let cache = diff
+            .patterns
+            .iter()
+            .find(|p| p.pattern.starts_with("cache miss"))
+            .unwrap();
+        assert_eq!(
+            (cache.left_count, cache.right_count, cache.delta),
+            (1, 1, 0)
+        );
+        assert_eq!(diff.patterns.last().unwrap().delta, 0);
+    }
+}
@@ -7,7 +7,7 @@
 use la_core::domain::filter::{Filter, LineMetadata};
 use la_core::error::CommandError;
 use la_core::models::SearchFilters;
-use la_core::utils::{level_to_mask, parse_metadata, TimestampParser};
+use la_core::utils::{level_to_mask, parse_metadata, LevelOrder, TimestampParser};
 use regex::Regex;
 use std::collections::HashSet;
 
@@ -15,10 +15,14 @@ use std::collections::HashSet;
 pub(crate) struct CompiledSearchFilters {
     pub(crate) levels: Option<HashSet<String>>,
     pub(crate) level_mask: Option<u8>,
+    /// `level >= min_level` 过滤：最低级别在工作区级别顺序中的位置
+    pub(crate) min_level_rank: Option<usize>,
     pub(crate) time_start: Option<chrono::NaiveDateTime>,
     pub(crate) time_end: Option<chrono::NaiveDateTime>,
     pub(crate) file_matcher: Option<FilePatternMatcher>,
     pub(crate) database_file_pattern: Option<String>,
+    pub(crate) include_paths: Vec<PathGlob>,
+    pub(crate) exclude_paths: Vec<PathGlob>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +41,50 @@ pub(crate) enum FilePatternMatcher {
     Wildcard(Regex),
 }
 
+/// 虚拟路径 glob：`**` 可跨目录，`*` 与 `?` 不跨 `/`
+#[derive(Debug, Clone)]
+pub(crate) struct PathGlob(Regex);
+
+impl PathGlob {
+    pub(crate) fn compile(raw: &str) -> Result<Self, CommandError> {
+        let t = raw.trim().replace('\\', "/");
+        let mut re = String::with_capacity(t.len() * 2 + 2);
+        re.push('^');
+        let mut rest = t.as_str();
+        while let Some(ch) = rest.chars().next() {
+            if let Some(tail) = rest.strip_prefix("**/") {
+                re.push_str("(?:.*/)?");
+                rest = tail;
+                continue;
+            }
+            if let Some(tail) = rest.strip_prefix("**") {
+                re.push_str(".*");
+                rest = tail;
+                continue;
+            }
+            match ch {
+                '*' => re.push_str("[^/]*"),
+                '?' => re.push_str("[^/]"),
+                _ => re.push_str(&regex::escape(ch.encode_utf8(&mut [0; 4]))),
+            }
+            rest = &rest[ch.len_utf8()..];
+        }
+        re.push('$');
+        Regex::new(&re).map(Self).map_err(|e| {
+            CommandError::new("VALIDATION_ERROR", format!("Invalid path glob '{t}': {e}"))
+                .with_help("Use patterns like '**/app-server/**/*.log'")
+        })
+    }
+
+    pub(crate) fn matches(&self, virtual_path: &str) -> bool {
+        if virtual_path.contains('\\') {
+            self.0.is_match(&virtual_path.replace('\\', "/"))
+        } else {
+            self.0.is_match(virtual_path)
+        }
+    }
+}
+
 fn escape_sqlite_glob_literal(value: &str) -> String {
     let mut e = String::with_capacity(value.len());
     for ch in value.chars() {
@@ -112,6 +160,7 @@ impl CompiledSearchFilters {
         let level_mask = levels
             .as_ref()
             .map(|l| l.iter().fold(0u8, |m, l| m | level_to_mask(l)));
+        let min_level_rank = Self::resolve_min_level(filters)?;
         let time_start = Self::parse_dt(filters.time_start.as_deref(), "start time")?;
         let time_end = Self::parse_dt(filters.time_end.as_deref(), "end time")?;
         if let (Some(s), Some(e)) = (time_start, time_end) {
@@ -136,15 +185,53 @@ impl CompiledSearchFilters {
             .map(str::trim)
             .filter(|p| !p.is_empty())
             .map(Self::build_db_pattern);
+        let include_paths = Self::compile_path_globs(&filters.include_paths)?;
+        let exclude_paths = Self::compile_path_globs(&filters.exclude_paths)?;
         Ok(Self {
             levels,
             level_mask,
+            min_level_rank,
             time_start,
             time_end,
             file_matcher,
             database_file_pattern: db_pattern,
+            include_paths,
+            exclude_paths,
         })
     }
+    /// 最低级别在工作区级别顺序（未设置时为默认顺序）中的位置
+    fn resolve_min_level(filters: &SearchFilters) -> Result<Option<usize>, CommandError> {
+        let Some(min) = filters
+            .min_level
+            .as_deref()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+        else {
+            return Ok(None);
+        };
+        let default_order = LevelOrder::default();
+        let order = filters.level_order.as_ref().unwrap_or(&default_order);
+        order.rank(min).map(Some).ok_or_else(|| {
+            CommandError::new("VALIDATION_ERROR", format!("Unknown log level '{min}'"))
+                .with_help(format!("Use one of: {}", order.to_spec()))
+        })
+    }
+    fn compile_path_globs(raw: &[String]) -> Result<Vec<PathGlob>, CommandError> {
+        raw.iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(PathGlob::compile)
+            .collect()
+    }
+    /// 虚拟路径是否落在 include / exclude glob 限定的搜索范围内
+    pub(crate) fn matches_path_scope(&self, virtual_path: &str) -> bool {
+        (self.include_paths.is_empty()
+            || self.include_paths.iter().any(|g| g.matches(virtual_path)))
+            && !self.exclude_paths.iter().any(|g| g.matches(virtual_path))
+    }
+    pub(crate) fn has_path_scope(&self) -> bool {
+        !self.include_paths.is_empty() || !self.exclude_paths.is_empty()
+    }
     fn build_db_pattern(p: &str) -> String {
         if p.contains('*') || p.contains('?') {
             escape_sqlite_glob_literal(p)
@@ -172,6 +259,15 @@ impl CompiledSearchFilters {
         self.time_start.is_some() || self.time_end.is_some()
     }
 
+    /// 换算到时钟偏移为 `offset_secs` 的文件的本地时间：
+    /// 行时间 + 偏移 ∈ [start, end] 等价于 行时间 ∈ [start - 偏移, end - 偏移]
+    pub(crate) fn shifted_by(mut self, offset_secs: i64) -> Self {
+        let delta = chrono::Duration::seconds(offset_secs);
+        self.time_start = self.time_start.map(|t| t - delta);
+        self.time_end = self.time_end.map(|t| t - delta);
+        self
+    }
+
     /// Return SQLite-compatible glob pattern for database-side file filtering.
     pub(crate) fn database_file_pattern(&self) -> Option<String> {
         self.database_file_pattern.clone()
@@ -184,6 +280,9 @@ impl CompiledSearchFilters {
 
 impl Filter for CompiledSearchFilters {
     fn matches_file(&self, virtual_path: &str, real_path: Option<&str>) -> bool {
+        if !self.matches_path_scope(virtual_path) {
+            return false;
+        }
         let Some(m) = &self.file_matcher else {
             return true;
         };
@@ -196,6 +295,11 @@ impl Filter for CompiledSearchFilters {
                 return false;
             }
         }
+        if let Some(min) = self.min_level_rank {
+            if metadata.level_rank.is_none_or(|rank| rank < min) {
+                return false;
+            }
+        }
         if !self.has_time_filter() {
             return true;
         }
@@ -219,3 +323,99 @@ impl Filter for CompiledSearchFilters {
         self.time_start.is_some() || self.time_end.is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_glob_double_star_spans_directories() {
+        let glob = PathGlob::compile("**/app-server/**/*.log").unwrap();
+        assert!(glob.matches("app-server/app.log"));
+        assert!(glob.matches("bundle.zip/node-1/app-server/2024/01/app.log"));
+        assert!(glob.matches("bundle.zip\\app-server\\app.log"));
+        assert!(!glob.matches("bundle.zip/web/app.log"));
+        assert!(!glob.matches("app-server/app.log.gz"));
+    }
+
+    #[test]
+    fn test_path_glob_single_star_stays_in_segment() {
+        let glob = PathGlob::compile("logs/*.log").unwrap();
+        assert!(glob.matches("logs/a.log"));
+        assert!(!glob.matches("logs/nested/a.log"));
+    }
+
+    #[test]
+    fn test_path_scope_include_then_exclude() {
+        let filters = SearchFilters {
+            include_paths: vec!["**/app-server/**".into()],
+            exclude_paths: vec!["**/debug/**".into()],
+            ..Default::default()
+        };
+        let compiled = CompiledSearchFilters::compile(&filters).unwrap();
+        assert!(compiled.has_path_scope());
+        assert!(compiled.matches_path_scope("app-server/app.log"));
+        assert!(!compiled.matches_path_scope("app-server/debug/trace.log"));
+        assert!(!compiled.matches_path_scope("web/app.log"));
+        assert!(compiled.matches_file("app-server/app.log", None));
+    }
+
+    #[test]
+    fn test_min_level_follows_workspace_order() {
+        let order = LevelOrder::parse("DEBUG < INFO < NOTICE < WARN < ERROR < FATAL").unwrap();
+        let filters = SearchFilters {
+            min_level: Some("notice".into()),
+            level_order: Some(order.clone()),
+            ..Default::default()
+        };
+        let compiled = CompiledSearchFilters::compile(&filters).unwrap();
+        let check = |line: &str| {
+            let parsed = ParsedLineMetadata::parse(line, false);
+            compiled.matches_line(&LineMetadata {
+                timestamp: parsed.timestamp.clone(),
+                level: parsed.level,
+                level_normalized: parsed.level_normalized,
+                datetime: None,
+                level_mask: parsed.level_mask,
+                level_rank: order.line_rank(line, parsed.level),
+            })
+        };
+        assert!(check("2024-01-15 10:00:00 FATAL out of memory"));
+        assert!(check("2024-01-15 10:00:00 NOTICE disk at 80%"));
+        assert!(!check("2024-01-15 10:00:00 INFO started"));
+        assert!(!check("no level here"));
+
+        // 默认顺序中没有 NOTICE
+        let unknown = SearchFilters {
+            min_level: Some("notice".into()),
+            ..Default::default()
+        };
+        assert!(CompiledSearchFilters::compile(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_shifted_time_range_applies_file_offset() {
+        let filters = SearchFilters {
+            time_start: Some("2024-01-15 10:00:00".into()),
+            time_end: Some("2024-01-15 10:05:00".into()),
+            ..Default::default()
+        };
+        // 文件时钟慢 1 小时：本地 09:02 校正后为 10:02
+        let shifted = CompiledSearchFilters::compile(&filters)
+            .unwrap()
+            .shifted_by(3600);
+        let line = ParsedLineMetadata::parse("2024-01-15 09:02:00 INFO ok", true);
+        let metadata = LineMetadata {
+            timestamp: line.timestamp.clone(),
+            level: line.level,
+            level_normalized: line.level_normalized,
+            datetime: line.datetime,
+            level_mask: line.level_mask,
+            level_rank: None,
+        };
+        assert!(shifted.matches_line(&metadata));
+        assert!(!CompiledSearchFilters::compile(&filters)
+            .unwrap()
+            .matches_line(&metadata));
+    }
+}
@@ -0,0 +1,93 @@
+//! 静默检测：找出某个文件停止输出日志的异常时段
+//!
+//! 每个文件有自己的输出节奏（心跳日志每 10 秒、访问日志每秒数十条）。
+//! 以相邻时间戳间隔的中位数作为典型节奏，间隔超过 `节奏 × 倍数`
+//! 且不短于最小阈值的时段视为可疑静默——故障排查中往往比错误日志更能说明问题。
+
+use serde::Serialize;
+
+/// 判定为静默的默认倍数
+pub const DEFAULT_CADENCE_FACTOR: f64 = 10.0;
+/// 短于该秒数的间隔不视为静默，避免低频文件的正常抖动
+pub const DEFAULT_MIN_GAP_SECS: i64 = 60;
+/// 样本不足时无法估计节奏
+const MIN_SAMPLES: usize = 3;
+
+/// 一段可疑静默；时间均为校正时钟偏移后的 Unix 秒
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Silence {
+    pub virtual_path: String,
+    /// 静默前最后一条日志的时间
+    pub start: i64,
+    /// 静默后第一条日志的时间
+    pub end: i64,
+    pub duration_secs: i64,
+    /// 该文件的典型间隔（中位数，秒）
+    pub typical_interval_secs: f64,
+}
+
+/// 相邻时间戳间隔的中位数；同一秒内的多条日志计为 0 间隔
+pub fn typical_interval(timestamps: &[i64]) -> Option<f64> {
+    if timestamps.len() < MIN_SAMPLES {
+        return None;
+    }
+    let mut intervals: Vec<i64> = timestamps.windows(2).map(|w| w[1] - w[0]).collect();
+    intervals.sort_unstable();
+    let mid = intervals.len() / 2;
+    Some(if intervals.len() % 2 == 0 {
+        (intervals[mid - 1] + intervals[mid]) as f64 / 2.0
+    } else {
+        intervals[mid] as f64
+    })
+}
+
+/// 在已排序的时间戳中查找静默时段
+///
+/// 阈值为 `max(典型间隔 × factor, min_gap_secs)`；典型间隔小于 1 秒时按 1 秒计，
+/// 否则高频文件任何一秒的停顿都会被判为静默。
+pub fn find_silences(
+    virtual_path: &str,
+    timestamps: &[i64],
+    factor: f64,
+    min_gap_secs: i64,
+) -> Vec<Silence> {
+    let Some(typical) = typical_interval(timestamps) else {
+        return Vec::new();
+    };
+    let threshold = (typical.max(1.0) * factor).max(min_gap_secs as f64);
+
+    timestamps
+        .windows(2)
+        .filter(|w| (w[1] - w[0]) as f64 > threshold)
+        .map(|w| Silence {
+            virtual_path: virtual_path.to_string(),
+            start: w[0],
+            end: w[1],
+            duration_secs: w[1] - w[0],
+            typical_interval_secs: typical,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gap_longer_than_cadence_is_reported() {
+        // 每 10 秒一条心跳，中间停了 5 分钟
+        let mut timestamps: Vec<i64> = (0..20).map(|i| i * 10).collect();
+        timestamps.extend((0..20).map(|i| 490 + i * 10));
+
+        let silences = find_silences("heartbeat.log", &timestamps, 10.0, 60);
+        assert_eq!(silences.len(), 1);
+        assert_eq!(silences[0].start, 190);
+        assert_eq!(silences[0].end, 490);
+        assert_eq!(silences[0].typical_interval_secs, 10.0);
+
+        // 最小阈值高于实际间隔时不报告
+        assert!(find_silences("heartbeat.log", &timestamps, 10.0, 600).is_empty());
+        assert!(find_silences("short.log", &[1, 2], 10.0, 0).is_empty());
+    }
+}
@@ -0,0 +1,522 @@
+//! 启动自检
+//!
+//! 启动时快速检查配置文件、各工作区数据库、中断的导入、残留的临时目录、
+//! 被其他进程占用的索引锁以及上次运行留下的崩溃报告，汇总为 [`StartupReport`]。
+//! 每个问题附带建议的恢复操作，对应前端可直接调用的命令及参数。
+//!
+//! 检查除打开元数据库时执行的迁移外不写入任何数据；
+//! 残留临时目录由 [`cleanup_orphaned_temp`] 清理。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::monitoring::CRASH_REPORT_DIR;
+use crate::utils::workspace_paths::{PRIMARY_WORKSPACE_DIR_NAME, TENANT_WORKSPACE_DIR_NAME};
+
+/// tantivy 写入锁文件名（进程退出后由操作系统释放文件锁）
+const INDEX_WRITER_LOCK: &str = ".tantivy-writer.lock";
+const SEARCH_INDEX_DIR: &str = "search_index";
+
+/// 工作区内的临时目录：CAS 写入暂存与压缩包解压目录，导入完成后均应为空
+const WORKSPACE_TEMP_DIRS: [&str; 2] = ["tmp", "extracted"];
+
+/// 应用数据目录下的暂存目录：URL 下载与快速扫描转正
+const APP_STAGING_DIRS: [&str; 2] = ["downloads", "quick-scan"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StartupCheck {
+    Config,
+    WorkspaceDatabase,
+    InterruptedImport,
+    OrphanedTemp,
+    IndexLock,
+    CrashReports,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// 建议的恢复操作：`command` 为 Tauri 命令名，`args` 为调用参数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryAction {
+    pub command: String,
+    pub args: serde_json::Value,
+    pub label: String,
+}
+
+impl RecoveryAction {
+    fn new(command: &str, args: serde_json::Value, label: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            args,
+            label: label.to_string(),
+        }
+    }
+
+    fn cleanup_temp() -> Self {
+        Self::new(
+            "cleanup_orphaned_temp_dirs",
+            json!({}),
+            "Remove leftover temporary files",
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupIssue {
+    pub check: StartupCheck,
+    pub severity: IssueSeverity,
+    pub workspace_id: Option<String>,
+    pub message: String,
+    pub path: Option<String>,
+    pub actions: Vec<RecoveryAction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub workspaces_checked: usize,
+    pub duration_ms: u64,
+    /// 按严重程度从高到低排列
+    pub issues: Vec<StartupIssue>,
+}
+
+impl StartupReport {
+    /// 没有警告或错误（可能仍有提示）
+    pub fn is_healthy(&self) -> bool {
+        self.issues
+            .iter()
+            .all(|issue| issue.severity == IssueSeverity::Info)
+    }
+}
+
+/// `cleanup_orphaned_temp` 的结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempCleanupSummary {
+    pub removed_entries: usize,
+    pub freed_bytes: u64,
+    pub failures: Vec<String>,
+}
+
+/// 运行全部检查
+///
+/// `skip_workspaces` 中的工作区已在本进程中打开，跳过索引锁检查（锁由本进程持有）
+/// 与临时目录检查（可能有进行中的导入）。
+pub async fn run_startup_checks(
+    config_path: Option<&Path>,
+    app_data_dir: &Path,
+    skip_workspaces: &HashSet<String>,
+) -> StartupReport {
+    let started = std::time::Instant::now();
+    let mut issues = Vec::new();
+
+    if let Some(path) = config_path {
+        issues.extend(check_config(path));
+    }
+
+    let workspaces = list_workspace_dirs(app_data_dir);
+    for (workspace_id, dir) in &workspaces {
+        issues.extend(
+            check_workspace(workspace_id, dir, skip_workspaces.contains(workspace_id)).await,
+        );
+    }
+
+    issues.extend(check_app_staging(app_data_dir));
+    issues.extend(check_crash_reports(&app_data_dir.join(CRASH_REPORT_DIR)));
+
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    StartupReport {
+        workspaces_checked: workspaces.len(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        issues,
+    }
+}
+
+/// 配置文件能否解析并通过校验
+pub fn check_config(config_path: &Path) -> Vec<StartupIssue> {
+    use la_core::models::config::{AppConfigLoader, ConfigValidator};
+
+    if !config_path.exists() {
+        return Vec::new();
+    }
+    let reset = RecoveryAction::new(
+        "save_config",
+        json!({ "config": la_core::models::config::AppConfig::default() }),
+        "Reset configuration to defaults",
+    );
+    let issue = |severity, message: String| StartupIssue {
+        check: StartupCheck::Config,
+        severity,
+        workspace_id: None,
+        message,
+        path: Some(config_path.to_string_lossy().to_string()),
+        actions: vec![reset.clone()],
+    };
+
+    match AppConfigLoader::load(Some(config_path.to_path_buf())) {
+        Err(e) => vec![issue(
+            IssueSeverity::Error,
+            format!("Configuration file could not be parsed, defaults are in use: {e}"),
+        )],
+        Ok(loader) => {
+            let validation = loader.get_config().validate();
+            if validation.is_valid {
+                return Vec::new();
+            }
+            let errors = validation
+                .errors
+                .iter()
+                .map(|e| format!("{}: {}", e.field, e.message))
+                .collect::<Vec<_>>()
+                .join("; ");
+            vec![issue(
+                IssueSeverity::Warning,
+                format!("Configuration has invalid values: {errors}"),
+            )]
+        }
+    }
+}
+
+/// 单个工作区：数据库、中断的导入、残留临时文件与索引锁
+pub async fn check_workspace(
+    workspace_id: &str,
+    workspace_dir: &Path,
+    in_use: bool,
+) -> Vec<StartupIssue> {
+    let mut issues = Vec::new();
+    let issue = |check, severity, message: String, path: &Path, actions| StartupIssue {
+        check,
+        severity,
+        workspace_id: Some(workspace_id.to_string()),
+        message,
+        path: Some(path.to_string_lossy().to_string()),
+        actions,
+    };
+    let delete = RecoveryAction::new(
+        "delete_workspace",
+        json!({ "workspaceId": workspace_id }),
+        "Delete the workspace",
+    );
+    let refresh = RecoveryAction::new(
+        "refresh_workspace",
+        json!({ "workspaceId": workspace_id }),
+        "Re-import the workspace source",
+    );
+
+    let metadata_db = workspace_dir.join("metadata.db");
+    if !metadata_db.exists() || !workspace_dir.join("objects").is_dir() {
+        // 导入失败时工作区目录会被删除，留下不完整的目录说明导入过程中进程退出
+        issues.push(issue(
+            StartupCheck::InterruptedImport,
+            IssueSeverity::Warning,
+            "Workspace has no metadata database or object store; its import did not finish"
+                .to_string(),
+            workspace_dir,
+            vec![delete.clone()],
+        ));
+        return issues;
+    }
+
+    if !in_use {
+        match la_storage::MetadataStore::new(workspace_dir).await {
+            Ok(store) => store.close().await,
+            Err(e) => issues.push(issue(
+                StartupCheck::WorkspaceDatabase,
+                IssueSeverity::Error,
+                format!("Metadata database could not be opened: {e}"),
+                &metadata_db,
+                vec![delete.clone()],
+            )),
+        }
+
+        if let Some(lock) = held_index_lock(&workspace_dir.join(SEARCH_INDEX_DIR)) {
+            issues.push(issue(
+                StartupCheck::IndexLock,
+                IssueSeverity::Error,
+                "Search index is locked by another process; close other running instances"
+                    .to_string(),
+                &lock,
+                Vec::new(),
+            ));
+        }
+
+        let extracted = workspace_dir.join("extracted");
+        let (entries, bytes) = dir_usage(&extracted);
+        if entries > 0 {
+            issues.push(issue(
+                StartupCheck::InterruptedImport,
+                IssueSeverity::Warning,
+                format!(
+                    "{entries} archive extraction(s) ({bytes} bytes) were left behind by an interrupted import"
+                ),
+                &extracted,
+                vec![refresh, RecoveryAction::cleanup_temp()],
+            ));
+        }
+
+        let tmp = workspace_dir.join("tmp");
+        let (entries, bytes) = dir_usage(&tmp);
+        if entries > 0 {
+            issues.push(issue(
+                StartupCheck::OrphanedTemp,
+                IssueSeverity::Info,
+                format!("{entries} temporary file(s) ({bytes} bytes) left in the object store"),
+                &tmp,
+                vec![RecoveryAction::cleanup_temp()],
+            ));
+        }
+    }
+
+    issues
+}
+
+/// 应用级暂存目录中的残留（中断的 URL 下载与快速扫描转正）
+pub fn check_app_staging(app_data_dir: &Path) -> Vec<StartupIssue> {
+    APP_STAGING_DIRS
+        .iter()
+        .filter_map(|name| {
+            let dir = app_data_dir.join(name);
+            let (entries, bytes) = dir_usage(&dir);
+            (entries > 0).then(|| StartupIssue {
+                check: StartupCheck::OrphanedTemp,
+                severity: IssueSeverity::Info,
+                workspace_id: None,
+                message: format!(
+                    "{entries} staged import(s) ({bytes} bytes) left in '{name}'; \
+                     retrying a URL import resumes its partial download"
+                ),
+                path: Some(dir.to_string_lossy().to_string()),
+                actions: vec![RecoveryAction::cleanup_temp()],
+            })
+        })
+        .collect()
+}
+
+/// 上次运行留下的崩溃报告
+pub fn check_crash_reports(crash_dir: &Path) -> Vec<StartupIssue> {
+    let count = crate::monitoring::crash_reports::list_crash_reports(crash_dir)
+        .map(|reports| reports.len())
+        .unwrap_or(0);
+    if count == 0 {
+        return Vec::new();
+    }
+    vec![StartupIssue {
+        check: StartupCheck::CrashReports,
+        severity: IssueSeverity::Warning,
+        workspace_id: None,
+        message: format!("{count} crash report(s) from previous runs"),
+        path: Some(crash_dir.to_string_lossy().to_string()),
+        actions: vec![RecoveryAction::new(
+            "list_crash_reports",
+            json!({}),
+            "Review crash reports",
+        )],
+    }]
+}
+
+/// 清理残留的临时目录内容，跳过 `skip_workspaces` 中的工作区
+pub fn cleanup_orphaned_temp(
+    app_data_dir: &Path,
+    skip_workspaces: &HashSet<String>,
+) -> TempCleanupSummary {
+    let mut dirs: Vec<PathBuf> = APP_STAGING_DIRS
+        .iter()
+        .map(|name| app_data_dir.join(name))
+        .collect();
+    for (workspace_id, workspace_dir) in list_workspace_dirs(app_data_dir) {
+        if !skip_workspaces.contains(&workspace_id) {
+            dirs.extend(
+                WORKSPACE_TEMP_DIRS
+                    .iter()
+                    .map(|name| workspace_dir.join(name)),
+            );
+        }
+    }
+
+    let mut summary = TempCleanupSummary::default();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let (_, bytes) = path_usage(&path);
+            let result = if path.is_dir() {
+                std::fs::remove_dir_all(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            match result {
+                Ok(()) => {
+                    summary.removed_entries += 1;
+                    summary.freed_bytes += bytes;
+                }
+                Err(e) => summary.failures.push(format!("{}: {e}", path.display())),
+            }
+        }
+    }
+    summary
+}
+
+/// 本地工作区与各租户命名空间下的工作区
+fn list_workspace_dirs(app_data_dir: &Path) -> Vec<(String, PathBuf)> {
+    let root = app_data_dir.join(PRIMARY_WORKSPACE_DIR_NAME);
+    let mut workspaces = workspace_dirs_in(&root);
+    if let Ok(tenants) = std::fs::read_dir(root.join(TENANT_WORKSPACE_DIR_NAME)) {
+        for tenant in tenants.flatten() {
+            workspaces.extend(workspace_dirs_in(&tenant.path()));
+        }
+    }
+    workspaces.sort();
+    workspaces
+}
+
+fn workspace_dirs_in(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.to_string();
+            if id == TENANT_WORKSPACE_DIR_NAME {
+                return None;
+            }
+            crate::utils::validation::validate_workspace_id(&id).ok()?;
+            Some((id, entry.path()))
+        })
+        .collect()
+}
+
+/// 写入锁文件存在且被其他进程持有时返回其路径
+fn held_index_lock(index_dir: &Path) -> Option<PathBuf> {
+    use fs4::fs_std::FileExt;
+
+    let lock_path = index_dir.join(INDEX_WRITER_LOCK);
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(&lock_path)
+        .ok()?;
+    match file.try_lock_exclusive() {
+        Ok(()) => {
+            let _ = FileExt::unlock(&file);
+            None
+        }
+        Err(_) => Some(lock_path),
+    }
+}
+
+/// 目录下的直接条目数与总字节数；目录不存在时为 0
+fn dir_usage(dir: &Path) -> (usize, u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    entries.flatten().fold((0, 0), |(count, bytes), entry| {
+        (count + 1, bytes + path_usage(&entry.path()).1)
+    })
+}
+
+fn path_usage(path: &Path) -> (usize, u64) {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .fold((0, 0), |(count, bytes), metadata| {
+            (count + 1, bytes + metadata.len())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(root: &Path, id: &str) -> PathBuf {
+        let dir = root.join(PRIMARY_WORKSPACE_DIR_NAME).join(id);
+        std::fs::create_dir_all(dir.join("objects")).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn reports_interrupted_imports_and_cleans_temp_dirs() {
+        let root = tempfile::tempdir().unwrap();
+
+        // 导入中途退出：没有 metadata.db
+        let broken = root
+            .path()
+            .join(PRIMARY_WORKSPACE_DIR_NAME)
+            .join("ws-broken");
+        std::fs::create_dir_all(&broken).unwrap();
+
+        let healthy = workspace(root.path(), "ws-healthy");
+        la_storage::MetadataStore::new(&healthy)
+            .await
+            .unwrap()
+            .close()
+            .await;
+        std::fs::create_dir_all(healthy.join("extracted/app_zip_1")).unwrap();
+        std::fs::write(healthy.join("extracted/app_zip_1/a.log"), b"0123456789").unwrap();
+        std::fs::create_dir_all(healthy.join("tmp")).unwrap();
+        std::fs::write(healthy.join("tmp/.tmp.x.tmp"), b"abc").unwrap();
+        std::fs::create_dir_all(root.path().join("quick-scan/abc")).unwrap();
+
+        let config = root.path().join("config.json");
+        std::fs::write(&config, "{ not json").unwrap();
+
+        let report = run_startup_checks(Some(&config), root.path(), &HashSet::new()).await;
+        assert_eq!(report.workspaces_checked, 2);
+        assert!(!report.is_healthy());
+        assert_eq!(report.issues[0].severity, IssueSeverity::Error);
+        assert_eq!(report.issues[0].check, StartupCheck::Config);
+
+        let kinds: Vec<_> = report
+            .issues
+            .iter()
+            .map(|i| (i.check, i.workspace_id.as_deref()))
+            .collect();
+        assert!(kinds.contains(&(StartupCheck::InterruptedImport, Some("ws-broken"))));
+        assert!(kinds.contains(&(StartupCheck::InterruptedImport, Some("ws-healthy"))));
+        assert!(kinds.contains(&(StartupCheck::OrphanedTemp, Some("ws-healthy"))));
+        assert!(kinds.contains(&(StartupCheck::OrphanedTemp, None)));
+        assert!(!kinds.contains(&(StartupCheck::WorkspaceDatabase, Some("ws-healthy"))));
+        let refresh = &report
+            .issues
+            .iter()
+            .find(|i| {
+                i.check == StartupCheck::InterruptedImport
+                    && i.workspace_id.as_deref() == Some("ws-healthy")
+            })
+            .unwrap()
+            .actions[0];
+        assert_eq!(refresh.command, "refresh_workspace");
+        assert_eq!(refresh.args["workspaceId"], "ws-healthy");
+
+        // 正在使用的工作区不清理
+        let skip: HashSet<String> = ["ws-healthy".to_string()].into();
+        assert_eq!(cleanup_orphaned_temp(root.path(), &skip).removed_entries, 1);
+        assert!(healthy.join("tmp/.tmp.x.tmp").exists());
+
+        let summary = cleanup_orphaned_temp(root.path(), &HashSet::new());
+        assert_eq!(summary.removed_entries, 2);
+        assert_eq!(summary.freed_bytes, 13);
+        assert!(summary.failures.is_empty());
+
+        std::fs::remove_file(&config).unwrap();
+        let report = run_startup_checks(Some(&config), root.path(), &HashSet::new()).await;
+        let remaining: Vec<_> = report.issues.iter().map(|i| i.check).collect();
+        assert_eq!(remaining, vec![StartupCheck::InterruptedImport]);
+    }
+}
@@ -0,0 +1,275 @@
+//! 日志内容翻译
+//!
+//! 前端只对当前可见的条目请求翻译，结果按原文缓存，译文填充到 `LogEntry::translation`，
+//! 原文保持不变。提供者可以是本地词典（配置中的短语表），也可以是运行时注册的
+//! 外部翻译服务（实现 [`TranslationProvider`] 后调用 [`TranslationRegistry::register_provider`]）。
+//!
+//! 只含 ASCII 的内容视为无需翻译，直接跳过。
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use aho_corasick::{AhoCorasick, MatchKind};
+use la_core::models::config::{TranslationConfig, DICTIONARY_TRANSLATION_PROVIDER};
+use la_core::models::LogEntry;
+use moka::sync::Cache;
+use parking_lot::RwLock;
+
+/// 译文缓存单条的固定开销（两个 Arc 头 + moka 节点）
+const CACHE_ENTRY_OVERHEAD: usize = 2 * 16 + 96;
+
+/// 翻译提供者
+pub trait TranslationProvider: Send + Sync {
+    /// 配置中 `translation.provider` 引用的名称
+    fn name(&self) -> &str;
+
+    /// 翻译一段文本；无法翻译时返回 `None`（保留原文）
+    fn translate(&self, text: &str, target_language: &str) -> Option<String>;
+}
+
+/// 本地词典：按最长匹配把已知短语替换为译文，其余文本原样保留
+pub struct DictionaryProvider {
+    matcher: Option<AhoCorasick>,
+    replacements: Vec<String>,
+}
+
+impl DictionaryProvider {
+    pub fn new(dictionary: &BTreeMap<String, String>) -> Self {
+        let (phrases, replacements): (Vec<&str>, Vec<String>) = dictionary
+            .iter()
+            .filter(|(phrase, _)| !phrase.is_empty())
+            .map(|(phrase, translated)| (phrase.as_str(), translated.clone()))
+            .unzip();
+        let matcher = if phrases.is_empty() {
+            None
+        } else {
+            AhoCorasick::builder()
+                .match_kind(MatchKind::LeftmostLongest)
+                .build(&phrases)
+                .map_err(|e| tracing::warn!(error = %e, "Failed to build translation dictionary"))
+                .ok()
+        };
+        Self {
+            matcher,
+            replacements,
+        }
+    }
+}
+
+impl TranslationProvider for DictionaryProvider {
+    fn name(&self) -> &str {
+        DICTIONARY_TRANSLATION_PROVIDER
+    }
+
+    fn translate(&self, text: &str, _target_language: &str) -> Option<String> {
+        let matcher = self.matcher.as_ref()?;
+        if !matcher.is_match(text) {
+            return None;
+        }
+        Some(matcher.replace_all(text, &self.replacements))
+    }
+}
+
+/// 当前生效的提供者及其译文缓存（按原文缓存，包括“无法翻译”的结果）
+struct Translator {
+    provider: Arc<dyn TranslationProvider>,
+    target_language: String,
+    cache: Cache<Arc<str>, Option<Arc<str>>>,
+}
+
+impl Translator {
+    fn translate(&self, content: &Arc<str>) -> Option<Arc<str>> {
+        if content.is_ascii() {
+            return None;
+        }
+        self.cache.get_with(Arc::clone(content), || {
+            self.provider
+                .translate(content, &self.target_language)
+                .filter(|translated| translated.as_str() != &**content)
+                .map(Arc::from)
+        })
+    }
+}
+
+/// 全局翻译注册表（配置保存或提供者注册后重建当前翻译器）
+#[derive(Default)]
+pub struct TranslationRegistry {
+    config: RwLock<TranslationConfig>,
+    providers: RwLock<HashMap<String, Arc<dyn TranslationProvider>>>,
+    active: RwLock<Option<Arc<Translator>>>,
+}
+
+impl TranslationRegistry {
+    pub fn configure(&self, config: &TranslationConfig) {
+        *self.config.write() = config.clone();
+        self.rebuild();
+    }
+
+    /// 注册外部翻译服务；同名提供者被替换
+    pub fn register_provider(&self, provider: Arc<dyn TranslationProvider>) {
+        self.providers
+            .write()
+            .insert(provider.name().to_string(), provider);
+        self.rebuild();
+    }
+
+    /// 可选的提供者名称（含内置词典）
+    pub fn provider_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.providers.read().keys().cloned().collect();
+        names.push(DICTIONARY_TRANSLATION_PROVIDER.to_string());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.active.read().is_some()
+    }
+
+    /// 为条目填充译文，返回实际翻译的条目数；未启用时不做任何处理
+    pub fn annotate(&self, entries: &mut [LogEntry]) -> usize {
+        let Some(translator) = self.active.read().clone() else {
+            return 0;
+        };
+        let mut translated = 0;
+        for entry in entries {
+            entry.translation = translator.translate(&entry.content).map(|t| t.to_string());
+            translated += usize::from(entry.translation.is_some());
+        }
+        translated
+    }
+
+    /// 译文缓存的估算占用：(条目数, 字节)；未启用时为 0
+    pub fn cache_usage(&self) -> (u64, u64) {
+        let Some(translator) = self.active.read().clone() else {
+            return (0, 0);
+        };
+        let mut entries = 0;
+        let mut bytes = 0;
+        for (original, translated) in translator.cache.iter() {
+            entries += 1;
+            bytes += (CACHE_ENTRY_OVERHEAD
+                + original.len()
+                + translated.as_ref().map_or(0, |t| t.len())) as u64;
+        }
+        (entries, bytes)
+    }
+
+    fn rebuild(&self) {
+        let config = self.config.read().clone();
+        let translator = if !config.enabled {
+            None
+        } else if config.provider == DICTIONARY_TRANSLATION_PROVIDER {
+            Some(Arc::new(DictionaryProvider::new(&config.dictionary))
+                as Arc<dyn TranslationProvider>)
+        } else {
+            let provider = self.providers.read().get(&config.provider).cloned();
+            if provider.is_none() {
+                tracing::warn!(provider = %config.provider, "Translation provider not registered");
+            }
+            provider
+        }
+        .map(|provider| {
+            Arc::new(Translator {
+                provider,
+                target_language: config.target_language.clone(),
+                cache: Cache::new(config.cache_entries.max(1)),
+            })
+        });
+        *self.active.write() = translator;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn entry(content: &str) -> LogEntry {
+        LogEntry {
+            id: 0,
+            timestamp: Arc::from(""),
+            level: Arc::from("ERROR"),
+            file: Arc::from("fw.log"),
+            real_path: Arc::from("fw.log"),
+            line: 1,
+            content: Arc::from(content),
+            tags: vec![],
+            match_details: None,
+            matched_keywords: None,
+            time_offset_secs: None,
+            links: None,
+            translation: None,
+            source_files: None,
+            original_length: None,
+            fields: None,
+        }
+    }
+
+    struct CountingProvider(AtomicUsize);
+
+    impl TranslationProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn translate(&self, text: &str, target_language: &str) -> Option<String> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Some(format!("[{target_language}] {text}"))
+        }
+    }
+
+    #[test]
+    fn test_dictionary_translates_longest_phrases() {
+        let registry = TranslationRegistry::default();
+        registry.configure(&TranslationConfig {
+            enabled: true,
+            dictionary: BTreeMap::from([
+                ("电源".to_string(), "power".to_string()),
+                ("电源故障".to_string(), "power failure".to_string()),
+                ("重启".to_string(), "reboot".to_string()),
+            ]),
+            ..Default::default()
+        });
+
+        let mut entries = vec![
+            entry("电源故障, 准备重启 (code=7)"),
+            entry("plain ascii line"),
+            entry("未知消息"),
+        ];
+        assert_eq!(registry.annotate(&mut entries), 1);
+        assert_eq!(
+            entries[0].translation.as_deref(),
+            Some("power failure, 准备reboot (code=7)")
+        );
+        assert_eq!(&*entries[0].content, "电源故障, 准备重启 (code=7)");
+        assert!(entries[1].translation.is_none());
+        assert!(entries[2].translation.is_none());
+    }
+
+    #[test]
+    fn test_registered_provider_results_are_cached() {
+        let registry = TranslationRegistry::default();
+        let provider = Arc::new(CountingProvider(AtomicUsize::new(0)));
+        registry.configure(&TranslationConfig {
+            enabled: true,
+            provider: "counting".to_string(),
+            ..Default::default()
+        });
+        // 提供者尚未注册时不启用
+        assert!(!registry.is_enabled());
+
+        registry.register_provider(provider.clone());
+        assert!(registry.provider_names().contains(&"counting".to_string()));
+
+        let mut entries = vec![entry("温度过高"), entry("温度过高")];
+        assert_eq!(registry.annotate(&mut entries), 2);
+        registry.annotate(&mut entries);
+        assert_eq!(entries[1].translation.as_deref(), Some("[en] 温度过高"));
+        assert_eq!(provider.0.load(Ordering::SeqCst), 1);
+
+        let (cached, bytes) = registry.cache_usage();
+        assert_eq!(cached, 1);
+        assert!(bytes > CACHE_ENTRY_OVERHEAD as u64);
+    }
+}
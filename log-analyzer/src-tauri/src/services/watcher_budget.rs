@@ -0,0 +1,355 @@
+//! 全局文件监听预算
+//!
+//! 每个原生监听都会占用操作系统资源（Linux 上递归监听每个目录占用一个
+//! inotify watch，且每个用户的 inotify 实例数有限）。多个工作区同时监听时
+//! 需要统一协调：
+//! - 原生监听数量不超过 `watch.max_native_watchers`
+//! - 预估的 inotify watch 占用不超过系统上限（预留余量给其他进程）
+//! - 超出预算或创建时触发系统限制的，自动降级为轮询，并记录原因供前端展示
+//! - 位于网络文件系统上的路径直接使用轮询（见 `services::polling_watcher`）
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// 为其他进程预留的 inotify watch 比例
+const HANDLE_HEADROOM_RATIO: f64 = 0.8;
+
+/// 估算目录数时的扫描上限，避免在超大目录树上长时间遍历
+const MAX_DIRECTORY_SCAN: usize = 200_000;
+
+/// 监听模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchMode {
+    /// 操作系统原生事件通知
+    Native,
+    /// 周期性扫描（延迟更高，但不受系统监听上限限制）
+    Polling,
+}
+
+/// 单个工作区的监听状态（供前端展示）
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherStatus {
+    pub workspace_id: String,
+    pub mode: WatchMode,
+    /// 降级为轮询的原因（原生模式为 None）
+    pub fallback_reason: Option<String>,
+    /// 预估占用的原生监听句柄数（轮询模式为 0）
+    pub estimated_handles: u64,
+    /// 检测到的网络文件系统类型（如 nfs4 / cifs）
+    pub network_filesystem: Option<String>,
+    /// 轮询模式当前的扫描间隔（自适应，原生模式为 None）
+    pub poll_interval_ms: Option<u64>,
+}
+
+/// 全局预算快照
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatcherBudgetSnapshot {
+    pub max_native_watchers: usize,
+    pub native_watchers: usize,
+    pub polling_watchers: usize,
+    pub estimated_handles_in_use: u64,
+    /// 系统 inotify watch 上限（非 Linux 或读取失败时为 None）
+    pub os_handle_limit: Option<u64>,
+    pub watchers: Vec<WatcherStatus>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    max_native: usize,
+    min_poll_interval: Duration,
+    max_poll_interval: Duration,
+    poll_network_filesystems: bool,
+    os_handle_limit: Option<u64>,
+    watchers: HashMap<String, WatcherStatus>,
+}
+
+impl Inner {
+    fn native_count(&self) -> usize {
+        self.watchers
+            .values()
+            .filter(|w| w.mode == WatchMode::Native)
+            .count()
+    }
+
+    fn handles_in_use(&self) -> u64 {
+        self.watchers.values().map(|w| w.estimated_handles).sum()
+    }
+}
+
+/// 全局监听预算（所有工作区共享）
+#[derive(Debug)]
+pub struct WatcherBudget {
+    inner: Mutex<Inner>,
+}
+
+impl Default for WatcherBudget {
+    fn default() -> Self {
+        let config = la_core::models::config::WatchConfig::default();
+        let budget = Self::new(config.max_native_watchers, os_watch_handle_limit());
+        budget.configure(&config);
+        budget
+    }
+}
+
+impl WatcherBudget {
+    pub fn new(max_native: usize, os_handle_limit: Option<u64>) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                max_native,
+                min_poll_interval: Duration::from_secs(2),
+                max_poll_interval: Duration::from_secs(30),
+                poll_network_filesystems: true,
+                os_handle_limit,
+                watchers: HashMap::new(),
+            }),
+        }
+    }
+
+    /// 应用配置；已启动的监听不受影响，新的预算限制对后续启动生效
+    pub fn configure(&self, config: &la_core::models::config::WatchConfig) {
+        let mut inner = self.inner.lock();
+        inner.max_native = config.max_native_watchers;
+        inner.min_poll_interval = Duration::from_millis(config.poll_interval_ms);
+        inner.max_poll_interval = Duration::from_millis(config.max_poll_interval_ms);
+        inner.poll_network_filesystems = config.poll_network_filesystems;
+    }
+
+    /// 轮询模式的扫描间隔范围（最小, 最大）
+    pub fn poll_intervals(&self) -> (Duration, Duration) {
+        let inner = self.inner.lock();
+        (inner.min_poll_interval, inner.max_poll_interval)
+    }
+
+    /// 网络文件系统上是否直接使用轮询
+    pub fn poll_network_filesystems(&self) -> bool {
+        self.inner.lock().poll_network_filesystems
+    }
+
+    /// 为工作区申请监听模式
+    ///
+    /// 预算充足时登记为原生监听并返回 `Native`；否则登记为轮询并返回降级原因。
+    /// 同一工作区重复申请会先释放之前的登记。
+    pub fn reserve(&self, workspace_id: &str, estimated_handles: u64) -> WatcherStatus {
+        let mut inner = self.inner.lock();
+        inner.watchers.remove(workspace_id);
+
+        let fallback_reason = if inner.native_count() >= inner.max_native {
+            Some(format!(
+                "Native watcher budget exhausted ({} of {} in use)",
+                inner.native_count(),
+                inner.max_native
+            ))
+        } else if let Some(limit) = inner.os_handle_limit {
+            let usable = (limit as f64 * HANDLE_HEADROOM_RATIO) as u64;
+            let needed = inner.handles_in_use() + estimated_handles;
+            (needed > usable).then(|| {
+                format!(
+                    "Watching needs ~{estimated_handles} OS watch handles, \
+                     exceeding the available budget ({} in use, system limit {limit})",
+                    inner.handles_in_use()
+                )
+            })
+        } else {
+            None
+        };
+
+        let status = match fallback_reason {
+            None => WatcherStatus {
+                workspace_id: workspace_id.to_string(),
+                mode: WatchMode::Native,
+                fallback_reason: None,
+                estimated_handles,
+                network_filesystem: None,
+                poll_interval_ms: None,
+            },
+            Some(reason) => polling_status(workspace_id, reason),
+        };
+        inner
+            .watchers
+            .insert(workspace_id.to_string(), status.clone());
+        status
+    }
+
+    /// 原生监听创建失败（如触发系统上限）后改登记为轮询
+    pub fn downgrade(&self, workspace_id: &str, reason: String) -> WatcherStatus {
+        self.register_polling(polling_status(workspace_id, reason))
+    }
+
+    /// 登记位于网络文件系统上的轮询监听（不占用原生预算）
+    pub fn register_network(&self, workspace_id: &str, fs_type: &str) -> WatcherStatus {
+        let mut status = polling_status(
+            workspace_id,
+            format!("Network filesystem ({fs_type}) detected; native change events are unreliable"),
+        );
+        status.network_filesystem = Some(fs_type.to_string());
+        self.register_polling(status)
+    }
+
+    fn register_polling(&self, status: WatcherStatus) -> WatcherStatus {
+        self.inner
+            .lock()
+            .watchers
+            .insert(status.workspace_id.clone(), status.clone());
+        status
+    }
+
+    pub fn release(&self, workspace_id: &str) {
+        self.inner.lock().watchers.remove(workspace_id);
+    }
+
+    pub fn status(&self, workspace_id: &str) -> Option<WatcherStatus> {
+        self.inner.lock().watchers.get(workspace_id).cloned()
+    }
+
+    pub fn snapshot(&self) -> WatcherBudgetSnapshot {
+        let inner = self.inner.lock();
+        let mut watchers: Vec<WatcherStatus> = inner.watchers.values().cloned().collect();
+        watchers.sort_by(|a, b| a.workspace_id.cmp(&b.workspace_id));
+        let native_watchers = inner.native_count();
+        WatcherBudgetSnapshot {
+            max_native_watchers: inner.max_native,
+            native_watchers,
+            polling_watchers: watchers.len() - native_watchers,
+            estimated_handles_in_use: inner.handles_in_use(),
+            os_handle_limit: inner.os_handle_limit,
+            watchers,
+        }
+    }
+}
+
+fn polling_status(workspace_id: &str, reason: String) -> WatcherStatus {
+    WatcherStatus {
+        workspace_id: workspace_id.to_string(),
+        mode: WatchMode::Polling,
+        fallback_reason: Some(reason),
+        estimated_handles: 0,
+        network_filesystem: None,
+        poll_interval_ms: None,
+    }
+}
+
+/// 估算递归监听 `path` 需要的原生句柄数
+///
+/// inotify 递归监听为每个目录注册一个 watch；其他平台按单个句柄计。
+pub fn estimate_watch_handles(path: &Path) -> u64 {
+    if !cfg!(target_os = "linux") || path.is_file() {
+        return 1;
+    }
+    walkdir::WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .take(MAX_DIRECTORY_SCAN)
+        .count()
+        .max(1) as u64
+}
+
+/// 系统 inotify watch 上限（`/proc/sys/fs/inotify/max_user_watches`）
+#[cfg(target_os = "linux")]
+pub fn os_watch_handle_limit() -> Option<u64> {
+    std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 系统原生监听上限（非 Linux 平台没有按目录计数的限制）
+#[cfg(not(target_os = "linux"))]
+pub fn os_watch_handle_limit() -> Option<u64> {
+    None
+}
+
+/// 判断 notify 错误是否为系统监听资源耗尽（应降级为轮询而非报错）
+pub fn is_os_limit_error(err: &notify::Error) -> bool {
+    const EMFILE: i32 = 24;
+    const ENOSPC: i32 = 28;
+    match &err.kind {
+        notify::ErrorKind::MaxFilesWatch => true,
+        notify::ErrorKind::Io(io) => {
+            matches!(io.raw_os_error(), Some(EMFILE) | Some(ENOSPC))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_downgrades_when_count_exhausted() {
+        let budget = WatcherBudget::new(1, None);
+        assert_eq!(budget.reserve("ws-1", 10).mode, WatchMode::Native);
+
+        let second = budget.reserve("ws-2", 10);
+        assert_eq!(second.mode, WatchMode::Polling);
+        assert!(second.fallback_reason.unwrap().contains("budget"));
+
+        budget.release("ws-1");
+        assert_eq!(budget.reserve("ws-3", 10).mode, WatchMode::Native);
+    }
+
+    #[test]
+    fn test_budget_accounts_os_handles() {
+        let budget = WatcherBudget::new(10, Some(1000));
+        assert_eq!(budget.reserve("ws-1", 500).mode, WatchMode::Native);
+        // 500 + 400 > 1000 * 0.8
+        assert_eq!(budget.reserve("ws-2", 400).mode, WatchMode::Polling);
+
+        let snapshot = budget.snapshot();
+        assert_eq!(snapshot.native_watchers, 1);
+        assert_eq!(snapshot.polling_watchers, 1);
+        assert_eq!(snapshot.estimated_handles_in_use, 500);
+    }
+
+    #[test]
+    fn test_rereserve_replaces_previous_entry() {
+        let budget = WatcherBudget::new(1, None);
+        budget.reserve("ws-1", 1);
+        assert_eq!(budget.reserve("ws-1", 1).mode, WatchMode::Native);
+        budget.downgrade("ws-1", "limit".into());
+        assert_eq!(budget.snapshot().native_watchers, 0);
+    }
+
+    #[test]
+    fn test_network_watchers_do_not_use_native_budget() {
+        let budget = WatcherBudget::new(1, None);
+        let status = budget.register_network("ws-nfs", "nfs4");
+        assert_eq!(status.mode, WatchMode::Polling);
+        assert_eq!(status.network_filesystem.as_deref(), Some("nfs4"));
+        assert_eq!(budget.reserve("ws-local", 1).mode, WatchMode::Native);
+    }
+
+    #[test]
+    fn test_is_os_limit_error() {
+        assert!(is_os_limit_error(&notify::Error::new(
+            notify::ErrorKind::MaxFilesWatch
+        )));
+        assert!(is_os_limit_error(&notify::Error::io(
+            std::io::Error::from_raw_os_error(28)
+        )));
+        assert!(!is_os_limit_error(&notify::Error::generic("boom")));
+    }
+
+    #[test]
+    fn test_estimate_watch_handles_counts_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::write(dir.path().join("a/x.log"), "x").unwrap();
+        let estimate = estimate_watch_handles(dir.path());
+        if cfg!(target_os = "linux") {
+            assert_eq!(estimate, 3);
+        } else {
+            assert_eq!(estimate, 1);
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! 工作区事件 Webhook
+//!
+//! 每个工作区可登记若干 Webhook（保存在工作区设置 [`WEBHOOKS_SETTING`] 中，
+//! 签名密钥另存于工作区密钥表，查询控制台读不到，列表接口只返回 `hasSecret`），
+//! 订阅导入完成、告警（跟随查询命中）、监听出错三类事件。事件发生时向 URL
+//! POST 一个 JSON 负载：
+//!
+//! ```json
+//! { "id": "...", "event": "importComplete", "workspaceId": "ws", "timestamp": 1700000000, "data": { ... } }
+//! ```
+//!
+//! 配置了 `secret` 时附带 `X-LogAnalyzer-Signature: sha256=<hex>`，为请求体的
+//! HMAC-SHA256，接收方据此校验来源。网络错误、5xx 与 429 按指数退避重试，
+//! 最多 [`MAX_DELIVERY_ATTEMPTS`] 次；投递在后台进行，不阻塞触发事件的操作。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tracing::{debug, warn};
+
+/// 工作区设置键：Webhook 列表（JSON）
+pub const WEBHOOKS_SETTING: &str = "webhooks";
+/// 每个工作区最多登记的 Webhook 数
+pub const MAX_WEBHOOKS: usize = 16;
+/// 单个事件的最多投递次数（含首次）
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 4;
+/// 告警负载中最多附带的命中条目数
+pub const MAX_ALERT_SAMPLES: usize = 10;
+
+pub const SIGNATURE_HEADER: &str = "X-LogAnalyzer-Signature";
+pub const EVENT_HEADER: &str = "X-LogAnalyzer-Event";
+pub const DELIVERY_HEADER: &str = "X-LogAnalyzer-Delivery";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_ID_LEN: usize = 64;
+
+static CLIENT: Lazy<Option<reqwest::Client>> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .inspect_err(|e| warn!(error = %e, "Failed to create webhook HTTP client"))
+        .ok()
+});
+
+/// 可订阅的工作区事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEventKind {
+    /// 导入完成
+    ImportComplete,
+    /// 跟随查询命中了新写入的条目
+    AlertFired,
+    /// 监听模式读取、索引或提交失败
+    WatchError,
+}
+
+impl WebhookEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ImportComplete => "importComplete",
+            Self::AlertFired => "alertFired",
+            Self::WatchError => "watchError",
+        }
+    }
+}
+
+/// 登记的 Webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    /// HMAC-SHA256 签名密钥，`None` 时不签名。只在保存时传入，从不序列化：
+    /// 保存时省略表示保留已有密钥，空字符串表示删除
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEventKind>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Webhook {
+    pub fn subscribes(&self, kind: WebhookEventKind) -> bool {
+        self.enabled && self.events.contains(&kind)
+    }
+}
+
+/// 返回给前端的 Webhook，不含密钥
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookInfo {
+    pub id: String,
+    pub url: String,
+    /// 是否配置了签名密钥
+    pub has_secret: bool,
+    pub events: Vec<WebhookEventKind>,
+    pub enabled: bool,
+}
+
+impl From<Webhook> for WebhookInfo {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            url: webhook.url,
+            has_secret: webhook.secret.is_some(),
+            events: webhook.events,
+            enabled: webhook.enabled,
+        }
+    }
+}
+
+/// 工作区密钥表中 Webhook 签名密钥的键
+fn secret_key(id: &str) -> String {
+    format!("webhook:{id}")
+}
+
+/// 投递给接收方的 JSON 负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    /// 本次投递的唯一标识，重试时不变，供接收方去重
+    pub id: String,
+    pub event: WebhookEventKind,
+    pub workspace_id: String,
+    /// Unix 秒
+    pub timestamp: i64,
+    pub data: Value,
+}
+
+impl WebhookPayload {
+    pub fn new(workspace_id: &str, event: WebhookEventKind, data: Value) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            event,
+            workspace_id: workspace_id.to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            data,
+        }
+    }
+}
+
+/// 校验单个 Webhook
+pub fn validate_webhook(webhook: &Webhook) -> Result<(), String> {
+    if webhook.id.trim().is_empty() || webhook.id.len() > MAX_ID_LEN {
+        return Err(format!("Webhook id must be 1-{MAX_ID_LEN} characters"));
+    }
+    let url = Url::parse(&webhook.url).map_err(|e| format!("Invalid webhook URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!(
+            "Webhook URL must be an http(s) URL with a host: {}",
+            webhook.url
+        ));
+    }
+    if webhook.events.is_empty() {
+        return Err(format!("Webhook '{}' subscribes to no events", webhook.id));
+    }
+    Ok(())
+}
+
+/// 校验整个列表：数量上限与 id 唯一
+pub fn validate_webhooks(webhooks: &[Webhook]) -> Result<(), String> {
+    if webhooks.len() > MAX_WEBHOOKS {
+        return Err(format!("At most {MAX_WEBHOOKS} webhooks per workspace"));
+    }
+    for (i, webhook) in webhooks.iter().enumerate() {
+        validate_webhook(webhook)?;
+        if webhooks[..i].iter().any(|w| w.id == webhook.id) {
+            return Err(format!("Duplicate webhook id: {}", webhook.id));
+        }
+    }
+    Ok(())
+}
+
+/// 请求体的 HMAC-SHA256 签名，形如 `sha256=<hex>`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// 一次投递尝试的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AttemptOutcome {
+    Delivered(u16),
+    /// 可重试：网络错误、5xx、429
+    Retry(String),
+    /// 不重试：其他 4xx 等
+    Rejected(String),
+}
+
+fn classify_status(status: reqwest::StatusCode) -> AttemptOutcome {
+    if status.is_success() {
+        AttemptOutcome::Delivered(status.as_u16())
+    } else if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        AttemptOutcome::Retry(format!("HTTP {status}"))
+    } else {
+        AttemptOutcome::Rejected(format!("HTTP {status}"))
+    }
+}
+
+/// 第 `attempt` 次（从 1 开始）失败后的等待时间：1s、2s、4s…
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY * 2u32.saturating_pow(attempt.saturating_sub(1))
+}
+
+/// 投递一次事件（含重试），返回接收方的 HTTP 状态码
+pub async fn deliver(webhook: &Webhook, payload: &WebhookPayload) -> Result<u16, String> {
+    let client = CLIENT
+        .as_ref()
+        .ok_or_else(|| "Webhook HTTP client unavailable".to_string())?;
+    let body =
+        serde_json::to_vec(payload).map_err(|e| format!("Failed to serialize payload: {e}"))?;
+    let signature = webhook.secret.as_deref().map(|secret| sign(secret, &body));
+
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+        let mut request = client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, payload.event.as_str())
+            .header(DELIVERY_HEADER, &payload.id)
+            .body(body.clone());
+        if let Some(signature) = &signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        let outcome = match request.send().await {
+            Ok(response) => classify_status(response.status()),
+            Err(e) => AttemptOutcome::Retry(e.to_string()),
+        };
+        match outcome {
+            AttemptOutcome::Delivered(status) => return Ok(status),
+            AttemptOutcome::Rejected(e) => return Err(e),
+            AttemptOutcome::Retry(e) => {
+                debug!(webhook_id = %webhook.id, attempt, error = %e, "Webhook delivery failed");
+                last_error = e;
+                if attempt < MAX_DELIVERY_ATTEMPTS {
+                    tokio::time::sleep(retry_delay(attempt)).await;
+                }
+            }
+        }
+    }
+    Err(format!(
+        "Gave up after {MAX_DELIVERY_ATTEMPTS} attempts: {last_error}"
+    ))
+}
+
+/// 读取工作区登记的 Webhook（含签名密钥，仅供投递使用）；无效的已保存内容按空列表处理
+pub async fn load_webhooks(
+    store: &la_storage::MetadataStore,
+) -> la_core::error::Result<Vec<Webhook>> {
+    let mut webhooks: Vec<Webhook> = store
+        .get_workspace_setting(WEBHOOKS_SETTING)
+        .await?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    for webhook in &mut webhooks {
+        webhook.secret = store.get_workspace_secret(&secret_key(&webhook.id)).await?;
+    }
+    Ok(webhooks)
+}
+
+/// 读取工作区登记的 Webhook，不含密钥
+pub async fn list_webhooks(
+    store: &la_storage::MetadataStore,
+) -> la_core::error::Result<Vec<WebhookInfo>> {
+    Ok(load_webhooks(store)
+        .await?
+        .into_iter()
+        .map(WebhookInfo::from)
+        .collect())
+}
+
+/// 新增或按 id 替换一个 Webhook；`webhook.secret` 省略时保留已有密钥，
+/// 为空字符串时删除密钥
+pub async fn save_webhook(
+    store: &la_storage::MetadataStore,
+    mut webhook: Webhook,
+) -> Result<(), SaveError> {
+    validate_webhook(&webhook).map_err(SaveError::Invalid)?;
+    let secret = webhook.secret.take();
+    let mut webhooks = load_webhooks(store).await?;
+    match webhooks.iter_mut().find(|w| w.id == webhook.id) {
+        Some(existing) => *existing = webhook.clone(),
+        None => webhooks.push(webhook.clone()),
+    }
+    validate_webhooks(&webhooks).map_err(SaveError::Invalid)?;
+
+    let key = secret_key(&webhook.id);
+    match secret.as_deref() {
+        Some("") => {
+            store.delete_workspace_secret(&key).await?;
+        }
+        Some(secret) => store.set_workspace_secret(&key, secret).await?,
+        None => {}
+    }
+    store_webhooks(store, &webhooks).await?;
+    Ok(())
+}
+
+/// 按 id 删除 Webhook 及其密钥；返回是否存在
+pub async fn delete_webhook(
+    store: &la_storage::MetadataStore,
+    id: &str,
+) -> la_core::error::Result<bool> {
+    let mut webhooks = load_webhooks(store).await?;
+    let before = webhooks.len();
+    webhooks.retain(|w| w.id != id);
+    if webhooks.len() == before {
+        return Ok(false);
+    }
+    store_webhooks(store, &webhooks).await?;
+    store.delete_workspace_secret(&secret_key(id)).await?;
+    Ok(true)
+}
+
+/// 写回 Webhook 列表；`secret` 不参与序列化，不会落入工作区设置
+async fn store_webhooks(
+    store: &la_storage::MetadataStore,
+    webhooks: &[Webhook],
+) -> la_core::error::Result<()> {
+    let json = serde_json::to_string(webhooks).map_err(|e| {
+        la_core::error::AppError::internal_error(format!("Failed to serialize webhooks: {e}"))
+    })?;
+    store.set_workspace_setting(WEBHOOKS_SETTING, &json).await
+}
+
+/// 保存 Webhook 失败的原因
+#[derive(Debug)]
+pub enum SaveError {
+    /// 校验未通过
+    Invalid(String),
+    Storage(la_core::error::AppError),
+}
+
+impl From<la_core::error::AppError> for SaveError {
+    fn from(e: la_core::error::AppError) -> Self {
+        Self::Storage(e)
+    }
+}
+
+/// 向订阅了 `kind` 的 Webhook 后台投递事件；失败只记录日志
+pub fn notify(
+    runtime: &tokio::runtime::Handle,
+    store: Arc<la_storage::MetadataStore>,
+    workspace_id: &str,
+    kind: WebhookEventKind,
+    data: Value,
+) {
+    let workspace_id = workspace_id.to_string();
+    runtime.spawn(async move {
+        let webhooks = match load_webhooks(&store).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                warn!(workspace_id = %workspace_id, error = %e, "Failed to load webhooks");
+                return;
+            }
+        };
+        let payload = WebhookPayload::new(&workspace_id, kind, data);
+        for webhook in webhooks.into_iter().filter(|w| w.subscribes(kind)) {
+            let payload = payload.clone();
+            tokio::spawn(async move {
+                if let Err(e) = deliver(&webhook, &payload).await {
+                    warn!(
+                        workspace_id = %payload.workspace_id,
+                        webhook_id = %webhook.id,
+                        event = payload.event.as_str(),
+                        error = %e,
+                        "Webhook delivery failed"
+                    );
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(id: &str, url: &str) -> Webhook {
+        Webhook {
+            id: id.to_string(),
+            url: url.to_string(),
+            secret: None,
+            events: vec![WebhookEventKind::ImportComplete],
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn signature_matches_rfc4231_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn validates_url_events_and_ids() {
+        assert!(validate_webhook(&webhook("ci", "https://hooks.example.com/x")).is_ok());
+        assert!(validate_webhook(&webhook("ci", "ftp://example.com")).is_err());
+        assert!(validate_webhook(&webhook("ci", "not a url")).is_err());
+        assert!(validate_webhook(&webhook("", "https://example.com")).is_err());
+
+        let mut no_events = webhook("ci", "https://example.com");
+        no_events.events.clear();
+        assert!(validate_webhook(&no_events).is_err());
+
+        let duplicate = vec![
+            webhook("ci", "https://a.example.com"),
+            webhook("ci", "https://b.example.com"),
+        ];
+        assert!(validate_webhooks(&duplicate).is_err());
+    }
+
+    #[test]
+    fn secret_is_accepted_but_never_serialized() {
+        let parsed: Webhook = serde_json::from_value(serde_json::json!({
+            "id": "ci",
+            "url": "https://example.com",
+            "secret": "s3cret",
+            "events": ["importComplete"]
+        }))
+        .unwrap();
+        assert_eq!(parsed.secret.as_deref(), Some("s3cret"));
+
+        let stored = serde_json::to_value(&parsed).unwrap();
+        assert!(stored.get("secret").is_none());
+        let listed = serde_json::to_value(WebhookInfo::from(parsed)).unwrap();
+        assert!(listed.get("secret").is_none());
+        assert_eq!(listed["hasSecret"], true);
+    }
+
+    #[test]
+    fn retries_server_errors_and_rate_limits_only() {
+        use reqwest::StatusCode;
+        assert_eq!(
+            classify_status(StatusCode::OK),
+            AttemptOutcome::Delivered(200)
+        );
+        assert!(matches!(
+            classify_status(StatusCode::BAD_GATEWAY),
+            AttemptOutcome::Retry(_)
+        ));
+        assert!(matches!(
+            classify_status(StatusCode::TOO_MANY_REQUESTS),
+            AttemptOutcome::Retry(_)
+        ));
+        assert!(matches!(
+            classify_status(StatusCode::NOT_FOUND),
+            AttemptOutcome::Rejected(_)
+        ));
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(3), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn subscription_and_payload_shape() {
+        let mut hook = webhook("ci", "https://example.com");
+        assert!(hook.subscribes(WebhookEventKind::ImportComplete));
+        assert!(!hook.subscribes(WebhookEventKind::WatchError));
+        hook.enabled = false;
+        assert!(!hook.subscribes(WebhookEventKind::ImportComplete));
+
+        let payload = WebhookPayload::new(
+            "ws",
+            WebhookEventKind::AlertFired,
+            serde_json::json!({ "queryId": "q" }),
+        );
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["event"], "alertFired");
+        assert_eq!(json["workspaceId"], "ws");
+        assert_eq!(json["data"]["queryId"], "q");
+
+        let parsed: Webhook = serde_json::from_value(serde_json::json!({
+            "id": "ops",
+            "url": "https://example.com",
+            "events": ["watchError", "alertFired"]
+        }))
+        .unwrap();
+        assert!(parsed.enabled);
+        assert!(parsed.subscribes(WebhookEventKind::WatchError));
+    }
+}
@@ -0,0 +1,546 @@
+//! 工作区只读网络共享
+//!
+//! 一台机器以 HTTP 只读地共享工作区（索引查询、文件读取、虚拟文件树），其他实例把它
+//! 挂载为“远程工作区”：数据只保存在共享端，队友在自己的机器上搜索。本模块只负责
+//! 协议——请求解析与路由、令牌校验、响应编码以及挂载列表的持久化；监听与转发的
+//! 执行见 `commands::workspace_share`。
+//!
+//! 协议（均需 `Authorization: Bearer <token>`）：
+//! - `GET /api/info`：共享的工作区 id 与可调用的操作
+//! - `POST /api/<action>`：以 JSON 对象为参数调用 [`SHARED_ACTIONS`] 中的操作，
+//!   `workspaceId` 固定为共享的工作区；成功返回操作结果，失败返回 `CommandError`
+//!
+//! 安全约束：
+//! - 只暴露只读操作，导入、删除、配置写入等一律返回 403
+//! - 令牌在每次开始共享时随机生成，按常量时间比较
+//! - 请求头与请求体有大小上限，每个连接只处理一个请求
+
+use std::path::Path;
+
+use la_core::error::CommandError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// 应用配置目录下保存远程工作区挂载的文件名
+pub const REMOTES_FILE: &str = "remote_workspaces.json";
+/// 最多保存的远程工作区挂载数
+pub const MAX_REMOTES: usize = 32;
+/// 请求行与请求头的字节上限
+pub const MAX_HEAD_BYTES: usize = 16 * 1024;
+/// 请求体的字节上限
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// 共享端允许调用的只读操作
+pub const SHARED_ACTIONS: &[&str] = &[
+    "search_logs",
+    "get_virtual_file_tree",
+    "read_file_by_hash",
+    "seek_file_time",
+    "get_workspace_overview",
+    "get_workspace_time_range",
+    "get_entries_around_time",
+];
+/// 由共享端决定、不接受调用方传入的参数
+const SERVER_OWNED_ARGS: &[&str] = &["origin", "clientId"];
+
+/// 正在进行的共享
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInfo {
+    pub workspace_id: String,
+    /// 监听地址，如 `http://0.0.0.0:3000`；绑定到所有网卡时队友需换成本机 IP
+    pub url: String,
+    /// 挂载时需要提供的访问令牌
+    pub token: String,
+    /// 携带令牌的共享链接，挂载端可直接按链接打开（见 [`parse_share_link`]）
+    pub link: String,
+    /// 开始共享的时间（Unix 秒）
+    pub started_at: i64,
+}
+
+/// 本机挂载的远程工作区
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWorkspace {
+    /// 本机的挂载 id
+    pub id: String,
+    pub name: String,
+    /// 共享端地址，如 `http://10.0.0.5:3000`
+    pub url: String,
+    pub token: String,
+    /// 共享端的工作区 id
+    pub workspace_id: String,
+}
+
+/// 请求行与请求头中用到的部分
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestHead {
+    pub method: String,
+    pub path: String,
+    pub content_length: usize,
+    /// `Authorization: Bearer` 携带的令牌
+    pub token: Option<String>,
+}
+
+/// 请求路由结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    Info,
+    Action(&'static str),
+}
+
+/// 以 HTTP 状态码返回的错误，响应体为 `CommandError`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpError {
+    pub status: u16,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl HttpError {
+    pub fn new(status: u16, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(401, "UNAUTHORIZED", "Missing or invalid share token")
+    }
+
+    pub fn to_response(&self) -> Vec<u8> {
+        let error = CommandError::new(self.code, self.message.clone());
+        encode_response(
+            self.status,
+            &serde_json::to_value(error).unwrap_or(Value::Null),
+        )
+    }
+}
+
+/// 随机生成共享令牌（UUID v4，32 位十六进制）
+pub fn generate_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// 常量时间比较令牌，避免按响应时间逐字节猜测
+pub fn token_matches(expected: &str, provided: Option<&str>) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// 解析请求行与请求头（不含结尾空行）
+pub fn parse_head(head: &str) -> Result<RequestHead, HttpError> {
+    let mut lines = head.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(HttpError::new(400, "BAD_REQUEST", "Malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(HttpError::new(
+            505,
+            "BAD_REQUEST",
+            format!("Unsupported protocol version: {version}"),
+        ));
+    }
+
+    let mut content_length = 0;
+    let mut token = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(HttpError::new(400, "BAD_REQUEST", "Malformed header line"));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .parse()
+                .map_err(|_| HttpError::new(400, "BAD_REQUEST", "Invalid Content-Length"))?;
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(HttpError::new(
+                411,
+                "BAD_REQUEST",
+                "Chunked requests are not supported; send Content-Length",
+            ));
+        } else if name.eq_ignore_ascii_case("authorization") {
+            token = value.strip_prefix("Bearer ").map(|t| t.trim().to_string());
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(HttpError::new(
+            413,
+            "VALIDATION_ERROR",
+            format!("Request body exceeds {MAX_BODY_BYTES} bytes"),
+        ));
+    }
+
+    Ok(RequestHead {
+        method: method.to_string(),
+        path: path.to_string(),
+        content_length,
+        token,
+    })
+}
+
+/// 按方法与路径路由；未共享的操作返回 403
+pub fn route(method: &str, path: &str) -> Result<Route, HttpError> {
+    let path = path.split('?').next().unwrap_or_default();
+    let Some(name) = path.strip_prefix("/api/").filter(|n| !n.is_empty()) else {
+        return Err(HttpError::new(
+            404,
+            "NOT_FOUND",
+            format!("No such endpoint: {path}"),
+        ));
+    };
+    let expected_method = if name == "info" { "GET" } else { "POST" };
+    if method != expected_method {
+        return Err(HttpError::new(
+            405,
+            "VALIDATION_ERROR",
+            format!("Use {expected_method} for /api/{name}"),
+        ));
+    }
+    if name == "info" {
+        return Ok(Route::Info);
+    }
+    SHARED_ACTIONS
+        .iter()
+        .copied()
+        .find(|action| *action == name)
+        .map(Route::Action)
+        .ok_or_else(|| {
+            HttpError::new(
+                403,
+                "FORBIDDEN",
+                format!("Action '{name}' is not available on a shared workspace"),
+            )
+        })
+}
+
+/// 解析请求体为操作参数，并把 `workspaceId` 固定为共享的工作区
+pub fn scope_args(body: &[u8], workspace_id: &str) -> Result<Map<String, Value>, HttpError> {
+    let mut args = if body.iter().all(u8::is_ascii_whitespace) {
+        Map::new()
+    } else {
+        match serde_json::from_slice(body) {
+            Ok(Value::Object(args)) => args,
+            Ok(_) => {
+                return Err(HttpError::new(
+                    400,
+                    "VALIDATION_ERROR",
+                    "Arguments must be a JSON object",
+                ))
+            }
+            Err(e) => {
+                return Err(HttpError::new(
+                    400,
+                    "VALIDATION_ERROR",
+                    format!("Invalid JSON body: {e}"),
+                ))
+            }
+        }
+    };
+    match args.get("workspaceId") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(id)) if id == workspace_id => {}
+        Some(_) => {
+            return Err(HttpError::new(
+                403,
+                "FORBIDDEN",
+                format!("This server only shares workspace {workspace_id}"),
+            ))
+        }
+    }
+    for key in SERVER_OWNED_ARGS {
+        args.remove(*key);
+    }
+    args.insert("workspaceId".to_string(), json!(workspace_id));
+    Ok(args)
+}
+
+/// `GET /api/info` 的响应体
+pub fn info_json(workspace_id: &str) -> Value {
+    json!({
+        "workspaceId": workspace_id,
+        "readOnly": true,
+        "actions": SHARED_ACTIONS,
+    })
+}
+
+/// 操作失败时的 HTTP 状态码
+pub fn error_status(code: &str) -> u16 {
+    match code {
+        "VALIDATION_ERROR" => 400,
+        "FORBIDDEN" => 403,
+        "NOT_FOUND" => 404,
+        _ => 500,
+    }
+}
+
+/// 编码一个 JSON 响应；每个连接只处理一个请求，响应后关闭
+pub fn encode_response(status: u16, body: &Value) -> Vec<u8> {
+    let body = body.to_string();
+    let mut response = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        reason_phrase(status),
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body.as_bytes());
+    response
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        505 => "HTTP Version Not Supported",
+        _ => "Internal Server Error",
+    }
+}
+
+/// 规范化共享端地址：只接受 http / https 的根地址，去掉结尾的 `/`
+pub fn normalize_remote_url(url: &str) -> Result<String, String> {
+    let url = url.trim().trim_end_matches('/');
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .ok_or_else(|| format!("Remote address must start with http:// or https://: {url}"))?;
+    if rest.is_empty() || rest.contains(['/', '?', '#', '@']) || rest.contains(char::is_whitespace)
+    {
+        return Err(format!(
+            "Remote address must be a host and port such as http://10.0.0.5:3000: {url}"
+        ));
+    }
+    Ok(url.to_string())
+}
+
+/// 共享链接：`<url>/?token=<token>`
+pub fn share_link(url: &str, token: &str) -> String {
+    format!("{url}/?token={token}")
+}
+
+/// 解析共享链接或共享端地址，返回规范化的地址与链接中携带的令牌
+pub fn parse_share_link(link: &str) -> Result<(String, Option<String>), String> {
+    let link = link.trim();
+    let Some((base, query)) = link.split_once('?') else {
+        return Ok((normalize_remote_url(link)?, None));
+    };
+    let token = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| format!("Share link has no token: {link}"))?;
+    Ok((normalize_remote_url(base)?, Some(token.to_string())))
+}
+
+/// 读取挂载列表；文件不存在时返回空列表
+pub fn load_remotes(path: &Path) -> Result<Vec<RemoteWorkspace>, String> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Failed to parse remote workspaces: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read remote workspaces: {e}")),
+    }
+}
+
+/// 保存挂载列表（先写临时文件再替换，避免写入中断留下半个文件）
+pub fn save_remotes(path: &Path, remotes: &[RemoteWorkspace]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {e}"))?;
+    }
+    let json = serde_json::to_vec_pretty(remotes)
+        .map_err(|e| format!("Failed to serialize remote workspaces: {e}"))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write remote workspaces: {e}"))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to save remote workspaces: {e}"))
+}
+
+/// 添加挂载；同一地址上的同一工作区沿用原有 id，只更新名称与令牌
+pub fn upsert_remote(
+    remotes: &mut Vec<RemoteWorkspace>,
+    mut remote: RemoteWorkspace,
+) -> Result<RemoteWorkspace, String> {
+    if let Some(existing) = remotes
+        .iter_mut()
+        .find(|r| r.url == remote.url && r.workspace_id == remote.workspace_id)
+    {
+        remote.id = existing.id.clone();
+        *existing = remote.clone();
+        return Ok(remote);
+    }
+    if remotes.len() >= MAX_REMOTES {
+        return Err(format!("Too many remote workspaces (max {MAX_REMOTES})"));
+    }
+    remotes.push(remote.clone());
+    Ok(remote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(id: &str, url: &str, token: &str) -> RemoteWorkspace {
+        RemoteWorkspace {
+            id: id.to_string(),
+            name: "prod".to_string(),
+            url: url.to_string(),
+            token: token.to_string(),
+            workspace_id: "ws-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_request_head() {
+        let head = parse_head(
+            "POST /api/search_logs HTTP/1.1\r\nHost: x\r\nContent-Length: 12\r\nAuthorization: Bearer abc\r\n",
+        )
+        .unwrap();
+        assert_eq!(head.method, "POST");
+        assert_eq!(head.path, "/api/search_logs");
+        assert_eq!(head.content_length, 12);
+        assert_eq!(head.token.as_deref(), Some("abc"));
+
+        assert_eq!(parse_head("GET /").unwrap_err().status, 400);
+        assert_eq!(parse_head("GET / SPDY/3").unwrap_err().status, 505);
+        let too_big = format!(
+            "POST /api/x HTTP/1.1\r\nContent-Length: {}",
+            MAX_BODY_BYTES + 1
+        );
+        assert_eq!(parse_head(&too_big).unwrap_err().status, 413);
+        let chunked = "POST /api/x HTTP/1.1\r\nTransfer-Encoding: chunked";
+        assert_eq!(parse_head(chunked).unwrap_err().status, 411);
+    }
+
+    #[test]
+    fn routes_only_shared_read_only_actions() {
+        assert_eq!(route("GET", "/api/info").unwrap(), Route::Info);
+        assert_eq!(
+            route("POST", "/api/search_logs?x=1").unwrap(),
+            Route::Action("search_logs")
+        );
+        assert_eq!(
+            route("POST", "/api/delete_workspace").unwrap_err().status,
+            403
+        );
+        assert_eq!(route("POST", "/api/import_folder").unwrap_err().status, 403);
+        assert_eq!(route("GET", "/api/search_logs").unwrap_err().status, 405);
+        assert_eq!(route("GET", "/index.html").unwrap_err().status, 404);
+    }
+
+    #[test]
+    fn args_are_scoped_to_the_shared_workspace() {
+        let args = scope_args(
+            br#"{"query":"error","origin":"x","clientId":"tenant-a"}"#,
+            "ws-1",
+        )
+        .unwrap();
+        assert_eq!(args["workspaceId"], "ws-1");
+        assert_eq!(args["query"], "error");
+        assert!(!args.contains_key("origin"));
+        assert!(!args.contains_key("clientId"));
+
+        assert_eq!(scope_args(b"", "ws-1").unwrap().len(), 1);
+        assert!(scope_args(br#"{"workspaceId":"ws-1"}"#, "ws-1").is_ok());
+        assert_eq!(
+            scope_args(br#"{"workspaceId":"other"}"#, "ws-1")
+                .unwrap_err()
+                .status,
+            403
+        );
+        assert_eq!(scope_args(b"[1]", "ws-1").unwrap_err().status, 400);
+        assert_eq!(scope_args(b"{", "ws-1").unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn tokens_compare_exactly() {
+        let token = generate_token();
+        assert_eq!(token.len(), 32);
+        assert!(token_matches(&token, Some(&token)));
+        assert!(!token_matches(&token, Some(&token[1..])));
+        assert!(!token_matches(&token, Some(&"0".repeat(32))));
+        assert!(!token_matches(&token, None));
+    }
+
+    #[test]
+    fn encodes_json_responses() {
+        let response = String::from_utf8(encode_response(200, &json!({"ok": true}))).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Length: 11\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"ok\":true}"));
+
+        let error = String::from_utf8(HttpError::unauthorized().to_response()).unwrap();
+        assert!(error.starts_with("HTTP/1.1 401 Unauthorized"));
+        assert!(error.contains("\"code\":\"UNAUTHORIZED\""));
+        assert_eq!(error_status("NOT_FOUND"), 404);
+        assert_eq!(error_status("SEARCH_ERROR"), 500);
+    }
+
+    #[test]
+    fn remote_urls_are_normalized() {
+        assert_eq!(
+            normalize_remote_url(" http://10.0.0.5:3000/ ").unwrap(),
+            "http://10.0.0.5:3000"
+        );
+        assert!(normalize_remote_url("https://logs.example.com").is_ok());
+        assert!(normalize_remote_url("ftp://host").is_err());
+        assert!(normalize_remote_url("http://host/api").is_err());
+        assert!(normalize_remote_url("http://user@host").is_err());
+        assert!(normalize_remote_url("http://").is_err());
+    }
+
+    #[test]
+    fn share_links_round_trip() {
+        let link = share_link("http://10.0.0.5:3000", "abc123");
+        assert_eq!(
+            parse_share_link(&link).unwrap(),
+            (
+                "http://10.0.0.5:3000".to_string(),
+                Some("abc123".to_string())
+            )
+        );
+        assert_eq!(
+            parse_share_link("http://h:1").unwrap(),
+            ("http://h:1".to_string(), None)
+        );
+        assert!(parse_share_link("http://h:1/?name=x").is_err());
+        assert!(parse_share_link("http://h:1/api?token=x").is_err());
+    }
+
+    #[test]
+    fn remotes_persist_and_upsert() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(REMOTES_FILE);
+        assert!(load_remotes(&path).unwrap().is_empty());
+
+        let mut remotes = Vec::new();
+        upsert_remote(&mut remotes, remote("a", "http://h:1", "t1")).unwrap();
+        let updated = upsert_remote(&mut remotes, remote("b", "http://h:1", "t2")).unwrap();
+        assert_eq!(updated.id, "a");
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(remotes[0].token, "t2");
+        upsert_remote(&mut remotes, remote("c", "http://h:2", "t3")).unwrap();
+
+        save_remotes(&path, &remotes).unwrap();
+        assert_eq!(load_remotes(&path).unwrap(), remotes);
+    }
+}
@@ -0,0 +1,312 @@
+//! 工作区模板
+//!
+//! 把一类事故（同一产品的支持包）反复用到的工作区配置打包成模板：解压后处理钩子、
+//! 字段提取器、搜索默认值、单行长度上限，以及按路径匹配的忽略规则与格式规则。
+//! 用模板创建工作区时，前四项写入工作区设置，路径规则在导入完成后应用到命中的文件。
+//!
+//! 模板保存在应用配置目录的 [`TEMPLATES_FILE`] 中，可导出为 JSON 在机器间共享。
+//!
+//! 路径规则使用与选择性解压相同的 glob 语法（见 `la_archive::entry_selection`），
+//! 同时匹配完整虚拟路径与去掉首段（导入根目录名）后的相对路径。
+
+use std::path::Path;
+
+use la_archive::post_extract::PostExtractHook;
+use la_archive::EntrySelection;
+use la_core::utils::LogFormat;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::search::{WorkspaceSearchDefaults, MAX_SEARCH_RESULTS};
+use crate::services::field_extractors::{validate_extractors, FieldExtractor};
+
+/// 应用配置目录下保存模板的文件名
+pub const TEMPLATES_FILE: &str = "workspace_templates.json";
+/// 最多保存的模板数
+pub const MAX_TEMPLATES: usize = 64;
+/// 单个模板中路径规则（忽略 + 格式）的数量上限
+pub const MAX_PATH_RULES: usize = 256;
+/// 单行长度上限的取值范围（与 `set_max_line_length` 一致）
+pub const MIN_LINE_LENGTH: usize = 256;
+pub const MAX_LINE_LENGTH: usize = 16 * 1024 * 1024;
+const MAX_ID_LEN: usize = 64;
+
+/// 工作区模板
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub post_extract_hooks: Vec<PostExtractHook>,
+    #[serde(default)]
+    pub field_extractors: Vec<FieldExtractor>,
+    #[serde(default)]
+    pub search_defaults: Option<WorkspaceSearchDefaults>,
+    #[serde(default)]
+    pub max_line_length: Option<usize>,
+    /// 导入后标记为忽略（不参与搜索、不建索引）的文件
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// 导入后按路径改判格式的规则，先命中的规则生效
+    #[serde(default)]
+    pub format_rules: Vec<FormatRule>,
+}
+
+/// 按路径改判文件格式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatRule {
+    pub pattern: String,
+    pub format: LogFormat,
+}
+
+impl WorkspaceTemplate {
+    /// 校验模板；返回第一个错误信息
+    pub fn check(&self) -> Result<(), String> {
+        if !is_valid_template_id(&self.id) {
+            return Err(format!(
+                "Invalid template id '{}': use 1-{MAX_ID_LEN} letters, digits, '_', '-' or '.'",
+                self.id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err(format!("Template '{}' must have a name", self.id));
+        }
+        la_archive::post_extract::validate_hooks(&self.post_extract_hooks)?;
+        validate_extractors(&self.field_extractors)?;
+        if let Some(max_results) = self.search_defaults.as_ref().and_then(|d| d.max_results) {
+            if !(1..=MAX_SEARCH_RESULTS).contains(&max_results) {
+                return Err(format!(
+                    "maxResults must be between 1 and {MAX_SEARCH_RESULTS}"
+                ));
+            }
+        }
+        if let Some(limit) = self.max_line_length {
+            if !(MIN_LINE_LENGTH..=MAX_LINE_LENGTH).contains(&limit) {
+                return Err(format!(
+                    "maxLineLength must be between {MIN_LINE_LENGTH} and {MAX_LINE_LENGTH} bytes"
+                ));
+            }
+        }
+        let rule_count = self.ignore_patterns.len() + self.format_rules.len();
+        if rule_count > MAX_PATH_RULES {
+            return Err(format!(
+                "Too many path rules ({rule_count}, max {MAX_PATH_RULES})"
+            ));
+        }
+        TemplatePathRules::compile(self).map(|_| ())
+    }
+
+    /// 是否包含需要在导入后应用的路径规则
+    pub fn has_path_rules(&self) -> bool {
+        !self.ignore_patterns.is_empty() || !self.format_rules.is_empty()
+    }
+}
+
+fn is_valid_template_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// 编译后的模板路径规则
+pub struct TemplatePathRules {
+    ignore: Option<EntrySelection>,
+    formats: Vec<(EntrySelection, LogFormat)>,
+}
+
+impl TemplatePathRules {
+    pub fn compile(template: &WorkspaceTemplate) -> Result<Self, String> {
+        let ignore = if template.ignore_patterns.iter().all(|p| p.trim().is_empty()) {
+            None
+        } else {
+            Some(EntrySelection::new(&template.ignore_patterns)?)
+        };
+        let formats = template
+            .format_rules
+            .iter()
+            .map(|rule| {
+                EntrySelection::new(std::slice::from_ref(&rule.pattern))
+                    .map(|selection| (selection, rule.format))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { ignore, formats })
+    }
+
+    /// 文件是否命中忽略规则
+    pub fn is_ignored(&self, virtual_path: &str) -> bool {
+        self.ignore
+            .as_ref()
+            .is_some_and(|selection| matches_path(selection, virtual_path))
+    }
+
+    /// 文件命中的第一条格式规则
+    pub fn format_for(&self, virtual_path: &str) -> Option<LogFormat> {
+        self.formats
+            .iter()
+            .find(|(selection, _)| matches_path(selection, virtual_path))
+            .map(|(_, format)| *format)
+    }
+}
+
+fn matches_path(selection: &EntrySelection, virtual_path: &str) -> bool {
+    let trimmed = virtual_path.trim_start_matches('/');
+    selection.matches(trimmed)
+        || trimmed
+            .split_once('/')
+            .is_some_and(|(_, relative)| selection.matches(relative))
+}
+
+/// 读取模板列表；文件不存在时返回空列表
+pub fn load_templates(path: &Path) -> Result<Vec<WorkspaceTemplate>, String> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Failed to parse workspace templates: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read workspace templates: {e}")),
+    }
+}
+
+/// 保存模板列表（先写临时文件再替换，避免写入中断留下半个文件）
+pub fn save_templates(path: &Path, templates: &[WorkspaceTemplate]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config directory: {e}"))?;
+    }
+    let json = serde_json::to_vec_pretty(templates)
+        .map_err(|e| format!("Failed to serialize workspace templates: {e}"))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write workspace templates: {e}"))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to save workspace templates: {e}"))
+}
+
+/// 插入或替换同 id 的模板；`overwrite` 为 `false` 且已存在时返回错误
+pub fn upsert_template(
+    templates: &mut Vec<WorkspaceTemplate>,
+    template: WorkspaceTemplate,
+    overwrite: bool,
+) -> Result<(), String> {
+    match templates.iter().position(|t| t.id == template.id) {
+        Some(_) if !overwrite => Err(format!("Template '{}' already exists", template.id)),
+        Some(index) => {
+            templates[index] = template;
+            Ok(())
+        }
+        None if templates.len() >= MAX_TEMPLATES => Err(format!(
+            "Too many workspace templates (max {MAX_TEMPLATES})"
+        )),
+        None => {
+            templates.push(template);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::field_extractors::ExtractorKind;
+
+    fn template(id: &str) -> WorkspaceTemplate {
+        WorkspaceTemplate {
+            id: id.to_string(),
+            name: "Gateway incident".to_string(),
+            description: String::new(),
+            post_extract_hooks: vec![PostExtractHook::DecompressGz],
+            field_extractors: vec![FieldExtractor {
+                name: "request_id".to_string(),
+                kind: ExtractorKind::Regex {
+                    pattern: r"req=(?P<request_id>\w+)".to_string(),
+                },
+                enabled: true,
+            }],
+            search_defaults: Some(WorkspaceSearchDefaults {
+                case_sensitive: Some(true),
+                max_results: Some(5000),
+            }),
+            max_line_length: Some(4096),
+            ignore_patterns: vec!["**/*.bak".to_string(), "debug".to_string()],
+            format_rules: vec![FormatRule {
+                pattern: "**/access*.log".to_string(),
+                format: LogFormat::Plain,
+            }],
+        }
+    }
+
+    #[test]
+    fn template_round_trips_and_fills_defaults() {
+        let t = template("gateway");
+        t.check().unwrap();
+        let json = serde_json::to_string(&t).unwrap();
+        assert!(json.contains("\"postExtractHooks\""));
+        assert_eq!(serde_json::from_str::<WorkspaceTemplate>(&json).unwrap(), t);
+
+        let minimal: WorkspaceTemplate =
+            serde_json::from_str(r#"{"id":"empty","name":"Empty"}"#).unwrap();
+        minimal.check().unwrap();
+        assert!(!minimal.has_path_rules());
+        assert!(minimal.search_defaults.is_none());
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        let mut t = template("bad id");
+        assert!(t.check().unwrap_err().contains("Invalid template id"));
+
+        t = template("gateway");
+        t.max_line_length = Some(10);
+        assert!(t.check().unwrap_err().contains("maxLineLength"));
+
+        t = template("gateway");
+        t.post_extract_hooks = vec![PostExtractHook::Plugin {
+            name: "not-registered".to_string(),
+        }];
+        assert!(t.check().is_err());
+
+        t = template("gateway");
+        t.field_extractors[0].kind = ExtractorKind::Regex {
+            pattern: "(".to_string(),
+        };
+        assert!(t.check().is_err());
+    }
+
+    #[test]
+    fn path_rules_match_full_and_root_relative_paths() {
+        let rules = TemplatePathRules::compile(&template("gateway")).unwrap();
+
+        assert!(rules.is_ignored("bundle/debug/trace.log"));
+        assert!(rules.is_ignored("bundle/a/b/app.log.bak"));
+        assert!(!rules.is_ignored("bundle/app.log"));
+        assert!(!rules.is_ignored("bundle/service/debug/trace.log"));
+
+        assert_eq!(
+            rules.format_for("bundle/nginx/access-2024.log"),
+            Some(LogFormat::Plain)
+        );
+        assert_eq!(rules.format_for("bundle/nginx/error.log"), None);
+    }
+
+    #[test]
+    fn templates_persist_and_upsert() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join(TEMPLATES_FILE);
+        assert!(load_templates(&path).unwrap().is_empty());
+
+        let mut templates = Vec::new();
+        upsert_template(&mut templates, template("gateway"), false).unwrap();
+        assert!(upsert_template(&mut templates, template("gateway"), false).is_err());
+
+        let mut renamed = template("gateway");
+        renamed.name = "Gateway v2".to_string();
+        upsert_template(&mut templates, renamed, true).unwrap();
+        save_templates(&path, &templates).unwrap();
+
+        let loaded = load_templates(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "Gateway v2");
+    }
+}
@@ -8,7 +8,10 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::state_sync::{WorkspaceEvent, WorkspaceStatus};
+    use crate::state_sync::{
+        FileIndexDelta, LeaseOperation, OperationLease, WorkspaceEvent, WorkspaceStatus,
+    };
+    use la_core::models::LogEntry;
     use std::time::{Duration, SystemTime};
 
     const FIXTURE_JSON: &str =
@@ -55,6 +58,53 @@ mod tests {
                 workspace_id: ws(),
                 new_lines: 42,
             },
+            WorkspaceEvent::NewLogs {
+                workspace_id: ws(),
+                query_id: "follow-errors".to_string(),
+                entries: vec![LogEntry {
+                    id: 0,
+                    timestamp: "2024-01-01 00:00:00".into(),
+                    level: "error".into(),
+                    file: "logs/app.log".into(),
+                    real_path: "/var/log/app.log".into(),
+                    line: 7,
+                    content: "upload failed".into(),
+                    tags: vec![],
+                    match_details: None,
+                    matched_keywords: None,
+                    time_offset_secs: None,
+                    links: None,
+                    translation: None,
+                    source_files: None,
+                    original_length: None,
+                    fields: None,
+                }],
+            },
+            WorkspaceEvent::LeaseChanged {
+                workspace_id: ws(),
+                lease: Some(OperationLease {
+                    operation: LeaseOperation::Refresh,
+                    holder: "alice".to_string(),
+                    acquired_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_003),
+                }),
+            },
+            WorkspaceEvent::LeaseChanged {
+                workspace_id: ws(),
+                lease: None,
+            },
+            WorkspaceEvent::IndexCommitted {
+                workspace_id: ws(),
+                files: vec![
+                    FileIndexDelta {
+                        virtual_path: "logs/app.log".to_string(),
+                        new_entries: 12,
+                    },
+                    FileIndexDelta {
+                        virtual_path: "logs/db.log".to_string(),
+                        new_entries: 3,
+                    },
+                ],
+            },
         ]
     }
 
@@ -0,0 +1,204 @@
+//! 工作区操作租约
+//!
+//! 作为共享后端运行时，多个客户端可能同时刷新或删除同一个工作区。
+//! 导入 / 刷新 / 删除在执行期间持有该工作区的独占租约，冲突的命令
+//! 要么立即失败（报告当前持有者与开始时间），要么排队等待至超时。
+//! 租约的获取与释放通过 `WorkspaceEvent::LeaseChanged` 广播给所有前端。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+
+use super::models::{LeaseOperation, OperationLease, WorkspaceEvent};
+use super::StateSync;
+
+/// 各工作区当前持有的租约
+#[derive(Default)]
+pub struct WorkspaceLeases {
+    active: Mutex<HashMap<String, OperationLease>>,
+    released: Notify,
+}
+
+impl WorkspaceLeases {
+    /// 立即尝试获取租约；已被占用时返回当前持有的租约
+    pub fn try_acquire(
+        self: &Arc<Self>,
+        workspace_id: &str,
+        operation: LeaseOperation,
+        holder: &str,
+    ) -> Result<LeaseGuard, OperationLease> {
+        let mut active = self.active.lock();
+        if let Some(current) = active.get(workspace_id) {
+            return Err(current.clone());
+        }
+        let lease = OperationLease {
+            operation,
+            holder: holder.to_string(),
+            acquired_at: SystemTime::now(),
+        };
+        active.insert(workspace_id.to_string(), lease.clone());
+        Ok(LeaseGuard {
+            leases: Arc::clone(self),
+            workspace_id: workspace_id.to_string(),
+            lease,
+            sync: None,
+        })
+    }
+
+    /// 排队获取租约，最多等待 `wait`；超时后返回仍在持有的租约
+    pub async fn acquire(
+        self: &Arc<Self>,
+        workspace_id: &str,
+        operation: LeaseOperation,
+        holder: &str,
+        wait: Duration,
+    ) -> Result<LeaseGuard, OperationLease> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            // 先登记唤醒再检查，避免检查与等待之间的释放被漏掉
+            let released = self.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            let current = match self.try_acquire(workspace_id, operation, holder) {
+                Ok(guard) => return Ok(guard),
+                Err(current) => current,
+            };
+            if tokio::time::timeout_at(deadline, released).await.is_err() {
+                return Err(current);
+            }
+        }
+    }
+
+    /// 工作区当前的租约
+    pub fn get(&self, workspace_id: &str) -> Option<OperationLease> {
+        self.active.lock().get(workspace_id).cloned()
+    }
+
+    /// 所有持有中的租约，按工作区 ID 索引
+    pub fn snapshot(&self) -> HashMap<String, OperationLease> {
+        self.active.lock().clone()
+    }
+
+    fn release(&self, workspace_id: &str) {
+        self.active.lock().remove(workspace_id);
+        self.released.notify_waiters();
+    }
+}
+
+/// 持有期间独占工作区；drop 时释放并唤醒排队者
+pub struct LeaseGuard {
+    leases: Arc<WorkspaceLeases>,
+    workspace_id: String,
+    lease: OperationLease,
+    sync: Option<StateSync>,
+}
+
+impl LeaseGuard {
+    pub fn lease(&self) -> &OperationLease {
+        &self.lease
+    }
+
+    /// 广播租约已获取，并在释放时广播 `lease: None`
+    pub async fn announce(&mut self, sync: StateSync) {
+        let _ = sync
+            .broadcast_workspace_event(WorkspaceEvent::LeaseChanged {
+                workspace_id: self.workspace_id.clone(),
+                lease: Some(self.lease.clone()),
+            })
+            .await;
+        self.sync = Some(sync);
+    }
+}
+
+impl Drop for LeaseGuard {
+    fn drop(&mut self) {
+        self.leases.release(&self.workspace_id);
+        let Some(sync) = self.sync.take() else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let workspace_id = std::mem::take(&mut self.workspace_id);
+        handle.spawn(async move {
+            let _ = sync
+                .broadcast_workspace_event(WorkspaceEvent::LeaseChanged {
+                    workspace_id,
+                    lease: None,
+                })
+                .await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflicting_acquire_reports_holder_until_released() {
+        let leases = Arc::new(WorkspaceLeases::default());
+        let guard = leases
+            .try_acquire("ws-1", LeaseOperation::Refresh, "alice")
+            .unwrap();
+
+        let current = leases
+            .try_acquire("ws-1", LeaseOperation::Delete, "bob")
+            .err()
+            .unwrap();
+        assert_eq!(current.holder, "alice");
+        assert_eq!(current.operation, LeaseOperation::Refresh);
+        // 其他工作区不受影响
+        assert!(leases
+            .try_acquire("ws-2", LeaseOperation::Import, "bob")
+            .is_ok());
+
+        drop(guard);
+        assert!(leases.get("ws-1").is_none());
+        assert!(leases
+            .try_acquire("ws-1", LeaseOperation::Delete, "bob")
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_queued_acquire_waits_for_release_or_times_out() {
+        let leases = Arc::new(WorkspaceLeases::default());
+        let guard = leases
+            .try_acquire("ws-1", LeaseOperation::Import, "alice")
+            .unwrap();
+
+        let timed_out = leases
+            .acquire(
+                "ws-1",
+                LeaseOperation::Refresh,
+                "bob",
+                Duration::from_millis(20),
+            )
+            .await;
+        assert_eq!(timed_out.err().unwrap().holder, "alice");
+
+        let queued = {
+            let leases = Arc::clone(&leases);
+            tokio::spawn(async move {
+                leases
+                    .acquire(
+                        "ws-1",
+                        LeaseOperation::Refresh,
+                        "bob",
+                        Duration::from_secs(5),
+                    )
+                    .await
+                    .map(|guard| guard.lease().holder.clone())
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(guard);
+        assert_eq!(queued.await.unwrap().ok().as_deref(), Some("bob"));
+        // 排队者的 guard 随任务结束释放
+        assert!(leases.snapshot().is_empty());
+    }
+}
@@ -6,6 +6,7 @@
 
 use crate::infrastructure::TauriEventPublisher;
 
+pub mod leases;
 pub mod models;
 
 #[cfg(test)]
@@ -13,7 +14,10 @@ mod contract_tests;
 #[cfg(test)]
 mod property_tests;
 
-pub use models::{WorkspaceEvent, WorkspaceStatus};
+pub use leases::{LeaseGuard, WorkspaceLeases};
+pub use models::{
+    FileIndexDelta, LeaseOperation, OperationLease, WorkspaceEvent, WorkspaceStatus,
+};
 
 /// State synchronization — 纯事件发射器。
 ///
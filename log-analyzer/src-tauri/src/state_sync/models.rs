@@ -5,8 +5,9 @@ use std::time::{Duration, SystemTime};
 
 /// Workspace event types
 ///
-/// 线上协议事实：目前仅有 `StatusChanged` 一个变体在生产代码中构造
-/// （load_workspace / delete_workspace）。历史上的 ProgressUpdate /
+/// 线上协议事实：`StatusChanged` 由 load_workspace / delete_workspace 构造，
+/// `LeaseChanged` 由导入 / 刷新 / 删除的租约获取与释放构造，`FilesUpdated` /
+/// `NewLogs` / `IndexCommitted` 由 watch 模式的增量索引构造。历史上的 ProgressUpdate /
 /// TaskCompleted / Error 变体从未被发送（进度与任务生命周期由
 /// `task-update` 通道承载），已作为死协议面移除。
 ///
@@ -26,6 +27,59 @@ pub enum WorkspaceEvent {
         /// 本轮 debounce 窗口内累计写入的行数
         new_lines: u64,
     },
+    /// Watch mode: 新写入的条目命中了挂载的跟随查询（不做 debounce）。
+    NewLogs {
+        workspace_id: String,
+        query_id: String,
+        entries: Vec<la_core::models::LogEntry>,
+    },
+    /// 工作区操作租约变化：`lease` 为当前持有者，释放时为 null。
+    LeaseChanged {
+        workspace_id: String,
+        lease: Option<OperationLease>,
+    },
+    /// Watch mode: 一次增量索引提交中新增了条目的文件（不做 debounce）。
+    IndexCommitted {
+        workspace_id: String,
+        files: Vec<FileIndexDelta>,
+    },
+}
+
+/// 一次索引提交中单个文件新增的条目数
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FileIndexDelta {
+    pub virtual_path: String,
+    pub new_entries: u64,
+}
+
+/// 持有工作区独占租约的操作
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaseOperation {
+    Import,
+    Refresh,
+    Delete,
+}
+
+impl LeaseOperation {
+    /// 用于错误消息的动词形式
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Import => "importing",
+            Self::Refresh => "refreshing",
+            Self::Delete => "deleting",
+        }
+    }
+}
+
+/// 工作区上正在进行的操作：谁、做什么、从何时开始
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OperationLease {
+    pub operation: LeaseOperation,
+    /// 发起操作的客户端标识；本机桌面端为 `local`
+    pub holder: String,
+    #[serde(with = "system_time_serde")]
+    pub acquired_at: SystemTime,
 }
 
 /// Workspace status
@@ -204,8 +204,11 @@ mod tests {
                 let state = states.entry(workspace_id.clone()).or_default();
                 state.status = status_to_name(status);
             }
-            WorkspaceEvent::FilesUpdated { .. } => {
-                // FilesUpdated 不改变工作区状态，此处忽略
+            WorkspaceEvent::FilesUpdated { .. }
+            | WorkspaceEvent::NewLogs { .. }
+            | WorkspaceEvent::LeaseChanged { .. }
+            | WorkspaceEvent::IndexCommitted { .. } => {
+                // 监听与租约事件不改变工作区状态，此处忽略
             }
         }
     }
@@ -24,6 +24,7 @@ use tauri::{AppHandle, Emitter};
 use thiserror::Error;
 use tokio::sync::mpsc;
 use tokio::time::{interval, timeout};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace, warn};
 
 /// 任务管理器错误类型
@@ -310,6 +311,7 @@ impl TaskManagerActor {
                 const VERSION_RESET_THRESHOLD: u64 = u64::MAX - 10_000;
 
                 let result = if let Some(task) = self.tasks.get_mut(&id) {
+                    let was_running = task.status == TaskStatus::Running;
                     task.progress = progress;
                     task.message = message.clone();
                     task.status = status;
@@ -331,6 +333,13 @@ impl TaskManagerActor {
                         TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Stopped
                     ) {
                         task.completed_at = Some(Instant::now());
+                        if was_running {
+                            crate::monitoring::telemetry().record_operation(
+                                &format!("task.{}", task.task_type),
+                                task.created_at.elapsed(),
+                                &format!("{status:?}").to_lowercase(),
+                            );
+                        }
                         info!(
                             task_id = %id,
                             status = ?status,
@@ -586,6 +595,8 @@ impl TaskManagerActor {
 pub struct TaskManager {
     sender: mpsc::Sender<ActorMessage>,
     config: TaskManagerConfig,
+    /// 支持协作式取消的运行中任务 → 取消令牌
+    cancellations: Arc<parking_lot::Mutex<HashMap<String, CancellationToken>>>,
 }
 
 impl TaskManager {
@@ -610,7 +621,11 @@ impl TaskManager {
         });
 
         info!("TaskManager initialized successfully");
-        Ok(Self { sender, config })
+        Ok(Self {
+            sender,
+            config,
+            cancellations: Arc::default(),
+        })
     }
 
     /// 创建新任务（异步版本）
@@ -644,6 +659,16 @@ impl TaskManager {
             .map_err(|_| TaskManagerError::ActorDroppedResponse)
     }
 
+    /// 登记任务的取消令牌：任务被停止（如 `cancel_task`）时令牌随之取消，
+    /// 长时间运行的工作据此协作式退出。任务结束后登记自动移除
+    pub fn cancellation_token(&self, id: &str) -> CancellationToken {
+        self.cancellations
+            .lock()
+            .entry(id.to_string())
+            .or_default()
+            .clone()
+    }
+
     /// 更新任务进度（异步版本）
     pub async fn update_task_async(
         &self,
@@ -652,6 +677,14 @@ impl TaskManager {
         message: String,
         status: TaskStatus,
     ) -> Result<Option<TaskInfo>, TaskManagerError> {
+        if status != TaskStatus::Running {
+            if let Some(token) = self.cancellations.lock().remove(id) {
+                if status == TaskStatus::Stopped {
+                    token.cancel();
+                }
+            }
+        }
+
         let (tx, rx) = tokio::sync::oneshot::channel();
 
         let msg = ActorMessage::UpdateTask {
@@ -21,10 +21,8 @@
 /// # Returns
 /// `(min_timestamp, max_timestamp, level_mask)`
 pub fn compute_file_stats(content: &[u8]) -> (Option<i64>, Option<i64>, Option<u8>) {
-    let text = match std::str::from_utf8(content) {
-        Ok(s) => s,
-        Err(_) => return (None, None, None),
-    };
+    // 个别无效字节不影响其余行的时间戳与级别
+    let text = String::from_utf8_lossy(content);
 
     // 大文件优化：超过 10MB 只解析前 1000 行和后 1000 行
     const MAX_FULL_PARSE_BYTES: usize = 10 * 1024 * 1024;
@@ -53,7 +51,7 @@ pub fn compute_file_stats(content: &[u8]) -> (Option<i64>, Option<i64>, Option<u
     };
 
     if text.len() > MAX_FULL_PARSE_BYTES {
-        let all_lines: Vec<&str> = text.lines().collect();
+        let all_lines: Vec<&str> = la_core::utils::split_lines(&text).collect();
         let total = all_lines.len();
         if total > MAX_LINES_SAMPLE * 2 {
             for line in &all_lines[..MAX_LINES_SAMPLE] {
@@ -68,7 +66,7 @@ pub fn compute_file_stats(content: &[u8]) -> (Option<i64>, Option<i64>, Option<u
             }
         }
     } else {
-        for line in text.lines() {
+        for line in la_core::utils::split_lines(&text) {
             process_line(line);
         }
     }
@@ -97,12 +95,22 @@ mod tests {
     }
 
     #[test]
-    fn non_utf8_content_returns_none() {
+    fn binary_content_has_no_timestamps() {
         let content = vec![0xFF, 0xFE, 0x00, 0x01];
         let (min_ts, max_ts, levels) = compute_file_stats(&content);
         assert_eq!(min_ts, None);
         assert_eq!(max_ts, None);
-        assert_eq!(levels, None);
+        // 无级别关键字的行按 debug 计
+        assert_eq!(levels, Some(la_core::utils::level_to_mask("debug")));
+    }
+
+    #[test]
+    fn invalid_bytes_and_bare_cr_do_not_hide_stats() {
+        let content = b"2024-01-01 10:00:00 INFO a \xff\r2024-01-02 10:00:00 ERROR b\r";
+        let (min_ts, max_ts, levels) = compute_file_stats(content);
+        assert!(min_ts.is_some());
+        assert!(max_ts > min_ts);
+        assert!(levels.is_some());
     }
 
     #[test]
@@ -126,7 +126,8 @@ pub fn normalize_path_separator(path: &str) -> String {
 /// - 非 Windows：原样返回，无任何修改
 /// - Windows，路径 ≤ 260 字节：原样返回
 /// - Windows，已有 `\\?\` 前缀：原样返回（防止双重前缀）
-/// - Windows，路径 > 260 字节：使用 `dunce::simplified` 规范化后追加 `\\?\`
+/// - Windows，路径 > 260 字节：词法规范化后追加 `\\?\`（UNC 路径转换为 `\\?\UNC\`），
+///   见 `la_core::utils::path::to_extended_length_path`
 ///
 /// # 参数
 ///
@@ -153,9 +154,7 @@ pub fn apply_unc_prefix_if_needed(path: &Path) -> PathBuf {
         if path_str.starts_with(r"\\?\") || path_str.len() <= 260 {
             return path.to_path_buf();
         }
-        // 使用 dunce 规范化（消除多余的 . 和 ..）后追加前缀
-        let simplified = dunce::simplified(path);
-        PathBuf::from(format!(r"\\?\{}", simplified.display()))
+        la_core::utils::path::to_extended_length_path(path)
     }
     #[cfg(not(target_os = "windows"))]
     {
@@ -251,17 +251,10 @@ pub fn sanitize_file_name_strict(name: &str) -> Result<String, String> {
         return Err("Filename too long (max 255 characters)".to_string());
     }
 
-    // 检查保留名称（Windows）
-    let reserved_names = [
-        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
-        "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
-    ];
-
-    let name_upper = sanitized.to_uppercase();
-    let name_without_ext = name_upper.split('.').next().unwrap_or("");
-
-    if reserved_names.contains(&name_without_ext) {
-        return Err(format!("Reserved filename: {name_without_ext}"));
+    // 检查保留名称（Windows，与归档提取共用同一规则）
+    if la_core::utils::is_windows_reserved_name(&sanitized) {
+        let base = sanitized.split('.').next().unwrap_or("").to_uppercase();
+        return Err(format!("Reserved filename: {base}"));
     }
 
     Ok(sanitized)
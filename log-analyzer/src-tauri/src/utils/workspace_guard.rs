@@ -8,27 +8,32 @@
 //! # Usage
 //!
 //! ```rust,ignore
-//! let (service, workspace_dir) = require_cas_workspace(&app, &state, &workspace_id).await?;
+//! let (service, workspace_dir) =
+//!     require_tenant_workspace(&app, &state, &workspace_id, client_id.as_deref()).await?;
 //! // Use service.cas(), service.metadata_store(), service.search_engine(), etc.
 //! ```
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use tauri::AppHandle;
 
 use crate::application::workspace_service::WorkspaceServiceRef;
 use crate::infrastructure::workspace_service_factory::get_or_create_workspace_service;
 use crate::models::AppState;
+use crate::state_sync::{LeaseGuard, LeaseOperation, OperationLease};
 use crate::utils::validation::validate_workspace_id;
-use crate::utils::workspace_paths::resolve_workspace_dir;
+use crate::utils::workspace_paths::{
+    resolve_workspace_dir, tenant_of_workspace_dir, tenant_workspace_dir,
+};
 use la_core::error::CommandError;
 
 /// Validate workspace ID, resolve directory, check CAS format, and acquire the service.
 ///
 /// Returns the pre-assembled [`WorkspaceServiceRef`] and its workspace directory.
-/// Every command that needs a ready-to-use workspace should call this instead of
-/// duplicating the preamble or creating standalone CAS/MetadataStore instances.
-pub async fn require_cas_workspace(
+/// Does not authorize the caller: commands go through [`require_tenant_workspace`]
+/// (or [`require_writable_workspace`]) instead.
+async fn require_cas_workspace(
     app: &AppHandle,
     state: &AppState,
     workspace_id: &str,
@@ -60,14 +65,256 @@ pub async fn require_cas_workspace(
     }
 
     // 5. Get or create the WorkspaceService
-    let service = get_or_create_workspace_service(app, state, workspace_id, &workspace_dir)
+    let service = get_or_create_workspace_service(app, state, workspace_id, &workspace_dir).await?;
+
+    Ok((service, workspace_dir))
+}
+
+/// Workspace setting that marks an archived workspace as read-only (`"true"` / `"false"`).
+pub const READ_ONLY_SETTING: &str = "read_only";
+
+/// Whether the workspace is marked read-only; a missing setting means writable.
+pub async fn is_read_only(service: &WorkspaceServiceRef) -> Result<bool, CommandError> {
+    let value = service
+        .metadata_store()
+        .get_workspace_setting(READ_ONLY_SETTING)
         .await
-        .map_err(|e| {
-            CommandError::new(
-                "RUNTIME_ERROR",
-                format!("Failed to initialize workspace: {e}"),
-            )
-        })?;
+        .map_err(|e| CommandError::new("DATABASE_ERROR", e.to_string()))?;
+    Ok(value.as_deref() == Some("true"))
+}
 
+/// Like [`require_tenant_workspace`], but rejects workspaces marked read-only.
+///
+/// Use for every command that changes workspace data (refresh, watch,
+/// per-file annotations, hooks) so archived evidence stays untouched.
+pub async fn require_writable_workspace(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    client_id: Option<&str>,
+) -> Result<(WorkspaceServiceRef, PathBuf), CommandError> {
+    let (service, workspace_dir) =
+        require_tenant_workspace(app, state, workspace_id, client_id).await?;
+    if is_read_only(&service).await? {
+        return Err(read_only_error(workspace_id));
+    }
     Ok((service, workspace_dir))
 }
+
+/// Reject the command if the workspace exists in CAS format and is read-only.
+///
+/// Missing or legacy workspaces have no stored flag and pass, so commands that
+/// may also create or clean up such workspaces (refresh, delete) can call this
+/// before doing anything else.
+///
+/// Tenant-scoped callers may only touch workspaces in their own namespace,
+/// whatever their format.
+pub async fn ensure_writable(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    client_id: Option<&str>,
+) -> Result<(), CommandError> {
+    let tenant = caller_tenant(state, client_id)?;
+    let Ok(workspace_dir) = resolve_workspace_dir(app, workspace_id) else {
+        return Ok(());
+    };
+    if tenant.is_some()
+        && workspace_dir.exists()
+        && tenant_of_workspace_dir(&workspace_dir) != tenant
+    {
+        return Err(not_found_error());
+    }
+    if !workspace_dir.join("metadata.db").exists() || !workspace_dir.join("objects").exists() {
+        return Ok(());
+    }
+    require_writable_workspace(app, state, workspace_id, client_id)
+        .await
+        .map(|_| ())
+}
+
+fn read_only_error(workspace_id: &str) -> CommandError {
+    CommandError::new(
+        "WORKSPACE_READ_ONLY",
+        format!("Workspace '{workspace_id}' is read-only"),
+    )
+    .with_help("Clear the read-only flag in the workspace settings to modify it")
+}
+
+/// Tenant the caller is scoped to; `None` for unscoped callers (operator
+/// sessions, and the local desktop app outside server mode).
+///
+/// In server mode (`security.auth_enabled`) every caller must present the
+/// `clientId` of an authenticated session; the tenant is the one its API key
+/// was bound to when the session was opened, never a value the client sends.
+pub fn caller_tenant(
+    state: &AppState,
+    client_id: Option<&str>,
+) -> Result<Option<String>, CommandError> {
+    let clients = state.sync.clients();
+    match client_id.and_then(|id| clients.scope_of(id)) {
+        Some(tenant) => Ok(tenant),
+        None if clients.auth_required() => Err(unauthenticated_error()),
+        None => Ok(None),
+    }
+}
+
+/// Reject callers that are scoped to a tenant; for backend-wide commands
+/// (configuration, maintenance) that only operators may run in server mode.
+pub fn require_unscoped_caller(
+    state: &AppState,
+    client_id: Option<&str>,
+) -> Result<(), CommandError> {
+    if caller_tenant(state, client_id)?.is_some() {
+        return Err(CommandError::new(
+            "FORBIDDEN",
+            "This operation is not available to tenant sessions",
+        )
+        .with_help("Open the session with the operator key"));
+    }
+    Ok(())
+}
+
+/// Tenant that owns the workspace, as recorded in its metadata store.
+pub fn workspace_tenant(service: &WorkspaceServiceRef) -> Option<String> {
+    service.metadata_store().tenant().map(str::to_string)
+}
+
+/// Reject a tenant-scoped caller whose tenant does not own the workspace.
+///
+/// The error is indistinguishable from a missing workspace, so other tenants'
+/// workspace ids cannot be probed. Unscoped callers (operators, the local
+/// desktop app) pass.
+pub async fn authorize_workspace(
+    state: &AppState,
+    service: &WorkspaceServiceRef,
+    client_id: Option<&str>,
+) -> Result<(), CommandError> {
+    let Some(tenant) = caller_tenant(state, client_id)? else {
+        return Ok(());
+    };
+    if service.metadata_store().tenant() == Some(tenant.as_str()) {
+        return Ok(());
+    }
+    Err(not_found_error())
+}
+
+/// Acquire the workspace for a command, checking the caller's tenant.
+///
+/// Every command that takes a workspace id goes through this (or
+/// [`require_writable_workspace`]). A tenant-scoped caller is checked against
+/// the workspace's namespace before its metadata store is opened, so another
+/// tenant's workspace is never loaded on its behalf.
+pub async fn require_tenant_workspace(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    client_id: Option<&str>,
+) -> Result<(WorkspaceServiceRef, PathBuf), CommandError> {
+    let tenant = caller_tenant(state, client_id)?;
+    if tenant.is_some() {
+        let workspace_dir =
+            resolve_workspace_dir(app, workspace_id).map_err(|_| not_found_error())?;
+        if tenant_of_workspace_dir(&workspace_dir) != tenant {
+            return Err(not_found_error());
+        }
+    }
+    let (service, workspace_dir) = require_cas_workspace(app, state, workspace_id).await?;
+    authorize_workspace(state, &service, client_id).await?;
+    Ok((service, workspace_dir))
+}
+
+/// Authorize an import into the workspace, creating it in the caller's
+/// namespace if it is new.
+///
+/// Workspace ids are unique across namespaces: a tenant-scoped caller can only
+/// import into a new id or one already in its own namespace; any other id is
+/// reported as missing.
+pub async fn claim_workspace(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    client_id: Option<&str>,
+) -> Result<(), CommandError> {
+    let Some(tenant) = caller_tenant(state, client_id)? else {
+        return Ok(());
+    };
+    let workspace_dir = resolve_workspace_dir(app, workspace_id).map_err(|e| {
+        CommandError::new("VALIDATION_ERROR", e).with_help("Workspace ID format is invalid")
+    })?;
+    if workspace_dir.exists() {
+        return match tenant_of_workspace_dir(&workspace_dir) {
+            Some(owner) if owner == tenant => Ok(()),
+            _ => Err(not_found_error()),
+        };
+    }
+    let scoped_dir = tenant_workspace_dir(app, &tenant, workspace_id)
+        .map_err(|e| CommandError::new("VALIDATION_ERROR", e))?;
+    tokio::fs::create_dir_all(&scoped_dir).await.map_err(|e| {
+        CommandError::new(
+            "IO_ERROR",
+            format!("Failed to create workspace directory: {e}"),
+        )
+    })
+}
+
+fn unauthenticated_error() -> CommandError {
+    CommandError::new("UNAUTHORIZED", "No authenticated client session")
+        .with_help("Open a client session with an API key and pass its clientId")
+}
+
+fn not_found_error() -> CommandError {
+    CommandError::new("NOT_FOUND", "Workspace not found")
+        .with_help("The workspace may have been deleted or moved. Try re-importing")
+}
+
+/// Client identity recorded on leases when the caller does not supply one.
+pub const LOCAL_LEASE_HOLDER: &str = "local";
+
+/// Longest a command may queue behind another operation on the same workspace.
+pub const MAX_LEASE_WAIT_SECS: u64 = 600;
+
+/// Take the workspace's exclusive operation lease for an import, refresh or delete.
+///
+/// Without `wait_secs` a conflicting command fails immediately with
+/// `WORKSPACE_BUSY`, naming the current holder and when it started; with it
+/// the command queues until the lease is released or the wait runs out.
+/// Acquisition and release are broadcast as `LeaseChanged` workspace events.
+pub async fn acquire_workspace_lease(
+    state: &AppState,
+    workspace_id: &str,
+    operation: LeaseOperation,
+    client_id: Option<String>,
+    wait_secs: Option<u64>,
+) -> Result<LeaseGuard, CommandError> {
+    let holder = client_id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| LOCAL_LEASE_HOLDER.to_string());
+    let leases = state.sync.leases();
+    let acquired = match wait_secs.filter(|&secs| secs > 0) {
+        Some(secs) => {
+            let wait = Duration::from_secs(secs.min(MAX_LEASE_WAIT_SECS));
+            leases.acquire(workspace_id, operation, &holder, wait).await
+        }
+        None => leases.try_acquire(workspace_id, operation, &holder),
+    };
+    let mut guard = acquired.map_err(|current| busy_error(workspace_id, &current))?;
+    if let Some(sync) = state.get_state_sync() {
+        guard.announce(sync).await;
+    }
+    Ok(guard)
+}
+
+fn busy_error(workspace_id: &str, current: &OperationLease) -> CommandError {
+    let since = chrono::DateTime::<chrono::Utc>::from(current.acquired_at)
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    CommandError::new(
+        "WORKSPACE_BUSY",
+        format!(
+            "Workspace '{workspace_id}' is busy: {} is {} it since {since}",
+            current.holder,
+            current.operation.verb()
+        ),
+    )
+    .with_help("Wait for the running operation to finish, or retry with waitSecs to queue")
+}
@@ -4,6 +4,12 @@ use tauri::{AppHandle, Manager};
 
 pub const PRIMARY_WORKSPACE_DIR_NAME: &str = "workspaces";
 
+/// 租户工作区的命名空间目录：`workspaces/tenants/<tenant>/<workspace_id>`
+///
+/// 本地（无租户）工作区直接位于 `workspaces/<workspace_id>`。工作区 id 在所有
+/// 命名空间中唯一（见 `workspace_guard::claim_workspace`），按 id 解析时无需知道租户。
+pub const TENANT_WORKSPACE_DIR_NAME: &str = "tenants";
+
 /// Generate a workspace ID from a human-readable name.
 ///
 /// Slugifies the name (lowercase, special chars → '-'), appends a short UUID suffix,
@@ -63,17 +69,82 @@ pub fn preferred_workspace_dir_from_root(
     app_data_dir: &Path,
     workspace_id: &str,
 ) -> Result<PathBuf, String> {
-    crate::utils::validation::validate_workspace_id(workspace_id)?;
+    validate_namespaced_id(workspace_id)?;
+    Ok(app_data_dir
+        .join(PRIMARY_WORKSPACE_DIR_NAME)
+        .join(workspace_id))
+}
+
+/// 租户命名空间下的工作区目录
+pub fn tenant_workspace_dir(
+    app: &AppHandle,
+    tenant: &str,
+    workspace_id: &str,
+) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {e}"))?;
+
+    tenant_workspace_dir_from_root(&app_data_dir, tenant, workspace_id)
+}
+
+pub fn tenant_workspace_dir_from_root(
+    app_data_dir: &Path,
+    tenant: &str,
+    workspace_id: &str,
+) -> Result<PathBuf, String> {
+    validate_namespaced_id(tenant)?;
+    validate_namespaced_id(workspace_id)?;
     Ok(app_data_dir
         .join(PRIMARY_WORKSPACE_DIR_NAME)
+        .join(TENANT_WORKSPACE_DIR_NAME)
+        .join(tenant)
         .join(workspace_id))
 }
 
-fn resolve_workspace_dir_from_root(
+/// 已存在的工作区目录（本地或任一租户命名空间），不存在时返回本地布局下的新目录
+pub fn resolve_workspace_dir_from_root(
     app_data_dir: &Path,
     workspace_id: &str,
 ) -> Result<PathBuf, String> {
-    preferred_workspace_dir_from_root(app_data_dir, workspace_id)
+    let local = preferred_workspace_dir_from_root(app_data_dir, workspace_id)?;
+    if local.exists() {
+        return Ok(local);
+    }
+    let tenants_dir = app_data_dir
+        .join(PRIMARY_WORKSPACE_DIR_NAME)
+        .join(TENANT_WORKSPACE_DIR_NAME);
+    if let Ok(entries) = std::fs::read_dir(&tenants_dir) {
+        for entry in entries.flatten() {
+            let candidate = entry.path().join(workspace_id);
+            if candidate.is_dir() {
+                return Ok(candidate);
+            }
+        }
+    }
+    Ok(local)
+}
+
+/// 工作区目录所属的租户（由目录布局决定）；本地工作区为 `None`
+pub fn tenant_of_workspace_dir(workspace_dir: &Path) -> Option<String> {
+    let tenant_dir = workspace_dir.parent()?;
+    let namespace = tenant_dir.parent()?;
+    if namespace.file_name()? != TENANT_WORKSPACE_DIR_NAME
+        || namespace.parent()?.file_name()? != PRIMARY_WORKSPACE_DIR_NAME
+    {
+        return None;
+    }
+    tenant_dir.file_name()?.to_str().map(str::to_string)
+}
+
+/// 校验 id 并拒绝与租户命名空间目录同名的 id
+fn validate_namespaced_id(id: &str) -> Result<(), String> {
+    crate::utils::validation::validate_workspace_id(id)?;
+    if id == TENANT_WORKSPACE_DIR_NAME {
+        return Err(format!("'{id}' is reserved"));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -109,6 +180,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resolves_tenant_namespaced_workspaces_by_id() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scoped = tenant_workspace_dir_from_root(temp_dir.path(), "acme", "ws-1").unwrap();
+        std::fs::create_dir_all(&scoped).unwrap();
+
+        assert_eq!(
+            resolve_workspace_dir_from_root(temp_dir.path(), "ws-1").unwrap(),
+            scoped
+        );
+        assert_eq!(tenant_of_workspace_dir(&scoped).as_deref(), Some("acme"));
+
+        let local = resolve_workspace_dir_from_root(temp_dir.path(), "ws-2").unwrap();
+        assert_eq!(tenant_of_workspace_dir(&local), None);
+        assert!(
+            resolve_workspace_dir_from_root(temp_dir.path(), TENANT_WORKSPACE_DIR_NAME).is_err()
+        );
+        assert!(tenant_workspace_dir_from_root(temp_dir.path(), "../acme", "ws-1").is_err());
+    }
+
     #[test]
     fn rejects_path_traversal_in_workspace_id() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -16,6 +16,9 @@ interface KeywordStatsPanelProps {
   /** 搜索耗时（毫秒） */
   searchDurationMs: number;
 
+  /** 搜索时工作区仍在导入：已导入的百分比（可选） */
+  importProgress?: number;
+
   /** 关闭回调（可选） */
   onClose?: () => void;
 }
@@ -33,6 +36,7 @@ export const KeywordStatsPanel: React.FC<KeywordStatsPanelProps> = ({
   keywords,
   totalMatches,
   searchDurationMs,
+  importProgress,
   onClose,
 }) => {
   const { t } = useTranslation();
@@ -51,6 +55,7 @@ export const KeywordStatsPanel: React.FC<KeywordStatsPanelProps> = ({
           </span>
           <span className="text-[10px] text-text-dim">
             {formatNumber(totalMatches)} 条匹配 · {searchDurationMs}ms
+            {importProgress !== undefined && ` · 工作区已导入 ${importProgress}%`}
           </span>
         </div>
         <div className="flex items-center gap-1">
@@ -38,6 +38,9 @@ describe("workspace-event wire contract", () => {
     const types = fixture.map((entry) => (entry as { type: string }).type);
     expect(types).toContain("StatusChanged");
     expect(types).toContain("FilesUpdated");
+    expect(types).toContain("NewLogs");
+    expect(types).toContain("LeaseChanged");
+    expect(types).toContain("IndexCommitted");
   });
 
   it.each(fixture.map((_, index) => [index]))(
@@ -27,6 +27,7 @@ export interface TauriEventProjectionOptions {
  * - import-complete → 直接更新 task/workspace store（带幂等性检查）
  * - import-error → toast
  * - validation-report → 导入后完整性校验发现问题时 toast 警告
+ * - security-warning → 压缩包条目因路径穿越被隔离时 toast 警告
  *
  * @returns 卸载函数：逐个调用 Tauri unlisten，忽略异常
  */
@@ -183,6 +184,30 @@ export async function mountTauriEventProjection(
     }
   );
 
+  // security-warning：压缩包内的 ../ 等越界条目未被解压，已隔离并记录到元数据。
+  interface SecurityWarningPayload {
+    workspaceId: string;
+    category: "security";
+    kind: string;
+    archivePath: string;
+    entryName: string;
+    reason: string;
+  }
+  const securityWarningUnlisten = await listen<SecurityWarningPayload>(
+    "security-warning",
+    (event) => {
+      const { archivePath, entryName } = event.payload;
+      logger.warn(
+        { payload: event.payload },
+        "[TauriEventProjection] Archive entry quarantined"
+      );
+      showToast(
+        "error",
+        `安全警告：压缩包 ${archivePath} 中的条目 ${entryName} 存在路径穿越风险，已隔离`
+      );
+    }
+  );
+
   const workspaceEventUnlisten = await listen<unknown>(
     "workspace-event",
     (event) => {
@@ -207,6 +232,7 @@ export async function mountTauriEventProjection(
       importCompleteUnlisten,
       importErrorUnlisten,
       validationReportUnlisten,
+      securityWarningUnlisten,
       workspaceEventUnlisten,
     ].forEach((unlisten) => {
       try {
@@ -12,6 +12,7 @@
  */
 
 import { z } from "zod";
+import { LogEntrySchema } from "../types/api-responses";
 
 // ============================================================================
 // 基础类型
@@ -32,7 +33,13 @@ export type TaskStatus = z.infer<typeof TaskStatusSchema>;
 /**
  * 任务类型枚举
  */
-export const TaskTypeSchema = z.enum(["Import", "Export", "Search", "Index"]);
+export const TaskTypeSchema = z.enum([
+  "Import",
+  "Scan",
+  "Export",
+  "Search",
+  "Index",
+]);
 
 export type TaskType = z.infer<typeof TaskTypeSchema>;
 
@@ -102,6 +109,29 @@ export const WorkspaceStatusSchema = z.object({
 
 export type WorkspaceStatusPayload = z.infer<typeof WorkspaceStatusSchema>;
 
+/**
+ * 工作区操作租约（state_sync/models.rs OperationLease）
+ *
+ * 导入 / 刷新 / 删除期间由发起方独占持有；释放时 LeaseChanged 的 lease 为 null。
+ */
+export const OperationLeaseSchema = z.object({
+  operation: z.enum(["import", "refresh", "delete"]),
+  holder: z.string(),
+  acquired_at: z.number().int().positive(),
+});
+
+export type OperationLease = z.infer<typeof OperationLeaseSchema>;
+
+/**
+ * 一次增量索引提交中单个文件新增的条目数（state_sync/models.rs FileIndexDelta）
+ */
+export const FileIndexDeltaSchema = z.object({
+  virtual_path: z.string(),
+  new_entries: z.number().int().min(0),
+});
+
+export type FileIndexDelta = z.infer<typeof FileIndexDeltaSchema>;
+
 export const WorkspaceEventSchema = z.discriminatedUnion("type", [
   z.object({
     type: z.literal("StatusChanged"),
@@ -113,6 +143,22 @@ export const WorkspaceEventSchema = z.discriminatedUnion("type", [
     workspace_id: z.string().min(1, "workspace_id is required"),
     new_lines: z.number().int().min(0),
   }),
+  z.object({
+    type: z.literal("NewLogs"),
+    workspace_id: z.string().min(1, "workspace_id is required"),
+    query_id: z.string().min(1, "query_id is required"),
+    entries: z.array(LogEntrySchema),
+  }),
+  z.object({
+    type: z.literal("LeaseChanged"),
+    workspace_id: z.string().min(1, "workspace_id is required"),
+    lease: OperationLeaseSchema.nullable(),
+  }),
+  z.object({
+    type: z.literal("IndexCommitted"),
+    workspace_id: z.string().min(1, "workspace_id is required"),
+    files: z.array(FileIndexDeltaSchema),
+  }),
 ]);
 
 export type WorkspaceEvent = z.infer<typeof WorkspaceEventSchema>;
@@ -104,6 +104,18 @@ export const useEventBusSubscriptions = () => {
             refreshWorkspaces();
             break;
           }
+          case "NewLogs": {
+            // 跟随查询命中：由订阅了对应 query_id 的视图自行消费
+            break;
+          }
+          case "LeaseChanged": {
+            // 操作租约：冲突提示由被拒绝的命令自身的 WORKSPACE_BUSY 错误承载
+            break;
+          }
+          case "IndexCommitted": {
+            // 增量索引提交：文件树按 virtual_path 标记变更，实时查询只重算这些文件
+            break;
+          }
         }
       }
     );
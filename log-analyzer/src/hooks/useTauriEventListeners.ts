@@ -17,6 +17,7 @@ import { logger } from "../utils/logger";
  *   - import-complete → 直接更新 task/workspace 状态
  *   - import-error → toast 错误提示
  *   - validation-report → 导入完整性校验问题 toast
+ *   - security-warning → 路径穿越条目隔离 toast
  *
  * 使用 tauriCleanupRef 确保异步注册完成后同步清理。
  */
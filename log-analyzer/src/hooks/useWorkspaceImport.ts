@@ -57,7 +57,7 @@ export const useWorkspaceImport = (): UseWorkspaceImportReturn => {
           multiple: false,
           filters: [{
             name: 'Log Files & Archives',
-            extensions: ['log', 'txt', 'gz', 'zip', 'tar', 'tgz', 'rar', '*']
+            extensions: ['log', 'txt', 'gz', 'zip', 'tar', 'tgz', 'zst', 'tzst', 'xz', 'txz', 'bz2', 'tbz2', 'lz4', 'rar', '*']
           }]
         });
         if (import.meta.env.DEV) logger.debug('Selected file:', selected);
@@ -409,6 +409,7 @@ const SearchPage: React.FC = () => {
             keywords={keywordStats}
             totalMatches={searchSummary.totalMatches}
             searchDurationMs={searchSummary.searchDurationMs}
+            importProgress={searchSummary.importProgress}
             onClose={() => dispatchSearchExec({ type: "RESET" })}
           />
         )}
@@ -236,6 +236,27 @@ describe('SearchSchemas', () => {
       }
     });
 
+    it('should parse file distribution, time range, source and phase timings', () => {
+      const result = SearchResultSummarySchema.safeParse({
+        ...validSummary,
+        fileStats: [{ file: 'app.log', matchCount: 300 }],
+        timeRange: { earliest: '2024-01-01 09:00:00', latest: '2024-01-01 18:00:00' },
+        source: 'fresh',
+        phaseTimings: { fileSelectionMs: 3, planningMs: 1, scanMs: 121 },
+      });
+      expect(result.success).toBe(true);
+      if (result.success) {
+        expect(result.data.fileStats?.[0].file).toBe('app.log');
+        expect(result.data.phaseTimings?.scanMs).toBe(121);
+      }
+
+      const invalidSource = SearchResultSummarySchema.safeParse({
+        ...validSummary,
+        source: 'l3',
+      });
+      expect(invalidSource.success).toBe(false);
+    });
+
     it('should reject summary with invalid keywordStats - empty array', () => {
       const invalidSummary = {
         ...validSummary,
@@ -31,6 +31,9 @@ const LogEntrySchema = z.object({
   tags: z.array(z.string()),
   match_details: z.array(MatchDetailSchema).optional(),
   matched_keywords: z.array(z.string()).optional(),
+  source_files: z.array(z.string()).optional(),
+  original_length: z.number().optional(),
+  fields: z.record(z.string(), z.string()).optional(),
 });
 
 /**
@@ -47,11 +50,35 @@ const KeywordStatisticsSchema = z.object({
  * 搜索结果摘要 Schema
  * 对应 Rust 后端 SearchResultSummary 结构（使用 serde rename）
  */
+const SearchTruncationSchema = z.object({
+  reason: z.enum(['maxResults', 'timeout', 'cancelled']),
+  filesScanned: z.number(),
+  filesTotal: z.number(),
+  countsAreLowerBound: z.boolean(),
+});
+
 const SearchResultSummarySchema = z.object({
   totalMatches: z.number(),
   keywordStats: z.array(KeywordStatisticsSchema),
   searchDurationMs: z.number(),
   truncated: z.boolean(),
+  truncation: SearchTruncationSchema.optional(),
+  fileStats: z
+    .array(z.object({ file: z.string(), matchCount: z.number() }))
+    .optional(),
+  timeRange: z
+    .object({ earliest: z.string(), latest: z.string() })
+    .nullable()
+    .optional(),
+  source: z.enum(['l1', 'l2', 'fresh']).optional(),
+  phaseTimings: z
+    .object({
+      fileSelectionMs: z.number(),
+      planningMs: z.number(),
+      scanMs: z.number(),
+    })
+    .optional(),
+  importProgress: z.number().optional(),
 });
 
 /**
@@ -75,6 +102,7 @@ export {
   MatchDetailSchema,
   LogEntrySchema,
   KeywordStatisticsSchema,
+  SearchTruncationSchema,
   SearchResultSummarySchema,
   PagedSearchResultSchema,
 };
@@ -25,6 +25,7 @@ describe('errors', () => {
       [ErrorCode.NOT_FOUND, ErrorCategory.USER],
       [ErrorCode.QUERY_EXECUTION_ERROR, ErrorCategory.USER],
       [ErrorCode.PARSE_ERROR, ErrorCategory.USER],
+      [ErrorCode.PASSWORD_REQUIRED, ErrorCategory.USER],
       // FILESYSTEM errors
       [ErrorCode.IO_ERROR, ErrorCategory.FILESYSTEM],
       [ErrorCode.ENCODING_ERROR, ErrorCategory.FILESYSTEM],
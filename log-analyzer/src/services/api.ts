@@ -345,12 +345,13 @@ class LogAnalyzerApi {
    *
    * @param path - 文件夹路径
    * @param workspaceId - 工作区 ID
+   * @param password - 加密压缩包的密码；缺少或错误时抛出 `PASSWORD_REQUIRED` 错误
    * @returns 任务 ID
    */
-  async importFolder(path: string, workspaceId: string): Promise<string> {
+  async importFolder(path: string, workspaceId: string, password?: string): Promise<string> {
     return this.invokeWithErrorHandling(
       'import_folder',
-      { path, workspaceId },
+      { path, workspaceId, password },
       (raw) => z.string().parse(raw)
     );
   }
@@ -523,16 +524,23 @@ class LogAnalyzerApi {
   /**
    * 导出结果
    *
+   * 剪贴板目的地由后端返回文本，在此写入系统剪贴板
+   *
    * @param params - 导出参数
-   * @returns 导出文件路径
+   * @returns 导出位置（文件路径、sftp:// 地址或命令名）；剪贴板目的地返回 'clipboard'
    */
   async exportResults(params: ExportParams): Promise<string> {
     const validatedParams = ExportParamsSchema.parse(params);
-    return this.invokeWithErrorHandling(
+    const result = await this.invokeWithErrorHandling(
       'export_results',
       validatedParams as unknown as InvokeArgs,
       (raw) => raw as string
     );
+    if (validatedParams.options?.destination.kind === 'clipboard') {
+      await navigator.clipboard.writeText(result);
+      return 'clipboard';
+    }
+    return result;
   }
 
   // ========================================================================
@@ -549,6 +557,12 @@ class LogAnalyzerApi {
     workspaceId: string;
     hash: string;
     maxLength?: number;
+    /** 只读取该行（从 1 开始），用于取回被截断的超长行 */
+    line?: number;
+    /** 行内字节偏移 */
+    offset?: number;
+    /** 行内读取的字节数 */
+    length?: number;
   }): Promise<string> {
     return this.invokeWithErrorHandling(
       'read_file_by_hash',
@@ -557,6 +571,24 @@ class LogAnalyzerApi {
     );
   }
 
+  /**
+   * 查找文件中不早于给定时间的第一行，供查看器跳转
+   *
+   * @param params - `time` 可为 `14:32`、`14:32:05`（按文件首个时间戳所在日期）或完整日期时间
+   * @returns 目标行号（从 1 开始，没有这么晚的行时为 null）及是否使用了时间索引
+   */
+  async seekFileTime(params: {
+    workspaceId: string;
+    hash: string;
+    time: string;
+  }): Promise<{ line: number | null; indexed: boolean }> {
+    return this.invokeWithErrorHandling(
+      'seek_file_time',
+      params,
+      (raw) => raw as { line: number | null; indexed: boolean }
+    );
+  }
+
 }
 
 // ============================================================================
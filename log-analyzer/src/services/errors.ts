@@ -21,6 +21,7 @@ export enum ErrorCode {
   IO_ERROR = 'IO_ERROR',
   SEARCH_ERROR = 'SEARCH_ERROR',
   ARCHIVE_ERROR = 'ARCHIVE_ERROR',
+  PASSWORD_REQUIRED = 'PASSWORD_REQUIRED',
   VALIDATION_ERROR = 'VALIDATION_ERROR',
   SECURITY_ERROR = 'SECURITY_ERROR',
   NOT_FOUND = 'NOT_FOUND',
@@ -97,6 +98,7 @@ const ERROR_CODE_CATEGORIES: Record<string, ErrorCategory> = {
   [ErrorCode.NOT_FOUND]: ErrorCategory.USER,
   [ErrorCode.QUERY_EXECUTION_ERROR]: ErrorCategory.USER,
   [ErrorCode.PARSE_ERROR]: ErrorCategory.USER,
+  [ErrorCode.PASSWORD_REQUIRED]: ErrorCategory.USER,
 
   // 文件系统错误
   [ErrorCode.IO_ERROR]: ErrorCategory.FILESYSTEM,
@@ -129,6 +131,7 @@ const ERROR_CODE_I18N_KEYS: Record<string, string> = {
   [ErrorCode.IO_ERROR]: 'errors.io.error',
   [ErrorCode.SEARCH_ERROR]: 'errors.search.execution_error',
   [ErrorCode.ARCHIVE_ERROR]: 'errors.io.error',
+  [ErrorCode.PASSWORD_REQUIRED]: 'errors.import.password_required',
   [ErrorCode.VALIDATION_ERROR]: 'errors.validation.path_canonicalization_failed',
   [ErrorCode.SECURITY_ERROR]: 'errors.validation.path_traversal',
   [ErrorCode.NOT_FOUND]: 'errors.workspace.not_found',
@@ -30,6 +30,240 @@ export type RarSupportInfo = z.infer<typeof RarSupportInfoSchema>;
 // 虚拟文件节点（与后端 VirtualTreeNode tagged enum 对齐）
 // ============================================================================
 
+/**
+ * 文件数据质量统计（无效 UTF-8、NUL 字节、换行符）
+ */
+export type DataQuality = {
+  invalidSequences: number;
+  nulBytes: number;
+  crlfLines: number;
+  lfLines: number;
+  crLines: number;
+};
+
+export const DataQualitySchema: z.ZodType<DataQuality> = z.object({
+  invalidSequences: z.number(),
+  nulBytes: z.number(),
+  crlfLines: z.number(),
+  lfLines: z.number(),
+  crLines: z.number(),
+});
+
+/**
+ * 日志文件格式（导入时按内容识别，可在文件树中改判）
+ */
+export const LogFormatSchema = z.enum([
+  'plain',
+  'ndjson',
+  'logfmt',
+  'apache',
+  'syslog',
+  'csv',
+  'pcap',
+  'har',
+  'binary',
+]);
+
+export type LogFormat = z.infer<typeof LogFormatSchema>;
+
+/**
+ * CSV/TSV 列映射：分隔符、表头与时间/级别/消息列（列号从 0 开始）
+ */
+export type CsvMapping = {
+  delimiter: string;
+  hasHeader: boolean;
+  columns: string[];
+  timeColumn?: number | null;
+  levelColumn?: number | null;
+  messageColumn?: number | null;
+  extraColumns: number[];
+};
+
+export const CsvMappingSchema: z.ZodType<CsvMapping> = z.object({
+  delimiter: z.string().length(1),
+  hasHeader: z.boolean(),
+  columns: z.array(z.string()),
+  timeColumn: z.number().int().nonnegative().nullable().optional(),
+  levelColumn: z.number().int().nonnegative().nullable().optional(),
+  messageColumn: z.number().int().nonnegative().nullable().optional(),
+  extraColumns: z.array(z.number().int().nonnegative()),
+});
+
+/**
+ * 文件格式：识别结果、置信度（0-1）、用户改判的格式，CSV 的识别/改写列映射，
+ * 以及用户设置的编码、时区（固定偏移）与解析器正则覆盖
+ */
+export type FileFormat = {
+  detected: LogFormat;
+  confidence: number;
+  overrideFormat?: LogFormat | null;
+  detectedCsvMapping?: CsvMapping | null;
+  csvMappingOverride?: CsvMapping | null;
+  encodingOverride?: string | null;
+  timezoneOverride?: string | null;
+  parserOverride?: string | null;
+};
+
+export const FileFormatSchema: z.ZodType<FileFormat> = z.object({
+  detected: LogFormatSchema,
+  confidence: z.number(),
+  overrideFormat: LogFormatSchema.nullable().optional(),
+  detectedCsvMapping: CsvMappingSchema.nullable().optional(),
+  csvMappingOverride: CsvMappingSchema.nullable().optional(),
+  encodingOverride: z.string().nullable().optional(),
+  timezoneOverride: z.string().nullable().optional(),
+  parserOverride: z.string().nullable().optional(),
+});
+
+/**
+ * `set_file_overrides` 的参数：各项为空时恢复自动识别
+ */
+export type FileOverrides = {
+  encoding?: string | null;
+  format?: LogFormat | null;
+  timezone?: string | null;
+  parser?: string | null;
+};
+
+/**
+ * 崩溃产物：类型、构建 ID 与崩溃时间（Unix 秒）
+ */
+export const CrashArtifactKindSchema = z.enum(['coredump', 'minidump', 'hprof']);
+
+export type CrashArtifactKind = z.infer<typeof CrashArtifactKindSchema>;
+
+export type CrashArtifactInfo = {
+  kind: CrashArtifactKind;
+  buildId?: string | null;
+  crashedAt?: number | null;
+};
+
+export const CrashArtifactInfoSchema: z.ZodType<CrashArtifactInfo> = z.object({
+  kind: CrashArtifactKindSchema,
+  buildId: z.string().nullable().optional(),
+  crashedAt: z.number().nullable().optional(),
+});
+
+/**
+ * 产物目录中的一项（get_crash_artifacts）
+ */
+export type CrashArtifact = CrashArtifactInfo & {
+  virtualPath: string;
+  sha256Hash: string;
+  size: number;
+  modifiedTime: number;
+};
+
+export const CrashArtifactSchema: z.ZodType<CrashArtifact> = z.object({
+  kind: CrashArtifactKindSchema,
+  buildId: z.string().nullable().optional(),
+  crashedAt: z.number().nullable().optional(),
+  virtualPath: z.string(),
+  sha256Hash: z.string(),
+  size: z.number(),
+  modifiedTime: z.number(),
+});
+
+/**
+ * 工作区模板：解压后处理钩子、字段提取器、搜索默认值、单行长度上限，
+ * 以及导入后按路径应用的忽略规则与格式规则
+ */
+export const WorkspaceTemplateSchema = z.object({
+  id: z.string(),
+  name: z.string(),
+  description: z.string().default(''),
+  postExtractHooks: z.array(z.object({ action: z.string() }).passthrough()).default([]),
+  fieldExtractors: z
+    .array(z.object({ name: z.string(), kind: z.string(), enabled: z.boolean() }).passthrough())
+    .default([]),
+  searchDefaults: z
+    .object({
+      caseSensitive: z.boolean().nullable().optional(),
+      maxResults: z.number().int().positive().nullable().optional(),
+    })
+    .nullable()
+    .optional(),
+  maxLineLength: z.number().int().positive().nullable().optional(),
+  ignorePatterns: z.array(z.string()).default([]),
+  formatRules: z.array(z.object({ pattern: z.string(), format: LogFormatSchema })).default([]),
+});
+
+export type WorkspaceTemplate = z.infer<typeof WorkspaceTemplateSchema>;
+
+/**
+ * create_workspace_from_template 的结果
+ */
+export const TemplateWorkspaceResultSchema = z.object({
+  workspaceId: z.string(),
+  taskId: z.string(),
+  ignoredFiles: z.number(),
+  reformattedFiles: z.number(),
+});
+
+export type TemplateWorkspaceResult = z.infer<typeof TemplateWorkspaceResultSchema>;
+
+/**
+ * 操作注册表（list_actions）：命令面板与脚本宏通过 invoke_action 按名称调用
+ */
+export const ActionArgSchema = z.object({
+  name: z.string(),
+  type: z.enum(['string', 'integer', 'number', 'boolean', 'array', 'object']),
+  typeName: z.string(),
+  required: z.boolean(),
+});
+
+export type ActionArg = z.infer<typeof ActionArgSchema>;
+
+export const ActionDescriptorSchema = z.object({
+  name: z.string(),
+  title: z.string(),
+  category: z.string(),
+  args: z.array(ActionArgSchema),
+});
+
+export type ActionDescriptor = z.infer<typeof ActionDescriptorSchema>;
+
+/**
+ * 分析宏：录制的 invoke_action 步骤，可通过 replay_macro 对其他工作区回放
+ */
+export const MacroStepSchema = z.object({
+  action: z.string(),
+  args: z.record(z.string(), z.unknown()),
+});
+
+export const AnalysisMacroSchema = z.object({
+  id: z.string(),
+  name: z.string(),
+  description: z.string(),
+  steps: z.array(MacroStepSchema),
+});
+
+export type AnalysisMacro = z.infer<typeof AnalysisMacroSchema>;
+
+export const MacroStepReportSchema = z.object({
+  index: z.number(),
+  action: z.string(),
+  ok: z.boolean(),
+  durationMs: z.number(),
+  result: z.unknown().optional(),
+  totalItems: z.number().optional(),
+  error: z.string().optional(),
+});
+
+export const MacroReportSchema = z.object({
+  macroId: z.string(),
+  macroName: z.string(),
+  workspaceId: z.string(),
+  startedAt: z.string(),
+  durationMs: z.number(),
+  succeeded: z.number(),
+  failed: z.number(),
+  skipped: z.number(),
+  steps: z.array(MacroStepReportSchema),
+});
+
+export type MacroReport = z.infer<typeof MacroReportSchema>;
+
 /**
  * 文件节点类型
  */
@@ -40,6 +274,18 @@ export type VirtualFileNode = {
   hash: string;
   size: number;
   mimeType?: string;
+  dataQuality?: DataQuality;
+  format?: FileFormat;
+  crashArtifact?: CrashArtifactInfo;
+};
+
+/**
+ * 选择性解压跳过的条目（未导入，置灰显示）
+ */
+export type SkippedTreeEntry = {
+  name: string;
+  path: string;
+  size: number;
 };
 
 /**
@@ -52,6 +298,7 @@ export type VirtualArchiveNode = {
   hash: string;
   archiveType: string;
   children: VirtualTreeNode[];
+  skippedEntries?: SkippedTreeEntry[];
 };
 
 /**
@@ -69,6 +316,9 @@ export const VirtualFileNodeSchema: z.ZodType<VirtualFileNode> = z.object({
   hash: z.string(),
   size: z.number(),
   mimeType: z.string().optional(),
+  dataQuality: DataQualitySchema.optional(),
+  format: FileFormatSchema.optional(),
+  crashArtifact: CrashArtifactInfoSchema.optional(),
 });
 
 /**
@@ -81,6 +331,9 @@ export const VirtualArchiveNodeSchema: z.ZodType<VirtualArchiveNode> = z.object(
   hash: z.string(),
   archiveType: z.string(),
   children: z.lazy(() => VirtualTreeNodeSchema.array()),
+  skippedEntries: z
+    .array(z.object({ name: z.string(), path: z.string(), size: z.number() }))
+    .optional(),
 });
 
 /**
@@ -258,6 +511,18 @@ export const MatchDetailSchema = z.object({
  */
 export type MatchDetail = z.infer<typeof MatchDetailSchema>;
 
+/**
+ * 外部链接 Schema（与后端 models::ExternalLink 一致）
+ */
+export const ExternalLinkSchema = z.object({
+  templateId: z.string(),
+  label: z.string(),
+  text: z.string(),
+  url: z.string(),
+});
+
+export type ExternalLink = z.infer<typeof ExternalLinkSchema>;
+
 /**
  * LogEntry Schema（与后端 models::LogEntry 一致）
  *
@@ -267,6 +532,11 @@ export type MatchDetail = z.infer<typeof MatchDetailSchema>;
  * - tags: string[]（后端为 Vec<String>）
  * - match_details: 可选数组
  * - matched_keywords: 可选数组
+ * - time_offset_secs: 可选，文件时钟偏移（秒）
+ * - links: 可选，外部链接模板解析出的链接
+ * - source_files: 可选，跨文件去重后出现过该条目的全部文件
+ * - original_length: 可选，超长行被截断时原始行的字节长度
+ * - fields: 可选，工作区字段提取器提取的字段（展示列）
  */
 export const LogEntrySchema = z.object({
   id: z.number(),
@@ -279,6 +549,11 @@ export const LogEntrySchema = z.object({
   tags: z.array(z.string()),
   match_details: z.array(MatchDetailSchema).optional(),
   matched_keywords: z.array(z.string()).optional(),
+  time_offset_secs: z.number().optional(),
+  links: z.array(ExternalLinkSchema).optional(),
+  source_files: z.array(z.string()).optional(),
+  original_length: z.number().optional(),
+  fields: z.record(z.string(), z.string()).optional(),
 });
 
 /**
@@ -331,15 +606,37 @@ export const SearchParamsSchema = z.object({
   structuredQuery: SearchQuerySchema.optional(),
   workspaceId: z.string().optional(),
   maxResults: z.number().int().positive().optional(),
+  /** 覆盖工作区 / 应用的默认大小写设置（结构化查询按各项设置） */
+  caseSensitive: z.boolean().optional(),
   filters: FilterOptionsSchema.optional(),
 });
 
 export type SearchParamsValidated = z.infer<typeof SearchParamsSchema>;
 
+export const ExportDestinationSchema = z.discriminatedUnion('kind', [
+  z.object({ kind: z.literal('file') }),
+  z.object({ kind: z.literal('clipboard') }),
+  z.object({
+    kind: z.literal('sftp'),
+    host: z.string().min(1),
+    port: z.number().int().min(1).max(65535).optional(),
+    user: z.string().min(1).optional(),
+    remotePath: z.string().min(1),
+  }),
+  z.object({ kind: z.literal('command'), name: z.string().min(1) }),
+]);
+
+export type ExportDestination = z.infer<typeof ExportDestinationSchema>;
+
 export const ExportParamsSchema = z.object({
   results: z.array(LogEntrySchema),
   format: z.enum(['csv', 'json']),
   savePath: z.string().min(1),
+  options: z
+    .object({
+      destination: ExportDestinationSchema,
+    })
+    .optional(),
 });
 
 export type ExportParamsValidated = z.infer<typeof ExportParamsSchema>;
@@ -384,9 +681,30 @@ export type TaskManagerConfigValidated = z.infer<typeof TaskManagerConfigSchema>
 /**
  * 工作区加载响应 Schema（对应 api.ts WorkspaceLoadResponse）
  */
+/**
+ * 索引兼容性 Schema（reindexing：正在按当前版本重建索引；needsUpgrade：由更新版本写入）
+ */
+export const IndexCompatibilitySchema = z.discriminatedUnion('status', [
+  z.object({ status: z.literal('compatible') }),
+  z.object({
+    status: z.literal('reindexing'),
+    foundIndexVersion: z.number().int(),
+    expectedIndexVersion: z.number().int(),
+  }),
+  z.object({
+    status: z.literal('needsUpgrade'),
+    foundSchemaVersion: z.number().int(),
+    supportedSchemaVersion: z.number().int(),
+    writtenBy: z.string().nullable(),
+  }),
+]);
+
+export type IndexCompatibility = z.infer<typeof IndexCompatibilitySchema>;
+
 export const WorkspaceLoadResponseSchema = z.object({
   success: z.boolean(),
   fileCount: z.number().int().nonnegative(),
+  indexCompatibility: IndexCompatibilitySchema.optional(),
 });
 
 /**
@@ -403,6 +721,8 @@ export const WorkspaceStatusResponseSchema = z.object({
   status: WorkspaceStatusSchema,
   size: z.string(),
   files: z.number().int().nonnegative(),
+  /** 导入进行中时的估算完成百分比（已导入的文件可以搜索） */
+  importProgress: z.number().int().min(0).max(100).optional(),
 });
 
 /**
@@ -424,6 +744,33 @@ export const WorkspaceTimeRangeSchema = z.object({
  */
 export type WorkspaceTimeRangeValidated = z.infer<typeof WorkspaceTimeRangeSchema>;
 
+/**
+ * 工作区 Webhook Schema（get_webhooks / save_webhook 的返回值，不含签名密钥）
+ */
+export const WebhookEventKindSchema = z.enum(['importComplete', 'alertFired', 'watchError']);
+
+export const WebhookSchema = z.object({
+  id: z.string().min(1),
+  url: z.string().url(),
+  hasSecret: z.boolean(),
+  events: z.array(WebhookEventKindSchema).min(1),
+  enabled: z.boolean().default(true),
+});
+
+export type WebhookEventKind = z.infer<typeof WebhookEventKindSchema>;
+export type Webhook = z.infer<typeof WebhookSchema>;
+
+/**
+ * save_webhook 的参数：secret 省略时保留已有密钥，空字符串表示删除
+ */
+export interface WebhookInput {
+  id: string;
+  url: string;
+  secret?: string;
+  events: WebhookEventKind[];
+  enabled?: boolean;
+}
+
 // ============================================================================
 // 应用配置
 // ============================================================================
@@ -154,6 +154,66 @@ export interface SearchResultSummary {
   
   /** 是否因超限截断 */
   truncated: boolean;
+
+  /** 结果不完整时的详细信息（超时 / 取消 / 超限） */
+  truncation?: SearchTruncation;
+
+  /** 按命中数降序的文件分布 */
+  fileStats?: FileStatistics[];
+
+  /** 命中行的时间跨度（无可解析时间戳时为 null） */
+  timeRange?: MatchTimeRange | null;
+
+  /** 结果来源：内存会话 / 磁盘结果存储 / 新扫描 */
+  source?: 'l1' | 'l2' | 'fresh';
+
+  /** 各阶段耗时（毫秒） */
+  phaseTimings?: SearchPhaseTimings;
+
+  /** 搜索提交时工作区仍在导入：已导入的估算百分比（结果只覆盖已导入的文件） */
+  importProgress?: number;
+}
+
+/**
+ * 单个文件的命中统计
+ */
+export interface FileStatistics {
+  /** 文件虚拟路径 */
+  file: string;
+  matchCount: number;
+}
+
+/**
+ * 命中行的时间跨度（原始时间戳文本）
+ */
+export interface MatchTimeRange {
+  earliest: string;
+  latest: string;
+}
+
+/**
+ * 搜索各阶段耗时
+ */
+export interface SearchPhaseTimings {
+  /** 候选文件筛选 */
+  fileSelectionMs: number;
+  /** 构建执行计划 */
+  planningMs: number;
+  /** 扫描文件内容 */
+  scanMs: number;
+}
+
+/**
+ * 搜索截断信息
+ */
+export interface SearchTruncation {
+  reason: 'maxResults' | 'timeout' | 'cancelled';
+  /** 已完整扫描的文件数 */
+  filesScanned: number;
+  /** 候选文件总数 */
+  filesTotal: number;
+  /** 统计数字是否只是下限 */
+  countsAreLowerBound: boolean;
 }
 
 /**